@@ -0,0 +1,752 @@
+//! 3-tier hierarchical context compression (LoCoBench-Agent / Cursor pattern)
+//!
+//! Pure functions over the prompt history: the chat loop and the replay
+//! harness both call them before sending a prompt to the engine. The chat
+//! loop uses the async variant, whose Compressed tier asks the model for a
+//! summary through a closure it supplies.
+
+use std::future::Future;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::agent::prompts::build_context_compression_prompt;
+use crate::storage::settings::MaskingStyle;
+use crate::types::message::{Message, Role};
+
+
+/// Context threshold for Working memory tier (40% of max context)
+/// At this tier, only selective pruning is applied (observation masking)
+pub const WORKING_THRESHOLD: f32 = 0.40;
+
+/// Context threshold for Compressed memory tier (60% of max context)
+/// At this tier, incremental summarization is applied
+pub const COMPRESSED_THRESHOLD: f32 = 0.60;
+
+/// Context threshold for Archived memory tier (80% of max context)
+/// At this tier, aggressive truncation keeping anchors + last 2 messages
+pub const ARCHIVED_THRESHOLD: f32 = 0.80;
+
+/// Compression tier based on context usage level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionTier {
+    /// Working memory: 0-40% context used - selective pruning only
+    Working,
+    /// Compressed: 40-60% context used - incremental summarization
+    Compressed,
+    /// Archived: 60-80% context used - aggressive truncation
+    Archived,
+    /// Critical: >80% context - fallback to existing compression
+    Critical,
+}
+
+impl CompressionTier {
+    /// Get tier name for logging
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompressionTier::Working => "Working",
+            CompressionTier::Compressed => "Compressed",
+            CompressionTier::Archived => "Archived",
+            CompressionTier::Critical => "Critical",
+        }
+    }
+}
+
+/// Determine the current compression tier based on context usage
+/// 
+/// # Arguments
+/// * `current_tokens` - Estimated current token count
+/// * `max_tokens` - Maximum available context tokens
+/// 
+/// # Returns
+/// The appropriate CompressionTier based on usage percentage
+pub fn get_compression_tier(current_tokens: usize, max_tokens: usize) -> CompressionTier {
+    if max_tokens == 0 {
+        return CompressionTier::Critical;
+    }
+    
+    let usage_ratio = current_tokens as f32 / max_tokens as f32;
+    
+    if usage_ratio <= WORKING_THRESHOLD {
+        CompressionTier::Working
+    } else if usage_ratio <= COMPRESSED_THRESHOLD {
+        CompressionTier::Compressed
+    } else if usage_ratio <= ARCHIVED_THRESHOLD {
+        CompressionTier::Archived
+    } else {
+        CompressionTier::Critical
+    }
+}
+
+/// Message characters kept in a digest
+const DIGEST_MESSAGE_CHARS: usize = 120;
+/// String fields of a result's data worth keeping in a digest
+const DIGEST_STRING_KEYS: &[&str] = &["path", "file", "url", "command", "source", "destination", "query", "pattern", "branch"];
+/// Files listed from an array of matches
+const DIGEST_MAX_FILES: usize = 5;
+
+/// Whether a message holds a tool result observation masking may shrink
+fn is_tool_result(msg: &Message) -> bool {
+    let content = &msg.content;
+    !msg.is_pinned()
+        && (msg.role == Role::System || msg.role == Role::Tool)
+        && !content.starts_with("[Tool result")
+        && (content.starts_with("{\"tool\":")
+            || is_compact_result(content)
+            || content.contains("file_read")
+            || content.contains("tool_result")
+            || content.contains("executed:")
+            || content.contains("Output:"))
+}
+
+/// First line of a compact result: `[tool] ok: message`
+fn is_compact_result(content: &str) -> bool {
+    let first = content.lines().next().unwrap_or_default();
+    first.starts_with('[') && (first.contains("] ok: ") || first.contains("] failed: "))
+}
+
+/// Tool name guessed from the first line of a result
+fn tool_name_of(content: &str) -> String {
+    if is_compact_result(content) {
+        if let Some(end) = content.find(']') {
+            return content[1..end].to_string();
+        }
+    }
+    content.lines()
+        .next()
+        .unwrap_or("tool")
+        .split(':')
+        .next()
+        .unwrap_or("tool")
+        .trim()
+        .to_string()
+}
+
+/// Key facts of a result's data: numbers, path-like strings, array sizes and the files they mention
+fn digest_facts(data: &Value) -> Vec<String> {
+    let Some(fields) = data.as_object() else {
+        return Vec::new();
+    };
+    let mut keys: Vec<&String> = fields.keys().collect();
+    keys.sort();
+    let mut facts = Vec::new();
+    for key in keys {
+        match &fields[key] {
+            Value::Number(n) => facts.push(format!("{}: {}", key, n)),
+            Value::String(text)
+                if DIGEST_STRING_KEYS.contains(&key.as_str())
+                    && text.chars().count() <= DIGEST_MESSAGE_CHARS
+                    && !text.contains('\n') =>
+            {
+                facts.push(format!("{}: {}", key, text))
+            }
+            Value::Array(items) => {
+                let mut files: Vec<&str> = Vec::new();
+                for item in items {
+                    let file = item.get("file").or_else(|| item.get("path")).and_then(Value::as_str);
+                    if let Some(file) = file {
+                        if !files.contains(&file) {
+                            files.push(file);
+                        }
+                    }
+                }
+                if files.is_empty() {
+                    facts.push(format!("{}: {} item(s)", key, items.len()));
+                } else {
+                    let more = if files.len() > DIGEST_MAX_FILES { ", …" } else { "" };
+                    files.truncate(DIGEST_MAX_FILES);
+                    facts.push(format!("{}: {} item(s) in {}{}", key, items.len(), files.join(", "), more));
+                }
+            }
+            _ => {}
+        }
+    }
+    facts
+}
+
+/// Compact digest of the tool result at `idx`
+///
+/// The structured result comes from the tool call recorded on the reply
+/// that asked for it, or from the result itself when it was injected as full
+/// JSON; otherwise the digest keeps the start of the text.
+fn digest_tool_result(messages: &[Message], idx: usize) -> String {
+    let content = &messages[idx].content;
+    let recorded = messages[..idx]
+        .iter()
+        .rev()
+        .take_while(|m| m.role != Role::User)
+        .find_map(|m| m.metadata.as_ref().filter(|meta| !meta.tool_calls.is_empty()))
+        .and_then(|meta| {
+            // A reply that made several calls is matched by tool name
+            let name = tool_name_of(content);
+            meta.tool_calls
+                .iter()
+                .rev()
+                .find(|entry| entry.tool_name == name && entry.result.is_some())
+                .or_else(|| meta.tool_calls.last().filter(|_| meta.tool_calls.len() == 1))
+        })
+        .and_then(|entry| entry.result.as_ref().map(|r| (entry.tool_name.clone(), r.success, r.message.clone(), r.data.clone())));
+    let parsed = || {
+        let value: Value = serde_json::from_str(content).ok()?;
+        let tool = value.get("tool")?.as_str()?.to_string();
+        let success = value.get("success").and_then(Value::as_bool).unwrap_or(true);
+        let message = value.get("message").and_then(Value::as_str).unwrap_or_default().to_string();
+        Some((tool, success, message, value.get("data").cloned().unwrap_or(Value::Null)))
+    };
+    let (tool, success, message, data) = recorded
+        .or_else(parsed)
+        .unwrap_or_else(|| (tool_name_of(content), true, content.clone(), Value::Null));
+
+    let mut facts = digest_facts(&data);
+    if !success {
+        facts.insert(0, "failed".to_string());
+    }
+    let message: String = message.lines().next().unwrap_or_default().chars().take(DIGEST_MESSAGE_CHARS).collect();
+    let mut digest = format!("[Tool result digest: {}]", tool);
+    if !facts.is_empty() {
+        digest.push_str(&format!(" {}", facts.join("; ")));
+    }
+    if !message.is_empty() {
+        digest.push_str(&format!(" | {}", message));
+    }
+    digest
+}
+
+/// Apply observation masking: shrink old tool results
+/// This is a zero-cost operation (no LLM needed) that reduces context while
+/// preserving the fact that tools were executed, and with `MaskingStyle::Digest`
+/// the facts the model may need later (paths, counts, exit codes).
+/// 
+/// # Arguments
+/// * `messages` - Mutable reference to message Vec
+/// * `keep_count` - Number of recent tool results to preserve (default: 3)
+/// * `style` - Placeholder or digest
+/// 
+/// # Returns
+/// Number of characters saved by masking
+pub fn apply_observation_masking(messages: &mut [Message], keep_count: usize, style: MaskingStyle) -> usize {
+    let mut chars_saved = 0;
+
+    // Find all tool result messages (typically system messages with tool output)
+    let tool_result_indices: Vec<usize> = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, msg)| is_tool_result(msg) && msg.content.len() > 150)
+        .map(|(idx, _)| idx)
+        .collect();
+
+    // Mask all but the most recent tool results
+    let mask_count = tool_result_indices.len().saturating_sub(keep_count);
+    for &idx in &tool_result_indices[..mask_count] {
+        let replacement = match style {
+            MaskingStyle::Placeholder => format!(
+                "[Tool result for {} omitted for brevity - see earlier context]",
+                tool_name_of(&messages[idx].content)
+            ),
+            MaskingStyle::Digest => digest_tool_result(messages, idx),
+        };
+        let original_len = messages[idx].content.len();
+        if replacement.len() < original_len {
+            chars_saved += original_len - replacement.len();
+            messages[idx].content = replacement;
+        }
+    }
+
+    chars_saved
+}
+
+/// Apply hierarchical context compression based on the current tier
+/// 
+/// This implements the 3-tier approach from LoCoBench-Agent:
+/// - Working (0-40%): Selective pruning (observation masking)
+/// - Compressed (40-60%): Incremental summarization
+/// - Archived (60-80%): Aggressive truncation with anchors
+/// 
+/// # Arguments
+/// * `messages` - Mutable reference to message Vec
+/// * `current_tokens` - Estimated current token count
+/// * `max_tokens` - Maximum available context tokens
+/// * `anchor_messages` - Critical info to preserve from AgentContext
+/// * `masking` - What observation masking leaves of old tool results
+/// 
+/// # Returns
+/// Tuple of (characters_saved, whether compression was applied)
+pub fn apply_hierarchical_compression(
+    messages: &mut Vec<Message>,
+    current_tokens: usize,
+    max_tokens: usize,
+    anchor_messages: &[(String, String)], // (content, reason)
+    masking: MaskingStyle,
+) -> (usize, bool) {
+    let tier = get_compression_tier(current_tokens, max_tokens);
+    
+    tracing::info!(
+        "Hierarchical compression: tier={} ({}% context, {}/{} tokens)",
+        tier.name(),
+        if max_tokens > 0 { (current_tokens as f32 / max_tokens as f32 * 100.0) as usize } else { 0 },
+        current_tokens,
+        max_tokens
+    );
+    
+    let mut total_saved = 0usize;
+    
+    match tier {
+        CompressionTier::Working => {
+            // Tier 1: Selective pruning only - zero-cost observation masking
+            let saved = apply_observation_masking(messages, 3, masking);
+            total_saved += saved;
+            
+            if saved > 0 {
+                tracing::info!("Tier 1 (Working): Observation masking saved {} chars", saved);
+            }
+        }
+        
+        CompressionTier::Compressed => {
+            // Tier 2: Incremental summarization approach
+            // First apply observation masking, then truncate old messages
+            let saved_masking = apply_observation_masking(messages, 2, masking);
+            total_saved += saved_masking;
+
+            // Without a model to ask, the middle messages are only counted
+            if let Some((kept, middle, recent)) = split_compressed_tier(messages) {
+                let summary = compressed_placeholder(middle.len());
+                total_saved += replace_middle(messages, kept, summary, recent);
+                tracing::info!(
+                    "Tier 2 (Compressed): {} msgs dropped, {} total chars saved",
+                    middle.len(),
+                    total_saved
+                );
+            }
+        }
+        
+        CompressionTier::Archived | CompressionTier::Critical => {
+            // Tier 3: Aggressive truncation - keep anchors + last 2 messages
+            let msg_count = messages.len();
+            
+            // Preserve: last 2 messages + anchor messages as system notes
+            let keep_recent = 2.min(msg_count);
+            
+            // Build anchor content
+            let anchor_content: String = if !anchor_messages.is_empty() {
+                format!(
+                    "\n\n[ANCHORED CONTEXT - PRESERVED]\n{}",
+                    anchor_messages
+                        .iter()
+                        .map(|(content, reason)| format!("- {}: {}", reason, content))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            } else {
+                String::new()
+            };
+            
+            // Get last messages
+            let pinned: Vec<_> = messages.iter().filter(|m| m.is_pinned()).cloned().collect();
+            let recent: Vec<_> = messages.iter()
+                .rev()
+                .filter(|m| !m.is_pinned())
+                .take(keep_recent)
+                .cloned()
+                .collect();
+            
+            // Clear and rebuild with the pinned prompt and anchors
+            messages.clear();
+            messages.extend(pinned);
+            
+            if !anchor_content.is_empty() {
+                messages.push(Message::new(
+                    Role::System,
+                    format!(
+                        "[{} previous messages archived - critical context preserved]{}",
+                        msg_count.saturating_sub(keep_recent),
+                        anchor_content
+                    ),
+                ));
+            }
+            
+            messages.extend(recent.into_iter().rev());
+            
+            let saved_archived = msg_count * 300; // Rough estimate
+            total_saved += saved_archived;
+            
+            tracing::info!(
+                "Tier 3 (Archived): Aggressive truncation, {} total chars saved",
+                total_saved
+            );
+        }
+    }
+    
+    (total_saved, total_saved > 0)
+}
+
+/// How long the Compressed tier waits for an LLM summary before using the placeholder
+pub const SUMMARY_BUDGET: Duration = Duration::from_secs(10);
+
+/// Recent messages the Compressed tier keeps verbatim
+const COMPRESSED_KEEP_RECENT: usize = 4;
+
+/// Characters of each message quoted in a summarization request
+const SUMMARY_MESSAGE_CHARS: usize = 300;
+
+/// Upper bound of the transcript sent for summarization; the oldest messages go first
+const SUMMARY_TRANSCRIPT_CHARS: usize = 8000;
+
+/// Split for the Compressed tier: (kept, middle, recent)
+///
+/// Pinned messages and the leading system message are kept, and so are the
+/// last `COMPRESSED_KEEP_RECENT` others. `None` when there is too little in
+/// between to be worth it.
+fn split_compressed_tier(messages: &[Message]) -> Option<(Vec<Message>, Vec<Message>, Vec<Message>)> {
+    let msg_count = messages.len();
+    let preserve_count = COMPRESSED_KEEP_RECENT.min(msg_count);
+    if msg_count <= preserve_count + 2 {
+        return None;
+    }
+
+    let recent_start = messages.iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, m)| !m.is_pinned())
+        .take(preserve_count)
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(msg_count);
+
+    let (mut kept, mut middle, mut recent) = (Vec::new(), Vec::new(), Vec::new());
+    for (i, m) in messages.iter().enumerate() {
+        if m.is_pinned() || (i == 0 && m.role == Role::System) {
+            kept.push(m.clone());
+        } else if i >= recent_start {
+            recent.push(m.clone());
+        } else {
+            middle.push(m.clone());
+        }
+    }
+    Some((kept, middle, recent))
+}
+
+fn compressed_placeholder(middle_count: usize) -> String {
+    format!("[{} messages compressed via incremental summarization]", middle_count)
+}
+
+/// Rebuild the history as kept messages, the summary, then recent messages
+///
+/// # Returns
+/// Rough estimate of the characters saved
+fn replace_middle(messages: &mut Vec<Message>, kept: Vec<Message>, summary: String, recent: Vec<Message>) -> usize {
+    let msg_count = messages.len();
+    messages.clear();
+    messages.extend(kept);
+    messages.push(Message::new(Role::System, summary));
+    messages.extend(recent);
+    msg_count * 200 // Rough estimate
+}
+
+/// Transcript of the messages to summarize, most recent kept when it runs long
+fn summary_transcript(messages: &[Message]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut total = 0;
+    for m in messages.iter().rev() {
+        let role = match m.role {
+            Role::User => "U",
+            Role::Assistant => "A",
+            Role::System => "S",
+            Role::Tool => "T",
+        };
+        let content = if m.content.len() > SUMMARY_MESSAGE_CHARS {
+            format!("{}...", crate::truncate_str(&m.content, SUMMARY_MESSAGE_CHARS))
+        } else {
+            m.content.clone()
+        };
+        let line = format!("[{}]: {}", role, content);
+        total += line.len();
+        if total > SUMMARY_TRANSCRIPT_CHARS && !lines.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    lines.reverse();
+    lines.join("\n")
+}
+
+/// `apply_hierarchical_compression`, with the Compressed tier summarizing for real
+///
+/// `summarize` gets a `build_context_compression_prompt` request over the
+/// dropped messages and returns the model's answer. If it fails, returns
+/// nothing or takes longer than `SUMMARY_BUDGET`, the placeholder is used as
+/// in the synchronous version. Other tiers are unchanged.
+pub async fn apply_hierarchical_compression_llm<F, Fut, E>(
+    messages: &mut Vec<Message>,
+    current_tokens: usize,
+    max_tokens: usize,
+    anchor_messages: &[(String, String)],
+    masking: MaskingStyle,
+    summarize: F,
+) -> (usize, bool)
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String, E>>,
+    E: std::fmt::Display,
+{
+    if get_compression_tier(current_tokens, max_tokens) != CompressionTier::Compressed {
+        return apply_hierarchical_compression(messages, current_tokens, max_tokens, anchor_messages, masking);
+    }
+
+    let mut total_saved = apply_observation_masking(messages, 2, masking);
+    let Some((kept, middle, recent)) = split_compressed_tier(messages) else {
+        return (total_saved, total_saved > 0);
+    };
+
+    let request = format!("{}\n\n---\n{}", build_context_compression_prompt(), summary_transcript(&middle));
+    let summary = match tokio::time::timeout(SUMMARY_BUDGET, summarize(request)).await {
+        Ok(Ok(text)) if !text.trim().is_empty() => {
+            format!("[Summary of {} earlier messages]\n{}", middle.len(), text.trim())
+        }
+        Ok(Ok(_)) => {
+            tracing::warn!("Tier 2 summarization returned nothing, using the placeholder");
+            compressed_placeholder(middle.len())
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("Tier 2 summarization failed, using the placeholder: {}", e);
+            compressed_placeholder(middle.len())
+        }
+        Err(_) => {
+            tracing::warn!("Tier 2 summarization took over {}s, using the placeholder", SUMMARY_BUDGET.as_secs());
+            compressed_placeholder(middle.len())
+        }
+    };
+    total_saved += replace_middle(messages, kept, summary, recent);
+    tracing::info!(
+        "Tier 2 (Compressed): {} msgs summarized, {} total chars saved",
+        middle.len(),
+        total_saved
+    );
+
+    (total_saved, total_saved > 0)
+}
+
+/// Legacy zero-cost pruning: cut long system and tool messages to 500 bytes
+///
+/// Fallback when hierarchical compression did not apply after a truncated
+/// response. Pinned messages are left intact.
+///
+/// # Returns
+/// Number of characters saved
+pub fn prune_long_system_messages(messages: &mut [Message]) -> usize {
+    let mut chars_saved = 0usize;
+    for msg in messages.iter_mut() {
+        if msg.is_pinned() || !matches!(msg.role, Role::System | Role::Tool) || msg.content.len() <= 2000 {
+            continue;
+        }
+        let original_len = msg.content.len();
+        let truncated = format!(
+            "{}...\n\n[Contenu tronqué - {} caractères]",
+            crate::truncate_str(&msg.content, 500),
+            original_len
+        );
+        chars_saved += original_len - truncated.len();
+        msg.content = truncated;
+    }
+    chars_saved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_tiers() {
+        assert_eq!(get_compression_tier(100, 1000), CompressionTier::Working);
+        assert_eq!(get_compression_tier(500, 1000), CompressionTier::Compressed);
+        assert_eq!(get_compression_tier(700, 1000), CompressionTier::Archived);
+        assert_eq!(get_compression_tier(900, 1000), CompressionTier::Critical);
+        assert_eq!(get_compression_tier(1, 0), CompressionTier::Critical);
+    }
+
+    #[test]
+    fn test_archived_keeps_anchors_and_recent_messages() {
+        let mut messages: Vec<Message> = (0..6)
+            .map(|i| Message::new(if i % 2 == 0 { Role::User } else { Role::Assistant }, format!("message {}", i)))
+            .collect();
+        let anchors = vec![("port is 8080".to_string(), "KeyFinding".to_string())];
+
+        let (saved, applied) = apply_hierarchical_compression(&mut messages, 700, 1000, &anchors, MaskingStyle::default());
+        assert!(applied && saved > 0);
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].content.contains("KeyFinding: port is 8080"));
+        assert_eq!(messages[2].content, "message 5");
+    }
+
+    #[test]
+    fn test_goal_anchor_survives_archived_tiers_verbatim() {
+        use crate::agent::loop_runner::{AgentContext, AnchorReason};
+
+        let goal = "Migre la configuration de `server.toml` vers YAML, garde les commentaires et ne touche pas à `[tls]`.";
+        let mut ctx = AgentContext::new();
+        ctx.add_anchor(goal.to_string(), AnchorReason::Goal);
+        ctx.add_anchor("`file_read`: port = 8080".to_string(), AnchorReason::Success);
+        // Anchors are passed the way the chat loop builds them
+        let anchors: Vec<(String, String)> = ctx
+            .get_anchors()
+            .iter()
+            .map(|a| (a.content.clone(), format!("{:?}", a.reason)))
+            .collect();
+
+        for used in [700, 900] {
+            let mut messages = vec![Message::system_prompt("You are an agent."), Message::new(Role::User, goal)];
+            messages.extend((0..12).map(|i| Message::new(Role::Assistant, format!("step {} {}", i, "x".repeat(400)))));
+
+            apply_hierarchical_compression(&mut messages, used, 1000, &anchors, MaskingStyle::default());
+            // The user message itself is archived, its text survives in the anchor block
+            assert!(!messages.iter().any(|m| m.role == Role::User), "tier at {}", used);
+            let archive = messages.iter().find(|m| m.content.contains("[ANCHORED CONTEXT - PRESERVED]")).unwrap();
+            assert!(archive.content.contains(&format!("- Goal: {}\n", goal)), "tier at {}", used);
+            assert!(archive.content.contains("- Success: `file_read`: port = 8080"));
+        }
+    }
+
+    #[test]
+    fn test_post_truncation_keeps_pinned_system_prompt() {
+        let prompt = format!("You are an agent. Tools:\n{}", "- file_read: read a file\n".repeat(200));
+        assert!(prompt.len() > 5000);
+        let tool_output = format!("file_read result:\n{}", "x".repeat(5000));
+        let mut messages = vec![
+            Message::system_prompt(prompt.clone()),
+            Message::new(Role::User, "read the log"),
+            Message::new(Role::System, tool_output.clone()),
+            Message::new(Role::Tool, tool_output.clone()),
+            Message::new(Role::Assistant, "reading"),
+        ];
+
+        let saved = prune_long_system_messages(&mut messages);
+        assert!(saved > 0);
+        assert_eq!(messages[0].content, prompt);
+        assert!(messages[2].content.len() < 600 && messages[2].content.contains("[Contenu tronqué - "));
+        assert!(messages[3].content.len() < 600);
+
+        // Masking and every tier leave it in place too
+        let mut masked = messages.clone();
+        masked[2].content = tool_output.clone();
+        apply_observation_masking(&mut masked, 0, MaskingStyle::Placeholder);
+        assert_eq!(masked[0].content, prompt);
+        assert!(masked[2].content.starts_with("[Tool result for"));
+
+        for used in [500, 700, 900] {
+            let mut tiered = messages.clone();
+            tiered.extend((0..6).map(|i| Message::new(Role::User, format!("message {}", i))));
+            apply_hierarchical_compression(&mut tiered, used, 1000, &[], MaskingStyle::default());
+            assert_eq!(tiered.iter().filter(|m| m.is_pinned()).count(), 1, "tier at {}", used);
+            assert_eq!(tiered[0].content, prompt);
+            assert_eq!(tiered.last().unwrap().content, "message 5");
+        }
+    }
+
+    #[test]
+    fn test_digest_keeps_paths_and_counts() {
+        use crate::agent::loop_runner::ToolHistoryEntry;
+        use crate::agent::runner::format_tool_result;
+        use crate::agent::tools::ToolResult;
+        use crate::storage::settings::ToolResultVerbosity;
+        use crate::types::message::MessageMetadata;
+
+        let file_read = ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "content": "fn main() {}\n".repeat(40),
+                "path": "/work/demo/src/main.rs",
+                "total_lines": 40
+            }),
+            message: "File read: /work/demo/src/main.rs (40 lines)".to_string(),
+        };
+        let grep = ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "matches": [
+                    { "file": "src/main.rs", "line": 3, "text": "fn main() {" },
+                    { "file": "src/lib.rs", "line": 12, "text": "pub fn main_loop() {" },
+                    { "file": "src/lib.rs", "line": 40, "text": "fn main_inner() {" }
+                ],
+                "total_matches": 3,
+                "truncated": false
+            }),
+            message: "3 match(es) found".to_string(),
+        };
+
+        // Full results carry their data; compact ones rely on the recorded call
+        let mut shown = Message::new(Role::Assistant, r#"{"tool": "grep", "params": {"pattern": "fn main"}}"#);
+        shown.metadata = Some(MessageMetadata {
+            tool_calls: vec![ToolHistoryEntry {
+                tool_name: "grep".to_string(),
+                params: serde_json::json!({ "pattern": "fn main" }),
+                result: Some(grep.clone()),
+                error: None,
+                timestamp: 0,
+                duration_ms: 100,
+            }],
+            ..Default::default()
+        });
+        let mut messages = vec![
+            Message::new(Role::User, "where is main?"),
+            Message::new(Role::System, format_tool_result("file_read", &file_read, ToolResultVerbosity::Full)),
+            shown,
+            Message::new(Role::System, format_tool_result("grep", &grep, ToolResultVerbosity::Compact)),
+            Message::new(Role::Assistant, "main is in src/main.rs"),
+        ];
+
+        let saved = apply_observation_masking(&mut messages, 0, MaskingStyle::Digest);
+        assert!(saved > 0);
+        let read_digest = &messages[1].content;
+        assert!(read_digest.starts_with("[Tool result digest: file_read]"), "{}", read_digest);
+        assert!(read_digest.contains("path: /work/demo/src/main.rs"));
+        assert!(read_digest.contains("total_lines: 40"));
+        assert!(!read_digest.contains("fn main() {}"));
+        let grep_digest = &messages[3].content;
+        assert!(grep_digest.contains("total_matches: 3"), "{}", grep_digest);
+        assert!(grep_digest.contains("matches: 3 item(s) in src/main.rs, src/lib.rs"));
+        assert!(grep_digest.ends_with("| 3 match(es) found"));
+
+        // Digests are not digested again
+        assert_eq!(apply_observation_masking(&mut messages, 0, MaskingStyle::Digest), 0);
+    }
+
+    fn compressed_history() -> Vec<Message> {
+        let mut messages = vec![Message::system_prompt("You are an agent.")];
+        messages.extend((0..10).map(|i| {
+            Message::new(if i % 2 == 0 { Role::User } else { Role::Assistant }, format!("message {}", i))
+        }));
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_compressed_tier_inserts_llm_summary() {
+        let mut messages = compressed_history();
+        let (saved, applied) = apply_hierarchical_compression_llm(&mut messages, 500, 1000, &[], MaskingStyle::default(), |request: String| async move {
+            assert!(request.contains("CONTEXT COMPRESSION REQUIRED"));
+            assert!(request.contains("[U]: message 0") && request.contains("[A]: message 5"));
+            // Recent messages stay verbatim and are not summarized
+            assert!(!request.contains("message 6"));
+            Ok::<_, String>("The user asked for messages 0 to 5.".to_string())
+        })
+        .await;
+
+        assert!(applied && saved > 0);
+        assert!(messages[0].is_pinned());
+        assert_eq!(messages[1].content, "[Summary of 6 earlier messages]\nThe user asked for messages 0 to 5.");
+        let recent: Vec<&str> = messages[2..].iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(recent, ["message 6", "message 7", "message 8", "message 9"]);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_tier_falls_back_to_placeholder() {
+        for answer in [Err("engine busy".to_string()), Ok("  ".to_string())] {
+            let mut messages = compressed_history();
+            let (_, applied) = apply_hierarchical_compression_llm(&mut messages, 500, 1000, &[], MaskingStyle::default(), |_| async {
+                answer
+            })
+            .await;
+            assert!(applied);
+            assert_eq!(messages[1].content, "[6 messages compressed via incremental summarization]");
+            assert_eq!(messages.len(), 6);
+        }
+    }
+}
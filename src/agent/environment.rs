@@ -0,0 +1,248 @@
+//! Environment context for the system prompt
+//!
+//! Gathers a compact snapshot of the machine the agent runs on (OS, shell,
+//! date, workspace, git branch, model) so the model stops guessing path
+//! separators and commands. Gathered once per run, not per iteration.
+
+use crate::storage::settings::EnvironmentContextSettings;
+use std::path::{Path, PathBuf};
+
+/// Snapshot of the runtime environment. `None` fields are omitted from the prompt.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvironmentContext {
+    /// OS name and version, e.g. "Windows 11 (x86_64)"
+    pub os: Option<String>,
+    /// Path separator used on this OS
+    pub path_separator: Option<char>,
+    /// Shell used by the command tools
+    pub shell: Option<String>,
+    /// Local date/time with timezone offset
+    pub datetime: Option<String>,
+    /// Workspace root directory
+    pub workspace: Option<PathBuf>,
+    /// Current git branch (`Some("")` for a repo in detached HEAD)
+    pub git_branch: Option<String>,
+    /// Whether the workspace is a git repository (only set when git info is enabled)
+    pub is_git_repo: Option<bool>,
+    /// Loaded model name
+    pub model: Option<String>,
+}
+
+impl EnvironmentContext {
+    /// Gather the environment, skipping everything disabled in `settings`
+    pub fn gather(settings: &EnvironmentContextSettings, model_name: Option<&str>) -> Self {
+        let mut env = Self::default();
+        let windows = cfg!(windows);
+
+        if settings.include_os {
+            let name = match std::env::consts::OS {
+                "windows" => "Windows",
+                "macos" => "macOS",
+                "linux" => "Linux",
+                other => other,
+            };
+            env.os = Some(match os_version() {
+                Some(version) => format!("{} {} ({})", name, version, std::env::consts::ARCH),
+                None => format!("{} ({})", name, std::env::consts::ARCH),
+            });
+            env.path_separator = Some(std::path::MAIN_SEPARATOR);
+        }
+
+        if settings.include_shell {
+            env.shell = Some(if windows { "PowerShell".to_string() } else { "bash".to_string() });
+        }
+
+        if settings.include_datetime {
+            env.datetime = Some(chrono::Local::now().format("%Y-%m-%d %H:%M %:z").to_string());
+        }
+
+        let cwd = std::env::current_dir().ok();
+        if settings.include_workspace {
+            env.workspace = cwd.clone();
+        }
+
+        if settings.include_git {
+            let branch = cwd.as_deref().and_then(find_git_branch);
+            env.is_git_repo = Some(branch.is_some());
+            env.git_branch = branch;
+        }
+
+        if settings.include_model {
+            env.model = model_name.map(|m| m.to_string()).filter(|m| !m.is_empty());
+        }
+
+        env
+    }
+
+    /// Render the compact `## Environment` block (empty when nothing is enabled)
+    pub fn render(&self) -> String {
+        let mut lines = Vec::new();
+
+        if let Some(ref os) = self.os {
+            lines.push(format!("- OS: {}", os));
+        }
+        if let Some(sep) = self.path_separator {
+            lines.push(format!("- Path separator: {}", sep));
+        }
+        if let Some(ref shell) = self.shell {
+            lines.push(format!("- Shell: {}", shell));
+        }
+        if let Some(ref datetime) = self.datetime {
+            lines.push(format!("- Date: {}", datetime));
+        }
+        if let Some(ref workspace) = self.workspace {
+            lines.push(format!("- Workspace: {}", workspace.display()));
+        }
+        match (self.is_git_repo, self.git_branch.as_deref()) {
+            (Some(true), Some("")) => lines.push("- Git: yes (detached HEAD)".to_string()),
+            (Some(true), Some(branch)) => lines.push(format!("- Git: yes (branch {})", branch)),
+            (Some(false), _) => lines.push("- Git: not a repository".to_string()),
+            _ => {}
+        }
+        if let Some(ref model) = self.model {
+            lines.push(format!("- Model: {}", model));
+        }
+
+        if lines.is_empty() {
+            return String::new();
+        }
+
+        format!("## Environment\n{}\n", lines.join("\n"))
+    }
+}
+
+/// Best-effort OS version, without extra dependencies
+fn os_version() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        let release = std::fs::read_to_string("/etc/os-release").ok()?;
+        return release
+            .lines()
+            .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+            .map(|v| v.trim_matches('"').to_string());
+    }
+
+    let output = if cfg!(windows) {
+        std::process::Command::new("cmd").args(["/C", "ver"]).output().ok()?
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("sw_vers").arg("-productVersion").output().ok()?
+    } else {
+        return None;
+    };
+
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Find the enclosing git repository and return its current branch
+///
+/// Reads `.git/HEAD` directly instead of spawning git.
+fn find_git_branch(start: &Path) -> Option<String> {
+    for dir in start.ancestors() {
+        let git = dir.join(".git");
+        let head_path = if git.is_dir() {
+            git.join("HEAD")
+        } else if git.is_file() {
+            // Worktrees/submodules: ".git" is a file containing "gitdir: <path>"
+            let content = std::fs::read_to_string(&git).ok()?;
+            let gitdir = content.trim().strip_prefix("gitdir:")?.trim();
+            dir.join(gitdir).join("HEAD")
+        } else {
+            continue;
+        };
+
+        let head = std::fs::read_to_string(head_path).unwrap_or_default();
+        return Some(
+            head.trim()
+                .strip_prefix("ref: refs/heads/")
+                .unwrap_or("")
+                .to_string(),
+        );
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows_fixture() -> EnvironmentContext {
+        EnvironmentContext {
+            os: Some("Windows 11 (x86_64)".to_string()),
+            path_separator: Some('\\'),
+            shell: Some("PowerShell".to_string()),
+            datetime: Some("2025-03-14 09:30 +01:00".to_string()),
+            workspace: Some(PathBuf::from(r"C:\Users\alice\project")),
+            git_branch: Some("main".to_string()),
+            is_git_repo: Some(true),
+            model: Some("qwen2.5-7b-instruct-q4_k_m".to_string()),
+        }
+    }
+
+    fn unix_fixture() -> EnvironmentContext {
+        EnvironmentContext {
+            os: Some("Ubuntu 24.04 LTS (x86_64)".to_string()),
+            path_separator: Some('/'),
+            shell: Some("bash".to_string()),
+            datetime: Some("2025-03-14 09:30 +00:00".to_string()),
+            workspace: Some(PathBuf::from("/home/alice/project")),
+            git_branch: None,
+            is_git_repo: Some(false),
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_render_windows_fixture() {
+        let block = windows_fixture().render();
+        assert!(block.starts_with("## Environment\n"));
+        assert!(block.contains("- OS: Windows 11 (x86_64)"));
+        assert!(block.contains("- Path separator: \\"));
+        assert!(block.contains("- Shell: PowerShell"));
+        assert!(block.contains(r"- Workspace: C:\Users\alice\project"));
+        assert!(block.contains("- Git: yes (branch main)"));
+        assert!(block.contains("- Model: qwen2.5-7b-instruct-q4_k_m"));
+        // Stays compact (~300 tokens ≈ 1200 chars)
+        assert!(block.len() < 1200);
+    }
+
+    #[test]
+    fn test_render_unix_fixture() {
+        let block = unix_fixture().render();
+        assert!(block.contains("- Path separator: /"));
+        assert!(block.contains("- Shell: bash"));
+        assert!(block.contains("- Workspace: /home/alice/project"));
+        assert!(block.contains("- Git: not a repository"));
+        assert!(!block.contains("- Model:"));
+    }
+
+    #[test]
+    fn test_toggles_disable_everything() {
+        let settings = EnvironmentContextSettings {
+            include_os: false,
+            include_shell: false,
+            include_datetime: false,
+            include_workspace: false,
+            include_git: false,
+            include_model: false,
+        };
+        let env = EnvironmentContext::gather(&settings, Some("model"));
+        assert_eq!(env, EnvironmentContext::default());
+        assert!(env.render().is_empty());
+    }
+
+    #[test]
+    fn test_find_git_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(find_git_branch(dir.path()), None);
+
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git").join("HEAD"), "ref: refs/heads/feature/x\n").unwrap();
+        let nested = dir.path().join("src");
+        std::fs::create_dir(&nested).unwrap();
+        assert_eq!(find_git_branch(&nested).as_deref(), Some("feature/x"));
+    }
+}
@@ -0,0 +1,656 @@
+//! Headless agent loop for scenario tests
+//!
+//! The chat loop needs a loaded model, so it is only exercised by hand. This
+//! harness runs the same steps — prompt, streamed generation, tool call
+//! extraction, permission, execution, compression — against a [`FakeEngine`]
+//! that streams scripted tokens, with [`FakeTool`]s and a permission resolver
+//! that answers without a user. Whole runs are then tested by `cargo test`
+//! with no model files.
+//!
+//! The steps follow `ui::chat` one for one and share its helpers (notices,
+//! compression, garbage and malformed call detection). When the chat loop
+//! changes, change the mirrored step here too.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+use tokio::sync::mpsc as tokio_mpsc;
+use uuid::Uuid;
+
+use crate::agent::compression::{apply_hierarchical_compression, get_compression_tier, CompressionTier};
+use crate::agent::language::{AgentStrings, Locale};
+use crate::agent::loop_runner::{
+    ensure_pending_reply, has_stray_empty_replies, prune_empty_replies, push_notice, AgentContext, AgentLoop,
+    AgentLoopConfig, AnchorReason, ToolHistoryEntry, PERMISSION_DENIED_ERROR,
+};
+use crate::agent::permissions::{PermissionDecision, PermissionRequest};
+use crate::agent::prompt_builder::{prompt_budget, PromptBuilder};
+use crate::agent::prompts::{build_agent_system_prompt, build_error_reflection_prompt, ThinkingGuidance};
+use crate::agent::runner::{
+    extract_tool_call, find_fabricated_tool_output, format_tool_result, is_garbage_text, looks_like_malformed_tool_call,
+};
+use crate::agent::tools::{Tool, ToolError, ToolRegistry, ToolResult};
+use crate::agent::get_call_permission;
+use crate::inference::streaming::StreamToken;
+use crate::storage::settings::{GarbageSensitivity, MaskingStyle, ToolCallFormat, ToolResultVerbosity};
+use crate::types::message::{Message, NoticeKind, Role};
+
+/// Bytes per streamed token chunk
+const CHUNK_BYTES: usize = 8;
+
+type PromptMatcher = Box<dyn Fn(&[Message]) -> bool + Send>;
+
+/// Stand-in engine that streams scripted token sequences
+///
+/// Replies registered with [`FakeEngine::on_prompt`] are used when their
+/// matcher accepts the prompt; otherwise the ordered replies are used in turn.
+/// An exhausted script streams an error, so a scenario that runs longer than
+/// planned fails loudly.
+#[derive(Default)]
+pub struct FakeEngine {
+    rules: Vec<(PromptMatcher, VecDeque<Vec<StreamToken>>)>,
+    replies: VecDeque<Vec<StreamToken>>,
+    /// Prompts received, in order
+    pub prompts: Vec<Vec<Message>>,
+}
+
+impl FakeEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Next ordered reply: `text`, streamed in chunks and ended by `Done`
+    pub fn reply(self, text: &str) -> Self {
+        self.stream(text_tokens(text, StreamToken::Done))
+    }
+
+    /// Next ordered reply: `text`, cut off by `max_tokens`
+    pub fn truncated(self, text: &str) -> Self {
+        let end = StreamToken::Truncated { tokens_generated: 512, max_tokens: 512 };
+        self.stream(text_tokens(text, end))
+    }
+
+    /// Next ordered reply, as raw stream tokens
+    pub fn stream(mut self, tokens: Vec<StreamToken>) -> Self {
+        self.replies.push_back(tokens);
+        self
+    }
+
+    /// Reply with `text` whenever `matcher` accepts the prompt, once per registration
+    pub fn on_prompt(mut self, matcher: impl Fn(&[Message]) -> bool + Send + 'static, text: &str) -> Self {
+        let tokens = text_tokens(text, StreamToken::Done);
+        self.rules.push((Box::new(matcher), VecDeque::from([tokens])));
+        self
+    }
+
+    /// Stream the reply for `prompt`, already queued in the returned channel
+    pub fn generate(&mut self, prompt: &[Message]) -> Receiver<StreamToken> {
+        self.prompts.push(prompt.to_vec());
+        let scripted = self
+            .rules
+            .iter_mut()
+            .find(|(matcher, replies)| !replies.is_empty() && matcher(prompt))
+            .and_then(|(_, replies)| replies.pop_front())
+            .or_else(|| self.replies.pop_front());
+        let tokens = scripted.unwrap_or_else(|| vec![StreamToken::Error("fake engine script exhausted".to_string())]);
+
+        let (tx, rx) = mpsc::channel();
+        for token in tokens {
+            let _ = tx.send(token);
+        }
+        rx
+    }
+}
+
+/// Split `text` into small token chunks on char boundaries, then `end`
+fn text_tokens(text: &str, end: StreamToken) -> Vec<StreamToken> {
+    let mut tokens = Vec::new();
+    let mut chunk = String::new();
+    for ch in text.chars() {
+        chunk.push(ch);
+        if chunk.len() >= CHUNK_BYTES {
+            tokens.push(StreamToken::Token(std::mem::take(&mut chunk)));
+        }
+    }
+    if !chunk.is_empty() {
+        tokens.push(StreamToken::Token(chunk));
+    }
+    tokens.push(end);
+    tokens
+}
+
+/// Tool with programmable results that records its calls
+pub struct FakeTool {
+    name: String,
+    results: Mutex<VecDeque<Result<ToolResult, ToolError>>>,
+    calls: Mutex<Vec<Value>>,
+}
+
+impl FakeTool {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            results: Mutex::new(VecDeque::new()),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue the result of the next call; once the queue is empty calls succeed with "ok"
+    pub fn returning(self, result: Result<ToolResult, ToolError>) -> Self {
+        self.results.lock().unwrap().push_back(result);
+        self
+    }
+
+    /// Queue a successful call returning `message`
+    pub fn succeeding(self, message: &str) -> Self {
+        self.returning(Ok(ToolResult { success: true, data: json!({}), message: message.to_string() }))
+    }
+
+    /// Params of every call so far
+    pub fn calls(&self) -> Vec<Value> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Tool for FakeTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "Scripted test tool"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({"type": "object", "properties": {}})
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.calls.lock().unwrap().push(params);
+        self.results
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Ok(ToolResult { success: true, data: json!({}), message: "ok".to_string() }))
+    }
+}
+
+/// Permission resolver that decides at once: approves everything but the denied tools
+#[derive(Default)]
+pub struct AutoPermissions {
+    denied: HashSet<String>,
+}
+
+impl AutoPermissions {
+    pub fn deny(mut self, tool: &str) -> Self {
+        self.denied.insert(tool.to_string());
+        self
+    }
+
+    pub fn resolve(&self, request: &PermissionRequest) -> PermissionDecision {
+        if self.denied.contains(&request.tool_name) {
+            PermissionDecision::Denied
+        } else {
+            PermissionDecision::Approved
+        }
+    }
+}
+
+/// Why a scenario run ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunEnd {
+    /// Reply without a tool call
+    Answered,
+    Stuck,
+    /// Generated text looked corrupted
+    Garbage,
+    /// Made-up tool results after a correction
+    FabricatedToolOutput,
+    /// Stream errors in a row
+    StreamErrors,
+    /// Truncated again after compressing twice, or nothing left to compress
+    ContextExhausted,
+    MaxIterations,
+}
+
+/// What happened in a scenario run
+#[derive(Debug)]
+pub struct ScenarioOutcome {
+    pub end: RunEnd,
+    pub messages: Vec<Message>,
+    /// Tools the model called (extracted, whether or not they ran)
+    pub tool_calls: Vec<String>,
+    pub iterations: usize,
+    pub compressions: usize,
+    /// Prompts sent to the engine, in order
+    pub prompts: Vec<Vec<Message>>,
+}
+
+impl ScenarioOutcome {
+    /// Last non-empty assistant message
+    pub fn final_reply(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| m.role == Role::Assistant && !m.content.is_empty())
+            .map(|m| m.content.as_str())
+    }
+}
+
+/// A scripted run: engine, tools, permissions and context size
+pub struct Scenario {
+    pub engine: FakeEngine,
+    pub registry: Arc<ToolRegistry>,
+    pub permissions: AutoPermissions,
+    pub max_context_tokens: usize,
+    pub max_iterations: usize,
+}
+
+impl Scenario {
+    pub async fn new(engine: FakeEngine, tools: Vec<Arc<FakeTool>>) -> Self {
+        let registry = Arc::new(ToolRegistry::new());
+        for tool in tools {
+            registry.register(tool).await;
+        }
+        Self {
+            engine,
+            registry,
+            permissions: AutoPermissions::default(),
+            max_context_tokens: 16384,
+            max_iterations: AgentLoopConfig::default().max_iterations,
+        }
+    }
+
+    pub fn with_permissions(mut self, permissions: AutoPermissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_context(mut self, max_context_tokens: usize) -> Self {
+        self.max_context_tokens = max_context_tokens;
+        self
+    }
+
+    /// Run the agent loop on `history`, which ends with the user message
+    pub async fn run(mut self, history: Vec<Message>) -> ScenarioOutcome {
+        let strings = AgentStrings::new(Locale::En);
+        let config = AgentLoopConfig {
+            // Backoff between retries only slows scenarios down
+            enable_retry: false,
+            ..Default::default()
+        };
+        let loop_runner = AgentLoop::new(config, self.registry.clone());
+        let (event_tx, mut event_rx) = tokio_mpsc::channel(64);
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let tools = self.registry.list_tools();
+        let mut messages = history;
+        ensure_pending_reply(&mut messages);
+        let mut ctx = AgentContext::new();
+        if let Some(goal) = messages.iter().rev().find(|m| m.role == Role::User) {
+            ctx.add_anchor(goal.content.clone(), AnchorReason::Goal);
+        }
+        let mut tool_calls = Vec::new();
+        let mut compressions = 0usize;
+        let mut end = RunEnd::MaxIterations;
+
+        while ctx.iteration < self.max_iterations {
+            ctx.iteration += 1;
+
+            if has_stray_empty_replies(&messages) {
+                prune_empty_replies(&mut messages);
+            }
+
+            if ctx.is_stuck() {
+                messages.push(Message::new(Role::Assistant, strings.stuck()));
+                end = RunEnd::Stuck;
+                break;
+            }
+
+            let system_prompt = build_agent_system_prompt(
+                "",
+                &tools,
+                Some(&ctx),
+                None,
+                None,
+                None,
+                Some(Locale::En),
+                ThinkingGuidance::Off,
+                ToolCallFormat::default(),
+            );
+            // Proactive compression, once per run, sized on the whole history
+            let estimated_tokens: usize = system_prompt.len() / 4 + messages.iter().map(|m| m.content.len() / 4).sum::<usize>();
+            let prompt = PromptBuilder::new()
+                .with_system(system_prompt)
+                .with_history(&messages)
+                .with_budget(prompt_budget(self.max_context_tokens))
+                .build();
+            let tier = get_compression_tier(estimated_tokens, self.max_context_tokens);
+            if tier != CompressionTier::Working && compressions == 0 {
+                let (saved, applied) = self.compress(&mut messages, estimated_tokens, &ctx);
+                if applied {
+                    compressions += 1;
+                    push_notice(&mut messages, NoticeKind::Compression, strings.compression_applied(tier, saved), &strings);
+                    continue;
+                }
+            }
+
+            // Stream the reply into the pending message
+            let rx = self.engine.generate(&prompt);
+            ensure_pending_reply(&mut messages);
+            let mut was_truncated = false;
+            let mut garbage = false;
+            while let Ok(token) = rx.recv() {
+                let last = messages.last_mut().expect("pending reply");
+                match token {
+                    StreamToken::Token(text) => {
+                        last.content.push_str(&text);
+                        if is_garbage_text(&last.content, GarbageSensitivity::default()) {
+                            last.content.push_str(&strings.corrupted_output());
+                            garbage = true;
+                            break;
+                        }
+                    }
+                    StreamToken::Stats(_) => {}
+                    StreamToken::Done => break,
+                    StreamToken::Truncated { .. } => {
+                        was_truncated = true;
+                        break;
+                    }
+                    StreamToken::Error(e) => {
+                        ctx.consecutive_errors += 1;
+                        last.content.push_str(&format!("\n\n{} {e}", strings.stream_error_marker()));
+                        break;
+                    }
+                }
+            }
+            if garbage {
+                end = RunEnd::Garbage;
+                break;
+            }
+
+            // Post-truncation compression, then retry
+            if was_truncated {
+                if compressions >= 2 {
+                    end = RunEnd::ContextExhausted;
+                    break;
+                }
+                let estimated_tokens: usize = messages.iter().map(|m| m.content.len() / 4).sum();
+                let tier = get_compression_tier(estimated_tokens, self.max_context_tokens);
+                let (saved, applied) = self.compress(&mut messages, estimated_tokens, &ctx);
+                if !applied {
+                    end = RunEnd::ContextExhausted;
+                    break;
+                }
+                compressions += 1;
+                push_notice(&mut messages, NoticeKind::Compression, strings.compression_applied(tier, saved), &strings);
+                continue;
+            }
+
+            let last_text = messages.last().map(|m| m.content.clone()).unwrap_or_default();
+            if last_text.contains(strings.stream_error_marker()) {
+                if ctx.consecutive_errors < 3 {
+                    push_notice(&mut messages, NoticeKind::StreamRetry, strings.stream_error_retry(), &strings);
+                    continue;
+                }
+                end = RunEnd::StreamErrors;
+                break;
+            }
+            ctx.consecutive_errors = 0;
+            ctx.last_response = Some(last_text.clone());
+
+            let Some(call) = extract_tool_call(&last_text) else {
+                if let Some(start) = find_fabricated_tool_output(&last_text) {
+                    ctx.hallucinated_tool_outputs += 1;
+                    let last = messages.last_mut().expect("pending reply");
+                    last.content.truncate(start);
+                    last.content.truncate(last.content.trim_end().len());
+                    if ctx.hallucinated_tool_outputs < 2 {
+                        push_notice(&mut messages, NoticeKind::FabricatedToolOutput, strings.fabricated_tool_output(), &strings);
+                        continue;
+                    }
+                    last.content.push_str(&strings.fabricated_tool_output_stopped());
+                    end = RunEnd::FabricatedToolOutput;
+                    break;
+                }
+                if looks_like_malformed_tool_call(&last_text) && ctx.consecutive_errors < 2 {
+                    ctx.consecutive_errors += 1;
+                    push_notice(&mut messages, NoticeKind::InvalidToolCall, strings.invalid_tool_json(), &strings);
+                    continue;
+                }
+                end = RunEnd::Answered;
+                break;
+            };
+            tool_calls.push(call.tool.clone());
+            // The reply keeps the model's text; calls are recorded on it
+            let reply_index = messages.len().saturating_sub(1);
+
+            let request = PermissionRequest {
+                id: Uuid::new_v4(),
+                tool_name: call.tool.clone(),
+                operation: "execute".to_string(),
+                target: call.params.to_string(),
+                level: get_call_permission(&call.tool, &call.params),
+                params: call.params.clone(),
+                timestamp: Utc::now(),
+                preview: None,
+            };
+            if self.permissions.resolve(&request) == PermissionDecision::Denied {
+                ctx.tool_history.push(ToolHistoryEntry {
+                    tool_name: call.tool.clone(),
+                    params: call.params.clone(),
+                    result: None,
+                    error: Some(PERMISSION_DENIED_ERROR.to_string()),
+                    timestamp: Utc::now().timestamp() as u64,
+                    duration_ms: 0,
+                });
+                record_last_call(&mut messages, reply_index, &ctx);
+                push_notice(&mut messages, NoticeKind::ToolRefused, strings.tool_refused_hint(&call.tool), &strings);
+                continue;
+            }
+
+            match loop_runner.execute_tool_with_retry(&call, &mut ctx, &event_tx).await {
+                Ok(result) => {
+                    record_last_call(&mut messages, reply_index, &ctx);
+                    messages.push(Message::new(
+                        Role::System,
+                        format_tool_result(&call.tool, &result, ToolResultVerbosity::default()),
+                    ));
+                    ensure_pending_reply(&mut messages);
+                }
+                Err(e) => {
+                    ctx.consecutive_errors += 1;
+                    record_last_call(&mut messages, reply_index, &ctx);
+                    if ctx.consecutive_errors < 4 {
+                        push_notice(&mut messages, NoticeKind::Reflection, build_error_reflection_prompt(&call.tool, &e), &strings);
+                    } else {
+                        push_notice(&mut messages, NoticeKind::TooManyErrors, strings.too_many_errors(ctx.consecutive_errors), &strings);
+                    }
+                }
+            }
+        }
+
+        ScenarioOutcome {
+            end,
+            messages,
+            tool_calls,
+            iterations: ctx.iteration,
+            compressions,
+            prompts: self.engine.prompts,
+        }
+    }
+
+    fn compress(&self, messages: &mut Vec<Message>, estimated_tokens: usize, ctx: &AgentContext) -> (usize, bool) {
+        let anchors: Vec<(String, String)> = ctx
+            .get_anchors()
+            .iter()
+            .map(|a| (a.content.clone(), format!("{:?}", a.reason)))
+            .collect();
+        apply_hierarchical_compression(messages, estimated_tokens, self.max_context_tokens, &anchors, MaskingStyle::default())
+    }
+}
+
+/// Record the latest call of the run on the reply that made it, as the chat does
+fn record_last_call(messages: &mut [Message], reply_index: usize, ctx: &AgentContext) {
+    if let (Some(reply), Some(entry)) = (messages.get_mut(reply_index), ctx.tool_history.last()) {
+        reply.metadata.get_or_insert_with(Default::default).tool_calls.push(entry.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(text: &str) -> Vec<Message> {
+        vec![Message::new(Role::User, text)]
+    }
+
+    fn prompt_text(prompt: &[Message]) -> String {
+        prompt.iter().map(|m| m.content.as_str()).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Distinct filler, so it never reads as repeated garbage
+    fn filler(words: usize, tag: &str) -> String {
+        (0..words).map(|i| format!("{}{} ", tag, i)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_simple_tool_use() {
+        let file_read = Arc::new(FakeTool::new("file_read").succeeding("fn main() {}"));
+        let engine = FakeEngine::new()
+            .reply(r#"{"tool": "file_read", "params": {"path": "src/main.rs"}}"#)
+            .reply("The file only defines an empty main.");
+        let outcome = Scenario::new(engine, vec![file_read.clone()]).await.run(user("What is in src/main.rs?")).await;
+
+        assert_eq!(outcome.end, RunEnd::Answered);
+        assert_eq!(outcome.tool_calls, vec!["file_read"]);
+        assert_eq!(file_read.calls(), vec![json!({"path": "src/main.rs"})]);
+        assert_eq!(outcome.final_reply(), Some("The file only defines an empty main."));
+        // The second prompt carries the tool result
+        assert!(prompt_text(&outcome.prompts[1]).contains("fn main() {}"));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_is_retried() {
+        let file_read = Arc::new(FakeTool::new("file_read").succeeding("hello"));
+        let engine = FakeEngine::new()
+            .reply(r#"{"tool": "file_read", "params": {"path": "a.txt",}}"#)
+            .reply(r#"{"tool": "file_read", "params": {"path": "a.txt"}}"#)
+            .reply("It says hello.");
+        let outcome = Scenario::new(engine, vec![file_read.clone()]).await.run(user("Read a.txt")).await;
+
+        assert_eq!(outcome.end, RunEnd::Answered);
+        assert_eq!(outcome.tool_calls, vec!["file_read"]);
+        assert_eq!(file_read.calls().len(), 1);
+        let strings = AgentStrings::new(Locale::En);
+        assert!(prompt_text(&outcome.prompts[1]).contains(&strings.invalid_tool_json()));
+    }
+
+    #[tokio::test]
+    async fn test_fabricated_tool_output_is_corrected() {
+        let file_read = Arc::new(FakeTool::new("file_read").succeeding("hello"));
+        let engine = FakeEngine::new()
+            .reply("Reading it.\n✅ file_read: a.txt\nhello")
+            .reply(r#"{"tool": "file_read", "params": {"path": "a.txt"}}"#)
+            .reply("It says hello.");
+        let outcome = Scenario::new(engine, vec![file_read.clone()]).await.run(user("Read a.txt")).await;
+
+        assert_eq!(outcome.end, RunEnd::Answered);
+        assert_eq!(file_read.calls().len(), 1);
+        let retry = prompt_text(&outcome.prompts[1]);
+        assert!(retry.contains(&AgentStrings::new(Locale::En).fabricated_tool_output()), "{}", retry);
+        assert!(!retry.contains("✅ file_read"), "{}", retry);
+
+        // A second made-up result ends the run with what was real
+        let engine = FakeEngine::new()
+            .reply("✅ file_read: a.txt\nhello")
+            .reply("Done.\n=== OUTPUT ===\nhello")
+            .reply("never sent");
+        let outcome = Scenario::new(engine, vec![file_read.clone()]).await.run(user("Read a.txt")).await;
+        assert_eq!(outcome.end, RunEnd::FabricatedToolOutput);
+        assert_eq!(outcome.prompts.len(), 2);
+        let reply = outcome.final_reply().unwrap_or_default();
+        assert!(reply.starts_with("Done.\n\n⚠️"), "{}", reply);
+        assert_eq!(file_read.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_permission_denial_recovery() {
+        let bash = Arc::new(FakeTool::new("bash"));
+        let file_read = Arc::new(FakeTool::new("file_read").succeeding("[package]\nname = \"demo\""));
+        let engine = FakeEngine::new()
+            .reply(r#"{"tool": "bash", "params": {"command": "cat Cargo.toml"}}"#)
+            .on_prompt(
+                |prompt| prompt_text(prompt).contains("The tool bash was refused"),
+                r#"{"tool": "file_read", "params": {"path": "Cargo.toml"}}"#,
+            )
+            .reply("The crate is called demo.");
+        let outcome = Scenario::new(engine, vec![bash.clone(), file_read.clone()])
+            .await
+            .with_permissions(AutoPermissions::default().deny("bash"))
+            .run(user("What is the crate name?"))
+            .await;
+
+        assert_eq!(outcome.end, RunEnd::Answered, "{:?}", outcome.messages);
+        assert_eq!(outcome.tool_calls, vec!["bash", "file_read"]);
+        assert!(bash.calls().is_empty());
+        assert_eq!(file_read.calls().len(), 1);
+        assert_eq!(outcome.final_reply(), Some("The crate is called demo."));
+    }
+
+    #[tokio::test]
+    async fn test_stuck_loop_aborts() {
+        let grep = Arc::new(FakeTool::new("grep"));
+        let call = r#"{"tool": "grep", "params": {"pattern": "TODO"}}"#;
+        let engine = FakeEngine::new().reply(call).reply(call).reply(call).reply(call).reply(call);
+        let outcome = Scenario::new(engine, vec![grep.clone()]).await.run(user("Find the TODOs")).await;
+
+        assert_eq!(outcome.end, RunEnd::Stuck);
+        assert_eq!(grep.calls().len(), 3);
+        assert_eq!(outcome.final_reply(), Some(AgentStrings::new(Locale::En).stuck().as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_truncation_compresses_and_retries() {
+        let mut history = Vec::new();
+        for i in 0..10 {
+            let role = if i % 2 == 0 { Role::User } else { Role::Assistant };
+            history.push(Message::new(role, filler(180, &format!("m{}w", i))));
+        }
+        history.push(Message::new(Role::User, "Summarize everything above"));
+
+        let engine = FakeEngine::new()
+            .truncated(&filler(1500, "partial"))
+            .reply("Here is the summary.");
+        let outcome = Scenario::new(engine, Vec::new()).await.with_context(16384).run(history).await;
+
+        assert_eq!(outcome.end, RunEnd::Answered);
+        assert_eq!(outcome.compressions, 1);
+        assert_eq!(outcome.prompts.len(), 2);
+        let retry = prompt_text(&outcome.prompts[1]);
+        assert!(retry.contains("messages compressed via incremental summarization"), "{}", retry);
+        assert!(!retry.contains("m0w0 "));
+        assert_eq!(outcome.final_reply(), Some("Here is the summary."));
+    }
+
+    #[tokio::test]
+    async fn test_garbage_text_aborts() {
+        let file_read = Arc::new(FakeTool::new("file_read"));
+        let garbage = "assistantcommentary toolresult: ".repeat(12);
+        let engine = FakeEngine::new().reply(&garbage).reply("never sent");
+        let outcome = Scenario::new(engine, vec![file_read.clone()]).await.run(user("Hi")).await;
+
+        assert_eq!(outcome.end, RunEnd::Garbage);
+        assert_eq!(outcome.prompts.len(), 1);
+        assert!(file_read.calls().is_empty());
+        // What was generated stays, followed by the warning
+        let reply = outcome.final_reply().unwrap_or_default();
+        assert!(reply.starts_with("assistantcommentary toolresult: "), "{}", reply);
+        assert!(reply.ends_with(&AgentStrings::new(Locale::En).corrupted_output()), "{}", reply);
+    }
+}
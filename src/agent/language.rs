@@ -0,0 +1,906 @@
+//! Conversation language detection and localized agent status messages
+//!
+//! The agent loop injects its own messages (tool indicators, errors, recovery
+//! hints). These follow the language the user is actually writing in, detected
+//! from recent user messages, with the settings language as fallback, unless
+//! the `agent_language` setting fixes one. Tool results follow the same
+//! language, set for each run with `set_tool_language`: the tools take their
+//! messages from the catalog below with `tool_text!`.
+
+use crate::agent::compression::CompressionTier;
+pub use crate::storage::settings::Locale;
+use crate::types::message::NoticeKind;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether tools write their results in English
+static TOOLS_IN_ENGLISH: AtomicBool = AtomicBool::new(true);
+
+/// Set the language tools write their results in
+pub fn set_tool_language(lang: Locale) {
+    TOOLS_IN_ENGLISH.store(lang == Locale::En, Ordering::Relaxed);
+}
+
+/// Language tools write their results in; English until a run sets it
+pub fn tool_language() -> Locale {
+    if TOOLS_IN_ENGLISH.load(Ordering::Relaxed) {
+        Locale::En
+    } else {
+        Locale::Fr
+    }
+}
+
+/// Look up a tool message in the tool language; arguments fill its `{}` in order
+macro_rules! tool_text {
+    ($key:literal) => {
+        $crate::agent::language::tool_string($key)
+    };
+    ($key:literal, $($arg:expr),+ $(,)?) => {
+        $crate::agent::language::fill(
+            $crate::agent::language::tool_string($key),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+pub(crate) use tool_text;
+
+/// Messages of the built-in tools: `(key, English, French)`
+const TOOL_CATALOG: &[(&str, &str, &str)] = &[
+    // Filesystem errors
+    ("file.read_error", "Error reading file: {}", "Erreur lecture fichier: {}"),
+    ("file.open_error", "Error opening file: {}", "Erreur ouverture fichier: {}"),
+    ("file.write_error", "Error writing: {}", "Erreur écriture: {}"),
+    ("dir.create_error", "Error creating directory: {}", "Erreur création dossier: {}"),
+    ("dir.read_error", "Error reading directory: {}", "Erreur lecture dossier: {}"),
+    // file_read
+    ("file_read.hex", "Hex dump: {} (bytes {}-{} of {})", "Dump hex: {} (octets {}-{} sur {})"),
+    ("file_read.binary", "Binary file: {} ({} bytes, {} shown as hex)", "Fichier binaire: {} ({} octets, {} affichés en hex)"),
+    ("file_read.preview", "File preview: {} ({} lines, {} bytes)", "Aperçu du fichier: {} ({} lignes, {} octets)"),
+    ("file_read.done", "File read: {} ({} lines){}", "Fichier lu: {} ({} lignes){}"),
+    ("file_read.lines", " (lines {}-{})", " (lignes {}-{})"),
+    ("file_read.lines_truncated", " (lines {}-{}, rest truncated)", " (lignes {}-{}, suite tronquée)"),
+    ("file_read.from_line", " (from line {})", " (depuis ligne {})"),
+    ("file_read.empty_range", " (no lines in this range)", " (aucune ligne dans cette plage)"),
+    // file_write, file_list, grep, glob
+    ("file_write.done", "File written: {} ({} bytes, {} lines)", "Fichier écrit: {} ({} octets, {} lignes)"),
+    ("file_list.done", "{} entries in {}", "{} éléments dans {}"),
+    ("file_list.recursive", "{} files found recursively{}", "{} fichiers trouvés récursivement{}"),
+    ("file_list.truncated", " (list truncated)", " (liste tronquée)"),
+    ("grep.done", "{} match(es) found{}", "{} correspondance(s) trouvée(s){}"),
+    ("grep.truncated", " (results truncated)", " (résultats tronqués)"),
+    ("glob.done", "{} file(s) found for '{}'", "{} fichier(s) trouvé(s) pour '{}'"),
+    // think, todo_write
+    ("think.done", "Thought recorded. Continue your reasoning.", "Réflexion enregistrée. Continue ton raisonnement."),
+    (
+        "todo.done",
+        "Plan updated: {} tasks ({} pending, {} in progress, {} completed)",
+        "Plan mis à jour: {} tâches ({} en attente, {} en cours, {} terminées)",
+    ),
+    // Commands
+    ("command.denied", "Command denied by the command policy: {}", "Commande refusée par la politique de commandes : {}"),
+    ("command.done", "Command executed", "Commande exécutée"),
+    ("command.failed", "Command failed (code: {})", "Commande échouée (code: {})"),
+    ("command.error", "Execution error: {}", "Erreur exécution: {}"),
+    // file_edit, file_multi_edit, file_patch_lines
+    (
+        "file.conflict",
+        "Conflict: {} changed on disk since the approved preview. Read it again before editing it.",
+        "Conflit : {} a changé sur le disque depuis l'aperçu approuvé. Relisez-le avant de le modifier.",
+    ),
+    ("file_edit.done", "File edited: {} (1 replacement, mode: {})", "Fichier édité: {} (1 remplacement, mode: {})"),
+    (
+        "file_edit.not_found",
+        "old_string not found in the file. Check the indentation and whitespace.",
+        "old_string introuvable dans le fichier. Vérifiez l'indentation et les espaces.",
+    ),
+    (
+        "file_edit.ambiguous",
+        "old_string found {} times. Add more context to make it unique, or use replace_all=true.",
+        "old_string trouvé {} fois. Ajoutez plus de contexte pour le rendre unique, ou utilisez replace_all=true.",
+    ),
+    ("file_multi_edit.done", "File edited: {} ({} edits — {}; {} lines)", "Fichier édité: {} ({} edits — {}; {} lignes)"),
+    ("file_multi_edit.not_found", "edit {}: old_string not found", "edit {}: old_string introuvable"),
+    (
+        "file_multi_edit.ambiguous",
+        "edit {}: old_string found {} times, add context or replace_all=true",
+        "edit {}: old_string trouvé {} fois, ajoutez du contexte ou replace_all=true",
+    ),
+    (
+        "file_multi_edit.mismatches",
+        "{} edit(s) of {} do not match, file unchanged:\n{}",
+        "{} edit(s) sur {} ne correspondent pas, fichier inchangé:\n{}",
+    ),
+    (
+        "file_multi_edit.stale",
+        "edit {}: old_string no longer matches after the previous edits ({} occurrence(s)), file unchanged",
+        "edit {}: old_string ne correspond plus après les edits précédents ({} occurrence(s)), fichier inchangé",
+    ),
+    ("file_patch_lines.inserted", "{} line(s) inserted before line {}", "{} ligne(s) insérée(s) avant la ligne {}"),
+    ("file_patch_lines.replaced", "lines {}-{} replaced", "lignes {}-{} remplacées"),
+    (
+        "file_patch_lines.done",
+        "File modified: {} ({}, {} lines, {} lines in total)",
+        "Fichier modifié: {} ({}, {} lignes, {} lignes au total)",
+    ),
+    (
+        "file_patch_lines.hash_mismatch",
+        "Hash mismatch for lines {}-{}: expected '{}', found '{}'. The lines changed since file_read, read them again.",
+        "Hash mismatch pour les lignes {}-{}: attendu '{}', trouvé '{}'. Les lignes ont changé depuis file_read, relisez-les.",
+    ),
+    // file_create, file_delete, file_move, file_copy, file_info, directory_create, file_search
+    (
+        "file_create.exists",
+        "The file '{}' already exists. Use overwrite=true to replace it, or file_edit to modify it.",
+        "Le fichier '{}' existe déjà. Utilisez overwrite=true pour écraser, ou file_edit pour modifier.",
+    ),
+    ("file_create.error", "Cannot create the file: {}", "Impossible de créer le fichier: {}"),
+    ("file_create.done", "File created: {} ({} lines, {} bytes)", "Fichier créé: {} ({} lignes, {} octets)"),
+    ("path.not_found", "The path '{}' does not exist", "Le chemin '{}' n'existe pas"),
+    ("path.source_not_found", "Source '{}' does not exist", "Source '{}' n'existe pas"),
+    ("path.destination_exists", "Destination '{}' already exists", "Destination '{}' existe déjà"),
+    ("path.unsupported", "Unsupported path type: {}", "Type de chemin non supporté: {}"),
+    ("file_delete.error", "Cannot delete: {}", "Impossible de supprimer: {}"),
+    ("file_delete.file_done", "File deleted: {}", "Fichier supprimé: {}"),
+    ("file_delete.dir_error", "Cannot delete the directory: {}", "Impossible de supprimer le dossier: {}"),
+    ("file_delete.not_empty", "Directory not empty. Use recursive=true: {}", "Dossier non vide. Utilisez recursive=true: {}"),
+    ("file_delete.dir_done", "Directory deleted: {}", "Dossier supprimé: {}"),
+    ("file_move.error", "Cannot move: {}", "Impossible de déplacer: {}"),
+    ("file_move.done", "Moved: {} -> {}", "Déplacé: {} -> {}"),
+    ("file_copy.error", "Cannot copy: {}", "Impossible de copier: {}"),
+    ("file_copy.done", "Copied: {} -> {} ({} bytes)", "Copié: {} -> {} ({} octets)"),
+    ("file_info.error", "Cannot read the metadata: {}", "Impossible de lire les métadonnées: {}"),
+    ("file_info.read_only", "read-only", "lecture seule"),
+    ("file_info.read_write", "read/write", "lecture/écriture"),
+    ("file_info.lines", ", {} lines", ", {} lignes"),
+    ("directory_create.exists", "Directory already exists: {}", "Le dossier existe déjà: {}"),
+    ("directory_create.file_exists", "A file already exists at this path: {}", "Un fichier existe déjà à ce chemin: {}"),
+    ("directory_create.done", "Directory created: {}", "Dossier créé: {}"),
+    ("file_search.done", "{} result(s) for \"{}\"{}", "{} résultat(s) pour \"{}\"{}"),
+    // Background jobs
+    (
+        "job.too_many",
+        "{} jobs are already running: wait for one or stop it with job_kill",
+        "{} jobs tournent déjà : attendez-en un ou arrêtez-le avec job_kill",
+    ),
+    ("job.unknown", "Unknown job '{}' (jobs: {})", "Job inconnu '{}' (jobs: {})"),
+    ("job.none", "none", "aucun"),
+    ("job.killed", "Job {} stopped", "Job {} arrêté"),
+    ("job_start.done", "Job {} started (PID: {}): {}", "Job {} démarré (PID: {}): {}"),
+    ("job_status.list", "{} job(s), {} running", "{} job(s), {} en cours"),
+    ("job_status.running", "Job {} running ({} s)", "Job {} en cours ({} s)"),
+    ("job_status.exited", "Job {} exited (code: {})", "Job {} terminé (code: {})"),
+    ("job_status.signaled", "Job {} terminated by a signal", "Job {} terminé par un signal"),
+    ("job_logs.unknown_stream", "Unknown stream '{}' (all, stdout, stderr)", "stream inconnu '{}' (all, stdout, stderr)"),
+    ("job_logs.dropped", " ({} older lines dropped)", " ({} lignes plus anciennes supprimées)"),
+    ("job_logs.done", "{}: {} line(s){}", "{}: {} ligne(s){}"),
+    ("job_kill.not_running", "Job {} was no longer running ({})", "Job {} n'était plus en cours ({})"),
+    // Git
+    ("git.run_error", "Cannot run git: {}", "Impossible d'exécuter git: {}"),
+    ("git.not_a_repository", "Not a git repository: {}", "Pas un dépôt git: {}"),
+    ("git.index_copy_error", "Cannot copy the index: {}", "Copie de l'index impossible: {}"),
+    ("git.invalid_ref", "Invalid ref: '{}'", "ref invalide: '{}'"),
+    ("git_status.done", "Branch: {} | {} file(s) changed", "Branche: {} | {} fichier(s) modifié(s)"),
+    ("git_diff.done", "Diff: {} file(s) changed", "Diff: {} fichier(s) modifié(s)"),
+    ("git_log.done", "{} commit(s) shown", "{} commit(s) affichés"),
+    ("git_commit.done", "Commit created: {}", "Commit créé: {}"),
+    ("git_commit.created", "Commit {} created: {}", "Commit {} créé: {}"),
+    (
+        "git_commit.nothing_staged",
+        "Nothing to commit: no staged changes. Pass `paths` to stage files.",
+        "Rien à committer: aucun changement indexé. Passez `paths` pour indexer des fichiers.",
+    ),
+    (
+        "git_commit.nothing_in_paths",
+        "Nothing to commit: the given paths have no changes.",
+        "Rien à committer: les chemins donnés n'ont aucun changement.",
+    ),
+    (
+        "git_commit.diff_too_large",
+        "Staged diff too large ({} bytes, limit {}). Narrow `paths` or pass force: true.\n{}",
+        "Diff indexé trop gros ({} octets, limite {}). Réduisez `paths` ou passez force: true.\n{}",
+    ),
+    (
+        "git_commit.no_message",
+        "Cannot generate the commit message (no model loaded?). Pass an explicit message.",
+        "Génération du message de commit impossible (aucun modèle chargé ?). Passez un message explicite.",
+    ),
+    ("git_branch_diff.detached", " (detached HEAD)", " (HEAD détachée)"),
+    ("git_branch_diff.done", "{}...{}{}: {} commit(s), {} file(s), +{} -{}", "{}...{}{}: {} commit(s), {} fichier(s), +{} -{}"),
+    ("git_branch.list", "{} branch(es)", "{} branche(s)"),
+    ("git_branch.create_error", "Cannot create the branch: {}", "Impossible de créer la branche: {}"),
+    ("git_branch.created", "Branch created and checked out: {}", "Branche créée et activée: {}"),
+    ("git_branch.switch_error", "Cannot switch branch: {}", "Impossible de changer de branche: {}"),
+    ("git_branch.switched", "Switched to branch: {}", "Basculé sur la branche: {}"),
+    ("git_branch.delete_error", "Cannot delete the branch: {}", "Impossible de supprimer la branche: {}"),
+    ("git_branch.deleted", "Branch deleted: {}", "Branche supprimée: {}"),
+    ("tool.unknown_action", "Unknown action: {}", "Action inconnue: {}"),
+    ("file_multi_edit.count", "edit {}: {} replacement(s)", "edit {}: {} remplacement(s)"),
+    // diff, find_replace, patch, count_lines
+    ("dev.read_path_error", "Cannot read {}: {}", "Impossible de lire {}: {}"),
+    ("dev.write_path_error", "Cannot write {}: {}", "Impossible d'écrire {}: {}"),
+    ("diff.done", "Diff: {} change(s) between {} ({} lines) and {} ({} lines)", "Diff: {} changement(s) entre {} ({} lignes) et {} ({} lignes)"),
+    ("diff.none", "No differences found.", "Aucune différence trouvée."),
+    ("find_replace.done", "{}{} replacement(s) in {} file(s)", "{}{} remplacement(s) dans {} fichier(s)"),
+    ("patch.done", "Patch applied to {}", "Patch appliqué à {}"),
+    ("count_lines.done", "{}: {} lines, {} words, {} characters", "{}: {} lignes, {} mots, {} caractères"),
+    // Language servers
+    ("lsp.start_error", "Cannot start '{}': {}", "Impossible de démarrer '{}': {}"),
+    ("lsp.no_stdin", "Cannot reach the language server's stdin", "Impossible d'accéder au stdin du serveur LSP"),
+    ("lsp.no_stdout", "Cannot reach the language server's stdout", "Impossible d'accéder au stdout du serveur LSP"),
+    ("lsp.write_error", "Error writing to {}: {}", "Erreur écriture vers {}: {}"),
+    ("lsp.stopped", "The {} server stopped", "Le serveur {} s'est arrêté"),
+    ("lsp_diagnostics.done", "{}: {} error(s), {} warning(s)", "{}: {} erreur(s), {} avertissement(s)"),
+    (
+        "lsp_diagnostics.timeout",
+        "{}: no diagnostics received from {} in {}s (still indexing?), try again",
+        "{}: aucun diagnostic reçu de {} en {}s (indexation en cours ?), réessayez",
+    ),
+    ("lsp_hover.empty", "No information at {}:{}:{}", "Aucune information à {}:{}:{}"),
+    ("lsp_hover.done", "Hover at {}:{}:{}", "Survol de {}:{}:{}"),
+    // Cargo
+    ("cargo.not_installed", "cargo not found: install Rust (https://rustup.rs)", "cargo introuvable: installez Rust (https://rustup.rs)"),
+    ("cargo.run_error", "Cannot run cargo: {}", "Impossible d'exécuter cargo: {}"),
+    (
+        "cargo.no_project",
+        "No Cargo project here: {}. Pass working_dir with the folder of the Cargo.toml.",
+        "Pas de projet Cargo ici: {}. Passez working_dir avec le dossier du Cargo.toml.",
+    ),
+    ("cargo_check.done", "cargo check {}: {} error(s), {} warning(s)", "cargo check {}: {} erreur(s), {} avertissement(s)"),
+    ("cargo_check.ok", "ok", "ok"),
+    ("cargo_check.failed", "failed", "en échec"),
+    ("cargo_test.done", "cargo test: {} passed, {} failed, {} ignored", "cargo test: {} réussi(s), {} échoué(s), {} ignoré(s)"),
+    ("cargo_test.build_failed", "cargo test: build failed, {} error(s)", "cargo test: échec de compilation, {} erreur(s)"),
+    // CSV
+    ("csv.empty", "Empty file", "Fichier vide"),
+    ("csv.unknown_column", "Unknown column '{}' (columns: {})", "Colonne inconnue '{}' (colonnes: {})"),
+    ("csv.unknown_operator", "Unknown operator '{}' (valid: {})", "Opérateur inconnu '{}' (valides: {})"),
+    ("csv.unknown_aggregate", "Unknown aggregate '{}' (count, sum, mean)", "Agrégat inconnu '{}' (count, sum, mean)"),
+    ("csv.unknown_operation", "Unknown operation '{}' (preview, stats, filter, aggregate)", "Opération inconnue '{}' (preview, stats, filter, aggregate)"),
+    ("csv.preview", "{} column(s), first {} rows{}", "{} colonne(s), {} premières lignes{}"),
+    ("csv.end_of_file", " (end of file)", " (fin du fichier)"),
+    ("csv.stats", "{} row(s), {} column(s)", "{} ligne(s), {} colonne(s)"),
+    ("csv.aggregate", "{} group(s), {}{}", "{} groupe(s), {}{}"),
+    ("csv.filter", "{} of {} row(s) match ({} shown)", "{} ligne(s) sur {} correspondent ({} affichée(s))"),
+    (
+        "csv.too_many_groups",
+        "More than {} groups: pick a less detailed group-by column",
+        "Plus de {} groupes : choisissez une colonne de regroupement moins détaillée",
+    ),
+    ("csv.invalid_delimiter", "Invalid delimiter '{}': a single ASCII character", "Séparateur invalide '{}': un seul caractère ASCII"),
+    // PDF
+    ("pdf.not_found", "The file '{}' does not exist", "Le fichier '{}' n'existe pas"),
+    ("pdf.extract_error", "PDF extraction error: {}", "Erreur extraction PDF: {}"),
+    ("pdf.read_error", "PDF read error: {}", "Erreur lecture PDF: {}"),
+    ("pdf.save_error", "PDF save error: {}", "Erreur sauvegarde PDF: {}"),
+    ("pdf.font_error", "Font error: {}", "Erreur police: {}"),
+    (
+        "pdf_read.truncated",
+        "\n[... {} remaining pages truncated to save context. Use the 'pages' parameter for specific pages.]\n",
+        "\n[... {} pages restantes tronquées pour économiser le contexte. Utilisez le paramètre 'pages' pour des pages spécifiques.]\n",
+    ),
+    (
+        "pdf_read.no_text",
+        "(No extractable text - the PDF may contain images or be scanned)",
+        "(Aucun texte extractible - le PDF peut contenir des images ou être scanné)",
+    ),
+    ("pdf_read.done", "PDF read: {} pages with text of {} in total", "PDF lu: {} pages avec texte sur {} total"),
+    ("pdf_create.done", "PDF created: {} ({} lines)", "PDF créé: {} ({} lignes)"),
+    ("pdf_add_page.done", "Page added to the PDF: {} pages now", "Page ajoutée au PDF: {} pages maintenant"),
+    ("pdf_merge.too_few", "At least 2 files are needed to merge", "Il faut au moins 2 fichiers à fusionner"),
+    (
+        "pdf_merge.unsupported",
+        "Merging PDFs is not supported yet. Use pdf_create to create new PDFs.",
+        "La fusion PDF n'est pas encore supportée. Utilisez pdf_create pour créer de nouveaux PDFs.",
+    ),
+    // Web
+    ("web.client_error", "Cannot create the HTTP client: {}", "Impossible de créer le client HTTP: {}"),
+    ("web.unknown_method", "Unknown HTTP method: {}", "Méthode HTTP inconnue: {}"),
+    ("web.http_error", "HTTP error: {}", "Erreur HTTP: {}"),
+    ("web.response_error", "Cannot read the response: {}", "Impossible de lire la réponse: {}"),
+    ("web.too_many_redirects", "More than {} redirects for {}", "Plus de {} redirections pour {}"),
+    ("web.status", "HTTP {} for {}", "HTTP {} pour {}"),
+    ("web.no_host", "URL without a host: {}", "URL sans hôte: {}"),
+    ("web.resolve_error", "Cannot resolve {}: {}", "Résolution de {} impossible: {}"),
+    ("web.no_address", "No address for {}", "Aucune adresse pour {}"),
+    (
+        "web.private_address",
+        "{} resolves to the private address {}; allow the local network in the settings",
+        "{} résout vers l'adresse privée {} ; autorisez le réseau local dans les paramètres",
+    ),
+    ("web.invalid_url", "Invalid URL {}: {}", "URL invalide {}: {}"),
+    ("web.unsupported_scheme", "Unsupported scheme: {}", "Schéma non pris en charge: {}"),
+    ("web_read.not_a_page", "{} is not a web page ({}): use web_download", "{} n'est pas une page web ({}): utilisez web_download"),
+    ("web_download.done", "Downloaded: {} -> {} ({} bytes)", "Téléchargé: {} -> {} ({} octets)"),
+    ("http_request.done", "HTTP {} {} -> {} ({}, {} bytes{})", "HTTP {} {} -> {} ({}, {} octets{})"),
+    ("http_request.no_type", "no type", "sans type"),
+    ("http_request.truncated", ", truncated", ", tronqué"),
+    ("http_request.headers", "\nHeaders sent: {}", "\nEn-têtes envoyés: {}"),
+    // MCP servers
+    ("mcp.env_error", "MCP server '{}', variable {}: {}", "Serveur MCP '{}', variable {}: {}"),
+    (
+        "mcp.start_error",
+        "Cannot start the MCP server '{}': {}. Check that '{}' is installed.",
+        "Impossible de démarrer le serveur MCP '{}': {}. Vérifiez que '{}' est installé.",
+    ),
+    ("mcp.no_stdin", "Cannot reach the MCP server's stdin", "Impossible d'accéder au stdin du serveur MCP"),
+    ("mcp.no_stdout", "Cannot reach the MCP server's stdout", "Impossible d'accéder au stdout du serveur MCP"),
+    ("mcp.not_started", "MCP server not started", "Serveur MCP non démarré"),
+    ("mcp.not_initialized", "MCP server not initialized", "Serveur MCP non initialisé"),
+    ("mcp.serialize_error", "Serialization error: {}", "Erreur sérialisation: {}"),
+    ("mcp.stdin_error", "Error writing to stdin: {}", "Erreur écriture stdin: {}"),
+    ("mcp.stdout_error", "Error reading stdout: {}", "Erreur lecture stdout: {}"),
+    ("mcp.closed", "The MCP server closed the connection", "Le serveur MCP a fermé la connexion"),
+    ("mcp.invalid_tool_list", "Invalid tools/list response", "Réponse tools/list invalide"),
+    ("mcp.unknown_error", "Unknown MCP error", "Erreur MCP inconnue"),
+    ("mcp.no_result", "MCP response without a result", "Réponse MCP sans résultat"),
+    ("mcp.http_error", "MCP HTTP error: {}", "Erreur HTTP MCP: {}"),
+    ("mcp.http_status", "MCP HTTP error ({}): {}", "HTTP MCP erreur ({}): {}"),
+    ("mcp.invalid_response", "Invalid MCP response: {}", "Réponse MCP invalide: {}"),
+    ("mcp.invalid_sse", "Invalid MCP SSE response: {}", "Réponse MCP SSE invalide: {}"),
+    ("mcp.not_json_or_sse", "Invalid MCP response: expected JSON or SSE", "Réponse MCP invalide: attendu JSON ou SSE"),
+    ("mcp_tool.done", "[MCP:{}] {} executed", "[MCP:{}] {} exécuté"),
+    // file_convert
+    ("file_convert.invalid_utf8", "Invalid UTF-8 after the BOM: {}", "UTF-8 invalide après le BOM: {}"),
+    ("file_convert.odd_utf16", "Invalid UTF-16: odd byte count", "UTF-16 invalide: nombre d'octets impair"),
+    ("file_convert.invalid_utf16", "Invalid UTF-16: {}", "UTF-16 invalide: {}"),
+    ("file_convert.binary", "Binary file: file_convert only handles text files", "Fichier binaire: file_convert ne traite que les fichiers texte"),
+    ("file_convert.not_latin1", "'{}' (line {}) does not exist in Latin-1; keep UTF-8", "'{}' (ligne {}) n'existe pas en Latin-1 ; gardez UTF-8"),
+    ("file_convert.unknown_line_ending", "Unknown line_ending '{}' (lf, crlf)", "line_ending inconnu '{}' (lf, crlf)"),
+    (
+        "file_convert.unknown_encoding",
+        "Unknown encoding '{}' (utf-8, utf-16le, utf-16be, latin-1)",
+        "Encodage inconnu '{}' (utf-8, utf-16le, utf-16be, latin-1)",
+    ),
+    ("file_convert.unknown_trailing_newline", "Unknown trailing_newline '{}' (add, strip)", "trailing_newline inconnu '{}' (add, strip)"),
+    ("file_convert.line_endings", "{} line ending(s) -> {}", "{} fin(s) de ligne -> {}"),
+    ("file_convert.newline_added", "final newline added", "saut de ligne final ajouté"),
+    ("file_convert.newline_stripped", "final newline(s) removed", "saut(s) de ligne final(aux) retiré(s)"),
+    ("file_convert.bom_removed", "BOM removed", "BOM retiré"),
+    ("file_convert.bom_added", "BOM added", "BOM ajouté"),
+    ("file_convert.not_a_file", "{} is not a file", "{} n'est pas un fichier"),
+    ("file_convert.too_large", "File too large ({} bytes, max {})", "Fichier trop volumineux ({} octets, max {})"),
+    ("file_convert.with_bom", " with BOM", " avec BOM"),
+    ("file_convert.unchanged", "no changes", "aucun changement"),
+    ("file_convert.dry_run", " (dry run)", " (simulation)"),
+    ("file_convert.done", "File converted: {} ({})", "Fichier converti: {} ({})"),
+    // System
+    ("process_list.error", "Cannot list the processes: {}", "Impossible de lister les processus: {}"),
+    ("process_list.done", "Processes listed{}", "Processus listés{}"),
+    ("process_list.filter", " (filter: {})", " (filtre: {})"),
+    ("environment.unset", "Variable '{}' not set", "Variable '{}' non définie"),
+    ("environment.done", "{} environment variable(s)", "{} variable(s) d'environnement"),
+    ("which.error", "Error: {}", "Erreur: {}"),
+    ("which.not_found", "'{}' not found in the PATH", "'{}' non trouvé dans le PATH"),
+    ("tree.totals", "\n{} folder(s), {} file(s)", "\n{} dossier(s), {} fichier(s)"),
+    ("tree.done", "Tree: {} folder(s), {} file(s)", "Arborescence: {} dossier(s), {} fichier(s)"),
+    ("project_overview.text_file", "{}, {} lines", "{}, {} lignes"),
+    ("project_overview.binary_file", "{}, not text", "{}, non texte"),
+    (
+        "project_overview.truncated",
+        "… (tree truncated, raise max_entries or lower max_depth)\n",
+        "… (arborescence tronquée, augmentez max_entries ou réduisez max_depth)\n",
+    ),
+    ("project_overview.totals", "{} folder(s), {} file(s), {}, {} lines{}", "{} dossier(s), {} fichier(s), {}, {} lignes{}"),
+    ("project_overview.partial", " (partial totals)", " (totaux partiels)"),
+    ("project_overview.done", "Project overview: {}", "Aperçu du projet: {}"),
+    // Document index
+    (
+        "index.no_model",
+        "No embedding model configured: pick a GGUF embedding model (nomic-embed-text, bge...) in Settings > Index.",
+        "Aucun modèle d'embedding configuré: choisissez un modèle GGUF d'embedding (nomic-embed-text, bge...) dans Paramètres > Index.",
+    ),
+    ("index.folder_not_found", "The folder '{}' does not exist", "Le dossier '{}' n'existe pas"),
+    (
+        "index.no_folders",
+        "No indexed folder: pass `folder` or add folders in Settings > Index.",
+        "Aucun dossier indexé: passez `folder` ou ajoutez des dossiers dans Paramètres > Index.",
+    ),
+    ("index.model_error", "Unusable embedding model: {}", "Modèle d'embedding inutilisable: {}"),
+    ("index.embed_error", "Embedding failed: {}", "Embedding impossible: {}"),
+    ("index.storage_error", "Index of {}: {}", "Index de {}: {}"),
+    ("index.unreadable", "unreadable", "lecture impossible"),
+    (
+        "index_build.done",
+        "Index up to date: {} folder(s), {} file(s) re-indexed, {} passages in total",
+        "Index à jour: {} dossier(s), {} fichier(s) ré-indexé(s), {} passages au total",
+    ),
+    (
+        "index_search.other_model",
+        "indexed with another embedding model, run index_build again",
+        "indexé avec un autre modèle d'embedding, relancez index_build",
+    ),
+    ("index_search.not_indexed", "not indexed yet, run index_build", "pas encore indexé, lancez index_build"),
+    ("index_search.no_index", "No usable index: {}", "Aucun index utilisable: {}"),
+    ("index_search.empty_embedding", "Empty embedding", "Embedding vide"),
+    ("index_search.done", "{} passage(s) found for \"{}\"", "{} passage(s) trouvé(s) pour \"{}\""),
+    ("index_status.done", "{} of {} folder(s) indexed", "{} dossier(s) indexé(s) sur {}"),
+    // Long-term memory
+    ("memory.error", "Memory unavailable: {}", "Mémoire inaccessible: {}"),
+    ("memory.no_note", "No note with id {}", "Aucune note avec l'id {}"),
+    ("memory_write.deleted", "Note {} forgotten", "Note {} oubliée"),
+    ("memory_write.updated", "Note {} updated", "Note {} mise à jour"),
+    ("memory_write.saved", "Note {} saved", "Note {} enregistrée"),
+    (
+        "memory_write.evicted",
+        " ({} old note(s) forgotten to stay under the size limit)",
+        " ({} note(s) ancienne(s) oubliée(s) pour rester sous la taille maximale)",
+    ),
+    ("memory_read.no_topic", "No note about '{}'", "Aucune note sur '{}'"),
+    ("memory_read.empty", "Memory is empty", "La mémoire est vide"),
+    ("memory_read.done", "{} note(s)", "{} note(s)"),
+    ("memory_search.none", "No note for '{}'", "Aucune note pour '{}'"),
+    ("memory_search.done", "{} note(s) for '{}'", "{} note(s) pour '{}'"),
+    // File backups
+    ("backup.none_for", "No backup for '{}'", "Aucune sauvegarde pour '{}'"),
+    ("backup.unreadable", "Unreadable backup: {}", "Sauvegarde illisible: {}"),
+    ("backup.restore_error", "Cannot restore: {}", "Impossible de restaurer: {}"),
+    ("backup.remove_error", "Cannot delete: {}", "Impossible de supprimer: {}"),
+    ("backup.index_error", "Backup index not saved: {}", "Index des sauvegardes non enregistré: {}"),
+    (
+        "backup.too_large",
+        "No backup: {} is {} bytes (> 5 MB), undo_file_change will not be able to restore it",
+        "Pas de sauvegarde: {} fait {} octets (> 5 Mo), undo_file_change ne pourra pas le restaurer",
+    ),
+    ("backup.read_error", "No backup: unreadable ({})", "Pas de sauvegarde: lecture impossible ({})"),
+    ("backup.failed", "No backup: {}", "Pas de sauvegarde: {}"),
+    (
+        "undo_file_change.restored",
+        "File restored: {} (version from before {} at {}, {} backup(s) left)",
+        "Fichier restauré: {} (version d'avant {} du {}, {} sauvegarde(s) restante(s))",
+    ),
+    (
+        "undo_file_change.deleted",
+        "File deleted: {} (it did not exist before {} at {})",
+        "Fichier supprimé: {} (il n'existait pas avant {} du {})",
+    ),
+    ("list_file_backups.file", "{} ({} version(s))", "{} ({} version(s))"),
+    ("list_file_backups.size", "{} bytes", "{} octets"),
+    ("list_file_backups.absent", "did not exist", "inexistant"),
+    ("list_file_backups.version", "  - {} before {}: {}", "  - {} avant {}: {}"),
+    ("list_file_backups.none", "No backups", "Aucune sauvegarde"),
+    // code_search
+    ("code_search.more_lines", "\n… ({} more lines)", "\n… ({} lignes de plus)"),
+    ("code_search.no_extension", "(none)", "(aucune)"),
+    (
+        "code_search.fallback",
+        "Unsupported language (.{}): regex search, approximate results",
+        "Langage non pris en charge (.{}) : recherche par regex, résultats approximatifs",
+    ),
+    (
+        "code_search.definitions",
+        "{} definition(s) of \"{}\" ({} file(s) scanned)",
+        "{} définition(s) de \"{}\" ({} fichier(s) analysé(s))",
+    ),
+    (
+        "code_search.references",
+        "{} reference(s) of \"{}\" ({} file(s) scanned)",
+        "{} référence(s) de \"{}\" ({} fichier(s) analysé(s))",
+    ),
+    ("code_search.truncated", " (results truncated)", " (résultats tronqués)"),
+    // image_info
+    (
+        "image.unsupported",
+        "{}: unsupported image format (PNG, JPEG or WebP)",
+        "{}: format d'image non supporté (PNG, JPEG ou WebP)",
+    ),
+    ("image.read_error", "Cannot read {}: {}", "Lecture impossible de {}: {}"),
+    (
+        "image.too_large",
+        "{}: image too large to decode (max {}x{} px)",
+        "{}: image trop grande pour être décodée (max {}x{} px)",
+    ),
+    ("image.corrupt_detail", "{}: corrupt or unreadable image ({})", "{}: image corrompue ou illisible ({})"),
+    ("image.corrupt", "{}: corrupt or unreadable image", "{}: image corrompue ou illisible"),
+    ("image.preview_error", "Cannot encode the preview: {}", "Encodage de l'aperçu impossible: {}"),
+    ("image_info.folder", "{}: {} image(s), {}", "{}: {} image(s), {}"),
+    ("image_info.with_preview", " (preview included)", " (aperçu inclus)"),
+    // Command policy
+    ("command_policy.denied_pattern", "denied pattern '{}'", "motif interdit '{}'"),
+    (
+        "command_policy.substitution",
+        "command substitution ($(...) or `...`)",
+        "substitution de commande ($(...) ou `...`)",
+    ),
+    ("command_policy.unlisted", "command '{}' is not on the allowlist", "commande '{}' hors de la liste autorisée"),
+    ("command_policy.redirection", "redirection to the file '{}'", "redirection vers le fichier '{}'"),
+    ("command_policy.tee", "'tee' writes to the file '{}'", "'tee' écrit dans le fichier '{}'"),
+    ("command_policy.denied_argument", "argument '{}' is denied", "argument '{}' interdit"),
+    ("command_policy.arguments_required", "arguments required", "arguments requis"),
+    ("command_policy.arguments_not_allowed", "arguments '{}' are not allowed", "arguments '{}' non autorisés"),
+    // Test runners
+    ("tests.unparsed", "{}: unreadable result, see output_tail", "{}: résultat illisible, voir output_tail"),
+    (
+        "tests.done",
+        "{}: {} passed, {} failed, {} skipped",
+        "{}: {} réussi(s), {} échoué(s), {} ignoré(s)",
+    ),
+    ("tests.errors", ", {} error(s)", ", {} erreur(s)"),
+    ("tests.run_error", "Cannot run {}: {}", "Impossible d'exécuter {}: {}"),
+    (
+        "npm_test.no_package",
+        "No package.json in '{}'. Pass working_dir with the project folder.",
+        "Pas de package.json dans '{}'. Passez working_dir avec le dossier du projet.",
+    ),
+    // GitHub
+    ("github.gh_error", "Cannot run gh: {}", "Impossible d'exécuter gh: {}"),
+    ("github_issue_list.done", "{} {} issue(s) in {}", "{} issue(s) {} dans {}"),
+    ("github_pr_create.done", "PR #{} created: {}", "PR #{} créée: {}"),
+    // Exa web search
+    ("exa.web_search", "Web search for \"{}\" - {} results", "Recherche web pour \"{}\" - {} résultats"),
+    ("exa.code_search", "Code search for \"{}\"", "Recherche code pour \"{}\""),
+    ("exa.company_research", "Company research: {}", "Recherche entreprise: {}"),
+    ("exa.deep_research_start", "Deep research started for: {}", "Recherche approfondie démarrée pour: {}"),
+    ("exa.deep_research_check", "Research status: {}", "Statut recherche: {}"),
+    ("exa.web_crawl", "Content extracted from: {}", "Contenu extrait de: {}"),
+    // ai_consult
+    ("ai_consult.done", "Answer from {}:\n\n{}", "Réponse de {} :\n\n{}"),
+    // MCP server management
+    (
+        "mcp_add_server.done",
+        "MCP server '{}' added. Restart the agent to apply.",
+        "Serveur MCP '{}' ajouté. Redémarrez l'agent pour appliquer.",
+    ),
+    ("mcp_list_servers.done", "{} configured servers", "{} serveurs configurés"),
+    ("mcp_remove_server.done", "MCP server '{}' removed.", "Serveur MCP '{}' supprimé."),
+];
+
+/// The tool message for `key` in the tool language, or `key` itself when the
+/// catalog lacks it
+pub fn tool_string(key: &'static str) -> &'static str {
+    catalog_string(tool_language(), key)
+}
+
+fn catalog_string(locale: Locale, key: &'static str) -> &'static str {
+    match TOOL_CATALOG.iter().find(|(k, _, _)| *k == key) {
+        Some((_, en, fr)) => match locale {
+            Locale::En => en,
+            Locale::Fr => fr,
+        },
+        None => {
+            tracing::warn!("Missing tool message: {}", key);
+            key
+        }
+    }
+}
+
+/// `template` with each `{}` replaced by the next of `args`
+pub fn fill(template: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    out.push_str(parts.next().unwrap_or_default());
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+const FRENCH_MARKERS: &[&str] = &[
+    "le", "la", "les", "des", "une", "est", "et", "je", "tu", "vous", "nous", "pour", "dans",
+    "avec", "pas", "que", "qui", "sur", "mon", "mes", "ce", "cette", "peux", "fais", "merci",
+    "bonjour", "du", "au", "aux", "il", "elle", "c'est", "j'ai", "moi",
+];
+
+const ENGLISH_MARKERS: &[&str] = &[
+    "the", "and", "is", "are", "you", "i", "to", "of", "in", "for", "with", "my", "this",
+    "that", "can", "please", "what", "how", "it", "do", "does", "on", "be", "thanks", "hello",
+    "me", "your", "from", "have", "was",
+];
+
+/// Score a single text: positive = English, negative = French
+fn score(text: &str) -> i32 {
+    let mut score = 0;
+    // Skip command-line flags and paths ("-la", "src/le.rs") which look like words
+    for word in text
+        .split_whitespace()
+        .filter(|t| !t.starts_with('-') && !t.contains('/') && !t.contains('\\'))
+        .flat_map(|t| t.split(|c: char| !c.is_alphanumeric() && c != '\''))
+        .filter(|w| !w.is_empty())
+    {
+        let lower = word.to_lowercase();
+        if FRENCH_MARKERS.contains(&lower.as_str()) {
+            score -= 1;
+        }
+        if ENGLISH_MARKERS.contains(&lower.as_str()) {
+            score += 1;
+        }
+    }
+    // Accented letters are a strong French signal
+    score -= text.chars().filter(|c| "éèêàçùâîôû".contains(*c)).count().min(5) as i32;
+    score
+}
+
+/// Detect the language of recent user messages (oldest first)
+///
+/// Only the last three messages are considered, the most recent weighing the
+/// most, so a conversation that switched language follows the switch.
+/// Returns `None` when there is no clear signal.
+pub fn detect_language(user_messages: &[&str]) -> Option<Locale> {
+    let total: i32 = user_messages
+        .iter()
+        .rev()
+        .take(3)
+        .enumerate()
+        .map(|(i, text)| score(text) * (3 - i as i32))
+        .sum();
+
+    match total {
+        t if t >= 2 => Some(Locale::En),
+        t if t <= -2 => Some(Locale::Fr),
+        _ => None,
+    }
+}
+
+/// Localized strings injected by the agent loop
+#[derive(Debug, Clone, Copy)]
+pub struct AgentStrings {
+    pub lang: Locale,
+}
+
+impl AgentStrings {
+    pub fn new(lang: Locale) -> Self {
+        Self { lang }
+    }
+
+    fn pick(&self, fr: &str, en: &str) -> String {
+        match self.lang {
+            Locale::Fr => fr.to_string(),
+            Locale::En => en.to_string(),
+        }
+    }
+
+    pub fn stuck(&self) -> String {
+        self.pick(
+            "⚠️ J'ai détecté que je répète les mêmes actions. Laisse-moi reformuler ma réponse.",
+            "⚠️ I noticed I'm repeating the same actions. Let me rephrase my answer.",
+        )
+    }
+
+    pub fn max_runtime(&self) -> String {
+        self.pick(
+            "⏱️ Temps d'exécution maximal atteint. Voici ce que j'ai trouvé jusqu'à présent.",
+            "⏱️ Maximum run time reached. Here is what I found so far.",
+        )
+    }
+
+    pub fn generation_error(&self, error: &str) -> String {
+        match self.lang {
+            Locale::Fr => format!("❌ Erreur de génération: {}", error),
+            Locale::En => format!("❌ Generation error: {}", error),
+        }
+    }
+
+    /// Notice shown once hierarchical compression shrank the history
+    pub fn compression_applied(&self, tier: CompressionTier, saved: usize) -> String {
+        match self.lang {
+            Locale::Fr => {
+                let tier = match tier {
+                    CompressionTier::Working => "travail",
+                    CompressionTier::Compressed => "compressé",
+                    CompressionTier::Archived => "archivé",
+                    CompressionTier::Critical => "critique",
+                };
+                format!("💾 Compression hiérarchique appliquée (niveau: {}, ~{} caractères économisés).", tier, saved)
+            }
+            Locale::En => format!(
+                "💾 Hierarchical compression applied (tier: {}, ~{} chars saved).",
+                tier.name(),
+                saved
+            ),
+        }
+    }
+
+    /// Prefix appended to the streamed text when the engine reports an error
+    pub fn stream_error_marker(&self) -> &'static str {
+        match self.lang {
+            Locale::Fr => "❌ Erreur:",
+            Locale::En => "❌ Error:",
+        }
+    }
+
+    pub fn stream_error_retry(&self) -> String {
+        self.pick(
+            "Une erreur est survenue pendant la génération. Reformule ta réponse ou essaie une approche différente.",
+            "An error occurred during generation. Rephrase your answer or try a different approach.",
+        )
+    }
+
+    pub fn corrupted_output(&self) -> String {
+        self.pick(
+            "\n\n⚠️ Génération interrompue: texte corrompu détecté. Le début de la réponse est conservé ci-dessus.",
+            "\n\n⚠️ Generation stopped: corrupted text detected. The output so far is kept above.",
+        )
+    }
+
+    pub fn invalid_tool_json(&self) -> String {
+        self.pick(
+            "Le format JSON de l'appel d'outil était invalide. Rappel: utilise exactement ce format sans texte avant ni après:\n```json\n{\"tool\": \"nom_outil\", \"params\": {...}}\n```\nRéessaie avec le bon format.",
+            "The tool call JSON was invalid. Reminder: use exactly this format with no text before or after:\n```json\n{\"tool\": \"tool_name\", \"params\": {...}}\n```\nTry again with the correct format.",
+        )
+    }
+
+    pub fn fabricated_tool_output(&self) -> String {
+        self.pick(
+            "Tu as écrit toi-même un résultat d'outil: aucun outil n'a été exécuté et ce texte a été retiré. Pour utiliser un outil, émets uniquement l'appel JSON et attends son résultat:\n```json\n{\"tool\": \"nom_outil\", \"params\": {...}}\n```",
+            "You wrote a tool result yourself: no tool was run and that text was removed. To use a tool, emit only the JSON call and wait for its result:\n```json\n{\"tool\": \"tool_name\", \"params\": {...}}\n```",
+        )
+    }
+
+    pub fn fabricated_tool_output_stopped(&self) -> String {
+        self.pick(
+            "\n\n⚠️ Exécution arrêtée: le modèle invente des résultats d'outils au lieu d'appeler les outils.",
+            "\n\n⚠️ Run stopped: the model keeps making up tool results instead of calling the tools.",
+        )
+    }
+
+    pub fn tool_refused_hint(&self, tool: &str) -> String {
+        match self.lang {
+            Locale::Fr => format!("L'outil {} a été refusé. Essaie une autre approche ou réponds avec les informations disponibles.", tool),
+            Locale::En => format!("The tool {} was refused. Try another approach or answer with the information available.", tool),
+        }
+    }
+
+    pub fn tool_calls_deferred(&self, tool: &str, deferred: &str) -> String {
+        match self.lang {
+            Locale::Fr => format!("Seul `{}` est exécuté maintenant. Ces appels n'ont pas été faits : {}. Refais-les après avoir vu son résultat si tu en as encore besoin.", tool, deferred),
+            Locale::En => format!("Only `{}` runs now. These calls were not made: {}. Make them again after seeing its result if you still need them.", tool, deferred),
+        }
+    }
+
+    pub fn read_only_hint(&self, tool: &str) -> String {
+        match self.lang {
+            Locale::Fr => format!("Cette conversation est en lecture seule : l'outil {} ne peut pas être utilisé. N'utilise que des outils de lecture ou de recherche, et décris les modifications au lieu de les appliquer.", tool),
+            Locale::En => format!("This conversation is read-only: the tool {} cannot be used. Only use read or research tools, and describe changes instead of applying them.", tool),
+        }
+    }
+
+    pub fn tool_not_found_hint(&self, tool: &str, available: &str) -> String {
+        match self.lang {
+            Locale::Fr => format!("L'outil `{}` n'existe pas. Voici les outils disponibles: {}. Utilise un des outils existants ou réponds directement.", tool, available),
+            Locale::En => format!("The tool `{}` does not exist. Available tools: {}. Use one of the existing tools or answer directly.", tool, available),
+        }
+    }
+
+    pub fn model_switched(&self, model: &str) -> String {
+        match self.lang {
+            Locale::Fr => format!("Modèle changé pour {}", model),
+            Locale::En => format!("Model switched to {}", model),
+        }
+    }
+
+    pub fn tool_timeout(&self) -> String {
+        self.pick("Timeout dépassé", "Timeout exceeded")
+    }
+
+    /// Header of a notice standing for `count` consecutive ones of the same kind
+    pub fn notice_repeats(&self, kind: NoticeKind, count: u32) -> String {
+        let (fr, en) = match kind {
+            NoticeKind::Reflection => ("invites de réflexion", "reflection prompts"),
+            NoticeKind::StreamRetry => ("relances après erreur", "retries after an error"),
+            NoticeKind::InvalidToolCall => ("rappels du format d'appel", "tool format reminders"),
+            NoticeKind::FabricatedToolOutput => ("résultats d'outil inventés", "made-up tool results"),
+            NoticeKind::ToolRefused => ("outils refusés", "refused tools"),
+            NoticeKind::ToolNotFound => ("outils introuvables", "unknown tools"),
+            NoticeKind::ReadOnly => ("blocages en lecture seule", "read-only blocks"),
+            NoticeKind::TooManyErrors => ("alertes d'erreurs", "error warnings"),
+            NoticeKind::Compression => ("compressions", "compressions"),
+            NoticeKind::DeferredCalls => ("appels reportés", "deferred calls"),
+            NoticeKind::ModelSwitch => ("changements de modèle", "model switches"),
+        };
+        match self.lang {
+            Locale::Fr => format!("[{} {}]", count, fr),
+            Locale::En => format!("[{} {}]", count, en),
+        }
+    }
+
+    pub fn too_many_errors(&self, count: usize) -> String {
+        match self.lang {
+            Locale::Fr => format!("Trop d'erreurs consécutives ({}). Arrête d'utiliser des outils et donne une réponse finale à l'utilisateur en expliquant ce que tu as essayé et ce qui n'a pas marché. Propose des solutions alternatives si possible.", count),
+            Locale::En => format!("Too many consecutive errors ({}). Stop using tools and give the user a final answer explaining what you tried and what did not work. Suggest alternatives if possible.", count),
+        }
+    }
+
+    pub fn result_truncated(&self, total_chars: usize) -> String {
+        match self.lang {
+            Locale::Fr => format!("[Résultat tronqué: {} caractères au total]", total_chars),
+            Locale::En => format!("[Result truncated: {} characters in total]", total_chars),
+        }
+    }
+
+    pub fn summary_fallback(&self) -> String {
+        self.pick("Conversation précédente résumée.", "Previous conversation summarized.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_catalog() {
+        assert_eq!(catalog_string(Locale::En, "file.read_error"), "Error reading file: {}");
+        assert_eq!(catalog_string(Locale::Fr, "file.read_error"), "Erreur lecture fichier: {}");
+        assert_eq!(catalog_string(Locale::En, "no.such.key"), "no.such.key");
+        assert_eq!(fill("{} of {}{}", &[&3, &"src", &""]), "3 of src");
+
+        let mut keys: Vec<&str> = TOOL_CATALOG.iter().map(|(key, _, _)| *key).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), TOOL_CATALOG.len(), "duplicate keys");
+        for (key, en, fr) in TOOL_CATALOG {
+            assert_eq!(en.matches("{}").count(), fr.matches("{}").count(), "placeholders of {}", key);
+        }
+
+        // Every key the tools use exists
+        let sources = [
+            include_str!("tools.rs"),
+            include_str!("tools/backup.rs"),
+            include_str!("tools/cargo.rs"),
+            include_str!("tools/command_policy.rs"),
+            include_str!("tools/csv.rs"),
+            include_str!("tools/dev.rs"),
+            include_str!("tools/encoding.rs"),
+            include_str!("tools/exa.rs"),
+            include_str!("tools/filesystem.rs"),
+            include_str!("tools/git.rs"),
+            include_str!("tools/github.rs"),
+            include_str!("tools/images.rs"),
+            include_str!("tools/index.rs"),
+            include_str!("tools/lsp.rs"),
+            include_str!("tools/mcp_client.rs"),
+            include_str!("tools/mcp_management.rs"),
+            include_str!("tools/memory.rs"),
+            include_str!("tools/openrouter.rs"),
+            include_str!("tools/pdf.rs"),
+            include_str!("tools/shell.rs"),
+            include_str!("tools/symbols.rs"),
+            include_str!("tools/system.rs"),
+            include_str!("tools/test_runners.rs"),
+            include_str!("tools/web.rs"),
+        ];
+        for source in sources {
+            for (at, _) in source.match_indices("tool_text!(") {
+                let key = source[at..].split('"').nth(1).unwrap_or_default();
+                assert!(TOOL_CATALOG.iter().any(|(k, _, _)| *k == key), "missing key {}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_single_language() {
+        assert_eq!(detect_language(&["Can you list the files in my project?"]), Some(Locale::En));
+        assert_eq!(detect_language(&["Peux-tu lister les fichiers de mon projet ?"]), Some(Locale::Fr));
+    }
+
+    #[test]
+    fn test_detect_follows_recent_switch() {
+        let history = [
+            "Bonjour, je voudrais de l'aide avec mon code",
+            "Merci, c'est parfait",
+            "Now can you explain what this function does and how to test it?",
+        ];
+        assert_eq!(detect_language(&history), Some(Locale::En));
+    }
+
+    #[test]
+    fn test_detect_mixed_or_empty_is_undecided() {
+        assert_eq!(detect_language(&[]), None);
+        assert_eq!(detect_language(&["ls -la", "cargo build"]), None);
+        // One marker of each cancels out
+        assert_eq!(detect_language(&["ok merci, thanks"]), None);
+    }
+
+    #[test]
+    fn test_strings_are_localized() {
+        let fr = AgentStrings::new(Locale::Fr);
+        let en = AgentStrings::new(Locale::En);
+        assert!(fr.tool_refused_hint("grep").contains("refusé"));
+        assert!(en.tool_refused_hint("grep").contains("refused"));
+        assert_ne!(fr.stream_error_marker(), en.stream_error_marker());
+        assert!(fr.compression_applied(CompressionTier::Archived, 120).contains("archivé"));
+        assert!(en.compression_applied(CompressionTier::Archived, 120).contains("tier: Archived"));
+        assert_eq!(Locale::from_code("en").map(|l| l.code()), Some("en"));
+    }
+}
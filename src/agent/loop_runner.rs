@@ -8,16 +8,21 @@
 //! - Dynamic planning with TODO lists
 //! - Configurable iteration limits
 
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::agent::tools::{ToolRegistry, ToolResult, ToolError};
+use crate::agent::tools::schema::validate_tool_params;
 use crate::agent::planning::{TaskPlan, TaskStatus, PlanManager};
 use crate::agent::runner::{ToolCall, extract_tool_call};
+use crate::agent::language::AgentStrings;
+use crate::types::message::{Message, MessageMetadata, Notice, NoticeKind, Role};
 
 /// Agent loop configuration
 #[derive(Clone, Debug)]
@@ -38,6 +43,8 @@ pub struct AgentLoopConfig {
     pub enable_retry: bool,
     /// Maximum retries per tool call
     pub max_retries: usize,
+    /// Successful tool results at least this long (in bytes) are anchored
+    pub anchor_min_result_chars: usize,
 }
 
 impl Default for AgentLoopConfig {
@@ -51,6 +58,60 @@ impl Default for AgentLoopConfig {
             min_iteration_delay_ms: 100,
             enable_retry: true,
             max_retries: 2,
+            anchor_min_result_chars: 200,
+        }
+    }
+}
+
+impl AgentLoopConfig {
+    /// Retries allowed per tool call
+    pub fn retry_limit(&self) -> usize {
+        if self.enable_retry { self.max_retries } else { 0 }
+    }
+}
+
+/// Backoff before retry number `retry` (from 1): 100ms, 200ms, 400ms...
+pub fn retry_delay(retry: usize) -> Duration {
+    Duration::from_millis(100 * 2_u64.pow(retry.saturating_sub(1) as u32))
+}
+
+/// Run `attempt` until it succeeds, fails for good, or `max_retries` retries are spent
+///
+/// Only `ToolError::is_retryable` failures are retried. `on_retry` gets each
+/// of them with its retry number before the backoff; cancelling `cancel`
+/// during a backoff ends the call with `ToolError::Cancelled`.
+pub async fn retry_tool_call<F, Fut>(
+    max_retries: usize,
+    cancel: &CancellationToken,
+    mut attempt: F,
+    mut on_retry: impl FnMut(&ToolError, usize),
+) -> Result<ToolResult, ToolError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<ToolResult, ToolError>>,
+{
+    let mut retry_count = 0;
+    loop {
+        match attempt().await {
+            Err(e) if e.is_retryable() && retry_count < max_retries => {
+                retry_count += 1;
+                on_retry(&e, retry_count);
+                tokio::select! {
+                    _ = tokio::time::sleep(retry_delay(retry_count)) => {}
+                    _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+                }
+            }
+            outcome => return outcome,
+        }
+    }
+}
+
+impl AgentLoopConfig {
+    /// Defaults for a loaded model: thinking is expected only from reasoning models
+    pub fn for_model(reasoning: bool) -> Self {
+        Self {
+            enable_thinking: reasoning,
+            ..Self::default()
         }
     }
 }
@@ -93,6 +154,15 @@ pub enum ProgressState {
     Unknown,
 }
 
+/// Anchored tool results and errors are cut to about this many bytes
+const ANCHOR_MAX_CHARS: usize = 300;
+
+/// Error recorded for a tool call the user refused
+pub const PERMISSION_DENIED_ERROR: &str = "Permission denied";
+
+/// Error recorded for a tool call read-only mode blocked
+pub const READ_ONLY_BLOCKED_ERROR: &str = "Blocked by read-only mode";
+
 /// Reason for anchoring a message - determines preservation priority
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AnchorReason {
@@ -128,12 +198,14 @@ pub enum AgentEvent {
     StateChanged { from: AgentState, to: AgentState },
     /// Thinking/reasoning output
     Thinking { content: String },
+    /// Tool call waiting for the user's approval
+    PermissionRequested { tool: String, level: String, target: String },
     /// Tool call initiated
     ToolCallStarted { tool: String, params: Value },
     /// Tool call completed
     ToolCallCompleted { tool: String, result: ToolResult },
-    /// Tool call failed
-    ToolCallFailed { tool: String, error: String, retry_count: usize },
+    /// Tool call failed; `will_retry` when it is run again after a backoff
+    ToolCallFailed { tool: String, error: String, retry_count: usize, will_retry: bool },
     /// Plan updated
     PlanUpdated { plan: TaskPlan },
     /// Progress update
@@ -146,6 +218,72 @@ pub enum AgentEvent {
     Failed { error: String },
 }
 
+/// Tool call of the current run that has not finished yet
+#[derive(Clone, Debug, PartialEq)]
+pub enum PendingToolCall {
+    /// Waiting for the user to approve it
+    AwaitingPermission { tool: String, level: String, target: String },
+    /// Executing; `retry_count` is 0 on the first attempt
+    Running { tool: String, params: Value, retry_count: usize },
+}
+
+impl PendingToolCall {
+    pub fn tool(&self) -> &str {
+        match self {
+            Self::AwaitingPermission { tool, .. } | Self::Running { tool, .. } => tool,
+        }
+    }
+}
+
+/// Calls started (or waiting for approval) in `events` and not finished yet, in order
+pub fn pending_tool_calls(events: &[AgentEvent]) -> Vec<PendingToolCall> {
+    let mut pending: Vec<PendingToolCall> = Vec::new();
+    for event in events {
+        match event {
+            AgentEvent::PermissionRequested { tool, level, target } => pending.push(PendingToolCall::AwaitingPermission {
+                tool: tool.clone(),
+                level: level.clone(),
+                target: target.clone(),
+            }),
+            AgentEvent::ToolCallStarted { tool, params } => {
+                let running = PendingToolCall::Running { tool: tool.clone(), params: params.clone(), retry_count: 0 };
+                // An approved call moves on from waiting; a retried one keeps its count
+                match pending.iter_mut().find(|call| {
+                    call.tool() == tool
+                        && matches!(call, PendingToolCall::AwaitingPermission { .. } | PendingToolCall::Running { retry_count: 1.., .. })
+                }) {
+                    Some(PendingToolCall::Running { .. }) => {}
+                    Some(waiting) => *waiting = running,
+                    None => pending.push(running),
+                }
+            }
+            AgentEvent::ToolCallFailed { tool, retry_count, will_retry: true, .. } => {
+                if let Some(PendingToolCall::Running { retry_count: count, .. }) = pending
+                    .iter_mut()
+                    .find(|call| matches!(call, PendingToolCall::Running { .. }) && call.tool() == tool)
+                {
+                    *count = *retry_count;
+                }
+            }
+            AgentEvent::ToolCallCompleted { tool, .. } | AgentEvent::ToolCallFailed { tool, .. } => {
+                if let Some(index) = pending.iter().position(|call| call.tool() == tool) {
+                    pending.remove(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    pending
+}
+
+/// Latest iteration count reported by `events`
+pub fn latest_progress(events: &[AgentEvent]) -> Option<(usize, usize)> {
+    events.iter().rev().find_map(|event| match event {
+        AgentEvent::Progress { iteration, max_iterations, .. } => Some((*iteration, *max_iterations)),
+        _ => None,
+    })
+}
+
 /// Result of a single iteration
 #[derive(Debug)]
 pub enum IterationResult {
@@ -196,6 +334,8 @@ pub struct AgentContext {
     pub progress_state: ProgressState,
     /// Anchor messages - critical info preserved during compression
     pub anchor_messages: Vec<AnchorMessage>,
+    /// Replies that made up a tool result instead of calling the tool
+    pub hallucinated_tool_outputs: usize,
 }
 
 impl AgentContext {
@@ -217,6 +357,7 @@ impl AgentContext {
             stuck_iterations: 0,
             progress_state: ProgressState::Unknown,
             anchor_messages: Vec::new(),
+            hallucinated_tool_outputs: 0,
         }
     }
     
@@ -432,6 +573,31 @@ impl AgentContext {
         }
     }
     
+    /// Anchor what a successful tool call established, before it enters `tool_history`
+    ///
+    /// A success right after a failure of the same tool records the fixed error;
+    /// a result of at least `min_result_chars` bytes is kept as a success.
+    pub fn anchor_tool_success(&mut self, tool_name: &str, result: &ToolResult, min_result_chars: usize) {
+        let fixed_error = self
+            .tool_history
+            .last()
+            .filter(|e| e.tool_name == tool_name && e.result.is_none())
+            .and_then(|e| e.error.clone())
+            .filter(|error| error != PERMISSION_DENIED_ERROR && error != READ_ONLY_BLOCKED_ERROR);
+        if let Some(error) = fixed_error {
+            self.add_anchor(
+                format!("`{}` works after fixing: {}", tool_name, crate::truncate_str(&error, ANCHOR_MAX_CHARS)),
+                AnchorReason::ErrorFixed,
+            );
+        }
+        if result.success && result.message.len() >= min_result_chars {
+            self.add_anchor(
+                format!("`{}`: {}", tool_name, crate::truncate_str(&result.message, ANCHOR_MAX_CHARS)),
+                AnchorReason::Success,
+            );
+        }
+    }
+
     /// Get all anchors for preservation during context compression
     pub fn get_anchors(&self) -> Vec<AnchorMessage> {
         self.anchor_messages.clone()
@@ -459,7 +625,7 @@ impl AgentContext {
 }
 
 /// Entry in tool call history
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ToolHistoryEntry {
     pub tool_name: String,
     pub params: Value,
@@ -573,10 +739,32 @@ impl AgentLoop {
         event_tx: &mpsc::Sender<AgentEvent>,
     ) -> Result<ToolResult, ToolError> {
         let tool = self.tool_registry.get(&tool_call.tool)
-            .ok_or_else(|| ToolError::NotFound(tool_call.tool.clone()))?;
+            .ok_or_else(|| ToolError::UnknownTool(tool_call.tool.clone()))?;
+
+        // Invalid params would fail the same way on every retry
+        if let Err(e) = validate_tool_params(tool.as_ref(), &tool_call.params) {
+            let _ = event_tx.send(AgentEvent::ToolCallFailed {
+                tool: tool_call.tool.clone(),
+                error: e.to_string(),
+                retry_count: 0,
+                will_retry: false,
+            }).await;
+            ctx.tool_history.push(ToolHistoryEntry {
+                tool_name: tool_call.tool.clone(),
+                params: tool_call.params.clone(),
+                result: None,
+                error: Some(e.to_string()),
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                duration_ms: 0,
+            });
+            return Err(e);
+        }
         
         let mut retry_count = 0;
-        let max_retries = if self.config.enable_retry { self.config.max_retries } else { 0 };
+        let max_retries = self.config.retry_limit();
         
         loop {
             let start = Instant::now();
@@ -586,7 +774,7 @@ impl AgentLoop {
                 params: tool_call.params.clone(),
             }).await;
             
-            match tool.execute(tool_call.params.clone()).await {
+            match self.tool_registry.execute_tool(tool.as_ref(), tool_call.params.clone(), CancellationToken::new()).await {
                 Ok(result) => {
                     let duration_ms = start.elapsed().as_millis() as u64;
                     
@@ -612,14 +800,16 @@ impl AgentLoop {
                 }
                 Err(e) => {
                     retry_count += 1;
+                    let will_retry = e.is_retryable() && retry_count <= max_retries;
                     
                     let _ = event_tx.send(AgentEvent::ToolCallFailed {
                         tool: tool_call.tool.clone(),
                         error: e.to_string(),
                         retry_count,
+                        will_retry,
                     }).await;
                     
-                    if retry_count > max_retries {
+                    if !will_retry {
                         // Record failure in history
                         ctx.tool_history.push(ToolHistoryEntry {
                             tool_name: tool_call.tool.clone(),
@@ -636,9 +826,7 @@ impl AgentLoop {
                         return Err(e);
                     }
                     
-                    // Exponential backoff
-                    let delay = Duration::from_millis(100 * (2_u64.pow(retry_count as u32 - 1)));
-                    tokio::time::sleep(delay).await;
+                    tokio::time::sleep(retry_delay(retry_count)).await;
                 }
             }
         }
@@ -684,6 +872,110 @@ impl AgentLoop {
     }
 }
 
+/// Chat message as seen by the loop bookkeeping helpers below
+///
+/// Implemented by the stored message and the UI message so both the chat
+/// loop and tests share the same placeholder/notice handling.
+pub trait LoopMessage: Sized {
+    fn is_reply(&self) -> bool;
+    fn text(&self) -> &str;
+    fn notice(&self) -> Option<Notice>;
+    fn empty_reply() -> Self;
+    fn notice_message(content: String, notice: Notice) -> Self;
+
+    fn is_empty_reply(&self) -> bool {
+        self.is_reply() && self.text().is_empty()
+    }
+}
+
+impl LoopMessage for Message {
+    fn is_reply(&self) -> bool {
+        self.role == Role::Assistant
+    }
+
+    fn text(&self) -> &str {
+        &self.content
+    }
+
+    fn notice(&self) -> Option<Notice> {
+        self.metadata.as_ref().and_then(|m| m.notice)
+    }
+
+    fn empty_reply() -> Self {
+        Message::new(Role::Assistant, String::new())
+    }
+
+    fn notice_message(content: String, notice: Notice) -> Self {
+        let mut msg = Message::new(Role::System, content);
+        msg.metadata = Some(MessageMetadata { notice: Some(notice), ..Default::default() });
+        msg
+    }
+}
+
+/// Make sure the log ends with an empty reply to stream into, reusing the current one
+pub fn ensure_pending_reply<M: LoopMessage>(messages: &mut Vec<M>) {
+    if !messages.last().map(|m| m.is_empty_reply()).unwrap_or(false) {
+        messages.push(M::empty_reply());
+    }
+}
+
+/// Inject a loop notice followed by a pending reply.
+///
+/// A notice of the same kind as the previous one, with only the failed attempt
+/// in between, replaces it (and the attempt that led to it) and carries the
+/// running count, so error/reflection cycles don't grow the log.
+pub fn push_notice<M: LoopMessage>(
+    messages: &mut Vec<M>,
+    kind: NoticeKind,
+    content: String,
+    strings: &AgentStrings,
+) {
+    if messages.last().map(|m| m.is_empty_reply()).unwrap_or(false) {
+        messages.pop();
+    }
+
+    let mut count = 1;
+    let len = messages.len();
+    if len >= 2 && messages[len - 1].is_reply() {
+        if let Some(previous) = messages[len - 2].notice().filter(|n| n.kind == kind) {
+            count = previous.count + 1;
+            messages.remove(len - 2);
+            if len >= 3 && messages[len - 3].is_reply() {
+                messages.remove(len - 3);
+            }
+        }
+    }
+
+    let content = if count > 1 {
+        format!("{}\n{}", strings.notice_repeats(kind, count), content)
+    } else {
+        content
+    };
+    messages.push(M::notice_message(content, Notice { kind, count }));
+    messages.push(M::empty_reply());
+}
+
+/// Whether empty replies other than the trailing placeholder are left in the log
+pub fn has_stray_empty_replies<M: LoopMessage>(messages: &[M]) -> bool {
+    messages.iter().rev().skip(1).any(|m| m.is_empty_reply())
+}
+
+/// Drop empty replies left behind by earlier branches, keeping the trailing placeholder
+pub fn prune_empty_replies<M: LoopMessage>(messages: &mut Vec<M>) {
+    let last = messages.len().saturating_sub(1);
+    let mut index = 0;
+    messages.retain(|m| {
+        let keep = index == last || !m.is_empty_reply();
+        index += 1;
+        keep
+    });
+}
+
+/// Messages worth persisting: everything but empty replies
+pub fn persisted_messages<M: LoopMessage + Clone>(messages: &[M]) -> Vec<M> {
+    messages.iter().filter(|m| !m.is_empty_reply()).cloned().collect()
+}
+
 /// Check if response contains plan markers
 fn contains_plan_markers(response: &str) -> bool {
     let markers = [
@@ -782,4 +1074,206 @@ mod tests {
         
         assert!(ctx.is_stuck());
     }
+
+    #[test]
+    fn test_tool_success_anchors() {
+        let mut ctx = AgentContext::new();
+        ctx.add_anchor("Find the port the server listens on".to_string(), AnchorReason::Goal);
+        ctx.tool_history.push(ToolHistoryEntry {
+            tool_name: "file_read".to_string(),
+            params: serde_json::json!({"path": "confg.toml"}),
+            result: None,
+            error: Some("File not found: confg.toml".to_string()),
+            timestamp: 0,
+            duration_ms: 3,
+        });
+
+        let long = ToolResult { success: true, data: Value::Null, message: format!("port = 8080\n{}", "# comment\n".repeat(100)) };
+        ctx.anchor_tool_success("file_read", &long, 200);
+        let anchors = ctx.get_anchors();
+        assert_eq!(anchors.len(), 3);
+        assert_eq!(anchors[0].reason, AnchorReason::Goal);
+        assert_eq!(anchors[1].reason, AnchorReason::ErrorFixed);
+        assert!(anchors[1].content.contains("File not found: confg.toml"));
+        assert_eq!(anchors[2].reason, AnchorReason::Success);
+        assert!(anchors[2].content.starts_with("`file_read`: port = 8080"));
+        assert!(anchors[2].content.len() <= ANCHOR_MAX_CHARS + 20);
+
+        // Short results and refused calls anchor nothing
+        let mut ctx = AgentContext::new();
+        ctx.tool_history.push(ToolHistoryEntry {
+            tool_name: "shell".to_string(),
+            params: serde_json::json!({"command": "ls"}),
+            result: None,
+            error: Some(PERMISSION_DENIED_ERROR.to_string()),
+            timestamp: 0,
+            duration_ms: 0,
+        });
+        let short = ToolResult { success: true, data: Value::Null, message: "ok".to_string() };
+        ctx.anchor_tool_success("shell", &short, 200);
+        assert!(ctx.get_anchors().is_empty());
+    }
+
+    #[test]
+    fn test_error_reflection_cycles_stay_bounded() {
+        let strings = AgentStrings::new(crate::agent::language::Locale::En);
+        let mut messages = vec![Message::new(Role::User, "Read the config"), Message::empty_reply()];
+
+        for cycle in 1..=10 {
+            // The failed attempt takes the pending reply, then the loop reflects
+            messages.last_mut().unwrap().content = format!("❌ file_read failed ({})", cycle);
+            push_notice(&mut messages, NoticeKind::Reflection, format!("Reflect on error {}", cycle), &strings);
+            if has_stray_empty_replies(&messages) {
+                prune_empty_replies(&mut messages);
+            }
+            assert!(messages.len() <= 4, "cycle {}: {} messages", cycle, messages.len());
+        }
+
+        let notice = messages[2].notice().unwrap();
+        assert_eq!(notice, Notice { kind: NoticeKind::Reflection, count: 10 });
+        assert!(messages[2].content.starts_with("[10 reflection prompts]"));
+        assert!(messages[2].content.ends_with("Reflect on error 10"));
+        assert_eq!(messages[1].content, "❌ file_read failed (10)");
+
+        let mut conv = crate::storage::conversations::Conversation::new(None);
+        conv.messages = persisted_messages(&messages);
+        let json = serde_json::to_string(&conv).unwrap();
+        let saved: crate::storage::conversations::Conversation = serde_json::from_str(&json).unwrap();
+        assert_eq!(saved.messages.len(), 3);
+        assert!(!saved.messages.iter().any(|m| m.is_empty_reply()));
+    }
+
+    #[test]
+    fn test_notices_of_other_kinds_are_kept() {
+        let strings = AgentStrings::new(crate::agent::language::Locale::En);
+        let mut messages = vec![Message::new(Role::User, "hi"), Message::new(Role::Assistant, "attempt")];
+        push_notice(&mut messages, NoticeKind::Reflection, "reflect".to_string(), &strings);
+        messages.last_mut().unwrap().content = "second attempt".to_string();
+        push_notice(&mut messages, NoticeKind::ToolRefused, "refused".to_string(), &strings);
+
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[4].notice().unwrap().count, 1);
+        assert!(messages[5].is_empty_reply());
+    }
+
+    /// Scripted model: reads the file unless the prompt says it was already read
+    fn scripted_model(system_prompt: &str, path: &str) -> String {
+        let already_read = system_prompt
+            .lines()
+            .any(|l| l.starts_with("Files already read") && l.contains(path));
+        if already_read {
+            "In summary, the file contains the configuration you asked about, nothing else is needed.".to_string()
+        } else {
+            format!(r#"{{"tool": "file_read", "params": {{"path": "{}"}}}}"#, path.replace('\\', "\\\\"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_summary_prevents_rereading_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.toml");
+        std::fs::write(&file, "key = 1\n").unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register(Arc::new(crate::agent::tools::builtins::FileReadTool)).await;
+        let loop_runner = AgentLoop::new(AgentLoopConfig::default(), registry.clone());
+        let (event_tx, _event_rx) = mpsc::channel(32);
+
+        let mut ctx = AgentContext::new();
+        let mut final_answer = None;
+        while ctx.iteration < 5 {
+            ctx.iteration += 1;
+            let prompt = crate::agent::prompts::build_agent_system_prompt(
+                "", &registry.list_tools(), Some(&ctx), None, None, None, None,
+                crate::agent::prompts::ThinkingGuidance::Prompted,
+                crate::storage::settings::ToolCallFormat::Json,
+            );
+            match loop_runner.analyze_response(&scripted_model(&prompt, &path), &ctx) {
+                IterationResult::ToolCall(call) => {
+                    loop_runner.execute_tool_with_retry(&call, &mut ctx, &event_tx).await.unwrap();
+                }
+                IterationResult::Complete(answer) => {
+                    final_answer = Some(answer);
+                    break;
+                }
+                other => panic!("unexpected iteration result: {:?}", other),
+            }
+        }
+
+        assert!(final_answer.is_some());
+        assert_eq!(ctx.iteration, 2);
+        let reads = ctx.tool_history.iter().filter(|e| e.tool_name == "file_read").count();
+        assert_eq!(reads, 1);
+    }
+
+    #[test]
+    fn test_pending_tool_calls_follow_events() {
+        let events = vec![
+            AgentEvent::Progress { iteration: 2, max_iterations: 25, message: "file_read".to_string() },
+            AgentEvent::ToolCallStarted { tool: "file_read".to_string(), params: serde_json::json!({"path": "a"}) },
+            AgentEvent::PermissionRequested { tool: "bash".to_string(), level: "Execute".to_string(), target: "ls".to_string() },
+        ];
+        let pending = pending_tool_calls(&events);
+        assert_eq!(pending.len(), 2);
+        assert!(matches!(&pending[1], PendingToolCall::AwaitingPermission { tool, .. } if tool == "bash"));
+        assert_eq!(latest_progress(&events), Some((2, 25)));
+
+        let mut events = events;
+        events.push(AgentEvent::ToolCallCompleted {
+            tool: "file_read".to_string(),
+            result: ToolResult { success: true, data: Value::Null, message: "ok".to_string() },
+        });
+        events.push(AgentEvent::ToolCallStarted { tool: "bash".to_string(), params: serde_json::json!({"command": "ls"}) });
+        assert_eq!(
+            pending_tool_calls(&events),
+            vec![PendingToolCall::Running { tool: "bash".to_string(), params: serde_json::json!({"command": "ls"}), retry_count: 0 }]
+        );
+
+        // A failure that will be retried keeps the card, with its count
+        events.push(AgentEvent::ToolCallFailed { tool: "bash".to_string(), error: "Timeout".to_string(), retry_count: 1, will_retry: true });
+        events.push(AgentEvent::ToolCallStarted { tool: "bash".to_string(), params: serde_json::json!({"command": "ls"}) });
+        assert!(matches!(pending_tool_calls(&events).as_slice(), [PendingToolCall::Running { retry_count: 1, .. }]));
+
+        events.push(AgentEvent::ToolCallFailed { tool: "bash".to_string(), error: PERMISSION_DENIED_ERROR.to_string(), retry_count: 0, will_retry: false });
+        assert!(pending_tool_calls(&events).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retry_tool_call_only_retries_transient_errors() {
+        let cancel = CancellationToken::new();
+        let mut attempts = 0;
+        let mut retries = Vec::new();
+        let result = retry_tool_call(
+            2,
+            &cancel,
+            || {
+                attempts += 1;
+                let outcome = if attempts < 3 {
+                    Err(ToolError::Timeout { after_secs: Some(1) })
+                } else {
+                    Ok(ToolResult { success: true, data: Value::Null, message: "ok".to_string() })
+                };
+                async move { outcome }
+            },
+            |_, retry| retries.push(retry),
+        )
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(retries, vec![1, 2]);
+
+        let mut attempts = 0;
+        let result = retry_tool_call(
+            2,
+            &cancel,
+            || {
+                attempts += 1;
+                async { Err(ToolError::InvalidParameters("missing path".to_string())) }
+            },
+            |_, _| {},
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+        assert_eq!(attempts, 1);
+    }
 }
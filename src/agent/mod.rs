@@ -15,22 +15,33 @@ pub mod loop_runner;
 pub mod planning;
 pub mod prompts;
 pub mod mcp_config;
+pub mod environment;
+pub mod language;
+pub mod compression;
+pub mod prompt_builder;
+pub mod replay;
+#[cfg(test)]
+pub mod harness;
 
 use std::sync::Arc;
 use skills::{SkillRegistry, loader::SkillLoader};
+use crate::storage::settings::ToolCallFormat;
 
 pub use permissions::{
     PermissionLevel, PermissionManager, PermissionRequest, PermissionResult,
     PermissionPolicy, PermissionSignals, PermissionDecision, PermissionNotification,
 };
-pub use tools::{Tool, ToolRegistry, ToolResult, ToolError, ToolInfo};
+pub use tools::{ErrorCategory, Tool, ToolRegistry, ToolResult, ToolError, ToolInfo};
 pub use tools::exa::{ExaSearchTool, ExaSearchConfig, create_exa_tools};
 pub use tools::mcp_client::{McpServerConfig, McpTransport, McpServerManager};
 pub use tools::mcp_presets::{McpPreset, McpCategory, get_all_presets};
-pub use runner::{ToolCall, extract_tool_call, build_tool_instructions, format_tool_result_for_system};
+pub use runner::{ToolCall, extract_tool_call, extract_tool_calls, build_tool_instructions, format_tool_result, format_tool_result_for_system};
 pub use loop_runner::{AgentLoop, AgentLoopConfig, AgentState, AgentContext, AgentEvent, IterationResult};
 pub use planning::{TaskPlan, Task, TaskStatus, TaskPriority, PlanManager};
-pub use prompts::{build_agent_system_prompt, build_tool_instructions_advanced, build_context_compression_prompt};
+pub use environment::EnvironmentContext;
+pub use language::{detect_language, set_tool_language, tool_language, AgentStrings, Locale};
+pub use prompt_builder::{prompt_budget, PromptBuilder};
+pub use prompts::{build_agent_system_prompt, ThinkingGuidance, build_tool_instructions_advanced, build_context_compression_prompt};
 
 /// Agent configuration
 #[derive(Clone, Debug)]
@@ -99,6 +110,8 @@ pub struct Agent {
     pub permission_manager: Arc<PermissionManager>,
     pub plan_manager: PlanManager,
     pub skill_registry: Arc<SkillRegistry>,
+    /// Page cache of the web_fetch tool, scoped to the active conversation
+    pub web_cache: Arc<tools::web::FetchCache>,
 }
 
 impl Agent {
@@ -113,6 +126,7 @@ impl Agent {
             permission_manager,
             plan_manager: PlanManager::new(),
             skill_registry,
+            web_cache: Arc::new(tools::web::FetchCache::default()),
         }
     }
     
@@ -121,12 +135,17 @@ impl Agent {
         use tools::builtins;
         use tools::filesystem;
         use tools::shell;
+        use tools::cargo;
+        use tools::test_runners;
         use tools::git;
+        use tools::github;
         use tools::dev;
+        use tools::lsp;
         use tools::system;
         use tools::skill_create;
         use tools::skill_invoke;
         use tools::skill_list;
+        use tools::memory;
         
         tracing::info!("Initializing agent tools...");
         
@@ -145,7 +164,14 @@ impl Agent {
         // ============================================================
         self.tool_registry.register(Arc::new(skill_invoke::SkillInvokeTool)).await;
         self.tool_registry.register(Arc::new(skill_list::SkillListTool)).await;
-        tracing::info!("Core tools registered (think, todo_write, skill_create, skill_invoke, skill_list)");
+
+        // ============================================================
+        // Long-term memory tools
+        // ============================================================
+        self.tool_registry.register(Arc::new(memory::MemoryWriteTool)).await;
+        self.tool_registry.register(Arc::new(memory::MemoryReadTool)).await;
+        self.tool_registry.register(Arc::new(memory::MemorySearchTool)).await;
+        tracing::info!("Core tools registered (think, todo_write, skill_create, skill_invoke, skill_list, memory_write, memory_read, memory_search)");
         
         // ============================================================
         // Web search tools (Exa)
@@ -159,6 +185,17 @@ impl Agent {
             tracing::info!("Exa search tools registered (web_search, code_search, company_research, deep_research, web_crawl)");
         }
         
+        // ============================================================
+        // Web fetch tools
+        // ============================================================
+        if self.config.enable_web_fetch {
+            self.tool_registry.register(Arc::new(tools::web::WebFetchTool::new(self.web_cache.clone()))).await;
+            self.tool_registry.register(Arc::new(tools::web::WebReadTool)).await;
+            self.tool_registry.register(Arc::new(tools::web::WebDownloadTool::default())).await;
+            self.tool_registry.register(Arc::new(tools::web::HttpRequestTool::default())).await;
+            tracing::info!("Web tools registered (web_fetch, web_read, web_download, http_request)");
+        }
+        
         // ============================================================
         // File system tools (read-only)
         // ============================================================
@@ -169,7 +206,10 @@ impl Agent {
             self.tool_registry.register(Arc::new(builtins::GlobTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileInfoTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileSearchContentTool)).await;
-            tracing::info!("Filesystem read tools registered (file_read, file_list, grep, glob, file_info, file_search)");
+            self.tool_registry.register(Arc::new(tools::symbols::CodeSearchTool)).await;
+            self.tool_registry.register(Arc::new(tools::csv::CsvAnalyzeTool)).await;
+            self.tool_registry.register(Arc::new(tools::images::ImageInfoTool)).await;
+            tracing::info!("Filesystem read tools registered (file_read, file_list, grep, glob, file_info, file_search, code_search, csv_analyze, image_info)");
         }
         
         // ============================================================
@@ -178,12 +218,17 @@ impl Agent {
         if self.config.enable_file_write {
             self.tool_registry.register(Arc::new(builtins::FileWriteTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileEditTool)).await;
+            self.tool_registry.register(Arc::new(filesystem::FileMultiEditTool)).await;
+            self.tool_registry.register(Arc::new(filesystem::FilePatchLinesTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileCreateTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileDeleteTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileMoveTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileCopyTool)).await;
             self.tool_registry.register(Arc::new(filesystem::DirectoryCreateTool)).await;
-            tracing::info!("Filesystem write tools registered (file_write, file_edit, file_create, file_delete, file_move, file_copy, directory_create)");
+            self.tool_registry.register(Arc::new(tools::encoding::FileConvertTool)).await;
+            self.tool_registry.register(Arc::new(tools::backup::UndoFileChangeTool)).await;
+            self.tool_registry.register(Arc::new(tools::backup::ListFileBackupsTool)).await;
+            tracing::info!("Filesystem write tools registered (file_write, file_edit, file_multi_edit, file_patch_lines, file_create, file_delete, file_move, file_copy, directory_create, file_convert, undo_file_change, list_file_backups)");
         }
         
         // ============================================================
@@ -192,7 +237,15 @@ impl Agent {
         if self.config.enable_bash {
             self.tool_registry.register(Arc::new(shell::BashTool)).await;
             self.tool_registry.register(Arc::new(shell::BashBackgroundTool)).await;
-            tracing::info!("Shell tools registered (bash, bash_background)");
+            self.tool_registry.register(Arc::new(shell::JobStartTool)).await;
+            self.tool_registry.register(Arc::new(shell::JobStatusTool)).await;
+            self.tool_registry.register(Arc::new(shell::JobLogsTool)).await;
+            self.tool_registry.register(Arc::new(shell::JobKillTool)).await;
+            self.tool_registry.register(Arc::new(cargo::CargoCheckTool)).await;
+            self.tool_registry.register(Arc::new(cargo::CargoTestTool)).await;
+            self.tool_registry.register(Arc::new(test_runners::PytestRunTool)).await;
+            self.tool_registry.register(Arc::new(test_runners::NpmTestTool)).await;
+            tracing::info!("Shell tools registered (bash, bash_background, job_start, job_status, job_logs, job_kill, cargo_check, cargo_test, pytest_run, npm_test)");
         }
         
         // Legacy safe command tool
@@ -207,11 +260,16 @@ impl Agent {
         if self.config.enable_git {
             self.tool_registry.register(Arc::new(git::GitStatusTool)).await;
             self.tool_registry.register(Arc::new(git::GitDiffTool)).await;
+            self.tool_registry.register(Arc::new(git::GitBranchDiffTool)).await;
             self.tool_registry.register(Arc::new(git::GitLogTool)).await;
             self.tool_registry.register(Arc::new(git::GitCommitTool)).await;
             self.tool_registry.register(Arc::new(git::GitBranchTool)).await;
             self.tool_registry.register(Arc::new(git::GitStashTool)).await;
-            tracing::info!("Git tools registered (git_status, git_diff, git_log, git_commit, git_branch, git_stash)");
+            self.tool_registry.register(Arc::new(github::GitHubIssueViewTool)).await;
+            self.tool_registry.register(Arc::new(github::GitHubIssueListTool)).await;
+            self.tool_registry.register(Arc::new(github::GitHubPrViewTool)).await;
+            self.tool_registry.register(Arc::new(github::GitHubPrCreateTool)).await;
+            tracing::info!("Git tools registered (git_status, git_diff, git_branch_diff, git_log, git_commit, git_branch, git_stash, github_issue_view, github_issue_list, github_pr_view, github_pr_create)");
         }
         
         // ============================================================
@@ -262,7 +320,9 @@ impl Agent {
             self.tool_registry.register(Arc::new(dev::FindReplaceTool)).await;
             self.tool_registry.register(Arc::new(dev::PatchTool)).await;
             self.tool_registry.register(Arc::new(dev::CountLinesTool)).await;
-            tracing::info!("Developer tools registered (diff, find_replace, patch, wc)");
+            self.tool_registry.register(Arc::new(lsp::LspDiagnosticsTool)).await;
+            self.tool_registry.register(Arc::new(lsp::LspHoverTool)).await;
+            tracing::info!("Developer tools registered (diff, find_replace, patch, wc, lsp_diagnostics, lsp_hover)");
         }
         
         // ============================================================
@@ -273,8 +333,9 @@ impl Agent {
             self.tool_registry.register(Arc::new(system::EnvironmentTool)).await;
             self.tool_registry.register(Arc::new(system::SystemInfoTool)).await;
             self.tool_registry.register(Arc::new(system::WhichTool)).await;
-            self.tool_registry.register(Arc::new(system::TreeTool)).await;
-            tracing::info!("System tools registered (process_list, environment, system_info, which, tree)");
+            self.tool_registry.register(Arc::new(system::TreeTool::default())).await;
+            self.tool_registry.register(Arc::new(system::ProjectOverviewTool)).await;
+            tracing::info!("System tools registered (process_list, environment, system_info, which, tree, project_overview)");
         }
         
         // ============================================================
@@ -298,9 +359,12 @@ impl Agent {
         // Skills (loaded from .localclaw/skills)
         // ============================================================
         tracing::info!("Loading skills...");
-        let skills = SkillLoader::load_all().await;
-        let skill_count = skills.len();
-        for skill in skills {
+        let report = SkillLoader::load_all_with_report().await;
+        let skill_count = report.skills.len();
+        if let skills::loader::SkillsStatus::Unreadable { path, error } = report.status() {
+            tracing::warn!("Skills directory {} unreadable: {}", path.display(), error);
+        }
+        for skill in report.skills {
             self.skill_registry.register(skill).await;
         }
         self.skill_registry.register_as_tools(&self.tool_registry).await;
@@ -312,10 +376,13 @@ impl Agent {
         Ok(())
     }
     
-    /// Create an agent loop runner
-    pub fn create_loop(&self) -> AgentLoop {
+    /// Create an agent loop runner for the loaded model
+    pub fn create_loop(&self, reasoning_model: bool) -> AgentLoop {
         AgentLoop::new(
-            self.config.loop_config.clone(),
+            AgentLoopConfig {
+                enable_thinking: reasoning_model,
+                ..self.config.loop_config.clone()
+            },
             self.tool_registry.clone(),
         )
     }
@@ -331,7 +398,7 @@ impl Agent {
         let ctx = None; // Will be provided during execution
         let plan = self.plan_manager.current();
         
-        build_agent_system_prompt(base_prompt, &tools, ctx, plan)
+        build_agent_system_prompt(base_prompt, &tools, ctx, plan, None, None, None, ThinkingGuidance::Prompted, ToolCallFormat::Json)
     }
 }
 
@@ -340,10 +407,12 @@ pub fn get_tool_permission(tool_name: &str) -> PermissionLevel {
     match tool_name {
         // Read-only tools (no side effects)
         "file_read" | "file_list" | "grep" | "glob" | "think" | "todo_write"
-        | "file_info" | "file_search" | "diff" | "wc" | "tree"
+        | "file_info" | "file_search" | "code_search" | "csv_analyze" | "image_info" | "diff" | "wc" | "tree" | "project_overview"
         | "process_list" | "environment" | "system_info" | "which"
-        | "git_status" | "git_diff" | "git_log" | "git_branch"
-        | "pdf_read"
+        | "git_status" | "git_diff" | "git_branch_diff" | "git_log" | "git_branch"
+        | "pdf_read" | "list_file_backups" | "job_status" | "job_logs"
+        | "index_build" | "index_search" | "index_status"
+        | "memory_read" | "memory_search"
         | "skill_list" | "skill_invoke" 
         | "mcp_list_servers" => {
             PermissionLevel::ReadOnly
@@ -351,22 +420,28 @@ pub fn get_tool_permission(tool_name: &str) -> PermissionLevel {
         // Network tools (external requests)
         "web_search" | "code_search" | "company_research" 
         | "deep_research_start" | "deep_research_check" | "web_crawl"
-        | "web_fetch" | "web_download" | "ai_consult" => {
+        | "web_fetch" | "web_read" | "web_download" | "ai_consult"
+        | "github_issue_view" | "github_issue_list" | "github_pr_view" => {
             PermissionLevel::Network
         }
         // Write tools (file modifications)
-        "file_write" | "file_edit" | "file_create" | "file_delete" 
-        | "file_move" | "file_copy" | "directory_create"
+        "file_write" | "file_edit" | "file_multi_edit" | "file_patch_lines"
+        | "file_create" | "file_delete" 
+        | "file_move" | "file_copy" | "directory_create" | "file_convert" | "undo_file_change"
+        | "http_request" | "github_pr_create"
         | "find_replace" | "patch"
         | "pdf_create" | "pdf_add_page" | "pdf_merge"
-        | "skill_create" 
+        | "skill_create" | "memory_write"
         | "mcp_add_server" | "mcp_remove_server" => {
             PermissionLevel::WriteFile
         }
         // Safe command execution
-        "command" => PermissionLevel::ExecuteSafe,
+        "command" | "job_kill" | "cargo_check" | "cargo_test" | "pytest_run" | "npm_test" | "lsp_diagnostics"
+        | "lsp_hover" => {
+            PermissionLevel::ExecuteSafe
+        }
         // Unsafe execution (full shell, git writes)
-        "bash" | "bash_background" | "git_commit" | "git_stash" => {
+        "bash" | "bash_background" | "job_start" | "git_commit" | "git_commit_prepared" | "git_stash" => {
             PermissionLevel::ExecuteUnsafe
         }
         // MCP tools (from external servers)
@@ -376,6 +451,57 @@ pub fn get_tool_permission(tool_name: &str) -> PermissionLevel {
     }
 }
 
+/// Tools whose `command` parameter goes through the command policy
+pub const POLICY_CHECKED_TOOLS: &[&str] = &["command", "job_start"];
+
+/// Permission level of one call: `get_tool_permission`, raised to
+/// `ExecuteUnsafe` for a command the command policy leaves to the user
+pub fn get_call_permission(tool_name: &str, params: &serde_json::Value) -> PermissionLevel {
+    if needs_policy_approval(tool_name, params) {
+        PermissionLevel::ExecuteUnsafe
+    } else {
+        get_tool_permission(tool_name)
+    }
+}
+
+/// Whether the command policy wants the user to approve this call, even
+/// when the tool is allow-listed
+pub fn needs_policy_approval(tool_name: &str, params: &serde_json::Value) -> bool {
+    POLICY_CHECKED_TOOLS.contains(&tool_name) && params["command"].as_str().is_some_and(needs_command_approval)
+}
+
+/// Whether the command policy wants the user to approve `command`
+pub fn needs_command_approval(command: &str) -> bool {
+    matches!(
+        tools::command_policy::check_command(command),
+        tools::command_policy::CommandVerdict::Prompt(_)
+    )
+}
+
+/// Permission prompt text of calls that are not file edits (those have
+/// `filesystem::preview_change`)
+pub async fn call_preview(tool_name: &str, params: &serde_json::Value) -> Option<String> {
+    match tool_name {
+        "git_commit_prepared" => tools::git::prepared_commit_preview(params).await,
+        "github_pr_create" => tools::github::pr_create_preview(params),
+        _ => None,
+    }
+}
+
+/// Whether a tool may run in a read-only conversation
+pub fn is_tool_allowed_read_only(tool_name: &str) -> bool {
+    let level = get_tool_permission(tool_name);
+    match tool_name {
+        // Network level but writes the downloaded file to disk
+        "web_download" => false,
+        // Sub-agent tool calls are not checked against read-only mode
+        tools::task::AGENT_TASK_TOOL => false,
+        // External MCP tools: side effects unknown
+        name if name.starts_with("mcp_") && level == PermissionLevel::Network => false,
+        _ => level.allowed_in_read_only(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -402,24 +528,59 @@ mod tests {
         assert_eq!(get_tool_permission("git_status"), PermissionLevel::ReadOnly);
         assert_eq!(get_tool_permission("tree"), PermissionLevel::ReadOnly);
         assert_eq!(get_tool_permission("diff"), PermissionLevel::ReadOnly);
+        assert_eq!(get_tool_permission("index_search"), PermissionLevel::ReadOnly);
         // Network
         assert_eq!(get_tool_permission("web_search"), PermissionLevel::Network);
         assert_eq!(get_tool_permission("web_fetch"), PermissionLevel::Network);
+        assert_eq!(get_tool_permission("web_read"), PermissionLevel::Network);
         // Write
         assert_eq!(get_tool_permission("file_write"), PermissionLevel::WriteFile);
         assert_eq!(get_tool_permission("file_edit"), PermissionLevel::WriteFile);
         assert_eq!(get_tool_permission("file_create"), PermissionLevel::WriteFile);
         assert_eq!(get_tool_permission("find_replace"), PermissionLevel::WriteFile);
+        assert_eq!(get_tool_permission("http_request"), PermissionLevel::WriteFile);
+        assert_eq!(get_tool_permission("github_pr_create"), PermissionLevel::WriteFile);
+        assert_eq!(get_tool_permission("github_issue_view"), PermissionLevel::Network);
         // Execute
         assert_eq!(get_tool_permission("command"), PermissionLevel::ExecuteSafe);
+        assert_eq!(get_call_permission("command", &serde_json::json!({"command": "ls"})), PermissionLevel::ExecuteSafe);
+        assert_eq!(
+            get_call_permission("command", &serde_json::json!({"command": "make install"})),
+            PermissionLevel::ExecuteUnsafe
+        );
         assert_eq!(get_tool_permission("bash"), PermissionLevel::ExecuteUnsafe);
+        assert_eq!(get_tool_permission("job_start"), PermissionLevel::ExecuteUnsafe);
+        assert!(needs_policy_approval("job_start", &serde_json::json!({"command": "make install"})));
+        assert!(!needs_policy_approval("job_start", &serde_json::json!({"command": "cargo test"})));
+        assert!(!needs_policy_approval("bash", &serde_json::json!({"command": "make install"})));
+        assert_eq!(get_tool_permission("cargo_test"), PermissionLevel::ExecuteSafe);
         assert_eq!(get_tool_permission("git_commit"), PermissionLevel::ExecuteUnsafe);
+        assert_eq!(get_tool_permission("git_commit_prepared"), PermissionLevel::ExecuteUnsafe);
         // Skill tools
         assert_eq!(get_tool_permission("skill_invoke"), PermissionLevel::ReadOnly);
         assert_eq!(get_tool_permission("skill_list"), PermissionLevel::ReadOnly);
+        // Memory tools
+        assert_eq!(get_tool_permission("memory_search"), PermissionLevel::ReadOnly);
+        assert_eq!(get_tool_permission("memory_write"), PermissionLevel::WriteFile);
         // MCP
         assert_eq!(get_tool_permission("mcp_github_list_repos"), PermissionLevel::Network);
     }
+
+    #[test]
+    fn test_read_only_tool_filter() {
+        assert!(is_tool_allowed_read_only("file_read"));
+        assert!(is_tool_allowed_read_only("git_log"));
+        assert!(is_tool_allowed_read_only("web_search"));
+        assert!(is_tool_allowed_read_only("mcp_list_servers"));
+        assert!(!is_tool_allowed_read_only("file_write"));
+        assert!(!is_tool_allowed_read_only("file_delete"));
+        assert!(!is_tool_allowed_read_only("command"));
+        assert!(!is_tool_allowed_read_only("bash"));
+        assert!(!is_tool_allowed_read_only("git_commit"));
+        assert!(!is_tool_allowed_read_only("web_download"));
+        assert!(!is_tool_allowed_read_only("mcp_github_create_issue"));
+        assert!(!is_tool_allowed_read_only("mcp_add_server"));
+    }
     
     #[tokio::test]
     #[ignore = "Agent::new créée PermissionManager avec Signaux Dioxus qui nécessitent un contexte VirtualDom"]
@@ -445,21 +606,41 @@ mod tests {
         assert!(names.contains(&"grep"));
         assert!(names.contains(&"glob"));
         assert!(names.contains(&"file_info"));
+        assert!(names.contains(&"code_search"));
+        assert!(names.contains(&"csv_analyze"));
+        assert!(names.contains(&"image_info"));
         // Write tools
         assert!(names.contains(&"file_edit"));
+        assert!(names.contains(&"file_multi_edit"));
+        assert!(names.contains(&"file_patch_lines"));
         assert!(names.contains(&"file_create"));
         assert!(names.contains(&"file_delete"));
+        assert!(names.contains(&"file_convert"));
+        assert!(names.contains(&"undo_file_change"));
+        assert!(names.contains(&"list_file_backups"));
         // Shell tools
         assert!(names.contains(&"bash"));
+        assert!(names.contains(&"job_start"));
+        assert!(names.contains(&"job_logs"));
+        assert!(names.contains(&"cargo_check"));
+        assert!(names.contains(&"cargo_test"));
+        assert!(names.contains(&"pytest_run"));
+        assert!(names.contains(&"npm_test"));
         // Git tools
         assert!(names.contains(&"git_status"));
         assert!(names.contains(&"git_diff"));
+        assert!(names.contains(&"git_branch_diff"));
+        assert!(names.contains(&"github_issue_view"));
+        assert!(names.contains(&"github_pr_create"));
         assert!(names.contains(&"git_log"));
         // Dev tools
         assert!(names.contains(&"diff"));
         assert!(names.contains(&"find_replace"));
+        assert!(names.contains(&"lsp_diagnostics"));
+        assert!(names.contains(&"lsp_hover"));
         // System tools
         assert!(names.contains(&"tree"));
+        assert!(names.contains(&"project_overview"));
         assert!(names.contains(&"which"));
         assert!(names.contains(&"system_info"));
         
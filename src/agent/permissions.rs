@@ -69,6 +69,14 @@ impl PermissionLevel {
         }
     }
     
+    /// Whether this level may run while a conversation is in read-only mode
+    ///
+    /// Read-only mode is an override on top of the normal approval flow:
+    /// anything that writes or executes is rejected, even when auto-approved.
+    pub fn allowed_in_read_only(self) -> bool {
+        matches!(self, PermissionLevel::ReadOnly | PermissionLevel::Network)
+    }
+
     /// Icon for UI
     pub fn icon(&self) -> &'static str {
         match self {
@@ -92,6 +100,9 @@ pub struct PermissionRequest {
     pub level: PermissionLevel,
     pub params: Value,
     pub timestamp: DateTime<Utc>,
+    /// Diff of the file change the call would make, for file writing tools
+    #[serde(default)]
+    pub preview: Option<String>,
 }
 
 /// Policy configuration for permission checks.
@@ -0,0 +1,251 @@
+//! Token-budgeted prompt assembly
+//!
+//! The chat loop, the test harness and the replay all build their prompts
+//! here: the system prompt, the history and any pending tool results, trimmed
+//! to a token budget. The oldest unanchored messages are dropped first; the
+//! system prompt and the last user message are only cut down once nothing else
+//! is left to drop.
+
+use crate::inference::tokens::estimate_tokens;
+use crate::types::message::{Message, Role};
+
+/// Tokens a message costs beyond its text (role markers of the chat template)
+pub const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Appended to a message cut down to fit the budget
+const TRUNCATION_MARKER: &str = "\n[... truncated to fit the context ...]";
+
+/// Prompt budget for a context window, leaving a quarter of it for the reply
+pub fn prompt_budget(context_tokens: usize) -> usize {
+    context_tokens - context_tokens / 4
+}
+
+/// Estimated cost of a message in the prompt
+pub fn message_tokens(message: &Message) -> usize {
+    estimate_tokens(&message.content) + MESSAGE_OVERHEAD_TOKENS
+}
+
+/// Builds the messages sent to the model
+///
+/// ```ignore
+/// let prompt = PromptBuilder::new()
+///     .with_system(system_prompt)
+///     .with_history(&history)
+///     .with_budget(prompt_budget(context_size))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PromptBuilder {
+    system: Option<String>,
+    history: Vec<Message>,
+    tool_results: Vec<Message>,
+    budget: Option<usize>,
+}
+
+impl PromptBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// System prompt, pinned first; a blank one is left out
+    pub fn with_system(mut self, prompt: impl Into<String>) -> Self {
+        let prompt = prompt.into();
+        self.system = (!prompt.trim().is_empty()).then_some(prompt);
+        self
+    }
+
+    /// Conversation history, oldest first; empty messages (a reply still to
+    /// stream) are left out
+    pub fn with_history(mut self, history: &[Message]) -> Self {
+        self.history.extend(history.iter().filter(|m| !m.content.is_empty()).cloned());
+        self
+    }
+
+    /// Tool results not in the history yet, placed after it
+    pub fn with_tool_results(mut self, results: impl IntoIterator<Item = Message>) -> Self {
+        self.tool_results.extend(results);
+        self
+    }
+
+    /// Most tokens the prompt may take (see `message_tokens`)
+    pub fn with_budget(mut self, tokens: usize) -> Self {
+        self.budget = Some(tokens);
+        self
+    }
+
+    pub fn build(self) -> Vec<Message> {
+        let mut messages = Vec::with_capacity(self.history.len() + self.tool_results.len() + 1);
+        if let Some(system) = self.system {
+            messages.push(Message::system_prompt(system));
+        }
+        messages.extend(self.history);
+        messages.extend(self.tool_results);
+        if let Some(budget) = self.budget {
+            fit_to_budget(&mut messages, budget);
+        }
+        messages
+    }
+}
+
+/// Trim `messages` until their estimated cost is within `budget`
+fn fit_to_budget(messages: &mut Vec<Message>, budget: usize) {
+    let mut total: usize = messages.iter().map(message_tokens).sum();
+    if total <= budget {
+        return;
+    }
+
+    // Oldest unanchored messages first
+    let last_user = messages.iter().rposition(|m| m.role == Role::User);
+    let mut dropped = 0usize;
+    let mut index = 0usize;
+    messages.retain(|message| {
+        let anchored = message.is_pinned() || Some(index) == last_user;
+        index += 1;
+        if total > budget && !anchored {
+            total -= message_tokens(message);
+            dropped += 1;
+            return false;
+        }
+        true
+    });
+    if dropped > 0 {
+        tracing::debug!("Prompt over budget: dropped {} oldest message(s)", dropped);
+    }
+
+    // Then cut the anchored ones down: cap every text at the largest size that fits
+    if total > budget {
+        let available = budget.saturating_sub(messages.len() * MESSAGE_OVERHEAD_TOKENS);
+        let mut sizes: Vec<usize> = messages.iter().map(|m| estimate_tokens(&m.content)).collect();
+        sizes.sort_unstable();
+        let mut remaining = available;
+        let mut cap = usize::MAX;
+        for (i, size) in sizes.iter().enumerate() {
+            let left = sizes.len() - i;
+            if size * left > remaining {
+                cap = remaining / left;
+                break;
+            }
+            remaining -= size;
+        }
+        for message in messages.iter_mut() {
+            if estimate_tokens(&message.content) > cap {
+                truncate_to_tokens(&mut message.content, cap);
+            }
+        }
+        total = messages.iter().map(message_tokens).sum();
+        tracing::debug!("Prompt over budget: cut messages to ~{} tokens each", cap);
+    }
+
+    // A budget below the per-message overhead: drop from the oldest
+    while total > budget && !messages.is_empty() {
+        total -= message_tokens(&messages.remove(0));
+    }
+}
+
+/// Keep the start of `content` within `tokens`, marking the cut
+fn truncate_to_tokens(content: &mut String, tokens: usize) {
+    let Some(mut keep) = (tokens * 4).checked_sub(TRUNCATION_MARKER.len()) else {
+        content.clear();
+        return;
+    };
+    if keep >= content.len() {
+        return;
+    }
+    while !content.is_char_boundary(keep) {
+        keep -= 1;
+    }
+    content.truncate(keep);
+    content.push_str(TRUNCATION_MARKER);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(messages: &[Message]) -> usize {
+        messages.iter().map(message_tokens).sum()
+    }
+
+    #[test]
+    fn test_fits_without_trimming() {
+        let history = vec![Message::new(Role::User, "Hello"), Message::new(Role::Assistant, "")];
+        let prompt = PromptBuilder::new()
+            .with_system("You are helpful.")
+            .with_history(&history)
+            .with_tool_results([Message::new(Role::System, "Tool result: ok")])
+            .with_budget(1000)
+            .build();
+        // The empty reply being streamed into is left out
+        assert_eq!(prompt.len(), 3);
+        assert!(prompt[0].is_pinned());
+        assert_eq!(prompt[1].content, "Hello");
+        assert_eq!(prompt[2].content, "Tool result: ok");
+
+        assert_eq!(PromptBuilder::new().with_system("  ").build().len(), 0);
+    }
+
+    #[test]
+    fn test_drops_oldest_unanchored_first() {
+        let mut history = Vec::new();
+        for i in 0..10 {
+            history.push(Message::new(Role::User, format!("question {} {}", i, "x".repeat(200))));
+            history.push(Message::new(Role::Assistant, format!("answer {} {}", i, "y".repeat(200))));
+        }
+        let prompt = PromptBuilder::new()
+            .with_system("system")
+            .with_history(&history)
+            .with_budget(300)
+            .build();
+
+        assert!(cost(&prompt) <= 300);
+        assert!(prompt[0].is_pinned());
+        // The newest messages survive, in order, the last question included
+        let last = &prompt[prompt.len() - 1];
+        assert!(last.content.starts_with("answer 9"));
+        assert!(prompt.iter().any(|m| m.content.starts_with("question 9")));
+        assert!(!prompt.iter().any(|m| m.content.starts_with("question 0")));
+    }
+
+    #[test]
+    fn test_never_exceeds_budget_for_adversarial_histories() {
+        let huge = "é".repeat(20_000);
+        let histories: Vec<Vec<Message>> = vec![
+            // One enormous user message
+            vec![Message::new(Role::User, huge.clone())],
+            // Hundreds of small messages
+            (0..500).map(|i| Message::new(Role::Assistant, format!("step {}", i))).collect(),
+            // Huge tool outputs around a huge question
+            vec![
+                Message::new(Role::System, huge.clone()),
+                Message::new(Role::User, huge.clone()),
+                Message::new(Role::System, huge.clone()),
+            ],
+            // Pinned messages only
+            vec![Message::system_prompt(huge.clone()), Message::system_prompt(huge.clone())],
+        ];
+        for history in &histories {
+            for budget in [0, 1, 3, 4, 10, 50, 500, 5000] {
+                let prompt = PromptBuilder::new()
+                    .with_system(huge.clone())
+                    .with_history(history)
+                    .with_tool_results([Message::new(Role::System, huge.clone())])
+                    .with_budget(budget)
+                    .build();
+                assert!(cost(&prompt) <= budget, "{} tokens over a budget of {}", cost(&prompt), budget);
+            }
+        }
+    }
+
+    #[test]
+    fn test_anchors_are_cut_not_dropped() {
+        let history = vec![Message::new(Role::User, "a".repeat(4000))];
+        let prompt = PromptBuilder::new()
+            .with_system("b".repeat(4000))
+            .with_history(&history)
+            .with_budget(600)
+            .build();
+        assert_eq!(prompt.len(), 2);
+        assert!(cost(&prompt) <= 600);
+        assert!(prompt.iter().all(|m| m.content.ends_with(TRUNCATION_MARKER)));
+    }
+}
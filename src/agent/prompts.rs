@@ -3,10 +3,43 @@
 //! Provides context injection, system reminders, and specialized prompts
 //! for different agent states and tasks.
 
+use crate::agent::environment::EnvironmentContext;
+use crate::agent::language::Locale;
 use crate::agent::loop_runner::AgentContext;
 use crate::agent::planning::TaskPlan;
-use crate::agent::tools::ToolInfo;
+use crate::agent::tools::git::BranchDiff;
+use crate::agent::tools::{ErrorCategory, ToolError, ToolInfo};
+use crate::inference::engine::{GenerationParams, LlamaEngine};
+use crate::inference::streaming::collect_stream_text;
+use crate::storage::memory::MemoryNote;
+use crate::storage::settings::ToolCallFormat;
 use crate::types::message::{Message, Role};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::AtomicBool;
+use tokio::sync::Mutex;
+
+/// How the system prompt talks about thinking
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThinkingGuidance {
+    /// Ask the model to reason in <thinking> tags it keeps to itself
+    #[default]
+    Prompted,
+    /// Reasoning model: it thinks in <think> on its own, only ask it to close the block
+    Native,
+    /// Ordinary model: no thinking guidance
+    Off,
+}
+
+impl ThinkingGuidance {
+    pub fn for_model(reasoning: bool) -> Self {
+        if reasoning {
+            Self::Native
+        } else {
+            Self::Off
+        }
+    }
+}
 
 /// Build the complete system prompt with tool instructions and context
 pub fn build_agent_system_prompt(
@@ -14,6 +47,11 @@ pub fn build_agent_system_prompt(
     tools: &[ToolInfo],
     ctx: Option<&AgentContext>,
     plan: Option<&TaskPlan>,
+    env: Option<&EnvironmentContext>,
+    memory: Option<&str>,
+    language: Option<Locale>,
+    thinking: ThinkingGuidance,
+    call_format: ToolCallFormat,
 ) -> String {
     let mut prompt = String::new();
 
@@ -23,17 +61,37 @@ pub fn build_agent_system_prompt(
         prompt.push_str("\n\n");
     }
 
+    // Environment block (OS, shell, date, workspace...)
+    if let Some(env) = env {
+        let block = env.render();
+        if !block.is_empty() {
+            prompt.push_str(&block);
+            prompt.push('\n');
+        }
+    }
+
+    // Long-term memory notes recalled for this conversation
+    if let Some(memory) = memory.filter(|m| !m.is_empty()) {
+        prompt.push_str(memory);
+        prompt.push('\n');
+    }
+
     // Agent identity and capabilities
     prompt.push_str(AGENT_IDENTITY);
     prompt.push('\n');
 
     // Thinking instructions
-    prompt.push_str(THINKING_INSTRUCTIONS);
+    match thinking {
+        ThinkingGuidance::Prompted => prompt.push_str(THINKING_INSTRUCTIONS),
+        ThinkingGuidance::Native => prompt.push_str(NATIVE_THINKING_INSTRUCTIONS),
+        ThinkingGuidance::Off => {}
+    }
+    prompt.push_str(BEHAVIOR_INSTRUCTIONS);
     prompt.push('\n');
 
     // Tool instructions
     if !tools.is_empty() {
-        prompt.push_str(&build_tool_instructions_advanced(tools));
+        prompt.push_str(&build_tool_instructions_advanced(tools, call_format));
         prompt.push('\n');
     }
 
@@ -53,6 +111,14 @@ pub fn build_agent_system_prompt(
         prompt.push('\n');
     }
 
+    // Response language last, so it wins over any earlier instruction
+    if let Some(lang) = language {
+        prompt.push_str(&format!(
+            "## Response Language\nThe user is writing in {name}. Always respond in {name}.\n",
+            name = lang.name()
+        ));
+    }
+
     prompt
 }
 
@@ -93,7 +159,18 @@ IMPORTANT: <thinking> tags are for YOUR reasoning only. They must NEVER appear i
 - Keep thinking internal, only output the final answer
 - If you need to show reasoning, explain it naturally in your response
 
-## Honesty & Uncertainty
+"#;
+
+/// Instructions for models that reason natively in <think> blocks
+const NATIVE_THINKING_INSTRUCTIONS: &str = r#"## Thinking
+You reason in a <think> block before answering. Keep it focused on the next step
+and always close it with </think> before calling a tool or answering the user.
+Never put tool calls inside the <think> block.
+
+"#;
+
+/// Honesty, error handling and self-correction, for every model
+const BEHAVIOR_INSTRUCTIONS: &str = r#"## Honesty & Uncertainty
 When you don't know something or are uncertain:
 - Say "I don't know" or "I'm not certain"
 - NEVER fabricate information or make up facts
@@ -128,8 +205,107 @@ For complex tasks, create a structured plan:
 You can update your plan with the todo_write tool if available.
 "#;
 
+/// Rules of the tool-call grammar shared by every tool set: optional
+/// thinking, then free text or one JSON call, bare or in the ```json fence
+/// the instructions show (`tool` rule added per tool set)
+///
+/// Free text never holds `{"tool"`, so a call cannot slip past the grammar
+/// after some prose. The `text-*` rules track how much of it was just seen.
+const TOOL_CALL_GRAMMAR: &str = r#"root ::= think? ws (call | fenced-call | text)?
+think ::= ("<think>" | "<thinking>")? think-char* ("</think>" | "</thinking>")
+think-char ::= [^<] | "<" [^/]
+fenced-call ::= "```json" ws call "```" ws
+text ::= [^{ \t\n\x00] text-rest
+text-rest ::= ([^{\x00] text-rest | "{" text-brace)?
+text-brace ::= ("{" text-brace | "\"" text-quote | [^{"\x00] text-rest)?
+text-quote ::= ("{" text-brace | "t" text-t | [^{t\x00] text-rest)?
+text-t ::= ("{" text-brace | "o" text-to | [^{o\x00] text-rest)?
+text-to ::= ("{" text-brace | "o" text-too | [^{o\x00] text-rest)?
+text-too ::= ("{" text-brace | "l" text-tool | [^{l\x00] text-rest)?
+text-tool ::= ("{" text-brace | [^{"\x00] text-rest)?
+call ::= "{" ws "\"tool\"" ws ":" ws tool ws "," ws "\"params\"" ws ":" ws object ws "}" ws
+object ::= "{" ws (string ws ":" ws value (ws "," ws string ws ":" ws value)*)? ws "}"
+array ::= "[" ws (value (ws "," ws value)*)? ws "]"
+value ::= object | array | string | number | "true" | "false" | "null"
+string ::= "\"" ([^"\\\x00-\x1F] | "\\" (["\\/bfnrt] | "u" [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F]))* "\""
+number ::= "-"? ("0" | [1-9] [0-9]*) ("." [0-9]+)? ([eE] [-+]? [0-9]+)?
+ws ::= [ \t\n]*
+"#;
+
+/// GBNF grammar for strict tool calls: a reply is free text, or starts with
+/// a well-formed `{"tool": ..., "params": {...}}` naming one of `tools`,
+/// fenced or not
+///
+/// `None` without tools. Rebuild it whenever the tool set changes.
+pub fn build_tool_call_grammar(tools: &[ToolInfo]) -> Option<String> {
+    if tools.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = tools
+        .iter()
+        .map(|tool| gbnf_literal(&serde_json::Value::String(tool.name.clone()).to_string()))
+        .collect();
+    Some(format!("{}tool ::= {}\n", TOOL_CALL_GRAMMAR, names.join(" | ")))
+}
+
+/// `text` as a quoted GBNF literal
+fn gbnf_literal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON call section of the tool instructions, as written for `ToolCallFormat::Json`
+const JSON_CALL_SECTION: &str = r#"### 2. JSON Format (For Simple Calls)
+Use this for simple, single-line queries like searches.
+```json
+{"tool": "tool_name", "params": {"key": "value"}}
+```"#;
+
+/// The JSON call section for the format the model was trained on
+fn call_format_section(format: ToolCallFormat) -> &'static str {
+    match format {
+        ToolCallFormat::Json => JSON_CALL_SECTION,
+        ToolCallFormat::XmlTag => r#"### 2. Tool Call Tags (For Simple Calls)
+Use this for simple, single-line queries like searches. Wrap the JSON call in `<tool_call>` tags.
+```
+<tool_call>{"name": "tool_name", "arguments": {"key": "value"}}</tool_call>
+```"#,
+        ToolCallFormat::FunctionCall => r#"### 2. JSON Function Call (For Simple Calls)
+Use this for simple, single-line queries like searches. Give the tool `name` and its `arguments`.
+```json
+{"name": "tool_name", "arguments": {"key": "value"}}
+```"#,
+    }
+}
+
+/// `example`, a `{"tool": ..., "params": ...}` call, written in `format`
+fn call_example(example: &str, format: ToolCallFormat) -> String {
+    if format == ToolCallFormat::Json {
+        return example.to_string();
+    }
+    let call = example
+        .replacen("{\"tool\": ", "{\"name\": ", 1)
+        .replacen(", \"params\": ", ", \"arguments\": ", 1);
+    match format {
+        ToolCallFormat::XmlTag => format!("<tool_call>{}</tool_call>", call),
+        _ => call,
+    }
+}
+
 /// Build advanced tool instructions with examples
-pub fn build_tool_instructions_advanced(tools: &[ToolInfo]) -> String {
+///
+/// Calls are shown in `format`, the one the model's chat template prefers.
+pub fn build_tool_instructions_advanced(tools: &[ToolInfo], format: ToolCallFormat) -> String {
     if tools.is_empty() {
         return String::new();
     }
@@ -150,11 +326,7 @@ Use this format when writing code, creating files, or sending multi-line content
 </use_tool>
 ```
 
-### 2. JSON Format (For Simple Calls)
-Use this for simple, single-line queries like searches.
-```json
-{"tool": "tool_name", "params": {"key": "value"}}
-```
+{call_section}
 
 ## ⚡ CONCISENESS & EXECUTION RULES
 - **Executable Skills**: When creating a skill, you MUST provide an executable file (e.g., `main.py`, `run.sh`) in the `files` parameter.
@@ -238,7 +410,18 @@ Before giving your final answer, ask yourself:
 - "Should I add a caveat about uncertainty?"
 
 "#,
-    );
+    )
+    .replacen("{call_section}", call_format_section(format), 1);
+    if format != ToolCallFormat::Json {
+        // The edit examples follow the same format
+        out = out
+            .lines()
+            .map(|line| if line.starts_with("{\"tool\": ") { call_example(line, format) } else { line.to_string() })
+            .collect::<Vec<_>>()
+            .join("\n")
+            .replace("```json\n<tool_call>", "```\n<tool_call>")
+            + "\n";
+    }
 
     out.push_str("### Tool List:\n\n");
 
@@ -263,7 +446,7 @@ Before giving your final answer, ask yourself:
 
         // Add example for common tools
         if let Some(example) = get_tool_example(&tool.name) {
-            out.push_str(&format!("  Example: {}\n", example));
+            out.push_str(&format!("  Example: {}\n", call_example(example, format)));
         }
 
         out.push('\n');
@@ -293,6 +476,18 @@ fn get_tool_example(tool_name: &str) -> Option<&'static str> {
         "file_search" => Some(
             r#"{"tool": "file_search", "params": {"query": "TODO", "path": "./src", "file_pattern": "rs"}}"#,
         ),
+        "code_search" => Some(
+            r#"{"tool": "code_search", "params": {"kind": "references", "symbol": "save_conversation", "path": "./src"}}"#,
+        ),
+        "csv_analyze" => Some(
+            r#"{"tool": "csv_analyze", "params": {"path": "data/sales.csv", "operation": "aggregate", "group_by": "region", "agg": "sum", "column": "amount"}}"#,
+        ),
+        "image_info" => Some(r#"{"tool": "image_info", "params": {"path": "assets/logo.png", "include_preview": true}}"#),
+        "index_build" => Some(r#"{"tool": "index_build", "params": {"folder": "./docs"}}"#),
+        "index_search" => Some(
+            r#"{"tool": "index_search", "params": {"query": "how are refunds approved?", "top_k": 5}}"#,
+        ),
+        "index_status" => Some(r#"{"tool": "index_status", "params": {}}"#),
         // File write/edit tools
         "file_write" => Some(
             r#"<use_tool name="file_write">
@@ -309,6 +504,12 @@ Line 3</param>
     <param name="new_string">fn new_name()</param>
 </use_tool>"#,
         ),
+        "file_multi_edit" => Some(
+            r#"{"tool": "file_multi_edit", "params": {"path": "src/main.rs", "edits": [{"old_string": "fn old_name()", "new_string": "fn new_name()"}, {"old_string": "old_name()", "new_string": "new_name()", "replace_all": true}]}}"#,
+        ),
+        "file_patch_lines" => Some(
+            r#"{"tool": "file_patch_lines", "params": {"path": "src/main.rs", "start_line": 10, "end_line": 12, "new_content": "    let x = 1;\n    let y = 2;"}}"#,
+        ),
         "file_create" => Some(
             r#"{"tool": "file_create", "params": {"path": "src/new_file.rs", "content": "//! New module\n"}}"#,
         ),
@@ -319,9 +520,14 @@ Line 3</param>
         "file_copy" => Some(
             r#"{"tool": "file_copy", "params": {"source": "template.rs", "destination": "new_module.rs"}}"#,
         ),
+        "file_convert" => Some(
+            r#"{"tool": "file_convert", "params": {"path": "scripts/deploy.sh", "line_ending": "lf", "dry_run": true}}"#,
+        ),
         "directory_create" => {
             Some(r#"{"tool": "directory_create", "params": {"path": "src/new_module"}}"#)
         }
+        "undo_file_change" => Some(r#"{"tool": "undo_file_change", "params": {"path": "src/main.rs"}}"#),
+        "list_file_backups" => Some(r#"{"tool": "list_file_backups", "params": {"path": "src/main.rs"}}"#),
         // Search tools
         "grep" => Some(r#"{"tool": "grep", "params": {"pattern": "fn main", "path": "./src"}}"#),
         "glob" => Some(r#"{"tool": "glob", "params": {"pattern": "**/*.rs"}}"#),
@@ -332,24 +538,46 @@ Line 3</param>
         "bash_background" => {
             Some(r#"{"tool": "bash_background", "params": {"command": "cargo watch -x run"}}"#)
         }
+        "job_start" => Some(r#"{"tool": "job_start", "params": {"command": "cargo build --release"}}"#),
+        "job_status" => Some(r#"{"tool": "job_status", "params": {"job_id": "job-1"}}"#),
+        "job_logs" => Some(r#"{"tool": "job_logs", "params": {"job_id": "job-1", "stream": "stderr"}}"#),
+        "job_kill" => Some(r#"{"tool": "job_kill", "params": {"job_id": "job-1"}}"#),
+        "cargo_check" => Some(r#"{"tool": "cargo_check", "params": {"all_targets": true}}"#),
+        "cargo_test" => Some(r#"{"tool": "cargo_test", "params": {"filter": "parser", "package": "core"}}"#),
+        "pytest_run" => Some(r#"{"tool": "pytest_run", "params": {"paths": ["tests/test_api.py"], "keyword": "login"}}"#),
+        "npm_test" => Some(r#"{"tool": "npm_test", "params": {"filter": "renders header"}}"#),
         // Git tools
         "git_status" => Some(r#"{"tool": "git_status", "params": {}}"#),
         "git_diff" => Some(r#"{"tool": "git_diff", "params": {"staged": false}}"#),
+        "git_branch_diff" => Some(r#"{"tool": "git_branch_diff", "params": {"base": "origin/main", "pr_description": true}}"#),
         "git_log" => Some(r#"{"tool": "git_log", "params": {"count": 10, "oneline": true}}"#),
         "git_commit" => Some(
             r#"{"tool": "git_commit", "params": {"message": "feat: add new feature", "files": ["src/main.rs"]}}"#,
         ),
+        "git_commit_prepared" => Some(
+            r#"{"tool": "git_commit_prepared", "params": {"message": "auto", "paths": ["src/agent/tools/git.rs"]}}"#,
+        ),
         "git_branch" => Some(r#"{"tool": "git_branch", "params": {"action": "list"}}"#),
         "git_stash" => {
             Some(r#"{"tool": "git_stash", "params": {"action": "save", "message": "WIP"}}"#)
         }
+        "github_issue_view" => Some(r#"{"tool": "github_issue_view", "params": {"number": 42}}"#),
+        "github_issue_list" => Some(r#"{"tool": "github_issue_list", "params": {"state": "open", "labels": ["bug"]}}"#),
+        "github_pr_view" => Some(r#"{"tool": "github_pr_view", "params": {"number": 17}}"#),
+        "github_pr_create" => Some(
+            r#"{"tool": "github_pr_create", "params": {"title": "Fix login timeout", "base": "main"}}"#,
+        ),
         // Web tools
         "web_fetch" => {
             Some(r#"{"tool": "web_fetch", "params": {"url": "https://api.example.com/data"}}"#)
         }
+        "web_read" => Some(r#"{"tool": "web_read", "params": {"url": "https://blog.rust-lang.org/2025/02/20/Rust-1.85.0.html"}}"#),
         "web_download" => Some(
             r#"{"tool": "web_download", "params": {"url": "https://example.com/file.zip", "path": "downloads/file.zip"}}"#,
         ),
+        "http_request" => Some(
+            r#"{"tool": "http_request", "params": {"method": "POST", "url": "https://api.example.com/items", "headers": {"Authorization": "Bearer <token>"}, "body": {"name": "demo"}}}"#,
+        ),
         // Dev tools
         "diff" => Some(r#"{"tool": "diff", "params": {"file_a": "old.rs", "file_b": "new.rs"}}"#),
         "find_replace" => Some(
@@ -359,8 +587,11 @@ Line 3</param>
             r#"{"tool": "patch", "params": {"path": "src/main.rs", "patch": "-old line\n+new line"}}"#,
         ),
         "wc" => Some(r#"{"tool": "wc", "params": {"path": "src/main.rs"}}"#),
+        "lsp_diagnostics" => Some(r#"{"tool": "lsp_diagnostics", "params": {"path": "src/parser.rs"}}"#),
+        "lsp_hover" => Some(r#"{"tool": "lsp_hover", "params": {"path": "src/parser.rs", "line": 42, "column": 17}}"#),
         // System tools
         "tree" => Some(r#"{"tool": "tree", "params": {"path": ".", "max_depth": 3}}"#),
+        "project_overview" => Some(r#"{"tool": "project_overview", "params": {"path": ".", "max_depth": 2}}"#),
         "which" => Some(r#"{"tool": "which", "params": {"command": "cargo"}}"#),
         "system_info" => Some(r#"{"tool": "system_info", "params": {}}"#),
         "process_list" => Some(r#"{"tool": "process_list", "params": {"filter": "node"}}"#),
@@ -372,6 +603,9 @@ Line 3</param>
         "todo_write" => Some(
             r#"{"tool": "todo_write", "params": {"todos": [{"id": "1", "content": "Analyze the code", "status": "in_progress"}]}}"#,
         ),
+        "agent_task" => Some(
+            r#"{"tool": "agent_task", "params": {"prompt": "Find where settings are saved in ./src and list the fields", "allowed_tools": ["grep", "file_read"]}}"#,
+        ),
         // Skill tools - simple examples
         // Skill tools
         "skill_create" => Some(
@@ -386,6 +620,12 @@ Line 3</param>
         ),
         "skill_invoke" => Some(r#"{"tool": "skill_invoke", "params": {"name": "my-skill"}}"#),
         "skill_list" => Some(r#"{"tool": "skill_list", "params": {}}"#),
+        // Long-term memory
+        "memory_write" => Some(
+            r#"{"tool": "memory_write", "params": {"content": "The user prefers answers in short bullet points", "topics": ["preferences"]}}"#,
+        ),
+        "memory_read" => Some(r#"{"tool": "memory_read", "params": {"topic": "preferences"}}"#),
+        "memory_search" => Some(r#"{"tool": "memory_search", "params": {"query": "deploy staging cluster"}}"#),
         _ => None,
     }
 }
@@ -406,13 +646,10 @@ fn build_context_reminder(ctx: &AgentContext) -> String {
         ));
     }
 
-    // Recent tool usage
-    if !ctx.tool_history.is_empty() {
-        reminder.push_str("- Recently used tools:\n");
-        for entry in ctx.tool_history.iter().rev().take(3) {
-            let status = if entry.error.is_some() { "❌" } else { "✅" };
-            reminder.push_str(&format!("  {} {}\n", status, entry.tool_name));
-        }
+    // What was already done, so it survives compression of the history
+    let summary = build_run_summary(ctx);
+    if !summary.is_empty() {
+        reminder.push_str(&summary);
     }
 
     // Warnings
@@ -432,6 +669,132 @@ fn build_context_reminder(ctx: &AgentContext) -> String {
     reminder
 }
 
+/// Maximum size of the run summary (~400 tokens)
+const RUN_SUMMARY_MAX_CHARS: usize = 1600;
+
+/// Number of recent tool calls listed in the run summary
+const RUN_SUMMARY_MAX_CALLS: usize = 8;
+
+/// Tools whose `path` param is a file the model has read
+const READ_TOOLS: &[&str] = &["file_read", "pdf_read", "csv_analyze"];
+
+/// Build the "already done this run" section
+///
+/// Lists the last tool calls with a one-line outcome, the files already read
+/// (paths only) and the plan status. Injected at prompt time only, never
+/// stored in the conversation.
+pub fn build_run_summary(ctx: &AgentContext) -> String {
+    if ctx.tool_history.is_empty() && ctx.plan.is_none() {
+        return String::new();
+    }
+
+    let mut calls: Vec<String> = ctx
+        .tool_history
+        .iter()
+        .rev()
+        .take(RUN_SUMMARY_MAX_CALLS)
+        .rev()
+        .map(|entry| {
+            let target = call_target(&entry.params)
+                .map(|t| format!(" `{}`", one_line(&t, 60)))
+                .unwrap_or_default();
+            let outcome = match (&entry.result, &entry.error) {
+                (_, Some(error)) => format!("❌ {}{} → {}", entry.tool_name, target, one_line(error, 80)),
+                (Some(result), None) if !result.success => {
+                    format!("⚠️ {}{} → {}", entry.tool_name, target, one_line(&result.message, 80))
+                }
+                (Some(result), None) => format!("✅ {}{} → {}", entry.tool_name, target, one_line(&result.message, 80)),
+                (None, None) => format!("✅ {}{}", entry.tool_name, target),
+            };
+            format!("- {}\n", outcome)
+        })
+        .collect();
+
+    let mut files_read: Vec<&str> = Vec::new();
+    for entry in &ctx.tool_history {
+        let succeeded = entry.error.is_none() && entry.result.as_ref().map_or(false, |r| r.success);
+        if !succeeded || !READ_TOOLS.contains(&entry.tool_name.as_str()) {
+            continue;
+        }
+        if let Some(path) = entry.params.get("path").and_then(|p| p.as_str()) {
+            if !files_read.contains(&path) {
+                files_read.push(path);
+            }
+        }
+    }
+
+    let files_line = if files_read.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "Files already read (do not read them again unless they changed): {}\n",
+            files_read.join(", ")
+        )
+    };
+
+    let plan_line = ctx.plan.as_ref().map(|plan| {
+        let done = plan.tasks.iter()
+            .filter(|t| matches!(t.status, crate::agent::planning::TaskStatus::Completed | crate::agent::planning::TaskStatus::Skipped))
+            .count();
+        let current = plan.tasks.iter()
+            .find(|t| t.status == crate::agent::planning::TaskStatus::InProgress)
+            .map(|t| format!(", in progress: {}", one_line(&t.description, 80)))
+            .unwrap_or_default();
+        format!("Plan: {}/{} tasks done{}\n", done, plan.tasks.len(), current)
+    }).unwrap_or_default();
+
+    // Drop the oldest calls first to stay within budget
+    let header = "\n## Already Done This Run\n";
+    let fixed = header.len() + files_line.len() + plan_line.len();
+    while !calls.is_empty() && fixed + calls.iter().map(|c| c.len()).sum::<usize>() > RUN_SUMMARY_MAX_CHARS {
+        calls.remove(0);
+    }
+
+    let mut summary = String::from(header);
+    for call in &calls {
+        summary.push_str(call);
+    }
+    if fixed + calls.iter().map(|c| c.len()).sum::<usize>() > RUN_SUMMARY_MAX_CHARS {
+        // Even the file list alone is too long: keep the most recent paths
+        let mut kept: Vec<&str> = Vec::new();
+        let mut len = 0;
+        for path in files_read.iter().rev() {
+            len += path.len() + 2;
+            if header.len() + plan_line.len() + 100 + len > RUN_SUMMARY_MAX_CHARS {
+                break;
+            }
+            kept.insert(0, path);
+        }
+        summary.push_str(&format!(
+            "Files already read (do not read them again unless they changed): {} (+{} more)\n",
+            kept.join(", "),
+            files_read.len() - kept.len()
+        ));
+    } else {
+        summary.push_str(&files_line);
+    }
+    summary.push_str(&plan_line);
+    summary
+}
+
+/// Main target of a tool call, for display
+fn call_target(params: &serde_json::Value) -> Option<String> {
+    ["path", "query", "command", "url", "pattern"]
+        .iter()
+        .find_map(|key| params.get(*key).and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+}
+
+/// First line of `text`, truncated to `max` chars
+fn one_line(text: &str, max: usize) -> String {
+    let line = text.lines().next().unwrap_or("").trim();
+    if line.chars().count() > max {
+        format!("{}…", line.chars().take(max).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
 /// Build plan reminder
 fn build_plan_reminder(plan: &TaskPlan) -> String {
     let mut reminder = String::from("\n## Current Plan\n");
@@ -461,6 +824,26 @@ fn build_plan_reminder(plan: &TaskPlan) -> String {
     reminder
 }
 
+/// Memory section of the system prompt: the notes recalled for this conversation
+///
+/// Empty when there are none, so the prompt gets no section.
+pub fn build_memory_digest(notes: &[MemoryNote]) -> String {
+    if notes.is_empty() {
+        return String::new();
+    }
+    let mut digest = String::from(
+        "## Memory\nNotes saved in earlier conversations. Rely on them, and correct an outdated one with memory_write and its id.\n",
+    );
+    for note in notes {
+        digest.push_str(&format!("- [{}] {}", note.id, note.content));
+        if !note.topics.is_empty() {
+            digest.push_str(&format!(" (topics: {})", note.topics.join(", ")));
+        }
+        digest.push('\n');
+    }
+    digest
+}
+
 /// Build a focused prompt for a specific task
 pub fn build_task_prompt(task_description: &str, available_tools: &[&str]) -> String {
     let prompt = format!(
@@ -520,6 +903,55 @@ Choose an approach and act NOW.
     }
 }
 
+/// Build the reflection prompt after a failed tool call
+///
+/// The error is rendered category first, and the advice depends on the
+/// category: a missing path calls for a search, a timeout for a smaller
+/// request, and so on.
+pub fn build_error_reflection_prompt(tool_name: &str, error: &ToolError) -> String {
+    let advice = match error.category() {
+        ErrorCategory::InvalidParameters => {
+            "The parameters were rejected. Check the tool schema (required fields, names, types) and call the tool again with corrected parameters."
+        }
+        ErrorCategory::NotFound => {
+            "The path does not exist. Do not retry the same path: list the parent directory or search with `glob`/`grep` to find the right one."
+        }
+        ErrorCategory::PermissionDenied => {
+            "This action is not allowed. Do not retry it as is: use a permitted tool or command, or ask the user."
+        }
+        ErrorCategory::Timeout => {
+            "The tool took too long. Narrow the request (smaller scope, fewer results, shorter command) before trying again."
+        }
+        ErrorCategory::Network => {
+            "The network request failed. Check the URL. For a 4xx status change the request; for a 5xx status or a connection error, retry once or use another source."
+        }
+        ErrorCategory::UnknownTool => {
+            "This tool does not exist. Use one of the available tools listed in the system prompt."
+        }
+        ErrorCategory::Cancelled => {
+            "The user stopped this call. Do not run it again unless they ask."
+        }
+        ErrorCategory::Execution => {
+            "Think and choose a new strategy:\n1. Were the parameters correct? (check path, syntax, names)\n2. Can you use another tool to achieve the same goal?\n3. Can you reformulate your request?"
+        }
+    };
+
+    format!(
+        r#"## Tool `{}` failed
+
+{}
+
+DO NOT STOP. {}
+If nothing works after 2 attempts, explain the problem to the user and propose alternatives.
+
+Choose an approach and act NOW.
+"#,
+        tool_name,
+        error.render_for_model(tool_name),
+        advice
+    )
+}
+
 /// Build a summary request prompt
 pub fn build_summary_prompt(context: &str) -> String {
     format!(
@@ -615,6 +1047,7 @@ pub fn build_incremental_summary_prompt(
                 Role::User => "User",
                 Role::Assistant => "Assistant",
                 Role::System => "System",
+                Role::Tool => "Tool",
             };
             format!("{}: {}", role_str, msg.content)
         })
@@ -702,6 +1135,7 @@ pub fn build_compression_summary_prompt(messages: &[Message]) -> String {
                 Role::User => "User",
                 Role::Assistant => "Assistant",
                 Role::System => "System",
+                Role::Tool => "Tool",
             };
             format!("{}: {}", role_str, msg.content)
         })
@@ -745,11 +1179,164 @@ pub fn build_title_generation_prompt(
     )
 }
 
+/// Diff bytes shown to the model when it writes a commit message
+const COMMIT_DIFF_PROMPT_BYTES: usize = 12_000;
+
+/// Build the prompt asking for a conventional-commit message for a staged diff
+pub fn build_commit_message_prompt(diff: &str) -> String {
+    let shown = crate::truncate_str(diff, COMMIT_DIFF_PROMPT_BYTES);
+    let cut = if shown.len() < diff.len() { "\n[diff truncated]" } else { "" };
+    format!(
+        "Write a git commit message for the staged changes below, in the Conventional Commits format.\n\
+First line: `type(optional scope): summary`, type one of feat, fix, refactor, docs, test, chore, perf, style, build, ci; \
+imperative mood, at most 72 characters. Add a short body after a blank line only if the summary is not enough.\n\
+Reply with the message only.\n\n```diff\n{}{}\n```",
+        shown.trim_end(), cut
+    )
+}
+
+/// Diff bytes included in a PR description prompt
+const PR_DIFF_PROMPT_BYTES: usize = 12_000;
+
+/// Build the prompt asking for a pull request description of a branch
+///
+/// Commits and changed files come first, so a diff cut to fit still leaves
+/// the model the whole picture.
+pub fn build_pr_description_prompt(branch: &BranchDiff) -> String {
+    let count = |n: Option<u64>| n.map_or("bin".to_string(), |n| n.to_string());
+    let files = branch
+        .files
+        .iter()
+        .map(|f| format!("- {} (+{} -{})", f.path, count(f.added), count(f.deleted)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let commits = branch.commits.iter().map(|c| format!("- {}", c)).collect::<Vec<_>>().join("\n");
+    let shown = crate::truncate_str(&branch.diff, PR_DIFF_PROMPT_BYTES);
+    let cut = if shown.len() < branch.diff.len() { "\n[diff truncated]" } else { "" };
+    format!(
+        r###"## PULL REQUEST DESCRIPTION
+
+Write the description of a pull request merging `{head}` into `{base}`.
+
+### Commits
+{commits}
+
+### Changed files
+{files}
+
+### Diff
+```diff
+{diff}{cut}
+```
+
+### Instructions:
+1. First line: a title under 72 characters saying what the change does
+2. "## Summary": 1-3 sentences on what changes and why
+3. "## Changes": one bullet per notable change, grouped by area, naming files when useful
+4. "## Testing": tests added or changed in the diff; say so if there are none
+5. Describe only what the diff shows; do not invent motivation or results
+
+**Respond ONLY with the description, in Markdown.**"###,
+        head = branch.head,
+        base = branch.base,
+        commits = if commits.is_empty() { "(none)".to_string() } else { commits },
+        files = if files.is_empty() { "(none)".to_string() } else { files },
+        diff = shown.trim_end(),
+        cut = cut,
+    )
+}
+
+/// Commit message from the model's reply: thinking, code fences and quotes
+/// removed, None when nothing usable is left
+pub fn parse_commit_message(reply: &str) -> Option<String> {
+    let mut text = reply.to_string();
+    for (open, close) in [("<think>", "</think>"), ("<thinking>", "</thinking>")] {
+        while let Some(start) = text.find(open) {
+            let end = text[start..].find(close).map(|e| start + e + close.len()).unwrap_or(text.len());
+            text.replace_range(start..end, "");
+        }
+    }
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .skip_while(|line| line.trim().is_empty())
+        .collect();
+    let (subject, body) = lines.split_first()?;
+    let subject = subject.trim().trim_matches(|c| c == '"' || c == '\'' || c == '`').trim();
+    if subject.is_empty() {
+        return None;
+    }
+    let body = body.join("\n");
+    let body = body.trim();
+    Some(if body.is_empty() {
+        subject.to_string()
+    } else {
+        format!("{}\n\n{}", subject, body)
+    })
+}
+
+/// Ask the model for a conventional-commit message describing `diff`
+///
+/// A short generation separate from the conversation; None when no model is
+/// loaded or the reply holds no message. `cancel` is the chat's Stop button.
+pub async fn generate_commit_message(
+    engine: &Mutex<LlamaEngine>,
+    diff: &str,
+    cancel: &AtomicBool,
+) -> Option<String> {
+    let params = GenerationParams {
+        max_tokens: 200,
+        temperature: 0.2,
+        max_context_size: 8192,
+        ..GenerationParams::default()
+    };
+    let messages = vec![Message::new(Role::User, build_commit_message_prompt(diff))];
+    let (rx, stop) = engine.lock().await.generate_stream_messages(messages, params).ok()?;
+    let (text, _) = collect_stream_text(rx, &stop, cancel).await;
+    parse_commit_message(&text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_pr_description_prompt() {
+        let branch = BranchDiff {
+            base: "main".into(),
+            head: "feature".into(),
+            merge_base: "abc".into(),
+            commits: vec!["1a2b3c4 add two".into()],
+            files: vec![crate::agent::tools::git::ChangedFile {
+                path: "logo.png".into(),
+                added: None,
+                deleted: None,
+            }],
+            diff: "+two\n".into(),
+        };
+        let prompt = build_pr_description_prompt(&branch);
+        assert!(prompt.contains("merging `feature` into `main`"));
+        assert!(prompt.contains("- 1a2b3c4 add two"));
+        assert!(prompt.contains("- logo.png (+bin -bin)"));
+        assert!(prompt.contains("```diff\n+two\n```"));
+    }
+
+    #[test]
+    fn test_parse_commit_message() {
+        assert_eq!(
+            parse_commit_message("<think>small fix</think>\n```\nfix(git): handle empty diff\n```").as_deref(),
+            Some("fix(git): handle empty diff")
+        );
+        assert_eq!(
+            parse_commit_message("\"feat: add tool\"\n\nLonger explanation.\n").as_deref(),
+            Some("feat: add tool\n\nLonger explanation.")
+        );
+        assert_eq!(parse_commit_message("<think>unfinished"), None);
+        assert!(build_commit_message_prompt("+x").contains("```diff\n+x\n```"));
+    }
+
     #[test]
     fn test_build_tool_instructions() {
         let tools = vec![ToolInfo {
@@ -763,8 +1350,347 @@ mod tests {
             }),
         }];
 
-        let instructions = build_tool_instructions_advanced(&tools);
+        let instructions = build_tool_instructions_advanced(&tools, ToolCallFormat::Json);
         assert!(instructions.contains("web_search"));
         assert!(instructions.contains("Search the web"));
+        assert!(instructions.contains(r#"Example: {"tool": "web_search", "params": {"query": "latest AI news 2024"}}"#));
+
+        // Other formats advertise their own syntax, examples included
+        let tagged = build_tool_instructions_advanced(&tools, ToolCallFormat::XmlTag);
+        assert!(tagged.contains(r#"Example: <tool_call>{"name": "web_search", "arguments": {"query": "latest AI news 2024"}}</tool_call>"#));
+        assert!(tagged.contains(r#"<tool_call>{"name": "file_edit", "arguments": {"path": "main.rs""#));
+        assert!(!tagged.contains(r#"{"tool": "#), "{}", tagged);
+        let function = build_tool_instructions_advanced(&tools, ToolCallFormat::FunctionCall);
+        assert!(function.contains(r#"{"name": "tool_name", "arguments": {"key": "value"}}"#));
+        assert!(!function.contains("<tool_call>"));
+    }
+
+    #[test]
+    fn test_tool_call_grammar_lists_registered_tools() {
+        assert!(build_tool_call_grammar(&[]).is_none());
+
+        let tool = |name: &str| ToolInfo {
+            name: name.to_string(),
+            description: String::new(),
+            parameters_schema: json!({"type": "object"}),
+        };
+        let grammar = build_tool_call_grammar(&[tool("file_read"), tool("mcp_exa.web_search")]).unwrap();
+        assert!(grammar.starts_with("root ::= "));
+        assert!(grammar.ends_with("tool ::= \"\\\"file_read\\\"\" | \"\\\"mcp_exa.web_search\\\"\"\n"));
+        // Every rule referenced is defined
+        for rule in ["think", "text", "call", "object", "value", "string", "number", "ws", "tool"] {
+            assert!(grammar.contains(&format!("\n{} ::= ", rule)), "missing rule {}", rule);
+        }
+
+        assert_eq!(gbnf_literal("a\"b\\c"), "\"a\\\"b\\\\c\"");
+    }
+
+    /// Just enough of GBNF to check a text against the tool-call grammar
+    mod gbnf {
+        use std::collections::{BTreeSet, HashMap};
+
+        enum Expr {
+            Alt(Vec<Vec<Expr>>),
+            Literal(Vec<char>),
+            Class { negated: bool, ranges: Vec<(char, char)> },
+            Rule(String),
+            Repeat(Box<Expr>, usize, Option<usize>),
+        }
+
+        pub struct Grammar(HashMap<String, Expr>);
+
+        fn escaped(chars: &mut std::iter::Peekable<std::str::Chars>) -> char {
+            match chars.next().unwrap() {
+                'n' => '\n',
+                't' => '\t',
+                'x' => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    char::from(u8::from_str_radix(&hex, 16).unwrap())
+                }
+                c => c,
+            }
+        }
+
+        fn parse_alt(chars: &mut std::iter::Peekable<std::str::Chars>) -> Expr {
+            let mut alts = vec![Vec::new()];
+            while let Some(&c) = chars.peek() {
+                let item = match c {
+                    ' ' => {
+                        chars.next();
+                        continue;
+                    }
+                    ')' => break,
+                    '|' => {
+                        chars.next();
+                        alts.push(Vec::new());
+                        continue;
+                    }
+                    '(' => {
+                        chars.next();
+                        let group = parse_alt(chars);
+                        assert_eq!(chars.next(), Some(')'));
+                        group
+                    }
+                    '"' => {
+                        chars.next();
+                        let mut text = Vec::new();
+                        while let Some(c) = chars.next() {
+                            match c {
+                                '"' => break,
+                                '\\' => text.push(escaped(chars)),
+                                c => text.push(c),
+                            }
+                        }
+                        Expr::Literal(text)
+                    }
+                    '[' => {
+                        chars.next();
+                        let negated = chars.next_if_eq(&'^').is_some();
+                        let mut ranges = Vec::new();
+                        while let Some(c) = chars.next() {
+                            let start = match c {
+                                ']' => break,
+                                '\\' => escaped(chars),
+                                c => c,
+                            };
+                            let end = match chars.next_if_eq(&'-') {
+                                Some(_) if chars.peek() != Some(&']') => match chars.next().unwrap() {
+                                    '\\' => escaped(chars),
+                                    c => c,
+                                },
+                                Some(_) => {
+                                    ranges.push(('-', '-'));
+                                    start
+                                }
+                                None => start,
+                            };
+                            ranges.push((start, end));
+                        }
+                        Expr::Class { negated, ranges }
+                    }
+                    _ => {
+                        let mut name = String::new();
+                        while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '-') {
+                            name.push(c);
+                        }
+                        Expr::Rule(name)
+                    }
+                };
+                let item = match chars.peek() {
+                    Some('?') => Expr::Repeat(Box::new(item), 0, Some(1)),
+                    Some('*') => Expr::Repeat(Box::new(item), 0, None),
+                    Some('+') => Expr::Repeat(Box::new(item), 1, None),
+                    _ => item,
+                };
+                if matches!(item, Expr::Repeat(..)) {
+                    chars.next();
+                }
+                alts.last_mut().unwrap().push(item);
+            }
+            Expr::Alt(alts)
+        }
+
+        impl Grammar {
+            pub fn parse(text: &str) -> Self {
+                let rules = text.lines().filter(|line| !line.is_empty()).map(|line| {
+                    let (name, body) = line.split_once(" ::= ").expect("rule line");
+                    (name.to_string(), parse_alt(&mut body.chars().peekable()))
+                });
+                Self(rules.collect())
+            }
+
+            /// Whether `root` derives exactly `text`
+            pub fn accepts(&self, text: &str) -> bool {
+                let chars: Vec<char> = text.chars().collect();
+                self.ends(&Expr::Rule("root".into()), &chars, 0).contains(&chars.len())
+            }
+
+            /// Every position a match of `expr` starting at `at` may end at
+            fn ends(&self, expr: &Expr, text: &[char], at: usize) -> BTreeSet<usize> {
+                match expr {
+                    Expr::Alt(alts) => alts
+                        .iter()
+                        .flat_map(|seq| {
+                            seq.iter().fold(BTreeSet::from([at]), |starts, item| {
+                                starts.iter().flat_map(|&start| self.ends(item, text, start)).collect()
+                            })
+                        })
+                        .collect(),
+                    Expr::Literal(literal) => {
+                        let end = at + literal.len();
+                        (text.get(at..end) == Some(literal.as_slice())).then_some(end).into_iter().collect()
+                    }
+                    Expr::Class { negated, ranges } => text
+                        .get(at)
+                        .filter(|c| ranges.iter().any(|(lo, hi)| (lo..=hi).contains(c)) != *negated)
+                        .map(|_| at + 1)
+                        .into_iter()
+                        .collect(),
+                    Expr::Rule(name) => self.ends(&self.0[name], text, at),
+                    Expr::Repeat(item, min, max) => {
+                        let mut all = if *min == 0 { BTreeSet::from([at]) } else { BTreeSet::new() };
+                        let mut frontier = BTreeSet::from([at]);
+                        let mut count = 0;
+                        while !frontier.is_empty() && max.map_or(true, |max| count < max) {
+                            count += 1;
+                            frontier = frontier.iter().flat_map(|&start| self.ends(item, text, start)).collect();
+                            if count >= *min {
+                                // Seen ends are not explored twice, so empty matches stop too
+                                frontier.retain(|end| !all.contains(end));
+                                all.extend(&frontier);
+                            }
+                        }
+                        all
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tool_call_grammar_accepts_fenced_calls() {
+        let tool = ToolInfo {
+            name: "file_read".to_string(),
+            description: String::new(),
+            parameters_schema: json!({"type": "object"}),
+        };
+        let grammar = gbnf::Grammar::parse(&build_tool_call_grammar(&[tool]).unwrap());
+        let call = r#"{"tool": "file_read", "params": {"path": "src/main.rs", "end_line": 50}}"#;
+
+        // The call as the instructions show it, fenced, and bare
+        assert!(grammar.accepts(&format!("```json\n{}\n```", call)));
+        assert!(grammar.accepts(&format!("<think>Read it first.</think>\n```json\n{}\n```\n", call)));
+        assert!(grammar.accepts(call));
+        assert!(grammar.accepts("The file is saved. Use {braces} and \"quotes\" freely."));
+
+        // Free text does not carry calls past the grammar
+        assert!(!grammar.accepts(&format!("Let me look.\n```json\n{}\n```", call)));
+        assert!(!grammar.accepts(&format!("Sure: {}", call)));
+        assert!(!grammar.accepts(&format!("```json\n{}\n```", call.replace("file_read", "file_write"))));
+        assert!(!grammar.accepts(r#"```json
+{"tool": "file_read", "params": {"path": }}
+```"#));
+    }
+
+    #[test]
+    fn test_run_summary_lists_calls_and_files() {
+        use crate::agent::loop_runner::ToolHistoryEntry;
+        use crate::agent::tools::ToolResult;
+
+        let mut ctx = AgentContext::new();
+        assert!(build_run_summary(&ctx).is_empty());
+
+        ctx.tool_history.push(ToolHistoryEntry {
+            tool_name: "file_read".to_string(),
+            params: json!({"path": "src/main.rs"}),
+            result: Some(ToolResult { success: true, data: json!({}), message: "Read 42 lines\nfn main".to_string() }),
+            error: None,
+            timestamp: 0,
+            duration_ms: 3,
+        });
+        ctx.tool_history.push(ToolHistoryEntry {
+            tool_name: "grep".to_string(),
+            params: json!({"pattern": "TODO", "path": "src"}),
+            result: None,
+            error: Some("Invalid regex".to_string()),
+            timestamp: 0,
+            duration_ms: 1,
+        });
+
+        let summary = build_run_summary(&ctx);
+        assert!(summary.contains("## Already Done This Run"));
+        assert!(summary.contains("- ✅ file_read `src/main.rs` → Read 42 lines\n"));
+        assert!(summary.contains("- ❌ grep `src` → Invalid regex"));
+        assert!(summary.contains("Files already read (do not read them again unless they changed): src/main.rs\n"));
+
+        // Stays within budget with a long history
+        for i in 0..200 {
+            ctx.tool_history.push(ToolHistoryEntry {
+                tool_name: "file_read".to_string(),
+                params: json!({"path": format!("src/some/deeply/nested/module_{}.rs", i)}),
+                result: Some(ToolResult { success: true, data: json!({}), message: "ok".to_string() }),
+                error: None,
+                timestamp: 0,
+                duration_ms: 1,
+            });
+        }
+        let summary = build_run_summary(&ctx);
+        assert!(summary.len() <= RUN_SUMMARY_MAX_CHARS + 100, "{}", summary.len());
+        assert!(summary.contains("module_199.rs"));
+    }
+
+    #[test]
+    fn test_system_prompt_includes_environment_block() {
+        let env = EnvironmentContext {
+            shell: Some("bash".to_string()),
+            path_separator: Some('/'),
+            ..Default::default()
+        };
+        let prompt = build_agent_system_prompt("Base", &[], None, None, Some(&env), None, None, ThinkingGuidance::Prompted, ToolCallFormat::Json);
+        assert!(prompt.contains("## Environment\n- Path separator: /\n- Shell: bash"));
+
+        let without = build_agent_system_prompt("Base", &[], None, None, None, None, None, ThinkingGuidance::Prompted, ToolCallFormat::Json);
+        assert!(!without.contains("## Environment"));
+    }
+
+    #[test]
+    fn test_system_prompt_includes_memory_digest() {
+        let now = chrono::Utc::now();
+        let note = MemoryNote {
+            id: "a1b2c3d4".to_string(),
+            content: "User prefers tabs".to_string(),
+            topics: vec!["preferences".to_string(), "editor".to_string()],
+            created_at: now,
+            updated_at: now,
+            last_used_at: now,
+        };
+        let digest = build_memory_digest(&[note]);
+        assert!(digest.ends_with("\n- [a1b2c3d4] User prefers tabs (topics: preferences, editor)\n"));
+        assert!(build_memory_digest(&[]).is_empty());
+
+        let prompt = build_agent_system_prompt("Base", &[], None, None, None, Some(&digest), None, ThinkingGuidance::Off, ToolCallFormat::Json);
+        assert!(prompt.contains("## Memory\n"));
+        let without = build_agent_system_prompt("Base", &[], None, None, None, Some(""), None, ThinkingGuidance::Off, ToolCallFormat::Json);
+        assert!(!without.contains("## Memory"));
+    }
+
+
+    #[test]
+    fn test_system_prompt_response_language() {
+        let prompt = build_agent_system_prompt("Base", &[], None, None, None, None, Some(Locale::En), ThinkingGuidance::Prompted, ToolCallFormat::Json);
+        assert!(prompt.trim_end().ends_with("Always respond in English."));
+    }
+
+    #[test]
+    fn test_system_prompt_thinking_guidance() {
+        let prompted = build_agent_system_prompt("Base", &[], None, None, None, None, None, ThinkingGuidance::Prompted, ToolCallFormat::Json);
+        assert!(prompted.contains("## Thinking Mode"));
+        assert!(prompted.contains("<thinking>\n- What is the main objective?"));
+
+        let native = build_agent_system_prompt("Base", &[], None, None, None, None, None, ThinkingGuidance::for_model(true), ToolCallFormat::Json);
+        assert!(native.contains("close it with </think>"));
+        assert!(!native.contains("<thinking>"));
+
+        let off = build_agent_system_prompt("Base", &[], None, None, None, None, None, ThinkingGuidance::for_model(false), ToolCallFormat::Json);
+        assert!(!off.contains("<think"));
+        // Behavior guidance stays for every model
+        for prompt in [&prompted, &native, &off] {
+            assert!(prompt.contains("## Honesty & Uncertainty"));
+            assert!(prompt.contains("## Self-Correction"));
+        }
+    }
+
+    #[test]
+    fn test_error_reflection_branches_on_category() {
+        let err = ToolError::Timeout { after_secs: Some(30) };
+        let prompt = build_error_reflection_prompt("web_fetch", &err);
+        assert!(prompt.contains("ERROR category=timeout after=30s tool=web_fetch\nTimeout"));
+        assert!(prompt.contains("Narrow the request"));
+
+        let err = ToolError::NotFound { path: "src/mian.rs".into(), message: "Erreur lecture fichier".into() };
+        let prompt = build_error_reflection_prompt("file_read", &err);
+        assert!(prompt.contains("ERROR category=not_found path=\"src/mian.rs\" tool=file_read"));
+        assert!(prompt.contains("Do not retry the same path"));
+        assert!(!prompt.contains("Narrow the request"));
     }
 }
@@ -0,0 +1,496 @@
+//! Record and replay agent runs for regression testing
+//!
+//! With developer mode on, the chat loop records every prompt exactly as it is
+//! sent to the engine, together with the model's response. Replaying feeds the
+//! recorded responses back through the agent loop in place of the engine and
+//! checks that the run still behaves the same: same tools in the same order,
+//! no extra iterations, same number of compressions and a non-empty final
+//! answer. Prompts are expected to drift when prompts or compression settings
+//! change; drift is reported but is not a failure on its own.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+use crate::agent::compression::{apply_hierarchical_compression, get_compression_tier, CompressionTier};
+use crate::agent::language::{AgentStrings, Locale};
+use crate::agent::loop_runner::{AgentContext, AgentLoop, AgentLoopConfig};
+use crate::agent::prompt_builder::{prompt_budget, PromptBuilder};
+use crate::agent::prompts::{build_agent_system_prompt, build_error_reflection_prompt, ThinkingGuidance};
+use crate::agent::runner::{extract_tool_call, format_tool_result_for_system};
+use crate::agent::tools::{builtins, ToolInfo, ToolRegistry};
+use crate::storage::settings::{MaskingStyle, ToolCallFormat};
+use crate::storage::{get_data_dir, StorageError};
+use crate::types::message::{Message, Role};
+
+/// Current recording format
+pub const RECORDING_VERSION: u32 = 1;
+
+/// Replaced by the replay workspace directory in recorded messages and responses
+pub const WORKSPACE_PLACEHOLDER: &str = "{workspace}";
+
+/// Tool results longer than this are cut before being injected, as in the chat loop
+const MAX_TOOL_RESULT_CHARS: usize = 4000;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid recording: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unsupported recording version {0} (expected {RECORDING_VERSION})")]
+    Version(u32),
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
+/// One model call: the prompt as sent and the full response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub prompt: Vec<Message>,
+    pub response: String,
+}
+
+/// A recorded agent run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecording {
+    pub version: u32,
+    pub name: String,
+    /// Base system prompt the dynamic prompt was built from
+    pub base_system_prompt: String,
+    /// Tools listed in the prompt (empty: the replay registry's tools)
+    #[serde(default)]
+    pub tools: Vec<ToolInfo>,
+    /// Conversation before the run, ending with the user message
+    pub history: Vec<Message>,
+    /// Context size of the run, which drives compression on replay
+    pub max_context_tokens: usize,
+    /// Thinking guidance of the prompt (recordings made before it existed: prompted)
+    #[serde(default)]
+    pub thinking: ThinkingGuidance,
+    /// Tool call format the prompt asked for
+    #[serde(default)]
+    pub tool_call_format: ToolCallFormat,
+    /// Memory section recalled into the prompt, replayed as is
+    #[serde(default)]
+    pub memory: Option<String>,
+    /// Files created in the replay workspace (relative path -> content)
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    pub exchanges: Vec<RecordedExchange>,
+    /// Tools called, in order
+    pub tool_calls: Vec<String>,
+    pub iterations: usize,
+    #[serde(default)]
+    pub compressions: usize,
+    pub final_answer: String,
+}
+
+/// Collects a run as the chat loop executes it
+pub struct RunRecorder {
+    recording: RunRecording,
+}
+
+impl RunRecorder {
+    pub fn new(
+        name: impl Into<String>,
+        base_system_prompt: &str,
+        tools: Vec<ToolInfo>,
+        history: Vec<Message>,
+        max_context_tokens: usize,
+    ) -> Self {
+        Self {
+            recording: RunRecording {
+                version: RECORDING_VERSION,
+                name: name.into(),
+                base_system_prompt: base_system_prompt.to_string(),
+                tools,
+                history,
+                max_context_tokens,
+                thinking: ThinkingGuidance::default(),
+                tool_call_format: ToolCallFormat::default(),
+                memory: None,
+                files: BTreeMap::new(),
+                exchanges: Vec::new(),
+                tool_calls: Vec::new(),
+                iterations: 0,
+                compressions: 0,
+                final_answer: String::new(),
+            },
+        }
+    }
+
+    /// Record a prompt captured right before it was sent, and the response it got
+    pub fn record_exchange(&mut self, prompt: &[Message], response: &str) {
+        self.recording.exchanges.push(RecordedExchange {
+            prompt: prompt.to_vec(),
+            response: response.to_string(),
+        });
+    }
+
+    pub fn record_tool_call(&mut self, tool: &str) {
+        self.recording.tool_calls.push(tool.to_string());
+    }
+
+    pub fn record_compression(&mut self) {
+        self.recording.compressions += 1;
+    }
+
+    /// Thinking guidance the run's prompts were built with
+    pub fn with_thinking(mut self, thinking: ThinkingGuidance) -> Self {
+        self.recording.thinking = thinking;
+        self
+    }
+
+    /// Tool call format the run's prompts asked for
+    pub fn with_tool_call_format(mut self, format: ToolCallFormat) -> Self {
+        self.recording.tool_call_format = format;
+        self
+    }
+
+    /// Memory digest the run's prompts included
+    pub fn with_memory(mut self, memory: Option<String>) -> Self {
+        self.recording.memory = memory;
+        self
+    }
+
+    pub fn finish(mut self, iterations: usize, final_answer: &str) -> RunRecording {
+        self.recording.iterations = iterations;
+        self.recording.final_answer = final_answer.to_string();
+        self.recording
+    }
+}
+
+/// Directory where developer mode stores recordings
+pub fn recordings_dir() -> Result<PathBuf, ReplayError> {
+    Ok(get_data_dir()?.join("recordings"))
+}
+
+/// Save a recording as `<timestamp>-<name>.json` in `dir`
+pub fn save_recording(recording: &RunRecording, dir: &Path) -> Result<PathBuf, ReplayError> {
+    std::fs::create_dir_all(dir)?;
+    let slug: String = recording
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .take(40)
+        .collect();
+    let path = dir.join(format!("{}-{}.json", chrono::Utc::now().format("%Y%m%d-%H%M%S"), slug.trim_matches('-')));
+    std::fs::write(&path, serde_json::to_string_pretty(recording)?)?;
+    Ok(path)
+}
+
+pub fn load_recording(path: &Path) -> Result<RunRecording, ReplayError> {
+    let recording: RunRecording = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if recording.version != RECORDING_VERSION {
+        return Err(ReplayError::Version(recording.version));
+    }
+    Ok(recording)
+}
+
+/// Stand-in for the inference engine that answers with scripted responses, in order
+pub struct ScriptedEngine {
+    responses: VecDeque<String>,
+    /// Prompts received, in order
+    pub prompts: Vec<Vec<Message>>,
+}
+
+impl ScriptedEngine {
+    pub fn new(responses: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            responses: responses.into_iter().collect(),
+            prompts: Vec::new(),
+        }
+    }
+
+    /// Next response, or `None` once the script is exhausted
+    pub fn generate(&mut self, prompt: &[Message]) -> Option<String> {
+        self.prompts.push(prompt.to_vec());
+        self.responses.pop_front()
+    }
+}
+
+/// What happened when a recording was replayed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReplayOutcome {
+    pub tool_calls: Vec<String>,
+    pub iterations: usize,
+    pub compressions: usize,
+    pub final_answer: Option<String>,
+    /// Model calls whose prompt differs from the recorded one (0-indexed)
+    pub prompt_drift: Vec<usize>,
+    /// Prompts sent to the scripted engine, to re-record after an intended change
+    pub prompts: Vec<Vec<Message>>,
+}
+
+/// Tools executed on replay: read-only, so replays have no side effects
+///
+/// Other recorded tool calls fail as unknown tools, which keeps the call order
+/// comparable without re-running writes or commands.
+pub async fn replay_registry() -> Arc<ToolRegistry> {
+    let registry = Arc::new(ToolRegistry::new());
+    registry.register(Arc::new(builtins::FileReadTool)).await;
+    registry.register(Arc::new(builtins::FileListTool)).await;
+    registry.register(Arc::new(builtins::GrepTool)).await;
+    registry.register(Arc::new(builtins::GlobTool)).await;
+    registry.register(Arc::new(builtins::ThinkTool)).await;
+    registry
+}
+
+fn with_workspace(text: &str, workspace: &str) -> String {
+    text.replace(WORKSPACE_PLACEHOLDER, workspace)
+}
+
+/// Compare a sent prompt with a recorded one, ignoring timestamps
+fn same_prompt(sent: &[Message], recorded: &[Message], workspace: &str) -> bool {
+    sent.len() == recorded.len()
+        && sent
+            .iter()
+            .zip(recorded)
+            .all(|(s, r)| s.role == r.role && s.content == with_workspace(&r.content, workspace))
+}
+
+/// Replay a recording through the agent loop with a scripted engine
+///
+/// The recording's files are written to `workspace` first. The loop mirrors
+/// the chat loop: dynamic system prompt, proactive compression once the
+/// context passes the Working tier, tool execution and result injection.
+pub async fn replay_run(
+    recording: &RunRecording,
+    registry: Arc<ToolRegistry>,
+    workspace: &Path,
+) -> Result<ReplayOutcome, ReplayError> {
+    let workspace_str = workspace.to_string_lossy().to_string();
+    std::fs::create_dir_all(workspace)?;
+    for (relative, content) in &recording.files {
+        let path = workspace.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+    }
+
+    let config = AgentLoopConfig {
+        // Backoff between retries only slows replays down
+        enable_retry: false,
+        ..Default::default()
+    };
+    let max_iterations = config.max_iterations;
+    let loop_runner = AgentLoop::new(config, registry.clone());
+    let (event_tx, mut event_rx) = mpsc::channel(64);
+    tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+    let mut engine = ScriptedEngine::new(
+        recording.exchanges.iter().map(|e| with_workspace(&e.response, &workspace_str)),
+    );
+    let mut messages: Vec<Message> = recording
+        .history
+        .iter()
+        .map(|m| Message::new(m.role.clone(), with_workspace(&m.content, &workspace_str)))
+        .collect();
+    // Prompts list the recorded tools so they match the recorded run, even
+    // though only the replay registry's tools actually execute
+    let tools = if recording.tools.is_empty() { registry.list_tools() } else { recording.tools.clone() };
+
+    let mut ctx = AgentContext::new();
+    let mut outcome = ReplayOutcome::default();
+
+    while ctx.iteration < max_iterations {
+        ctx.iteration += 1;
+
+        let system_prompt = build_agent_system_prompt(
+            &recording.base_system_prompt,
+            &tools,
+            Some(&ctx),
+            None,
+            None,
+            recording.memory.as_deref(),
+            None,
+            recording.thinking,
+            recording.tool_call_format,
+        );
+        let estimated_tokens: usize = system_prompt.len() / 4 + messages.iter().map(|m| m.content.len() / 4).sum::<usize>();
+        let prompt = PromptBuilder::new()
+            .with_system(system_prompt)
+            .with_history(&messages)
+            .with_budget(prompt_budget(recording.max_context_tokens))
+            .build();
+        let tier = get_compression_tier(estimated_tokens, recording.max_context_tokens);
+        if tier != CompressionTier::Working && outcome.compressions == 0 {
+            let anchors: Vec<(String, String)> = ctx
+                .get_anchors()
+                .iter()
+                .map(|a| (a.content.clone(), format!("{:?}", a.reason)))
+                .collect();
+            let (saved, applied) = apply_hierarchical_compression(
+                &mut messages,
+                estimated_tokens,
+                recording.max_context_tokens,
+                &anchors,
+                MaskingStyle::default(),
+            );
+            if applied {
+                outcome.compressions += 1;
+                messages.push(Message::new(
+                    Role::System,
+                    AgentStrings::new(Locale::En).compression_applied(tier, saved),
+                ));
+                continue;
+            }
+        }
+
+        let call_index = engine.prompts.len();
+        let Some(response) = engine.generate(&prompt) else {
+            tracing::warn!("Replay script exhausted at iteration {}", ctx.iteration);
+            break;
+        };
+        let drifted = !matches!(
+            recording.exchanges.get(call_index),
+            Some(e) if same_prompt(&prompt, &e.prompt, &workspace_str)
+        );
+        if drifted {
+            outcome.prompt_drift.push(call_index);
+        }
+
+        messages.push(Message::new(Role::Assistant, response.clone()));
+        ctx.last_response = Some(response.clone());
+
+        let Some(call) = extract_tool_call(&response) else {
+            outcome.final_answer = Some(response);
+            break;
+        };
+        outcome.tool_calls.push(call.tool.clone());
+
+        match loop_runner.execute_tool_with_retry(&call, &mut ctx, &event_tx).await {
+            Ok(result) => {
+                ctx.consecutive_errors = 0;
+                let text = format_tool_result_for_system(&call.tool, &result);
+                let text = if text.len() > MAX_TOOL_RESULT_CHARS {
+                    crate::truncate_str(&text, MAX_TOOL_RESULT_CHARS).to_string()
+                } else {
+                    text
+                };
+                messages.push(Message::new(Role::System, text));
+            }
+            Err(e) => {
+                ctx.consecutive_errors += 1;
+                messages.push(Message::new(Role::System, build_error_reflection_prompt(&call.tool, &e)));
+            }
+        }
+    }
+
+    outcome.iterations = ctx.iteration;
+    outcome.prompts = engine.prompts;
+    Ok(outcome)
+}
+
+/// Invariants a replay must keep; returns the violations
+pub fn check_invariants(recording: &RunRecording, outcome: &ReplayOutcome) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    if outcome.tool_calls != recording.tool_calls {
+        violations.push(format!(
+            "tool calls changed: recorded [{}], replayed [{}]",
+            recording.tool_calls.join(", "),
+            outcome.tool_calls.join(", ")
+        ));
+    }
+    if outcome.iterations > recording.iterations {
+        violations.push(format!(
+            "extra iterations: recorded {}, replayed {}",
+            recording.iterations, outcome.iterations
+        ));
+    }
+    if outcome.compressions != recording.compressions {
+        violations.push(format!(
+            "compressions changed: recorded {}, replayed {}",
+            recording.compressions, outcome.compressions
+        ));
+    }
+    if outcome.final_answer.as_deref().unwrap_or("").trim().is_empty() {
+        violations.push("no final answer".to_string());
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join("replay")
+    }
+
+    async fn replay_fixture(name: &str) -> (RunRecording, ReplayOutcome) {
+        let recording = load_recording(&fixtures_dir().join(name)).unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let outcome = replay_run(&recording, replay_registry().await, workspace.path()).await.unwrap();
+        (recording, outcome)
+    }
+
+    #[tokio::test]
+    async fn test_replay_all_fixtures_keep_invariants() {
+        let mut names: Vec<String> = std::fs::read_dir(fixtures_dir())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".json"))
+            .collect();
+        names.sort();
+        assert!(names.len() >= 2);
+
+        for name in names {
+            let (recording, outcome) = replay_fixture(&name).await;
+            let violations = check_invariants(&recording, &outcome);
+            assert!(violations.is_empty(), "{}: {}", name, violations.join("; "));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_run_fixture() {
+        let (recording, outcome) = replay_fixture("tool_run.json").await;
+        assert_eq!(outcome.tool_calls, vec!["file_read", "grep"]);
+        assert_eq!(outcome.iterations, recording.iterations);
+        assert_eq!(outcome.compressions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_compression_run_fixture() {
+        let (recording, outcome) = replay_fixture("compression_run.json").await;
+        assert_eq!(recording.compressions, 1);
+        assert_eq!(outcome.compressions, 1);
+        assert!(outcome.final_answer.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_invariants_catch_regressions() {
+        let (recording, outcome) = replay_fixture("tool_run.json").await;
+
+        // A prompt change that makes the model skip a tool and loop longer
+        let regressed = ReplayOutcome {
+            tool_calls: vec!["file_read".to_string()],
+            iterations: outcome.iterations + 1,
+            final_answer: Some("   ".to_string()),
+            ..outcome
+        };
+        let violations = check_invariants(&recording, &regressed);
+        assert_eq!(violations.len(), 3, "{:?}", violations);
+    }
+
+    #[test]
+    fn test_recorder_round_trip() {
+        let mut recorder = RunRecorder::new("My run: test", "base", Vec::new(), vec![Message::new(Role::User, "hi")], 4096);
+        recorder.record_exchange(&[Message::new(Role::User, "hi")], "hello");
+        let recording = recorder.finish(1, "hello");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = save_recording(&recording, dir.path()).unwrap();
+        assert!(path.file_name().unwrap().to_string_lossy().ends_with("-my-run--test.json"));
+        assert_eq!(load_recording(&path).unwrap(), recording);
+    }
+}
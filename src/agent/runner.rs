@@ -4,8 +4,10 @@
 
 use regex::Regex;
 use serde_json::Value;
+use std::collections::HashMap;
 
 use crate::agent::tools::{ToolInfo, ToolResult};
+use crate::storage::settings::{GarbageSensitivity, ToolResultVerbosity};
 
 #[derive(Clone, Debug)]
 pub struct ToolCall {
@@ -42,28 +44,151 @@ Available tools:\n",
     out
 }
 
+/// Stop sequences for tool-enabled generations: the real result comes from
+/// the system after the closing fence, so a model writing one is inventing it
+pub const TOOL_CALL_STOP_SEQUENCES: &[&str] = &["<tool_result>", "[TOOL_RESULT]", "<tool_response>"];
+
 pub fn format_tool_result_for_system(tool: &str, result: &ToolResult) -> String {
-    // For skills, use a more readable format since output is the key data
-    if tool.starts_with("skill_") {
-        return format!(
+    let formatted = if tool.starts_with("skill_") {
+        // For skills, use a more readable format since output is the key data
+        format!(
             "<tool_result>\n<tool>{}</tool>\n<success>{}</success>\n<output>\n{}\n</output>\n</tool_result>",
             tool,
             result.success,
             result.message
-        );
+        )
+    } else {
+        // Standard compact format for other tools
+        let data = serde_json::to_string(&result.data).unwrap_or_else(|_| "{}".to_string());
+        format!(
+            "{{\"tool\":\"{}\",\"success\":{},\"message\":{},\"data\":{}}}",
+            tool,
+            result.success,
+            serde_json::to_string(&result.message).unwrap_or_else(|_| "\"\"".to_string()),
+            data
+        )
+    };
+    match continuation_hint(tool, result) {
+        Some(hint) => format!("{}\n{}", formatted, hint),
+        None => formatted,
     }
+}
 
-    // Standard compact format for other tools
-    let data = serde_json::to_string(&result.data).unwrap_or_else(|_| "{}".to_string());
-    format!(
-        "{{\"tool\":\"{}\",\"success\":{},\"message\":{},\"data\":{}}}",
-        tool,
-        result.success,
-        serde_json::to_string(&result.message).unwrap_or_else(|_| "\"\"".to_string()),
-        data
-    )
+/// How to fetch the next page of a paged result (see `mark_truncated`)
+pub fn continuation_hint(tool: &str, result: &ToolResult) -> Option<String> {
+    if result.data["truncated"].as_bool() != Some(true) {
+        return None;
+    }
+    let next = result.data.get("continuation").filter(|next| next.is_object())?;
+    Some(format!(
+        "[Output truncated. For the next page, call {} again with the same params plus {}]",
+        tool, next
+    ))
+}
+
+/// Hard cap of the compact format, in characters
+const COMPACT_MAX_CHARS: usize = 1000;
+/// Longer (or multi-line) string fields go below the key fields in the compact format
+const COMPACT_INLINE_CHARS: usize = 120;
+/// Array items listed in the compact format
+const COMPACT_MAX_ITEMS: usize = 5;
+const COMPACT_ITEM_CHARS: usize = 150;
+/// String fields are cut to this many characters in the JSON format
+const JSON_STRING_MAX_CHARS: usize = 500;
+
+/// Format a tool result for injection at the chosen verbosity
+pub fn format_tool_result(tool: &str, result: &ToolResult, verbosity: ToolResultVerbosity) -> String {
+    match verbosity {
+        ToolResultVerbosity::Full => format_tool_result_for_system(tool, result),
+        ToolResultVerbosity::Compact => format_tool_result_compact(tool, result),
+        ToolResultVerbosity::Json => {
+            let data = if result.data.is_null() {
+                serde_json::json!({ "message": result.message })
+            } else {
+                truncate_json_strings(&result.data)
+            };
+            serde_json::to_string_pretty(&data).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+fn format_tool_result_compact(tool: &str, result: &ToolResult) -> String {
+    let status = if result.success { "ok" } else { "failed" };
+    let mut out = format!("[{}] {}: {}", tool, status, result.message);
+
+    if let Some(fields) = result.data.as_object() {
+        let mut keys: Vec<&String> = fields.keys().collect();
+        keys.sort();
+        let mut long_fields = Vec::new();
+        for key in keys {
+            match &fields[key] {
+                Value::Null | Value::Object(_) => {}
+                Value::String(text) if text.chars().count() > COMPACT_INLINE_CHARS || text.contains('\n') => {
+                    long_fields.push((key, text));
+                }
+                Value::String(text) => out.push_str(&format!("\n{}: {}", key, text)),
+                Value::Array(items) => {
+                    out.push_str(&format!("\n{}: {} item(s)", key, items.len()));
+                    for item in items.iter().take(COMPACT_MAX_ITEMS) {
+                        let line = match item {
+                            Value::String(text) => text.clone(),
+                            other => other.to_string(),
+                        };
+                        out.push_str(&format!("\n- {}", truncate_chars(&line, COMPACT_ITEM_CHARS)));
+                    }
+                }
+                other => out.push_str(&format!("\n{}: {}", key, other)),
+            }
+        }
+        for (key, text) in long_fields {
+            out.push_str(&format!("\n{}:\n{}", key, text));
+        }
+    }
+
+    let out = truncate_chars(&out, COMPACT_MAX_CHARS);
+    match continuation_hint(tool, result) {
+        Some(hint) => format!("{}\n{}", out, hint),
+        None => out,
+    }
+}
+
+/// Cut to at most `max` characters, the last one being an ellipsis when cut
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Copy of `value` with long strings cut, object keys in sorted order
+fn truncate_json_strings(value: &Value) -> Value {
+    match value {
+        Value::String(text) if text.chars().count() > JSON_STRING_MAX_CHARS => {
+            let cut: String = text.chars().take(JSON_STRING_MAX_CHARS).collect();
+            Value::String(format!("{}… [{} chars]", cut, text.chars().count()))
+        }
+        Value::Array(items) => Value::Array(items.iter().map(truncate_json_strings).collect()),
+        Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            let mut out = serde_json::Map::new();
+            for key in keys {
+                out.insert(key.clone(), truncate_json_strings(&fields[key]));
+            }
+            Value::Object(out)
+        }
+        other => other.clone(),
+    }
 }
 
+/// First tool call in a reply, whatever format the model wrote it in
+///
+/// Understands `{"tool": ..., "params": ...}` (bare or in a code block),
+/// `<use_tool>` blocks, `<tool_call>` wrappers (Qwen, Hermes) and
+/// `{"name": ..., "arguments": ...}` objects (Llama 3.1, OpenAI function
+/// calling), all normalized to a `ToolCall`.
 pub fn extract_tool_call(text: &str) -> Option<ToolCall> {
     let trimmed = text.trim();
     if trimmed.is_empty() {
@@ -74,6 +199,9 @@ pub fn extract_tool_call(text: &str) -> Option<ToolCall> {
     if let Some(call) = extract_xml_tool_call(trimmed) {
         return Some(call);
     }
+    if let Some(call) = tool_call_tag_bodies(trimmed).iter().find_map(|body| parse_tool_call_json(body)) {
+        return Some(call);
+    }
 
     // Try 1: Direct JSON parse
     if let Some(call) = parse_tool_call_json(trimmed) {
@@ -128,24 +256,94 @@ pub fn extract_tool_call(text: &str) -> Option<ToolCall> {
     None
 }
 
+/// Read-only tools whose calls can run concurrently within one response
+pub const PARALLEL_SAFE_TOOLS: &[&str] = &["file_read", "grep", "glob", "file_info"];
+
+/// Every tool call in a response, in order
+///
+/// A response with several ```json {"tool": ...}``` blocks yields them all;
+/// otherwise this is `extract_tool_call` and its fallbacks.
+pub fn extract_tool_calls(text: &str) -> Vec<ToolCall> {
+    let calls: Vec<ToolCall> = extract_all_json_objects(text.trim())
+        .iter()
+        .filter_map(|block| parse_tool_call_json(block))
+        .collect();
+    if calls.len() > 1 {
+        return calls;
+    }
+    extract_tool_call(text).into_iter().collect()
+}
+
+/// Reply text without its tool calls, for display
+///
+/// Removes JSON calls, `<use_tool>` blocks and the code fences left empty
+/// around them; the calls themselves are shown from the recorded history.
+pub fn strip_tool_calls(text: &str) -> String {
+    let mut out = text.to_string();
+    for block in extract_all_json_objects(text) {
+        if parse_tool_call_json(&block).is_some() {
+            out = out.replacen(&block, "", 1);
+        }
+    }
+    if let Ok(tags) = Regex::new(r"(?s)<tool_call>\s*(</tool_call>|$)") {
+        out = tags.replace_all(&out, "").into_owned();
+    }
+    if let Ok(xml) = Regex::new(r"(?s)<use_tool\s+name=['\x22][^'\x22]+['\x22]\s*>.*?</use_tool>") {
+        out = xml.replace_all(&out, "").into_owned();
+    }
+    if let Ok(empty_fence) = Regex::new(r"```[A-Za-z]*\s*```") {
+        out = empty_fence.replace_all(&out, "").into_owned();
+    }
+    out.trim().to_string()
+}
+
+/// Number of leading calls that can run together (see `PARALLEL_SAFE_TOOLS`)
+///
+/// Only a leading run is batched, so a read never overtakes an earlier write.
+pub fn parallel_batch_len(calls: &[ToolCall]) -> usize {
+    calls
+        .iter()
+        .take_while(|call| PARALLEL_SAFE_TOOLS.contains(&call.tool.as_str()))
+        .count()
+}
+
 fn parse_tool_call_json(input: &str) -> Option<ToolCall> {
     let value: Value = serde_json::from_str(input).ok()?;
-    let obj = value.as_object()?;
-    // Support both "tool" and "name" fields for tool call format
-    let tool = obj
-        .get("tool")
-        .and_then(|v| v.as_str())
-        .or_else(|| obj.get("name").and_then(|v| v.as_str()))?
-        .to_string();
-    let params = obj
-        .get("params")
-        .cloned()
-        .or_else(|| obj.get("arguments").cloned())
-        .unwrap_or(Value::Null);
+    tool_call_from_value(&value)
+}
 
+/// A tool call object in any supported shape
+///
+/// `{"tool": ..., "params": ...}`, `{"name": ..., "arguments": ...}` (or
+/// `"parameters"`, as Llama 3.1 writes it) and OpenAI's
+/// `{"type": "function", "function": {...}}` with its arguments as a JSON
+/// string. A bare `name` is not enough: tool params often have one.
+fn tool_call_from_value(value: &Value) -> Option<ToolCall> {
+    let obj = value.as_object()?;
+    if let Some(function) = obj.get("function").filter(|f| f.is_object()) {
+        return tool_call_from_value(function);
+    }
+    if let Some(tool) = obj.get("tool").and_then(Value::as_str) {
+        let params = obj.get("params").cloned().unwrap_or(Value::Null);
+        return Some(ToolCall { tool: tool.to_string(), params });
+    }
+    let tool = obj.get("name").and_then(Value::as_str)?.to_string();
+    let params = match obj.get("arguments").or_else(|| obj.get("parameters"))? {
+        Value::String(json) => serde_json::from_str(json).unwrap_or_else(|_| Value::String(json.clone())),
+        arguments => arguments.clone(),
+    };
     Some(ToolCall { tool, params })
 }
 
+/// Contents of the `<tool_call>` tags in `text`; the last one may be left
+/// open when generation stopped before its closing tag
+fn tool_call_tag_bodies(text: &str) -> Vec<&str> {
+    text.split("<tool_call>")
+        .skip(1)
+        .map(|rest| rest.split("</tool_call>").next().unwrap_or(rest).trim())
+        .collect()
+}
+
 fn extract_xml_tool_call(text: &str) -> Option<ToolCall> {
     // Regex for <use_tool name="...">...</use_tool>
     // Using dot matches all (?s) to handle newlines
@@ -272,3 +470,478 @@ fn extract_all_json_objects(text: &str) -> Vec<String> {
 
     results
 }
+
+/// Fake conversation markup a hallucinating model writes out; more than a
+/// few of one means it is no longer answering
+const GARBAGE_PATTERNS: &[&str] = &["assistantcommentary", "userresponse", "toolresult:"];
+
+/// Characters at the end of a reply checked for glued text and loops
+const GARBAGE_SCAN_CHARS: usize = 8000;
+
+/// Length of the passages the repetition check compares
+const REPEAT_WINDOW: usize = 40;
+
+/// Detect if generated text is garbage/corrupted (model hallucinating)
+///
+/// Three signs: fake tool output, prose glued together without spaces, and
+/// the same passage coming back again and again. The last two leave out
+/// code blocks; the glued-text one also skips URLs, paths, encoded data and
+/// scripts written without spaces (Chinese, Japanese, Thai...).
+pub fn is_garbage_text(content: &str, sensitivity: GarbageSensitivity) -> bool {
+    // Most fake outputs, longest average word, most repeats of a passage
+    let (max_patterns, max_word_len, max_repeats) = match sensitivity {
+        GarbageSensitivity::Off => return false,
+        GarbageSensitivity::Low => (3, 40, 10),
+        GarbageSensitivity::Strict => (2, 25, 5),
+    };
+    if content.len() <= 200 {
+        return false;
+    }
+
+    // Patterns that indicate model is generating fake tool outputs
+    let lower = content.to_lowercase();
+    for pattern in GARBAGE_PATTERNS {
+        if lower.matches(pattern).count() > max_patterns {
+            tracing::warn!("Garbage detected: pattern '{}' repeated", pattern);
+            return true;
+        }
+    }
+    // Made-up status lines of tool results ("✅ file_read: ...")
+    let fake_statuses = content
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("✅ ")?.split_once(':'))
+        .filter(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_'))
+        .count();
+    if fake_statuses > max_patterns {
+        tracing::warn!("Garbage detected: {} fake tool statuses", fake_statuses);
+        return true;
+    }
+
+    let prose = text_outside_code(content);
+    let tail_start = prose.char_indices().rev().nth(GARBAGE_SCAN_CHARS - 1).map_or(0, |(i, _)| i);
+    let prose = &prose[tail_start..];
+
+    // Check for abnormal word length (text stuck together without spaces)
+    let letters = prose.chars().filter(|c| !c.is_whitespace()).count();
+    let unspaced = prose.chars().filter(|&c| is_unspaced_script(c)).count();
+    if prose.len() > 300 && unspaced * 10 < letters {
+        let words: Vec<&str> = prose.split_whitespace().filter(|word| !is_opaque_token(word)).collect();
+        if !words.is_empty() {
+            let avg_word_len = words.iter().map(|word| word.chars().count()).sum::<usize>() / words.len();
+            if avg_word_len > max_word_len {
+                tracing::warn!("Garbage detected: abnormal word length ratio {}", avg_word_len);
+                return true;
+            }
+        }
+    }
+
+    // Check for a passage repeated over and over (a generation loop)
+    let repeats = most_repeated_passage(prose);
+    if repeats >= max_repeats {
+        tracing::warn!("Garbage detected: passage repeated {} times", repeats);
+        return true;
+    }
+
+    false
+}
+
+/// Text outside fenced code blocks; a fence still open runs to the end
+fn text_outside_code(content: &str) -> String {
+    let mut text = String::new();
+    let mut in_code = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        } else if !in_code {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Characters of scripts written without spaces between words
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c as u32,
+        0x0E00..=0x0EFF // Thai, Lao
+        | 0x0F00..=0x0FFF // Tibetan
+        | 0x1000..=0x109F // Myanmar
+        | 0x1780..=0x17FF // Khmer
+        | 0x3000..=0x30FF // CJK punctuation, Hiragana, Katakana
+        | 0x3400..=0x4DBF // CJK extension A
+        | 0x4E00..=0x9FFF // CJK ideographs
+        | 0xF900..=0xFAFF // CJK compatibility ideographs
+        | 0xFF00..=0xFFEF // Full and halfwidth forms
+    )
+}
+
+/// URLs, paths and encoded data (base64, hex): long, but not glued words
+fn is_opaque_token(word: &str) -> bool {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+    let encoded = word.chars().any(|c| c.is_ascii_digit())
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || "+/=-_".contains(c));
+    word.contains(['/', '\\']) || encoded
+}
+
+/// Most non-overlapping occurrences of any `REPEAT_WINDOW`-character passage
+///
+/// Case and runs of whitespace are ignored. Passages are compared by a
+/// rolling hash, so the text is scanned once.
+fn most_repeated_passage(text: &str) -> usize {
+    const BASE: u64 = 1_000_003;
+    let mut chars = Vec::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        let c = if c.is_whitespace() { ' ' } else { c };
+        if !(c == ' ' && chars.last() == Some(&' ')) {
+            chars.push(c);
+        }
+    }
+    if chars.len() < REPEAT_WINDOW {
+        return 0;
+    }
+
+    let top = BASE.wrapping_pow(REPEAT_WINDOW as u32 - 1);
+    let mut hash = chars[..REPEAT_WINDOW].iter().fold(0u64, |hash, &c| hash.wrapping_mul(BASE).wrapping_add(c as u64));
+    // Hash -> (occurrences, start of the last one counted)
+    let mut seen: HashMap<u64, (usize, usize)> = HashMap::new();
+    let mut most = 0;
+    for start in 0..=chars.len() - REPEAT_WINDOW {
+        if start > 0 {
+            hash = hash
+                .wrapping_sub((chars[start - 1] as u64).wrapping_mul(top))
+                .wrapping_mul(BASE)
+                .wrapping_add(chars[start + REPEAT_WINDOW - 1] as u64);
+        }
+        let (count, last) = seen.entry(hash).or_insert((0, 0));
+        if *count == 0 || start >= *last + REPEAT_WINDOW {
+            *count += 1;
+            *last = start;
+            most = most.max(*count);
+        }
+    }
+    most
+}
+
+/// A reply that tried to call a tool but whose JSON did not parse
+///
+/// Strict on purpose: needs both the "tool" object opener and a "params" key,
+/// or a `<tool_call>` tag with a "name" in it.
+pub fn looks_like_malformed_tool_call(text: &str) -> bool {
+    let json_call = (text.contains("{\"tool\"") || text.contains("{ \"tool\"")) && text.contains("\"params\"");
+    json_call || (text.contains("<tool_call>") && text.contains("\"name\""))
+}
+
+/// Start of a tool result the model wrote itself instead of calling the tool
+///
+/// Looks for the formats the app shows or injects: tool card status lines
+/// ("✅ file_read: ...", "❌ `bash` (0.2s): ..."), skill output markers
+/// ("=== OUTPUT ===") and `<tool_result>` blocks. Returns the byte offset of
+/// the first such line.
+pub fn find_fabricated_tool_output(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let status = ["✅ ", "❌ "].iter().find_map(|prefix| trimmed.strip_prefix(prefix));
+        let fabricated = status.is_some_and(looks_like_tool_status)
+            || trimmed.starts_with("=== OUTPUT")
+            || trimmed.starts_with("=== END OUTPUT")
+            || trimmed.starts_with("<tool_result>");
+        if fabricated {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// `tool_name: ...` or `` `tool_name` (0.1s): ... `` after a status emoji
+fn looks_like_tool_status(rest: &str) -> bool {
+    let (name, after) = match rest.strip_prefix('`') {
+        Some(quoted) => match quoted.split_once('`') {
+            Some((name, after)) => (name, after),
+            None => return false,
+        },
+        None => match rest.find(|c: char| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')) {
+            Some(end) => rest.split_at(end),
+            None => return false,
+        },
+    };
+    let is_tool_name = name.len() > 1
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    is_tool_name && (after.starts_with(':') || after.starts_with(" ("))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Compare against `fixtures/tool_results/<name>`; `UPDATE_GOLDEN=1` rewrites the file
+    fn assert_golden(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/tool_results").join(name);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+        assert_eq!(actual, expected, "{} changed, rerun with UPDATE_GOLDEN=1 to accept", name);
+    }
+
+    // Keys are written in sorted order so the full format is stable whatever the map ordering
+    fn sample_results() -> Vec<(&'static str, ToolResult)> {
+        vec![
+            (
+                "file_read",
+                ToolResult {
+                    success: true,
+                    data: serde_json::json!({
+                        "content": "   1|a3f| [package]\n   2|9c1| name = \"demo\"\n   3|b07| version = \"0.1.0\"",
+                        "path": "/work/demo/Cargo.toml",
+                        "total_lines": 3
+                    }),
+                    message: "Fichier lu: /work/demo/Cargo.toml (3 lignes)".to_string(),
+                },
+            ),
+            (
+                "grep",
+                ToolResult {
+                    success: true,
+                    data: serde_json::json!({
+                        "matches": [
+                            { "file": "src/main.rs", "line": 3, "text": "fn main() {" },
+                            { "file": "src/lib.rs", "line": 12, "text": "pub fn main_loop() {" }
+                        ],
+                        "total_matches": 2,
+                        "truncated": false
+                    }),
+                    message: "2 correspondance(s) trouvée(s)".to_string(),
+                },
+            ),
+            (
+                "web_fetch",
+                ToolResult {
+                    success: true,
+                    data: serde_json::json!({
+                        "cached": false,
+                        "content": "Lorem ipsum dolor sit amet. ".repeat(50),
+                        "content_length": 1400,
+                        "content_type": "text/html",
+                        "headers": { "content-type": "text/html" },
+                        "status": 200,
+                        "url": "https://example.com/"
+                    }),
+                    message: "HTTP GET 200 (text/html, 1400 chars)".to_string(),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_tool_result_formats_golden() {
+        for (tool, result) in sample_results() {
+            for verbosity in [ToolResultVerbosity::Full, ToolResultVerbosity::Compact, ToolResultVerbosity::Json] {
+                let text = format_tool_result(tool, &result, verbosity);
+                assert_golden(&format!("{}.{}.txt", tool, verbosity.as_str()), &text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compact_format_hard_cap() {
+        let result = ToolResult {
+            success: true,
+            data: serde_json::json!({ "content": "é".repeat(5000) }),
+            message: "big".to_string(),
+        };
+        let text = format_tool_result("file_read", &result, ToolResultVerbosity::Compact);
+        assert_eq!(text.chars().count(), COMPACT_MAX_CHARS);
+        assert!(text.ends_with('…'));
+    }
+
+    #[test]
+    fn test_continuation_hint_survives_truncation() {
+        let mut result = ToolResult {
+            success: true,
+            data: serde_json::json!({ "content": "x".repeat(5000), "truncated": true, "continuation": { "offset": 40 } }),
+            message: "page 1".to_string(),
+        };
+        for verbosity in [ToolResultVerbosity::Full, ToolResultVerbosity::Compact] {
+            let text = format_tool_result("grep", &result, verbosity);
+            assert!(text.ends_with(r#"call grep again with the same params plus {"offset":40}]"#), "{}", text);
+        }
+
+        // No hint without a continuation
+        result.data["continuation"] = Value::Null;
+        assert!(continuation_hint("grep", &result).is_none());
+    }
+
+    #[test]
+    fn test_strip_tool_calls() {
+        let text = "Reading it.\n```json\n{\"tool\": \"file_read\", \"params\": {\"path\": \"a.rs\"}}\n```";
+        assert_eq!(strip_tool_calls(text), "Reading it.");
+        assert_eq!(strip_tool_calls(r#"<use_tool name="glob"><param name="pattern">*</param></use_tool>"#), "");
+        assert_eq!(strip_tool_calls("Plain answer with {\"json\": 1}"), "Plain answer with {\"json\": 1}");
+    }
+
+    #[test]
+    fn test_extract_multiple_tool_calls() {
+        let text = r#"Reading both files.
+```json
+{"tool": "file_read", "params": {"path": "src/main.rs"}}
+```
+```json
+{"tool": "grep", "params": {"pattern": "fn main", "path": "./src"}}
+```
+```json
+{"tool": "file_write", "params": {"path": "out.txt", "content": "{}"}}
+```"#;
+        let calls = extract_tool_calls(text);
+        let names: Vec<&str> = calls.iter().map(|c| c.tool.as_str()).collect();
+        assert_eq!(names, ["file_read", "grep", "file_write"]);
+        assert_eq!(calls[1].params["pattern"], "fn main");
+        assert_eq!(parallel_batch_len(&calls), 2);
+
+        // A single call still goes through the usual fallbacks
+        let single = extract_tool_calls(r#"<use_tool name="glob"><param name="pattern">**/*.rs</param></use_tool>"#);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].tool, "glob");
+        assert_eq!(parallel_batch_len(&extract_tool_calls(r#"{"tool": "bash", "params": {}}"#)), 0);
+    }
+
+    #[test]
+    fn test_extract_tool_call_formats() {
+        // (model, raw reply, tool, params)
+        let cases = [
+            (
+                "json block",
+                "I'll look at the manifest.\n```json\n{\"tool\": \"file_read\", \"params\": {\"path\": \"Cargo.toml\"}}\n```",
+                "file_read",
+                serde_json::json!({"path": "Cargo.toml"}),
+            ),
+            (
+                "qwen2.5",
+                "<tool_call>\n{\"name\": \"grep\", \"arguments\": {\"pattern\": \"fn main\", \"path\": \"./src\"}}\n</tool_call>",
+                "grep",
+                serde_json::json!({"pattern": "fn main", "path": "./src"}),
+            ),
+            (
+                "qwen3, after thinking",
+                "<think>\nThe user wants the Rust files.\n</think>\n\n<tool_call>\n{\"name\": \"glob\", \"arguments\": {\"pattern\": \"**/*.rs\"}}\n</tool_call>",
+                "glob",
+                serde_json::json!({"pattern": "**/*.rs"}),
+            ),
+            (
+                "hermes, stopped before the closing tag",
+                "<tool_call>\n{\"arguments\": {\"command\": \"cargo test\"}, \"name\": \"bash\"}\n",
+                "bash",
+                serde_json::json!({"command": "cargo test"}),
+            ),
+            (
+                "llama 3.1",
+                "{\"name\": \"web_search\", \"parameters\": {\"query\": \"rust 1.80 release notes\"}}",
+                "web_search",
+                serde_json::json!({"query": "rust 1.80 release notes"}),
+            ),
+            (
+                "llama 3.1 with python tag",
+                "<|python_tag|>{\"name\": \"file_list\", \"parameters\": {\"path\": \".\"}}",
+                "file_list",
+                serde_json::json!({"path": "."}),
+            ),
+            (
+                "openai function call",
+                "{\"type\": \"function\", \"function\": {\"name\": \"file_info\", \"arguments\": \"{\\\"path\\\": \\\"src/main.rs\\\"}\"}}",
+                "file_info",
+                serde_json::json!({"path": "src/main.rs"}),
+            ),
+        ];
+        for (model, reply, tool, params) in cases {
+            let call = extract_tool_call(reply).unwrap_or_else(|| panic!("no call found for {}", model));
+            assert_eq!(call.tool, tool, "{}", model);
+            assert_eq!(call.params, params, "{}", model);
+            assert_eq!(extract_tool_calls(reply).len(), 1, "{}", model);
+        }
+
+        // Several tagged calls come out in order, and leave no text behind
+        let two = "<tool_call>\n{\"name\": \"file_read\", \"arguments\": {\"path\": \"a.rs\"}}\n</tool_call>\n<tool_call>\n{\"name\": \"file_read\", \"arguments\": {\"path\": \"b.rs\"}}\n</tool_call>";
+        let calls = extract_tool_calls(two);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].params["path"], "b.rs");
+        assert_eq!(strip_tool_calls(two), "");
+        // A lone "name" is a param, not a call
+        assert!(extract_tool_call("{\"name\": \"demo\", \"version\": \"0.1.0\"}").is_none());
+    }
+
+    /// Pseudo-random base64, the way an encoded image looks
+    fn base64_blob(len: usize) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut seed: u32 = 12345;
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                ALPHABET[(seed >> 16) as usize % 64] as char
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_garbage_ignores_encoded_data_and_urls() {
+        let blob = base64_blob(1200);
+        let inline = format!("Here is the encoded icon you asked for: {} You can paste it into an img tag.", blob);
+        let fenced = format!("The encoded icon:\n\n```\n{}\n```\n\nPaste it into an img tag.", blob);
+        let url = format!(
+            "The release notes are at https://example.com/releases/{}/notes.html and list every change since the last version. Read them before upgrading, especially the part about the configuration format, which changed in a way that is not backward compatible.",
+            &blob[..200]
+        );
+        for sensitivity in [GarbageSensitivity::Low, GarbageSensitivity::Strict] {
+            assert!(!is_garbage_text(&inline, sensitivity));
+            assert!(!is_garbage_text(&fenced, sensitivity));
+            assert!(!is_garbage_text(&url, sensitivity));
+        }
+    }
+
+    #[test]
+    fn test_garbage_ignores_unspaced_scripts() {
+        let japanese = "東京は日本の首都であり、世界でも有数の大都市です。江戸時代には徳川幕府が置かれ、政治と経済の中心として栄えました。\
+            明治維新の後、天皇が京都から移り住み、正式に首都となりました。現在では約千四百万人が暮らし、\
+            高層ビルが立ち並ぶ一方で、古い寺や神社、静かな庭園も数多く残っています。季節ごとに桜や紅葉を楽しむことができ、\
+            国内外から多くの観光客が訪れます。";
+        assert!(japanese.len() > 300);
+        assert!(!is_garbage_text(japanese, GarbageSensitivity::Low));
+        assert!(!is_garbage_text(japanese, GarbageSensitivity::Strict));
+    }
+
+    #[test]
+    fn test_garbage_detects_loops_and_fake_output() {
+        let looping = format!(
+            "I will read the configuration to find the port.\n\n{}",
+            "Let me check the configuration file again to be sure it is right.\n".repeat(12)
+        );
+        assert!(is_garbage_text(&looping, GarbageSensitivity::Low));
+        assert!(is_garbage_text(&looping, GarbageSensitivity::Strict));
+        assert!(!is_garbage_text(&looping, GarbageSensitivity::Off));
+        assert!(most_repeated_passage(&looping.to_uppercase()) >= 12);
+
+        let fake = "✅ file_read: config.toml\n✅ pdf_read: report.pdf\n".repeat(3) + &"Voici le contenu. ".repeat(10);
+        assert!(is_garbage_text(&fake, GarbageSensitivity::Low));
+        let glued = "Thisisalongsentencethatthemodelwrotewithoutanyspacesbetweenthewordsatall ".repeat(6);
+        assert!(is_garbage_text(&glued, GarbageSensitivity::Low));
+
+        let prose = "The parser reads the file line by line, keeps track of open sections and reports the first malformed entry with its line number. ".to_string()
+            + "Errors are collected rather than returned at once, so a single run lists everything that needs fixing.";
+        assert!(!is_garbage_text(&prose, GarbageSensitivity::Strict));
+    }
+
+    #[test]
+    fn test_find_fabricated_tool_output() {
+        let faked = "Let me read it.\n✅ file_read: Cargo.toml\nname = \"demo\"";
+        assert_eq!(find_fabricated_tool_output(faked), Some("Let me read it.\n".len()));
+        assert_eq!(find_fabricated_tool_output("❌ `bash` (0.2s): exit code 1"), Some(0));
+        assert_eq!(find_fabricated_tool_output("Running the skill.\n\n=== OUTPUT ===\ndone"), Some("Running the skill.\n\n".len()));
+        assert!(find_fabricated_tool_output("<tool_result>\n<tool>glob</tool>").is_some());
+        // Ordinary checklists are left alone
+        assert_eq!(find_fabricated_tool_output("✅ Tests pass\n❌ Docs: still missing\n✅ done"), None);
+        assert_eq!(find_fabricated_tool_output("All good."), None);
+    }
+}
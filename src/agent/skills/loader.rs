@@ -1,30 +1,125 @@
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::fs;
 use crate::agent::skills::{Skill, parse_skill, SkillError};
 
+/// README written into a freshly created skills directory
+const STARTER_README: &str = r#"# Skills
+
+Each sub-directory containing a `SKILL.md` file is a skill. The agent sees it
+as a `skill_<name>` tool and can invoke it when relevant.
+
+```
+skills/
+  my-skill/
+    SKILL.md      # frontmatter (name, description) + instructions
+    main.py       # optional script executed when the skill is invoked
+```
+
+See `example-hello/` for a minimal example.
+"#;
+
+/// Example skill written into a freshly created skills directory
+const EXAMPLE_SKILL: &str = r#"---
+name: example-hello
+description: Example skill showing the SKILL.md format. Greets the user.
+disable_auto_invoke: true
+---
+
+When this skill is invoked, greet the user and briefly explain that skills
+live in the skills directory as folders containing a SKILL.md file.
+"#;
+
+/// Set once a read failure has been logged, so repeated loads don't spam the log
+static READ_FAILURE_LOGGED: AtomicBool = AtomicBool::new(false);
+
+/// Why the skills list looks the way it does
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkillsStatus {
+    /// Skills were found and loaded
+    Loaded(usize),
+    /// Directories are readable but contain no skills yet
+    Empty,
+    /// A skills directory exists but could not be read
+    Unreadable { path: PathBuf, error: String },
+}
+
+/// Result of loading skills from all locations
+#[derive(Debug, Clone, Default)]
+pub struct SkillLoadReport {
+    pub skills: Vec<Skill>,
+    /// Directories that could not be read, with the underlying error
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+impl SkillLoadReport {
+    /// Summarize the report; read failures win over "no skills yet"
+    pub fn status(&self) -> SkillsStatus {
+        if let Some((path, error)) = self.failures.first() {
+            SkillsStatus::Unreadable { path: path.clone(), error: error.clone() }
+        } else if self.skills.is_empty() {
+            SkillsStatus::Empty
+        } else {
+            SkillsStatus::Loaded(self.skills.len())
+        }
+    }
+}
+
 /// Loader for discovering and loading skills
 pub struct SkillLoader;
 
 impl SkillLoader {
     /// Load skills from all standard locations (global and project-local)
     pub async fn load_all() -> Vec<Skill> {
-        let mut skills = Vec::new();
+        Self::load_all_with_report().await.skills
+    }
 
-        // 1. Load global skills
+    /// Load skills from all standard locations, keeping track of unreadable directories
+    pub async fn load_all_with_report() -> SkillLoadReport {
+        let mut report = SkillLoadReport::default();
+
+        // 1. Load global skills (created with a starter README on first run)
         if let Some(global_dir) = Self::get_global_skills_dir() {
-            if let Ok(mut global_skills) = Self::load_from_dir(&global_dir).await {
-                skills.append(&mut global_skills);
+            if let Err(e) = Self::ensure_dir(&global_dir).await {
+                tracing::debug!("Could not create skills directory {}: {}", global_dir.display(), e);
+            }
+            Self::load_into(&global_dir, &mut report).await;
+        }
+
+        // 2. Load project-local skills (.localclaw/skills)
+        // We assume we are running in the project root
+        let local_dir = PathBuf::from(".localclaw").join("skills");
+        Self::load_into(&local_dir, &mut report).await;
+
+        report
+    }
+
+    async fn load_into(dir: &Path, report: &mut SkillLoadReport) {
+        match Self::load_from_dir(dir).await {
+            Ok(mut skills) => report.skills.append(&mut skills),
+            Err(e) => {
+                if !READ_FAILURE_LOGGED.swap(true, Ordering::Relaxed) {
+                    tracing::warn!("Cannot read skills directory {}: {}", dir.display(), e);
+                } else {
+                    tracing::debug!("Cannot read skills directory {}: {}", dir.display(), e);
+                }
+                report.failures.push((dir.to_path_buf(), e.to_string()));
             }
         }
+    }
 
-    // 2. Load project-local skills (.localclaw/skills)
-    // We assume we are running in the project root
-    let local_dir = PathBuf::from(".localclaw").join("skills");
-        if let Ok(mut local_skills) = Self::load_from_dir(&local_dir).await {
-            skills.append(&mut local_skills);
+    /// Create the skills directory with a README and an example skill if it doesn't exist
+    pub async fn ensure_dir(path: &Path) -> Result<(), SkillError> {
+        if path.exists() {
+            return Ok(());
         }
 
-        skills
+        let example_dir = path.join("example-hello");
+        fs::create_dir_all(&example_dir).await?;
+        fs::write(path.join("README.md"), STARTER_README).await?;
+        fs::write(example_dir.join("SKILL.md"), EXAMPLE_SKILL).await?;
+        tracing::info!("Created skills directory at {}", path.display());
+        Ok(())
     }
 
     /// Load skills from a specific directory
@@ -69,7 +164,7 @@ impl SkillLoader {
     }
 
     /// Get the global skills directory based on OS
-    fn get_global_skills_dir() -> Option<PathBuf> {
+    pub fn get_global_skills_dir() -> Option<PathBuf> {
         // Use directories crate to find standard data dir
         if let Some(proj_dirs) = directories::ProjectDirs::from("com", "clawRS", "clawRS") {
             let _data_dir = proj_dirs.data_dir();
@@ -81,3 +176,43 @@ impl SkillLoader {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ensure_dir_creates_starter_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("skills");
+
+        SkillLoader::ensure_dir(&dir).await.unwrap();
+        assert!(dir.join("README.md").exists());
+
+        let skills = SkillLoader::load_from_dir(&dir).await.unwrap();
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].name, "skill_example_hello");
+        assert!(skills[0].disable_auto_invoke);
+    }
+
+    #[tokio::test]
+    async fn test_report_distinguishes_empty_from_unreadable() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        let mut report = SkillLoadReport::default();
+        SkillLoader::load_into(tmp.path(), &mut report).await;
+        assert_eq!(report.status(), SkillsStatus::Empty);
+
+        // A file where a directory is expected cannot be listed
+        let not_a_dir = tmp.path().join("skills");
+        std::fs::write(&not_a_dir, "oops").unwrap();
+        SkillLoader::load_into(&not_a_dir, &mut report).await;
+        match report.status() {
+            SkillsStatus::Unreadable { path, error } => {
+                assert_eq!(path, not_a_dir);
+                assert!(!error.is_empty());
+            }
+            other => panic!("expected Unreadable, got {:?}", other),
+        }
+    }
+}
@@ -1,9 +1,16 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use dashmap::DashMap;
 use thiserror::Error;
+use tokio::sync::{watch, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+use crate::agent::language::tool_text;
+use crate::storage::secrets;
+use crate::storage::settings::ToolLimit;
 
 /// Compute a short hash (2 chars) for a line of content
 /// This is used for Hashline - see https://github.com/0xZKnw/oh-my-pi
@@ -26,6 +33,18 @@ pub trait Tool: Send + Sync {
     fn description(&self) -> &str;
     fn parameters_schema(&self) -> Value;
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError>;
+
+    /// `execute`, giving up with `ToolError::Cancelled` when `cancel` fires
+    ///
+    /// Dropping the call is enough for most tools. Those holding something
+    /// that outlives the future (a child process, a protocol exchange)
+    /// override this to clean up.
+    async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
+        tokio::select! {
+            result = self.execute(params) => result,
+            _ = cancel.cancelled() => Err(ToolError::Cancelled),
+        }
+    }
 }
 
 /// Tool execution result
@@ -42,51 +61,376 @@ impl PartialEq for ToolResult {
     }
 }
 
+/// Default `output_limit` of the paging tools, in bytes: a page fits in what
+/// the chat injects for one result
+pub const DEFAULT_OUTPUT_LIMIT: usize = 3000;
+
+/// Byte budget of a paging tool's output (file_read, grep, file_search,
+/// recursive file_list), from its optional `output_limit` param
+#[derive(Debug, Clone, Copy)]
+pub struct OutputBudget {
+    limit: usize,
+    used: usize,
+}
+
+impl OutputBudget {
+    pub fn new(limit: usize) -> Self {
+        Self { limit, used: 0 }
+    }
+
+    pub fn from_params(params: &Value) -> Self {
+        Self::new(params["output_limit"].as_u64().map_or(DEFAULT_OUTPUT_LIMIT, |n| n as usize))
+    }
+
+    /// Count `bytes` more output, refused once it would go over the limit
+    ///
+    /// The first piece always fits, so every page makes progress.
+    pub fn take(&mut self, bytes: usize) -> bool {
+        if self.used > 0 && self.used + bytes > self.limit {
+            return false;
+        }
+        self.used += bytes;
+        true
+    }
+}
+
+/// Schema of the `output_limit` param of the paging tools
+pub fn output_limit_schema() -> Value {
+    serde_json::json!({
+        "type": "integer",
+        "description": "Maximum bytes of output (default 3000). Longer output is paged: call again with the params in `continuation`"
+    })
+}
+
+/// Mark `data` as one page of a longer output, `continuation` holding the
+/// params that fetch the next one
+pub fn mark_truncated(data: &mut Value, continuation: Value) {
+    data["truncated"] = Value::Bool(true);
+    data["continuation"] = continuation;
+}
+
+/// Results from `offset` on, as many as `max_results` and the budget
+/// allow, with the offset of the next page if any of the `total` remain
+pub fn page_results(
+    results: Vec<Value>,
+    offset: usize,
+    max_results: usize,
+    total: usize,
+    budget: &mut OutputBudget,
+) -> (Vec<Value>, Option<usize>) {
+    let page: Vec<Value> = results
+        .into_iter()
+        .skip(offset)
+        .take(max_results)
+        .take_while(|result| budget.take(result.to_string().len()))
+        .collect();
+    let shown = offset + page.len();
+    (page, (shown < total).then_some(shown))
+}
+
+/// Machine-readable class of a tool failure
+///
+/// Stated first in the error text shown to the model, and used as the label
+/// of the per-category error counters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    InvalidParameters,
+    NotFound,
+    PermissionDenied,
+    Timeout,
+    Network,
+    UnknownTool,
+    Execution,
+    Cancelled,
+}
+
+impl ErrorCategory {
+    /// Every category, in declaration order
+    pub const ALL: [ErrorCategory; 8] = [
+        ErrorCategory::InvalidParameters,
+        ErrorCategory::NotFound,
+        ErrorCategory::PermissionDenied,
+        ErrorCategory::Timeout,
+        ErrorCategory::Network,
+        ErrorCategory::UnknownTool,
+        ErrorCategory::Execution,
+        ErrorCategory::Cancelled,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCategory::InvalidParameters => "invalid_params",
+            ErrorCategory::NotFound => "not_found",
+            ErrorCategory::PermissionDenied => "permission_denied",
+            ErrorCategory::Timeout => "timeout",
+            ErrorCategory::Network => "network",
+            ErrorCategory::UnknownTool => "unknown_tool",
+            ErrorCategory::Execution => "execution",
+            ErrorCategory::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Lowercase fragments of `ExecutionFailed` messages that point at a transient cause
+const TRANSIENT_MARKERS: &[&str] = &[
+    "connection",
+    "timed out",
+    "temporarily unavailable",
+    "try again",
+    "broken pipe",
+    "dns",
+    "unreachable",
+    "502",
+    "503",
+    "504",
+];
+
 /// Tool errors
+///
+/// `Display` keeps the historical log format; the structured fields are for
+/// `category()` and `render_for_model()`.
 #[derive(Debug, Error)]
 pub enum ToolError {
     #[error("Invalid parameters: {0}")]
     InvalidParameters(String),
     #[error("Execution failed: {0}")]
     ExecutionFailed(String),
-    #[error("Permission denied: {0}")]
-    PermissionDenied(String),
-    #[error("Tool not found: {0}")]
-    NotFound(String),
+    /// `rule` names the check that refused, when there is one
+    #[error("Permission denied: {message}")]
+    PermissionDenied { rule: Option<String>, message: String },
+    /// A file or directory the tool works on does not exist
+    #[error("Execution failed: {message}")]
+    NotFound { path: String, message: String },
     #[error("Timeout")]
-    Timeout,
+    Timeout { after_secs: Option<u64> },
+    /// Transport failure, or an HTTP error status when `status` is set
+    #[error("Execution failed: {message}")]
+    Network { status: Option<u16>, message: String },
+    /// The requested tool is not registered
+    #[error("Tool not found: {0}")]
+    UnknownTool(String),
+    /// Stopped by the user before it finished
+    #[error("cancelled by user")]
+    Cancelled,
+}
+
+impl ToolError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ToolError::InvalidParameters(_) => ErrorCategory::InvalidParameters,
+            ToolError::ExecutionFailed(_) => ErrorCategory::Execution,
+            ToolError::PermissionDenied { .. } => ErrorCategory::PermissionDenied,
+            ToolError::NotFound { .. } => ErrorCategory::NotFound,
+            ToolError::Timeout { .. } => ErrorCategory::Timeout,
+            ToolError::Network { .. } => ErrorCategory::Network,
+            ToolError::UnknownTool(_) => ErrorCategory::UnknownTool,
+            ToolError::Cancelled => ErrorCategory::Cancelled,
+        }
+    }
+
+    /// Whether running the same call again may succeed
+    ///
+    /// Timeouts, 5xx/429 statuses and connection-level failures are
+    /// transient; bad parameters, refusals and missing files are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ToolError::Timeout { .. } => true,
+            ToolError::Network { status, .. } => !matches!(status, Some(s) if *s < 500 && *s != 429),
+            ToolError::ExecutionFailed(message) => {
+                let message = message.to_lowercase();
+                TRANSIENT_MARKERS.iter().any(|marker| message.contains(marker))
+            }
+            _ => false,
+        }
+    }
+
+    /// Classify an I/O error on `path`; `message` is the text logged before
+    pub fn io(path: impl AsRef<std::path::Path>, message: String, err: &std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => ToolError::NotFound {
+                path: path.as_ref().display().to_string(),
+                message,
+            },
+            std::io::ErrorKind::PermissionDenied => ToolError::PermissionDenied { rule: None, message },
+            _ => ToolError::ExecutionFailed(message),
+        }
+    }
+
+    /// Classify a failed HTTP request made with a `timeout_secs` client timeout
+    pub fn http(message: String, err: &reqwest::Error, timeout_secs: u64) -> Self {
+        if err.is_timeout() {
+            ToolError::Timeout { after_secs: Some(timeout_secs) }
+        } else {
+            ToolError::Network { status: err.status().map(|s| s.as_u16()), message }
+        }
+    }
+
+    /// The error with secret values masked in its text
+    pub fn scrubbed(self) -> Self {
+        match self {
+            ToolError::InvalidParameters(message) => ToolError::InvalidParameters(secrets::scrub(&message)),
+            ToolError::ExecutionFailed(message) => ToolError::ExecutionFailed(secrets::scrub(&message)),
+            ToolError::PermissionDenied { rule, message } => {
+                ToolError::PermissionDenied { rule, message: secrets::scrub(&message) }
+            }
+            ToolError::NotFound { path, message } => {
+                ToolError::NotFound { path: secrets::scrub(&path), message: secrets::scrub(&message) }
+            }
+            ToolError::Network { status, message } => ToolError::Network { status, message: secrets::scrub(&message) },
+            other => other,
+        }
+    }
+
+    /// Error text for the model: category first, then the structured context
+    ///
+    /// `ERROR category=timeout after=30s tool=web_fetch`, followed by the
+    /// usual message on the next line.
+    pub fn render_for_model(&self, tool: &str) -> String {
+        let mut header = format!("ERROR category={}", self.category().as_str());
+        match self {
+            ToolError::NotFound { path, .. } => header.push_str(&format!(" path={:?}", path)),
+            ToolError::PermissionDenied { rule: Some(rule), .. } => header.push_str(&format!(" rule={}", rule)),
+            ToolError::Timeout { after_secs: Some(secs) } => header.push_str(&format!(" after={}s", secs)),
+            ToolError::Network { status: Some(status), .. } => header.push_str(&format!(" status={}", status)),
+            _ => {}
+        }
+        format!("{} tool={}\n{}", header, tool, self)
+    }
 }
 
 /// Tool information for listing
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ToolInfo {
     pub name: String,
     pub description: String,
     pub parameters_schema: Value,
 }
 
+/// Tools that reach a remote service; they get `NETWORK_CALLS_PER_MINUTE` by default
+const NETWORK_TOOLS: &[&str] = &[
+    "web_search",
+    "code_search",
+    "company_research",
+    "deep_research_start",
+    "deep_research_check",
+    "web_crawl",
+    "web_fetch",
+    "web_read",
+    "web_download",
+    "http_request",
+    "ai_consult",
+    "github_issue_view",
+    "github_issue_list",
+    "github_pr_view",
+    "github_pr_create",
+];
+
+/// Local tools managing MCP servers, unlike the `mcp_<server>_<tool>` ones they add
+const MCP_MANAGEMENT_TOOLS: &[&str] = &["mcp_add_server", "mcp_list_servers", "mcp_remove_server"];
+
+/// Default rate for web, Exa and MCP tools
+const NETWORK_CALLS_PER_MINUTE: u32 = 20;
+
+/// Limits a tool gets until `set_limit` says otherwise
+pub fn default_tool_limit(name: &str) -> ToolLimit {
+    let remote_mcp = name.starts_with("mcp_") && !MCP_MANAGEMENT_TOOLS.contains(&name);
+    if NETWORK_TOOLS.contains(&name) || remote_mcp {
+        ToolLimit { max_calls_per_minute: Some(NETWORK_CALLS_PER_MINUTE), max_concurrent: None }
+    } else {
+        ToolLimit::default()
+    }
+}
+
+/// Token bucket and concurrency cap of one tool
+struct RateLimiter {
+    limit: ToolLimit,
+    /// Calls left, refilled continuously up to `max_calls_per_minute`
+    bucket: Mutex<(f64, Instant)>,
+    running: Option<Semaphore>,
+}
+
+impl RateLimiter {
+    fn new(limit: ToolLimit) -> Self {
+        let capacity = limit.max_calls_per_minute.unwrap_or(0) as f64;
+        Self {
+            limit,
+            bucket: Mutex::new((capacity, Instant::now())),
+            running: limit.max_concurrent.map(|n| Semaphore::new(n.max(1) as usize)),
+        }
+    }
+
+    /// Take one call from the bucket, or tell how long until the next one
+    fn take_call(&self) -> Result<(), Duration> {
+        let Some(per_minute) = self.limit.max_calls_per_minute else {
+            return Ok(());
+        };
+        let capacity = per_minute.max(1) as f64;
+        let per_sec = capacity / 60.0;
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let (tokens, refilled_at) = &mut *bucket;
+        *tokens = (*tokens + refilled_at.elapsed().as_secs_f64() * per_sec).min(capacity);
+        *refilled_at = Instant::now();
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - *tokens) / per_sec))
+        }
+    }
+}
+
 /// Tool registry - singleton pattern
+///
+/// Every change to the set of tools bumps a generation counter published on a
+/// `watch` channel, so prompt caches and UI lists can refresh when it moves.
+/// Calls made through `execute`/`execute_tool` are held to per-tool limits,
+/// shared with every `subset` of the registry.
 pub struct ToolRegistry {
     tools: DashMap<String, Arc<dyn Tool>>,
+    limiters: Arc<DashMap<String, Arc<RateLimiter>>>,
+    generation: watch::Sender<u64>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: DashMap::new(),
+            limiters: Arc::new(DashMap::new()),
+            generation: watch::Sender::new(0),
         }
     }
     
     pub async fn register(&self, tool: Arc<dyn Tool>) {
-        self.tools.insert(tool.name().to_string(), tool);
+        self.register_sync(tool);
     }
     
     pub fn register_sync(&self, tool: Arc<dyn Tool>) {
+        warn_if_malformed_schema(tool.as_ref());
         self.tools.insert(tool.name().to_string(), tool);
+        self.bump_generation();
+    }
+
+    /// Replace a tool with the same name, returning the previous one if any
+    pub fn replace(&self, tool: Arc<dyn Tool>) -> Option<Arc<dyn Tool>> {
+        warn_if_malformed_schema(tool.as_ref());
+        let previous = self.tools.insert(tool.name().to_string(), tool);
+        self.bump_generation();
+        previous
+    }
+
+    /// Remove a tool. Returns false if no tool had that name.
+    ///
+    /// Runs holding an `Arc` to the tool finish normally; later lookups get "not found".
+    pub fn unregister(&self, name: &str) -> bool {
+        let removed = self.tools.remove(name).is_some();
+        if removed {
+            self.bump_generation();
+        }
+        removed
     }
 
     pub fn remove(&self, name: &str) {
-        self.tools.remove(name);
+        self.unregister(name);
     }
     
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
@@ -104,9 +448,93 @@ impl ToolRegistry {
             .collect()
     }
     
+    /// Separate registry holding the tools whose name passes `keep`
+    ///
+    /// It shares this registry's limits, so a sub-agent can't get around them.
+    pub fn subset(&self, keep: impl Fn(&str) -> bool) -> ToolRegistry {
+        let subset = ToolRegistry {
+            limiters: self.limiters.clone(),
+            ..ToolRegistry::new()
+        };
+        for entry in self.tools.iter().filter(|entry| keep(entry.key())) {
+            subset.tools.insert(entry.key().clone(), entry.value().clone());
+        }
+        subset
+    }
+
     pub fn count(&self) -> usize {
         self.tools.len()
     }
+
+    /// Limit calls to `name` (`None` is unlimited), replacing its default
+    ///
+    /// Calls over the rate fail with a "rate limited" error; calls over the
+    /// concurrency cap wait for a running one to finish.
+    pub fn set_limit(&self, name: &str, max_calls_per_minute: Option<u32>, max_concurrent: Option<u32>) {
+        let limit = ToolLimit { max_calls_per_minute, max_concurrent };
+        self.limiters.insert(name.to_string(), Arc::new(RateLimiter::new(limit)));
+    }
+
+    /// Limits in force for `name`
+    pub fn limit(&self, name: &str) -> ToolLimit {
+        self.limiters
+            .get(name)
+            .map(|limiter| limiter.limit)
+            .unwrap_or_else(|| default_tool_limit(name))
+    }
+
+    /// Run a registered tool by name, within its limits
+    pub async fn execute(&self, name: &str, params: Value) -> Result<ToolResult, ToolError> {
+        let tool = self.get(name).ok_or_else(|| ToolError::UnknownTool(name.to_string()))?;
+        self.execute_tool(tool.as_ref(), params, CancellationToken::new()).await
+    }
+
+    /// Run a tool already looked up (it may have been unregistered since), within its limits
+    pub async fn execute_tool(&self, tool: &dyn Tool, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
+        let limiter = self
+            .limiters
+            .entry(tool.name().to_string())
+            .or_insert_with(|| Arc::new(RateLimiter::new(default_tool_limit(tool.name()))))
+            .clone();
+        if let Err(wait) = limiter.take_call() {
+            tracing::warn!("Tool {} rate limited for {:.1}s", tool.name(), wait.as_secs_f64());
+            return Err(ToolError::ExecutionFailed(format!(
+                "rate limited, retry in {}s",
+                wait.as_secs_f64().ceil() as u64
+            )));
+        }
+        let _permit = match &limiter.running {
+            Some(running) => tokio::select! {
+                permit = running.acquire() => permit.ok(),
+                _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+            },
+            None => None,
+        };
+        // `${secret:NAME}` is resolved only here, so the conversation keeps the placeholder
+        let params = secrets::substitute_value(&params).map_err(|e| ToolError::InvalidParameters(e.to_string()))?;
+        match tool.execute_cancellable(params, cancel).await {
+            Ok(mut result) => {
+                result.message = secrets::scrub(&result.message);
+                secrets::scrub_value(&mut result.data);
+                Ok(result)
+            }
+            Err(e) => Err(e.scrubbed()),
+        }
+    }
+
+    /// Current generation of the tool set
+    pub fn generation(&self) -> u64 {
+        *self.generation.borrow()
+    }
+
+    /// Subscribe to tool set changes (the value is the generation counter)
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.generation.subscribe()
+    }
+
+    fn bump_generation(&self) {
+        self.generation.send_modify(|g| *g += 1);
+    }
 }
 
 impl Default for ToolRegistry {
@@ -115,33 +543,88 @@ impl Default for ToolRegistry {
     }
 }
 
+/// Malformed schemas are still registered, but their params can't be validated reliably
+fn warn_if_malformed_schema(tool: &dyn Tool) {
+    if let Err(problem) = schema::check_schema(&tool.parameters_schema()) {
+        tracing::warn!("Tool {} has a malformed parameters schema: {}", tool.name(), problem);
+    }
+}
+
 /// Exa search tool
 pub mod exa;
 
 /// File system tools (edit, create, delete, move, info, mkdir, copy, search)
 pub mod filesystem;
 
-/// Shell execution tools (bash, background)
+/// Backups of changed files (undo, list)
+pub mod backup;
+
+/// Shell execution tools (bash, background, jobs)
 pub mod shell;
 
+/// Cargo tools (check, test) with structured diagnostics
+pub mod cargo;
+
+/// Test runner tools (pytest, npm test) with failure extraction
+pub mod test_runners;
+
+/// Allowlist, denied patterns and argument rules of the `command` tool
+pub mod command_policy;
+
 /// Git tools (status, diff, log, commit, branch, stash)
 pub mod git;
 
-/// Web tools (fetch, download)
+/// GitHub tools (issues, pull requests) through gh or the REST API
+pub mod github;
+
+/// Web tools (fetch, read as Markdown, download)
 pub mod web;
 
+/// Main-content extraction and HTML to Markdown conversion for web_read
+pub mod readability;
+
+/// Parameter validation against tool schemas
+pub mod schema;
+
+/// Streaming views of large files (head/tail, pattern matches, ranges)
+pub mod file_view;
+
 /// Developer tools (diff, find-replace, patch, wc)
 pub mod dev;
 
+/// Language server tools (diagnostics, hover) over an LSP client
+pub mod lsp;
+
+/// .gitignore matching for the recursive search tools
+pub mod gitignore;
+
+/// Document index tools (semantic search with an embedding model)
+pub mod index;
+
+/// Definition and reference search by symbol name
+pub mod symbols;
+
 /// System tools (process list, environment, system info, which, tree)
 pub mod system;
 
+/// CSV/TSV analysis tool (preview, stats, filter, aggregate)
+pub mod csv;
+
+/// Image inspection tool (dimensions, EXIF, thumbnail)
+pub mod images;
+
+/// Line-ending and encoding conversion tool
+pub mod encoding;
+
 /// PDF tools (read, create, add page, merge)
 pub mod pdf;
 
 /// OpenRouter AI consultation tool
 pub mod openrouter;
 
+/// Long-term memory tools (notes kept across conversations)
+pub mod memory;
+
 /// Skill creation tool
 pub mod skill_create;
 pub mod skill_invoke;
@@ -156,16 +639,23 @@ pub mod mcp_presets;
 /// MCP management tools
 pub mod mcp_management;
 
+/// Sub-agent tool running an isolated agent loop
+pub mod task;
+
 /// Builtin tools module
 pub mod builtins {
     use super::*;
+    use super::command_policy::{self, CommandVerdict};
     use tokio::process::Command;
     use tokio::time::{timeout, Duration};
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use glob::glob as glob_match;
     use regex::Regex;
     
     /// File read tool - improved with line numbers and range support
+    ///
+    /// Files above `file_view::SMART_VIEW_THRESHOLD` get a streamed smart view
+    /// (head, tail, pattern matches) instead of their full content.
     pub struct FileReadTool;
     
     #[async_trait]
@@ -175,7 +665,7 @@ pub mod builtins {
         }
         
         fn description(&self) -> &str {
-            "Read the contents of a file. Can optionally read specific line ranges."
+            "Read the contents of a file. Can optionally read specific line ranges. Large files return a smart view (first/last lines, line count, and lines matching `pattern` with context); then read specific ranges with start_line/end_line. Binary files return a hex dump; mode=hex dumps any byte range."
         }
         
         fn parameters_schema(&self) -> Value {
@@ -188,12 +678,26 @@ pub mod builtins {
                     },
                     "start_line": {
                         "type": "integer",
-                        "description": "Optional start line number (1-indexed)"
+                        "description": "Optional start line number (1-indexed). In hex mode: start byte offset"
                     },
                     "end_line": {
                         "type": "integer",
-                        "description": "Optional end line number (1-indexed)"
-                    }
+                        "description": "Optional end line number (1-indexed). In hex mode: end byte offset (exclusive)"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["auto", "full", "smart", "text", "hex"],
+                        "description": "auto (default): smart view for large files, hex dump for binary files, full content otherwise. text: read as text even if it looks binary. hex: hex dump of a byte range"
+                    },
+                    "pattern": {
+                        "type": "string",
+                        "description": "Optional regex: the smart view lists matching lines with context"
+                    },
+                    "context_lines": {
+                        "type": "integer",
+                        "description": "Lines of context around each match (default 2)"
+                    },
+                    "output_limit": output_limit_schema()
                 },
                 "required": ["path"]
             })
@@ -203,58 +707,219 @@ pub mod builtins {
             let path = params["path"].as_str()
                 .ok_or_else(|| ToolError::InvalidParameters("path is required".to_string()))?;
             
-            let path = PathBuf::from(path);
+            let path = filesystem::resolve_and_validate_path(path)?;
             let start_line = params["start_line"].as_u64().map(|n| n as usize);
             let end_line = params["end_line"].as_u64().map(|n| n as usize);
+            let mode = params["mode"].as_str().unwrap_or("auto");
+            let pattern = params["pattern"].as_str().filter(|p| !p.is_empty());
+
+            let size = tokio::fs::metadata(&path).await
+                .map_err(|e| ToolError::io(&path, tool_text!("file.read_error", e), &e))?
+                .len();
+            let read_error = |e: std::io::Error| ToolError::io(&path, tool_text!("file.read_error", e), &e);
+
+            if mode == "hex" {
+                let start = start_line.unwrap_or(0) as u64;
+                let end = end_line.map_or(start + file_view::HEX_PREVIEW_BYTES as u64, |end| end as u64);
+                if end <= start {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "in hex mode end_line is an exclusive byte offset and must be greater than start_line ({})",
+                        start
+                    )));
+                }
+                let len = (end - start).min(file_view::HEX_DUMP_MAX_BYTES) as usize;
+                let bytes = file_view::read_bytes_at(&path, start, len).await.map_err(read_error)?;
+                return Ok(ToolResult {
+                    success: true,
+                    data: serde_json::json!({
+                        "content": file_view::hex_dump(&bytes, start),
+                        "mode": "hex",
+                        "offset": start,
+                        "bytes": bytes.len(),
+                        "total_bytes": size,
+                        "path": path.display().to_string()
+                    }),
+                    message: tool_text!("file_read.hex", path.display(), start, start + bytes.len() as u64, size),
+                });
+            }
+
+            // Binary files get a dump rather than a decoding error the model would retry
+            let sample = file_view::read_bytes_at(&path, 0, file_view::SNIFF_BYTES).await.map_err(read_error)?;
+            let detected = file_view::detect_encoding(&sample);
+            if detected.is_none() && mode != "text" {
+                let complete = size <= file_view::HEX_DUMP_MAX_BYTES;
+                let bytes = if complete {
+                    tokio::fs::read(&path).await.map_err(read_error)?
+                } else {
+                    sample[..file_view::HEX_PREVIEW_BYTES.min(sample.len())].to_vec()
+                };
+                let mut content = file_view::hex_dump(&bytes, 0);
+                if !complete {
+                    content.push_str(&format!(
+                        "\n[Binary file: {} bytes, first {} shown. Use mode=hex with start_line/end_line as byte offsets to see more.]",
+                        size, bytes.len()
+                    ));
+                }
+                return Ok(ToolResult {
+                    success: true,
+                    data: serde_json::json!({
+                        "content": content,
+                        "mode": "hex",
+                        "binary": true,
+                        "bytes": bytes.len(),
+                        "total_bytes": size,
+                        "path": path.display().to_string()
+                    }),
+                    message: tool_text!("file_read.binary", path.display(), size, bytes.len()),
+                });
+            }
+            let encoding = detected.unwrap_or(file_view::TextEncoding::Utf8);
+            // Other encodings are transcoded up front; UTF-8 keeps the streamed paths
+            let decoded = match encoding {
+                file_view::TextEncoding::Utf8 => None,
+                _ => Some(file_view::decode(&tokio::fs::read(&path).await.map_err(read_error)?, encoding)),
+            };
+            let encoding_label = match (detected, encoding) {
+                (None, _) => Some("binary, read as utf-8"),
+                (_, file_view::TextEncoding::Utf8) => None,
+                (_, other) => Some(other.label()),
+            };
+
+            let is_large = size > file_view::SMART_VIEW_THRESHOLD;
+            let use_smart = match mode {
+                "smart" => true,
+                "full" | "text" => false,
+                _ => start_line.is_none() && (is_large || pattern.is_some()),
+            };
+
+            if use_smart {
+                let regex = pattern
+                    .map(|p| Regex::new(p).map_err(|e| ToolError::InvalidParameters(format!("invalid pattern: {}", e))))
+                    .transpose()?;
+                let options = file_view::SmartViewOptions {
+                    pattern: regex,
+                    context: params["context_lines"].as_u64().map(|n| n as usize).unwrap_or(file_view::DEFAULT_CONTEXT_LINES),
+                    ..Default::default()
+                };
+                let view = match &decoded {
+                    Some(text) => file_view::smart_view(std::io::Cursor::new(text.as_bytes()), &options),
+                    None => file_view::smart_view_file(&path, options).await,
+                }
+                .map_err(read_error)?;
+
+                let mut data = serde_json::json!({
+                    "content": file_view::render_smart_view(&view, pattern),
+                    "mode": "smart",
+                    "total_lines": view.total_lines,
+                    "total_bytes": view.total_bytes,
+                    "match_count": view.match_count,
+                    "path": path.display().to_string()
+                });
+                if let Some(label) = encoding_label {
+                    data["encoding"] = Value::String(label.to_string());
+                }
+                return Ok(ToolResult {
+                    success: true,
+                    data,
+                    message: tool_text!("file_read.preview", path.display(), view.total_lines, view.total_bytes),
+                });
+            }
+
+            // Ranges of large files are streamed rather than loading the whole file
+            if is_large && mode != "full" && decoded.is_none() {
+                if let Some(start) = start_line {
+                    let (mut lines, total_lines) = file_view::read_range_file(&path, start, end_line).await
+                        .map_err(|e| ToolError::io(&path, tool_text!("file.read_error", e), &e))?;
+                    let mut budget = OutputBudget::from_params(&params);
+                    let shown = lines.iter()
+                        .take_while(|(n, text)| budget.take(file_view::format_line(*n, text).len() + 1))
+                        .count();
+                    lines.truncate(shown);
+                    let range_info = match (lines.first(), lines.last()) {
+                        (Some((first, _)), Some((last, _))) => tool_text!("file_read.lines", first, last),
+                        _ => tool_text!("file_read.empty_range").to_string(),
+                    };
+
+                    let range: Vec<&str> = lines.iter().map(|(_, line)| line.as_str()).collect();
+                    let mut data = serde_json::json!({
+                        "content": file_view::render_range(&lines),
+                        "total_lines": total_lines,
+                        "range_sha256": filesystem::range_hash(&range),
+                        "path": path.display().to_string()
+                    });
+                    // Cut by the budget or the per-call line cap
+                    let requested_end = end_line.unwrap_or(total_lines).min(total_lines);
+                    if let Some((last, _)) = lines.last().filter(|(last, _)| *last < requested_end) {
+                        let mut next = serde_json::json!({ "start_line": last + 1 });
+                        if let Some(end) = end_line {
+                            next["end_line"] = serde_json::json!(end);
+                        }
+                        mark_truncated(&mut data, next);
+                    }
+                    return Ok(ToolResult {
+                        success: true,
+                        data,
+                        message: tool_text!("file_read.done", path.display(), total_lines, range_info),
+                    });
+                }
+            }
             
-            match tokio::fs::read_to_string(&path).await {
+            let content = match decoded {
+                Some(text) => Ok(text),
+                None => tokio::fs::read(&path).await.map(|bytes| file_view::decode(&bytes, encoding)),
+            };
+            match content {
                 Ok(content) => {
                     let lines: Vec<&str> = content.lines().collect();
                     let total_lines = lines.len();
                     
-                    let (output, range_info) = match (start_line, end_line) {
-                        (Some(start), Some(end)) => {
-                            let start = start.saturating_sub(1).min(total_lines);
-                            let end = end.min(total_lines);
-                            let selected: Vec<String> = lines[start..end]
-                                .iter()
-                                .enumerate()
-                                .map(|(i, l)| format!("{:>4}|{}| {}", start + i + 1, compute_line_hash(l), l))
-                                .collect();
-                            (selected.join("\n"), format!(" (lignes {}-{})", start + 1, end))
-                        }
-                        (Some(start), None) => {
-                            let start = start.saturating_sub(1).min(total_lines);
-                            let selected: Vec<String> = lines[start..]
-                                .iter()
-                                .enumerate()
-                                .map(|(i, l)| format!("{:>4}|{}| {}", start + i + 1, compute_line_hash(l), l))
-                                .collect();
-                            (selected.join("\n"), format!(" (depuis ligne {})", start + 1))
-                        }
-                        _ => {
-                            // Hashline format: line number | hash | content
-                            let numbered: Vec<String> = lines
-                                .iter()
-                                .enumerate()
-                                .map(|(i, l)| format!("{:>4}|{}| {}", i + 1, compute_line_hash(l), l))
-                                .collect();
-                            (numbered.join("\n"), String::new())
-                        }
+                    // Requested range, 0-indexed and end-exclusive
+                    let start = start_line.map_or(0, |start| start.saturating_sub(1).min(total_lines));
+                    let end = end_line.map_or(total_lines, |end| end.min(total_lines).max(start));
+
+                    // Hashline format: line number | hash | content, up to the output budget
+                    let mut budget = OutputBudget::from_params(&params);
+                    let selected: Vec<String> = lines[start..end]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, l)| format!("{:>4}|{}| {}", start + i + 1, compute_line_hash(l), l))
+                        .take_while(|line| budget.take(line.len() + 1))
+                        .collect();
+                    let shown_end = start + selected.len();
+
+                    let range_info = match (start_line, end_line) {
+                        _ if shown_end < end => tool_text!("file_read.lines_truncated", start + 1, shown_end),
+                        (Some(_), Some(_)) => tool_text!("file_read.lines", start + 1, end),
+                        (Some(_), None) => tool_text!("file_read.from_line", start + 1),
+                        _ => String::new(),
                     };
                     
+                    let mut data = serde_json::json!({
+                        "content": selected.join("\n"),
+                        "total_lines": total_lines,
+                        "path": path.display().to_string()
+                    });
+                    // sha256 of the lines shown, for file_patch_lines' expected_hash
+                    if start_line.is_some() || shown_end < end {
+                        data["range_sha256"] = Value::String(filesystem::range_hash(&lines[start..shown_end]));
+                    }
+                    if shown_end < end {
+                        let mut next = serde_json::json!({ "start_line": shown_end + 1 });
+                        if end_line.is_some() {
+                            next["end_line"] = serde_json::json!(end);
+                        }
+                        mark_truncated(&mut data, next);
+                    }
+                    if let Some(label) = encoding_label {
+                        data["encoding"] = Value::String(label.to_string());
+                    }
                     Ok(ToolResult {
                         success: true,
-                        data: serde_json::json!({ 
-                            "content": output,
-                            "total_lines": total_lines,
-                            "path": path.display().to_string()
-                        }),
-                        message: format!("Fichier lu: {} ({} lignes){}",
-                            path.display(), total_lines, range_info),
+                        data,
+                        message: tool_text!("file_read.done", path.display(), total_lines, range_info),
                     })
                 }
-                Err(e) => Err(ToolError::ExecutionFailed(format!("Erreur lecture fichier: {}", e))),
+                Err(e) => Err(ToolError::io(&path, tool_text!("file.read_error", e), &e)),
             }
         }
     }
@@ -301,16 +966,17 @@ pub mod builtins {
                 .ok_or_else(|| ToolError::InvalidParameters("content is required".to_string()))?;
             let append = params["append"].as_bool().unwrap_or(false);
             
-            let path = PathBuf::from(path);
+            let path = filesystem::resolve_and_validate_path(path)?;
             
             // Create parent directories if needed
             if let Some(parent) = path.parent() {
                 if !parent.exists() {
                     tokio::fs::create_dir_all(parent).await
-                        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur création dossier: {}", e)))?;
+                        .map_err(|e| ToolError::io(parent, tool_text!("dir.create_error", e), &e))?;
                 }
             }
             
+            let backup = backup::PendingBackup::capture(&path).await;
             let result = if append {
                 use tokio::io::AsyncWriteExt;
                 let mut file = tokio::fs::OpenOptions::new()
@@ -318,7 +984,7 @@ pub mod builtins {
                     .append(true)
                     .open(&path)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Erreur ouverture fichier: {}", e)))?;
+                    .map_err(|e| ToolError::io(&path, tool_text!("file.open_error", e), &e))?;
                 file.write_all(content.as_bytes()).await
             } else {
                 tokio::fs::write(&path, content).await
@@ -326,9 +992,10 @@ pub mod builtins {
             
             match result {
                 Ok(_) => {
+                    let backup_warning = backup.commit(self.name());
                     let bytes = content.len();
                     let lines = content.lines().count();
-                    Ok(ToolResult {
+                    Ok(backup::with_backup_warning(ToolResult {
                         success: true,
                         data: serde_json::json!({
                             "path": path.display().to_string(),
@@ -336,11 +1003,10 @@ pub mod builtins {
                             "lines": lines,
                             "mode": if append { "append" } else { "write" }
                         }),
-                        message: format!("Fichier écrit: {} ({} octets, {} lignes)",
-                            path.display(), bytes, lines),
-                    })
+                        message: tool_text!("file_write.done", path.display(), bytes, lines),
+                    }, backup_warning))
                 }
-                Err(e) => Err(ToolError::ExecutionFailed(format!("Erreur écriture: {}", e))),
+                Err(e) => Err(ToolError::io(&path, tool_text!("file.write_error", e), &e)),
             }
         }
     }
@@ -375,6 +1041,17 @@ pub mod builtins {
                         "type": "integer",
                         "description": "Maximum depth for recursive listing",
                         "default": 3
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "description": "Recursive listing: skip this many entries (paging, see `continuation`)",
+                        "default": 0
+                    },
+                    "output_limit": output_limit_schema(),
+                    "respect_gitignore": {
+                        "type": "boolean",
+                        "description": "Skip paths ignored by .gitignore/.ignore files (default: true)",
+                        "default": true
                     }
                 },
                 "required": ["path"]
@@ -386,11 +1063,14 @@ pub mod builtins {
                 .ok_or_else(|| ToolError::InvalidParameters("path is required".to_string()))?;
             let recursive = params["recursive"].as_bool().unwrap_or(false);
             let max_depth = params["max_depth"].as_u64().unwrap_or(3) as usize;
+            let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
             
-            let path = PathBuf::from(path);
+            let path = filesystem::resolve_and_validate_path(path)?;
             
             if recursive {
-                list_recursive(&path, 0, max_depth).await
+                let ignore = gitignore::IgnoreRules::for_root(&path, respect_gitignore);
+                let page = ListingPage::new(params["offset"].as_u64().unwrap_or(0) as usize, OutputBudget::from_params(&params));
+                list_recursive(&path, 0, max_depth, ignore, page).await
             } else {
                 list_directory(&path).await
             }
@@ -430,32 +1110,61 @@ pub mod builtins {
                 Ok(ToolResult {
                     success: true,
                     data: serde_json::json!({ "files": files }),
-                    message: format!("{} éléments dans {}", files.len(), path.display()),
+                    message: tool_text!("file_list.done", files.len(), path.display()),
                 })
             }
-            Err(e) => Err(ToolError::ExecutionFailed(format!("Erreur lecture dossier: {}", e))),
+            Err(e) => Err(ToolError::io(path, tool_text!("dir.read_error", e), &e)),
         }
     }
     
-    async fn list_recursive(path: &PathBuf, depth: usize, max_depth: usize) -> Result<ToolResult, ToolError> {
-        let all_files = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new()));
-        collect_files_recursive(path.clone(), all_files.clone(), depth, max_depth).await?;
+    /// One page of a recursive listing, filled as the walk goes
+    struct ListingPage {
+        /// Entries before the page, skipped
+        offset: usize,
+        seen: usize,
+        files: Vec<Value>,
+        budget: OutputBudget,
+        /// The budget ran out: the walk stops
+        full: bool,
+    }
+
+    impl ListingPage {
+        fn new(offset: usize, budget: OutputBudget) -> Self {
+            Self { offset, seen: 0, files: Vec::new(), budget, full: false }
+        }
+    }
+
+    async fn list_recursive(
+        path: &PathBuf,
+        depth: usize,
+        max_depth: usize,
+        ignore: gitignore::IgnoreRules,
+        page: ListingPage,
+    ) -> Result<ToolResult, ToolError> {
+        let page = std::sync::Arc::new(tokio::sync::Mutex::new(page));
+        collect_files_recursive(path.clone(), page.clone(), depth, max_depth, ignore).await?;
         
-        let files = all_files.lock().await;
-        let count = files.len();
+        let page = page.lock().await;
+        let count = page.files.len();
         
+        let mut data = serde_json::json!({ "files": page.files.clone() });
+        if page.full {
+            mark_truncated(&mut data, serde_json::json!({ "offset": page.offset + count }));
+        }
         Ok(ToolResult {
             success: true,
-            data: serde_json::json!({ "files": files.clone() }),
-            message: format!("{} fichiers trouvés récursivement", count),
+            data,
+            message: tool_text!("file_list.recursive", count,
+                if page.full { tool_text!("file_list.truncated") } else { "" }),
         })
     }
     
     fn collect_files_recursive(
         path: PathBuf,
-        files: std::sync::Arc<tokio::sync::Mutex<Vec<Value>>>,
+        files: std::sync::Arc<tokio::sync::Mutex<ListingPage>>,
         depth: usize,
         max_depth: usize,
+        ignore: gitignore::IgnoreRules,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), ToolError>> + Send>> {
         Box::pin(async move {
             if depth > max_depth {
@@ -463,7 +1172,7 @@ pub mod builtins {
             }
             
             let mut entries = tokio::fs::read_dir(&path).await
-                .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+                .map_err(|e| ToolError::io(&path, e.to_string(), &e))?;
             
             while let Some(entry) = entries.next_entry().await
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?
@@ -477,21 +1186,34 @@ pub mod builtins {
                 if file_name.starts_with('.') || 
                    file_name == "node_modules" || 
                    file_name == "target" ||
-                   file_name == "__pycache__" {
+                   file_name == "__pycache__" ||
+                   ignore.is_ignored(&entry_path, is_dir) {
                     continue;
                 }
                 
                 {
-                    let mut files_guard = files.lock().await;
-                    files_guard.push(serde_json::json!({
-                        "path": name,
-                        "is_directory": is_dir,
-                        "depth": depth,
-                    }));
+                    let mut page = files.lock().await;
+                    if page.full {
+                        return Ok(());
+                    }
+                    page.seen += 1;
+                    if page.seen > page.offset {
+                        let entry = serde_json::json!({
+                            "path": name,
+                            "is_directory": is_dir,
+                            "depth": depth,
+                        });
+                        if !page.budget.take(entry.to_string().len()) {
+                            page.full = true;
+                            return Ok(());
+                        }
+                        page.files.push(entry);
+                    }
                 }
                 
                 if is_dir {
-                    collect_files_recursive(entry_path, files.clone(), depth + 1, max_depth).await?;
+                    let child_ignore = ignore.child(&entry_path);
+                    collect_files_recursive(entry_path, files.clone(), depth + 1, max_depth, child_ignore).await?;
                 }
             }
             
@@ -538,6 +1260,17 @@ pub mod builtins {
                         "type": "integer",
                         "description": "Maximum number of results",
                         "default": 50
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "description": "Skip this many matches (paging, see `continuation`)",
+                        "default": 0
+                    },
+                    "output_limit": output_limit_schema(),
+                    "respect_gitignore": {
+                        "type": "boolean",
+                        "description": "Skip paths ignored by .gitignore/.ignore files (default: true)",
+                        "default": true
                     }
                 },
                 "required": ["pattern", "path"]
@@ -552,6 +1285,11 @@ pub mod builtins {
             let case_insensitive = params["case_insensitive"].as_bool().unwrap_or(false);
             let context_lines = params["context_lines"].as_u64().unwrap_or(2) as usize;
             let max_results = params["max_results"].as_u64().unwrap_or(50) as usize;
+            let offset = params["offset"].as_u64().unwrap_or(0) as usize;
+            let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+            let mut budget = OutputBudget::from_params(&params);
+            // Matches kept while searching: the skipped ones plus a page
+            let limit = offset + max_results;
             
             let regex_pattern = if case_insensitive {
                 format!("(?i){}", pattern)
@@ -562,54 +1300,74 @@ pub mod builtins {
             let regex = Regex::new(&regex_pattern)
                 .map_err(|e| ToolError::InvalidParameters(format!("Invalid regex: {}", e)))?;
             
-            let path = PathBuf::from(path);
+            let path = filesystem::resolve_and_validate_path(path)?;
             
-            if path.is_file() {
+            let (results, total) = if path.is_file() {
                 let mut results = Vec::new();
                 let mut total_matches = 0;
-                search_file(&path, &regex, context_lines, &mut results, &mut total_matches, max_results).await?;
-                
-                let truncated = total_matches > max_results;
-                
-                Ok(ToolResult {
-                    success: true,
-                    data: serde_json::json!({
-                        "matches": results,
-                        "total_matches": total_matches,
-                        "truncated": truncated
-                    }),
-                    message: format!("{} correspondance(s) trouvée(s){}", 
-                        total_matches,
-                        if truncated { " (résultats tronqués)" } else { "" }),
-                })
+                search_file(&path, &regex, context_lines, &mut results, &mut total_matches, limit).await?;
+                (results, total_matches)
             } else if path.is_dir() {
                 let results = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new()));
                 let total_matches = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
                 let regex = std::sync::Arc::new(regex);
                 
-                search_directory(path, regex, context_lines, results.clone(), total_matches.clone(), max_results).await?;
-                
-                let results_vec = results.lock().await;
-                let total = total_matches.load(std::sync::atomic::Ordering::Relaxed);
-                let truncated = total > max_results;
+                let ignore = gitignore::IgnoreRules::for_root(&path, respect_gitignore);
+                search_directory(path, regex, context_lines, results.clone(), total_matches.clone(), limit, ignore).await?;
                 
-                Ok(ToolResult {
-                    success: true,
-                    data: serde_json::json!({
-                        "matches": results_vec.clone(),
-                        "total_matches": total,
-                        "truncated": truncated
-                    }),
-                    message: format!("{} correspondance(s) trouvée(s){}", 
-                        total,
-                        if truncated { " (résultats tronqués)" } else { "" }),
-                })
+                let results_vec = std::mem::take(&mut *results.lock().await);
+                (results_vec, total_matches.load(std::sync::atomic::Ordering::Relaxed))
             } else {
-                Err(ToolError::InvalidParameters("Path does not exist".to_string()))
+                return Err(ToolError::NotFound {
+                    path: path.display().to_string(),
+                    message: "Path does not exist".to_string(),
+                });
+            };
+
+            let (page, next_offset) = page_results(results, offset, max_results, total, &mut budget);
+            let mut data = serde_json::json!({
+                "matches": page,
+                "total_matches": total,
+                "truncated": false
+            });
+            if let Some(next) = next_offset {
+                mark_truncated(&mut data, serde_json::json!({ "offset": next }));
             }
+            Ok(ToolResult {
+                success: true,
+                data,
+                message: tool_text!("grep.done", total,
+                    if next_offset.is_some() { tool_text!("grep.truncated") } else { "" }),
+            })
         }
     }
-    
+
+    /// Bytes kept of each matching or context line
+    const MAX_MATCH_LINE_CHARS: usize = 300;
+
+    /// One match of grep, with its context lines
+    fn grep_match(path: &Path, lines: &[&str], i: usize, context_lines: usize) -> Value {
+        let start = i.saturating_sub(context_lines);
+        let end = (i + context_lines + 1).min(lines.len());
+        
+        let context: Vec<String> = lines[start..end]
+            .iter()
+            .enumerate()
+            .map(|(j, l)| {
+                let line_num = start + j + 1;
+                let marker = if start + j == i { ">" } else { " " };
+                format!("{}{:>4}| {}", marker, line_num, crate::truncate_str(l, MAX_MATCH_LINE_CHARS))
+            })
+            .collect();
+        
+        serde_json::json!({
+            "file": path.display().to_string(),
+            "line": i + 1,
+            "content": crate::truncate_str(lines[i], MAX_MATCH_LINE_CHARS),
+            "context": context.join("\n")
+        })
+    }
+
     async fn search_file(
         path: &PathBuf,
         regex: &Regex,
@@ -630,25 +1388,7 @@ pub mod builtins {
                 *total_matches += 1;
                 
                 if results.len() < max_results {
-                    let start = i.saturating_sub(context_lines);
-                    let end = (i + context_lines + 1).min(lines.len());
-                    
-                    let context: Vec<String> = lines[start..end]
-                        .iter()
-                        .enumerate()
-                        .map(|(j, l)| {
-                            let line_num = start + j + 1;
-                            let marker = if start + j == i { ">" } else { " " };
-                            format!("{}{:>4}| {}", marker, line_num, l)
-                        })
-                        .collect();
-                    
-                    results.push(serde_json::json!({
-                        "file": path.display().to_string(),
-                        "line": i + 1,
-                        "content": line,
-                        "context": context.join("\n")
-                    }));
+                    results.push(grep_match(path, &lines, i, context_lines));
                 }
             }
         }
@@ -677,25 +1417,7 @@ pub mod builtins {
                 
                 let mut results_guard = results.lock().await;
                 if results_guard.len() < max_results {
-                    let start = i.saturating_sub(context_lines);
-                    let end = (i + context_lines + 1).min(lines.len());
-                    
-                    let context: Vec<String> = lines[start..end]
-                        .iter()
-                        .enumerate()
-                        .map(|(j, l)| {
-                            let line_num = start + j + 1;
-                            let marker = if start + j == i { ">" } else { " " };
-                            format!("{}{:>4}| {}", marker, line_num, l)
-                        })
-                        .collect();
-                    
-                    results_guard.push(serde_json::json!({
-                        "file": path.display().to_string(),
-                        "line": i + 1,
-                        "content": line,
-                        "context": context.join("\n")
-                    }));
+                    results_guard.push(grep_match(path, &lines, i, context_lines));
                 }
             }
         }
@@ -710,6 +1432,7 @@ pub mod builtins {
         results: std::sync::Arc<tokio::sync::Mutex<Vec<Value>>>,
         total_matches: std::sync::Arc<std::sync::atomic::AtomicUsize>,
         max_results: usize,
+        ignore: gitignore::IgnoreRules,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), ToolError>> + Send>> {
         Box::pin(async move {
             let mut entries = match tokio::fs::read_dir(&path).await {
@@ -733,7 +1456,8 @@ pub mod builtins {
                    name == "node_modules" || 
                    name == "target" ||
                    name == "__pycache__" ||
-                   name.ends_with(".lock") {
+                   name.ends_with(".lock") ||
+                   ignore.is_ignored(&entry_path, entry_path.is_dir()) {
                     continue;
                 }
                 
@@ -750,7 +1474,8 @@ pub mod builtins {
                         search_file_async(&entry_path, &regex, context_lines, &results, &total_matches, max_results).await?;
                     }
                 } else if entry_path.is_dir() {
-                    search_directory(entry_path, regex.clone(), context_lines, results.clone(), total_matches.clone(), max_results).await?;
+                    let child_ignore = ignore.child(&entry_path);
+                    search_directory(entry_path, regex.clone(), context_lines, results.clone(), total_matches.clone(), max_results, child_ignore).await?;
                 }
             }
             
@@ -787,6 +1512,11 @@ pub mod builtins {
                         "type": "integer",
                         "description": "Maximum number of results",
                         "default": 100
+                    },
+                    "respect_gitignore": {
+                        "type": "boolean",
+                        "description": "Skip paths ignored by .gitignore/.ignore files (default: true)",
+                        "default": true
                     }
                 },
                 "required": ["pattern"]
@@ -796,20 +1526,25 @@ pub mod builtins {
         async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
             let pattern = params["pattern"].as_str()
                 .ok_or_else(|| ToolError::InvalidParameters("pattern is required".to_string()))?;
-            let base_path = params["base_path"].as_str().unwrap_or(".");
+            let base_path = filesystem::resolve_and_validate_path(params["base_path"].as_str().unwrap_or("."))?;
             let max_results = params["max_results"].as_u64().unwrap_or(100) as usize;
+            let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+            let mut ignore = gitignore::IgnoreCache::new(gitignore::IgnoreRules::for_root(&base_path, respect_gitignore));
             
             let full_pattern = if pattern.starts_with('/') || pattern.starts_with("C:") {
                 pattern.to_string()
             } else {
-                format!("{}/{}", base_path, pattern)
+                format!("{}/{}", base_path.display(), pattern)
             };
             
             let mut files = Vec::new();
             
             match glob_match(&full_pattern) {
                 Ok(paths) => {
-                    for entry in paths.take(max_results) {
+                    // `..` or an absolute pattern may reach out of the workspace
+                    let inside = |path: &PathBuf| path.to_str().is_some_and(|p| filesystem::resolve_and_validate_path(p).is_ok());
+                    let mut kept = |path: &PathBuf| inside(path) && !ignore.is_ignored(path, path.is_dir());
+                    for entry in paths.filter(|entry| match entry { Ok(path) => kept(path), Err(_) => true }).take(max_results) {
                         match entry {
                             Ok(path) => {
                                 let is_dir = path.is_dir();
@@ -830,7 +1565,7 @@ pub mod builtins {
             Ok(ToolResult {
                 success: true,
                 data: serde_json::json!({ "files": files }),
-                message: format!("{} fichier(s) trouvé(s) pour '{}'", files.len(), pattern),
+                message: tool_text!("glob.done", files.len(), pattern),
             })
         }
     }
@@ -873,7 +1608,7 @@ pub mod builtins {
                     "thought": thought,
                     "recorded": true
                 }),
-                message: "Réflexion enregistrée. Continue ton raisonnement.".to_string(),
+                message: tool_text!("think.done").to_string(),
             })
         }
     }
@@ -972,14 +1707,130 @@ pub mod builtins {
                         "completed": completed
                     }
                 }),
-                message: format!(
-                    "Plan mis à jour: {} tâches ({} en attente, {} en cours, {} terminées)",
-                    valid_todos.len(), pending, in_progress, completed
-                ),
+                message: tool_text!("todo.done", valid_todos.len(), pending, in_progress, completed),
             })
         }
     }
     
+    /// Seconds without output after which a command whose stdin is still
+    /// open is taken to be waiting for input
+    const PROMPT_IDLE_SECS: u64 = 10;
+
+    /// How a command run by `run_with_stdin` ended
+    pub(super) enum CommandRun {
+        Finished {
+            status: std::process::ExitStatus,
+            stdout: Vec<u8>,
+            stderr: Vec<u8>,
+        },
+        /// Killed after `prompt_idle` without output; what it printed so far
+        WaitingForInput { output: String },
+    }
+
+    /// Run `cmd` with `stdin` written to it and then closed
+    ///
+    /// Without `stdin` and with `prompt_idle` set, stdin is left open and a
+    /// process that stays silent that long is killed: it is most likely
+    /// blocked on a prompt, and would otherwise eat the whole timeout.
+    pub(super) async fn run_with_stdin(
+        mut cmd: Command,
+        stdin: Option<&str>,
+        prompt_idle: Option<Duration>,
+    ) -> std::io::Result<CommandRun> {
+        use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+        use tokio::sync::Notify;
+
+        let keep_stdin_open = stdin.is_none() && prompt_idle.is_some();
+        cmd.stdin(if stdin.is_some() || keep_stdin_open {
+            std::process::Stdio::piped()
+        } else {
+            std::process::Stdio::null()
+        });
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.kill_on_drop(true);
+        let mut child = cmd.spawn()?;
+
+        // Held until the process exits, so it sees an open stdin
+        let mut open_stdin = child.stdin.take();
+        if let (Some(input), Some(mut pipe)) = (stdin, open_stdin.take()) {
+            // Written from a task: a child that does not read would otherwise
+            // block us while its own output fills up
+            let input = input.to_string();
+            tokio::spawn(async move {
+                let _ = pipe.write_all(input.as_bytes()).await;
+            });
+        }
+
+        let output = Arc::new(std::sync::Mutex::new((Vec::new(), Vec::new())));
+        let activity = Arc::new(Notify::new());
+        let read_into = |mut pipe: Box<dyn AsyncRead + Unpin + Send>, stderr: bool| {
+            let output = output.clone();
+            let activity = activity.clone();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 8192];
+                while let Ok(n) = pipe.read(&mut buf).await {
+                    if n == 0 {
+                        break;
+                    }
+                    let mut output = output.lock().unwrap_or_else(|e| e.into_inner());
+                    let target = if stderr { &mut output.1 } else { &mut output.0 };
+                    target.extend_from_slice(&buf[..n]);
+                    activity.notify_one();
+                }
+            })
+        };
+        let mut readers = Vec::new();
+        if let Some(pipe) = child.stdout.take() {
+            readers.push(read_into(Box::new(pipe), false));
+        }
+        if let Some(pipe) = child.stderr.take() {
+            readers.push(read_into(Box::new(pipe), true));
+        }
+
+        enum Event {
+            Exited(std::io::Result<std::process::ExitStatus>),
+            Output,
+            Idle,
+        }
+        let status = loop {
+            let idle = async {
+                match prompt_idle.filter(|_| open_stdin.is_some()) {
+                    Some(limit) => tokio::time::sleep(limit).await,
+                    None => std::future::pending::<()>().await,
+                }
+            };
+            let event = tokio::select! {
+                status = child.wait() => Event::Exited(status),
+                _ = activity.notified() => Event::Output,
+                _ = idle => Event::Idle,
+            };
+            match event {
+                Event::Exited(status) => break status?,
+                Event::Output => continue,
+                Event::Idle => {
+                    let _ = child.kill().await;
+                    let output = output.lock().unwrap_or_else(|e| e.into_inner());
+                    let printed = [output.0.as_slice(), output.1.as_slice()].concat();
+                    return Ok(CommandRun::WaitingForInput {
+                        output: String::from_utf8_lossy(&printed).to_string(),
+                    });
+                }
+            }
+        };
+        drop(open_stdin);
+        for reader in readers {
+            let _ = reader.await;
+        }
+
+        let mut output = output.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(CommandRun::Finished {
+            status,
+            stdout: std::mem::take(&mut output.0),
+            stderr: std::mem::take(&mut output.1),
+        })
+    }
+
     /// Command execution tool
     pub struct CommandTool;
     
@@ -990,7 +1841,7 @@ pub mod builtins {
         }
         
         fn description(&self) -> &str {
-            "Execute a shell command (requires approval). Only safe read-only commands allowed."
+            "Execute a shell command. Allow-listed commands run directly, others need the user's approval or are refused."
         }
         
         fn parameters_schema(&self) -> Value {
@@ -1009,6 +1860,20 @@ pub mod builtins {
                         "type": "integer",
                         "description": "Timeout in seconds",
                         "default": 30
+                    },
+                    "stdin": {
+                        "type": "string",
+                        "description": "Input written to the command's stdin, which is then closed (optional)"
+                    },
+                    "detect_prompt": {
+                        "type": "boolean",
+                        "description": "Without stdin, stop a command that prints nothing for prompt_idle_secs: it is likely waiting for input (default: true)",
+                        "default": true
+                    },
+                    "prompt_idle_secs": {
+                        "type": "integer",
+                        "description": "Silence before a command is taken to wait for input (default: 10)",
+                        "default": 10
                     }
                 },
                 "required": ["command"]
@@ -1016,29 +1881,32 @@ pub mod builtins {
         }
         
         async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+            self.execute_cancellable(params, CancellationToken::new()).await
+        }
+
+        async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
             let command_str = params["command"].as_str()
                 .ok_or_else(|| ToolError::InvalidParameters("command is required".to_string()))?;
             let working_dir = params["working_dir"].as_str();
             let timeout_secs = params["timeout_secs"].as_u64().unwrap_or(30);
+            let stdin = params["stdin"].as_str();
+            let idle_secs = params["prompt_idle_secs"].as_u64().unwrap_or(PROMPT_IDLE_SECS).max(1);
+            let prompt_idle = params["detect_prompt"]
+                .as_bool()
+                .unwrap_or(true)
+                .then(|| Duration::from_secs(idle_secs));
             
-            // SECURITY: Only allow safe read-only commands
-            let allowed_commands = [
-                "ls", "dir", "cat", "type", "echo", "pwd", "cd", 
-                "whoami", "date", "wc", "head", "tail", "find", 
-                "grep", "rg", "tree", "which", "where", "env", "mkdir"
-            ];
-            
-            let cmd_parts: Vec<&str> = command_str.split_whitespace().collect();
-            if cmd_parts.is_empty() {
+            if command_str.trim().is_empty() {
                 return Err(ToolError::InvalidParameters("Empty command".to_string()));
             }
             
-            let base_cmd = cmd_parts[0].split('/').last().unwrap_or(cmd_parts[0]);
-            if !allowed_commands.contains(&base_cmd) {
-                return Err(ToolError::PermissionDenied(
-                    format!("Commande '{}' non autorisée. Commandes permises: {:?}", 
-                        base_cmd, allowed_commands)
-                ));
+            // SECURITY: the command policy decides; commands it leaves to the
+            // user were approved before the call (see `get_call_permission`)
+            if let CommandVerdict::Deny(reason) = command_policy::check_command(command_str) {
+                return Err(ToolError::PermissionDenied {
+                    rule: Some("command_policy".to_string()),
+                    message: tool_text!("command.denied", reason),
+                });
             }
             
             // Build command
@@ -1052,34 +1920,382 @@ pub mod builtins {
                 cmd.current_dir(dir);
             }
             
-            // Execute with timeout
-            let result = timeout(
-                Duration::from_secs(timeout_secs),
-                cmd.output()
-            ).await;
+            // Execute with timeout; a cancelled or timed-out command is killed
+            // when its future is dropped
+            let result = tokio::select! {
+                result = timeout(Duration::from_secs(timeout_secs), run_with_stdin(cmd, stdin, prompt_idle)) => result,
+                _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+            };
             
             match result {
-                Ok(Ok(output)) => {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    let stderr = String::from_utf8_lossy(&output.stderr);
+                Ok(Ok(CommandRun::Finished { status, stdout, stderr })) => {
+                    let stdout = String::from_utf8_lossy(&stdout);
+                    let stderr = String::from_utf8_lossy(&stderr);
                     
                     Ok(ToolResult {
-                        success: output.status.success(),
+                        success: status.success(),
                         data: serde_json::json!({
                             "stdout": stdout,
                             "stderr": stderr,
-                            "exit_code": output.status.code(),
+                            "exit_code": status.code(),
                         }),
-                        message: if output.status.success() {
-                            "Commande exécutée".to_string()
+                        message: if status.success() {
+                            tool_text!("command.done").to_string()
                         } else {
-                            format!("Commande échouée (code: {:?})", output.status.code())
+                            tool_text!("command.failed", format!("{:?}", status.code()))
                         },
                     })
                 }
-                Ok(Err(e)) => Err(ToolError::ExecutionFailed(format!("Erreur exécution: {}", e))),
-                Err(_) => Err(ToolError::Timeout),
+                Ok(Ok(CommandRun::WaitingForInput { output })) => {
+                    let tail_start = output.char_indices().rev().nth(199).map_or(0, |(i, _)| i);
+                    Err(ToolError::ExecutionFailed(format!(
+                        "process appears to be waiting for input: no output for {} s, stopped. Last output: {:?}. Retry with the `stdin` parameter holding the answers.",
+                        idle_secs,
+                        &output[tail_start..]
+                    )))
+                }
+                Ok(Err(e)) => Err(ToolError::ExecutionFailed(tool_text!("command.error", e))),
+                Err(_) => Err(ToolError::Timeout { after_secs: Some(timeout_secs) }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_registry_generation_notifications() {
+        let registry = ToolRegistry::new();
+        let mut rx = registry.subscribe();
+        assert_eq!(registry.generation(), 0);
+
+        registry.register(Arc::new(builtins::ThinkTool)).await;
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(*rx.borrow_and_update(), 1);
+
+        // Replacing returns the previous tool and notifies
+        let previous = registry.replace(Arc::new(builtins::ThinkTool));
+        assert!(previous.is_some());
+        assert_eq!(*rx.borrow_and_update(), 2);
+
+        // Unregistering a missing tool is a no-op without notification
+        assert!(!registry.unregister("missing"));
+        assert!(!rx.has_changed().unwrap());
+
+        assert!(registry.unregister("think"));
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(registry.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_registry_subset() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(builtins::ThinkTool)).await;
+        registry.register(Arc::new(builtins::FileReadTool)).await;
+
+        let subset = registry.subset(|name| name == "file_read");
+        assert_eq!(subset.count(), 1);
+        assert!(subset.get("file_read").is_some());
+        assert!(subset.get("think").is_none());
+        // The original registry is untouched
+        assert_eq!(registry.count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_rejects_calls_over_the_rate() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(builtins::ThinkTool)).await;
+        assert_eq!(registry.limit("think"), ToolLimit::default());
+        assert_eq!(registry.limit("web_fetch").max_calls_per_minute, Some(20));
+        assert_eq!(registry.limit("mcp_github_search").max_calls_per_minute, Some(20));
+        assert_eq!(registry.limit("mcp_add_server"), ToolLimit::default());
+
+        registry.set_limit("think", Some(2), Some(1));
+        let params = serde_json::json!({"thought": "hm"});
+        assert!(registry.execute("think", params.clone()).await.is_ok());
+        assert!(registry.execute("think", params.clone()).await.is_ok());
+        let err = registry.execute("think", params.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("rate limited, retry in 30s"), "{}", err);
+
+        // Subsets draw from the same bucket
+        let subset = registry.subset(|name| name == "think");
+        assert!(subset.execute("think", params).await.is_err());
+        assert!(matches!(registry.execute("nope", Value::Null).await, Err(ToolError::UnknownTool(_))));
+    }
+
+    #[tokio::test]
+    async fn test_tool_removed_mid_run_stays_usable() {
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register(Arc::new(builtins::ThinkTool)).await;
+
+        // A run that already resolved the tool keeps its Arc
+        let tool = registry.get("think").unwrap();
+        let remover = {
+            let registry = registry.clone();
+            tokio::spawn(async move { registry.unregister("think") })
+        };
+        assert!(remover.await.unwrap());
+
+        let result = tool.execute(serde_json::json!({"thought": "still here"})).await;
+        assert!(result.is_ok());
+        // Later lookups take the normal "not found" path
+        assert!(registry.get("think").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_read_switches_to_smart_view() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.txt");
+        std::fs::write(&small, "one\ntwo\nthree\n").unwrap();
+        let large = dir.path().join("large.log");
+        let body: String = (1..=50_000).map(|i| format!("line {} of a generated log file\n", i)).collect();
+        std::fs::write(&large, body).unwrap();
+
+        let tool = builtins::FileReadTool;
+        let result = tool.execute(serde_json::json!({"path": small})).await.unwrap();
+        assert!(result.data.get("mode").is_none());
+        assert!(result.data["content"].as_str().unwrap().contains("three"));
+
+        let result = tool.execute(serde_json::json!({"path": large, "pattern": "line 4242 "})).await.unwrap();
+        assert_eq!(result.data["mode"], "smart");
+        assert_eq!(result.data["total_lines"], 50_000);
+        assert_eq!(result.data["match_count"], 1);
+        let content = result.data["content"].as_str().unwrap();
+        assert!(content.len() < 16 * 1024);
+        assert!(content.contains("line 4242 of"));
+
+        // Ranges of large files are streamed
+        let result = tool.execute(serde_json::json!({"path": large, "start_line": 100, "end_line": 101})).await.unwrap();
+        assert_eq!(result.data["content"].as_str().unwrap().lines().count(), 2);
+
+        // An invalid pattern is a parameter error
+        let err = tool.execute(serde_json::json!({"path": large, "pattern": "("})).await.unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+    }
+
+    #[tokio::test]
+    async fn test_search_tools_respect_gitignore() {
+        use crate::agent::tools::filesystem::FileSearchContentTool;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["src", "dist", "venv/lib"] {
+            std::fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "dist/\nvenv\n*.gen.rs\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "// NEEDLE kept\n").unwrap();
+        std::fs::write(root.join("src/schema.gen.rs"), "// NEEDLE generated\n").unwrap();
+        std::fs::write(root.join("dist/bundle.js"), "// NEEDLE bundled\n").unwrap();
+        std::fs::write(root.join("venv/lib/site.py"), "# NEEDLE vendored\n").unwrap();
+
+        let all_output = |result: &ToolResult| result.data.to_string();
+        let ignored = ["schema.gen.rs", "dist", "bundle.js", "venv", "site.py"];
+        let calls: Vec<(Arc<dyn Tool>, Value)> = vec![
+            (Arc::new(builtins::GrepTool), serde_json::json!({"pattern": "NEEDLE", "path": root})),
+            (Arc::new(builtins::GlobTool), serde_json::json!({"pattern": "**/*", "base_path": root})),
+            (Arc::new(builtins::FileListTool), serde_json::json!({"path": root, "recursive": true})),
+            (Arc::new(FileSearchContentTool), serde_json::json!({"query": "needle", "path": root})),
+        ];
+        for (tool, params) in calls {
+            let output = all_output(&tool.execute(params.clone()).await.unwrap());
+            assert!(output.contains("main.rs"), "{} lost main.rs: {}", tool.name(), output);
+            for name in ignored {
+                assert!(!output.contains(name), "{} returned ignored {}: {}", tool.name(), name, output);
             }
+
+            // Opting out brings them back
+            let mut params = params;
+            params["respect_gitignore"] = Value::Bool(false);
+            let output = all_output(&tool.execute(params).await.unwrap());
+            assert!(output.contains("bundle.js"), "{} with respect_gitignore=false: {}", tool.name(), output);
         }
     }
+
+    #[tokio::test]
+    async fn test_file_read_handles_binary_and_other_encodings() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("image.bin");
+        std::fs::write(&binary, (0..=255u8).cycle().take(100_000).collect::<Vec<_>>()).unwrap();
+        let latin1 = dir.path().join("notes.txt");
+        std::fs::write(&latin1, b"caf\xe9\nd\xe9j\xe0 vu\n").unwrap();
+
+        let tool = builtins::FileReadTool;
+        let result = tool.execute(serde_json::json!({"path": binary})).await.unwrap();
+        assert_eq!(result.data["binary"], true);
+        assert_eq!(result.data["bytes"], 1024);
+        assert!(result.data["content"].as_str().unwrap().starts_with("00000000  00 01 02"));
+
+        // Byte offsets in hex mode
+        let result = tool.execute(serde_json::json!({"path": binary, "mode": "hex", "start_line": 256, "end_line": 260})).await.unwrap();
+        assert_eq!(result.data["content"], "00000100  00 01 02 03                                      |....|");
+
+        let result = tool.execute(serde_json::json!({"path": latin1})).await.unwrap();
+        assert_eq!(result.data["encoding"], "latin-1");
+        assert!(result.data["content"].as_str().unwrap().contains("déjà vu"));
+    }
+
+    #[tokio::test]
+    async fn test_large_outputs_are_paged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.txt");
+        let content: String = (1..=200).map(|i| format!("line {} needle {}\n", i, "x".repeat(40))).collect();
+        std::fs::write(&path, content).unwrap();
+
+        // file_read: the continuation picks up right after the last line shown
+        let read = builtins::FileReadTool;
+        let params = serde_json::json!({"path": path, "output_limit": 1000});
+        let first = read.execute(params.clone()).await.unwrap();
+        assert_eq!(first.data["truncated"], true);
+        let next = first.data["continuation"]["start_line"].as_u64().unwrap();
+        assert!(next > 1 && next < 200, "next page at {}", next);
+        let mut params = params;
+        params["start_line"] = serde_json::json!(next);
+        let second = read.execute(params).await.unwrap();
+        assert!(second.data["content"].as_str().unwrap().contains(&format!("line {} needle", next)));
+
+        // grep: pages follow each other without gaps or repeats
+        let grep = builtins::GrepTool;
+        let mut params = serde_json::json!({"pattern": "needle", "path": path, "max_results": 500, "output_limit": 2000});
+        let mut lines = Vec::new();
+        loop {
+            let result = grep.execute(params.clone()).await.unwrap();
+            lines.extend(result.data["matches"].as_array().unwrap().iter().map(|m| m["line"].as_u64().unwrap()));
+            match result.data["continuation"]["offset"].as_u64() {
+                Some(offset) if result.data["truncated"] == true => params["offset"] = serde_json::json!(offset),
+                _ => break,
+            }
+        }
+        assert_eq!(lines, (1..=200).collect::<Vec<u64>>());
+    }
+
+    #[tokio::test]
+    async fn test_builtin_failures_map_to_categories() {
+        use crate::agent::tools::filesystem::{FileCopyTool, FileDeleteTool, FileEditTool, FileInfoTool, FileMoveTool};
+        use crate::agent::tools::web::{FetchCache, WebFetchTool, DEFAULT_CACHE_MAX_BYTES, DEFAULT_CACHE_TTL};
+
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.txt");
+        let elsewhere = dir.path().join("elsewhere.txt");
+        let cache = Arc::new(FetchCache::new(DEFAULT_CACHE_TTL, DEFAULT_CACHE_MAX_BYTES));
+
+        let cases: Vec<(Arc<dyn Tool>, Value, ErrorCategory)> = vec![
+            (Arc::new(builtins::FileReadTool), serde_json::json!({"path": missing}), ErrorCategory::NotFound),
+            (Arc::new(builtins::FileReadTool), serde_json::json!({}), ErrorCategory::InvalidParameters),
+            (Arc::new(builtins::FileListTool), serde_json::json!({"path": missing}), ErrorCategory::NotFound),
+            (Arc::new(builtins::FileListTool), serde_json::json!({"path": missing, "recursive": true}), ErrorCategory::NotFound),
+            (Arc::new(builtins::GrepTool), serde_json::json!({"pattern": "x", "path": missing}), ErrorCategory::NotFound),
+            (Arc::new(builtins::GrepTool), serde_json::json!({"pattern": "(", "path": dir.path()}), ErrorCategory::InvalidParameters),
+            (Arc::new(builtins::CommandTool), serde_json::json!({"command": "rm -rf /"}), ErrorCategory::PermissionDenied),
+            (Arc::new(FileEditTool), serde_json::json!({"path": missing, "old_string": "a", "new_string": "b"}), ErrorCategory::NotFound),
+            (Arc::new(FileDeleteTool), serde_json::json!({"path": missing}), ErrorCategory::NotFound),
+            (Arc::new(FileMoveTool), serde_json::json!({"source": missing, "destination": elsewhere}), ErrorCategory::NotFound),
+            (Arc::new(FileCopyTool), serde_json::json!({"source": missing, "destination": elsewhere}), ErrorCategory::NotFound),
+            (Arc::new(FileInfoTool), serde_json::json!({"path": missing}), ErrorCategory::NotFound),
+            // Nothing listens on the discard port
+            (Arc::new(WebFetchTool::new(cache)), serde_json::json!({"url": "http://127.0.0.1:9/"}), ErrorCategory::Network),
+        ];
+
+        for (tool, params, expected) in cases {
+            let err = tool.execute(params.clone()).await.unwrap_err();
+            assert_eq!(err.category(), expected, "{} {}: {}", tool.name(), params, err);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shell_timeouts_carry_the_limit() {
+        let err = shell::BashTool
+            .execute(serde_json::json!({"command": "sleep 5", "timeout_secs": 1}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.category(), ErrorCategory::Timeout);
+        assert!(err.render_for_model("bash").starts_with("ERROR category=timeout after=1s tool=bash\n"));
+        // Logs keep the old text
+        assert_eq!(err.to_string(), "Timeout");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancelled_command_stops_promptly() {
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            trigger.cancel();
+        });
+
+        let start = std::time::Instant::now();
+        let err = shell::BashTool
+            .execute_cancellable(serde_json::json!({"command": "sleep 30"}), cancel)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Cancelled));
+        assert_eq!(err.to_string(), "cancelled by user");
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_stdin_and_prompt_detection() {
+        let echoed = builtins::CommandTool
+            .execute(serde_json::json!({"command": "cat", "stdin": "hello"}))
+            .await
+            .unwrap();
+        assert_eq!(echoed.data["stdout"], "hello");
+
+        let start = std::time::Instant::now();
+        let err = builtins::CommandTool
+            .execute(serde_json::json!({"command": "cat", "prompt_idle_secs": 1}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("process appears to be waiting for input"), "{}", err);
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+
+        let script = || {
+            let mut cmd = tokio::process::Command::new("sh");
+            cmd.args(["-c", "printf 'Name? '; read name; echo \"Hi $name\""]);
+            cmd
+        };
+        let idle = Some(std::time::Duration::from_secs(1));
+        match builtins::run_with_stdin(script(), None, idle).await.unwrap() {
+            builtins::CommandRun::WaitingForInput { output } => assert_eq!(output, "Name? "),
+            builtins::CommandRun::Finished { .. } => panic!("the prompt was not detected"),
+        }
+        match builtins::run_with_stdin(script(), Some("Ada\n"), idle).await.unwrap() {
+            builtins::CommandRun::Finished { status, stdout, .. } => {
+                assert!(status.success());
+                assert_eq!(String::from_utf8_lossy(&stdout), "Name? Hi Ada\n");
+            }
+            builtins::CommandRun::WaitingForInput { .. } => panic!("stdin was not delivered"),
+        }
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(ToolError::Timeout { after_secs: Some(30) }.is_retryable());
+        assert!(ToolError::Network { status: None, message: "reset".into() }.is_retryable());
+        assert!(ToolError::Network { status: Some(503), message: "busy".into() }.is_retryable());
+        assert!(ToolError::Network { status: Some(429), message: "slow down".into() }.is_retryable());
+        assert!(!ToolError::Network { status: Some(404), message: "gone".into() }.is_retryable());
+        assert!(ToolError::ExecutionFailed("Connection refused (os error 111)".into()).is_retryable());
+        assert!(!ToolError::ExecutionFailed("exit status 1".into()).is_retryable());
+        assert!(!ToolError::InvalidParameters("missing path".into()).is_retryable());
+        assert!(!ToolError::PermissionDenied { rule: None, message: "no".into() }.is_retryable());
+        assert!(!ToolError::Cancelled.is_retryable());
+    }
+
+    #[test]
+    fn test_render_for_model_states_category_first() {
+        let err = ToolError::NotFound { path: "src/lib.rs".into(), message: "Erreur lecture fichier: No such file".into() };
+        assert_eq!(
+            err.render_for_model("file_read"),
+            "ERROR category=not_found path=\"src/lib.rs\" tool=file_read\nExecution failed: Erreur lecture fichier: No such file"
+        );
+
+        let err = ToolError::Network { status: Some(404), message: "HTTP 404 Not Found pour https://example.com".into() };
+        assert!(err.render_for_model("web_download").starts_with("ERROR category=network status=404 tool=web_download\n"));
+        assert_eq!(ToolError::UnknownTool("nope".into()).render_for_model("nope").lines().next(), Some("ERROR category=unknown_tool tool=nope"));
+    }
 }
@@ -0,0 +1,457 @@
+//! File backups - a safety net for agent edits
+//!
+//! Before file_write, file_edit, file_multi_edit, file_patch_lines,
+//! file_create, file_delete and file_convert change a file, its previous
+//! content is captured; once the change succeeds it is kept under
+//! `{data_dir}/backups`, at most `MAX_VERSIONS` per file. `undo_file_change` puts the most recent
+//! version back and `list_file_backups` shows what can be restored.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+
+/// Versions kept per file, oldest dropped first
+pub const MAX_VERSIONS: usize = 20;
+
+/// Files above this size are not backed up
+pub const MAX_BACKUP_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Tools whose successful calls can be undone
+pub const BACKED_UP_TOOLS: &[&str] = &[
+    "file_write",
+    "file_edit",
+    "file_multi_edit",
+    "file_patch_lines",
+    "file_create",
+    "file_delete",
+    "file_convert",
+];
+
+const INDEX_FILE: &str = "index.json";
+
+/// Serializes index updates between concurrent tool calls
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// One saved version of a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    /// Name of the copy in the backups directory; `None` when the file did
+    /// not exist before the change, so undoing it deletes the file
+    pub file: Option<String>,
+    pub size: u64,
+    /// Tool whose call replaced this version
+    pub tool: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Versions by absolute file path, oldest first
+pub type BackupIndex = BTreeMap<String, Vec<BackupEntry>>;
+
+/// Directory of file copies plus their index
+pub struct BackupStore {
+    dir: PathBuf,
+}
+
+impl BackupStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Store in the app data directory
+    pub fn open_default() -> Result<Self, String> {
+        crate::storage::get_data_dir()
+            .map(|dir| Self::new(dir.join("backups")))
+            .map_err(|e| e.to_string())
+    }
+
+    fn load_index(&self) -> BackupIndex {
+        std::fs::read_to_string(self.dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_index(&self, index: &BackupIndex) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(index)?;
+        std::fs::write(self.dir.join(INDEX_FILE), json)
+    }
+
+    /// Save `prior` as the latest version of `key`, `None` meaning it did not exist
+    pub fn record(&self, key: &str, prior: Option<&[u8]>, tool: &str) -> std::io::Result<()> {
+        let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::fs::create_dir_all(&self.dir)?;
+
+        let created_at = Utc::now();
+        let file = match prior {
+            Some(content) => {
+                let mut stamp = created_at.timestamp_micros();
+                let mut name = format!("{}-{}.bak", path_hash(key), stamp);
+                while self.dir.join(&name).exists() {
+                    stamp += 1;
+                    name = format!("{}-{}.bak", path_hash(key), stamp);
+                }
+                std::fs::write(self.dir.join(&name), content)?;
+                Some(name)
+            }
+            None => None,
+        };
+
+        let mut index = self.load_index();
+        let versions = index.entry(key.to_string()).or_default();
+        versions.push(BackupEntry {
+            file,
+            size: prior.map_or(0, |content| content.len() as u64),
+            tool: tool.to_string(),
+            created_at,
+        });
+        while versions.len() > MAX_VERSIONS {
+            let dropped = versions.remove(0);
+            self.remove_copy(&dropped);
+        }
+        self.save_index(&index)
+    }
+
+    /// Saved versions of `key`, oldest first
+    pub fn versions(&self, key: &str) -> Vec<BackupEntry> {
+        self.load_index().remove(key).unwrap_or_default()
+    }
+
+    /// Every file with saved versions
+    pub fn all(&self) -> BackupIndex {
+        self.load_index()
+    }
+
+    /// Put the latest version of `key` back at `path`, consuming it
+    pub fn restore_latest(&self, key: &str, path: &Path) -> Result<BackupEntry, ToolError> {
+        let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut index = self.load_index();
+        let entry = index
+            .get_mut(key)
+            .and_then(|versions| versions.pop())
+            .ok_or_else(|| ToolError::NotFound {
+                path: key.to_string(),
+                message: tool_text!("backup.none_for", key),
+            })?;
+
+        match &entry.file {
+            Some(name) => {
+                let content = std::fs::read(self.dir.join(name))
+                    .map_err(|e| ToolError::ExecutionFailed(tool_text!("backup.unreadable", e)))?;
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| ToolError::io(parent, tool_text!("dir.create_error", e), &e))?;
+                }
+                std::fs::write(path, content)
+                    .map_err(|e| ToolError::io(path, tool_text!("backup.restore_error", e), &e))?;
+            }
+            // The change created the file
+            None if path.is_file() => {
+                std::fs::remove_file(path)
+                    .map_err(|e| ToolError::io(path, tool_text!("backup.remove_error", e), &e))?;
+            }
+            None => {}
+        }
+
+        self.remove_copy(&entry);
+        if index.get(key).is_some_and(|versions| versions.is_empty()) {
+            index.remove(key);
+        }
+        self.save_index(&index)
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("backup.index_error", e)))?;
+        Ok(entry)
+    }
+
+    fn remove_copy(&self, entry: &BackupEntry) {
+        if let Some(name) = &entry.file {
+            if let Err(e) = std::fs::remove_file(self.dir.join(name)) {
+                tracing::warn!("Could not remove backup {}: {}", name, e);
+            }
+        }
+    }
+}
+
+/// File name prefix of the copies of `key`
+fn path_hash(key: &str) -> String {
+    Sha256::digest(key.as_bytes())[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Index key of `path`: absolute, canonical as far as it exists
+///
+/// Missing components are appended as written, so a file and its later
+/// undo agree on the key whether or not it exists at the time.
+pub fn backup_key(path: &Path) -> String {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        let probe = if existing.as_os_str().is_empty() { Path::new(".") } else { existing };
+        if let Ok(canonical) = std::fs::canonicalize(probe) {
+            let full = missing.iter().rev().fold(canonical, |full, name| full.join(name));
+            return full.display().to_string();
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.display().to_string(),
+        }
+    }
+}
+
+/// What a file held before a tool changed it
+enum Prior {
+    Missing,
+    Content(Vec<u8>),
+    TooLarge(u64),
+    Unreadable(String),
+}
+
+/// Content of a file captured before a change, saved once the change succeeds
+pub struct PendingBackup {
+    key: String,
+    prior: Prior,
+}
+
+impl PendingBackup {
+    pub async fn capture(path: &Path) -> Self {
+        let key = backup_key(path);
+        let prior = match tokio::fs::metadata(path).await {
+            Err(_) => Prior::Missing,
+            Ok(meta) if meta.len() > MAX_BACKUP_BYTES => Prior::TooLarge(meta.len()),
+            Ok(_) => match tokio::fs::read(path).await {
+                Ok(content) => Prior::Content(content),
+                Err(e) => Prior::Unreadable(e.to_string()),
+            },
+        };
+        Self { key, prior }
+    }
+
+    /// Record the backup; the returned warning belongs in the tool result
+    pub fn commit(self, tool: &str) -> Option<String> {
+        let prior = match &self.prior {
+            Prior::Missing => None,
+            Prior::Content(content) => Some(content.as_slice()),
+            Prior::TooLarge(size) => {
+                return Some(tool_text!("backup.too_large", self.key, size));
+            }
+            Prior::Unreadable(e) => return Some(tool_text!("backup.read_error", e)),
+        };
+        let saved = BackupStore::open_default()
+            .and_then(|store| store.record(&self.key, prior, tool).map_err(|e| e.to_string()));
+        match saved {
+            Ok(()) => None,
+            Err(e) => {
+                tracing::warn!("Backup of {} failed: {}", self.key, e);
+                Some(tool_text!("backup.failed", e))
+            }
+        }
+    }
+}
+
+/// Add a backup warning to a successful tool result
+pub fn with_backup_warning(mut result: ToolResult, warning: Option<String>) -> ToolResult {
+    if let Some(warning) = warning {
+        result.message = format!("{}\n⚠ {}", result.message, warning);
+        result.data["backup_warning"] = Value::String(warning);
+    }
+    result
+}
+
+// ============================================================================
+// UndoFileChangeTool - Restore the most recent backup of a file
+// ============================================================================
+
+pub struct UndoFileChangeTool;
+
+#[async_trait]
+impl Tool for UndoFileChangeTool {
+    fn name(&self) -> &str {
+        "undo_file_change"
+    }
+
+    fn description(&self) -> &str {
+        "Undo the last file_write, file_edit, file_multi_edit, file_patch_lines, file_create or file_delete on a file by restoring its previous content (or deleting it if the change created it). Call again to go further back. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path of the file to restore"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let path_buf = resolve_and_validate_path(path)?;
+        let key = backup_key(&path_buf);
+
+        let store = BackupStore::open_default().map_err(ToolError::ExecutionFailed)?;
+        let entry = store.restore_latest(&key, &path_buf)?;
+        let remaining = store.versions(&key).len();
+        let when = entry.created_at.format("%Y-%m-%d %H:%M:%S");
+
+        let message = match entry.file {
+            Some(_) => tool_text!("undo_file_change.restored", path, entry.tool, when, remaining),
+            None => tool_text!("undo_file_change.deleted", path, entry.tool, when),
+        };
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path,
+                "restored": entry.file.is_some(),
+                "undone_tool": entry.tool,
+                "backed_up_at": entry.created_at.to_rfc3339(),
+                "remaining": remaining
+            }),
+            message,
+        })
+    }
+}
+
+// ============================================================================
+// ListFileBackupsTool - Show what undo_file_change can restore
+// ============================================================================
+
+pub struct ListFileBackupsTool;
+
+#[async_trait]
+impl Tool for ListFileBackupsTool {
+    fn name(&self) -> &str {
+        "list_file_backups"
+    }
+
+    fn description(&self) -> &str {
+        "List the saved versions of files changed by file tools, newest first, i.e. what undo_file_change can restore. Without a path, lists every backed-up file."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Only list the backups of this file"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let store = BackupStore::open_default().map_err(ToolError::ExecutionFailed)?;
+        let index = match params["path"].as_str() {
+            Some(path) => {
+                let key = backup_key(&resolve_and_validate_path(path)?);
+                let versions = store.versions(&key);
+                BackupIndex::from([(key, versions)])
+            }
+            None => store.all(),
+        };
+
+        let mut files = Vec::new();
+        let mut lines = Vec::new();
+        for (path, versions) in index.iter().filter(|(_, versions)| !versions.is_empty()) {
+            lines.push(tool_text!("list_file_backups.file", path, versions.len()));
+            let mut listed = Vec::new();
+            for entry in versions.iter().rev() {
+                let what = match entry.file {
+                    Some(_) => tool_text!("list_file_backups.size", entry.size),
+                    None => tool_text!("list_file_backups.absent").to_string(),
+                };
+                lines.push(tool_text!(
+                    "list_file_backups.version",
+                    entry.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    entry.tool,
+                    what
+                ));
+                listed.push(serde_json::json!({
+                    "backed_up_at": entry.created_at.to_rfc3339(),
+                    "tool": entry.tool,
+                    "existed": entry.file.is_some(),
+                    "size": entry.size
+                }));
+            }
+            files.push(serde_json::json!({ "path": path, "versions": listed }));
+        }
+
+        let message = if lines.is_empty() {
+            tool_text!("list_file_backups.none").to_string()
+        } else {
+            lines.join("\n")
+        };
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({ "files": files }),
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_walks_back_through_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(dir.path().join("backups"));
+        let file = dir.path().join("notes.txt");
+        let key = backup_key(&file);
+
+        // Created by a first write, then edited twice
+        store.record(&key, None, "file_write").unwrap();
+        store.record(&key, Some(b"v1"), "file_edit").unwrap();
+        store.record(&key, Some(b"v2"), "file_edit").unwrap();
+        std::fs::write(&file, "v3").unwrap();
+
+        store.restore_latest(&key, &file).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "v2");
+        store.restore_latest(&key, &file).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "v1");
+        let entry = store.restore_latest(&key, &file).unwrap();
+        assert!(entry.file.is_none());
+        assert!(!file.exists());
+
+        assert!(matches!(
+            store.restore_latest(&key, &file),
+            Err(ToolError::NotFound { .. })
+        ));
+        assert!(store.all().is_empty());
+    }
+
+    #[test]
+    fn test_versions_are_capped_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = dir.path().join("backups");
+        let store = BackupStore::new(&backups);
+
+        for i in 0..MAX_VERSIONS + 5 {
+            store.record("/tmp/a.txt", Some(i.to_string().as_bytes()), "file_write").unwrap();
+        }
+        store.record("/tmp/b.txt", Some(b"b"), "file_write").unwrap();
+
+        let versions = store.versions("/tmp/a.txt");
+        assert_eq!(versions.len(), MAX_VERSIONS);
+        let oldest = std::fs::read(backups.join(versions[0].file.as_ref().unwrap())).unwrap();
+        assert_eq!(oldest, b"5");
+        // Dropped versions take their copies with them
+        let copies = std::fs::read_dir(&backups).unwrap().count() - 1;
+        assert_eq!(copies, MAX_VERSIONS + 1);
+    }
+}
@@ -0,0 +1,556 @@
+//! Cargo tools - check and test with structured diagnostics
+//!
+//! Cargo runs with `--message-format=json`; compiler messages become a short
+//! list of errors and warnings (file, line, code, rendered text cut to a few
+//! lines) and the libtest output of `cargo test` becomes pass/fail counts with
+//! the output of the failing tests. Only the first diagnostics are returned,
+//! with the total, so a broken build does not flood the context.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+
+const DEFAULT_MAX_DIAGNOSTICS: u64 = 20;
+const MAX_DIAGNOSTICS: u64 = 200;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// Bytes of a diagnostic's rendered text, and of a failing test's output
+const MAX_RENDERED_BYTES: usize = 1500;
+const MAX_FAILURE_OUTPUT_BYTES: usize = 2000;
+
+/// Failing tests whose output is returned
+const MAX_FAILURES: usize = 10;
+
+/// End of cargo's stderr shown when the build fails without diagnostics
+const STDERR_TAIL_BYTES: usize = 2000;
+
+/// Compiler error or warning
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Diagnostic {
+    level: String,
+    code: Option<String>,
+    message: String,
+    file: Option<String>,
+    line: Option<u64>,
+    column: Option<u64>,
+    rendered: String,
+}
+
+impl Diagnostic {
+    /// From a `compiler-message` line; None for notes and the closing
+    /// "aborting due to..." / "N warnings emitted" summaries
+    fn from_message(message: &Value) -> Option<Self> {
+        let level = message["level"].as_str()?;
+        if !level.starts_with("error") && level != "warning" {
+            return None;
+        }
+        let text = message["message"].as_str().unwrap_or_default();
+        let spans = message["spans"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if spans.is_empty()
+            && (text.starts_with("aborting due to")
+                || text.ends_with("emitted")
+                || text.contains("could not compile"))
+        {
+            return None;
+        }
+        let span = spans
+            .iter()
+            .find(|s| s["is_primary"].as_bool() == Some(true))
+            .or(spans.first());
+        let rendered = message["rendered"].as_str().unwrap_or(text);
+        let shown = crate::truncate_str(rendered, MAX_RENDERED_BYTES);
+        Some(Self {
+            level: if level.starts_with("error") {
+                "error"
+            } else {
+                "warning"
+            }
+            .to_string(),
+            code: message["code"]["code"].as_str().map(str::to_string),
+            message: text.to_string(),
+            file: span
+                .and_then(|s| s["file_name"].as_str())
+                .map(str::to_string),
+            line: span.and_then(|s| s["line_start"].as_u64()),
+            column: span.and_then(|s| s["column_start"].as_u64()),
+            rendered: if shown.len() < rendered.len() {
+                format!("{}\n[...]", shown.trim_end())
+            } else {
+                rendered.trim_end().to_string()
+            },
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "level": self.level,
+            "code": self.code,
+            "message": self.message,
+            "file": self.file,
+            "line": self.line,
+            "column": self.column,
+            "rendered": self.rendered,
+        })
+    }
+}
+
+/// What cargo said on stdout: JSON messages, and libtest's text for tests
+#[derive(Debug, Default)]
+struct CargoOutput {
+    diagnostics: Vec<Diagnostic>,
+    /// From the `build-finished` message
+    build_success: Option<bool>,
+    passed: u64,
+    failed: u64,
+    ignored: u64,
+    /// Failing tests with their captured output, when libtest printed it
+    failures: Vec<(String, String)>,
+}
+
+impl CargoOutput {
+    fn parse(stdout: &str) -> Self {
+        let mut output = CargoOutput::default();
+        // The same diagnostic comes once per target built (lib, tests...)
+        let mut seen = HashSet::new();
+        let mut failure: Option<(String, String)> = None;
+
+        for line in stdout.lines() {
+            if line.starts_with('{') {
+                if let Ok(message) = serde_json::from_str::<Value>(line) {
+                    match message["reason"].as_str() {
+                        Some("compiler-message") => {
+                            if let Some(diagnostic) = Diagnostic::from_message(&message["message"])
+                            {
+                                let key = (
+                                    diagnostic.level.clone(),
+                                    diagnostic.file.clone(),
+                                    diagnostic.line,
+                                    diagnostic.message.clone(),
+                                );
+                                if seen.insert(key) {
+                                    output.diagnostics.push(diagnostic);
+                                }
+                            }
+                        }
+                        Some("build-finished") => {
+                            output.build_success = message["success"].as_bool()
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+            }
+
+            // libtest: `---- name stdout ----` opens a failure's output, which
+            // runs until the next one or the `failures:` list
+            if let Some(name) = line
+                .strip_prefix("---- ")
+                .and_then(|l| l.strip_suffix(" stdout ----"))
+            {
+                output.failures.extend(failure.take());
+                failure = Some((name.to_string(), String::new()));
+                continue;
+            }
+            if line == "failures:" || line.starts_with("test result:") {
+                output.failures.extend(failure.take());
+            }
+            if let Some((_, text)) = failure.as_mut() {
+                text.push_str(line);
+                text.push('\n');
+                continue;
+            }
+            if let Some(counts) = line.strip_prefix("test result: ") {
+                for part in counts.split(['.', ';']) {
+                    let mut words = part.split_whitespace();
+                    if let (Some(n), Some(kind)) = (
+                        words.next().and_then(|n| n.parse::<u64>().ok()),
+                        words.next(),
+                    ) {
+                        match kind {
+                            "passed" => output.passed += n,
+                            "failed" => output.failed += n,
+                            "ignored" => output.ignored += n,
+                            _ => {}
+                        }
+                    }
+                }
+            } else if let Some(name) = line
+                .strip_prefix("test ")
+                .and_then(|l| l.strip_suffix(" ... FAILED"))
+            {
+                if !output.failures.iter().any(|(n, _)| n == name) && failure.is_none() {
+                    output.failures.push((name.to_string(), String::new()));
+                }
+            }
+        }
+        output.failures.extend(failure);
+        // A name listed on its `... FAILED` line and again with its output
+        let mut failures: Vec<(String, String)> = Vec::new();
+        for (name, text) in output.failures.drain(..) {
+            match failures.iter_mut().find(|(n, _)| *n == name) {
+                Some(existing) if existing.1.is_empty() => existing.1 = text,
+                Some(_) => {}
+                None => failures.push((name, text)),
+            }
+        }
+        output.failures = failures;
+        output
+    }
+
+    fn count(&self, level: &str) -> usize {
+        self.diagnostics.iter().filter(|d| d.level == level).count()
+    }
+}
+
+/// `cargo <subcommand>` args shared by both tools
+fn cargo_args(subcommand: &str, params: &Value) -> Result<Vec<String>, ToolError> {
+    let mut args = vec![subcommand.to_string(), "--message-format=json".to_string()];
+    if let Some(package) = params["package"].as_str() {
+        if package.starts_with('-') {
+            return Err(ToolError::InvalidParameters(format!(
+                "invalid package '{}'",
+                package
+            )));
+        }
+        args.extend(["--package".to_string(), package.to_string()]);
+    }
+    let features: Vec<String> = match &params["features"] {
+        Value::Array(features) => features
+            .iter()
+            .filter_map(|f| f.as_str())
+            .map(str::to_string)
+            .collect(),
+        Value::String(features) => features
+            .split([',', ' '])
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect(),
+        Value::Null => Vec::new(),
+        _ => {
+            return Err(ToolError::InvalidParameters(
+                "features must be a list of feature names".into(),
+            ))
+        }
+    };
+    if !features.is_empty() {
+        args.extend(["--features".to_string(), features.join(",")]);
+    }
+    if params["all_features"].as_bool().unwrap_or(false) {
+        args.push("--all-features".to_string());
+    }
+    if params["all_targets"].as_bool().unwrap_or(false) {
+        args.push("--all-targets".to_string());
+    }
+    Ok(args)
+}
+
+/// Run cargo, returning its stdout, stderr and success
+async fn run_cargo(args: &[String], params: &Value) -> Result<(String, String, bool), ToolError> {
+    let timeout_secs = params["timeout_secs"]
+        .as_u64()
+        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+    // Builds run code (build scripts, tests), so they stay in the workspace
+    let dir = resolve_and_validate_path(params["working_dir"].as_str().unwrap_or("."))?;
+    let mut cmd = Command::new("cargo");
+    cmd.args(args).env("CARGO_TERM_COLOR", "never").current_dir(dir);
+    // A cancelled or timed-out build does not keep running
+    cmd.kill_on_drop(true);
+
+    let output = timeout(Duration::from_secs(timeout_secs), cmd.output())
+        .await
+        .map_err(|_| ToolError::Timeout {
+            after_secs: Some(timeout_secs),
+        })?
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ToolError::ExecutionFailed(tool_text!("cargo.not_installed").to_string()),
+            _ => ToolError::ExecutionFailed(tool_text!("cargo.run_error", e)),
+        })?;
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if stderr.contains("could not find `Cargo.toml`") {
+        return Err(ToolError::InvalidParameters(tool_text!("cargo.no_project", stderr.trim())));
+    }
+    Ok((
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr,
+        output.status.success(),
+    ))
+}
+
+/// Result data shared by both tools: counts, the first diagnostics, and the
+/// end of stderr when cargo failed without saying why on stdout
+fn diagnostics_data(output: &CargoOutput, stderr: &str, success: bool, params: &Value) -> Value {
+    let include_warnings = params["include_warnings"].as_bool().unwrap_or(true);
+    let max = params["max_diagnostics"]
+        .as_u64()
+        .unwrap_or(DEFAULT_MAX_DIAGNOSTICS)
+        .min(MAX_DIAGNOSTICS) as usize;
+    // Errors first: they are what stops the build
+    let mut listed: Vec<&Diagnostic> = output
+        .diagnostics
+        .iter()
+        .filter(|d| include_warnings || d.level == "error")
+        .collect();
+    listed.sort_by_key(|d| d.level != "error");
+    let total = listed.len();
+
+    let mut data = serde_json::json!({
+        "success": success,
+        "errors": output.count("error"),
+        "warnings": output.count("warning"),
+        "diagnostics_total": total,
+        "diagnostics": listed.iter().take(max).map(|d| d.to_json()).collect::<Vec<_>>(),
+    });
+    if total > max {
+        data["diagnostics_truncated"] = true.into();
+    }
+    if !success && output.diagnostics.iter().all(|d| d.level != "error") {
+        let start = stderr.len().saturating_sub(STDERR_TAIL_BYTES);
+        let start = (start..=stderr.len())
+            .find(|i| stderr.is_char_boundary(*i))
+            .unwrap_or(stderr.len());
+        data["stderr_tail"] = stderr[start..].trim().into();
+    }
+    data
+}
+
+fn common_properties() -> Value {
+    serde_json::json!({
+        "working_dir": {
+            "type": "string",
+            "description": "Folder of the Cargo.toml (default: current directory, or the workspace root)"
+        },
+        "package": {
+            "type": "string",
+            "description": "Workspace member to build (-p)"
+        },
+        "features": {
+            "type": "array",
+            "items": { "type": "string" },
+            "description": "Features to enable"
+        },
+        "all_features": {
+            "type": "boolean",
+            "description": "Enable all features",
+            "default": false
+        },
+        "max_diagnostics": {
+            "type": "integer",
+            "description": "Diagnostics returned, errors first (default: 20, max: 200)",
+            "default": 20
+        },
+        "include_warnings": {
+            "type": "boolean",
+            "description": "Also list warnings (default: true)",
+            "default": true
+        },
+        "timeout_secs": {
+            "type": "integer",
+            "description": "Timeout in seconds (default: 600)",
+            "default": 600
+        }
+    })
+}
+
+// ============================================================================
+// CargoCheckTool
+// ============================================================================
+
+pub struct CargoCheckTool;
+
+#[async_trait]
+impl Tool for CargoCheckTool {
+    fn name(&self) -> &str {
+        "cargo_check"
+    }
+
+    fn description(&self) -> &str {
+        "Run `cargo check` and get its errors and warnings as a structured list (file, line, code, message), errors first, capped with a total count. Use it instead of running cargo through a shell."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let mut properties = common_properties();
+        properties["all_targets"] = serde_json::json!({
+            "type": "boolean",
+            "description": "Also check tests, benches and examples",
+            "default": false
+        });
+        serde_json::json!({ "type": "object", "properties": properties })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let args = cargo_args("check", &params)?;
+        let (stdout, stderr, success) = run_cargo(&args, &params).await?;
+        let output = CargoOutput::parse(&stdout);
+        let success = output.build_success.unwrap_or(success);
+        let data = diagnostics_data(&output, &stderr, success, &params);
+
+        Ok(ToolResult {
+            success,
+            message: tool_text!(
+                "cargo_check.done",
+                if success { tool_text!("cargo_check.ok") } else { tool_text!("cargo_check.failed") },
+                output.count("error"),
+                output.count("warning")
+            ),
+            data,
+        })
+    }
+}
+
+// ============================================================================
+// CargoTestTool
+// ============================================================================
+
+pub struct CargoTestTool;
+
+#[async_trait]
+impl Tool for CargoTestTool {
+    fn name(&self) -> &str {
+        "cargo_test"
+    }
+
+    fn description(&self) -> &str {
+        "Run `cargo test` and get pass/fail/ignored counts, the failing tests with their output, and compile errors as a structured list. Use filter to run only matching tests."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let mut properties = common_properties();
+        properties["filter"] = serde_json::json!({
+            "type": "string",
+            "description": "Only run tests whose name contains this"
+        });
+        serde_json::json!({ "type": "object", "properties": properties })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let mut args = cargo_args("test", &params)?;
+        if let Some(filter) = params["filter"].as_str() {
+            args.extend(["--".to_string(), filter.to_string()]);
+        }
+        let (stdout, stderr, success) = run_cargo(&args, &params).await?;
+        let output = CargoOutput::parse(&stdout);
+        let built = output.build_success.unwrap_or(true);
+        let mut data = diagnostics_data(&output, &stderr, success, &params);
+        data["tests"] = serde_json::json!({
+            "passed": output.passed,
+            "failed": output.failed,
+            "ignored": output.ignored,
+        });
+        data["failures"] = output
+            .failures
+            .iter()
+            .take(MAX_FAILURES)
+            .map(|(name, text)| {
+                let shown = crate::truncate_str(text.trim_end(), MAX_FAILURE_OUTPUT_BYTES);
+                serde_json::json!({ "name": name, "output": shown })
+            })
+            .collect();
+
+        let message = if built {
+            tool_text!("cargo_test.done", output.passed, output.failed, output.ignored)
+        } else {
+            tool_text!("cargo_test.build_failed", output.count("error"))
+        };
+        Ok(ToolResult {
+            success,
+            message,
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERROR_LINE: &str = r#"{"reason":"compiler-message","package_id":"p","message":{"level":"error","code":{"code":"E0308","explanation":null},"message":"mismatched types","spans":[{"file_name":"src/main.rs","line_start":4,"column_start":18,"is_primary":true}],"rendered":"error[E0308]: mismatched types\n --> src/main.rs:4:18\n"}}"#;
+
+    #[test]
+    fn test_parse_compiler_messages() {
+        let warning = r#"{"reason":"compiler-message","message":{"level":"warning","code":null,"message":"unused variable: `x`","spans":[{"file_name":"src/lib.rs","line_start":2,"column_start":9,"is_primary":true}],"rendered":"warning: unused variable"}}"#;
+        let summary = r#"{"reason":"compiler-message","message":{"level":"error","code":null,"message":"aborting due to 1 previous error","spans":[],"rendered":"error: aborting"}}"#;
+        let finished = r#"{"reason":"build-finished","success":false}"#;
+        // The error twice: once for the lib, once for its tests
+        let stdout = [ERROR_LINE, warning, ERROR_LINE, summary, finished].join("\n");
+
+        let output = CargoOutput::parse(&stdout);
+        assert_eq!(output.build_success, Some(false));
+        assert_eq!(output.count("error"), 1);
+        assert_eq!(output.count("warning"), 1);
+        let error = &output.diagnostics[0];
+        assert_eq!(error.code.as_deref(), Some("E0308"));
+        assert_eq!(error.file.as_deref(), Some("src/main.rs"));
+        assert_eq!((error.line, error.column), (Some(4), Some(18)));
+
+        let params = serde_json::json!({ "max_diagnostics": 1 });
+        let data = diagnostics_data(&output, "", false, &params);
+        assert_eq!(data["diagnostics_total"], 2);
+        assert_eq!(data["diagnostics"][0]["level"], "error");
+        assert_eq!(data["diagnostics_truncated"], true);
+        assert!(data.get("stderr_tail").is_none());
+    }
+
+    #[test]
+    fn test_parse_test_results() {
+        let stdout = "\
+running 3 tests
+test tests::adds ... ok
+test tests::subtracts ... FAILED
+test tests::slow ... ignored
+
+failures:
+
+---- tests::subtracts stdout ----
+thread 'tests::subtracts' panicked at src/lib.rs:9:9:
+assertion `left == right` failed
+
+failures:
+    tests::subtracts
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+running 1 test
+test it_works ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+        let output = CargoOutput::parse(stdout);
+        assert_eq!((output.passed, output.failed, output.ignored), (2, 1, 1));
+        assert_eq!(output.failures.len(), 1);
+        assert_eq!(output.failures[0].0, "tests::subtracts");
+        assert!(output.failures[0]
+            .1
+            .contains("assertion `left == right` failed"));
+    }
+
+    #[test]
+    fn test_cargo_args() {
+        let params =
+            serde_json::json!({ "package": "core", "features": ["a", "b"], "all_targets": true });
+        assert_eq!(
+            cargo_args("check", &params).unwrap(),
+            [
+                "check",
+                "--message-format=json",
+                "--package",
+                "core",
+                "--features",
+                "a,b",
+                "--all-targets"
+            ]
+        );
+        assert!(cargo_args("check", &serde_json::json!({ "features": 3 })).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+//! Command policy of the `command` and `job_start` tools
+//!
+//! A command line is split into the simple commands it chains (`&&`, `||`,
+//! `;`, `|`, `&`, newlines). Each must name an allow-listed binary whose
+//! arguments pass its `ArgumentRule`; command substitutions are never
+//! allow-listed, and neither are commands writing to a file (`>`, `>>`,
+//! `tee`). Denied patterns refuse the whole line in every mode.
+//! What happens to the rest depends on the `CommandPolicyMode`: refused,
+//! left to the user through the permission manager, or run.
+
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+
+use crate::agent::language::tool_text;
+use crate::storage::settings::{ArgumentRule, CommandPolicy, CommandPolicyMode};
+
+/// Policy in use, installed from the settings at startup and on each change
+static POLICY: Lazy<RwLock<CommandPolicy>> = Lazy::new(|| RwLock::new(CommandPolicy::default()));
+
+pub fn set_command_policy(policy: CommandPolicy) {
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// Outcome of checking a command line against a policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandVerdict {
+    Allow,
+    /// Runs once the user approves it; holds why it is not allow-listed
+    Prompt(String),
+    Deny(String),
+}
+
+/// Check `command` against the policy in use
+pub fn check_command(command: &str) -> CommandVerdict {
+    evaluate(&POLICY.read().unwrap_or_else(|e| e.into_inner()), command)
+}
+
+pub fn evaluate(policy: &CommandPolicy, command: &str) -> CommandVerdict {
+    let normalized = normalize(command);
+    if let Some(pattern) = policy
+        .denied_patterns
+        .iter()
+        .map(|p| normalize(p))
+        .find(|p| !p.is_empty() && normalized.contains(p.as_str()))
+    {
+        return CommandVerdict::Deny(tool_text!("command_policy.denied_pattern", pattern));
+    }
+    if policy.mode == CommandPolicyMode::AllowAll {
+        return CommandVerdict::Allow;
+    }
+
+    let reason = if has_substitution(command) {
+        Some(tool_text!("command_policy.substitution").to_string())
+    } else {
+        split_commands(command)
+            .iter()
+            .find_map(|segment| unlisted_reason(policy, segment))
+    };
+    match (reason, policy.mode) {
+        (None, _) => CommandVerdict::Allow,
+        (Some(reason), CommandPolicyMode::Strict) => CommandVerdict::Deny(reason),
+        (Some(reason), _) => CommandVerdict::Prompt(reason),
+    }
+}
+
+/// Why a simple command is outside the allowlist, `None` when it is in
+fn unlisted_reason(policy: &CommandPolicy, segment: &str) -> Option<String> {
+    let mut words = segment
+        .split_whitespace()
+        .skip_while(|word| is_assignment(word));
+    let first = words.next()?;
+    let binary = binary_name(first);
+    if !policy.allowed_binaries.iter().any(|b| b == binary) {
+        return Some(tool_text!("command_policy.unlisted", binary));
+    }
+    let args: Vec<&str> = words.collect();
+    if let Some(target) = written_file(segment) {
+        return Some(tool_text!("command_policy.redirection", target));
+    }
+    if binary == "tee" {
+        if let Some(target) = args.iter().find(|arg| !arg.starts_with('-') && !is_null_device(arg)) {
+            return Some(tool_text!("command_policy.tee", target));
+        }
+    }
+    let rule = policy.argument_rules.get(binary)?;
+    arguments_reason(rule, &args).map(|why| format!("'{}': {}", binary, why))
+}
+
+/// Target of the first `>` / `>>` output redirection to a file, outside
+/// quotes; duplicating a descriptor (`2>&1`) or discarding to the null
+/// device writes nothing
+fn written_file(segment: &str) -> Option<String> {
+    let chars: Vec<char> = segment.chars().collect();
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\\') => i += 1,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '>') => {
+                if chars.get(i) == Some(&'>') {
+                    i += 1;
+                }
+                if chars.get(i) == Some(&'&') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit() || *c == '-') {
+                    continue;
+                }
+                if chars.get(i) == Some(&'&') {
+                    i += 1;
+                }
+                while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                    i += 1;
+                }
+                let target: String = chars[i..]
+                    .iter()
+                    .take_while(|c| !c.is_whitespace() && !matches!(c, '<' | '>'))
+                    .filter(|c| !matches!(c, '\'' | '"'))
+                    .collect();
+                if !is_null_device(&target) {
+                    return Some(target);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_null_device(path: &str) -> bool {
+    matches!(path, "/dev/null" | "NUL" | "nul")
+}
+
+fn arguments_reason(rule: &ArgumentRule, args: &[&str]) -> Option<String> {
+    if let Some(arg) = args
+        .iter()
+        .find(|arg| rule.denied.iter().any(|d| d == *arg))
+    {
+        return Some(tool_text!("command_policy.denied_argument", arg));
+    }
+    let joined = args.join(" ");
+    let allowed = rule.allowed_prefixes.is_empty()
+        || rule.allowed_prefixes.iter().any(|prefix| {
+            let prefix = normalize(prefix);
+            if prefix.is_empty() {
+                joined.is_empty()
+            } else {
+                joined == prefix || joined.starts_with(&format!("{} ", prefix))
+            }
+        });
+    if allowed {
+        None
+    } else if joined.is_empty() {
+        Some(tool_text!("command_policy.arguments_required").to_string())
+    } else {
+        Some(tool_text!("command_policy.arguments_not_allowed", joined))
+    }
+}
+
+/// File name of a command word, without quotes, path or `.exe`
+fn binary_name(word: &str) -> &str {
+    let word = word.trim_matches(|c| c == '"' || c == '\'');
+    let name = word.rsplit(['/', '\\']).next().unwrap_or(word);
+    name.strip_suffix(".exe").unwrap_or(name)
+}
+
+/// `NAME=value` prefix setting a variable for the command
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `$(...)` or backticks outside single quotes
+fn has_substitution(command: &str) -> bool {
+    let mut in_single = false;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if !in_single => {
+                chars.next();
+            }
+            '\'' => in_single = !in_single,
+            '`' if !in_single => return true,
+            '$' if !in_single && chars.peek() == Some(&'(') => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Simple commands of a command line, split on the shell's control
+/// operators outside quotes; `2>&1` and `&>` stay redirections
+fn split_commands(command: &str) -> Vec<String> {
+    let chars: Vec<char> = command.chars().collect();
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '\\') if i + 1 < chars.len() => {
+                current.push(c);
+                i += 1;
+                current.push(chars[i]);
+                i += 1;
+                continue;
+            }
+            (None, '&') if i > 0 && matches!(chars[i - 1], '>' | '<') => {}
+            (None, '&') if chars.get(i + 1) == Some(&'>') => {}
+            (None, ';' | '|' | '&' | '\n' | '(' | ')') => {
+                segments.push(std::mem::take(&mut current));
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+        i += 1;
+    }
+    segments.push(current);
+    segments
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: CommandPolicyMode) -> CommandPolicy {
+        CommandPolicy {
+            mode,
+            ..CommandPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_allowlisted_commands_run() {
+        let policy = policy(CommandPolicyMode::Strict);
+        assert_eq!(evaluate(&policy, "ls -la"), CommandVerdict::Allow);
+        assert_eq!(evaluate(&policy, "/bin/ls src"), CommandVerdict::Allow);
+        assert_eq!(
+            evaluate(&policy, "cargo test 2>&1 | tail -n 20"),
+            CommandVerdict::Allow
+        );
+        assert_eq!(
+            evaluate(&policy, "RUST_LOG=debug cargo check"),
+            CommandVerdict::Allow
+        );
+        assert_eq!(
+            evaluate(&policy, "grep -n 'a|b; c' src/main.rs"),
+            CommandVerdict::Allow
+        );
+        assert_eq!(evaluate(&policy, "env"), CommandVerdict::Allow);
+    }
+
+    #[test]
+    fn test_chained_and_substituted_commands_are_checked() {
+        let policy = policy(CommandPolicyMode::Strict);
+        assert!(matches!(
+            evaluate(&policy, "ls && curl http://x"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&policy, "ls; rm file"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&policy, "echo $(rm file)"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&policy, "echo `rm file`"),
+            CommandVerdict::Deny(_)
+        ));
+        // Quoted, it is only text
+        assert_eq!(
+            evaluate(&policy, "echo '$(rm file)'"),
+            CommandVerdict::Allow
+        );
+    }
+
+    #[test]
+    fn test_argument_rules() {
+        let policy = policy(CommandPolicyMode::Strict);
+        assert_eq!(
+            evaluate(&policy, "git status --short"),
+            CommandVerdict::Allow
+        );
+        assert_eq!(evaluate(&policy, "git remote -v"), CommandVerdict::Allow);
+        assert_eq!(
+            evaluate(&policy, "python -m pytest -q"),
+            CommandVerdict::Allow
+        );
+        assert!(matches!(
+            evaluate(&policy, "git push --force"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&policy, "git statusx"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&policy, "find . -name '*.tmp' -delete"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&policy, "env rm file"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&policy, "python"),
+            CommandVerdict::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn test_file_writes_are_not_allow_listed() {
+        let prompt = policy(CommandPolicyMode::Prompt);
+        for command in [
+            "echo x > ~/.bashrc",
+            "echo x >> ~/.bashrc",
+            "ls>out.txt",
+            "cat a 1> 'b c'",
+            "ls &> out.txt",
+            "echo x | tee -a ~/.profile",
+        ] {
+            assert!(
+                matches!(evaluate(&prompt, command), CommandVerdict::Prompt(_)),
+                "{}",
+                command
+            );
+        }
+        for command in ["cargo test 2>&1", "ls 2>/dev/null", "grep -c '>' src/main.rs", "echo 'a > b'"] {
+            assert_eq!(evaluate(&prompt, command), CommandVerdict::Allow, "{}", command);
+        }
+        let mut with_tee = prompt.clone();
+        with_tee.allowed_binaries.push("tee".to_string());
+        assert!(matches!(
+            evaluate(&with_tee, "echo x | tee out.txt"),
+            CommandVerdict::Prompt(_)
+        ));
+        assert_eq!(evaluate(&with_tee, "echo x | tee /dev/null"), CommandVerdict::Allow);
+        assert!(matches!(
+            evaluate(&policy(CommandPolicyMode::Strict), "echo x > ~/.bashrc"),
+            CommandVerdict::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn test_modes() {
+        let prompt = policy(CommandPolicyMode::Prompt);
+        assert!(matches!(
+            evaluate(&prompt, "make build"),
+            CommandVerdict::Prompt(_)
+        ));
+        assert_eq!(evaluate(&prompt, "ls"), CommandVerdict::Allow);
+        // Script runners are asked for, not auto-allowed
+        for command in ["npm run build", "cargo build", "cargo run", "go build ./..."] {
+            assert!(matches!(
+                evaluate(&prompt, command),
+                CommandVerdict::Prompt(_)
+            ));
+        }
+        assert_eq!(evaluate(&prompt, "npm test"), CommandVerdict::Allow);
+
+        let allow_all = policy(CommandPolicyMode::AllowAll);
+        assert_eq!(evaluate(&allow_all, "make build"), CommandVerdict::Allow);
+        // Denied patterns apply in every mode
+        assert!(matches!(
+            evaluate(&allow_all, "sudo rm  -rf   /"),
+            CommandVerdict::Deny(_)
+        ));
+        assert!(matches!(
+            evaluate(&prompt, "cat x > /dev/sda"),
+            CommandVerdict::Deny(_)
+        ));
+    }
+}
@@ -0,0 +1,825 @@
+//! CSV analysis tool - preview, column stats, filter and group-by
+//!
+//! Files are read as a stream of records with the `csv` crate, so multi-GB
+//! tables are never loaded whole; only the rows returned are kept, and
+//! those are capped.
+//! `.tsv` files and gzip-compressed ones (`.csv.gz`, `.tsv.gz`) are handled
+//! transparently.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+
+/// Rows returned at most by preview and filter
+const MAX_ROWS: usize = 200;
+
+/// Groups kept at most while aggregating; past it the file has too many keys
+const MAX_GROUPS: usize = 100_000;
+
+/// Groups returned at most by aggregate
+const MAX_RETURNED_GROUPS: usize = 200;
+
+/// A record growing past this (an unterminated quote, no line breaks) is an
+/// error
+const MAX_RECORD_BYTES: usize = 1024 * 1024;
+
+/// Bytes kept of each returned cell
+const MAX_CELL_CHARS: usize = 200;
+
+/// Cells counted as missing by stats
+const NULL_VALUES: &[&str] = &["", "NA", "N/A", "null", "NULL", "None", "NaN", "nan"];
+
+fn io_error(path: &Path, e: std::io::Error) -> ToolError {
+    ToolError::io(path, tool_text!("dev.read_path_error", path.display(), e), &e)
+}
+
+/// Input failing once the record being read grows past `MAX_RECORD_BYTES`,
+/// so a file without line breaks or with an unterminated quote is never
+/// read whole
+struct CappedInput {
+    inner: Box<dyn BufRead + Send>,
+    read: u64,
+    /// Offset where the current record starts, moved by `RecordReader`
+    record_start: Arc<AtomicU64>,
+}
+
+impl Read for CappedInput {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.read - self.record_start.load(Ordering::Relaxed) > MAX_RECORD_BYTES as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "record longer than {} bytes (unterminated quote or no line breaks)",
+                    MAX_RECORD_BYTES
+                ),
+            ));
+        }
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        Ok(n)
+    }
+}
+
+/// Streaming reader of delimited records
+///
+/// Quoted fields, `""` escapes and line breaks inside quotes are the `csv`
+/// crate's; fields that are not UTF-8 are read as Latin-1.
+struct RecordReader {
+    reader: csv::Reader<CappedInput>,
+    record: csv::ByteRecord,
+    record_start: Arc<AtomicU64>,
+}
+
+impl RecordReader {
+    fn new(input: Box<dyn BufRead + Send>, delimiter: char) -> Self {
+        let record_start = Arc::new(AtomicU64::new(0));
+        let input = CappedInput {
+            inner: input,
+            read: 0,
+            record_start: record_start.clone(),
+        };
+        let reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter as u8)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(input);
+        Self {
+            reader,
+            record: csv::ByteRecord::new(),
+            record_start,
+        }
+    }
+
+    /// Next record, `None` at the end of the file; blank lines are skipped
+    fn next_record(&mut self) -> std::io::Result<Option<Vec<String>>> {
+        let more = self
+            .reader
+            .read_byte_record(&mut self.record)
+            .map_err(|e| match e.into_kind() {
+                csv::ErrorKind::Io(e) => e,
+                kind => std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", kind)),
+            })?;
+        if !more {
+            return Ok(None);
+        }
+        self.record_start
+            .store(self.reader.position().byte(), Ordering::Relaxed);
+        Ok(Some(self.record.iter().map(decode_field).collect()))
+    }
+}
+
+/// The field as UTF-8, or as Latin-1 when it is not valid UTF-8
+fn decode_field(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// The file, decompressed when it is gzip
+fn open_input(path: &Path) -> Result<Box<dyn BufRead + Send>, ToolError> {
+    let mut file = std::fs::File::open(path).map_err(|e| io_error(path, e))?;
+    let mut magic = [0u8; 2];
+    let gzip = file.read_exact(&mut magic).is_ok() && magic == [0x1f, 0x8b];
+    let file = std::fs::File::open(path).map_err(|e| io_error(path, e))?;
+    Ok(if gzip {
+        Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    })
+}
+
+/// `.tsv` means tabs; otherwise the most frequent candidate in the first line
+fn detect_delimiter(path: &Path, first_line: &str) -> char {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if name.ends_with(".tsv") || name.ends_with(".tsv.gz") || name.ends_with(".tab") {
+        return '\t';
+    }
+    [',', ';', '\t', '|']
+        .into_iter()
+        .max_by_key(|d| first_line.matches(*d).count())
+        .filter(|d| first_line.contains(*d))
+        .unwrap_or(',')
+}
+
+/// Reader positioned after the header, and the column names
+fn open_table(
+    path: &Path,
+    delimiter: Option<char>,
+    has_header: bool,
+) -> Result<(RecordReader, Vec<String>), ToolError> {
+    let delimiter = match delimiter {
+        Some(d) => d,
+        None => {
+            let mut first_line = Vec::new();
+            open_input(path)?
+                .take(MAX_RECORD_BYTES as u64)
+                .read_until(b'\n', &mut first_line)
+                .map_err(|e| io_error(path, e))?;
+            detect_delimiter(path, &String::from_utf8_lossy(&first_line))
+        }
+    };
+    let mut reader = RecordReader::new(open_input(path)?, delimiter);
+    if !has_header {
+        let width = open_table_width(path, delimiter)?;
+        return Ok((reader, (1..=width).map(|i| format!("c{}", i)).collect()));
+    }
+    let header = reader
+        .next_record()
+        .map_err(|e| io_error(path, e))?
+        .ok_or_else(|| ToolError::ExecutionFailed(tool_text!("csv.empty").to_string()))?;
+    Ok((
+        reader,
+        header.into_iter().map(|h| h.trim().to_string()).collect(),
+    ))
+}
+
+/// Field count of the first record, naming the columns of a header-less file
+fn open_table_width(path: &Path, delimiter: char) -> Result<usize, ToolError> {
+    let mut reader = RecordReader::new(open_input(path)?, delimiter);
+    Ok(reader
+        .next_record()
+        .map_err(|e| io_error(path, e))?
+        .map_or(0, |r| r.len()))
+}
+
+fn column_index(columns: &[String], name: &str) -> Result<usize, ToolError> {
+    columns
+        .iter()
+        .position(|c| c == name)
+        .or_else(|| columns.iter().position(|c| c.eq_ignore_ascii_case(name)))
+        .ok_or_else(|| {
+            ToolError::InvalidParameters(tool_text!(
+                "csv.unknown_column",
+                name,
+                columns.join(", ")
+            ))
+        })
+}
+
+fn cell(record: &[String], index: usize) -> &str {
+    record.get(index).map(String::as_str).unwrap_or("")
+}
+
+fn row_value(record: &[String]) -> Value {
+    Value::Array(
+        record
+            .iter()
+            .map(|c| Value::String(crate::truncate_str(c, MAX_CELL_CHARS).to_string()))
+            .collect(),
+    )
+}
+
+fn number(text: &str) -> Option<f64> {
+    text.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Running statistics of one column
+#[derive(Debug, Default)]
+struct ColumnStats {
+    nulls: usize,
+    values: usize,
+    integers: usize,
+    numbers: usize,
+    booleans: usize,
+    sum: f64,
+    min_number: Option<f64>,
+    max_number: Option<f64>,
+    min_text: Option<String>,
+    max_text: Option<String>,
+}
+
+impl ColumnStats {
+    fn add(&mut self, raw: &str) {
+        let text = raw.trim();
+        if NULL_VALUES.contains(&text) {
+            self.nulls += 1;
+            return;
+        }
+        self.values += 1;
+        if let Some(n) = number(text) {
+            self.numbers += 1;
+            if text.parse::<i64>().is_ok() {
+                self.integers += 1;
+            }
+            self.sum += n;
+            self.min_number = Some(self.min_number.map_or(n, |m| m.min(n)));
+            self.max_number = Some(self.max_number.map_or(n, |m| m.max(n)));
+        }
+        if matches!(text.to_lowercase().as_str(), "true" | "false") {
+            self.booleans += 1;
+        }
+        if self.min_text.as_deref().map_or(true, |m| text < m) {
+            self.min_text = Some(text.to_string());
+        }
+        if self.max_text.as_deref().map_or(true, |m| text > m) {
+            self.max_text = Some(text.to_string());
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.values {
+            0 => "empty",
+            n if self.integers == n => "integer",
+            n if self.numbers == n => "float",
+            n if self.booleans == n => "boolean",
+            _ => "string",
+        }
+    }
+
+    fn to_json(&self, name: &str) -> Value {
+        let kind = self.kind();
+        let mut stats = serde_json::json!({
+            "column": name,
+            "type": kind,
+            "values": self.values,
+            "nulls": self.nulls,
+        });
+        if matches!(kind, "integer" | "float") {
+            stats["min"] = serde_json::json!(self.min_number);
+            stats["max"] = serde_json::json!(self.max_number);
+            stats["mean"] = serde_json::json!(self.sum / self.values as f64);
+        } else {
+            let short = |t: &Option<String>| {
+                t.as_deref()
+                    .map(|t| crate::truncate_str(t, MAX_CELL_CHARS).to_string())
+            };
+            stats["min"] = serde_json::json!(short(&self.min_text));
+            stats["max"] = serde_json::json!(short(&self.max_text));
+        }
+        stats
+    }
+}
+
+/// A `column op value` test on rows
+struct Predicate {
+    column: usize,
+    op: String,
+    value: String,
+}
+
+impl Predicate {
+    fn matches(&self, record: &[String]) -> bool {
+        let cell = cell(record, self.column).trim();
+        match self.op.as_str() {
+            "contains" => return cell.contains(&self.value),
+            "starts_with" => return cell.starts_with(&self.value),
+            "ends_with" => return cell.ends_with(&self.value),
+            _ => {}
+        }
+        // Numbers compare as numbers, anything else as text; a missing or
+        // non-numeric cell never passes a numeric comparison
+        let ordering = match (number(cell), number(&self.value)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            (None, Some(_)) => return matches!(self.op.as_str(), "!=" | "ne"),
+            _ => Some(cell.cmp(self.value.as_str())),
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self.op.as_str() {
+            "==" | "=" | "eq" => ordering.is_eq(),
+            "!=" | "ne" => ordering.is_ne(),
+            ">" | "gt" => ordering.is_gt(),
+            ">=" | "ge" => ordering.is_ge(),
+            "<" | "lt" => ordering.is_lt(),
+            "<=" | "le" => ordering.is_le(),
+            _ => false,
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "==",
+    "=",
+    "eq",
+    "!=",
+    "ne",
+    ">",
+    "gt",
+    ">=",
+    "ge",
+    "<",
+    "lt",
+    "<=",
+    "le",
+    "contains",
+    "starts_with",
+    "ends_with",
+];
+
+/// Per-group accumulator: rows, and sum/count of the numeric cells
+#[derive(Debug, Default, Clone, Copy)]
+struct Group {
+    rows: usize,
+    sum: f64,
+    numbers: usize,
+}
+
+/// What a call asks for, checked before the file is read
+enum Operation {
+    Preview {
+        limit: usize,
+    },
+    Stats,
+    Filter {
+        column: String,
+        op: String,
+        value: String,
+        limit: usize,
+    },
+    Aggregate {
+        group_by: Vec<String>,
+        agg: String,
+        column: Option<String>,
+    },
+}
+
+impl Operation {
+    fn from_params(params: &Value) -> Result<Self, ToolError> {
+        let limit = |default: u64| {
+            params["limit"]
+                .as_u64()
+                .unwrap_or(default)
+                .clamp(1, MAX_ROWS as u64) as usize
+        };
+        let text = |key: &str| params[key].as_str().map(str::to_string);
+        match params["operation"].as_str().unwrap_or("preview") {
+            "preview" => Ok(Self::Preview { limit: limit(10) }),
+            "stats" => Ok(Self::Stats),
+            "filter" => {
+                let column = text("column")
+                    .ok_or_else(|| ToolError::InvalidParameters("filter needs column".into()))?;
+                let op = text("op").unwrap_or_else(|| "==".to_string());
+                if !OPERATORS.contains(&op.as_str()) {
+                    return Err(ToolError::InvalidParameters(tool_text!(
+                        "csv.unknown_operator",
+                        op,
+                        OPERATORS.join(" ")
+                    )));
+                }
+                let value = match &params["value"] {
+                    Value::String(s) => s.clone(),
+                    Value::Null => {
+                        return Err(ToolError::InvalidParameters("filter needs value".into()))
+                    }
+                    other => other.to_string(),
+                };
+                Ok(Self::Filter {
+                    column,
+                    op,
+                    value,
+                    limit: limit(20),
+                })
+            }
+            "aggregate" => {
+                let group_by = match &params["group_by"] {
+                    Value::String(s) => vec![s.clone()],
+                    Value::Array(items) => items
+                        .iter()
+                        .filter_map(|i| i.as_str().map(str::to_string))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let agg = text("agg").unwrap_or_else(|| "count".to_string());
+                let column = text("column");
+                match agg.as_str() {
+                    "count" => {}
+                    "sum" | "mean" if column.is_some() => {}
+                    "sum" | "mean" => {
+                        return Err(ToolError::InvalidParameters(format!(
+                            "{} needs column",
+                            agg
+                        )));
+                    }
+                    other => {
+                        return Err(ToolError::InvalidParameters(tool_text!("csv.unknown_aggregate", other)))
+                    }
+                }
+                Ok(Self::Aggregate {
+                    group_by,
+                    agg,
+                    column,
+                })
+            }
+            other => Err(ToolError::InvalidParameters(tool_text!("csv.unknown_operation", other))),
+        }
+    }
+}
+
+/// Run `operation` over the table, returning its data and a summary line
+fn analyze(
+    path: &Path,
+    delimiter: Option<char>,
+    has_header: bool,
+    operation: Operation,
+) -> Result<(Value, String), ToolError> {
+    let (mut reader, columns) = open_table(path, delimiter, has_header)?;
+    let mut next = || reader.next_record().map_err(|e| io_error(path, e));
+
+    match operation {
+        Operation::Preview { limit } => {
+            let mut rows = Vec::new();
+            while rows.len() < limit {
+                match next()? {
+                    Some(record) => rows.push(row_value(&record)),
+                    None => break,
+                }
+            }
+            let more = next()?.is_some();
+            let summary = tool_text!(
+                "csv.preview",
+                columns.len(),
+                rows.len(),
+                if more { "" } else { tool_text!("csv.end_of_file") }
+            );
+            Ok((
+                serde_json::json!({ "columns": columns, "rows": rows, "more_rows": more }),
+                summary,
+            ))
+        }
+        Operation::Stats => {
+            let mut stats: Vec<ColumnStats> =
+                columns.iter().map(|_| ColumnStats::default()).collect();
+            let mut rows = 0usize;
+            while let Some(record) = next()? {
+                rows += 1;
+                for (i, column) in stats.iter_mut().enumerate() {
+                    column.add(cell(&record, i));
+                }
+            }
+            let stats: Vec<Value> = stats
+                .iter()
+                .zip(&columns)
+                .map(|(s, name)| s.to_json(name))
+                .collect();
+            let summary = tool_text!("csv.stats", rows, columns.len());
+            Ok((
+                serde_json::json!({ "rows": rows, "columns": stats }),
+                summary,
+            ))
+        }
+        Operation::Filter {
+            column,
+            op,
+            value,
+            limit,
+        } => {
+            let predicate = Predicate {
+                column: column_index(&columns, &column)?,
+                op,
+                value,
+            };
+            let mut rows = Vec::new();
+            let (mut scanned, mut matched) = (0usize, 0usize);
+            while let Some(record) = next()? {
+                scanned += 1;
+                if predicate.matches(&record) {
+                    matched += 1;
+                    if rows.len() < limit {
+                        rows.push(row_value(&record));
+                    }
+                }
+            }
+            let summary = tool_text!(
+                "csv.filter",
+                matched,
+                scanned,
+                rows.len()
+            );
+            Ok((
+                serde_json::json!({
+                    "columns": columns,
+                    "rows": rows,
+                    "matched": matched,
+                    "scanned": scanned,
+                    "truncated": matched > rows.len()
+                }),
+                summary,
+            ))
+        }
+        Operation::Aggregate {
+            group_by,
+            agg,
+            column,
+        } => {
+            let keys: Vec<usize> = group_by
+                .iter()
+                .map(|name| column_index(&columns, name))
+                .collect::<Result<_, _>>()?;
+            let value_column = column
+                .as_deref()
+                .map(|name| column_index(&columns, name))
+                .transpose()?;
+            let mut groups: BTreeMap<Vec<String>, Group> = BTreeMap::new();
+            while let Some(record) = next()? {
+                let key: Vec<String> = keys
+                    .iter()
+                    .map(|&i| cell(&record, i).trim().to_string())
+                    .collect();
+                if !groups.contains_key(&key) && groups.len() >= MAX_GROUPS {
+                    return Err(ToolError::ExecutionFailed(tool_text!("csv.too_many_groups", MAX_GROUPS)));
+                }
+                let group = groups.entry(key).or_default();
+                group.rows += 1;
+                if let Some(n) = value_column.and_then(|i| number(cell(&record, i))) {
+                    group.sum += n;
+                    group.numbers += 1;
+                }
+            }
+            let total_groups = groups.len();
+            let results: Vec<Value> = groups
+                .into_iter()
+                .take(MAX_RETURNED_GROUPS)
+                .map(|(key, group)| {
+                    let value = match agg.as_str() {
+                        "sum" => serde_json::json!(group.sum),
+                        "mean" if group.numbers > 0 => {
+                            serde_json::json!(group.sum / group.numbers as f64)
+                        }
+                        "mean" => Value::Null,
+                        _ => serde_json::json!(group.rows),
+                    };
+                    let mut entry = serde_json::json!({ "key": key, "rows": group.rows });
+                    entry[agg.as_str()] = value;
+                    entry
+                })
+                .collect();
+            let summary = tool_text!(
+                "csv.aggregate",
+                total_groups,
+                agg,
+                column.map(|c| format!("({})", c)).unwrap_or_default()
+            );
+            Ok((
+                serde_json::json!({
+                    "group_by": group_by,
+                    "groups": results,
+                    "total_groups": total_groups,
+                    "truncated": total_groups > MAX_RETURNED_GROUPS
+                }),
+                summary,
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// CsvAnalyzeTool - Query CSV/TSV files without reading them into the context
+// ============================================================================
+
+pub struct CsvAnalyzeTool;
+
+#[async_trait]
+impl Tool for CsvAnalyzeTool {
+    fn name(&self) -> &str {
+        "csv_analyze"
+    }
+
+    fn description(&self) -> &str {
+        "Analyze a CSV/TSV file (also .gz) without reading it whole: preview (header + first rows), stats (per-column type, min/max/mean, nulls), filter (rows where column op value) or aggregate (group-by with count/sum/mean). Much cheaper than file_read for data questions."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "CSV, TSV or gzipped file"
+                },
+                "operation": {
+                    "type": "string",
+                    "enum": ["preview", "stats", "filter", "aggregate"],
+                    "description": "What to compute (default: preview)",
+                    "default": "preview"
+                },
+                "delimiter": {
+                    "type": "string",
+                    "description": "Field separator (default: tab for .tsv, else detected from the header)"
+                },
+                "has_header": {
+                    "type": "boolean",
+                    "description": "First row holds column names (default: true); otherwise columns are c1, c2...",
+                    "default": true
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Rows returned by preview (default 10) and filter (default 20), at most 200"
+                },
+                "column": {
+                    "type": "string",
+                    "description": "filter: column tested; aggregate: column summed or averaged"
+                },
+                "op": {
+                    "type": "string",
+                    "enum": OPERATORS,
+                    "description": "filter: comparison, numeric when both sides are numbers (default: ==)"
+                },
+                "value": {
+                    "type": ["string", "number", "boolean"],
+                    "description": "filter: value compared against"
+                },
+                "group_by": {
+                    "type": ["string", "array"],
+                    "items": { "type": "string" },
+                    "description": "aggregate: column(s) to group by; none for a single total"
+                },
+                "agg": {
+                    "type": "string",
+                    "enum": ["count", "sum", "mean"],
+                    "description": "aggregate: function (default: count)",
+                    "default": "count"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path_str = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let operation = Operation::from_params(&params)?;
+        let delimiter = match params["delimiter"].as_str() {
+            Some("\\t") | Some("tab") => Some('\t'),
+            Some(d) if d.len() == 1 => d.chars().next(),
+            Some(d) => {
+                return Err(ToolError::InvalidParameters(tool_text!("csv.invalid_delimiter", d)))
+            }
+            None => None,
+        };
+        let has_header = params["has_header"].as_bool().unwrap_or(true);
+
+        let path = resolve_and_validate_path(path_str)?;
+        if !path.is_file() {
+            return Err(ToolError::NotFound {
+                path: path.display().to_string(),
+                message: "File does not exist".to_string(),
+            });
+        }
+
+        let file = path.clone();
+        let (mut data, summary) =
+            tokio::task::spawn_blocking(move || analyze(&file, delimiter, has_header, operation))
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionFailed(format!("Analysis task failed: {}", e))
+                })??;
+        data["path"] = Value::String(path_str.to_string());
+
+        Ok(ToolResult {
+            success: true,
+            data,
+            message: format!("{}: {}", path_str, summary),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const SALES: &str = "region,product,amount,note\nnorth,apple,10,\nsouth,pear,5.5,\"multi\nline, quoted\"\nnorth,pear,NA,\"say \"\"hi\"\"\"\nnorth,apple,4,\n";
+
+    async fn run(params: Value) -> ToolResult {
+        CsvAnalyzeTool.execute(params).await.unwrap()
+    }
+
+    #[test]
+    fn test_records_handle_quotes_and_line_breaks() {
+        let mut reader = RecordReader::new(Box::new(SALES.as_bytes()), ',');
+        let mut records = Vec::new();
+        while let Some(record) = reader.next_record().unwrap() {
+            records.push(record);
+        }
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[2][3], "multi\nline, quoted");
+        assert_eq!(records[3][3], "say \"hi\"");
+        assert_eq!(detect_delimiter(Path::new("x.csv"), "a;b;c"), ';');
+        assert_eq!(detect_delimiter(Path::new("x.tsv"), "a,b"), '\t');
+    }
+
+    #[test]
+    fn test_records_are_capped_and_decoded_lossily() {
+        let latin1 = b"ville,prix\nS\xe8te,3\n".to_vec();
+        let mut reader = RecordReader::new(Box::new(std::io::Cursor::new(latin1)), ',');
+        reader.next_record().unwrap();
+        assert_eq!(reader.next_record().unwrap().unwrap()[0], "Sète");
+
+        let endless = "x".repeat(MAX_RECORD_BYTES * 2);
+        let mut reader = RecordReader::new(Box::new(std::io::Cursor::new(endless)), ',');
+        let err = reader.next_record().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // Records after the first are capped on their own length
+        let rows = "a,b\n".repeat(MAX_RECORD_BYTES / 2);
+        let mut reader = RecordReader::new(Box::new(std::io::Cursor::new(rows)), ',');
+        let mut count = 0;
+        while reader.next_record().unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_RECORD_BYTES / 2);
+    }
+
+    #[tokio::test]
+    async fn test_operations_on_plain_and_gzipped_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("sales.csv");
+        std::fs::write(&plain, SALES).unwrap();
+        let gzipped = dir.path().join("sales.csv.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&gzipped).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(SALES.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        for path in [&plain, &gzipped] {
+            let preview = run(serde_json::json!({"path": path, "limit": 2})).await;
+            assert_eq!(preview.data["columns"][2], "amount");
+            assert_eq!(preview.data["rows"].as_array().unwrap().len(), 2);
+            assert_eq!(preview.data["more_rows"], true);
+
+            let stats = run(serde_json::json!({"path": path, "operation": "stats"})).await;
+            let amount = &stats.data["columns"][2];
+            assert_eq!(
+                (amount["type"].as_str(), amount["nulls"].as_u64()),
+                (Some("float"), Some(1))
+            );
+            assert_eq!(
+                (amount["min"].as_f64(), amount["max"].as_f64()),
+                (Some(4.0), Some(10.0))
+            );
+
+            let filtered = run(serde_json::json!({"path": path, "operation": "filter", "column": "amount", "op": ">", "value": 5})).await;
+            assert_eq!(filtered.data["matched"], 2);
+
+            let grouped = run(serde_json::json!({
+                "path": path, "operation": "aggregate", "group_by": "region", "agg": "sum", "column": "amount"
+            }))
+            .await;
+            assert_eq!(grouped.data["groups"][0]["key"][0], "north");
+            assert_eq!(grouped.data["groups"][0]["sum"], 14.0);
+            assert_eq!(grouped.data["groups"][0]["rows"], 3);
+        }
+
+        let err = CsvAnalyzeTool
+            .execute(serde_json::json!({"path": plain, "operation": "filter", "column": "price", "value": 1}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+    }
+}
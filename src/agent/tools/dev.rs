@@ -6,6 +6,8 @@ use async_trait::async_trait;
 use serde_json::Value;
 use std::path::PathBuf;
 
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 
 // ============================================================================
@@ -57,9 +59,9 @@ impl Tool for DiffTool {
         let context_lines = params["context_lines"].as_u64().unwrap_or(3) as usize;
 
         let text_a = if let Some(path) = params["file_a"].as_str() {
-            tokio::fs::read_to_string(path)
+            tokio::fs::read_to_string(resolve_and_validate_path(path)?)
                 .await
-                .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire {}: {}", path, e)))?
+                .map_err(|e| ToolError::ExecutionFailed(tool_text!("dev.read_path_error", path, e)))?
         } else if let Some(text) = params["text_a"].as_str() {
             text.to_string()
         } else {
@@ -69,9 +71,9 @@ impl Tool for DiffTool {
         };
 
         let text_b = if let Some(path) = params["file_b"].as_str() {
-            tokio::fs::read_to_string(path)
+            tokio::fs::read_to_string(resolve_and_validate_path(path)?)
                 .await
-                .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire {}: {}", path, e)))?
+                .map_err(|e| ToolError::ExecutionFailed(tool_text!("dev.read_path_error", path, e)))?
         } else if let Some(text) = params["text_b"].as_str() {
             text.to_string()
         } else {
@@ -109,8 +111,8 @@ impl Tool for DiffTool {
                 "label_a": label_a,
                 "label_b": label_b,
             }),
-            message: format!(
-                "Diff: {} changement(s) entre {} ({} lignes) et {} ({} lignes)",
+            message: tool_text!(
+                "diff.done",
                 changes,
                 label_a,
                 lines_a.len(),
@@ -186,7 +188,7 @@ impl Tool for FindReplaceTool {
         let dry_run = params["dry_run"].as_bool().unwrap_or(false);
         let max_files = params["max_files"].as_u64().unwrap_or(50) as usize;
 
-        let path_buf = PathBuf::from(path);
+        let path_buf = resolve_and_validate_path(path)?;
         let mut modified_files = Vec::new();
         let mut total_replacements = 0usize;
 
@@ -211,8 +213,8 @@ impl Tool for FindReplaceTool {
                 "search": search,
                 "replace": replace
             }),
-            message: format!(
-                "{}{} remplacement(s) dans {} fichier(s)",
+            message: tool_text!(
+                "find_replace.done",
                 if dry_run { "[DRY RUN] " } else { "" },
                 total_replacements,
                 modified_files.len()
@@ -258,11 +260,7 @@ fn find_replace_recursive<'a>(
                         tokio::fs::write(path, new_content)
                             .await
                             .map_err(|e| {
-                                ToolError::ExecutionFailed(format!(
-                                    "Impossible d'écrire {}: {}",
-                                    path.display(),
-                                    e
-                                ))
+                                ToolError::ExecutionFailed(tool_text!("dev.write_path_error", path.display(), e))
                             })?;
                     }
                 }
@@ -344,16 +342,17 @@ impl Tool for PatchTool {
         let patch = params["patch"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("patch is required".into()))?;
+        let path_buf = resolve_and_validate_path(path)?;
 
-        let content = tokio::fs::read_to_string(path)
+        let content = tokio::fs::read_to_string(&path_buf)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file.read_error", e)))?;
 
         let new_content = apply_simple_patch(&content, patch)?;
 
-        tokio::fs::write(path, &new_content)
+        tokio::fs::write(&path_buf, &new_content)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible d'écrire: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file.write_error", e)))?;
 
         Ok(ToolResult {
             success: true,
@@ -362,7 +361,7 @@ impl Tool for PatchTool {
                 "lines_before": content.lines().count(),
                 "lines_after": new_content.lines().count()
             }),
-            message: format!("Patch appliqué à {}", path),
+            message: tool_text!("patch.done", path),
         })
     }
 }
@@ -401,9 +400,9 @@ impl Tool for CountLinesTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
 
-        let content = tokio::fs::read_to_string(path)
+        let content = tokio::fs::read_to_string(resolve_and_validate_path(path)?)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file.read_error", e)))?;
 
         let lines = content.lines().count();
         let words = content.split_whitespace().count();
@@ -419,7 +418,7 @@ impl Tool for CountLinesTool {
                 "characters": chars,
                 "bytes": bytes
             }),
-            message: format!("{}: {} lignes, {} mots, {} caractères", path, lines, words, chars),
+            message: tool_text!("count_lines.done", path, lines, words, chars),
         })
     }
 }
@@ -428,6 +427,13 @@ impl Tool for CountLinesTool {
 // Helpers
 // ============================================================================
 
+/// Line diff of a file's content before and after a change, with 3 lines of context
+pub fn content_diff(before: &str, after: &str) -> String {
+    let lines_a: Vec<&str> = before.lines().collect();
+    let lines_b: Vec<&str> = after.lines().collect();
+    compute_line_diff(&lines_a, &lines_b, 3).join("\n")
+}
+
 /// Simple line-by-line diff with context
 fn compute_line_diff(lines_a: &[&str], lines_b: &[&str], context: usize) -> Vec<String> {
     let mut result = Vec::new();
@@ -474,7 +480,7 @@ fn compute_line_diff(lines_a: &[&str], lines_b: &[&str], context: usize) -> Vec<
         .collect();
 
     if change_indices.is_empty() {
-        result.push("Aucune différence trouvée.".to_string());
+        result.push(tool_text!("diff.none").to_string());
         return result;
     }
 
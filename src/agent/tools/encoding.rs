@@ -0,0 +1,590 @@
+//! Line-ending and encoding conversion tool
+//!
+//! Reports how a text file is stored (encoding, BOM, LF/CRLF counts, final
+//! newline) and rewrites it with other conventions. The new content goes to
+//! a temporary file in the same directory that is then renamed over the
+//! original, so an interrupted write never leaves a half-converted file.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::backup::{with_backup_warning, PendingBackup};
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+
+/// Files above this size are not converted
+const MAX_CONVERT_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Some(Self::Utf8),
+            "utf-16" | "utf16" | "utf-16le" | "utf16le" => Some(Self::Utf16Le),
+            "utf-16be" | "utf16be" => Some(Self::Utf16Be),
+            "latin-1" | "latin1" | "iso-8859-1" => Some(Self::Latin1),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16be",
+            Self::Latin1 => "latin-1",
+        }
+    }
+
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Self::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Self::Utf16Le => &[0xFF, 0xFE],
+            Self::Utf16Be => &[0xFE, 0xFF],
+            Self::Latin1 => &[],
+        }
+    }
+}
+
+/// A file's text and how it was stored
+#[derive(Debug)]
+struct Decoded {
+    encoding: Encoding,
+    bom: bool,
+    text: String,
+}
+
+/// UTF-16 without a BOM: most high bytes of ASCII-range text are zero
+fn utf16_without_bom(bytes: &[u8]) -> Option<Encoding> {
+    if bytes.len() < 4 || bytes.len() % 2 != 0 {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let zeros_at = |parity: usize| {
+        bytes
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|b| **b == 0)
+            .count()
+    };
+    if zeros_at(1) * 2 > pairs && zeros_at(0) == 0 {
+        Some(Encoding::Utf16Le)
+    } else if zeros_at(0) * 2 > pairs && zeros_at(1) == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Detect the encoding (BOM, then UTF-16 by its zero bytes, then valid
+/// UTF-8, else Latin-1) and decode; NUL bytes in 8-bit text mean binary
+fn decode(bytes: &[u8]) -> Result<Decoded, ToolError> {
+    let with_bom = [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be]
+        .into_iter()
+        .find(|encoding| bytes.starts_with(encoding.bom()));
+    let (encoding, body) = match with_bom {
+        Some(encoding) => (encoding, &bytes[encoding.bom().len()..]),
+        None => match utf16_without_bom(bytes) {
+            Some(encoding) => (encoding, bytes),
+            None if std::str::from_utf8(bytes).is_ok() => (Encoding::Utf8, bytes),
+            None => (Encoding::Latin1, bytes),
+        },
+    };
+    let text = match encoding {
+        Encoding::Utf8 => String::from_utf8(body.to_vec()).map_err(|e| {
+            ToolError::ExecutionFailed(tool_text!("file_convert.invalid_utf8", e))
+        })?,
+        Encoding::Latin1 => body.iter().map(|&b| b as char).collect(),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if body.len() % 2 != 0 {
+                return Err(ToolError::ExecutionFailed(tool_text!("file_convert.odd_utf16").into()));
+            }
+            let units = body.chunks_exact(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units)
+                .collect::<Result<String, _>>()
+                .map_err(|e| ToolError::ExecutionFailed(tool_text!("file_convert.invalid_utf16", e)))?
+        }
+    };
+    if encoding != Encoding::Utf16Le && encoding != Encoding::Utf16Be && text.contains('\0') {
+        return Err(ToolError::InvalidParameters(tool_text!("file_convert.binary").into()));
+    }
+    Ok(Decoded {
+        encoding,
+        bom: with_bom.is_some(),
+        text,
+    })
+}
+
+fn encode(text: &str, encoding: Encoding, bom: bool) -> Result<Vec<u8>, ToolError> {
+    let mut bytes = if bom {
+        encoding.bom().to_vec()
+    } else {
+        Vec::new()
+    };
+    match encoding {
+        Encoding::Utf8 => bytes.extend_from_slice(text.as_bytes()),
+        Encoding::Utf16Le => bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+        Encoding::Utf16Be => bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+        Encoding::Latin1 => {
+            for (number, line) in text.split('\n').enumerate() {
+                if let Some(c) = line.chars().find(|c| *c as u32 > 0xFF) {
+                    return Err(ToolError::ExecutionFailed(tool_text!("file_convert.not_latin1", c, number + 1)));
+                }
+            }
+            bytes.extend(text.chars().map(|c| c as u8));
+        }
+    }
+    Ok(bytes)
+}
+
+/// Line breaks of each kind
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct LineEndings {
+    lf: usize,
+    crlf: usize,
+    /// Lone `\r` (classic Mac)
+    cr: usize,
+}
+
+impl LineEndings {
+    fn count(text: &str) -> Self {
+        let mut counts = Self::default();
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                    counts.crlf += 1;
+                }
+                '\r' => counts.cr += 1,
+                '\n' => counts.lf += 1,
+                _ => {}
+            }
+        }
+        counts
+    }
+
+    /// The most used line break, LF when there is none
+    fn dominant(self) -> &'static str {
+        if self.crlf > self.lf && self.crlf >= self.cr {
+            "\r\n"
+        } else if self.cr > self.lf && self.cr > self.crlf {
+            "\r"
+        } else {
+            "\n"
+        }
+    }
+
+    fn to_json(self) -> Value {
+        serde_json::json!({ "lf": self.lf, "crlf": self.crlf, "cr": self.cr })
+    }
+}
+
+/// Every line break of `text` as `ending`
+fn normalize_endings(text: &str, ending: &str) -> String {
+    let unix = text.replace("\r\n", "\n").replace('\r', "\n");
+    if ending == "\n" {
+        unix
+    } else {
+        unix.replace('\n', ending)
+    }
+}
+
+/// Write through a temporary file renamed over `path`, keeping its permissions
+fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = dir.join(format!(".{}.{}.tmp", name, uuid::Uuid::new_v4().simple()));
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        if let Ok(meta) = std::fs::metadata(path) {
+            std::fs::set_permissions(&tmp, meta.permissions())?;
+        }
+        std::fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// What a call asks to change; `None` fields are kept as they are
+#[derive(Debug, Default)]
+struct Conversion {
+    line_ending: Option<&'static str>,
+    encoding: Option<Encoding>,
+    /// Some(true): end with a line break, Some(false): no trailing line break
+    trailing_newline: Option<bool>,
+    strip_bom: bool,
+}
+
+impl Conversion {
+    fn from_params(params: &Value) -> Result<Self, ToolError> {
+        let line_ending = match params["line_ending"].as_str() {
+            None | Some("keep") => None,
+            Some("lf") | Some("LF") => Some("\n"),
+            Some("crlf") | Some("CRLF") => Some("\r\n"),
+            Some(other) => {
+                return Err(ToolError::InvalidParameters(tool_text!("file_convert.unknown_line_ending", other)))
+            }
+        };
+        let encoding = match params["encoding"].as_str() {
+            None | Some("keep") => None,
+            Some(name) => Some(Encoding::parse(name).ok_or_else(|| {
+                ToolError::InvalidParameters(tool_text!("file_convert.unknown_encoding", name))
+            })?),
+        };
+        let trailing_newline = match params["trailing_newline"].as_str() {
+            None | Some("keep") => None,
+            Some("add") => Some(true),
+            Some("strip") => Some(false),
+            Some(other) => {
+                return Err(ToolError::InvalidParameters(tool_text!("file_convert.unknown_trailing_newline", other)))
+            }
+        };
+        Ok(Self {
+            line_ending,
+            encoding,
+            trailing_newline,
+            strip_bom: params["strip_bom"].as_bool().unwrap_or(false),
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.line_ending.is_none()
+            && self.encoding.is_none()
+            && self.trailing_newline.is_none()
+            && !self.strip_bom
+    }
+
+    /// New bytes of the file, and a description of each change made
+    fn apply(&self, source: &Decoded) -> Result<(Vec<u8>, Vec<String>), ToolError> {
+        let mut changes = Vec::new();
+        let before = LineEndings::count(&source.text);
+        let mut text = match self.line_ending {
+            Some(ending) => {
+                let affected = match ending {
+                    "\n" => before.crlf + before.cr,
+                    _ => before.lf + before.cr,
+                };
+                if affected > 0 {
+                    changes.push(tool_text!(
+                        "file_convert.line_endings",
+                        affected,
+                        if ending == "\n" { "LF" } else { "CRLF" }
+                    ));
+                }
+                normalize_endings(&source.text, ending)
+            }
+            None => source.text.clone(),
+        };
+
+        let ends_with_break = text.ends_with('\n') || text.ends_with('\r');
+        match self.trailing_newline {
+            Some(true) if !text.is_empty() && !ends_with_break => {
+                text.push_str(self.line_ending.unwrap_or(before.dominant()));
+                changes.push(tool_text!("file_convert.newline_added").to_string());
+            }
+            Some(false) if ends_with_break => {
+                text.truncate(text.trim_end_matches(['\r', '\n']).len());
+                changes.push(tool_text!("file_convert.newline_stripped").to_string());
+            }
+            _ => {}
+        }
+
+        let encoding = self.encoding.unwrap_or(source.encoding);
+        if encoding != source.encoding {
+            changes.push(format!("{} -> {}", source.encoding.name(), encoding.name()));
+        }
+        // UTF-16 is written with a BOM unless asked otherwise; other targets
+        // keep the source's choice
+        let bom = !self.strip_bom
+            && match encoding {
+                Encoding::Utf16Le | Encoding::Utf16Be => source.bom || encoding != source.encoding,
+                _ => source.bom,
+            };
+        if source.bom && !bom {
+            changes.push(tool_text!("file_convert.bom_removed").to_string());
+        } else if bom && !source.bom {
+            changes.push(tool_text!("file_convert.bom_added").to_string());
+        }
+        Ok((encode(&text, encoding, bom)?, changes))
+    }
+}
+
+// ============================================================================
+// FileConvertTool - Line endings, final newline, BOM and encoding
+// ============================================================================
+
+pub struct FileConvertTool;
+
+#[async_trait]
+impl Tool for FileConvertTool {
+    fn name(&self) -> &str {
+        "file_convert"
+    }
+
+    fn description(&self) -> &str {
+        "Report or convert how a text file is stored: line endings (LF/CRLF), trailing newline, BOM and encoding (utf-8, utf-16le/be, latin-1). With no conversion params it only reports. Use dry_run to see what would change; writes are atomic. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Text file to inspect or convert"
+                },
+                "line_ending": {
+                    "type": "string",
+                    "enum": ["lf", "crlf", "keep"],
+                    "description": "Line break to use everywhere (default: keep)"
+                },
+                "encoding": {
+                    "type": "string",
+                    "enum": ["utf-8", "utf-16le", "utf-16be", "latin-1", "keep"],
+                    "description": "Target encoding (default: keep the detected one)"
+                },
+                "trailing_newline": {
+                    "type": "string",
+                    "enum": ["add", "strip", "keep"],
+                    "description": "End the file with a line break, or remove trailing ones (default: keep)"
+                },
+                "strip_bom": {
+                    "type": "boolean",
+                    "description": "Remove the byte order mark (default: false)",
+                    "default": false
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Report what would change without writing (default: false)",
+                    "default": false
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path_str = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let conversion = Conversion::from_params(&params)?;
+        let dry_run = params["dry_run"].as_bool().unwrap_or(false) || conversion.is_empty();
+
+        let path = resolve_and_validate_path(path_str)?;
+        let meta = tokio::fs::metadata(&path).await.map_err(|e| {
+            ToolError::io(&path, tool_text!("file.read_error", e), &e)
+        })?;
+        if !meta.is_file() {
+            return Err(ToolError::InvalidParameters(tool_text!("file_convert.not_a_file", path_str)));
+        }
+        if meta.len() > MAX_CONVERT_BYTES {
+            return Err(ToolError::ExecutionFailed(tool_text!("file_convert.too_large", meta.len(), MAX_CONVERT_BYTES)));
+        }
+        let bytes = tokio::fs::read(&path).await.map_err(|e| {
+            ToolError::io(&path, tool_text!("file.read_error", e), &e)
+        })?;
+        let source = decode(&bytes)?;
+        let (converted, changes) = conversion.apply(&source)?;
+        let changed = converted != bytes;
+
+        let mut data = serde_json::json!({
+            "path": path_str,
+            "encoding": source.encoding.name(),
+            "bom": source.bom,
+            "line_endings": LineEndings::count(&source.text).to_json(),
+            "trailing_newline": source.text.ends_with('\n') || source.text.ends_with('\r'),
+            "changes": changes,
+            "changed": changed,
+            "dry_run": dry_run,
+            "written": false,
+        });
+
+        if conversion.is_empty() {
+            let endings = LineEndings::count(&source.text);
+            return Ok(ToolResult {
+                success: true,
+                data,
+                message: format!(
+                    "{}: {}{}, LF {} / CRLF {} / CR {}",
+                    path_str,
+                    source.encoding.name(),
+                    if source.bom { tool_text!("file_convert.with_bom") } else { "" },
+                    endings.lf,
+                    endings.crlf,
+                    endings.cr
+                ),
+            });
+        }
+        if dry_run || !changed {
+            let summary = if changed {
+                changes.join(", ")
+            } else {
+                tool_text!("file_convert.unchanged").to_string()
+            };
+            return Ok(ToolResult {
+                success: true,
+                data,
+                message: format!(
+                    "{}{}: {}",
+                    path_str,
+                    if dry_run { tool_text!("file_convert.dry_run") } else { "" },
+                    summary
+                ),
+            });
+        }
+
+        let backup = PendingBackup::capture(&path).await;
+        let target = path.clone();
+        tokio::task::spawn_blocking(move || write_atomic(&target, &converted))
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Write task failed: {}", e)))?
+            .map_err(|e| {
+                ToolError::ExecutionFailed(tool_text!("file.write_error", e))
+            })?;
+        let backup_warning = backup.commit(self.name());
+        data["written"] = Value::Bool(true);
+
+        Ok(with_backup_warning(
+            ToolResult {
+                success: true,
+                data,
+                message: tool_text!("file_convert.done", path_str, changes.join(", ")),
+            },
+            backup_warning,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn convert(params: Value) -> Result<ToolResult, ToolError> {
+        FileConvertTool.execute(params).await
+    }
+
+    #[test]
+    fn test_decode_detects_encodings() {
+        let utf16: Vec<u8> = "hi\r\n".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let decoded = decode(&utf16).unwrap();
+        assert_eq!(
+            (decoded.encoding, decoded.bom, decoded.text.as_str()),
+            (Encoding::Utf16Le, false, "hi\r\n")
+        );
+
+        let decoded = decode(b"\xEF\xBB\xBFabc").unwrap();
+        assert_eq!(
+            (decoded.encoding, decoded.bom, decoded.text.as_str()),
+            (Encoding::Utf8, true, "abc")
+        );
+
+        let decoded = decode(b"caf\xE9").unwrap();
+        assert_eq!(
+            (decoded.encoding, decoded.text.as_str()),
+            (Encoding::Latin1, "café")
+        );
+
+        assert!(decode(b"\x7fELF\x02\x01\x01\x00\x00\x00\xff").is_err());
+        assert_eq!(
+            LineEndings::count("a\r\nb\nc\rd\r\n"),
+            LineEndings {
+                lf: 1,
+                crlf: 2,
+                cr: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_then_convert() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("script.sh");
+        std::fs::write(&file, b"\xEF\xBB\xBFecho a\r\necho b\r\necho c").unwrap();
+
+        let preview = convert(serde_json::json!({
+            "path": file, "line_ending": "lf", "trailing_newline": "add", "strip_bom": true, "dry_run": true
+        }))
+        .await
+        .unwrap();
+        assert_eq!(preview.data["line_endings"]["crlf"], 2);
+        assert_eq!(preview.data["changes"].as_array().unwrap().len(), 3);
+        assert_eq!(preview.data["written"], false);
+        assert!(std::fs::read(&file).unwrap().starts_with(b"\xEF\xBB\xBF"));
+
+        let result = convert(serde_json::json!({
+            "path": file, "line_ending": "lf", "trailing_newline": "add", "strip_bom": true
+        }))
+        .await
+        .unwrap();
+        assert_eq!(result.data["written"], true);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "echo a\necho b\necho c\n"
+        );
+        // No temporary file is left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let again = convert(serde_json::json!({"path": file, "line_ending": "lf"}))
+            .await
+            .unwrap();
+        assert_eq!(again.data["changed"], false);
+    }
+
+    #[tokio::test]
+    async fn test_transcoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "prix: 5 €\n").unwrap();
+
+        convert(serde_json::json!({"path": file, "encoding": "utf-16le"}))
+            .await
+            .unwrap();
+        let bytes = std::fs::read(&file).unwrap();
+        assert_eq!(&bytes[..2], &[0xFF, 0xFE]);
+        let report = convert(serde_json::json!({"path": file})).await.unwrap();
+        assert_eq!(
+            (
+                report.data["encoding"].as_str(),
+                report.data["bom"].as_bool()
+            ),
+            (Some("utf-16le"), Some(true))
+        );
+
+        // The euro sign has no Latin-1 code; the file stays as it was
+        let err = convert(serde_json::json!({"path": file, "encoding": "latin-1"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Latin-1"));
+        assert_eq!(std::fs::read(&file).unwrap(), bytes);
+
+        convert(serde_json::json!({"path": file, "encoding": "utf-8", "strip_bom": true}))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "prix: 5 €\n");
+    }
+}
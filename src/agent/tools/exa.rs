@@ -11,8 +11,12 @@ use serde_json::Value;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
+use crate::agent::language::tool_text;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 
+/// Client timeout of Exa MCP requests
+const MCP_TIMEOUT_SECS: u64 = 60;
+
 /// Exa search configuration
 #[derive(Clone, Debug)]
 pub struct ExaSearchConfig {
@@ -46,7 +50,7 @@ impl ExaMcpClient {
         Self {
             config,
             client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(60))
+                .timeout(std::time::Duration::from_secs(MCP_TIMEOUT_SECS))
                 .build()
                 .unwrap_or_else(|_| reqwest::Client::new()),
             initialized: AtomicBool::new(false),
@@ -76,7 +80,7 @@ impl ExaMcpClient {
             .json(&request)
             .send()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("MCP request failed: {}", e)))?;
+            .map_err(|e| ToolError::http(format!("MCP request failed: {}", e), &e, MCP_TIMEOUT_SECS))?;
 
         let status = response.status();
         let body = response
@@ -85,10 +89,10 @@ impl ExaMcpClient {
             .unwrap_or_else(|_| "Unknown response".to_string());
 
         if !status.is_success() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "MCP HTTP error ({}): {}",
-                status, body
-            )));
+            return Err(ToolError::Network {
+                status: Some(status.as_u16()),
+                message: format!("MCP HTTP error ({}): {}", status, body),
+            });
         }
 
         let value = parse_mcp_body(&body)?;
@@ -233,7 +237,7 @@ impl Tool for ExaSearchTool {
                 "content": content_text,
                 "num_results": num_results
             }),
-            message: format!("Recherche web pour \"{}\" - {} résultats", query, num_results),
+            message: tool_text!("exa.web_search", query, num_results),
         })
     }
 }
@@ -319,7 +323,7 @@ impl Tool for ExaCodeSearchTool {
                 "content": content_text,
                 "tokens": tokens_num
             }),
-            message: format!("Recherche code pour \"{}\"", query),
+            message: tool_text!("exa.code_search", query),
         })
     }
 }
@@ -399,7 +403,7 @@ impl Tool for ExaCompanyResearchTool {
                 "company": company_name,
                 "content": content_text
             }),
-            message: format!("Recherche entreprise: {}", company_name),
+            message: tool_text!("exa.company_research", company_name),
         })
     }
 }
@@ -485,7 +489,7 @@ impl Tool for ExaDeepResearchStartTool {
                 "task_info": extracted_id,
                 "status": "started"
             }),
-            message: format!("Recherche approfondie démarrée pour: {}", query),
+            message: tool_text!("exa.deep_research_start", query),
         })
     }
 }
@@ -567,7 +571,7 @@ impl Tool for ExaDeepResearchCheckTool {
                 "status": status,
                 "content": content_text
             }),
-            message: format!("Statut recherche: {}", status),
+            message: tool_text!("exa.deep_research_check", status),
         })
     }
 }
@@ -639,7 +643,7 @@ impl Tool for ExaCrawlTool {
                 "url": url,
                 "content": content_text
             }),
-            message: format!("Contenu extrait de: {}", url),
+            message: tool_text!("exa.web_crawl", url),
         })
     }
 }
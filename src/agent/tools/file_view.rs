@@ -0,0 +1,509 @@
+//! Streaming views of large files for `file_read`
+//!
+//! Files above `SMART_VIEW_THRESHOLD` are never loaded whole: they are scanned
+//! in fixed-size chunks, keeping only the head, a ring buffer for the tail and
+//! the lines matching an optional pattern. Every kept line is capped, so memory
+//! and output stay bounded whatever the file size or line length.
+//!
+//! Binary files get a hex dump instead, and text in other encodings (UTF-16
+//! with a BOM, Latin-1) is transcoded to UTF-8.
+
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::path::Path;
+
+use super::compute_line_hash;
+
+/// Files larger than this use the smart view by default
+pub const SMART_VIEW_THRESHOLD: u64 = 256 * 1024;
+
+/// Lines shown from the start and the end of the file
+pub const DEFAULT_EDGE_LINES: usize = 40;
+
+/// Lines of context around each match
+pub const DEFAULT_CONTEXT_LINES: usize = 2;
+
+/// Matches shown (all matches are still counted)
+pub const MAX_MATCHES: usize = 50;
+
+/// Lines returned by a single range read on a large file
+pub const MAX_RANGE_LINES: usize = 2000;
+
+/// Bytes kept per line while scanning (matching only sees these)
+const MAX_LINE_BYTES: usize = 2048;
+
+/// Characters shown per line in the output
+const MAX_DISPLAY_CHARS: usize = 300;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A kept line: 1-indexed number and (possibly truncated) text
+pub type NumberedLine = (usize, String);
+
+#[derive(Debug, Clone)]
+pub struct SmartViewOptions {
+    pub head: usize,
+    pub tail: usize,
+    pub pattern: Option<Regex>,
+    pub context: usize,
+    pub max_matches: usize,
+}
+
+impl Default for SmartViewOptions {
+    fn default() -> Self {
+        Self {
+            head: DEFAULT_EDGE_LINES,
+            tail: DEFAULT_EDGE_LINES,
+            pattern: None,
+            context: DEFAULT_CONTEXT_LINES,
+            max_matches: MAX_MATCHES,
+        }
+    }
+}
+
+/// Summary of a file built in a single streaming pass
+#[derive(Debug, Default)]
+pub struct SmartView {
+    pub total_lines: usize,
+    pub total_bytes: u64,
+    pub head: Vec<NumberedLine>,
+    pub tail: Vec<NumberedLine>,
+    /// Matching lines with their context, one group per contiguous block
+    pub match_groups: Vec<Vec<NumberedLine>>,
+    /// Total number of matching lines, including those not shown
+    pub match_count: usize,
+}
+
+/// Call `f` for every line of `reader`, reading it in fixed-size chunks
+///
+/// Lines longer than `MAX_LINE_BYTES` are cut and suffixed with the number of
+/// dropped bytes. Returns `(lines, bytes)` read.
+fn for_each_line<R: Read>(mut reader: R, mut f: impl FnMut(usize, String)) -> io::Result<(usize, u64)> {
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    let mut line: Vec<u8> = Vec::with_capacity(256);
+    let mut dropped = 0usize;
+    let mut line_no = 0usize;
+    let mut bytes = 0u64;
+
+    let mut emit = |line: &mut Vec<u8>, dropped: &mut usize, line_no: &mut usize| {
+        *line_no += 1;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        let mut text = String::from_utf8_lossy(line).into_owned();
+        if *dropped > 0 {
+            text.push_str(&format!(" …[+{} bytes]", dropped));
+        }
+        f(*line_no, text);
+        line.clear();
+        *dropped = 0;
+    };
+
+    loop {
+        let n = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        bytes += n as u64;
+
+        for &byte in &chunk[..n] {
+            if byte == b'\n' {
+                emit(&mut line, &mut dropped, &mut line_no);
+            } else if line.len() < MAX_LINE_BYTES {
+                line.push(byte);
+            } else {
+                dropped += 1;
+            }
+        }
+    }
+
+    if !line.is_empty() || dropped > 0 {
+        emit(&mut line, &mut dropped, &mut line_no);
+    }
+
+    Ok((line_no, bytes))
+}
+
+/// Build the smart view of a file in one pass
+pub fn smart_view<R: Read>(reader: R, options: &SmartViewOptions) -> io::Result<SmartView> {
+    let mut view = SmartView::default();
+    let mut tail: VecDeque<NumberedLine> = VecDeque::with_capacity(options.tail + 1);
+    let mut before: VecDeque<NumberedLine> = VecDeque::with_capacity(options.context + 1);
+    let mut current: Vec<NumberedLine> = Vec::new();
+    let mut after_remaining = 0usize;
+    let mut shown_matches = 0usize;
+
+    let (total_lines, total_bytes) = for_each_line(reader, |n, text| {
+        if n <= options.head {
+            view.head.push((n, text.clone()));
+        }
+
+        if let Some(ref pattern) = options.pattern {
+            let is_match = pattern.is_match(&text);
+            if is_match {
+                view.match_count += 1;
+            }
+
+            if is_match && shown_matches < options.max_matches {
+                if current.is_empty() {
+                    current.extend(before.drain(..));
+                }
+                before.clear();
+                current.push((n, text.clone()));
+                shown_matches += 1;
+                after_remaining = options.context;
+            } else if after_remaining > 0 {
+                current.push((n, text.clone()));
+                after_remaining -= 1;
+            } else {
+                if !current.is_empty() {
+                    view.match_groups.push(std::mem::take(&mut current));
+                }
+                if options.context > 0 {
+                    before.push_back((n, text.clone()));
+                    if before.len() > options.context {
+                        before.pop_front();
+                    }
+                }
+            }
+        }
+
+        if options.tail > 0 {
+            tail.push_back((n, text));
+            if tail.len() > options.tail {
+                tail.pop_front();
+            }
+        }
+    })?;
+
+    if !current.is_empty() {
+        view.match_groups.push(current);
+    }
+
+    view.total_lines = total_lines;
+    view.total_bytes = total_bytes;
+    // Small files: the tail must not repeat lines already in the head
+    view.tail = tail.into_iter().filter(|(n, _)| *n > view.head.len()).collect();
+    Ok(view)
+}
+
+/// Stream lines `start..=end` (1-indexed), capped to `MAX_RANGE_LINES`
+///
+/// Returns the selected lines and the total number of lines in the file.
+pub fn read_range<R: Read>(reader: R, start: usize, end: Option<usize>) -> io::Result<(Vec<NumberedLine>, usize)> {
+    let start = start.max(1);
+    let cap_end = start + MAX_RANGE_LINES - 1;
+    let end = end.map_or(cap_end, |e| e.min(cap_end));
+    let mut lines = Vec::new();
+
+    let (total_lines, _) = for_each_line(reader, |n, text| {
+        if n >= start && n <= end {
+            lines.push((n, text));
+        }
+    })?;
+
+    Ok((lines, total_lines))
+}
+
+/// Open `path` and build its smart view on the blocking thread pool
+pub async fn smart_view_file(path: &Path, options: SmartViewOptions) -> io::Result<SmartView> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || smart_view(std::fs::File::open(&path)?, &options))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Open `path` and stream a line range on the blocking thread pool
+pub async fn read_range_file(path: &Path, start: usize, end: Option<usize>) -> io::Result<(Vec<NumberedLine>, usize)> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || read_range(std::fs::File::open(&path)?, start, end))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Hashline-formatted line, as in the full `file_read` output
+pub fn format_line(n: usize, text: &str) -> String {
+    let display = match text.char_indices().nth(MAX_DISPLAY_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    };
+    format!("{:>4}|{}| {}", n, compute_line_hash(text), display)
+}
+
+fn format_lines(lines: &[NumberedLine]) -> String {
+    lines.iter().map(|(n, text)| format_line(*n, text)).collect::<Vec<_>>().join("\n")
+}
+
+/// Render a smart view for the model, ending with how to read more
+pub fn render_smart_view(view: &SmartView, pattern: Option<&str>) -> String {
+    let mut out = format!(
+        "[Smart view: {} lines, {} bytes. The file is too large to read in full.]\n",
+        view.total_lines, view.total_bytes
+    );
+
+    if !view.head.is_empty() {
+        out.push_str(&format!("\n--- First {} lines ---\n", view.head.len()));
+        out.push_str(&format_lines(&view.head));
+        out.push('\n');
+    }
+
+    if !view.tail.is_empty() {
+        if view.tail[0].0 > view.head.len() + 1 {
+            out.push_str(&format!("\n... {} lines omitted ...\n", view.tail[0].0 - view.head.len() - 1));
+        }
+        out.push_str(&format!("\n--- Last {} lines ---\n", view.tail.len()));
+        out.push_str(&format_lines(&view.tail));
+        out.push('\n');
+    }
+
+    if let Some(pattern) = pattern {
+        let shown: usize = view.match_groups.len();
+        out.push_str(&format!(
+            "\n--- {} matching line(s) for /{}/ in {} block(s) ---\n",
+            view.match_count, pattern, shown
+        ));
+        let blocks: Vec<String> = view.match_groups.iter().map(|g| format_lines(g)).collect();
+        out.push_str(&blocks.join("\n--\n"));
+        if !blocks.is_empty() {
+            out.push('\n');
+        }
+    }
+
+    out.push_str(&format!(
+        "\nTo see more, call file_read again with start_line/end_line (up to {} lines per call) or with a `pattern` to search the file.",
+        MAX_RANGE_LINES
+    ));
+    out
+}
+
+/// Render a streamed range read
+pub fn render_range(lines: &[NumberedLine]) -> String {
+    format_lines(lines)
+}
+
+// ============================================================================
+// Binary and non-UTF-8 files
+// ============================================================================
+
+/// Binary files up to this size are dumped in full
+pub const HEX_DUMP_MAX_BYTES: u64 = 64 * 1024;
+
+/// Bytes dumped from larger binary files, and by default in hex mode
+pub const HEX_PREVIEW_BYTES: usize = 1024;
+
+/// Start of the file looked at to tell text from binary
+pub const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Encoding of a text file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl TextEncoding {
+    pub fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Utf8Bom => "utf-8 (bom)",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+            TextEncoding::Latin1 => "latin-1",
+        }
+    }
+}
+
+/// Encoding of `bytes`, the start of a file, or `None` if it looks binary
+///
+/// Without a BOM or valid UTF-8, text is taken for Latin-1 when it has no NUL
+/// and almost no control characters.
+pub fn detect_encoding(bytes: &[u8]) -> Option<TextEncoding> {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return Some(TextEncoding::Utf8Bom);
+    }
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        return Some(TextEncoding::Utf16Le);
+    }
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        return Some(TextEncoding::Utf16Be);
+    }
+    if bytes.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => return Some(TextEncoding::Utf8),
+        // The sample ends in the middle of a character
+        Err(e) if e.error_len().is_none() => return Some(TextEncoding::Utf8),
+        Err(_) => {}
+    }
+    let controls = bytes
+        .iter()
+        .filter(|&&b| (b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C)) || b == 0x7F)
+        .count();
+    (controls * 100 <= bytes.len()).then_some(TextEncoding::Latin1)
+}
+
+/// `bytes` transcoded to UTF-8, invalid sequences replaced
+pub fn decode(bytes: &[u8], encoding: TextEncoding) -> String {
+    let utf16 = |little_endian: bool| {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|pair| match little_endian {
+                true => u16::from_le_bytes([pair[0], pair[1]]),
+                false => u16::from_be_bytes([pair[0], pair[1]]),
+            })
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    match encoding {
+        TextEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        TextEncoding::Utf8Bom => String::from_utf8_lossy(&bytes[3..]).into_owned(),
+        TextEncoding::Utf16Le => utf16(true),
+        TextEncoding::Utf16Be => utf16(false),
+        TextEncoding::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Hex dump: offset, 16 bytes in hex, then the printable ASCII
+pub fn hex_dump(bytes: &[u8], offset: u64) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(i, row)| {
+            let hex: Vec<String> = row.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = row
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|", offset + i as u64 * 16, hex.join(" "), ascii)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Up to `len` bytes of `path` starting at `offset`
+pub async fn read_bytes_at(path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(io::SeekFrom::Start(offset)).await?;
+    let mut bytes = Vec::with_capacity(len);
+    file.take(len as u64).read_to_end(&mut bytes).await?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// ~4 MB log with an ERROR line every 10,000 lines
+    fn generate_log(lines: usize) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        {
+            let mut writer = io::BufWriter::new(file.as_file_mut());
+            for i in 1..=lines {
+                let level = if i % 10_000 == 0 { "ERROR" } else { "INFO" };
+                writeln!(writer, "2024-01-01T00:00:00Z {} request {} handled in 12ms by worker-7", level, i).unwrap();
+            }
+        }
+        file
+    }
+
+    #[test]
+    fn test_detects_binary_and_other_encodings() {
+        assert_eq!(detect_encoding("héllo\n".as_bytes()), Some(TextEncoding::Utf8));
+        // Cut in the middle of "é"
+        assert_eq!(detect_encoding(&"hé".as_bytes()[..2]), Some(TextEncoding::Utf8));
+        assert_eq!(detect_encoding(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"), None);
+
+        let latin1 = b"caf\xe9 cr\xe8me\n";
+        assert_eq!(detect_encoding(latin1), Some(TextEncoding::Latin1));
+        assert_eq!(decode(latin1, TextEncoding::Latin1), "café crème\n");
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("été".encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(detect_encoding(&utf16), Some(TextEncoding::Utf16Le));
+        assert_eq!(decode(&utf16, TextEncoding::Utf16Le), "été");
+    }
+
+    #[test]
+    fn test_hex_dump_rows() {
+        let dump = hex_dump(b"ABC\x00\x01 xyz0123456789!", 0x20);
+        let rows: Vec<&str> = dump.lines().collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("00000020  41 42 43 00 01 20"));
+        assert!(rows[0].ends_with("|ABC.. xyz0123456|"));
+        assert!(rows[1].starts_with("00000030  37 38 39 21"));
+    }
+
+    #[test]
+    fn test_smart_view_large_file_is_bounded() {
+        let file = generate_log(60_000);
+        let size = file.as_file().metadata().unwrap().len();
+        assert!(size > 3 * 1024 * 1024);
+
+        let options = SmartViewOptions {
+            pattern: Some(Regex::new("ERROR").unwrap()),
+            ..Default::default()
+        };
+        let view = smart_view(std::fs::File::open(file.path()).unwrap(), &options).unwrap();
+
+        assert_eq!(view.total_lines, 60_000);
+        assert_eq!(view.total_bytes, size);
+        assert_eq!(view.head.len(), DEFAULT_EDGE_LINES);
+        assert_eq!(view.tail.len(), DEFAULT_EDGE_LINES);
+        assert_eq!(view.tail.last().unwrap().0, 60_000);
+        assert_eq!(view.match_count, 6);
+        assert_eq!(view.match_groups.len(), 6);
+        // Each group is the match plus two lines of context on each side
+        assert_eq!(view.match_groups[0].len(), 5);
+        assert_eq!(view.match_groups[0][2].0, 10_000);
+
+        let rendered = render_smart_view(&view, Some("ERROR"));
+        assert!(rendered.len() < 32 * 1024, "output is {} bytes", rendered.len());
+        assert!(rendered.contains("60000 lines"));
+        assert!(rendered.contains("request 60000 handled"));
+        assert!(rendered.contains("start_line/end_line"));
+    }
+
+    #[test]
+    fn test_smart_view_caps_huge_lines_and_matches() {
+        // A single 5 MB line never ends up in memory as a whole
+        let line = "x".repeat(5 * 1024 * 1024);
+        let view = smart_view(line.as_bytes(), &SmartViewOptions::default()).unwrap();
+        assert_eq!(view.total_lines, 1);
+        assert!(view.head[0].1.len() < MAX_LINE_BYTES + 64);
+        assert!(view.tail.is_empty());
+        assert!(render_smart_view(&view, None).len() < 1024);
+
+        // Matches beyond the cap are counted but not shown
+        let text = "hit\n".repeat(500);
+        let options = SmartViewOptions {
+            pattern: Some(Regex::new("hit").unwrap()),
+            context: 0,
+            ..Default::default()
+        };
+        let view = smart_view(text.as_bytes(), &options).unwrap();
+        assert_eq!(view.match_count, 500);
+        let shown: usize = view.match_groups.iter().map(|g| g.len()).sum();
+        assert_eq!(shown, MAX_MATCHES);
+    }
+
+    #[test]
+    fn test_read_range_streams_capped_window() {
+        let file = generate_log(60_000);
+        let (lines, total) = read_range(std::fs::File::open(file.path()).unwrap(), 30_000, Some(30_004)).unwrap();
+        assert_eq!(total, 60_000);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].1.contains("request 30000 "));
+
+        let (lines, _) = read_range(std::fs::File::open(file.path()).unwrap(), 1, None).unwrap();
+        assert_eq!(lines.len(), MAX_RANGE_LINES);
+    }
+}
@@ -5,9 +5,144 @@
 
 use async_trait::async_trait;
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::language::tool_text;
+use crate::agent::tools::backup::{with_backup_warning, PendingBackup};
+use crate::agent::tools::dev::content_diff;
+use crate::agent::tools::gitignore::IgnoreRules;
+use crate::agent::tools::{
+    mark_truncated, output_limit_schema, page_results, OutputBudget, Tool, ToolError, ToolResult,
+};
+
+// ============================================================================
+// Workspace sandbox - shared path check of the filesystem, dev and PDF tools
+// ============================================================================
+
+/// Directory the file tools are confined to, when restricted
+static WORKSPACE_SANDBOX: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Confine file tools to `root`, or lift the restriction with `None`
+pub fn set_workspace_sandbox(root: Option<PathBuf>) {
+    let root = root.map(|root| std::fs::canonicalize(&root).unwrap_or(root));
+    *WORKSPACE_SANDBOX.write().unwrap_or_else(|e| e.into_inner()) = root;
+}
+
+/// Root the file tools are confined to, if any
+pub fn workspace_sandbox() -> Option<PathBuf> {
+    WORKSPACE_SANDBOX.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Path given to a file tool, checked against the workspace sandbox
+///
+/// Unrestricted, the path is returned as given. Restricted, relative paths
+/// start from the root and the result is canonical.
+pub fn resolve_and_validate_path(raw: &str) -> Result<PathBuf, ToolError> {
+    match workspace_sandbox() {
+        Some(root) => resolve_within(&root, raw),
+        None => Ok(PathBuf::from(raw)),
+    }
+}
+
+/// `raw` resolved from the canonical `root`, refused if it lands outside
+///
+/// Existing components are canonicalized one at a time, so `..` and symlinks
+/// are followed the way the OS would; the part that does not exist yet (a
+/// file about to be created) is appended as written.
+fn resolve_within(root: &Path, raw: &str) -> Result<PathBuf, ToolError> {
+    let outside = |path: &Path| ToolError::PermissionDenied {
+        rule: Some("workspace".to_string()),
+        message: format!("{} is outside the workspace {}", path.display(), root.display()),
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in root.join(raw).components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                match std::fs::canonicalize(&resolved) {
+                    Ok(canonical) => resolved = canonical,
+                    // A link whose target is missing can't be checked
+                    Err(_) if resolved.is_symlink() => return Err(outside(&resolved)),
+                    Err(_) => {}
+                }
+            }
+        }
+    }
+
+    if resolved.starts_with(root) {
+        Ok(resolved)
+    } else {
+        Err(outside(&resolved))
+    }
+}
+
+/// [`resolve_and_validate_path`] against `root` when a tool was given one,
+/// otherwise against the workspace sandbox
+pub fn resolve_path_in(root: Option<&Path>, raw: &str) -> Result<PathBuf, ToolError> {
+    match root {
+        Some(root) => resolve_within(root, raw),
+        None => resolve_and_validate_path(raw),
+    }
+}
+
+// ============================================================================
+// Change previews - diff shown in the permission prompt before a write
+// ============================================================================
+
+/// Change a file tool call would make, computed before it is approved
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangePreview {
+    pub path: PathBuf,
+    /// Content the diff was computed from (None: no readable file yet)
+    pub before: Option<String>,
+    pub diff: String,
+}
+
+impl ChangePreview {
+    /// Refuse to apply the change if the file moved on since the preview
+    pub async fn check_unchanged(&self) -> Result<(), ToolError> {
+        let current = tokio::fs::read_to_string(&self.path).await.ok();
+        if current == self.before {
+            Ok(())
+        } else {
+            Err(ToolError::ExecutionFailed(tool_text!("file.conflict", self.path.display())))
+        }
+    }
+}
+
+/// Preview of what `file_edit`, `file_multi_edit`, `file_patch_lines`,
+/// `file_write` or `file_create` would write
+///
+/// None for other tools, and for calls that will fail anyway (the tool then
+/// reports why).
+pub async fn preview_change(tool: &str, params: &Value) -> Option<ChangePreview> {
+    if !matches!(tool, "file_edit" | "file_multi_edit" | "file_patch_lines" | "file_write" | "file_create") {
+        return None;
+    }
+    let path = resolve_and_validate_path(params["path"].as_str()?).ok()?;
+    let before = tokio::fs::read_to_string(&path).await.ok();
+    let after = match tool {
+        "file_edit" => edited_content(before.as_deref()?, params).ok()?,
+        "file_multi_edit" => multi_edited_content(before.as_deref()?, &params["edits"]).ok()?.0,
+        "file_patch_lines" => patched_content(before.as_deref()?, params).ok()?.0,
+        _ => {
+            let content = params["content"].as_str()?;
+            match (&before, params["append"].as_bool().unwrap_or(false)) {
+                (Some(existing), true) => format!("{}{}", existing, content),
+                _ => content.to_string(),
+            }
+        }
+    };
+    let diff = content_diff(before.as_deref().unwrap_or_default(), &after);
+    Some(ChangePreview { path, before, diff })
+}
 
 // ============================================================================
 // FileEditTool - String replacement editing (like Claude Code's StrReplace)
@@ -68,85 +203,25 @@ impl Tool for FileEditTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("new_string is required".into()))?;
         
+        let path_buf = resolve_and_validate_path(path)?;
+        
         // Hashline mode: line_number + hash provided
         let hashline_mode = params.get("line_number").is_some() && params.get("hash").is_some();
         
-        let content = tokio::fs::read_to_string(path)
+        let content = tokio::fs::read_to_string(&path_buf)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire le fichier: {}", e)))?;
+            .map_err(|e| ToolError::io(path, tool_text!("file.read_error", e), &e))?;
 
-        let new_content = if hashline_mode {
-            // Hashline mode: edit by line number + hash
-            let line_number = params["line_number"]
-                .as_u64()
-                .ok_or_else(|| ToolError::InvalidParameters("line_number must be a number".into()))? as usize;
-            let hash = params["hash"]
-                .as_str()
-                .ok_or_else(|| ToolError::InvalidParameters("hash must be a string".into()))?;
-            
-            let lines: Vec<&str> = content.lines().collect();
-            let line_idx = line_number.saturating_sub(1);
-            
-            if line_idx >= lines.len() {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "Line {} does not exist (file has {} lines)", line_number, lines.len()
-                )));
-            }
-            
-            let target_line = lines[line_idx];
-            
-            // Compute hash of current line content (without the hash prefix)
-            let current_hash = compute_line_hash(target_line);
-            if current_hash != hash {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "Hash mismatch! Expected '{}' but found '{}'. The line content has changed since file_read.",
-                    hash, current_hash
-                )));
-            }
-            
-            // Replace the line
-            let mut new_lines: Vec<&str> = lines.clone();
-            new_lines[line_idx] = new_string;
-            new_lines.join("\n")
-        } else {
-            // Classic str_replace mode
-            let old_string = params["old_string"]
-                .as_str()
-                .ok_or_else(|| ToolError::InvalidParameters("old_string is required (or use hashline mode with line_number + hash)".into()))?;
-            let replace_all = params["replace_all"].as_bool().unwrap_or(false);
-
-            if old_string == new_string {
-                return Err(ToolError::InvalidParameters(
-                    "old_string and new_string must be different".into(),
-                ));
-            }
+        let new_content = edited_content(&content, &params)?;
 
-            let count = content.matches(old_string).count();
-            if count == 0 {
-                return Err(ToolError::ExecutionFailed(
-                    "old_string introuvable dans le fichier. Vérifiez l'indentation et les espaces.".into(),
-                ));
-            }
-            if count > 1 && !replace_all {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "old_string trouvé {} fois. Ajoutez plus de contexte pour le rendre unique, ou utilisez replace_all=true.",
-                    count
-                )));
-            }
-
-            if replace_all {
-                content.replace(old_string, new_string)
-            } else {
-                content.replacen(old_string, new_string, 1)
-            }
-        };
-
-        tokio::fs::write(path, &new_content)
+        let backup = PendingBackup::capture(&path_buf).await;
+        tokio::fs::write(&path_buf, &new_content)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible d'écrire le fichier: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file.write_error", e)))?;
+        let backup_warning = backup.commit(self.name());
 
         let count = new_content.matches(new_string).count();
-        Ok(ToolResult {
+        Ok(with_backup_warning(ToolResult {
             success: true,
             data: serde_json::json!({
                 "path": path,
@@ -154,13 +229,439 @@ impl Tool for FileEditTool {
                 "mode": if hashline_mode { "hashline" } else { "str_replace" },
                 "total_lines": new_content.lines().count()
             }),
-            message: format!(
-                "Fichier édité: {} (1 remplacement, mode: {})",
+            message: tool_text!(
+                "file_edit.done",
                 path,
                 if hashline_mode { "hashline" } else { "str_replace" }
             ),
+        }, backup_warning))
+    }
+}
+
+/// Content of the file after a `file_edit` call with `params`
+///
+/// Shared by the tool and its change preview, so an approved preview is
+/// exactly what gets written.
+fn edited_content(content: &str, params: &Value) -> Result<String, ToolError> {
+    let new_string = params["new_string"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("new_string is required".into()))?;
+    let hashline_mode = params.get("line_number").is_some() && params.get("hash").is_some();
+
+    if hashline_mode {
+        // Hashline mode: edit by line number + hash
+        let line_number = params["line_number"]
+            .as_u64()
+            .ok_or_else(|| ToolError::InvalidParameters("line_number must be a number".into()))? as usize;
+        let hash = params["hash"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("hash must be a string".into()))?;
+        
+        let lines: Vec<&str> = content.lines().collect();
+        let line_idx = line_number.saturating_sub(1);
+        
+        if line_idx >= lines.len() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Line {} does not exist (file has {} lines)", line_number, lines.len()
+            )));
+        }
+        
+        let target_line = lines[line_idx];
+        
+        // Compute hash of current line content (without the hash prefix)
+        let current_hash = compute_line_hash(target_line);
+        if current_hash != hash {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Hash mismatch! Expected '{}' but found '{}'. The line content has changed since file_read.",
+                hash, current_hash
+            )));
+        }
+        
+        // Replace the line
+        let mut new_lines: Vec<&str> = lines.clone();
+        new_lines[line_idx] = new_string;
+        new_lines.join("\n")
+    } else {
+        // Classic str_replace mode
+        let old_string = params["old_string"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("old_string is required (or use hashline mode with line_number + hash)".into()))?;
+        let replace_all = params["replace_all"].as_bool().unwrap_or(false);
+
+        if old_string == new_string {
+            return Err(ToolError::InvalidParameters(
+                "old_string and new_string must be different".into(),
+            ));
+        }
+
+        let count = content.matches(old_string).count();
+        if count == 0 {
+            return Err(ToolError::ExecutionFailed(tool_text!("file_edit.not_found").into()));
+        }
+        if count > 1 && !replace_all {
+            return Err(ToolError::ExecutionFailed(tool_text!("file_edit.ambiguous", count)));
+        }
+
+        if replace_all {
+            content.replace(old_string, new_string)
+        } else {
+            content.replacen(old_string, new_string, 1)
+        }
+    }
+}
+
+// ============================================================================
+// FileMultiEditTool - Several string replacements in one file, all or nothing
+// ============================================================================
+
+pub struct FileMultiEditTool;
+
+#[async_trait]
+impl Tool for FileMultiEditTool {
+    fn name(&self) -> &str {
+        "file_multi_edit"
+    }
+
+    fn description(&self) -> &str {
+        "Apply several exact string replacements to one file in a single call. Edits are applied in order; if any old_string does not match (or is not unique without replace_all), nothing is written and the failing edits are listed. Prefer this over repeated file_edit calls on the same file. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the file to edit"
+                },
+                "edits": {
+                    "type": "array",
+                    "description": "Replacements to apply, in order",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "old_string": {
+                                "type": "string",
+                                "description": "Exact string to find (must be unique in file unless replace_all=true)"
+                            },
+                            "new_string": {
+                                "type": "string",
+                                "description": "Replacement string"
+                            },
+                            "replace_all": {
+                                "type": "boolean",
+                                "description": "Replace ALL occurrences (default: false)",
+                                "default": false
+                            }
+                        },
+                        "required": ["old_string", "new_string"]
+                    }
+                }
+            },
+            "required": ["path", "edits"]
         })
     }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+
+        let path_buf = resolve_and_validate_path(path)?;
+
+        let content = tokio::fs::read_to_string(&path_buf)
+            .await
+            .map_err(|e| ToolError::io(path, tool_text!("file.read_error", e), &e))?;
+
+        let (new_content, counts) = multi_edited_content(&content, &params["edits"])?;
+
+        let backup = PendingBackup::capture(&path_buf).await;
+        tokio::fs::write(&path_buf, &new_content)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file.write_error", e)))?;
+        let backup_warning = backup.commit(self.name());
+
+        let total_lines = new_content.lines().count();
+        let per_edit = counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| tool_text!("file_multi_edit.count", i + 1, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(with_backup_warning(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path,
+                "edits": counts.len(),
+                "replacements": counts,
+                "total_lines": total_lines
+            }),
+            message: tool_text!(
+                "file_multi_edit.done",
+                path,
+                counts.len(),
+                per_edit,
+                total_lines
+            ),
+        }, backup_warning))
+    }
+}
+
+/// Content of the file after a `file_multi_edit` call, with the replacement
+/// count of each edit
+///
+/// Every edit is first matched against the original content so all the
+/// mismatches are reported at once; the edits are then applied in order.
+fn multi_edited_content(content: &str, edits: &Value) -> Result<(String, Vec<usize>), ToolError> {
+    let edits = edits
+        .as_array()
+        .filter(|edits| !edits.is_empty())
+        .ok_or_else(|| ToolError::InvalidParameters("edits must be a non-empty array".into()))?;
+
+    let mut parsed = Vec::with_capacity(edits.len());
+    for (i, edit) in edits.iter().enumerate() {
+        let old_string = edit["old_string"]
+            .as_str()
+            .filter(|old| !old.is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters(format!("edits[{}].old_string is required", i)))?;
+        let new_string = edit["new_string"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters(format!("edits[{}].new_string is required", i)))?;
+        if old_string == new_string {
+            return Err(ToolError::InvalidParameters(format!(
+                "edits[{}]: old_string and new_string must be different",
+                i
+            )));
+        }
+        parsed.push((old_string, new_string, edit["replace_all"].as_bool().unwrap_or(false)));
+    }
+
+    let mismatches: Vec<String> = parsed
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (old_string, _, replace_all))| match content.matches(old_string).count() {
+            0 => Some(tool_text!("file_multi_edit.not_found", i + 1)),
+            count if count > 1 && !replace_all => Some(tool_text!("file_multi_edit.ambiguous", i + 1, count)),
+            _ => None,
+        })
+        .collect();
+    if !mismatches.is_empty() {
+        return Err(ToolError::ExecutionFailed(tool_text!(
+            "file_multi_edit.mismatches",
+            mismatches.len(),
+            parsed.len(),
+            mismatches.join("\n")
+        )));
+    }
+
+    let mut edited = content.to_string();
+    let mut counts = Vec::with_capacity(parsed.len());
+    for (i, (old_string, new_string, replace_all)) in parsed.into_iter().enumerate() {
+        // An earlier edit may have rewritten the text this one targets
+        let count = edited.matches(old_string).count();
+        if count == 0 || (count > 1 && !replace_all) {
+            return Err(ToolError::ExecutionFailed(tool_text!("file_multi_edit.stale", i + 1, count)));
+        }
+        if replace_all {
+            edited = edited.replace(old_string, new_string);
+            counts.push(count);
+        } else {
+            edited = edited.replacen(old_string, new_string, 1);
+            counts.push(1);
+        }
+    }
+    Ok((edited, counts))
+}
+
+// ============================================================================
+// FilePatchLinesTool - Replace or insert lines by number
+// ============================================================================
+
+pub struct FilePatchLinesTool;
+
+#[async_trait]
+impl Tool for FilePatchLinesTool {
+    fn name(&self) -> &str {
+        "file_patch_lines"
+    }
+
+    fn description(&self) -> &str {
+        "Replace an inclusive 1-indexed line range of a file with new content, or insert lines before `insert_at` (or with start_line = end_line + 1). Use when file_edit's exact matching fails on whitespace. Pass the range_sha256 from file_read as expected_hash to refuse stale edits. Empty new_content deletes the range. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the file to patch"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to replace (1-indexed)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to replace, inclusive (default: start_line). start_line - 1 inserts before start_line."
+                },
+                "insert_at": {
+                    "type": "integer",
+                    "description": "Insert new_content before this line instead of replacing (total lines + 1 appends). Use instead of start_line/end_line."
+                },
+                "new_content": {
+                    "type": "string",
+                    "description": "Replacement lines"
+                },
+                "expected_hash": {
+                    "type": "string",
+                    "description": "range_sha256 of the same range from file_read; the patch is refused if the lines changed since"
+                }
+            },
+            "required": ["path", "new_content"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+
+        let path_buf = resolve_and_validate_path(path)?;
+
+        let content = tokio::fs::read_to_string(&path_buf)
+            .await
+            .map_err(|e| ToolError::io(path, tool_text!("file.read_error", e), &e))?;
+
+        let (new_content, patch) = patched_content(&content, &params)?;
+
+        let backup = PendingBackup::capture(&path_buf).await;
+        tokio::fs::write(&path_buf, &new_content)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file.write_error", e)))?;
+        let backup_warning = backup.commit(self.name());
+
+        let delta = patch.inserted as i64 - patch.removed as i64;
+        let what = if patch.removed == 0 {
+            tool_text!("file_patch_lines.inserted", patch.inserted, patch.start)
+        } else {
+            tool_text!("file_patch_lines.replaced", patch.start, patch.start + patch.removed - 1)
+        };
+        Ok(with_backup_warning(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path,
+                "start_line": patch.start,
+                "lines_removed": patch.removed,
+                "lines_inserted": patch.inserted,
+                "line_delta": delta,
+                "total_lines": patch.total_lines
+            }),
+            message: tool_text!("file_patch_lines.done", path, what, format!("{:+}", delta), patch.total_lines),
+        }, backup_warning))
+    }
+}
+
+/// sha256 of a line range, as `file_read` reports it (`range_sha256`) and
+/// `file_patch_lines` checks it: the lines joined with `\n`, hex encoded
+pub fn range_hash<S: AsRef<str>>(lines: &[S]) -> String {
+    use sha2::{Digest, Sha256};
+    let joined = lines.iter().map(AsRef::as_ref).collect::<Vec<_>>().join("\n");
+    Sha256::digest(joined.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Lines touched by a `file_patch_lines` call
+#[derive(Debug, PartialEq)]
+struct LinePatch {
+    /// First line replaced, or the line the insertion goes before
+    start: usize,
+    removed: usize,
+    inserted: usize,
+    total_lines: usize,
+}
+
+/// Content of the file after a `file_patch_lines` call
+fn patched_content(content: &str, params: &Value) -> Result<(String, LinePatch), ToolError> {
+    let new_content = params["new_content"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("new_content is required".into()))?;
+    let line_param = |name: &str| -> Result<Option<usize>, ToolError> {
+        match &params[name] {
+            Value::Null => Ok(None),
+            value => value
+                .as_u64()
+                .map(|n| Some(n as usize))
+                .ok_or_else(|| ToolError::InvalidParameters(format!("{} must be a positive integer", name))),
+        }
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let total = lines.len();
+
+    // Replaced range as [start, end), 0-indexed
+    let (start, end) = match (line_param("insert_at")?, line_param("start_line")?) {
+        (Some(at), _) => {
+            if at == 0 || at > total + 1 {
+                return Err(ToolError::InvalidParameters(format!(
+                    "insert_at {} is out of range: the file has {} lines, use 1 to {} ({} appends)",
+                    at, total, total + 1, total + 1
+                )));
+            }
+            (at - 1, at - 1)
+        }
+        (None, Some(start_line)) => {
+            let end_line = line_param("end_line")?.unwrap_or(start_line);
+            if start_line == 0 {
+                return Err(ToolError::InvalidParameters("start_line is 1-indexed, use 1 for the first line".into()));
+            }
+            if end_line + 1 < start_line {
+                return Err(ToolError::InvalidParameters(format!(
+                    "end_line {} is before start_line {}: use end_line >= start_line to replace, or end_line = start_line - 1 to insert",
+                    end_line, start_line
+                )));
+            }
+            if end_line > total || start_line > total + 1 {
+                return Err(ToolError::InvalidParameters(format!(
+                    "lines {}-{} are out of range: the file has {} lines",
+                    start_line, end_line, total
+                )));
+            }
+            (start_line - 1, end_line)
+        }
+        (None, None) => {
+            return Err(ToolError::InvalidParameters("start_line (or insert_at) is required".into()));
+        }
+    };
+
+    if let Some(expected) = params["expected_hash"].as_str().filter(|_| end > start) {
+        let current = range_hash(&lines[start..end]);
+        if !current.eq_ignore_ascii_case(expected.trim()) {
+            return Err(ToolError::ExecutionFailed(tool_text!(
+                "file_patch_lines.hash_mismatch",
+                start + 1, end, expected, current
+            )));
+        }
+    }
+
+    let replacement: Vec<&str> = new_content.lines().collect();
+    let mut patched: Vec<&str> = Vec::with_capacity(total - (end - start) + replacement.len());
+    patched.extend_from_slice(&lines[..start]);
+    patched.extend_from_slice(&replacement);
+    patched.extend_from_slice(&lines[end..]);
+
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut result = patched.join(newline);
+    if content.ends_with('\n') && !result.is_empty() {
+        result.push_str(newline);
+    }
+
+    let patch = LinePatch {
+        start: start + 1,
+        removed: end - start,
+        inserted: replacement.len(),
+        total_lines: patched.len(),
+    };
+    Ok((result, patch))
 }
 
 /// Compute hash for a line (must match the one in tools.rs)
@@ -220,14 +721,11 @@ impl Tool for FileCreateTool {
             .ok_or_else(|| ToolError::InvalidParameters("content is required".into()))?;
         let overwrite = params["overwrite"].as_bool().unwrap_or(false);
 
-        let path_buf = PathBuf::from(path);
+        let path_buf = resolve_and_validate_path(path)?;
 
         // Check if file already exists
         if path_buf.exists() && !overwrite {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Le fichier '{}' existe déjà. Utilisez overwrite=true pour écraser, ou file_edit pour modifier.",
-                path
-            )));
+            return Err(ToolError::ExecutionFailed(tool_text!("file_create.exists", path)));
         }
 
         // Create parent directories
@@ -235,18 +733,20 @@ impl Tool for FileCreateTool {
             if !parent.exists() {
                 tokio::fs::create_dir_all(parent)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier parent: {}", e)))?;
+                    .map_err(|e| ToolError::ExecutionFailed(tool_text!("dir.create_error", e)))?;
             }
         }
 
+        let backup = PendingBackup::capture(&path_buf).await;
         tokio::fs::write(&path_buf, content)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le fichier: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file_create.error", e)))?;
+        let backup_warning = backup.commit(self.name());
 
         let lines = content.lines().count();
         let bytes = content.len();
 
-        Ok(ToolResult {
+        Ok(with_backup_warning(ToolResult {
             success: true,
             data: serde_json::json!({
                 "path": path,
@@ -254,8 +754,8 @@ impl Tool for FileCreateTool {
                 "lines": lines,
                 "created": true
             }),
-            message: format!("Fichier créé: {} ({} lignes, {} octets)", path, lines, bytes),
-        })
+            message: tool_text!("file_create.done", path, lines, bytes),
+        }, backup_warning))
     }
 }
 
@@ -299,46 +799,45 @@ impl Tool for FileDeleteTool {
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
         let recursive = params["recursive"].as_bool().unwrap_or(false);
 
-        let path_buf = PathBuf::from(path);
+        let path_buf = resolve_and_validate_path(path)?;
 
         if !path_buf.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Le chemin '{}' n'existe pas",
-                path
-            )));
+            return Err(ToolError::NotFound {
+                path: path.to_string(),
+                message: tool_text!("path.not_found", path),
+            });
         }
 
         if path_buf.is_file() {
+            let backup = PendingBackup::capture(&path_buf).await;
             tokio::fs::remove_file(&path_buf)
                 .await
-                .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de supprimer: {}", e)))?;
+                .map_err(|e| ToolError::io(path, tool_text!("file_delete.error", e), &e))?;
+            let backup_warning = backup.commit(self.name());
 
-            Ok(ToolResult {
+            Ok(with_backup_warning(ToolResult {
                 success: true,
                 data: serde_json::json!({ "path": path, "type": "file" }),
-                message: format!("Fichier supprimé: {}", path),
-            })
+                message: tool_text!("file_delete.file_done", path),
+            }, backup_warning))
         } else if path_buf.is_dir() {
             if recursive {
                 tokio::fs::remove_dir_all(&path_buf)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de supprimer le dossier: {}", e)))?;
+                    .map_err(|e| ToolError::ExecutionFailed(tool_text!("file_delete.dir_error", e)))?;
             } else {
                 tokio::fs::remove_dir(&path_buf)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Dossier non vide. Utilisez recursive=true: {}", e)))?;
+                    .map_err(|e| ToolError::ExecutionFailed(tool_text!("file_delete.not_empty", e)))?;
             }
 
             Ok(ToolResult {
                 success: true,
                 data: serde_json::json!({ "path": path, "type": "directory", "recursive": recursive }),
-                message: format!("Dossier supprimé: {}", path),
+                message: tool_text!("file_delete.dir_done", path),
             })
         } else {
-            Err(ToolError::ExecutionFailed(format!(
-                "Type de chemin non supporté: {}",
-                path
-            )))
+            Err(ToolError::ExecutionFailed(tool_text!("path.unsupported", path)))
         }
     }
 }
@@ -384,21 +883,18 @@ impl Tool for FileMoveTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("destination is required".into()))?;
 
-        let src = PathBuf::from(source);
-        let dst = PathBuf::from(destination);
+        let src = resolve_and_validate_path(source)?;
+        let dst = resolve_and_validate_path(destination)?;
 
         if !src.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Source '{}' n'existe pas",
-                source
-            )));
+            return Err(ToolError::NotFound {
+                path: source.to_string(),
+                message: tool_text!("path.source_not_found", source),
+            });
         }
 
         if dst.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Destination '{}' existe déjà",
-                destination
-            )));
+            return Err(ToolError::ExecutionFailed(tool_text!("path.destination_exists", destination)));
         }
 
         // Create parent directories
@@ -406,13 +902,13 @@ impl Tool for FileMoveTool {
             if !parent.exists() {
                 tokio::fs::create_dir_all(parent)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+                    .map_err(|e| ToolError::ExecutionFailed(tool_text!("dir.create_error", e)))?;
             }
         }
 
         tokio::fs::rename(&src, &dst)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de déplacer: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file_move.error", e)))?;
 
         Ok(ToolResult {
             success: true,
@@ -420,7 +916,7 @@ impl Tool for FileMoveTool {
                 "source": source,
                 "destination": destination
             }),
-            message: format!("Déplacé: {} -> {}", source, destination),
+            message: tool_text!("file_move.done", source, destination),
         })
     }
 }
@@ -459,10 +955,10 @@ impl Tool for FileInfoTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
 
-        let path_buf = PathBuf::from(path);
+        let path_buf = resolve_and_validate_path(path)?;
         let metadata = tokio::fs::metadata(&path_buf)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire les métadonnées: {}", e)))?;
+            .map_err(|e| ToolError::io(path, tool_text!("file_info.error", e), &e))?;
 
         let file_type = if metadata.is_file() {
             "file"
@@ -526,9 +1022,9 @@ impl Tool for FileInfoTool {
                 path,
                 file_type,
                 size_human,
-                if readonly { "lecture seule" } else { "lecture/écriture" },
+                if readonly { tool_text!("file_info.read_only") } else { tool_text!("file_info.read_write") },
                 line_count
-                    .map(|c| format!(", {} lignes", c))
+                    .map(|c| tool_text!("file_info.lines", c))
                     .unwrap_or_default()
             ),
         })
@@ -569,31 +1065,28 @@ impl Tool for DirectoryCreateTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
 
-        let path_buf = PathBuf::from(path);
+        let path_buf = resolve_and_validate_path(path)?;
 
         if path_buf.exists() {
             if path_buf.is_dir() {
                 return Ok(ToolResult {
                     success: true,
                     data: serde_json::json!({ "path": path, "already_existed": true }),
-                    message: format!("Le dossier existe déjà: {}", path),
+                    message: tool_text!("directory_create.exists", path),
                 });
             } else {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "Un fichier existe déjà à ce chemin: {}",
-                    path
-                )));
+                return Err(ToolError::ExecutionFailed(tool_text!("directory_create.file_exists", path)));
             }
         }
 
         tokio::fs::create_dir_all(&path_buf)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("dir.create_error", e)))?;
 
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({ "path": path, "created": true }),
-            message: format!("Dossier créé: {}", path),
+            message: tool_text!("directory_create.done", path),
         })
     }
 }
@@ -639,26 +1132,26 @@ impl Tool for FileCopyTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("destination is required".into()))?;
 
-        let src = PathBuf::from(source);
+        let src = resolve_and_validate_path(source)?;
         if !src.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Source '{}' n'existe pas",
-                source
-            )));
+            return Err(ToolError::NotFound {
+                path: source.to_string(),
+                message: tool_text!("path.source_not_found", source),
+            });
         }
 
-        let dst = PathBuf::from(destination);
+        let dst = resolve_and_validate_path(destination)?;
         if let Some(parent) = dst.parent() {
             if !parent.exists() {
                 tokio::fs::create_dir_all(parent)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+                    .map_err(|e| ToolError::ExecutionFailed(tool_text!("dir.create_error", e)))?;
             }
         }
 
         let bytes = tokio::fs::copy(&src, &dst)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de copier: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file_copy.error", e)))?;
 
         Ok(ToolResult {
             success: true,
@@ -667,7 +1160,7 @@ impl Tool for FileCopyTool {
                 "destination": destination,
                 "bytes": bytes
             }),
-            message: format!("Copié: {} -> {} ({} octets)", source, destination, bytes),
+            message: tool_text!("file_copy.done", source, destination, bytes),
         })
     }
 }
@@ -714,6 +1207,17 @@ impl Tool for FileSearchContentTool {
                     "type": "integer",
                     "description": "Maximum results to return",
                     "default": 30
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Skip this many results (paging, see `continuation`)",
+                    "default": 0
+                },
+                "output_limit": output_limit_schema(),
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Skip paths ignored by .gitignore/.ignore files (default: true)",
+                    "default": true
                 }
             },
             "required": ["query", "path"]
@@ -728,6 +1232,11 @@ impl Tool for FileSearchContentTool {
         let file_pattern = params["file_pattern"].as_str();
         let case_sensitive = params["case_sensitive"].as_bool().unwrap_or(false);
         let max_results = params["max_results"].as_u64().unwrap_or(30) as usize;
+        let offset = params["offset"].as_u64().unwrap_or(0) as usize;
+        let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+        let mut budget = OutputBudget::from_params(&params);
+        // Results kept while searching: the skipped ones plus a page
+        let limit = offset + max_results;
 
         let search_query = if case_sensitive {
             query.to_string()
@@ -735,7 +1244,8 @@ impl Tool for FileSearchContentTool {
             query.to_lowercase()
         };
 
-        let path_buf = PathBuf::from(path);
+        let path_buf = resolve_and_validate_path(path)?;
+        let ignore = IgnoreRules::for_root(&path_buf, respect_gitignore);
         let mut results = Vec::new();
 
         search_content_recursive(
@@ -744,23 +1254,35 @@ impl Tool for FileSearchContentTool {
             case_sensitive,
             file_pattern,
             &mut results,
-            max_results,
+            limit,
+            &ignore,
         )
         .await?;
 
-        let total = results.len();
+        // The search stops at `limit`: reaching it means there may be more
+        let found = if results.len() >= limit { usize::MAX } else { results.len() };
+        let (page, next_offset) = page_results(results, offset, max_results, found, &mut budget);
+        let total = page.len();
+        let mut data = serde_json::json!({
+            "matches": page,
+            "total": total,
+            "query": query
+        });
+        if let Some(next) = next_offset {
+            mark_truncated(&mut data, serde_json::json!({ "offset": next }));
+        }
         Ok(ToolResult {
             success: true,
-            data: serde_json::json!({
-                "matches": results,
-                "total": total,
-                "query": query
-            }),
-            message: format!("{} résultat(s) pour \"{}\"", total, query),
+            data,
+            message: tool_text!("file_search.done", total, query,
+                if next_offset.is_some() { tool_text!("grep.truncated") } else { "" }),
         })
     }
 }
 
+/// Bytes kept of each matching line
+const MAX_MATCH_LINE_CHARS: usize = 300;
+
 fn search_content_recursive<'a>(
     path: &'a PathBuf,
     query: &'a str,
@@ -768,6 +1290,7 @@ fn search_content_recursive<'a>(
     file_pattern: Option<&'a str>,
     results: &'a mut Vec<Value>,
     max_results: usize,
+    ignore: &'a IgnoreRules,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), ToolError>> + Send + 'a>> {
     Box::pin(async move {
         if results.len() >= max_results {
@@ -797,7 +1320,7 @@ fn search_content_recursive<'a>(
                         results.push(serde_json::json!({
                             "file": path.display().to_string(),
                             "line_number": i + 1,
-                            "content": line.trim()
+                            "content": crate::truncate_str(line.trim(), MAX_MATCH_LINE_CHARS)
                         }));
                     }
                 }
@@ -813,21 +1336,26 @@ fn search_content_recursive<'a>(
                     break;
                 }
                 let name = entry.file_name().to_string_lossy().to_string();
+                let entry_path = entry.path();
+                let is_dir = entry.file_type().await.map(|ft| ft.is_dir()).unwrap_or(false);
                 if name.starts_with('.')
                     || name == "node_modules"
                     || name == "target"
                     || name == "__pycache__"
                     || name == ".git"
+                    || ignore.is_ignored(&entry_path, is_dir)
                 {
                     continue;
                 }
+                let child_ignore = if is_dir { ignore.child(&entry_path) } else { ignore.clone() };
                 search_content_recursive(
-                    &entry.path(),
+                    &entry_path,
                     query,
                     case_sensitive,
                     file_pattern,
                     results,
                     max_results,
+                    &child_ignore,
                 )
                 .await?;
             }
@@ -840,7 +1368,7 @@ fn search_content_recursive<'a>(
 // Helpers
 // ============================================================================
 
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -855,3 +1383,164 @@ fn format_size(bytes: u64) -> String {
         format!("{} B", bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        (dir, root)
+    }
+
+    #[test]
+    fn test_sandbox_rejects_parent_traversal() {
+        let (_dir, root) = workspace();
+        assert_eq!(resolve_within(&root, "src/main.rs").unwrap(), root.join("src/main.rs"));
+        assert_eq!(resolve_within(&root, "src/../new/file.txt").unwrap(), root.join("new/file.txt"));
+
+        let err = resolve_within(&root, "src/../../outside.txt").unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied { .. }));
+        assert!(err.to_string().contains(&root.parent().unwrap().join("outside.txt").display().to_string()));
+        assert!(resolve_within(&root, "new/../../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_sandbox_rejects_absolute_paths_outside() {
+        let (_dir, root) = workspace();
+        let inside = root.join("src/main.rs");
+        assert_eq!(resolve_within(&root, inside.to_str().unwrap()).unwrap(), inside);
+        let outside = std::env::temp_dir().join("clawrs-sandbox-outside.txt");
+        assert!(resolve_within(&root, outside.to_str().unwrap()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sandbox_follows_symlinks() {
+        let (_dir, root) = workspace();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret"), "key").unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.join("escape")).unwrap();
+        std::os::unix::fs::symlink(root.join("src"), root.join("code")).unwrap();
+        std::os::unix::fs::symlink(outside.path().join("missing"), root.join("dangling")).unwrap();
+
+        assert!(resolve_within(&root, "escape/secret").is_err());
+        assert!(resolve_within(&root, "escape/new.txt").is_err());
+        assert!(resolve_within(&root, "dangling").is_err());
+        // Links that stay inside are fine
+        assert_eq!(resolve_within(&root, "code/main.rs").unwrap(), root.join("src/main.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_preview_matches_edit_and_detects_conflicts() {
+        let (_dir, root) = workspace();
+        let file = root.join("src/main.rs");
+        let params = serde_json::json!({
+            "path": file.to_str().unwrap(),
+            "old_string": "fn main() {}",
+            "new_string": "fn main() { run() }",
+        });
+        let preview = preview_change("file_edit", &params).await.unwrap();
+        assert_eq!(preview.diff, "-fn main() {}\n+fn main() { run() }");
+        assert!(preview.check_unchanged().await.is_ok());
+
+        std::fs::write(&file, "fn main() { other() }").unwrap();
+        let err = preview.check_unchanged().await.unwrap_err();
+        assert!(err.to_string().contains("Conflict"));
+
+        let created = serde_json::json!({"path": root.join("new.txt").to_str().unwrap(), "content": "hello"});
+        assert_eq!(preview_change("file_write", &created).await.unwrap().diff, "+hello");
+        assert!(preview_change("file_read", &created).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_is_all_or_nothing() {
+        let (_dir, root) = workspace();
+        let file = root.join("src/lib.rs");
+        let original = "let a = 1;\nlet b = 2;\nlet b2 = 2;\n";
+        std::fs::write(&file, original).unwrap();
+
+        let params = serde_json::json!({
+            "path": file.to_str().unwrap(),
+            "edits": [
+                { "old_string": "let a = 1;", "new_string": "let a = 10;" },
+                { "old_string": "= 2;", "new_string": "= 20;" },
+                { "old_string": "missing", "new_string": "x" }
+            ]
+        });
+        let err = FileMultiEditTool.execute(params).await.unwrap_err().to_string();
+        assert!(err.contains("edit 2: old_string found 2 times"));
+        assert!(err.contains("edit 3: old_string not found"));
+        assert!(!err.contains("edit 1"));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), original);
+
+        let params = serde_json::json!({
+            "path": file.to_str().unwrap(),
+            "edits": [
+                { "old_string": "let a = 1;", "new_string": "let a = 10;" },
+                { "old_string": "= 2;", "new_string": "= 20;", "replace_all": true }
+            ]
+        });
+        let result = FileMultiEditTool.execute(params).await.unwrap();
+        assert_eq!(result.data["replacements"], serde_json::json!([1, 2]));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "let a = 10;\nlet b = 20;\nlet b2 = 20;\n"
+        );
+    }
+
+    #[test]
+    fn test_patch_lines_replaces_inserts_and_checks_ranges() {
+        let content = "a\nb\nc\nd\n";
+        let patch = |params: Value| patched_content(content, &params);
+
+        let (patched, info) = patch(serde_json::json!({
+            "new_content": "B1\nB2\nB3", "start_line": 2, "end_line": 3,
+            "expected_hash": range_hash(&["b", "c"])
+        }))
+        .unwrap();
+        assert_eq!(patched, "a\nB1\nB2\nB3\nd\n");
+        assert_eq!((info.removed, info.inserted, info.total_lines), (2, 3, 5));
+
+        // Insertions, explicit and as an empty range
+        let (patched, _) = patch(serde_json::json!({ "new_content": "x", "insert_at": 5 })).unwrap();
+        assert_eq!(patched, "a\nb\nc\nd\nx\n");
+        let (patched, info) = patch(serde_json::json!({ "new_content": "x", "start_line": 2, "end_line": 1 })).unwrap();
+        assert_eq!(patched, "a\nx\nb\nc\nd\n");
+        assert_eq!(info.removed, 0);
+
+        // Deleting a range
+        let (patched, _) = patch(serde_json::json!({ "new_content": "", "start_line": 1, "end_line": 2 })).unwrap();
+        assert_eq!(patched, "c\nd\n");
+
+        assert!(matches!(
+            patch(serde_json::json!({ "new_content": "x", "start_line": 3, "end_line": 9 })),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            patch(serde_json::json!({ "new_content": "x", "insert_at": 0 })),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        let stale = patch(serde_json::json!({
+            "new_content": "x", "start_line": 1, "expected_hash": range_hash(&["old"])
+        }));
+        assert!(stale.unwrap_err().to_string().contains("Hash mismatch"));
+    }
+
+    #[test]
+    fn test_sandbox_drive_letter_paths() {
+        let (_dir, root) = workspace();
+        for raw in ["C:\\Windows\\System32\\drivers\\etc\\hosts", "D:/secrets.txt", "C:..\\outside.txt"] {
+            match resolve_within(&root, raw) {
+                // On Windows these name other drives or leave the root
+                Err(ToolError::PermissionDenied { .. }) => assert!(cfg!(windows), "{} refused", raw),
+                // Elsewhere they are plain file names inside the root
+                Ok(path) => assert!(path.starts_with(&root), "{} resolved to {}", raw, path.display()),
+                Err(e) => panic!("{}: {}", raw, e),
+            }
+        }
+    }
+}
@@ -2,18 +2,38 @@
 //!
 //! Provides git operations by shelling out to the git CLI.
 
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use serde_json::Value;
 use tokio::process::Command;
+use tokio::sync::Mutex;
+use uuid::Uuid;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::language::tool_text;
+use crate::agent::prompts::{build_pr_description_prompt, generate_commit_message};
+use crate::agent::tools::{mark_truncated, output_limit_schema, OutputBudget, Tool, ToolError, ToolResult};
+use crate::inference::engine::LlamaEngine;
 
 /// Helper to run git commands
 async fn run_git(args: &[&str], working_dir: Option<&str>) -> Result<(String, String, bool), ToolError> {
+    run_git_env(args, working_dir, &[]).await
+}
+
+/// `run_git` with extra environment variables
+async fn run_git_env(
+    args: &[&str],
+    working_dir: Option<&str>,
+    envs: &[(&str, &OsStr)],
+) -> Result<(String, String, bool), ToolError> {
     let mut cmd = Command::new("git");
     for arg in args {
         cmd.arg(arg);
     }
+    cmd.envs(envs.iter().copied());
     if let Some(dir) = working_dir {
         cmd.current_dir(dir);
     }
@@ -21,7 +41,7 @@ async fn run_git(args: &[&str], working_dir: Option<&str>) -> Result<(String, St
     let output = cmd
         .output()
         .await
-        .map_err(|e| ToolError::ExecutionFailed(format!("Impossible d'exécuter git: {}", e)))?;
+        .map_err(|e| ToolError::ExecutionFailed(tool_text!("git.run_error", e)))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -68,9 +88,7 @@ impl Tool for GitStatusTool {
                 "status_porcelain": status_out,
                 "status_short": status_short,
             }),
-            message: format!("Branche: {} | {} fichier(s) modifié(s)", 
-                branch.trim(), 
-                status_short.lines().count()),
+            message: tool_text!("git_status.done", branch.trim(), status_short.lines().count()),
         })
     }
 }
@@ -160,7 +178,7 @@ impl Tool for GitDiffTool {
                 "stat": stat_out,
                 "diff": diff_display,
             }),
-            message: format!("Diff: {} fichier(s) modifié(s)", stat_out.lines().count().saturating_sub(1)),
+            message: tool_text!("git_diff.done", stat_out.lines().count().saturating_sub(1)),
         })
     }
 }
@@ -264,7 +282,7 @@ impl Tool for GitLogTool {
                 "log": log_out,
                 "count": log_out.lines().filter(|l| !l.is_empty()).count()
             }),
-            message: format!("{} commit(s) affichés", count),
+            message: tool_text!("git_log.done", count),
         })
     }
 }
@@ -347,7 +365,446 @@ impl Tool for GitCommitTool {
                 "message": message,
                 "output": stdout.trim(),
             }),
-            message: format!("Commit créé: {}", message),
+            message: tool_text!("git_commit.done", message),
+        })
+    }
+}
+
+// ============================================================================
+// GitCommitPreparedTool
+// ============================================================================
+
+/// Staged diff size above which `git_commit_prepared` wants `force: true`
+const MAX_COMMIT_DIFF_BYTES: usize = 50_000;
+
+/// Diff bytes returned in the result and shown in the permission prompt
+const COMMIT_PREVIEW_BYTES: usize = 8_000;
+
+fn paths_param(params: &Value) -> Vec<String> {
+    params["paths"]
+        .as_array()
+        .map(|paths| paths.iter().filter_map(|p| p.as_str()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// `git add -A` limited to `paths`, so deletions are staged too
+async fn stage_paths(paths: &[String], wd: Option<&str>, envs: &[(&str, &OsStr)]) -> Result<(), ToolError> {
+    let mut args = vec!["add", "-A", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let (_, stderr, success) = run_git_env(&args, wd, envs).await?;
+    if !success {
+        return Err(ToolError::ExecutionFailed(format!("git add failed: {}", stderr.trim())));
+    }
+    Ok(())
+}
+
+/// What `git commit` would record once `paths` are staged
+///
+/// Computed on a copy of the index (`--git-path` finds the one of the
+/// current worktree), so nothing is staged yet. Returns the diff and its stat.
+async fn preview_staged_diff(paths: &[String], wd: Option<&str>) -> Result<(String, String), ToolError> {
+    let (index, stderr, success) = run_git(&["rev-parse", "--git-path", "index"], wd).await?;
+    if !success {
+        return Err(ToolError::ExecutionFailed(tool_text!("git.not_a_repository", stderr.trim())));
+    }
+    let mut index = PathBuf::from(index.trim());
+    if index.is_relative() {
+        index = Path::new(wd.unwrap_or(".")).join(index);
+    }
+    let scratch = std::env::temp_dir().join(format!("clawrs-index-{}", Uuid::new_v4()));
+    if index.exists() {
+        tokio::fs::copy(&index, &scratch)
+            .await
+            .map_err(|e| ToolError::io(&scratch, tool_text!("git.index_copy_error", e), &e))?;
+    }
+    let envs = [("GIT_INDEX_FILE", scratch.as_os_str())];
+    let result = async {
+        if !paths.is_empty() {
+            stage_paths(paths, wd, &envs).await?;
+        }
+        let (diff, _, _) = run_git_env(&["diff", "--cached", "--no-color", "--no-ext-diff"], wd, &envs).await?;
+        let (stat, _, _) = run_git_env(&["diff", "--cached", "--stat", "--no-color"], wd, &envs).await?;
+        Ok((diff, stat))
+    }
+    .await;
+    let _ = tokio::fs::remove_file(&scratch).await;
+    result
+}
+
+/// Staged diff a `git_commit_prepared` call would commit, for its permission prompt
+pub async fn prepared_commit_preview(params: &Value) -> Option<String> {
+    let (diff, _) = preview_staged_diff(&paths_param(params), params["working_dir"].as_str()).await.ok()?;
+    (!diff.is_empty()).then(|| crate::truncate_str(&diff, COMMIT_PREVIEW_BYTES).to_string())
+}
+
+/// Commit of the staged changes, checked before anything is staged
+///
+/// Refuses an empty diff, and one over `MAX_COMMIT_DIFF_BYTES` unless forced.
+/// With `"message": "auto"` the message is written by the model from the diff,
+/// hence the engine: the tool is registered by the app next to `agent_task`.
+pub struct GitCommitPreparedTool {
+    engine: Arc<Mutex<LlamaEngine>>,
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl GitCommitPreparedTool {
+    /// `stop_signal` is the chat's Stop button, which ends message generation
+    pub fn new(engine: Arc<Mutex<LlamaEngine>>, stop_signal: Arc<AtomicBool>) -> Self {
+        Self { engine, stop_signal }
+    }
+}
+
+#[async_trait]
+impl Tool for GitCommitPreparedTool {
+    fn name(&self) -> &str { "git_commit_prepared" }
+
+    fn description(&self) -> &str {
+        "Commit staged changes after checking the staged diff: optionally stage `paths` first. Refuses an empty diff, or a large one without force. message \"auto\" writes a conventional-commit message from the diff. Returns the committed diff. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "Commit message, or \"auto\" to generate one from the staged diff"
+                },
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Files or folders to stage first (omit to commit what is already staged)"
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Commit a staged diff larger than 50 KB",
+                    "default": false
+                },
+                "working_dir": {
+                    "type": "string",
+                    "description": "Repository or worktree path"
+                }
+            },
+            "required": ["message"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let message = params["message"]
+            .as_str()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("message is required".into()))?;
+        let paths = paths_param(&params);
+        let force = params["force"].as_bool().unwrap_or(false);
+        let wd = params["working_dir"].as_str();
+
+        let (diff, stat) = preview_staged_diff(&paths, wd).await?;
+        if diff.trim().is_empty() {
+            return Err(ToolError::InvalidParameters(if paths.is_empty() {
+                tool_text!("git_commit.nothing_staged").to_string()
+            } else {
+                tool_text!("git_commit.nothing_in_paths").to_string()
+            }));
+        }
+        if diff.len() > MAX_COMMIT_DIFF_BYTES && !force {
+            return Err(ToolError::InvalidParameters(tool_text!(
+                "git_commit.diff_too_large",
+                diff.len(),
+                MAX_COMMIT_DIFF_BYTES,
+                stat.trim_end()
+            )));
+        }
+
+        let generated = message.eq_ignore_ascii_case("auto");
+        let message = if generated {
+            generate_commit_message(&self.engine, &diff, &self.stop_signal)
+                .await
+                .ok_or_else(|| {
+                    ToolError::ExecutionFailed(tool_text!("git_commit.no_message").to_string())
+                })?
+        } else {
+            message.to_string()
+        };
+
+        if !paths.is_empty() {
+            stage_paths(&paths, wd, &[]).await?;
+        }
+        let (_, stderr, success) = run_git(&["commit", "-m", &message], wd).await?;
+        if !success {
+            return Err(ToolError::ExecutionFailed(format!("git commit failed: {}", stderr.trim())));
+        }
+        let (hash, _, _) = run_git(&["rev-parse", "--short", "HEAD"], wd).await?;
+        let (worktree, _, _) = run_git(&["rev-parse", "--show-toplevel"], wd).await?;
+
+        let shown = crate::truncate_str(&diff, COMMIT_PREVIEW_BYTES);
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "commit": hash.trim(),
+                "message": message,
+                "message_generated": generated,
+                "worktree": worktree.trim(),
+                "stat": stat,
+                "diff": shown,
+                "diff_truncated": shown.len() < diff.len(),
+            }),
+            message: tool_text!("git_commit.created", hash.trim(), message.lines().next().unwrap_or_default()),
+        })
+    }
+}
+
+// ============================================================================
+// GitBranchDiffTool
+// ============================================================================
+
+/// Changed files and commits listed by `git_branch_diff`, at most
+const MAX_BRANCH_FILES: usize = 500;
+const MAX_BRANCH_COMMITS: usize = 200;
+
+/// File changed between two refs; counts are None for binary files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub path: String,
+    pub added: Option<u64>,
+    pub deleted: Option<u64>,
+}
+
+/// What a branch brings over its base, as a pull request would show it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchDiff {
+    pub base: String,
+    pub head: String,
+    pub merge_base: String,
+    /// `hash subject`, oldest last
+    pub commits: Vec<String>,
+    pub files: Vec<ChangedFile>,
+    pub diff: String,
+}
+
+/// `git diff --numstat` lines
+fn parse_numstat(out: &str) -> Vec<ChangedFile> {
+    out.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let added = parts.next()?.parse().ok();
+            let deleted = parts.next()?.parse().ok();
+            let path = parts.next()?.to_string();
+            Some(ChangedFile { path, added, deleted })
+        })
+        .collect()
+}
+
+/// Whether the repository is a shallow clone, whose history may stop short
+async fn is_shallow(wd: Option<&str>) -> bool {
+    matches!(run_git(&["rev-parse", "--is-shallow-repository"], wd).await, Ok((out, _, true)) if out.trim() == "true")
+}
+
+/// Commit a ref names, or an error saying what to try instead
+async fn resolve_ref(reference: &str, wd: Option<&str>) -> Result<String, ToolError> {
+    if reference.is_empty() || reference.starts_with('-') {
+        return Err(ToolError::InvalidParameters(tool_text!("git.invalid_ref", reference)));
+    }
+    let spec = format!("{}^{{commit}}", reference);
+    let (sha, _, success) = run_git(&["rev-parse", "--verify", "--quiet", &spec], wd).await?;
+    if success {
+        return Ok(sha.trim().to_string());
+    }
+
+    if reference == "HEAD" {
+        return Err(ToolError::NotFound {
+            path: reference.to_string(),
+            message: "ref not found: HEAD has no commit yet (empty repository)".to_string(),
+        });
+    }
+    let (refs, _, _) = run_git(
+        &["for-each-ref", "--format=%(refname:short)", "refs/heads", "refs/remotes", "refs/tags"],
+        wd,
+    )
+    .await?;
+    let short = reference.rsplit('/').next().unwrap_or(reference);
+    let candidates: Vec<&str> = refs
+        .lines()
+        .filter(|r| *r != reference && (r.ends_with(&format!("/{}", short)) || *r == short))
+        .take(3)
+        .collect();
+    let mut message = format!("ref not found: '{}'", reference);
+    if !candidates.is_empty() {
+        message.push_str(&format!(": did you mean {}?", candidates.join(", ")));
+    }
+    if is_shallow(wd).await {
+        message.push_str(&format!(
+            " The clone is shallow and may miss it: run `git fetch origin {}` or `git fetch --unshallow`.",
+            reference
+        ));
+    } else if candidates.is_empty() {
+        message.push_str(". List refs with git_branch, or fetch it from the remote.");
+    }
+    Err(ToolError::NotFound { path: reference.to_string(), message })
+}
+
+/// Changes of `head` since it forked from `base`
+pub async fn branch_diff(base: &str, head: &str, wd: Option<&str>) -> Result<BranchDiff, ToolError> {
+    let (_, stderr, success) = run_git(&["rev-parse", "--git-dir"], wd).await?;
+    if !success {
+        return Err(ToolError::ExecutionFailed(tool_text!("git.not_a_repository", stderr.trim())));
+    }
+    let base_sha = resolve_ref(base, wd).await?;
+    let head_sha = resolve_ref(head, wd).await?;
+
+    let (merge_base, _, success) = run_git(&["merge-base", &base_sha, &head_sha], wd).await?;
+    if !success {
+        let message = if is_shallow(wd).await {
+            format!(
+                "no common ancestor between '{}' and '{}' in this shallow clone: run `git fetch --deepen=200` or `git fetch --unshallow`, then retry",
+                base, head
+            )
+        } else {
+            format!("no common ancestor between '{}' and '{}': unrelated histories, compare other refs", base, head)
+        };
+        return Err(ToolError::ExecutionFailed(message));
+    }
+    let merge_base = merge_base.trim().to_string();
+
+    let range = format!("{}..{}", merge_base, head_sha);
+    let (numstat, _, _) = run_git(&["diff", "--numstat", "--no-color", &range], wd).await?;
+    let (diff, stderr, success) = run_git(&["diff", "--no-color", "--no-ext-diff", &range], wd).await?;
+    if !success {
+        return Err(ToolError::ExecutionFailed(format!("git diff failed: {}", stderr.trim())));
+    }
+    let max_commits = format!("-n{}", MAX_BRANCH_COMMITS);
+    let (log, _, _) = run_git(&["log", &max_commits, "--format=%h %s", &range], wd).await?;
+
+    Ok(BranchDiff {
+        base: base.to_string(),
+        head: head.to_string(),
+        merge_base,
+        commits: log.lines().map(str::to_string).collect(),
+        files: parse_numstat(&numstat),
+        diff,
+    })
+}
+
+/// Diff of a branch against its base: changed files with line counts,
+/// commit subjects and the full diff, paged by bytes
+pub struct GitBranchDiffTool;
+
+#[async_trait]
+impl Tool for GitBranchDiffTool {
+    fn name(&self) -> &str { "git_branch_diff" }
+
+    fn description(&self) -> &str {
+        "Compare a branch with its base, as a pull request would: changed files with added/deleted lines, commit subjects, and the diff paged by output_limit. Set pr_description to get instructions for writing the PR summary from it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "base": {
+                    "type": "string",
+                    "description": "Ref the branch targets, e.g. main or origin/main"
+                },
+                "head": {
+                    "type": "string",
+                    "description": "Branch or commit to compare (default: HEAD)",
+                    "default": "HEAD"
+                },
+                "diff_offset": {
+                    "type": "integer",
+                    "description": "Byte offset in the diff to continue from (default: 0)"
+                },
+                "pr_description": {
+                    "type": "boolean",
+                    "description": "Also return instructions to write a PR description from this diff (default: false)",
+                    "default": false
+                },
+                "output_limit": output_limit_schema(),
+                "working_dir": {
+                    "type": "string",
+                    "description": "Repository path"
+                }
+            },
+            "required": ["base"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let base = params["base"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("base is required".into()))?;
+        let head = params["head"].as_str().unwrap_or("HEAD");
+        let wd = params["working_dir"].as_str();
+        let offset = params["diff_offset"].as_u64().unwrap_or(0) as usize;
+        let mut budget = OutputBudget::from_params(&params);
+
+        let branch = branch_diff(base, head, wd).await?;
+        let detached = head == "HEAD" && !run_git(&["symbolic-ref", "-q", "HEAD"], wd).await?.2;
+
+        // Whole lines from the offset, as many as the budget allows
+        let offset = offset.min(branch.diff.len());
+        let offset = (0..=offset).rev().find(|i| branch.diff.is_char_boundary(*i)).unwrap_or(0);
+        let mut end = offset;
+        for line in branch.diff[offset..].split_inclusive('\n') {
+            if !budget.take(line.len()) {
+                break;
+            }
+            end += line.len();
+        }
+
+        let (added, deleted) = branch
+            .files
+            .iter()
+            .fold((0, 0), |(a, d), f| (a + f.added.unwrap_or(0), d + f.deleted.unwrap_or(0)));
+        let mut data = serde_json::json!({
+            "base": base,
+            "head": head,
+            "merge_base": branch.merge_base,
+            "head_detached": detached,
+            "files_changed": branch.files.len(),
+            "lines_added": added,
+            "lines_deleted": deleted,
+            "diff_bytes": branch.diff.len(),
+            "diff_offset": offset,
+        });
+        // Files and commits come with the first page only
+        if offset == 0 {
+            data["files"] = branch
+                .files
+                .iter()
+                .take(MAX_BRANCH_FILES)
+                .map(|f| serde_json::json!({ "path": f.path, "added": f.added, "deleted": f.deleted }))
+                .collect();
+            data["commits"] = branch.commits.iter().map(|c| Value::from(c.as_str())).collect();
+        }
+        if params["pr_description"].as_bool().unwrap_or(false) {
+            // The prompt holds the diff already
+            data["pr_prompt"] = build_pr_description_prompt(&branch).into();
+        } else {
+            data["diff"] = branch.diff[offset..end].into();
+            if end < branch.diff.len() {
+                let mut continuation = serde_json::json!({ "base": base, "head": head, "diff_offset": end });
+                if let Some(wd) = wd {
+                    continuation["working_dir"] = wd.into();
+                }
+                mark_truncated(&mut data, continuation);
+            }
+        }
+
+        let detached_note = if detached { tool_text!("git_branch_diff.detached") } else { "" };
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!(
+                "git_branch_diff.done",
+                base,
+                head,
+                detached_note,
+                branch.commits.len(),
+                branch.files.len(),
+                added,
+                deleted
+            ),
+            data,
         })
     }
 }
@@ -399,46 +856,46 @@ impl Tool for GitBranchTool {
                 Ok(ToolResult {
                     success: true,
                     data: serde_json::json!({ "branches": stdout }),
-                    message: format!("{} branche(s)", stdout.lines().count()),
+                    message: tool_text!("git_branch.list", stdout.lines().count()),
                 })
             }
             "create" => {
                 let branch = name.ok_or_else(|| ToolError::InvalidParameters("name is required for create".into()))?;
                 let (_, stderr, success) = run_git(&["checkout", "-b", branch], wd).await?;
                 if !success {
-                    return Err(ToolError::ExecutionFailed(format!("Impossible de créer la branche: {}", stderr)));
+                    return Err(ToolError::ExecutionFailed(tool_text!("git_branch.create_error", stderr)));
                 }
                 Ok(ToolResult {
                     success: true,
                     data: serde_json::json!({ "branch": branch, "action": "created" }),
-                    message: format!("Branche créée et activée: {}", branch),
+                    message: tool_text!("git_branch.created", branch),
                 })
             }
             "switch" => {
                 let branch = name.ok_or_else(|| ToolError::InvalidParameters("name is required for switch".into()))?;
                 let (_, stderr, success) = run_git(&["checkout", branch], wd).await?;
                 if !success {
-                    return Err(ToolError::ExecutionFailed(format!("Impossible de changer de branche: {}", stderr)));
+                    return Err(ToolError::ExecutionFailed(tool_text!("git_branch.switch_error", stderr)));
                 }
                 Ok(ToolResult {
                     success: true,
                     data: serde_json::json!({ "branch": branch, "action": "switched" }),
-                    message: format!("Basculé sur la branche: {}", branch),
+                    message: tool_text!("git_branch.switched", branch),
                 })
             }
             "delete" => {
                 let branch = name.ok_or_else(|| ToolError::InvalidParameters("name is required for delete".into()))?;
                 let (_, stderr, success) = run_git(&["branch", "-d", branch], wd).await?;
                 if !success {
-                    return Err(ToolError::ExecutionFailed(format!("Impossible de supprimer la branche: {}", stderr)));
+                    return Err(ToolError::ExecutionFailed(tool_text!("git_branch.delete_error", stderr)));
                 }
                 Ok(ToolResult {
                     success: true,
                     data: serde_json::json!({ "branch": branch, "action": "deleted" }),
-                    message: format!("Branche supprimée: {}", branch),
+                    message: tool_text!("git_branch.deleted", branch),
                 })
             }
-            _ => Err(ToolError::InvalidParameters(format!("Action inconnue: {}", action))),
+            _ => Err(ToolError::InvalidParameters(tool_text!("tool.unknown_action", action))),
         }
     }
 }
@@ -495,7 +952,7 @@ impl Tool for GitStashTool {
             "pop" => run_git(&["stash", "pop"], wd).await?,
             "list" => run_git(&["stash", "list"], wd).await?,
             "drop" => run_git(&["stash", "drop"], wd).await?,
-            _ => return Err(ToolError::InvalidParameters(format!("Action inconnue: {}", action))),
+            _ => return Err(ToolError::InvalidParameters(tool_text!("tool.unknown_action", action))),
         };
 
         if !success && action != "list" {
@@ -512,3 +969,65 @@ impl Tool for GitStashTool {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn git(dir: &Path, args: &[&str]) {
+        let (_, stderr, success) = run_git(args, dir.to_str()).await.unwrap();
+        assert!(success, "git {:?}: {}", args, stderr);
+    }
+
+    #[test]
+    fn test_parse_numstat() {
+        let files = parse_numstat("3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n");
+        assert_eq!(files[0], ChangedFile { path: "src/lib.rs".into(), added: Some(3), deleted: Some(1) });
+        assert_eq!(files[1].added, None);
+    }
+
+    #[tokio::test]
+    async fn test_branch_diff_and_missing_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let wd = dir.path().to_str();
+        git(dir.path(), &["init", "-q", "-b", "main"]).await;
+        git(dir.path(), &["config", "user.email", "t@example.com"]).await;
+        git(dir.path(), &["config", "user.name", "t"]).await;
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        git(dir.path(), &["add", "-A"]).await;
+        git(dir.path(), &["commit", "-q", "-m", "init"]).await;
+        git(dir.path(), &["checkout", "-q", "-b", "feature"]).await;
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        git(dir.path(), &["commit", "-q", "-am", "add two"]).await;
+
+        let branch = branch_diff("main", "HEAD", wd).await.unwrap();
+        assert_eq!(branch.commits.len(), 1);
+        assert!(branch.commits[0].ends_with(" add two"));
+        assert_eq!(branch.files, vec![ChangedFile { path: "a.txt".into(), added: Some(1), deleted: Some(0) }]);
+        assert!(branch.diff.contains("+two"));
+
+        let err = branch_diff("origin/feature", "HEAD", wd).await.unwrap_err();
+        assert!(err.to_string().contains("did you mean feature?"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_preview_stages_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]).await;
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "two\n").unwrap();
+        let wd = dir.path().to_str();
+
+        let (diff, _) = preview_staged_diff(&[], wd).await.unwrap();
+        assert!(diff.is_empty());
+
+        let (diff, stat) = preview_staged_diff(&["a.txt".to_string()], wd).await.unwrap();
+        assert!(diff.contains("+one"));
+        assert!(!diff.contains("b.txt"));
+        assert!(stat.contains("a.txt"));
+
+        // The real index is untouched
+        let (staged, _, _) = run_git(&["diff", "--cached", "--name-only"], wd).await.unwrap();
+        assert!(staged.is_empty());
+    }
+}
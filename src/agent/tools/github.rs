@@ -0,0 +1,802 @@
+//! GitHub tools - issues and pull requests
+//!
+//! Calls go through the `gh` CLI when it is installed and logged in, and
+//! otherwise through the REST API with the `GITHUB_TOKEN` secret of the
+//! vault. Both backends answer with the same normalized JSON, so the model
+//! sees one structure whichever was used.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::process::Command;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::storage::secrets;
+
+/// Vault secrets holding a GitHub token, first found wins
+const TOKEN_SECRETS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+
+const API_ROOT: &str = "https://api.github.com";
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Bytes of an issue or PR body returned to the model
+const MAX_BODY_BYTES: usize = 4000;
+
+const DEFAULT_LIST_LIMIT: u64 = 20;
+const MAX_LIST_LIMIT: u64 = 100;
+
+/// Fields `gh` is asked for, matching what `normalize` reads
+const GH_ISSUE_FIELDS: &str = "number,title,state,body,labels,url,author,createdAt";
+const GH_PR_FIELDS: &str =
+    "number,title,state,body,labels,url,author,createdAt,headRefName,baseRefName,isDraft";
+
+enum Backend {
+    Gh,
+    Rest(String),
+}
+
+fn auth_error(message: impl Into<String>) -> ToolError {
+    ToolError::PermissionDenied {
+        rule: Some("github_auth".to_string()),
+        message: message.into(),
+    }
+}
+
+fn token() -> Option<String> {
+    TOKEN_SECRETS.iter().find_map(|name| secrets::get(name))
+}
+
+/// `gh` when it is installed and logged in, else REST with the vault token
+async fn backend() -> Result<Backend, ToolError> {
+    let gh_ready = Command::new("gh")
+        .args(["auth", "status"])
+        .output()
+        .await
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if gh_ready {
+        return Ok(Backend::Gh);
+    }
+    token().map(Backend::Rest).ok_or_else(|| {
+        auth_error(
+            "GitHub is not configured: install the gh CLI and run `gh auth login`, \
+             or add a GITHUB_TOKEN secret (Settings > Secrets) with the repo scope.",
+        )
+    })
+}
+
+/// `owner/name` of a github.com remote URL (https, ssh or scp-like)
+fn parse_github_slug(url: &str) -> Option<String> {
+    let url = url.trim();
+    let path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.split_once("github.com/").map(|(_, path)| path))?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.split_once('/')?;
+    (!owner.is_empty() && !name.is_empty() && !name.contains('/'))
+        .then(|| format!("{}/{}", owner, name))
+}
+
+/// Repository from the `repo` param, else from the `origin` remote
+async fn repo_slug(params: &Value) -> Result<String, ToolError> {
+    if let Some(repo) = params["repo"].as_str() {
+        return match repo.split_once('/') {
+            Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+                Ok(repo.to_string())
+            }
+            _ => Err(ToolError::InvalidParameters(format!(
+                "repo must be owner/name, got '{}'",
+                repo
+            ))),
+        };
+    }
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "get-url", "origin"]);
+    if let Some(dir) = params["working_dir"].as_str() {
+        cmd.current_dir(dir);
+    }
+    let output = cmd.output().await.ok().filter(|o| o.status.success());
+    output
+        .and_then(|o| parse_github_slug(&String::from_utf8_lossy(&o.stdout)))
+        .ok_or_else(|| {
+            ToolError::InvalidParameters(
+                "No GitHub repository found: the origin remote is missing or not on github.com. Pass repo as owner/name."
+                    .to_string(),
+            )
+        })
+}
+
+fn number_param(params: &Value) -> Result<u64, ToolError> {
+    params["number"].as_u64().filter(|n| *n > 0).ok_or_else(|| {
+        ToolError::InvalidParameters("number is required (issue or PR number)".into())
+    })
+}
+
+/// Issue or PR in the shape shared by both backends
+///
+/// Reads `gh --json` fields (`author`, `headRefName`...) as well as REST
+/// ones (`user`, `head.ref`...).
+fn normalize(item: &Value) -> Value {
+    let text = |value: &Value| value.as_str().map(str::to_string);
+    let body = item["body"].as_str().unwrap_or_default();
+    let shown = crate::truncate_str(body, MAX_BODY_BYTES);
+    let merged = !item["merged_at"].is_null();
+    let state = if merged {
+        "merged".to_string()
+    } else {
+        item["state"].as_str().unwrap_or_default().to_lowercase()
+    };
+    let labels: Vec<Value> = item["labels"]
+        .as_array()
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|l| l["name"].as_str())
+                .map(Value::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut normalized = serde_json::json!({
+        "number": item["number"],
+        "title": item["title"],
+        "state": state,
+        "body": shown,
+        "body_truncated": shown.len() < body.len(),
+        "labels": labels,
+        "url": text(&item["html_url"]).or_else(|| text(&item["url"])),
+        "author": text(&item["author"]["login"]).or_else(|| text(&item["user"]["login"])),
+        "created_at": text(&item["createdAt"]).or_else(|| text(&item["created_at"])),
+    });
+    let head = text(&item["headRefName"]).or_else(|| text(&item["head"]["ref"]));
+    if let Some(head) = head {
+        normalized["head"] = head.into();
+        normalized["base"] = text(&item["baseRefName"])
+            .or_else(|| text(&item["base"]["ref"]))
+            .into();
+        normalized["draft"] = item["isDraft"]
+            .as_bool()
+            .or(item["draft"].as_bool())
+            .unwrap_or(false)
+            .into();
+    }
+    normalized
+}
+
+// ============================================================================
+// gh backend
+// ============================================================================
+
+/// Error for a failed `gh` call, from what it printed
+fn gh_error(stderr: &str, what: &str) -> ToolError {
+    let lower = stderr.to_lowercase();
+    let stderr = stderr.trim();
+    if lower.contains("gh auth login")
+        || lower.contains("authentication")
+        || lower.contains("bad credentials")
+    {
+        auth_error(format!(
+            "gh is not authenticated: run `gh auth login`, then retry. ({})",
+            stderr
+        ))
+    } else if lower.contains("could not resolve host")
+        || lower.contains("dial tcp")
+        || lower.contains("connection refused")
+        || lower.contains("timeout")
+    {
+        ToolError::Network {
+            status: None,
+            message: format!(
+                "Cannot reach GitHub: {}. Check the network connection and retry.",
+                stderr
+            ),
+        }
+    } else if lower.contains("could not resolve to") || lower.contains("not found") {
+        ToolError::NotFound {
+            path: what.to_string(),
+            message: format!("{} not found on GitHub: {}", what, stderr),
+        }
+    } else {
+        ToolError::ExecutionFailed(format!("gh failed: {}", stderr))
+    }
+}
+
+async fn gh(args: &[&str], what: &str) -> Result<String, ToolError> {
+    let output = Command::new("gh")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(tool_text!("github.gh_error", e)))?;
+    if !output.status.success() {
+        return Err(gh_error(&String::from_utf8_lossy(&output.stderr), what));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+async fn gh_json(args: &[&str], what: &str) -> Result<Value, ToolError> {
+    let out = gh(args, what).await?;
+    serde_json::from_str(&out)
+        .map_err(|e| ToolError::ExecutionFailed(format!("Unexpected gh output: {}", e)))
+}
+
+// ============================================================================
+// REST backend
+// ============================================================================
+
+async fn rest(
+    method: reqwest::Method,
+    path: &str,
+    query: &[(&str, &str)],
+    body: Option<Value>,
+    token: &str,
+    what: &str,
+) -> Result<Value, ToolError> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .user_agent("clawRS/0.2.0")
+        .build()
+        .map_err(|e| ToolError::ExecutionFailed(tool_text!("web.client_error", e)))?;
+    let mut request = client
+        .request(method, format!("{}{}", API_ROOT, path))
+        .query(query)
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    let response = request.send().await.map_err(|e| {
+        ToolError::http(
+            format!(
+                "Cannot reach GitHub: {}. Check the network connection and retry.",
+                e
+            ),
+            &e,
+            REQUEST_TIMEOUT_SECS,
+        )
+    })?;
+
+    let status = response.status();
+    let rate_limited = response
+        .headers()
+        .get("x-ratelimit-remaining")
+        .is_some_and(|remaining| remaining == "0");
+    let reset = response
+        .headers()
+        .get("x-ratelimit-reset")
+        .and_then(|reset| reset.to_str().ok())
+        .and_then(|reset| reset.parse::<i64>().ok())
+        .and_then(|reset| chrono::DateTime::from_timestamp(reset, 0));
+    let value: Value = response.json().await.unwrap_or(Value::Null);
+    if status.is_success() {
+        return Ok(value);
+    }
+
+    let detail = value["message"].as_str().unwrap_or_default();
+    Err(match status.as_u16() {
+        401 => auth_error(format!(
+            "GitHub refused the token (401 {}): update the GITHUB_TOKEN secret in Settings > Secrets.",
+            detail
+        )),
+        403 | 429 if rate_limited => ToolError::Network {
+            status: Some(status.as_u16()),
+            message: match reset {
+                Some(reset) => format!("GitHub rate limit reached until {}: wait before retrying.", reset.format("%H:%M UTC")),
+                None => "GitHub rate limit reached: wait before retrying.".to_string(),
+            },
+        },
+        403 => auth_error(format!(
+            "GitHub refused access (403 {}): the token lacks the repo scope or access to this repository.",
+            detail
+        )),
+        404 => ToolError::NotFound {
+            path: what.to_string(),
+            message: format!(
+                "{} not found on GitHub (a private repository the token cannot see also answers 404)",
+                what
+            ),
+        },
+        422 => {
+            let errors: Vec<&str> = value["errors"]
+                .as_array()
+                .map(|errors| errors.iter().filter_map(|e| e["message"].as_str()).collect())
+                .unwrap_or_default();
+            ToolError::InvalidParameters(format!("GitHub rejected the request (422 {}): {}", detail, errors.join("; ")))
+        }
+        code if status.is_server_error() => ToolError::Network {
+            status: Some(code),
+            message: format!("GitHub server error ({} {}): retry later.", code, detail),
+        },
+        code => ToolError::Network {
+            status: Some(code),
+            message: format!("GitHub answered {}: {}", code, detail),
+        },
+    })
+}
+
+// ============================================================================
+// GitHubIssueViewTool
+// ============================================================================
+
+fn repo_schema() -> Value {
+    serde_json::json!({
+        "type": "string",
+        "description": "Repository as owner/name (default: the origin remote)"
+    })
+}
+
+fn working_dir_schema() -> Value {
+    serde_json::json!({
+        "type": "string",
+        "description": "Local repository whose origin remote is used (default: current directory)"
+    })
+}
+
+pub struct GitHubIssueViewTool;
+
+#[async_trait]
+impl Tool for GitHubIssueViewTool {
+    fn name(&self) -> &str {
+        "github_issue_view"
+    }
+
+    fn description(&self) -> &str {
+        "Read a GitHub issue: number, title, state, body (truncated), labels, author and URL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "number": { "type": "integer", "description": "Issue number" },
+                "repo": repo_schema(),
+                "working_dir": working_dir_schema()
+            },
+            "required": ["number"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let number = number_param(&params)?;
+        let repo = repo_slug(&params).await?;
+        let what = format!("issue #{} of {}", number, repo);
+        let item = match backend().await? {
+            Backend::Gh => {
+                let number = number.to_string();
+                gh_json(
+                    &[
+                        "issue",
+                        "view",
+                        &number,
+                        "--repo",
+                        &repo,
+                        "--json",
+                        GH_ISSUE_FIELDS,
+                    ],
+                    &what,
+                )
+                .await?
+            }
+            Backend::Rest(token) => {
+                let path = format!("/repos/{}/issues/{}", repo, number);
+                rest(reqwest::Method::GET, &path, &[], None, &token, &what).await?
+            }
+        };
+        let issue = normalize(&item);
+        Ok(ToolResult {
+            success: true,
+            message: format!(
+                "#{} [{}] {}",
+                number,
+                issue["state"].as_str().unwrap_or_default(),
+                issue["title"].as_str().unwrap_or_default()
+            ),
+            data: issue,
+        })
+    }
+}
+
+// ============================================================================
+// GitHubIssueListTool
+// ============================================================================
+
+pub struct GitHubIssueListTool;
+
+#[async_trait]
+impl Tool for GitHubIssueListTool {
+    fn name(&self) -> &str {
+        "github_issue_list"
+    }
+
+    fn description(&self) -> &str {
+        "List GitHub issues of a repository (pull requests excluded), filtered by state and labels."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "state": {
+                    "type": "string",
+                    "enum": ["open", "closed", "all"],
+                    "description": "Issues to list (default: open)",
+                    "default": "open"
+                },
+                "labels": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only issues having all these labels"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum issues (default: 20, max: 100)",
+                    "default": 20
+                },
+                "repo": repo_schema(),
+                "working_dir": working_dir_schema()
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let state = params["state"].as_str().unwrap_or("open");
+        if !matches!(state, "open" | "closed" | "all") {
+            return Err(ToolError::InvalidParameters(format!(
+                "state must be open, closed or all, got '{}'",
+                state
+            )));
+        }
+        let labels: Vec<&str> = params["labels"]
+            .as_array()
+            .map(|labels| labels.iter().filter_map(|l| l.as_str()).collect())
+            .unwrap_or_default();
+        let limit = params["limit"]
+            .as_u64()
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .clamp(1, MAX_LIST_LIMIT);
+        let repo = repo_slug(&params).await?;
+        let what = format!("repository {}", repo);
+
+        let items = match backend().await? {
+            Backend::Gh => {
+                let limit = limit.to_string();
+                let mut args = vec![
+                    "issue", "list", "--repo", &repo, "--state", state, "--limit", &limit,
+                ];
+                for label in &labels {
+                    args.extend(["--label", label]);
+                }
+                args.extend(["--json", GH_ISSUE_FIELDS]);
+                gh_json(&args, &what).await?
+            }
+            Backend::Rest(token) => {
+                let path = format!("/repos/{}/issues", repo);
+                let per_page = limit.to_string();
+                let labels = labels.join(",");
+                let mut query = vec![("state", state), ("per_page", per_page.as_str())];
+                if !labels.is_empty() {
+                    query.push(("labels", labels.as_str()));
+                }
+                rest(reqwest::Method::GET, &path, &query, None, &token, &what).await?
+            }
+        };
+        // The REST issues list also holds pull requests
+        let issues: Vec<Value> = items
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter(|i| i.get("pull_request").is_none())
+                    .map(normalize)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!("github_issue_list.done", issues.len(), state, repo),
+            data: serde_json::json!({ "repo": repo, "state": state, "issues": issues }),
+        })
+    }
+}
+
+// ============================================================================
+// GitHubPrViewTool
+// ============================================================================
+
+pub struct GitHubPrViewTool;
+
+#[async_trait]
+impl Tool for GitHubPrViewTool {
+    fn name(&self) -> &str {
+        "github_pr_view"
+    }
+
+    fn description(&self) -> &str {
+        "Read a GitHub pull request: number, title, state (open/closed/merged), body (truncated), labels, head and base branches, draft flag and URL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "number": { "type": "integer", "description": "Pull request number" },
+                "repo": repo_schema(),
+                "working_dir": working_dir_schema()
+            },
+            "required": ["number"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let number = number_param(&params)?;
+        let repo = repo_slug(&params).await?;
+        let what = format!("pull request #{} of {}", number, repo);
+        let item = fetch_pr(number, &repo, &what).await?;
+        let pr = normalize(&item);
+        Ok(ToolResult {
+            success: true,
+            message: format!(
+                "PR #{} [{}] {}",
+                number,
+                pr["state"].as_str().unwrap_or_default(),
+                pr["title"].as_str().unwrap_or_default()
+            ),
+            data: pr,
+        })
+    }
+}
+
+async fn fetch_pr(number: u64, repo: &str, what: &str) -> Result<Value, ToolError> {
+    match backend().await? {
+        Backend::Gh => {
+            let number = number.to_string();
+            gh_json(
+                &[
+                    "pr",
+                    "view",
+                    &number,
+                    "--repo",
+                    repo,
+                    "--json",
+                    GH_PR_FIELDS,
+                ],
+                what,
+            )
+            .await
+        }
+        Backend::Rest(token) => {
+            let path = format!("/repos/{}/pulls/{}", repo, number);
+            rest(reqwest::Method::GET, &path, &[], None, &token, what).await
+        }
+    }
+}
+
+// ============================================================================
+// GitHubPrCreateTool
+// ============================================================================
+
+/// What a `github_pr_create` call would open, for its permission prompt
+pub fn pr_create_preview(params: &Value) -> Option<String> {
+    let title = params["title"].as_str()?;
+    Some(format!(
+        "Pull request on {}\nbase: {}\nhead: {}{}\n\n{}\n\n{}",
+        params["repo"].as_str().unwrap_or("(origin remote)"),
+        params["base"].as_str().unwrap_or("(default branch)"),
+        params["head"].as_str().unwrap_or("(current branch)"),
+        if params["draft"].as_bool().unwrap_or(false) {
+            "\ndraft"
+        } else {
+            ""
+        },
+        title,
+        params["body"].as_str().unwrap_or_default()
+    ))
+}
+
+async fn current_branch(params: &Value) -> Result<String, ToolError> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "--show-current"]);
+    if let Some(dir) = params["working_dir"].as_str() {
+        cmd.current_dir(dir);
+    }
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(tool_text!("git.run_error", e)))?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch.is_empty() {
+        return Err(ToolError::InvalidParameters(
+            "HEAD is detached: pass head with the branch to open the pull request from."
+                .to_string(),
+        ));
+    }
+    Ok(branch)
+}
+
+pub struct GitHubPrCreateTool;
+
+#[async_trait]
+impl Tool for GitHubPrCreateTool {
+    fn name(&self) -> &str {
+        "github_pr_create"
+    }
+
+    fn description(&self) -> &str {
+        "Open a GitHub pull request from a pushed branch. Push the branch first. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string", "description": "Pull request title" },
+                "body": { "type": "string", "description": "Description in Markdown" },
+                "base": {
+                    "type": "string",
+                    "description": "Branch to merge into (default: the repository's default branch)"
+                },
+                "head": {
+                    "type": "string",
+                    "description": "Branch holding the changes (default: current branch)"
+                },
+                "draft": { "type": "boolean", "description": "Open as a draft", "default": false },
+                "repo": repo_schema(),
+                "working_dir": working_dir_schema()
+            },
+            "required": ["title"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let title = params["title"]
+            .as_str()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("title is required".into()))?;
+        let body = params["body"].as_str().unwrap_or_default();
+        let draft = params["draft"].as_bool().unwrap_or(false);
+        let repo = repo_slug(&params).await?;
+        let head = match params["head"].as_str() {
+            Some(head) => head.to_string(),
+            None => current_branch(&params).await?,
+        };
+        let what = format!("repository {}", repo);
+
+        let item = match backend().await? {
+            Backend::Gh => {
+                let mut args = vec![
+                    "pr", "create", "--repo", &repo, "--title", title, "--body", body, "--head",
+                    &head,
+                ];
+                if let Some(base) = params["base"].as_str() {
+                    args.extend(["--base", base]);
+                }
+                if draft {
+                    args.push("--draft");
+                }
+                let url = gh(&args, &what).await?;
+                let number = url
+                    .trim()
+                    .rsplit('/')
+                    .next()
+                    .and_then(|n| n.parse::<u64>().ok())
+                    .ok_or_else(|| {
+                        ToolError::ExecutionFailed(format!("Unexpected gh output: {}", url.trim()))
+                    })?;
+                fetch_pr(number, &repo, &what).await?
+            }
+            Backend::Rest(token) => {
+                let base = match params["base"].as_str() {
+                    Some(base) => base.to_string(),
+                    None => {
+                        let path = format!("/repos/{}", repo);
+                        let info =
+                            rest(reqwest::Method::GET, &path, &[], None, &token, &what).await?;
+                        info["default_branch"]
+                            .as_str()
+                            .unwrap_or("main")
+                            .to_string()
+                    }
+                };
+                let request = serde_json::json!({ "title": title, "body": body, "head": head, "base": base, "draft": draft });
+                let path = format!("/repos/{}/pulls", repo);
+                rest(
+                    reqwest::Method::POST,
+                    &path,
+                    &[],
+                    Some(request),
+                    &token,
+                    &what,
+                )
+                .await?
+            }
+        };
+        let pr = normalize(&item);
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!(
+                "github_pr_create.done",
+                pr["number"],
+                pr["url"].as_str().unwrap_or_default()
+            ),
+            data: pr,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github_slug() {
+        assert_eq!(
+            parse_github_slug("https://github.com/o/r.git").as_deref(),
+            Some("o/r")
+        );
+        assert_eq!(
+            parse_github_slug("git@github.com:o/r.git\n").as_deref(),
+            Some("o/r")
+        );
+        assert_eq!(
+            parse_github_slug("ssh://git@github.com/o/r").as_deref(),
+            Some("o/r")
+        );
+        assert_eq!(parse_github_slug("https://gitlab.com/o/r.git"), None);
+    }
+
+    #[test]
+    fn test_backends_normalize_alike() {
+        let gh = serde_json::json!({
+            "number": 7, "title": "Fix", "state": "MERGED", "body": "b",
+            "labels": [{"name": "bug"}], "url": "https://github.com/o/r/pull/7",
+            "author": {"login": "ann"}, "createdAt": "2024-01-01T00:00:00Z",
+            "headRefName": "fix", "baseRefName": "main", "isDraft": false
+        });
+        let rest = serde_json::json!({
+            "number": 7, "title": "Fix", "state": "closed", "body": "b",
+            "labels": [{"name": "bug", "color": "red"}], "url": "https://api.github.com/repos/o/r/pulls/7",
+            "html_url": "https://github.com/o/r/pull/7", "user": {"login": "ann"},
+            "created_at": "2024-01-01T00:00:00Z", "merged_at": "2024-01-02T00:00:00Z",
+            "head": {"ref": "fix"}, "base": {"ref": "main"}, "draft": false
+        });
+        assert_eq!(normalize(&gh), normalize(&rest));
+        assert_eq!(normalize(&gh)["state"], "merged");
+
+        let issue = normalize(
+            &serde_json::json!({"number": 1, "state": "open", "body": null, "user": {"login": "x"}}),
+        );
+        assert_eq!(issue["state"], "open");
+        assert!(issue.get("head").is_none());
+    }
+
+    #[test]
+    fn test_gh_errors_are_classified() {
+        assert!(matches!(
+            gh_error(
+                "To get started with GitHub CLI, please run:  gh auth login",
+                "x"
+            ),
+            ToolError::PermissionDenied { .. }
+        ));
+        assert!(matches!(
+            gh_error(
+                "error connecting to api.github.com: could not resolve host",
+                "x"
+            ),
+            ToolError::Network { .. }
+        ));
+        assert!(matches!(
+            gh_error(
+                "GraphQL: Could not resolve to an issue or pull request with the number of 9.",
+                "x"
+            ),
+            ToolError::NotFound { .. }
+        ));
+    }
+}
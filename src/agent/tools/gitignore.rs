@@ -0,0 +1,249 @@
+//! `.gitignore` / `.ignore` matching for the recursive file tools
+//!
+//! grep, glob, file_search and recursive file_list skip what the project
+//! ignores (build outputs, virtualenvs, vendored trees...). Rules are read
+//! from the searched directory, from its ancestors up to the repository root,
+//! and from each directory on the way down; deeper files and later lines win,
+//! and `!pattern` re-includes, as in git.
+
+use glob::{MatchOptions, Pattern};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Files holding ignore rules, in the order they are applied
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore"];
+
+/// `*` and `?` stop at `/`, like git's wildmatch
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// One line of an ignore file
+#[derive(Debug, Clone)]
+struct Rule {
+    /// Directory of the ignore file, absolute
+    base: PathBuf,
+    pattern: Pattern,
+    negated: bool,
+    /// Trailing `/`: only matches directories
+    dir_only: bool,
+    /// Contains a `/`: matched against the path from `base`, else the name alone
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str, base: &Path) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        if line.is_empty() {
+            return None;
+        }
+        Some(Self {
+            base: base.to_path_buf(),
+            pattern: Pattern::new(line).ok()?,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+        if self.anchored {
+            let relative: Vec<_> = relative.iter().map(|part| part.to_string_lossy()).collect();
+            self.pattern.matches_with(&relative.join("/"), MATCH_OPTIONS)
+        } else {
+            path.file_name()
+                .is_some_and(|name| self.pattern.matches_with(&name.to_string_lossy(), MATCH_OPTIONS))
+        }
+    }
+}
+
+fn read_rules(dir: &Path) -> Vec<Rule> {
+    IGNORE_FILES
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(dir.join(file)).ok())
+        .flat_map(|content| content.lines().filter_map(|line| Rule::parse(line, dir)).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Ignore rules in effect for one directory of a walk
+///
+/// Cheap to clone; `child` adds the rules of a subdirectory.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    /// The walk's root as the caller spells it, and its absolute form
+    root: PathBuf,
+    root_abs: PathBuf,
+    rules: Arc<Vec<Rule>>,
+    enabled: bool,
+}
+
+impl IgnoreRules {
+    /// Rules for a walk starting at `root`, or none when `enabled` is false
+    pub fn for_root(root: &Path, enabled: bool) -> Self {
+        let root_abs = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        let mut rules = Vec::new();
+        if enabled {
+            // Ancestors up to the repository root, outermost first
+            let ancestors: Vec<&Path> = root_abs.ancestors().skip(1).collect();
+            if let Some(repo) = ancestors.iter().position(|dir| dir.join(".git").exists()) {
+                for dir in ancestors[..=repo].iter().rev() {
+                    rules.extend(read_rules(dir));
+                }
+            }
+            rules.extend(read_rules(&root_abs));
+        }
+        Self { root: root.to_path_buf(), root_abs, rules: Arc::new(rules), enabled }
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) => self.root_abs.join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    /// Whether `path`, an entry of the walk, is ignored
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let path = self.absolute(path);
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(&path, is_dir))
+            .is_some_and(|rule| !rule.negated)
+    }
+
+    /// Rules for the subdirectory `dir`, adding its own ignore files
+    pub fn child(&self, dir: &Path) -> Self {
+        if !self.enabled {
+            return self.clone();
+        }
+        let own = read_rules(&self.absolute(dir));
+        if own.is_empty() {
+            return self.clone();
+        }
+        let mut rules: Vec<Rule> = self.rules.iter().cloned().collect();
+        rules.extend(own);
+        Self { rules: Arc::new(rules), ..self.clone() }
+    }
+}
+
+/// Checks arbitrary paths under a root, for tools that don't walk themselves
+/// (glob); the rules of each directory are read once
+pub struct IgnoreCache {
+    root: IgnoreRules,
+    dirs: HashMap<PathBuf, IgnoreRules>,
+}
+
+impl IgnoreCache {
+    pub fn new(root: IgnoreRules) -> Self {
+        Self { root, dirs: HashMap::new() }
+    }
+
+    /// Whether `path` or one of its parent directories below the root is ignored
+    pub fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        if !self.root.enabled {
+            return false;
+        }
+        let relative = path
+            .strip_prefix(&self.root.root)
+            .or_else(|_| path.strip_prefix(&self.root.root_abs));
+        let Ok(relative) = relative else {
+            return false;
+        };
+        let parts: Vec<_> = relative.iter().collect();
+        let mut rules = self.root.clone();
+        let mut current = self.root.root.clone();
+        for (i, part) in parts.iter().enumerate() {
+            current.push(part);
+            let last = i + 1 == parts.len();
+            if rules.is_ignored(&current, is_dir || !last) {
+                return true;
+            }
+            if !last {
+                rules = self
+                    .dirs
+                    .entry(current.clone())
+                    .or_insert_with(|| rules.child(&current))
+                    .clone();
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["src", "dist", ".venv/lib", "logs", "docs/build", "vendor/big"] {
+            std::fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "# outputs\ndist/\n.venv\n*.log\n!keep.log\n/vendor\n").unwrap();
+        std::fs::write(root.join("docs/.ignore"), "build/\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_rules_follow_gitignore_semantics() {
+        let dir = project();
+        let root = dir.path();
+        let rules = IgnoreRules::for_root(root, true);
+
+        assert!(rules.is_ignored(&root.join("dist"), true));
+        assert!(!rules.is_ignored(&root.join("dist"), false));
+        assert!(rules.is_ignored(&root.join(".venv"), true));
+        assert!(rules.is_ignored(&root.join("vendor"), true));
+        assert!(!rules.is_ignored(&root.join("src/vendor"), true));
+        assert!(rules.is_ignored(&root.join("logs/app.log"), false));
+        assert!(!rules.is_ignored(&root.join("logs/keep.log"), false));
+        assert!(!rules.is_ignored(&root.join("src/main.rs"), false));
+
+        // Nested ignore files only apply below their directory
+        assert!(!rules.is_ignored(&root.join("build"), true));
+        let docs = rules.child(&root.join("docs"));
+        assert!(docs.is_ignored(&root.join("docs/build"), true));
+
+        assert!(!IgnoreRules::for_root(root, false).is_ignored(&root.join("dist"), true));
+    }
+
+    #[test]
+    fn test_cache_checks_parent_directories() {
+        let dir = project();
+        let root = dir.path();
+        let mut cache = IgnoreCache::new(IgnoreRules::for_root(root, true));
+
+        assert!(cache.is_ignored(&root.join("dist/bundle.js"), false));
+        assert!(cache.is_ignored(&root.join("vendor/big/lib.rs"), false));
+        assert!(cache.is_ignored(&root.join("docs/build/index.html"), false));
+        assert!(!cache.is_ignored(&root.join("docs/guide.md"), false));
+        assert!(!cache.is_ignored(&root.join("src/main.rs"), false));
+    }
+}
@@ -0,0 +1,461 @@
+//! Image inspection tool - dimensions, format, EXIF basics, preview
+//!
+//! Only the image header is read for the metadata; pixels are decoded when a
+//! preview is asked for, under size limits so that a small file declaring
+//! huge dimensions (a decompression bomb) is refused instead of exhausting
+//! memory.
+
+use async_trait::async_trait;
+use base64::Engine;
+use image::{DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageReader, Limits};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::{format_size, resolve_and_validate_path};
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+
+/// Formats inspected
+const SUPPORTED_FORMATS: &[ImageFormat] = &[ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::WebP];
+
+/// Extensions listed when `path` is a folder
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+/// Largest side of the preview, in pixels
+const PREVIEW_MAX_SIDE: u32 = 256;
+
+const PREVIEW_JPEG_QUALITY: u8 = 75;
+
+/// Images wider or taller than this are not decoded
+const MAX_DECODE_SIDE: u32 = 16_384;
+
+/// Memory the decoder may allocate
+const MAX_DECODE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Images described at most for a folder
+const MAX_FOLDER_IMAGES: usize = 200;
+
+fn decode_limits() -> Limits {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DECODE_SIDE);
+    limits.max_image_height = Some(MAX_DECODE_SIDE);
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    limits
+}
+
+fn unsupported(path: &Path) -> ToolError {
+    ToolError::InvalidParameters(tool_text!("image.unsupported", path.display()))
+}
+
+/// Map a decoder error to unsupported / too large / corrupt
+///
+/// Truncated or garbled data can also come back as an I/O error from the
+/// decoder; those are reported as corrupt too.
+fn image_error(path: &Path, e: ImageError) -> ToolError {
+    match e {
+        ImageError::IoError(e)
+            if !matches!(
+                e.kind(),
+                std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::InvalidData
+            ) =>
+        {
+            ToolError::io(path, tool_text!("image.read_error", path.display(), e), &e)
+        }
+        ImageError::Unsupported(_) => unsupported(path),
+        ImageError::Limits(_) => ToolError::ExecutionFailed(tool_text!(
+            "image.too_large",
+            path.display(),
+            MAX_DECODE_SIDE,
+            MAX_DECODE_SIDE
+        )),
+        other => {
+            ToolError::ExecutionFailed(tool_text!("image.corrupt_detail", path.display(), other))
+        }
+    }
+}
+
+/// Byte size of one value of a TIFF field type
+fn tiff_type_size(kind: u16) -> usize {
+    match kind {
+        3 | 8 => 2,
+        4 | 9 | 11 => 4,
+        5 | 10 | 12 => 8,
+        _ => 1,
+    }
+}
+
+/// Camera make and model, dates, orientation and GPS presence from raw EXIF
+///
+/// Reads IFD0 and the Exif sub-IFD; anything malformed ends the scan with
+/// the fields found so far.
+fn exif_summary(raw: &[u8]) -> Option<Value> {
+    let tiff = raw.strip_prefix(b"Exif\0\0").unwrap_or(raw);
+    let little_endian = match tiff.get(..4)? {
+        b"II*\0" => true,
+        b"MM\0*" => false,
+        _ => return None,
+    };
+    let read16 = |at: usize| {
+        let bytes: [u8; 2] = tiff.get(at..at.checked_add(2)?)?.try_into().ok()?;
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let read32 = |at: usize| {
+        let bytes: [u8; 4] = tiff.get(at..at.checked_add(4)?)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let mut fields = serde_json::Map::new();
+    let mut ifds = vec![read32(4)? as usize];
+    let mut visited = 0;
+    while let Some(ifd) = ifds.pop() {
+        visited += 1;
+        if visited > 2 {
+            break;
+        }
+        let Some(count) = read16(ifd) else { break };
+        for i in 0..count as usize {
+            let entry = ifd + 2 + i * 12;
+            let (Some(tag), Some(kind), Some(n), Some(offset)) = (
+                read16(entry),
+                read16(entry + 2),
+                read32(entry + 4),
+                read32(entry + 8),
+            ) else {
+                break;
+            };
+            let n = n as usize;
+            let at = if tiff_type_size(kind).saturating_mul(n) <= 4 {
+                entry + 8
+            } else {
+                offset as usize
+            };
+            let name = match tag {
+                0x010F => "make",
+                0x0110 => "model",
+                0x0112 => "orientation",
+                0x0132 => "date_time",
+                0x9003 => "date_time_original",
+                0x8769 => {
+                    ifds.push(offset as usize);
+                    continue;
+                }
+                0x8825 => {
+                    fields.insert("has_gps".to_string(), Value::Bool(true));
+                    continue;
+                }
+                _ => continue,
+            };
+            let value = match kind {
+                2 => tiff
+                    .get(at..at.saturating_add(n))
+                    .map(|text| {
+                        String::from_utf8_lossy(text)
+                            .trim_matches(['\0', ' '])
+                            .to_string()
+                    })
+                    .filter(|text| !text.is_empty())
+                    .map(Value::String),
+                3 => read16(at).map(Value::from),
+                4 => read32(at).map(Value::from),
+                _ => None,
+            };
+            if let Some(value) = value {
+                fields.insert(name.to_string(), value);
+            }
+        }
+    }
+    (!fields.is_empty()).then_some(Value::Object(fields))
+}
+
+/// Header metadata of one image, plus a JPEG thumbnail when `preview` is set
+fn inspect(path: &Path, preview: bool) -> Result<Value, ToolError> {
+    let io = |e: std::io::Error| {
+        ToolError::io(path, tool_text!("image.read_error", path.display(), e), &e)
+    };
+    let size = std::fs::metadata(path).map_err(io)?.len();
+    let mut reader = ImageReader::open(path)
+        .map_err(io)?
+        .with_guessed_format()
+        .map_err(io)?;
+    let format = reader
+        .format()
+        .filter(|format| SUPPORTED_FORMATS.contains(format))
+        .ok_or_else(|| unsupported(path))?;
+    reader.limits(decode_limits());
+    let mut decoder = reader.into_decoder().map_err(|e| image_error(path, e))?;
+    let (width, height) = decoder.dimensions();
+    let color = decoder.color_type();
+    let exif = decoder
+        .exif_metadata()
+        .ok()
+        .flatten()
+        .and_then(|raw| exif_summary(&raw));
+
+    let mut info = serde_json::json!({
+        "format": format!("{:?}", format).to_lowercase(),
+        "width": width,
+        "height": height,
+        "color": format!("{:?}", color),
+        "has_alpha": color.has_alpha(),
+        "size_bytes": size,
+        "size": format_size(size),
+        "exif": exif,
+    });
+
+    if preview {
+        let image = DynamicImage::from_decoder(decoder).map_err(|e| image_error(path, e))?;
+        let thumbnail = image
+            .thumbnail(PREVIEW_MAX_SIDE, PREVIEW_MAX_SIDE)
+            .to_rgb8();
+        let mut jpeg = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, PREVIEW_JPEG_QUALITY)
+            .encode_image(&thumbnail)
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("image.preview_error", e)))?;
+        info["thumbnail_width"] = thumbnail.width().into();
+        info["thumbnail_height"] = thumbnail.height().into();
+        info["thumbnail_base64"] = base64::engine::general_purpose::STANDARD
+            .encode(&jpeg)
+            .into();
+    }
+    Ok(info)
+}
+
+/// Images directly in `dir`, sorted by name, at most MAX_FOLDER_IMAGES
+fn folder_images(dir: &Path) -> Result<(Vec<std::path::PathBuf>, bool), ToolError> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| ToolError::io(dir, tool_text!("image.read_error", dir.display(), e), &e))?;
+    let mut images: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| {
+                    IMAGE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+                })
+        })
+        .collect();
+    images.sort();
+    let truncated = images.len() > MAX_FOLDER_IMAGES;
+    images.truncate(MAX_FOLDER_IMAGES);
+    Ok((images, truncated))
+}
+
+// ============================================================================
+// ImageInfoTool - Describe images without sending their bytes
+// ============================================================================
+
+pub struct ImageInfoTool;
+
+#[async_trait]
+impl Tool for ImageInfoTool {
+    fn name(&self) -> &str {
+        "image_info"
+    }
+
+    fn description(&self) -> &str {
+        "Inspect PNG/JPEG/WebP images: dimensions, format, color type, file size and EXIF basics (camera, date, orientation, GPS presence). On a folder, describes every image in it. include_preview adds a small JPEG thumbnail (max 256px) as base64."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Image file, or a folder to describe all its images"
+                },
+                "include_preview": {
+                    "type": "boolean",
+                    "description": "Add thumbnail_base64, a JPEG of at most 256px (single file only, default: false)",
+                    "default": false
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path_str = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let preview = params["include_preview"].as_bool().unwrap_or(false);
+
+        let path = resolve_and_validate_path(path_str)?;
+        if !path.exists() {
+            return Err(ToolError::NotFound {
+                path: path.display().to_string(),
+                message: "File does not exist".to_string(),
+            });
+        }
+
+        let is_dir = path.is_dir();
+        let result = tokio::task::spawn_blocking(move || {
+            if !is_dir {
+                return inspect(&path, preview).map(|info| (info, None));
+            }
+            let (images, truncated) = folder_images(&path)?;
+            let mut total = 0u64;
+            let described: Vec<Value> = images
+                .iter()
+                .map(|image| {
+                    let name = image
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let mut info = inspect(image, false)
+                        .unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }));
+                    total += info["size_bytes"].as_u64().unwrap_or(0);
+                    info["name"] = Value::String(name);
+                    info
+                })
+                .collect();
+            let data = serde_json::json!({
+                "images": described,
+                "count": described.len(),
+                "total_bytes": total,
+                "truncated": truncated,
+            });
+            Ok((data, Some((described.len(), total))))
+        })
+        .await
+        .map_err(|e| {
+            // A decoder panic on a malformed file must not surface as a raw message
+            if e.is_panic() {
+                ToolError::ExecutionFailed(tool_text!("image.corrupt", path_str))
+            } else {
+                ToolError::ExecutionFailed(format!("Inspection task failed: {}", e))
+            }
+        })?;
+        let (mut data, folder) = result?;
+
+        let message = match folder {
+            Some((count, total)) => {
+                tool_text!("image_info.folder", path_str, count, format_size(total))
+            }
+            None => format!(
+                "{}: {} {}x{}, {}{}",
+                path_str,
+                data["format"].as_str().unwrap_or("?"),
+                data["width"],
+                data["height"],
+                data["size"].as_str().unwrap_or("?"),
+                if data["thumbnail_base64"].is_string() {
+                    tool_text!("image_info.with_preview")
+                } else {
+                    ""
+                }
+            ),
+        };
+        data["path"] = Value::String(path_str.to_string());
+
+        Ok(ToolResult {
+            success: true,
+            data,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal little-endian EXIF block: make, orientation, GPS pointer
+    fn exif_block() -> Vec<u8> {
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend(8u32.to_le_bytes());
+        tiff.extend(3u16.to_le_bytes());
+        let make_offset = 8 + 2 + 3 * 12 + 4;
+        for (tag, kind, count, value) in [
+            (0x010Fu16, 2u16, 6u32, make_offset as u32),
+            (0x0112, 3, 1, 6),
+            (0x8825, 4, 1, 0),
+        ] {
+            tiff.extend(tag.to_le_bytes());
+            tiff.extend(kind.to_le_bytes());
+            tiff.extend(count.to_le_bytes());
+            tiff.extend(value.to_le_bytes());
+        }
+        tiff.extend(0u32.to_le_bytes());
+        tiff.extend(b"Canon\0");
+        let mut raw = b"Exif\0\0".to_vec();
+        raw.extend(tiff);
+        raw
+    }
+
+    #[test]
+    fn test_exif_summary() {
+        let exif = exif_summary(&exif_block()).unwrap();
+        assert_eq!(exif["make"], "Canon");
+        assert_eq!(exif["orientation"], 6);
+        assert_eq!(exif["has_gps"], true);
+        assert!(exif_summary(b"not exif").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_image_info_and_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("photo.png");
+        image::RgbImage::from_pixel(600, 300, image::Rgb([200, 30, 30]))
+            .save(&photo)
+            .unwrap();
+        std::fs::write(
+            dir.path().join("broken.png"),
+            b"\x89PNG\r\n\x1a\nnot really a png",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+
+        let result = ImageInfoTool
+            .execute(serde_json::json!({"path": photo, "include_preview": true}))
+            .await
+            .unwrap();
+        assert_eq!(result.data["format"], "png");
+        assert_eq!(
+            (
+                result.data["width"].as_u64(),
+                result.data["height"].as_u64()
+            ),
+            (Some(600), Some(300))
+        );
+        assert_eq!(
+            (
+                result.data["thumbnail_width"].as_u64(),
+                result.data["thumbnail_height"].as_u64()
+            ),
+            (Some(256), Some(128))
+        );
+        let jpeg = base64::engine::general_purpose::STANDARD
+            .decode(result.data["thumbnail_base64"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+
+        // Corrupt and unsupported files give structured errors
+        let broken = ImageInfoTool
+            .execute(serde_json::json!({"path": dir.path().join("broken.png")}))
+            .await
+            .unwrap_err();
+        assert!(matches!(broken, ToolError::ExecutionFailed(ref m) if m.contains("corrupt")));
+        let text = ImageInfoTool
+            .execute(serde_json::json!({"path": dir.path().join("notes.txt")}))
+            .await
+            .unwrap_err();
+        assert!(matches!(text, ToolError::InvalidParameters(_)));
+
+        let folder = ImageInfoTool
+            .execute(serde_json::json!({"path": dir.path()}))
+            .await
+            .unwrap();
+        assert_eq!(folder.data["count"], 2);
+        assert_eq!(folder.data["images"][0]["name"], "broken.png");
+        assert!(folder.data["images"][0]["error"].is_string());
+        assert_eq!(folder.data["images"][1]["width"], 600);
+    }
+}
@@ -0,0 +1,514 @@
+//! Document index tools - semantic search over folders of documents
+//!
+//! `index_build` cuts the text, Markdown and PDF files of a folder into chunks
+//! and embeds them with the GGUF embedding model chosen in the settings; the
+//! engine worker runs it next to the chat model (`inference::Embedder`).
+//! Rebuilds only re-embed files whose size or mtime changed. `index_search`
+//! embeds the query the same way and returns the closest chunks with their
+//! file and lines. Storage is `storage::index`.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::inference::engine::{Embedder, LlamaEngine};
+use crate::storage::index::{read_document, scan_documents, Chunk, DocumentIndex, IndexedFile};
+use crate::storage::settings::DocumentIndexSettings;
+
+/// Embedding models are small: all their layers go to the GPU when there is one
+const EMBED_GPU_LAYERS: u32 = 99;
+
+/// Chunks per embedding request; the worker takes chat requests in between
+const EMBED_BATCH: usize = 16;
+
+/// Files embedded between two saves, so an interrupted build keeps its progress
+const SAVE_EVERY_FILES: usize = 20;
+
+const DEFAULT_TOP_K: u64 = 5;
+const MAX_TOP_K: u64 = 20;
+
+/// Embedding model and folders, installed from the settings at startup and on each change
+static SETTINGS: Lazy<RwLock<DocumentIndexSettings>> =
+    Lazy::new(|| RwLock::new(DocumentIndexSettings::default()));
+
+pub fn set_document_index_settings(settings: DocumentIndexSettings) {
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+fn settings() -> DocumentIndexSettings {
+    SETTINGS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Configured embedding model
+fn embedding_model() -> Result<String, ToolError> {
+    settings()
+        .embedding_model_path
+        .filter(|path| !path.trim().is_empty())
+        .ok_or_else(|| ToolError::InvalidParameters(tool_text!("index.no_model").to_string()))
+}
+
+/// Folders a call works on: `folder` when given, else the configured ones
+fn target_folders(params: &Value) -> Result<Vec<PathBuf>, ToolError> {
+    match params["folder"].as_str().filter(|f| !f.trim().is_empty()) {
+        Some(folder) => {
+            let path = resolve_and_validate_path(folder)?;
+            if !path.is_dir() {
+                return Err(ToolError::NotFound {
+                    path: folder.to_string(),
+                    message: tool_text!("index.folder_not_found", folder),
+                });
+            }
+            Ok(vec![path])
+        }
+        None => Ok(settings().folders),
+    }
+}
+
+fn no_folders() -> ToolError {
+    ToolError::InvalidParameters(tool_text!("index.no_folders").to_string())
+}
+
+/// Embedding handle for `model`, starting the engine worker if no model was loaded yet
+async fn embedder(engine: &Mutex<LlamaEngine>, model: &str) -> Result<Embedder, ToolError> {
+    let mut engine = engine.lock().await;
+    if !engine.is_initialized() {
+        engine
+            .init()
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+    }
+    engine
+        .embedder(PathBuf::from(model), EMBED_GPU_LAYERS)
+        .map_err(|e| ToolError::ExecutionFailed(tool_text!("index.model_error", e)))
+}
+
+async fn embed(embedder: &Embedder, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ToolError> {
+    embedder
+        .embed(texts)
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(tool_text!("index.embed_error", e)))
+}
+
+fn storage_error(root: &Path, e: impl std::fmt::Display) -> ToolError {
+    ToolError::ExecutionFailed(tool_text!("index.storage_error", root.display(), e))
+}
+
+/// Bring the index of `root` up to date, re-embedding only changed files
+async fn build_index(
+    embedder: &Embedder,
+    model: &str,
+    root: &Path,
+    full: bool,
+) -> Result<Value, ToolError> {
+    let mut index = match DocumentIndex::load(root) {
+        Ok(Some(index)) if !full && index.model == model => index,
+        Ok(_) => DocumentIndex::new(root.to_path_buf(), model.to_string()),
+        Err(e) => {
+            tracing::warn!("Unreadable index of {}, rebuilding: {}", root.display(), e);
+            DocumentIndex::new(root.to_path_buf(), model.to_string())
+        }
+    };
+
+    let scan_root = root.to_path_buf();
+    let documents = tokio::task::spawn_blocking(move || scan_documents(&scan_root))
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?
+        .map_err(|e| storage_error(root, e))?;
+    let plan = index.plan(&documents);
+    for relative in &plan.removed {
+        index.files.remove(relative);
+    }
+
+    let mut errors = Vec::new();
+    let mut embedded_chunks = 0;
+    for (done, document) in plan.changed.iter().enumerate() {
+        let path = document.path.clone();
+        // pdf-extract panics on some files: a panic only skips that file
+        let chunks = match tokio::task::spawn_blocking(move || read_document(&path)).await {
+            Ok(Ok(chunks)) => chunks,
+            Ok(Err(e)) => {
+                index.files.remove(&document.relative);
+                errors.push(serde_json::json!({ "file": document.relative, "error": e }));
+                continue;
+            }
+            Err(_) => {
+                index.files.remove(&document.relative);
+                errors.push(
+                    serde_json::json!({ "file": document.relative, "error": tool_text!("index.unreadable") }),
+                );
+                continue;
+            }
+        };
+
+        let mut vectors = Vec::with_capacity(chunks.len());
+        for batch in chunks.chunks(EMBED_BATCH) {
+            match embed(embedder, batch.iter().map(|c| c.text.clone()).collect()).await {
+                Ok(batch_vectors) => vectors.extend(batch_vectors),
+                Err(e) => {
+                    // Keep what was embedded so far for the next build
+                    if let Err(save_error) = index.save() {
+                        tracing::warn!("Failed to save partial index: {}", save_error);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        embedded_chunks += chunks.len();
+        index.files.insert(
+            document.relative.clone(),
+            IndexedFile {
+                modified_ms: document.modified_ms,
+                size: document.size,
+                chunks: chunks
+                    .into_iter()
+                    .zip(vectors)
+                    .map(|(chunk, vector)| Chunk {
+                        start_line: chunk.start_line,
+                        end_line: chunk.end_line,
+                        page: chunk.page,
+                        text: chunk.text,
+                        vector,
+                    })
+                    .collect(),
+            },
+        );
+        if (done + 1) % SAVE_EVERY_FILES == 0 {
+            index.save().map_err(|e| storage_error(root, e))?;
+        }
+    }
+
+    index.updated_at = Utc::now();
+    index.save().map_err(|e| storage_error(root, e))?;
+
+    Ok(serde_json::json!({
+        "folder": root.display().to_string(),
+        "files": index.files.len(),
+        "chunks": index.chunk_count(),
+        "files_embedded": plan.changed.len() - errors.len(),
+        "files_unchanged": plan.unchanged,
+        "files_removed": plan.removed.len(),
+        "chunks_embedded": embedded_chunks,
+        "errors": errors,
+    }))
+}
+
+// ============================================================================
+// index_build
+// ============================================================================
+
+/// Builds or updates the document index; needs the engine for embeddings,
+/// hence registered by the app next to `agent_task`
+pub struct IndexBuildTool {
+    engine: Arc<Mutex<LlamaEngine>>,
+}
+
+impl IndexBuildTool {
+    pub fn new(engine: Arc<Mutex<LlamaEngine>>) -> Self {
+        Self { engine }
+    }
+}
+
+#[async_trait]
+impl Tool for IndexBuildTool {
+    fn name(&self) -> &str {
+        "index_build"
+    }
+
+    fn description(&self) -> &str {
+        "Build or update the semantic index of a folder of documents (text, Markdown, PDF) with the configured embedding model. Only new and modified files are embedded again. Without folder, updates every folder configured in the settings."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "folder": {
+                    "type": "string",
+                    "description": "Folder to index (default: the folders configured in the settings)"
+                },
+                "full": {
+                    "type": "boolean",
+                    "description": "Embed every file again, even unchanged ones",
+                    "default": false
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let folders = target_folders(&params)?;
+        if folders.is_empty() {
+            return Err(no_folders());
+        }
+        let full = params["full"].as_bool().unwrap_or(false);
+        let model = embedding_model()?;
+        let embedder = embedder(&self.engine, &model).await?;
+
+        let mut reports = Vec::new();
+        for root in &folders {
+            reports.push(build_index(&embedder, &model, root, full).await?);
+        }
+        let embedded: u64 = reports
+            .iter()
+            .filter_map(|r| r["files_embedded"].as_u64())
+            .sum();
+        let chunks: u64 = reports.iter().filter_map(|r| r["chunks"].as_u64()).sum();
+
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!("index_build.done", folders.len(), embedded, chunks),
+            data: serde_json::json!({ "indexes": reports }),
+        })
+    }
+}
+
+// ============================================================================
+// index_search
+// ============================================================================
+
+/// Semantic search in the document index
+pub struct IndexSearchTool {
+    engine: Arc<Mutex<LlamaEngine>>,
+}
+
+impl IndexSearchTool {
+    pub fn new(engine: Arc<Mutex<LlamaEngine>>) -> Self {
+        Self { engine }
+    }
+}
+
+#[async_trait]
+impl Tool for IndexSearchTool {
+    fn name(&self) -> &str {
+        "index_search"
+    }
+
+    fn description(&self) -> &str {
+        "Semantic search in the indexed documents: returns the passages closest in meaning to the query, with file, lines (and PDF page) and a similarity score. Build the index first with index_build."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Question or description of what to find"
+                },
+                "top_k": {
+                    "type": "integer",
+                    "description": "Passages returned (default: 5, max: 20)",
+                    "default": 5
+                },
+                "folder": {
+                    "type": "string",
+                    "description": "Indexed folder to search (default: all configured folders)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let query = params["query"]
+            .as_str()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("query is required".into()))?;
+        let top_k = params["top_k"]
+            .as_u64()
+            .unwrap_or(DEFAULT_TOP_K)
+            .clamp(1, MAX_TOP_K) as usize;
+        let folders = target_folders(&params)?;
+        if folders.is_empty() {
+            return Err(no_folders());
+        }
+        let model = embedding_model()?;
+
+        let mut indexes = Vec::new();
+        let mut skipped = Vec::new();
+        for root in &folders {
+            match DocumentIndex::load(root) {
+                Ok(Some(index)) if index.model == model => indexes.push(index),
+                Ok(Some(_)) => skipped.push(serde_json::json!({
+                    "folder": root.display().to_string(),
+                    "reason": tool_text!("index_search.other_model")
+                })),
+                Ok(None) => skipped.push(serde_json::json!({
+                    "folder": root.display().to_string(),
+                    "reason": tool_text!("index_search.not_indexed")
+                })),
+                Err(e) => skipped.push(serde_json::json!({
+                    "folder": root.display().to_string(),
+                    "reason": e.to_string()
+                })),
+            }
+        }
+        if indexes.is_empty() {
+            return Err(ToolError::ExecutionFailed(tool_text!(
+                "index_search.no_index",
+                serde_json::to_string(&skipped).unwrap_or_default()
+            )));
+        }
+
+        let embedder = embedder(&self.engine, &model).await?;
+        let query_vector = embed(&embedder, vec![query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                ToolError::ExecutionFailed(tool_text!("index_search.empty_embedding").to_string())
+            })?;
+
+        let mut hits: Vec<_> = indexes
+            .iter()
+            .flat_map(|index| {
+                index
+                    .search(&query_vector, top_k)
+                    .into_iter()
+                    .map(move |hit| (index.root.join(&hit.file), hit))
+            })
+            .collect();
+        hits.sort_by(|(_, a), (_, b)| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+
+        let results: Vec<Value> = hits
+            .iter()
+            .map(|(path, hit)| {
+                serde_json::json!({
+                    "file": path.display().to_string(),
+                    "start_line": hit.start_line,
+                    "end_line": hit.end_line,
+                    "page": hit.page,
+                    "score": (hit.score * 1000.0).round() / 1000.0,
+                    "text": hit.text,
+                })
+            })
+            .collect();
+
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!("index_search.done", results.len(), query),
+            data: serde_json::json!({
+                "query": query,
+                "results": results,
+                "skipped": skipped,
+            }),
+        })
+    }
+}
+
+// ============================================================================
+// index_status
+// ============================================================================
+
+/// Indexed folders, their size, and the files changed since the last build
+pub struct IndexStatusTool;
+
+#[async_trait]
+impl Tool for IndexStatusTool {
+    fn name(&self) -> &str {
+        "index_status"
+    }
+
+    fn description(&self) -> &str {
+        "Show the document index: embedding model, indexed folders with their file and passage counts, last build time, and how many files changed since (run index_build to update)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "folder": {
+                    "type": "string",
+                    "description": "Folder to inspect (default: all configured folders)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let folders = target_folders(&params)?;
+        let model = settings().embedding_model_path;
+
+        let mut statuses = Vec::new();
+        for root in folders {
+            let folder = root.display().to_string();
+            let index = match DocumentIndex::load(&root) {
+                Ok(Some(index)) => index,
+                Ok(None) => {
+                    statuses.push(serde_json::json!({ "folder": folder, "indexed": false }));
+                    continue;
+                }
+                Err(e) => {
+                    statuses.push(serde_json::json!({ "folder": folder, "indexed": false, "error": e.to_string() }));
+                    continue;
+                }
+            };
+            let scan_root = root.clone();
+            let plan = match tokio::task::spawn_blocking(move || scan_documents(&scan_root)).await {
+                Ok(Ok(documents)) => Some(index.plan(&documents)),
+                _ => None,
+            };
+            statuses.push(serde_json::json!({
+                "folder": folder,
+                "indexed": true,
+                "files": index.files.len(),
+                "chunks": index.chunk_count(),
+                "model": index.model,
+                "model_current": model.as_deref() == Some(index.model.as_str()),
+                "updated_at": index.updated_at.to_rfc3339(),
+                "files_changed": plan.as_ref().map(|p| p.changed.len()),
+                "files_removed": plan.as_ref().map(|p| p.removed.len()),
+            }));
+        }
+
+        let indexed = statuses.iter().filter(|s| s["indexed"] == true).count();
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!("index_status.done", indexed, statuses.len()),
+            data: serde_json::json!({
+                "embedding_model": model,
+                "folders": statuses,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requires_folders_and_model() {
+        set_document_index_settings(DocumentIndexSettings::default());
+        let engine = Arc::new(Mutex::new(LlamaEngine::new()));
+
+        let error = IndexBuildTool::new(engine.clone())
+            .execute(serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("No indexed folder"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().to_string_lossy().to_string();
+        let error = IndexSearchTool::new(engine)
+            .execute(serde_json::json!({ "query": "setup", "folder": folder }))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("embedding model"));
+
+        let status = IndexStatusTool
+            .execute(serde_json::json!({ "folder": folder }))
+            .await
+            .unwrap();
+        assert_eq!(status.data["folders"][0]["indexed"], false);
+        assert_eq!(status.data["embedding_model"], Value::Null);
+    }
+}
@@ -0,0 +1,947 @@
+//! LSP client - diagnostics and hover from language servers
+//!
+//! The language server configured for a file's extension is spawned over
+//! stdio and kept running per (language, workspace root), so a project is
+//! indexed once. Messages use the LSP `Content-Length` framing; like the MCP
+//! client, requests are matched to responses by id. A reader task answers the
+//! server's own requests and records `publishDiagnostics`, which the
+//! diagnostics tool waits for after opening or updating the file. Servers are
+//! killed when the app exits.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde_json::Value;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::storage::settings::{default_language_servers, LanguageServerConfig};
+
+/// Time a server gets to answer `initialize` (it may start indexing first)
+const INIT_TIMEOUT_SECS: u64 = 60;
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+const DEFAULT_WAIT_SECS: u64 = 20;
+const MAX_WAIT_SECS: u64 = 120;
+
+/// Quiet time after the last `publishDiagnostics` before the list counts as
+/// settled; servers often publish an empty list first
+const SETTLE: Duration = Duration::from_millis(1500);
+
+const DEFAULT_MAX_DIAGNOSTICS: u64 = 50;
+const MAX_HOVER_BYTES: usize = 4000;
+
+/// Servers in use, installed from the settings at startup and on each change
+static LANGUAGE_SERVERS: Lazy<RwLock<Vec<LanguageServerConfig>>> =
+    Lazy::new(|| RwLock::new(default_language_servers()));
+
+/// (language, workspace root)
+type ServerKey = (String, PathBuf);
+
+/// Running servers by (language, workspace root)
+static SERVERS: Lazy<StdMutex<HashMap<ServerKey, Arc<LspServer>>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+/// Use `servers` from now on; running servers are stopped so the next call
+/// starts the configured command
+pub fn set_language_servers(servers: Vec<LanguageServerConfig>) {
+    let mut current = LANGUAGE_SERVERS.write().unwrap_or_else(|e| e.into_inner());
+    if *current != servers {
+        *current = servers;
+        drop(current);
+        shutdown_all();
+    }
+}
+
+/// Kill every running language server; called when the app exits
+///
+/// Synchronous so it works from the event loop's exit handler.
+pub fn shutdown_all() {
+    let servers: Vec<_> = SERVERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain()
+        .map(|(_, server)| server)
+        .collect();
+    for server in servers {
+        tracing::info!(
+            "Stopping language server {} ({})",
+            server.command,
+            server.root.display()
+        );
+        server.kill();
+    }
+}
+
+fn server_for(path: &Path) -> Result<LanguageServerConfig, ToolError> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let servers = LANGUAGE_SERVERS.read().unwrap_or_else(|e| e.into_inner());
+    servers
+        .iter()
+        .find(|s| {
+            s.extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+        })
+        .cloned()
+        .ok_or_else(|| {
+            let configured: Vec<String> = servers
+                .iter()
+                .map(|s| format!("{} (.{})", s.language, s.extensions.join(", .")))
+                .collect();
+            ToolError::InvalidParameters(format!(
+                "No language server configured for '.{}' files. Configured: {}",
+                extension,
+                if configured.is_empty() {
+                    "none".to_string()
+                } else {
+                    configured.join("; ")
+                }
+            ))
+        })
+}
+
+/// Files marking a project root, by language
+fn root_markers(language: &str) -> &'static [&'static str] {
+    match language {
+        "rust" => &["Cargo.toml"],
+        "python" => &[
+            "pyproject.toml",
+            "setup.py",
+            "setup.cfg",
+            "requirements.txt",
+        ],
+        "typescript" | "javascript" => &["tsconfig.json", "jsconfig.json", "package.json"],
+        "go" => &["go.mod"],
+        _ => &[],
+    }
+}
+
+/// Nearest folder above `file` with a root marker, else a `.git`, else the
+/// file's folder
+fn workspace_root(file: &Path, language: &str) -> PathBuf {
+    let parent = file.parent().unwrap_or(file);
+    let find = |markers: &[&str]| {
+        parent
+            .ancestors()
+            .find(|dir| markers.iter().any(|m| dir.join(m).exists()))
+            .map(Path::to_path_buf)
+    };
+    find(root_markers(language))
+        .or_else(|| find(&[".git"]))
+        .unwrap_or_else(|| parent.to_path_buf())
+}
+
+/// Language id of a document, which for TypeScript servers depends on the
+/// extension
+fn language_id(language: &str, path: &Path) -> String {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "tsx" => "typescriptreact",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "javascriptreact",
+        _ => language,
+    }
+    .to_string()
+}
+
+/// Running server for `path`, started on first use
+async fn server(path: &Path, params: &Value) -> Result<Arc<LspServer>, ToolError> {
+    let config = server_for(path)?;
+    let root = match params["workspace_root"].as_str() {
+        Some(root) => canonical(root)?,
+        None => workspace_root(path, &config.language),
+    };
+    let key = (config.language.clone(), root.clone());
+    if let Some(server) = SERVERS.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        if server.alive.load(Ordering::Relaxed) {
+            return Ok(server.clone());
+        }
+    }
+
+    let server = LspServer::start(&config, root).await?;
+    let mut servers = SERVERS.lock().unwrap_or_else(|e| e.into_inner());
+    // Another call may have started one meanwhile
+    match servers.get(&key) {
+        Some(running) if running.alive.load(Ordering::Relaxed) => {
+            server.kill();
+            Ok(running.clone())
+        }
+        _ => {
+            if let Some(dead) = servers.insert(key, server.clone()) {
+                dead.kill();
+            }
+            Ok(server)
+        }
+    }
+}
+
+fn canonical(raw: &str) -> Result<PathBuf, ToolError> {
+    let path = resolve_and_validate_path(raw)?;
+    std::fs::canonicalize(&path)
+        .map_err(|e| ToolError::io(&path, format!("{}: {}", path.display(), e), &e))
+}
+
+fn file_uri(path: &Path) -> Result<String, ToolError> {
+    Url::from_file_path(path)
+        .map(|url| url.to_string())
+        .map_err(|_| {
+            ToolError::InvalidParameters(format!("{} is not an absolute path", path.display()))
+        })
+}
+
+// ============================================================================
+// Framing
+// ============================================================================
+
+async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    message: &Value,
+) -> std::io::Result<()> {
+    let body = serde_json::to_string(message)?;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).as_bytes())
+        .await?;
+    writer.flush().await
+}
+
+/// Next message, `None` at end of stream; a body that is not JSON is an
+/// `InvalidData` error after which the stream is still in step
+async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Option<Value>> {
+    let mut length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let mut body = vec![0; length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// Diagnostics last published for a document, with a counter bumped on each
+/// publication
+#[derive(Default)]
+struct Published {
+    generation: u64,
+    diagnostics: Vec<Value>,
+}
+
+struct LspServer {
+    command: String,
+    language: String,
+    root: PathBuf,
+    child: StdMutex<Option<Child>>,
+    stdin: Arc<Mutex<ChildStdin>>,
+    pending: Arc<StdMutex<HashMap<u64, oneshot::Sender<Value>>>>,
+    published: Arc<StdMutex<HashMap<String, Published>>>,
+    /// Open documents by uri: version and the text the server has
+    documents: Mutex<HashMap<String, (i64, String)>>,
+    alive: Arc<AtomicBool>,
+    request_id: AtomicU64,
+}
+
+impl LspServer {
+    async fn start(config: &LanguageServerConfig, root: PathBuf) -> Result<Arc<Self>, ToolError> {
+        let mut cmd = Command::new(&config.command);
+        cmd.args(&config.args)
+            .current_dir(&root)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true);
+        let mut child = cmd.spawn().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ToolError::ExecutionFailed(format!(
+                "executable '{}' not found: install the {} language server or change its command in Settings > Tools > Language servers",
+                config.command, config.language
+            )),
+            _ => ToolError::ExecutionFailed(tool_text!("lsp.start_error", config.command, e)),
+        })?;
+        let stdin = child.stdin.take().ok_or_else(|| {
+            ToolError::ExecutionFailed(tool_text!("lsp.no_stdin").into())
+        })?;
+        let stdout = child.stdout.take().ok_or_else(|| {
+            ToolError::ExecutionFailed(tool_text!("lsp.no_stdout").into())
+        })?;
+        tracing::info!(
+            "Started language server {} for {}",
+            config.command,
+            root.display()
+        );
+
+        let server = Arc::new(Self {
+            command: config.command.clone(),
+            language: config.language.clone(),
+            root,
+            child: StdMutex::new(Some(child)),
+            stdin: Arc::new(Mutex::new(stdin)),
+            pending: Arc::new(StdMutex::new(HashMap::new())),
+            published: Arc::new(StdMutex::new(HashMap::new())),
+            documents: Mutex::new(HashMap::new()),
+            alive: Arc::new(AtomicBool::new(true)),
+            request_id: AtomicU64::new(1),
+        });
+        server.spawn_reader(stdout);
+
+        let root_uri = file_uri(&server.root)?;
+        let name = server
+            .root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let init = server.request(
+            "initialize",
+            serde_json::json!({
+                "processId": std::process::id(),
+                "rootUri": root_uri,
+                "workspaceFolders": [{ "uri": root_uri, "name": name }],
+                "capabilities": {
+                    "textDocument": {
+                        "synchronization": { "didSave": true },
+                        "publishDiagnostics": { "relatedInformation": false },
+                        "hover": { "contentFormat": ["markdown", "plaintext"] }
+                    },
+                    "workspace": { "configuration": true, "workspaceFolders": true }
+                },
+                "clientInfo": { "name": "localclaw", "version": env!("CARGO_PKG_VERSION") }
+            }),
+            INIT_TIMEOUT_SECS,
+        );
+        if let Err(e) = init.await {
+            server.kill();
+            return Err(e);
+        }
+        server.notify("initialized", serde_json::json!({})).await?;
+        Ok(server)
+    }
+
+    /// Read the server's messages until it exits: responses go to their
+    /// request, the server's requests get an empty answer, diagnostics are
+    /// recorded
+    fn spawn_reader(&self, stdout: tokio::process::ChildStdout) {
+        let stdin = self.stdin.clone();
+        let pending = self.pending.clone();
+        let published = self.published.clone();
+        let alive = self.alive.clone();
+        let command = self.command.clone();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(stdout);
+            loop {
+                let message = match read_message(&mut reader).await {
+                    Ok(Some(message)) => message,
+                    Ok(None) => break,
+                    Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                        tracing::warn!("Language server {} sent invalid JSON: {}", command, e);
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Reading from language server {} failed: {}", command, e);
+                        break;
+                    }
+                };
+                match (message.get("id"), message["method"].as_str()) {
+                    (Some(id), None) => {
+                        let sender = id.as_u64().and_then(|id| {
+                            pending
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .remove(&id)
+                        });
+                        if let Some(sender) = sender {
+                            let _ = sender.send(message);
+                        }
+                    }
+                    (Some(id), Some(method)) => {
+                        // One null per requested setting: the server's defaults
+                        let result = match method {
+                            "workspace/configuration" => Value::Array(vec![
+                                Value::Null;
+                                message["params"]
+                                    ["items"]
+                                    .as_array()
+                                    .map_or(0, Vec::len)
+                            ]),
+                            _ => Value::Null,
+                        };
+                        let reply =
+                            serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result });
+                        if write_message(&mut *stdin.lock().await, &reply)
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    (None, Some("textDocument/publishDiagnostics")) => {
+                        let params = &message["params"];
+                        if let Some(uri) = params["uri"].as_str() {
+                            let mut published = published.lock().unwrap_or_else(|e| e.into_inner());
+                            let entry = published.entry(uri.to_string()).or_default();
+                            entry.generation += 1;
+                            entry.diagnostics = params["diagnostics"]
+                                .as_array()
+                                .cloned()
+                                .unwrap_or_default();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            alive.store(false, Ordering::Relaxed);
+            // Requests still waiting fail at once
+            pending.lock().unwrap_or_else(|e| e.into_inner()).clear();
+            tracing::info!("Language server {} exited", command);
+        });
+    }
+
+    fn kill(&self) {
+        self.alive.store(false, Ordering::Relaxed);
+        if let Some(mut child) = self.child.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            let _ = child.start_kill();
+        }
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<(), ToolError> {
+        let message = serde_json::json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&mut *self.stdin.lock().await, &message)
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionFailed(tool_text!("lsp.write_error", self.command, e))
+            })
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        params: Value,
+        timeout_secs: u64,
+    ) -> Result<Value, ToolError> {
+        if !self.alive.load(Ordering::Relaxed) {
+            return Err(ToolError::ExecutionFailed(tool_text!("lsp.stopped", self.command)));
+        }
+        let id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, sender);
+
+        let message =
+            serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let response = async move {
+            write_message(&mut *self.stdin.lock().await, &message)
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionFailed(tool_text!("lsp.write_error", self.command, e))
+                })?;
+            receiver.await.map_err(|_| {
+                ToolError::ExecutionFailed(tool_text!("lsp.stopped", self.command))
+            })
+        };
+        let response = match tokio::time::timeout(Duration::from_secs(timeout_secs), response).await
+        {
+            Ok(response) => response?,
+            Err(_) => {
+                self.pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&id);
+                return Err(ToolError::Timeout {
+                    after_secs: Some(timeout_secs),
+                });
+            }
+        };
+        if let Some(error) = response.get("error") {
+            return Err(ToolError::ExecutionFailed(format!(
+                "{} ({}): {}",
+                method,
+                self.command,
+                error["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(response["result"].clone())
+    }
+
+    fn generation(&self, uri: &str) -> u64 {
+        self.published
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(uri)
+            .map_or(0, |p| p.generation)
+    }
+
+    /// Give the server the file's current text; false when it already had it
+    async fn sync_document(&self, path: &Path, uri: &str) -> Result<bool, ToolError> {
+        let text = tokio::fs::read_to_string(path).await.map_err(|e| {
+            ToolError::io(path, tool_text!("dev.read_path_error", path.display(), e), &e)
+        })?;
+        let mut documents = self.documents.lock().await;
+        match documents.get_mut(uri) {
+            Some((_, current)) if *current == text => Ok(false),
+            Some((version, current)) => {
+                *version += 1;
+                *current = text.clone();
+                let version = *version;
+                drop(documents);
+                self.notify(
+                    "textDocument/didChange",
+                    serde_json::json!({
+                        "textDocument": { "uri": uri, "version": version },
+                        "contentChanges": [{ "text": text }]
+                    }),
+                )
+                .await?;
+                Ok(true)
+            }
+            None => {
+                documents.insert(uri.to_string(), (1, text.clone()));
+                drop(documents);
+                self.notify(
+                    "textDocument/didOpen",
+                    serde_json::json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id(&self.language, path),
+                            "version": 1,
+                            "text": text
+                        }
+                    }),
+                )
+                .await?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Diagnostics of `uri` once they settle after `since`; the bool is false
+    /// when none were published before `deadline`
+    async fn wait_diagnostics(
+        &self,
+        uri: &str,
+        since: u64,
+        changed: bool,
+        deadline: Instant,
+    ) -> (Vec<Value>, bool) {
+        let mut seen = since;
+        // Unchanged, what was published is current unless something follows
+        let mut last_change = (!changed).then(Instant::now);
+        loop {
+            let generation = self.generation(uri);
+            if generation > seen {
+                seen = generation;
+                last_change = Some(Instant::now());
+            }
+            let settled = last_change.is_some_and(|t| t.elapsed() >= SETTLE);
+            if settled || Instant::now() >= deadline || !self.alive.load(Ordering::Relaxed) {
+                let diagnostics = self
+                    .published
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(uri)
+                    .map(|p| p.diagnostics.clone())
+                    .unwrap_or_default();
+                return (diagnostics, last_change.is_some());
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+fn severity(diagnostic: &Value) -> &'static str {
+    match diagnostic["severity"].as_u64() {
+        Some(1) => "error",
+        Some(2) => "warning",
+        Some(3) => "information",
+        Some(4) => "hint",
+        // Unset: up to the client, errors are the safe reading
+        _ => "error",
+    }
+}
+
+/// LSP diagnostic with 1-based lines and columns
+fn diagnostic_json(diagnostic: &Value) -> Value {
+    let position = |end: &str, field: &str| diagnostic["range"][end][field].as_u64().map(|n| n + 1);
+    let code = match &diagnostic["code"] {
+        Value::String(code) => Some(code.clone()),
+        Value::Number(code) => Some(code.to_string()),
+        _ => None,
+    };
+    serde_json::json!({
+        "severity": severity(diagnostic),
+        "line": position("start", "line"),
+        "column": position("start", "character"),
+        "end_line": position("end", "line"),
+        "end_column": position("end", "character"),
+        "message": diagnostic["message"],
+        "code": code,
+        "source": diagnostic["source"],
+    })
+}
+
+/// Text of a hover result: MarkupContent, MarkedString or a list of them
+fn hover_text(contents: &Value) -> String {
+    match contents {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(hover_text)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        Value::Object(object) => match (
+            object.get("language").and_then(Value::as_str),
+            object.get("value"),
+        ) {
+            (Some(language), Some(Value::String(value))) => {
+                format!("```{}\n{}\n```", language, value)
+            }
+            (None, Some(Value::String(value))) => value.clone(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+/// UTF-16 offset of the 1-based `column` (in characters) of `line_text`
+fn utf16_character(line_text: &str, column: u64) -> u64 {
+    line_text
+        .chars()
+        .take(column.saturating_sub(1) as usize)
+        .map(|c| c.len_utf16() as u64)
+        .sum()
+}
+
+fn path_param(params: &Value) -> Result<PathBuf, ToolError> {
+    let raw = params["path"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+    canonical(raw)
+}
+
+// ============================================================================
+// LspDiagnosticsTool
+// ============================================================================
+
+pub struct LspDiagnosticsTool;
+
+#[async_trait]
+impl Tool for LspDiagnosticsTool {
+    fn name(&self) -> &str {
+        "lsp_diagnostics"
+    }
+
+    fn description(&self) -> &str {
+        "Open a file in its language server (rust-analyzer, pyright, typescript-language-server...) and return its diagnostics: severity, line, column, message, code. The server stays running, so later calls are fast."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "File to check"
+                },
+                "workspace_root": {
+                    "type": "string",
+                    "description": "Project root (default: nearest Cargo.toml, pyproject.toml, package.json... above the file)"
+                },
+                "wait_secs": {
+                    "type": "integer",
+                    "description": "Longest wait for the server's diagnostics (default: 20, max: 120)",
+                    "default": 20
+                },
+                "max_diagnostics": {
+                    "type": "integer",
+                    "description": "Diagnostics returned, errors first (default: 50)",
+                    "default": 50
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = path_param(&params)?;
+        let server = server(&path, &params).await?;
+        let uri = file_uri(&path)?;
+        let wait = params["wait_secs"]
+            .as_u64()
+            .unwrap_or(DEFAULT_WAIT_SECS)
+            .min(MAX_WAIT_SECS);
+        let deadline = Instant::now() + Duration::from_secs(wait);
+
+        let since = server.generation(&uri);
+        let changed = server.sync_document(&path, &uri).await?;
+        // Saving is what makes rust-analyzer run cargo check
+        server
+            .notify(
+                "textDocument/didSave",
+                serde_json::json!({ "textDocument": { "uri": uri } }),
+            )
+            .await?;
+        let (mut diagnostics, complete) = server
+            .wait_diagnostics(&uri, since, changed, deadline)
+            .await;
+
+        let rank = |d: &Value| match severity(d) {
+            "error" => 0,
+            "warning" => 1,
+            "information" => 2,
+            _ => 3,
+        };
+        diagnostics.sort_by_key(|d| (rank(d), d["range"]["start"]["line"].as_u64().unwrap_or(0)));
+        let count = |level: &str| diagnostics.iter().filter(|d| severity(d) == level).count();
+        let (errors, warnings) = (count("error"), count("warning"));
+        let max = params["max_diagnostics"]
+            .as_u64()
+            .unwrap_or(DEFAULT_MAX_DIAGNOSTICS) as usize;
+
+        let mut data = serde_json::json!({
+            "path": path.display().to_string(),
+            "language": server.language,
+            "server": server.command,
+            "workspace_root": server.root.display().to_string(),
+            "errors": errors,
+            "warnings": warnings,
+            "diagnostics_total": diagnostics.len(),
+            "diagnostics": diagnostics.iter().take(max).map(diagnostic_json).collect::<Vec<_>>(),
+            "complete": complete,
+        });
+        if diagnostics.len() > max {
+            data["diagnostics_truncated"] = true.into();
+        }
+
+        let message = if complete {
+            tool_text!(
+                "lsp_diagnostics.done",
+                path.display(),
+                errors,
+                warnings
+            )
+        } else {
+            tool_text!(
+                "lsp_diagnostics.timeout",
+                path.display(),
+                server.command,
+                wait
+            )
+        };
+        Ok(ToolResult {
+            success: true,
+            message,
+            data,
+        })
+    }
+}
+
+// ============================================================================
+// LspHoverTool
+// ============================================================================
+
+pub struct LspHoverTool;
+
+#[async_trait]
+impl Tool for LspHoverTool {
+    fn name(&self) -> &str {
+        "lsp_hover"
+    }
+
+    fn description(&self) -> &str {
+        "Ask the file's language server what the symbol at a position is: its type, signature and documentation."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "File containing the symbol"
+                },
+                "line": {
+                    "type": "integer",
+                    "description": "Line of the symbol (1-based)"
+                },
+                "column": {
+                    "type": "integer",
+                    "description": "Column of the symbol (1-based)"
+                },
+                "workspace_root": {
+                    "type": "string",
+                    "description": "Project root (default: detected from the file)"
+                }
+            },
+            "required": ["path", "line", "column"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = path_param(&params)?;
+        let (Some(line), Some(column)) = (params["line"].as_u64(), params["column"].as_u64())
+        else {
+            return Err(ToolError::InvalidParameters(
+                "line and column are required".into(),
+            ));
+        };
+        if line == 0 || column == 0 {
+            return Err(ToolError::InvalidParameters(
+                "line and column start at 1".into(),
+            ));
+        }
+        let server = server(&path, &params).await?;
+        let uri = file_uri(&path)?;
+        server.sync_document(&path, &uri).await?;
+
+        let line_text = {
+            let documents = server.documents.lock().await;
+            documents
+                .get(&uri)
+                .and_then(|(_, text)| text.lines().nth(line as usize - 1).map(str::to_string))
+                .ok_or_else(|| {
+                    ToolError::InvalidParameters(format!("{} has no line {}", path.display(), line))
+                })?
+        };
+        let result = server
+            .request(
+                "textDocument/hover",
+                serde_json::json!({
+                    "textDocument": { "uri": uri },
+                    "position": { "line": line - 1, "character": utf16_character(&line_text, column) }
+                }),
+                REQUEST_TIMEOUT_SECS,
+            )
+            .await?;
+
+        let text = hover_text(&result["contents"]);
+        if text.trim().is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                message: tool_text!(
+                    "lsp_hover.empty",
+                    path.display(),
+                    line,
+                    column
+                ),
+                data: serde_json::json!({ "contents": null }),
+            });
+        }
+        let shown = crate::truncate_str(text.trim(), MAX_HOVER_BYTES);
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!("lsp_hover.done", path.display(), line, column),
+            data: serde_json::json!({
+                "contents": shown,
+                "truncated": shown.len() < text.trim().len(),
+                "line_text": line_text.trim(),
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_framing_round_trip() {
+        let mut buffer = Vec::new();
+        let first = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": { "text": "été" } });
+        let second = serde_json::json!({ "jsonrpc": "2.0", "method": "window/logMessage" });
+        write_message(&mut buffer, &first).await.unwrap();
+        write_message(&mut buffer, &second).await.unwrap();
+
+        // Servers may send extra headers
+        let mut stream = b"Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n".to_vec();
+        stream.extend_from_slice(&buffer);
+        let mut reader = BufReader::new(stream.as_slice());
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(first));
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(second));
+        assert_eq!(read_message(&mut reader).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_diagnostic_json() {
+        let diagnostic = serde_json::json!({
+            "range": { "start": { "line": 3, "character": 8 }, "end": { "line": 3, "character": 12 } },
+            "severity": 2,
+            "code": 6133,
+            "source": "ts",
+            "message": "'x' is declared but its value is never read."
+        });
+        let json = diagnostic_json(&diagnostic);
+        assert_eq!(json["severity"], "warning");
+        assert_eq!(
+            (json["line"].as_u64(), json["column"].as_u64()),
+            (Some(4), Some(9))
+        );
+        assert_eq!(json["code"], "6133");
+    }
+
+    #[test]
+    fn test_hover_text_and_positions() {
+        let markup = serde_json::json!({ "kind": "markdown", "value": "```rust\nfn main()\n```" });
+        assert_eq!(hover_text(&markup), "```rust\nfn main()\n```");
+        let marked =
+            serde_json::json!([{ "language": "python", "value": "def f() -> int" }, "Docs"]);
+        assert_eq!(
+            hover_text(&marked),
+            "```python\ndef f() -> int\n```\n\nDocs"
+        );
+
+        // '😀' takes two UTF-16 code units
+        assert_eq!(utf16_character("let 😀x = 1;", 6), 6);
+        assert_eq!(
+            language_id("typescript", Path::new("App.tsx")),
+            "typescriptreact"
+        );
+    }
+
+    #[test]
+    fn test_workspace_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let crate_dir = dir.path().join("core");
+        std::fs::create_dir_all(crate_dir.join("src")).unwrap();
+        std::fs::write(crate_dir.join("Cargo.toml"), "").unwrap();
+        let file = crate_dir.join("src").join("lib.rs");
+        assert_eq!(workspace_root(&file, "rust"), crate_dir);
+        // No marker: the file's folder
+        assert_eq!(workspace_root(&file, "python"), crate_dir.join("src"));
+    }
+}
@@ -12,8 +12,11 @@ use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
+use crate::agent::language::tool_text;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::storage::secrets;
 
 // ============================================================================
 // MCP Server Configuration
@@ -114,21 +117,21 @@ impl StdioMcpClient {
         cmd.stderr(std::process::Stdio::piped());
 
         for (key, value) in &self.config.env {
+            let value = secrets::substitute(value).map_err(|e| {
+                ToolError::ExecutionFailed(tool_text!("mcp.env_error", self.config.name, key, e))
+            })?;
             cmd.env(key, value);
         }
 
         let mut child = cmd.spawn().map_err(|e| {
-            ToolError::ExecutionFailed(format!(
-                "Impossible de démarrer le serveur MCP '{}': {}. Vérifiez que '{}' est installé.",
-                self.config.name, e, command
-            ))
+            ToolError::ExecutionFailed(tool_text!("mcp.start_error", self.config.name, e, command))
         })?;
 
         let stdin = child.stdin.take().ok_or_else(|| {
-            ToolError::ExecutionFailed("Impossible d'accéder au stdin du serveur MCP".into())
+            ToolError::ExecutionFailed(tool_text!("mcp.no_stdin").into())
         })?;
         let stdout = child.stdout.take().ok_or_else(|| {
-            ToolError::ExecutionFailed("Impossible d'accéder au stdout du serveur MCP".into())
+            ToolError::ExecutionFailed(tool_text!("mcp.no_stdout").into())
         })?;
 
         *self.child.lock().await = Some(child);
@@ -170,16 +173,16 @@ impl StdioMcpClient {
     async fn send_notification(&self, notification: Value) -> Result<(), ToolError> {
         let mut stdin = self.stdin.lock().await;
         let stdin = stdin.as_mut().ok_or_else(|| {
-            ToolError::ExecutionFailed("Serveur MCP non démarré".into())
+            ToolError::ExecutionFailed(tool_text!("mcp.not_started").into())
         })?;
 
         let msg = serde_json::to_string(&notification)
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur sérialisation: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("mcp.serialize_error", e)))?;
 
         stdin
             .write_all(format!("{}\n", msg).as_bytes())
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur écriture stdin: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("mcp.stdin_error", e)))?;
         stdin.flush().await.ok();
 
         Ok(())
@@ -188,23 +191,23 @@ impl StdioMcpClient {
     async fn send_request(&self, request: Value) -> Result<Value, ToolError> {
         let mut stdin = self.stdin.lock().await;
         let stdin = stdin.as_mut().ok_or_else(|| {
-            ToolError::ExecutionFailed("Serveur MCP non démarré".into())
+            ToolError::ExecutionFailed(tool_text!("mcp.not_started").into())
         })?;
 
         let msg = serde_json::to_string(&request)
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur sérialisation: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("mcp.serialize_error", e)))?;
 
         stdin
             .write_all(format!("{}\n", msg).as_bytes())
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur écriture stdin: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("mcp.stdin_error", e)))?;
         stdin.flush().await.ok();
         let _ = stdin;
 
         // Read response
         let mut reader = self.reader.lock().await;
         let reader = reader.as_mut().ok_or_else(|| {
-            ToolError::ExecutionFailed("Serveur MCP non démarré".into())
+            ToolError::ExecutionFailed(tool_text!("mcp.not_started").into())
         })?;
 
         let mut line = String::new();
@@ -214,12 +217,10 @@ impl StdioMcpClient {
             let bytes_read = reader
                 .read_line(&mut line)
                 .await
-                .map_err(|e| ToolError::ExecutionFailed(format!("Erreur lecture stdout: {}", e)))?;
+                .map_err(|e| ToolError::ExecutionFailed(tool_text!("mcp.stdout_error", e)))?;
 
             if bytes_read == 0 {
-                return Err(ToolError::ExecutionFailed(
-                    "Le serveur MCP a fermé la connexion".into(),
-                ));
+                return Err(ToolError::ExecutionFailed(tool_text!("mcp.closed").into()));
             }
 
             let trimmed = line.trim();
@@ -229,11 +230,12 @@ impl StdioMcpClient {
 
             // Try to parse as JSON
             if let Ok(value) = serde_json::from_str::<Value>(trimmed) {
-                // Check if it's a response (has "id" field) vs notification
-                if value.get("id").is_some() {
+                // Check if it's our response (same "id") vs a notification, or the
+                // late answer to a call that was cancelled
+                if value.get("id").is_some() && value.get("id") == request.get("id") {
                     return Ok(value);
                 }
-                // Skip notifications, keep reading
+                // Skip anything else, keep reading
                 continue;
             }
         }
@@ -242,9 +244,7 @@ impl StdioMcpClient {
     /// List available tools from the MCP server
     pub async fn list_tools(&self) -> Result<Vec<McpToolDescription>, ToolError> {
         if !self.initialized.load(Ordering::Relaxed) {
-            return Err(ToolError::ExecutionFailed(
-                "Serveur MCP non initialisé".into(),
-            ));
+            return Err(ToolError::ExecutionFailed(tool_text!("mcp.not_initialized").into()));
         }
 
         let request = serde_json::json!({
@@ -259,7 +259,7 @@ impl StdioMcpClient {
             .get("result")
             .and_then(|r| r.get("tools"))
             .and_then(|t| t.as_array())
-            .ok_or_else(|| ToolError::ExecutionFailed("Réponse tools/list invalide".into()))?;
+            .ok_or_else(|| ToolError::ExecutionFailed(tool_text!("mcp.invalid_tool_list").into()))?;
 
         let mut tool_descriptions = Vec::new();
         for tool in tools {
@@ -272,20 +272,23 @@ impl StdioMcpClient {
     }
 
     /// Call a tool on the MCP server
+    ///
+    /// On cancellation the server is sent `notifications/cancelled`; its late
+    /// answer, if any, is skipped by the next request.
     pub async fn call_tool(
         &self,
         tool_name: &str,
         arguments: Value,
+        cancel: &CancellationToken,
     ) -> Result<Value, ToolError> {
         if !self.initialized.load(Ordering::Relaxed) {
-            return Err(ToolError::ExecutionFailed(
-                "Serveur MCP non initialisé".into(),
-            ));
+            return Err(ToolError::ExecutionFailed(tool_text!("mcp.not_initialized").into()));
         }
 
+        let id = self.next_id();
         let request = serde_json::json!({
             "jsonrpc": "2.0",
-            "id": self.next_id(),
+            "id": id,
             "method": "tools/call",
             "params": {
                 "name": tool_name,
@@ -293,19 +296,32 @@ impl StdioMcpClient {
             }
         });
 
-        let response = self.send_request(request).await?;
+        let response = tokio::select! {
+            response = self.send_request(request) => response?,
+            _ = cancel.cancelled() => {
+                let notification = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/cancelled",
+                    "params": { "requestId": id, "reason": "cancelled by user" }
+                });
+                if let Err(e) = self.send_notification(notification).await {
+                    tracing::warn!("Failed to notify MCP server of the cancellation: {}", e);
+                }
+                return Err(ToolError::Cancelled);
+            }
+        };
 
         if let Some(error) = response.get("error") {
             let message = error
                 .get("message")
                 .and_then(|m| m.as_str())
-                .unwrap_or("Erreur MCP inconnue");
+                .unwrap_or(tool_text!("mcp.unknown_error"));
             return Err(ToolError::ExecutionFailed(message.to_string()));
         }
 
         let result = response
             .get("result")
-            .ok_or_else(|| ToolError::ExecutionFailed("Réponse MCP sans résultat".into()))?;
+            .ok_or_else(|| ToolError::ExecutionFailed(tool_text!("mcp.no_result").into()))?;
 
         Ok(result.clone())
     }
@@ -378,16 +394,13 @@ impl HttpMcpClient {
             .json(&request)
             .send()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP MCP: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("mcp.http_error", e)))?;
 
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
 
         if !status.is_success() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "HTTP MCP erreur ({}): {}",
-                status, body
-            )));
+            return Err(ToolError::ExecutionFailed(tool_text!("mcp.http_status", status, body)));
         }
 
         parse_mcp_response(&body)
@@ -409,7 +422,7 @@ impl HttpMcpClient {
             .get("result")
             .and_then(|r| r.get("tools"))
             .and_then(|t| t.as_array())
-            .ok_or_else(|| ToolError::ExecutionFailed("Réponse tools/list invalide".into()))?;
+            .ok_or_else(|| ToolError::ExecutionFailed(tool_text!("mcp.invalid_tool_list").into()))?;
 
         let mut descriptions = Vec::new();
         for tool in tools {
@@ -445,7 +458,7 @@ impl HttpMcpClient {
             let message = error
                 .get("message")
                 .and_then(|m| m.as_str())
-                .unwrap_or("Erreur MCP");
+                .unwrap_or(tool_text!("mcp.unknown_error"));
             return Err(ToolError::ExecutionFailed(message.to_string()));
         }
 
@@ -472,7 +485,8 @@ pub struct DynamicMcpTool {
 /// Trait for MCP clients (both stdio and HTTP)
 #[async_trait]
 pub trait McpClient: Send + Sync {
-    async fn call_tool(&self, name: &str, args: Value) -> Result<Value, ToolError>;
+    /// Fails with `ToolError::Cancelled` once `cancel` fires
+    async fn call_tool(&self, name: &str, args: Value, cancel: &CancellationToken) -> Result<Value, ToolError>;
 }
 
 /// Wrapper that holds an Arc<StdioMcpClient> and implements McpClient
@@ -488,8 +502,8 @@ impl StdioMcpClientWrapper {
 
 #[async_trait]
 impl McpClient for StdioMcpClientWrapper {
-    async fn call_tool(&self, name: &str, args: Value) -> Result<Value, ToolError> {
-        self.inner.call_tool(name, args).await
+    async fn call_tool(&self, name: &str, args: Value, cancel: &CancellationToken) -> Result<Value, ToolError> {
+        self.inner.call_tool(name, args, cancel).await
     }
 }
 
@@ -506,8 +520,12 @@ impl HttpMcpClientWrapper {
 
 #[async_trait]
 impl McpClient for HttpMcpClientWrapper {
-    async fn call_tool(&self, name: &str, args: Value) -> Result<Value, ToolError> {
-        self.inner.call_tool(name, args).await
+    async fn call_tool(&self, name: &str, args: Value, cancel: &CancellationToken) -> Result<Value, ToolError> {
+        // Dropping the request closes it; there is no session state to clean up
+        tokio::select! {
+            result = self.inner.call_tool(name, args) => result,
+            _ = cancel.cancelled() => Err(ToolError::Cancelled),
+        }
     }
 }
 
@@ -552,6 +570,10 @@ impl Tool for DynamicMcpTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_cancellable(params, CancellationToken::new()).await
+    }
+
+    async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
         tracing::debug!(
             "MCP tool call: {}:{} with params: {:?}",
             self.server_id,
@@ -559,7 +581,7 @@ impl Tool for DynamicMcpTool {
             params
         );
 
-        let result = self.client.call_tool(&self.tool_name, params).await?;
+        let result = self.client.call_tool(&self.tool_name, params, &cancel).await?;
 
         // Extract text content from MCP response
         let _content_text = extract_mcp_text(&result);
@@ -567,10 +589,7 @@ impl Tool for DynamicMcpTool {
         Ok(ToolResult {
             success: true,
             data: result,
-            message: format!(
-                "[MCP:{}] {} exécuté",
-                self.server_id, self.tool_name
-            ),
+            message: tool_text!("mcp_tool.done", self.server_id, self.tool_name),
         })
     }
 }
@@ -746,7 +765,7 @@ fn parse_mcp_response(body: &str) -> Result<Value, ToolError> {
     // Direct JSON
     if trimmed.starts_with('{') || trimmed.starts_with('[') {
         return serde_json::from_str(trimmed)
-            .map_err(|e| ToolError::ExecutionFailed(format!("Réponse MCP invalide: {}", e)));
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("mcp.invalid_response", e)));
     }
 
     // SSE format
@@ -757,14 +776,12 @@ fn parse_mcp_response(body: &str) -> Result<Value, ToolError> {
                 let data = line.trim_start_matches("data:").trim();
                 if !data.is_empty() && data != "[DONE]" {
                     return serde_json::from_str(data).map_err(|e| {
-                        ToolError::ExecutionFailed(format!("Réponse MCP SSE invalide: {}", e))
+                        ToolError::ExecutionFailed(tool_text!("mcp.invalid_sse", e))
                     });
                 }
             }
         }
     }
 
-    Err(ToolError::ExecutionFailed(
-        "Réponse MCP invalide: attendu JSON ou SSE".into(),
-    ))
+    Err(ToolError::ExecutionFailed(tool_text!("mcp.not_json_or_sse").into()))
 }
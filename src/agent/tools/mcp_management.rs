@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use serde_json::Value;
 
+use crate::agent::language::tool_text;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 use crate::agent::tools::mcp_client::{McpServerConfig, McpTransport};
 use crate::agent::mcp_config;
@@ -114,7 +115,7 @@ impl Tool for McpAddServerTool {
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({ "id": id }),
-            message: format!("Serveur MCP '{}' ajouté. Redémarrez l'agent pour appliquer.", id),
+            message: tool_text!("mcp_add_server.done", id),
         })
     }
 }
@@ -155,7 +156,7 @@ impl Tool for McpListServersTool {
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({ "servers": values }),
-            message: format!("{} serveurs configurés", values.len()),
+            message: tool_text!("mcp_list_servers.done", values.len()),
         })
     }
 }
@@ -196,7 +197,7 @@ impl Tool for McpRemoveServerTool {
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({ "id": id }),
-            message: format!("Serveur MCP '{}' supprimé.", id),
+            message: tool_text!("mcp_remove_server.done", id),
         })
     }
 }
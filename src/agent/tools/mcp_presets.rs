@@ -15,7 +15,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "github".to_string(),
             name: "GitHub".to_string(),
-            description: "Accès à l'API GitHub: repos, issues, PRs, fichiers, branches, commits. Nécessite GITHUB_PERSONAL_ACCESS_TOKEN.".to_string(),
+            description: "GitHub API access: repos, issues, PRs, files, branches, commits. Needs GITHUB_PERSONAL_ACCESS_TOKEN.".to_string(),
             category: McpCategory::VersionControl,
             config: McpServerConfig {
                 id: "github".to_string(),
@@ -34,7 +34,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "filesystem".to_string(),
             name: "Filesystem (MCP)".to_string(),
-            description: "Serveur MCP officiel pour les opérations de fichiers avancées.".to_string(),
+            description: "Official MCP server for advanced file operations.".to_string(),
             category: McpCategory::FileSystem,
             config: McpServerConfig {
                 id: "filesystem".to_string(),
@@ -57,7 +57,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "git".to_string(),
             name: "Git (MCP)".to_string(),
-            description: "Serveur MCP officiel pour les opérations Git: status, diff, log, commit, branch, etc.".to_string(),
+            description: "Official MCP server for Git operations: status, diff, log, commit, branch, etc.".to_string(),
             category: McpCategory::VersionControl,
             config: McpServerConfig {
                 id: "git".to_string(),
@@ -76,7 +76,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "brave-search".to_string(),
             name: "Brave Search".to_string(),
-            description: "Recherche web via l'API Brave Search. Alternative gratuite. Nécessite BRAVE_API_KEY.".to_string(),
+            description: "Web search through the Brave Search API. Free alternative. Needs BRAVE_API_KEY.".to_string(),
             category: McpCategory::Search,
             config: McpServerConfig {
                 id: "brave_search".to_string(),
@@ -95,7 +95,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "fetch".to_string(),
             name: "Fetch (MCP)".to_string(),
-            description: "Récupérer le contenu de pages web et d'APIs. Convertit HTML en Markdown.".to_string(),
+            description: "Fetch the content of web pages and APIs. Converts HTML to Markdown.".to_string(),
             category: McpCategory::Web,
             config: McpServerConfig {
                 id: "fetch".to_string(),
@@ -114,7 +114,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "memory".to_string(),
             name: "Memory (Knowledge Graph)".to_string(),
-            description: "Mémoire persistante sous forme de graphe de connaissances. Permet de stocker et rappeler des informations entre sessions.".to_string(),
+            description: "Persistent memory as a knowledge graph. Stores and recalls information across sessions.".to_string(),
             category: McpCategory::KnowledgeMemory,
             config: McpServerConfig {
                 id: "memory".to_string(),
@@ -133,7 +133,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "sequential-thinking".to_string(),
             name: "Sequential Thinking".to_string(),
-            description: "Raisonnement séquentiel avancé pour résoudre des problèmes complexes étape par étape.".to_string(),
+            description: "Advanced sequential reasoning to solve complex problems step by step.".to_string(),
             category: McpCategory::DeveloperTools,
             config: McpServerConfig {
                 id: "thinking".to_string(),
@@ -155,7 +155,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "sqlite".to_string(),
             name: "SQLite".to_string(),
-            description: "Accès aux bases de données SQLite: requêtes SQL, création de tables, analyse de données.".to_string(),
+            description: "SQLite database access: SQL queries, table creation, data analysis.".to_string(),
             category: McpCategory::Database,
             config: McpServerConfig {
                 id: "sqlite".to_string(),
@@ -174,7 +174,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "postgres".to_string(),
             name: "PostgreSQL".to_string(),
-            description: "Accès aux bases de données PostgreSQL. Nécessite POSTGRES_CONNECTION_STRING.".to_string(),
+            description: "PostgreSQL database access. Needs POSTGRES_CONNECTION_STRING.".to_string(),
             category: McpCategory::Database,
             config: McpServerConfig {
                 id: "postgres".to_string(),
@@ -196,7 +196,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "puppeteer".to_string(),
             name: "Puppeteer (Browser)".to_string(),
-            description: "Automatisation de navigateur web: navigation, screenshots, interaction avec les pages.".to_string(),
+            description: "Web browser automation: navigation, screenshots, page interaction.".to_string(),
             category: McpCategory::BrowserAutomation,
             config: McpServerConfig {
                 id: "puppeteer".to_string(),
@@ -215,7 +215,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "playwright".to_string(),
             name: "Playwright (Browser)".to_string(),
-            description: "Automatisation de navigateur avec Playwright: navigation, tests, screenshots.".to_string(),
+            description: "Browser automation with Playwright: navigation, tests, screenshots.".to_string(),
             category: McpCategory::BrowserAutomation,
             config: McpServerConfig {
                 id: "playwright".to_string(),
@@ -237,7 +237,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "docker".to_string(),
             name: "Docker".to_string(),
-            description: "Gestion de conteneurs Docker: images, conteneurs, volumes, réseaux.".to_string(),
+            description: "Docker container management: images, containers, volumes, networks.".to_string(),
             category: McpCategory::CloudDevOps,
             config: McpServerConfig {
                 id: "docker".to_string(),
@@ -256,7 +256,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "kubernetes".to_string(),
             name: "Kubernetes".to_string(),
-            description: "Gestion de clusters Kubernetes: pods, services, deployments, logs.".to_string(),
+            description: "Kubernetes cluster management: pods, services, deployments, logs.".to_string(),
             category: McpCategory::CloudDevOps,
             config: McpServerConfig {
                 id: "kubernetes".to_string(),
@@ -278,7 +278,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "slack".to_string(),
             name: "Slack".to_string(),
-            description: "Accès à Slack: envoyer/lire des messages, canaux, recherche. Nécessite SLACK_BOT_TOKEN.".to_string(),
+            description: "Slack access: send/read messages, channels, search. Needs SLACK_BOT_TOKEN.".to_string(),
             category: McpCategory::Communication,
             config: McpServerConfig {
                 id: "slack".to_string(),
@@ -300,7 +300,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "exa".to_string(),
             name: "Exa Search".to_string(),
-            description: "Recherche web avancée avec Exa: recherche sémantique, code, entreprises, recherche approfondie.".to_string(),
+            description: "Advanced web search with Exa: semantic search, code, companies, deep research.".to_string(),
             category: McpCategory::Search,
             config: McpServerConfig {
                 id: "exa".to_string(),
@@ -312,7 +312,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
                 enabled: false,
             },
             required_env: vec!["EXA_API_KEY".to_string()],
-            install_hint: "Aucune installation requise - serveur HTTP distant.".to_string(),
+            install_hint: "No installation needed - remote HTTP server.".to_string(),
         },
 
         // ============================================================
@@ -321,7 +321,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "everything-search".to_string(),
             name: "Everything Search".to_string(),
-            description: "Recherche de fichiers ultra-rapide sur Windows avec Everything SDK.".to_string(),
+            description: "Very fast file search on Windows with the Everything SDK.".to_string(),
             category: McpCategory::FileSystem,
             config: McpServerConfig {
                 id: "everything".to_string(),
@@ -334,13 +334,13 @@ pub fn get_all_presets() -> Vec<McpPreset> {
                 enabled: false,
             },
             required_env: vec![],
-            install_hint: "npm install -g mcp-everything-search (Windows uniquement, nécessite Everything)".to_string(),
+            install_hint: "npm install -g mcp-everything-search (Windows only, needs Everything)".to_string(),
         },
 
         McpPreset {
             id: "notionapi".to_string(),
             name: "Notion".to_string(),
-            description: "Accès à Notion: pages, bases de données, blocs. Nécessite NOTION_API_KEY.".to_string(),
+            description: "Notion access: pages, databases, blocks. Needs NOTION_API_KEY.".to_string(),
             category: McpCategory::KnowledgeMemory,
             config: McpServerConfig {
                 id: "notion".to_string(),
@@ -359,7 +359,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "google-drive".to_string(),
             name: "Google Drive".to_string(),
-            description: "Accès à Google Drive: lister, lire et rechercher des fichiers.".to_string(),
+            description: "Google Drive access: list, read and search files.".to_string(),
             category: McpCategory::CloudStorage,
             config: McpServerConfig {
                 id: "gdrive".to_string(),
@@ -378,7 +378,7 @@ pub fn get_all_presets() -> Vec<McpPreset> {
         McpPreset {
             id: "sentry".to_string(),
             name: "Sentry".to_string(),
-            description: "Accès aux erreurs et issues Sentry. Nécessite SENTRY_AUTH_TOKEN.".to_string(),
+            description: "Access to Sentry errors and issues. Needs SENTRY_AUTH_TOKEN.".to_string(),
             category: McpCategory::Monitoring,
             config: McpServerConfig {
                 id: "sentry".to_string(),
@@ -416,18 +416,18 @@ pub enum McpCategory {
 impl McpCategory {
     pub fn label(&self) -> &'static str {
         match self {
-            McpCategory::VersionControl => "Contrôle de version",
-            McpCategory::FileSystem => "Système de fichiers",
-            McpCategory::Search => "Recherche",
+            McpCategory::VersionControl => "Version control",
+            McpCategory::FileSystem => "File system",
+            McpCategory::Search => "Search",
             McpCategory::Web => "Web",
-            McpCategory::Database => "Base de données",
-            McpCategory::BrowserAutomation => "Automatisation navigateur",
+            McpCategory::Database => "Database",
+            McpCategory::BrowserAutomation => "Browser automation",
             McpCategory::CloudDevOps => "Cloud & DevOps",
             McpCategory::Communication => "Communication",
-            McpCategory::KnowledgeMemory => "Mémoire & Connaissances",
-            McpCategory::CloudStorage => "Stockage cloud",
+            McpCategory::KnowledgeMemory => "Memory & knowledge",
+            McpCategory::CloudStorage => "Cloud storage",
             McpCategory::Monitoring => "Monitoring",
-            McpCategory::DeveloperTools => "Outils développeur",
+            McpCategory::DeveloperTools => "Developer tools",
         }
     }
 
@@ -0,0 +1,289 @@
+//! Long-term memory tools - facts the agent keeps across conversations
+//!
+//! `memory_write` saves a short note tagged with topics, `memory_read` lists
+//! notes by topic and `memory_search` finds the ones relevant to a text. The
+//! notes most relevant to each new user message are also recalled into the
+//! system prompt (`prompts::build_memory_digest`). Storage is `storage::memory`.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::storage::memory::{update_memory, MemoryNote, MAX_NOTE_CHARS};
+use crate::storage::redaction::Redactor;
+use crate::storage::settings::load_settings;
+use crate::storage::StorageError;
+
+const DEFAULT_LIMIT: u64 = 10;
+const MAX_LIMIT: u64 = 50;
+
+fn memory_error(e: StorageError) -> ToolError {
+    match e {
+        StorageError::InvalidMemory(message) => ToolError::InvalidParameters(message),
+        e => ToolError::ExecutionFailed(tool_text!("memory.error", e)),
+    }
+}
+
+fn note_json(note: &MemoryNote) -> Value {
+    serde_json::json!({
+        "id": note.id,
+        "content": note.content,
+        "topics": note.topics,
+        "updated_at": note.updated_at.to_rfc3339(),
+    })
+}
+
+fn limit(params: &Value) -> usize {
+    params["limit"]
+        .as_u64()
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, MAX_LIMIT) as usize
+}
+
+// ============================================================================
+// memory_write
+// ============================================================================
+
+/// Save or update a note in long-term memory
+pub struct MemoryWriteTool;
+
+#[async_trait]
+impl Tool for MemoryWriteTool {
+    fn name(&self) -> &str {
+        "memory_write"
+    }
+
+    fn description(&self) -> &str {
+        "Save a short fact to long-term memory so it is available in future conversations (user preferences, project facts, decisions). One fact per note. Pass id to replace an existing note, delete: true with id to forget it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "content": {
+                    "type": "string",
+                    "description": format!("The fact to remember, one or two sentences (max {} bytes)", MAX_NOTE_CHARS)
+                },
+                "topics": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Topics for later lookup, e.g. [\"preferences\", \"project:foo\"]"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Id of the note to replace or delete"
+                },
+                "delete": {
+                    "type": "boolean",
+                    "description": "Forget the note `id`",
+                    "default": false
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let id = params["id"].as_str().filter(|id| !id.trim().is_empty());
+
+        if params["delete"].as_bool().unwrap_or(false) {
+            let id = id.ok_or_else(|| {
+                ToolError::InvalidParameters("id is required to delete a note".into())
+            })?;
+            let deleted = update_memory(|store| store.delete(id)).map_err(memory_error)?;
+            if !deleted {
+                return Err(ToolError::InvalidParameters(tool_text!(
+                    "memory.no_note",
+                    id
+                )));
+            }
+            return Ok(ToolResult {
+                success: true,
+                data: serde_json::json!({ "deleted": id }),
+                message: tool_text!("memory_write.deleted", id),
+            });
+        }
+
+        let content = params["content"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("content is required".into()))?;
+        let content = match Redactor::for_indexing(&load_settings().redaction) {
+            Some(redactor) => redactor.redact(content).text,
+            None => content.to_string(),
+        };
+        let topics: Vec<String> = params["topics"]
+            .as_array()
+            .map(|topics| {
+                topics
+                    .iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let outcome = update_memory(|store| store.write(id, &content, &topics))
+            .map_err(memory_error)?
+            .map_err(memory_error)?;
+
+        let mut message = if outcome.updated {
+            tool_text!("memory_write.updated", outcome.note.id)
+        } else {
+            tool_text!("memory_write.saved", outcome.note.id)
+        };
+        if !outcome.evicted.is_empty() {
+            message.push_str(&tool_text!("memory_write.evicted", outcome.evicted.len()));
+        }
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "note": note_json(&outcome.note),
+                "updated": outcome.updated,
+                "evicted": outcome.evicted.iter().map(note_json).collect::<Vec<_>>(),
+            }),
+            message,
+        })
+    }
+}
+
+// ============================================================================
+// memory_read
+// ============================================================================
+
+/// List notes, optionally by topic
+pub struct MemoryReadTool;
+
+#[async_trait]
+impl Tool for MemoryReadTool {
+    fn name(&self) -> &str {
+        "memory_read"
+    }
+
+    fn description(&self) -> &str {
+        "List notes from long-term memory, most recently used first. Filter by topic to see everything remembered about it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "topic": {
+                    "type": "string",
+                    "description": "Only notes with this topic"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of notes",
+                    "default": DEFAULT_LIMIT
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let topic = params["topic"].as_str().filter(|t| !t.trim().is_empty());
+        let limit = limit(&params);
+        let notes = update_memory(|store| store.read(topic, limit)).map_err(memory_error)?;
+
+        let message = match (notes.len(), topic) {
+            (0, Some(topic)) => tool_text!("memory_read.no_topic", topic),
+            (0, None) => tool_text!("memory_read.empty").to_string(),
+            (n, _) => tool_text!("memory_read.done", n),
+        };
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "notes": notes.iter().map(note_json).collect::<Vec<_>>(),
+                "count": notes.len(),
+            }),
+            message,
+        })
+    }
+}
+
+// ============================================================================
+// memory_search
+// ============================================================================
+
+/// Find the notes relevant to a text
+pub struct MemorySearchTool;
+
+#[async_trait]
+impl Tool for MemorySearchTool {
+    fn name(&self) -> &str {
+        "memory_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search long-term memory for notes sharing words or topics with the query, best matches first."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words to look for"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of notes",
+                    "default": DEFAULT_LIMIT
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let query = params["query"]
+            .as_str()
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("query is required".into()))?;
+        let limit = limit(&params);
+        let hits = update_memory(|store| store.search(query, limit)).map_err(memory_error)?;
+
+        let notes: Vec<Value> = hits
+            .iter()
+            .map(|(note, score)| {
+                let mut json = note_json(note);
+                json["score"] = serde_json::json!(score);
+                json
+            })
+            .collect();
+        Ok(ToolResult {
+            success: true,
+            message: if notes.is_empty() {
+                tool_text!("memory_search.none", query)
+            } else {
+                tool_text!("memory_search.done", notes.len(), query)
+            },
+            data: serde_json::json!({ "notes": notes, "count": notes.len() }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_write_validates_before_touching_the_store() {
+        let tool = MemoryWriteTool;
+        let err = tool
+            .execute(serde_json::json!({ "delete": true }))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        let err = tool
+            .execute(serde_json::json!({ "topics": ["x"] }))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters(_)));
+        assert!(matches!(
+            memory_error(StorageError::InvalidMemory("x".to_string())),
+            ToolError::InvalidParameters(_)
+        ));
+    }
+}
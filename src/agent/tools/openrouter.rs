@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::agent::language::tool_text;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 
 // ============================================================================
@@ -224,10 +225,7 @@ impl Tool for OpenRouterConsultTool {
                 "tokens_max": max_tokens,
                 "optimized": optimize_for_local
             }),
-            message: format!(
-                "Réponse de {} :\n\n{}",
-                model, content
-            ),
+            message: tool_text!("ai_consult.done", model, content),
         })
     }
 }
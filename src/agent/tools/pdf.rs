@@ -4,8 +4,9 @@
 
 use async_trait::async_trait;
 use serde_json::Value;
-use std::path::PathBuf;
 
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 
 // ============================================================================
@@ -21,7 +22,7 @@ impl Tool for PdfReadTool {
     }
 
     fn description(&self) -> &str {
-        "Lire et extraire le texte d'un fichier PDF."
+        "Read a PDF file and extract its text."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -30,12 +31,12 @@ impl Tool for PdfReadTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Chemin vers le fichier PDF à lire"
+                    "description": "Path to the PDF file to read"
                 },
                 "pages": {
                     "type": "array",
                     "items": { "type": "integer" },
-                    "description": "Numéros des pages à extraire (optionnel, toutes par défaut)"
+                    "description": "Page numbers to extract (optional, all by default)"
                 }
             },
             "required": ["path"]
@@ -47,16 +48,14 @@ impl Tool for PdfReadTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
         
-        let path = PathBuf::from(path_str);
+        let path = resolve_and_validate_path(path_str)?;
         if !path.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Le fichier '{}' n'existe pas", path_str
-            )));
+            return Err(ToolError::ExecutionFailed(tool_text!("pdf.not_found", path_str)));
         }
 
         // Use pdf-extract for better text extraction (handles more PDF formats)
         let pages_text = pdf_extract::extract_text_by_pages(&path).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur extraction PDF: {}", e))
+            ToolError::ExecutionFailed(tool_text!("pdf.extract_error", e))
         })?;
 
         let total_pages = pages_text.len();
@@ -105,15 +104,12 @@ impl Tool for PdfReadTool {
         
         // Add truncation notice
         if let Some(page) = truncated_at_page {
-            extracted_text.push_str(&format!(
-                "\n[... {} pages restantes tronquées pour économiser le contexte. Utilisez le paramètre 'pages' pour des pages spécifiques.]\n",
-                total_pages - page
-            ));
+            extracted_text.push_str(&tool_text!("pdf_read.truncated", total_pages - page));
         }
 
         // Fallback message if no text found
         if extracted_text.is_empty() {
-            extracted_text = "(Aucun texte extractible - le PDF peut contenir des images ou être scanné)".to_string();
+            extracted_text = tool_text!("pdf_read.no_text").to_string();
         }
 
         Ok(ToolResult {
@@ -125,10 +121,7 @@ impl Tool for PdfReadTool {
                 "pages": page_texts,
                 "text": extracted_text
             }),
-            message: format!(
-                "PDF lu: {} pages avec texte sur {} total",
-                page_texts.len(), total_pages
-            ),
+            message: tool_text!("pdf_read.done", page_texts.len(), total_pages),
         })
     }
 }
@@ -147,7 +140,7 @@ impl Tool for PdfCreateTool {
     }
 
     fn description(&self) -> &str {
-        "Créer un nouveau fichier PDF avec du contenu texte."
+        "Create a new PDF file with text content."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -156,19 +149,19 @@ impl Tool for PdfCreateTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Chemin où sauvegarder le PDF"
+                    "description": "Path to save the PDF to"
                 },
                 "title": {
                     "type": "string",
-                    "description": "Titre du document (optionnel)"
+                    "description": "Document title (optional)"
                 },
                 "content": {
                     "type": "string",
-                    "description": "Contenu texte du PDF"
+                    "description": "Text content of the PDF"
                 },
                 "font_size": {
                     "type": "number",
-                    "description": "Taille de police (défaut: 12)"
+                    "description": "Font size (default: 12)"
                 }
             },
             "required": ["path", "content"]
@@ -201,7 +194,7 @@ impl Tool for PdfCreateTool {
         
         // Use built-in font
         let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur police: {}", e))
+            ToolError::ExecutionFailed(tool_text!("pdf.font_error", e))
         })?;
 
         // Add content - split into lines (collect as owned strings)
@@ -229,22 +222,22 @@ impl Tool for PdfCreateTool {
         }
 
         // Save PDF
-        let path = PathBuf::from(path_str);
+        let path = resolve_and_validate_path(path_str)?;
         if let Some(parent) = path.parent() {
             if !parent.exists() {
                 std::fs::create_dir_all(parent).map_err(|e| {
-                    ToolError::ExecutionFailed(format!("Erreur création dossier: {}", e))
+                    ToolError::ExecutionFailed(tool_text!("dir.create_error", e))
                 })?;
             }
         }
 
         let file = std::fs::File::create(&path).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur création fichier: {}", e))
+            ToolError::ExecutionFailed(tool_text!("file_create.error", e))
         })?;
         
         let mut buf_writer = std::io::BufWriter::new(file);
         doc.save(&mut buf_writer).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur sauvegarde PDF: {}", e))
+            ToolError::ExecutionFailed(tool_text!("pdf.save_error", e))
         })?;
 
         Ok(ToolResult {
@@ -255,7 +248,7 @@ impl Tool for PdfCreateTool {
                 "lines": line_count,
                 "size_bytes": std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0)
             }),
-            message: format!("PDF créé: {} ({} lignes)", path_str, line_count),
+            message: tool_text!("pdf_create.done", path_str, line_count),
         })
     }
 }
@@ -273,7 +266,7 @@ impl Tool for PdfAddPageTool {
     }
 
     fn description(&self) -> &str {
-        "Ajouter une page avec du texte à un PDF existant."
+        "Add a page of text to an existing PDF."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -282,11 +275,11 @@ impl Tool for PdfAddPageTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Chemin vers le PDF à modifier"
+                    "description": "Path to the PDF to modify"
                 },
                 "content": {
                     "type": "string",
-                    "description": "Contenu texte de la nouvelle page"
+                    "description": "Text content of the new page"
                 }
             },
             "required": ["path", "content"]
@@ -302,16 +295,14 @@ impl Tool for PdfAddPageTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("content is required".into()))?;
 
-        let path = PathBuf::from(path_str);
+        let path = resolve_and_validate_path(path_str)?;
         if !path.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Le fichier '{}' n'existe pas", path_str
-            )));
+            return Err(ToolError::ExecutionFailed(tool_text!("pdf.not_found", path_str)));
         }
 
         // Load existing PDF
         let mut doc = lopdf::Document::load(&path).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur lecture PDF: {}", e))
+            ToolError::ExecutionFailed(tool_text!("pdf.read_error", e))
         })?;
 
         let pages_before = doc.get_pages().len();
@@ -378,7 +369,7 @@ impl Tool for PdfAddPageTool {
 
         // Save modified PDF
         doc.save(&path).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur sauvegarde PDF: {}", e))
+            ToolError::ExecutionFailed(tool_text!("pdf.save_error", e))
         })?;
 
         Ok(ToolResult {
@@ -388,7 +379,7 @@ impl Tool for PdfAddPageTool {
                 "pages_before": pages_before,
                 "pages_after": pages_before + 1
             }),
-            message: format!("Page ajoutée au PDF: {} pages maintenant", pages_before + 1),
+            message: tool_text!("pdf_add_page.done", pages_before + 1),
         })
     }
 }
@@ -406,7 +397,7 @@ impl Tool for PdfMergeTool {
     }
 
     fn description(&self) -> &str {
-        "Fusionner plusieurs fichiers PDF en un seul."
+        "Merge several PDF files into one."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -416,11 +407,11 @@ impl Tool for PdfMergeTool {
                 "input_files": {
                     "type": "array",
                     "items": { "type": "string" },
-                    "description": "Liste des chemins des PDFs à fusionner"
+                    "description": "Paths of the PDFs to merge"
                 },
                 "output_path": {
                     "type": "string",
-                    "description": "Chemin du PDF de sortie"
+                    "description": "Path of the output PDF"
                 }
             },
             "required": ["input_files", "output_path"]
@@ -435,28 +426,23 @@ impl Tool for PdfMergeTool {
             .filter_map(|v| v.as_str().map(String::from))
             .collect();
         
-        let _output_path = params["output_path"]
+        let output_path = params["output_path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("output_path is required".into()))?;
+        resolve_and_validate_path(output_path)?;
 
         if input_files.len() < 2 {
-            return Err(ToolError::InvalidParameters(
-                "Il faut au moins 2 fichiers à fusionner".into()
-            ));
+            return Err(ToolError::InvalidParameters(tool_text!("pdf_merge.too_few").into()));
         }
 
         // Verify all input files exist
         for file in &input_files {
-            if !PathBuf::from(file).exists() {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "Le fichier '{}' n'existe pas", file
-                )));
+            if !resolve_and_validate_path(file)?.exists() {
+                return Err(ToolError::ExecutionFailed(tool_text!("pdf.not_found", file)));
             }
         }
 
         // Note: lopdf doesn't have merge_pages built-in - feature not yet implemented
-        Err(ToolError::ExecutionFailed(
-            "La fusion PDF n'est pas encore supportée. Utilisez pdf_create pour créer de nouveaux PDFs.".into()
-        ))
+        Err(ToolError::ExecutionFailed(tool_text!("pdf_merge.unsupported").into()))
     }
 }
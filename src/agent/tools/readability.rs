@@ -0,0 +1,1067 @@
+//! Readability-style article extraction and HTML to Markdown conversion
+//!
+//! A lenient parser builds a small element tree (no scripts, styles or
+//! comments). Boilerplate is dropped: navigation, forms, and elements whose
+//! class or id reads like a sidebar, footer or ad. Paragraphs score their
+//! parent and grandparent by length and commas, scores are lowered by link
+//! density, and the best-scoring element is converted to Markdown with links
+//! and images made absolute.
+
+use reqwest::Url;
+
+/// Elements never holding article content
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "form", "button",
+    "input", "select", "textarea", "nav", "footer", "aside", "dialog", "head", "object", "embed",
+];
+
+/// Elements without an end tag
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is raw text up to their end tag
+const RAW_TEXT_TAGS: &[&str] = &[
+    "script", "style", "textarea", "title", "noscript", "template",
+];
+
+/// Elements that end an open `<p>`
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "ul",
+    "ol",
+    "dl",
+    "table",
+    "pre",
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "nav",
+    "aside",
+    "figure",
+    "hr",
+    "main",
+    "form",
+];
+
+/// Class or id fragments of boilerplate, and of content (which wins)
+const UNLIKELY: &[&str] = &[
+    "comment",
+    "sidebar",
+    "footer",
+    "footnote-back",
+    "menu",
+    "nav",
+    "share",
+    "social",
+    "cookie",
+    "banner",
+    "promo",
+    "related",
+    "advert",
+    "sponsor",
+    "popup",
+    "modal",
+    "subscribe",
+    "newsletter",
+    "breadcrumb",
+    "masthead",
+    "skip-link",
+    "pagination",
+    "toolbar",
+];
+const LIKELY: &[&str] = &[
+    "article", "content", "main", "post", "entry", "story", "body", "text",
+];
+
+/// Paragraphs shorter than this do not score
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// Readable content of an HTML page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Article {
+    pub title: Option<String>,
+    pub byline: Option<String>,
+    pub markdown: String,
+}
+
+/// Extract the main content of `html`; `base` makes relative links absolute
+pub fn extract(html: &str, base: Option<&Url>) -> Article {
+    let doc = Document::parse(html);
+    // A <base href> overrides the page's own URL
+    let base_href = doc
+        .find(|n| n.tag == "base")
+        .and_then(|n| doc.node(n).attr("href"))
+        .and_then(|href| match base {
+            Some(base) => base.join(href).ok(),
+            None => Url::parse(href).ok(),
+        });
+    let base = base_href.as_ref().or(base);
+
+    let title = doc.meta("og:title").or_else(|| {
+        doc.find(|n| n.tag == "title")
+            .map(|n| doc.text(n))
+            .filter(|t| !t.is_empty())
+    });
+    let byline = doc.byline();
+
+    let root = doc
+        .best_candidate()
+        .or_else(|| doc.find(|n| n.tag == "body"))
+        .unwrap_or(0);
+    let mut markdown = Renderer { doc: &doc, base }.block(root);
+    markdown = tidy(&markdown);
+
+    // The page title repeated as the first heading
+    if let Some(title) = &title {
+        if let Some(rest) = markdown.strip_prefix(&format!("# {}", title)) {
+            markdown = rest.trim_start().to_string();
+        }
+    }
+    Article {
+        title,
+        byline,
+        markdown,
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[derive(Debug, Default)]
+struct Node {
+    /// Lowercase tag name; empty for text
+    tag: String,
+    attrs: Vec<(String, String)>,
+    text: String,
+    children: Vec<usize>,
+    parent: Option<usize>,
+}
+
+impl Node {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn is_text(&self) -> bool {
+        self.tag.is_empty()
+    }
+
+    /// Lowercase class and id
+    fn class_and_id(&self) -> String {
+        format!(
+            "{} {}",
+            self.attr("class").unwrap_or_default(),
+            self.attr("id").unwrap_or_default()
+        )
+        .to_lowercase()
+    }
+
+    fn is_hidden(&self) -> bool {
+        let style = self
+            .attr("style")
+            .unwrap_or_default()
+            .replace(' ', "")
+            .to_lowercase();
+        self.attr("hidden").is_some()
+            || self.attr("aria-hidden") == Some("true")
+            || style.contains("display:none")
+            || style.contains("visibility:hidden")
+    }
+
+    fn is_unlikely(&self) -> bool {
+        if matches!(self.tag.as_str(), "body" | "html" | "article" | "main") {
+            return false;
+        }
+        let names = self.class_and_id();
+        UNLIKELY.iter().any(|u| names.contains(u)) && !LIKELY.iter().any(|l| names.contains(l))
+    }
+}
+
+struct Document {
+    nodes: Vec<Node>,
+}
+
+impl Document {
+    fn parse(html: &str) -> Self {
+        let mut nodes = vec![Node {
+            tag: "#root".to_string(),
+            ..Default::default()
+        }];
+        let mut stack = vec![0usize];
+        let lower = html.to_ascii_lowercase();
+        let mut i = 0;
+
+        let append = |nodes: &mut Vec<Node>, parent: usize, node: Node| {
+            let id = nodes.len();
+            nodes.push(Node {
+                parent: Some(parent),
+                ..node
+            });
+            nodes[parent].children.push(id);
+            id
+        };
+
+        while i < html.len() {
+            let rest = &html[i..];
+            if !rest.starts_with('<') {
+                let end = rest.find('<').map_or(html.len(), |n| i + n);
+                let text = decode_entities(&html[i..end]);
+                append(
+                    &mut nodes,
+                    *stack.last().unwrap_or(&0),
+                    Node {
+                        text,
+                        ..Default::default()
+                    },
+                );
+                i = end;
+                continue;
+            }
+            if rest.starts_with("<!--") {
+                i = lower[i + 4..]
+                    .find("-->")
+                    .map_or(html.len(), |n| i + 4 + n + 3);
+                continue;
+            }
+            if rest.starts_with("<!") || rest.starts_with("<?") {
+                i = rest.find('>').map_or(html.len(), |n| i + n + 1);
+                continue;
+            }
+            if let Some(name) = rest.strip_prefix("</") {
+                let name: String = name
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric())
+                    .collect::<String>()
+                    .to_ascii_lowercase();
+                i = rest.find('>').map_or(html.len(), |n| i + n + 1);
+                // Close up to the matching element; stray end tags are ignored
+                if let Some(pos) = stack.iter().rposition(|&id| nodes[id].tag == name) {
+                    if pos > 0 {
+                        stack.truncate(pos);
+                    }
+                }
+                continue;
+            }
+
+            let Some((tag, attrs, self_closing, end)) = parse_start_tag(rest) else {
+                // A lone '<' is text
+                append(
+                    &mut nodes,
+                    *stack.last().unwrap_or(&0),
+                    Node {
+                        text: "<".to_string(),
+                        ..Default::default()
+                    },
+                );
+                i += 1;
+                continue;
+            };
+            i += end;
+
+            let open = |stack: &[usize], nodes: &[Node], tag: &str| {
+                stack.last().is_some_and(|&id| nodes[id].tag == tag)
+            };
+            if BLOCK_TAGS.contains(&tag.as_str()) && open(&stack, &nodes, "p") {
+                stack.pop();
+            }
+            if tag == "li" {
+                if let Some(pos) = stack
+                    .iter()
+                    .rposition(|&id| matches!(nodes[id].tag.as_str(), "li" | "ul" | "ol"))
+                {
+                    if nodes[stack[pos]].tag == "li" {
+                        stack.truncate(pos);
+                    }
+                }
+            }
+            if matches!(tag.as_str(), "tr" | "td" | "th") {
+                let closes: &[&str] = if tag == "tr" {
+                    &["tr", "td", "th"]
+                } else {
+                    &["td", "th"]
+                };
+                if let Some(pos) = stack.iter().rposition(|&id| {
+                    nodes[id].tag == "table" || closes.contains(&nodes[id].tag.as_str())
+                }) {
+                    if nodes[stack[pos]].tag != "table" {
+                        stack.truncate(pos);
+                    }
+                }
+            }
+
+            let parent = *stack.last().unwrap_or(&0);
+            let id = append(
+                &mut nodes,
+                parent,
+                Node {
+                    tag: tag.clone(),
+                    attrs,
+                    ..Default::default()
+                },
+            );
+
+            if RAW_TEXT_TAGS.contains(&tag.as_str()) && !self_closing {
+                let close = format!("</{}", tag);
+                let end = lower[i..].find(&close).map_or(html.len(), |n| i + n);
+                let raw = &html[i..end];
+                let text = if tag == "title" || tag == "textarea" {
+                    decode_entities(raw)
+                } else {
+                    raw.to_string()
+                };
+                append(
+                    &mut nodes,
+                    id,
+                    Node {
+                        text,
+                        ..Default::default()
+                    },
+                );
+                i = html[end..].find('>').map_or(html.len(), |n| end + n + 1);
+            } else if !self_closing && !VOID_TAGS.contains(&tag.as_str()) {
+                stack.push(id);
+            }
+        }
+        Document { nodes }
+    }
+
+    fn node(&self, id: usize) -> &Node {
+        &self.nodes[id]
+    }
+
+    fn find(&self, predicate: impl Fn(&Node) -> bool) -> Option<usize> {
+        (0..self.nodes.len()).find(|&id| predicate(&self.nodes[id]))
+    }
+
+    fn meta(&self, name: &str) -> Option<String> {
+        self.nodes
+            .iter()
+            .filter(|n| n.tag == "meta")
+            .find(|n| n.attr("property") == Some(name) || n.attr("name") == Some(name))
+            .and_then(|n| n.attr("content"))
+            .map(|c| collapse_whitespace(c).trim().to_string())
+            .filter(|c| !c.is_empty())
+    }
+
+    /// Author meta tag, else a short element marked as author or byline
+    fn byline(&self) -> Option<String> {
+        let from_meta = self
+            .meta("author")
+            .or_else(|| self.meta("article:author"))
+            .filter(|a| !a.starts_with("http"));
+        from_meta.or_else(|| {
+            (0..self.nodes.len())
+                .filter(|&id| {
+                    let node = &self.nodes[id];
+                    !node.is_text()
+                        && (node.attr("rel") == Some("author")
+                            || node.attr("itemprop") == Some("author")
+                            || node.class_and_id().contains("byline")
+                            || node
+                                .class_and_id()
+                                .split_whitespace()
+                                .any(|w| w == "author"))
+                })
+                .map(|id| self.text(id))
+                .find(|t| !t.is_empty() && t.chars().count() < 100)
+        })
+    }
+
+    /// Text of an element, whitespace collapsed
+    fn text(&self, id: usize) -> String {
+        let mut out = String::new();
+        self.collect_text(id, &mut out);
+        collapse_whitespace(&out).trim().to_string()
+    }
+
+    fn collect_text(&self, id: usize, out: &mut String) {
+        let node = &self.nodes[id];
+        if node.is_text() {
+            out.push_str(&node.text);
+        } else if !DROPPED_TAGS.contains(&node.tag.as_str()) {
+            for &child in &node.children {
+                self.collect_text(child, out);
+            }
+        }
+    }
+
+    /// Element removed from the article, with everything in it
+    fn is_removed(&self, id: usize) -> bool {
+        let node = &self.nodes[id];
+        !node.is_text()
+            && (DROPPED_TAGS.contains(&node.tag.as_str()) || node.is_hidden() || node.is_unlikely())
+    }
+
+    fn has_removed_ancestor(&self, id: usize) -> bool {
+        let mut current = self.nodes[id].parent;
+        while let Some(parent) = current {
+            if self.is_removed(parent) {
+                return true;
+            }
+            current = self.nodes[parent].parent;
+        }
+        false
+    }
+
+    /// Share of an element's text inside links
+    fn link_density(&self, id: usize) -> f64 {
+        let total = self.text(id).chars().count();
+        if total == 0 {
+            return 0.0;
+        }
+        let linked: usize = (0..self.nodes.len())
+            .filter(|&n| self.nodes[n].tag == "a" && self.is_descendant(n, id))
+            .map(|n| self.text(n).chars().count())
+            .sum();
+        linked as f64 / total as f64
+    }
+
+    fn is_descendant(&self, mut id: usize, ancestor: usize) -> bool {
+        while let Some(parent) = self.nodes[id].parent {
+            if parent == ancestor {
+                return true;
+            }
+            id = parent;
+        }
+        false
+    }
+
+    fn initial_score(&self, id: usize) -> f64 {
+        let node = &self.nodes[id];
+        let tag_score = match node.tag.as_str() {
+            "article" | "main" => 10.0,
+            "div" | "section" => 5.0,
+            "pre" | "td" | "blockquote" => 3.0,
+            "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+            _ => 0.0,
+        };
+        let names = node.class_and_id();
+        let class_score = if LIKELY.iter().any(|l| names.contains(l)) {
+            25.0
+        } else if UNLIKELY.iter().any(|u| names.contains(u)) {
+            -25.0
+        } else {
+            0.0
+        };
+        tag_score + class_score
+    }
+
+    /// Element with the best content score, if any paragraph scored
+    fn best_candidate(&self) -> Option<usize> {
+        let mut scores: Vec<Option<f64>> = vec![None; self.nodes.len()];
+        for id in 0..self.nodes.len() {
+            let node = &self.nodes[id];
+            let scoring = matches!(node.tag.as_str(), "p" | "pre" | "td" | "blockquote")
+                || (node.tag == "div"
+                    && !node
+                        .children
+                        .iter()
+                        .any(|&c| BLOCK_TAGS.contains(&self.nodes[c].tag.as_str())));
+            if !scoring || self.is_removed(id) || self.has_removed_ancestor(id) {
+                continue;
+            }
+            let text = self.text(id);
+            let length = text.chars().count();
+            if length < MIN_PARAGRAPH_CHARS {
+                continue;
+            }
+            let score =
+                1.0 + text.matches([',', '，']).count() as f64 + (length as f64 / 100.0).min(3.0);
+            let mut ancestor = node.parent;
+            for share in [1.0, 0.5] {
+                let Some(a) = ancestor else { break };
+                if a == 0 {
+                    break;
+                }
+                let entry = scores[a].get_or_insert_with(|| self.initial_score(a));
+                *entry += score * share;
+                ancestor = self.nodes[a].parent;
+            }
+        }
+        scores
+            .iter()
+            .enumerate()
+            .filter_map(|(id, score)| score.map(|s| (id, s * (1.0 - self.link_density(id)))))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+}
+
+/// Name, attributes, whether it ends with `/>`, and byte length of a start tag
+type StartTag = (String, Vec<(String, String)>, bool, usize);
+
+/// `<tag attrs...>` at the start of `rest`
+fn parse_start_tag(rest: &str) -> Option<StartTag> {
+    let bytes = rest.as_bytes();
+    let name_len = rest[1..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+        .unwrap_or(rest.len() - 1);
+    if name_len == 0 || !bytes[1].is_ascii_alphabetic() {
+        return None;
+    }
+    let tag = rest[1..1 + name_len].to_ascii_lowercase();
+    let mut attrs = Vec::new();
+    let mut i = 1 + name_len;
+    loop {
+        while i < rest.len()
+            && (bytes[i].is_ascii_whitespace()
+                || bytes[i] == b'/' && bytes.get(i + 1) != Some(&b'>'))
+        {
+            i += 1;
+        }
+        if i >= rest.len() {
+            return Some((tag, attrs, false, rest.len()));
+        }
+        if rest[i..].starts_with("/>") {
+            return Some((tag, attrs, true, i + 2));
+        }
+        if bytes[i] == b'>' {
+            return Some((tag, attrs, false, i + 1));
+        }
+        let name_end = rest[i..]
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '>' || c == '/')
+            .map_or(rest.len(), |n| i + n);
+        let name = rest[i..name_end].to_ascii_lowercase();
+        i = name_end;
+        while i < rest.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if i < rest.len() && bytes[i] == b'=' {
+            i += 1;
+            while i < rest.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i < rest.len() && (bytes[i] == b'"' || bytes[i] == b'\'') {
+                let quote = bytes[i] as char;
+                let end = rest[i + 1..].find(quote).map_or(rest.len(), |n| i + 1 + n);
+                value = decode_entities(&rest[i + 1..end]);
+                i = (end + 1).min(rest.len());
+            } else {
+                let end = rest[i..]
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .map_or(rest.len(), |n| i + n);
+                value = decode_entities(&rest[i..end]);
+                i = end;
+            }
+        }
+        if !name.is_empty() {
+            attrs.push((name, value));
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Named and numeric character references
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let end = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+            .map(|n| n + 1);
+        let decoded = end.and_then(|end| {
+            let name = &rest[1..end];
+            let c =
+                if let Some(number) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    u32::from_str_radix(number, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                } else if let Some(number) = name.strip_prefix('#') {
+                    number.parse().ok().and_then(char::from_u32)
+                } else {
+                    named_entity(name)
+                }?;
+            let consumed = if rest[end..].starts_with(';') {
+                end + 1
+            } else {
+                end
+            };
+            Some((c, consumed))
+        });
+        match decoded {
+            Some((c, consumed)) => {
+                out.push(c);
+                rest = &rest[consumed..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn named_entity(name: &str) -> Option<char> {
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "mdash" => '—',
+        "ndash" => '–',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "euro" => '€',
+        "eacute" => 'é',
+        "egrave" => 'è',
+        "ecirc" => 'ê',
+        "agrave" => 'à',
+        "acirc" => 'â',
+        "ccedil" => 'ç',
+        "ocirc" => 'ô',
+        "uuml" => 'ü',
+        "ouml" => 'ö',
+        "auml" => 'ä',
+        "szlig" => 'ß',
+        "times" => '×',
+        "rarr" => '→',
+        "larr" => '←',
+        _ => return None,
+    })
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            space = true;
+        } else {
+            if space {
+                out.push(' ');
+                space = false;
+            }
+            out.push(c);
+        }
+    }
+    if space {
+        out.push(' ');
+    }
+    out
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+struct Renderer<'a> {
+    doc: &'a Document,
+    base: Option<&'a Url>,
+}
+
+impl Renderer<'_> {
+    /// Markdown of an element and everything in it
+    fn block(&self, id: usize) -> String {
+        let mut out = String::new();
+        for &child in &self.doc.node(id).children {
+            push_inline(&mut out, &self.node(child));
+        }
+        out
+    }
+
+    fn node(&self, id: usize) -> String {
+        let node = self.doc.node(id);
+        if node.is_text() {
+            return collapse_whitespace(&node.text);
+        }
+        if self.doc.is_removed(id) {
+            return String::new();
+        }
+        let tag = node.tag.as_str();
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = self.inline(id);
+                if text.is_empty() {
+                    return String::new();
+                }
+                let level = tag[1..].parse::<usize>().unwrap_or(1);
+                format!("\n\n{} {}\n\n", "#".repeat(level), text)
+            }
+            "br" => "\n".to_string(),
+            "hr" => "\n\n---\n\n".to_string(),
+            "pre" => {
+                let mut code = String::new();
+                self.raw_text(id, &mut code);
+                let language = self
+                    .doc
+                    .nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(n, _)| *n == id || self.doc.is_descendant(*n, id))
+                    .filter_map(|(_, n)| n.attr("class"))
+                    .flat_map(str::split_whitespace)
+                    .find_map(|c| {
+                        c.strip_prefix("language-")
+                            .or_else(|| c.strip_prefix("lang-"))
+                    })
+                    .unwrap_or_default();
+                format!("\n\n```{}\n{}\n```\n\n", language, code.trim_matches('\n'))
+            }
+            "code" | "kbd" | "samp" => {
+                let text = self.doc.text(id);
+                if text.is_empty() {
+                    String::new()
+                } else if text.contains('`') {
+                    format!("`` {} ``", text)
+                } else {
+                    format!("`{}`", text)
+                }
+            }
+            "strong" | "b" => wrap(&self.block(id), "**"),
+            "em" | "i" => wrap(&self.block(id), "*"),
+            "del" | "s" | "strike" => wrap(&self.block(id), "~~"),
+            "a" => {
+                let text = self.block(id);
+                let text = text.trim();
+                let href = node.attr("href").unwrap_or_default().trim();
+                if text.is_empty() {
+                    return String::new();
+                }
+                if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+                    return text.to_string();
+                }
+                format!("[{}]({})", text, self.absolute(href))
+            }
+            "img" => {
+                let src = node
+                    .attr("src")
+                    .or_else(|| node.attr("data-src"))
+                    .unwrap_or_default();
+                if src.is_empty() || src.starts_with("data:") {
+                    return String::new();
+                }
+                let alt = collapse_whitespace(node.attr("alt").unwrap_or_default());
+                format!("![{}]({})", alt.trim(), self.absolute(src))
+            }
+            "ul" | "ol" => self.list(id, tag == "ol"),
+            "blockquote" => {
+                let content = tidy(&self.block(id));
+                if content.is_empty() {
+                    return String::new();
+                }
+                let quoted: Vec<String> = content
+                    .lines()
+                    .map(|l| {
+                        if l.is_empty() {
+                            ">".to_string()
+                        } else {
+                            format!("> {}", l)
+                        }
+                    })
+                    .collect();
+                format!("\n\n{}\n\n", quoted.join("\n"))
+            }
+            "table" => self.table(id),
+            "p" | "div" | "section" | "article" | "main" | "header" | "figure" | "figcaption"
+            | "address" | "dl" | "dt" | "dd" | "details" | "summary" | "center" | "body"
+            | "html" => {
+                let content = self.block(id);
+                let content = content.trim_matches(' ');
+                if content.trim().is_empty() {
+                    String::new()
+                } else {
+                    format!("\n\n{}\n\n", content)
+                }
+            }
+            _ => self.block(id),
+        }
+    }
+
+    /// Content on one line: headings, table cells
+    fn inline(&self, id: usize) -> String {
+        collapse_whitespace(&self.block(id)).trim().to_string()
+    }
+
+    fn raw_text(&self, id: usize, out: &mut String) {
+        for &child in &self.doc.node(id).children {
+            let node = self.doc.node(child);
+            if node.is_text() {
+                out.push_str(&node.text);
+            } else if node.tag == "br" {
+                out.push('\n');
+            } else {
+                self.raw_text(child, out);
+            }
+        }
+    }
+
+    fn list(&self, id: usize, ordered: bool) -> String {
+        let items: Vec<usize> = self
+            .doc
+            .node(id)
+            .children
+            .iter()
+            .copied()
+            .filter(|&c| self.doc.node(c).tag == "li" && !self.doc.is_removed(c))
+            .collect();
+        let start = self
+            .doc
+            .node(id)
+            .attr("start")
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(1);
+        let mut out = String::from("\n\n");
+        for (n, &item) in items.iter().enumerate() {
+            let content = tidy(&self.block(item));
+            if content.is_empty() {
+                continue;
+            }
+            let marker = if ordered {
+                format!("{}. ", start + n)
+            } else {
+                "- ".to_string()
+            };
+            let indent = " ".repeat(marker.len());
+            for (i, line) in content.lines().enumerate() {
+                match (i, line.is_empty()) {
+                    (0, _) => out.push_str(&marker),
+                    (_, false) => out.push_str(&indent),
+                    _ => {}
+                }
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        out.push('\n');
+        out
+    }
+
+    fn table(&self, id: usize) -> String {
+        let rows: Vec<usize> = (0..self.doc.nodes.len())
+            .filter(|&n| self.doc.node(n).tag == "tr" && self.doc.is_descendant(n, id))
+            .filter(|&n| {
+                // Rows of a nested table belong to it
+                let mut current = self.doc.node(n).parent;
+                while let Some(p) = current {
+                    if p == id {
+                        return true;
+                    }
+                    if self.doc.node(p).tag == "table" {
+                        return false;
+                    }
+                    current = self.doc.node(p).parent;
+                }
+                false
+            })
+            .collect();
+        let cells: Vec<Vec<String>> = rows
+            .iter()
+            .map(|&row| {
+                self.doc
+                    .node(row)
+                    .children
+                    .iter()
+                    .filter(|&&c| matches!(self.doc.node(c).tag.as_str(), "td" | "th"))
+                    .map(|&c| self.inline(c).replace('|', "\\|"))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row: &Vec<String>| !row.is_empty())
+            .collect();
+        let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return self.block(id);
+        }
+        let line = |row: &[String]| {
+            let padded: Vec<&str> = (0..columns)
+                .map(|i| row.get(i).map(String::as_str).unwrap_or_default())
+                .collect();
+            format!("| {} |\n", padded.join(" | "))
+        };
+        let mut out = String::from("\n\n");
+        out.push_str(&line(&cells[0]));
+        out.push_str(&format!("|{}\n", " --- |".repeat(columns)));
+        for row in &cells[1..] {
+            out.push_str(&line(row));
+        }
+        out.push('\n');
+        out
+    }
+
+    fn absolute(&self, href: &str) -> String {
+        match self.base {
+            Some(base) => base
+                .join(href)
+                .map(|u| u.to_string())
+                .unwrap_or_else(|_| href.to_string()),
+            None => href.to_string(),
+        }
+    }
+}
+
+fn wrap(content: &str, marker: &str) -> String {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return content.to_string();
+    }
+    // Keep the spaces around so words stay apart
+    let before = if content.starts_with(' ') { " " } else { "" };
+    let after = if content.ends_with(' ') { " " } else { "" };
+    format!("{}{}{}{}{}", before, marker, trimmed, marker, after)
+}
+
+/// Append `piece`, dropping spaces at line starts and doubled spaces
+fn push_inline(out: &mut String, piece: &str) {
+    let mut piece = piece;
+    if out.is_empty() || out.ends_with('\n') || out.ends_with(' ') {
+        piece = piece.trim_start_matches(' ');
+    }
+    if piece.starts_with('\n') {
+        while out.ends_with(' ') {
+            out.pop();
+        }
+    }
+    out.push_str(piece);
+}
+
+/// Trailing spaces removed, at most one blank line in a row (outside code
+/// blocks), no blank lines at the ends
+fn tidy(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut blank = 0;
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        let line = if in_code { line } else { line.trim_end() };
+        if line.is_empty() && !in_code {
+            blank += 1;
+            if blank > 1 {
+                continue;
+            }
+        } else {
+            blank = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head>
+<title>Rust 2024 is out | Blog</title>
+<meta property="og:title" content="Rust 2024 is out">
+<meta name="author" content="Jane Doe">
+<script>var tracking = "<p>not content</p>";</script>
+<style>p { color: red }</style>
+</head>
+<body>
+<nav><a href="/">Home</a> <a href="/blog">Blog</a></nav>
+<div class="sidebar"><p>Subscribe to our newsletter, it is great, really, we promise.</p></div>
+<article class="post">
+  <h1>Rust 2024 is out</h1>
+  <p>The new edition brings <strong>async closures</strong>, a cleaner prelude, and a
+  <a href="/docs/edition">migration guide</a> for existing crates.</p>
+  <p>Upgrading is one command, and most projects need no manual change at all &mdash; cargo fixes the rest.</p>
+  <pre><code class="language-sh">cargo fix --edition
+cargo build</code></pre>
+  <ul><li>Faster builds<li>Better errors</ul>
+  <table><tr><th>Version</th><th>Date</th></tr><tr><td>1.85</td><td>Feb 2025</td></tr></table>
+  <img src="/img/logo.png" alt="Rust logo">
+</article>
+<footer><p>Copyright 2025, all rights reserved, do not copy this page.</p></footer>
+</body></html>"#;
+
+    #[test]
+    fn test_extracts_article_as_markdown() {
+        let base = Url::parse("https://blog.example.com/posts/rust-2024").unwrap();
+        let article = extract(PAGE, Some(&base));
+        assert_eq!(article.title.as_deref(), Some("Rust 2024 is out"));
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+
+        let md = &article.markdown;
+        assert!(
+            md.starts_with("The new edition brings **async closures**, a cleaner prelude"),
+            "{}",
+            md
+        );
+        assert!(md.contains("[migration guide](https://blog.example.com/docs/edition)"));
+        assert!(md.contains("no manual change at all — cargo fixes the rest."));
+        assert!(md.contains("```sh\ncargo fix --edition\ncargo build\n```"));
+        assert!(md.contains("- Faster builds\n- Better errors"));
+        assert!(md.contains("| Version | Date |\n| --- | --- |\n| 1.85 | Feb 2025 |"));
+        assert!(md.contains("![Rust logo](https://blog.example.com/img/logo.png)"));
+        for noise in ["Home", "newsletter", "Copyright", "tracking", "color: red"] {
+            assert!(!md.contains(noise), "{} in {}", noise, md);
+        }
+    }
+
+    #[test]
+    fn test_nested_lists_and_quotes() {
+        let html = "<body><div><ol><li>First<ul><li>Inner</li></ul></li><li>Second</li></ol>\
+                    <blockquote><p>Quoted text</p><p>More</p></blockquote></div></body>";
+        let article = extract(html, None);
+        assert_eq!(
+            article.markdown,
+            "1. First\n\n   - Inner\n2. Second\n\n> Quoted text\n>\n> More"
+        );
+    }
+
+    #[test]
+    fn test_entities_and_attributes() {
+        assert_eq!(
+            decode_entities("a &amp; b &lt;c&gt; &#233;&#x41; &unknown; &"),
+            "a & b <c> éA &unknown; &"
+        );
+        let (tag, attrs, self_closing, len) =
+            parse_start_tag("<IMG SRC='a.png' alt=\"x &amp; y\" hidden/>rest").unwrap();
+        assert_eq!(tag, "img");
+        assert_eq!(
+            attrs,
+            vec![
+                ("src".to_string(), "a.png".to_string()),
+                ("alt".to_string(), "x & y".to_string()),
+                ("hidden".to_string(), String::new()),
+            ]
+        );
+        assert!(self_closing);
+        assert_eq!(
+            &"<IMG SRC='a.png' alt=\"x &amp; y\" hidden/>rest"[len..],
+            "rest"
+        );
+        assert!(parse_start_tag("< p>").is_none());
+    }
+}
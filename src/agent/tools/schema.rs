@@ -0,0 +1,290 @@
+//! Tool parameter validation
+//!
+//! Checks tool call params against the tool's `parameters_schema` before
+//! `execute` runs: required fields, basic JSON types and enums, recursing into
+//! nested objects and array items. Every violation is reported at once so the
+//! model can fix the whole call in a single retry.
+
+use serde_json::Value;
+
+use super::{Tool, ToolError};
+
+/// Outcome of validating params against a schema
+#[derive(Debug, Default, PartialEq)]
+pub struct SchemaReport {
+    /// Violations that prevent execution
+    pub errors: Vec<String>,
+    /// Fields not declared in the schema (ignored by the tool)
+    pub unknown_fields: Vec<String>,
+}
+
+/// Validate params against an object schema
+pub fn validate_params(schema: &Value, params: &Value) -> SchemaReport {
+    let mut report = SchemaReport::default();
+    check_value(schema, params, "", &mut report);
+    report
+}
+
+/// Validate a tool call, returning a uniform `InvalidParameters` error
+///
+/// Unknown extra fields are logged but accepted.
+pub fn validate_tool_params(tool: &dyn Tool, params: &Value) -> Result<(), ToolError> {
+    let schema = tool.parameters_schema();
+    let report = validate_params(&schema, params);
+
+    if !report.unknown_fields.is_empty() {
+        tracing::warn!(
+            "Tool {} called with unknown parameter(s): {}",
+            tool.name(),
+            report.unknown_fields.join(", ")
+        );
+    }
+
+    if report.errors.is_empty() {
+        return Ok(());
+    }
+
+    Err(ToolError::InvalidParameters(format!(
+        "{} invalid parameter(s) for `{}`: {}. Expected: {}",
+        report.errors.len(),
+        tool.name(),
+        report.errors.join("; "),
+        describe_schema(&schema)
+    )))
+}
+
+/// Check that a tool schema is usable: an object schema whose `required`
+/// fields are all declared
+pub fn check_schema(schema: &Value) -> Result<(), String> {
+    let obj = schema.as_object().ok_or("schema is not a JSON object")?;
+
+    if obj.get("type").and_then(|t| t.as_str()) != Some("object") {
+        return Err("schema type must be \"object\"".to_string());
+    }
+
+    let properties = match obj.get("properties") {
+        None => None,
+        Some(Value::Object(props)) => Some(props),
+        Some(_) => return Err("\"properties\" must be an object".to_string()),
+    };
+
+    match obj.get("required") {
+        None => {}
+        Some(Value::Array(required)) => {
+            for field in required {
+                let name = field.as_str().ok_or("\"required\" must only contain strings")?;
+                if !properties.map_or(false, |p| p.contains_key(name)) {
+                    return Err(format!("required field `{}` is not declared in \"properties\"", name));
+                }
+            }
+        }
+        Some(_) => return Err("\"required\" must be an array".to_string()),
+    }
+
+    Ok(())
+}
+
+/// Compact signature of the expected params, e.g. `path: string (required), start_line: integer`
+fn describe_schema(schema: &Value) -> String {
+    let required = required_fields(schema);
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return "{}".to_string();
+    };
+
+    properties
+        .iter()
+        .map(|(name, prop)| {
+            let kind = match prop.get("enum").and_then(|e| e.as_array()) {
+                Some(values) => values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("|"),
+                None => type_names(prop).join("|"),
+            };
+            let kind = if kind.is_empty() { "any".to_string() } else { kind };
+            if required.contains(&name.as_str()) {
+                format!("{}: {} (required)", name, kind)
+            } else {
+                format!("{}: {}", name, kind)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn required_fields(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|f| f.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Declared type(s) of a schema node (`"type": "string"` or `"type": ["string", "null"]`)
+fn type_names(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn matches_type(value: &Value, type_name: &str) -> bool {
+    match type_name {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        // Unknown type keywords are not enforced
+        _ => true,
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn field_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+fn check_value(schema: &Value, value: &Value, path: &str, report: &mut SchemaReport) {
+    let label = if path.is_empty() { "params" } else { path };
+
+    let types = type_names(schema);
+    if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+        report.errors.push(format!(
+            "`{}` must be {}, got {}",
+            label,
+            types.join(" or "),
+            json_type_name(value)
+        ));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            let options = allowed.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+            report.errors.push(format!("`{}` must be one of [{}], got {}", label, options, value));
+            return;
+        }
+    }
+
+    if let (Some(obj), Some(properties)) = (
+        value.as_object(),
+        schema.get("properties").and_then(|p| p.as_object()),
+    ) {
+        for name in required_fields(schema) {
+            // An explicit null counts as missing
+            if obj.get(name).map_or(true, |v| v.is_null()) {
+                report.errors.push(format!("missing required field `{}`", field_path(path, name)));
+            }
+        }
+
+        for (name, field) in obj {
+            match properties.get(name) {
+                // Optional fields sent as null are treated as absent
+                Some(_) if field.is_null() => {}
+                Some(prop) => check_value(prop, field, &field_path(path, name), report),
+                None => report.unknown_fields.push(field_path(path, name)),
+            }
+        }
+    }
+
+    if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check_value(item_schema, item, &format!("{}[{}]", label, i), report);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::tools::builtins::{FileReadTool, GrepTool, TodoWriteTool};
+    use serde_json::json;
+
+    fn invalid_message(result: Result<(), ToolError>) -> String {
+        match result {
+            Err(ToolError::InvalidParameters(message)) => message,
+            other => panic!("expected InvalidParameters, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_valid_params_pass() {
+        assert!(validate_tool_params(&FileReadTool, &json!({"path": "src/main.rs", "start_line": 10})).is_ok());
+        assert!(validate_tool_params(&GrepTool, &json!({"pattern": "fn", "path": ".", "case_insensitive": true})).is_ok());
+        // Optional fields sent as null are fine
+        assert!(validate_tool_params(&FileReadTool, &json!({"path": "a.txt", "end_line": null})).is_ok());
+    }
+
+    #[test]
+    fn test_missing_and_wrong_types_reported_together() {
+        let message = invalid_message(validate_tool_params(
+            &GrepTool,
+            &json!({"pattern": 42, "case_insensitive": "yes", "max_results": 1.5}),
+        ));
+        assert!(message.starts_with("4 invalid parameter(s) for `grep`"), "{}", message);
+        assert!(message.contains("missing required field `path`"));
+        assert!(message.contains("`pattern` must be string, got integer"));
+        assert!(message.contains("`case_insensitive` must be boolean, got string"));
+        assert!(message.contains("`max_results` must be integer, got number"));
+        assert!(message.contains("pattern: string (required)"));
+    }
+
+    #[test]
+    fn test_file_read_wrong_params() {
+        let message = invalid_message(validate_tool_params(&FileReadTool, &json!({"start_line": "10"})));
+        assert!(message.contains("missing required field `path`"));
+        assert!(message.contains("`start_line` must be integer, got string"));
+
+        let message = invalid_message(validate_tool_params(&FileReadTool, &json!("src/main.rs")));
+        assert!(message.contains("`params` must be object, got string"));
+    }
+
+    #[test]
+    fn test_nested_enum_violation() {
+        let message = invalid_message(validate_tool_params(
+            &TodoWriteTool,
+            &json!({"todos": [{"id": "1", "content": "x", "status": "done"}]}),
+        ));
+        assert!(message.contains("`todos[0].status` must be one of"), "{}", message);
+    }
+
+    #[test]
+    fn test_unknown_fields_warn_only() {
+        let report = validate_params(
+            &FileReadTool.parameters_schema(),
+            &json!({"path": "a.txt", "encoding": "utf-8"}),
+        );
+        assert!(report.errors.is_empty());
+        assert_eq!(report.unknown_fields, vec!["encoding".to_string()]);
+    }
+
+    #[test]
+    fn test_check_schema() {
+        assert!(check_schema(&FileReadTool.parameters_schema()).is_ok());
+        assert!(check_schema(&json!({"type": "object"})).is_ok());
+        assert!(check_schema(&json!({"type": "string"})).is_err());
+        assert!(check_schema(&json!([])).is_err());
+        assert!(check_schema(&json!({
+            "type": "object",
+            "properties": {"a": {"type": "string"}},
+            "required": ["b"]
+        }))
+        .is_err());
+    }
+}
@@ -1,13 +1,23 @@
 //! Shell execution tools - Full bash/cmd/powershell execution
 //!
-//! Provides unrestricted shell access (with permission system).
+//! Provides unrestricted shell access (with permission system), and
+//! background jobs the agent can start, check on and kill across turns.
 
 use async_trait::async_trait;
+use once_cell::sync::Lazy;
 use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::language::tool_text;
+use crate::agent::tools::command_policy::{self, CommandVerdict};
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{mark_truncated, output_limit_schema, OutputBudget, Tool, ToolError, ToolResult};
 
 // ============================================================================
 // BashTool - Full shell execution (like Claude Code's bash tool)
@@ -52,6 +62,10 @@ impl Tool for BashTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_cancellable(params, CancellationToken::new()).await
+    }
+
+    async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
         let command_str = params["command"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("command is required".into()))?;
@@ -83,9 +97,11 @@ impl Tool for BashTool {
 
         cmd.stdout(std::process::Stdio::piped());
         cmd.stderr(std::process::Stdio::piped());
+        // A cancelled or timed-out command does not keep running
+        cmd.kill_on_drop(true);
 
         // Execute with timeout
-        let result = timeout(Duration::from_secs(timeout_secs), async {
+        let run = timeout(Duration::from_secs(timeout_secs), async {
             let mut child = cmd.spawn().map_err(|e| {
                 ToolError::ExecutionFailed(format!("Failed to launch command: {}", e))
             })?;
@@ -102,8 +118,11 @@ impl Tool for BashTool {
                 .wait_with_output()
                 .await
                 .map_err(|e| ToolError::ExecutionFailed(format!("Execution error: {}", e)))
-        })
-        .await;
+        });
+        let result = tokio::select! {
+            result = run => result,
+            _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+        };
 
         match result {
             Ok(Ok(output)) => {
@@ -131,7 +150,7 @@ impl Tool for BashTool {
                 })
             }
             Ok(Err(e)) => Err(e),
-            Err(_) => Err(ToolError::Timeout),
+            Err(_) => Err(ToolError::Timeout { after_secs: Some(timeout_secs) }),
         }
     }
 }
@@ -212,6 +231,632 @@ impl Tool for BashBackgroundTool {
     }
 }
 
+// ============================================================================
+// Background jobs - long-running commands the agent checks on later
+// ============================================================================
+
+/// Output kept per job; past it the oldest lines are dropped
+pub const JOB_OUTPUT_CAP: usize = 256 * 1024;
+
+/// Longer output lines are cut to this many bytes
+const JOB_LINE_MAX_BYTES: usize = 4096;
+
+/// Jobs allowed to run at once
+const MAX_RUNNING_JOBS: usize = 8;
+
+/// Finished jobs remembered, oldest forgotten first
+const MAX_FINISHED_JOBS: usize = 32;
+
+/// Lines of output in a job_status tail
+const STATUS_TAIL_LINES: usize = 20;
+
+/// How long a finished job's output readers may take to drain the pipes
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+static JOB_MANAGER: Lazy<JobManager> = Lazy::new(JobManager::default);
+
+/// One line of a job's output
+#[derive(Debug, Clone)]
+struct JobLine {
+    stderr: bool,
+    text: String,
+}
+
+/// Ring buffer of a job's output, stdout and stderr interleaved
+///
+/// Lines are numbered from the start of the job, so a line keeps its
+/// number after older ones were dropped.
+#[derive(Debug, Default)]
+struct JobOutput {
+    lines: VecDeque<JobLine>,
+    /// Number of the oldest line kept
+    first_line: usize,
+    bytes: usize,
+}
+
+impl JobOutput {
+    fn push(&mut self, stderr: bool, text: &str) {
+        let text = crate::truncate_str(text, JOB_LINE_MAX_BYTES).to_string();
+        self.bytes += text.len();
+        self.lines.push_back(JobLine { stderr, text });
+        while self.bytes > JOB_OUTPUT_CAP {
+            let Some(line) = self.lines.pop_front() else {
+                break;
+            };
+            self.bytes -= line.text.len();
+            self.first_line += 1;
+        }
+    }
+
+    /// Number the next line will get
+    fn end(&self) -> usize {
+        self.first_line + self.lines.len()
+    }
+
+    /// Kept lines numbered `start` or more, with their numbers
+    fn lines_from(&self, start: usize) -> impl Iterator<Item = (usize, &JobLine)> {
+        let first = self.first_line;
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(start.saturating_sub(first))
+            .map(move |(i, line)| (first + i, line))
+    }
+}
+
+/// Which output of a job to show
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JobStream {
+    All,
+    Stdout,
+    Stderr,
+}
+
+impl JobStream {
+    fn from_params(params: &Value) -> Result<Self, ToolError> {
+        match params["stream"].as_str().unwrap_or("all") {
+            "all" => Ok(Self::All),
+            "stdout" => Ok(Self::Stdout),
+            "stderr" => Ok(Self::Stderr),
+            other => Err(ToolError::InvalidParameters(tool_text!("job_logs.unknown_stream", other))),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
+
+    /// The line as shown, or None when it belongs to the other stream
+    fn render(self, line: &JobLine) -> Option<String> {
+        match (self, line.stderr) {
+            (Self::All, true) => Some(format!("[stderr] {}", line.text)),
+            (Self::All, false) | (Self::Stdout, false) | (Self::Stderr, true) => Some(line.text.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobState {
+    Running,
+    /// Exit code, None when the process was ended by a signal
+    Exited(Option<i32>),
+    Killed,
+}
+
+impl JobState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobState::Running => "running",
+            JobState::Exited(_) => "exited",
+            JobState::Killed => "killed",
+        }
+    }
+}
+
+/// A command started by job_start
+pub struct Job {
+    pub id: String,
+    pub command: String,
+    pub pid: Option<u32>,
+    started: Instant,
+    /// State, and how long the job ran once it is over
+    state: Mutex<(JobState, Option<Duration>)>,
+    output: Mutex<JobOutput>,
+    cancel: CancellationToken,
+}
+
+impl Job {
+    pub fn state(&self) -> JobState {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).0
+    }
+
+    fn finish(&self, state: JobState) {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = (state, Some(self.started.elapsed()));
+    }
+
+    fn status_json(&self) -> Value {
+        let (state, ran_for) = *self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        serde_json::json!({
+            "job_id": self.id,
+            "command": self.command,
+            "pid": self.pid,
+            "state": state.as_str(),
+            "exit_code": match state {
+                JobState::Exited(code) => code,
+                _ => None,
+            },
+            "elapsed_secs": ran_for.unwrap_or_else(|| self.started.elapsed()).as_secs(),
+            "output_lines": output.end(),
+            "dropped_lines": output.first_line,
+        })
+    }
+
+    /// Last `count` lines of output, stderr lines marked
+    fn tail(&self, count: usize) -> Vec<String> {
+        let output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        output
+            .lines_from(output.end().saturating_sub(count))
+            .filter_map(|(_, line)| JobStream::All.render(line))
+            .collect()
+    }
+
+    /// Stop the job and its child processes; false if it was not running
+    ///
+    /// Synchronous, so it also works while the app is exiting; the job reads
+    /// as killed once its waiter task has seen the cancellation.
+    pub fn kill(&self) -> bool {
+        if self.state() != JobState::Running {
+            return false;
+        }
+        self.cancel.cancel();
+        if let Some(pid) = self.pid {
+            kill_process_tree(pid);
+        }
+        true
+    }
+
+    /// Wait up to `limit` for the job to be over
+    async fn wait_finished(&self, limit: Duration) {
+        let deadline = Instant::now() + limit;
+        while self.state() == JobState::Running && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
+
+/// End `pid` and every process it started
+///
+/// Jobs run in their own process group on Unix, so the whole group is
+/// signalled; on Windows taskkill follows the process tree.
+fn kill_process_tree(pid: u32) {
+    let mut kill = if cfg!(windows) {
+        let mut cmd = std::process::Command::new("taskkill");
+        cmd.args(["/F", "/T", "/PID", &pid.to_string()]);
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new("kill");
+        cmd.args(["-KILL", "--", &format!("-{}", pid)]);
+        cmd
+    };
+    let _ = kill
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+}
+
+/// Append each line read from `stream` to the job's output
+async fn collect_output(job: Arc<Job>, stream: impl tokio::io::AsyncRead + Unpin, stderr: bool) {
+    use tokio::io::AsyncBufReadExt;
+    let mut reader = tokio::io::BufReader::new(stream);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let text = String::from_utf8_lossy(&line);
+                job.output
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(stderr, text.trim_end_matches(['\r', '\n']));
+            }
+        }
+    }
+}
+
+/// Jobs started by job_start, for the lifetime of the app
+#[derive(Default)]
+pub struct JobManager {
+    jobs: Mutex<Vec<Arc<Job>>>,
+    next_id: AtomicUsize,
+}
+
+impl JobManager {
+    pub fn global() -> &'static JobManager {
+        &JOB_MANAGER
+    }
+
+    /// Spawn `command` in the platform shell and start collecting its output
+    pub fn start(
+        &self,
+        command: &str,
+        cwd: Option<&std::path::Path>,
+        env: &[(String, String)],
+    ) -> Result<Arc<Job>, ToolError> {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        let running = jobs.iter().filter(|job| job.state() == JobState::Running).count();
+        if running >= MAX_RUNNING_JOBS {
+            return Err(ToolError::ExecutionFailed(tool_text!("job.too_many", running)));
+        }
+
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("powershell");
+            cmd.args(["-NoProfile", "-Command"]);
+            cmd
+        } else {
+            let mut cmd = Command::new("bash");
+            cmd.arg("-c");
+            cmd
+        };
+        cmd.arg(command);
+        if let Some(dir) = cwd {
+            cmd.current_dir(dir);
+        }
+        cmd.envs(env.iter().map(|(key, value)| (key, value)));
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.kill_on_drop(true);
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to launch command: {}", e)))?;
+        let id = format!("job-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let job = Arc::new(Job {
+            id,
+            command: command.to_string(),
+            pid: child.id(),
+            started: Instant::now(),
+            state: Mutex::new((JobState::Running, None)),
+            output: Mutex::new(JobOutput::default()),
+            cancel: CancellationToken::new(),
+        });
+
+        let stdout = child.stdout.take().map(|out| tokio::spawn(collect_output(job.clone(), out, false)));
+        let stderr = child.stderr.take().map(|err| tokio::spawn(collect_output(job.clone(), err, true)));
+        let waiter = job.clone();
+        tokio::spawn(async move {
+            let exited = tokio::select! {
+                biased;
+                _ = waiter.cancel.cancelled() => None,
+                status = child.wait() => Some(status),
+            };
+            let state = match exited {
+                Some(status) => JobState::Exited(status.ok().and_then(|s| s.code())),
+                None => {
+                    let _ = child.kill().await;
+                    JobState::Killed
+                }
+            };
+            // Let the readers take the last lines before the job reads as over
+            let drain = async {
+                for reader in [stdout, stderr].into_iter().flatten() {
+                    let _ = reader.await;
+                }
+            };
+            let _ = timeout(OUTPUT_DRAIN_TIMEOUT, drain).await;
+            waiter.finish(state);
+        });
+
+        jobs.push(job.clone());
+        let finished = jobs.iter().filter(|job| job.state() != JobState::Running).count();
+        if finished > MAX_FINISHED_JOBS {
+            if let Some(oldest) = jobs.iter().position(|job| job.state() != JobState::Running) {
+                jobs.remove(oldest);
+            }
+        }
+        Ok(job)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<Job>, ToolError> {
+        let jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.iter().find(|job| job.id == id).cloned().ok_or_else(|| {
+            let known: Vec<&str> = jobs.iter().map(|job| job.id.as_str()).collect();
+            ToolError::InvalidParameters(tool_text!(
+                "job.unknown",
+                id,
+                if known.is_empty() { tool_text!("job.none").to_string() } else { known.join(", ") }
+            ))
+        })
+    }
+
+    pub fn list(&self) -> Vec<Arc<Job>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Kill every running job; called when the app exits
+    pub fn kill_all(&self) {
+        for job in self.list() {
+            if job.kill() {
+                tracing::info!("Killed background job {} ({})", job.id, job.command);
+            }
+        }
+    }
+}
+
+fn job_id_param(params: &Value) -> Result<&str, ToolError> {
+    params["job_id"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("job_id is required".into()))
+}
+
+// ============================================================================
+// JobStartTool - Start a command and return at once with a job id
+// ============================================================================
+
+pub struct JobStartTool;
+
+#[async_trait]
+impl Tool for JobStartTool {
+    fn name(&self) -> &str {
+        "job_start"
+    }
+
+    fn description(&self) -> &str {
+        "Start a long-running command (build, test suite, dev server) as a background job and return its job_id at once. Check on it later with job_status / job_logs, stop it with job_kill. Jobs are killed when the app exits. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "Shell command to run"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory (optional)"
+                },
+                "env": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Extra environment variables (optional)"
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let command = params["command"]
+            .as_str()
+            .filter(|c| !c.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("command is required".into()))?;
+        // Same policy as the `command` tool: what it leaves to the user was
+        // approved before the call (see `get_call_permission`)
+        if let CommandVerdict::Deny(reason) = command_policy::check_command(command) {
+            return Err(ToolError::PermissionDenied {
+                rule: Some("command_policy".to_string()),
+                message: tool_text!("command.denied", reason),
+            });
+        }
+        let cwd = params["cwd"].as_str().map(resolve_and_validate_path).transpose()?;
+        let env: Vec<(String, String)> = match &params["env"] {
+            Value::Null => Vec::new(),
+            Value::Object(vars) => vars
+                .iter()
+                .map(|(key, value)| match value {
+                    Value::String(s) => (key.clone(), s.clone()),
+                    other => (key.clone(), other.to_string()),
+                })
+                .collect(),
+            _ => return Err(ToolError::InvalidParameters("env must be an object".into())),
+        };
+
+        let job = JobManager::global().start(command, cwd.as_deref(), &env)?;
+        Ok(ToolResult {
+            success: true,
+            data: job.status_json(),
+            message: tool_text!("job_start.done", job.id, job.pid.unwrap_or(0), command),
+        })
+    }
+}
+
+// ============================================================================
+// JobStatusTool - State of one job (or all) with the end of its output
+// ============================================================================
+
+pub struct JobStatusTool;
+
+#[async_trait]
+impl Tool for JobStatusTool {
+    fn name(&self) -> &str {
+        "job_status"
+    }
+
+    fn description(&self) -> &str {
+        "State of a background job (running / exited with code / killed) and the last lines of its output. Without job_id, lists all jobs."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "job_id": {
+                    "type": "string",
+                    "description": "Job returned by job_start (optional: all jobs)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let Some(id) = params["job_id"].as_str() else {
+            let jobs: Vec<Value> = JobManager::global().list().iter().map(|job| job.status_json()).collect();
+            let running = jobs.iter().filter(|job| job["state"] == "running").count();
+            return Ok(ToolResult {
+                success: true,
+                message: tool_text!("job_status.list", jobs.len(), running),
+                data: serde_json::json!({ "jobs": jobs }),
+            });
+        };
+
+        let job = JobManager::global().get(id)?;
+        let mut data = job.status_json();
+        data["tail"] = serde_json::json!(job.tail(STATUS_TAIL_LINES));
+        let message = match job.state() {
+            JobState::Running => tool_text!("job_status.running", job.id, data["elapsed_secs"]),
+            JobState::Exited(Some(code)) => tool_text!("job_status.exited", job.id, code),
+            JobState::Exited(None) => tool_text!("job_status.signaled", job.id),
+            JobState::Killed => tool_text!("job.killed", job.id),
+        };
+        Ok(ToolResult { success: true, data, message })
+    }
+}
+
+// ============================================================================
+// JobLogsTool - A job's output, paged by line number
+// ============================================================================
+
+pub struct JobLogsTool;
+
+#[async_trait]
+impl Tool for JobLogsTool {
+    fn name(&self) -> &str {
+        "job_logs"
+    }
+
+    fn description(&self) -> &str {
+        "Read a background job's output from a line number on, paged by output_limit. Lines are numbered from the start of the job; the oldest are dropped once the job's output passes its cap."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "job_id": {
+                    "type": "string",
+                    "description": "Job returned by job_start"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to return (default: oldest kept line)"
+                },
+                "stream": {
+                    "type": "string",
+                    "enum": ["all", "stdout", "stderr"],
+                    "description": "Output to read (default: all, stderr lines marked)",
+                    "default": "all"
+                },
+                "output_limit": output_limit_schema()
+            },
+            "required": ["job_id"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let job = JobManager::global().get(job_id_param(&params)?)?;
+        let stream = JobStream::from_params(&params)?;
+        let mut budget = OutputBudget::from_params(&params);
+
+        let output = job.output.lock().unwrap_or_else(|e| e.into_inner());
+        let start = params["start_line"].as_u64().map_or(output.first_line, |n| n as usize);
+        let mut lines = Vec::new();
+        let mut next = None;
+        for (number, line) in output.lines_from(start) {
+            let Some(text) = stream.render(line) else {
+                continue;
+            };
+            if !budget.take(text.len() + 1) {
+                next = Some(number);
+                break;
+            }
+            lines.push(text);
+        }
+
+        let mut data = serde_json::json!({
+            "job_id": job.id,
+            "state": job.state().as_str(),
+            "stream": stream.as_str(),
+            "start_line": start.max(output.first_line),
+            "lines": lines,
+            "dropped_lines": output.first_line,
+        });
+        if let Some(next) = next {
+            mark_truncated(
+                &mut data,
+                serde_json::json!({ "job_id": job.id, "stream": stream.as_str(), "start_line": next }),
+            );
+        } else {
+            data["next_line"] = output.end().into();
+        }
+        let skipped = if start < output.first_line {
+            tool_text!("job_logs.dropped", output.first_line - start)
+        } else {
+            String::new()
+        };
+        Ok(ToolResult {
+            success: true,
+            message: tool_text!("job_logs.done", job.id, lines.len(), skipped),
+            data,
+        })
+    }
+}
+
+// ============================================================================
+// JobKillTool - Stop a running job
+// ============================================================================
+
+pub struct JobKillTool;
+
+#[async_trait]
+impl Tool for JobKillTool {
+    fn name(&self) -> &str {
+        "job_kill"
+    }
+
+    fn description(&self) -> &str {
+        "Stop a background job started with job_start, along with the processes it started."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "job_id": {
+                    "type": "string",
+                    "description": "Job returned by job_start"
+                }
+            },
+            "required": ["job_id"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let job = JobManager::global().get(job_id_param(&params)?)?;
+        let killed = job.kill();
+        job.wait_finished(OUTPUT_DRAIN_TIMEOUT * 2).await;
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({ "job_id": job.id, "killed": killed, "state": job.state().as_str() }),
+            message: if killed {
+                tool_text!("job.killed", job.id)
+            } else {
+                tool_text!("job_kill.not_running", job.id, job.state().as_str())
+            },
+        })
+    }
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -231,3 +876,70 @@ fn truncate_output(output: &str, max_chars: usize) -> String {
         )
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_output_is_capped() {
+        let mut output = JobOutput::default();
+        let line = "x".repeat(1000);
+        for _ in 0..(JOB_OUTPUT_CAP / 1000 + 10) {
+            output.push(false, &line);
+        }
+        assert!(output.bytes <= JOB_OUTPUT_CAP);
+        assert!(output.first_line >= 10);
+        assert_eq!(output.end(), JOB_OUTPUT_CAP / 1000 + 10);
+        assert_eq!(output.lines_from(output.end() - 1).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_job_lifecycle() {
+        let started = JobStartTool
+            .execute(serde_json::json!({
+                "command": "echo one; echo oops >&2; echo \"$GREETING\"; exit 3",
+                "env": {"GREETING": "hello"}
+            }))
+            .await
+            .unwrap();
+        let id = started.data["job_id"].as_str().unwrap().to_string();
+        let job = JobManager::global().get(&id).unwrap();
+        job.wait_finished(Duration::from_secs(10)).await;
+
+        let status = JobStatusTool.execute(serde_json::json!({"job_id": id})).await.unwrap();
+        assert_eq!(status.data["state"], "exited");
+        assert_eq!(status.data["exit_code"], 3);
+        // stdout and stderr are read separately, so only each stream's order is fixed
+        let tail = status.data["tail"].as_array().unwrap();
+        assert_eq!(tail.len(), 3);
+        assert!(tail.contains(&serde_json::json!("[stderr] oops")));
+
+        let first = JobLogsTool
+            .execute(serde_json::json!({"job_id": id, "stream": "stdout", "output_limit": 4}))
+            .await
+            .unwrap();
+        assert_eq!(first.data["lines"], serde_json::json!(["one"]));
+        let mut next = first.data["continuation"].clone();
+        next["output_limit"] = serde_json::json!(4);
+        let second = JobLogsTool.execute(next).await.unwrap();
+        assert_eq!(second.data["lines"], serde_json::json!(["hello"]));
+        assert_eq!(second.data["truncated"], Value::Null);
+
+        // Denied patterns refuse a job before it is spawned
+        let denied = JobStartTool
+            .execute(serde_json::json!({"command": "dd if=/dev/zero of=/dev/null count=1"}))
+            .await
+            .unwrap_err();
+        assert!(matches!(denied, ToolError::PermissionDenied { rule: Some(ref rule), .. } if rule == "command_policy"));
+
+        let sleeper = JobStartTool.execute(serde_json::json!({"command": "sleep 30"})).await.unwrap();
+        let sleeper_id = sleeper.data["job_id"].as_str().unwrap();
+        let killed = JobKillTool.execute(serde_json::json!({"job_id": sleeper_id})).await.unwrap();
+        assert_eq!(killed.data["killed"], true);
+        assert_eq!(killed.data["state"], "killed");
+
+        let unknown = JobStatusTool.execute(serde_json::json!({"job_id": "job-0"})).await.unwrap_err();
+        assert!(matches!(unknown, ToolError::InvalidParameters(_)));
+    }
+}
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use crate::agent::tools::{Tool, ToolResult, ToolError};
-use crate::agent::skills::loader::SkillLoader;
+use crate::agent::skills::loader::{SkillLoader, SkillsStatus};
 
 pub struct SkillListTool;
 
@@ -24,7 +24,9 @@ impl Tool for SkillListTool {
     }
 
     async fn execute(&self, _params: Value) -> Result<ToolResult, ToolError> {
-        let skills = SkillLoader::load_all().await;
+        let report = SkillLoader::load_all_with_report().await;
+        let status = report.status();
+        let skills = report.skills;
         
         let skill_infos: Vec<Value> = skills.iter().map(|s| {
             serde_json::json!({
@@ -36,13 +38,37 @@ impl Tool for SkillListTool {
             })
         }).collect();
         
+        // Tell the model *why* the list is empty so it doesn't invent skills
+        let (status_str, message) = match &status {
+            SkillsStatus::Loaded(n) => ("loaded", format!("Found {} skills.", n)),
+            SkillsStatus::Empty => (
+                "empty",
+                "No skills installed yet. Skills can be created with skill_create.".to_string(),
+            ),
+            SkillsStatus::Unreadable { path, error } => (
+                "unreadable",
+                format!(
+                    "Found {} skills, but the skills directory {} could not be read: {}",
+                    skills.len(),
+                    path.display(),
+                    error
+                ),
+            ),
+        };
+
+        let failures: Vec<Value> = report.failures.iter().map(|(path, error)| {
+            serde_json::json!({ "path": path, "error": error })
+        }).collect();
+
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({
                 "skills": skill_infos,
-                "count": skills.len()
+                "count": skills.len(),
+                "status": status_str,
+                "errors": failures
             }),
-            message: format!("Found {} skills.", skills.len()),
+            message,
         })
     }
 }
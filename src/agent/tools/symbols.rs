@@ -0,0 +1,761 @@
+//! Structural code search - definitions and references by symbol name
+//!
+//! Sources are parsed with tree-sitter: definitions are the declaration nodes
+//! of the language's grammar, references the identifiers naming the symbol,
+//! so comments and strings never match. Grammars are loaded on first use.
+//! Files in other languages fall back to a plain regex search, with a warning.
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser, StreamingIterator};
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::gitignore::IgnoreRules;
+use crate::agent::tools::{
+    mark_truncated, output_limit_schema, page_results, OutputBudget, Tool, ToolError, ToolResult,
+};
+
+/// Larger files are skipped (generated code, bundles, data)
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Files scanned at most by one search
+const MAX_FILES: usize = 5000;
+
+/// Lines of a definition's text returned; the range still covers all of it
+const MAX_NODE_LINES: usize = 80;
+
+/// Bytes kept of a reference line
+const MAX_LINE_CHARS: usize = 300;
+
+/// Directories never worth scanning, ignore files or not
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "__pycache__", "vendor", "dist"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    /// TypeScript with JSX, a grammar of its own
+    Tsx,
+    Go,
+}
+
+impl Language {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "rust" | "rs" => Some(Self::Rust),
+            "python" | "py" => Some(Self::Python),
+            "javascript" | "js" => Some(Self::JavaScript),
+            "typescript" | "ts" => Some(Self::TypeScript),
+            "go" | "golang" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// Whether a `language` filter naming `self` covers files of `other`
+    fn covers(self, other: Self) -> bool {
+        self == other || (self == Self::TypeScript && other == Self::Tsx)
+    }
+
+    /// Parser and definition query of the language, loaded on first use
+    fn grammar(self) -> &'static Grammar {
+        match self {
+            Self::Rust => &RUST,
+            Self::Python => &PYTHON,
+            Self::JavaScript => &JAVASCRIPT,
+            Self::TypeScript => &TYPESCRIPT,
+            Self::Tsx => &TSX,
+            Self::Go => &GO,
+        }
+    }
+}
+
+/// What a definition declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DefKind {
+    Function,
+    /// struct, enum, trait, class, interface, type alias, module
+    Type,
+}
+
+impl DefKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Function => "function_definition",
+            Self::Type => "type_definition",
+        }
+    }
+}
+
+struct Grammar {
+    language: tree_sitter::Language,
+    /// Declarations, captured as `@function` or `@type` with their `@name`
+    definitions: tree_sitter::Query,
+}
+
+fn load(language: tree_sitter::Language, definitions: &str) -> Grammar {
+    let definitions = tree_sitter::Query::new(&language, definitions).expect("valid definition query");
+    Grammar { language, definitions }
+}
+
+/// Node kinds an identifier of the symbol may have, across grammars
+const IDENTIFIER_KINDS: &[&str] = &[
+    "identifier",
+    "type_identifier",
+    "field_identifier",
+    "property_identifier",
+    "shorthand_property_identifier",
+    "shorthand_property_identifier_pattern",
+    "package_identifier",
+];
+
+/// Nodes a declaration is widened to, so its text shows the whole statement
+/// (`export const f = () => {}`, Python decorators, `type ( ... )` groups)
+const WRAPPER_KINDS: &[&str] = &[
+    "decorated_definition",
+    "export_statement",
+    "lexical_declaration",
+    "variable_declaration",
+    "type_declaration",
+];
+
+const JS_DEFINITIONS: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(generator_function_declaration name: (identifier) @name) @function
+(method_definition name: (property_identifier) @name) @function
+(variable_declarator name: (identifier) @name value: [(arrow_function) (function_expression)]) @function
+"#;
+
+const TS_DEFINITIONS: &str = r#"
+(function_signature name: (identifier) @name) @function
+(class_declaration name: (type_identifier) @name) @type
+(abstract_class_declaration name: (type_identifier) @name) @type
+(interface_declaration name: (type_identifier) @name) @type
+(enum_declaration name: (identifier) @name) @type
+(type_alias_declaration name: (type_identifier) @name) @type
+(internal_module name: (identifier) @name) @type
+"#;
+
+static RUST: Lazy<Grammar> = Lazy::new(|| {
+    load(
+        tree_sitter_rust::LANGUAGE.into(),
+        r#"
+(function_item name: (identifier) @name) @function
+(function_signature_item name: (identifier) @name) @function
+(struct_item name: (type_identifier) @name) @type
+(enum_item name: (type_identifier) @name) @type
+(union_item name: (type_identifier) @name) @type
+(trait_item name: (type_identifier) @name) @type
+(type_item name: (type_identifier) @name) @type
+(mod_item name: (identifier) @name) @type
+"#,
+    )
+});
+
+static PYTHON: Lazy<Grammar> = Lazy::new(|| {
+    load(
+        tree_sitter_python::LANGUAGE.into(),
+        r#"
+(function_definition name: (identifier) @name) @function
+(class_definition name: (identifier) @name) @type
+"#,
+    )
+});
+
+static JAVASCRIPT: Lazy<Grammar> = Lazy::new(|| {
+    let definitions = format!("{}\n(class_declaration name: (identifier) @name) @type", JS_DEFINITIONS);
+    load(tree_sitter_javascript::LANGUAGE.into(), &definitions)
+});
+
+static TYPESCRIPT: Lazy<Grammar> = Lazy::new(|| {
+    load(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(), &format!("{}{}", JS_DEFINITIONS, TS_DEFINITIONS))
+});
+
+static TSX: Lazy<Grammar> =
+    Lazy::new(|| load(tree_sitter_typescript::LANGUAGE_TSX.into(), &format!("{}{}", JS_DEFINITIONS, TS_DEFINITIONS)));
+
+static GO: Lazy<Grammar> = Lazy::new(|| {
+    load(
+        tree_sitter_go::LANGUAGE.into(),
+        r#"
+(function_declaration name: (identifier) @name) @function
+(method_declaration name: (field_identifier) @name) @function
+(type_spec name: (type_identifier) @name) @type
+(type_alias name: (type_identifier) @name) @type
+"#,
+    )
+});
+
+/// One definition found in a file, lines 0-based and inclusive
+#[derive(Debug, Clone, PartialEq)]
+struct Definition {
+    kind: DefKind,
+    name: String,
+    /// Decorators and `export` included
+    start: usize,
+    end: usize,
+    /// Byte range of the name, so it is not taken for a reference
+    name_bytes: std::ops::Range<usize>,
+}
+
+/// Last line of `node`, not counting a final line break it swallows
+fn end_row(node: Node) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Every definition of a parsed file, in order
+fn definitions(root: Node, source: &str, grammar: &Grammar) -> Vec<Definition> {
+    let query = &grammar.definitions;
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut matches = cursor.matches(query, root, source.as_bytes());
+    let mut found = Vec::new();
+    while let Some(m) = matches.next() {
+        let (mut decl, mut kind, mut name) = (None, None, None);
+        for capture in m.captures {
+            match query.capture_names()[capture.index as usize] {
+                "name" => name = Some(capture.node),
+                "function" => (decl, kind) = (Some(capture.node), Some(DefKind::Function)),
+                "type" => (decl, kind) = (Some(capture.node), Some(DefKind::Type)),
+                _ => {}
+            }
+        }
+        let (Some(mut decl), Some(kind), Some(name)) = (decl, kind, name) else {
+            continue;
+        };
+        while let Some(parent) = decl.parent().filter(|p| WRAPPER_KINDS.contains(&p.kind())) {
+            decl = parent;
+        }
+        found.push(Definition {
+            kind,
+            name: source[name.byte_range()].to_string(),
+            start: decl.start_position().row,
+            end: end_row(decl),
+            name_bytes: name.byte_range(),
+        });
+    }
+    found.sort_by_key(|def| (def.start, def.name_bytes.start));
+    found
+}
+
+/// Lines (0-based, in order) where an identifier spells `symbol`, the
+/// definitions' own names left out
+fn reference_lines(root: Node, source: &str, symbol: &str, defs: &[Definition]) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        if IDENTIFIER_KINDS.contains(&node.kind())
+            && &source[node.byte_range()] == symbol
+            && !defs.iter().any(|def| def.name_bytes == node.byte_range())
+        {
+            let row = node.start_position().row;
+            if lines.last() != Some(&row) {
+                lines.push(row);
+            }
+        }
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        loop {
+            if !cursor.goto_parent() {
+                return lines;
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// What to look for
+#[derive(Debug, Clone)]
+enum Query {
+    /// Definitions named `name`, of one kind or any
+    Definition { kind: Option<DefKind>, name: String },
+    /// Uses of `symbol` outside its definitions
+    References { symbol: String, word: Regex },
+}
+
+impl Query {
+    fn from_params(params: &Value) -> Result<Self, ToolError> {
+        let kind = params["kind"].as_str().unwrap_or("definition");
+        let name = params["name"]
+            .as_str()
+            .or_else(|| params["symbol"].as_str())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("name (or symbol) is required".into()))?
+            .to_string();
+        let kind = match kind {
+            "references" | "reference" | "callers" => {
+                let word = Regex::new(&format!(r"(?:^|[^\w$]){}(?:[^\w$]|$)", regex::escape(&name)))
+                    .map_err(|e| ToolError::InvalidParameters(format!("Invalid symbol: {}", e)))?;
+                return Ok(Self::References { symbol: name, word });
+            }
+            "definition" | "any" => None,
+            "function_definition" | "function" | "method" => Some(DefKind::Function),
+            "type_definition" | "class_definition" | "struct" | "class" | "enum" | "trait" | "interface" | "type" => {
+                Some(DefKind::Type)
+            }
+            other => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Unknown kind '{}': use function_definition, type_definition, definition or references",
+                    other
+                )))
+            }
+        };
+        Ok(Self::Definition { kind, name })
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Definition { name, .. } => name,
+            Self::References { symbol, .. } => symbol,
+        }
+    }
+}
+
+/// The first `MAX_NODE_LINES` lines of `start..=end`
+fn node_text(lines: &[&str], start: usize, end: usize) -> String {
+    let shown = (end - start + 1).min(MAX_NODE_LINES);
+    let mut text = lines[start..start + shown].join("\n");
+    if shown < end - start + 1 {
+        text.push_str(&tool_text!("code_search.more_lines", end - start + 1 - shown));
+    }
+    text
+}
+
+/// Matches of `query` in one file of a supported language
+fn search_source(
+    parser: &mut Parser,
+    path: &Path,
+    content: &str,
+    language: Language,
+    query: &Query,
+    results: &mut Vec<Value>,
+    limit: usize,
+) {
+    let grammar = language.grammar();
+    if parser.set_language(&grammar.language).is_err() {
+        return;
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return;
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let defs = definitions(tree.root_node(), content, grammar);
+    let file = path.display().to_string();
+
+    match query {
+        Query::Definition { kind, name } => {
+            for def in defs.iter().filter(|def| &def.name == name && kind.map_or(true, |kind| kind == def.kind)) {
+                if results.len() >= limit {
+                    return;
+                }
+                results.push(serde_json::json!({
+                    "file": file,
+                    "kind": def.kind.as_str(),
+                    "name": def.name,
+                    "start_line": def.start + 1,
+                    "end_line": def.end + 1,
+                    "text": node_text(&lines, def.start, def.end),
+                }));
+            }
+        }
+        Query::References { symbol, .. } => {
+            for i in reference_lines(tree.root_node(), content, symbol, &defs) {
+                if results.len() >= limit {
+                    return;
+                }
+                // Innermost definition around the use: the caller
+                let caller = defs
+                    .iter()
+                    .filter(|def| def.start <= i && i <= def.end)
+                    .min_by_key(|def| def.end - def.start)
+                    .map(|def| def.name.clone());
+                results.push(serde_json::json!({
+                    "file": file,
+                    "kind": "reference",
+                    "name": symbol,
+                    "start_line": i + 1,
+                    "end_line": i + 1,
+                    "text": crate::truncate_str(lines.get(i).map_or("", |line| line.trim()), MAX_LINE_CHARS),
+                    "in": caller,
+                }));
+            }
+        }
+    }
+}
+
+/// Lines naming `query` in a file no grammar covers
+fn search_plain(path: &Path, content: &str, query: &Query, results: &mut Vec<Value>, limit: usize) {
+    let word = match query {
+        Query::References { word, .. } => word.clone(),
+        Query::Definition { name, .. } => match Regex::new(&format!(r"\b{}\b", regex::escape(name))) {
+            Ok(word) => word,
+            Err(_) => return,
+        },
+    };
+    for (i, line) in content.lines().enumerate() {
+        if results.len() >= limit {
+            return;
+        }
+        if word.is_match(line) {
+            results.push(serde_json::json!({
+                "file": path.display().to_string(),
+                "kind": "text_match",
+                "name": query.name(),
+                "start_line": i + 1,
+                "end_line": i + 1,
+                "text": crate::truncate_str(line.trim(), MAX_LINE_CHARS),
+            }));
+        }
+    }
+}
+
+fn collect_files(dir: &Path, ignore: &IgnoreRules, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    entries.sort();
+    for path in entries {
+        if files.len() >= MAX_FILES {
+            return;
+        }
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let is_dir = path.is_dir();
+        if name.starts_with('.') || (is_dir && SKIPPED_DIRS.contains(&name.as_str())) || ignore.is_ignored(&path, is_dir) {
+            continue;
+        }
+        if is_dir {
+            collect_files(&path, &ignore.child(&path), files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Outcome of a search over a file or tree
+#[derive(Debug, Default)]
+struct SearchReport {
+    results: Vec<Value>,
+    files_scanned: usize,
+    skipped_large: usize,
+    /// Extensions searched by regex for want of a grammar
+    fallback: Vec<String>,
+}
+
+fn run_search(root: &Path, query: &Query, language: Option<Language>, respect_gitignore: bool, limit: usize) -> SearchReport {
+    let mut files = Vec::new();
+    let explicit = root.is_file();
+    if explicit {
+        files.push(root.to_path_buf());
+    } else {
+        collect_files(root, &IgnoreRules::for_root(root, respect_gitignore), &mut files);
+    }
+
+    let supported: Vec<(PathBuf, Language)> = files
+        .iter()
+        .filter_map(|path| Some((path.clone(), Language::from_path(path)?)))
+        .filter(|(_, lang)| language.map_or(true, |wanted| wanted.covers(*lang)))
+        .collect();
+    // Regex only when no grammar applies: a file named explicitly, or a tree without supported sources
+    let plain: Vec<PathBuf> = if supported.is_empty() && (explicit || language.is_none()) {
+        files.into_iter().filter(|path| Language::from_path(path).is_none()).collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut report = SearchReport::default();
+    let mut parser = Parser::new();
+    let too_large = |path: &Path| std::fs::metadata(path).map(|m| m.len() > MAX_FILE_BYTES).unwrap_or(true);
+    for (path, lang) in &supported {
+        if report.results.len() >= limit {
+            break;
+        }
+        if too_large(path) {
+            report.skipped_large += 1;
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        report.files_scanned += 1;
+        search_source(&mut parser, path, &content, *lang, query, &mut report.results, limit);
+    }
+    for path in &plain {
+        if report.results.len() >= limit {
+            break;
+        }
+        if too_large(path) {
+            report.skipped_large += 1;
+            continue;
+        }
+        // Binary files fail to decode and are passed over
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        report.files_scanned += 1;
+        let ext = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| tool_text!("code_search.no_extension").into());
+        if !report.fallback.contains(&ext) {
+            report.fallback.push(ext);
+        }
+        search_plain(path, &content, query, &mut report.results, limit);
+    }
+    report
+}
+
+// ============================================================================
+// CodeSearchTool - Find definitions and references by name
+// ============================================================================
+
+pub struct CodeSearchTool;
+
+#[async_trait]
+impl Tool for CodeSearchTool {
+    fn name(&self) -> &str {
+        "code_search"
+    }
+
+    fn description(&self) -> &str {
+        "Find where a function/class/type is defined, or every place a symbol is used, in Rust, Python, JS/TS and Go sources, parsed with tree-sitter. Returns file, line range and the code of each definition (or the line and enclosing function of each reference), saving file_read round trips. Other languages fall back to a plain text search."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "kind": {
+                    "type": "string",
+                    "enum": ["function_definition", "type_definition", "definition", "references"],
+                    "description": "function_definition, type_definition (struct/enum/trait/class/interface/type), definition (any) or references (uses of the symbol)",
+                    "default": "definition"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Symbol name, exact (e.g. 'handle_send')"
+                },
+                "symbol": {
+                    "type": "string",
+                    "description": "Alias of name, for kind=references"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Directory or file to search in",
+                    "default": "."
+                },
+                "language": {
+                    "type": "string",
+                    "enum": ["rust", "python", "javascript", "typescript", "go"],
+                    "description": "Only search files of this language"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum results to return",
+                    "default": 20
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Skip this many results (paging, see `continuation`)",
+                    "default": 0
+                },
+                "output_limit": output_limit_schema(),
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Skip paths ignored by .gitignore/.ignore files (default: true)",
+                    "default": true
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let query = Query::from_params(&params)?;
+        let path = params["path"].as_str().unwrap_or(".");
+        let language = match params["language"].as_str() {
+            Some(name) => Some(Language::from_name(name).ok_or_else(|| {
+                ToolError::InvalidParameters(format!(
+                    "Unsupported language '{}': use rust, python, javascript, typescript or go",
+                    name
+                ))
+            })?),
+            None => None,
+        };
+        let max_results = params["max_results"].as_u64().unwrap_or(20) as usize;
+        let offset = params["offset"].as_u64().unwrap_or(0) as usize;
+        let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+        let mut budget = OutputBudget::from_params(&params);
+        // Results kept while searching: the skipped ones plus a page
+        let limit = offset + max_results;
+
+        let root = resolve_and_validate_path(path)?;
+        if !root.exists() {
+            return Err(ToolError::NotFound {
+                path: root.display().to_string(),
+                message: "Path does not exist".to_string(),
+            });
+        }
+
+        let search_query = query.clone();
+        let report = tokio::task::spawn_blocking(move || run_search(&root, &search_query, language, respect_gitignore, limit))
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Search task failed: {}", e)))?;
+
+        // The search stops at `limit`: reaching it means there may be more
+        let found = if report.results.len() >= limit { usize::MAX } else { report.results.len() };
+        let (page, next_offset) = page_results(report.results, offset, max_results, found, &mut budget);
+        let total = page.len();
+        let warning = (!report.fallback.is_empty()).then(|| {
+            tool_text!("code_search.fallback", report.fallback.join(", ."))
+        });
+        let mut data = serde_json::json!({
+            "results": page,
+            "total": total,
+            "name": query.name(),
+            "files_scanned": report.files_scanned,
+            "skipped_large_files": report.skipped_large,
+        });
+        if let Some(warning) = &warning {
+            data["warning"] = Value::String(warning.clone());
+        }
+        if let Some(next) = next_offset {
+            mark_truncated(&mut data, serde_json::json!({ "offset": next }));
+        }
+
+        let mut message = match &query {
+            Query::Definition { .. } => {
+                tool_text!("code_search.definitions", total, query.name(), report.files_scanned)
+            }
+            Query::References { .. } => {
+                tool_text!("code_search.references", total, query.name(), report.files_scanned)
+            }
+        };
+        if next_offset.is_some() {
+            message.push_str(tool_text!("code_search.truncated"));
+        }
+        if let Some(warning) = warning {
+            message.push_str(&format!("\n⚠ {}", warning));
+        }
+        Ok(ToolResult { success: true, data, message })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defs_of(source: &str, language: Language) -> Vec<(DefKind, String, usize, usize)> {
+        let grammar = language.grammar();
+        let mut parser = Parser::new();
+        parser.set_language(&grammar.language).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        definitions(tree.root_node(), source, grammar)
+            .into_iter()
+            .map(|def| (def.kind, def.name, def.start + 1, def.end + 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_grammars_load() {
+        for language in [Language::Rust, Language::Python, Language::JavaScript, Language::TypeScript, Language::Tsx, Language::Go] {
+            assert!(language.grammar().definitions.pattern_count() > 0);
+        }
+    }
+
+    #[test]
+    fn test_definition_ranges_per_language() {
+        let rust = "pub struct Unit;\n\n/// Sends it\npub async fn handle_send(\n    msg: &str,\n) -> Result<(), E>\nwhere\n    E: Error,\n{\n    let s = \"}\";\n    if msg.is_empty() {\n        return Ok(());\n    }\n    Ok(())\n}\n";
+        assert_eq!(
+            defs_of(rust, Language::Rust),
+            [(DefKind::Type, "Unit".to_string(), 1, 1), (DefKind::Function, "handle_send".to_string(), 4, 15)]
+        );
+
+        let python = "@cached\ndef load(path,\n         mode='r'):\n    data = read(path)\n\n    return data\n\nclass Store:\n    def save(self):\n        pass\n";
+        assert_eq!(
+            defs_of(python, Language::Python),
+            [
+                (DefKind::Function, "load".to_string(), 1, 6),
+                (DefKind::Type, "Store".to_string(), 8, 10),
+                (DefKind::Function, "save".to_string(), 9, 10),
+            ]
+        );
+
+        let ts = "export interface Props {\n  id: string;\n}\nexport const render = async (p: Props) => {\n  if (p.id) {\n    return `{${p.id}`;\n  }\n};\n";
+        assert_eq!(
+            defs_of(ts, Language::TypeScript),
+            [(DefKind::Type, "Props".to_string(), 1, 3), (DefKind::Function, "render".to_string(), 4, 8)]
+        );
+
+        let go = "type ID int\n\nfunc (s *Server) Start() error {\n\t/* { */\n\treturn nil\n}\n";
+        assert_eq!(
+            defs_of(go, Language::Go),
+            [(DefKind::Type, "ID".to_string(), 1, 1), (DefKind::Function, "Start".to_string(), 3, 6)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_code_search_finds_definitions_and_callers() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("store.rs"),
+            "pub fn save_conversation(id: u32) {\n    // save_conversation in a comment\n}\n\nfn on_exit() {\n    save_conversation(1);\n}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("app.py"), "def main():\n    save_conversation(2)\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "call save_conversation later\n").unwrap();
+
+        let tool = CodeSearchTool;
+        let result = tool
+            .execute(serde_json::json!({"kind": "function_definition", "name": "save_conversation", "path": root}))
+            .await
+            .unwrap();
+        let found = result.data["results"].as_array().unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0]["start_line"].as_u64(), found[0]["end_line"].as_u64()), (Some(1), Some(3)));
+        assert!(result.data.get("warning").is_none());
+
+        let result = tool
+            .execute(serde_json::json!({"kind": "references", "symbol": "save_conversation", "path": root}))
+            .await
+            .unwrap();
+        let callers: Vec<&str> = result.data["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["in"].as_str().unwrap())
+            .collect();
+        assert_eq!(callers, ["main", "on_exit"]);
+
+        // No grammar for .txt: regex search, flagged
+        let result = tool
+            .execute(serde_json::json!({"name": "save_conversation", "path": root.join("notes.txt")}))
+            .await
+            .unwrap();
+        assert_eq!(result.data["results"][0]["kind"], "text_match");
+        assert!(result.data["warning"].as_str().unwrap().contains(".txt"));
+    }
+}
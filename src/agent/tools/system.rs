@@ -1,11 +1,16 @@
-//! System tools - Process list, Environment, System info
+//! System tools - Process list, Environment, System info, Project overview
 //!
 //! Provides system-level information and operations.
 
 use async_trait::async_trait;
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
 
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::{format_size, resolve_and_validate_path, resolve_path_in};
+use crate::agent::tools::gitignore::IgnoreRules;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 
 // ============================================================================
@@ -52,7 +57,7 @@ impl Tool for ProcessListTool {
         };
 
         let output = output.map_err(|e| {
-            ToolError::ExecutionFailed(format!("Impossible de lister les processus: {}", e))
+            ToolError::ExecutionFailed(tool_text!("process_list.error", e))
         })?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -75,8 +80,10 @@ impl Tool for ProcessListTool {
                 "processes": filtered,
                 "filter": filter
             }),
-            message: format!("Processus listés{}", 
-                filter.map(|f| format!(" (filtre: {})", f)).unwrap_or_default()),
+            message: tool_text!(
+                "process_list.done",
+                filter.map(|f| tool_text!("process_list.filter", f)).unwrap_or_default()
+            ),
         })
     }
 }
@@ -130,7 +137,7 @@ impl Tool for EnvironmentTool {
                 Err(_) => Ok(ToolResult {
                     success: false,
                     data: serde_json::json!({ "name": var_name, "value": null }),
-                    message: format!("Variable '{}' non définie", var_name),
+                    message: tool_text!("environment.unset", var_name),
                 }),
             }
         } else {
@@ -161,7 +168,7 @@ impl Tool for EnvironmentTool {
                     "variables": safe_vars,
                     "count": safe_vars.len()
                 }),
-                message: format!("{} variable(s) d'environnement", safe_vars.len()),
+                message: tool_text!("environment.done", safe_vars.len()),
             })
         }
     }
@@ -280,7 +287,7 @@ impl Tool for WhichTool {
             .args(&args)
             .output()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("which.error", e)))?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
 
@@ -300,7 +307,7 @@ impl Tool for WhichTool {
                     "command": command_name,
                     "path": null
                 }),
-                message: format!("'{}' non trouvé dans le PATH", command_name),
+                message: tool_text!("which.not_found", command_name),
             })
         }
     }
@@ -310,7 +317,18 @@ impl Tool for WhichTool {
 // TreeTool - Show directory tree
 // ============================================================================
 
-pub struct TreeTool;
+/// Lists inside the workspace sandbox, or under `root` when one is given
+#[derive(Default)]
+pub struct TreeTool {
+    root: Option<PathBuf>,
+}
+
+impl TreeTool {
+    /// Tool confined to `root` instead of the workspace sandbox setting
+    pub fn confined_to(root: PathBuf) -> Self {
+        Self { root: Some(root) }
+    }
+}
 
 #[async_trait]
 impl Tool for TreeTool {
@@ -350,12 +368,9 @@ impl Tool for TreeTool {
         let max_depth = params["max_depth"].as_u64().unwrap_or(3) as usize;
         let show_hidden = params["show_hidden"].as_bool().unwrap_or(false);
 
-        let path_buf = std::path::PathBuf::from(path);
+        let path_buf = resolve_path_in(self.root.as_deref(), path)?;
         if !path_buf.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Le chemin '{}' n'existe pas",
-                path
-            )));
+            return Err(ToolError::ExecutionFailed(tool_text!("path.not_found", path)));
         }
 
         let mut tree = String::new();
@@ -375,10 +390,7 @@ impl Tool for TreeTool {
         )
         .await?;
 
-        tree.push_str(&format!(
-            "\n{} dossier(s), {} fichier(s)",
-            dir_count, file_count
-        ));
+        tree.push_str(&tool_text!("tree.totals", dir_count, file_count));
 
         Ok(ToolResult {
             success: true,
@@ -387,10 +399,7 @@ impl Tool for TreeTool {
                 "files": file_count,
                 "directories": dir_count
             }),
-            message: format!(
-                "Arborescence: {} dossier(s), {} fichier(s)",
-                dir_count, file_count
-            ),
+            message: tool_text!("tree.done", dir_count, file_count),
         })
     }
 }
@@ -470,6 +479,254 @@ fn build_tree<'a>(
     })
 }
 
+// ============================================================================
+// ProjectOverviewTool - Compact, annotated project tree
+// ============================================================================
+
+/// Files walked at most for the totals; past it they are partial
+const OVERVIEW_MAX_FILES: usize = 20_000;
+
+/// Larger files are sized but not read for their line count
+const OVERVIEW_MAX_COUNTED_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Directories left out of the overview, ignore files or not
+const OVERVIEW_SKIPPED_DIRS: &[&str] = &["node_modules", "target", "__pycache__", ".git"];
+
+struct OverviewOptions {
+    max_depth: usize,
+    max_entries: usize,
+    max_children: usize,
+    show_hidden: bool,
+}
+
+/// Tree text and totals of a walk
+#[derive(Debug, Default)]
+struct Overview {
+    tree: String,
+    /// Lines of `tree` so far, held under `max_entries`
+    entries: usize,
+    files: usize,
+    directories: usize,
+    bytes: u64,
+    lines: usize,
+    /// Extension -> (files, lines), sorted so identical calls give identical output
+    by_extension: BTreeMap<String, (usize, usize)>,
+    /// `max_entries` cut the tree
+    truncated: bool,
+    /// `OVERVIEW_MAX_FILES` cut the totals
+    partial: bool,
+}
+
+/// Line count of a text file, `None` for binary or unreadable ones
+fn count_lines(path: &Path, size: u64) -> Option<usize> {
+    if size > OVERVIEW_MAX_COUNTED_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+    if bytes[..bytes.len().min(8192)].contains(&0) {
+        return None;
+    }
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    Some(newlines + usize::from(bytes.last().is_some_and(|&b| b != b'\n')))
+}
+
+/// Walks `dir` for the totals, and renders it while `prefix` is set
+fn walk_overview(
+    dir: &Path,
+    ignore: &IgnoreRules,
+    depth: usize,
+    prefix: Option<&str>,
+    options: &OverviewOptions,
+    out: &mut Overview,
+) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<(String, PathBuf, bool)> = read_dir
+        .flatten()
+        .map(|entry| {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            (entry.file_name().to_string_lossy().to_string(), entry.path(), is_dir)
+        })
+        .filter(|(name, path, is_dir)| {
+            (options.show_hidden || !name.starts_with('.'))
+                && !(*is_dir && OVERVIEW_SKIPPED_DIRS.contains(&name.as_str()))
+                && !ignore.is_ignored(path, *is_dir)
+        })
+        .collect();
+    // Directories first, then by name
+    entries.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+    let prefix = prefix.filter(|_| depth < options.max_depth);
+    let collapsed = entries.len().saturating_sub(options.max_children);
+    for (i, (name, path, is_dir)) in entries.iter().enumerate() {
+        if out.files >= OVERVIEW_MAX_FILES {
+            out.partial = true;
+            return;
+        }
+        let mut shown = prefix.filter(|_| i < options.max_children);
+        if shown.is_some() && out.entries >= options.max_entries {
+            out.truncated = true;
+            shown = None;
+        }
+        let is_last = collapsed == 0 && i + 1 == entries.len();
+        let connector = if is_last { "└── " } else { "├── " };
+
+        if *is_dir {
+            out.directories += 1;
+            let child_prefix = shown.map(|p| {
+                out.entries += 1;
+                out.tree.push_str(&format!("{}{}{}/\n", p, connector, name));
+                format!("{}{}", p, if is_last { "    " } else { "│   " })
+            });
+            walk_overview(path, &ignore.child(path), depth + 1, child_prefix.as_deref(), options, out);
+        } else {
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            let lines = count_lines(path, size);
+            out.files += 1;
+            out.bytes += size;
+            if let Some(lines) = lines {
+                out.lines += lines;
+                let ext = path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or_else(|| "(sans extension)".to_string());
+                let totals = out.by_extension.entry(ext).or_default();
+                totals.0 += 1;
+                totals.1 += lines;
+            }
+            if let Some(p) = shown {
+                out.entries += 1;
+                let annotation = match lines {
+                    Some(lines) => tool_text!("project_overview.text_file", format_size(size), lines),
+                    None => tool_text!("project_overview.binary_file", format_size(size)),
+                };
+                out.tree.push_str(&format!("{}{}{} ({})\n", p, connector, name, annotation));
+            }
+        }
+    }
+    if let Some(p) = prefix {
+        if collapsed > 0 && out.entries < options.max_entries {
+            out.entries += 1;
+            out.tree.push_str(&format!("{}└── … +{} more\n", p, collapsed));
+        }
+    }
+}
+
+pub struct ProjectOverviewTool;
+
+#[async_trait]
+impl Tool for ProjectOverviewTool {
+    fn name(&self) -> &str {
+        "project_overview"
+    }
+
+    fn description(&self) -> &str {
+        "Compact overview of a project: a tree annotated with each file's size and line count, large directories collapsed, plus totals (files, lines of code by extension). Skips .gitignore'd paths. Cheaper than tree + file_info for getting oriented."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Root directory (default: current dir)",
+                    "default": "."
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "Maximum depth shown in the tree (default: 3); totals cover everything",
+                    "default": 3
+                },
+                "max_entries": {
+                    "type": "integer",
+                    "description": "Maximum lines of tree (default: 200)",
+                    "default": 200
+                },
+                "max_children": {
+                    "type": "integer",
+                    "description": "Directories with more entries show this many, then '… +k more' (default: 25)",
+                    "default": 25
+                },
+                "show_hidden": {
+                    "type": "boolean",
+                    "description": "Show hidden files/directories",
+                    "default": false
+                },
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Skip paths ignored by .gitignore/.ignore files (default: true)",
+                    "default": true
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = params["path"].as_str().unwrap_or(".");
+        let options = OverviewOptions {
+            max_depth: params["max_depth"].as_u64().unwrap_or(3) as usize,
+            max_entries: params["max_entries"].as_u64().unwrap_or(200) as usize,
+            max_children: params["max_children"].as_u64().unwrap_or(25).max(1) as usize,
+            show_hidden: params["show_hidden"].as_bool().unwrap_or(false),
+        };
+        let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+
+        let root = resolve_and_validate_path(path)?;
+        if !root.is_dir() {
+            return Err(ToolError::NotFound {
+                path: root.display().to_string(),
+                message: "Not a directory".to_string(),
+            });
+        }
+
+        let walk_root = root.clone();
+        let out = tokio::task::spawn_blocking(move || {
+            let mut out = Overview::default();
+            let ignore = IgnoreRules::for_root(&walk_root, respect_gitignore);
+            walk_overview(&walk_root, &ignore, 0, Some(""), &options, &mut out);
+            out
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Overview task failed: {}", e)))?;
+
+        let mut tree = format!("{}/\n{}", path.trim_end_matches('/'), out.tree);
+        if out.truncated {
+            tree.push_str(tool_text!("project_overview.truncated"));
+        }
+        let by_extension: serde_json::Map<String, Value> = out
+            .by_extension
+            .iter()
+            .map(|(ext, (files, lines))| (ext.clone(), serde_json::json!({ "files": files, "lines": lines })))
+            .collect();
+        let summary = tool_text!(
+            "project_overview.totals",
+            out.directories,
+            out.files,
+            format_size(out.bytes),
+            out.lines,
+            if out.partial { tool_text!("project_overview.partial") } else { "" }
+        );
+        tree.push_str(&format!("\n{}", summary));
+
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "tree": tree,
+                "files": out.files,
+                "directories": out.directories,
+                "total_bytes": out.bytes,
+                "total_lines": out.lines,
+                "lines_by_extension": by_extension,
+                "truncated": out.truncated,
+                "partial_totals": out.partial
+            }),
+            message: tool_text!("project_overview.done", summary),
+        })
+    }
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -507,3 +764,59 @@ async fn get_disk_info() -> Value {
         Err(_) => serde_json::json!({ "info": "unavailable" }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tree_stays_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        let tool = TreeTool::confined_to(root.clone());
+
+        let result = tool.execute(serde_json::json!({"path": "."})).await.unwrap();
+        assert!(result.data["tree"].as_str().unwrap().contains("src"));
+
+        let outside = root.parent().unwrap().to_string_lossy().to_string();
+        for path in ["../", outside.as_str()] {
+            let err = tool.execute(serde_json::json!({"path": path})).await.unwrap_err();
+            assert!(matches!(err, ToolError::PermissionDenied { rule: Some(ref rule), .. } if rule == "workspace"), "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_project_overview_annotates_and_collapses() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/gen")).unwrap();
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        std::fs::write(root.join("build/out.js"), "x\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    run();\n}").unwrap();
+        std::fs::write(root.join("logo.png"), [0x89, b'P', b'N', b'G', 0, 0, 1]).unwrap();
+        for i in 0..12 {
+            std::fs::write(root.join(format!("src/gen/part{:02}.rs", i)), "// generated\n").unwrap();
+        }
+
+        let tool = ProjectOverviewTool;
+        let params = serde_json::json!({"path": root, "max_children": 5});
+        let result = tool.execute(params.clone()).await.unwrap();
+        let tree = result.data["tree"].as_str().unwrap();
+        assert!(tree.contains("main.rs (24 B, 3 lines)"), "{}", tree);
+        assert!(tree.contains("logo.png (7 B, not text)"), "{}", tree);
+        assert!(tree.contains("part04.rs") && !tree.contains("part05.rs"), "{}", tree);
+        assert!(tree.contains("└── … +7 more"), "{}", tree);
+        assert!(!tree.contains("build"), "{}", tree);
+
+        // Totals include the collapsed files
+        assert_eq!(result.data["files"], 14);
+        assert_eq!(result.data["lines_by_extension"]["rs"]["files"], 13);
+        assert_eq!(result.data["lines_by_extension"]["rs"]["lines"], 15);
+
+        // Identical calls give identical output
+        let again = tool.execute(params).await.unwrap();
+        assert_eq!(again.data, result.data);
+    }
+}
@@ -0,0 +1,263 @@
+//! Sub-agent tool
+//!
+//! `agent_task` hands a self-contained job to a sub-agent: a fresh
+//! `AgentContext`, a restricted copy of the tool registry and a bounded loop
+//! against the same engine. Only the sub-agent's final summary comes back, so
+//! long explorations stay out of the parent's context.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::agent::{call_preview, get_call_permission};
+use crate::agent::loop_runner::{AgentContext, AnchorReason, ToolHistoryEntry, PERMISSION_DENIED_ERROR};
+use crate::agent::permissions::{PermissionDecision, PermissionManager, PermissionRequest, PermissionResult};
+use crate::agent::prompts::{build_agent_system_prompt, ThinkingGuidance};
+use crate::agent::runner::{extract_tool_call, format_tool_result};
+use crate::agent::tools::filesystem::preview_change;
+use crate::agent::tools::schema::validate_tool_params;
+use crate::agent::tools::{Tool, ToolError, ToolRegistry, ToolResult};
+use crate::inference::engine::{GenerationParams, LlamaEngine};
+use crate::inference::streaming::collect_stream_text;
+use crate::storage::settings::{ToolCallFormat, ToolResultVerbosity};
+use crate::types::message::{Message, Role};
+
+/// Name of the sub-agent tool, never offered to a sub-agent itself
+pub const AGENT_TASK_TOOL: &str = "agent_task";
+
+/// Iterations a sub-agent gets before it must summarize
+const MAX_ITERATIONS: usize = 8;
+
+/// Whole sub-agent run, summary included
+const TASK_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Single tool call inside the sub-agent
+const TOOL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long a sub-agent tool call waits for the user's permission
+const PERMISSION_TIMEOUT: Duration = Duration::from_secs(120);
+
+const SUBAGENT_PROMPT: &str = "You are a sub-agent working on one task for another agent. \
+Use the tools to investigate, then answer with a concise summary of your findings: \
+facts, file paths, values and conclusions the other agent needs. It only sees that summary.";
+
+const SUMMARY_REQUEST: &str = "Stop using tools now. Reply with the final summary of your findings.";
+
+pub struct AgentTaskTool {
+    engine: Arc<Mutex<LlamaEngine>>,
+    registry: Arc<ToolRegistry>,
+    permissions: Arc<PermissionManager>,
+    stop_signal: Arc<AtomicBool>,
+    params: GenerationParams,
+}
+
+impl AgentTaskTool {
+    /// `params` are the generation settings the sub-agent runs with;
+    /// `stop_signal` is the chat's Stop button.
+    pub fn new(
+        engine: Arc<Mutex<LlamaEngine>>,
+        registry: Arc<ToolRegistry>,
+        permissions: Arc<PermissionManager>,
+        stop_signal: Arc<AtomicBool>,
+        params: GenerationParams,
+    ) -> Self {
+        Self { engine, registry, permissions, stop_signal, params }
+    }
+
+    /// Tools the sub-agent may use: the allowed ones, never `agent_task`
+    fn restricted_registry(&self, allowed: Option<&[String]>) -> ToolRegistry {
+        self.registry.subset(|name| {
+            name != AGENT_TASK_TOOL
+                && match allowed {
+                    Some(allowed) => allowed.iter().any(|a| a == name),
+                    None => true,
+                }
+        })
+    }
+
+    async fn generate(&self, messages: &[Message]) -> Result<String, ToolError> {
+        let stream = self
+            .engine
+            .lock()
+            .await
+            .generate_stream_messages(messages.to_vec(), self.params.clone());
+        let (rx, stop) = stream.map_err(|e| ToolError::ExecutionFailed(format!("Sub-agent generation failed: {}", e)))?;
+        let (text, _) = collect_stream_text(rx, &stop, &self.stop_signal).await;
+        Ok(text)
+    }
+
+    /// Ask the permission manager, waiting for the user when it has to
+    async fn permitted(&self, tool_name: &str, params: &Value) -> bool {
+        let request = PermissionRequest {
+            id: Uuid::new_v4(),
+            tool_name: tool_name.to_string(),
+            operation: "execute".to_string(),
+            target: params.to_string(),
+            level: get_call_permission(tool_name, params),
+            params: params.clone(),
+            timestamp: Utc::now(),
+            preview: match preview_change(tool_name, params).await {
+                Some(preview) => Some(preview.diff),
+                None => call_preview(tool_name, params).await,
+            },
+        };
+        match self.permissions.request_permission(request.clone()).await {
+            PermissionResult::Approved => true,
+            PermissionResult::Denied => false,
+            PermissionResult::Pending => matches!(
+                self.permissions.wait_for_decision(request.id, PERMISSION_TIMEOUT).await,
+                Some(PermissionDecision::Approved)
+            ),
+        }
+    }
+
+    async fn run(&self, prompt: &str, registry: &ToolRegistry, cancel: &CancellationToken) -> Result<String, ToolError> {
+        let tools = registry.list_tools();
+        let mut ctx = AgentContext::new();
+        ctx.add_anchor(prompt.to_string(), AnchorReason::Goal);
+        let mut history = vec![Message::new(Role::User, prompt)];
+
+        while ctx.iteration < MAX_ITERATIONS {
+            ctx.iteration += 1;
+            if self.stop_signal.load(Ordering::Relaxed) {
+                return Err(ToolError::ExecutionFailed("Sub-agent stopped by the user".to_string()));
+            }
+
+            let system = build_agent_system_prompt(SUBAGENT_PROMPT, &tools, Some(&ctx), None, None, None, None, ThinkingGuidance::Prompted, ToolCallFormat::Json);
+            let mut prompt_messages = vec![Message::system_prompt(system)];
+            prompt_messages.extend(history.iter().cloned());
+            let reply = self.generate(&prompt_messages).await?;
+
+            let Some(call) = extract_tool_call(&reply) else {
+                return Ok(reply);
+            };
+            tracing::info!("Sub-agent iteration {}: {}", ctx.iteration, call.tool);
+            history.push(Message::new(Role::Assistant, reply));
+
+            let Some(tool) = registry.get(&call.tool) else {
+                let available: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+                history.push(Message::new(
+                    Role::System,
+                    format!("Tool `{}` is not available here. Available tools: {}", call.tool, available.join(", ")),
+                ));
+                continue;
+            };
+
+            if !self.permitted(&call.tool, &call.params).await {
+                ctx.tool_history.push(ToolHistoryEntry {
+                    tool_name: call.tool.clone(),
+                    params: call.params.clone(),
+                    result: None,
+                    error: Some(PERMISSION_DENIED_ERROR.to_string()),
+                    timestamp: Utc::now().timestamp() as u64,
+                    duration_ms: 0,
+                });
+                history.push(Message::new(
+                    Role::System,
+                    format!("The user refused `{}`. Continue without it.", call.tool),
+                ));
+                continue;
+            }
+
+            let start = std::time::Instant::now();
+            let outcome = match validate_tool_params(tool.as_ref(), &call.params) {
+                Err(e) => Err(e),
+                Ok(()) => match tokio::time::timeout(TOOL_TIMEOUT, registry.execute_tool(tool.as_ref(), call.params.clone(), cancel.clone())).await {
+                    Ok(result) => result,
+                    Err(_) => Err(ToolError::Timeout { after_secs: Some(TOOL_TIMEOUT.as_secs()) }),
+                },
+            };
+            let duration_ms = start.elapsed().as_millis() as u64;
+
+            let (result, error, feedback) = match outcome {
+                Ok(result) => {
+                    let feedback = format_tool_result(&call.tool, &result, ToolResultVerbosity::default());
+                    (Some(result), None, feedback)
+                }
+                Err(ToolError::Cancelled) => return Err(ToolError::Cancelled),
+                Err(e) => (None, Some(e.to_string()), e.render_for_model(&call.tool)),
+            };
+            ctx.tool_history.push(ToolHistoryEntry {
+                tool_name: call.tool.clone(),
+                params: call.params.clone(),
+                result,
+                error,
+                timestamp: Utc::now().timestamp() as u64,
+                duration_ms,
+            });
+            history.push(Message::new(Role::System, feedback));
+        }
+
+        // Out of iterations: one last generation, without tools
+        history.push(Message::new(Role::System, SUMMARY_REQUEST));
+        let mut prompt_messages = vec![Message::system_prompt(SUBAGENT_PROMPT)];
+        prompt_messages.extend(history);
+        self.generate(&prompt_messages).await
+    }
+}
+
+#[async_trait]
+impl Tool for AgentTaskTool {
+    fn name(&self) -> &str {
+        AGENT_TASK_TOOL
+    }
+
+    fn description(&self) -> &str {
+        "Delegate a self-contained task (exploring a codebase, researching a question) to a sub-agent with its own context. It can use tools for up to 8 steps and returns only a summary of its findings, keeping long explorations out of this conversation."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "prompt": {
+                    "type": "string",
+                    "description": "The task, with all the context the sub-agent needs: it does not see this conversation"
+                },
+                "allowed_tools": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional: names of the tools the sub-agent may use (default: all)"
+                }
+            },
+            "required": ["prompt"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_cancellable(params, CancellationToken::new()).await
+    }
+
+    /// Cancelling stops the sub-agent's tool call in flight as well
+    async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
+        let prompt = params["prompt"]
+            .as_str()
+            .filter(|p| !p.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("prompt is required".to_string()))?;
+        let allowed: Option<Vec<String>> = params["allowed_tools"]
+            .as_array()
+            .map(|names| names.iter().filter_map(|n| n.as_str().map(str::to_string)).collect());
+        let registry = self.restricted_registry(allowed.as_deref());
+
+        let summary = tokio::time::timeout(TASK_TIMEOUT, self.run(prompt, &registry, &cancel))
+            .await
+            .map_err(|_| ToolError::Timeout { after_secs: Some(TASK_TIMEOUT.as_secs()) })??;
+        let summary = summary.trim().to_string();
+        if summary.is_empty() {
+            return Err(ToolError::ExecutionFailed("The sub-agent returned no summary".to_string()));
+        }
+
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({ "tools": registry.list_tools().len() }),
+            message: summary,
+        })
+    }
+}
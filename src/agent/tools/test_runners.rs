@@ -0,0 +1,800 @@
+//! Test runner tools - pytest and npm test with failure extraction
+//!
+//! pytest runs with `-q --tb=short -rfE`: its summary line gives the counts
+//! and the short tracebacks give each failure's file, line and assertion.
+//! `npm test` asks jest or vitest for a JSON report when the test script uses
+//! one of them, and falls back to mocha-style text otherwise. A missing
+//! runner is reported by executable name so it can be installed.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_and_validate_path;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+
+const DEFAULT_MAX_FAILURES: u64 = 10;
+const MAX_FAILURES: u64 = 50;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// Bytes of a failure's assertion message and traceback excerpt
+const MAX_MESSAGE_BYTES: usize = 500;
+const MAX_TRACEBACK_BYTES: usize = 1200;
+
+/// End of the output shown when nothing could be parsed from it
+const OUTPUT_TAIL_BYTES: usize = 2000;
+
+/// One failing test
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct TestFailure {
+    name: String,
+    file: Option<String>,
+    line: Option<u64>,
+    message: String,
+    traceback: String,
+}
+
+impl TestFailure {
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "name": self.name,
+            "file": self.file,
+            "line": self.line,
+            "message": cut(&self.message, MAX_MESSAGE_BYTES),
+            "traceback": cut(&self.traceback, MAX_TRACEBACK_BYTES),
+        })
+    }
+}
+
+/// Counts and failures of a run
+#[derive(Debug, Default)]
+struct TestReport {
+    passed: u64,
+    failed: u64,
+    skipped: u64,
+    errors: u64,
+    /// False when no summary was found in the output
+    parsed: bool,
+    failures: Vec<TestFailure>,
+}
+
+impl TestReport {
+    fn to_json(&self, success: bool, output: &str, params: &Value) -> Value {
+        let max = params["max_failures"]
+            .as_u64()
+            .unwrap_or(DEFAULT_MAX_FAILURES)
+            .min(MAX_FAILURES) as usize;
+        let mut data = serde_json::json!({
+            "success": success,
+            "passed": self.passed,
+            "failed": self.failed,
+            "skipped": self.skipped,
+            "errors": self.errors,
+            "failures_total": self.failures.len(),
+            "failures": self.failures.iter().take(max).map(TestFailure::to_json).collect::<Vec<_>>(),
+        });
+        if self.failures.len() > max {
+            data["failures_truncated"] = true.into();
+        }
+        if !self.parsed || (!success && self.failures.is_empty()) {
+            data["output_tail"] = tail(output, OUTPUT_TAIL_BYTES).trim().into();
+        }
+        data
+    }
+
+    fn message(&self, runner: &str) -> String {
+        if !self.parsed {
+            return tool_text!("tests.unparsed", runner);
+        }
+        let mut message = tool_text!(
+            "tests.done",
+            runner,
+            self.passed,
+            self.failed,
+            self.skipped
+        );
+        if self.errors > 0 {
+            message.push_str(&tool_text!("tests.errors", self.errors));
+        }
+        message
+    }
+}
+
+fn cut(text: &str, max: usize) -> String {
+    let text = text.trim_end();
+    let shown = crate::truncate_str(text, max);
+    if shown.len() < text.len() {
+        format!("{}\n[...]", shown.trim_end())
+    } else {
+        text.to_string()
+    }
+}
+
+fn tail(text: &str, max: usize) -> &str {
+    let start = text.len().saturating_sub(max);
+    let start = (start..=text.len())
+        .find(|i| text.is_char_boundary(*i))
+        .unwrap_or(text.len());
+    &text[start..]
+}
+
+/// Executable a shell reported missing (`sh: 1: jest: not found`,
+/// `jest: command not found`, `'jest' is not recognized...`)
+fn missing_executable(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let line = line.trim();
+        if let Some(rest) = line.strip_suffix(": not found") {
+            rest.rsplit(": ").next().map(str::to_string)
+        } else if let Some(rest) = line.strip_suffix(": command not found") {
+            rest.rsplit(": ").next().map(str::to_string)
+        } else if let Some(rest) = line.strip_prefix("'") {
+            rest.split_once("' is not recognized")
+                .map(|(exe, _)| exe.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+fn not_found(executable: &str, hint: &str) -> ToolError {
+    ToolError::ExecutionFailed(format!("executable '{}' not found: {}", executable, hint))
+}
+
+/// Project folder the runner starts in, checked against the workspace
+/// sandbox since tests run project code
+fn working_dir(params: &Value) -> Result<PathBuf, ToolError> {
+    resolve_and_validate_path(params["working_dir"].as_str().unwrap_or("."))
+}
+
+/// Run a test runner in `dir`; its combined stdout and stderr, and whether it passed
+async fn run(
+    mut cmd: Command,
+    dir: &Path,
+    executable: &str,
+    hint: &str,
+    params: &Value,
+) -> Result<(String, String, bool), ToolError> {
+    let timeout_secs = params["timeout_secs"]
+        .as_u64()
+        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+    cmd.current_dir(dir);
+    cmd.env("NO_COLOR", "1").env("CI", "1").kill_on_drop(true);
+
+    let output = timeout(Duration::from_secs(timeout_secs), cmd.output())
+        .await
+        .map_err(|_| ToolError::Timeout {
+            after_secs: Some(timeout_secs),
+        })?
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => not_found(executable, hint),
+            _ => ToolError::ExecutionFailed(tool_text!("tests.run_error", executable, e)),
+        })?;
+    Ok((
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.success(),
+    ))
+}
+
+fn string_list(params: &Value, key: &str) -> Vec<String> {
+    match &params[key] {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|i| i.as_str())
+            .map(str::to_string)
+            .collect(),
+        Value::String(item) => vec![item.clone()],
+        _ => Vec::new(),
+    }
+}
+
+fn common_properties() -> Value {
+    serde_json::json!({
+        "working_dir": {
+            "type": "string",
+            "description": "Project folder (default: current directory)"
+        },
+        "max_failures": {
+            "type": "integer",
+            "description": "Failures returned (default: 10, max: 50)",
+            "default": 10
+        },
+        "timeout_secs": {
+            "type": "integer",
+            "description": "Timeout in seconds (default: 600)",
+            "default": 600
+        }
+    })
+}
+
+// ============================================================================
+// pytest
+// ============================================================================
+
+/// Python to run pytest with: the `python` param, the project's virtualenv,
+/// then the usual names on PATH
+fn python_candidates(params: &Value, dir: &Path) -> Vec<String> {
+    if let Some(python) = params["python"].as_str() {
+        return vec![python.to_string()];
+    }
+    let venv_python = if cfg!(windows) {
+        "Scripts/python.exe"
+    } else {
+        "bin/python"
+    };
+    let mut candidates: Vec<String> = [".venv", "venv"]
+        .iter()
+        .map(|venv| dir.join(venv).join(venv_python))
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    let system: &[&str] = if cfg!(windows) {
+        &["python", "py"]
+    } else {
+        &["python3", "python"]
+    };
+    candidates.extend(system.iter().map(|p| p.to_string()));
+    candidates
+}
+
+fn parse_pytest(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    // Short tracebacks by header (`test_name`, `TestClass.test_name`)
+    let mut tracebacks: Vec<(String, String)> = Vec::new();
+    let mut section = "";
+
+    for line in output.lines() {
+        if line.starts_with("====") || (line.starts_with('=') && line.ends_with('=')) {
+            let title = line.trim_matches(|c| c == '=' || c == ' ');
+            section = match title {
+                "FAILURES" => "failures",
+                "ERRORS" => "errors",
+                "short test summary info" => "summary",
+                _ => "",
+            };
+            // A summary line wrapped in `===` without -q
+            if section.is_empty() {
+                parse_pytest_counts(title, &mut report);
+            }
+            continue;
+        }
+        if matches!(section, "failures" | "errors") {
+            if line.starts_with("___") && line.ends_with("___") {
+                let header = line.trim_matches(|c| c == '_' || c == ' ');
+                tracebacks.push((header.to_string(), String::new()));
+            } else if let Some((_, text)) = tracebacks.last_mut() {
+                text.push_str(line);
+                text.push('\n');
+            }
+            continue;
+        }
+        if let Some(rest) = line
+            .strip_prefix("FAILED ")
+            .or_else(|| line.strip_prefix("ERROR "))
+        {
+            let (node, message) = rest.split_once(" - ").unwrap_or((rest, ""));
+            let (file, name) = match node.split_once("::") {
+                Some((file, name)) => (Some(file.to_string()), name.to_string()),
+                None => (Some(node.to_string()), node.to_string()),
+            };
+            report.failures.push(TestFailure {
+                name,
+                file,
+                message: message.to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        parse_pytest_counts(line, &mut report);
+    }
+
+    for failure in &mut report.failures {
+        // The header is the node id's tail, `::` turned to `.`
+        let dotted = failure.name.replace("::", ".");
+        let block = tracebacks.iter().find(|(header, _)| {
+            *header == dotted
+                || dotted.ends_with(&format!(".{}", header))
+                || header.ends_with(&failure.name)
+        });
+        if let Some((_, text)) = block {
+            failure.traceback = text.trim().to_string();
+            // Last `file:line:` of the test's own file is where it failed
+            let file = failure.file.as_deref().unwrap_or_default();
+            failure.line = text
+                .lines()
+                .rev()
+                .find_map(|l| {
+                    l.strip_prefix(file)?
+                        .strip_prefix(':')?
+                        .split(':')
+                        .next()?
+                        .parse()
+                        .ok()
+                });
+            if failure.message.is_empty() {
+                failure.message = text
+                    .lines()
+                    .filter_map(|l| l.strip_prefix("E "))
+                    .map(str::trim)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        }
+    }
+    report
+}
+
+/// `1 failed, 2 passed, 1 skipped in 0.05s`
+fn parse_pytest_counts(line: &str, report: &mut TestReport) {
+    let Some((counts, duration)) = line.rsplit_once(" in ") else {
+        return;
+    };
+    let starts_with_digit = |s: &str| s.trim_start().starts_with(|c: char| c.is_ascii_digit());
+    if !starts_with_digit(duration) || !starts_with_digit(counts) {
+        return;
+    }
+    let mut any = false;
+    for part in counts.split(", ") {
+        let Some((n, kind)) = part.split_once(' ') else {
+            continue;
+        };
+        let Ok(n) = n.parse::<u64>() else { continue };
+        any = true;
+        match kind {
+            "passed" | "xpassed" => report.passed += n,
+            "failed" => report.failed += n,
+            "skipped" | "xfailed" | "deselected" => report.skipped += n,
+            "error" | "errors" => report.errors += n,
+            _ => {}
+        }
+    }
+    report.parsed |= any;
+}
+
+pub struct PytestRunTool;
+
+#[async_trait]
+impl Tool for PytestRunTool {
+    fn name(&self) -> &str {
+        "pytest_run"
+    }
+
+    fn description(&self) -> &str {
+        "Run pytest and get pass/fail counts plus each failure's test name, file, line, assertion message and a short traceback. Uses the project's .venv when there is one."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let mut properties = common_properties();
+        properties["paths"] = serde_json::json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "description": "Test files, folders or node ids (default: pytest's discovery)"
+        });
+        properties["keyword"] = serde_json::json!({
+            "type": "string",
+            "description": "Only run tests matching this expression (-k)"
+        });
+        properties["python"] = serde_json::json!({
+            "type": "string",
+            "description": "Python interpreter to use (default: .venv, then python3/python)"
+        });
+        serde_json::json!({ "type": "object", "properties": properties })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let mut args: Vec<String> = [
+            "-m",
+            "pytest",
+            "-q",
+            "--tb=short",
+            "-rfE",
+            "-p",
+            "no:cacheprovider",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+        if let Some(keyword) = params["keyword"].as_str() {
+            args.extend(["-k".to_string(), keyword.to_string()]);
+        }
+        args.extend(string_list(&params, "paths"));
+
+        let dir = working_dir(&params)?;
+        let candidates = python_candidates(&params, &dir);
+        let hint = "install Python 3 or pass its path in 'python'";
+        let mut result = None;
+        for python in &candidates {
+            let mut cmd = Command::new(python);
+            cmd.args(&args);
+            match run(cmd, &dir, python, hint, &params).await {
+                Ok(output) => {
+                    result = Some((python, output));
+                    break;
+                }
+                // Missing: try the next name
+                Err(ToolError::ExecutionFailed(_)) if candidates.len() > 1 => {}
+                Err(e) => return Err(e),
+            }
+        }
+        let Some((python, (stdout, stderr, success))) = result else {
+            return Err(not_found(&candidates.join("' / '"), hint));
+        };
+        if stderr.contains("No module named pytest") {
+            return Err(not_found(
+                "pytest",
+                &format!(
+                    "not installed for '{}', run `{} -m pip install pytest`",
+                    python, python
+                ),
+            ));
+        }
+
+        let output = format!("{}{}", stdout, stderr);
+        let report = parse_pytest(&output);
+        let data = report.to_json(success, &output, &params);
+        Ok(ToolResult {
+            success,
+            message: report.message("pytest"),
+            data,
+        })
+    }
+}
+
+// ============================================================================
+// npm test
+// ============================================================================
+
+/// Test framework named by the `test` script of package.json
+fn test_framework(dir: &Path) -> Option<&'static str> {
+    let manifest: Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("package.json")).ok()?).ok()?;
+    let script = manifest["scripts"]["test"].as_str()?;
+    // The last command of the script, past `VAR=value` and npx
+    let first = script
+        .split("&&")
+        .last()?
+        .split_whitespace()
+        .find(|w| !w.contains('=') && *w != "npx")?;
+    match first.rsplit('/').next()? {
+        "jest" => Some("jest"),
+        "vitest" => Some("vitest"),
+        _ => None,
+    }
+}
+
+/// jest's `--json` report; vitest's JSON reporter has the same shape
+fn parse_jest_json(report: &Value) -> TestReport {
+    let count = |key: &str| report[key].as_u64().unwrap_or(0);
+    let mut parsed = TestReport {
+        passed: count("numPassedTests"),
+        failed: count("numFailedTests"),
+        skipped: count("numPendingTests") + count("numTodoTests"),
+        errors: count("numRuntimeErrorTestSuites"),
+        parsed: true,
+        failures: Vec::new(),
+    };
+
+    for suite in report["testResults"].as_array().into_iter().flatten() {
+        let file = suite["name"].as_str().unwrap_or_default();
+        let assertions = suite["assertionResults"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        // A suite that failed to load has a message and no assertions
+        if suite["status"] == "failed" && assertions.iter().all(|a| a["status"] != "failed") {
+            let message = strip_ansi(suite["message"].as_str().unwrap_or_default());
+            parsed.failures.push(TestFailure {
+                name: Path::new(file)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                file: Some(file.to_string()),
+                line: None,
+                message: message
+                    .lines()
+                    .find(|l| !l.trim().is_empty())
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+                traceback: message.trim().to_string(),
+            });
+        }
+        for assertion in assertions.iter().filter(|a| a["status"] == "failed") {
+            let text = strip_ansi(
+                &assertion["failureMessages"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|m| m.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            // The message runs until the first stack frame
+            let (message, stack): (Vec<&str>, Vec<&str>) = {
+                let at = text
+                    .lines()
+                    .position(|l| l.trim_start().starts_with("at "))
+                    .unwrap_or(text.lines().count());
+                (
+                    text.lines().take(at).collect(),
+                    text.lines().skip(at).take(5).collect(),
+                )
+            };
+            let line = assertion["location"]["line"]
+                .as_u64()
+                .or_else(|| stack_line(&stack, file));
+            parsed.failures.push(TestFailure {
+                name: assertion["fullName"]
+                    .as_str()
+                    .or(assertion["title"].as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                file: Some(file.to_string()),
+                line,
+                message: message.join("\n").trim().to_string(),
+                traceback: stack
+                    .iter()
+                    .map(|l| l.trim())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            });
+        }
+    }
+    parsed
+}
+
+/// Line of the first `at ... (file:line:col)` frame in `file`
+fn stack_line(stack: &[&str], file: &str) -> Option<u64> {
+    stack.iter().find_map(|frame| {
+        let start = frame.find(file)? + file.len();
+        frame[start..]
+            .strip_prefix(':')?
+            .split(':')
+            .next()?
+            .parse()
+            .ok()
+    })
+}
+
+/// mocha-style `N passing` / `N failing` / `N pending` and node's test
+/// runner `# pass N` / `# fail N`
+fn parse_test_text(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    for line in output.lines().map(str::trim) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (n, kind) = match words.as_slice() {
+            ["#", kind, n, ..] => (n.parse::<u64>(), *kind),
+            [n, kind, ..] => (n.parse::<u64>(), *kind),
+            _ => continue,
+        };
+        let Ok(n) = n else { continue };
+        match kind {
+            "passing" | "pass" => report.passed += n,
+            "failing" | "fail" => report.failed += n,
+            "pending" | "skipped" | "skip" | "todo" => report.skipped += n,
+            _ => continue,
+        }
+        report.parsed = true;
+    }
+    report
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequence: ESC [ params final-byte
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+pub struct NpmTestTool;
+
+#[async_trait]
+impl Tool for NpmTestTool {
+    fn name(&self) -> &str {
+        "npm_test"
+    }
+
+    fn description(&self) -> &str {
+        "Run `npm test` and get pass/fail counts plus each failure's test name, file, line, assertion message and stack excerpt. Reads jest/vitest JSON reports when the test script uses them."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let mut properties = common_properties();
+        properties["filter"] = serde_json::json!({
+            "type": "string",
+            "description": "Only run tests whose name matches this pattern (jest/vitest -t)"
+        });
+        properties["files"] = serde_json::json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "description": "Test files to run (jest/vitest)"
+        });
+        serde_json::json!({ "type": "object", "properties": properties })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let dir = working_dir(&params)?;
+        if !dir.join("package.json").is_file() {
+            return Err(ToolError::InvalidParameters(tool_text!(
+                "npm_test.no_package",
+                dir.display()
+            )));
+        }
+        let framework = test_framework(&dir);
+        let report_path =
+            std::env::temp_dir().join(format!("clawrs-test-report-{}.json", uuid::Uuid::new_v4()));
+
+        let npm = if cfg!(windows) { "npm.cmd" } else { "npm" };
+        let mut cmd = Command::new(npm);
+        cmd.args(["test", "--silent"]);
+        if let Some(framework) = framework {
+            cmd.arg("--");
+            if framework == "jest" {
+                cmd.arg("--json");
+            } else {
+                cmd.args(["--run", "--reporter=json"]);
+            }
+            cmd.arg(format!("--outputFile={}", report_path.display()));
+            if let Some(filter) = params["filter"].as_str() {
+                cmd.args(["-t", filter]);
+            }
+            cmd.args(string_list(&params, "files"));
+        }
+        let result = run(cmd, &dir, "npm", "install Node.js (https://nodejs.org)", &params).await;
+        let report_json = std::fs::read_to_string(&report_path).ok();
+        let _ = std::fs::remove_file(&report_path);
+        let (stdout, stderr, success) = result?;
+
+        let output = format!("{}{}", stdout, stderr);
+        let report = match report_json
+            .as_deref()
+            .and_then(|r| serde_json::from_str::<Value>(r).ok())
+        {
+            Some(json) => parse_jest_json(&json),
+            None => {
+                if !success {
+                    if let Some(executable) = missing_executable(&output) {
+                        return Err(not_found(
+                            &executable,
+                            "the test script needs it, run `npm install` in the project",
+                        ));
+                    }
+                }
+                parse_test_text(&output)
+            }
+        };
+        let mut data = report.to_json(success, &output, &params);
+        data["framework"] = framework.into();
+        Ok(ToolResult {
+            success,
+            message: report.message(framework.unwrap_or("npm test")),
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pytest() {
+        let output = "\
+F.s                                                                  [100%]
+=================================== FAILURES ===================================
+______________________________ TestMath.test_sub _______________________________
+tests/test_math.py:12: in test_sub
+    assert sub(1, 1) == 1
+E   assert 0 == 1
+E    +  where 0 = sub(1, 1)
+=========================== short test summary info ============================
+FAILED tests/test_math.py::TestMath::test_sub - assert 0 == 1
+1 failed, 1 passed, 1 skipped in 0.03s
+";
+        let report = parse_pytest(output);
+        assert!(report.parsed);
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 1, 1));
+        let failure = &report.failures[0];
+        assert_eq!(failure.name, "TestMath::test_sub");
+        assert_eq!(failure.file.as_deref(), Some("tests/test_math.py"));
+        assert_eq!(failure.line, Some(12));
+        assert_eq!(failure.message, "assert 0 == 1");
+        assert!(failure.traceback.contains("assert sub(1, 1) == 1"));
+    }
+
+    #[test]
+    fn test_parse_pytest_collection_error() {
+        let output = "\
+==================================== ERRORS ====================================
+____________________ ERROR collecting tests/test_broken.py _____________________
+tests/test_broken.py:1: in <module>
+    import missing
+E   ModuleNotFoundError: No module named 'missing'
+=========================== short test summary info ============================
+ERROR tests/test_broken.py
+1 error in 0.10s
+";
+        let report = parse_pytest(output);
+        assert_eq!(report.errors, 1);
+        assert_eq!(
+            report.failures[0].file.as_deref(),
+            Some("tests/test_broken.py")
+        );
+        assert_eq!(
+            report.failures[0].message,
+            "ModuleNotFoundError: No module named 'missing'"
+        );
+    }
+
+    #[test]
+    fn test_parse_jest_json() {
+        let json = serde_json::json!({
+            "numPassedTests": 3,
+            "numFailedTests": 1,
+            "numPendingTests": 0,
+            "testResults": [{
+                "name": "/app/src/sum.test.js",
+                "status": "failed",
+                "assertionResults": [
+                    { "fullName": "sum adds", "status": "passed", "failureMessages": [] },
+                    {
+                        "fullName": "sum subtracts",
+                        "status": "failed",
+                        "location": null,
+                        "failureMessages": ["\u{1b}[31mexpect(received).toBe(expected)\u{1b}[39m\n\nExpected: 1\nReceived: 0\n    at Object.<anonymous> (/app/src/sum.test.js:9:21)\n    at Promise.then.completed (/app/node_modules/jest-circus/build/utils.js:298:28)"]
+                    }
+                ]
+            }]
+        });
+        let report = parse_jest_json(&json);
+        assert_eq!((report.passed, report.failed), (3, 1));
+        let failure = &report.failures[0];
+        assert_eq!(failure.name, "sum subtracts");
+        assert_eq!(failure.line, Some(9));
+        assert!(failure
+            .message
+            .starts_with("expect(received).toBe(expected)"));
+        assert!(failure.traceback.starts_with("at Object.<anonymous>"));
+    }
+
+    #[test]
+    fn test_parse_text_and_missing_executable() {
+        let report = parse_test_text("  4 passing (12ms)\n  1 failing\n  2 pending\n");
+        assert_eq!((report.passed, report.failed, report.skipped), (4, 1, 2));
+        let report = parse_test_text("# tests 3\n# pass 2\n# fail 1\n");
+        assert_eq!((report.passed, report.failed), (2, 1));
+
+        assert_eq!(
+            missing_executable("> jest\n\nsh: 1: jest: not found\n").as_deref(),
+            Some("jest")
+        );
+        assert_eq!(
+            missing_executable("bash: mocha: command not found").as_deref(),
+            Some("mocha")
+        );
+        assert_eq!(
+            missing_executable("'vitest' is not recognized as an internal or external command,")
+                .as_deref(),
+            Some("vitest")
+        );
+    }
+}
@@ -1,17 +1,239 @@
-//! Web tools - HTTP fetch, URL content extraction
+//! Web tools - HTTP fetch, URL content extraction, arbitrary HTTP requests
 //!
 //! Provides web content fetching capabilities.
 
 use async_trait::async_trait;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
+use crate::agent::language::tool_text;
+use crate::agent::tools::filesystem::resolve_path_in;
+use crate::agent::tools::readability;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 
+// ============================================================================
+// FetchCache - Per-conversation cache for web_fetch
+// ============================================================================
+
+/// Client timeout of `web_fetch`
+const FETCH_TIMEOUT_SECS: u64 = 30;
+/// Client timeout of `web_download`
+const DOWNLOAD_TIMEOUT_SECS: u64 = 120;
+
+/// Redirects `web_read` follows before giving up
+const READ_MAX_REDIRECTS: usize = 5;
+/// Bytes of a page `web_read` downloads
+const READ_MAX_BYTES: usize = 5 * 1024 * 1024;
+/// Default `max_chars` of `web_read`
+const READ_DEFAULT_MAX_CHARS: usize = 20_000;
+
+/// Default time-to-live of a cached page
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Default total size cap of the cache (extracted content, in bytes)
+pub const DEFAULT_CACHE_MAX_BYTES: usize = 8 * 1024 * 1024;
+
+/// A fetched page, stored after content extraction and before truncation
+#[derive(Debug, Clone)]
+pub struct CachedPage {
+    pub status: u16,
+    pub content_type: String,
+    pub content: String,
+    pub headers: serde_json::Map<String, Value>,
+    stored_at: Instant,
+}
+
+impl CachedPage {
+    pub fn new(status: u16, content_type: String, content: String, headers: serde_json::Map<String, Value>) -> Self {
+        Self { status, content_type, content, headers, stored_at: Instant::now() }
+    }
+}
+
+/// Hit/miss counters of the fetch cache
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FetchCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+impl FetchCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+
+    /// Render the counters in the Prometheus text format, next to the engine metrics
+    pub fn render_prometheus(&self) -> String {
+        format!(
+            "# HELP clawrs_web_cache_hits_total web_fetch requests served from the cache.\n\
+             # TYPE clawrs_web_cache_hits_total counter\n\
+             clawrs_web_cache_hits_total {}\n\
+             # HELP clawrs_web_cache_misses_total web_fetch requests that went to the network.\n\
+             # TYPE clawrs_web_cache_misses_total counter\n\
+             clawrs_web_cache_misses_total {}\n\
+             # HELP clawrs_web_cache_entries Pages currently cached.\n\
+             # TYPE clawrs_web_cache_entries gauge\n\
+             clawrs_web_cache_entries {}\n",
+            self.hits, self.misses, self.entries
+        )
+    }
+}
+
+#[derive(Default)]
+struct FetchCacheInner {
+    /// Conversation the cached pages belong to
+    conversation_id: Option<String>,
+    pages: HashMap<String, CachedPage>,
+    bytes: usize,
+}
+
+/// In-memory cache of fetched pages, scoped to one conversation
+///
+/// Shared between the agent and the `web_fetch` tool. Binding it to another
+/// conversation drops every cached page.
+pub struct FetchCache {
+    inner: Mutex<FetchCacheInner>,
+    ttl: Duration,
+    max_bytes: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl FetchCache {
+    pub fn new(ttl: Duration, max_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(FetchCacheInner::default()),
+            ttl,
+            max_bytes,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache key for a request
+    pub fn key(method: &str, url: &str) -> String {
+        format!("{} {}", method.to_uppercase(), url)
+    }
+
+    /// Scope the cache to a conversation, dropping pages from the previous one
+    pub fn bind(&self, conversation_id: &str) {
+        let mut inner = self.inner.lock().expect("fetch cache mutex poisoned");
+        if inner.conversation_id.as_deref() != Some(conversation_id) {
+            if !inner.pages.is_empty() {
+                tracing::debug!("Dropping {} cached page(s) from previous conversation", inner.pages.len());
+            }
+            inner.pages.clear();
+            inner.bytes = 0;
+            inner.conversation_id = Some(conversation_id.to_string());
+        }
+    }
+
+    /// Drop every cached page
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().expect("fetch cache mutex poisoned");
+        inner.pages.clear();
+        inner.bytes = 0;
+    }
+
+    /// Look up a fresh page, counting the hit or miss
+    pub fn get(&self, key: &str) -> Option<CachedPage> {
+        let mut inner = self.inner.lock().expect("fetch cache mutex poisoned");
+        let expired = inner.pages.get(key).map(|p| p.stored_at.elapsed() > self.ttl);
+        let page = match expired {
+            Some(false) => inner.pages.get(key).cloned(),
+            Some(true) => {
+                if let Some(old) = inner.pages.remove(key) {
+                    inner.bytes -= old.content.len();
+                }
+                None
+            }
+            None => None,
+        };
+        drop(inner);
+
+        let counter = if page.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        page
+    }
+
+    /// Store a page, evicting the oldest ones to stay under the size cap
+    pub fn insert(&self, key: String, page: CachedPage) {
+        let size = page.content.len();
+        // A single page may use at most a quarter of the cache
+        if size > self.max_bytes / 4 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().expect("fetch cache mutex poisoned");
+        if let Some(old) = inner.pages.remove(&key) {
+            inner.bytes -= old.content.len();
+        }
+        while inner.bytes + size > self.max_bytes {
+            let oldest = inner
+                .pages
+                .iter()
+                .min_by_key(|(_, p)| p.stored_at)
+                .map(|(k, _)| k.clone());
+            match oldest.and_then(|k| inner.pages.remove(&k)) {
+                Some(evicted) => inner.bytes -= evicted.content.len(),
+                None => break,
+            }
+        }
+        inner.bytes += size;
+        inner.pages.insert(key, page);
+    }
+
+    pub fn stats(&self) -> FetchCacheStats {
+        let inner = self.inner.lock().expect("fetch cache mutex poisoned");
+        FetchCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: inner.pages.len(),
+            bytes: inner.bytes,
+        }
+    }
+}
+
+impl Default for FetchCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_TTL, DEFAULT_CACHE_MAX_BYTES)
+    }
+}
+
+/// Whether response headers forbid storing the page (`Cache-Control: no-store`)
+fn is_no_store(headers: &serde_json::Map<String, Value>) -> bool {
+    headers
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("cache-control"))
+        .filter_map(|(_, v)| v.as_str())
+        .any(|v| v.split(',').any(|d| d.trim().eq_ignore_ascii_case("no-store")))
+}
+
 // ============================================================================
 // WebFetchTool - Fetch URL content
 // ============================================================================
 
-pub struct WebFetchTool;
+pub struct WebFetchTool {
+    cache: Arc<FetchCache>,
+}
+
+impl WebFetchTool {
+    pub fn new(cache: Arc<FetchCache>) -> Self {
+        Self { cache }
+    }
+}
 
 #[async_trait]
 impl Tool for WebFetchTool {
@@ -49,6 +271,11 @@ impl Tool for WebFetchTool {
                     "type": "integer",
                     "description": "Maximum response length in characters (default: 50000)",
                     "default": 50000
+                },
+                "force_refresh": {
+                    "type": "boolean",
+                    "description": "Bypass the cache and fetch the URL again (default: false)",
+                    "default": false
                 }
             },
             "required": ["url"]
@@ -63,12 +290,26 @@ impl Tool for WebFetchTool {
         let headers = params["headers"].as_object();
         let body = params["body"].as_str();
         let max_length = params["max_length"].as_u64().unwrap_or(50000) as usize;
+        let force_refresh = params["force_refresh"].as_bool().unwrap_or(false);
+
+        // Only plain GETs are cached: custom headers or a body may change the response
+        let cache_key = (method.eq_ignore_ascii_case("GET") && headers.is_none() && body.is_none())
+            .then(|| FetchCache::key(method, url));
+
+        if let Some(ref key) = cache_key {
+            if !force_refresh {
+                if let Some(page) = self.cache.get(key) {
+                    tracing::debug!("web_fetch cache hit for {}", url);
+                    return Ok(fetch_result(url, method, page, max_length, true));
+                }
+            }
+        }
 
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
             .user_agent("clawRS/0.2.0")
             .build()
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le client HTTP: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("web.client_error", e)))?;
 
         let mut request = match method.to_uppercase().as_str() {
             "GET" => client.get(url),
@@ -77,7 +318,7 @@ impl Tool for WebFetchTool {
             "DELETE" => client.delete(url),
             "PATCH" => client.patch(url),
             "HEAD" => client.head(url),
-            _ => return Err(ToolError::InvalidParameters(format!("Méthode HTTP inconnue: {}", method))),
+            _ => return Err(ToolError::InvalidParameters(tool_text!("web.unknown_method", method))),
         };
 
         // Add headers
@@ -97,7 +338,7 @@ impl Tool for WebFetchTool {
         let response = request
             .send()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP: {}", e)))?;
+            .map_err(|e| ToolError::http(tool_text!("web.http_error", e), &e, FETCH_TIMEOUT_SECS))?;
 
         let status = response.status().as_u16();
         let content_type = response
@@ -120,7 +361,7 @@ impl Tool for WebFetchTool {
         let text = response
             .text()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire la réponse: {}", e)))?;
+            .map_err(|e| ToolError::http(tool_text!("web.response_error", e), &e, FETCH_TIMEOUT_SECS))?;
 
         // Process content based on type
         let processed = if content_type.contains("text/html") {
@@ -129,39 +370,289 @@ impl Tool for WebFetchTool {
             text
         };
 
-        // Truncate if needed (safe char-boundary slicing)
-        let display = if processed.len() > max_length {
-            let safe = crate::truncate_str(&processed, max_length);
-            format!(
-                "{}...\n\n[Truncated: {} chars out of {}]",
-                safe,
-                safe.len(),
-                processed.len()
-            )
+        let page = CachedPage::new(status, content_type, processed, response_headers);
+        if let Some(key) = cache_key {
+            if status < 400 && !is_no_store(&page.headers) {
+                self.cache.insert(key, page.clone());
+            }
+        }
+
+        Ok(fetch_result(url, method, page, max_length, false))
+    }
+}
+
+/// Build the web_fetch result, truncating the content to `max_length`
+fn fetch_result(url: &str, method: &str, page: CachedPage, max_length: usize, cached: bool) -> ToolResult {
+    // Truncate if needed (safe char-boundary slicing)
+    let display = if page.content.len() > max_length {
+        let safe = crate::truncate_str(&page.content, max_length);
+        format!(
+            "{}...\n\n[Truncated: {} chars out of {}]",
+            safe,
+            safe.len(),
+            page.content.len()
+        )
+    } else {
+        page.content
+    };
+
+    ToolResult {
+        success: page.status < 400,
+        data: serde_json::json!({
+            "url": url,
+            "status": page.status,
+            "content_type": page.content_type,
+            "content": display,
+            "headers": page.headers,
+            "content_length": display.len(),
+            "cached": cached
+        }),
+        message: format!(
+            "HTTP {} {} ({}, {} chars{})",
+            method,
+            page.status,
+            page.content_type,
+            display.len(),
+            if cached { ", cached" } else { "" }
+        ),
+    }
+}
+
+// ============================================================================
+// WebReadTool - Main content of a page as Markdown
+// ============================================================================
+
+pub struct WebReadTool;
+
+#[async_trait]
+impl Tool for WebReadTool {
+    fn name(&self) -> &str {
+        "web_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read a web page: extracts the main content (no menus, ads or footers) as Markdown with links kept, plus the title and author. Much shorter than web_fetch; prefer it for articles and documentation."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The page to read"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum Markdown length in characters (default: 20000)",
+                    "default": 20000
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Character to start from, to continue a truncated page (default: 0)",
+                    "default": 0
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_cancellable(params, CancellationToken::new()).await
+    }
+
+    async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
+        let url = params["url"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("url is required".into()))?;
+        let max_chars = params["max_chars"].as_u64().map_or(READ_DEFAULT_MAX_CHARS, |n| n as usize);
+        let offset = params["offset"].as_u64().unwrap_or(0) as usize;
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .redirect(reqwest::redirect::Policy::limited(READ_MAX_REDIRECTS))
+            .user_agent("clawRS/0.2.0")
+            .build()
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("web.client_error", e)))?;
+
+        let download = async {
+            let mut response = client
+                .get(url)
+                .header("Accept", "text/html,application/xhtml+xml,text/plain;q=0.9,*/*;q=0.5")
+                .send()
+                .await
+                .map_err(|e| {
+                    if e.is_redirect() {
+                        ToolError::Network {
+                            status: None,
+                            message: tool_text!("web.too_many_redirects", READ_MAX_REDIRECTS, url),
+                        }
+                    } else {
+                        ToolError::http(tool_text!("web.http_error", e), &e, FETCH_TIMEOUT_SECS)
+                    }
+                })?;
+            let mut bytes = Vec::new();
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| ToolError::http(tool_text!("web.response_error", e), &e, FETCH_TIMEOUT_SECS))?
+            {
+                bytes.extend_from_slice(&chunk);
+                if bytes.len() >= READ_MAX_BYTES {
+                    bytes.truncate(READ_MAX_BYTES);
+                    break;
+                }
+            }
+            Ok::<_, ToolError>((response, bytes))
+        };
+        let (response, bytes) = tokio::select! {
+            result = download => result?,
+            _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+        };
+
+        let status = response.status().as_u16();
+        let final_url = response.url().clone();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_lowercase();
+        let is_html = content_type.contains("html") || (content_type.is_empty() && looks_like_html(&bytes));
+        if !is_html && !content_type.starts_with("text/") && !content_type.contains("json") {
+            return Err(ToolError::InvalidParameters(tool_text!("web_read.not_a_page", final_url, content_type)));
+        }
+
+        let charset = detect_charset(&bytes, &content_type, is_html);
+        let text = decode_charset(&bytes, &charset);
+        let (title, byline, markdown) = if is_html {
+            let article = readability::extract(&text, Some(&final_url));
+            (article.title, article.byline, article.markdown)
         } else {
-            processed
+            (None, None, text)
         };
 
+        let total_chars = markdown.chars().count();
+        let content: String = markdown.chars().skip(offset).take(max_chars).collect();
+        let end = (offset + max_chars).min(total_chars);
+        let truncated = end < total_chars;
+
+        let mut data = serde_json::json!({
+            "url": url,
+            "final_url": final_url.as_str(),
+            "status": status,
+            "title": title,
+            "byline": byline,
+            "charset": charset,
+            "content": content,
+            "total_chars": total_chars,
+            "truncated": truncated,
+        });
+        if truncated {
+            data["next_offset"] = end.into();
+        }
+
         Ok(ToolResult {
             success: status < 400,
-            data: serde_json::json!({
-                "url": url,
-                "status": status,
-                "content_type": content_type,
-                "content": display,
-                "headers": response_headers,
-                "content_length": display.len()
-            }),
-            message: format!("HTTP {} {} ({}, {} chars)", method, status, content_type, display.len()),
+            message: format!(
+                "HTTP {} {} ({} chars{})",
+                status,
+                title.as_deref().unwrap_or(final_url.as_str()),
+                content.chars().count(),
+                if truncated { format!(" of {}, truncated", total_chars) } else { String::new() }
+            ),
+            data,
         })
     }
 }
 
+/// No Content-Type: an HTML document starts with a doctype or a tag
+fn looks_like_html(bytes: &[u8]) -> bool {
+    let start = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).trim_start().to_lowercase();
+    start.starts_with("<!doctype html") || start.starts_with("<html") || start.starts_with("<head")
+}
+
+/// Charset of a page: a BOM, the Content-Type header, then for HTML a
+/// `<meta charset>` in the first bytes; UTF-8 when nothing says
+fn detect_charset(bytes: &[u8], content_type: &str, is_html: bool) -> String {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return "utf-8".to_string();
+    }
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        return "utf-16le".to_string();
+    }
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        return "utf-16be".to_string();
+    }
+    let declared = |text: &str| {
+        let start = text.find("charset=")? + "charset=".len();
+        let value: String = text[start..]
+            .trim_start_matches(['"', '\''])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+            .collect();
+        (!value.is_empty()).then(|| value.to_lowercase())
+    };
+    if let Some(charset) = declared(content_type) {
+        return charset;
+    }
+    if is_html {
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]).to_lowercase();
+        if let Some(charset) = declared(&head) {
+            return charset;
+        }
+    }
+    "utf-8".to_string()
+}
+
+/// Decode `bytes` in `charset`; charsets other than UTF-8, UTF-16, Latin-1
+/// and Windows-1252 are read as UTF-8, replacing invalid sequences
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match charset {
+        "utf-16" | "utf-16le" | "utf-16be" => {
+            let big_endian = charset == "utf-16be";
+            let bytes = bytes.strip_prefix(&[0xFF, 0xFE]).or_else(|| bytes.strip_prefix(&[0xFE, 0xFF])).unwrap_or(bytes);
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| if big_endian { u16::from_be_bytes([pair[0], pair[1]]) } else { u16::from_le_bytes([pair[0], pair[1]]) })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        "iso-8859-1" | "latin1" | "latin-1" | "us-ascii" | "ascii" | "windows-1252" | "cp1252" => {
+            bytes.iter().map(|&b| windows_1252(b)).collect()
+        }
+        _ => String::from_utf8_lossy(bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes)).into_owned(),
+    }
+}
+
+/// Windows-1252, which browsers also use for pages labelled ISO-8859-1
+fn windows_1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}', '\u{90}', '‘',
+        '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
 // ============================================================================
 // WebDownloadTool - Download files from URL
 // ============================================================================
 
-pub struct WebDownloadTool;
+/// Saves into the workspace sandbox, or under `root` when one is given
+#[derive(Default)]
+pub struct WebDownloadTool {
+    root: Option<PathBuf>,
+}
+
+impl WebDownloadTool {
+    /// Tool confined to `root` instead of the workspace sandbox setting
+    pub fn confined_to(root: PathBuf) -> Self {
+        Self { root: Some(root) }
+    }
+}
 
 #[async_trait]
 impl Tool for WebDownloadTool {
@@ -191,50 +682,62 @@ impl Tool for WebDownloadTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_cancellable(params, CancellationToken::new()).await
+    }
+
+    async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
         let url = params["url"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("url is required".into()))?;
         let path = params["path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        // Checked before anything is fetched
+        let path_buf = resolve_path_in(self.root.as_deref(), path)?;
 
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
+            .timeout(std::time::Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
             .user_agent("clawRS/0.2.0")
             .build()
             .map_err(|e| ToolError::ExecutionFailed(format!("Client HTTP: {}", e)))?;
 
-        let response = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP: {}", e)))?;
+        // Only the transfer can be cancelled: once the file is being written it is finished,
+        // so a stop never leaves a partial file behind
+        let download = async {
+            let response = client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| ToolError::http(tool_text!("web.http_error", e), &e, DOWNLOAD_TIMEOUT_SECS))?;
 
-        if !response.status().is_success() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "HTTP {} pour {}",
-                response.status(),
-                url
-            )));
-        }
+            if !response.status().is_success() {
+                return Err(ToolError::Network {
+                    status: Some(response.status().as_u16()),
+                    message: tool_text!("web.status", response.status(), url),
+                });
+            }
 
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur lecture: {}", e)))?;
+            response
+                .bytes()
+                .await
+                .map_err(|e| ToolError::http(tool_text!("web.response_error", e), &e, DOWNLOAD_TIMEOUT_SECS))
+        };
+        let bytes = tokio::select! {
+            bytes = download => bytes?,
+            _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+        };
 
-        let path_buf = std::path::PathBuf::from(path);
         if let Some(parent) = path_buf.parent() {
             if !parent.exists() {
                 tokio::fs::create_dir_all(parent)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+                    .map_err(|e| ToolError::ExecutionFailed(tool_text!("dir.create_error", e)))?;
             }
         }
 
         tokio::fs::write(&path_buf, &bytes)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible d'écrire: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("file.write_error", e)))?;
 
         Ok(ToolResult {
             success: true,
@@ -243,11 +746,314 @@ impl Tool for WebDownloadTool {
                 "path": path,
                 "bytes": bytes.len()
             }),
-            message: format!("Téléchargé: {} -> {} ({} octets)", url, path, bytes.len()),
+            message: tool_text!("web_download.done", url, path, bytes.len()),
         })
     }
 }
 
+// ============================================================================
+// HttpRequestTool - Arbitrary HTTP calls (APIs with bodies and auth)
+// ============================================================================
+
+/// Default and maximum client timeout of `http_request`
+const HTTP_REQUEST_TIMEOUT_SECS: u64 = 30;
+const HTTP_REQUEST_MAX_TIMEOUT_SECS: u64 = 300;
+
+/// Default and maximum bytes of response body read by `http_request`
+const HTTP_REQUEST_MAX_BYTES: usize = 1024 * 1024;
+const HTTP_REQUEST_HARD_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+/// Request headers whose values never appear in results
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "x-api-key"];
+
+/// Whether `http_request` may reach localhost and private networks, per the
+/// settings
+static ALLOW_PRIVATE_NETWORK: AtomicBool = AtomicBool::new(false);
+
+/// Let `http_request` reach localhost and private ranges, or block them again
+pub fn set_allow_private_network(allow: bool) {
+    ALLOW_PRIVATE_NETWORK.store(allow, Ordering::Relaxed);
+}
+
+/// Loopback, private, link-local, shared (CGNAT) and unspecified addresses
+pub fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || (a == 100 && (64..128).contains(&b))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_private_address(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// Address `url` is to be fetched from, refused if private unless
+/// `allow_private`
+///
+/// The client is pinned to the returned address, so the name can't resolve
+/// somewhere else between the check and the request.
+async fn checked_address(url: &reqwest::Url, allow_private: bool) -> Result<SocketAddr, ToolError> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| ToolError::InvalidParameters(tool_text!("web.no_host", url)))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| ToolError::Network { status: None, message: tool_text!("web.resolve_error", host, e) })?
+        .collect();
+    let first = *addrs
+        .first()
+        .ok_or_else(|| ToolError::Network { status: None, message: tool_text!("web.no_address", host) })?;
+    if !allow_private {
+        if let Some(private) = addrs.iter().find(|addr| is_private_address(addr.ip())) {
+            return Err(ToolError::PermissionDenied {
+                rule: Some("private_network".to_string()),
+                message: tool_text!(
+                    "web.private_address",
+                    host,
+                    private.ip()
+                ),
+            });
+        }
+    }
+    Ok(first)
+}
+
+/// `name: value` list of request headers, secrets masked
+fn redacted_headers(headers: &[(String, String)]) -> Vec<String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            if SENSITIVE_HEADERS.contains(&name.to_lowercase().as_str()) {
+                format!("{}: [redacted]", name)
+            } else {
+                format!("{}: {}", name, value)
+            }
+        })
+        .collect()
+}
+
+/// Follows the private network setting, or `allow_private_network` when set
+#[derive(Default)]
+pub struct HttpRequestTool {
+    allow_private_network: Option<bool>,
+}
+
+impl HttpRequestTool {
+    /// Tool allowing private networks or not, whatever the setting
+    pub fn allowing_private_network(allow: bool) -> Self {
+        Self { allow_private_network: Some(allow) }
+    }
+}
+
+#[async_trait]
+impl Tool for HttpRequestTool {
+    fn name(&self) -> &str {
+        "http_request"
+    }
+
+    fn description(&self) -> &str {
+        "Send an HTTP request with any method, headers and body (e.g. POST JSON to an API with an Authorization header). Returns status, response headers and body, parsed into `json` for JSON responses. Redirects are not followed: the Location header is returned. Localhost and private networks are blocked unless allowed in settings. REQUIRES APPROVAL."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "method": {
+                    "type": "string",
+                    "enum": ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"],
+                    "description": "HTTP method (default: GET)",
+                    "default": "GET"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "http(s) URL"
+                },
+                "headers": {
+                    "type": "object",
+                    "description": "Request headers as key-value pairs"
+                },
+                "body": {
+                    "type": ["string", "object", "array"],
+                    "description": "Request body: a string is sent as is, JSON is serialized with Content-Type application/json"
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Timeout in seconds (default: 30, max: 300)",
+                    "default": 30
+                },
+                "max_response_bytes": {
+                    "type": "integer",
+                    "description": "Bytes of response body kept, the rest is not downloaded (default: 1048576, max: 10485760)",
+                    "default": 1048576
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_cancellable(params, CancellationToken::new()).await
+    }
+
+    async fn execute_cancellable(&self, params: Value, cancel: CancellationToken) -> Result<ToolResult, ToolError> {
+        let raw_url = params["url"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("url is required".into()))?;
+        let method = params["method"].as_str().unwrap_or("GET").to_uppercase();
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|_| ToolError::InvalidParameters(tool_text!("web.unknown_method", method)))?;
+        let timeout_secs = params["timeout_secs"]
+            .as_u64()
+            .unwrap_or(HTTP_REQUEST_TIMEOUT_SECS)
+            .clamp(1, HTTP_REQUEST_MAX_TIMEOUT_SECS);
+        let max_bytes = params["max_response_bytes"]
+            .as_u64()
+            .map_or(HTTP_REQUEST_MAX_BYTES, |n| n as usize)
+            .min(HTTP_REQUEST_HARD_MAX_BYTES);
+
+        let url = reqwest::Url::parse(raw_url)
+            .map_err(|e| ToolError::InvalidParameters(tool_text!("web.invalid_url", raw_url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(ToolError::InvalidParameters(tool_text!("web.unsupported_scheme", url.scheme())));
+        }
+
+        let mut headers: Vec<(String, String)> = Vec::new();
+        if let Some(given) = params["headers"].as_object() {
+            for (name, value) in given {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                headers.push((name.clone(), value));
+            }
+        }
+        let has_content_type = headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+        let body = match &params["body"] {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            json => {
+                if !has_content_type {
+                    headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                }
+                Some(json.to_string())
+            }
+        };
+
+        let allow_private = self
+            .allow_private_network
+            .unwrap_or_else(|| ALLOW_PRIVATE_NETWORK.load(Ordering::Relaxed));
+        let address = checked_address(&url, allow_private).await?;
+        let mut client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_secs))
+            .user_agent("clawRS/0.2.0")
+            .redirect(reqwest::redirect::Policy::none());
+        if let Some(domain) = url.domain() {
+            client = client.resolve(domain, address);
+        }
+        let client = client
+            .build()
+            .map_err(|e| ToolError::ExecutionFailed(tool_text!("web.client_error", e)))?;
+
+        let mut request = client.request(method.clone(), url.clone());
+        for (name, value) in &headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+
+        // The body is read chunk by chunk and dropped past the cap
+        let exchange = async {
+            let mut response = request
+                .send()
+                .await
+                .map_err(|e| ToolError::http(tool_text!("web.http_error", e), &e, timeout_secs))?;
+            let mut bytes: Vec<u8> = Vec::new();
+            let mut truncated = false;
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| ToolError::http(tool_text!("web.response_error", e), &e, timeout_secs))?
+            {
+                let room = max_bytes - bytes.len();
+                if chunk.len() > room {
+                    bytes.extend_from_slice(&chunk[..room]);
+                    truncated = true;
+                    break;
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            Ok::<_, ToolError>((response, bytes, truncated))
+        };
+        let (response, bytes, truncated) = tokio::select! {
+            result = exchange => result?,
+            _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+        };
+
+        let status = response.status().as_u16();
+        let response_headers: serde_json::Map<String, Value> = response
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| v.to_str().ok().map(|val| (k.to_string(), Value::String(val.to_string()))))
+            .collect();
+        let content_type = response_headers
+            .get("content-type")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+        let text = String::from_utf8_lossy(&bytes).to_string();
+        let json = (!truncated && content_type.contains("json"))
+            .then(|| serde_json::from_str::<Value>(&text).ok())
+            .flatten();
+
+        let sent = redacted_headers(&headers);
+        let mut data = serde_json::json!({
+            "url": url.as_str(),
+            "method": method.as_str(),
+            "status": status,
+            "headers": response_headers,
+            "request_headers": sent,
+            "content_type": content_type,
+            "body": text,
+            "bytes": bytes.len(),
+            "truncated": truncated
+        });
+        if let Some(json) = json {
+            data["json"] = json;
+        }
+
+        let mut message = tool_text!(
+            "http_request.done",
+            method,
+            url,
+            status,
+            if content_type.is_empty() { tool_text!("http_request.no_type") } else { content_type.as_str() },
+            bytes.len(),
+            if truncated { tool_text!("http_request.truncated") } else { "" }
+        );
+        if !sent.is_empty() {
+            message.push_str(&tool_text!("http_request.headers", sent.join(", ")));
+        }
+        Ok(ToolResult { success: status < 400, data, message })
+    }
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -367,3 +1173,234 @@ fn html_to_text(html: &str) -> String {
 
     result.trim().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(content: &str) -> CachedPage {
+        CachedPage::new(200, "text/plain".to_string(), content.to_string(), serde_json::Map::new())
+    }
+
+    #[test]
+    fn test_cache_hit_and_miss_counted() {
+        let cache = FetchCache::default();
+        let key = FetchCache::key("get", "https://example.com");
+        assert!(cache.get(&key).is_none());
+
+        cache.insert(key.clone(), page("hello"));
+        assert_eq!(cache.get(&key).unwrap().content, "hello");
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+        assert_eq!(stats.hit_rate(), 0.5);
+        assert!(stats.render_prometheus().contains("clawrs_web_cache_hits_total 1\n"));
+
+        let result = fetch_result("https://example.com", "GET", cache.get(&key).unwrap(), 3, true);
+        assert_eq!(result.data["cached"], true);
+        assert!(result.data["content"].as_str().unwrap().starts_with("hel..."));
+    }
+
+    #[test]
+    fn test_cache_ttl_expiry() {
+        let cache = FetchCache::new(Duration::ZERO, DEFAULT_CACHE_MAX_BYTES);
+        cache.insert("k".to_string(), page("stale"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("k").is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_cache_size_cap_evicts_oldest() {
+        let cache = FetchCache::new(DEFAULT_CACHE_TTL, 40);
+        cache.insert("a".to_string(), page("0123456789"));
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("b".to_string(), page("0123456789"));
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("c".to_string(), page("0123456789"));
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("d".to_string(), page("0123456789"));
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("e".to_string(), page("0123456789"));
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 4);
+        assert!(stats.bytes <= 40);
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("e").is_some());
+
+        // Larger than a quarter of the cap: never stored
+        cache.insert("big".to_string(), page("01234567890"));
+        assert!(cache.get("big").is_none());
+    }
+
+    #[test]
+    fn test_cache_dropped_on_conversation_change() {
+        let cache = FetchCache::default();
+        cache.bind("conv-1");
+        cache.insert("k".to_string(), page("x"));
+        cache.bind("conv-1");
+        assert!(cache.get("k").is_some());
+        cache.bind("conv-2");
+        assert!(cache.get("k").is_none());
+    }
+
+    #[test]
+    fn test_no_store_detection() {
+        let mut headers = serde_json::Map::new();
+        headers.insert("cache-control".to_string(), Value::String("private, max-age=60".to_string()));
+        assert!(!is_no_store(&headers));
+        headers.insert("cache-control".to_string(), Value::String("no-cache, No-Store".to_string()));
+        assert!(is_no_store(&headers));
+    }
+
+    #[test]
+    fn test_private_addresses() {
+        for private in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:192.168.0.1"] {
+            assert!(is_private_address(private.parse().unwrap()), "{}", private);
+        }
+        for public in ["1.1.1.1", "100.128.0.1", "2606:4700::1111"] {
+            assert!(!is_private_address(public.parse().unwrap()), "{}", public);
+        }
+    }
+
+    /// One-route HTTP server answering every request with `body` as JSON
+    async fn json_server(body: &'static str) -> std::net::SocketAddr {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_http_request_guards_and_caps() {
+        let addr = json_server(r#"{"ok":true,"items":[1,2,3]}"#).await;
+        let url = format!("http://{}/api", addr);
+
+        // Localhost is refused until allowed
+        let blocked = HttpRequestTool::allowing_private_network(false);
+        let err = blocked.execute(serde_json::json!({"url": url})).await.unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied { rule: Some(ref rule), .. } if rule == "private_network"));
+
+        let tool = HttpRequestTool::allowing_private_network(true);
+        let result = tool
+            .execute(serde_json::json!({
+                "method": "POST",
+                "url": url,
+                "headers": {"Authorization": "Bearer s3cret"},
+                "body": {"name": "demo"}
+            }))
+            .await;
+        let capped = tool.execute(serde_json::json!({"url": url, "max_response_bytes": 4})).await;
+
+        let result = result.unwrap();
+        assert_eq!(result.data["status"], 200);
+        assert_eq!(result.data["json"]["items"][2], 3);
+        assert!(result.message.contains("Authorization: [redacted]"), "{}", result.message);
+        assert!(!result.data.to_string().contains("s3cret") && !result.message.contains("s3cret"));
+
+        let capped = capped.unwrap();
+        assert_eq!(capped.data["body"], r#"{"ok"#);
+        assert_eq!(capped.data["truncated"], true);
+        assert!(capped.data.get("json").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_web_download_stays_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        let tool = WebDownloadTool::confined_to(root.clone());
+        let outside = root.parent().unwrap().join("clawrs-download-outside.txt");
+
+        for path in ["../clawrs-download-outside.txt", outside.to_str().unwrap()] {
+            let params = serde_json::json!({"url": "http://example.invalid/file.txt", "path": path});
+            let err = tool.execute(params).await.unwrap_err();
+            assert!(matches!(err, ToolError::PermissionDenied { rule: Some(ref rule), .. } if rule == "workspace"), "{}", path);
+        }
+        assert!(!outside.exists());
+    }
+
+    #[test]
+    fn test_charset_detection() {
+        assert_eq!(detect_charset(b"<p>x</p>", "text/html; charset=ISO-8859-1", true), "iso-8859-1");
+        assert_eq!(
+            detect_charset(b"<html><head><meta charset=\"windows-1252\">", "text/html", true),
+            "windows-1252"
+        );
+        assert_eq!(detect_charset(&[0xFF, 0xFE, b'<', 0], "text/html; charset=utf-8", true), "utf-16le");
+        assert_eq!(detect_charset(b"plain", "text/plain", false), "utf-8");
+
+        assert_eq!(decode_charset(b"caf\xe9 \x93quoted\x94 \x80", "iso-8859-1"), "café “quoted” €");
+        assert_eq!(decode_charset(&[0xFF, 0xFE, b'h', 0, b'i', 0], "utf-16le"), "hi");
+        assert_eq!(decode_charset("déjà".as_bytes(), "utf-8"), "déjà");
+    }
+
+    #[tokio::test]
+    async fn test_web_read_follows_redirects() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let response: Vec<u8> = if String::from_utf8_lossy(&request).starts_with("GET /old ") {
+                    b"HTTP/1.1 301 Moved\r\nLocation: /post\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec()
+                } else {
+                    let body: &[u8] = b"<html><head><title>Caf\xe9</title></head><body><article>\
+                        <p>Un article sur le caf\xe9, assez long pour compter comme paragraphe.</p>\
+                        <p><a href=\"/suite\">Lire la suite</a></p></article></body></html>";
+                    let mut response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=iso-8859-1\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .into_bytes();
+                    response.extend_from_slice(body);
+                    response
+                };
+                let _ = socket.write_all(&response).await;
+            }
+        });
+
+        let result = WebReadTool
+            .execute(serde_json::json!({"url": format!("http://{}/old", addr), "max_chars": 30}))
+            .await
+            .unwrap();
+        assert_eq!(result.data["final_url"], format!("http://{}/post", addr));
+        assert_eq!(result.data["title"], "Café");
+        assert_eq!(result.data["content"], "Un article sur le café, assez ");
+        assert_eq!(result.data["truncated"], true);
+        assert_eq!(result.data["next_offset"], 30);
+
+        let rest = WebReadTool
+            .execute(serde_json::json!({"url": format!("http://{}/post", addr), "offset": 30}))
+            .await
+            .unwrap();
+        let content = rest.data["content"].as_str().unwrap();
+        assert!(content.ends_with(&format!("[Lire la suite](http://{}/suite)", addr)), "{}", content);
+    }
+}
@@ -2,59 +2,616 @@
 //!
 //! This module contains the main App component that serves as the root of the UI tree.
 
-use crate::inference::LlamaEngine;
-use crate::storage::conversations::Conversation;
-use crate::storage::settings::{AppSettings, load_settings};
+pub mod server;
+
+use crate::inference::chat_template::ChatTemplate;
+use crate::inference::reasoning;
+use crate::inference::streaming::collect_stream_text;
+use crate::inference::{
+    estimate_memory, read_gguf_metadata, EngineError, EngineMetrics, GenerationParams, InferenceBackend, LlamaEngine, LoadConfig,
+    LoadControl, MemoryEstimate, MemoryShortfall, OpenAiCompatBackend, RopeScaling,
+};
+use crate::storage::conversations::{cleanup_old_conversations, list_conversations, save_conversation, Conversation};
+use crate::storage::secrets;
+use crate::system::resources::{free_ram_bytes, free_vram_bytes};
+use crate::storage::settings::{AppSettings, load_settings, ToolCallFormat};
+use crate::app::server::ApiServer;
 use crate::ui::Layout;
-use crate::agent::{Agent, AgentConfig};
+use crate::agent::tools::filesystem::set_workspace_sandbox;
+use crate::agent::tools::command_policy::set_command_policy;
+use crate::agent::tools::lsp::set_language_servers;
+use crate::agent::tools::web::set_allow_private_network;
+use crate::agent::tools::git::GitCommitPreparedTool;
+use crate::agent::tools::index::{set_document_index_settings, IndexBuildTool, IndexSearchTool, IndexStatusTool};
+use crate::agent::tools::task::AgentTaskTool;
+use crate::agent::loop_runner::{persisted_messages, LoopMessage};
+use crate::agent::{Agent, AgentConfig, AgentEvent, AgentStrings, Locale};
 use dioxus::prelude::*;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use crate::types::message::{Message as ChatMessage, Notice, NoticeKind, Role};
 use crate::ui::chat::message::Message;
+use crate::ui::chat::queue::SendQueue;
+use crate::ui::chat::scroll::ChatScroll;
+
+/// Time between two retention cleanups
+const RETENTION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
 
 /// Represents the current state of the model
 #[derive(Clone, PartialEq, Debug)]
 pub enum ModelState {
     NotLoaded,
-    Loading,
-    Loaded(String),
+    /// Load in progress, with the fraction of the weights read in `[0, 1]`
+    Loading(f32),
+    /// Replacing the loaded model `from` with `to`, keeping the conversation
+    Swapping { from: String, to: String, progress: f32 },
+    Loaded(ModelSource),
     Error(String),
 }
 
+/// Where the loaded model runs
+#[derive(Clone, PartialEq, Debug)]
+pub enum ModelSource {
+    /// GGUF file run by the local engine, with the context it was trained
+    /// for and the RoPE scaling stretching it, from its metadata
+    Local { path: String, context_length: u32, rope_scaling: Option<RopeScaling> },
+    /// Model served by an OpenAI-compatible server
+    Remote { base_url: String, model: String },
+}
+
+impl ModelSource {
+    /// Path of a local model
+    pub fn local_path(&self) -> Option<&str> {
+        match self {
+            ModelSource::Local { path, .. } => Some(path),
+            ModelSource::Remote { .. } => None,
+        }
+    }
+
+    /// Short name: the file stem of a local model, the server's model name
+    pub fn display_name(&self) -> String {
+        match self {
+            ModelSource::Local { path, .. } => std::path::Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone()),
+            ModelSource::Remote { model, .. } => model.clone(),
+        }
+    }
+
+    /// Context a local model was trained for, when its metadata gives one
+    pub fn trained_context(&self) -> Option<u32> {
+        match self {
+            ModelSource::Local { context_length, .. } if *context_length > 0 => Some(*context_length),
+            _ => None,
+        }
+    }
+}
+
+/// A model load refused before it started, its estimate exceeding free memory
+#[derive(Clone, PartialEq, Debug)]
+pub struct MemoryRefusal {
+    pub path: String,
+    pub estimate: MemoryEstimate,
+    pub shortfall: MemoryShortfall,
+    /// Context size the estimate assumes
+    pub context_size: u32,
+}
+
+impl ModelState {
+    /// Whether a load or swap is in progress (no generation possible)
+    pub fn is_loading(&self) -> bool {
+        matches!(self, ModelState::Loading(_) | ModelState::Swapping { .. })
+    }
+
+    /// Fraction of the weights read, when a load is in progress
+    pub fn load_progress(&self) -> Option<f32> {
+        match self {
+            ModelState::Loading(progress) | ModelState::Swapping { progress, .. } => Some(*progress),
+            _ => None,
+        }
+    }
+}
+
 /// Global application state shared across components
 #[derive(Clone)]
 pub struct AppState {
     pub agent: Arc<Agent>,
     pub engine: Arc<Mutex<LlamaEngine>>,
+    /// Engine metrics, readable without locking the engine
+    pub metrics: Arc<EngineMetrics>,
     pub current_conversation: Signal<Option<Conversation>>,
     pub conversations: Signal<Vec<Conversation>>,
     pub settings: Signal<AppSettings>,
     pub model_state: Signal<ModelState>,
+    /// Whether the loaded model was detected as a reasoning model
+    pub reasoning_detected: Signal<bool>,
+    /// Tool call format the loaded model's chat template prefers
+    pub tool_call_format_detected: Signal<ToolCallFormat>,
+    /// Chat template the loaded model's prompts are rendered with
+    /// (None = llama.cpp's own)
+    pub chat_template_in_use: Signal<Option<ChatTemplate>>,
+    /// Last load refused because the model would not fit in memory
+    pub memory_refusal: Signal<Option<MemoryRefusal>>,
+    /// Progress/cancel handle of the model load in progress
+    pub model_load: Signal<Option<Arc<LoadControl>>>,
     pub stop_signal: Arc<AtomicBool>,
+    /// Cancels the tool calls of the current run; replaced when a run starts
+    pub tool_cancel: Signal<CancellationToken>,
     /// Global generation flag - generation continues even when navigating away
     pub is_generating: Signal<bool>,
     /// Active messages buffer - persists across navigation
     pub active_messages: Signal<Vec<Message>>,
+    /// Events of the current agent run (tool calls, permissions, progress)
+    pub agent_events: Signal<Vec<AgentEvent>>,
+    /// Text to pre-fill the chat input with (e.g. a template scaffold)
+    pub pending_input: Signal<Option<String>>,
+    /// Messages sent during a run, started in order once it ends
+    pub send_queue: Signal<SendQueue>,
+    /// Message the chat should scroll to once it is shown (e.g. a search hit)
+    pub scroll_to_message: Signal<Option<usize>>,
+    /// Scroll position of the chat, kept while other views are shown
+    pub chat_scroll: Signal<ChatScroll>,
+    /// Local API server, while it runs
+    pub api_server: Signal<Option<Arc<ApiServer>>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         tracing::info!("AppState initialized");
         let settings = load_settings();
+        if let Some(dir) = settings.workspace_directory.as_ref() {
+            if let Err(e) = std::env::set_current_dir(dir) {
+                tracing::warn!("Cannot use workspace {}: {}", dir.display(), e);
+            }
+        }
+        set_workspace_sandbox(settings.sandbox_root());
+        set_allow_private_network(settings.allow_private_network_requests);
+        set_command_policy(settings.command_policy.clone());
+        set_language_servers(settings.language_servers.clone());
+        set_document_index_settings(settings.document_index.clone());
+        secrets::install_default();
         let mut agent_config = AgentConfig::default();
         agent_config.disabled_mcp_servers = settings.disabled_mcp_servers.clone();
         
+        let engine = LlamaEngine::new();
+        let metrics = engine.metrics();
+        
         Self {
             agent: Arc::new(Agent::new(agent_config)),
-            engine: Arc::new(Mutex::new(LlamaEngine::new())),
+            engine: Arc::new(Mutex::new(engine)),
+            metrics,
             current_conversation: Signal::new(None),
             conversations: Signal::new(Vec::new()),
             settings: Signal::new(settings),
             model_state: Signal::new(ModelState::NotLoaded),
+            reasoning_detected: Signal::new(false),
+            tool_call_format_detected: Signal::new(ToolCallFormat::default()),
+            chat_template_in_use: Signal::new(None),
+            memory_refusal: Signal::new(None),
+            model_load: Signal::new(None),
             stop_signal: Arc::new(AtomicBool::new(false)),
+            tool_cancel: Signal::new(CancellationToken::new()),
             is_generating: Signal::new(false),
             active_messages: Signal::new(Vec::new()),
+            agent_events: Signal::new(Vec::new()),
+            pending_input: Signal::new(None),
+            send_queue: Signal::new(SendQueue::default()),
+            scroll_to_message: Signal::new(None),
+            chat_scroll: Signal::new(ChatScroll::default()),
+            api_server: Signal::new(None),
+        }
+    }
+
+    /// Language of the interface, for `t!` lookups
+    pub fn locale(&self) -> Locale {
+        self.settings.read().locale()
+    }
+
+    /// Stop the current run: generation, the tool call in flight and the queue
+    pub fn stop_generation(&mut self) {
+        self.send_queue.write().stop();
+        self.stop_signal.store(true, Ordering::Relaxed);
+        // Abort the tool call in flight, if any
+        self.tool_cancel.peek().cancel();
+        // Cancel the worker directly too, in case the loop is between polls
+        if let Ok(engine) = self.engine.try_lock() {
+            engine.cancel_generation();
+        }
+        self.is_generating.set(false);
+    }
+
+    /// Load a model in the background, publishing progress through `model_state`
+    pub fn start_model_load(&self, path: String) {
+        self.load_model_in_background(path, None, true);
+    }
+
+    /// Replace the loaded model, keeping the messages and conversation on screen
+    ///
+    /// Refused during a generation. Without a loaded model this is a plain load.
+    pub fn start_model_swap(&self, path: String) {
+        self.swap_or_load(path, true);
+    }
+
+    /// Load the model of the last memory refusal without checking memory again
+    pub fn load_model_anyway(&self) {
+        let mut memory_refusal = self.memory_refusal;
+        let Some(refusal) = memory_refusal.peek().clone() else {
+            return;
+        };
+        memory_refusal.set(None);
+        tracing::warn!("Loading {} despite the memory estimate", refusal.path);
+        self.swap_or_load(refusal.path, false);
+    }
+
+    fn swap_or_load(&self, path: String, check_memory: bool) {
+        let from = match &*self.model_state.peek() {
+            ModelState::Loaded(ModelSource::Local { path: current, .. }) if *current == path => return,
+            ModelState::Loaded(ModelSource::Local { path: current, .. }) => current.clone(),
+            _ => return self.load_model_in_background(path, None, check_memory),
+        };
+        if *self.is_generating.peek() {
+            tracing::warn!("Model swap refused while generating");
+            return;
+        }
+        self.load_model_in_background(path, Some(from), check_memory);
+    }
+
+    fn load_model_in_background(&self, path: String, swap_from: Option<String>, check_memory: bool) {
+        // Estimate first (file and GPU queries block), then load unless it would not fit
+        if check_memory {
+            let app_state = self.clone();
+            let settings = self.settings.peek().clone();
+            spawn(async move {
+                let (check_path, check_from) = (path.clone(), swap_from.clone());
+                let refusal = tokio::task::spawn_blocking(move || memory_refusal(&check_path, check_from.as_deref(), &settings))
+                    .await
+                    .ok()
+                    .flatten();
+                let mut memory_refusal = app_state.memory_refusal;
+                match refusal {
+                    Some(refusal) => {
+                        tracing::warn!(
+                            "Load of {} refused: needs ~{} MB of {:?}, {} MB free",
+                            path,
+                            refusal.shortfall.needed_bytes / (1024 * 1024),
+                            refusal.shortfall.kind,
+                            refusal.shortfall.available_bytes / (1024 * 1024)
+                        );
+                        memory_refusal.set(Some(refusal));
+                    }
+                    None => app_state.load_model_in_background(path, swap_from, false),
+                }
+            });
+            return;
+        }
+
+        let mut memory_refusal = self.memory_refusal;
+        memory_refusal.set(None);
+        let mut model_state = self.model_state;
+        let mut model_load = self.model_load;
+        let mut reasoning_detected = self.reasoning_detected;
+        let mut tool_call_format_detected = self.tool_call_format_detected;
+        let mut chat_template_in_use = self.chat_template_in_use;
+        let engine = self.engine.clone();
+        let gpu_layers = self.settings.read().gpu_layers;
+        let draft_model = self.settings.read().draft_model_path.clone().map(std::path::PathBuf::from);
+        let probe_reasoning = self.settings.read().probe_reasoning;
+        let auto_context = self.settings.read().auto_context;
+        let chat_template = self.settings.read().model_profile(&path).and_then(|p| p.chat_template);
+        let control = Arc::new(LoadControl::new());
+        let loading = {
+            let to = path.clone();
+            let swap_from = swap_from.clone();
+            move |progress: f32| match &swap_from {
+                Some(from) => ModelState::Swapping { from: from.clone(), to: to.clone(), progress },
+                None => ModelState::Loading(progress),
+            }
+        };
+        reasoning_detected.set(false);
+        tool_call_format_detected.set(ToolCallFormat::default());
+        chat_template_in_use.set(None);
+        model_state.set(loading(0.0));
+        model_load.set(Some(control.clone()));
+
+        // Poll progress until this load is over
+        let poll = control.clone();
+        spawn(async move {
+            while !poll.is_finished() && model_state.peek().is_loading() {
+                model_state.set(loading(poll.progress()));
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            }
+        });
+
+        let app_state = self.clone();
+        spawn(async move {
+            let result = {
+                let mut engine = engine.lock().await;
+                if !engine.is_initialized() {
+                    if let Err(e) = engine.init() {
+                        model_load.set(None);
+                        return model_state.set(ModelState::Error(e.to_string()));
+                    }
+                }
+                engine.set_remote_backend(None);
+                engine.set_draft_model(draft_model);
+                engine.set_chat_template(chat_template);
+                match swap_from {
+                    Some(_) => engine.swap_model(&path, gpu_layers, control).await,
+                    None => engine.load_model_with_control(&path, gpu_layers, control).await,
+                }
+            };
+            model_load.set(None);
+            match result {
+                Ok(info) => {
+                    let reasoning = info.reasoning || (probe_reasoning && probe_thinking(&engine).await);
+                    tracing::info!("Reasoning model: {}", reasoning);
+                    reasoning_detected.set(reasoning);
+                    tracing::info!("Tool call format: {}", info.tool_call_format.as_str());
+                    tool_call_format_detected.set(info.tool_call_format);
+                    chat_template_in_use.set(info.chat_template);
+                    if swap_from.is_some() {
+                        app_state.record_model_switch(&path);
+                    }
+                    let context_size = app_state.settings.peek().context_size;
+                    if !auto_context && context_size > info.context_length {
+                        tracing::warn!(
+                            "Context size {} is above the {} tokens the model was trained for, capping it",
+                            context_size, info.context_length
+                        );
+                    }
+                    model_state.set(ModelState::Loaded(ModelSource::Local {
+                        path,
+                        context_length: info.context_length,
+                        rope_scaling: info.rope_scaling,
+                    }));
+                }
+                Err(EngineError::LoadCancelled) => model_state.set(ModelState::NotLoaded),
+                Err(e) => model_state.set(ModelState::Error(e.to_string())),
+            }
+        });
+    }
+
+    /// Note a model switch in the open conversation, and save it
+    fn record_model_switch(&self, path: &str) {
+        let mut active_messages = self.active_messages;
+        let mut current_conversation = self.current_conversation;
+        let model = std::path::Path::new(path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let lang = {
+            let settings = self.settings.peek();
+            settings.agent_locale().unwrap_or_else(|| settings.locale())
+        };
+        let note = AgentStrings::new(lang).model_switched(&model);
+
+        let mut conv_write = current_conversation.write();
+        let Some(ref mut conv) = *conv_write else {
+            return;
+        };
+        active_messages
+            .write()
+            .push(Message::notice_message(note, Notice { kind: NoticeKind::ModelSwitch, count: 1 }));
+        conv.messages = persisted_messages(&active_messages.peek())
+            .into_iter()
+            .map(|m| m.into())
+            .collect();
+        if let Err(e) = save_conversation(conv) {
+            tracing::error!("Failed to save conversation: {}", e);
+        }
+    }
+
+    /// Whether the loaded model is a reasoning model: its saved profile
+    /// override, or else what was detected at load
+    pub fn is_reasoning_model(&self) -> bool {
+        let override_ = match &*self.model_state.peek() {
+            ModelState::Loaded(source) => source
+                .local_path()
+                .and_then(|path| self.settings.peek().model_profile(path).and_then(|p| p.reasoning)),
+            _ => return false,
+        };
+        override_.unwrap_or(*self.reasoning_detected.peek())
+    }
+
+    /// Tool call format of the loaded model: its saved profile override, or
+    /// else what its chat template prefers
+    pub fn tool_call_format(&self) -> ToolCallFormat {
+        let override_ = match &*self.model_state.peek() {
+            ModelState::Loaded(source) => source
+                .local_path()
+                .and_then(|path| self.settings.peek().model_profile(path).and_then(|p| p.tool_call_format)),
+            _ => return ToolCallFormat::default(),
+        };
+        override_.unwrap_or(*self.tool_call_format_detected.peek())
+    }
+
+    /// Context cap for generations: the settings' cap, lowered to what the
+    /// loaded model was trained for
+    pub fn context_cap(&self) -> u32 {
+        let cap = self.settings.peek().context_cap();
+        match &*self.model_state.peek() {
+            ModelState::Loaded(source) => source.trained_context().map_or(cap, |trained| cap.min(trained)),
+            _ => cap,
+        }
+    }
+
+    /// Token count of `text` with the loaded model's tokenizer, or estimated
+    pub async fn count_tokens(&self, text: &str) -> usize {
+        self.engine.lock().await.count_tokens(text).await
+    }
+
+    /// Token count of each active message
+    ///
+    /// Messages without a cached count for their current content are counted
+    /// in one batch. Only exact counts are cached; estimates are retried on the
+    /// next call.
+    pub async fn count_message_tokens(&self) -> Vec<usize> {
+        let mut messages = self.active_messages;
+        let stale: Vec<(usize, String)> = messages
+            .peek()
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| !m.content.is_empty() && m.tokens.get(&m.content).is_none())
+            .map(|(i, m)| (i, m.content.clone()))
+            .collect();
+
+        if !stale.is_empty() {
+            let texts = stale.iter().map(|(_, text)| text.clone()).collect();
+            let counted = self.engine.lock().await.tokenize_counts(texts).await;
+            match counted {
+                Ok(counts) => {
+                    let mut msgs = messages.write();
+                    for ((i, text), count) in stale.iter().zip(counts) {
+                        // Skip messages that changed or moved while counting
+                        if let Some(m) = msgs.get_mut(*i).filter(|m| m.content == *text) {
+                            m.tokens.set(text, count);
+                        }
+                    }
+                }
+                Err(e) => tracing::debug!("Token counting unavailable, estimating: {}", e),
+            }
+        }
+
+        messages
+            .peek()
+            .iter()
+            .map(|m| m.tokens.get_or_estimate(&m.content))
+            .collect()
+    }
+
+    /// Generate with the OpenAI-compatible server from the settings instead
+    /// of a local model, which is unloaded
+    pub fn connect_remote_backend(&self) {
+        let mut model_state = self.model_state;
+        let mut reasoning_detected = self.reasoning_detected;
+        let mut tool_call_format_detected = self.tool_call_format_detected;
+        let mut chat_template_in_use = self.chat_template_in_use;
+        let engine = self.engine.clone();
+        let remote = self.settings.read().remote_backend.clone();
+        let backend = match OpenAiCompatBackend::new(&remote.base_url, &remote.api_key, &remote.model) {
+            Ok(backend) => backend,
+            Err(e) => return model_state.set(ModelState::Error(e.to_string())),
+        };
+        let source = ModelSource::Remote { base_url: backend.base_url().to_string(), model: backend.model_name() };
+        spawn(async move {
+            let mut engine = engine.lock().await;
+            engine.unload_model();
+            engine.set_remote_backend(Some(Arc::new(backend)));
+            tracing::info!("Generating with {} at {:?}", remote.model, remote.base_url);
+            reasoning_detected.set(false);
+            tool_call_format_detected.set(ToolCallFormat::default());
+            chat_template_in_use.set(None);
+            model_state.set(ModelState::Loaded(source));
+        });
+    }
+
+    /// Start, stop or move the local API server to match the settings
+    pub fn apply_api_server_settings(&self) {
+        let mut api_server = self.api_server;
+        let (config, defaults) = {
+            let settings = self.settings.peek();
+            (settings.api_server.clone(), default_generation_params(&settings))
+        };
+        let running = api_server.peek().clone();
+        if let Some(server) = &running {
+            if config.enabled && server.bind_address() == config.bind_address.trim() {
+                return;
+            }
+            server.stop();
+            api_server.set(None);
+        }
+        if !config.enabled {
+            return;
+        }
+        let (engine, metrics) = (self.engine.clone(), self.metrics.clone());
+        spawn(async move {
+            match ApiServer::start(&config.bind_address, engine, metrics, defaults).await {
+                Ok(server) => api_server.set(Some(Arc::new(server))),
+                Err(e) => tracing::error!("API server not started: {}", e),
+            }
+        });
+    }
+
+    /// Abort the model load in progress, if it is still reading the weights
+    pub fn cancel_model_load(&self) {
+        if let Some(control) = self.model_load.peek().as_ref() {
+            if control.cancel() {
+                tracing::info!("Model load cancellation requested");
+            } else {
+                tracing::info!("Model load is in llama.cpp and cannot be cancelled");
+            }
+        }
+    }
+}
+
+/// Context size auto mode is estimated at: it starts small and only grows
+/// into free memory
+const AUTO_CONTEXT_ESTIMATE: u32 = 8192;
+
+/// Memory needed to load `path` with the current settings, and the context
+/// size it assumes (the setting, capped to what the model was trained for)
+pub fn estimate_model_memory(path: &str, settings: &AppSettings) -> Option<(MemoryEstimate, u32)> {
+    let metadata = read_gguf_metadata(path).ok()?;
+    let context = if settings.auto_context { AUTO_CONTEXT_ESTIMATE } else { settings.context_size };
+    let context_size = metadata.context_length.map_or(context, |trained| context.min(trained));
+    let config = LoadConfig { context_size, gpu_layers: settings.gpu_layers };
+    Some((estimate_memory(&metadata, &config), context_size))
+}
+
+/// Refusal for loading `path` when its estimate exceeds free memory. The
+/// model being swapped out is released first, so its memory counts as free.
+fn memory_refusal(path: &str, swap_from: Option<&str>, settings: &AppSettings) -> Option<MemoryRefusal> {
+    let (estimate, context_size) = estimate_model_memory(path, settings)?;
+    let released = swap_from
+        .and_then(|from| estimate_model_memory(from, settings))
+        .map(|(released, _)| released)
+        .unwrap_or_default();
+    let free_ram = free_ram_bytes().map(|free| free + released.ram_bytes());
+    let free_vram = free_vram_bytes().map(|free| free + released.gpu_bytes);
+    let shortfall = estimate.shortfall(free_ram, free_vram)?;
+    Some(MemoryRefusal { path: path.to_string(), estimate, shortfall, context_size })
+}
+
+/// Generation settings from the app settings, for generations outside a chat
+fn default_generation_params(settings: &AppSettings) -> GenerationParams {
+    GenerationParams {
+        max_tokens: settings.max_tokens,
+        temperature: settings.temperature,
+        top_k: settings.top_k,
+        top_p: settings.top_p,
+        min_p: 0.0,
+        repeat_penalty: 1.1,
+        seed: 0,
+        max_context_size: settings.context_cap(),
+        auto_context: settings.auto_context,
+        grammar: None,
+        stop_sequences: settings.stop_sequences.clone(),
+        frequency_penalty: settings.frequency_penalty,
+        presence_penalty: settings.presence_penalty,
+    }
+}
+
+/// Ask the just-loaded model a trivial question and check whether it thinks first
+async fn probe_thinking(engine: &Mutex<LlamaEngine>) -> bool {
+    let params = GenerationParams {
+        max_tokens: reasoning::PROBE_MAX_TOKENS,
+        temperature: 0.0,
+        max_context_size: 2048,
+        ..GenerationParams::default()
+    };
+    let messages = vec![ChatMessage::new(Role::User, reasoning::PROBE_PROMPT)];
+    let stream = engine.lock().await.generate_stream_background(messages, params);
+    match stream {
+        Ok((rx, stop)) => {
+            let (reply, _) = collect_stream_text(rx, &stop, &AtomicBool::new(false)).await;
+            reasoning::probe_shows_thinking(&reply)
+        }
+        Err(e) => {
+            tracing::warn!("Reasoning probe failed: {}", e);
+            false
         }
     }
 }
@@ -65,13 +622,79 @@ pub fn App() -> Element {
     use_context_provider(|| app_state);
 
     {
-        let agent = use_context::<AppState>().agent.clone();
+        let app_state = use_context::<AppState>();
         use_effect(move || {
-            let agent = agent.clone();
+            let agent = app_state.agent.clone();
+            let task_tool = {
+                let params = default_generation_params(&app_state.settings.peek());
+                AgentTaskTool::new(
+                    app_state.engine.clone(),
+                    agent.tool_registry.clone(),
+                    agent.permission_manager.clone(),
+                    app_state.stop_signal.clone(),
+                    params,
+                )
+            };
+            let commit_tool = GitCommitPreparedTool::new(app_state.engine.clone(), app_state.stop_signal.clone());
+            let index_build_tool = IndexBuildTool::new(app_state.engine.clone());
+            let index_search_tool = IndexSearchTool::new(app_state.engine.clone());
+            // User limits replace the registry defaults; the Tools tab updates them live
+            for (tool, limit) in &app_state.settings.peek().tool_limits {
+                agent.tool_registry.set_limit(tool, limit.max_calls_per_minute, limit.max_concurrent);
+            }
             spawn(async move {
                 if let Err(e) = agent.initialize_tools().await {
                     tracing::error!("Failed to initialize tools: {}", e);
                 }
+                // Need the engine, so registered here rather than with the other tools
+                agent.tool_registry.register(Arc::new(task_tool)).await;
+                if agent.config.enable_git {
+                    agent.tool_registry.register(Arc::new(commit_tool)).await;
+                }
+                if agent.config.enable_filesystem {
+                    agent.tool_registry.register(Arc::new(index_build_tool)).await;
+                    agent.tool_registry.register(Arc::new(index_search_tool)).await;
+                    agent.tool_registry.register(Arc::new(IndexStatusTool)).await;
+                }
+            });
+        });
+    }
+
+    // Local API server, when enabled
+    {
+        let app_state = use_context::<AppState>();
+        use_effect(move || app_state.apply_api_server_settings());
+    }
+
+    // Retention cleanup at startup, then once a day
+    {
+        let app_state = use_context::<AppState>();
+        use_effect(move || {
+            let mut conversations = app_state.conversations;
+            let settings = app_state.settings;
+            let current_conversation = app_state.current_conversation;
+            spawn(async move {
+                loop {
+                    let policy = settings.peek().retention.clone();
+                    if policy.is_enabled() {
+                        let open_id = current_conversation.peek().as_ref().map(|c| c.id.clone());
+                        let result = tokio::task::spawn_blocking(move || {
+                            cleanup_old_conversations(&policy, open_id.as_deref())
+                        })
+                        .await;
+                        match result {
+                            Ok(Ok(report)) if report.removed > 0 => {
+                                if let Ok(list) = list_conversations() {
+                                    conversations.set(list);
+                                }
+                            }
+                            Ok(Ok(_)) => {}
+                            Ok(Err(e)) => tracing::error!("Retention cleanup failed: {}", e),
+                            Err(e) => tracing::error!("Retention cleanup task failed: {}", e),
+                        }
+                    }
+                    tokio::time::sleep(RETENTION_INTERVAL).await;
+                }
             });
         });
     }
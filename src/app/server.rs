@@ -0,0 +1,463 @@
+//! Local OpenAI-compatible API server
+//!
+//! Lets other apps (editors, scripts) use the loaded model through
+//! `/v1/chat/completions` and `/v1/models`, and monitor it through `/health`
+//! and `/metrics`, which never wait on the engine. Requests go to the engine's
+//! background lane, so a chat in the app is always served first, and run one
+//! at a time in arrival order.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+use crate::inference::{
+    EngineError, EngineMetrics, GenerationParams, GenerationStats, HealthStatus, InferenceBackend, LlamaEngine, StopReason,
+    StreamToken,
+};
+use crate::types::message::{Message as ChatMessage, Role};
+
+#[derive(Clone)]
+struct ServerState {
+    engine: Arc<Mutex<LlamaEngine>>,
+    /// Sampling settings (as of the server start) for what a request leaves out
+    defaults: GenerationParams,
+    /// One API generation at a time, first come first served
+    turn: Arc<Semaphore>,
+}
+
+/// A running server; dropping it does not stop it, `stop` does
+pub struct ApiServer {
+    bind_address: String,
+    addr: SocketAddr,
+    shutdown: CancellationToken,
+}
+
+impl ApiServer {
+    /// Bind `bind_address` and serve in the background
+    pub async fn start(
+        bind_address: &str,
+        engine: Arc<Mutex<LlamaEngine>>,
+        metrics: Arc<EngineMetrics>,
+        defaults: GenerationParams,
+    ) -> Result<Self, String> {
+        let listener = tokio::net::TcpListener::bind(bind_address.trim())
+            .await
+            .map_err(|e| format!("Failed to listen on {}: {}", bind_address.trim(), e))?;
+        let addr = listener.local_addr().map_err(|e| e.to_string())?;
+
+        let state = ServerState { engine, defaults, turn: Arc::new(Semaphore::new(1)) };
+        let router = router(state, metrics);
+
+        let shutdown = CancellationToken::new();
+        let stopped = shutdown.clone();
+        tokio::spawn(async move {
+            let serve = axum::serve(listener, router).with_graceful_shutdown(async move { stopped.cancelled().await });
+            if let Err(e) = serve.await {
+                tracing::error!("API server stopped: {}", e);
+            }
+        });
+        tracing::info!("API server listening on http://{}", addr);
+        Ok(Self { bind_address: bind_address.trim().to_string(), addr, shutdown })
+    }
+
+    /// Address as configured
+    pub fn bind_address(&self) -> &str {
+        &self.bind_address
+    }
+
+    /// Address actually bound (the port is resolved when configured as 0)
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+        tracing::info!("API server on {} stopped", self.addr);
+    }
+}
+
+/// The OpenAI routes, plus health and metrics read from `metrics` alone
+fn router(state: ServerState, metrics: Arc<EngineMetrics>) -> Router {
+    let monitoring = Router::new()
+        .route("/health", get(health))
+        .route("/metrics", get(prometheus))
+        .with_state(metrics);
+    Router::new()
+        .route("/v1/models", get(list_models))
+        .route("/v1/chat/completions", post(chat_completions))
+        .with_state(state)
+        .merge(monitoring)
+}
+
+async fn health(State(metrics): State<Arc<EngineMetrics>>) -> Json<HealthStatus> {
+    Json(metrics.health())
+}
+
+/// Prometheus text exposition of the engine counters
+async fn prometheus(State(metrics): State<Arc<EngineMetrics>>) -> Response {
+    ([(CONTENT_TYPE, "text/plain; version=0.0.4")], metrics.render_prometheus()).into_response()
+}
+
+/// OpenAI-style error response
+fn api_error(status: StatusCode, code: &str, message: impl Into<String>) -> Response {
+    let kind = if status.is_server_error() { "server_error" } else { "invalid_request_error" };
+    let body = json!({ "error": { "message": message.into(), "type": kind, "code": code } });
+    (status, Json(body)).into_response()
+}
+
+fn engine_error(e: &EngineError) -> Response {
+    match e {
+        EngineError::NoModelLoaded | EngineError::BackendNotInitialized => {
+            api_error(StatusCode::SERVICE_UNAVAILABLE, "model_not_loaded", "No model is loaded in LocalClaw")
+        }
+        e => api_error(StatusCode::INTERNAL_SERVER_ERROR, "generation_failed", e.to_string()),
+    }
+}
+
+/// Name of the loaded model, none when nothing is loaded
+fn loaded_model(engine: &LlamaEngine) -> Option<String> {
+    engine.is_model_loaded().then(|| engine.model_name())
+}
+
+async fn list_models(State(state): State<ServerState>) -> Response {
+    let model = loaded_model(&*state.engine.lock().await);
+    let data: Vec<Value> = model
+        .into_iter()
+        .map(|id| json!({ "id": id, "object": "model", "created": 0, "owned_by": "clawrs" }))
+        .collect();
+    Json(json!({ "object": "list", "data": data })).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionRequest {
+    messages: Vec<RequestMessage>,
+    #[serde(default)]
+    stream: bool,
+    max_tokens: Option<u32>,
+    max_completion_tokens: Option<u32>,
+    temperature: Option<f32>,
+    top_p: Option<f32>,
+    seed: Option<u32>,
+    stop: Option<StopField>,
+    frequency_penalty: Option<f32>,
+    presence_penalty: Option<f32>,
+}
+
+/// `stop` is either one string or a list of them
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StopField {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestMessage {
+    role: String,
+    #[serde(default)]
+    content: Value,
+}
+
+impl RequestMessage {
+    fn to_chat_message(&self) -> ChatMessage {
+        let role = match self.role.as_str() {
+            "system" | "developer" => Role::System,
+            "assistant" => Role::Assistant,
+            "tool" => Role::Tool,
+            _ => Role::User,
+        };
+        ChatMessage::new(role, content_text(&self.content))
+    }
+}
+
+/// Text of a message content: a string, or the text parts of an array
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+impl ChatCompletionRequest {
+    fn params(&self, defaults: &GenerationParams) -> GenerationParams {
+        let mut params = defaults.clone();
+        if let Some(max_tokens) = self.max_completion_tokens.or(self.max_tokens) {
+            params.max_tokens = max_tokens.max(1);
+        }
+        if let Some(temperature) = self.temperature {
+            params.temperature = temperature.clamp(0.0, 2.0);
+        }
+        if let Some(top_p) = self.top_p {
+            params.top_p = top_p.clamp(0.0, 1.0);
+        }
+        if let Some(seed) = self.seed {
+            params.seed = seed;
+        }
+        match &self.stop {
+            Some(StopField::One(stop)) => params.stop_sequences = vec![stop.clone()],
+            Some(StopField::Many(stops)) => params.stop_sequences = stops.clone(),
+            None => {}
+        }
+        if let Some(penalty) = self.frequency_penalty {
+            params.frequency_penalty = penalty.clamp(-2.0, 2.0);
+        }
+        if let Some(penalty) = self.presence_penalty {
+            params.presence_penalty = penalty.clamp(-2.0, 2.0);
+        }
+        params
+    }
+}
+
+fn finish_reason(stats: Option<&GenerationStats>, truncated: bool) -> &'static str {
+    if truncated || stats.is_some_and(|s| s.stop_reason == StopReason::MaxTokens) {
+        "length"
+    } else {
+        "stop"
+    }
+}
+
+async fn chat_completions(State(state): State<ServerState>, body: Bytes) -> Response {
+    let request: ChatCompletionRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, "invalid_json", format!("Invalid request body: {}", e)),
+    };
+    if request.messages.is_empty() {
+        return api_error(StatusCode::BAD_REQUEST, "invalid_messages", "`messages` must not be empty");
+    }
+    let messages: Vec<ChatMessage> = request.messages.iter().map(RequestMessage::to_chat_message).collect();
+    let params = request.params(&state.defaults);
+
+    // Wait for the previous API request before touching the engine
+    let Ok(permit) = state.turn.clone().acquire_owned().await else {
+        return api_error(StatusCode::SERVICE_UNAVAILABLE, "server_closing", "The server is shutting down");
+    };
+    let started = {
+        let engine = state.engine.lock().await;
+        match loaded_model(&engine) {
+            None => Err(EngineError::NoModelLoaded),
+            Some(model) => engine.generate_stream_background(messages, params).map(|stream| (model, stream)),
+        }
+    };
+    let (model, (rx, stop)) = match started {
+        Ok(started) => started,
+        Err(e) => return engine_error(&e),
+    };
+
+    let id = format!("chatcmpl-{}", uuid::Uuid::new_v4().simple());
+    let created = chrono::Utc::now().timestamp();
+    let mut tokens = forward_stream(rx, stop, permit);
+
+    if request.stream {
+        let chunk = move |delta: Value, finish: Option<&str>| {
+            let body = json!({
+                "id": id,
+                "object": "chat.completion.chunk",
+                "created": created,
+                "model": model,
+                "choices": [{ "index": 0, "delta": delta, "finish_reason": finish }],
+            });
+            Ok::<_, Infallible>(Event::default().data(body.to_string()))
+        };
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let _ = event_tx.send(chunk(json!({ "role": "assistant" }), None));
+            let mut stats = None;
+            while let Some(token) = tokens.recv().await {
+                let event = match token {
+                    StreamToken::Token(text) => chunk(json!({ "content": text }), None),
+                    StreamToken::Stats(s) => {
+                        stats = Some(s);
+                        continue;
+                    }
+                    StreamToken::Done | StreamToken::Truncated { .. } => {
+                        let truncated = matches!(token, StreamToken::Truncated { .. });
+                        chunk(json!({}), Some(finish_reason(stats.as_ref(), truncated)))
+                    }
+                    StreamToken::Error(e) => {
+                        let error = json!({ "error": { "message": e, "type": "server_error", "code": "generation_failed" } });
+                        Ok(Event::default().data(error.to_string()))
+                    }
+                };
+                if event_tx.send(event).is_err() {
+                    // Client gone: dropping `tokens` stops the generation
+                    return;
+                }
+            }
+            let _ = event_tx.send(Ok(Event::default().data("[DONE]")));
+        });
+        let events = futures::stream::poll_fn(move |cx| event_rx.poll_recv(cx));
+        return Sse::new(events).keep_alive(KeepAlive::default()).into_response();
+    }
+
+    let mut text = String::new();
+    let mut stats = None;
+    let mut truncated = false;
+    while let Some(token) = tokens.recv().await {
+        match token {
+            StreamToken::Token(t) => text.push_str(&t),
+            StreamToken::Stats(s) => stats = Some(s),
+            StreamToken::Done => break,
+            StreamToken::Truncated { .. } => {
+                truncated = true;
+                break;
+            }
+            StreamToken::Error(e) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, "generation_failed", e),
+        }
+    }
+    let (prompt_tokens, completion_tokens) = stats.as_ref().map(|s| (s.prompt_tokens, s.tokens_generated)).unwrap_or((0, 0));
+    Json(json!({
+        "id": id,
+        "object": "chat.completion",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": text },
+            "finish_reason": finish_reason(stats.as_ref(), truncated),
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens,
+        },
+    }))
+    .into_response()
+}
+
+/// Move a worker stream onto an async channel
+///
+/// The generation is stopped once the receiver is dropped (the client went
+/// away); `permit` is released when the stream ends.
+fn forward_stream(rx: Receiver<StreamToken>, stop: Arc<AtomicBool>, permit: OwnedSemaphorePermit) -> mpsc::UnboundedReceiver<StreamToken> {
+    let (tx, tokens) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let _permit = permit;
+        loop {
+            if tx.is_closed() {
+                stop.store(true, Ordering::Relaxed);
+            }
+            match rx.try_recv() {
+                Ok(token) => {
+                    let last = matches!(token, StreamToken::Done | StreamToken::Truncated { .. } | StreamToken::Error(_));
+                    let _ = tx.send(token);
+                    if last {
+                        break;
+                    }
+                }
+                Err(TryRecvError::Empty) => tokio::time::sleep(Duration::from_millis(5)).await,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+    });
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chat_request() {
+        let request: ChatCompletionRequest = serde_json::from_str(
+            r#"{
+                "model": "anything",
+                "messages": [
+                    {"role": "developer", "content": "Be brief."},
+                    {"role": "user", "content": [{"type": "text", "text": "Hello"}, {"type": "text", "text": "there"}]}
+                ],
+                "max_completion_tokens": 64,
+                "temperature": 5.0,
+                "stop": "\n\n"
+            }"#,
+        )
+        .unwrap();
+        assert!(!request.stream);
+
+        let messages: Vec<ChatMessage> = request.messages.iter().map(RequestMessage::to_chat_message).collect();
+        assert_eq!(messages[0].role, Role::System);
+        assert_eq!(messages[1].content, "Hello\nthere");
+
+        let params = request.params(&GenerationParams::default());
+        assert_eq!(params.max_tokens, 64);
+        assert_eq!(params.temperature, 2.0);
+        assert_eq!(params.top_p, GenerationParams::default().top_p);
+        assert_eq!(params.stop_sequences, vec!["\n\n".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_without_model() {
+        let state = ServerState {
+            engine: Arc::new(Mutex::new(LlamaEngine::new())),
+            defaults: GenerationParams::default(),
+            turn: Arc::new(Semaphore::new(1)),
+        };
+        let body = Bytes::from_static(br#"{"messages":[{"role":"user","content":"hi"}]}"#);
+        let response = chat_completions(State(state.clone()), body).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let response = chat_completions(State(state), Bytes::from_static(b"not json")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_health_and_metrics_skip_engine_lock() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let engine = Arc::new(Mutex::new(LlamaEngine::new()));
+        let metrics = engine.lock().await.metrics();
+        let state = ServerState {
+            engine: engine.clone(),
+            defaults: GenerationParams::default(),
+            turn: Arc::new(Semaphore::new(1)),
+        };
+        let router = router(state, metrics);
+
+        metrics.set_model(Some("qwen2.5-7b".to_string()));
+        metrics.record_enqueued();
+
+        // A generation holding the engine does not delay monitoring
+        let _busy = engine.lock().await;
+        let mut bodies = Vec::new();
+        for uri in ["/health", "/metrics"] {
+            let request = Request::get(uri).body(Body::empty()).unwrap();
+            let response = tokio::time::timeout(Duration::from_secs(5), router.clone().oneshot(request))
+                .await
+                .expect("route waited on the engine")
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+            let content_type = response.headers()[CONTENT_TYPE].to_str().unwrap().to_string();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            bodies.push((content_type, String::from_utf8(body.to_vec()).unwrap()));
+        }
+
+        let health: Value = serde_json::from_str(&bodies[0].1).unwrap();
+        assert_eq!(health["model_loaded"], true);
+        assert_eq!(health["model_name"], "qwen2.5-7b");
+        assert_eq!(health["queue_depth"], 1);
+        assert!(bodies[1].0.starts_with("text/plain"));
+        assert!(bodies[1].1.lines().any(|line| line == "clawrs_requests_total 1"), "{}", bodies[1].1);
+    }
+}
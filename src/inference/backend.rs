@@ -0,0 +1,54 @@
+//! Inference backends
+//!
+//! `LlamaEngine` runs GGUF models in-process; `OpenAiCompatBackend` sends the
+//! same requests to a server (Ollama, llama-server, vLLM...). Both stream into
+//! the `StreamToken` channel the chat already reads.
+
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+use crate::inference::engine::{EngineError, GenerationParams, LlamaEngine};
+use crate::inference::streaming::StreamToken;
+use crate::types::message::Message as ChatMessage;
+
+/// Something that turns a conversation into a token stream
+pub trait InferenceBackend: Send + Sync {
+    /// Name of the model being served, as shown in the UI
+    fn model_name(&self) -> String;
+
+    /// Start a generation; it stops when the returned flag is raised
+    fn generate_stream_messages(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError>;
+
+    /// Stop the most recent generation
+    fn cancel_generation(&self);
+}
+
+impl InferenceBackend for LlamaEngine {
+    fn model_name(&self) -> String {
+        if let Some(remote) = self.remote_backend() {
+            return remote.model_name();
+        }
+        self.model_info()
+            .and_then(|info| Path::new(&info.path).file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn generate_stream_messages(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        LlamaEngine::generate_stream_messages(self, messages, params)
+    }
+
+    fn cancel_generation(&self) {
+        LlamaEngine::cancel_generation(self)
+    }
+}
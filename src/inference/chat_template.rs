@@ -0,0 +1,219 @@
+//! Chat templates of the common model families
+//!
+//! llama.cpp guesses the format from the GGUF template, and when it cannot
+//! the prompt falls back to a generic `User: ...` transcript the model was
+//! never trained on. The families here are rendered by hand instead; others
+//! still go through llama.cpp. BOS is left to the tokenizer.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+
+/// Prompt format of a model family
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatTemplate {
+    /// `<|im_start|>role ... <|im_end|>` (Qwen, Hermes, Yi)
+    ChatMl,
+    /// `<|start_header_id|>role<|end_header_id|>` (Llama 3.x)
+    Llama3,
+    /// `[INST] ... [/INST]` (Mistral, Mixtral, Llama 2)
+    Mistral,
+    /// `<|user|> ... <|end|>` (Phi-3)
+    Phi3,
+    /// `<start_of_turn>user ... <end_of_turn>` (Gemma)
+    Gemma,
+}
+
+impl ChatTemplate {
+    pub const ALL: [ChatTemplate; 5] =
+        [ChatTemplate::ChatMl, ChatTemplate::Llama3, ChatTemplate::Mistral, ChatTemplate::Phi3, ChatTemplate::Gemma];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ChatMl => "chat_ml",
+            Self::Llama3 => "llama3",
+            Self::Mistral => "mistral",
+            Self::Phi3 => "phi3",
+            Self::Gemma => "gemma",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|template| template.as_str() == value.trim())
+    }
+
+    /// Name shown in the model list
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ChatMl => "ChatML",
+            Self::Llama3 => "Llama 3",
+            Self::Mistral => "Mistral",
+            Self::Phi3 => "Phi-3",
+            Self::Gemma => "Gemma",
+        }
+    }
+
+    /// Family of a `tokenizer.chat_template`, from the markers it writes
+    pub fn detect(chat_template: &str) -> Option<Self> {
+        if chat_template.contains("<|im_start|>") {
+            Some(Self::ChatMl)
+        } else if chat_template.contains("<|start_header_id|>") {
+            Some(Self::Llama3)
+        } else if chat_template.contains("<start_of_turn>") {
+            Some(Self::Gemma)
+        } else if chat_template.contains("<|assistant|>") && chat_template.contains("<|end|>") {
+            Some(Self::Phi3)
+        } else if chat_template.contains("[INST]") {
+            Some(Self::Mistral)
+        } else {
+            None
+        }
+    }
+
+    /// Prompt for `messages`, ending where the assistant's reply starts
+    pub fn render(&self, messages: &[ChatMessage]) -> String {
+        let mut out = String::with_capacity(messages.iter().map(|m| m.content.len() + 32).sum());
+        match self {
+            Self::ChatMl => {
+                for msg in messages {
+                    match msg.role {
+                        ChatRole::System => out.push_str("<|im_start|>system\n"),
+                        ChatRole::User => out.push_str("<|im_start|>user\n"),
+                        ChatRole::Assistant => out.push_str("<|im_start|>assistant\n"),
+                        // Qwen and Hermes pass tool results as a user turn
+                        ChatRole::Tool => {
+                            out.push_str(&format!("<|im_start|>user\n<tool_response>\n{}\n</tool_response><|im_end|>\n", msg.content));
+                            continue;
+                        }
+                    }
+                    out.push_str(&msg.content);
+                    out.push_str("<|im_end|>\n");
+                }
+                out.push_str("<|im_start|>assistant\n");
+            }
+            Self::Llama3 => {
+                for msg in messages {
+                    let role = match msg.role {
+                        ChatRole::System => "system",
+                        ChatRole::User => "user",
+                        ChatRole::Assistant => "assistant",
+                        ChatRole::Tool => "ipython",
+                    };
+                    out.push_str(&format!("<|start_header_id|>{}<|end_header_id|>\n\n{}<|eot_id|>", role, msg.content.trim()));
+                }
+                out.push_str("<|start_header_id|>assistant<|end_header_id|>\n\n");
+            }
+            Self::Phi3 => {
+                for msg in messages {
+                    let role = match msg.role {
+                        ChatRole::System => "system",
+                        ChatRole::User | ChatRole::Tool => "user",
+                        ChatRole::Assistant => "assistant",
+                    };
+                    out.push_str(&format!("<|{}|>\n{}<|end|>\n", role, msg.content));
+                }
+                out.push_str("<|assistant|>\n");
+            }
+            // Neither has a system role and both require strict alternation
+            Self::Mistral => {
+                for (from_user, content) in alternating_turns(messages) {
+                    if from_user {
+                        out.push_str(&format!("[INST] {} [/INST]", content));
+                    } else {
+                        out.push_str(&format!("{}</s>", content));
+                    }
+                }
+            }
+            Self::Gemma => {
+                for (from_user, content) in alternating_turns(messages) {
+                    let role = if from_user { "user" } else { "model" };
+                    out.push_str(&format!("<start_of_turn>{}\n{}<end_of_turn>\n", role, content));
+                }
+                out.push_str("<start_of_turn>model\n");
+            }
+        }
+        out
+    }
+}
+
+/// Messages as user/assistant turns (`true` for the user side)
+///
+/// System prompts and tool results join the user side, and consecutive
+/// messages of one side are merged.
+fn alternating_turns(messages: &[ChatMessage]) -> Vec<(bool, String)> {
+    let mut turns: Vec<(bool, String)> = Vec::new();
+    for msg in messages {
+        let from_user = msg.role != ChatRole::Assistant;
+        let content = msg.content.trim();
+        match turns.last_mut() {
+            Some((side, text)) if *side == from_user => {
+                text.push_str("\n\n");
+                text.push_str(content);
+            }
+            _ => turns.push((from_user, content.to_string())),
+        }
+    }
+    turns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::new(ChatRole::System, "You are helpful."),
+            ChatMessage::new(ChatRole::User, "Read a.txt"),
+            ChatMessage::new(ChatRole::Assistant, "{\"tool\": \"file_read\", \"params\": {\"path\": \"a.txt\"}}"),
+            ChatMessage::new(ChatRole::Tool, "hello"),
+        ]
+    }
+
+    #[test]
+    fn test_detect_from_gguf_templates() {
+        let cases = [
+            ("{% for message in messages %}{{'<|im_start|>' + message['role'] + '\n' + message['content'] + '<|im_end|>' + '\n'}}{% endfor %}", Some(ChatTemplate::ChatMl)),
+            ("{{- '<|start_header_id|>' + message['role'] + '<|end_header_id|>\n\n'+ message['content'] | trim + '<|eot_id|>' }}", Some(ChatTemplate::Llama3)),
+            ("{{ bos_token }}{% for message in messages %}{% if message['role'] == 'user' %}{{ '[INST] ' + message['content'] + ' [/INST]' }}{% endif %}{% endfor %}", Some(ChatTemplate::Mistral)),
+            ("{% for message in messages %}{{'<|' + message['role'] + '|>' + '\n' + message['content'] + '<|end|>\n'}}{% endfor %}{{ '<|assistant|>\n' }}", Some(ChatTemplate::Phi3)),
+            ("{{ '<start_of_turn>' + role + '\n' + message['content'] | trim + '<end_of_turn>\n' }}", Some(ChatTemplate::Gemma)),
+            ("{{ message['content'] }}", None),
+        ];
+        for (template, expected) in cases {
+            assert_eq!(ChatTemplate::detect(template), expected, "{}", template);
+        }
+        for template in ChatTemplate::ALL {
+            assert_eq!(ChatTemplate::parse(template.as_str()), Some(template));
+        }
+    }
+
+    #[test]
+    fn test_render_families() {
+        let messages = conversation();
+        assert_eq!(
+            ChatTemplate::ChatMl.render(&messages),
+            "<|im_start|>system\nYou are helpful.<|im_end|>\n<|im_start|>user\nRead a.txt<|im_end|>\n\
+             <|im_start|>assistant\n{\"tool\": \"file_read\", \"params\": {\"path\": \"a.txt\"}}<|im_end|>\n\
+             <|im_start|>user\n<tool_response>\nhello\n</tool_response><|im_end|>\n<|im_start|>assistant\n"
+        );
+        let llama = ChatTemplate::Llama3.render(&messages);
+        assert!(llama.starts_with("<|start_header_id|>system<|end_header_id|>\n\nYou are helpful.<|eot_id|>"));
+        assert!(llama.contains("<|start_header_id|>ipython<|end_header_id|>\n\nhello<|eot_id|>"));
+        assert!(llama.ends_with("<|start_header_id|>assistant<|end_header_id|>\n\n"));
+        assert!(ChatTemplate::Phi3.render(&messages).ends_with("<|user|>\nhello<|end|>\n<|assistant|>\n"));
+    }
+
+    #[test]
+    fn test_render_merges_turns_without_system_role() {
+        let messages = conversation();
+        assert_eq!(
+            ChatTemplate::Mistral.render(&messages),
+            "[INST] You are helpful.\n\nRead a.txt [/INST]{\"tool\": \"file_read\", \"params\": {\"path\": \"a.txt\"}}</s>[INST] hello [/INST]"
+        );
+        let gemma = ChatTemplate::Gemma.render(&messages);
+        assert!(gemma.starts_with("<start_of_turn>user\nYou are helpful.\n\nRead a.txt<end_of_turn>\n<start_of_turn>model\n"));
+        assert!(gemma.ends_with("<start_of_turn>user\nhello<end_of_turn>\n<start_of_turn>model\n"));
+        assert_eq!(gemma.matches("<start_of_turn>user").count(), 2);
+    }
+}
@@ -0,0 +1,185 @@
+//! Automatic context sizing
+//!
+//! In auto mode the KV cache starts small and grows in steps as a conversation
+//! gets longer, instead of allocating the configured maximum up front. The
+//! ceiling is the smallest of the model's training context, the configured cap
+//! and what fits in free memory. Everything here is pure so growth decisions can
+//! be tested against made-up memory figures.
+
+/// Context sizes the engine allocates (reuse works best on a few fixed sizes)
+pub const CONTEXT_STEPS: [u32; 7] = [2048, 4096, 8192, 16384, 32768, 65536, 131072];
+
+/// Grow once the estimated need passes this share of the current allocation
+pub const GROW_AT: f32 = 0.9;
+
+/// Share of free memory the KV cache may take when growing
+pub const KV_MEMORY_SHARE: f64 = 0.5;
+
+/// Fallback KV cost when the model metadata lacks attention dimensions
+/// (roughly a 7B model with grouped-query attention, f16 cache)
+pub const DEFAULT_KV_BYTES_PER_TOKEN: u64 = 128 * 1024;
+
+/// What to do with the context before a generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextDecision {
+    /// The current allocation has room
+    Reuse,
+    /// No context yet, create one of this size
+    Allocate(u32),
+    /// Recreate the context at a larger size
+    Grow { from: u32, to: u32 },
+}
+
+impl ContextDecision {
+    /// Context size after applying the decision
+    pub fn size(&self, current: u32) -> u32 {
+        match *self {
+            ContextDecision::Reuse => current,
+            ContextDecision::Allocate(size) => size,
+            ContextDecision::Grow { to, .. } => to,
+        }
+    }
+}
+
+/// KV cache bytes per token for an f16 cache: keys and values for every layer
+pub fn kv_bytes_per_token(n_layer: u32, n_embd: u32, n_head: u32, n_head_kv: u32) -> u64 {
+    if n_layer == 0 || n_embd == 0 || n_head == 0 {
+        return DEFAULT_KV_BYTES_PER_TOKEN;
+    }
+    let n_head_kv = if n_head_kv == 0 { n_head } else { n_head_kv };
+    let kv_dim = n_embd as u64 / n_head as u64 * n_head_kv as u64;
+    2 * n_layer as u64 * kv_dim * 2
+}
+
+/// Largest context the engine may grow to.
+///
+/// `free_bytes` is free system memory while `current` tokens are allocated;
+/// that allocation is released before growing, so it counts as available.
+/// Unknown memory leaves the cap to the model and the setting.
+pub fn auto_context_limit(
+    model_max: u32,
+    configured_max: u32,
+    free_bytes: Option<u64>,
+    current: u32,
+    kv_bytes_per_token: u64,
+) -> u32 {
+    let mut limit = model_max.min(configured_max);
+    if let Some(free) = free_bytes {
+        let available = free + current as u64 * kv_bytes_per_token;
+        let fits = (available as f64 * KV_MEMORY_SHARE) as u64 / kv_bytes_per_token.max(1);
+        limit = limit.min(fits.min(u32::MAX as u64) as u32);
+    }
+    // Snap down to a step, but never below the smallest one
+    CONTEXT_STEPS
+        .iter()
+        .rev()
+        .copied()
+        .find(|&s| s <= limit)
+        .unwrap_or(CONTEXT_STEPS[0].min(model_max.max(1)))
+}
+
+/// Decide whether the context has to grow for `needed` tokens.
+///
+/// Growth leaves some headroom so the next turns of the same conversation do
+/// not reallocate again right away.
+pub fn plan_context(needed: u32, current: u32, limit: u32) -> ContextDecision {
+    let target = ((needed as f32 / GROW_AT).ceil() as u32).min(limit);
+    let size = CONTEXT_STEPS
+        .iter()
+        .copied()
+        .find(|&s| s >= target && s <= limit)
+        .unwrap_or(limit);
+
+    if current == 0 {
+        return ContextDecision::Allocate(size);
+    }
+    if current >= limit || needed as f32 <= current as f32 * GROW_AT || size <= current {
+        return ContextDecision::Reuse;
+    }
+    ContextDecision::Grow { from: current, to: size }
+}
+
+/// Next step below `size`, used to retry when a context allocation fails
+pub fn step_below(size: u32) -> Option<u32> {
+    CONTEXT_STEPS.iter().rev().copied().find(|&s| s < size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_kv_bytes_per_token() {
+        // Llama-2 7B: 32 layers, 4096 embd, no GQA -> 512 KiB per token
+        assert_eq!(kv_bytes_per_token(32, 4096, 32, 32), 512 * 1024);
+        // Qwen2.5 7B: 28 layers, 3584 embd, 28 heads, 4 KV heads
+        assert_eq!(kv_bytes_per_token(28, 3584, 28, 4), 2 * 28 * 512 * 2);
+        assert_eq!(kv_bytes_per_token(0, 0, 0, 0), DEFAULT_KV_BYTES_PER_TOKEN);
+    }
+
+    #[test]
+    fn test_limit_follows_free_memory() {
+        let kv = 128 * 1024;
+        // 8 GB free -> 4 GB for KV -> 32K tokens
+        assert_eq!(auto_context_limit(131072, 131072, Some(8 * GB), 0, kv), 32768);
+        // 1 GB free -> 4K tokens
+        assert_eq!(auto_context_limit(131072, 131072, Some(GB), 0, kv), 4096);
+        // Memory unknown: model and setting decide
+        assert_eq!(auto_context_limit(32768, 131072, None, 0, kv), 32768);
+        assert_eq!(auto_context_limit(131072, 16384, None, 0, kv), 16384);
+        // Nearly no memory still allows the smallest step
+        assert_eq!(auto_context_limit(131072, 131072, Some(0), 0, kv), 2048);
+    }
+
+    #[test]
+    fn test_limit_counts_current_allocation_as_available() {
+        let kv = 128 * 1024;
+        // 16K context (2 GB) allocated, 2 GB free: 4 GB after release -> 16K
+        assert_eq!(auto_context_limit(131072, 131072, Some(2 * GB), 16384, kv), 16384);
+        assert_eq!(auto_context_limit(131072, 131072, Some(2 * GB), 0, kv), 8192);
+    }
+
+    #[test]
+    fn test_plan_allocates_smallest_fitting_step() {
+        assert_eq!(plan_context(1000, 0, 32768), ContextDecision::Allocate(2048));
+        assert_eq!(plan_context(4500, 0, 32768), ContextDecision::Allocate(8192));
+        // Bounded by the limit
+        assert_eq!(plan_context(20000, 0, 8192), ContextDecision::Allocate(8192));
+    }
+
+    #[test]
+    fn test_plan_grows_as_conversation_approaches_allocation() {
+        assert_eq!(plan_context(3000, 4096, 32768), ContextDecision::Reuse);
+        assert_eq!(
+            plan_context(3800, 4096, 32768),
+            ContextDecision::Grow { from: 4096, to: 8192 }
+        );
+        assert_eq!(
+            plan_context(9000, 4096, 32768),
+            ContextDecision::Grow { from: 4096, to: 16384 }
+        );
+    }
+
+    #[test]
+    fn test_plan_stops_growing_at_memory_limit() {
+        let kv = 128 * 1024;
+        // Conversation keeps growing while only 2 GB are free
+        let limit = auto_context_limit(131072, 131072, Some(2 * GB), 0, kv);
+        assert_eq!(limit, 8192);
+        let mut current = 0;
+        for needed in [1500, 3000, 6000, 7900, 12000] {
+            current = plan_context(needed, current, limit).size(current);
+        }
+        assert_eq!(current, 8192);
+        assert_eq!(plan_context(20000, 8192, limit), ContextDecision::Reuse);
+    }
+
+    #[test]
+    fn test_step_below() {
+        assert_eq!(step_below(16384), Some(8192));
+        assert_eq!(step_below(3000), Some(2048));
+        assert_eq!(step_below(2048), None);
+    }
+}
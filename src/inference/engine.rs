@@ -14,13 +14,26 @@
 //! Creating a new context allocates VRAM and can take 2-5 seconds.
 //! Reusing it with a KV cache clear is nearly instant.
 //! This is what makes Ollama/LMStudio fast.
+//!
+//! Within a `GenerationSession` (one agent run) the KV cache itself is kept
+//! too: each prompt only evaluates the tokens after the longest prefix it
+//! shares with the previous one.
+//!
+//! With a draft model configured, a small model of the same family proposes a
+//! few tokens at a time and the main model verifies them in one batch
+//! (speculative decoding). Tokens are still streamed one by one.
+//!
+//! The worker also holds an optional embedding model, independent of the chat
+//! model, used through `Embedder` by the document index.
 
 use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use llama_cpp_2::context::params::LlamaContextParams;
 use llama_cpp_2::context::LlamaContext;
@@ -29,10 +42,19 @@ use llama_cpp_2::llama_batch::LlamaBatch;
 use llama_cpp_2::model::params::LlamaModelParams;
 use llama_cpp_2::model::{AddBos, LlamaChatMessage, LlamaModel, Special};
 use llama_cpp_2::sampling::LlamaSampler;
+use llama_cpp_2::token::LlamaToken;
 use thiserror::Error;
 
-use crate::inference::model::{validate_gguf, ModelError};
-use crate::inference::streaming::StreamToken;
+use crate::inference::backend::InferenceBackend;
+use crate::inference::chat_template::ChatTemplate;
+use crate::inference::context::{self, ContextDecision};
+use crate::inference::metrics::EngineMetrics;
+use crate::inference::model::{check_gguf_layout, validate_gguf, ModelError, RopeScaling};
+use crate::inference::reasoning;
+use crate::inference::tokens;
+use crate::inference::streaming::{GenerationStats, StopReason, StopSequences, StreamToken};
+use crate::storage::settings::ToolCallFormat;
+use crate::system::resources::free_ram_bytes;
 use crate::types::message::{Message as ChatMessage, Role as ChatRole};
 
 /// Errors that can occur during inference operations
@@ -50,6 +72,9 @@ pub enum EngineError {
     #[error("Failed to load model: {0}")]
     ModelLoad(String),
 
+    #[error("Model load cancelled")]
+    LoadCancelled,
+
     #[error("Failed to create context: {0}")]
     ContextCreate(String),
 
@@ -79,9 +104,23 @@ pub struct GenerationParams {
     pub temperature: f32,
     pub top_k: u32,
     pub top_p: f32,
+    /// Drop tokens below this fraction of the top token's probability (0 = off)
+    pub min_p: f32,
     pub repeat_penalty: f32,
     pub seed: u32,
+    /// Pinned context size, or the cap when `auto_context` is set
     pub max_context_size: u32,
+    /// Grow the context with the conversation, bounded by model and memory
+    pub auto_context: bool,
+    /// GBNF grammar the output must follow (rule `root`), e.g. strict tool calls
+    pub grammar: Option<String>,
+    /// Generation ends (with `Done`) once one of these is produced; the
+    /// sequence itself is not streamed
+    pub stop_sequences: Vec<String>,
+    /// Penalize tokens by how often they already appeared (0 = off)
+    pub frequency_penalty: f32,
+    /// Penalize tokens that already appeared at all (0 = off)
+    pub presence_penalty: f32,
 }
 
 impl Default for GenerationParams {
@@ -91,9 +130,15 @@ impl Default for GenerationParams {
             temperature: 0.7,
             top_k: 40,
             top_p: 0.95,
+            min_p: 0.0,
             repeat_penalty: 1.1,
             seed: 0,
             max_context_size: 16384, // 16K context - validated with LM Studio on 8GB VRAM
+            auto_context: false,
+            grammar: None,
+            stop_sequences: Vec::new(),
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
         }
     }
 }
@@ -105,9 +150,15 @@ impl GenerationParams {
             temperature: 0.0,
             top_k: 1,
             top_p: 1.0,
+            min_p: 0.0,
             repeat_penalty: 1.0,
             seed: 0,
             max_context_size: 4096,
+            auto_context: false,
+            grammar: None,
+            stop_sequences: Vec::new(),
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
         }
     }
     
@@ -117,9 +168,15 @@ impl GenerationParams {
             temperature: 0.7,
             top_k: 40,
             top_p: 0.9,
+            min_p: 0.0,
             repeat_penalty: 1.1,
             seed: 0,
             max_context_size: 8192,
+            auto_context: false,
+            grammar: None,
+            stop_sequences: Vec::new(),
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
         }
     }
     
@@ -129,9 +186,15 @@ impl GenerationParams {
             temperature: 0.8,
             top_k: 50,
             top_p: 0.95,
+            min_p: 0.0,
             repeat_penalty: 1.1,
             seed: 0,
             max_context_size: 16384,
+            auto_context: false,
+            grammar: None,
+            stop_sequences: Vec::new(),
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
         }
     }
 }
@@ -142,9 +205,177 @@ pub struct LoadedModelInfo {
     pub path: String,
     pub vocab_size: i32,
     pub embedding_dim: i32,
+    /// Context the model was trained for (n_ctx_train)
     pub context_length: u32,
+    /// RoPE scaling its metadata declares
+    pub rope_scaling: Option<RopeScaling>,
     pub param_count: u64,
     pub size_bytes: u64,
+    /// Reasoning model according to its name and chat template
+    pub reasoning: bool,
+    /// Tool call format its chat template was trained on
+    pub tool_call_format: ToolCallFormat,
+    /// Chat template prompts are rendered with (None = llama.cpp's own)
+    pub chat_template: Option<ChatTemplate>,
+    /// Draft model used for speculative decoding, when it loaded and its
+    /// vocabulary matches
+    pub draft: Option<String>,
+}
+
+/// How long a token count waits for a busy worker before estimating
+const COUNT_TOKENS_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Recent tokens the frequency and presence penalties look at
+const PENALTY_LAST_N: i32 = 64;
+
+/// Tokens the draft model proposes per verification step
+const DRAFT_TOKENS: usize = 5;
+
+/// Largest vocabulary size difference tolerated between draft and main model
+///
+/// Qwen checkpoints of one family pad the same 151,665-token vocabulary to
+/// 151,936 or 152,064 entries depending on size; the token texts are still
+/// compared, so this only has to cover the padding.
+const DRAFT_VOCAB_MAX_DIFF: i32 = 512;
+
+/// Tokens whose text is compared between the two vocabularies
+const DRAFT_VOCAB_CHECKED: i32 = 512;
+
+/// Context of the embedding model; longer texts are cut to fit
+const EMBED_MAX_CTX: u32 = 2048;
+
+/// Size of the reads that pull model weights into the page cache
+const LOAD_CHUNK_BYTES: usize = 8 * 1024 * 1024;
+
+/// Phases of a model load, see [`LoadControl::phase`]
+const PHASE_READING: u8 = 0;
+const PHASE_BUILDING: u8 = 1;
+const PHASE_CANCELLED: u8 = 2;
+
+/// Progress and cancellation of one model load
+///
+/// A load has two phases: reading the weights into the page cache, which
+/// reports progress and can be cancelled, then llama.cpp building the model
+/// (and uploading GPU layers), which can do neither. The loading task holds
+/// the engine lock until it is done, so the UI keeps its own clone of this
+/// handle to poll progress and request cancellation.
+#[derive(Debug)]
+pub struct LoadControl {
+    bytes_read: AtomicU64,
+    total_bytes: AtomicU64,
+    phase: AtomicU8,
+    finished: AtomicBool,
+    started: Instant,
+}
+
+/// Where a model load is, as seen from the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadPhase {
+    /// Reading the weights; progress is known and cancel is honoured
+    Reading,
+    /// llama.cpp is building the model; no progress, no cancel
+    Building,
+    /// Cancelled while reading; the worker stops at the next chunk
+    Cancelled,
+}
+
+impl LoadControl {
+    pub fn new() -> Self {
+        Self {
+            bytes_read: AtomicU64::new(0),
+            total_bytes: AtomicU64::new(0),
+            phase: AtomicU8::new(PHASE_READING),
+            finished: AtomicBool::new(false),
+            started: Instant::now(),
+        }
+    }
+
+    /// Ask the worker to abort while it is still reading the weights
+    ///
+    /// Returns false once llama.cpp has the load: it cannot be interrupted,
+    /// so the load runs to its end and its result stands.
+    pub fn cancel(&self) -> bool {
+        self.phase
+            .compare_exchange(PHASE_READING, PHASE_CANCELLED, Ordering::AcqRel, Ordering::Acquire)
+            .map_or_else(|phase| phase == PHASE_CANCELLED, |_| true)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.phase() == LoadPhase::Cancelled
+    }
+
+    pub fn phase(&self) -> LoadPhase {
+        match self.phase.load(Ordering::Acquire) {
+            PHASE_READING => LoadPhase::Reading,
+            PHASE_BUILDING => LoadPhase::Building,
+            _ => LoadPhase::Cancelled,
+        }
+    }
+
+    /// Hand the load to llama.cpp, unless it was cancelled first
+    fn start_building(&self) -> bool {
+        self.phase
+            .compare_exchange(PHASE_READING, PHASE_BUILDING, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    /// Whether the worker is done with this load (loaded, failed or cancelled)
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    /// Fraction of the weights read, in `[0, 1]`
+    ///
+    /// Reaches 1 when llama.cpp takes over, not when the model is loaded;
+    /// check [`LoadControl::phase`] for that part.
+    pub fn progress(&self) -> f32 {
+        if self.phase() == LoadPhase::Building || self.is_finished() {
+            return 1.0;
+        }
+        let total = self.total_bytes.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        let read = self.bytes_read.load(Ordering::Relaxed).min(total);
+        read as f32 / total as f32
+    }
+
+    /// Estimated time until the weights are read, from the read rate so far
+    pub fn eta(&self) -> Option<Duration> {
+        estimate_remaining(
+            self.bytes_read.load(Ordering::Relaxed),
+            self.total_bytes.load(Ordering::Relaxed),
+            self.started.elapsed(),
+        )
+    }
+}
+
+impl Default for LoadControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Time left to read `total` bytes at the rate `done` bytes took `elapsed`
+///
+/// `None` before anything is read and once reading is complete.
+fn estimate_remaining(done: u64, total: u64, elapsed: Duration) -> Option<Duration> {
+    if done == 0 || done >= total {
+        return None;
+    }
+    let rate = done as f64 / elapsed.as_secs_f64().max(0.001);
+    Some(Duration::from_secs_f64((total - done) as f64 / rate))
+}
+
+/// Queue a generation runs in
+///
+/// Interactive requests (the chat) always go before queued background work
+/// (experiments, embeddings and other side jobs); a running generation is
+/// never preempted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    Interactive,
+    Background,
 }
 
 /// Commands sent to the worker thread
@@ -152,26 +383,122 @@ enum WorkerCommand {
     Init,
     LoadModel {
         path: PathBuf,
+        draft_path: Option<PathBuf>,
+        /// Template forced on the model instead of the one its metadata names
+        chat_template: Option<ChatTemplate>,
         gpu_layers: u32,
+        control: Arc<LoadControl>,
         response_tx: Sender<Result<LoadedModelInfo, EngineError>>,
     },
     UnloadModel,
+    CountTokens {
+        texts: Vec<String>,
+        response_tx: Sender<Result<Vec<usize>, EngineError>>,
+    },
+    /// Embeddings of `texts`, loading the embedding model at `model_path` if
+    /// it is not the one held
+    Embed {
+        model_path: PathBuf,
+        gpu_layers: u32,
+        texts: Vec<String>,
+        response_tx: Sender<Result<Vec<Vec<f32>>, EngineError>>,
+    },
     Generate {
         messages: Vec<ChatMessage>,
         params: GenerationParams,
         token_tx: Sender<StreamToken>,
         stop_signal: Arc<AtomicBool>,
+        enqueued_at: std::time::Instant,
+        lane: Lane,
+        /// Session whose KV cache the prompt may reuse
+        session: Option<u64>,
     },
+    /// A session was dropped: its cached prompt is no longer needed
+    EndSession { id: u64 },
     Shutdown,
 }
 
+impl WorkerCommand {
+    fn is_background(&self) -> bool {
+        matches!(
+            self,
+            WorkerCommand::Generate { lane: Lane::Background, .. } | WorkerCommand::Embed { .. }
+        )
+    }
+}
+
 /// The main LLM inference engine using llama-cpp-2
 pub struct LlamaEngine {
     command_tx: Option<Sender<WorkerCommand>>,
     worker_handle: Option<JoinHandle<()>>,
     model_info: Option<LoadedModelInfo>,
+    /// Draft model loaded along with the next model, for speculative decoding
+    draft_model_path: Option<PathBuf>,
+    /// Chat template forced on the next model loaded
+    chat_template_override: Option<ChatTemplate>,
+    /// Server generating in place of the local model, when one is connected
+    remote: Option<Arc<dyn InferenceBackend>>,
     initialized: bool,
     model_loaded: bool,
+    /// Stop signal of the most recent generation (for `cancel_generation`)
+    active_stop: std::sync::Mutex<Option<Arc<AtomicBool>>>,
+    /// Lock-free counters for health/metrics endpoints
+    metrics: Arc<EngineMetrics>,
+    next_session_id: AtomicU64,
+}
+
+/// Keeps the KV cache of one generation session alive
+///
+/// Generations through `LlamaEngine::generate_stream_session` only evaluate
+/// the part of their prompt that differs from the previous one in the same
+/// session. Dropping the handle lets the worker release the cached prompt.
+pub struct GenerationSession {
+    id: u64,
+    command_tx: Sender<WorkerCommand>,
+}
+
+impl GenerationSession {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for GenerationSession {
+    fn drop(&mut self) {
+        let _ = self.command_tx.send(WorkerCommand::EndSession { id: self.id });
+    }
+}
+
+/// Computes embeddings with one GGUF embedding model on the worker thread
+///
+/// Requests go through the background lane, so the chat is served first.
+/// The handle does not borrow the engine: long indexing jobs run without
+/// holding its lock.
+#[derive(Clone)]
+pub struct Embedder {
+    model_path: PathBuf,
+    gpu_layers: u32,
+    command_tx: Sender<WorkerCommand>,
+}
+
+impl Embedder {
+    /// Unit-length embedding of each text, in order
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EngineError> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.command_tx
+            .send(WorkerCommand::Embed {
+                model_path: self.model_path.clone(),
+                gpu_layers: self.gpu_layers,
+                texts,
+                response_tx,
+            })
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+
+        tokio::task::spawn_blocking(move || response_rx.recv())
+            .await
+            .map_err(|e| EngineError::WorkerError(format!("Task join error: {}", e)))?
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?
+    }
 }
 
 impl LlamaEngine {
@@ -180,20 +507,59 @@ impl LlamaEngine {
             command_tx: None,
             worker_handle: None,
             model_info: None,
+            draft_model_path: None,
+            chat_template_override: None,
+            remote: None,
             initialized: false,
             model_loaded: false,
+            active_stop: std::sync::Mutex::new(None),
+            metrics: Arc::new(EngineMetrics::new()),
+            next_session_id: AtomicU64::new(1),
         }
     }
 
+    /// Draft model for speculative decoding, loaded with the next model
+    ///
+    /// A draft that fails to load or whose vocabulary does not match the
+    /// model's is skipped, and generation decodes normally.
+    pub fn set_draft_model(&mut self, path: Option<PathBuf>) {
+        self.draft_model_path = path;
+    }
+
+    /// Chat template for the next model loaded; `None` uses the one its
+    /// GGUF metadata names, or llama.cpp's guess for other families
+    pub fn set_chat_template(&mut self, template: Option<ChatTemplate>) {
+        self.chat_template_override = template;
+    }
+
+    /// Shared metrics handle; clone it once so readers never need the engine lock
+    /// Send generations to `backend` instead of the local model
+    ///
+    /// Sessions, lanes and token counting stay local: a remote generation
+    /// evaluates its whole prompt and token counts are estimated.
+    pub fn set_remote_backend(&mut self, backend: Option<Arc<dyn InferenceBackend>>) {
+        self.remote = backend;
+    }
+
+    /// Connected remote backend, if any
+    pub fn remote_backend(&self) -> Option<&Arc<dyn InferenceBackend>> {
+        self.remote.as_ref()
+    }
+
+    pub fn metrics(&self) -> Arc<EngineMetrics> {
+        self.metrics.clone()
+    }
+
     pub fn init(&mut self) -> Result<(), EngineError> {
         if self.initialized {
             return Ok(());
         }
 
         let (command_tx, command_rx) = mpsc::channel::<WorkerCommand>();
+        let metrics = self.metrics.clone();
 
         let handle = thread::spawn(move || {
-            worker_thread_main(command_rx);
+            worker_thread_main(command_rx, metrics);
         });
 
         self.command_tx = Some(command_tx.clone());
@@ -212,6 +578,20 @@ impl LlamaEngine {
         &mut self,
         path: P,
         gpu_layers: u32,
+    ) -> Result<LoadedModelInfo, EngineError> {
+        self.load_model_with_control(path, gpu_layers, Arc::new(LoadControl::new()))
+            .await
+    }
+
+    /// Load a model, reporting progress and honouring cancellation through `control`
+    ///
+    /// Any previously loaded model is released first, so on error (including
+    /// [`EngineError::LoadCancelled`]) the engine is left with no model.
+    pub async fn load_model_with_control<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        gpu_layers: u32,
+        control: Arc<LoadControl>,
     ) -> Result<LoadedModelInfo, EngineError> {
         let command_tx = self
             .command_tx
@@ -227,11 +607,19 @@ impl LlamaEngine {
         command_tx
             .send(WorkerCommand::LoadModel {
                 path,
+                draft_path: self.draft_model_path.clone(),
+                chat_template: self.chat_template_override,
                 gpu_layers,
+                control,
                 response_tx,
             })
             .map_err(|e| EngineError::WorkerError(e.to_string()))?;
 
+        // The worker has dropped the previous model by now
+        self.model_info = None;
+        self.model_loaded = false;
+        self.metrics.set_model(None);
+
         // Use spawn_blocking to not block the async runtime
         let result = tokio::task::spawn_blocking(move || {
             response_rx.recv()
@@ -242,10 +630,30 @@ impl LlamaEngine {
 
         self.model_info = Some(result.clone());
         self.model_loaded = true;
+        self.metrics.set_model(Some(model_display_name(&result.path)));
 
         Ok(result)
     }
 
+    /// Replace the loaded model with another one
+    ///
+    /// The new file is validated first, so a bad path leaves the current model
+    /// in place. Then any generation in progress is cancelled and the current
+    /// model released before loading, so the two never sit in memory together.
+    pub async fn swap_model<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        gpu_layers: u32,
+        control: Arc<LoadControl>,
+    ) -> Result<LoadedModelInfo, EngineError> {
+        let path = path.as_ref();
+        validate_gguf(path)?;
+        self.cancel_generation();
+        self.unload_model();
+        tracing::info!("Swapping model to {}", path.display());
+        self.load_model_with_control(path, gpu_layers, control).await
+    }
+
     /// Synchronous version for backward compatibility (blocks!)
     pub fn load_model<P: AsRef<Path>>(
         &mut self,
@@ -265,17 +673,25 @@ impl LlamaEngine {
         command_tx
             .send(WorkerCommand::LoadModel {
                 path: path.to_path_buf(),
+                draft_path: self.draft_model_path.clone(),
+                chat_template: self.chat_template_override,
                 gpu_layers,
+                control: Arc::new(LoadControl::new()),
                 response_tx,
             })
             .map_err(|e| EngineError::WorkerError(e.to_string()))?;
 
+        self.model_info = None;
+        self.model_loaded = false;
+        self.metrics.set_model(None);
+
         let result = response_rx
             .recv()
             .map_err(|e| EngineError::WorkerError(e.to_string()))??;
 
         self.model_info = Some(result.clone());
         self.model_loaded = true;
+        self.metrics.set_model(Some(model_display_name(&result.path)));
 
         Ok(result)
     }
@@ -286,6 +702,8 @@ impl LlamaEngine {
         }
         self.model_info = None;
         self.model_loaded = false;
+        self.remote = None;
+        self.metrics.set_model(None);
         tracing::info!("Model unload requested");
     }
 
@@ -293,8 +711,9 @@ impl LlamaEngine {
         self.model_info.as_ref()
     }
 
+    /// A local model is loaded or a remote backend connected
     pub fn is_model_loaded(&self) -> bool {
-        self.model_loaded
+        self.model_loaded || self.remote.is_some()
     }
 
     pub fn is_initialized(&self) -> bool {
@@ -315,6 +734,71 @@ impl LlamaEngine {
         messages: Vec<ChatMessage>,
         params: GenerationParams,
     ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        self.generate_interactive(messages, params, None)
+    }
+
+    /// Start a session for a run of related generations (the iterations of
+    /// one agent turn); hold it for the run and drop it afterwards
+    pub fn begin_session(&self) -> Result<GenerationSession, EngineError> {
+        let command_tx = self
+            .command_tx
+            .as_ref()
+            .ok_or(EngineError::BackendNotInitialized)?;
+        Ok(GenerationSession {
+            id: self.next_session_id.fetch_add(1, Ordering::Relaxed),
+            command_tx: command_tx.clone(),
+        })
+    }
+
+    /// Like `generate_stream_messages`, reusing the KV cache of the session's
+    /// previous generation for the shared prompt prefix
+    pub fn generate_stream_session(
+        &self,
+        session: &GenerationSession,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        self.generate_interactive(messages, params, Some(session.id))
+    }
+
+    fn generate_interactive(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+        session: Option<u64>,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        let (token_rx, stop_signal) = self.enqueue_generation(messages, params, Lane::Interactive, session)?;
+
+        if let Ok(mut active) = self.active_stop.lock() {
+            *active = Some(stop_signal.clone());
+        }
+
+        Ok((token_rx, stop_signal))
+    }
+
+    /// Queue a generation on the background lane.
+    ///
+    /// It runs once no interactive request is waiting, and `cancel_generation`
+    /// does not touch it: stop it through the returned signal.
+    pub fn generate_stream_background(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        self.enqueue_generation(messages, params, Lane::Background, None)
+    }
+
+    fn enqueue_generation(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+        lane: Lane,
+        session: Option<u64>,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        if let Some(remote) = &self.remote {
+            return remote.generate_stream_messages(messages, params);
+        }
+
         let command_tx = self
             .command_tx
             .as_ref()
@@ -333,11 +817,85 @@ impl LlamaEngine {
                 params,
                 token_tx,
                 stop_signal: stop_signal.clone(),
+                enqueued_at: std::time::Instant::now(),
+                lane,
+                session,
             })
             .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+        self.metrics.record_enqueued();
 
         Ok((token_rx, stop_signal))
     }
+
+    /// Token count of `text` with the loaded model's tokenizer
+    ///
+    /// Falls back to the chars/4 estimate when no model is loaded or the worker
+    /// is busy generating.
+    pub async fn count_tokens(&self, text: &str) -> usize {
+        match self.tokenize_counts(vec![text.to_string()]).await {
+            Ok(counts) => counts[0],
+            Err(_) => tokens::estimate_tokens(text),
+        }
+    }
+
+    /// Exact token counts of several texts, in one round trip to the worker
+    ///
+    /// Fails when no model is loaded or the worker stays busy past a short
+    /// timeout, so callers can estimate instead and count again later.
+    pub async fn tokenize_counts(&self, texts: Vec<String>) -> Result<Vec<usize>, EngineError> {
+        let command_tx = self
+            .command_tx
+            .as_ref()
+            .ok_or(EngineError::BackendNotInitialized)?;
+        if !self.model_loaded {
+            return Err(EngineError::NoModelLoaded);
+        }
+
+        let (response_tx, response_rx) = mpsc::channel();
+        command_tx
+            .send(WorkerCommand::CountTokens { texts, response_tx })
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+
+        tokio::task::spawn_blocking(move || response_rx.recv_timeout(COUNT_TOKENS_TIMEOUT))
+            .await
+            .map_err(|e| EngineError::WorkerError(format!("Task join error: {}", e)))?
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?
+    }
+
+    /// Embedding handle for the GGUF model at `model_path`
+    ///
+    /// The worker loads the model on the first request, next to the chat model,
+    /// and keeps it until another embedding model is asked for.
+    pub fn embedder(&self, model_path: PathBuf, gpu_layers: u32) -> Result<Embedder, EngineError> {
+        let command_tx = self
+            .command_tx
+            .as_ref()
+            .ok_or(EngineError::BackendNotInitialized)?
+            .clone();
+        validate_gguf(&model_path)?;
+        Ok(Embedder { model_path, gpu_layers, command_tx })
+    }
+
+    /// Cancel the most recent generation.
+    ///
+    /// The worker checks the stop signal before every token (and between prompt
+    /// batches), so it is free for the next request almost immediately.
+    pub fn cancel_generation(&self) {
+        if let Ok(active) = self.active_stop.lock() {
+            if let Some(stop) = active.as_ref() {
+                stop.store(true, Ordering::Relaxed);
+                tracing::info!("Generation cancellation requested");
+            }
+        }
+    }
+}
+
+/// Model name shown in health output: the file stem of the model path
+fn model_display_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
 }
 
 impl Default for LlamaEngine {
@@ -372,25 +930,101 @@ struct WorkerState {
     ctx_n_ctx: u32,
     /// Current batch size (needed to verify reuse compatibility)
     ctx_n_batch: u32,
+    /// Tokens in the KV cache and the session they belong to
+    prompt_cache: Option<PromptCache>,
+    /// Draft model for speculative decoding; dropped before `model`
+    draft: Option<DraftState>,
+    /// Template prompts of the loaded model are rendered with
+    chat_template: Option<ChatTemplate>,
+    /// Embedding model of the document index, unrelated to `model`
+    embedder: Option<EmbedderState>,
+    /// KV cache cost of the loaded model, for auto context sizing
+    kv_bytes_per_token: u64,
     /// Optimal thread count (cached)
     n_threads: i32,
+    /// Shared with `LlamaEngine` for health/metrics
+    metrics: Arc<EngineMetrics>,
 }
 
 impl WorkerState {
-    fn new() -> Self {
+    fn new(metrics: Arc<EngineMetrics>) -> Self {
         Self {
             backend: None,
             model: None,
             ctx: None,
             ctx_n_ctx: 0,
             ctx_n_batch: 0,
+            prompt_cache: None,
+            draft: None,
+            chat_template: None,
+            embedder: None,
+            kv_bytes_per_token: context::DEFAULT_KV_BYTES_PER_TOKEN,
             n_threads: get_optimal_threads(),
+            metrics,
+        }
+    }
+}
+
+/// Small model proposing tokens for the main one to verify
+struct DraftState {
+    /// Declared before `model` so it is dropped first (it borrows the model)
+    ctx: Option<LlamaContext<'static>>,
+    ctx_n_ctx: u32,
+    model: LlamaModel,
+}
+
+/// Embedding model and its context, created on the first request
+struct EmbedderState {
+    /// Declared before `model` so it is dropped first (it borrows the model)
+    ctx: Option<LlamaContext<'static>>,
+    ctx_n_ctx: u32,
+    model: LlamaModel,
+    path: PathBuf,
+}
+
+/// What the KV cache holds after a session generation, positions 0..len
+struct PromptCache {
+    session: u64,
+    tokens: Vec<LlamaToken>,
+}
+
+/// Prompt tokens that can stay in the KV cache: the prefix shared with the
+/// cached tokens, always leaving the last prompt token to evaluate (its logits
+/// start the generation)
+fn reusable_prefix<T: PartialEq>(cached: &[T], prompt: &[T]) -> usize {
+    let shared = cached.iter().zip(prompt).take_while(|(a, b)| a == b).count();
+    shared.min(prompt.len().saturating_sub(1))
+}
+
+/// Next command for the worker: everything already queued on the interactive
+/// lane first, then the oldest background job; blocks only when both are empty
+fn next_command<T>(
+    rx: &Receiver<T>,
+    background: &mut VecDeque<T>,
+    is_background: impl Fn(&T) -> bool,
+) -> Option<T> {
+    loop {
+        match rx.try_recv() {
+            Ok(cmd) if is_background(&cmd) => background.push_back(cmd),
+            Ok(cmd) => return Some(cmd),
+            Err(TryRecvError::Empty) => {
+                if let Some(cmd) = background.pop_front() {
+                    return Some(cmd);
+                }
+                match rx.recv() {
+                    Ok(cmd) if is_background(&cmd) => background.push_back(cmd),
+                    Ok(cmd) => return Some(cmd),
+                    Err(_) => return None,
+                }
+            }
+            Err(TryRecvError::Disconnected) => return background.pop_front(),
         }
     }
 }
 
-fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
-    let mut state = WorkerState::new();
+fn worker_thread_main(command_rx: Receiver<WorkerCommand>, metrics: Arc<EngineMetrics>) {
+    let mut state = WorkerState::new(metrics);
+    let mut background = VecDeque::new();
     
     // We use unsafe to create a self-referential struct where ctx borrows model.
     // This is safe because:
@@ -399,8 +1033,8 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
     // 3. The model is never moved while the context exists
 
     loop {
-        match command_rx.recv() {
-            Ok(WorkerCommand::Init) => {
+        match next_command(&command_rx, &mut background, WorkerCommand::is_background) {
+            Some(WorkerCommand::Init) => {
                 match LlamaBackend::init() {
                     Ok(b) => {
                         state.backend = Some(b);
@@ -411,59 +1045,133 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
                     }
                 }
             }
-            Ok(WorkerCommand::LoadModel {
+            Some(WorkerCommand::LoadModel {
                 path,
+                draft_path,
+                chat_template,
                 gpu_layers,
+                control,
                 response_tx,
             }) => {
                 // Drop existing context FIRST (before model)
+                state.prompt_cache = None;
+                state.draft = None;
                 state.ctx = None;
                 state.ctx_n_ctx = 0;
                 state.ctx_n_batch = 0;
                 state.model = None;
+                state.chat_template = None;
+                state.metrics.set_context(0, 0);
                 
-                match load_model_internal(&state.backend, &path, gpu_layers) {
-                    Ok((info, loaded_model)) => {
+                let result = load_model_internal(&state.backend, &path, gpu_layers, &control);
+                control.finished.store(true, Ordering::Relaxed);
+                match result {
+                    Ok((mut info, loaded_model)) => {
+                        state.kv_bytes_per_token = model_kv_bytes_per_token(&loaded_model);
+                        if let Some(draft_path) = draft_path {
+                            match load_draft_model(&state.backend, &loaded_model, &draft_path, gpu_layers) {
+                                Ok(draft) => {
+                                    info.draft = Some(draft_path.to_string_lossy().to_string());
+                                    state.draft = Some(DraftState { ctx: None, ctx_n_ctx: 0, model: draft });
+                                }
+                                Err(e) => tracing::warn!("Draft model not used, decoding normally: {}", e),
+                            }
+                        }
+                        if chat_template.is_some() {
+                            info.chat_template = chat_template;
+                        }
+                        tracing::info!("Chat template: {}", info.chat_template.map_or("llama.cpp", |t| t.label()));
+                        state.chat_template = info.chat_template;
                         state.model = Some(loaded_model);
                         let _ = response_tx.send(Ok(info));
                     }
                     Err(e) => {
+                        tracing::warn!("Model load failed: {}", e);
                         let _ = response_tx.send(Err(e));
                     }
                 }
             }
-            Ok(WorkerCommand::UnloadModel) => {
+            Some(WorkerCommand::UnloadModel) => {
                 // Drop context FIRST, then model
+                state.prompt_cache = None;
+                state.draft = None;
                 state.ctx = None;
                 state.ctx_n_ctx = 0;
                 state.ctx_n_batch = 0;
                 state.model = None;
+                state.chat_template = None;
+                state.metrics.set_context(0, 0);
                 tracing::info!("Model and context unloaded");
             }
-            Ok(WorkerCommand::Generate {
+            Some(WorkerCommand::CountTokens { texts, response_tx }) => {
+                let counts = match state.model.as_ref() {
+                    Some(model) => texts
+                        .iter()
+                        .map(|text| {
+                            model
+                                .str_to_token(text, AddBos::Never)
+                                .map(|tokens| tokens.len())
+                                .map_err(|e| EngineError::Tokenization(e.to_string()))
+                        })
+                        .collect(),
+                    None => Err(EngineError::NoModelLoaded),
+                };
+                let _ = response_tx.send(counts);
+            }
+            Some(WorkerCommand::Embed {
+                model_path,
+                gpu_layers,
+                texts,
+                response_tx,
+            }) => {
+                let result = run_embeddings(&mut state, &model_path, gpu_layers, &texts);
+                if let Err(e) = &result {
+                    tracing::warn!("Embedding failed: {}", e);
+                }
+                let _ = response_tx.send(result);
+            }
+            Some(WorkerCommand::Generate {
                 messages,
                 params,
                 token_tx,
                 stop_signal,
+                enqueued_at,
+                session,
+                ..
             }) => {
+                state.metrics.record_started(enqueued_at.elapsed());
                 if state.backend.is_none() || state.model.is_none() {
+                    state.metrics.record_failed();
                     let _ = token_tx.send(StreamToken::Error("No model loaded".to_string()));
                     continue;
                 }
                 
-                if let Err(e) = run_generation_persistent(&mut state, &messages, params, &token_tx, &stop_signal) {
+                if let Err(e) = run_generation_persistent(&mut state, &messages, params, session, &token_tx, &stop_signal) {
+                    state.prompt_cache = None;
+                    state.metrics.record_failed();
                     let _ = token_tx.send(StreamToken::Error(e));
                 }
             }
-            Ok(WorkerCommand::Shutdown) => {
+            Some(WorkerCommand::EndSession { id }) => {
+                if state.prompt_cache.as_ref().is_some_and(|cache| cache.session == id) {
+                    state.prompt_cache = None;
+                    if let Some(ctx) = state.ctx.as_mut() {
+                        ctx.clear_kv_cache();
+                    }
+                    tracing::debug!("Generation session {} ended, KV cache released", id);
+                }
+            }
+            Some(WorkerCommand::Shutdown) => {
                 // Clean shutdown: drop context first, then model
+                state.embedder = None;
+                state.draft = None;
                 state.ctx = None;
                 state.model = None;
                 state.backend = None;
                 tracing::info!("Worker thread shut down");
                 break;
             }
-            Err(_) => {
+            None => {
                 break;
             }
         }
@@ -478,9 +1186,8 @@ fn load_model_internal(
     backend: &Option<LlamaBackend>,
     path: &Path,
     gpu_layers: u32,
+    control: &LoadControl,
 ) -> Result<(LoadedModelInfo, LlamaModel), EngineError> {
-    let backend = backend.as_ref().ok_or(EngineError::BackendNotInitialized)?;
-
     let metadata = std::fs::metadata(path)
         .map_err(|e| EngineError::ModelLoad(format!("Cannot read model file: {}", e)))?;
 
@@ -488,6 +1195,11 @@ fn load_model_internal(
         return Err(EngineError::ModelLoad("Model file is empty".to_string()));
     }
 
+    // Catch truncated downloads before llama.cpp maps the file
+    let layout = check_gguf_layout(path)?;
+
+    let backend = backend.as_ref().ok_or(EngineError::BackendNotInitialized)?;
+
     tracing::info!(
         "Loading model: {:?} ({:.2} GB, {} GPU layers)",
         path,
@@ -499,16 +1211,36 @@ fn load_model_internal(
     let model_params = LlamaModelParams::default()
         .with_n_gpu_layers(gpu_layers);
 
-    let model = LlamaModel::load_from_file(backend, path, &model_params)
-        .map_err(|e| EngineError::ModelLoad(format!("Load failed: {}", e)))?;
+    // llama-cpp-2 does not expose llama.cpp's load progress callback, so the
+    // weights are read ahead here, where progress and cancel are available
+    read_weights(path, warm_up_bytes(metadata.len(), free_ram_bytes()), control)?;
+    if !control.start_building() {
+        tracing::info!("Model load cancelled before llama.cpp started");
+        return Err(EngineError::LoadCancelled);
+    }
+
+    let model = LlamaModel::load_from_file(backend, path, &model_params).map_err(|e| {
+        let free_bytes = free_ram_bytes();
+        EngineError::ModelLoad(load_failure_message(&e.to_string(), metadata.len(), free_bytes, gpu_layers))
+    })?;
 
+    let chat_template = model.meta_val_str("tokenizer.chat_template").ok();
     let info = LoadedModelInfo {
         path: path.to_string_lossy().to_string(),
         vocab_size: model.n_vocab(),
         embedding_dim: model.n_embd(),
         context_length: model.n_ctx_train(),
+        rope_scaling: layout.metadata.rope_scaling,
         param_count: model.n_params() as u64,
         size_bytes: model.size() as u64,
+        reasoning: reasoning::detect_reasoning_model(
+            &path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            model.meta_val_str("general.name").ok().as_deref(),
+            chat_template.as_deref(),
+        ),
+        tool_call_format: ToolCallFormat::from_chat_template(chat_template.as_deref()),
+        chat_template: chat_template.as_deref().and_then(ChatTemplate::detect),
+        draft: None,
     };
 
     tracing::info!(
@@ -517,10 +1249,126 @@ fn load_model_internal(
         info.context_length / 1024,
         info.vocab_size
     );
+    if let Some(scaling) = &info.rope_scaling {
+        tracing::info!(
+            "RoPE scaling: {} x{} (base ctx {:?})",
+            scaling.kind,
+            scaling.factor,
+            scaling.original_context_length
+        );
+    }
 
     Ok((info, model))
 }
 
+/// Load the draft model and check it can propose tokens for `target`
+fn load_draft_model(
+    backend: &Option<LlamaBackend>,
+    target: &LlamaModel,
+    path: &Path,
+    gpu_layers: u32,
+) -> Result<LlamaModel, String> {
+    let (_, draft) = load_model_internal(backend, path, gpu_layers, &LoadControl::new()).map_err(|e| e.to_string())?;
+    if !draft_vocab_sizes_match(target.n_vocab(), draft.n_vocab()) {
+        return Err(format!("vocabulary sizes differ ({} vs {})", target.n_vocab(), draft.n_vocab()));
+    }
+    if target.token_bos() != draft.token_bos() || target.token_eos() != draft.token_eos() {
+        return Err("special tokens differ".to_string());
+    }
+    // Past the first few (special) tokens the texts must match one for one
+    for id in 5..target.n_vocab().min(draft.n_vocab()).min(DRAFT_VOCAB_CHECKED) {
+        let token = LlamaToken::new(id);
+        if target.token_to_bytes(token, Special::Tokenize).ok() != draft.token_to_bytes(token, Special::Tokenize).ok() {
+            return Err(format!("token {} differs between the vocabularies", id));
+        }
+    }
+    tracing::info!("Draft model ready for speculative decoding: {:?}", path);
+    Ok(draft)
+}
+
+/// Whether a draft vocabulary is close enough in size to the model's
+fn draft_vocab_sizes_match(target: i32, draft: i32) -> bool {
+    (target - draft).abs() <= DRAFT_VOCAB_MAX_DIFF
+}
+
+/// Bytes of a `file_bytes` model worth reading ahead with `free_bytes` of RAM
+///
+/// Past free RAM the first pages read would be evicted before llama.cpp maps
+/// them, so the file would be read twice. Unknown free RAM reads it all.
+fn warm_up_bytes(file_bytes: u64, free_bytes: Option<u64>) -> u64 {
+    free_bytes.map_or(file_bytes, |free| file_bytes.min(free))
+}
+
+/// Read the first `total` bytes of the model file so llama.cpp maps them from
+/// the page cache.
+///
+/// On a slow disk this read is most of the load time, and unlike the llama.cpp
+/// call it can report progress and stop between chunks.
+fn read_weights(path: &Path, total: u64, control: &LoadControl) -> Result<(), EngineError> {
+    use std::io::Read;
+
+    control.total_bytes.store(total, Ordering::Relaxed);
+    let file = std::fs::File::open(path)
+        .map_err(|e| EngineError::ModelLoad(format!("Cannot read model file: {}", e)))?;
+    let mut file = file.take(total);
+    let mut buf = vec![0u8; LOAD_CHUNK_BYTES];
+    loop {
+        if control.is_cancelled() {
+            tracing::info!("Model load cancelled while reading weights");
+            return Err(EngineError::LoadCancelled);
+        }
+        let n = file.read(&mut buf).map_err(|e| {
+            EngineError::ModelLoad(format!(
+                "Read failed at byte {}: {}",
+                control.bytes_read.load(Ordering::Relaxed),
+                e
+            ))
+        })?;
+        if n == 0 {
+            return Ok(());
+        }
+        control.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+/// KV cache bytes per token, from the attention dimensions in the GGUF metadata
+fn model_kv_bytes_per_token(model: &LlamaModel) -> u64 {
+    let arch = model.meta_val_str("general.architecture").unwrap_or_default();
+    let meta = |key: &str| -> u32 {
+        model
+            .meta_val_str(&format!("{}.{}", arch, key))
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0)
+    };
+    let n_embd = u32::try_from(model.n_embd()).unwrap_or(0);
+    context::kv_bytes_per_token(
+        meta("block_count"),
+        n_embd,
+        meta("attention.head_count"),
+        meta("attention.head_count_kv"),
+    )
+}
+
+/// Error text for a failed llama.cpp load
+///
+/// llama.cpp only reports that loading failed, so point at the likely cause:
+/// memory when a CPU-only model cannot fit, otherwise a damaged or unsupported file.
+fn load_failure_message(error: &str, size_bytes: u64, free_bytes: Option<u64>, gpu_layers: u32) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    match free_bytes {
+        Some(free) if gpu_layers == 0 && size_bytes > free => format!(
+            "Out of memory: the model needs about {:.1} GB but only {:.1} GB of RAM is free. Try a smaller quantization.",
+            size_bytes as f64 / GB,
+            free as f64 / GB
+        ),
+        _ => format!(
+            "Load failed: {} (the file may be corrupt or use an architecture this build does not support)",
+            error
+        ),
+    }
+}
+
 // =============================================================================
 // Generation with PERSISTENT context (the main performance optimization)
 // =============================================================================
@@ -529,6 +1377,7 @@ fn run_generation_persistent(
     state: &mut WorkerState,
     messages: &[ChatMessage],
     params: GenerationParams,
+    session: Option<u64>,
     tx: &Sender<StreamToken>,
     stop_signal: &Arc<AtomicBool>,
 ) -> Result<(), String> {
@@ -537,17 +1386,20 @@ fn run_generation_persistent(
     let backend = state.backend.as_ref().ok_or("Backend not initialized")?;
     let model = state.model.as_ref().ok_or("Model not loaded")?;
 
-    // Build prompt
-    let prompt = match build_chat_prompt_from_messages(model, messages) {
-        Ok(p) => p,
-        Err(e) => {
-            tracing::warn!("Chat template error: {e}, using fallback");
-            build_fallback_prompt(messages)
-        }
+    // Build prompt: a known family is rendered here, anything else by llama.cpp
+    let prompt = match state.chat_template {
+        Some(template) => template.render(messages),
+        None => match build_chat_prompt_from_messages(model, messages) {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::warn!("Chat template error: {e}, using fallback");
+                build_fallback_prompt(messages)
+            }
+        },
     };
 
     // Tokenize
-    let tokens = model
+    let mut tokens = model
         .str_to_token(&prompt, AddBos::Always)
         .map_err(|e| format!("Tokenization failed: {}", e))?;
     
@@ -564,8 +1416,29 @@ fn run_generation_persistent(
     let needed = std::cmp::max(needed, prompt_len + min_gen);
     let needed = std::cmp::min(needed, effective_max);
     
-    // Round up to next standard size for better context reuse
-    let n_ctx = pick_context_size(needed, effective_max);
+    let (n_ctx, context_limit) = if params.auto_context {
+        // Auto: grow in steps with the conversation, bounded by free memory
+        let limit = context::auto_context_limit(
+            model_max,
+            params.max_context_size,
+            free_ram_bytes(),
+            state.ctx_n_ctx,
+            state.kv_bytes_per_token,
+        );
+        let needed = std::cmp::min(prompt_len + params.max_tokens, limit).max(prompt_len + min_gen);
+        let decision = context::plan_context(needed, state.ctx_n_ctx, limit);
+        if let ContextDecision::Grow { from, to } = decision {
+            tracing::info!(
+                "Auto context: growing {}K -> {}K (need ~{} tokens, limit {}K)",
+                from / 1024, to / 1024, needed, limit / 1024
+            );
+            state.metrics.record_context_grow();
+        }
+        (decision.size(state.ctx_n_ctx), limit)
+    } else {
+        // Round up to next standard size for better context reuse
+        (pick_context_size(needed, effective_max), effective_max)
+    };
     
     tracing::info!(
         "Prompt: {} tokens, need ctx: {}, model max: {}",
@@ -610,25 +1483,43 @@ fn run_generation_persistent(
     
     if need_new_ctx {
         // Drop old context first to free VRAM
+        state.prompt_cache = None;
         state.ctx = None;
         state.ctx_n_ctx = 0;
         state.ctx_n_batch = 0;
         
         let n_threads = state.n_threads;
-        let n_batch = calculate_optimal_batch(n_ctx, prompt_len);
-        
-        let ctx_params = LlamaContextParams::default()
-            .with_n_ctx(Some(NonZeroU32::new(n_ctx).unwrap()))
-            .with_n_batch(n_batch)
-            .with_n_threads(n_threads)
-            .with_n_threads_batch(n_threads);
         
         // SAFETY: The model outlives the context because we always drop ctx before model.
         // Both are owned by WorkerState and we always drop in the right order.
         let model_static: &'static LlamaModel = unsafe { &*(model as *const LlamaModel) };
         
-        let ctx = model_static.new_context(backend, ctx_params)
-            .map_err(|e| format!("Failed to create context ({}K): {}", n_ctx / 1024, e))?;
+        let mut n_ctx = n_ctx;
+        let (ctx, n_batch) = loop {
+            let n_batch = calculate_optimal_batch(n_ctx, prompt_len);
+            let ctx_params = LlamaContextParams::default()
+                .with_n_ctx(Some(NonZeroU32::new(n_ctx).unwrap()))
+                .with_n_batch(n_batch)
+                .with_n_threads(n_threads)
+                .with_n_threads_batch(n_threads);
+            
+            match model_static.new_context(backend, ctx_params) {
+                Ok(ctx) => break (ctx, n_batch),
+                Err(e) => {
+                    // Auto mode backs off a step when the allocation does not fit
+                    match context::step_below(n_ctx) {
+                        Some(smaller) if params.auto_context && smaller >= prompt_len + min_gen => {
+                            tracing::warn!(
+                                "Auto context: {}K allocation failed ({}), retrying with {}K",
+                                n_ctx / 1024, e, smaller / 1024
+                            );
+                            n_ctx = smaller;
+                        }
+                        _ => return Err(format!("Failed to create context ({}K): {}", n_ctx / 1024, e)),
+                    }
+                }
+            }
+        };
         
         state.ctx = Some(ctx);
         state.ctx_n_ctx = n_ctx;
@@ -639,13 +1530,11 @@ fn run_generation_persistent(
             start_time.elapsed(), n_ctx / 1024, n_batch, n_threads
         );
     }
+    state.metrics.set_context(state.ctx_n_ctx, context_limit.max(state.ctx_n_ctx));
     
     let ctx = state.ctx.as_mut().ok_or("Context disappeared")?;
     let actual_n_ctx = state.ctx_n_ctx;
     
-    // Clear the KV cache for fresh generation
-    ctx.clear_kv_cache();
-    
     // Clamp max_tokens to fit in context
     let available = actual_n_ctx.saturating_sub(prompt_len).max(64);
     let effective_max = std::cmp::min(params.max_tokens, available);
@@ -659,6 +1548,37 @@ fn run_generation_persistent(
     
     let mut clamped = params.clone();
     clamped.max_tokens = effective_max;
+
+    // Truncate prompt if needed (keep most recent tokens)
+    let max_prompt = (actual_n_ctx as usize).saturating_sub(effective_max as usize).max(1);
+    if tokens.len() > max_prompt {
+        let start = tokens.len() - max_prompt;
+        tokens.drain(..start);
+        tracing::warn!("Prompt truncated to {} tokens", tokens.len());
+    }
+
+    // Keep the prefix this session already evaluated; anything else starts
+    // from an empty KV cache
+    let cache = state.prompt_cache.take();
+    let reused = match (&cache, session) {
+        (Some(cache), Some(id)) if cache.session == id => reusable_prefix(&cache.tokens, &tokens),
+        _ => 0,
+    };
+    let trimmed = reused > 0
+        && ctx
+            .clear_kv_cache_seq(Some(0), Some(reused as u32), None)
+            .unwrap_or(false);
+    let reused = if trimmed {
+        tracing::info!(
+            "Prompt cache: reusing {} of {} tokens, evaluating {}",
+            reused, tokens.len(), tokens.len() - reused
+        );
+        state.metrics.record_prompt_reuse(reused as u32);
+        reused
+    } else {
+        ctx.clear_kv_cache();
+        0
+    };
     
     let ctx_ready_time = start_time.elapsed();
     tracing::info!(
@@ -667,7 +1587,159 @@ fn run_generation_persistent(
     );
 
     let n_batch = calculate_optimal_batch(actual_n_ctx, prompt_len);
-    run_inference(ctx, model, tokens, clamped, actual_n_ctx, n_batch, tx, stop_signal)
+
+    // A draft that cannot follow (no memory for its context) is dropped for good
+    if let Some(draft) = state.draft.as_mut() {
+        if let Err(e) = prepare_draft(draft, backend, actual_n_ctx, n_batch, state.n_threads, &tokens) {
+            tracing::warn!("Draft model disabled, decoding normally: {}", e);
+            state.draft = None;
+        }
+    }
+    let draft_ctx = state.draft.as_mut().and_then(|draft| draft.ctx.as_mut());
+
+    let cached = run_inference(ctx, draft_ctx, model, tokens, reused, clamped, n_batch, tx, stop_signal, &state.metrics)?;
+    state.prompt_cache = session.map(|session| PromptCache { session, tokens: cached });
+    Ok(())
+}
+
+/// Give the draft a context as large as the model's and evaluate the prompt
+/// in it, so it can start proposing tokens
+fn prepare_draft(
+    draft: &mut DraftState,
+    backend: &LlamaBackend,
+    n_ctx: u32,
+    n_batch: u32,
+    n_threads: i32,
+    prompt_tokens: &[LlamaToken],
+) -> Result<(), String> {
+    if draft.ctx.is_none() || draft.ctx_n_ctx < n_ctx {
+        draft.ctx = None;
+        // SAFETY: same as the main context; `DraftState` drops ctx before model
+        let model_static: &'static LlamaModel = unsafe { &*(&draft.model as *const LlamaModel) };
+        let ctx_params = LlamaContextParams::default()
+            .with_n_ctx(Some(NonZeroU32::new(n_ctx).ok_or("Empty context")?))
+            .with_n_batch(n_batch)
+            .with_n_threads(n_threads)
+            .with_n_threads_batch(n_threads);
+        let ctx = model_static
+            .new_context(backend, ctx_params)
+            .map_err(|e| format!("Failed to create draft context: {}", e))?;
+        draft.ctx = Some(ctx);
+        draft.ctx_n_ctx = n_ctx;
+    }
+    let ctx = draft.ctx.as_mut().ok_or("Draft context disappeared")?;
+
+    // The draft is small: it evaluates the whole prompt every time
+    ctx.clear_kv_cache();
+    let batch_size = n_batch.max(1) as usize;
+    let mut batch = LlamaBatch::new(batch_size, 1);
+    for (chunk_index, chunk) in prompt_tokens.chunks(batch_size).enumerate() {
+        batch.clear();
+        for (i, token) in chunk.iter().enumerate() {
+            let index = chunk_index * batch_size + i;
+            batch
+                .add(*token, index as i32, &[0], index + 1 == prompt_tokens.len())
+                .map_err(|e| format!("Draft batch add error: {}", e))?;
+        }
+        ctx.decode(&mut batch)
+            .map_err(|e| format!("Draft decode error: {}", e))?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Embeddings
+// =============================================================================
+
+/// Embed `texts` with the model at `path`, loading it first when the worker
+/// holds another embedding model or none
+fn run_embeddings(
+    state: &mut WorkerState,
+    path: &Path,
+    gpu_layers: u32,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, EngineError> {
+    if state.embedder.as_ref().is_some_and(|embedder| embedder.path != path) {
+        state.embedder = None;
+    }
+    if state.embedder.is_none() {
+        let (_, model) = load_model_internal(&state.backend, path, gpu_layers, &LoadControl::new())?;
+        tracing::info!("Embedding model loaded: {:?} ({} dimensions)", path, model.n_embd());
+        state.embedder = Some(EmbedderState { ctx: None, ctx_n_ctx: 0, model, path: path.to_path_buf() });
+    }
+    let backend = state.backend.as_ref().ok_or(EngineError::BackendNotInitialized)?;
+    let n_threads = state.n_threads;
+    let Some(EmbedderState { ctx, ctx_n_ctx, model, .. }) = state.embedder.as_mut() else {
+        return Err(EngineError::NoModelLoaded);
+    };
+
+    if ctx.is_none() {
+        let n_ctx = model.n_ctx_train().clamp(1, EMBED_MAX_CTX);
+        // SAFETY: same as the draft context; `EmbedderState` drops ctx before model
+        let model_static: &'static LlamaModel = unsafe { &*(model as *const LlamaModel) };
+        // Encoder models see the whole text in one micro-batch
+        let ctx_params = LlamaContextParams::default()
+            .with_n_ctx(NonZeroU32::new(n_ctx))
+            .with_n_batch(n_ctx)
+            .with_n_ubatch(n_ctx)
+            .with_n_threads(n_threads)
+            .with_n_threads_batch(n_threads)
+            .with_embeddings(true);
+        *ctx = Some(
+            model_static
+                .new_context(backend, ctx_params)
+                .map_err(|e| EngineError::ContextCreate(e.to_string()))?,
+        );
+        *ctx_n_ctx = n_ctx;
+    }
+    let ctx = ctx.as_mut().ok_or_else(|| EngineError::ContextCreate("Embedding context disappeared".to_string()))?;
+    let max_tokens = *ctx_n_ctx as usize;
+    let mut batch = LlamaBatch::new(max_tokens, 1);
+
+    texts
+        .iter()
+        .map(|text| {
+            let mut tokens = model
+                .str_to_token(text, AddBos::Always)
+                .map_err(|e| EngineError::Tokenization(e.to_string()))?;
+            tokens.truncate(max_tokens);
+
+            ctx.clear_kv_cache();
+            batch.clear();
+            batch
+                .add_sequence(&tokens, 0, true)
+                .map_err(|e| EngineError::Inference(format!("Embedding batch add error: {}", e)))?;
+            ctx.decode(&mut batch)
+                .map_err(|e| EngineError::Inference(format!("Embedding decode error: {}", e)))?;
+
+            let mut vector = match ctx.embeddings_seq_ith(0) {
+                Ok(pooled) => pooled.to_vec(),
+                // Models without pooling give one vector per token: average them
+                Err(_) => {
+                    let mut sum = vec![0.0f32; model.n_embd().max(0) as usize];
+                    for i in 0..tokens.len() {
+                        let token_vector = ctx
+                            .embeddings_ith(i as i32)
+                            .map_err(|e| EngineError::Inference(format!("No embeddings: {}", e)))?;
+                        for (total, value) in sum.iter_mut().zip(token_vector) {
+                            *total += value;
+                        }
+                    }
+                    sum
+                }
+            };
+            l2_normalize(&mut vector);
+            Ok(vector)
+        })
+        .collect()
+}
+
+/// Scale `vector` to unit length, so cosine similarity is a dot product
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
 }
 
 /// Pick a good context size (round up for reusability)
@@ -732,6 +1804,7 @@ fn build_chat_prompt_from_messages(
             ChatRole::System => "system",
             ChatRole::User => "user",
             ChatRole::Assistant => "assistant",
+            ChatRole::Tool => "tool",
         };
         let chat_msg = LlamaChatMessage::new(role.to_string(), msg.content.clone())
             .map_err(|e| format!("Chat message error: {e}"))?;
@@ -750,6 +1823,7 @@ fn build_fallback_prompt(messages: &[ChatMessage]) -> String {
             ChatRole::System => "System",
             ChatRole::User => "User",
             ChatRole::Assistant => "Assistant",
+            ChatRole::Tool => "Tool",
         };
         out.push_str(role);
         out.push_str(": ");
@@ -764,43 +1838,54 @@ fn build_fallback_prompt(messages: &[ChatMessage]) -> String {
 // Inference loop
 // =============================================================================
 
+/// Evaluate the prompt after its first `reused` tokens (already in the KV
+/// cache) and stream the reply, speculatively when a draft context is given
+///
+/// Returns the tokens the KV cache holds afterwards, for the next generation
+/// of the session.
 fn run_inference(
     ctx: &mut LlamaContext,
+    draft: Option<&mut LlamaContext>,
     model: &LlamaModel,
-    mut prompt_tokens: Vec<llama_cpp_2::token::LlamaToken>,
+    prompt_tokens: Vec<LlamaToken>,
+    reused: usize,
     params: GenerationParams,
-    n_ctx: u32,
     n_batch: u32,
     tx: &Sender<StreamToken>,
     stop_signal: &Arc<AtomicBool>,
-) -> Result<(), String> {
+    metrics: &EngineMetrics,
+) -> Result<Vec<LlamaToken>, String> {
     let inference_start = std::time::Instant::now();
     
     if prompt_tokens.is_empty() {
         return Err("Empty prompt".to_string());
     }
 
-    // Truncate prompt if needed (keep most recent tokens)
-    let max_prompt = (n_ctx as usize).saturating_sub(params.max_tokens as usize).max(1);
-    if prompt_tokens.len() > max_prompt {
-        let start = prompt_tokens.len() - max_prompt;
-        prompt_tokens = prompt_tokens[start..].to_vec();
-        tracing::warn!("Prompt truncated to {} tokens", prompt_tokens.len());
-    }
-
     // Process prompt in batches
     let batch_size = std::cmp::max(1, n_batch) as usize;
     let mut batch = LlamaBatch::new(batch_size, 1);
     let prompt_len = prompt_tokens.len();
+    let mut cached = prompt_tokens[..reused].to_vec();
 
     let prompt_start = std::time::Instant::now();
-    for (chunk_index, chunk) in prompt_tokens.chunks(batch_size).enumerate() {
+    for (chunk_index, chunk) in prompt_tokens[reused..].chunks(batch_size).enumerate() {
         if stop_signal.load(Ordering::Relaxed) {
-            return Ok(());
+            tracing::info!("Generation cancelled during prompt evaluation");
+            let stats = GenerationStats {
+                prompt_tokens: prompt_len as u32,
+                tokens_generated: 0,
+                prompt_ms: prompt_start.elapsed().as_millis() as u64,
+                generation_ms: 0,
+                stop_reason: StopReason::Cancelled,
+            };
+            metrics.record_finished(&stats);
+            let _ = tx.send(StreamToken::Stats(stats));
+            let _ = tx.send(StreamToken::Done);
+            return Ok(cached);
         }
         
         batch.clear();
-        let offset = chunk_index * batch_size;
+        let offset = reused + chunk_index * batch_size;
         for (i, token) in chunk.iter().enumerate() {
             let global_index = offset + i;
             let is_last = global_index + 1 == prompt_len;
@@ -811,73 +1896,211 @@ fn run_inference(
 
         ctx.decode(&mut batch)
             .map_err(|e| format!("Decode error: {}", e))?;
+        cached.extend_from_slice(chunk);
     }
     
     let prompt_time = prompt_start.elapsed();
+    let evaluated = prompt_len - reused;
     tracing::info!(
-        "Prompt: {} tokens in {:?} ({:.0} t/s)",
-        prompt_len, prompt_time, prompt_len as f64 / prompt_time.as_secs_f64()
+        "Prompt: {} tokens in {:?} ({:.0} t/s), {} reused from cache",
+        evaluated, prompt_time, evaluated as f64 / prompt_time.as_secs_f64(), reused
     );
 
     // Sampler
     let seed = if params.seed == 0 { rand_seed() } else { params.seed };
 
-    let mut sampler = if params.temperature < 0.01 {
-        LlamaSampler::greedy()
+    // The grammar goes first so the other samplers only see allowed tokens
+    let mut chain = Vec::new();
+    if let Some(grammar) = params.grammar.as_deref() {
+        match LlamaSampler::grammar(model, grammar, "root") {
+            Ok(sampler) => chain.push(sampler),
+            Err(e) => tracing::warn!("Invalid grammar, generating unconstrained: {}", e),
+        }
+    }
+    if params.frequency_penalty != 0.0 || params.presence_penalty != 0.0 {
+        chain.push(LlamaSampler::penalties(PENALTY_LAST_N, 1.0, params.frequency_penalty, params.presence_penalty));
+    }
+    if params.temperature < 0.01 {
+        chain.push(LlamaSampler::greedy());
     } else {
-        LlamaSampler::chain_simple([
-            LlamaSampler::top_k(params.top_k as i32),
-            LlamaSampler::top_p(params.top_p, 1),
-            LlamaSampler::temp(params.temperature),
-            LlamaSampler::dist(seed),
-        ])
-    };
+        chain.push(LlamaSampler::top_k(params.top_k as i32));
+        chain.push(LlamaSampler::top_p(params.top_p, 1));
+        if params.min_p > 0.0 {
+            chain.push(LlamaSampler::min_p(params.min_p, 1));
+        }
+        chain.push(LlamaSampler::temp(params.temperature));
+        chain.push(LlamaSampler::dist(seed));
+    }
+    let mut sampler = LlamaSampler::chain_simple(chain);
 
-    let mut n_decoded = prompt_tokens.len() as i32;
+    let mut n_decoded = prompt_len as i32;
     let mut tokens_generated = 0u32;
     let mut utf8_buffer: Vec<u8> = Vec::with_capacity(32);
-    let mut hit_eos = false;  // Track if we stopped due to EOS
+    let mut stops = StopSequences::new(&params.stop_sequences);
+    let mut hit_eos = false;  // Track if we stopped due to EOS (or a stop sequence)
+    let mut cancelled = false;
 
     let gen_start = std::time::Instant::now();
     
-    for _ in 0..params.max_tokens {
-        if stop_signal.load(Ordering::Relaxed) {
-            break;
-        }
+    if let Some(draft) = draft {
+        let mut next = sampler.sample(ctx, batch.n_tokens() - 1);
+        let mut draft_sampler = LlamaSampler::greedy();
+        let mut draft_len = prompt_len;
+        let mut draft_batch = LlamaBatch::new(DRAFT_TOKENS + 2, 1);
+        let mut verify_batch = LlamaBatch::new(DRAFT_TOKENS + 1, 1);
+        let (mut drafted, mut accepted) = (0usize, 0usize);
+
+        // `next` is sampled but in neither KV cache yet; `cached` mirrors the
+        // main model's KV cache
+        loop {
+            if stop_signal.load(Ordering::Relaxed) {
+                cancelled = true;
+                break;
+            }
+            if model.is_eog_token(next) {
+                hit_eos = true;
+                break;
+            }
+            if tokens_generated >= params.max_tokens {
+                break;
+            }
+            tokens_generated += 1;
+            match emit_token(model, next, &mut utf8_buffer, &mut stops, tx)? {
+                Emitted::Continue => {}
+                Emitted::StopSequence => {
+                    hit_eos = true;
+                    break;
+                }
+                Emitted::Disconnected => {
+                    cancelled = true;
+                    break;
+                }
+            }
 
-        let new_token = sampler.sample(ctx, batch.n_tokens() - 1);
-        sampler.accept(new_token);
+            // The draft catches up on the accepted tokens, then guesses ahead greedily
+            let n_past = cached.len();
+            let budget = DRAFT_TOKENS.min((params.max_tokens - tokens_generated) as usize);
+            let mut proposals: Vec<LlamaToken> = Vec::with_capacity(budget);
+            draft_batch.clear();
+            for (i, token) in cached[draft_len..].iter().chain(std::iter::once(&next)).enumerate() {
+                draft_batch
+                    .add(*token, (draft_len + i) as i32, &[0], draft_len + i == n_past)
+                    .map_err(|e| format!("Draft batch add error: {}", e))?;
+            }
+            while proposals.len() < budget {
+                draft.decode(&mut draft_batch)
+                    .map_err(|e| format!("Draft decode error: {}", e))?;
+                draft_len += draft_batch.n_tokens() as usize;
+                let guess = draft_sampler.sample(draft, draft_batch.n_tokens() - 1);
+                if model.is_eog_token(guess) || guess.0 >= model.n_vocab() {
+                    break;
+                }
+                proposals.push(guess);
+                draft_batch.clear();
+                draft_batch
+                    .add(guess, draft_len as i32, &[0], true)
+                    .map_err(|e| format!("Draft batch add error: {}", e))?;
+            }
+            drafted += proposals.len();
+
+            // The main model scores `next` and every proposal in one batch
+            verify_batch.clear();
+            for (i, token) in std::iter::once(&next).chain(&proposals).enumerate() {
+                verify_batch
+                    .add(*token, (n_past + i) as i32, &[0], true)
+                    .map_err(|e| format!("Batch add error: {}", e))?;
+            }
+            ctx.decode(&mut verify_batch)
+                .map_err(|e| format!("Decode error: {}", e))?;
+            cached.push(next);
+
+            // Proposals are kept while the main model samples the same token;
+            // the first disagreement (or the token after them all) comes next
+            let mut i = 0;
+            next = loop {
+                let token = sampler.sample(ctx, i as i32);
+                if i < proposals.len() && token == proposals[i] {
+                    i += 1;
+                    continue;
+                }
+                break token;
+            };
+            accepted += i;
+            for token in &proposals[..i] {
+                tokens_generated += 1;
+                match emit_token(model, *token, &mut utf8_buffer, &mut stops, tx)? {
+                    Emitted::Continue => {}
+                    Emitted::StopSequence => {
+                        hit_eos = true;
+                        break;
+                    }
+                    Emitted::Disconnected => {
+                        cancelled = true;
+                        break;
+                    }
+                }
+                cached.push(*token);
+            }
+            if cancelled || hit_eos {
+                break;
+            }
 
-        if model.is_eog_token(new_token) {
-            flush_utf8_buffer(&mut utf8_buffer, tx);
-            hit_eos = true;
-            break;
+            // Forget the rejected proposals in both KV caches
+            ctx.clear_kv_cache_seq(Some(0), Some(cached.len() as u32), None)
+                .map_err(|e| format!("KV cache error: {}", e))?;
+            draft_len = draft_len.min(cached.len());
+            draft.clear_kv_cache_seq(Some(0), Some(draft_len as u32), None)
+                .map_err(|e| format!("Draft KV cache error: {}", e))?;
         }
+        if drafted > 0 {
+            tracing::info!(
+                "Speculative decoding: {}/{} draft tokens accepted ({:.0}%)",
+                accepted, drafted, accepted as f64 * 100.0 / drafted as f64
+            );
+        }
+    } else {
+        for _ in 0..params.max_tokens {
+            if stop_signal.load(Ordering::Relaxed) {
+                cancelled = true;
+                break;
+            }
 
-        tokens_generated += 1;
+            // sample() also accepts the token into the sampler chain
+            let new_token = sampler.sample(ctx, batch.n_tokens() - 1);
 
-        let token_bytes = model
-            .token_to_bytes(new_token, Special::Tokenize)
-            .map_err(|e| format!("Token convert error: {}", e))?;
+            if model.is_eog_token(new_token) {
+                hit_eos = true;
+                break;
+            }
 
-        utf8_buffer.extend_from_slice(&token_bytes);
-        
-        if !emit_valid_utf8(&mut utf8_buffer, tx) {
-            break;
-        }
+            tokens_generated += 1;
+            match emit_token(model, new_token, &mut utf8_buffer, &mut stops, tx)? {
+                Emitted::Continue => {}
+                Emitted::StopSequence => {
+                    hit_eos = true;
+                    break;
+                }
+                Emitted::Disconnected => {
+                    // Receiver dropped - nobody is listening anymore
+                    cancelled = true;
+                    break;
+                }
+            }
 
-        batch.clear();
-        batch
-            .add(new_token, n_decoded, &[0], true)
-            .map_err(|e| format!("Batch add error: {}", e))?;
+            batch.clear();
+            batch
+                .add(new_token, n_decoded, &[0], true)
+                .map_err(|e| format!("Batch add error: {}", e))?;
 
-        ctx.decode(&mut batch)
-            .map_err(|e| format!("Decode error: {}", e))?;
+            ctx.decode(&mut batch)
+                .map_err(|e| format!("Decode error: {}", e))?;
+            cached.push(new_token);
 
-        n_decoded += 1;
+            n_decoded += 1;
+        }
     }
 
-    flush_utf8_buffer(&mut utf8_buffer, tx);
+    flush_utf8_buffer(&mut utf8_buffer, &mut stops, tx);
 
     let gen_time = gen_start.elapsed();
     let total_time = inference_start.elapsed();
@@ -887,12 +2110,29 @@ fn run_inference(
             tokens_generated, gen_time,
             tokens_generated as f64 / gen_time.as_secs_f64(),
             total_time,
-            if !hit_eos { " [TRUNCATED]" } else { "" }
+            if cancelled { " [CANCELLED]" } else if !hit_eos { " [TRUNCATED]" } else { "" }
         );
     }
 
+    let stop_reason = if cancelled || (!hit_eos && stop_signal.load(Ordering::Relaxed)) {
+        StopReason::Cancelled
+    } else if hit_eos {
+        StopReason::Eos
+    } else {
+        StopReason::MaxTokens
+    };
+    let stats = GenerationStats {
+        prompt_tokens: prompt_len as u32,
+        tokens_generated,
+        prompt_ms: prompt_time.as_millis() as u64,
+        generation_ms: gen_time.as_millis() as u64,
+        stop_reason,
+    };
+    metrics.record_finished(&stats);
+    let _ = tx.send(StreamToken::Stats(stats));
+
     // Send appropriate completion signal
-    if hit_eos || stop_signal.load(Ordering::Relaxed) {
+    if stop_reason != StopReason::MaxTokens {
         let _ = tx.send(StreamToken::Done);
     } else {
         // Hit max_tokens without EOS - response is truncated
@@ -901,34 +2141,74 @@ fn run_inference(
             max_tokens: params.max_tokens,
         });
     }
-    Ok(())
+    Ok(cached)
 }
 
 // =============================================================================
 // UTF-8 helpers
 // =============================================================================
 
+/// What happened to a generated token
+enum Emitted {
+    Continue,
+    /// A stop sequence completed: the generation is over
+    StopSequence,
+    /// Nobody is listening anymore
+    Disconnected,
+}
+
+/// Stream one generated token
+fn emit_token(
+    model: &LlamaModel,
+    token: LlamaToken,
+    buffer: &mut Vec<u8>,
+    stops: &mut StopSequences,
+    tx: &Sender<StreamToken>,
+) -> Result<Emitted, String> {
+    let token_bytes = model
+        .token_to_bytes(token, Special::Tokenize)
+        .map_err(|e| format!("Token convert error: {}", e))?;
+    buffer.extend_from_slice(&token_bytes);
+    Ok(emit_valid_utf8(buffer, stops, tx))
+}
+
+/// Send whatever is left once the generation ends
 #[inline]
-fn flush_utf8_buffer(buffer: &mut Vec<u8>, tx: &Sender<StreamToken>) {
-    if !buffer.is_empty() {
-        if let Ok(s) = String::from_utf8(std::mem::take(buffer)) {
-            if !s.is_empty() {
-                let _ = tx.send(StreamToken::Token(s));
-            }
-        }
+fn flush_utf8_buffer(buffer: &mut Vec<u8>, stops: &mut StopSequences, tx: &Sender<StreamToken>) {
+    if stops.is_stopped() {
+        return;
     }
+    let rest = String::from_utf8(std::mem::take(buffer)).unwrap_or_default();
+    let (mut text, _) = stops.push(&rest);
+    text.push_str(&stops.finish());
+    let _ = send_text(text, tx);
 }
 
+/// Send text unless it is empty; false once nobody is listening anymore
 #[inline]
-fn emit_valid_utf8(buffer: &mut Vec<u8>, tx: &Sender<StreamToken>) -> bool {
+fn send_text(text: String, tx: &Sender<StreamToken>) -> bool {
+    text.is_empty() || tx.send(StreamToken::Token(text)).is_ok()
+}
+
+/// Pass decoded text through the stop sequences and stream what they release
+#[inline]
+fn emit_text(text: &str, stops: &mut StopSequences, tx: &Sender<StreamToken>) -> Emitted {
+    let (released, stopped) = stops.push(text);
+    if !send_text(released, tx) {
+        Emitted::Disconnected
+    } else if stopped {
+        Emitted::StopSequence
+    } else {
+        Emitted::Continue
+    }
+}
+
+#[inline]
+fn emit_valid_utf8(buffer: &mut Vec<u8>, stops: &mut StopSequences, tx: &Sender<StreamToken>) -> Emitted {
     if let Ok(s) = std::str::from_utf8(buffer) {
-        if !s.is_empty() {
-            if tx.send(StreamToken::Token(s.to_string())).is_err() {
-                return false;
-            }
-        }
+        let emitted = emit_text(s, stops, tx);
         buffer.clear();
-        return true;
+        return emitted;
     }
     
     // Find valid UTF-8 prefix
@@ -942,15 +2222,12 @@ fn emit_valid_utf8(buffer: &mut Vec<u8>, tx: &Sender<StreamToken>) -> bool {
     
     if valid_len > 0 {
         let s = unsafe { std::str::from_utf8_unchecked(&buffer[..valid_len]) };
-        if !s.is_empty() {
-            if tx.send(StreamToken::Token(s.to_string())).is_err() {
-                return false;
-            }
-        }
+        let emitted = emit_text(s, stops, tx);
         buffer.drain(..valid_len);
+        return emitted;
     }
     
-    true
+    Emitted::Continue
 }
 
 fn rand_seed() -> u32 {
@@ -963,6 +2240,17 @@ fn rand_seed() -> u32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_l2_normalize() {
+        let mut vector = vec![3.0, 4.0];
+        l2_normalize(&mut vector);
+        assert_eq!(vector, vec![0.6, 0.8]);
+
+        let mut zero = vec![0.0, 0.0];
+        l2_normalize(&mut zero);
+        assert_eq!(zero, vec![0.0, 0.0]);
+    }
+
     #[test]
     fn test_engine_new() {
         let engine = LlamaEngine::new();
@@ -987,10 +2275,133 @@ mod tests {
         assert_eq!(pick_context_size(10000, 32768), 16384);
     }
 
+    #[test]
+    fn test_draft_vocab_sizes_match() {
+        assert!(draft_vocab_sizes_match(151_936, 151_936));
+        // Same tokenizer, padded differently
+        assert!(draft_vocab_sizes_match(151_936, 151_665));
+        assert!(draft_vocab_sizes_match(152_064, 151_665));
+        assert!(!draft_vocab_sizes_match(128_256, 32_000));
+    }
+
+    #[test]
+    fn test_reusable_prefix() {
+        // Next agent iteration: previous prompt + reply, then a tool result
+        assert_eq!(reusable_prefix(&[1, 2, 3, 4], &[1, 2, 3, 4, 5, 6]), 4);
+        // Diverges midway (history trimmed or edited)
+        assert_eq!(reusable_prefix(&[1, 2, 3, 4], &[1, 2, 9, 4]), 2);
+        // Same prompt again: the last token is still evaluated
+        assert_eq!(reusable_prefix(&[1, 2, 3], &[1, 2, 3]), 2);
+        assert_eq!(reusable_prefix(&[1, 2, 3, 4], &[1, 2]), 1);
+        assert_eq!(reusable_prefix::<u32>(&[], &[1, 2]), 0);
+        assert_eq!(reusable_prefix::<u32>(&[1], &[]), 0);
+    }
+
     #[test]
     fn test_unload_without_model() {
         let mut engine = LlamaEngine::new();
         engine.unload_model();
         assert!(!engine.is_model_loaded());
     }
+
+    #[tokio::test]
+    async fn test_truncated_model_leaves_clean_state() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/gguf/truncated.gguf");
+        let mut engine = LlamaEngine::new();
+        engine.init().unwrap();
+
+        let control = Arc::new(LoadControl::new());
+        let err = engine
+            .load_model_with_control(&path, 0, control.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::ModelValidation(ref msg) if msg.contains("truncated")));
+        assert!(control.is_finished());
+        assert!(!engine.is_model_loaded());
+        assert!(engine.model_info().is_none());
+    }
+
+    #[test]
+    fn test_load_progress_and_eta() {
+        let control = LoadControl::new();
+        assert_eq!(control.progress(), 0.0);
+
+        control.total_bytes.store(1000, Ordering::Relaxed);
+        control.bytes_read.store(500, Ordering::Relaxed);
+        assert!((control.progress() - 0.5).abs() < 0.001);
+        assert_eq!(control.phase(), LoadPhase::Reading);
+
+        // Once llama.cpp has the load, cancel is refused
+        assert!(control.start_building());
+        assert_eq!(control.progress(), 1.0);
+        assert!(!control.cancel());
+        assert_eq!(control.phase(), LoadPhase::Building);
+
+        // Cancelled while reading, llama.cpp is never started
+        let control = LoadControl::new();
+        assert!(control.cancel());
+        assert!(control.cancel());
+        assert!(control.is_cancelled());
+        assert!(!control.start_building());
+
+        assert_eq!(
+            estimate_remaining(250, 1000, Duration::from_secs(10)),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(estimate_remaining(0, 1000, Duration::from_secs(1)), None);
+        assert_eq!(estimate_remaining(1000, 1000, Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn test_warm_up_read_capped_at_free_ram() {
+        assert_eq!(warm_up_bytes(8 << 30, Some(2 << 30)), 2 << 30);
+        assert_eq!(warm_up_bytes(1 << 30, Some(2 << 30)), 1 << 30);
+        assert_eq!(warm_up_bytes(1 << 30, None), 1 << 30);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        std::fs::write(&path, vec![0u8; LOAD_CHUNK_BYTES + 100]).unwrap();
+        let control = LoadControl::new();
+        read_weights(&path, 1000, &control).unwrap();
+        assert_eq!(control.bytes_read.load(Ordering::Relaxed), 1000);
+    }
+
+    #[test]
+    fn test_load_failure_message() {
+        let gb = 1024 * 1024 * 1024;
+        let msg = load_failure_message("null result", 8 * gb, Some(4 * gb), 0);
+        assert!(msg.starts_with("Out of memory"));
+        assert!(msg.contains("8.0 GB") && msg.contains("4.0 GB"));
+
+        // Offloaded layers do not need to fit in RAM
+        let msg = load_failure_message("null result", 8 * gb, Some(4 * gb), 99);
+        assert!(msg.contains("may be corrupt"));
+        assert!(load_failure_message("null result", gb, None, 0).contains("null result"));
+    }
+
+    #[test]
+    fn test_interactive_lane_goes_first() {
+        // (id, is_background)
+        let (tx, rx) = mpsc::channel();
+        let mut background = VecDeque::new();
+        let is_bg = |cmd: &(u32, bool)| cmd.1;
+
+        tx.send((1, true)).unwrap();
+        tx.send((2, true)).unwrap();
+        tx.send((3, false)).unwrap();
+        // Interactive work queued behind background jobs jumps ahead
+        assert_eq!(next_command(&rx, &mut background, is_bg), Some((3, false)));
+        assert_eq!(next_command(&rx, &mut background, is_bg), Some((1, true)));
+
+        // A new interactive request still beats the remaining background job
+        tx.send((4, false)).unwrap();
+        assert_eq!(next_command(&rx, &mut background, is_bg), Some((4, false)));
+        assert_eq!(next_command(&rx, &mut background, is_bg), Some((2, true)));
+
+        // Queued background work still runs after the sender is gone
+        tx.send((5, true)).unwrap();
+        drop(tx);
+        assert_eq!(next_command(&rx, &mut background, is_bg), Some((5, true)));
+        assert_eq!(next_command(&rx, &mut background, is_bg), None);
+    }
 }
@@ -0,0 +1,206 @@
+//! Sampling experiments
+//!
+//! Runs the prompt behind one user message with a few sampling presets so the
+//! replies can be compared side by side, then promotes one of them to the
+//! canonical reply and keeps the others as alternatives.
+
+use crate::inference::engine::GenerationParams;
+use crate::inference::streaming::GenerationStats;
+use crate::types::message::{AlternativeReply, Message, MessageMetadata, Role, SamplingParams};
+
+/// Fewest and most variants an experiment runs
+pub const MIN_VARIANTS: usize = 2;
+pub const MAX_VARIANTS: usize = 3;
+
+/// A named sampling configuration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamplingPreset {
+    pub id: &'static str,
+    pub temperature: f32,
+    pub top_p: f32,
+    pub min_p: f32,
+}
+
+pub const SAMPLING_PRESETS: [SamplingPreset; 4] = [
+    SamplingPreset { id: "precise", temperature: 0.2, top_p: 0.9, min_p: 0.1 },
+    SamplingPreset { id: "balanced", temperature: 0.7, top_p: 0.9, min_p: 0.05 },
+    SamplingPreset { id: "creative", temperature: 1.0, top_p: 0.95, min_p: 0.02 },
+    SamplingPreset { id: "wide", temperature: 1.2, top_p: 1.0, min_p: 0.05 },
+];
+
+impl SamplingPreset {
+    /// Parameters recorded in the message metadata
+    pub fn sampling(&self) -> SamplingParams {
+        SamplingParams {
+            label: self.id.to_string(),
+            temperature: self.temperature,
+            top_p: self.top_p,
+            min_p: self.min_p,
+        }
+    }
+
+    /// The chat's generation parameters with this preset's sampling
+    pub fn apply(&self, base: &GenerationParams) -> GenerationParams {
+        GenerationParams {
+            temperature: self.temperature,
+            top_p: self.top_p,
+            min_p: self.min_p,
+            ..base.clone()
+        }
+    }
+}
+
+/// Output of one variant
+#[derive(Debug, Clone)]
+pub struct VariantResult {
+    pub sampling: SamplingParams,
+    pub content: String,
+    pub stats: Option<GenerationStats>,
+}
+
+/// Conversation up to and including the user message at `user_index`,
+/// without the empty assistant placeholders left by streaming
+pub fn experiment_history(messages: &[Message], user_index: usize) -> Vec<Message> {
+    messages
+        .iter()
+        .take(user_index + 1)
+        .filter(|m| !(m.role == Role::Assistant && m.content.trim().is_empty()))
+        .cloned()
+        .collect()
+}
+
+/// Index of the reply to the user message at `user_index`, if it has one
+fn reply_index(messages: &[Message], user_index: usize) -> Option<usize> {
+    messages
+        .iter()
+        .enumerate()
+        .skip(user_index + 1)
+        .take_while(|(_, m)| m.role != Role::User)
+        .find(|(_, m)| m.role == Role::Assistant)
+        .map(|(i, _)| i)
+}
+
+/// Make `results[chosen]` the reply to the user message at `user_index`.
+///
+/// The previous reply and the other non-empty variants become alternatives.
+/// Returns the index of the reply.
+pub fn promote_variant(
+    messages: &mut Vec<Message>,
+    user_index: usize,
+    results: &[VariantResult],
+    chosen: usize,
+) -> usize {
+    let winner = &results[chosen];
+    let mut alternatives: Vec<AlternativeReply> = results
+        .iter()
+        .enumerate()
+        .filter(|(i, r)| *i != chosen && !r.content.trim().is_empty())
+        .map(|(_, r)| AlternativeReply {
+            content: r.content.clone(),
+            sampling: Some(r.sampling.clone()),
+        })
+        .collect();
+
+    let index = match reply_index(messages, user_index) {
+        Some(index) => {
+            let previous = &messages[index];
+            if !previous.content.trim().is_empty() && previous.content != winner.content {
+                let sampling = previous.metadata.as_ref().and_then(|m| m.sampling.clone());
+                alternatives.insert(0, AlternativeReply { content: previous.content.clone(), sampling });
+            }
+            // Alternatives from an earlier experiment are kept
+            if let Some(metadata) = previous.metadata.as_ref() {
+                alternatives.extend(metadata.alternatives.iter().cloned());
+            }
+            index
+        }
+        None => {
+            let index = (user_index + 1).min(messages.len());
+            messages.insert(index, Message::new(Role::Assistant, ""));
+            index
+        }
+    };
+
+    let reply = &mut messages[index];
+    reply.content = winner.content.clone();
+    reply.metadata = Some(MessageMetadata {
+        sampling: Some(winner.sampling.clone()),
+        alternatives,
+        ..Default::default()
+    });
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(preset: usize, content: &str) -> VariantResult {
+        VariantResult {
+            sampling: SAMPLING_PRESETS[preset].sampling(),
+            content: content.to_string(),
+            stats: None,
+        }
+    }
+
+    #[test]
+    fn test_preset_applies_sampling_only() {
+        let base = GenerationParams::default();
+        let params = SAMPLING_PRESETS[0].apply(&base);
+        assert_eq!(params.temperature, 0.2);
+        assert_eq!(params.min_p, 0.1);
+        assert_eq!(params.max_tokens, base.max_tokens);
+        assert_eq!(params.top_k, base.top_k);
+    }
+
+    #[test]
+    fn test_history_stops_at_user_message() {
+        let messages = vec![
+            Message::new(Role::User, "first"),
+            Message::new(Role::Assistant, "reply"),
+            Message::new(Role::User, "second"),
+            Message::new(Role::Assistant, ""),
+            Message::new(Role::User, "third"),
+        ];
+        let history = experiment_history(&messages, 2);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.last().unwrap().content, "second");
+    }
+
+    #[test]
+    fn test_promote_replaces_reply_and_keeps_alternatives() {
+        let mut messages = vec![
+            Message::new(Role::User, "question"),
+            Message::new(Role::Assistant, "original"),
+            Message::new(Role::User, "follow-up"),
+        ];
+        let results = vec![result(0, "precise answer"), result(2, "creative answer"), result(3, "")];
+
+        let index = promote_variant(&mut messages, 0, &results, 1);
+        assert_eq!(index, 1);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1].content, "creative answer");
+
+        let metadata = messages[1].metadata.as_ref().unwrap();
+        assert_eq!(metadata.sampling.as_ref().unwrap().label, "creative");
+        let kept: Vec<&str> = metadata.alternatives.iter().map(|a| a.content.as_str()).collect();
+        assert_eq!(kept, vec!["original", "precise answer"]);
+        assert!(metadata.alternatives[0].sampling.is_none());
+        // The later turn is untouched
+        assert_eq!(messages[2].content, "follow-up");
+    }
+
+    #[test]
+    fn test_promote_inserts_missing_reply() {
+        let mut messages = vec![
+            Message::new(Role::User, "question"),
+            Message::new(Role::User, "another"),
+        ];
+        let results = vec![result(0, "a"), result(1, "b")];
+        let index = promote_variant(&mut messages, 0, &results, 0);
+        assert_eq!(index, 1);
+        assert_eq!(messages[1].role, Role::Assistant);
+        assert_eq!(messages[1].content, "a");
+        assert_eq!(messages[2].content, "another");
+    }
+}
@@ -0,0 +1,366 @@
+//! Engine metrics and health
+//!
+//! Counters fed by the engine worker. Readers (the `/health` and `/metrics`
+//! endpoints of the local HTTP server, see `app::server`) only touch these
+//! atomics and short-held locks of their own, so they never wait on the
+//! engine mutex while a generation is running.
+
+use crate::inference::streaming::{GenerationStats, StopReason};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Upper bounds (seconds) of the generation duration histogram buckets
+const DURATION_BUCKETS: [f64; 8] = [0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/// Counters shared between the engine worker and metric readers
+#[derive(Debug)]
+pub struct EngineMetrics {
+    started_at: Instant,
+    model_loaded: AtomicBool,
+    model_name: RwLock<Option<String>>,
+    /// Requests submitted but not started yet
+    pending: AtomicU64,
+    /// Requests currently generating
+    active: AtomicU64,
+    requests_total: AtomicU64,
+    requests_cancelled: AtomicU64,
+    requests_failed: AtomicU64,
+    prompt_tokens_total: AtomicU64,
+    /// Prompt tokens a session found already in the KV cache
+    prompt_tokens_reused_total: AtomicU64,
+    generated_tokens_total: AtomicU64,
+    queue_wait_ms_total: AtomicU64,
+    prompt_ms_total: AtomicU64,
+    generation_ms_total: AtomicU64,
+    duration_buckets: [AtomicU64; DURATION_BUCKETS.len()],
+    /// Allocated context (tokens), 0 when none
+    context_size: AtomicU64,
+    /// Largest context the engine may use with the current settings and memory
+    context_limit: AtomicU64,
+    context_grows: AtomicU64,
+    /// Failed tool calls by error category label
+    tool_errors: Mutex<BTreeMap<String, u64>>,
+}
+
+/// Body of the `/health` endpoint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthStatus {
+    pub model_loaded: bool,
+    pub model_name: Option<String>,
+    pub queue_depth: u64,
+    pub active_requests: u64,
+    pub uptime_secs: u64,
+}
+
+impl EngineMetrics {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            model_loaded: AtomicBool::new(false),
+            model_name: RwLock::new(None),
+            pending: AtomicU64::new(0),
+            active: AtomicU64::new(0),
+            requests_total: AtomicU64::new(0),
+            requests_cancelled: AtomicU64::new(0),
+            requests_failed: AtomicU64::new(0),
+            prompt_tokens_total: AtomicU64::new(0),
+            prompt_tokens_reused_total: AtomicU64::new(0),
+            generated_tokens_total: AtomicU64::new(0),
+            queue_wait_ms_total: AtomicU64::new(0),
+            prompt_ms_total: AtomicU64::new(0),
+            generation_ms_total: AtomicU64::new(0),
+            duration_buckets: Default::default(),
+            context_size: AtomicU64::new(0),
+            context_limit: AtomicU64::new(0),
+            context_grows: AtomicU64::new(0),
+            tool_errors: Default::default(),
+        }
+    }
+
+    /// Record the loaded model (or `None` after unload)
+    pub fn set_model(&self, name: Option<String>) {
+        self.model_loaded.store(name.is_some(), Ordering::Relaxed);
+        if let Ok(mut current) = self.model_name.write() {
+            *current = name;
+        }
+    }
+
+    /// A generation request was submitted to the worker
+    pub fn record_enqueued(&self) {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        self.pending.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The worker picked up a request after waiting `wait` in the queue
+    pub fn record_started(&self, wait: Duration) {
+        saturating_dec(&self.pending);
+        self.active.fetch_add(1, Ordering::Relaxed);
+        self.queue_wait_ms_total.fetch_add(wait.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// A request finished (normally, truncated or cancelled)
+    pub fn record_finished(&self, stats: &GenerationStats) {
+        saturating_dec(&self.active);
+        if stats.stop_reason == StopReason::Cancelled {
+            self.requests_cancelled.fetch_add(1, Ordering::Relaxed);
+        }
+        self.prompt_tokens_total.fetch_add(stats.prompt_tokens as u64, Ordering::Relaxed);
+        self.generated_tokens_total.fetch_add(stats.tokens_generated as u64, Ordering::Relaxed);
+        self.prompt_ms_total.fetch_add(stats.prompt_ms, Ordering::Relaxed);
+        self.generation_ms_total.fetch_add(stats.generation_ms, Ordering::Relaxed);
+
+        let secs = (stats.prompt_ms + stats.generation_ms) as f64 / 1000.0;
+        for (bound, bucket) in DURATION_BUCKETS.iter().zip(&self.duration_buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// A session generation skipped `tokens` prompt tokens kept in the KV cache
+    pub fn record_prompt_reuse(&self, tokens: u32) {
+        self.prompt_tokens_reused_total.fetch_add(tokens as u64, Ordering::Relaxed);
+    }
+
+    /// A request failed before producing stats
+    pub fn record_failed(&self) {
+        saturating_dec(&self.active);
+        self.requests_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the allocated context and the size it may grow to
+    pub fn set_context(&self, size: u32, limit: u32) {
+        self.context_size.store(size as u64, Ordering::Relaxed);
+        self.context_limit.store(limit as u64, Ordering::Relaxed);
+    }
+
+    /// Auto context sizing recreated the context at a larger size
+    pub fn record_context_grow(&self) {
+        self.context_grows.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A tool call failed with an error of this category (e.g. `timeout`)
+    pub fn record_tool_error(&self, category: &str) {
+        if let Ok(mut errors) = self.tool_errors.lock() {
+            *errors.entry(category.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Failed tool calls of one category since startup
+    pub fn tool_errors(&self, category: &str) -> u64 {
+        self.tool_errors
+            .lock()
+            .ok()
+            .and_then(|errors| errors.get(category).copied())
+            .unwrap_or(0)
+    }
+
+    /// Allocated context in tokens (0 before the first generation)
+    pub fn context_size(&self) -> u32 {
+        self.context_size.load(Ordering::Relaxed) as u32
+    }
+
+    /// Context the engine may grow to (0 when not known yet)
+    pub fn context_limit(&self) -> u32 {
+        self.context_limit.load(Ordering::Relaxed) as u32
+    }
+
+    /// Snapshot for `/health`
+    pub fn health(&self) -> HealthStatus {
+        HealthStatus {
+            model_loaded: self.model_loaded.load(Ordering::Relaxed),
+            model_name: self.model_name.read().ok().and_then(|n| n.clone()),
+            queue_depth: self.pending.load(Ordering::Relaxed),
+            active_requests: self.active.load(Ordering::Relaxed),
+            uptime_secs: self.started_at.elapsed().as_secs(),
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let load = |c: &AtomicU64| c.load(Ordering::Relaxed);
+        let mut out = String::new();
+
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        };
+
+        metric("clawrs_model_loaded", "gauge", "Whether a model is loaded (1) or not (0).",
+            (self.model_loaded.load(Ordering::Relaxed) as u8).to_string());
+        metric("clawrs_uptime_seconds", "gauge", "Seconds since the engine started.",
+            self.started_at.elapsed().as_secs().to_string());
+        metric("clawrs_queue_depth", "gauge", "Generation requests waiting for the worker.",
+            load(&self.pending).to_string());
+        metric("clawrs_active_requests", "gauge", "Generation requests currently running.",
+            load(&self.active).to_string());
+        metric("clawrs_requests_total", "counter", "Generation requests submitted.",
+            load(&self.requests_total).to_string());
+        metric("clawrs_requests_cancelled_total", "counter", "Generation requests cancelled.",
+            load(&self.requests_cancelled).to_string());
+        metric("clawrs_requests_failed_total", "counter", "Generation requests that failed.",
+            load(&self.requests_failed).to_string());
+        metric("clawrs_prompt_tokens_total", "counter", "Prompt tokens evaluated.",
+            load(&self.prompt_tokens_total).to_string());
+        metric("clawrs_prompt_tokens_reused_total", "counter", "Prompt tokens reused from the KV cache instead of evaluated.",
+            load(&self.prompt_tokens_reused_total).to_string());
+        metric("clawrs_generated_tokens_total", "counter", "Tokens generated.",
+            load(&self.generated_tokens_total).to_string());
+        metric("clawrs_queue_wait_seconds_total", "counter", "Total time requests waited for the worker.",
+            format_secs(load(&self.queue_wait_ms_total)));
+        metric("clawrs_prompt_seconds_total", "counter", "Total prompt evaluation time.",
+            format_secs(load(&self.prompt_ms_total)));
+        metric("clawrs_context_size_tokens", "gauge", "Allocated context size.",
+            load(&self.context_size).to_string());
+        metric("clawrs_context_grows_total", "counter", "Times auto context sizing grew the context.",
+            load(&self.context_grows).to_string());
+
+        // Generation duration histogram (prompt + generation)
+        let name = "clawrs_generation_duration_seconds";
+        let _ = writeln!(out, "# HELP {} Duration of finished generations.", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (bound, bucket) in DURATION_BUCKETS.iter().zip(&self.duration_buckets) {
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, load(bucket));
+        }
+        let finished = load(&self.requests_total)
+            .saturating_sub(load(&self.pending))
+            .saturating_sub(load(&self.active))
+            .saturating_sub(load(&self.requests_failed));
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, finished);
+        let _ = writeln!(out, "{}_sum {}", name,
+            format_secs(load(&self.prompt_ms_total) + load(&self.generation_ms_total)));
+        let _ = writeln!(out, "{}_count {}", name, finished);
+
+        let name = "clawrs_tool_errors_total";
+        let _ = writeln!(out, "# HELP {} Failed tool calls by error category.", name);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        if let Ok(errors) = self.tool_errors.lock() {
+            for (category, count) in errors.iter() {
+                let _ = writeln!(out, "{}{{category=\"{}\"}} {}", name, category, count);
+            }
+        }
+
+        out
+    }
+}
+
+impl Default for EngineMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn saturating_dec(counter: &AtomicU64) {
+    let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| Some(v.saturating_sub(1)));
+}
+
+fn format_secs(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Minimal Prometheus text parser: returns sample name (with labels) -> value
+    fn parse_prometheus(text: &str) -> HashMap<String, f64> {
+        let mut samples = HashMap::new();
+        for line in text.lines() {
+            if line.starts_with("# HELP ") || line.starts_with("# TYPE ") {
+                assert!(line.split_whitespace().count() >= 4, "bad comment line: {}", line);
+                continue;
+            }
+            let (name, value) = line.rsplit_once(' ').expect("sample line has a value");
+            assert!(
+                name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_'),
+                "bad metric name: {}",
+                name
+            );
+            let value: f64 = value.parse().unwrap_or_else(|_| panic!("bad value in: {}", line));
+            samples.insert(name.to_string(), value);
+        }
+        samples
+    }
+
+    #[test]
+    fn test_counters_increment_across_mock_request() {
+        let metrics = EngineMetrics::new();
+        metrics.set_model(Some("qwen2.5-7b".to_string()));
+
+        metrics.record_enqueued();
+        assert_eq!(metrics.health().queue_depth, 1);
+
+        metrics.record_started(Duration::from_millis(250));
+        assert_eq!(metrics.health().queue_depth, 0);
+        assert_eq!(metrics.health().active_requests, 1);
+
+        metrics.record_finished(&GenerationStats {
+            prompt_tokens: 120,
+            tokens_generated: 40,
+            prompt_ms: 300,
+            generation_ms: 1200,
+            stop_reason: StopReason::Eos,
+        });
+        metrics.record_prompt_reuse(100);
+        metrics.record_context_grow();
+        metrics.set_context(8192, 32768);
+
+        let samples = parse_prometheus(&metrics.render_prometheus());
+        assert_eq!(samples["clawrs_model_loaded"], 1.0);
+        assert_eq!(samples["clawrs_requests_total"], 1.0);
+        assert_eq!(samples["clawrs_active_requests"], 0.0);
+        assert_eq!(samples["clawrs_generated_tokens_total"], 40.0);
+        assert_eq!(samples["clawrs_prompt_tokens_total"], 120.0);
+        assert_eq!(samples["clawrs_prompt_tokens_reused_total"], 100.0);
+        assert_eq!(samples["clawrs_queue_wait_seconds_total"], 0.25);
+        assert_eq!(samples["clawrs_generation_duration_seconds_bucket{le=\"1\"}"], 0.0);
+        assert_eq!(samples["clawrs_generation_duration_seconds_bucket{le=\"2.5\"}"], 1.0);
+        assert_eq!(samples["clawrs_generation_duration_seconds_count"], 1.0);
+        assert_eq!(samples["clawrs_generation_duration_seconds_sum"], 1.5);
+        assert_eq!(samples["clawrs_context_size_tokens"], 8192.0);
+        assert_eq!(samples["clawrs_context_grows_total"], 1.0);
+        assert_eq!(metrics.context_limit(), 32768);
+    }
+
+    #[test]
+    fn test_health_reflects_model_state() {
+        let metrics = EngineMetrics::new();
+        let health = metrics.health();
+        assert!(!health.model_loaded);
+        assert!(health.model_name.is_none());
+
+        metrics.set_model(Some("model".to_string()));
+        assert_eq!(metrics.health().model_name.as_deref(), Some("model"));
+
+        metrics.set_model(None);
+        assert!(!metrics.health().model_loaded);
+
+        metrics.record_enqueued();
+        metrics.record_started(Duration::ZERO);
+        metrics.record_failed();
+        let samples = parse_prometheus(&metrics.render_prometheus());
+        assert_eq!(samples["clawrs_requests_failed_total"], 1.0);
+        assert_eq!(samples["clawrs_generation_duration_seconds_count"], 0.0);
+    }
+
+    #[test]
+    fn test_tool_errors_aggregate_by_category() {
+        let metrics = EngineMetrics::new();
+        metrics.record_tool_error("timeout");
+        metrics.record_tool_error("timeout");
+        metrics.record_tool_error("not_found");
+
+        assert_eq!(metrics.tool_errors("timeout"), 2);
+        assert_eq!(metrics.tool_errors("network"), 0);
+        let samples = parse_prometheus(&metrics.render_prometheus());
+        assert_eq!(samples["clawrs_tool_errors_total{category=\"timeout\"}"], 2.0);
+        assert_eq!(samples["clawrs_tool_errors_total{category=\"not_found\"}"], 1.0);
+        assert!(!samples.contains_key("clawrs_tool_errors_total{category=\"network\"}"));
+    }
+}
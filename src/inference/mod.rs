@@ -2,11 +2,27 @@
 //!
 //! This module handles all interaction with llama-cpp for model loading and inference.
 
+pub mod backend;
+pub mod chat_template;
+pub mod context;
 pub mod engine;
+pub mod experiment;
+pub mod metrics;
 pub mod model;
+pub mod reasoning;
+pub mod remote;
 pub mod streaming;
+pub mod tokens;
+pub mod voice;
 
 // Re-export main types for convenience
-pub use engine::{EngineError, GenerationParams, LlamaEngine, LoadedModelInfo};
-pub use model::{validate_gguf, GgufMetadata, ModelError, GGUF_MAGIC};
-pub use streaming::StreamToken;
+pub use backend::InferenceBackend;
+pub use chat_template::ChatTemplate;
+pub use engine::{Embedder, EngineError, GenerationParams, GenerationSession, LlamaEngine, LoadControl, LoadPhase, LoadedModelInfo};
+pub use metrics::{EngineMetrics, HealthStatus};
+pub use model::{
+    check_gguf_layout, estimate_memory, read_gguf_metadata, validate_gguf, GgufLayout, GgufMetadata, LoadConfig, MemoryEstimate,
+    MemoryKind, MemoryShortfall, ModelError, RopeScaling, GGUF_MAGIC,
+};
+pub use remote::OpenAiCompatBackend;
+pub use streaming::{GenerationStats, StopReason, StreamToken};
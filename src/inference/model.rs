@@ -3,10 +3,12 @@
 //! Handles model loading, unloading, and configuration.
 
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 use thiserror::Error;
 
+use crate::inference::context::kv_bytes_per_token;
+
 /// GGUF magic bytes (little-endian: "GGUF")
 pub const GGUF_MAGIC: u32 = 0x46554747;
 
@@ -24,10 +26,16 @@ pub enum ModelError {
 
     #[error("File too small to be valid GGUF")]
     FileTooSmall,
+
+    #[error("Model file is truncated ({0}); the download may be incomplete")]
+    Truncated(String),
+
+    #[error("Malformed GGUF file: {0}")]
+    Malformed(String),
 }
 
 /// Metadata extracted from a GGUF file header
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct GgufMetadata {
     /// GGUF format version
     pub version: u32,
@@ -35,6 +43,32 @@ pub struct GgufMetadata {
     pub tensor_count: u64,
     /// Number of metadata key-value pairs
     pub metadata_kv_count: u64,
+    /// `general.architecture` (llama, qwen2, gemma2, ...)
+    pub architecture: Option<String>,
+    /// Context the model was trained for (`{arch}.context_length`, llama.cpp's n_ctx_train)
+    pub context_length: Option<u32>,
+    /// RoPE scaling stretching a shorter base context up to `context_length`
+    pub rope_scaling: Option<RopeScaling>,
+    /// Transformer layers (`{arch}.block_count`)
+    pub block_count: Option<u32>,
+    /// `{arch}.embedding_length`
+    pub embedding_length: Option<u32>,
+    /// `{arch}.attention.head_count`
+    pub head_count: Option<u32>,
+    /// `{arch}.attention.head_count_kv` (grouped-query attention)
+    pub head_count_kv: Option<u32>,
+    /// Bytes of tensor data, summed over the tensor table
+    pub tensor_bytes: u64,
+}
+
+/// RoPE scaling declared in the GGUF metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct RopeScaling {
+    /// Scaling method (`linear`, `yarn`, ...)
+    pub kind: String,
+    pub factor: f32,
+    /// Context before scaling, when the file records it
+    pub original_context_length: Option<u32>,
 }
 
 /// Validates that a file is a valid GGUF format and extracts basic metadata.
@@ -88,9 +122,469 @@ pub fn validate_gguf<P: AsRef<Path>>(path: P) -> Result<GgufMetadata, ModelError
         version,
         tensor_count,
         metadata_kv_count,
+        ..GgufMetadata::default()
     })
 }
 
+/// Reads the GGUF header along with the architecture, trained context,
+/// RoPE scaling and attention dimensions from its metadata, and the weight
+/// size from its tensor table, without touching the tensor data.
+pub fn read_gguf_metadata<P: AsRef<Path>>(path: P) -> Result<GgufMetadata, ModelError> {
+    let mut metadata = validate_gguf(&path)?;
+    let mut reader = GgufReader::open(&path)?;
+    read_metadata_kv(&mut reader, &mut metadata)?;
+    read_tensor_table(&mut reader, &mut metadata)?;
+    Ok(metadata)
+}
+
+/// How a model is going to be loaded, for memory estimates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadConfig {
+    /// Context the KV cache is allocated for
+    pub context_size: u32,
+    /// Layers offloaded to the GPU
+    pub gpu_layers: u32,
+}
+
+/// Memory a loaded model takes, split between RAM and VRAM
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    pub weights_bytes: u64,
+    /// f16 KV cache for the configured context
+    pub kv_cache_bytes: u64,
+    /// Compute buffers and runtime allocations
+    pub overhead_bytes: u64,
+    /// Part of the weights and KV cache offloaded to the GPU
+    pub gpu_bytes: u64,
+}
+
+/// Memory that would run out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryKind {
+    Ram,
+    Vram,
+}
+
+/// Estimated need above what is free
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryShortfall {
+    pub kind: MemoryKind,
+    pub needed_bytes: u64,
+    pub available_bytes: u64,
+}
+
+/// Need tolerated above free memory before a load is refused: the estimate is
+/// rough and the OS can reclaim caches
+pub const MEMORY_MARGIN: f64 = 0.10;
+
+/// Fixed runtime allocations of a loaded model (backend, scratch buffers)
+const BASE_OVERHEAD_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Embedding width assumed for compute buffers when the metadata has none
+const DEFAULT_EMBEDDING_LENGTH: u64 = 4096;
+
+impl MemoryEstimate {
+    pub fn total_bytes(&self) -> u64 {
+        self.weights_bytes + self.kv_cache_bytes + self.overhead_bytes
+    }
+
+    /// Part left in system memory
+    pub fn ram_bytes(&self) -> u64 {
+        self.total_bytes().saturating_sub(self.gpu_bytes)
+    }
+
+    /// Memory that would run short by more than [`MEMORY_MARGIN`], given free
+    /// RAM and VRAM. Unknown VRAM only checks the part kept in RAM.
+    pub fn shortfall(&self, free_ram: Option<u64>, free_vram: Option<u64>) -> Option<MemoryShortfall> {
+        let exceeds = |needed: u64, available: u64| needed as f64 > available as f64 * (1.0 + MEMORY_MARGIN);
+        if let Some(available) = free_vram {
+            if self.gpu_bytes > 0 && exceeds(self.gpu_bytes, available) {
+                return Some(MemoryShortfall { kind: MemoryKind::Vram, needed_bytes: self.gpu_bytes, available_bytes: available });
+            }
+        }
+        let available = free_ram?;
+        exceeds(self.ram_bytes(), available).then(|| MemoryShortfall {
+            kind: MemoryKind::Ram,
+            needed_bytes: self.ram_bytes(),
+            available_bytes: available,
+        })
+    }
+}
+
+/// Memory `model` needs once loaded with `config`: its weights, a KV cache
+/// for the context and compute buffers, with the offloaded layers' share on
+/// the GPU.
+pub fn estimate_memory(model: &GgufMetadata, config: &LoadConfig) -> MemoryEstimate {
+    let field = |value: Option<u32>| value.unwrap_or(0);
+    let kv_per_token = kv_bytes_per_token(
+        field(model.block_count),
+        field(model.embedding_length),
+        field(model.head_count),
+        field(model.head_count_kv),
+    );
+    let kv_cache_bytes = kv_per_token * config.context_size as u64;
+    let embedding = model.embedding_length.map_or(DEFAULT_EMBEDDING_LENGTH, u64::from);
+    let overhead_bytes = BASE_OVERHEAD_BYTES + config.context_size as u64 * embedding * 4;
+
+    // llama.cpp offloads whole layers; without a layer count any offload is taken as full
+    let gpu_share = match model.block_count {
+        _ if config.gpu_layers == 0 => 0.0,
+        Some(layers) if layers > 0 => config.gpu_layers.min(layers) as f64 / layers as f64,
+        _ => 1.0,
+    };
+    let gpu_bytes = ((model.tensor_bytes + kv_cache_bytes) as f64 * gpu_share) as u64;
+
+    MemoryEstimate {
+        weights_bytes: model.tensor_bytes,
+        kv_cache_bytes,
+        overhead_bytes,
+        gpu_bytes,
+    }
+}
+
+/// Default tensor data alignment when `general.alignment` is absent
+const GGUF_DEFAULT_ALIGNMENT: u64 = 32;
+
+/// Longest metadata key or tensor name accepted while scanning the header
+const GGUF_MAX_NAME_LEN: u64 = 64 * 1024;
+
+/// Layout of a GGUF file, read from its metadata and tensor table
+#[derive(Debug, Clone)]
+pub struct GgufLayout {
+    pub metadata: GgufMetadata,
+    /// Offset of the first byte of tensor data
+    pub data_offset: u64,
+    /// Smallest file size that holds every tensor
+    pub expected_size: u64,
+    /// Actual file size
+    pub file_size: u64,
+}
+
+/// Walks the metadata and tensor table of a GGUF file and checks that every
+/// tensor fits inside the file.
+///
+/// This catches interrupted downloads before llama.cpp maps the file. Values
+/// are skipped rather than read, so only keys and tensor names are allocated.
+pub fn check_gguf_layout<P: AsRef<Path>>(path: P) -> Result<GgufLayout, ModelError> {
+    let mut metadata = validate_gguf(&path)?;
+    let mut reader = GgufReader::open(&path)?;
+    let file_size = reader.file_size;
+    let alignment = read_metadata_kv(&mut reader, &mut metadata)?;
+    let data_end = read_tensor_table(&mut reader, &mut metadata)?;
+
+    let table_end = reader.position()?;
+    let data_offset = table_end.div_ceil(alignment) * alignment;
+    let expected_size = data_offset.saturating_add(data_end);
+    if expected_size > file_size {
+        return Err(ModelError::Truncated(format!(
+            "needs {} bytes, found {}",
+            expected_size, file_size
+        )));
+    }
+
+    Ok(GgufLayout {
+        metadata,
+        data_offset,
+        expected_size,
+        file_size,
+    })
+}
+
+/// Walks the tensor table, setting `metadata.tensor_bytes`, and returns the
+/// end of the furthest tensor relative to the data section
+fn read_tensor_table(reader: &mut GgufReader, metadata: &mut GgufMetadata) -> Result<u64, ModelError> {
+    let mut data_end = 0u64;
+    let mut tensor_bytes = 0u64;
+    for _ in 0..metadata.tensor_count {
+        let name = reader.read_name()?;
+        let n_dims = reader.read_u32()?;
+        if n_dims > 8 {
+            return Err(ModelError::Malformed(format!("tensor '{}' has {} dimensions", name, n_dims)));
+        }
+        let mut elements = 1u64;
+        for _ in 0..n_dims {
+            let dim = reader.read_u64()?;
+            elements = elements
+                .checked_mul(dim)
+                .ok_or_else(|| ModelError::Malformed(format!("tensor '{}' is too large", name)))?;
+        }
+        let ggml_type = reader.read_u32()?;
+        let offset = reader.read_u64()?;
+        let bytes = ggml_type_layout(ggml_type)
+            .map(|(block, size)| elements / block * size)
+            .unwrap_or(0);
+        tensor_bytes = tensor_bytes.saturating_add(bytes);
+        data_end = data_end.max(offset.saturating_add(bytes));
+    }
+    metadata.tensor_bytes = tensor_bytes;
+    Ok(data_end)
+}
+
+/// Metadata value kept while walking the key-value section
+enum MetaValue {
+    Int(u64),
+    Float(f32),
+    Str(String),
+}
+
+impl MetaValue {
+    fn as_u32(&self) -> Option<u32> {
+        match self {
+            MetaValue::Int(n) => u32::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+
+    fn as_f32(&self) -> Option<f32> {
+        match self {
+            MetaValue::Float(f) => Some(*f),
+            MetaValue::Int(n) => Some(*n as f32),
+            MetaValue::Str(_) => None,
+        }
+    }
+}
+
+/// Keys read under `{arch}.`; every other value is skipped
+const ARCH_KEYS: [&str; 9] = [
+    "context_length",
+    "block_count",
+    "embedding_length",
+    "attention.head_count",
+    "attention.head_count_kv",
+    "rope.scaling.type",
+    "rope.scaling.factor",
+    "rope.scaling.original_context_length",
+    "rope.scale_linear",
+];
+
+/// Walks the metadata key-value section, filling in the architecture, context
+/// and RoPE fields of `metadata`, and returns the tensor data alignment.
+///
+/// Architecture keys are matched once the whole section is read, since
+/// `general.architecture` is not guaranteed to come first.
+fn read_metadata_kv(reader: &mut GgufReader, metadata: &mut GgufMetadata) -> Result<u64, ModelError> {
+    let mut alignment = GGUF_DEFAULT_ALIGNMENT;
+    let mut candidates: Vec<(String, MetaValue)> = Vec::new();
+    for _ in 0..metadata.metadata_kv_count {
+        let key = reader.read_name()?;
+        let value_type = reader.read_u32()?;
+        if key == "general.alignment" && value_type == 4 {
+            alignment = reader.read_u32()? as u64;
+            if alignment == 0 || !alignment.is_power_of_two() {
+                return Err(ModelError::Malformed(format!("invalid alignment {}", alignment)));
+            }
+        } else if key == "general.architecture" && value_type == 8 {
+            metadata.architecture = Some(reader.read_name()?);
+        } else if ARCH_KEYS.iter().any(|suffix| key.ends_with(&format!(".{}", suffix))) {
+            match reader.read_scalar(value_type)? {
+                Some(value) => candidates.push((key, value)),
+                None => reader.skip_value(value_type)?,
+            }
+        } else {
+            reader.skip_value(value_type)?;
+        }
+    }
+
+    let Some(arch) = metadata.architecture.as_deref() else {
+        return Ok(alignment);
+    };
+    let value = |name: &str| {
+        let key = format!("{}.{}", arch, name);
+        candidates.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    };
+    metadata.context_length = value("context_length").and_then(MetaValue::as_u32);
+    metadata.block_count = value("block_count").and_then(MetaValue::as_u32);
+    metadata.embedding_length = value("embedding_length").and_then(MetaValue::as_u32);
+    metadata.head_count = value("attention.head_count").and_then(MetaValue::as_u32);
+    metadata.head_count_kv = value("attention.head_count_kv").and_then(MetaValue::as_u32);
+    let original_context_length = value("rope.scaling.original_context_length").and_then(MetaValue::as_u32);
+    metadata.rope_scaling = match value("rope.scaling.type") {
+        Some(MetaValue::Str(kind)) if kind != "none" => Some(RopeScaling {
+            kind: kind.clone(),
+            factor: value("rope.scaling.factor").and_then(MetaValue::as_f32).unwrap_or(1.0),
+            original_context_length,
+        }),
+        Some(_) => None,
+        // Older files only carry a linear factor
+        None => value("rope.scale_linear")
+            .and_then(MetaValue::as_f32)
+            .filter(|factor| *factor > 1.0)
+            .map(|factor| RopeScaling {
+                kind: "linear".to_string(),
+                factor,
+                original_context_length,
+            }),
+    };
+    Ok(alignment)
+}
+
+/// (elements per block, bytes per block) of a ggml tensor type
+///
+/// Unknown types return `None`; their tensors only count by offset.
+fn ggml_type_layout(ggml_type: u32) -> Option<(u64, u64)> {
+    Some(match ggml_type {
+        0 => (1, 4),      // F32
+        1 => (1, 2),      // F16
+        2 => (32, 18),    // Q4_0
+        3 => (32, 20),    // Q4_1
+        6 => (32, 22),    // Q5_0
+        7 => (32, 24),    // Q5_1
+        8 => (32, 34),    // Q8_0
+        9 => (32, 36),    // Q8_1
+        10 => (256, 84),  // Q2_K
+        11 => (256, 110), // Q3_K
+        12 => (256, 144), // Q4_K
+        13 => (256, 176), // Q5_K
+        14 => (256, 210), // Q6_K
+        15 => (256, 292), // Q8_K
+        16 => (256, 66),  // IQ2_XXS
+        17 => (256, 74),  // IQ2_XS
+        18 => (256, 98),  // IQ3_XXS
+        19 => (256, 50),  // IQ1_S
+        20 => (32, 18),   // IQ4_NL
+        21 => (256, 110), // IQ3_S
+        22 => (256, 82),  // IQ2_S
+        23 => (256, 136), // IQ4_XS
+        24 => (1, 1),     // I8
+        25 => (1, 2),     // I16
+        26 => (1, 4),     // I32
+        27 => (1, 8),     // I64
+        28 => (1, 8),     // F64
+        29 => (256, 56),  // IQ1_M
+        30 => (1, 2),     // BF16
+        34 => (256, 54),  // TQ1_0
+        35 => (256, 66),  // TQ2_0
+        _ => return None,
+    })
+}
+
+/// Sequential reader over a GGUF header that reports running off the end as truncation
+struct GgufReader {
+    inner: BufReader<File>,
+    file_size: u64,
+}
+
+impl GgufReader {
+    /// Reader positioned right after the fixed 24-byte header
+    fn open<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        let file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+        let mut reader = GgufReader {
+            inner: BufReader::new(file),
+            file_size,
+        };
+        reader.skip(24)?;
+        Ok(reader)
+    }
+
+    fn position(&mut self) -> Result<u64, ModelError> {
+        Ok(self.inner.stream_position()?)
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ModelError> {
+        let mut buf = [0u8; N];
+        self.inner.read_exact(&mut buf).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                ModelError::Truncated(format!("header ends at byte {}", self.file_size))
+            } else {
+                ModelError::FileOpen(e)
+            }
+        })?;
+        Ok(buf)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ModelError> {
+        Ok(u32::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ModelError> {
+        Ok(u64::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn skip(&mut self, len: u64) -> Result<(), ModelError> {
+        let target = self.position()?.saturating_add(len);
+        if target > self.file_size {
+            return Err(ModelError::Truncated(format!(
+                "header needs {} bytes, found {}",
+                target, self.file_size
+            )));
+        }
+        self.inner.seek(SeekFrom::Start(target))?;
+        Ok(())
+    }
+
+    fn read_name(&mut self) -> Result<String, ModelError> {
+        let len = self.read_u64()?;
+        if len > GGUF_MAX_NAME_LEN {
+            return Err(ModelError::Malformed(format!("name of {} bytes", len)));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.inner.read_exact(&mut buf).map_err(|_| {
+            ModelError::Truncated(format!("header ends at byte {}", self.file_size))
+        })?;
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    /// Read a number or string value; arrays and unknown types return `None`
+    /// with nothing consumed
+    fn read_scalar(&mut self, value_type: u32) -> Result<Option<MetaValue>, ModelError> {
+        Ok(Some(match value_type {
+            0 | 7 => MetaValue::Int(self.read_bytes::<1>()?[0] as u64),
+            1 => MetaValue::Int((self.read_bytes::<1>()?[0] as i8).max(0) as u64),
+            2 => MetaValue::Int(u16::from_le_bytes(self.read_bytes()?) as u64),
+            3 => MetaValue::Int(i16::from_le_bytes(self.read_bytes()?).max(0) as u64),
+            4 => MetaValue::Int(self.read_u32()? as u64),
+            5 => MetaValue::Int(i32::from_le_bytes(self.read_bytes()?).max(0) as u64),
+            6 => MetaValue::Float(f32::from_le_bytes(self.read_bytes()?)),
+            8 => MetaValue::Str(self.read_name()?),
+            10 => MetaValue::Int(self.read_u64()?),
+            11 => MetaValue::Int(i64::from_le_bytes(self.read_bytes()?).max(0) as u64),
+            12 => MetaValue::Float(f64::from_le_bytes(self.read_bytes()?) as f32),
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Skip one metadata value of the given GGUF type
+    fn skip_value(&mut self, value_type: u32) -> Result<(), ModelError> {
+        match value_type {
+            // u8, i8, bool
+            0 | 1 | 7 => self.skip(1),
+            // u16, i16
+            2 | 3 => self.skip(2),
+            // u32, i32, f32
+            4..=6 => self.skip(4),
+            // u64, i64, f64
+            10..=12 => self.skip(8),
+            // string
+            8 => {
+                let len = self.read_u64()?;
+                self.skip(len)
+            }
+            // array
+            9 => {
+                let item_type = self.read_u32()?;
+                let count = self.read_u64()?;
+                match item_type {
+                    0 | 1 | 7 => self.skip(count),
+                    2 | 3 => self.skip(count.saturating_mul(2)),
+                    4..=6 => self.skip(count.saturating_mul(4)),
+                    10..=12 => self.skip(count.saturating_mul(8)),
+                    _ => {
+                        if count > self.file_size {
+                            return Err(ModelError::Malformed(format!("array of {} items", count)));
+                        }
+                        for _ in 0..count {
+                            self.skip_value(item_type)?;
+                        }
+                        Ok(())
+                    }
+                }
+            }
+            other => Err(ModelError::Malformed(format!("unknown value type {}", other))),
+        }
+    }
+}
+
 /// Checks if a file appears to be a GGUF model file based on extension and magic bytes.
 pub fn is_gguf_file<P: AsRef<Path>>(path: P) -> bool {
     let path = path.as_ref();
@@ -164,6 +658,238 @@ mod tests {
         assert!(matches!(result, Err(ModelError::FileTooSmall)));
     }
 
+    /// GGUF v3 with an architecture string, an alignment and one 64x64 F32
+    /// tensor, cut to `data_len` bytes of tensor data
+    fn write_gguf_with_tensor(data_len: usize) -> NamedTempFile {
+        fn string(out: &mut Vec<u8>, s: &str) {
+            out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        let mut out = Vec::new();
+        out.extend_from_slice(&GGUF_MAGIC.to_le_bytes());
+        out.extend_from_slice(&3u32.to_le_bytes());
+        out.extend_from_slice(&1u64.to_le_bytes());
+        out.extend_from_slice(&2u64.to_le_bytes());
+        string(&mut out, "general.architecture");
+        out.extend_from_slice(&8u32.to_le_bytes());
+        string(&mut out, "llama");
+        string(&mut out, "general.alignment");
+        out.extend_from_slice(&4u32.to_le_bytes());
+        out.extend_from_slice(&32u32.to_le_bytes());
+        string(&mut out, "output.weight");
+        out.extend_from_slice(&2u32.to_le_bytes());
+        out.extend_from_slice(&64u64.to_le_bytes());
+        out.extend_from_slice(&64u64.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&0u64.to_le_bytes());
+        while out.len() % 32 != 0 {
+            out.push(0);
+        }
+        out.resize(out.len() + data_len, 0);
+
+        let mut file = tempfile::Builder::new().suffix(".gguf").tempfile().unwrap();
+        file.write_all(&out).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn test_check_gguf_layout_complete() {
+        let file = write_gguf_with_tensor(64 * 64 * 4);
+        let layout = check_gguf_layout(file.path()).unwrap();
+        assert_eq!(layout.data_offset, 160);
+        assert_eq!(layout.expected_size, 160 + 16384);
+        assert_eq!(layout.file_size, layout.expected_size);
+    }
+
+    #[test]
+    fn test_check_gguf_layout_truncated() {
+        let file = write_gguf_with_tensor(1000);
+        let result = check_gguf_layout(file.path());
+        assert!(matches!(result, Err(ModelError::Truncated(_))));
+
+        // Header promising tensors that are not there at all
+        let file = create_test_gguf();
+        assert!(matches!(check_gguf_layout(file.path()), Err(ModelError::Truncated(_))));
+    }
+
+    /// Metadata value of a test GGUF header
+    enum Kv<'a> {
+        U32(u32),
+        F32(f32),
+        Str(&'a str),
+        StrArray(&'a [&'a str]),
+    }
+
+    /// GGUF v3 with the given metadata and no tensors
+    fn write_gguf_with_metadata(kvs: &[(&str, Kv)]) -> NamedTempFile {
+        write_gguf_header(kvs, &[])
+    }
+
+    /// GGUF v3 header with the given metadata and 2D tensors given as
+    /// (ggml type, rows, columns), without tensor data
+    fn write_gguf_header(kvs: &[(&str, Kv)], tensors: &[(u32, u64, u64)]) -> NamedTempFile {
+        fn string(out: &mut Vec<u8>, s: &str) {
+            out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        let mut out = Vec::new();
+        out.extend_from_slice(&GGUF_MAGIC.to_le_bytes());
+        out.extend_from_slice(&3u32.to_le_bytes());
+        out.extend_from_slice(&(tensors.len() as u64).to_le_bytes());
+        out.extend_from_slice(&(kvs.len() as u64).to_le_bytes());
+        for (key, value) in kvs {
+            string(&mut out, key);
+            match value {
+                Kv::U32(n) => {
+                    out.extend_from_slice(&4u32.to_le_bytes());
+                    out.extend_from_slice(&n.to_le_bytes());
+                }
+                Kv::F32(f) => {
+                    out.extend_from_slice(&6u32.to_le_bytes());
+                    out.extend_from_slice(&f.to_le_bytes());
+                }
+                Kv::Str(s) => {
+                    out.extend_from_slice(&8u32.to_le_bytes());
+                    string(&mut out, s);
+                }
+                Kv::StrArray(items) => {
+                    out.extend_from_slice(&9u32.to_le_bytes());
+                    out.extend_from_slice(&8u32.to_le_bytes());
+                    out.extend_from_slice(&(items.len() as u64).to_le_bytes());
+                    for item in items.iter() {
+                        string(&mut out, item);
+                    }
+                }
+            }
+        }
+        for (i, (ggml_type, rows, columns)) in tensors.iter().enumerate() {
+            string(&mut out, &format!("blk.{}.weight", i));
+            out.extend_from_slice(&2u32.to_le_bytes());
+            out.extend_from_slice(&columns.to_le_bytes());
+            out.extend_from_slice(&rows.to_le_bytes());
+            out.extend_from_slice(&ggml_type.to_le_bytes());
+            out.extend_from_slice(&0u64.to_le_bytes());
+        }
+
+        let mut file = tempfile::Builder::new().suffix(".gguf").tempfile().unwrap();
+        file.write_all(&out).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn test_read_gguf_metadata_context_and_rope() {
+        // Architecture keys before `general.architecture`, as some converters write them
+        let file = write_gguf_with_metadata(&[
+            ("qwen2.context_length", Kv::U32(32768)),
+            ("tokenizer.ggml.tokens", Kv::StrArray(&["<s>", "</s>"])),
+            ("qwen2.rope.scaling.type", Kv::Str("yarn")),
+            ("qwen2.rope.scaling.factor", Kv::F32(4.0)),
+            ("qwen2.rope.scaling.original_context_length", Kv::U32(8192)),
+            ("general.architecture", Kv::Str("qwen2")),
+            ("llama.context_length", Kv::U32(4096)),
+        ]);
+        let metadata = read_gguf_metadata(file.path()).unwrap();
+        assert_eq!(metadata.architecture.as_deref(), Some("qwen2"));
+        assert_eq!(metadata.context_length, Some(32768));
+        assert_eq!(
+            metadata.rope_scaling,
+            Some(RopeScaling { kind: "yarn".to_string(), factor: 4.0, original_context_length: Some(8192) })
+        );
+    }
+
+    #[test]
+    fn test_read_gguf_metadata_without_scaling() {
+        let file = write_gguf_with_metadata(&[
+            ("general.architecture", Kv::Str("llama")),
+            ("llama.context_length", Kv::U32(8192)),
+            ("llama.rope.scaling.type", Kv::Str("none")),
+        ]);
+        let metadata = read_gguf_metadata(file.path()).unwrap();
+        assert_eq!(metadata.context_length, Some(8192));
+        assert_eq!(metadata.rope_scaling, None);
+
+        // Older files with only a linear factor
+        let file = write_gguf_with_metadata(&[
+            ("general.architecture", Kv::Str("llama")),
+            ("llama.context_length", Kv::U32(16384)),
+            ("llama.rope.scale_linear", Kv::F32(4.0)),
+        ]);
+        let scaling = read_gguf_metadata(file.path()).unwrap().rope_scaling.unwrap();
+        assert_eq!((scaling.kind.as_str(), scaling.factor), ("linear", 4.0));
+
+        // Nothing to go by without an architecture
+        let file = write_gguf_with_metadata(&[("llama.context_length", Kv::U32(8192))]);
+        let metadata = read_gguf_metadata(file.path()).unwrap();
+        assert_eq!((metadata.architecture, metadata.context_length), (None, None));
+    }
+
+    /// Two-layer model with two 256x256 weight tensors of the given types
+    fn quantized_model(types: [u32; 2]) -> GgufMetadata {
+        let file = write_gguf_header(
+            &[
+                ("general.architecture", Kv::Str("llama")),
+                ("llama.block_count", Kv::U32(2)),
+                ("llama.embedding_length", Kv::U32(256)),
+                ("llama.attention.head_count", Kv::U32(4)),
+                ("llama.attention.head_count_kv", Kv::U32(4)),
+            ],
+            &[(types[0], 256, 256), (types[1], 256, 256)],
+        );
+        read_gguf_metadata(file.path()).unwrap()
+    }
+
+    #[test]
+    fn test_estimate_memory_across_quantizations() {
+        let config = LoadConfig { context_size: 1024, gpu_layers: 0 };
+        // Q4_K_M mixes Q4_K with Q6_K for the sensitive tensors
+        let q4_k_m = estimate_memory(&quantized_model([12, 14]), &config);
+        let q8_0 = estimate_memory(&quantized_model([8, 8]), &config);
+        let f16 = estimate_memory(&quantized_model([1, 1]), &config);
+        assert_eq!(q4_k_m.weights_bytes, 256 * 144 + 256 * 210);
+        assert_eq!(q8_0.weights_bytes, 2 * 2048 * 34);
+        assert_eq!(f16.weights_bytes, 2 * 65536 * 2);
+
+        // The KV cache and overhead only depend on the architecture and context
+        for estimate in [q4_k_m, q8_0, f16] {
+            assert_eq!(estimate.kv_cache_bytes, 2048 * 1024);
+            assert_eq!(estimate.overhead_bytes, BASE_OVERHEAD_BYTES + 1024 * 256 * 4);
+            assert_eq!(estimate.gpu_bytes, 0);
+            assert_eq!(estimate.ram_bytes(), estimate.total_bytes());
+        }
+        assert!(q4_k_m.total_bytes() < q8_0.total_bytes() && q8_0.total_bytes() < f16.total_bytes());
+
+        // A longer context grows the KV cache linearly
+        let long = estimate_memory(&quantized_model([8, 8]), &LoadConfig { context_size: 4096, gpu_layers: 0 });
+        assert_eq!(long.kv_cache_bytes, 4 * q8_0.kv_cache_bytes);
+    }
+
+    #[test]
+    fn test_estimate_memory_offload_and_shortfall() {
+        let model = quantized_model([1, 1]);
+        let half = estimate_memory(&model, &LoadConfig { context_size: 1024, gpu_layers: 1 });
+        assert_eq!(half.gpu_bytes, (2 * 65536 * 2 + 2048 * 1024) / 2);
+        let full = estimate_memory(&model, &LoadConfig { context_size: 1024, gpu_layers: 99 });
+        assert_eq!(full.gpu_bytes, full.weights_bytes + full.kv_cache_bytes);
+        assert_eq!(full.ram_bytes(), full.overhead_bytes);
+
+        let cpu = estimate_memory(&model, &LoadConfig { context_size: 1024, gpu_layers: 0 });
+        let total = cpu.total_bytes();
+        assert_eq!(cpu.shortfall(Some(total), None), None);
+        // Within the margin still loads
+        assert_eq!(cpu.shortfall(Some(total * 100 / 105), None), None);
+        assert_eq!(
+            cpu.shortfall(Some(total / 2), None),
+            Some(MemoryShortfall { kind: MemoryKind::Ram, needed_bytes: total, available_bytes: total / 2 })
+        );
+        assert_eq!(cpu.shortfall(None, None), None);
+
+        assert_eq!(full.shortfall(Some(total), Some(1024)).map(|s| s.kind), Some(MemoryKind::Vram));
+        // Unknown VRAM only checks what stays in RAM
+        assert_eq!(full.shortfall(Some(full.ram_bytes()), None), None);
+    }
+
     #[test]
     fn test_is_gguf_file() {
         let file = create_test_gguf();
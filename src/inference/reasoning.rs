@@ -0,0 +1,112 @@
+//! Reasoning ("thinking mode") models
+//!
+//! Models such as DeepSeek-R1 distills and QwQ open every reply with a
+//! `<think>` block. They are recognized at load time from their name and chat
+//! template, or by a short probe generation, and get a larger output budget so
+//! the block is not cut off by `max_tokens`.
+
+/// Name fragments of known reasoning model families (lowercase)
+const REASONING_NAME_HINTS: &[&str] = &[
+    "deepseek-r1",
+    "r1-distill",
+    "qwq",
+    "qwen3",
+    "reasoning",
+    "thinking",
+    "magistral",
+    "exaone-deep",
+    "openthinker",
+    "skywork-or1",
+];
+
+/// Name fragments of variants that never think, checked first (lowercase)
+const NON_REASONING_NAME_HINTS: &[&str] = &["instruct-2507", "no-think", "nothink"];
+
+/// Output budget a reasoning model gets at least, so its thinking fits
+pub const REASONING_MIN_OUTPUT_TOKENS: u32 = 8192;
+
+/// Prompt of the probe generation: trivial, so any thinking is the model's habit
+pub const PROBE_PROMPT: &str = "What is 17 + 25? Reply with the number only.";
+
+/// Tokens the probe may generate; enough to see an opening `<think>`
+pub const PROBE_MAX_TOKENS: u32 = 24;
+
+/// Whether the model metadata points at a reasoning model
+///
+/// `file_name` is the GGUF file name, `general_name` the `general.name`
+/// metadata and `chat_template` the `tokenizer.chat_template` metadata.
+pub fn detect_reasoning_model(file_name: &str, general_name: Option<&str>, chat_template: Option<&str>) -> bool {
+    let names = format!("{} {}", file_name, general_name.unwrap_or_default())
+        .to_lowercase()
+        .replace(['_', ' '], "-");
+    if NON_REASONING_NAME_HINTS.iter().any(|hint| names.contains(hint)) {
+        return false;
+    }
+    // Templates of reasoning models strip or prefill the <think> block
+    if chat_template.is_some_and(|t| t.contains("<think>") || t.contains("</think>")) {
+        return true;
+    }
+    REASONING_NAME_HINTS.iter().any(|hint| names.contains(hint))
+}
+
+/// Whether a probe reply started with a thinking block
+pub fn probe_shows_thinking(reply: &str) -> bool {
+    let reply = reply.trim_start();
+    reply.starts_with("<think>") || reply.starts_with("<thinking>")
+}
+
+/// Output budget for a generation: reasoning models get room to think
+pub fn output_budget(max_tokens: u32, reasoning: bool) -> u32 {
+    if reasoning {
+        max_tokens.max(REASONING_MIN_OUTPUT_TOKENS)
+    } else {
+        max_tokens
+    }
+}
+
+/// Whether `text` ends inside an unclosed `<think>` block
+pub fn ends_inside_think_block(text: &str) -> bool {
+    let open = text.rfind("<think>");
+    let close = text.rfind("</think>");
+    match (open, close) {
+        (Some(open), Some(close)) => open > close,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_reasoning_models_from_metadata() {
+        assert!(detect_reasoning_model("DeepSeek-R1-Distill-Qwen-7B-Q4_K_M.gguf", None, None));
+        assert!(detect_reasoning_model("qwq-32b-q4_k_m.gguf", Some("QwQ 32B"), None));
+        assert!(detect_reasoning_model("model.gguf", Some("Qwen3 8B"), None));
+        // The chat template gives it away even under a neutral name
+        assert!(detect_reasoning_model("model.gguf", None, Some("{% if '</think>' in content %}...")));
+
+        assert!(!detect_reasoning_model("Qwen2.5-7B-Instruct-Q4_K_M.gguf", Some("Qwen2.5 7B Instruct"), None));
+        assert!(!detect_reasoning_model("Qwen3-4B-Instruct-2507-Q4_K_M.gguf", None, None));
+        assert!(!detect_reasoning_model("llama-3.1-8b.gguf", Some("Llama 3.1 8B"), Some("{{ bos_token }}")));
+    }
+
+    #[test]
+    fn test_probe_and_budget() {
+        assert!(probe_shows_thinking("  <think>\nThe user asks"));
+        assert!(!probe_shows_thinking("42"));
+
+        assert_eq!(output_budget(4096, true), REASONING_MIN_OUTPUT_TOKENS);
+        assert_eq!(output_budget(16384, true), 16384);
+        assert_eq!(output_budget(4096, false), 4096);
+    }
+
+    #[test]
+    fn test_ends_inside_think_block() {
+        assert!(ends_inside_think_block("<think>\nFirst, the file"));
+        assert!(!ends_inside_think_block("<think>done</think>\nThe answer is 42"));
+        assert!(ends_inside_think_block("<think>a</think> ok <think>again"));
+        assert!(!ends_inside_think_block("plain answer"));
+    }
+}
@@ -0,0 +1,375 @@
+//! OpenAI-compatible remote backend
+//!
+//! Streams `/v1/chat/completions` from a server such as Ollama or
+//! llama-server when the model is too large to run locally. Server-sent
+//! events are mapped onto `StreamToken`, and HTTP failures come through as
+//! `StreamToken::Error` with a readable message.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::inference::backend::InferenceBackend;
+use crate::inference::engine::{EngineError, GenerationParams};
+use crate::inference::streaming::{GenerationStats, StopReason, StreamToken};
+use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+
+/// How long the server may take to start answering
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Longest silence between two chunks (prompt processing included)
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Generates through an OpenAI-compatible `/v1/chat/completions` endpoint
+pub struct OpenAiCompatBackend {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    client: reqwest::Client,
+    /// Stop signal of the most recent generation (for `cancel_generation`)
+    active_stop: std::sync::Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl OpenAiCompatBackend {
+    /// `base_url` is the server root or its `/v1` path; a blank `api_key` sends none
+    pub fn new(base_url: &str, api_key: &str, model: &str) -> Result<Self, EngineError> {
+        let base_url = base_url.trim().trim_end_matches('/');
+        if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+            return Err(EngineError::BackendInit(format!("Invalid server URL: {}", base_url)));
+        }
+        if model.trim().is_empty() {
+            return Err(EngineError::BackendInit("No model name given for the server".to_string()));
+        }
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent(concat!("clawRS/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| EngineError::BackendInit(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            base_url: base_url.to_string(),
+            api_key: Some(api_key.trim().to_string()).filter(|key| !key.is_empty()),
+            model: model.trim().to_string(),
+            client,
+            active_stop: std::sync::Mutex::new(None),
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn completions_url(&self) -> String {
+        if self.base_url.ends_with("/v1") {
+            format!("{}/chat/completions", self.base_url)
+        } else {
+            format!("{}/v1/chat/completions", self.base_url)
+        }
+    }
+
+    fn request_body(&self, messages: &[ChatMessage], params: &GenerationParams) -> serde_json::Value {
+        let messages: Vec<serde_json::Value> = messages
+            .iter()
+            .map(|message| {
+                let role = match message.role {
+                    ChatRole::System => "system",
+                    ChatRole::Assistant => "assistant",
+                    // The `tool` role needs a tool_call_id, which the text tool protocol has not
+                    ChatRole::User | ChatRole::Tool => "user",
+                };
+                json!({ "role": role, "content": message.content })
+            })
+            .collect();
+        let mut body = json!({
+            "model": self.model,
+            "messages": messages,
+            "stream": true,
+            "stream_options": { "include_usage": true },
+            "max_tokens": params.max_tokens,
+            "temperature": params.temperature,
+            "top_p": params.top_p,
+        });
+        if params.seed != 0 {
+            body["seed"] = json!(params.seed);
+        }
+        // OpenAI accepts at most four stop sequences
+        if !params.stop_sequences.is_empty() {
+            body["stop"] = json!(params.stop_sequences.iter().take(4).collect::<Vec<_>>());
+        }
+        if params.frequency_penalty != 0.0 {
+            body["frequency_penalty"] = json!(params.frequency_penalty);
+        }
+        if params.presence_penalty != 0.0 {
+            body["presence_penalty"] = json!(params.presence_penalty);
+        }
+        body
+    }
+}
+
+impl InferenceBackend for OpenAiCompatBackend {
+    fn model_name(&self) -> String {
+        self.model.clone()
+    }
+
+    fn generate_stream_messages(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|_| EngineError::BackendNotInitialized)?;
+        let (token_tx, token_rx) = mpsc::channel();
+        let stop_signal = Arc::new(AtomicBool::new(false));
+
+        let mut request = self
+            .client
+            .post(self.completions_url())
+            .json(&self.request_body(&messages, &params));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let stop = stop_signal.clone();
+        runtime.spawn(async move {
+            if let Err(e) = stream_completion(request, &params, &token_tx, &stop).await {
+                tracing::error!("Remote generation failed: {}", e);
+                let _ = token_tx.send(StreamToken::Error(e));
+            }
+        });
+
+        if let Ok(mut active) = self.active_stop.lock() {
+            *active = Some(stop_signal.clone());
+        }
+        Ok((token_rx, stop_signal))
+    }
+
+    fn cancel_generation(&self) {
+        if let Ok(active) = self.active_stop.lock() {
+            if let Some(stop) = active.as_ref() {
+                stop.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    usage: Option<ChunkUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    content: Option<String>,
+    /// Thinking of reasoning models, as llama-server and some APIs send it
+    reasoning_content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+}
+
+/// One `data:` line of the event stream
+#[derive(Debug, PartialEq)]
+enum SseEvent {
+    Data(String),
+    Done,
+}
+
+fn parse_sse_line(line: &str) -> Option<SseEvent> {
+    let data = line.trim_end_matches(['\r', '\n']).strip_prefix("data:")?.trim();
+    match data {
+        "" => None,
+        "[DONE]" => Some(SseEvent::Done),
+        data => Some(SseEvent::Data(data.to_string())),
+    }
+}
+
+/// Readable message for a failed HTTP status, with the server's own when it sent one
+fn http_error_message(status: u16, body: &str) -> String {
+    let detail = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v["error"]["message"].as_str().or(v["error"].as_str()).map(str::to_string))
+        .unwrap_or_else(|| body.trim().chars().take(200).collect());
+    let summary = match status {
+        401 | 403 => "The server refused the API key",
+        404 => "Endpoint or model not found on the server",
+        429 => "The server is rate limiting requests, try again in a moment",
+        500..=599 => "The server failed to generate",
+        _ => "The server rejected the request",
+    };
+    if detail.is_empty() {
+        format!("{} (HTTP {})", summary, status)
+    } else {
+        format!("{} (HTTP {}): {}", summary, status, detail)
+    }
+}
+
+fn request_error_message(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        "The server did not answer in time".to_string()
+    } else if e.is_connect() {
+        format!("Could not reach the server: {}", e)
+    } else {
+        format!("Request to the server failed: {}", e)
+    }
+}
+
+async fn stream_completion(
+    request: reqwest::RequestBuilder,
+    params: &GenerationParams,
+    tx: &Sender<StreamToken>,
+    stop_signal: &AtomicBool,
+) -> Result<(), String> {
+    let started = Instant::now();
+    let mut response = request.send().await.map_err(|e| request_error_message(&e))?;
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(http_error_message(status, &body));
+    }
+
+    // Bytes, so a character split across two chunks is decoded whole
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut first_token: Option<Instant> = None;
+    let mut chunks = 0u32;
+    let mut usage = None;
+    let mut finish_reason = None;
+    let mut in_reasoning = false;
+    let mut cancelled = false;
+
+    'stream: loop {
+        if stop_signal.load(Ordering::Relaxed) {
+            cancelled = true;
+            break;
+        }
+        let chunk = tokio::time::timeout(CHUNK_TIMEOUT, response.chunk())
+            .await
+            .map_err(|_| "The server stopped sending tokens".to_string())?
+            .map_err(|e| request_error_message(&e))?;
+        let Some(chunk) = chunk else { break };
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            match parse_sse_line(&line) {
+                None => {}
+                Some(SseEvent::Done) => break 'stream,
+                Some(SseEvent::Data(data)) => {
+                    let parsed: CompletionChunk = serde_json::from_str(&data)
+                        .map_err(|e| format!("Unexpected reply from the server: {}", e))?;
+                    if parsed.usage.is_some() {
+                        usage = parsed.usage;
+                    }
+                    let Some(choice) = parsed.choices.into_iter().next() else { continue };
+                    let mut text = String::new();
+                    if let Some(reasoning) = choice.delta.reasoning_content.filter(|r| !r.is_empty()) {
+                        if !in_reasoning {
+                            text.push_str("<think>");
+                            in_reasoning = true;
+                        }
+                        text.push_str(&reasoning);
+                    }
+                    if let Some(content) = choice.delta.content.filter(|c| !c.is_empty()) {
+                        if in_reasoning {
+                            text.push_str("</think>");
+                            in_reasoning = false;
+                        }
+                        text.push_str(&content);
+                    }
+                    if !text.is_empty() {
+                        first_token.get_or_insert_with(Instant::now);
+                        chunks += 1;
+                        if tx.send(StreamToken::Token(text)).is_err() {
+                            // Nobody is reading anymore
+                            return Ok(());
+                        }
+                    }
+                    if choice.finish_reason.is_some() {
+                        finish_reason = choice.finish_reason;
+                    }
+                }
+            }
+        }
+    }
+    if in_reasoning {
+        let _ = tx.send(StreamToken::Token("</think>".to_string()));
+    }
+
+    let first_token = first_token.unwrap_or_else(Instant::now);
+    let stop_reason = if cancelled {
+        StopReason::Cancelled
+    } else if finish_reason.as_deref() == Some("length") {
+        StopReason::MaxTokens
+    } else {
+        StopReason::Eos
+    };
+    let stats = GenerationStats {
+        prompt_tokens: usage.as_ref().map(|u| u.prompt_tokens).unwrap_or(0),
+        tokens_generated: usage.as_ref().map(|u| u.completion_tokens).unwrap_or(chunks),
+        prompt_ms: first_token.duration_since(started).as_millis() as u64,
+        generation_ms: first_token.elapsed().as_millis() as u64,
+        stop_reason,
+    };
+    let tokens_generated = stats.tokens_generated;
+    let _ = tx.send(StreamToken::Stats(stats));
+    if stop_reason == StopReason::MaxTokens {
+        let _ = tx.send(StreamToken::Truncated { tokens_generated, max_tokens: params.max_tokens });
+    } else {
+        let _ = tx.send(StreamToken::Done);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sse_lines() {
+        assert_eq!(parse_sse_line("data: {\"a\":1}\r\n"), Some(SseEvent::Data("{\"a\":1}".to_string())));
+        assert_eq!(parse_sse_line("data: [DONE]"), Some(SseEvent::Done));
+        assert_eq!(parse_sse_line(": keep-alive"), None);
+        assert_eq!(parse_sse_line("\n"), None);
+
+        let chunk: CompletionChunk = serde_json::from_str(
+            r#"{"choices":[{"index":0,"delta":{"content":"Hi"},"finish_reason":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(chunk.choices[0].delta.content.as_deref(), Some("Hi"));
+    }
+
+    #[test]
+    fn test_http_errors_are_readable() {
+        let unauthorized = http_error_message(401, r#"{"error":{"message":"Invalid API key"}}"#);
+        assert_eq!(unauthorized, "The server refused the API key (HTTP 401): Invalid API key");
+        assert!(http_error_message(429, "").starts_with("The server is rate limiting"));
+        assert_eq!(
+            http_error_message(404, r#"{"error":"model 'x' not found"}"#),
+            "Endpoint or model not found on the server (HTTP 404): model 'x' not found"
+        );
+    }
+
+    #[test]
+    fn test_completions_url() {
+        let backend = OpenAiCompatBackend::new("http://localhost:11434/v1/", "", "qwen3").unwrap();
+        assert_eq!(backend.completions_url(), "http://localhost:11434/v1/chat/completions");
+        assert!(backend.api_key.is_none());
+        let backend = OpenAiCompatBackend::new("http://localhost:8080", "sk-1", "m").unwrap();
+        assert_eq!(backend.completions_url(), "http://localhost:8080/v1/chat/completions");
+        assert!(OpenAiCompatBackend::new("localhost:8080", "", "m").is_err());
+    }
+}
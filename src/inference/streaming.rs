@@ -2,11 +2,57 @@
 //!
 //! Handles token-by-token streaming output from the model.
 
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::Duration;
+
+/// Why a generation ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// The model emitted an end-of-generation token
+    Eos,
+    /// The max_tokens budget was exhausted
+    MaxTokens,
+    /// The caller raised the stop signal
+    Cancelled,
+}
+
+/// Statistics for a finished generation, sent right before the terminal token
+///
+/// Also kept in the metadata of the reply it produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    /// Number of prompt tokens evaluated
+    pub prompt_tokens: u32,
+    /// Number of tokens generated
+    pub tokens_generated: u32,
+    /// Time spent evaluating the prompt (ms)
+    pub prompt_ms: u64,
+    /// Time spent generating tokens (ms)
+    pub generation_ms: u64,
+    /// Why the generation ended
+    pub stop_reason: StopReason,
+}
+
+impl GenerationStats {
+    /// Generation speed in tokens per second
+    pub fn tokens_per_second(&self) -> f64 {
+        if self.generation_ms == 0 {
+            return 0.0;
+        }
+        self.tokens_generated as f64 * 1000.0 / self.generation_ms as f64
+    }
+}
+
 /// Represents a token emitted during streaming inference.
 #[derive(Debug, Clone)]
 pub enum StreamToken {
     /// A generated token string
     Token(String),
+    /// Statistics for the generation (emitted just before `Done`/`Truncated`)
+    Stats(GenerationStats),
     /// Generation completed successfully (EOS token reached)
     Done,
     /// Generation hit max_tokens limit without EOS (response may be incomplete)
@@ -53,9 +99,112 @@ impl StreamToken {
     }
 }
 
+/// Cuts a token stream at the first stop sequence
+///
+/// Text that could be the start of a stop sequence is held back until the
+/// next tokens settle it, so the sequence itself is never streamed.
+#[derive(Debug, Default)]
+pub struct StopSequences {
+    stops: Vec<String>,
+    pending: String,
+    stopped: bool,
+}
+
+impl StopSequences {
+    pub fn new(stops: &[String]) -> Self {
+        Self {
+            stops: stops.iter().filter(|s| !s.is_empty()).cloned().collect(),
+            pending: String::new(),
+            stopped: false,
+        }
+    }
+
+    /// True once a stop sequence was completed
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Feed newly generated text; returns the text that is safe to stream
+    /// and whether a stop sequence just completed
+    pub fn push(&mut self, text: &str) -> (String, bool) {
+        if self.stopped {
+            return (String::new(), true);
+        }
+        if self.stops.is_empty() {
+            return (text.to_string(), false);
+        }
+        self.pending.push_str(text);
+
+        if let Some(at) = self.stops.iter().filter_map(|stop| self.pending.find(stop.as_str())).min() {
+            self.pending.truncate(at);
+            self.stopped = true;
+            return (std::mem::take(&mut self.pending), true);
+        }
+
+        // Keep the longest tail that a stop sequence starts with
+        let held = self
+            .stops
+            .iter()
+            .filter_map(|stop| {
+                stop.char_indices()
+                    .skip(1)
+                    .map(|(i, _)| i)
+                    .filter(|&i| self.pending.ends_with(&stop[..i]))
+                    .max()
+            })
+            .max()
+            .unwrap_or(0);
+        let released = self.pending.len() - held;
+        let rest = self.pending.split_off(released);
+        (std::mem::replace(&mut self.pending, rest), false)
+    }
+
+    /// Text still held back when the generation ends without a stop sequence
+    pub fn finish(&mut self) -> String {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Drain a token stream into a string without blocking the async runtime.
+///
+/// When `cancel` is raised, the worker's `stop_signal` is set so the engine
+/// bails out promptly; the stream is still drained until its terminal token
+/// so the worker is free again when this returns.
+pub async fn collect_stream_text(
+    rx: Receiver<StreamToken>,
+    stop_signal: &AtomicBool,
+    cancel: &AtomicBool,
+) -> (String, Option<GenerationStats>) {
+    let mut text = String::new();
+    let mut stats = None;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            stop_signal.store(true, Ordering::Relaxed);
+        }
+
+        match rx.try_recv() {
+            Ok(StreamToken::Token(t)) => text.push_str(&t),
+            Ok(StreamToken::Stats(s)) => stats = Some(s),
+            Ok(StreamToken::Done) | Ok(StreamToken::Truncated { .. }) => break,
+            Ok(StreamToken::Error(e)) => {
+                tracing::warn!("Background generation failed: {}", e);
+                break;
+            }
+            Err(TryRecvError::Empty) => tokio::time::sleep(Duration::from_millis(5)).await,
+            Err(TryRecvError::Disconnected) => break,
+        }
+    }
+
+    (text, stats)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::time::Instant;
 
     #[test]
     fn test_stream_token_variants() {
@@ -76,4 +225,83 @@ mod tests {
         assert!(error.is_error());
         assert_eq!(error.as_error(), Some("test error"));
     }
+
+    #[test]
+    fn test_tokens_per_second() {
+        let stats = GenerationStats {
+            prompt_tokens: 10,
+            tokens_generated: 50,
+            prompt_ms: 100,
+            generation_ms: 2000,
+            stop_reason: StopReason::Eos,
+        };
+        assert!((stats.tokens_per_second() - 25.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_stop_sequence_is_never_streamed() {
+        let mut stops = StopSequences::new(&["<tool_result>".to_string()]);
+        assert_eq!(stops.push("Done.\n<tool"), ("Done.\n".to_string(), false));
+        assert_eq!(stops.push("_res"), (String::new(), false));
+        assert_eq!(stops.push("ult>fake"), (String::new(), true));
+        assert!(stops.is_stopped());
+        assert_eq!(stops.push("more"), (String::new(), true));
+
+        // A partial match that does not complete is released
+        let mut stops = StopSequences::new(&["STOP".to_string(), "".to_string()]);
+        assert_eq!(stops.push("ST"), (String::new(), false));
+        assert_eq!(stops.push("ay"), ("STay".to_string(), false));
+        assert_eq!(stops.push("é S"), ("é ".to_string(), false));
+        assert_eq!(stops.finish(), "S");
+
+        let mut none = StopSequences::new(&[]);
+        assert_eq!(none.push("<tool_result>"), ("<tool_result>".to_string(), false));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_releases_lock_quickly() {
+        // Fake slow backend: one token every 10ms, honors the stop signal per token
+        let engine_lock = Arc::new(tokio::sync::Mutex::new(()));
+        let (tx, rx) = mpsc::channel();
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let worker_stop = stop_signal.clone();
+        std::thread::spawn(move || {
+            let mut generated = 0u32;
+            for _ in 0..10_000 {
+                if worker_stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+                generated += 1;
+                let _ = tx.send(StreamToken::Token("x".to_string()));
+            }
+            let _ = tx.send(StreamToken::Stats(GenerationStats {
+                prompt_tokens: 1,
+                tokens_generated: generated,
+                prompt_ms: 0,
+                generation_ms: generated as u64 * 10,
+                stop_reason: StopReason::Cancelled,
+            }));
+            let _ = tx.send(StreamToken::Done);
+        });
+
+        let lock = engine_lock.clone();
+        let task_stop = stop_signal.clone();
+        let task_cancel = cancel.clone();
+        let handle = tokio::spawn(async move {
+            let _guard = lock.lock().await;
+            collect_stream_text(rx, &task_stop, &task_cancel).await
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let cancelled_at = Instant::now();
+        cancel.store(true, Ordering::Relaxed);
+
+        let (_text, stats) = handle.await.unwrap();
+        let _guard = engine_lock.lock().await;
+        assert!(cancelled_at.elapsed() < Duration::from_millis(500));
+        assert_eq!(stats.map(|s| s.stop_reason), Some(StopReason::Cancelled));
+    }
 }
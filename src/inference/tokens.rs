@@ -0,0 +1,72 @@
+//! Token counting
+//!
+//! With a model loaded, counts come from its tokenizer (see
+//! `LlamaEngine::count_tokens`). Without one, or when the worker is busy,
+//! they fall back to the chars/4 estimate. Counts are cached per message
+//! against a fingerprint of the text, so history is not re-tokenized on every
+//! iteration.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Heuristic token count: about 4 bytes per token
+///
+/// Good enough for English prose; code, JSON and accented text run higher.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len() / 4
+}
+
+/// Fingerprint of a text, to tell whether a cached count still applies
+pub fn fingerprint(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Token count cached with the fingerprint of the text it was made for
+///
+/// Two caches always compare equal: the cache is not part of a message's value.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenCount(Option<(u64, usize)>);
+
+impl TokenCount {
+    /// Cached count, if it was made for exactly `text`
+    pub fn get(&self, text: &str) -> Option<usize> {
+        self.0
+            .filter(|(print, _)| *print == fingerprint(text))
+            .map(|(_, count)| count)
+    }
+
+    pub fn set(&mut self, text: &str, count: usize) {
+        self.0 = Some((fingerprint(text), count));
+    }
+
+    /// Cached count, or the heuristic one
+    pub fn get_or_estimate(&self, text: &str) -> usize {
+        self.get(text).unwrap_or_else(|| estimate_tokens(text))
+    }
+}
+
+impl PartialEq for TokenCount {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_count_follows_the_text() {
+        let mut count = TokenCount::default();
+        assert_eq!(count.get("Bonjour à tous"), None);
+        assert_eq!(count.get_or_estimate("Bonjour à tous"), estimate_tokens("Bonjour à tous"));
+
+        count.set("Bonjour à tous", 5);
+        assert_eq!(count.get("Bonjour à tous"), Some(5));
+        assert_eq!(count.get_or_estimate("Bonjour à tous"), 5);
+        // Edited or streamed-into text is counted again
+        assert_eq!(count.get("Bonjour à tous !"), None);
+    }
+}
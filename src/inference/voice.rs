@@ -0,0 +1,390 @@
+//! Voice input: microphone recording and local speech-to-text
+//!
+//! Audio is captured with cpal and transcribed by whisper.cpp, both only in
+//! builds with the `voice` feature: whisper.cpp bundles its own ggml, built
+//! separately from the one llama.cpp links. Without it the same API is there
+//! but every call fails with `VoiceError::NotBuilt`.
+//!
+//! Neither the cpal stream nor the whisper context is `Send`, so recording
+//! runs on its own OS thread and `transcribe` blocks: call it from
+//! `spawn_blocking`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Recording stops by itself after this long
+pub const MAX_RECORDING: Duration = Duration::from_secs(120);
+
+/// Whisper takes 16 kHz mono audio
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+/// Whether this build can record and transcribe
+pub const AVAILABLE: bool = cfg!(feature = "voice");
+
+#[derive(Debug, Error)]
+pub enum VoiceError {
+    #[error("Voice input is not part of this build (enable the `voice` feature)")]
+    NotBuilt,
+
+    #[error("No microphone found")]
+    NoInputDevice,
+
+    #[error("Microphone unavailable: {0}")]
+    Microphone(String),
+
+    #[error("Nothing was recorded")]
+    NothingRecorded,
+
+    #[error("Only silence was recorded")]
+    Silent,
+
+    #[error("Whisper model not found: {0}")]
+    ModelMissing(PathBuf),
+
+    #[error("Transcription failed: {0}")]
+    Transcription(String),
+}
+
+/// Interleaved samples from the device as the 16 kHz mono audio whisper wants
+pub fn to_whisper_input(samples: &[f32], channels: u16, sample_rate: u32) -> Vec<f32> {
+    resample(
+        &downmix(samples, channels),
+        sample_rate,
+        WHISPER_SAMPLE_RATE,
+    )
+}
+
+/// Average of the channels of each frame
+fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Linear interpolation, good enough for speech
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || from == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = from as f64 / to as f64;
+    let len = (samples.len() as f64 / ratio) as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let next = samples.get(index + 1).copied().unwrap_or(samples[index]);
+            let weight = (position - index as f64) as f32;
+            samples[index] * (1.0 - weight) + next * weight
+        })
+        .collect()
+}
+
+/// Audio ready for whisper, or why there is nothing to transcribe
+#[cfg_attr(not(feature = "voice"), allow(dead_code))]
+fn check_recording(audio: Vec<f32>) -> Result<Vec<f32>, VoiceError> {
+    if audio.is_empty() {
+        return Err(VoiceError::NothingRecorded);
+    }
+    // A denied microphone permission often yields a stream of zeros
+    if audio.iter().all(|s| s.abs() < 1e-4) {
+        return Err(VoiceError::Silent);
+    }
+    Ok(audio)
+}
+
+pub use backend::{transcribe, Recorder};
+
+#[cfg(feature = "voice")]
+mod backend {
+    use super::{check_recording, to_whisper_input, VoiceError, MAX_RECORDING};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+    use std::sync::{Arc, Mutex};
+    use std::thread::JoinHandle;
+    use std::time::{Duration, Instant};
+    use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+    /// A recording from the default microphone, running until stopped or
+    /// `MAX_RECORDING`
+    pub struct Recorder {
+        stop: Arc<AtomicBool>,
+        started: Instant,
+        /// Taken by `stop`
+        worker: Option<JoinHandle<Result<Vec<f32>, VoiceError>>>,
+    }
+
+    impl Recorder {
+        /// Open the default microphone and start recording
+        pub fn start() -> Result<Self, VoiceError> {
+            let stop = Arc::new(AtomicBool::new(false));
+            let (ready_tx, ready_rx) = mpsc::channel();
+            let worker_stop = stop.clone();
+            let worker = std::thread::Builder::new()
+                .name("voice-recorder".to_string())
+                .spawn(move || record(&worker_stop, ready_tx))
+                .map_err(|e| VoiceError::Microphone(e.to_string()))?;
+
+            match ready_rx.recv() {
+                Ok(Ok(())) => Ok(Self {
+                    stop,
+                    started: Instant::now(),
+                    worker: Some(worker),
+                }),
+                Ok(Err(e)) => Err(e),
+                Err(_) => Err(match worker.join() {
+                    Ok(Err(e)) => e,
+                    _ => VoiceError::Microphone("recording thread ended".to_string()),
+                }),
+            }
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            self.started.elapsed()
+        }
+
+        /// Stopped by itself: time limit reached or device lost
+        pub fn is_finished(&self) -> bool {
+            self.worker.as_ref().map_or(true, JoinHandle::is_finished)
+        }
+
+        /// Stop and return the audio as 16 kHz mono; blocks until the
+        /// stream is closed
+        pub fn stop(mut self) -> Result<Vec<f32>, VoiceError> {
+            self.stop.store(true, Ordering::Relaxed);
+            match self.worker.take().map(JoinHandle::join) {
+                Some(Ok(outcome)) => outcome,
+                _ => Err(VoiceError::Microphone(
+                    "recording thread panicked".to_string(),
+                )),
+            }
+        }
+    }
+
+    /// A recording dropped without `stop` (input closed) releases the microphone
+    impl Drop for Recorder {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Recording thread: owns the stream, reports once it plays, then
+    /// collects samples until told to stop
+    fn record(
+        stop: &AtomicBool,
+        ready: mpsc::Sender<Result<(), VoiceError>>,
+    ) -> Result<Vec<f32>, VoiceError> {
+        let samples = Arc::new(Mutex::new(Vec::<f32>::new()));
+        let stream_error = Arc::new(Mutex::new(None::<String>));
+        let (stream, channels, sample_rate) =
+            match open_stream(samples.clone(), stream_error.clone()) {
+                Ok(opened) => opened,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return Err(VoiceError::NothingRecorded);
+                }
+            };
+        let _ = ready.send(Ok(()));
+
+        let started = Instant::now();
+        while !stop.load(Ordering::Relaxed) && started.elapsed() < MAX_RECORDING {
+            if stream_error.lock().map(|e| e.is_some()).unwrap_or(false) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        drop(stream);
+
+        if let Some(e) = stream_error.lock().ok().and_then(|mut e| e.take()) {
+            return Err(VoiceError::Microphone(e));
+        }
+        let samples = std::mem::take(&mut *samples.lock().unwrap_or_else(|e| e.into_inner()));
+        check_recording(to_whisper_input(&samples, channels, sample_rate))
+    }
+
+    fn open_stream(
+        samples: Arc<Mutex<Vec<f32>>>,
+        stream_error: Arc<Mutex<Option<String>>>,
+    ) -> Result<(cpal::Stream, u16, u32), VoiceError> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or(VoiceError::NoInputDevice)?;
+        let supported = device
+            .default_input_config()
+            .map_err(|e| VoiceError::Microphone(e.to_string()))?;
+        let channels = supported.channels();
+        let sample_rate = supported.sample_rate().0;
+        let config = supported.config();
+
+        let on_error = move |e: cpal::StreamError| {
+            tracing::error!("Microphone stream error: {}", e);
+            if let Ok(mut slot) = stream_error.lock() {
+                *slot = Some(e.to_string());
+            }
+        };
+        let push = move |data: &mut dyn Iterator<Item = f32>| {
+            if let Ok(mut samples) = samples.lock() {
+                samples.extend(data);
+            }
+        };
+
+        let stream = match supported.sample_format() {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &config,
+                move |data: &[f32], _: &_| push(&mut data.iter().copied()),
+                on_error,
+                None,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &config,
+                move |data: &[i16], _: &_| {
+                    push(&mut data.iter().map(|s| *s as f32 / i16::MAX as f32))
+                },
+                on_error,
+                None,
+            ),
+            cpal::SampleFormat::U16 => device.build_input_stream(
+                &config,
+                move |data: &[u16], _: &_| {
+                    push(&mut data.iter().map(|s| (*s as f32 - 32768.0) / 32768.0))
+                },
+                on_error,
+                None,
+            ),
+            other => {
+                return Err(VoiceError::Microphone(format!(
+                    "unsupported sample format {:?}",
+                    other
+                )));
+            }
+        }
+        .map_err(|e| match e {
+            cpal::BuildStreamError::DeviceNotAvailable => VoiceError::NoInputDevice,
+            e => VoiceError::Microphone(e.to_string()),
+        })?;
+        stream
+            .play()
+            .map_err(|e| VoiceError::Microphone(e.to_string()))?;
+        Ok((stream, channels, sample_rate))
+    }
+
+    /// Transcribe 16 kHz mono audio with the whisper model at `model`
+    ///
+    /// `language` is a code such as "en", or "auto" to detect it.
+    /// `on_progress` gets the percentage done.
+    pub fn transcribe(
+        model: &Path,
+        audio: &[f32],
+        language: &str,
+        on_progress: impl FnMut(i32) + 'static,
+    ) -> Result<String, VoiceError> {
+        if !model.exists() {
+            return Err(VoiceError::ModelMissing(model.to_path_buf()));
+        }
+        let failed = |e: whisper_rs::WhisperError| VoiceError::Transcription(e.to_string());
+        let path = model.to_str().ok_or_else(|| {
+            VoiceError::Transcription("model path is not valid UTF-8".to_string())
+        })?;
+        let context = WhisperContext::new_with_params(path, WhisperContextParameters::default())
+            .map_err(failed)?;
+        let mut state = context.create_state().map_err(failed)?;
+
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+            .min(8);
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(language));
+        params.set_n_threads(threads as i32);
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_progress_callback_safe(on_progress);
+        state.full(params, audio).map_err(failed)?;
+
+        let mut text = String::new();
+        for segment in 0..state.full_n_segments().map_err(failed)? {
+            text.push_str(&state.full_get_segment_text(segment).map_err(failed)?);
+        }
+        Ok(text.trim().to_string())
+    }
+}
+
+#[cfg(not(feature = "voice"))]
+mod backend {
+    use super::VoiceError;
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// Stand-in for builds without the `voice` feature: never starts
+    pub struct Recorder(());
+
+    impl Recorder {
+        pub fn start() -> Result<Self, VoiceError> {
+            Err(VoiceError::NotBuilt)
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        pub fn is_finished(&self) -> bool {
+            true
+        }
+
+        pub fn stop(self) -> Result<Vec<f32>, VoiceError> {
+            Err(VoiceError::NotBuilt)
+        }
+    }
+
+    pub fn transcribe(
+        _model: &Path,
+        _audio: &[f32],
+        _language: &str,
+        _on_progress: impl FnMut(i32) + 'static,
+    ) -> Result<String, VoiceError> {
+        Err(VoiceError::NotBuilt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whisper_input_is_16k_mono() {
+        // Stereo frames average to one sample
+        assert_eq!(downmix(&[0.2, 0.4, -1.0, 1.0], 2), vec![0.3, 0.0]);
+
+        let one_second_48k = vec![0.5; 48_000];
+        let audio = to_whisper_input(&one_second_48k, 1, 48_000);
+        assert_eq!(audio.len(), 16_000);
+        assert!(audio.iter().all(|s| (*s - 0.5).abs() < 1e-6));
+
+        let ramp: Vec<f32> = (0..8).map(|i| i as f32).collect();
+        assert_eq!(resample(&ramp, 8_000, 16_000)[..4], [0.0, 0.5, 1.0, 1.5]);
+        assert_eq!(resample(&ramp, 16_000, 16_000), ramp);
+    }
+
+    #[test]
+    fn test_empty_or_silent_recordings() {
+        assert!(matches!(
+            check_recording(Vec::new()),
+            Err(VoiceError::NothingRecorded)
+        ));
+        assert!(matches!(
+            check_recording(vec![0.0; 100]),
+            Err(VoiceError::Silent)
+        ));
+        assert!(check_recording(vec![0.0, 0.2, -0.1]).is_ok());
+    }
+}
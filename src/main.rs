@@ -6,6 +6,7 @@ use dioxus::desktop::{Config, LogicalSize, WindowBuilder};
 use tracing::info;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+use clawrs::agent::replay::{check_invariants, load_recording, replay_registry, replay_run};
 use clawrs::app::App;
 
 fn main() {
@@ -15,6 +16,12 @@ fn main() {
         .with(EnvFilter::from_default_env().add_directive("clawrs=info".parse().unwrap()))
         .init();
 
+    // `clawrs replay <recording.json>...` replays recorded runs instead of starting the UI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(|a| a == "replay").unwrap_or(false) {
+        std::process::exit(run_replay(&args[1..]));
+    }
+
     info!("Starting ClawRS v{}", env!("CARGO_PKG_VERSION"));
 
     // Initialize storage directory structure
@@ -27,6 +34,13 @@ fn main() {
         .with_cfg(
             Config::default()
                 .with_menu(None) // Remove the default menu bar
+                .with_custom_event_handler(|event, _| {
+                    // Background jobs and language servers do not outlive the app
+                    if let dioxus::desktop::tao::event::Event::LoopDestroyed = event {
+                        clawrs::agent::tools::shell::JobManager::global().kill_all();
+                        clawrs::agent::tools::lsp::shutdown_all();
+                    }
+                })
                 .with_window(
                     WindowBuilder::new()
                         .with_title("ClawRS")
@@ -35,3 +49,64 @@ fn main() {
         )
         .launch(App);
 }
+
+/// Replay recorded runs and report invariant violations; returns the exit code
+fn run_replay(paths: &[String]) -> i32 {
+    if paths.is_empty() {
+        eprintln!("usage: clawrs replay <recording.json>...");
+        return 2;
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start runtime: {}", e);
+            return 1;
+        }
+    };
+
+    runtime.block_on(async {
+        let mut failed = 0;
+        for path in paths {
+            let recording = match load_recording(std::path::Path::new(path)) {
+                Ok(recording) => recording,
+                Err(e) => {
+                    println!("FAIL {}: {}", path, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            let workspace = std::env::temp_dir().join(format!("clawrs-replay-{}", uuid::Uuid::new_v4()));
+            let outcome = replay_run(&recording, replay_registry().await, &workspace).await;
+            let _ = std::fs::remove_dir_all(&workspace);
+
+            match outcome {
+                Ok(outcome) => {
+                    let violations = check_invariants(&recording, &outcome);
+                    if violations.is_empty() {
+                        println!(
+                            "PASS {} ({} iteration(s), tools: [{}], prompt drift in {} call(s))",
+                            path,
+                            outcome.iterations,
+                            outcome.tool_calls.join(", "),
+                            outcome.prompt_drift.len()
+                        );
+                    } else {
+                        failed += 1;
+                        println!("FAIL {}", path);
+                        for violation in violations {
+                            println!("  - {}", violation);
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("FAIL {}: {}", path, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed > 0 { 1 } else { 0 }
+    })
+}
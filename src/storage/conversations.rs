@@ -2,12 +2,14 @@
 //!
 //! Manages saving and loading of chat conversations.
 
+use crate::storage::redaction::Redactor;
 use crate::storage::{get_data_dir, StorageError};
-use crate::types::message::Message;
+use crate::types::message::{Message, NoticeKind, Role};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// A chat conversation
@@ -23,6 +25,49 @@ pub struct Conversation {
     pub created_at: DateTime<Utc>,
     /// When the conversation was last updated
     pub updated_at: DateTime<Utc>,
+    /// Per-conversation overrides (e.g. from a template)
+    #[serde(default)]
+    pub overrides: ConversationOverrides,
+    /// Language detected from the user's messages ("fr"/"en"), kept stable across sessions
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Pinned conversations are never removed by the retention cleanup
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Settings that a conversation can override instead of using the global ones
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConversationOverrides {
+    /// Replaces the global system prompt
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Enables or disables tool use for this conversation
+    #[serde(default)]
+    pub enable_tools: Option<bool>,
+    /// Maximum agent loop iterations
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    /// Only read-only and research tools may run
+    #[serde(default)]
+    pub read_only: bool,
+    /// Sampling temperature, instead of the global one
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub top_k: Option<u32>,
+    /// Maximum tokens per reply
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+impl ConversationOverrides {
+    /// Whether any generation parameter is overridden
+    pub fn has_sampling(&self) -> bool {
+        self.temperature.is_some() || self.top_p.is_some() || self.top_k.is_some() || self.max_tokens.is_some()
+    }
 }
 
 impl Conversation {
@@ -44,13 +89,16 @@ impl Conversation {
             messages,
             created_at: now,
             updated_at: now,
+            overrides: ConversationOverrides::default(),
+            language: None,
+            pinned: false,
         }
     }
 
     /// Add a message to the conversation
     pub fn add_message(&mut self, message: Message) {
-        // If this is the first message, update the title
-        if self.messages.is_empty() {
+        // If this is the first message, update the title (unless a template set one)
+        if self.messages.is_empty() && self.title == "New Conversation" {
             self.title = generate_title(&message.content);
         }
 
@@ -59,6 +107,24 @@ impl Conversation {
     }
 }
 
+/// Copy of a conversation up to and including message `up_to_index`, as a new conversation
+///
+/// The fork keeps the conversation's overrides and language, and is not saved.
+pub fn fork_conversation(conv: &Conversation, up_to_index: usize) -> Conversation {
+    let now = Utc::now();
+    let end = (up_to_index + 1).min(conv.messages.len());
+    Conversation {
+        id: Uuid::new_v4().to_string(),
+        title: format!("{} (fork)", conv.title),
+        messages: conv.messages[..end].to_vec(),
+        created_at: now,
+        updated_at: now,
+        overrides: conv.overrides.clone(),
+        language: conv.language.clone(),
+        pinned: false,
+    }
+}
+
 /// Generate a conversation title from a message
 ///
 /// Takes the first 50 characters of the message content
@@ -81,6 +147,46 @@ fn get_conversation_path(id: &str) -> Result<PathBuf, StorageError> {
     Ok(get_conversations_dir()?.join(format!("{}.json", id)))
 }
 
+/// Folder of the files pasted into a conversation, `conversations/assets/{id}`
+///
+/// The folder itself is created on the first paste.
+pub fn conversation_assets_dir(id: &str) -> Result<PathBuf, StorageError> {
+    Ok(get_conversations_dir()?.join("assets").join(id))
+}
+
+/// Save a pasted image as PNG in the conversation's assets folder
+///
+/// Any format the `image` crate reads is accepted; returns the new file's path.
+pub fn save_conversation_image(id: &str, bytes: &[u8]) -> Result<PathBuf, StorageError> {
+    save_image_in(&conversation_assets_dir(id)?, bytes)
+}
+
+fn save_image_in(dir: &Path, bytes: &[u8]) -> Result<PathBuf, StorageError> {
+    let image = image::load_from_memory(bytes).map_err(|e| StorageError::InvalidImage(e.to_string()))?;
+    fs::create_dir_all(dir)?;
+    let name = format!(
+        "{}-{}.png",
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        &Uuid::new_v4().simple().to_string()[..8]
+    );
+    let path = dir.join(name);
+    image
+        .save_with_format(&path, image::ImageFormat::Png)
+        .map_err(|e| StorageError::InvalidImage(e.to_string()))?;
+    tracing::debug!("Saved pasted image: {:?}", path);
+    Ok(path)
+}
+
+/// Remove the assets folder of a conversation held in `dir`, if it has one
+fn remove_assets_in(dir: &Path, id: &str) {
+    let assets = dir.join("assets").join(id);
+    if assets.exists() {
+        if let Err(e) = fs::remove_dir_all(&assets) {
+            tracing::warn!("Could not remove the assets of {}: {}", id, e);
+        }
+    }
+}
+
 /// Save a conversation to disk
 pub fn save_conversation(conversation: &Conversation) -> Result<(), StorageError> {
     let dir = get_conversations_dir()?;
@@ -145,6 +251,140 @@ pub fn list_conversations() -> Result<Vec<Conversation>, StorageError> {
     Ok(conversations)
 }
 
+/// Characters kept on each side of a match in a search snippet
+pub const SNIPPET_RADIUS: usize = 80;
+
+/// A match of a search query in a saved conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub conversation_id: String,
+    pub title: String,
+    /// Message the match is in; `None` when it is in the title
+    pub message_index: Option<usize>,
+    /// Text around the match, on one line
+    pub snippet: String,
+}
+
+/// Lowercase a text one char at a time, keeping each char's byte offset
+fn fold_case(text: &str) -> Vec<(usize, char)> {
+    text.char_indices()
+        .map(|(i, c)| (i, c.to_lowercase().next().unwrap_or(c)))
+        .collect()
+}
+
+/// Byte range of the first case-insensitive occurrence of `needle` in `text`
+fn find_ignore_case(text: &str, needle: &[char]) -> Option<(usize, usize)> {
+    if needle.is_empty() {
+        return None;
+    }
+    let folded = fold_case(text);
+    let start = folded
+        .windows(needle.len())
+        .position(|window| window.iter().map(|(_, c)| c).eq(needle.iter()))?;
+    let end = folded.get(start + needle.len()).map(|(i, _)| *i).unwrap_or(text.len());
+    Some((folded[start].0, end))
+}
+
+/// Up to `SNIPPET_RADIUS` chars on each side of `text[start..end]`, whitespace collapsed
+fn snippet_around(text: &str, start: usize, end: usize) -> String {
+    let before: Vec<char> = text[..start].chars().rev().take(SNIPPET_RADIUS + 1).collect();
+    let after: Vec<char> = text[end..].chars().take(SNIPPET_RADIUS + 1).collect();
+    let mut snippet = String::new();
+    if before.len() > SNIPPET_RADIUS {
+        snippet.push('…');
+    }
+    snippet.extend(before.iter().take(SNIPPET_RADIUS).rev());
+    snippet.push_str(&text[start..end]);
+    snippet.extend(after.iter().take(SNIPPET_RADIUS));
+    if after.len() > SNIPPET_RADIUS {
+        snippet.push('…');
+    }
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Matches of `query` in one conversation: its title, then each user or assistant message
+///
+/// A message gives at most one hit, around its first match.
+pub fn search_conversation(conversation: &Conversation, query: &str) -> Vec<SearchHit> {
+    let needle: Vec<char> = fold_case(query.trim()).into_iter().map(|(_, c)| c).collect();
+    let hit = |message_index, snippet| SearchHit {
+        conversation_id: conversation.id.clone(),
+        title: conversation.title.clone(),
+        message_index,
+        snippet,
+    };
+
+    let mut hits = Vec::new();
+    if find_ignore_case(&conversation.title, &needle).is_some() {
+        hits.push(hit(None, conversation.title.clone()));
+    }
+    for (index, message) in conversation.messages.iter().enumerate() {
+        // System and tool messages are not shown, there is nothing to scroll to
+        if !matches!(message.role, Role::User | Role::Assistant) {
+            continue;
+        }
+        if let Some((start, end)) = find_ignore_case(&message.content, &needle) {
+            hits.push(hit(Some(index), snippet_around(&message.content, start, end)));
+        }
+    }
+    hits
+}
+
+/// Scan every saved conversation for `query`, most recently modified first
+///
+/// Each hit is handed to `on_hit` as soon as it is found; the scan stops when
+/// `on_hit` returns false. Unreadable files are skipped.
+pub fn scan_conversations(query: &str, mut on_hit: impl FnMut(SearchHit) -> bool) -> Result<(), StorageError> {
+    let conversations_dir = get_conversations_dir()?;
+    if query.trim().is_empty() || !conversations_dir.exists() {
+        return Ok(());
+    }
+
+    let mut files: Vec<(PathBuf, std::time::SystemTime)> = fs::read_dir(conversations_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+        .map(|path| {
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::UNIX_EPOCH);
+            (path, modified)
+        })
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1));
+
+    for (path, _) in files {
+        let conversation = match fs::read_to_string(&path)
+            .map_err(StorageError::from)
+            .and_then(|json| serde_json::from_str::<Conversation>(&json).map_err(StorageError::from))
+        {
+            Ok(conversation) => conversation,
+            Err(e) => {
+                tracing::warn!("Skipping conversation file {:?} in search: {}", path, e);
+                continue;
+            }
+        };
+        for hit in search_conversation(&conversation, query) {
+            if !on_hit(hit) {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// All matches of `query` in the saved conversations (blocking, see `scan_conversations`)
+pub fn search_conversations(query: &str) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    if let Err(e) = scan_conversations(query, |hit| {
+        hits.push(hit);
+        true
+    }) {
+        tracing::error!("Conversation search failed: {}", e);
+    }
+    hits
+}
+
 /// Delete a conversation
 pub fn delete_conversation(id: &str) -> Result<(), StorageError> {
     let path = get_conversation_path(id)?;
@@ -154,14 +394,210 @@ pub fn delete_conversation(id: &str) -> Result<(), StorageError> {
     }
 
     fs::remove_file(path)?;
+    remove_assets_in(&get_conversations_dir()?, id);
     tracing::debug!("Deleted conversation: {}", id);
     Ok(())
 }
 
+/// Limits on the saved conversations, enforced by `cleanup_old_conversations`
+///
+/// Every limit is optional; with none set, nothing is removed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Remove conversations not updated for this many days
+    pub max_age_days: Option<u32>,
+    /// Keep at most this many conversations
+    pub max_count: Option<usize>,
+    /// Keep the conversation files under this many megabytes in total
+    pub max_total_mb: Option<u64>,
+    /// Move removed conversations into a zip archive instead of deleting them
+    pub archive: bool,
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age_days.is_some() || self.max_count.is_some() || self.max_total_mb.is_some()
+    }
+}
+
+/// What a retention cleanup removed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CleanupReport {
+    pub removed: usize,
+    /// Size of the removed conversation files
+    pub bytes: u64,
+    /// Zip the removed conversations were archived to
+    pub archive: Option<PathBuf>,
+}
+
+/// Remove the oldest conversations beyond the policy's limits
+///
+/// Pinned conversations and `open_id` (the conversation on screen) are never
+/// removed, though they count towards the count and size limits.
+pub fn cleanup_old_conversations(policy: &RetentionPolicy, open_id: Option<&str>) -> Result<CleanupReport, StorageError> {
+    let dir = get_conversations_dir()?;
+    let report = cleanup_conversations_in(&dir, policy, open_id, Utc::now())?;
+    if report.removed > 0 {
+        tracing::info!(
+            "Retention cleanup removed {} conversation(s), reclaiming {} bytes{}",
+            report.removed,
+            report.bytes,
+            report
+                .archive
+                .as_ref()
+                .map(|path| format!(" (archived to {})", path.display()))
+                .unwrap_or_default()
+        );
+    } else {
+        tracing::debug!("Retention cleanup: nothing to remove");
+    }
+    Ok(report)
+}
+
+fn cleanup_conversations_in(
+    dir: &Path,
+    policy: &RetentionPolicy,
+    open_id: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<CleanupReport, StorageError> {
+    if !policy.is_enabled() || !dir.exists() {
+        return Ok(CleanupReport::default());
+    }
+
+    // (path, size, last update, removable), oldest first
+    let mut files: Vec<(PathBuf, u64, DateTime<Utc>, bool)> = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let size = fs::metadata(&path)?.len();
+        match fs::read_to_string(&path).map(|json| serde_json::from_str::<Conversation>(&json)) {
+            Ok(Ok(conv)) => {
+                let removable = !conv.pinned && Some(conv.id.as_str()) != open_id;
+                files.push((path, size, conv.updated_at, removable));
+            }
+            // Unreadable files still take space, but are left alone
+            _ => tracing::warn!("Retention cleanup skipping unreadable file {:?}", path),
+        }
+    }
+    files.sort_by(|a, b| a.2.cmp(&b.2));
+
+    let cutoff = policy.max_age_days.map(|days| now - chrono::Duration::days(days as i64));
+    let max_bytes = policy.max_total_mb.map(|mb| mb * 1024 * 1024);
+    let mut count = files.len();
+    let mut total: u64 = files.iter().map(|f| f.1).sum();
+
+    let mut doomed = Vec::new();
+    for (path, size, updated_at, removable) in files {
+        let too_old = cutoff.is_some_and(|cutoff| updated_at < cutoff);
+        let too_many = policy.max_count.is_some_and(|max| count > max);
+        let too_big = max_bytes.is_some_and(|max| total > max);
+        if removable && (too_old || too_many || too_big) {
+            count -= 1;
+            total -= size;
+            doomed.push((path, size));
+        }
+    }
+    if doomed.is_empty() {
+        return Ok(CleanupReport::default());
+    }
+
+    let archive = if policy.archive {
+        let archive_dir = dir.join("archive");
+        fs::create_dir_all(&archive_dir)?;
+        let path = archive_dir.join(format!("conversations-{}.zip", now.format("%Y%m%d-%H%M%S")));
+        let paths: Vec<&Path> = doomed.iter().map(|(path, _)| path.as_path()).collect();
+        write_archive(&path, &paths)?;
+        Some(path)
+    } else {
+        None
+    };
+
+    let mut report = CleanupReport { archive, ..Default::default() };
+    for (path, size) in doomed {
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                if let Some(id) = path.file_stem().and_then(|s| s.to_str()) {
+                    remove_assets_in(dir, id);
+                }
+                report.removed += 1;
+                report.bytes += size;
+            }
+            Err(e) => tracing::warn!("Retention cleanup could not remove {:?}: {}", path, e),
+        }
+    }
+    Ok(report)
+}
+
+/// Zip `files` (by file name) into a new archive at `path`
+fn write_archive(path: &Path, files: &[&Path]) -> Result<(), StorageError> {
+    let archive_err = |e: zip::result::ZipError| StorageError::ArchiveError(e.to_string());
+    let mut zip = zip::ZipWriter::new(fs::File::create(path)?);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for file in files {
+        let name = file.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        zip.start_file(name, options).map_err(archive_err)?;
+        zip.write_all(&fs::read(file)?)?;
+    }
+    zip.finish().map_err(archive_err)?;
+    Ok(())
+}
+
+/// Render a conversation as Markdown for export (system messages are skipped)
+pub fn conversation_to_markdown(conversation: &Conversation) -> String {
+    let mut out = format!("# {}\n\n", conversation.title);
+    for message in &conversation.messages {
+        // Model switches are kept, so the transcript says which model answered what
+        if message.metadata.as_ref().and_then(|m| m.notice).is_some_and(|n| n.kind == NoticeKind::ModelSwitch) {
+            out.push_str(&format!("*{}*\n\n", message.content.trim()));
+            continue;
+        }
+        if let Some(markdown) = message_to_markdown(message) {
+            out.push_str(&markdown);
+        }
+    }
+    out
+}
+
+/// One message as the Markdown export writes it, under its role heading;
+/// `None` for system and tool messages
+pub fn message_to_markdown(message: &Message) -> Option<String> {
+    let heading = match message.role {
+        Role::User => "User",
+        Role::Assistant => "Assistant",
+        Role::System | Role::Tool => return None,
+    };
+    Some(format!("## {}\n\n{}\n\n", heading, message.content.trim()))
+}
+
+/// Write a conversation to `path` as Markdown, redacted when a redactor is given
+pub fn export_conversation(
+    conversation: &Conversation,
+    path: &Path,
+    redactor: Option<&Redactor>,
+) -> Result<(), StorageError> {
+    let markdown = conversation_to_markdown(conversation);
+    let content = match redactor {
+        Some(redactor) => {
+            let redacted = redactor.redact(&markdown);
+            tracing::info!("Redacted {} value(s) before export", redacted.spans.len());
+            redacted.text
+        }
+        None => markdown,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    tracing::info!("Exported conversation {} to {}", conversation.id, path.display());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::message::Role;
 
     #[test]
     fn test_conversation_creation() {
@@ -214,4 +650,195 @@ mod tests {
         assert_eq!(conv.title, deserialized.title);
         assert_eq!(conv.messages.len(), deserialized.messages.len());
     }
+
+    #[test]
+    fn test_fork_conversation() {
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "Plan a trip")));
+        conv.add_message(Message::new(Role::Assistant, "Where to?"));
+        conv.add_message(Message::new(Role::User, "Japan"));
+        conv.overrides.system_prompt = Some("You are a travel agent.".to_string());
+        conv.language = Some("en".to_string());
+
+        let fork = fork_conversation(&conv, 1);
+        assert_ne!(fork.id, conv.id);
+        assert_eq!(fork.title, "Plan a trip (fork)");
+        assert_eq!(fork.messages, conv.messages[..2]);
+        assert_eq!(fork.overrides, conv.overrides);
+        assert_eq!(fork.language.as_deref(), Some("en"));
+        // The original is untouched
+        assert_eq!(conv.messages.len(), 3);
+
+        assert_eq!(fork_conversation(&conv, 10).messages.len(), 3);
+    }
+
+    #[test]
+    fn test_read_only_persisted() {
+        let mut conv = Conversation::new(None);
+        conv.overrides.read_only = true;
+        let json = serde_json::to_string(&conv).unwrap();
+        let deserialized: Conversation = serde_json::from_str(&json).unwrap();
+        assert!(deserialized.overrides.read_only);
+
+        // Older files without the field stay writable
+        let legacy = json.replace(",\"read_only\":true", "");
+        let deserialized: Conversation = serde_json::from_str(&legacy).unwrap();
+        assert!(!deserialized.overrides.read_only);
+    }
+
+    #[test]
+    fn test_generation_overrides_persisted() {
+        let mut conv = Conversation::new(None);
+        assert!(!conv.overrides.has_sampling());
+        conv.overrides.system_prompt = Some("You are a coding agent.".to_string());
+        conv.overrides.temperature = Some(0.2);
+        conv.overrides.max_tokens = Some(1024);
+
+        let json = serde_json::to_string(&conv).unwrap();
+        let deserialized: Conversation = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.overrides, conv.overrides);
+        assert!(deserialized.overrides.has_sampling());
+
+        // Files saved before these overrides use the global settings
+        let legacy = r#"{"id":"a","title":"t","messages":[],"created_at":"2024-01-01T00:00:00Z","updated_at":"2024-01-01T00:00:00Z","overrides":{"read_only":true}}"#;
+        let legacy: Conversation = serde_json::from_str(legacy).unwrap();
+        assert_eq!(legacy.overrides.temperature, None);
+        assert!(!legacy.overrides.has_sampling());
+    }
+
+    #[test]
+    fn test_markdown_keeps_model_switches() {
+        use crate::types::message::{MessageMetadata, Notice};
+
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "Hi")));
+        let mut note = Message::new(Role::System, "Model switched to qwen2.5-7b");
+        note.metadata = Some(MessageMetadata {
+            notice: Some(Notice { kind: NoticeKind::ModelSwitch, count: 1 }),
+            ..Default::default()
+        });
+        conv.add_message(note);
+        conv.add_message(Message::new(Role::System, "internal note"));
+
+        let markdown = conversation_to_markdown(&conv);
+        assert!(markdown.contains("*Model switched to qwen2.5-7b*"));
+        assert!(!markdown.contains("internal note"));
+    }
+
+    #[test]
+    fn test_message_markdown_matches_export() {
+        let reply = Message::new(Role::Assistant, "  Done.\n");
+        assert_eq!(message_to_markdown(&reply).as_deref(), Some("## Assistant\n\nDone.\n\n"));
+        assert_eq!(message_to_markdown(&Message::new(Role::Tool, "{}")), None);
+
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "Hi")));
+        conv.add_message(reply);
+        assert!(conversation_to_markdown(&conv).ends_with("## User\n\nHi\n\n## Assistant\n\nDone.\n\n"));
+    }
+
+    #[test]
+    fn test_redacted_export() {
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "My key is sk-proj-4fJ9aQ2mZx7LwP0vN3bT8cR1")));
+        conv.add_message(Message::new(Role::System, "hidden"));
+        conv.add_message(Message::new(Role::Assistant, "Revoke sk-proj-4fJ9aQ2mZx7LwP0vN3bT8cR1 now."));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.md");
+        let redactor = Redactor::new(&Default::default()).unwrap();
+        export_conversation(&conv, &path, Some(&redactor)).unwrap();
+
+        let exported = fs::read_to_string(&path).unwrap();
+        assert!(!exported.contains("sk-proj"));
+        assert!(!exported.contains("hidden"));
+        // Title (taken from the first message), question and answer
+        assert_eq!(exported.matches("[API_KEY_1]").count(), 3);
+        assert!(exported.contains("## Assistant"));
+    }
+
+    #[test]
+    fn test_search_conversation() {
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "Deploy the Rust service")));
+        conv.add_message(Message::new(Role::System, "rust tool output"));
+        let long = format!("{}Le déploiement RUST est prêt.{}", "a ".repeat(100), " b".repeat(100));
+        conv.add_message(Message::new(Role::Assistant, long));
+
+        let hits = search_conversation(&conv, "rust");
+        // Title, the user message and the reply; the system message is skipped
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].message_index, None);
+        assert_eq!(hits[1].message_index, Some(0));
+        assert_eq!(hits[2].message_index, Some(2));
+        assert!(hits[2].snippet.contains("déploiement RUST est"));
+        assert!(hits[2].snippet.starts_with('…') && hits[2].snippet.ends_with('…'));
+        assert!(hits[2].snippet.chars().count() <= 2 * SNIPPET_RADIUS + 6);
+
+        // Accented text matches without regard to case
+        assert_eq!(search_conversation(&conv, "DÉPLOIEMENT").len(), 1);
+        assert!(search_conversation(&conv, "  ").is_empty());
+        assert!(search_conversation(&conv, "kubernetes").is_empty());
+    }
+
+    fn saved(dir: &Path, title: &str, days_old: i64, pinned: bool) -> Conversation {
+        let mut conv = Conversation::new(Some(Message::new(Role::User, title)));
+        conv.updated_at = Utc::now() - chrono::Duration::days(days_old);
+        conv.pinned = pinned;
+        fs::write(dir.join(format!("{}.json", conv.id)), serde_json::to_string(&conv).unwrap()).unwrap();
+        conv
+    }
+
+    #[test]
+    fn test_retention_cleanup() {
+        let dir = tempfile::tempdir().unwrap();
+        let oldest = saved(dir.path(), "oldest", 90, false);
+        let pinned = saved(dir.path(), "pinned", 80, true);
+        let open = saved(dir.path(), "open", 70, false);
+        let recent = saved(dir.path(), "recent", 1, false);
+        let exists = |conv: &Conversation| dir.path().join(format!("{}.json", conv.id)).exists();
+        let oldest_assets = dir.path().join("assets").join(&oldest.id);
+        fs::create_dir_all(&oldest_assets).unwrap();
+
+        // No limit, no removal
+        let report = cleanup_conversations_in(dir.path(), &RetentionPolicy::default(), None, Utc::now()).unwrap();
+        assert_eq!(report.removed, 0);
+
+        // By age: the pinned and the open conversations stay
+        let policy = RetentionPolicy { max_age_days: Some(30), ..Default::default() };
+        let report = cleanup_conversations_in(dir.path(), &policy, Some(&open.id), Utc::now()).unwrap();
+        assert_eq!(report.removed, 1);
+        assert!(report.bytes > 0);
+        assert!(!exists(&oldest));
+        assert!(!oldest_assets.exists());
+        assert!(exists(&pinned) && exists(&open) && exists(&recent));
+
+        // By count, archived: the oldest removable one goes into the zip
+        let policy = RetentionPolicy { max_count: Some(2), archive: true, ..Default::default() };
+        let report = cleanup_conversations_in(dir.path(), &policy, None, Utc::now()).unwrap();
+        assert_eq!(report.removed, 1);
+        assert!(!exists(&open));
+        assert!(exists(&pinned) && exists(&recent));
+        assert!(report.archive.unwrap().exists());
+
+        // By size: nothing removable is left but the recent one
+        let policy = RetentionPolicy { max_total_mb: Some(0), ..Default::default() };
+        let report = cleanup_conversations_in(dir.path(), &policy, Some(&recent.id), Utc::now()).unwrap();
+        assert_eq!(report.removed, 0);
+        assert!(exists(&pinned) && exists(&recent));
+    }
+
+    #[test]
+    fn test_save_pasted_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let assets = dir.path().join("assets").join("conv");
+        let mut png = Vec::new();
+        image::RgbaImage::new(2, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let path = save_image_in(&assets, &png).unwrap();
+        assert!(path.starts_with(&assets));
+        assert_eq!(path.extension().and_then(|e| e.to_str()), Some("png"));
+        assert_eq!(image::open(&path).unwrap().width(), 2);
+        assert!(matches!(save_image_in(&assets, b"not an image"), Err(StorageError::InvalidImage(_))));
+
+        remove_assets_in(dir.path(), "conv");
+        assert!(!assets.exists());
+    }
 }
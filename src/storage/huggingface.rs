@@ -16,7 +16,9 @@ pub struct HuggingFaceUrl {
     pub revision: String,
 }
 
-fn sanitize_local_filename(filename: &str) -> Result<String, String> {
+/// Local file name for a repository path: subdirectories are flattened and
+/// characters invalid on Windows replaced
+pub(crate) fn sanitize_local_filename(filename: &str) -> Result<String, String> {
     let trimmed = filename.trim();
     if trimmed.is_empty() {
         return Err("Invalid model filename".to_string());
@@ -127,11 +129,10 @@ impl HuggingFaceUrl {
     }
 }
 
-/// Download a model from HuggingFace
-pub async fn download_model(
-    url: &str,
-    progress_callback: impl Fn(u64, u64) + Send + 'static,
-) -> Result<PathBuf, String> {
+/// Direct download URL and local file name for a HuggingFace URL or model ID
+///
+/// A repository without a file name resolves when it holds a single GGUF file.
+pub async fn resolve_model_file(url: &str) -> Result<(String, String), String> {
     let hf_url = HuggingFaceUrl::parse(url)?;
 
     // If no specific filename, we need to list available GGUF files
@@ -161,7 +162,15 @@ pub async fn download_model(
         hf_url.repo_id, hf_url.revision, filename
     );
 
-    let safe_filename = sanitize_local_filename(&filename)?;
+    Ok((download_url, sanitize_local_filename(&filename)?))
+}
+
+/// Download a model from HuggingFace
+pub async fn download_model(
+    url: &str,
+    progress_callback: impl Fn(u64, u64) + Send + 'static,
+) -> Result<PathBuf, String> {
+    let (download_url, safe_filename) = resolve_model_file(url).await?;
 
     // Get models directory
     let models_dir = get_data_dir()
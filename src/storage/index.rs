@@ -0,0 +1,473 @@
+//! Local document index for semantic search
+//!
+//! The text, Markdown and PDF files of a folder are cut into chunks of a few
+//! dozen lines, each stored with its embedding. An index is one JSON file per
+//! folder under `{data_dir}/indexes/`, searched by a flat cosine scan (vectors
+//! are unit length, so a dot product). Rebuilds only re-read the files whose
+//! size or modification time changed.
+
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Extensions of the files an index covers
+pub const DOCUMENT_EXTENSIONS: &[&str] =
+    &["md", "markdown", "txt", "rst", "adoc", "org", "tex", "pdf"];
+
+/// Folders never indexed (besides hidden ones)
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "__pycache__", "venv"];
+
+/// Larger files are left out of the index
+const MAX_FILE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Target chunk size; a chunk only grows past it with a single long line
+const CHUNK_CHARS: usize = 1200;
+
+/// A Markdown heading starts a new chunk once the current one has this much text
+const MIN_SECTION_CHARS: usize = 200;
+
+/// Passage of a document with its embedding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chunk {
+    /// First line, 1-based (within the page for PDFs)
+    pub start_line: usize,
+    pub end_line: usize,
+    /// PDF page, 1-based
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+    pub text: String,
+    pub vector: Vec<f32>,
+}
+
+/// A file as it was when indexed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedFile {
+    /// Modification time, milliseconds since the Unix epoch
+    pub modified_ms: u64,
+    pub size: u64,
+    pub chunks: Vec<Chunk>,
+}
+
+/// Semantic index of one folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentIndex {
+    pub root: PathBuf,
+    /// Embedding model the vectors come from; another model means a full rebuild
+    pub model: String,
+    pub updated_at: DateTime<Utc>,
+    /// Files by path relative to `root`, with `/` separators
+    pub files: BTreeMap<String, IndexedFile>,
+}
+
+/// A document found on disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    /// Path relative to the index root, with `/` separators
+    pub relative: String,
+    pub path: PathBuf,
+    pub modified_ms: u64,
+    pub size: u64,
+}
+
+/// What a rebuild has to do to bring an index up to date
+#[derive(Debug, Default, PartialEq)]
+pub struct UpdatePlan {
+    /// New files and files whose size or mtime changed
+    pub changed: Vec<Document>,
+    /// Indexed files no longer on disk
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+}
+
+/// Passage cut from a document, before embedding
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChunk {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub page: Option<usize>,
+    pub text: String,
+}
+
+/// A chunk matching a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// Path relative to the index root
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub page: Option<usize>,
+    /// Cosine similarity with the query
+    pub score: f32,
+    pub text: String,
+}
+
+impl DocumentIndex {
+    pub fn new(root: PathBuf, model: String) -> Self {
+        Self {
+            root,
+            model,
+            updated_at: Utc::now(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Where the index of `root` is stored: `{data_dir}/indexes/{folder}-{hash}.json`
+    pub fn file_path(root: &Path) -> Result<PathBuf, StorageError> {
+        let name: String = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let hash = Sha256::digest(root.to_string_lossy().as_bytes());
+        let hash: String = hash[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        Ok(get_data_dir()?
+            .join("indexes")
+            .join(format!("{}-{}.json", name, hash)))
+    }
+
+    /// Index of `root`, if one was built
+    pub fn load(root: &Path) -> Result<Option<Self>, StorageError> {
+        Self::load_from(&Self::file_path(root)?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Option<Self>, StorageError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        self.save_to(&Self::file_path(&self.root)?)
+    }
+
+    /// Write through a temporary file so an interrupted save keeps the old index
+    pub fn save_to(&self, path: &Path) -> Result<(), StorageError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_string(self)?)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.files.values().map(|file| file.chunks.len()).sum()
+    }
+
+    /// Compare the index with the documents now on disk
+    pub fn plan(&self, documents: &[Document]) -> UpdatePlan {
+        let mut plan = UpdatePlan::default();
+        for document in documents {
+            match self.files.get(&document.relative) {
+                Some(indexed)
+                    if indexed.modified_ms == document.modified_ms
+                        && indexed.size == document.size =>
+                {
+                    plan.unchanged += 1;
+                }
+                _ => plan.changed.push(document.clone()),
+            }
+        }
+        plan.removed = self
+            .files
+            .keys()
+            .filter(|relative| !documents.iter().any(|d| &d.relative == *relative))
+            .cloned()
+            .collect();
+        plan
+    }
+
+    /// The `top_k` chunks closest to `query` (a unit-length embedding)
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = self
+            .files
+            .iter()
+            .flat_map(|(file, indexed)| {
+                indexed
+                    .chunks
+                    .iter()
+                    .filter(|c| c.vector.len() == query.len())
+                    .map(move |chunk| SearchHit {
+                        file: file.clone(),
+                        start_line: chunk.start_line,
+                        end_line: chunk.end_line,
+                        page: chunk.page,
+                        score: chunk.vector.iter().zip(query).map(|(a, b)| a * b).sum(),
+                        text: chunk.text.clone(),
+                    })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+        hits
+    }
+}
+
+/// Documents under `root`, skipping hidden folders, build folders and large files
+pub fn scan_documents(root: &Path) -> Result<Vec<Document>, StorageError> {
+    let mut documents = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    let mut first = true;
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if first => return Err(e.into()),
+            Err(e) => {
+                tracing::debug!("Skipping unreadable folder {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+        first = false;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                    pending.push(path);
+                }
+                continue;
+            }
+            let extension = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if !metadata.is_file()
+                || !DOCUMENT_EXTENSIONS.contains(&extension.as_str())
+                || metadata.len() > MAX_FILE_BYTES
+            {
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            documents.push(Document {
+                relative: relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                modified_ms: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0),
+                size: metadata.len(),
+                path,
+            });
+        }
+    }
+    documents.sort_by(|a, b| a.relative.cmp(&b.relative));
+    Ok(documents)
+}
+
+/// Text of a document cut into chunks; PDFs page by page
+pub fn read_document(path: &Path) -> Result<Vec<TextChunk>, String> {
+    let is_pdf = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+    if is_pdf {
+        let pages = pdf_extract::extract_text_by_pages(path)
+            .map_err(|e| format!("Erreur extraction PDF: {}", e))?;
+        return Ok(pages
+            .iter()
+            .enumerate()
+            .flat_map(|(i, text)| chunk_text(text, Some(i + 1)))
+            .collect());
+    }
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    Ok(chunk_text(&String::from_utf8_lossy(&bytes), None))
+}
+
+/// Cut `text` into chunks of whole lines of about `CHUNK_CHARS`, starting a
+/// new one at Markdown headings; blank chunks are dropped
+pub fn chunk_text(text: &str, page: Option<usize>) -> Vec<TextChunk> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut start_line = 1;
+
+    let mut flush = |current: &mut String, start_line: usize, end_line: usize| {
+        if !current.trim().is_empty() {
+            chunks.push(TextChunk {
+                start_line,
+                end_line,
+                page,
+                text: current.trim_end().to_string(),
+            });
+        }
+        current.clear();
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let is_heading = line.starts_with('#') && line.trim_start_matches('#').starts_with(' ');
+        let full = current.len() + line.len() > CHUNK_CHARS;
+        if !current.is_empty()
+            && (full || (is_heading && current.trim().len() >= MIN_SECTION_CHARS))
+        {
+            flush(&mut current, start_line, line_number - 1);
+        }
+        if current.is_empty() {
+            start_line = line_number;
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    let end_line = text.lines().count();
+    flush(&mut current, start_line, end_line);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(relative: &str, modified_ms: u64, size: u64) -> Document {
+        Document {
+            relative: relative.to_string(),
+            path: PathBuf::from(relative),
+            modified_ms,
+            size,
+        }
+    }
+
+    fn indexed(modified_ms: u64, size: u64, vectors: &[(&str, Vec<f32>)]) -> IndexedFile {
+        IndexedFile {
+            modified_ms,
+            size,
+            chunks: vectors
+                .iter()
+                .enumerate()
+                .map(|(i, (text, vector))| Chunk {
+                    start_line: i * 10 + 1,
+                    end_line: i * 10 + 9,
+                    page: None,
+                    text: text.to_string(),
+                    vector: vector.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_chunk_text_splits_on_size_and_headings() {
+        let intro = "Intro line.\n".repeat(20);
+        let text = format!("{}# Usage\nRun it.\n", intro);
+        let chunks = chunk_text(&text, None);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 20));
+        assert_eq!(chunks[1].start_line, 21);
+        assert!(chunks[1].text.starts_with("# Usage"));
+
+        let long = "word ".repeat(50) + "\n";
+        let chunks = chunk_text(&long.repeat(12), Some(3));
+        assert!(chunks.len() >= 2);
+        assert!(chunks
+            .iter()
+            .all(|c| c.text.len() <= CHUNK_CHARS && c.page == Some(3)));
+        assert_eq!(chunks.last().unwrap().end_line, 12);
+
+        assert!(chunk_text("\n\n  \n", None).is_empty());
+    }
+
+    #[test]
+    fn test_plan_only_touches_changed_files() {
+        let mut index = DocumentIndex::new(PathBuf::from("/docs"), "embed.gguf".to_string());
+        index
+            .files
+            .insert("a.md".to_string(), indexed(100, 10, &[]));
+        index
+            .files
+            .insert("b.md".to_string(), indexed(100, 10, &[]));
+        index
+            .files
+            .insert("gone.md".to_string(), indexed(100, 10, &[]));
+
+        let plan = index.plan(&[
+            document("a.md", 100, 10),
+            document("b.md", 200, 10),
+            document("new.md", 1, 1),
+        ]);
+        assert_eq!(plan.unchanged, 1);
+        let changed: Vec<_> = plan.changed.iter().map(|d| d.relative.as_str()).collect();
+        assert_eq!(changed, vec!["b.md", "new.md"]);
+        assert_eq!(plan.removed, vec!["gone.md".to_string()]);
+    }
+
+    #[test]
+    fn test_search_ranks_by_cosine_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut index = DocumentIndex::new(dir.path().to_path_buf(), "embed.gguf".to_string());
+        index.files.insert(
+            "notes/rust.md".to_string(),
+            indexed(
+                1,
+                1,
+                &[
+                    ("borrow checker", vec![1.0, 0.0]),
+                    ("cargo", vec![0.6, 0.8]),
+                ],
+            ),
+        );
+        index.files.insert(
+            "cooking.txt".to_string(),
+            indexed(1, 1, &[("pasta", vec![0.0, 1.0])]),
+        );
+
+        let hits = index.search(&[1.0, 0.0], 2);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            (hits[0].file.as_str(), hits[0].text.as_str()),
+            ("notes/rust.md", "borrow checker")
+        );
+        assert_eq!(hits[1].text, "cargo");
+        assert!((hits[1].score - 0.6).abs() < 1e-6);
+        assert!(index.search(&[1.0, 0.0, 0.0], 5).is_empty());
+
+        let file = dir.path().join("index.json");
+        index.save_to(&file).unwrap();
+        assert_eq!(DocumentIndex::load_from(&file).unwrap(), Some(index));
+        assert_eq!(
+            DocumentIndex::load_from(&dir.path().join("missing.json")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_scan_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("guide")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        fs::write(dir.path().join("README.md"), "# Readme").unwrap();
+        fs::write(dir.path().join("guide/intro.TXT"), "intro").unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join(".git/notes.md"), "hidden").unwrap();
+        fs::write(dir.path().join("node_modules/pkg/readme.md"), "vendored").unwrap();
+
+        let documents = scan_documents(dir.path()).unwrap();
+        let names: Vec<_> = documents.iter().map(|d| d.relative.as_str()).collect();
+        assert_eq!(names, vec!["README.md", "guide/intro.TXT"]);
+        assert_eq!(documents[0].size, 8);
+        assert!(scan_documents(&dir.path().join("missing")).is_err());
+    }
+}
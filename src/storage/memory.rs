@@ -0,0 +1,396 @@
+//! Long-term agent memory
+//!
+//! Small facts the agent keeps across conversations ("user prefers tabs",
+//! "project root is ~/code/foo"), tagged with topics, in
+//! `{data_dir}/memory.json`. A note is relevant to a text when they share
+//! words, topics counting double. The store has a hard size cap: past it the
+//! least recently used notes are evicted.
+
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Longest note, in bytes
+pub const MAX_NOTE_CHARS: usize = 500;
+
+/// Size of all notes and their topics; older notes are evicted past it
+pub const MAX_TOTAL_CHARS: usize = 20_000;
+
+const MAX_TOPICS: usize = 8;
+
+/// Words too common to make a note relevant
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "are", "was", "you", "your", "what", "how", "les",
+    "des", "une", "pour", "dans", "est", "avec", "que", "qui", "sur", "pas", "mon", "mes",
+];
+
+/// Serializes read-modify-write cycles of the store file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A remembered fact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryNote {
+    pub id: String,
+    pub content: String,
+    /// Lowercase topics, e.g. `preferences` or `project:foo`
+    #[serde(default)]
+    pub topics: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Last time the note was written, read or recalled (drives eviction)
+    pub last_used_at: DateTime<Utc>,
+}
+
+impl MemoryNote {
+    /// Bytes counted against `MAX_TOTAL_CHARS`
+    pub fn size(&self) -> usize {
+        self.content.len() + self.topics.iter().map(String::len).sum::<usize>()
+    }
+}
+
+/// What a write did
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteOutcome {
+    pub note: MemoryNote,
+    /// An existing note was changed rather than a new one added
+    pub updated: bool,
+    /// Notes dropped to stay under `MAX_TOTAL_CHARS`
+    pub evicted: Vec<MemoryNote>,
+}
+
+/// All notes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryStore {
+    pub notes: Vec<MemoryNote>,
+}
+
+impl MemoryStore {
+    /// `{data_dir}/memory.json`
+    pub fn path() -> Result<PathBuf, StorageError> {
+        Ok(get_data_dir()?.join("memory.json"))
+    }
+
+    /// The store at `path`, empty if there is none yet
+    pub fn load_from(path: &Path) -> Result<Self, StorageError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), StorageError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn total_size(&self) -> usize {
+        self.notes.iter().map(MemoryNote::size).sum()
+    }
+
+    /// Save a note, or replace note `id`
+    ///
+    /// A note with the same content (ignoring case) is updated instead of
+    /// duplicated, merging the topics.
+    pub fn write(
+        &mut self,
+        id: Option<&str>,
+        content: &str,
+        topics: &[String],
+    ) -> Result<WriteOutcome, StorageError> {
+        let content = content.trim();
+        if content.is_empty() {
+            return Err(StorageError::InvalidMemory("the note is empty".to_string()));
+        }
+        if content.len() > MAX_NOTE_CHARS {
+            return Err(StorageError::InvalidMemory(format!(
+                "the note is {} bytes long, the limit is {}: save a shorter fact",
+                content.len(),
+                MAX_NOTE_CHARS
+            )));
+        }
+        let topics = normalize_topics(topics);
+        let now = Utc::now();
+
+        let existing =
+            match id {
+                Some(id) => Some(self.notes.iter().position(|n| n.id == id).ok_or_else(|| {
+                    StorageError::InvalidMemory(format!("no note with id {}", id))
+                })?),
+                None => self
+                    .notes
+                    .iter()
+                    .position(|n| n.content.eq_ignore_ascii_case(content)),
+            };
+        let (index, updated) = match existing {
+            Some(index) => {
+                let note = &mut self.notes[index];
+                if id.is_some() {
+                    note.topics = topics;
+                } else {
+                    note.topics = normalize_topics(&[note.topics.clone(), topics].concat());
+                }
+                note.content = content.to_string();
+                note.updated_at = now;
+                note.last_used_at = now;
+                (index, true)
+            }
+            None => {
+                self.notes.push(MemoryNote {
+                    id: Uuid::new_v4().simple().to_string()[..8].to_string(),
+                    content: content.to_string(),
+                    topics,
+                    created_at: now,
+                    updated_at: now,
+                    last_used_at: now,
+                });
+                (self.notes.len() - 1, false)
+            }
+        };
+        let note = self.notes[index].clone();
+        let evicted = self.evict(&note.id);
+        Ok(WriteOutcome {
+            note,
+            updated,
+            evicted,
+        })
+    }
+
+    pub fn delete(&mut self, id: &str) -> bool {
+        let before = self.notes.len();
+        self.notes.retain(|n| n.id != id);
+        self.notes.len() != before
+    }
+
+    /// Notes with `topic` (all notes without one), most recently used first
+    pub fn read(&mut self, topic: Option<&str>, limit: usize) -> Vec<MemoryNote> {
+        let topic = topic.map(|t| t.trim().to_lowercase());
+        let mut notes: Vec<&mut MemoryNote> = self
+            .notes
+            .iter_mut()
+            .filter(|n| topic.as_ref().map_or(true, |t| n.topics.contains(t)))
+            .collect();
+        notes.sort_by_key(|n| std::cmp::Reverse(n.last_used_at));
+        notes.truncate(limit);
+        let now = Utc::now();
+        notes
+            .into_iter()
+            .map(|note| {
+                note.last_used_at = now;
+                note.clone()
+            })
+            .collect()
+    }
+
+    /// Up to `limit` notes sharing words with `query`, best first
+    pub fn search(&mut self, query: &str, limit: usize) -> Vec<(MemoryNote, usize)> {
+        let query_words = words(query);
+        let mut scored: Vec<(usize, &mut MemoryNote)> = self
+            .notes
+            .iter_mut()
+            .filter_map(|note| {
+                let score = relevance(note, &query_words);
+                (score > 0).then_some((score, note))
+            })
+            .collect();
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then(b.last_used_at.cmp(&a.last_used_at))
+        });
+        scored.truncate(limit);
+        let now = Utc::now();
+        scored
+            .into_iter()
+            .map(|(score, note)| {
+                note.last_used_at = now;
+                (note.clone(), score)
+            })
+            .collect()
+    }
+
+    /// Drop the least recently used notes, never `keep`, until the store fits
+    fn evict(&mut self, keep: &str) -> Vec<MemoryNote> {
+        let mut evicted = Vec::new();
+        while self.total_size() > MAX_TOTAL_CHARS {
+            let Some(oldest) = self
+                .notes
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| n.id != keep)
+                .min_by_key(|(_, n)| n.last_used_at)
+                .map(|(i, _)| i)
+            else {
+                break;
+            };
+            evicted.push(self.notes.remove(oldest));
+        }
+        evicted
+    }
+}
+
+/// Load the store, apply `change` and save it back
+///
+/// Reads go through here too: they refresh the notes' last use.
+pub fn update_memory<T>(change: impl FnOnce(&mut MemoryStore) -> T) -> Result<T, StorageError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = MemoryStore::path()?;
+    let mut store = MemoryStore::load_from(&path)?;
+    let result = change(&mut store);
+    store.save_to(&path)?;
+    Ok(result)
+}
+
+/// Current notes, without touching them
+pub fn load_memory() -> Result<MemoryStore, StorageError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    MemoryStore::load_from(&MemoryStore::path()?)
+}
+
+fn normalize_topics(topics: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    topics
+        .iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty() && seen.insert(t.clone()))
+        .take(MAX_TOPICS)
+        .collect()
+}
+
+/// Lowercase words of three letters or more, without stopwords
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Words of `query_words` found in the note, topic words counting double
+fn relevance(note: &MemoryNote, query_words: &HashSet<String>) -> usize {
+    let content = words(&note.content);
+    let topics = words(&note.topics.join(" "));
+    query_words
+        .iter()
+        .map(|w| usize::from(content.contains(w)) + 2 * usize::from(topics.contains(w)))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics(list: &[&str]) -> Vec<String> {
+        list.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_write_dedupes_and_updates() {
+        let mut store = MemoryStore::default();
+        let first = store
+            .write(None, "User prefers tabs", &topics(&["Preferences"]))
+            .unwrap();
+        assert!(!first.updated);
+        assert_eq!(first.note.topics, vec!["preferences"]);
+
+        let again = store
+            .write(
+                None,
+                "user prefers TABS",
+                &topics(&["editor", "preferences"]),
+            )
+            .unwrap();
+        assert!(again.updated);
+        assert_eq!(store.notes.len(), 1);
+        assert_eq!(store.notes[0].topics, vec!["preferences", "editor"]);
+
+        let replaced = store
+            .write(Some(&first.note.id), "User prefers spaces", &[])
+            .unwrap();
+        assert!(replaced.updated);
+        assert_eq!(store.notes[0].content, "User prefers spaces");
+        assert!(store.notes[0].topics.is_empty());
+
+        assert!(store.write(Some("missing"), "x", &[]).is_err());
+        assert!(store.write(None, "  ", &[]).is_err());
+        assert!(store
+            .write(None, &"x".repeat(MAX_NOTE_CHARS + 1), &[])
+            .is_err());
+        assert!(store.delete(&first.note.id));
+        assert!(!store.delete(&first.note.id));
+    }
+
+    #[test]
+    fn test_search_and_read() {
+        let mut store = MemoryStore::default();
+        store
+            .write(None, "Project root is ~/code/foo", &topics(&["project"]))
+            .unwrap();
+        store
+            .write(
+                None,
+                "User prefers tabs over spaces",
+                &topics(&["preferences", "editor"]),
+            )
+            .unwrap();
+        store
+            .write(None, "Deploys go through the staging cluster", &[])
+            .unwrap();
+
+        let hits = store.search("Which editor settings does the user like? tabs?", 5);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.content, "User prefers tabs over spaces");
+        assert_eq!(hits[0].1, 4);
+        assert!(store.search("the and for", 5).is_empty());
+
+        let project = store.read(Some("PROJECT"), 10);
+        assert_eq!(project.len(), 1);
+        assert_eq!(store.read(None, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_eviction_drops_least_recently_used() {
+        let mut store = MemoryStore::default();
+        let note = "n".repeat(MAX_NOTE_CHARS - 10);
+        let count = MAX_TOTAL_CHARS / note.len();
+        for i in 0..count {
+            let outcome = store
+                .write(None, &format!("{:03}{}", i, note), &[])
+                .unwrap();
+            assert!(outcome.evicted.is_empty());
+        }
+        // The first note was used recently, so the second one goes
+        let first = store.notes[0].id.clone();
+        store.notes[0].last_used_at = Utc::now();
+        let outcome = store.write(None, &format!("new{}", note), &[]).unwrap();
+        assert_eq!(outcome.evicted.len(), 1);
+        assert!(outcome.evicted[0].content.starts_with("001"));
+        assert!(store.notes.iter().any(|n| n.id == first));
+        assert!(store.total_size() <= MAX_TOTAL_CHARS);
+    }
+
+    #[test]
+    fn test_store_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        assert_eq!(
+            MemoryStore::load_from(&path).unwrap(),
+            MemoryStore::default()
+        );
+
+        let mut store = MemoryStore::default();
+        store
+            .write(None, "Uses fish shell", &topics(&["environment"]))
+            .unwrap();
+        store.save_to(&path).unwrap();
+        assert_eq!(MemoryStore::load_from(&path).unwrap(), store);
+    }
+}
@@ -7,8 +7,13 @@ use thiserror::Error;
 
 pub mod conversations;
 pub mod huggingface;
+pub mod index;
+pub mod memory;
 pub mod models;
+pub mod redaction;
+pub mod secrets;
 pub mod settings;
+pub mod templates;
 
 /// Storage-related errors
 #[derive(Debug, Error)]
@@ -21,6 +26,12 @@ pub enum StorageError {
     JsonError(#[from] serde_json::Error),
     #[error("Conversation not found: {0}")]
     ConversationNotFound(String),
+    #[error("Failed to write archive: {0}")]
+    ArchiveError(String),
+    #[error("Invalid memory note: {0}")]
+    InvalidMemory(String),
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
 }
 
 /// Get the application data directory
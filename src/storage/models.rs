@@ -1,12 +1,45 @@
 //! Model metadata storage
 //!
-//! Tracks installed models and their configurations.
+//! Tracks installed models and their configurations, downloads new ones in
+//! the background (`ModelDownloader`) and searches the Hugging Face Hub for
+//! GGUF repositories (`search_hf_models`). The whisper.cpp models of voice
+//! input are a fixed list (`WHISPER_MODELS`) kept in their own folder.
 
+use crate::storage::huggingface::sanitize_local_filename;
 use crate::storage::{get_data_dir, StorageError};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::time::SystemTime;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// Suffix of a download in progress, next to its destination
+const PARTIAL_SUFFIX: &str = ".part";
+
+/// Attempts per download; each one resumes where the previous stopped
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+
+/// A connection that sends nothing for this long is retried
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(60);
+
+const HF_API_URL: &str = "https://huggingface.co/api";
+
+/// Repositories listed per Hub search
+const HF_SEARCH_LIMIT: usize = 20;
+
+/// How long Hub search results are reused, to stay clear of its rate limits
+const HF_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Hub search results by normalized query
+static HF_SEARCH_CACHE: Lazy<Mutex<HashMap<String, (Instant, Vec<HfModelInfo>)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// Information about a GGUF model file
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,7 +90,9 @@ impl ModelInfo {
 
 /// Scan a directory for GGUF model files
 ///
-/// Returns a list of ModelInfo for all .gguf files found in the directory
+/// Returns a list of ModelInfo for all .gguf files found in the directory.
+/// A model split in several files is listed once, as its first part with
+/// the size of all parts, and only once every part is there.
 pub fn scan_models_directory(directory: &PathBuf) -> Result<Vec<ModelInfo>, StorageError> {
     if !directory.exists() {
         tracing::warn!("Models directory does not exist: {}", directory.display());
@@ -79,8 +114,26 @@ pub fn scan_models_directory(directory: &PathBuf) -> Result<Vec<ModelInfo>, Stor
         if path.is_file() {
             if let Some(extension) = path.extension() {
                 if extension.to_str() == Some("gguf") {
+                    let split = path.file_name().and_then(|n| n.to_str()).and_then(split_gguf_part);
+                    let mut split_size = 0;
+                    if let Some((prefix, index, count)) = split {
+                        if index != 1 {
+                            continue;
+                        }
+                        let parts: Option<Vec<u64>> = (1..=count)
+                            .map(|i| fs::metadata(directory.join(split_gguf_name(prefix, i, count))).ok().map(|m| m.len()))
+                            .collect();
+                        let Some(parts) = parts else {
+                            tracing::debug!("Skipping incomplete split model: {:?}", path);
+                            continue;
+                        };
+                        split_size = parts.iter().sum();
+                    }
                     match ModelInfo::from_path(path.clone()) {
-                        Ok(model_info) => {
+                        Ok(mut model_info) => {
+                            if split.is_some() {
+                                model_info.size_bytes = split_size;
+                            }
                             tracing::debug!("Found model: {}", model_info.filename);
                             models.push(model_info);
                         }
@@ -101,6 +154,590 @@ pub fn scan_models_directory(directory: &PathBuf) -> Result<Vec<ModelInfo>, Stor
     Ok(models)
 }
 
+/// Prefix, index and count of a split GGUF file (`name-00001-of-00003.gguf`)
+pub fn split_gguf_part(filename: &str) -> Option<(&str, u32, u32)> {
+    let stem = filename.strip_suffix(".gguf")?;
+    let (head, count) = stem.rsplit_once("-of-")?;
+    let (prefix, index) = head.rsplit_once('-')?;
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_number(index) || !is_number(count) {
+        return None;
+    }
+    let (index, count) = (index.parse().ok()?, count.parse().ok()?);
+    (1..=count).contains(&index).then_some((prefix, index, count))
+}
+
+/// File name of one part of a split GGUF model, as llama.cpp names them
+fn split_gguf_name(prefix: &str, index: u32, count: u32) -> String {
+    format!("{}-{:05}-of-{:05}.gguf", prefix, index, count)
+}
+
+/// State of a background download
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadStatus {
+    Starting,
+    Downloading {
+        downloaded: u64,
+        /// Unknown when the server sends no length
+        total: Option<u64>,
+        bytes_per_sec: f64,
+        eta: Option<Duration>,
+    },
+    /// Checking the sha256 of the complete file
+    Verifying,
+    /// Verified and moved to its destination
+    Finished(PathBuf),
+    Failed(String),
+    /// Stopped by the user; the partial file is kept for a later resume
+    Cancelled,
+}
+
+impl DownloadStatus {
+    pub fn is_done(&self) -> bool {
+        matches!(self, Self::Finished(_) | Self::Failed(_) | Self::Cancelled)
+    }
+
+    /// Share downloaded, when the size is known
+    pub fn fraction(&self) -> Option<f32> {
+        match self {
+            Self::Downloading { downloaded, total: Some(total), .. } if *total > 0 => {
+                Some((*downloaded as f64 / *total as f64).min(1.0) as f32)
+            }
+            Self::Verifying | Self::Finished(_) => Some(1.0),
+            _ => None,
+        }
+    }
+}
+
+/// Progress and cancellation of one download started by `ModelDownloader`
+pub struct DownloadHandle {
+    status: watch::Receiver<DownloadStatus>,
+    cancel: CancellationToken,
+}
+
+impl DownloadHandle {
+    pub fn status(&self) -> DownloadStatus {
+        self.status.borrow().clone()
+    }
+
+    /// Receiver notified on every status change
+    pub fn subscribe(&self) -> watch::Receiver<DownloadStatus> {
+        self.status.clone()
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Downloads model files to disk in the background
+///
+/// Data goes to `<dest>.part` first. An interrupted download (network error,
+/// cancel, app closed) resumes from that file with an HTTP range request the
+/// next time the same destination is downloaded. The file only takes its
+/// final name, which makes it show up in the models list, once complete and
+/// matching the expected sha256.
+#[derive(Clone)]
+pub struct ModelDownloader {
+    client: reqwest::Client,
+}
+
+impl ModelDownloader {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(30))
+            .user_agent(concat!("clawRS/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Download `url` to `dest`, checking the file against `sha256` (hex) when given
+    pub fn start_download(&self, url: &str, dest: PathBuf, sha256: Option<String>) -> DownloadHandle {
+        self.start_download_parts(vec![DownloadPart { url: url.to_string(), dest, size: None, sha256 }])
+    }
+
+    /// Download every part in turn, reporting their overall progress
+    ///
+    /// Parts already on disk are kept. The download finishes with the path
+    /// of the first part, the one llama.cpp loads a split model from.
+    pub fn start_download_parts(&self, parts: Vec<DownloadPart>) -> DownloadHandle {
+        let (status_tx, status) = watch::channel(DownloadStatus::Starting);
+        let cancel = CancellationToken::new();
+        let client = self.client.clone();
+        let task_cancel = cancel.clone();
+        tokio::spawn(async move {
+            let outcome = run_download_parts(&client, &parts, &status_tx, &task_cancel).await;
+            let dest = parts.first().map(|part| part.dest.clone()).unwrap_or_default();
+            let url = parts.first().map(|part| part.url.as_str()).unwrap_or_default();
+            let status = match outcome {
+                Ok(()) => {
+                    tracing::info!("Download complete: {:?}", dest);
+                    DownloadStatus::Finished(dest)
+                }
+                Err(_) if task_cancel.is_cancelled() => {
+                    tracing::info!("Download cancelled: {:?}", dest);
+                    DownloadStatus::Cancelled
+                }
+                Err(e) => {
+                    tracing::error!("Download of {} failed: {}", url, e);
+                    DownloadStatus::Failed(e)
+                }
+            };
+            let _ = status_tx.send(status);
+        });
+        DownloadHandle { status, cancel }
+    }
+}
+
+impl Default for ModelDownloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One file of a download; a model split in several GGUF files has one per split
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadPart {
+    pub url: String,
+    pub dest: PathBuf,
+    /// Expected size, for the overall progress of a multi-part download
+    pub size: Option<u64>,
+    /// Hex sha256 the file must match
+    pub sha256: Option<String>,
+}
+
+/// Bytes of a multi-part download before the current part, and its overall size
+#[derive(Debug, Clone, Copy, Default)]
+struct BatchProgress {
+    before: u64,
+    total: Option<u64>,
+}
+
+/// Where a download to `dest` is written until it completes
+pub fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(PARTIAL_SUFFIX);
+    PathBuf::from(name)
+}
+
+async fn run_download_parts(
+    client: &reqwest::Client,
+    parts: &[DownloadPart],
+    status: &watch::Sender<DownloadStatus>,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    if parts.is_empty() {
+        return Err("Nothing to download".to_string());
+    }
+    // Known only when every part announces its size
+    let total: Option<u64> = parts.iter().map(|part| part.size).sum();
+    let mut before = 0;
+    for part in parts {
+        if !part.dest.exists() {
+            let batch = BatchProgress { before, total: if parts.len() > 1 { total } else { None } };
+            run_download(client, &part.url, &part.dest, part.sha256.as_deref(), batch, status, cancel).await?;
+        }
+        before += fs::metadata(&part.dest).map(|m| m.len()).unwrap_or(0);
+    }
+    Ok(())
+}
+
+async fn run_download(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    sha256: Option<&str>,
+    batch: BatchProgress,
+    status: &watch::Sender<DownloadStatus>,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    if let Some(dir) = dest.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let part = partial_path(dest);
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match download_attempt(client, url, &part, batch, status, cancel).await {
+            Ok(()) => break,
+            Err(_) if cancel.is_cancelled() => return Err("Cancelled".to_string()),
+            Err(e) if attempt < DOWNLOAD_ATTEMPTS => {
+                tracing::warn!("Download interrupted ({}), resuming (attempt {}/{})", e, attempt + 1, DOWNLOAD_ATTEMPTS);
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))) => {}
+                    _ = cancel.cancelled() => return Err("Cancelled".to_string()),
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    if let Some(expected) = sha256 {
+        let _ = status.send(DownloadStatus::Verifying);
+        let hash_path = part.clone();
+        let actual = tokio::task::spawn_blocking(move || file_sha256(&hash_path))
+            .await
+            .map_err(|e| format!("Checksum task failed: {}", e))?
+            .map_err(|e| format!("Failed to read the download: {}", e))?;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            let _ = fs::remove_file(&part);
+            return Err(format!("Checksum mismatch: expected {}, got {}", expected.trim(), actual));
+        }
+    }
+
+    fs::rename(&part, dest).map_err(|e| format!("Failed to move the download into place: {}", e))
+}
+
+/// One request, appending to whatever `part` already holds
+async fn download_attempt(
+    client: &reqwest::Client,
+    url: &str,
+    part: &Path,
+    batch: BatchProgress,
+    status: &watch::Sender<DownloadStatus>,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let offset = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = tokio::select! {
+        response = request.send() => response.map_err(|e| format!("Download failed: {}", e))?,
+        _ = cancel.cancelled() => return Err("Cancelled".to_string()),
+    };
+
+    let status_code = response.status();
+    if status_code == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+        // Everything was already there
+        return Ok(());
+    }
+    if !status_code.is_success() {
+        return Err(format!("Download failed with status: {}", status_code));
+    }
+
+    // A server ignoring the range sends the whole file again
+    let resumed = status_code == reqwest::StatusCode::PARTIAL_CONTENT;
+    let start = if resumed { offset } else { 0 };
+    let total = if resumed {
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(content_range_total)
+            .or_else(|| response.content_length().map(|len| len + offset))
+    } else {
+        response.content_length()
+    };
+    if resumed {
+        tracing::info!("Resuming download at {} bytes", offset);
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+
+    let overall_total = batch.total.or_else(|| total.map(|total| total + batch.before));
+    let started = Instant::now();
+    let mut downloaded = start;
+    loop {
+        let chunk = tokio::select! {
+            chunk = tokio::time::timeout(CHUNK_TIMEOUT, response.chunk()) => chunk
+                .map_err(|_| "Connection stalled".to_string())?
+                .map_err(|e| format!("Download error: {}", e))?,
+            _ = cancel.cancelled() => {
+                let _ = file.flush().await;
+                return Err("Cancelled".to_string());
+            }
+        };
+        let Some(chunk) = chunk else { break };
+        file.write_all(&chunk).await.map_err(|e| format!("Write error: {}", e))?;
+        downloaded += chunk.len() as u64;
+
+        let bytes_per_sec = (downloaded - start) as f64 / started.elapsed().as_secs_f64().max(0.001);
+        let overall = batch.before + downloaded;
+        let _ = status.send(DownloadStatus::Downloading {
+            downloaded: overall,
+            total: overall_total,
+            bytes_per_sec,
+            eta: overall_total.and_then(|total| download_eta(total.saturating_sub(overall), bytes_per_sec)),
+        });
+    }
+    file.flush().await.map_err(|e| format!("Write error: {}", e))?;
+
+    match total {
+        Some(total) if downloaded < total => Err(format!("Connection closed at {} of {} bytes", downloaded, total)),
+        _ => Ok(()),
+    }
+}
+
+/// Full size from a `Content-Range: bytes 100-999/1000` header
+fn content_range_total(header: &str) -> Option<u64> {
+    header.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// Time left at the current speed; none before any data arrived
+fn download_eta(remaining: u64, bytes_per_sec: f64) -> Option<Duration> {
+    (bytes_per_sec >= 1.0).then(|| Duration::from_secs_f64(remaining as f64 / bytes_per_sec))
+}
+
+/// Lowercase hex sha256 of a file
+pub fn file_sha256(path: &Path) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// A GGUF repository on the Hugging Face Hub
+#[derive(Debug, Clone, PartialEq)]
+pub struct HfModelInfo {
+    pub repo_id: String,
+    pub downloads: u64,
+    pub likes: u64,
+    pub license: Option<String>,
+    /// Downloadable models, smallest first
+    pub files: Vec<HfModelFile>,
+}
+
+/// One model of a repository: a single GGUF file, or all the splits of one
+#[derive(Debug, Clone, PartialEq)]
+pub struct HfModelFile {
+    /// Quantization read from the file name (`Q4_K_M`, `F16`...)
+    pub quantization: Option<String>,
+    /// Files in the repository, in split order
+    pub parts: Vec<HfFilePart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HfFilePart {
+    /// Path in the repository
+    pub path: String,
+    pub size_bytes: u64,
+    pub sha256: Option<String>,
+}
+
+impl HfModelFile {
+    pub fn size_bytes(&self) -> u64 {
+        self.parts.iter().map(|part| part.size_bytes).sum()
+    }
+
+    /// Name shown for the model: its first file
+    pub fn name(&self) -> &str {
+        self.parts.first().map(|part| part.path.as_str()).unwrap_or_default()
+    }
+
+    /// Downloads of every part into `directory`, for `ModelDownloader::start_download_parts`
+    pub fn download_parts(&self, repo_id: &str, directory: &Path) -> Result<Vec<DownloadPart>, String> {
+        self.parts
+            .iter()
+            .map(|part| {
+                Ok(DownloadPart {
+                    url: format!("https://huggingface.co/{}/resolve/main/{}", repo_id, part.path),
+                    dest: directory.join(sanitize_local_filename(&part.path)?),
+                    size: Some(part.size_bytes),
+                    sha256: part.sha256.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HfSearchEntry {
+    id: String,
+    #[serde(default)]
+    downloads: u64,
+    #[serde(default)]
+    likes: u64,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HfTreeEntry {
+    path: String,
+    #[serde(default)]
+    size: u64,
+    lfs: Option<HfLfsInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HfLfsInfo {
+    /// sha256 of the file
+    oid: String,
+}
+
+/// Search the Hugging Face Hub for GGUF repositories, most downloaded first
+///
+/// `quantization_filter` keeps the files whose quantization starts with it
+/// (`Q4` matches `Q4_K_M`); repositories left without files are dropped.
+/// Results are cached for an hour.
+pub async fn search_hf_models(query: &str, quantization_filter: Option<&str>) -> Result<Vec<HfModelInfo>, String> {
+    let key = query.trim().to_lowercase();
+    let cached = HF_SEARCH_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).filter(|(at, _)| at.elapsed() < HF_CACHE_TTL).map(|(_, models)| models.clone()));
+    let models = match cached {
+        Some(models) => models,
+        None => {
+            let models = fetch_hf_models(&key).await?;
+            if let Ok(mut cache) = HF_SEARCH_CACHE.lock() {
+                cache.retain(|_, (at, _)| at.elapsed() < HF_CACHE_TTL);
+                cache.insert(key, (Instant::now(), models.clone()));
+            }
+            models
+        }
+    };
+
+    let filter = quantization_filter.map(|f| f.trim().to_uppercase()).filter(|f| !f.is_empty());
+    Ok(models
+        .into_iter()
+        .filter_map(|mut model| {
+            if let Some(filter) = &filter {
+                model.files.retain(|file| file.quantization.as_deref().is_some_and(|q| q.starts_with(filter.as_str())));
+            }
+            (!model.files.is_empty()).then_some(model)
+        })
+        .collect())
+}
+
+async fn fetch_hf_models(query: &str) -> Result<Vec<HfModelInfo>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent(concat!("clawRS/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let limit = HF_SEARCH_LIMIT.to_string();
+    let response = client
+        .get(format!("{}/models", HF_API_URL))
+        .query(&[
+            ("search", query),
+            ("filter", "gguf"),
+            ("sort", "downloads"),
+            ("direction", "-1"),
+            ("limit", limit.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Hugging Face search failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Hugging Face search failed with status: {}", response.status()));
+    }
+    let entries: Vec<HfSearchEntry> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse the search results: {}", e))?;
+
+    // File sizes are only in each repository's tree
+    let trees = futures::future::join_all(entries.iter().map(|entry| fetch_hf_tree(&client, &entry.id))).await;
+    Ok(entries
+        .into_iter()
+        .zip(trees)
+        .filter_map(|(entry, tree)| {
+            let tree = match tree {
+                Ok(tree) => tree,
+                Err(e) => {
+                    tracing::warn!("Skipping {}: {}", entry.id, e);
+                    return None;
+                }
+            };
+            let files = group_gguf_files(tree);
+            if files.is_empty() {
+                return None;
+            }
+            let license = entry.tags.iter().find_map(|tag| tag.strip_prefix("license:")).map(str::to_string);
+            Some(HfModelInfo {
+                repo_id: entry.id,
+                downloads: entry.downloads,
+                likes: entry.likes,
+                license,
+                files,
+            })
+        })
+        .collect())
+}
+
+async fn fetch_hf_tree(client: &reqwest::Client, repo_id: &str) -> Result<Vec<HfTreeEntry>, String> {
+    let response = client
+        .get(format!("{}/models/{}/tree/main?recursive=true", HF_API_URL, repo_id))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch repo info: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+    response.json().await.map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// GGUF files of a repository tree, the splits of a model grouped together
+fn group_gguf_files(tree: Vec<HfTreeEntry>) -> Vec<HfModelFile> {
+    let mut groups: Vec<(String, Vec<(u32, HfFilePart)>)> = Vec::new();
+    for entry in tree.into_iter().filter(|entry| entry.path.ends_with(".gguf")) {
+        let (key, index) = match split_gguf_part(&entry.path) {
+            Some((prefix, index, count)) => (format!("{}-of-{}", prefix, count), index),
+            None => (entry.path.clone(), 1),
+        };
+        let part = HfFilePart {
+            sha256: entry.lfs.map(|lfs| lfs.oid),
+            size_bytes: entry.size,
+            path: entry.path,
+        };
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, parts)) => parts.push((index, part)),
+            None => groups.push((key, vec![(index, part)])),
+        }
+    }
+
+    let mut files: Vec<HfModelFile> = groups
+        .into_iter()
+        .map(|(_, mut parts)| {
+            parts.sort_by_key(|(index, _)| *index);
+            let parts: Vec<HfFilePart> = parts.into_iter().map(|(_, part)| part).collect();
+            let name = parts[0].path.rsplit('/').next().unwrap_or_default();
+            HfModelFile { quantization: quantization_of(name), parts }
+        })
+        .collect();
+    files.sort_by_key(|file| file.size_bytes());
+    files
+}
+
+/// Quantization named in a GGUF file name, uppercased
+fn quantization_of(filename: &str) -> Option<String> {
+    // Longest first, so `Q4_K` does not shadow `Q4_K_M`
+    const QUANTIZATIONS: &[&str] = &[
+        "IQ2_XXS", "IQ3_XXS", "IQ2_XS", "IQ3_XS", "IQ4_XS", "IQ4_NL", "Q2_K_S", "Q3_K_S", "Q3_K_M", "Q3_K_L",
+        "Q4_K_S", "Q4_K_M", "Q5_K_S", "Q5_K_M", "IQ1_S", "IQ1_M", "IQ2_S", "IQ2_M", "IQ3_S", "IQ3_M", "Q2_K",
+        "Q3_K", "Q4_K", "Q5_K", "Q6_K", "Q4_0", "Q4_1", "Q5_0", "Q5_1", "Q8_0", "BF16", "F16", "F32",
+    ];
+    let name = filename.to_uppercase();
+    let bytes = name.as_bytes();
+    QUANTIZATIONS.iter().find_map(|quant| {
+        name.match_indices(quant).find_map(|(at, _)| {
+            let end = at + quant.len();
+            let bounded = (at == 0 || !bytes[at - 1].is_ascii_alphanumeric())
+                && (end == bytes.len() || !bytes[end].is_ascii_alphanumeric());
+            bounded.then(|| quant.to_string())
+        })
+    })
+}
+
 /// Scan the default models directory
 ///
 /// Uses the models directory from the application data directory
@@ -109,6 +746,47 @@ pub fn scan_default_models_directory() -> Result<Vec<ModelInfo>, StorageError> {
     scan_models_directory(&models_dir)
 }
 
+/// Where the whisper.cpp models are published
+const WHISPER_MODELS_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// A whisper.cpp speech-to-text model offered for voice input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WhisperModel {
+    /// File name, also what the voice settings keep
+    pub file: &'static str,
+    pub label: &'static str,
+    pub size_mb: u32,
+}
+
+/// Whisper models from smallest to largest; all are multilingual
+pub const WHISPER_MODELS: &[WhisperModel] = &[
+    WhisperModel { file: "ggml-tiny.bin", label: "Tiny", size_mb: 75 },
+    WhisperModel { file: "ggml-base.bin", label: "Base", size_mb: 142 },
+    WhisperModel { file: "ggml-small.bin", label: "Small", size_mb: 466 },
+    WhisperModel { file: "ggml-medium.bin", label: "Medium", size_mb: 1533 },
+    WhisperModel { file: "ggml-large-v3-turbo.bin", label: "Large v3 Turbo", size_mb: 1624 },
+];
+
+impl WhisperModel {
+    pub fn find(file: &str) -> Option<&'static WhisperModel> {
+        WHISPER_MODELS.iter().find(|m| m.file == file)
+    }
+
+    pub fn url(&self) -> String {
+        format!("{}/{}", WHISPER_MODELS_URL, self.file)
+    }
+}
+
+/// Folder of the whisper models, `models/whisper` in the data directory
+pub fn whisper_models_dir() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("models").join("whisper"))
+}
+
+/// Where a whisper model is, or goes once downloaded
+pub fn whisper_model_path(file: &str) -> Result<PathBuf, StorageError> {
+    Ok(whisper_models_dir()?.join(file))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,6 +854,85 @@ mod tests {
         assert_eq!(result.unwrap().len(), 0);
     }
 
+    #[test]
+    fn test_download_helpers() {
+        assert_eq!(content_range_total("bytes 100-999/1000"), Some(1000));
+        assert_eq!(content_range_total("bytes */1000"), Some(1000));
+        assert_eq!(content_range_total("bytes 0-99/*"), None);
+
+        assert_eq!(download_eta(1000, 100.0), Some(Duration::from_secs(10)));
+        assert_eq!(download_eta(1000, 0.0), None);
+
+        assert_eq!(
+            partial_path(Path::new("/models/qwen.gguf")),
+            PathBuf::from("/models/qwen.gguf.part")
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("abc.bin");
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            file_sha256(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_partial_downloads_are_not_listed() {
+        let temp_dir = TempDir::new().unwrap();
+        File::create(temp_dir.path().join("done.gguf")).unwrap();
+        File::create(partial_path(&temp_dir.path().join("pending.gguf"))).unwrap();
+
+        let models = scan_models_directory(&temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].filename, "done.gguf");
+    }
+
+    #[test]
+    fn test_split_models_are_listed_once() {
+        assert_eq!(split_gguf_part("qwen-Q4_K_M-00002-of-00003.gguf"), Some(("qwen-Q4_K_M", 2, 3)));
+        assert_eq!(split_gguf_part("qwen-Q4_K_M.gguf"), None);
+        assert_eq!(split_gguf_part("qwen-00004-of-00003.gguf"), None);
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("big-00001-of-00002.gguf"), b"abc").unwrap();
+        fs::write(temp_dir.path().join("big-00002-of-00002.gguf"), b"de").unwrap();
+        // Second part still downloading
+        fs::write(temp_dir.path().join("partial-00001-of-00002.gguf"), b"abc").unwrap();
+
+        let models = scan_models_directory(&temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].filename, "big-00001-of-00002.gguf");
+        assert_eq!(models[0].size_bytes, 5);
+    }
+
+    #[test]
+    fn test_group_hf_files() {
+        let entry = |path: &str, size: u64| HfTreeEntry { path: path.to_string(), size, lfs: None };
+        let files = group_gguf_files(vec![
+            entry("README.md", 10),
+            entry("Q8_0/model-Q8_0-00002-of-00002.gguf", 400),
+            entry("model.Q4_K_M.gguf", 300),
+            entry("Q8_0/model-Q8_0-00001-of-00002.gguf", 500),
+            entry("model-bf16.gguf", 2000),
+        ]);
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].quantization.as_deref(), Some("Q4_K_M"));
+        assert_eq!(files[1].quantization.as_deref(), Some("Q8_0"));
+        assert_eq!(files[1].size_bytes(), 900);
+        assert_eq!(files[1].name(), "Q8_0/model-Q8_0-00001-of-00002.gguf");
+        assert_eq!(files[2].quantization.as_deref(), Some("BF16"));
+
+        let parts = files[1].download_parts("org/model-GGUF", Path::new("/models")).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].dest, PathBuf::from("/models/Q8_0__model-Q8_0-00001-of-00002.gguf"));
+        assert!(parts[1].url.ends_with("/org/model-GGUF/resolve/main/Q8_0/model-Q8_0-00002-of-00002.gguf"));
+
+        assert_eq!(quantization_of("tiny-iq4_xs.gguf").as_deref(), Some("IQ4_XS"));
+        assert_eq!(quantization_of("mmproj.gguf"), None);
+    }
+
     #[test]
     fn test_scan_empty_directory() {
         let temp_dir = TempDir::new().unwrap();
@@ -183,4 +940,12 @@ mod tests {
 
         assert_eq!(models.len(), 0);
     }
+
+    #[test]
+    fn test_whisper_models() {
+        let base = WhisperModel::find("ggml-base.bin").unwrap();
+        assert_eq!(base.url(), "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin");
+        assert!(WhisperModel::find("llama.gguf").is_none());
+        assert!(WHISPER_MODELS.windows(2).all(|pair| pair[0].size_mb < pair[1].size_mb));
+    }
 }
@@ -0,0 +1,573 @@
+//! Secrets vault
+//!
+//! Named secrets (API keys, tokens) are kept in the OS keyring (Keychain,
+//! Credential Manager, Secret Service). Where no keyring answers they go to
+//! `secrets.json` in the data directory instead, sealed with ChaCha20-Poly1305
+//! under a key derived (HKDF-SHA256) from the machine id and the user account.
+//! Nothing of the key is stored, so a copy of the data directory can't be
+//! opened on another machine or account; a program running as the same user
+//! on the same machine can derive it too, which only the keyring protects
+//! against. Tool params and MCP server `env` values refer to secrets as
+//! `${secret:NAME}`: values are substituted just before use and scrubbed
+//! (`***`) from what tools send back.
+
+use crate::storage::get_data_dir;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{RwLock, RwLockReadGuard};
+use thiserror::Error;
+
+/// File name of the fallback vault in the data directory
+const SECRETS_FILE: &str = "secrets.json";
+
+/// Random key of the fallback vault before keys were derived, next to it;
+/// read once to move its secrets under the derived key
+const LEGACY_KEY_FILE: &str = "secrets.key";
+
+/// Keyring entry holding the JSON map of secrets
+const KEYRING_SERVICE: &str = "clawrs";
+const KEYRING_USER: &str = "secrets";
+
+/// Format of the fallback file: 3 derives its key, 2 used `secrets.key`, and
+/// older files used a cipher that was dropped
+const VAULT_VERSION: u32 = 3;
+const LEGACY_VAULT_VERSION: u32 = 2;
+
+/// HKDF context of the fallback key
+const KEY_INFO: &[u8] = b"clawrs secrets vault v3";
+
+/// Values shorter than this are not scrubbed: they would mask ordinary text
+const MIN_SCRUBBED_LEN: usize = 4;
+
+/// What replaces a secret value in tool output
+pub const SCRUBBED: &str = "***";
+
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\{secret:([A-Za-z0-9_]+)\}").unwrap());
+
+/// Secrets in use, loaded at startup and after each change
+static ACTIVE: RwLock<Vault> = RwLock::new(Vault { secrets: BTreeMap::new() });
+
+/// Secrets errors
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("Unknown secret `{0}`: add it in Settings > Secrets")]
+    Unknown(String),
+    #[error("Invalid secret name `{0}`: use letters, digits and _")]
+    InvalidName(String),
+    #[error("The secrets file is damaged, or was sealed on another machine or account")]
+    Undecryptable,
+    #[error("No machine id to derive the secrets key from")]
+    NoMachineId,
+    #[error("OS keyring error: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error("Failed to access the secrets file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to read the secrets file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to access data directory: {0}")]
+    DataDir(String),
+}
+
+/// On-disk form of the fallback vault: the JSON map of secrets, sealed
+#[derive(Debug, Serialize, Deserialize)]
+struct SealedVault {
+    version: u32,
+    /// HKDF salt, new for each save; absent from version 2 files
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    salt: String,
+    nonce: String,
+    /// Ciphertext followed by the Poly1305 tag
+    ciphertext: String,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// Key of the fallback vault for `identity` (see `machine_identity`)
+fn derive_key(identity: &[u8], salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), identity)
+        .expand(KEY_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    key
+}
+
+fn seal(identity: &[u8], plaintext: &[u8]) -> Result<SealedVault, SecretError> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&derive_key(identity, &salt)));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|_| SecretError::Undecryptable)?;
+    Ok(SealedVault {
+        version: VAULT_VERSION,
+        salt: to_hex(&salt),
+        nonce: to_hex(&nonce),
+        ciphertext: to_hex(&ciphertext),
+    })
+}
+
+fn unseal(identity: &[u8], sealed: &SealedVault) -> Result<Vec<u8>, SecretError> {
+    if sealed.version != VAULT_VERSION {
+        return Err(SecretError::Undecryptable);
+    }
+    let salt = from_hex(&sealed.salt).ok_or(SecretError::Undecryptable)?;
+    decrypt(&derive_key(identity, &salt), sealed)
+}
+
+fn decrypt(key: &[u8; 32], sealed: &SealedVault) -> Result<Vec<u8>, SecretError> {
+    let (Some(nonce), Some(ciphertext)) = (from_hex(&sealed.nonce), from_hex(&sealed.ciphertext)) else {
+        return Err(SecretError::Undecryptable);
+    };
+    if nonce.len() != 12 {
+        return Err(SecretError::Undecryptable);
+    }
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| SecretError::Undecryptable)
+}
+
+/// Secrets of a version 2 file, sealed under the random key of `key_path`
+fn load_legacy(path: &Path, key_path: &Path) -> Result<BTreeMap<String, String>, SecretError> {
+    let key = from_hex(std::fs::read_to_string(key_path)?.trim())
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or(SecretError::Undecryptable)?;
+    let sealed: SealedVault = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if sealed.version != LEGACY_VAULT_VERSION {
+        return Err(SecretError::Undecryptable);
+    }
+    serde_json::from_slice(&decrypt(&key, &sealed)?).map_err(|_| SecretError::Undecryptable)
+}
+
+/// What the fallback key is derived from: the OS machine id, the account
+/// name and the home directory
+fn machine_identity() -> Result<Vec<u8>, SecretError> {
+    let machine_id = machine_id().ok_or(SecretError::NoMachineId)?;
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    let home = directories::BaseDirs::new()
+        .map(|dirs| dirs.home_dir().to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok([machine_id.as_str(), &user, &home].join("\0").into_bytes())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("\"IOPlatformUUID\""))
+        .and_then(|line| line.rsplit('"').nth(1))
+        .map(str::to_string)
+}
+
+#[cfg(windows)]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("reg")
+        .args(["query", r"HKLM\SOFTWARE\Microsoft\Cryptography", "/v", "MachineGuid"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("MachineGuid"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id", "/etc/hostid"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+/// Names of secrets: what `${secret:NAME}` accepts
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Where the secrets are kept
+enum Backend {
+    Keyring(keyring::Entry),
+    /// Sealed file, for systems without a keyring
+    File { path: PathBuf, identity: Vec<u8> },
+}
+
+/// Persistent secrets: the OS keyring, or the sealed fallback file
+pub struct SecretStore {
+    backend: Backend,
+}
+
+impl SecretStore {
+    /// The file at `path`, sealed under a key derived from `identity`
+    pub fn new(path: PathBuf, identity: Vec<u8>) -> Self {
+        Self { backend: Backend::File { path, identity } }
+    }
+
+    /// The OS keyring, when one answers
+    pub fn keyring() -> Result<Self, SecretError> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+        match entry.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(Self { backend: Backend::Keyring(entry) }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The keyring, or the sealed file of the data directory without one
+    pub fn open_default() -> Result<Self, SecretError> {
+        let dir = get_data_dir().map_err(|e| SecretError::DataDir(e.to_string()))?;
+        match Self::keyring() {
+            Ok(store) => {
+                if let Err(e) = store.import_fallback_file(&dir) {
+                    tracing::warn!("Secrets file not moved to the keyring: {}", e);
+                }
+                Ok(store)
+            }
+            Err(e) => {
+                tracing::warn!("OS keyring unavailable, using the encrypted secrets file: {}", e);
+                let store = Self::new(dir.join(SECRETS_FILE), machine_identity()?);
+                store.import_legacy_file(&dir)?;
+                Ok(store)
+            }
+        }
+    }
+
+    /// Move the secrets of a fallback file, written while no keyring answered,
+    /// into this store
+    fn import_fallback_file(&self, dir: &Path) -> Result<(), SecretError> {
+        let (path, key_path) = (dir.join(SECRETS_FILE), dir.join(LEGACY_KEY_FILE));
+        if !path.exists() {
+            return Ok(());
+        }
+        let fallback = if key_path.exists() {
+            load_legacy(&path, &key_path)?
+        } else {
+            Self::new(path.clone(), machine_identity()?).load()?
+        };
+        let mut secrets = self.load()?;
+        for (name, value) in fallback {
+            secrets.entry(name).or_insert(value);
+        }
+        self.save(&secrets)?;
+        std::fs::remove_file(&path)?;
+        if key_path.exists() {
+            std::fs::remove_file(&key_path)?;
+        }
+        tracing::info!("Secrets moved from {} to the OS keyring", path.display());
+        Ok(())
+    }
+
+    /// Reseal a version 2 file of `dir` under the derived key and delete the
+    /// random key that was stored next to it
+    fn import_legacy_file(&self, dir: &Path) -> Result<(), SecretError> {
+        let (path, key_path) = (dir.join(SECRETS_FILE), dir.join(LEGACY_KEY_FILE));
+        if !key_path.exists() {
+            return Ok(());
+        }
+        if path.exists() {
+            self.save(&load_legacy(&path, &key_path)?)?;
+        }
+        std::fs::remove_file(&key_path)?;
+        tracing::info!("Secrets file {} resealed under the machine key", path.display());
+        Ok(())
+    }
+
+    /// All secrets; none when nothing was saved yet
+    pub fn load(&self) -> Result<BTreeMap<String, String>, SecretError> {
+        match &self.backend {
+            Backend::Keyring(entry) => match entry.get_password() {
+                Ok(json) => Ok(serde_json::from_str(&json)?),
+                Err(keyring::Error::NoEntry) => Ok(BTreeMap::new()),
+                Err(e) => Err(e.into()),
+            },
+            Backend::File { path, identity } => {
+                if !path.exists() {
+                    return Ok(BTreeMap::new());
+                }
+                let sealed: SealedVault = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+                serde_json::from_slice(&unseal(identity, &sealed)?).map_err(|_| SecretError::Undecryptable)
+            }
+        }
+    }
+
+    /// Replace the stored secrets with `secrets`
+    pub fn save(&self, secrets: &BTreeMap<String, String>) -> Result<(), SecretError> {
+        match &self.backend {
+            Backend::Keyring(entry) => entry.set_password(&serde_json::to_string(secrets)?)?,
+            Backend::File { path, identity } => {
+                let sealed = seal(identity, &serde_json::to_vec(secrets)?)?;
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let tmp = path.with_extension("json.tmp");
+                write_private(&tmp, serde_json::to_string_pretty(&sealed)?.as_bytes())?;
+                std::fs::rename(&tmp, path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add or replace `name`, returning the updated secrets
+    pub fn set(&self, name: &str, value: &str) -> Result<BTreeMap<String, String>, SecretError> {
+        if !is_valid_name(name) {
+            return Err(SecretError::InvalidName(name.to_string()));
+        }
+        let mut secrets = self.load()?;
+        secrets.insert(name.to_string(), value.to_string());
+        self.save(&secrets)?;
+        Ok(secrets)
+    }
+
+    /// Delete `name`, returning the remaining secrets
+    pub fn remove(&self, name: &str) -> Result<BTreeMap<String, String>, SecretError> {
+        let mut secrets = self.load()?;
+        if secrets.remove(name).is_some() {
+            self.save(&secrets)?;
+        }
+        Ok(secrets)
+    }
+}
+
+/// Write `contents` to a new file readable by the owner only
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let _ = std::fs::remove_file(path);
+    let mut file = std::fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+/// Secrets in use, and the substitution and scrubbing done with them
+#[derive(Debug, Clone, Default)]
+pub struct Vault {
+    secrets: BTreeMap<String, String>,
+}
+
+impl Vault {
+    pub fn new(secrets: BTreeMap<String, String>) -> Self {
+        Self { secrets }
+    }
+
+    /// Names of the secrets
+    pub fn names(&self) -> Vec<String> {
+        self.secrets.keys().cloned().collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.secrets.get(name).cloned()
+    }
+
+    /// `text` with each `${secret:NAME}` replaced by the secret's value
+    pub fn substitute(&self, text: &str) -> Result<String, SecretError> {
+        if !text.contains("${secret:") {
+            return Ok(text.to_string());
+        }
+        if let Some(unknown) = PLACEHOLDER.captures_iter(text).find(|c| !self.secrets.contains_key(&c[1])) {
+            return Err(SecretError::Unknown(unknown[1].to_string()));
+        }
+        Ok(PLACEHOLDER.replace_all(text, |c: &regex::Captures| self.secrets[&c[1]].clone()).into_owned())
+    }
+
+    /// `value` with placeholders substituted in every string it holds
+    pub fn substitute_value(&self, value: &Value) -> Result<Value, SecretError> {
+        Ok(match value {
+            Value::String(text) => Value::String(self.substitute(text)?),
+            Value::Array(items) => Value::Array(items.iter().map(|item| self.substitute_value(item)).collect::<Result<_, _>>()?),
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, field)| Ok((key.clone(), self.substitute_value(field)?)))
+                    .collect::<Result<_, SecretError>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+
+    /// `text` with every secret value replaced by `***`
+    pub fn scrub(&self, text: &str) -> String {
+        let mut values: Vec<&String> = self.secrets.values().filter(|v| v.len() >= MIN_SCRUBBED_LEN).collect();
+        // Longest first, so a secret containing another is masked whole
+        values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+        let mut text = text.to_string();
+        for value in values {
+            if text.contains(value.as_str()) {
+                text = text.replace(value.as_str(), SCRUBBED);
+            }
+        }
+        text
+    }
+
+    /// Scrub every string `value` holds
+    pub fn scrub_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.scrub(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.scrub_value(item)),
+            Value::Object(fields) => fields.values_mut().for_each(|field| self.scrub_value(field)),
+            _ => {}
+        }
+    }
+}
+
+fn active() -> RwLockReadGuard<'static, Vault> {
+    ACTIVE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Make `secrets` the ones substituted and scrubbed
+pub fn install(secrets: BTreeMap<String, String>) {
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = Vault::new(secrets);
+}
+
+/// Load the vault of the data directory into use
+pub fn install_default() {
+    match SecretStore::open_default().and_then(|store| store.load()) {
+        Ok(secrets) => install(secrets),
+        Err(e) => tracing::warn!("Secrets not loaded: {}", e),
+    }
+}
+
+/// Names of the secrets in use
+pub fn names() -> Vec<String> {
+    active().names()
+}
+
+/// Value of a secret in use, for tools that authenticate on their own
+pub fn get(name: &str) -> Option<String> {
+    active().get(name)
+}
+
+/// `text` with each `${secret:NAME}` replaced by the value of a secret in use
+pub fn substitute(text: &str) -> Result<String, SecretError> {
+    active().substitute(text)
+}
+
+/// `value` with placeholders substituted in every string it holds
+pub fn substitute_value(value: &Value) -> Result<Value, SecretError> {
+    active().substitute_value(value)
+}
+
+/// `text` with every secret value in use replaced by `***`
+pub fn scrub(text: &str) -> String {
+    active().scrub(text)
+}
+
+/// Scrub every string `value` holds
+pub fn scrub_value(value: &mut Value) {
+    active().scrub_value(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_round_trip_and_tamper_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SECRETS_FILE);
+        let store = SecretStore::new(path.clone(), b"machine-a\0alice".to_vec());
+        assert!(store.load().unwrap().is_empty());
+
+        store.set("GITHUB_TOKEN", "ghp_example_value").unwrap();
+        let secrets = store.set("EXA_KEY", "exa-123456").unwrap();
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(store.load().unwrap()["GITHUB_TOKEN"], "ghp_example_value");
+        // Encrypted at rest
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("ghp_example_value"));
+
+        // A copy opened on another machine or account has another key
+        assert!(matches!(
+            SecretStore::new(path.clone(), b"machine-b\0alice".to_vec()).load(),
+            Err(SecretError::Undecryptable)
+        ));
+        assert!(matches!(store.set("bad name", "x"), Err(SecretError::InvalidName(_))));
+        assert_eq!(store.remove("EXA_KEY").unwrap().len(), 1);
+
+        // A flipped ciphertext byte fails authentication
+        let mut sealed: SealedVault = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let flipped = if sealed.ciphertext.starts_with('0') { "1" } else { "0" };
+        sealed.ciphertext.replace_range(0..1, flipped);
+        std::fs::write(&path, serde_json::to_string(&sealed).unwrap()).unwrap();
+        assert!(matches!(store.load(), Err(SecretError::Undecryptable)));
+    }
+
+    #[test]
+    fn test_key_is_derived_and_legacy_files_are_resealed() {
+        let salt = [1u8; 16];
+        assert_eq!(derive_key(b"id", &salt), derive_key(b"id", &salt));
+        assert_ne!(derive_key(b"id", &salt), derive_key(b"other", &salt));
+        assert_ne!(derive_key(b"id", &salt), derive_key(b"id", &[2u8; 16]));
+
+        // A version 2 file and its random key, as written before
+        let dir = tempfile::tempdir().unwrap();
+        let key = [9u8; 32];
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, br#"{"EXA_KEY":"exa-123456"}"#.as_slice()).unwrap();
+        let legacy = SealedVault {
+            version: LEGACY_VAULT_VERSION,
+            salt: String::new(),
+            nonce: to_hex(&nonce),
+            ciphertext: to_hex(&ciphertext),
+        };
+        std::fs::write(dir.path().join(SECRETS_FILE), serde_json::to_string(&legacy).unwrap()).unwrap();
+        std::fs::write(dir.path().join(LEGACY_KEY_FILE), to_hex(&key)).unwrap();
+
+        let store = SecretStore::new(dir.path().join(SECRETS_FILE), b"machine-a\0alice".to_vec());
+        store.import_legacy_file(dir.path()).unwrap();
+        assert!(!dir.path().join(LEGACY_KEY_FILE).exists());
+        assert_eq!(store.load().unwrap()["EXA_KEY"], "exa-123456");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path().join(SECRETS_FILE)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_substitute_and_scrub() {
+        let vault = Vault::new(BTreeMap::from([("API_KEY".to_string(), "sk-live-abcdef".to_string())]));
+
+        let params = serde_json::json!({
+            "url": "https://api.example.com",
+            "headers": { "Authorization": "Bearer ${secret:API_KEY}" },
+            "tags": ["${secret:API_KEY}", 3]
+        });
+        let resolved = vault.substitute_value(&params).unwrap();
+        assert_eq!(resolved["headers"]["Authorization"], "Bearer sk-live-abcdef");
+        assert_eq!(resolved["tags"][0], "sk-live-abcdef");
+        assert!(matches!(vault.substitute("${secret:MISSING}"), Err(SecretError::Unknown(name)) if name == "MISSING"));
+
+        assert_eq!(vault.scrub("sent sk-live-abcdef twice: sk-live-abcdef"), "sent *** twice: ***");
+        let mut data = serde_json::json!({ "echo": { "auth": "Bearer sk-live-abcdef" } });
+        vault.scrub_value(&mut data);
+        assert_eq!(data["echo"]["auth"], "Bearer ***");
+    }
+}
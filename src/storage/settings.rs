@@ -2,8 +2,12 @@
 //!
 //! Manages persistence of user preferences and application settings.
 
+use crate::inference::chat_template::ChatTemplate;
+use crate::storage::conversations::RetentionPolicy;
+use crate::storage::templates::ConversationTemplate;
 use crate::storage::{get_data_dir, StorageError};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -16,10 +20,23 @@ pub struct AppSettings {
     pub top_p: f32,
     /// Top-k sampling parameter
     pub top_k: u32,
+    /// Frequency penalty (-2.0 - 2.0, 0 = off)
+    #[serde(default)]
+    pub frequency_penalty: f32,
+    /// Presence penalty (-2.0 - 2.0, 0 = off)
+    #[serde(default)]
+    pub presence_penalty: f32,
+    /// Sequences that end a reply when the model writes them
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
     /// Maximum number of tokens to generate
     pub max_tokens: u32,
     /// Context window size
     pub context_size: u32,
+    /// Let the engine size the context to the conversation instead of pinning
+    /// `context_size` (settings saved before this option stay pinned)
+    #[serde(default)]
+    pub auto_context: bool,
     /// System prompt prepended to conversations
     pub system_prompt: String,
     /// Number of GPU layers to offload (0 = CPU only)
@@ -39,9 +56,13 @@ pub struct AppSettings {
     /// Auto-load last model on startup
     #[serde(default = "default_auto_load")]
     pub auto_load_model: bool,
-    /// UI and agent language: "fr" or "en"
+    /// UI language: "fr" or "en"
     #[serde(default = "default_language")]
     pub language: String,
+    /// Language of what the agent puts in the model's context (stock system
+    /// prompt, tool results, hints): "auto" follows the conversation, or "fr" / "en"
+    #[serde(default = "default_agent_language")]
+    pub agent_language: String,
     /// Auto-approve ALL tool calls without asking (dangerous but convenient)
     #[serde(default)]
     pub auto_approve_all_tools: bool,
@@ -54,16 +75,604 @@ pub struct AppSettings {
     /// OpenRouter model to use for ai_consult tool (default: openrouter/pony-alpha)
     #[serde(default = "default_openrouter_model")]
     pub openrouter_model: String,
+    /// Saved conversation templates offered in the "New chat" flow
+    #[serde(default)]
+    pub conversation_templates: Vec<ConversationTemplate>,
+    /// Which environment details are injected into the agent system prompt
+    #[serde(default)]
+    pub environment_context: EnvironmentContextSettings,
+    /// Skills read failure the user dismissed ("<path>: <error>"), shown again if it changes
+    #[serde(default)]
+    pub dismissed_skills_warning: Option<String>,
+    /// Reduced motion: "auto" (follow the OS preference), "on" or "off"
+    #[serde(default = "default_reduced_motion")]
+    pub reduced_motion: String,
+    /// Developer mode: record every agent run (prompts and responses) for replay
+    #[serde(default)]
+    pub developer_mode: bool,
+    /// First-run onboarding finished or skipped; settings files written before
+    /// onboarding existed count as completed
+    #[serde(default = "default_onboarding_completed")]
+    pub onboarding_completed: bool,
+    /// Working directory for the agent, applied on startup (None = launch directory)
+    #[serde(default)]
+    pub workspace_directory: Option<PathBuf>,
+    /// Directory file tools are confined to (None = the workspace directory)
+    #[serde(default)]
+    pub workspace_root: Option<PathBuf>,
+    /// Refuse file tool paths outside `workspace_root`
+    #[serde(default)]
+    pub restrict_tools_to_workspace: bool,
+    /// Let http_request reach localhost and private network addresses
+    #[serde(default)]
+    pub allow_private_network_requests: bool,
+    /// What gets redacted on export and before cross-conversation indexing
+    #[serde(default)]
+    pub redaction: RedactionSettings,
+    /// How tool results are written back into the conversation for the model
+    #[serde(default)]
+    pub tool_results: ToolResultSettings,
+    /// Saved per-model settings, by model file name
+    #[serde(default)]
+    pub model_profiles: BTreeMap<String, ModelProfile>,
+    /// After loading, run a short generation to check for thinking when the
+    /// metadata gives no hint
+    #[serde(default)]
+    pub probe_reasoning: bool,
+    /// Automatic cleanup of old conversations, run at startup and once a day
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    /// Constrain replies to free text or a well-formed JSON tool call
+    /// (a grammar built from the registered tools) when tools are enabled
+    #[serde(default)]
+    pub strict_tool_calls: bool,
+    /// How eagerly a streaming reply is stopped as corrupted
+    #[serde(default)]
+    pub garbage_detection: GarbageSensitivity,
+    /// Small model of the same family loaded with the main one for
+    /// speculative decoding
+    #[serde(default)]
+    pub draft_model_path: Option<String>,
+    /// OpenAI-compatible server used instead of a local model
+    #[serde(default)]
+    pub remote_backend: RemoteBackendSettings,
+    /// OpenAI-compatible server exposing the loaded model to other apps
+    #[serde(default)]
+    pub api_server: ApiServerSettings,
+    /// Per-tool call limits set by the user, replacing the registry defaults
+    #[serde(default)]
+    pub tool_limits: BTreeMap<String, ToolLimit>,
+    /// What the `command` tool may run
+    #[serde(default)]
+    pub command_policy: CommandPolicy,
+    /// Language servers of the LSP tools
+    #[serde(default = "default_language_servers")]
+    pub language_servers: Vec<LanguageServerConfig>,
+    /// Embedding model and folders of the document index
+    #[serde(default)]
+    pub document_index: DocumentIndexSettings,
+    /// Recall of long-term memory notes into the system prompt
+    #[serde(default)]
+    pub memory: MemorySettings,
+    /// Whisper model and language of voice input
+    #[serde(default)]
+    pub voice: VoiceSettings,
+}
+
+/// Privacy toggles for the environment block of the system prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentContextSettings {
+    /// OS name/version and path separator
+    pub include_os: bool,
+    /// Shell used by command tools
+    pub include_shell: bool,
+    /// Current date/time with timezone
+    pub include_datetime: bool,
+    /// Workspace root path
+    pub include_workspace: bool,
+    /// Whether the workspace is a git repo, and its branch
+    pub include_git: bool,
+    /// Loaded model name
+    pub include_model: bool,
+}
+
+impl Default for EnvironmentContextSettings {
+    fn default() -> Self {
+        Self {
+            include_os: true,
+            include_shell: true,
+            include_datetime: true,
+            include_workspace: true,
+            include_git: true,
+            include_model: true,
+        }
+    }
+}
+
+/// Redaction rules (see `storage::redaction`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionSettings {
+    /// API keys and tokens (OpenAI, GitHub, AWS, bearer headers, `key=value`)
+    pub redact_api_keys: bool,
+    pub redact_emails: bool,
+    /// IPv4 and full IPv6 addresses
+    pub redact_ip_addresses: bool,
+    /// Extra regexes; a capture group limits the replacement to the group
+    pub custom_patterns: Vec<String>,
+    /// Always redact content entering the conversation search index and memory
+    pub redact_indexed_content: bool,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            redact_api_keys: true,
+            redact_emails: true,
+            redact_ip_addresses: true,
+            custom_patterns: Vec::new(),
+            redact_indexed_content: true,
+        }
+    }
+}
+
+/// Shape of a tool result as injected into the prompt
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolResultVerbosity {
+    /// Complete result: message plus all data
+    #[default]
+    Full,
+    /// Message and key fields only, capped at 1000 characters
+    Compact,
+    /// `data` alone, pretty-printed, with long strings truncated
+    Json,
+}
+
+impl ToolResultVerbosity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Compact => "compact",
+            Self::Json => "json",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "full" => Some(Self::Full),
+            "compact" => Some(Self::Compact),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Role tool results are injected with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolResultRole {
+    #[default]
+    System,
+    /// Dedicated tool role, for chat templates that have one
+    Tool,
+}
+
+/// What observation masking leaves of an old tool result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskingStyle {
+    /// One-line note that the result was omitted
+    Placeholder,
+    /// Tool name, key facts from its data (paths, counts, exit codes) and the start of its message
+    #[default]
+    Digest,
+}
+
+impl MaskingStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Placeholder => "placeholder",
+            Self::Digest => "digest",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "placeholder" => Some(Self::Placeholder),
+            "digest" => Some(Self::Digest),
+            _ => None,
+        }
+    }
+}
+
+/// How eagerly a streaming reply is stopped as corrupted (fake tool
+/// output, text glued without spaces, a repetition loop)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GarbageSensitivity {
+    /// Never stop a reply
+    Off,
+    /// Only blatant cases
+    #[default]
+    Low,
+    /// Shorter loops and less glued text are enough
+    Strict,
+}
+
+impl GarbageSensitivity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Low => "low",
+            Self::Strict => "strict",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "off" => Some(Self::Off),
+            "low" => Some(Self::Low),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+/// Tool result injection settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolResultSettings {
+    pub verbosity: ToolResultVerbosity,
+    pub role: ToolResultRole,
+    /// Per-tool verbosity, by tool name
+    pub tool_overrides: BTreeMap<String, ToolResultVerbosity>,
+    /// How old results are shrunk by context compression
+    pub masking: MaskingStyle,
+}
+
+impl ToolResultSettings {
+    /// Verbosity for a tool, its override first
+    pub fn verbosity_for(&self, tool: &str) -> ToolResultVerbosity {
+        self.tool_overrides.get(tool).copied().unwrap_or(self.verbosity)
+    }
+}
+
+/// Call limits of one tool; `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolLimit {
+    pub max_calls_per_minute: Option<u32>,
+    pub max_concurrent: Option<u32>,
+}
+
+/// What the `command` tool does with a command outside its allowlist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandPolicyMode {
+    /// Refuse it
+    Strict,
+    /// Ask the user through the permission manager
+    #[default]
+    Prompt,
+    /// Run it; denied patterns still apply
+    AllowAll,
+}
+
+impl CommandPolicyMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Prompt => "prompt",
+            Self::AllowAll => "allow_all",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "strict" => Some(Self::Strict),
+            "prompt" => Some(Self::Prompt),
+            "allow_all" => Some(Self::AllowAll),
+            _ => None,
+        }
+    }
+}
+
+/// Arguments an allow-listed binary may take
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArgumentRule {
+    /// When not empty, the arguments must start with one of these words;
+    /// `""` stands for no arguments at all
+    pub allowed_prefixes: Vec<String>,
+    /// Arguments that are never accepted, e.g. `-delete` for `find`
+    pub denied: Vec<String>,
+}
+
+/// Rules of the `command` tool (see `agent::tools::command_policy`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandPolicy {
+    pub mode: CommandPolicyMode,
+    /// Binaries run without asking, by file name
+    pub allowed_binaries: Vec<String>,
+    /// Substrings refusing a command whatever the mode
+    pub denied_patterns: Vec<String>,
+    /// Argument restrictions of allow-listed binaries, by binary
+    pub argument_rules: BTreeMap<String, ArgumentRule>,
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let rule = |allowed: &[&str], denied: &[&str]| ArgumentRule {
+            allowed_prefixes: strings(allowed),
+            denied: strings(denied),
+        };
+        let mut argument_rules = BTreeMap::new();
+        argument_rules.insert(
+            "git".to_string(),
+            rule(
+                &["status", "log", "diff", "show", "branch", "rev-parse", "ls-files", "blame", "remote -v", "--version"],
+                &[],
+            ),
+        );
+        // Build and script runners execute project code (build scripts,
+        // `npm run` scripts), so only the test and check entry points skip
+        // the prompt
+        argument_rules.insert("cargo".to_string(), rule(&["test", "check", "--version"], &[]));
+        argument_rules.insert("npm".to_string(), rule(&["test", "--version"], &[]));
+        argument_rules.insert("node".to_string(), rule(&["--version"], &[]));
+        argument_rules.insert("go".to_string(), rule(&["test", "vet", "version"], &[]));
+        for python in ["python", "python3"] {
+            argument_rules.insert(
+                python.to_string(),
+                rule(&["-m pytest", "-m unittest", "--version", "-V"], &[]),
+            );
+        }
+        argument_rules.insert("find".to_string(), rule(&[], &["-delete", "-exec", "-execdir", "-ok", "-okdir"]));
+        // Bare `env` lists variables; with arguments it runs any command
+        argument_rules.insert("env".to_string(), rule(&[""], &[]));
+
+        Self {
+            mode: CommandPolicyMode::default(),
+            allowed_binaries: strings(&[
+                "ls", "dir", "cat", "type", "echo", "pwd", "cd", "whoami", "date", "wc", "head", "tail", "find",
+                "grep", "rg", "tree", "which", "where", "env", "mkdir", "sort", "uniq", "diff", "file", "stat",
+                "du", "df", "git", "cargo", "rustc", "npm", "node", "python", "python3", "pytest", "go",
+            ]),
+            denied_patterns: strings(&[
+                "rm -rf /",
+                "rm -rf ~",
+                "rm -rf *",
+                "--no-preserve-root",
+                "> /dev/sd",
+                "of=/dev/",
+                "mkfs",
+                ":(){",
+                "chmod -R 777 /",
+            ]),
+            argument_rules,
+        }
+    }
+}
+
+/// Language server the LSP tools start for files with one of `extensions`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageServerConfig {
+    /// LSP language id, e.g. `rust` or `typescript`
+    pub language: String,
+    /// File extensions without the dot
+    pub extensions: Vec<String>,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+pub fn default_language_servers() -> Vec<LanguageServerConfig> {
+    let server = |language: &str, extensions: &[&str], command: &str, args: &[&str]| LanguageServerConfig {
+        language: language.to_string(),
+        extensions: extensions.iter().map(|e| e.to_string()).collect(),
+        command: command.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+    };
+    vec![
+        server("rust", &["rs"], "rust-analyzer", &[]),
+        server("python", &["py", "pyi"], "pyright-langserver", &["--stdio"]),
+        server(
+            "typescript",
+            &["ts", "tsx", "js", "jsx", "mjs", "cjs"],
+            "typescript-language-server",
+            &["--stdio"],
+        ),
+    ]
+}
+
+/// Document index of the `index_*` tools (see `storage::index`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentIndexSettings {
+    /// GGUF embedding model, e.g. nomic-embed-text or bge-small
+    pub embedding_model_path: Option<String>,
+    /// Folders `index_build` and `index_search` use when given none
+    pub folders: Vec<PathBuf>,
+}
+
+/// Long-term memory of the `memory_*` tools (see `storage::memory`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemorySettings {
+    /// Put the notes relevant to the user's message in the system prompt
+    pub include_in_prompt: bool,
+    /// Most notes recalled into the prompt
+    pub prompt_notes: usize,
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            include_in_prompt: true,
+            prompt_notes: 5,
+        }
+    }
+}
+
+/// Voice input of the chat box (see `inference::voice`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceSettings {
+    /// File name of the whisper model, one of `storage::models::WHISPER_MODELS`
+    pub model: String,
+    /// Spoken language code ("en", "fr"...), or "auto" to detect it
+    pub language: String,
+}
+
+impl Default for VoiceSettings {
+    fn default() -> Self {
+        Self {
+            model: "ggml-base.bin".to_string(),
+            language: "auto".to_string(),
+        }
+    }
+}
+
+/// OpenAI-compatible server (Ollama, llama-server...) to generate with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteBackendSettings {
+    /// Server root or its `/v1` path, e.g. `http://localhost:11434/v1`
+    pub base_url: String,
+    /// Sent as a bearer token when not empty
+    pub api_key: String,
+    /// Model name the server knows it by
+    pub model: String,
+}
+
+/// Local OpenAI-compatible API server (see `app::server`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiServerSettings {
+    pub enabled: bool,
+    /// `host:port` to listen on; keep 127.0.0.1 to stay local to this machine
+    pub bind_address: String,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self { enabled: false, bind_address: "127.0.0.1:8765".to_string() }
+    }
+}
+
+/// How the model writes a tool call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallFormat {
+    /// `{"tool": ..., "params": {...}}` in a ```json block
+    #[default]
+    Json,
+    /// `<tool_call>{"name": ..., "arguments": {...}}</tool_call>` (Qwen, Hermes)
+    XmlTag,
+    /// Bare `{"name": ..., "arguments": {...}}` (Llama 3.1, OpenAI-style)
+    FunctionCall,
+}
+
+impl ToolCallFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::XmlTag => "xml_tag",
+            Self::FunctionCall => "function_call",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "json" => Some(Self::Json),
+            "xml_tag" => Some(Self::XmlTag),
+            "function_call" => Some(Self::FunctionCall),
+            _ => None,
+        }
+    }
+
+    /// Format the model was trained on, going by its `tokenizer.chat_template`
+    pub fn from_chat_template(chat_template: Option<&str>) -> Self {
+        match chat_template {
+            Some(t) if t.contains("<tool_call>") => Self::XmlTag,
+            Some(t) if t.contains("<|python_tag|>") || t.contains("\"parameters\"") => Self::FunctionCall,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Settings saved for one model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelProfile {
+    /// Reasoning model override (None = use what was detected at load)
+    pub reasoning: Option<bool>,
+    /// Tool call format override (None = what its chat template prefers)
+    pub tool_call_format: Option<ToolCallFormat>,
+    /// Chat template override (None = the family its metadata names)
+    pub chat_template: Option<ChatTemplate>,
 }
 
 fn default_auto_load() -> bool {
     true
 }
 
+/// Language of the interface, and of what the agent writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Fr,
+    En,
+}
+
+impl Locale {
+    /// Parse a settings/metadata language code ("fr", "en")
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "fr" => Some(Locale::Fr),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Fr => "fr",
+            Locale::En => "en",
+        }
+    }
+
+    /// Name of the language, in English (used in prompts)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::Fr => "French",
+            Locale::En => "English",
+        }
+    }
+}
+
 fn default_language() -> String {
     "fr".to_string()
 }
 
+fn default_agent_language() -> String {
+    "auto".to_string()
+}
+
+/// Upper bound for auto context sizing
+pub const AUTO_CONTEXT_MAX: u32 = 131072;
+
+fn default_onboarding_completed() -> bool {
+    true
+}
+
+fn default_reduced_motion() -> String {
+    "auto".to_string()
+}
+
 fn default_openrouter_model() -> String {
     "openrouter/pony-alpha".to_string()
 }
@@ -184,8 +793,12 @@ impl Default for AppSettings {
             temperature: 0.7,
             top_p: 0.9,
             top_k: 40,
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
+            stop_sequences: Vec::new(),
             max_tokens: 4096,    // 4K output - OK with 16K context
             context_size: 16384, // 16K context - user confirmed 36 tok/s in LM Studio with 16K on 8GB VRAM
+            auto_context: true,
             system_prompt: default_system_prompt(),
             gpu_layers: 99, // Offload all layers to GPU by default
             models_directory: get_data_dir()
@@ -198,15 +811,86 @@ impl Default for AppSettings {
             last_model_path: None,
             auto_load_model: true,
             language: "fr".to_string(),
+            agent_language: default_agent_language(),
             auto_approve_all_tools: false,
             tool_allowlist: Vec::new(),
             disabled_mcp_servers: Vec::new(),
             openrouter_model: default_openrouter_model(),
+            conversation_templates: Vec::new(),
+            environment_context: EnvironmentContextSettings::default(),
+            dismissed_skills_warning: None,
+            reduced_motion: default_reduced_motion(),
+            developer_mode: false,
+            onboarding_completed: false,
+            workspace_directory: None,
+            workspace_root: None,
+            restrict_tools_to_workspace: false,
+            allow_private_network_requests: false,
+            redaction: RedactionSettings::default(),
+            tool_results: ToolResultSettings::default(),
+            model_profiles: BTreeMap::new(),
+            probe_reasoning: false,
+            retention: RetentionPolicy::default(),
+            strict_tool_calls: false,
+            garbage_detection: GarbageSensitivity::default(),
+            draft_model_path: None,
+            remote_backend: RemoteBackendSettings::default(),
+            api_server: ApiServerSettings::default(),
+            tool_limits: BTreeMap::new(),
+            command_policy: CommandPolicy::default(),
+            language_servers: default_language_servers(),
+            document_index: DocumentIndexSettings::default(),
+            memory: MemorySettings::default(),
+            voice: VoiceSettings::default(),
         }
     }
 }
 
 impl AppSettings {
+    /// Interface language
+    pub fn locale(&self) -> Locale {
+        Locale::from_code(&self.language).unwrap_or(Locale::Fr)
+    }
+
+    /// Language fixed for the agent, none to follow the conversation
+    pub fn agent_locale(&self) -> Option<Locale> {
+        Locale::from_code(&self.agent_language)
+    }
+
+    /// Context cap passed to the engine: the pinned size, or the largest
+    /// standard size in auto mode (the engine lowers it to what fits)
+    pub fn context_cap(&self) -> u32 {
+        if self.auto_context {
+            AUTO_CONTEXT_MAX
+        } else {
+            self.context_size
+        }
+    }
+
+    /// Profile key of a model: its file name, so profiles survive moving the models folder
+    pub fn model_profile_key(model_path: &str) -> String {
+        std::path::Path::new(model_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| model_path.to_string())
+    }
+
+    /// Directory file tools are confined to, when the restriction is on
+    pub fn sandbox_root(&self) -> Option<PathBuf> {
+        if !self.restrict_tools_to_workspace {
+            return None;
+        }
+        self.workspace_root
+            .clone()
+            .or_else(|| self.workspace_directory.clone())
+            .or_else(|| std::env::current_dir().ok())
+    }
+
+    /// Saved profile of a model, if any
+    pub fn model_profile(&self, model_path: &str) -> Option<&ModelProfile> {
+        self.model_profiles.get(&Self::model_profile_key(model_path))
+    }
+
     /// Validate settings values
     ///
     /// Ensures all parameters are within acceptable ranges.
@@ -214,6 +898,9 @@ impl AppSettings {
     pub fn validate(&mut self) {
         self.temperature = self.temperature.clamp(0.0, 2.0);
         self.top_p = self.top_p.clamp(0.0, 1.0);
+        self.frequency_penalty = self.frequency_penalty.clamp(-2.0, 2.0);
+        self.presence_penalty = self.presence_penalty.clamp(-2.0, 2.0);
+        self.stop_sequences.retain(|stop| !stop.is_empty());
 
         if self.top_k == 0 {
             self.top_k = 40;
@@ -263,6 +950,14 @@ impl AppSettings {
         if self.language != "fr" && self.language != "en" {
             self.language = "fr".to_string();
         }
+
+        if !["auto", "fr", "en"].contains(&self.agent_language.as_str()) {
+            self.agent_language = default_agent_language();
+        }
+
+        if !["auto", "on", "off"].contains(&self.reduced_motion.as_str()) {
+            self.reduced_motion = default_reduced_motion();
+        }
     }
 }
 
@@ -349,6 +1044,36 @@ pub fn save_settings(settings: &AppSettings) -> Result<(), StorageError> {
     Ok(())
 }
 
+/// Export settings (including conversation templates) to a bundle file
+pub fn export_settings_bundle(settings: &AppSettings, path: &std::path::Path) -> Result<(), StorageError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Bundles get shared: the server's API key stays on this machine
+    let mut settings = settings.clone();
+    settings.remote_backend.api_key.clear();
+    let json = serde_json::to_string_pretty(&settings)?;
+    fs::write(path, json)?;
+    tracing::info!("Exported settings bundle to {}", path.display());
+    Ok(())
+}
+
+/// Import settings from a bundle file
+///
+/// Machine-specific values (models directory, last model) and the remote
+/// server's API key are kept from `current`.
+pub fn import_settings_bundle(current: &AppSettings, path: &std::path::Path) -> Result<AppSettings, StorageError> {
+    let json = fs::read_to_string(path)?;
+    let mut imported: AppSettings = serde_json::from_str(&json)?;
+    imported.models_directory = current.models_directory.clone();
+    imported.last_model_path = current.last_model_path.clone();
+    imported.remote_backend.api_key = current.remote_backend.api_key.clone();
+    imported.system_prompt = default_system_prompt_for_lang(&imported.language);
+    imported.validate();
+    tracing::info!("Imported settings bundle from {}", path.display());
+    Ok(imported)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,6 +1088,31 @@ mod tests {
         assert_eq!(settings.font_size, "medium");
     }
 
+    #[test]
+    fn test_model_profile_by_file_name() {
+        let mut settings = AppSettings::default();
+        assert!(settings.model_profile("/models/qwq-32b.gguf").is_none());
+
+        settings.model_profiles.insert(
+            AppSettings::model_profile_key("/models/qwq-32b.gguf"),
+            ModelProfile { reasoning: Some(false), ..Default::default() },
+        );
+        // Same file in another folder shares the profile
+        let profile = settings.model_profile("/elsewhere/qwq-32b.gguf").unwrap();
+        assert_eq!(profile.reasoning, Some(false));
+    }
+
+    #[test]
+    fn test_tool_call_format_from_chat_template() {
+        let qwen = "For each function call, return a json object with function name and arguments within <tool_call></tool_call> XML tags:\n<tool_call>\n{\"name\": <function-name>, \"arguments\": <args-json-object>}\n</tool_call>";
+        let llama = "Respond in the format {\"name\": function name, \"parameters\": dictionary of argument name and its value}. {%- if builtin_tools is defined %}<|python_tag|>";
+        assert_eq!(ToolCallFormat::from_chat_template(Some(qwen)), ToolCallFormat::XmlTag);
+        assert_eq!(ToolCallFormat::from_chat_template(Some(llama)), ToolCallFormat::FunctionCall);
+        assert_eq!(ToolCallFormat::from_chat_template(Some("{{ bos_token }}[INST] {{ message }} [/INST]")), ToolCallFormat::Json);
+        assert_eq!(ToolCallFormat::from_chat_template(None), ToolCallFormat::Json);
+        assert_eq!(ToolCallFormat::parse(ToolCallFormat::XmlTag.as_str()), Some(ToolCallFormat::XmlTag));
+    }
+
     #[test]
     fn test_settings_validation() {
         let mut settings = AppSettings::default();
@@ -390,6 +1140,16 @@ mod tests {
         settings.font_size = "huge".to_string();
         settings.validate();
         assert_eq!(settings.font_size, "medium");
+
+        // Test invalid reduced motion mode
+        settings.reduced_motion = "sometimes".to_string();
+        settings.validate();
+        assert_eq!(settings.reduced_motion, "auto");
+
+        // Test invalid agent language
+        settings.agent_language = "de".to_string();
+        settings.validate();
+        assert_eq!(settings.agent_language, "auto");
     }
 
     #[test]
@@ -417,4 +1177,71 @@ mod tests {
         assert_eq!(settings.temperature, loaded.temperature);
         assert_eq!(settings.theme, loaded.theme);
     }
+
+    #[test]
+    fn test_settings_bundle_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.json");
+
+        let mut settings = AppSettings::default();
+        let mut template = ConversationTemplate::new("PR review");
+        template.first_message = Some("Review the following PR: {{url}}".to_string());
+        settings.conversation_templates.push(template);
+        export_settings_bundle(&settings, &path).unwrap();
+
+        let mut current = AppSettings::default();
+        current.last_model_path = Some("/models/local.gguf".to_string());
+        let imported = import_settings_bundle(&current, &path).unwrap();
+
+        assert_eq!(imported.conversation_templates, settings.conversation_templates);
+        assert_eq!(imported.last_model_path.as_deref(), Some("/models/local.gguf"));
+    }
+
+    #[test]
+    fn test_settings_without_templates_field() {
+        let mut value = serde_json::to_value(AppSettings::default()).unwrap();
+        value.as_object_mut().unwrap().remove("conversation_templates");
+        let loaded: AppSettings = serde_json::from_value(value).unwrap();
+        assert!(loaded.conversation_templates.is_empty());
+    }
+
+    #[test]
+    fn test_onboarding_only_for_new_installs() {
+        assert!(!AppSettings::default().onboarding_completed);
+
+        // Settings saved before onboarding existed
+        let mut value = serde_json::to_value(AppSettings::default()).unwrap();
+        value.as_object_mut().unwrap().remove("onboarding_completed");
+        let loaded: AppSettings = serde_json::from_value(value).unwrap();
+        assert!(loaded.onboarding_completed);
+    }
+
+    #[test]
+    fn test_auto_context_only_for_new_installs() {
+        let settings = AppSettings::default();
+        assert!(settings.auto_context);
+        assert_eq!(settings.context_cap(), AUTO_CONTEXT_MAX);
+
+        // Older settings keep their pinned context size
+        let mut value = serde_json::to_value(AppSettings::default()).unwrap();
+        value.as_object_mut().unwrap().remove("auto_context");
+        let loaded: AppSettings = serde_json::from_value(value).unwrap();
+        assert!(!loaded.auto_context);
+        assert_eq!(loaded.context_cap(), loaded.context_size);
+    }
+
+    #[test]
+    fn test_tool_result_overrides() {
+        let mut value = serde_json::to_value(AppSettings::default()).unwrap();
+        value["tool_results"] = serde_json::json!({
+            "verbosity": "compact",
+            "tool_overrides": { "web_fetch": "json" }
+        });
+        let loaded: AppSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.tool_results.role, ToolResultRole::System);
+        assert_eq!(loaded.tool_results.verbosity_for("web_fetch"), ToolResultVerbosity::Json);
+        assert_eq!(loaded.tool_results.verbosity_for("grep"), ToolResultVerbosity::Compact);
+        // Settings written before masking styles existed get digests
+        assert_eq!(loaded.tool_results.masking, MaskingStyle::Digest);
+    }
 }
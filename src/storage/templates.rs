@@ -0,0 +1,121 @@
+//! Conversation templates
+//!
+//! Saved starting points for conversations: a title pattern, optional
+//! system prompt / agent overrides and a pre-filled first message.
+//! Templates live in `AppSettings` so they travel with the settings bundle.
+
+use crate::storage::conversations::{Conversation, ConversationOverrides};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A saved conversation starting point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationTemplate {
+    /// Unique identifier
+    pub id: String,
+    /// Name shown in the template picker
+    pub name: String,
+    /// Title pattern for new conversations (`{{date}}` and `{{time}}` are expanded)
+    #[serde(default)]
+    pub title_pattern: String,
+    /// Overrides applied to conversations created from this template
+    #[serde(default)]
+    pub overrides: ConversationOverrides,
+    /// Scaffold for the first user message, may contain `{{placeholders}}`
+    #[serde(default)]
+    pub first_message: Option<String>,
+}
+
+impl ConversationTemplate {
+    /// Create an empty template with a fresh id
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.into(),
+            title_pattern: String::new(),
+            overrides: ConversationOverrides::default(),
+            first_message: None,
+        }
+    }
+
+    /// Instantiate a conversation from this template
+    ///
+    /// Returns the conversation (overrides applied, title expanded) and the
+    /// scaffold text to pre-fill the chat input with, if any.
+    pub fn instantiate(&self) -> (Conversation, Option<String>) {
+        let mut conversation = Conversation::new(None);
+        let title = render_title(&self.title_pattern);
+        if !title.trim().is_empty() {
+            conversation.title = title;
+        }
+        conversation.overrides = self.overrides.clone();
+
+        let scaffold = self
+            .first_message
+            .as_ref()
+            .filter(|s| !s.trim().is_empty())
+            .cloned();
+
+        (conversation, scaffold)
+    }
+}
+
+/// Expand `{{date}}` and `{{time}}` in a title pattern
+pub fn render_title(pattern: &str) -> String {
+    let now = Local::now();
+    pattern
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &now.format("%H:%M").to_string())
+        .trim()
+        .to_string()
+}
+
+/// Locate the first `{{placeholder}}` in a scaffold
+///
+/// Returns the (start, end) range in UTF-16 code units, which is what the
+/// textarea selection API expects.
+pub fn first_placeholder_range(text: &str) -> Option<(usize, usize)> {
+    let start = text.find("{{")?;
+    let end = start + text[start..].find("}}")? + 2;
+    let to_utf16 = |byte_idx: usize| text[..byte_idx].encode_utf16().count();
+    Some((to_utf16(start), to_utf16(end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instantiate_applies_overrides() {
+        let mut template = ConversationTemplate::new("PR review");
+        template.title_pattern = "Review {{date}}".to_string();
+        template.overrides.system_prompt = Some("You review code.".to_string());
+        template.overrides.enable_tools = Some(false);
+        template.first_message = Some("Review the following PR: {{url}}".to_string());
+
+        let (conv, scaffold) = template.instantiate();
+        assert!(conv.title.starts_with("Review "));
+        assert!(!conv.title.contains("{{"));
+        assert!(conv.messages.is_empty());
+        assert_eq!(conv.overrides.system_prompt.as_deref(), Some("You review code."));
+        assert_eq!(conv.overrides.enable_tools, Some(false));
+        assert_eq!(scaffold.as_deref(), Some("Review the following PR: {{url}}"));
+    }
+
+    #[test]
+    fn test_empty_title_pattern_keeps_default() {
+        let (conv, scaffold) = ConversationTemplate::new("Blank").instantiate();
+        assert_eq!(conv.title, "New Conversation");
+        assert!(scaffold.is_none());
+    }
+
+    #[test]
+    fn test_first_placeholder_range() {
+        assert_eq!(first_placeholder_range("PR: {{url}} and {{notes}}"), Some((4, 11)));
+        // UTF-16 offsets, not bytes
+        assert_eq!(first_placeholder_range("é {{x}}"), Some((2, 7)));
+        assert_eq!(first_placeholder_range("no placeholder"), None);
+        assert_eq!(first_placeholder_range("unclosed {{x"), None);
+    }
+}
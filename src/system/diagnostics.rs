@@ -0,0 +1,401 @@
+//! Startup self-check
+//!
+//! Verifies what a first run needs (writable data directory, models, GPU
+//! backend, workspace) and renders the results as a plain-text report users
+//! can paste into a bug report.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::storage::get_data_dir;
+use crate::storage::settings::AppSettings;
+use crate::system::gpu::{detect_gpu, GpuInfo};
+
+/// How deep model locations are searched (LM Studio and the Hugging Face
+/// cache nest files three to four levels down)
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Directory entries visited per location before giving up, so a huge
+/// Downloads folder cannot stall the check
+const MAX_SCAN_ENTRIES: usize = 5000;
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn tag(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+/// What a check looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckKind {
+    DataDir,
+    Models,
+    GpuBackend,
+    Workspace,
+}
+
+impl CheckKind {
+    /// Label used in the text report (the UI has its own translated titles)
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckKind::DataDir => "Data directory",
+            CheckKind::Models => "Models",
+            CheckKind::GpuBackend => "GPU backend",
+            CheckKind::Workspace => "Workspace",
+        }
+    }
+}
+
+/// Result of one check, with a human-readable detail line
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub kind: CheckKind,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// A folder holding GGUF files
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelLocation {
+    pub path: PathBuf,
+    pub model_count: usize,
+}
+
+/// Results of a full self-check
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfCheckReport {
+    pub checks: Vec<CheckResult>,
+    /// Folders with GGUF files, the configured models directory first
+    pub model_locations: Vec<ModelLocation>,
+}
+
+impl SelfCheckReport {
+    pub fn status(&self, kind: CheckKind) -> Option<CheckStatus> {
+        self.checks.iter().find(|c| c.kind == kind).map(|c| c.status)
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    /// Plain-text report for the "Copy report" button
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "ClawRS {} diagnostics\nOS: {} ({})\n\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        for check in &self.checks {
+            out.push_str(&format!("[{}] {}: {}\n", check.status.tag(), check.kind.label(), check.detail));
+        }
+        if !self.model_locations.is_empty() {
+            out.push_str("\nModel locations:\n");
+            for location in &self.model_locations {
+                out.push_str(&format!("  {} ({} models)\n", location.path.display(), location.model_count));
+            }
+        }
+        out
+    }
+}
+
+/// Run every check; does blocking IO, so call it off the UI thread
+pub fn run_self_check(settings: &AppSettings) -> SelfCheckReport {
+    let model_locations = find_model_locations(&candidate_model_dirs(&settings.models_directory));
+    let checks = vec![
+        check_data_dir(get_data_dir().ok().as_deref()),
+        check_models(&settings.models_directory, &model_locations),
+        check_gpu_backend(&detect_gpu(), compiled_gpu_backend()),
+        check_workspace(std::env::current_dir().ok().as_deref()),
+    ];
+    SelfCheckReport {
+        checks,
+        model_locations,
+    }
+}
+
+/// GPU backend this binary was built with, if any
+pub fn compiled_gpu_backend() -> Option<&'static str> {
+    if cfg!(feature = "cuda") {
+        Some("CUDA")
+    } else if cfg!(feature = "vulkan") {
+        Some("Vulkan")
+    } else if cfg!(feature = "metal") {
+        Some("Metal")
+    } else {
+        None
+    }
+}
+
+/// Folders where GGUF files usually end up: the configured directory, the
+/// app's own models folder, Downloads, and other local LLM apps' caches
+fn candidate_model_dirs(models_directory: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![models_directory.to_path_buf()];
+    if let Ok(data_dir) = get_data_dir() {
+        dirs.push(data_dir.join("models"));
+    }
+    if let Some(user_dirs) = directories::UserDirs::new() {
+        let home = user_dirs.home_dir();
+        dirs.push(home.join("models"));
+        dirs.push(home.join(".lmstudio").join("models"));
+        dirs.push(home.join(".cache").join("lm-studio").join("models"));
+        dirs.push(home.join(".cache").join("huggingface").join("hub"));
+        if let Some(downloads) = user_dirs.download_dir() {
+            dirs.push(downloads.to_path_buf());
+        }
+    }
+    dirs
+}
+
+/// Count GGUF files under each candidate, keeping those that have any
+///
+/// Files found in subfolders are reported against the folder that directly
+/// contains them, since that is what the models directory setting expects.
+fn find_model_locations(candidates: &[PathBuf]) -> Vec<ModelLocation> {
+    let mut locations: Vec<ModelLocation> = Vec::new();
+    for candidate in candidates {
+        let mut visited = 0;
+        let mut found = Vec::new();
+        collect_gguf_dirs(candidate, 0, &mut visited, &mut found);
+        for path in found {
+            if !locations.iter().any(|l| l.path == path) {
+                let model_count = count_gguf(&path);
+                locations.push(ModelLocation { path, model_count });
+            }
+        }
+    }
+    locations
+}
+
+fn collect_gguf_dirs(dir: &Path, depth: usize, visited: &mut usize, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut has_gguf = false;
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        *visited += 1;
+        if *visited > MAX_SCAN_ENTRIES {
+            break;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if is_gguf_name(&path) {
+            has_gguf = true;
+        }
+    }
+    if has_gguf && !found.iter().any(|p| p == dir) {
+        found.push(dir.to_path_buf());
+    }
+    if depth < MAX_SCAN_DEPTH {
+        for subdir in subdirs {
+            collect_gguf_dirs(&subdir, depth + 1, visited, found);
+        }
+    }
+}
+
+fn count_gguf(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().filter(|e| is_gguf_name(&e.path())).count())
+        .unwrap_or(0)
+}
+
+fn is_gguf_name(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().eq_ignore_ascii_case("gguf"))
+        .unwrap_or(false)
+}
+
+/// Create the directory if needed, then write and remove a probe file
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(".write-probe-{}", std::process::id()));
+    fs::write(&probe, b"ok")?;
+    fs::remove_file(&probe)
+}
+
+fn check_data_dir(data_dir: Option<&Path>) -> CheckResult {
+    let (status, detail) = match data_dir {
+        None => (CheckStatus::Fail, "could not determine the app data directory".to_string()),
+        Some(dir) => match probe_writable(dir) {
+            Ok(()) => (CheckStatus::Pass, format!("{} is writable", dir.display())),
+            Err(e) => (
+                CheckStatus::Fail,
+                format!("{} is not writable: {} (settings and conversations cannot be saved)", dir.display(), e),
+            ),
+        },
+    };
+    CheckResult {
+        kind: CheckKind::DataDir,
+        status,
+        detail,
+    }
+}
+
+fn check_models(models_directory: &Path, locations: &[ModelLocation]) -> CheckResult {
+    let in_configured = locations
+        .iter()
+        .find(|l| l.path == models_directory)
+        .map(|l| l.model_count)
+        .unwrap_or(0);
+    let elsewhere: usize = locations
+        .iter()
+        .filter(|l| l.path != models_directory)
+        .map(|l| l.model_count)
+        .sum();
+
+    let (status, detail) = if in_configured > 0 {
+        (
+            CheckStatus::Pass,
+            format!("{} model(s) in {}", in_configured, models_directory.display()),
+        )
+    } else if elsewhere > 0 {
+        (
+            CheckStatus::Warn,
+            format!(
+                "no models in {}, but {} found in other folders",
+                models_directory.display(),
+                elsewhere
+            ),
+        )
+    } else {
+        (
+            CheckStatus::Fail,
+            format!("no .gguf models found (models directory: {})", models_directory.display()),
+        )
+    };
+    CheckResult {
+        kind: CheckKind::Models,
+        status,
+        detail,
+    }
+}
+
+fn check_gpu_backend(gpu: &GpuInfo, backend: Option<&str>) -> CheckResult {
+    let (status, detail) = match (gpu.is_available, backend) {
+        (true, Some(backend)) => (CheckStatus::Pass, format!("{} via {}", gpu.name, backend)),
+        (true, None) => (
+            CheckStatus::Warn,
+            format!(
+                "{} detected, but this build is CPU-only (rebuild with --features cuda, vulkan or metal)",
+                gpu.name
+            ),
+        ),
+        (false, Some(backend)) => (
+            CheckStatus::Warn,
+            format!("built with {} but no GPU detected; inference will run on the CPU", backend),
+        ),
+        (false, None) => (CheckStatus::Warn, "no GPU detected; inference will run on the CPU".to_string()),
+    };
+    CheckResult {
+        kind: CheckKind::GpuBackend,
+        status,
+        detail,
+    }
+}
+
+fn check_workspace(dir: Option<&Path>) -> CheckResult {
+    let (status, detail) = match dir {
+        None => (CheckStatus::Fail, "the current directory is not accessible".to_string()),
+        Some(dir) => match fs::metadata(dir) {
+            Ok(meta) if meta.permissions().readonly() => (
+                CheckStatus::Warn,
+                format!("{} is read-only; the agent cannot create files there", dir.display()),
+            ),
+            Ok(_) => (CheckStatus::Pass, dir.display().to_string()),
+            Err(e) => (CheckStatus::Fail, format!("{}: {}", dir.display(), e)),
+        },
+    };
+    CheckResult {
+        kind: CheckKind::Workspace,
+        status,
+        detail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_model_locations_nested() {
+        let root = TempDir::new().unwrap();
+        let nested = root.path().join("publisher").join("repo");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("a.gguf"), b"").unwrap();
+        fs::write(nested.join("b.GGUF"), b"").unwrap();
+        fs::write(root.path().join("notes.txt"), b"").unwrap();
+
+        let locations = find_model_locations(&[root.path().to_path_buf(), nested.clone()]);
+        assert_eq!(locations, vec![ModelLocation { path: nested, model_count: 2 }]);
+    }
+
+    #[test]
+    fn test_check_models_statuses() {
+        let configured = PathBuf::from("/models");
+        let here = ModelLocation { path: configured.clone(), model_count: 1 };
+        let other = ModelLocation { path: PathBuf::from("/elsewhere"), model_count: 3 };
+
+        assert_eq!(check_models(&configured, &[here.clone(), other.clone()]).status, CheckStatus::Pass);
+        assert_eq!(check_models(&configured, &[other]).status, CheckStatus::Warn);
+        assert_eq!(check_models(&configured, &[]).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_check_data_dir() {
+        let dir = TempDir::new().unwrap();
+        let result = check_data_dir(Some(&dir.path().join("clawRS")));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(fs::read_dir(dir.path().join("clawRS")).unwrap().count(), 0);
+
+        // A file where the directory should be
+        let blocker = dir.path().join("blocker");
+        fs::write(&blocker, b"").unwrap();
+        assert_eq!(check_data_dir(Some(&blocker.join("clawRS"))).status, CheckStatus::Fail);
+        assert_eq!(check_data_dir(None).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_check_gpu_backend() {
+        let gpu = GpuInfo {
+            name: "RTX 4070".to_string(),
+            is_available: true,
+            ..GpuInfo::default()
+        };
+        assert_eq!(check_gpu_backend(&gpu, Some("CUDA")).status, CheckStatus::Pass);
+        let cpu_only = check_gpu_backend(&gpu, None);
+        assert_eq!(cpu_only.status, CheckStatus::Warn);
+        assert!(cpu_only.detail.contains("--features"));
+    }
+
+    #[test]
+    fn test_report_text() {
+        let report = SelfCheckReport {
+            checks: vec![
+                check_data_dir(None),
+                check_workspace(Some(Path::new("/definitely/not/here"))),
+            ],
+            model_locations: vec![ModelLocation { path: PathBuf::from("/models"), model_count: 2 }],
+        };
+        let text = report.to_text();
+        assert!(text.contains("[FAIL] Data directory"));
+        assert!(text.contains("[FAIL] Workspace"));
+        assert!(text.contains("/models (2 models)"));
+        assert!(report.has_failures());
+        assert_eq!(report.status(CheckKind::Models), None);
+    }
+}
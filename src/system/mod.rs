@@ -1,6 +1,7 @@
 //! System utilities
 //!
-//! This module provides system-level functionality like GPU detection and resource monitoring.
+//! This module provides system-level functionality like GPU detection, resource monitoring and the startup self-check.
 
+pub mod diagnostics;
 pub mod gpu;
 pub mod resources;
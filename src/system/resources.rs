@@ -38,6 +38,24 @@ pub fn get_resource_usage() -> ResourceUsage {
     }
 }
 
+/// Free system RAM in bytes, when the platform reports it
+pub fn free_ram_bytes() -> Option<u64> {
+    let usage = get_resource_usage();
+    if usage.ram_total_mb == 0 {
+        return None;
+    }
+    Some(usage.ram_total_mb.saturating_sub(usage.ram_used_mb) * 1024 * 1024)
+}
+
+/// Free dedicated VRAM in bytes, when the GPU reports its usage
+pub fn free_vram_bytes() -> Option<u64> {
+    let gpu = crate::system::gpu::detect_gpu();
+    if !gpu.is_available || !gpu.vram_usage_available || gpu.vram_total_mb == 0 {
+        return None;
+    }
+    Some(gpu.vram_total_mb.saturating_sub(gpu.vram_used_mb) * 1024 * 1024)
+}
+
 // =============================================================================
 // macOS resource monitoring
 // =============================================================================
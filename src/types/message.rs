@@ -3,6 +3,10 @@
 //! Defines chat message structures and roles.
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::agent::loop_runner::ToolHistoryEntry;
+use crate::inference::streaming::GenerationStats;
 
 /// Role of a message sender
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -13,6 +17,8 @@ pub enum Role {
     Assistant,
     /// System prompt
     System,
+    /// Tool result fed back to the model
+    Tool,
 }
 
 /// A single chat message
@@ -24,6 +30,101 @@ pub struct Message {
     pub content: String,
     /// Timestamp when the message was created
     pub timestamp: u64,
+    /// Extra data about how the message was produced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<MessageMetadata>,
+}
+
+/// Metadata attached to an assistant reply
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageMetadata {
+    /// Sampling settings the reply was generated with, when chosen in an experiment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingParams>,
+    /// Other replies generated for the same prompt and not chosen
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<AlternativeReply>,
+    /// Set on system messages the agent loop injects for its own bookkeeping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<Notice>,
+    /// Set on the agent system prompt: compression never shortens or drops it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Tool calls the message reports, with their parameters and outcome
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolHistoryEntry>,
+    /// Performance of the generation that produced the reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<GenerationStats>,
+    /// Model file the reply was generated with (file stem)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Files attached to a user message (their content is in the message)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+/// A file attached to a user message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub path: PathBuf,
+    pub mime: String,
+    /// Size in bytes when it was attached
+    pub size: u64,
+}
+
+/// Kind of system notice injected by the agent loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeKind {
+    /// Reflection prompt after a failed tool call
+    Reflection,
+    /// Retry request after a stream error
+    StreamRetry,
+    /// Reminder of the tool call format
+    InvalidToolCall,
+    /// Correction after the model wrote a tool result instead of calling the tool
+    FabricatedToolOutput,
+    /// Hint after a refused permission
+    ToolRefused,
+    /// Hint listing the available tools
+    ToolNotFound,
+    /// Hint that read-only mode blocked a tool
+    ReadOnly,
+    /// Request for a final answer after repeated errors
+    TooManyErrors,
+    /// Context compression report
+    Compression,
+    /// Tool calls left for a later iteration
+    DeferredCalls,
+    /// The model was switched mid-conversation
+    ModelSwitch,
+}
+
+/// A loop notice, with how many consecutive ones it stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notice {
+    pub kind: NoticeKind,
+    pub count: u32,
+}
+
+/// Sampling parameters of one experiment variant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    /// Preset name shown in the UI
+    pub label: String,
+    pub temperature: f32,
+    pub top_p: f32,
+    pub min_p: f32,
+}
+
+/// A reply kept as an alternative to the canonical one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlternativeReply {
+    pub content: String,
+    /// `None` for a reply generated with the chat's own settings
+    #[serde(default)]
+    pub sampling: Option<SamplingParams>,
 }
 
 impl Message {
@@ -36,8 +137,20 @@ impl Message {
                 .duration_since(std::time::UNIX_EPOCH)
                 .map(|d| d.as_secs())
                 .unwrap_or(0),
+            metadata: None,
         }
     }
+
+    /// The agent system prompt, pinned against every compression path
+    pub fn system_prompt(content: impl Into<String>) -> Self {
+        let mut msg = Self::new(Role::System, content);
+        msg.metadata = Some(MessageMetadata { pinned: true, ..Default::default() });
+        msg
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.metadata.as_ref().is_some_and(|m| m.pinned)
+    }
 }
 
 /// Clean thinking tags from content for display
@@ -83,6 +196,51 @@ mod tests {
         assert!(msg.timestamp > 0);
     }
 
+    #[test]
+    fn test_metadata_optional_in_json() {
+        let msg = Message::new(Role::Assistant, "Hi");
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(!json.contains("metadata"));
+        let loaded: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.metadata, None);
+    }
+
+    #[test]
+    fn test_tool_calls_and_stats_round_trip() {
+        // Saved before tool calls were recorded
+        let old = r#"{"role":"Assistant","content":"✅ `file_read` (0.1s): ok","timestamp":1,"metadata":{}}"#;
+        let loaded: Message = serde_json::from_str(old).unwrap();
+        assert!(loaded.metadata.unwrap().tool_calls.is_empty());
+
+        let mut msg = Message::new(Role::Assistant, "✅ `file_read` (0.1s): ok");
+        msg.metadata = Some(MessageMetadata {
+            tool_calls: vec![ToolHistoryEntry {
+                tool_name: "file_read".to_string(),
+                params: serde_json::json!({ "path": "notes.md" }),
+                result: None,
+                error: Some("Permission denied".to_string()),
+                timestamp: 1,
+                duration_ms: 120,
+            }],
+            stats: Some(GenerationStats {
+                prompt_tokens: 812,
+                tokens_generated: 64,
+                prompt_ms: 400,
+                generation_ms: 2000,
+                stop_reason: crate::inference::streaming::StopReason::MaxTokens,
+            }),
+            model: Some("qwen2.5-7b-instruct-q4_k_m".to_string()),
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&msg).unwrap();
+        let loaded: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, msg);
+        let metadata = loaded.metadata.unwrap();
+        assert_eq!(metadata.tool_calls[0].params["path"], "notes.md");
+        assert!(json.contains("\"stop_reason\":\"max_tokens\""));
+        assert_eq!(metadata.stats.unwrap().tokens_per_second(), 32.0);
+    }
+
     #[test]
     fn test_role_equality() {
         assert_eq!(Role::User, Role::User);
@@ -0,0 +1,350 @@
+//! Files attached to a chat message
+//!
+//! Attached files reach the model inside the user message, after its text:
+//! small text files inlined as fenced blocks, PDFs as the text `pdf_read`
+//! extracts, anything else (or too large) as a path with its type and size so
+//! the agent can open it with its tools. The attachments themselves are kept in
+//! the message metadata, so the chips are still there after a reload.
+//!
+//! Pasted images are not attachments: they are saved with the conversation
+//! and referenced from the text as markdown images.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use base64::Engine;
+
+use crate::agent::tools::file_view::{decode, detect_encoding, SNIFF_BYTES};
+use crate::agent::tools::filesystem::format_size;
+use crate::agent::tools::pdf::PdfReadTool;
+use crate::agent::tools::Tool;
+use crate::types::message::Attachment;
+
+/// Larger text files are referenced by path instead of inlined
+pub const MAX_INLINE_FILE_BYTES: u64 = 32 * 1024;
+
+/// Inlined text across all the attachments of a message
+pub const MAX_INLINE_TOTAL_BYTES: usize = 96 * 1024;
+
+/// Separates the typed text from the attached content in a message
+pub const ATTACHMENTS_HEADER: &str = "\n\n## Attached files\n";
+
+const PDF_MIME: &str = "application/pdf";
+
+/// Types by extension; other files are sniffed as text or binary
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("log", "text/plain"),
+    ("md", "text/markdown"),
+    ("markdown", "text/markdown"),
+    ("csv", "text/csv"),
+    ("tsv", "text/tab-separated-values"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("xml", "application/xml"),
+    ("json", "application/json"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("toml", "application/toml"),
+    ("js", "text/javascript"),
+    ("ts", "text/x-typescript"),
+    ("py", "text/x-python"),
+    ("rs", "text/x-rust"),
+    ("sh", "application/x-sh"),
+    ("pdf", PDF_MIME),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+];
+
+/// Attachment for the file at `path`, refused for folders
+pub fn attachment_for(path: &Path) -> io::Result<Attachment> {
+    let metadata = std::fs::metadata(path)?;
+    if metadata.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is a folder", path.display()),
+        ));
+    }
+    Ok(Attachment {
+        path: path.to_path_buf(),
+        mime: guess_mime(path)?,
+        size: metadata.len(),
+    })
+}
+
+fn guess_mime(path: &Path) -> io::Result<String> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if let Some((_, mime)) = MIME_TYPES.iter().find(|(ext, _)| *ext == extension) {
+        return Ok(mime.to_string());
+    }
+    let mut sample = Vec::with_capacity(SNIFF_BYTES);
+    File::open(path)?
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut sample)?;
+    Ok(match detect_encoding(&sample) {
+        Some(_) => "text/plain".to_string(),
+        None => "application/octet-stream".to_string(),
+    })
+}
+
+fn is_text(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/json"
+                | "application/xml"
+                | "application/yaml"
+                | "application/toml"
+                | "application/x-sh"
+                | "image/svg+xml"
+        )
+}
+
+/// File name shown on a chip
+pub fn display_name(attachment: &Attachment) -> String {
+    attachment
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| attachment.path.display().to_string())
+}
+
+/// Typed text, then the attached files as the model should see them
+pub async fn compose_message(text: &str, attachments: &[Attachment]) -> String {
+    let mut content = text.trim_end().to_string();
+    if attachments.is_empty() {
+        return content;
+    }
+    content.push_str(ATTACHMENTS_HEADER);
+    let mut budget = MAX_INLINE_TOTAL_BYTES;
+    for attachment in attachments {
+        content.push('\n');
+        content.push_str(&render_attachment(attachment, &mut budget).await);
+    }
+    content
+}
+
+/// One attached file: a heading line, then its content or why it is left out
+async fn render_attachment(attachment: &Attachment, budget: &mut usize) -> String {
+    let heading = format!(
+        "### {} ({}, {})\n",
+        attachment.path.display(),
+        attachment.mime,
+        format_size(attachment.size)
+    );
+
+    if attachment.mime == PDF_MIME {
+        let params = serde_json::json!({ "path": attachment.path.to_string_lossy() });
+        return match PdfReadTool.execute(params).await {
+            Ok(result) => {
+                let text = result.data["text"].as_str().unwrap_or_default();
+                match fit(text, budget) {
+                    Some(text) => format!("{}Text extracted from the PDF:\n{}", heading, fence(&text, "text")),
+                    None => format!(
+                        "{}Not inlined: over the size limit for attachments. Read it with pdf_read.\n",
+                        heading
+                    ),
+                }
+            }
+            Err(e) => format!("{}Text could not be extracted ({}).\n", heading, e),
+        };
+    }
+
+    if !is_text(&attachment.mime) {
+        return format!(
+            "{}Binary file, not inlined. Use the file tools on its path if needed.\n",
+            heading
+        );
+    }
+    if attachment.size > MAX_INLINE_FILE_BYTES {
+        return format!(
+            "{}Not inlined: larger than {}. Read it with file_read.\n",
+            heading,
+            format_size(MAX_INLINE_FILE_BYTES)
+        );
+    }
+    let text = match std::fs::read(&attachment.path) {
+        Ok(bytes) => match detect_encoding(&bytes) {
+            Some(encoding) => decode(&bytes, encoding),
+            None => {
+                return format!(
+                    "{}Binary content, not inlined. Use the file tools on its path if needed.\n",
+                    heading
+                )
+            }
+        },
+        Err(e) => return format!("{}Could not be read ({}).\n", heading, e),
+    };
+    match fit(&text, budget) {
+        Some(text) => {
+            let language = attachment
+                .path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            format!("{}{}", heading, fence(&text, &language))
+        }
+        None => format!(
+            "{}Not inlined: over the size limit for attachments. Read it with file_read.\n",
+            heading
+        ),
+    }
+}
+
+/// `text` if it fits in what is left of `budget`, which it then uses up
+fn fit(text: &str, budget: &mut usize) -> Option<String> {
+    let text = text.trim_end();
+    if text.len() > *budget {
+        return None;
+    }
+    *budget -= text.len();
+    Some(text.to_string())
+}
+
+/// `text` in a code fence longer than any backtick run inside it
+fn fence(text: &str, language: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{language}\n{text}\n{fence}\n")
+}
+
+/// Typed text and attached content of a message
+pub fn split_attachments(content: &str) -> (&str, Option<&str>) {
+    match content.split_once(ATTACHMENTS_HEADER) {
+        Some((text, attached)) => (text, Some(attached)),
+        None => (content, None),
+    }
+}
+
+/// `content` with its typed text replaced, the attached content kept
+pub fn replace_text(content: &str, text: &str) -> String {
+    match split_attachments(content).1 {
+        Some(attached) => format!("{}{}{}", text.trim_end(), ATTACHMENTS_HEADER, attached),
+        None => text.to_string(),
+    }
+}
+
+/// Bytes of a `data:image/...;base64,` URL, the form a pasted image comes in
+pub fn decode_image_data_url(url: &str) -> Option<Vec<u8>> {
+    let (header, data) = url.strip_prefix("data:image/")?.split_once(',')?;
+    if !header.ends_with(";base64") {
+        return None;
+    }
+    base64::engine::general_purpose::STANDARD.decode(data).ok()
+}
+
+/// `text` with a markdown reference to a pasted image on its own line
+pub fn append_image_reference(text: &str, path: &Path) -> String {
+    let reference = format!("![pasted image]({})", path.display());
+    let text = text.trim_end();
+    if text.is_empty() {
+        reference
+    } else {
+        format!("{}\n{}", text, reference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "# Notes\n").unwrap();
+        let unknown_text = dir.path().join("Makefile");
+        std::fs::write(&unknown_text, "all:\n\tcargo build\n").unwrap();
+        let blob = dir.path().join("data.bin");
+        std::fs::write(&blob, [0u8, 159, 146, 150]).unwrap();
+
+        let attachment = attachment_for(&notes).unwrap();
+        assert_eq!(attachment.mime, "text/markdown");
+        assert_eq!(attachment.size, 8);
+        assert_eq!(display_name(&attachment), "notes.md");
+        assert_eq!(attachment_for(&unknown_text).unwrap().mime, "text/plain");
+        assert_eq!(
+            attachment_for(&blob).unwrap().mime,
+            "application/octet-stream"
+        );
+        assert!(attachment_for(dir.path()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_compose_inlines_text_and_references_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("build.rs");
+        std::fs::write(&script, "fn main() {\n    // ```\n}\n").unwrap();
+        let blob = dir.path().join("data.bin");
+        std::fs::write(&blob, [0u8; 16]).unwrap();
+        let big = dir.path().join("big.txt");
+        std::fs::write(&big, "x".repeat(MAX_INLINE_FILE_BYTES as usize + 1)).unwrap();
+        let attachments: Vec<Attachment> = [&script, &blob, &big]
+            .iter()
+            .map(|path| attachment_for(path).unwrap())
+            .collect();
+
+        let content = compose_message("Review these  \n", &attachments).await;
+        let (text, attached) = split_attachments(&content);
+        assert_eq!(text, "Review these");
+        let attached = attached.unwrap();
+        assert!(
+            attached.contains("(text/x-rust, 25 B)\n````rs\nfn main() {\n    // ```\n}\n````\n")
+        );
+        assert!(attached.contains("Binary file, not inlined"));
+        assert!(attached.contains("Not inlined: larger than 32.0 KB"));
+
+        assert_eq!(compose_message("Hi ", &[]).await, "Hi");
+        let edited = replace_text(&content, "Check these");
+        assert_eq!(split_attachments(&edited), ("Check these", Some(attached)));
+        assert_eq!(replace_text("plain", "new"), "new");
+    }
+
+    #[test]
+    fn test_inline_budget() {
+        let mut budget = 10;
+        assert_eq!(fit("12345\n", &mut budget).as_deref(), Some("12345"));
+        assert_eq!(budget, 5);
+        assert_eq!(fit("123456", &mut budget), None);
+        assert_eq!(budget, 5);
+    }
+
+    #[test]
+    fn test_pasted_image_reference() {
+        assert_eq!(
+            decode_image_data_url("data:image/png;base64,iVBORw==").as_deref(),
+            Some(&[0x89, b'P', b'N', b'G'][..])
+        );
+        assert_eq!(decode_image_data_url("data:text/plain;base64,aGk="), None);
+        assert_eq!(decode_image_data_url("data:image/svg+xml,<svg/>"), None);
+
+        let path = Path::new("/data/assets/conv/shot.png");
+        assert_eq!(
+            append_image_reference("", path),
+            "![pasted image](/data/assets/conv/shot.png)"
+        );
+        assert_eq!(
+            append_image_reference("Look:  ", path),
+            "Look:\n![pasted image](/data/assets/conv/shot.png)"
+        );
+    }
+}
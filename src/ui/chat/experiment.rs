@@ -0,0 +1,360 @@
+//! Sampling experiment panel
+//!
+//! Re-runs one user message with two or three sampling presets on the
+//! engine's background lane and shows the replies side by side. The chosen
+//! reply replaces the current one; the others are kept as alternatives.
+
+use dioxus::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::message::Message;
+use crate::agent::language::Locale;
+use crate::app::AppState;
+use crate::inference::engine::GenerationParams;
+use crate::inference::experiment::{
+    experiment_history, promote_variant, SamplingPreset, VariantResult, MAX_VARIANTS, MIN_VARIANTS, SAMPLING_PRESETS,
+};
+use crate::inference::streaming::collect_stream_text;
+use crate::storage::conversations::save_conversation;
+use crate::types::message::Message as StorageMessage;
+use crate::ui::i18n::t;
+
+/// Stop signal of the variant being generated, shared with Cancel and unmount
+type ActiveStop = Arc<Mutex<Option<Arc<AtomicBool>>>>;
+
+/// Make the chosen variant the reply and save the conversation
+fn promote_reply(mut app_state: AppState, user_index: usize, variants: &[VariantResult], chosen: usize) {
+    let mut stored: Vec<StorageMessage> = app_state
+        .active_messages
+        .read()
+        .iter()
+        .cloned()
+        .map(|m| m.into())
+        .collect();
+    promote_variant(&mut stored, user_index, variants, chosen);
+    app_state
+        .active_messages
+        .set(stored.iter().cloned().map(Message::from).collect());
+
+    let mut conv_write = app_state.current_conversation.write();
+    if let Some(ref mut conv) = *conv_write {
+        conv.messages = stored;
+        if let Err(e) = save_conversation(conv) {
+            tracing::error!("Failed to save conversation: {}", e);
+        }
+    }
+}
+
+fn stop_active(active: &ActiveStop) {
+    if let Ok(active) = active.lock() {
+        if let Some(stop) = active.as_ref() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Name of a sampling preset in the interface language
+fn preset_label(locale: Locale, preset: &SamplingPreset) -> &'static str {
+    match preset.id {
+        "precise" => t!(locale, "experiment.precise"),
+        "balanced" => t!(locale, "experiment.balanced"),
+        "creative" => t!(locale, "experiment.creative"),
+        _ => t!(locale, "experiment.wide"),
+    }
+}
+
+#[component]
+pub fn ExperimentPanel(user_index: usize, on_close: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+
+    let mut selected = use_signal(|| vec![1usize, 2]);
+    let mut results = use_signal(Vec::<VariantResult>::new);
+    let mut running = use_signal(|| None::<usize>);
+    let mut error = use_signal(|| None::<String>);
+    let cancel = use_hook(|| Arc::new(AtomicBool::new(false)));
+    let active_stop: ActiveStop = use_hook(|| Arc::new(Mutex::new(None)));
+
+    // Closing the panel must not leave a variant generating in the background
+    {
+        let cancel = cancel.clone();
+        let active_stop = active_stop.clone();
+        use_drop(move || {
+            cancel.store(true, Ordering::Relaxed);
+            stop_active(&active_stop);
+        });
+    }
+
+    let prompt_preview = app_state
+        .active_messages
+        .read()
+        .get(user_index)
+        .map(|m| m.content.chars().take(160).collect::<String>())
+        .unwrap_or_default();
+
+    let is_running = running().is_some();
+    let columns = selected.read().len().max(1);
+    let selection_ok = (MIN_VARIANTS..=MAX_VARIANTS).contains(&selected.read().len());
+
+    let handle_run = {
+        let cancel = cancel.clone();
+        let active_stop = active_stop.clone();
+        let app_state = app_state.clone();
+        move |_| {
+            cancel.store(false, Ordering::Relaxed);
+            results.set(Vec::new());
+            error.set(None);
+
+            let presets: Vec<_> = selected.read().iter().map(|&i| SAMPLING_PRESETS[i]).collect();
+            let (base, system_prompt) = {
+                let settings = app_state.settings.read();
+                let overrides = app_state
+                    .current_conversation
+                    .read()
+                    .as_ref()
+                    .map(|c| c.overrides.clone())
+                    .unwrap_or_default();
+                // Presets set temperature and top-p; the rest follows the conversation
+                let params = GenerationParams {
+                    max_tokens: overrides.max_tokens.unwrap_or(settings.max_tokens),
+                    temperature: overrides.temperature.unwrap_or(settings.temperature),
+                    top_k: overrides.top_k.unwrap_or(settings.top_k),
+                    top_p: overrides.top_p.unwrap_or(settings.top_p),
+                    min_p: 0.0,
+                    repeat_penalty: 1.1,
+                    seed: 0,
+                    max_context_size: app_state.context_cap(),
+                    auto_context: settings.auto_context,
+                    grammar: None,
+                    stop_sequences: settings.stop_sequences.clone(),
+                    frequency_penalty: settings.frequency_penalty,
+                    presence_penalty: settings.presence_penalty,
+                };
+                let system_prompt = overrides.system_prompt.unwrap_or_else(|| settings.system_prompt.clone());
+                (params, system_prompt)
+            };
+            let history: Vec<StorageMessage> = {
+                let msgs: Vec<StorageMessage> = app_state
+                    .active_messages
+                    .read()
+                    .iter()
+                    .cloned()
+                    .map(|m| m.into())
+                    .collect();
+                let mut prompt = Vec::new();
+                if !system_prompt.trim().is_empty() {
+                    prompt.push(StorageMessage::system_prompt(system_prompt));
+                }
+                prompt.extend(experiment_history(&msgs, user_index));
+                prompt
+            };
+
+            let cancel = cancel.clone();
+            let active_stop = active_stop.clone();
+            let app_state = app_state.clone();
+            spawn(async move {
+                for (i, preset) in presets.iter().enumerate() {
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    running.set(Some(i));
+
+                    // Release the engine lock before draining so the chat can queue ahead
+                    let stream = {
+                        let engine = app_state.engine.lock().await;
+                        engine.generate_stream_background(history.clone(), preset.apply(&base))
+                    };
+                    let (rx, stop) = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            tracing::error!("Experiment variant failed to start: {}", e);
+                            error.set(Some(e.to_string()));
+                            break;
+                        }
+                    };
+                    if let Ok(mut active) = active_stop.lock() {
+                        *active = Some(stop.clone());
+                    }
+                    let (content, stats) = collect_stream_text(rx, &stop, &cancel).await;
+                    if let Ok(mut active) = active_stop.lock() {
+                        *active = None;
+                    }
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    results.write().push(VariantResult {
+                        sampling: preset.sampling(),
+                        content,
+                        stats,
+                    });
+                }
+                running.set(None);
+            });
+        }
+    };
+
+    let handle_cancel = {
+        let cancel = cancel.clone();
+        let active_stop = active_stop.clone();
+        move |_| {
+            cancel.store(true, Ordering::Relaxed);
+            stop_active(&active_stop);
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/60 backdrop-blur-xl z-50 flex items-center justify-center p-4",
+            onclick: move |_| on_close.call(()),
+            onkeydown: move |evt: KeyboardEvent| {
+                if evt.key() == Key::Escape {
+                    on_close.call(());
+                }
+            },
+
+            div {
+                class: "w-full max-w-5xl max-h-[90vh] flex flex-col glass-strong rounded-2xl p-6 animate-scale-in",
+                role: "dialog",
+                "aria-modal": "true",
+                "aria-labelledby": "experiment-title",
+                onclick: move |e| e.stop_propagation(),
+
+                h3 {
+                    id: "experiment-title",
+                    class: "text-lg font-semibold text-[var(--text-primary)] mb-1",
+                    {t!(locale, "experiment.title")}
+                }
+                p { class: "text-xs text-[var(--text-tertiary)] mb-4 truncate", title: "{prompt_preview}", "{prompt_preview}" }
+
+                // Preset selection
+                div {
+                    class: "flex flex-wrap gap-2 mb-2",
+                    role: "group",
+                    "aria-label": t!(locale, "experiment.presets"),
+                    for (index, preset) in SAMPLING_PRESETS.iter().enumerate() {
+                        {
+                            let checked = selected.read().contains(&index);
+                            let full = selected.read().len() >= MAX_VARIANTS;
+                            let label = preset_label(locale, preset);
+                            let detail = format!("T {:.1} · top-p {:.2} · min-p {:.2}", preset.temperature, preset.top_p, preset.min_p);
+                            rsx! {
+                                button {
+                                    role: "checkbox",
+                                    "aria-checked": "{checked}",
+                                    disabled: is_running || (!checked && full),
+                                    onclick: move |_| {
+                                        let mut selected = selected.write();
+                                        if let Some(pos) = selected.iter().position(|&i| i == index) {
+                                            selected.remove(pos);
+                                        } else if selected.len() < MAX_VARIANTS {
+                                            selected.push(index);
+                                            selected.sort_unstable();
+                                        }
+                                    },
+                                    class: if checked {
+                                        "py-1.5 px-3 rounded-xl border text-xs border-[var(--accent-primary)] bg-[var(--accent-primary-10)] text-[var(--accent-primary)]"
+                                    } else {
+                                        "py-1.5 px-3 rounded-xl border text-xs border-[var(--border-subtle)] bg-white/[0.02] text-[var(--text-secondary)] hover:border-[var(--border-medium)] disabled:opacity-40"
+                                    },
+                                    span { class: "font-medium", "{label}" }
+                                    span { class: "ml-2 font-mono text-[10px] opacity-70", "{detail}" }
+                                }
+                            }
+                        }
+                    }
+                }
+                p { class: "text-[10px] text-[var(--text-tertiary)] mb-4",
+                    {t!(locale, "experiment.pick_hint")}
+                }
+
+                if let Some(message) = error.read().as_ref() {
+                    div {
+                        class: "p-3 mb-4 rounded-xl text-xs bg-[var(--bg-error-subtle)] border border-[var(--border-error-subtle)] text-[var(--text-error)]",
+                        role: "status",
+                        "{message}"
+                    }
+                }
+
+                // Results, one column per variant
+                div {
+                    class: "flex-1 min-h-0 grid gap-3 mb-4 overflow-y-auto custom-scrollbar",
+                    style: "grid-template-columns: repeat({columns}, minmax(0, 1fr));",
+                    "aria-live": "polite",
+                    for (index, preset_index) in selected.read().iter().copied().enumerate() {
+                        {
+                            let preset = SAMPLING_PRESETS[preset_index];
+                            let label = preset_label(locale, &preset);
+                            let result = results.read().get(index).cloned();
+                            let pending = running() == Some(index);
+                            let app_state = app_state.clone();
+                            rsx! {
+                                div {
+                                    class: "flex flex-col min-h-[10rem] p-3 rounded-xl bg-white/[0.02] border border-[var(--border-subtle)]",
+                                    div { class: "text-xs font-semibold text-[var(--text-primary)] mb-2", "{label}" }
+                                    match result {
+                                        Some(result) => {
+                                            let stats = result.stats.as_ref().map(|s| format!(
+                                                "{} tok · {:.1} tok/s · {} ms",
+                                                s.tokens_generated,
+                                                s.tokens_per_second(),
+                                                s.prompt_ms + s.generation_ms
+                                            ));
+                                            rsx! {
+                                                div { class: "flex-1 text-sm text-[var(--text-secondary)] whitespace-pre-wrap select-text", "{result.content}" }
+                                                if let Some(stats) = stats {
+                                                    div { class: "mt-2 text-[10px] font-mono text-[var(--text-tertiary)]", "{stats}" }
+                                                }
+                                                button {
+                                                    class: "btn-primary mt-3 text-xs",
+                                                    disabled: is_running || result.content.trim().is_empty(),
+                                                    onclick: move |_| {
+                                                        promote_reply(app_state.clone(), user_index, &results.read(), index);
+                                                        on_close.call(());
+                                                    },
+                                                    {t!(locale, "experiment.use_reply")}
+                                                }
+                                            }
+                                        }
+                                        None => rsx! {
+                                            div { class: "flex-1 text-xs text-[var(--text-tertiary)]",
+                                                if pending {
+                                                    {t!(locale, "experiment.generating")}
+                                                } else {
+                                                    "—"
+                                                }
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex gap-3",
+                    button {
+                        onclick: move |_| on_close.call(()),
+                        class: "btn-ghost flex-1",
+                        {t!(locale, "experiment.close")}
+                    }
+                    if is_running {
+                        button {
+                            onclick: handle_cancel,
+                            class: "btn-ghost flex-1",
+                            {t!(locale, "experiment.cancel")}
+                        }
+                    } else {
+                        button {
+                            onclick: handle_run,
+                            disabled: !selection_ok,
+                            class: "btn-primary flex-1",
+                            {t!(locale, "experiment.run")}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
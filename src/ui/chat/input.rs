@@ -3,7 +3,19 @@
 use crate::app::AppState;
 use crate::agent::skills::loader::SkillLoader;
 use crate::agent::skills::Skill;
+use crate::agent::tools::filesystem::format_size;
+use crate::inference::voice;
+use crate::storage::conversations::save_conversation_image;
+use crate::storage::templates::first_placeholder_range;
+use crate::types::message::Attachment;
+use crate::ui::chat::attachments::{
+    append_image_reference, attachment_for, compose_message, decode_image_data_url, display_name,
+};
+use crate::ui::chat::queue::Draft;
+use crate::ui::chat::voice::VoiceButton;
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
+use std::path::Path;
 
 /// Estimate how many rows the textarea needs based on content
 fn compute_rows(text: &str) -> usize {
@@ -16,9 +28,37 @@ fn compute_rows(text: &str) -> usize {
     total.clamp(1, 8)
 }
 
+/// Send the typed text with its attachments, once their content is read
+fn submit(text: String, attachments: Vec<Attachment>, on_send: EventHandler<Draft>) {
+    if attachments.is_empty() {
+        on_send.call(Draft { content: text, attachments });
+        return;
+    }
+    spawn(async move {
+        let content = compose_message(&text, &attachments).await;
+        on_send.call(Draft { content, attachments });
+    });
+}
+
+/// Add picked or dropped files, skipping ones already attached
+fn add_attachments(mut attachments: Signal<Vec<Attachment>>, mut attach_error: Signal<Option<String>>, paths: Vec<String>) {
+    let mut errors = Vec::new();
+    for path in paths {
+        match attachment_for(Path::new(&path)) {
+            Ok(attachment) => {
+                if !attachments.peek().iter().any(|a| a.path == attachment.path) {
+                    attachments.write().push(attachment);
+                }
+            }
+            Err(e) => errors.push(format!("{}: {}", path, e)),
+        }
+    }
+    attach_error.set((!errors.is_empty()).then(|| errors.join("; ")));
+}
+
 #[component]
 pub fn ChatInput(
-    on_send: EventHandler<String>,
+    on_send: EventHandler<Draft>,
     on_stop: EventHandler<()>,
     is_generating: bool,
 ) -> Element {
@@ -27,9 +67,85 @@ pub fn ChatInput(
     let mut filtered_skills = use_signal(Vec::<Skill>::new);
     let mut autocomplete_open = use_signal(|| false);
     let mut selected_index = use_signal(|| 0);
+    let mut attachments = use_signal(Vec::<Attachment>::new);
+    let mut attach_error = use_signal(|| None::<String>);
+    let mut drag_over = use_signal(|| false);
     
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
+    // No sending while a model loads or is swapped in
+    let model_state = app_state.model_state;
+    let model_busy = model_state.read().is_loading();
+
+    // Pre-fill from a template scaffold and select its first placeholder
+    {
+        let mut pending_input = app_state.pending_input.clone();
+        use_effect(move || {
+            let Some(scaffold) = pending_input.read().clone() else {
+                return;
+            };
+            pending_input.set(None);
+            let (start, end) = first_placeholder_range(&scaffold)
+                .unwrap_or_else(|| {
+                    let len = scaffold.encode_utf16().count();
+                    (len, len)
+                });
+            text.set(scaffold);
+            spawn(async move {
+                let js = format!(
+                    "setTimeout(() => {{ const el = document.getElementById('chat-input'); if (el) {{ el.focus(); el.setSelectionRange({start}, {end}); }} }}, 0);"
+                );
+                let _ = document::eval(&js).await;
+            });
+        });
+    }
+
+    // Pasted images: the webview hands them over as data URLs; each is saved
+    // with the conversation and referenced from the text
+    {
+        let current_conversation = app_state.current_conversation;
+        use_effect(move || {
+            spawn(async move {
+                let mut paste = document::eval(
+                    r#"
+                    const el = document.getElementById('chat-input');
+                    if (el) {
+                        el.addEventListener('paste', (e) => {
+                            const items = Array.from((e.clipboardData && e.clipboardData.items) || []);
+                            const images = items.filter((item) => item.kind === 'file' && item.type.startsWith('image/'));
+                            if (images.length === 0) return;
+                            e.preventDefault();
+                            for (const item of images) {
+                                const reader = new FileReader();
+                                reader.onload = () => dioxus.send(reader.result);
+                                reader.readAsDataURL(item.getAsFile());
+                            }
+                        });
+                    }
+                    await new Promise(() => {});
+                    "#,
+                );
+                while let Ok(data_url) = paste.recv::<String>().await {
+                    let Some(conv_id) = current_conversation.peek().as_ref().map(|c| c.id.clone()) else {
+                        continue;
+                    };
+                    let saved = decode_image_data_url(&data_url)
+                        .ok_or_else(|| "unreadable clipboard data".to_string())
+                        .and_then(|bytes| save_conversation_image(&conv_id, &bytes).map_err(|e| e.to_string()));
+                    match saved {
+                        Ok(path) => {
+                            let updated = append_image_reference(&text.peek(), &path);
+                            text.set(updated);
+                            attach_error.set(None);
+                        }
+                        Err(e) => {
+                            attach_error.set(Some(t!(locale, "input.image_not_saved", e)));
+                        }
+                    }
+                }
+            });
+        });
+    }
 
     // Load skills on mount
     use_effect(move || {
@@ -78,9 +194,12 @@ pub fn ChatInput(
             on_stop.call(());
         } else if evt.key() == Key::Enter && !evt.modifiers().contains(Modifiers::SHIFT) {
             evt.prevent_default();
-            if !is_generating && !text().trim().is_empty() {
-                on_send.call(text());
+            // During a run the message is queued rather than dropped
+            let has_content = !text().trim().is_empty() || !attachments.peek().is_empty();
+            if has_content && !model_state.peek().is_loading() {
+                submit(text(), attachments.take(), on_send);
                 text.set(String::new());
+                attach_error.set(None);
                 autocomplete_open.set(false);
             }
         }
@@ -114,7 +233,7 @@ pub fn ChatInput(
         }
     };
 
-    let can_send = !is_generating && !text().trim().is_empty();
+    let can_send = !is_generating && !model_busy && (!text().trim().is_empty() || !attachments.read().is_empty());
     let rows = compute_rows(&text());
     let rows_str = format!("{}", rows);
     let is_multiline = rows > 1;
@@ -127,19 +246,23 @@ pub fn ChatInput(
     };
 
     let textarea_style = if is_multiline {
-        "line-height: 22px; padding: 14px 0 14px 20px; max-height: 180px; overflow-y: auto;"
+        "line-height: 22px; padding: 14px 0 14px 4px; max-height: 180px; overflow-y: auto;"
     } else {
-        "line-height: 22px; padding: 15px 0 15px 20px; max-height: 180px; overflow: hidden;"
+        "line-height: 22px; padding: 15px 0 15px 4px; max-height: 180px; overflow: hidden;"
     };
 
-    let placeholder = if is_en { "Send a message..." } else { "Envoyer un message..." };
+    let placeholder = if is_generating {
+        t!(locale, "input.queue_placeholder")
+    } else {
+        t!(locale, "input.placeholder")
+    };
 
     let stop_style = if is_multiline {
         "background: var(--error); margin-bottom: 8px;"
     } else {
         "background: var(--error);"
     };
-    let stop_title = if is_en { "Stop (Esc)" } else { "Arreter (Esc)" };
+    let stop_title = t!(locale, "input.stop");
 
     let send_class = if can_send {
         "flex-shrink-0 w-9 h-9 rounded-full flex items-center justify-center transition-all hover:scale-105 active:scale-95"
@@ -154,8 +277,14 @@ pub fn ChatInput(
         format!("background: var(--bg-elevated);{mb}")
     };
 
-    let send_title = if is_en { "Send (Enter)" } else { "Envoyer (Entree)" };
-    let hint = if is_en { "Enter to send, Shift+Enter for a new line" } else { "Entree pour envoyer, Shift+Entree pour un saut de ligne" };
+    let send_title = t!(locale, "input.send");
+    let hint = t!(locale, "input.hint");
+    let attach_title = t!(locale, "input.attach");
+    let container_style = if drag_over() {
+        "border-radius: 28px; min-height: 52px; border-color: var(--accent-primary);"
+    } else {
+        "border-radius: 28px; min-height: 52px;"
+    };
 
     rsx! {
         div {
@@ -175,7 +304,7 @@ pub fn ChatInput(
                             class: "px-3 py-2 border-b border-[var(--border-subtle)] bg-white/5",
                             span {
                                 class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
-                                if is_en { "Available Skills" } else { "Skills disponibles" }
+                                {t!(locale, "input.skills")}
                             }
                         }
                         
@@ -223,20 +352,99 @@ pub fn ChatInput(
                     }
                 }
 
-                // Glass input container
+                // Attached files, above the input
+                if !attachments.read().is_empty() {
+                    div { class: "flex flex-wrap gap-2 mb-2",
+                        for (index, attachment) in attachments.read().iter().enumerate() {
+                            span {
+                                key: "{attachment.path.display()}",
+                                class: "inline-flex items-center gap-1.5 max-w-[260px] px-2.5 py-1 rounded-full glass-md text-[11px] text-[var(--text-secondary)]",
+                                title: "{attachment.path.display()} ({attachment.mime})",
+                                span { class: "truncate", "{display_name(attachment)}" }
+                                span { class: "text-[var(--text-tertiary)]", "{format_size(attachment.size)}" }
+                                button {
+                                    class: "text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                    "aria-label": t!(locale, "input.remove_attachment"),
+                                    onclick: move |_| {
+                                        attachments.write().remove(index);
+                                    },
+                                    "×"
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(error) = attach_error() {
+                    p { class: "text-[11px] text-[var(--error)] mb-2", role: "alert", "{error}" }
+                }
+
+                // Glass input container, also the drop target for files
                 div {
                     class: "{container_class}",
-                    style: "border-radius: 28px; min-height: 52px;",
+                    style: "{container_style}",
+                    ondragover: move |evt: DragEvent| {
+                        evt.prevent_default();
+                        drag_over.set(true);
+                    },
+                    ondragleave: move |_| drag_over.set(false),
+                    ondrop: move |evt: DragEvent| {
+                        evt.prevent_default();
+                        drag_over.set(false);
+                        if let Some(files) = evt.files() {
+                            add_attachments(attachments, attach_error, files.files());
+                        }
+                    },
+
+                    // File picker
+                    label {
+                        class: "flex-shrink-0 w-9 h-9 ml-2 rounded-full flex items-center justify-center cursor-pointer text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-colors",
+                        title: "{attach_title}",
+                        "aria-label": "{attach_title}",
+                        input {
+                            r#type: "file",
+                            multiple: true,
+                            class: "hidden",
+                            onchange: move |evt: FormEvent| {
+                                if let Some(files) = evt.files() {
+                                    add_attachments(attachments, attach_error, files.files());
+                                }
+                            },
+                        }
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            path { d: "M21.44 11.05l-9.19 9.19a6 6 0 0 1-8.49-8.49l9.19-9.19a4 4 0 0 1 5.66 5.66l-9.2 9.19a2 2 0 0 1-2.83-2.83l8.49-8.48" }
+                        }
+                    }
+
+                    // Dictation, when built with voice input
+                    if voice::AVAILABLE {
+                        VoiceButton {
+                            locale,
+                            on_transcript: move |transcript: String| {
+                                let typed = text.peek().trim_end().to_string();
+                                text.set(if typed.is_empty() { transcript } else { format!("{} {}", typed, transcript) });
+                                attach_error.set(None);
+                            },
+                            on_error: move |message: String| attach_error.set(Some(message)),
+                        }
+                    }
 
                     // Textarea — auto-expanding
                     textarea {
+                        id: "chat-input",
                         class: "flex-1 bg-transparent outline-none text-[var(--text-primary)] resize-none placeholder-[var(--text-tertiary)] text-[15px] custom-scrollbar",
                         style: "{textarea_style}",
                         placeholder: "{placeholder}",
                         value: "{text}",
                         oninput: handle_input,
                         onkeydown: handle_keydown,
-                        disabled: is_generating,
                         rows: "{rows_str}",
                     }
 
@@ -259,8 +467,9 @@ pub fn ChatInput(
                         button {
                             onclick: move |_| {
                                 if can_send {
-                                    on_send.call(text());
+                                    submit(text(), attachments.take(), on_send);
                                     text.set(String::new());
+                                    attach_error.set(None);
                                 }
                             },
                             disabled: !can_send,
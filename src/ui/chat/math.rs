@@ -0,0 +1,173 @@
+//! TeX math rendered as MathML for the webview
+//!
+//! `$$...$$` blocks and `$...$` spans go through latex2mathml. The result is
+//! injected as HTML, so it is only used when every tag and attribute is
+//! plain MathML; otherwise, or when the TeX does not convert, the message
+//! shows the source in monospace instead.
+
+use latex2mathml::{latex_to_mathml, DisplayStyle};
+
+/// Elements latex2mathml produces, all presentation MathML
+const MATHML_TAGS: &[&str] = &[
+    "math",
+    "semantics",
+    "annotation",
+    "mrow",
+    "mi",
+    "mn",
+    "mo",
+    "ms",
+    "mtext",
+    "mspace",
+    "msup",
+    "msub",
+    "msubsup",
+    "mfrac",
+    "msqrt",
+    "mroot",
+    "mover",
+    "munder",
+    "munderover",
+    "mtable",
+    "mtr",
+    "mtd",
+    "mstyle",
+    "mpadded",
+    "mphantom",
+    "menclose",
+    "merror",
+    "mmultiscripts",
+    "mprescripts",
+    "none",
+    "mfenced",
+];
+
+const MATHML_ATTRIBUTES: &[&str] = &[
+    "xmlns",
+    "display",
+    "mathvariant",
+    "stretchy",
+    "fence",
+    "separator",
+    "lspace",
+    "rspace",
+    "accent",
+    "accentunder",
+    "movablelimits",
+    "largeop",
+    "symmetric",
+    "linethickness",
+    "form",
+    "minsize",
+    "maxsize",
+    "width",
+    "height",
+    "depth",
+    "columnalign",
+    "columnspacing",
+    "rowspacing",
+    "columnlines",
+    "rowlines",
+    "scriptlevel",
+    "displaystyle",
+    "notation",
+    "open",
+    "close",
+    "separators",
+    "encoding",
+];
+
+/// MathML for `tex`, as a display block or inline, or `None` to fall back
+/// to the TeX source
+pub fn tex_to_mathml(tex: &str, block: bool) -> Option<String> {
+    let tex = tex.trim();
+    if tex.is_empty() {
+        return None;
+    }
+    let style = if block {
+        DisplayStyle::Block
+    } else {
+        DisplayStyle::Inline
+    };
+    // Malformed TeX from a model is common; a panicking converter must not
+    // take the chat down with it
+    let converted = std::panic::catch_unwind(|| latex_to_mathml(tex, style))
+        .ok()?
+        .ok()?;
+    is_plain_mathml(&converted).then_some(converted)
+}
+
+/// Every tag is a MathML element with MathML attributes only
+fn is_plain_mathml(markup: &str) -> bool {
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            return false;
+        };
+        if !is_plain_tag(&rest[start + 1..start + end]) {
+            return false;
+        }
+        rest = &rest[start + end + 1..];
+    }
+    true
+}
+
+/// Inside of one `<...>`: a known element and known, quoted attributes
+fn is_plain_tag(tag: &str) -> bool {
+    let tag = tag.strip_prefix('/').unwrap_or(tag);
+    let tag = tag.strip_suffix('/').unwrap_or(tag).trim();
+    let (name, mut attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    if !MATHML_TAGS.contains(&name) {
+        return false;
+    }
+    loop {
+        attributes = attributes.trim_start();
+        if attributes.is_empty() {
+            return true;
+        }
+        let Some((attribute, value)) = attributes.split_once('=') else {
+            return false;
+        };
+        if !MATHML_ATTRIBUTES.contains(&attribute.trim()) {
+            return false;
+        }
+        let value = value.trim_start();
+        let Some(quote @ ('"' | '\'')) = value.chars().next() else {
+            return false;
+        };
+        let Some(close) = value[1..].find(quote) else {
+            return false;
+        };
+        attributes = &value[close + 2..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tex_converts_to_mathml() {
+        let inline = tex_to_mathml("x^2 + \\frac{a}{b}", false).unwrap();
+        assert!(inline.starts_with("<math"));
+        assert!(inline.contains("<msup>") && inline.contains("<mfrac>"));
+        let block = tex_to_mathml("\\sum_{i=1}^n i", true).unwrap();
+        assert!(block.contains("display=\"block\""));
+        assert_eq!(tex_to_mathml("  ", true), None);
+    }
+
+    #[test]
+    fn test_only_plain_mathml_is_injected() {
+        assert!(is_plain_mathml(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><mi>x</mi><mo>&lt;</mo><mn>2</mn></math>"
+        ));
+        assert!(is_plain_mathml("<mspace width=\"0.2em\"/>"));
+        assert!(!is_plain_mathml(
+            "<math><img src=x onerror=alert(1)></math>"
+        ));
+        assert!(!is_plain_mathml("<mi onclick=\"alert(1)\">x</mi>"));
+        assert!(!is_plain_mathml("<mi mathvariant=normal>x</mi>"));
+        assert!(!is_plain_mathml("<math><mi>x</mi"));
+        assert!(!is_plain_mathml("<!-- --><math></math>"));
+    }
+}
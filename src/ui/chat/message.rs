@@ -1,19 +1,45 @@
 //! Message display components with Markdown rendering
 
+use crate::agent::language::Locale;
+use crate::agent::loop_runner::{
+    latest_progress, pending_tool_calls, LoopMessage, PendingToolCall, ToolHistoryEntry, PERMISSION_DENIED_ERROR,
+    READ_ONLY_BLOCKED_ERROR,
+};
+use crate::agent::runner::{format_tool_result_for_system, strip_tool_calls};
+use crate::agent::tools::backup::{UndoFileChangeTool, BACKED_UP_TOOLS};
+use crate::agent::tools::filesystem::format_size;
+use crate::agent::tools::Tool;
 use crate::app::AppState;
+use crate::inference::tokens::TokenCount;
+use crate::storage::conversations::message_to_markdown;
+use crate::storage::settings::save_settings;
+use crate::types::message::{MessageMetadata, Notice, Role};
+use crate::ui::chat::attachments::{display_name, split_attachments};
+use crate::ui::chat::math::tex_to_mathml;
+use crate::ui::components::a11y::is_activation_key;
+use crate::ui::components::clipboard::CopyButton;
+use crate::ui::components::permission_dialog::DiffPreview;
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum MessageRole {
     User,
     Assistant,
     System,
+    Tool,
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct Message {
     pub role: MessageRole,
     pub content: String,
+    pub metadata: Option<MessageMetadata>,
+    /// Token count of `content`, cached between agent iterations
+    pub tokens: TokenCount,
 }
 
 // Convert storage Message to UI Message
@@ -24,8 +50,11 @@ impl From<crate::types::message::Message> for Message {
                 crate::types::message::Role::User => MessageRole::User,
                 crate::types::message::Role::Assistant => MessageRole::Assistant,
                 crate::types::message::Role::System => MessageRole::System,
+                crate::types::message::Role::Tool => MessageRole::Tool,
             },
             content: msg.content,
+            metadata: msg.metadata,
+            tokens: TokenCount::default(),
         }
     }
 }
@@ -33,14 +62,57 @@ impl From<crate::types::message::Message> for Message {
 // Convert UI Message to storage Message
 impl From<Message> for crate::types::message::Message {
     fn from(msg: Message) -> Self {
-        crate::types::message::Message::new(
+        let mut stored = crate::types::message::Message::new(
             match msg.role {
                 MessageRole::User => crate::types::message::Role::User,
                 MessageRole::Assistant => crate::types::message::Role::Assistant,
                 MessageRole::System => crate::types::message::Role::System,
+                MessageRole::Tool => crate::types::message::Role::Tool,
             },
             msg.content,
-        )
+        );
+        stored.metadata = msg.metadata;
+        stored
+    }
+}
+
+impl Message {
+    /// Attach a tool call to the message reporting it, so it is saved with the conversation
+    pub fn record_tool_call(&mut self, entry: ToolHistoryEntry) {
+        self.metadata.get_or_insert_with(Default::default).tool_calls.push(entry);
+    }
+
+}
+
+impl LoopMessage for Message {
+    fn is_reply(&self) -> bool {
+        self.role == MessageRole::Assistant
+    }
+
+    fn text(&self) -> &str {
+        &self.content
+    }
+
+    fn notice(&self) -> Option<Notice> {
+        self.metadata.as_ref().and_then(|m| m.notice)
+    }
+
+    fn empty_reply() -> Self {
+        Message {
+            role: MessageRole::Assistant,
+            content: String::new(),
+            metadata: None,
+            tokens: TokenCount::default(),
+        }
+    }
+
+    fn notice_message(content: String, notice: Notice) -> Self {
+        Message {
+            role: MessageRole::System,
+            content,
+            metadata: Some(MessageMetadata { notice: Some(notice), ..Default::default() }),
+            tokens: TokenCount::default(),
+        }
     }
 }
 
@@ -52,6 +124,18 @@ enum ContentPart {
     ThinkingStreaming(String), // Open <think> block still being generated
 }
 
+/// The text parts of a reply, without its thinking, as "Copy text" puts them on the clipboard
+fn copy_text(parts: &[ContentPart]) -> String {
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text(text) if !text.trim().is_empty() => Some(text.trim()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 /// Parse thinking blocks from message content.
 /// Supports both <think>...</think> and <thinking>...</thinking> tags.
 /// Incomplete tags are rendered as live streaming blocks.
@@ -140,7 +224,7 @@ fn strip_xml_tags(content: &str, tag: &str) -> String {
 #[component]
 fn ThinkingBlock(content: String) -> Element {
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
     let mut is_expanded = use_signal(|| false);
 
     let chevron_class = if is_expanded() {
@@ -159,10 +243,20 @@ fn ThinkingBlock(content: String) -> Element {
         div { class: "thinking-block my-3",
             div {
                 class: "thinking-header",
+                role: "button",
+                tabindex: "0",
+                "aria-expanded": "{is_expanded()}",
                 onclick: move |_| is_expanded.set(!is_expanded()),
+                onkeydown: move |evt: KeyboardEvent| {
+                    if is_activation_key(&evt.key()) {
+                        evt.prevent_default();
+                        is_expanded.set(!is_expanded());
+                    }
+                },
 
                 svg {
                     class: "{chevron_class}",
+                    "aria-hidden": "true",
                     width: "12",
                     height: "12",
                     view_box: "0 0 24 24",
@@ -174,11 +268,12 @@ fn ThinkingBlock(content: String) -> Element {
                     polyline { points: "9 18 15 12 9 6" }
                 }
 
-                span { if is_en { "Thinking" } else { "Reflexion" } }
+                span { {t!(locale, "message.thinking")} }
             }
 
             div {
                 class: "{content_class}",
+                "aria-hidden": if is_expanded() { "false" } else { "true" },
                 div {
                     class: "text-sm text-[var(--text-secondary)] leading-relaxed px-4 pb-3",
                     MarkdownContent { content: content }
@@ -192,7 +287,7 @@ fn ThinkingBlock(content: String) -> Element {
 #[component]
 fn ThinkingBlockStreaming(content: String) -> Element {
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
 
     let display_content = if content.trim().is_empty() {
         "...".to_string()
@@ -225,7 +320,7 @@ fn ThinkingBlockStreaming(content: String) -> Element {
                 span {
                     class: "text-xs",
                     style: "color: var(--text-tertiary);",
-                    if is_en { "Thinking..." } else { "Reflexion en cours..." }
+                    {t!(locale, "message.thinking_live")}
                 }
             }
 
@@ -250,25 +345,422 @@ fn MarkdownContent(content: String) -> Element {
     rsx! {
         div { class: "markdown-content space-y-3",
             for block in blocks {
-                {render_block(block)}
+                {render_block(block, None)}
             }
         }
     }
 }
 
-#[derive(Clone, Debug)]
+/// Reply text rendered from an already parsed document, headings carrying their anchors
+#[component]
+fn MarkdownDocument(doc: MarkdownDoc) -> Element {
+    let mut anchors = doc.outline.into_iter().map(|entry| entry.anchor);
+    let blocks: Vec<(usize, MarkdownBlock, Option<String>)> = doc.blocks
+        .into_iter()
+        .map(|(offset, block)| {
+            let anchor = match block {
+                MarkdownBlock::Heading(..) => anchors.next(),
+                _ => None,
+            };
+            (offset, block, anchor)
+        })
+        .collect();
+
+    // Keyed by offset: while a reply streams, only its last block changes
+    // and the blocks before it are not rendered again
+    rsx! {
+        div { class: "markdown-content space-y-3",
+            for (offset, block, anchor) in blocks {
+                MarkdownBlockView { key: "{offset}", block, anchor }
+            }
+        }
+    }
+}
+
+#[component]
+fn MarkdownBlockView(block: MarkdownBlock, anchor: Option<String>) -> Element {
+    render_block(block, anchor)
+}
+
+/// Approve/Deny buttons for the pending permission request of a tool
+///
+/// Resolves the same request as the permission dialog, so the waiting agent
+/// loop resumes right away.
+#[component]
+fn PermissionActions(tool_name: String, locale: Locale) -> Element {
+    let app_state = use_context::<AppState>();
+    let manager = app_state.agent.permission_manager.clone();
+    let pending = manager.signals().pending_requests;
+    let Some((request_id, preview)) = pending
+        .read()
+        .iter()
+        .find(|r| r.tool_name == tool_name)
+        .map(|r| (r.id, r.preview.clone()))
+    else {
+        return rsx! {};
+    };
+
+    let approve = {
+        let manager = manager.clone();
+        move |_| {
+            let manager = manager.clone();
+            spawn(async move {
+                let _ = manager.approve(request_id).await;
+            });
+        }
+    };
+    let deny = {
+        let manager = manager.clone();
+        move |_| {
+            let manager = manager.clone();
+            spawn(async move {
+                let _ = manager.deny(request_id).await;
+            });
+        }
+    };
+    let always_allow = {
+        let manager = manager.clone();
+        let mut settings = app_state.settings;
+        let tool_name = tool_name.clone();
+        move |_| {
+            {
+                let mut settings = settings.write();
+                if !settings.tool_allowlist.contains(&tool_name) {
+                    settings.tool_allowlist.push(tool_name.clone());
+                }
+                if let Err(e) = save_settings(&settings) {
+                    tracing::error!("Failed to save settings: {}", e);
+                }
+            }
+            let manager = manager.clone();
+            spawn(async move {
+                let _ = manager.approve(request_id).await;
+            });
+        }
+    };
+
+    rsx! {
+        if let Some(diff) = preview {
+            div { class: "pl-4 pt-1.5",
+                DiffPreview { diff, locale }
+            }
+        }
+        div { class: "flex items-center gap-2 pl-4 pt-1.5",
+            button {
+                class: "btn-primary text-xs",
+                onclick: approve,
+                {t!(locale, "message.approve")}
+            }
+            button {
+                class: "btn-ghost text-xs",
+                onclick: deny,
+                {t!(locale, "permission.deny")}
+            }
+            button {
+                class: "text-[11px] text-[var(--text-tertiary)] hover:text-[var(--accent-primary)] transition-colors",
+                title: t!(locale, "message.always_allow_hint"),
+                onclick: always_allow,
+                {t!(locale, "message.always_allow")}
+            }
+        }
+    }
+}
+
+/// Button starting a new conversation from a message
+#[component]
+fn ForkButton(on_fork: EventHandler<()>, locale: Locale) -> Element {
+    rsx! {
+        button {
+            class: "text-[11px] text-[var(--text-tertiary)] hover:text-[var(--accent-primary)] transition-colors",
+            title: t!(locale, "message.fork_hint"),
+            onclick: move |_| on_fork.call(()),
+            {t!(locale, "message.fork")}
+        }
+    }
+}
+
+/// Temperatures offered when regenerating a reply
+const RETRY_TEMPERATURES: [f32; 4] = [0.2, 0.7, 1.0, 1.3];
+
+/// Replies shorter than this (in characters) never show an outline
+const OUTLINE_MIN_CHARS: usize = 1500;
+
+/// A heading of a reply and the element id the outline scrolls to
+#[derive(Clone, Debug, PartialEq)]
+struct OutlineEntry {
+    level: u8,
+    text: String,
+    anchor: String,
+}
+
+/// Parsed markdown blocks with the headings found among them
+#[derive(Clone, Debug, PartialEq)]
+struct MarkdownDoc {
+    /// Blocks with the byte offset each starts at in the text
+    blocks: Vec<(usize, MarkdownBlock)>,
+    outline: Vec<OutlineEntry>,
+}
+
+/// Hands out heading anchors unique within one message.
+///
+/// Anchors only depend on the headings before them, so they stay put while
+/// the rest of the message streams in.
+struct AnchorSet {
+    prefix: String,
+    seen: std::collections::HashMap<String, usize>,
+}
+
+impl AnchorSet {
+    fn new(prefix: &str) -> Self {
+        Self { prefix: prefix.to_string(), seen: std::collections::HashMap::new() }
+    }
+
+    fn next(&mut self, text: &str) -> String {
+        let mut slug = String::new();
+        for c in text.chars().flat_map(|c| c.to_lowercase()) {
+            if c.is_alphanumeric() {
+                slug.push(c);
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = match slug.trim_end_matches('-') {
+            "" => "section".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+        let count = self.seen.entry(slug.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            format!("{}-{}", self.prefix, slug)
+        } else {
+            format!("{}-{}-{}", self.prefix, slug, count)
+        }
+    }
+}
+
+/// Blocks of a reply that grows while it streams
+///
+/// A block followed by another one no longer changes as text is appended,
+/// so those are kept with the text they came from and each update only
+/// parses what comes after them. Text that does not extend the kept text
+/// (an edited or regenerated reply) is parsed again from the start.
+#[derive(Default)]
+struct StreamingBlocks {
+    /// Text up to the end of the settled blocks
+    settled_text: String,
+    settled: Vec<(usize, MarkdownBlock)>,
+}
+
+impl StreamingBlocks {
+    /// Blocks of `content` with their offsets, as `parse_markdown_blocks` reads them
+    fn parse(&mut self, content: &str) -> Vec<(usize, MarkdownBlock)> {
+        if !content.starts_with(self.settled_text.as_str()) {
+            *self = Self::default();
+        }
+        let base = self.settled_text.len();
+        let mut spans: Vec<(Range<usize>, MarkdownBlock)> = parse_markdown_spans(&content[base..])
+            .into_iter()
+            .map(|(range, block)| (range.start + base..range.end + base, block))
+            .collect();
+        // A block is settled once another one follows it and the line that
+        // ended it is complete: a cut line ("| 1" of a table row) can still
+        // change how the block before it ends
+        let complete = content.rfind('\n').map_or(0, |newline| newline + 1);
+        let settled = spans[..spans.len().saturating_sub(1)]
+            .iter()
+            .take_while(|(range, _)| range.end < complete)
+            .count();
+        if let Some((range, _)) = settled.checked_sub(1).map(|last| &spans[last]) {
+            self.settled_text.push_str(&content[base..range.end]);
+            self.settled.extend(spans.drain(..settled).map(|(range, block)| (range.start, block)));
+        }
+        let mut blocks = self.settled.clone();
+        blocks.extend(spans.into_iter().map(|(range, block)| (range.start, block)));
+        blocks
+    }
+}
+
+/// Collect the headings of a reply from its parsed blocks
+fn markdown_doc(blocks: Vec<(usize, MarkdownBlock)>, anchors: &mut AnchorSet) -> MarkdownDoc {
+    let outline = blocks
+        .iter()
+        .filter_map(|(_, block)| match block {
+            MarkdownBlock::Heading(level, text) => Some(OutlineEntry {
+                level: *level,
+                text: text.clone(),
+                anchor: anchors.next(text),
+            }),
+            _ => None,
+        })
+        .collect();
+    MarkdownDoc { blocks, outline }
+}
+
+/// Scroll the element with this id to the top of the chat
+fn scroll_to_anchor(id: &str) {
+    let js = format!(
+        "const el = document.getElementById({:?}); if (el) {{ el.scrollIntoView({{ behavior: 'smooth', block: 'start' }}); }}",
+        id
+    );
+    spawn(async move {
+        let _ = document::eval(&js).await;
+    });
+}
+
+/// Floating "On this message" menu listing the headings of a long reply
+#[component]
+fn MessageOutline(entries: Vec<OutlineEntry>, locale: Locale) -> Element {
+    let mut is_open = use_signal(|| false);
+    let count = entries.len();
+    let top_level = entries.iter().map(|e| e.level).min().unwrap_or(1);
+    let items: Vec<(OutlineEntry, u32)> = entries
+        .into_iter()
+        .map(|e| {
+            let indent = (e.level - top_level) as u32 * 10;
+            (e, indent)
+        })
+        .collect();
+
+    rsx! {
+        div { class: "sticky top-2 z-10 flex justify-end h-0 overflow-visible",
+            nav {
+                class: "glass-md rounded-lg border border-[var(--border-subtle)] text-xs max-w-[220px]",
+                "aria-label": t!(locale, "message.outline"),
+                button {
+                    class: "w-full px-3 py-1.5 text-left text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-colors",
+                    "aria-expanded": "{is_open()}",
+                    onclick: move |_| is_open.set(!is_open()),
+                    {t!(locale, "message.outline_count", count)}
+                }
+                if is_open() {
+                    ul { class: "px-2 pb-2 max-h-64 overflow-y-auto custom-scrollbar",
+                        for (entry, indent) in items {
+                            li {
+                                key: "{entry.anchor}",
+                                style: "padding-left: {indent}px;",
+                                button {
+                                    class: "w-full text-left truncate px-1 py-0.5 rounded text-[var(--text-secondary)] hover:text-[var(--accent-primary)] hover:bg-[var(--bg-tertiary)]",
+                                    title: "{entry.text}",
+                                    onclick: {
+                                        let anchor = entry.anchor.clone();
+                                        move |_| scroll_to_anchor(&anchor)
+                                    },
+                                    "{entry.text}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 enum MarkdownBlock {
     Paragraph(String),
     Heading(u8, String),
     CodeBlock(String, String), // (language, code)
-    MathBlock(String),         // LaTeX math block
-    UnorderedList(Vec<String>),
-    OrderedList(Vec<String>),
+    MathBlock(String, bool),   // (LaTeX, closed: false while `$$` is still streaming)
+    UnorderedList(Vec<ListItem>),
+    OrderedList(Vec<ListItem>),
     HorizontalRule,
     Blockquote(String),
     Table(Vec<Vec<String>>, Vec<String>), // (rows, headers)
 }
 
+/// One list entry, with the lists nested under it
+#[derive(Clone, Debug, PartialEq)]
+struct ListItem {
+    text: String,
+    kind: ListItemKind,
+    /// Indented lists below the item, ordered or not
+    children: Vec<MarkdownBlock>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ListItemKind {
+    Plain,
+    /// `- [ ]` / `- [x]` task item
+    Task { checked: bool },
+}
+
+impl ListItem {
+    fn new(text: &str) -> Self {
+        // "[x]" alone or before a space; "[x](url)" is a link
+        let marker = text.get(..3).filter(|_| text[3..].is_empty() || text[3..].starts_with(' '));
+        let (kind, text) = match marker {
+            Some("[ ]") => (ListItemKind::Task { checked: false }, &text[3..]),
+            Some("[x]") | Some("[X]") => (ListItemKind::Task { checked: true }, &text[3..]),
+            _ => (ListItemKind::Plain, text),
+        };
+        Self { text: text.trim().to_string(), kind, children: Vec::new() }
+    }
+}
+
+/// Indentation (tabs count as 4 columns), whether the list is ordered, and
+/// the item text, for a line starting a list item
+fn list_marker(line: &str) -> Option<(usize, bool, &str)> {
+    let indent = line
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    let trimmed = line.trim_start();
+    for bullet in ["- ", "* ", "• "] {
+        if let Some(text) = trimmed.strip_prefix(bullet) {
+            return Some((indent, false, text));
+        }
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    let text = trimmed[digits..].strip_prefix(". ")?;
+    (digits > 0).then_some((indent, true, text))
+}
+
+/// The list starting at `lines[*i]`, with the lists indented below its
+/// items; stops at a blank line, a heading, a code fence, a less indented
+/// item or an item of the other kind
+fn parse_list(lines: &[&str], i: &mut usize) -> MarkdownBlock {
+    let (indent, ordered, _) = list_marker(lines[*i]).unwrap_or((0, false, ""));
+    let mut items: Vec<ListItem> = Vec::new();
+    while *i < lines.len() {
+        let line = lines[*i];
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("```") {
+            break;
+        }
+        match list_marker(line) {
+            Some((item_indent, _, _)) if item_indent < indent => break,
+            Some((item_indent, _, _)) if item_indent > indent && !items.is_empty() => {
+                let nested = parse_list(lines, i);
+                if let Some(last) = items.last_mut() {
+                    last.children.push(nested);
+                }
+            }
+            Some((_, item_ordered, text)) => {
+                if item_ordered != ordered && !items.is_empty() {
+                    break;
+                }
+                items.push(ListItem::new(text));
+                *i += 1;
+            }
+            None => {
+                // Wrapped text of the previous item
+                if let Some(last) = items.last_mut() {
+                    last.text.push(' ');
+                    last.text.push_str(trimmed);
+                }
+                *i += 1;
+            }
+        }
+    }
+    if ordered {
+        MarkdownBlock::OrderedList(items)
+    } else {
+        MarkdownBlock::UnorderedList(items)
+    }
+}
+
 /// Parse a table row into cells
 fn parse_table_row(line: &str) -> Vec<String> {
     line.trim()
@@ -288,216 +780,213 @@ fn is_table_separator(line: &str) -> bool {
 }
 
 fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
-    let mut blocks = Vec::new();
+    parse_markdown_spans(content).into_iter().map(|(_, block)| block).collect()
+}
+
+/// Blocks of `content`, each with the byte range it was read from
+///
+/// A range ends where reading the next block starts, so it also covers the
+/// blank lines after the block.
+fn parse_markdown_spans(content: &str) -> Vec<(Range<usize>, MarkdownBlock)> {
     let lines: Vec<&str> = content.lines().collect();
+    // Byte offset of every line, then of the end of the text
+    let mut offsets: Vec<usize> = content
+        .split_inclusive('\n')
+        .scan(0, |end, line| {
+            let start = *end;
+            *end += line.len();
+            Some(start)
+        })
+        .collect();
+    offsets.push(content.len());
+
+    let mut spans = Vec::new();
     let mut i = 0;
-
     while i < lines.len() {
-        let line = lines[i];
-        let trimmed = line.trim();
-
-        // Empty line
-        if trimmed.is_empty() {
-            i += 1;
-            continue;
+        let start = i;
+        if let Some(block) = parse_block(&lines, &mut i) {
+            spans.push((offsets[start]..offsets[i.min(lines.len())], block));
         }
+    }
+    spans
+}
 
-        // Math block $$...$$
-        if trimmed.starts_with("$$") {
-            let first_line_content = trimmed.trim_start_matches('$').trim();
-            let mut math_lines = Vec::new();
+/// The block starting at `lines[*i]`, if any, moving `i` past it
+///
+/// Only looks at the lines from `*i` on: parsing a text from any block
+/// boundary gives the same blocks as parsing it whole.
+fn parse_block(lines: &[&str], i: &mut usize) -> Option<MarkdownBlock> {
+    let line = lines[*i];
+    let trimmed = line.trim();
+
+    // Empty line
+    if trimmed.is_empty() {
+        *i += 1;
+        return None;
+    }
 
-            if first_line_content.ends_with("$$") {
-                // Single line math block
-                let math = first_line_content.trim_end_matches('$').trim();
-                blocks.push(MarkdownBlock::MathBlock(math.to_string()));
-                i += 1;
-                continue;
-            }
+    // Math block $$...$$
+    if trimmed.starts_with("$$") {
+        let first_line_content = trimmed.trim_start_matches('$').trim();
+        let mut math_lines = Vec::new();
 
-            if !first_line_content.is_empty() {
-                math_lines.push(first_line_content.to_string());
-            }
-            i += 1;
-            while i < lines.len() {
-                let l = lines[i];
-                if l.trim().contains("$$") {
-                    let before_end = l.trim().trim_end_matches('$').trim();
-                    if !before_end.is_empty() {
-                        math_lines.push(before_end.to_string());
-                    }
-                    i += 1;
-                    break;
+        if first_line_content.ends_with("$$") {
+            // Single line math block
+            let math = first_line_content.trim_end_matches('$').trim();
+            *i += 1;
+            return Some(MarkdownBlock::MathBlock(math.to_string(), true));
+        }
+
+        if !first_line_content.is_empty() {
+            math_lines.push(first_line_content.to_string());
+        }
+        *i += 1;
+        let mut closed = false;
+        while *i < lines.len() {
+            let l = lines[*i];
+            if l.trim().contains("$$") {
+                let before_end = l.trim().trim_end_matches('$').trim();
+                if !before_end.is_empty() {
+                    math_lines.push(before_end.to_string());
                 }
-                math_lines.push(l.to_string());
-                i += 1;
+                closed = true;
+                *i += 1;
+                break;
             }
-            blocks.push(MarkdownBlock::MathBlock(math_lines.join("\n")));
-            continue;
+            math_lines.push(l.to_string());
+            *i += 1;
         }
+        return Some(MarkdownBlock::MathBlock(math_lines.join("\n"), closed));
+    }
 
-        // Code block ```
-        if trimmed.starts_with("```") {
-            let lang = trimmed.trim_start_matches('`').to_string();
-            let mut code_lines = Vec::new();
-            i += 1;
-            while i < lines.len() && !lines[i].trim().starts_with("```") {
-                code_lines.push(lines[i]);
-                i += 1;
-            }
-            blocks.push(MarkdownBlock::CodeBlock(lang, code_lines.join("\n")));
-            i += 1;
-            continue;
+    // Code block ```
+    if trimmed.starts_with("```") {
+        let lang = trimmed.trim_start_matches('`').to_string();
+        let mut code_lines = Vec::new();
+        *i += 1;
+        while *i < lines.len() && !lines[*i].trim().starts_with("```") {
+            code_lines.push(lines[*i]);
+            *i += 1;
         }
+        *i += 1;
+        return Some(MarkdownBlock::CodeBlock(lang, code_lines.join("\n")));
+    }
 
-        // Horizontal rule
-        if trimmed == "---" || trimmed == "***" || trimmed == "___" {
-            blocks.push(MarkdownBlock::HorizontalRule);
-            i += 1;
-            continue;
+    // Horizontal rule
+    if trimmed == "---" || trimmed == "***" || trimmed == "___" {
+        *i += 1;
+        return Some(MarkdownBlock::HorizontalRule);
+    }
+
+    // Heading
+    if trimmed.starts_with('#') {
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if level <= 6 {
+            let text = trimmed.trim_start_matches('#').trim().to_string();
+            *i += 1;
+            return Some(MarkdownBlock::Heading(level as u8, text));
         }
+    }
 
-        // Heading
-        if trimmed.starts_with('#') {
-            let level = trimmed.chars().take_while(|&c| c == '#').count();
-            if level <= 6 {
-                let text = trimmed.trim_start_matches('#').trim().to_string();
-                blocks.push(MarkdownBlock::Heading(level as u8, text));
-                i += 1;
-                continue;
-            }
+    // Blockquote
+    if trimmed.starts_with('>') {
+        let mut quote_lines = Vec::new();
+        while *i < lines.len() && lines[*i].trim().starts_with('>') {
+            quote_lines.push(lines[*i].trim().trim_start_matches('>').trim());
+            *i += 1;
         }
+        return Some(MarkdownBlock::Blockquote(quote_lines.join("\n")));
+    }
 
-        // Blockquote
-        if trimmed.starts_with('>') {
-            let mut quote_lines = Vec::new();
-            while i < lines.len() && lines[i].trim().starts_with('>') {
-                quote_lines.push(lines[i].trim().trim_start_matches('>').trim());
-                i += 1;
+    // Table (lines starting with |)
+    if trimmed.starts_with('|') && trimmed.ends_with('|') {
+        let mut table_lines: Vec<&str> = Vec::new();
+        while *i < lines.len() {
+            let l = lines[*i].trim();
+            if l.starts_with('|') && l.ends_with('|') {
+                table_lines.push(l);
+                *i += 1;
+            } else {
+                break;
             }
-            blocks.push(MarkdownBlock::Blockquote(quote_lines.join("\n")));
-            continue;
         }
 
-        // Table (lines starting with |)
-        if trimmed.starts_with('|') && trimmed.ends_with('|') {
-            let mut table_lines: Vec<&str> = Vec::new();
-            while i < lines.len() {
-                let l = lines[i].trim();
-                if l.starts_with('|') && l.ends_with('|') {
-                    table_lines.push(l);
-                    i += 1;
-                } else {
-                    break;
-                }
-            }
+        if table_lines.len() < 2 {
+            return None;
+        }
 
-            if table_lines.len() >= 2 {
-                // Parse header row
-                let headers: Vec<String> = parse_table_row(table_lines[0]);
+        // Parse header row
+        let headers: Vec<String> = parse_table_row(table_lines[0]);
 
-                // Skip separator row (|---|---|)
-                let data_start = if table_lines.len() > 1 && is_table_separator(table_lines[1]) {
-                    2
-                } else {
-                    1
-                };
+        // Skip separator row (|---|---|)
+        let data_start = if is_table_separator(table_lines[1]) { 2 } else { 1 };
 
-                // Parse data rows
-                let rows: Vec<Vec<String>> = table_lines[data_start..]
-                    .iter()
-                    .map(|line| parse_table_row(line))
-                    .collect();
+        // Parse data rows
+        let rows: Vec<Vec<String>> = table_lines[data_start..]
+            .iter()
+            .map(|line| parse_table_row(line))
+            .collect();
 
-                blocks.push(MarkdownBlock::Table(rows, headers));
-            }
-            continue;
-        }
+        return Some(MarkdownBlock::Table(rows, headers));
+    }
 
-        // Unordered list
-        if trimmed.starts_with("- ") || trimmed.starts_with("* ") || trimmed.starts_with("• ") {
-            let mut items = Vec::new();
-            while i < lines.len() {
-                let l = lines[i].trim();
-                if l.starts_with("- ") || l.starts_with("* ") || l.starts_with("• ") {
-                    items.push(l[2..].to_string());
-                    i += 1;
-                } else if l.is_empty() || l.starts_with('#') || l.starts_with("```") {
-                    break;
-                } else {
-                    // Continuation of previous item
-                    if let Some(last) = items.last_mut() {
-                        last.push(' ');
-                        last.push_str(l);
-                    }
-                    i += 1;
-                }
-            }
-            blocks.push(MarkdownBlock::UnorderedList(items));
-            continue;
-        }
+    // Lists, bulleted or numbered, possibly nested
+    if list_marker(line).is_some() {
+        return Some(parse_list(lines, i));
+    }
 
-        // Ordered list
-        if trimmed
-            .chars()
-            .next()
-            .map(|c| c.is_ascii_digit())
-            .unwrap_or(false)
-            && trimmed.contains(". ")
+    // Regular paragraph - collect until empty line or special block. The
+    // first line always belongs to it ("---x", "#######"), so parsing moves on
+    let mut para_lines = vec![line];
+    *i += 1;
+    while *i < lines.len() {
+        let l = lines[*i];
+        let t = l.trim();
+        if t.is_empty()
+            || t.starts_with('#')
+            || t.starts_with("```")
+            || t.starts_with("---")
+            || t.starts_with("- ")
+            || t.starts_with("* ")
+            || t.starts_with("> ")
         {
-            let mut items = Vec::new();
-            while i < lines.len() {
-                let l = lines[i].trim();
-                if let Some(pos) = l.find(". ") {
-                    if l[..pos].chars().all(|c| c.is_ascii_digit()) {
-                        items.push(l[pos + 2..].to_string());
-                        i += 1;
-                        continue;
-                    }
+            break;
+        }
+        para_lines.push(l);
+        *i += 1;
+    }
+    Some(MarkdownBlock::Paragraph(para_lines.join("\n")))
+}
+
+/// A list entry after its bullet or number, or a disabled checkbox for a task
+fn render_list_item(item: ListItem, marker: Element) -> Element {
+    let checked = item.kind == ListItemKind::Task { checked: true };
+    let text_class = if checked { "leading-[1.75] text-[var(--text-tertiary)]" } else { "leading-[1.75]" };
+    rsx! {
+        li { class: "flex items-start gap-2 text-[var(--text-primary)]",
+            if let ListItemKind::Task { checked } = item.kind {
+                input {
+                    r#type: "checkbox",
+                    checked,
+                    disabled: true,
+                    class: "mt-[0.45rem] accent-[var(--accent-primary)]",
                 }
-                if l.is_empty() || l.starts_with('#') || l.starts_with("```") {
-                    break;
+            } else {
+                {marker}
+            }
+            div { class: "flex-1 min-w-0",
+                span { class: "{text_class}",
+                    {render_inline(&item.text)}
                 }
-                // Continuation
-                if let Some(last) = items.last_mut() {
-                    last.push(' ');
-                    last.push_str(l);
+                for child in item.children {
+                    div { class: "mt-1.5", {render_block(child, None)} }
                 }
-                i += 1;
-            }
-            if !items.is_empty() {
-                blocks.push(MarkdownBlock::OrderedList(items));
-                continue;
             }
         }
-
-        // Regular paragraph - collect until empty line or special block
-        let mut para_lines = Vec::new();
-        while i < lines.len() {
-            let l = lines[i];
-            let t = l.trim();
-            if t.is_empty()
-                || t.starts_with('#')
-                || t.starts_with("```")
-                || t.starts_with("---")
-                || t.starts_with("- ")
-                || t.starts_with("* ")
-                || t.starts_with("> ")
-            {
-                break;
-            }
-            para_lines.push(l);
-            i += 1;
-        }
-        if !para_lines.is_empty() {
-            blocks.push(MarkdownBlock::Paragraph(para_lines.join("\n")));
-        }
     }
-
-    blocks
 }
 
-fn render_block(block: MarkdownBlock) -> Element {
+fn render_block(block: MarkdownBlock, anchor: Option<String>) -> Element {
     match block {
         MarkdownBlock::Paragraph(text) => rsx! {
             p { class: "text-[var(--text-primary)] leading-[1.75]",
@@ -513,7 +1002,8 @@ fn render_block(block: MarkdownBlock) -> Element {
                 _ => "text-sm font-semibold text-[var(--text-primary)] mt-2 mb-1",
             };
             rsx! {
-                div { class: "{class}",
+                div { class: "{class} scroll-mt-12",
+                    id: anchor,
                     {render_inline(&text)}
                 }
             }
@@ -521,10 +1011,9 @@ fn render_block(block: MarkdownBlock) -> Element {
         MarkdownBlock::CodeBlock(lang, code) => rsx! {
             div { class: "my-3 rounded-xl overflow-hidden border border-[var(--border-subtle)]",
                 style: "background: #121110;",
-                if !lang.is_empty() {
-                    div { class: "code-header",
-                        span { "{lang}" }
-                    }
+                div { class: "code-header",
+                    span { "{lang}" }
+                    CopyButton { text: code.clone(), label: "copy.code" }
                 }
                 pre { class: "p-4 overflow-x-auto",
                     code { class: "text-sm font-mono leading-relaxed",
@@ -537,34 +1026,36 @@ fn render_block(block: MarkdownBlock) -> Element {
         MarkdownBlock::UnorderedList(items) => rsx! {
             ul { class: "space-y-1.5 pl-1",
                 for item in items {
-                    li { class: "flex items-start gap-2 text-[var(--text-primary)]",
-                        span { class: "text-[var(--accent-primary)] mt-2 text-xs", "•" }
-                        span { class: "leading-[1.75] flex-1",
-                            {render_inline(&item)}
-                        }
-                    }
+                    {render_list_item(item, rsx! { span { class: "text-[var(--accent-primary)] mt-2 text-xs", "•" } })}
                 }
             }
         },
         MarkdownBlock::OrderedList(items) => rsx! {
             ol { class: "space-y-1.5 pl-1",
-                for (idx, item) in items.iter().enumerate() {
-                    li { class: "flex items-start gap-2 text-[var(--text-primary)]",
-                        span { class: "text-[var(--accent-primary)] font-medium text-sm min-w-[1.25rem]", "{idx + 1}." }
-                        span { class: "leading-[1.75] flex-1",
-                            {render_inline(item)}
-                        }
-                    }
+                for (idx, item) in items.into_iter().enumerate() {
+                    {render_list_item(item, rsx! { span { class: "text-[var(--accent-primary)] font-medium text-sm min-w-[1.25rem]", "{idx + 1}." } })}
                 }
             }
         },
-        MarkdownBlock::MathBlock(math) => rsx! {
-            div { class: "my-4 p-4 rounded-xl bg-[var(--bg-tertiary)]/50 border border-[var(--border-subtle)] overflow-x-auto",
-                pre { class: "font-mono text-sm text-[var(--accent-primary)] text-center whitespace-pre-wrap",
-                    "{math}"
-                }
+        MarkdownBlock::MathBlock(math, closed) => {
+            // A block still streaming keeps showing its TeX until `$$` closes it
+            match closed.then(|| tex_to_mathml(&math, true)).flatten() {
+                Some(mathml) => rsx! {
+                    div {
+                        class: "my-4 p-4 rounded-xl bg-[var(--bg-tertiary)]/50 border border-[var(--border-subtle)] overflow-x-auto text-[var(--text-primary)]",
+                        title: "{math}",
+                        dangerous_inner_html: "{mathml}",
+                    }
+                },
+                None => rsx! {
+                    div { class: "my-4 p-4 rounded-xl bg-[var(--bg-tertiary)]/50 border border-[var(--border-subtle)] overflow-x-auto",
+                        pre { class: "font-mono text-sm text-[var(--accent-primary)] text-center whitespace-pre-wrap",
+                            "{math}"
+                        }
+                    }
+                },
             }
-        },
+        }
         MarkdownBlock::HorizontalRule => rsx! {
             hr { class: "border-none h-px bg-[var(--border-subtle)] my-6" }
         },
@@ -623,10 +1114,37 @@ enum InlineSegment {
     Italic(String),
     BoldItalic(String),
     Code(String),
-    Link(String, String), // (text, url)
+    Link(String, String),  // (text, url)
+    Image(String, String), // (alt, path or url)
     InlineMath(String),
 }
 
+/// `[text](target)` starting at `start`: the text, the target and the index
+/// just past the closing parenthesis
+fn parse_bracketed_link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let close = start + 1 + chars[start + 1..].iter().position(|&c| c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + 2 + chars[close + 2..].iter().position(|&c| c == ')')?;
+    let text = chars[start + 1..close].iter().collect();
+    let target = chars[close + 2..end].iter().collect();
+    Some((text, target, end + 1))
+}
+
+/// Index of the `$` closing the inline formula opened at `open`
+///
+/// As in Pandoc, the formula may not start or end with a space and the
+/// closing `$` may not be followed by a digit.
+fn closing_dollar(chars: &[char], open: usize) -> Option<usize> {
+    if chars.get(open + 1).map_or(true, |c| c.is_whitespace()) {
+        return None;
+    }
+    let end = open + 1 + chars[open + 1..].iter().position(|&c| c == '$')?;
+    let closes = !chars[end - 1].is_whitespace() && !chars.get(end + 1).is_some_and(|c| c.is_ascii_digit());
+    closes.then_some(end)
+}
+
 fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
     let mut segments = Vec::new();
     let chars: Vec<char> = text.chars().collect();
@@ -655,21 +1173,19 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
             }
         }
 
-        // Inline math $...$
+        // Inline math $...$, told apart from prices ("$5 and $10")
         if chars[i] == '$' && !matches!(chars.get(i + 1), Some('$')) {
-            if let Some(close_offset) = chars[i + 1..].iter().position(|&c| c == '$') {
+            if let Some(end) = closing_dollar(&chars, i) {
                 if !current_text.is_empty() {
                     segments.push(InlineSegment::Text(current_text.clone()));
                     current_text.clear();
                 }
-                let start = i + 1;
-                let end = i + 1 + close_offset;
-                let math: String = chars[start..end].iter().collect();
+                let math: String = chars[i + 1..end].iter().collect();
                 segments.push(InlineSegment::InlineMath(math));
                 i = end + 1;
                 continue;
             } else {
-                // Unclosed dollar, treat as normal text
+                // Unclosed dollar or a price, treat as normal text
                 current_text.push('$');
                 i += 1;
                 continue;
@@ -740,34 +1256,30 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
             }
         }
 
+        // Image ![alt](path)
+        if chars[i] == '!' && chars.get(i + 1) == Some(&'[') {
+            if let Some((alt, path, next)) = parse_bracketed_link(&chars, i + 1) {
+                if !current_text.is_empty() {
+                    segments.push(InlineSegment::Text(current_text.clone()));
+                    current_text.clear();
+                }
+                segments.push(InlineSegment::Image(alt, path));
+                i = next;
+                continue;
+            }
+        }
+
         // Link [text](url)
         if chars[i] == '[' {
-            let bracket_start = i;
-            i += 1;
-            let text_start = i;
-            while i < chars.len() && chars[i] != ']' {
-                i += 1;
-            }
-            if i < chars.len() && i + 1 < chars.len() && chars[i + 1] == '(' {
-                let link_text: String = chars[text_start..i].iter().collect();
-                i += 2; // skip ](
-                let url_start = i;
-                while i < chars.len() && chars[i] != ')' {
-                    i += 1;
-                }
-                if i < chars.len() {
-                    let url: String = chars[url_start..i].iter().collect();
-                    if !current_text.is_empty() {
-                        segments.push(InlineSegment::Text(current_text.clone()));
-                        current_text.clear();
-                    }
-                    segments.push(InlineSegment::Link(link_text, url));
-                    i += 1;
-                    continue;
+            if let Some((link_text, url, next)) = parse_bracketed_link(&chars, i) {
+                if !current_text.is_empty() {
+                    segments.push(InlineSegment::Text(current_text.clone()));
+                    current_text.clear();
                 }
+                segments.push(InlineSegment::Link(link_text, url));
+                i = next;
+                continue;
             }
-            // Not a valid link, backtrack
-            i = bracket_start;
         }
 
         current_text.push(chars[i]);
@@ -805,45 +1317,72 @@ fn render_segment(segment: InlineSegment) -> Element {
                 "{text}"
             }
         },
-        InlineSegment::InlineMath(math) => rsx! {
-            code { class: "px-1.5 py-0.5 rounded-md bg-[var(--accent-primary)]/10 text-[var(--accent-primary)] font-mono text-[0.9em] italic", "{math}" }
+        InlineSegment::Image(alt, path) => rsx! {
+            LocalImage { key: "{path}", alt, path }
+        },
+        InlineSegment::InlineMath(math) => match tex_to_mathml(&math, false) {
+            Some(mathml) => rsx! {
+                span { class: "text-[var(--text-primary)]", title: "{math}", dangerous_inner_html: "{mathml}" }
+            },
+            None => rsx! {
+                code { class: "px-1.5 py-0.5 rounded-md bg-[var(--accent-primary)]/10 text-[var(--accent-primary)] font-mono text-[0.9em] italic", "{math}" }
+            },
         },
     }
 }
 
-/// Check if content is a tool-related message
-fn is_tool_message(content: &str) -> Option<ToolMessageType> {
-    let trimmed = content.trim();
-
-    // Detect by leading emoji
-    if trimmed.starts_with("🔧") || trimmed.starts_with("Utilisation de l'outil") {
-        return Some(ToolMessageType::InProgress);
+/// Image types the webview displays, by extension
+fn image_mime(path: &str) -> Option<&'static str> {
+    let extension = std::path::Path::new(path).extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "svg" => Some("image/svg+xml"),
+        _ => None,
     }
-    if trimmed.starts_with('⏳') || trimmed.starts_with("Autorisation requise") {
-        return Some(ToolMessageType::PermissionRequired);
-    }
-    if trimmed.starts_with('🚫')
-        || trimmed.starts_with("Permission refusée")
-        || trimmed.starts_with("Demande d'autorisation expirée")
-    {
-        return Some(ToolMessageType::PermissionDenied);
-    }
-    if trimmed.starts_with('✅') {
-        return Some(ToolMessageType::Result);
-    }
-    if trimmed.starts_with("Résultat de `") {
-        return Some(ToolMessageType::Result);
-    }
-    if trimmed.starts_with('❌') || trimmed.starts_with("Erreur pendant l'outil") {
-        return Some(ToolMessageType::Error);
-    }
-    if trimmed.starts_with("Outil introuvable") {
-        return Some(ToolMessageType::NotFound);
+}
+
+/// `src` for an image reference: web URLs as they are, local files inlined
+/// as a data URL since the webview cannot read the disk
+fn image_src(path: &str) -> Option<String> {
+    if path.starts_with("http://") || path.starts_with("https://") || path.starts_with("data:image/") {
+        return Some(path.to_string());
     }
-    if trimmed.starts_with('⏱') {
-        return Some(ToolMessageType::PermissionDenied);
+    let path = path.strip_prefix("file://").unwrap_or(path);
+    let mime = image_mime(path)?;
+    let bytes = std::fs::read(path).ok()?;
+    use base64::Engine;
+    Some(format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(bytes)))
+}
+
+/// Image from a markdown `![alt](path)`, or its alt text if the file is gone
+#[component]
+fn LocalImage(alt: String, path: String) -> Element {
+    // Read once per mount; the segment is keyed by path
+    let src = use_hook(|| image_src(&path));
+
+    match src {
+        Some(src) => rsx! {
+            img {
+                src: "{src}",
+                alt: "{alt}",
+                title: "{path}",
+                class: "inline-block max-w-full max-h-96 my-1 rounded-lg border border-[var(--border-subtle)]",
+            }
+        },
+        None => rsx! {
+            span { class: "text-[var(--text-tertiary)] italic", title: "{path}", "[{alt}]" }
+        },
     }
-    None
+}
+
+/// Status line that replaced the reply text in conversations saved before
+/// tool activity moved to agent events
+fn is_legacy_status_line(content: &str) -> bool {
+    let trimmed = content.trim_start();
+    ["🔧", "⏳", "🚫", "✅", "❌", "⏱", "⏹", "🔒"].iter().any(|emoji| trimmed.starts_with(emoji))
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -853,82 +1392,251 @@ enum ToolMessageType {
     PermissionDenied,
     Result,
     Error,
-    NotFound,
 }
 
-/// Extract tool name from message content (looks for `tool_name` pattern)
-fn extract_tool_name(content: &str) -> Option<String> {
-    if let Some(start) = content.find('`') {
-        if let Some(end) = content[start + 1..].find('`') {
-            return Some(content[start + 1..start + 1 + end].to_string());
+impl ToolMessageType {
+    /// Status of a recorded tool call
+    fn from_call(call: &ToolHistoryEntry) -> Self {
+        match (&call.result, call.error.as_deref()) {
+            (Some(result), _) if result.success => Self::Result,
+            (_, Some(PERMISSION_DENIED_ERROR | READ_ONLY_BLOCKED_ERROR)) => Self::PermissionDenied,
+            _ => Self::Error,
         }
     }
-    None
 }
 
-/// Extract detail text after the tool name section
-fn extract_detail(content: &str) -> Option<String> {
-    // For results: "✅ `tool` (Xs): detail text" -> extract detail text
-    // For permissions: "⏳ Autorisation ... Cible: detail" -> extract Cible value
-    if let Some(pos) = content.find("Cible:") {
-        let after = content[pos + 6..].trim();
-        if !after.is_empty() {
-            return Some(after.to_string());
+/// Card of a finished call, as recorded on the reply that made it
+#[component]
+fn RecordedToolCard(call: ToolHistoryEntry) -> Element {
+    let detail = call.error.clone().or_else(|| {
+        call.result
+            .as_ref()
+            .map(|r| crate::truncate_str(r.message.lines().next().unwrap_or_default(), 200).to_string())
+    });
+    let duration = (call.duration_ms > 0).then(|| format!("{:.1}s", call.duration_ms as f64 / 1000.0));
+    // Full result as the system injects it; the chat history may hold a
+    // truncated or compact copy
+    let output = call
+        .result
+        .as_ref()
+        .map(|r| format_tool_result_for_system(&call.tool_name, r))
+        .or_else(|| call.error.clone());
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let mut undo_status = use_signal(|| None::<String>);
+    let mut undoing = use_signal(|| false);
+
+    // Successful file changes can be rolled back from their backup
+    let undo_path = call
+        .result
+        .as_ref()
+        .filter(|r| r.success && BACKED_UP_TOOLS.contains(&call.tool_name.as_str()))
+        .and_then(|_| call.params["path"].as_str())
+        .map(str::to_string);
+
+    rsx! {
+        ToolCard {
+            message_type: ToolMessageType::from_call(&call),
+            tool_name: call.tool_name.clone(),
+            detail: detail.filter(|d| !d.is_empty()),
+            duration,
+            params: serde_json::to_string_pretty(&call.params).ok(),
         }
-    }
-    // For results with colon after the parenthesis: "✅ `tool` (Xs): the detail"
-    if content.starts_with('✅') {
-        if let Some(paren_close) = content.find("):") {
-            let after = content[paren_close + 2..].trim();
-            if !after.is_empty() {
-                return Some(after.to_string());
+        if let Some(output) = output {
+            ToolOutput { output, locale }
+        }
+        if let Some(path) = undo_path {
+            div { class: "flex items-center gap-2 pl-3 -mt-1",
+                button {
+                    disabled: *undoing.read(),
+                    onclick: move |_| {
+                        let path = path.clone();
+                        undoing.set(true);
+                        spawn(async move {
+                            let outcome = UndoFileChangeTool
+                                .execute(serde_json::json!({ "path": path }))
+                                .await;
+                            undo_status.set(Some(match outcome {
+                                Ok(result) => result.message,
+                                Err(e) => e.to_string(),
+                            }));
+                            undoing.set(false);
+                        });
+                    },
+                    class: "text-[10px] font-medium text-[var(--text-tertiary)] hover:text-[var(--text-primary)] px-2 py-0.5 rounded-md hover:bg-white/[0.04] transition-colors disabled:opacity-40",
+                    title: t!(locale, "message.undo_hint"),
+                    {t!(locale, "message.undo")}
+                }
+                if let Some(status) = undo_status.read().as_ref() {
+                    span { class: "text-[10px] font-mono text-[var(--text-tertiary)] truncate", "{status}" }
+                }
             }
         }
     }
-    None
 }
 
-/// Extract duration string like "(5.3s)" from content
-fn extract_duration(content: &str) -> Option<String> {
-    if let Some(start) = content.find('(') {
-        if let Some(end) = content[start..].find("s)") {
-            let dur = &content[start + 1..start + end];
-            // Check it looks like a number
-            if dur.parse::<f64>().is_ok() {
-                return Some(format!("{}s", dur));
+/// Pieces of `text`, marked where they match `query` regardless of case,
+/// and the number of matches
+fn match_segments(text: &str, query: &str) -> (Vec<(String, bool)>, usize) {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return (vec![(text.to_string(), false)], 0);
+    }
+    let mut segments = Vec::new();
+    let mut count = 0;
+    let mut plain_start = 0;
+    let mut rest = text.char_indices().peekable();
+    while let Some((start, _)) = rest.peek().copied() {
+        // Byte length of a match starting here, if any
+        let mut lowered = text[start..].char_indices().flat_map(|(i, c)| c.to_lowercase().map(move |l| (i + c.len_utf8(), l)));
+        let mut end = None;
+        for (index, wanted) in query.iter().enumerate() {
+            match lowered.next() {
+                Some((char_end, l)) if l == *wanted => {
+                    if index + 1 == query.len() {
+                        end = Some(start + char_end);
+                    }
+                }
+                _ => break,
             }
         }
+        match end {
+            Some(end) => {
+                if plain_start < start {
+                    segments.push((text[plain_start..start].to_string(), false));
+                }
+                segments.push((text[start..end].to_string(), true));
+                count += 1;
+                plain_start = end;
+                while rest.peek().is_some_and(|(i, _)| *i < end) {
+                    rest.next();
+                }
+            }
+            None => {
+                rest.next();
+            }
+        }
+    }
+    if plain_start < text.len() {
+        segments.push((text[plain_start..].to_string(), false));
     }
-    None
+    (segments, count)
 }
 
-/// Extract permission level from content like "(Réseau)" or "(Écriture fichier)"
-#[allow(dead_code)]
-fn extract_permission_level(content: &str) -> Option<String> {
-    // Look for pattern after tool name: `tool` (Level)
-    if let Some(backtick_end) = content.rfind('`') {
-        let after = &content[backtick_end + 1..];
-        if let Some(paren_start) = after.find('(') {
-            if let Some(paren_end) = after[paren_start..].find(')') {
-                let level = &after[paren_start + 1..paren_start + paren_end];
-                if !level.is_empty()
-                    && !level.contains("itération")
-                    && level.parse::<f64>().is_err()
+/// Collapsed disclosure with the output a tool call returned to the model
+#[component]
+fn ToolOutput(output: String, locale: Locale) -> Element {
+    let mut is_open = use_signal(|| false);
+    let mut query = use_signal(String::new);
+    let line_count = output.lines().count();
+
+    rsx! {
+        div { class: "pl-3 -mt-1 mb-2",
+            button {
+                class: "text-[10px] font-medium text-[var(--text-tertiary)] hover:text-[var(--text-primary)] px-2 py-0.5 rounded-md hover:bg-white/[0.04] transition-colors",
+                "aria-expanded": "{is_open()}",
+                onclick: move |_| is_open.set(!is_open()),
+                if is_open() { "▾ " } else { "▸ " }
+                {t!(locale, "message.tool_output", line_count)}
+            }
+            if is_open() {
                 {
-                    return Some(level.to_string());
+                    let (segments, matches) = match_segments(&output, &query());
+                    rsx! {
+                        div { class: "mt-1 rounded-lg border border-[var(--border-subtle)] bg-white/[0.02]",
+                            div { class: "flex items-center gap-2 px-2 py-1 border-b border-[var(--border-subtle)]",
+                                input {
+                                    r#type: "search",
+                                    class: "flex-1 bg-transparent outline-none text-[11px] text-[var(--text-primary)] placeholder:text-[var(--text-tertiary)]",
+                                    placeholder: t!(locale, "message.output_search"),
+                                    "aria-label": t!(locale, "message.output_search_label"),
+                                    value: "{query}",
+                                    oninput: move |evt| query.set(evt.value()),
+                                }
+                                if !query().is_empty() {
+                                    span { class: "text-[10px] tabular-nums text-[var(--text-tertiary)]",
+                                        {t!(locale, "message.output_matches", matches)}
+                                    }
+                                }
+                            }
+                            pre {
+                                class: "max-h-72 overflow-auto custom-scrollbar p-2 text-[11px] font-mono text-[var(--text-secondary)] whitespace-pre-wrap break-all select-text",
+                                for (text, marked) in segments {
+                                    if marked {
+                                        mark {
+                                            class: "px-0.5 rounded bg-[var(--accent-primary-10)] text-[var(--accent-primary)]",
+                                            "{text}"
+                                        }
+                                    } else {
+                                        "{text}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Calls of the current run still waiting or executing, from the agent events
+#[component]
+pub fn ToolActivity() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let events = app_state.agent_events.read();
+    let pending = pending_tool_calls(&events);
+    let progress = latest_progress(&events);
+    let max_retries = app_state.agent.config.loop_config.retry_limit();
+    if pending.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "message-layout",
+            for (index, call) in pending.into_iter().enumerate() {
+                match call {
+                    PendingToolCall::AwaitingPermission { tool, level, target } => rsx! {
+                        ToolCard {
+                            key: "{index}",
+                            message_type: ToolMessageType::PermissionRequired,
+                            tool_name: tool,
+                            detail: Some(format!("{} · {}", level, target)),
+                        }
+                    },
+                    PendingToolCall::Running { tool, params, retry_count } => rsx! {
+                        ToolCard {
+                            key: "{index}",
+                            message_type: ToolMessageType::InProgress,
+                            tool_name: tool,
+                            detail: (retry_count > 0).then(|| format!("{} ({}/{})…", t!(locale, "tool.retrying"), retry_count, max_retries)),
+                            params: serde_json::to_string_pretty(&params).ok(),
+                        }
+                    },
+                }
+            }
+            if let Some((iteration, max_iterations)) = progress {
+                div { class: "text-[10px] font-mono text-[var(--text-tertiary)] pl-2",
+                    {t!(locale, "tool.iteration")} " {iteration}/{max_iterations}"
                 }
             }
         }
     }
-    None
 }
 
 /// Premium tool status card component - ultra minimal design
 #[component]
-fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
-    let tool_name = extract_tool_name(&content).unwrap_or_else(|| "tool".to_string());
-    let detail = extract_detail(&content);
-    let duration = extract_duration(&content);
+fn ToolCard(
+    message_type: ToolMessageType,
+    tool_name: String,
+    detail: Option<String>,
+    duration: Option<String>,
+    // Parameters of the call, shown on hover
+    params: Option<String>,
+) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
 
     // Minimal accent colors using CSS variables
     let (accent_var, status_icon) = match message_type {
@@ -937,7 +1645,6 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
         ToolMessageType::PermissionDenied => ("var(--error)", "○"),
         ToolMessageType::Result => ("var(--success)", "●"),
         ToolMessageType::Error => ("var(--error)", "●"),
-        ToolMessageType::NotFound => ("var(--warning)", "○"),
     };
 
     let show_spinner = message_type == ToolMessageType::InProgress;
@@ -945,6 +1652,16 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
     let is_error =
         message_type == ToolMessageType::Error || message_type == ToolMessageType::PermissionDenied;
 
+    // Spoken state, since the colored dot alone carries no meaning for screen readers
+    let state_label = match message_type {
+        ToolMessageType::InProgress => t!(locale, "tool.running"),
+        ToolMessageType::PermissionRequired => t!(locale, "tool.awaiting_permission"),
+        ToolMessageType::PermissionDenied => t!(locale, "tool.denied"),
+        ToolMessageType::Result => t!(locale, "tool.done"),
+        ToolMessageType::Error => t!(locale, "tool.failed"),
+    };
+    let aria_label = format!("{} : {}", tool_name, state_label);
+
     // Compute duration style outside rsx for type inference
     let duration_style = if is_success {
         "color: var(--success);"
@@ -958,6 +1675,10 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
         div {
             class: "animate-fade-in",
             style: "margin: 0.35rem 0;",
+            role: "status",
+            "aria-label": "{aria_label}",
+            // Parameters of the call, on hover
+            title: params,
 
             // Ultra-minimal single line
             div {
@@ -971,6 +1692,7 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
                 if show_spinner {
                     div {
                         class: "flex items-center gap-0.5",
+                        "aria-hidden": "true",
                         div {
                             class: "w-1 h-1 rounded-full animate-pulse",
                             style: format!("background: {};", accent_var)
@@ -987,6 +1709,7 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
                 } else {
                     span {
                         class: "text-[8px]",
+                        "aria-hidden": "true",
                         style: format!("color: {}; opacity: 0.8;", accent_var),
                         "{status_icon}"
                     }
@@ -1019,44 +1742,233 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
                     }
                 }
             }
+
+            if message_type == ToolMessageType::PermissionRequired {
+                PermissionActions { tool_name: tool_name.clone(), locale }
+            }
         }
     }
 }
 
+/// "Copy text" and "Copy as Markdown", shown while the message is hovered
 #[component]
-pub fn MessageBubble(message: Message) -> Element {
-    let is_user = message.role == MessageRole::User;
+fn CopyActions(text: String, markdown: String) -> Element {
+    rsx! {
+        span { class: "flex items-center gap-3 opacity-0 group-hover:opacity-100 focus-within:opacity-100 transition-opacity",
+            CopyButton { text, label: "copy.text" }
+            CopyButton { text: markdown, label: "copy.markdown" }
+        }
+    }
+}
 
-    // Check if this is a tool-related message
-    if !is_user {
-        if let Some(tool_type) = is_tool_message(&message.content) {
-            return rsx! {
-                div { class: "message-layout",
-                    ToolCard {
-                        message_type: tool_type,
-                        content: message.content.clone()
-                    }
+#[component]
+pub fn MessageBubble(
+    message: Message,
+    on_experiment: Option<EventHandler<()>>,
+    // Replaces the user message with the edited text and resends it
+    on_edit: Option<EventHandler<String>>,
+    // Replaces this reply with a new one, at the chosen temperature if any
+    on_regenerate: Option<EventHandler<Option<f32>>>,
+    // Starts a new conversation from the messages up to this one
+    on_fork: Option<EventHandler<()>>,
+    // Prefix for heading anchors; replies only get an outline when it is set
+    anchor_prefix: Option<String>,
+    // Element id, so search results can scroll to the message
+    dom_id: Option<String>,
+) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let is_user = message.role == MessageRole::User;
+    // Draft of the user message while it is being edited
+    let mut draft = use_signal(|| None::<String>);
+    // Temperature picked for regenerating the reply; none keeps the settings one
+    let mut retry_temperature = use_signal(|| None::<f32>);
+
+    // Tool calls recorded on a reply are drawn as cards under the model's text
+    let tool_calls: Vec<ToolHistoryEntry> = if is_user {
+        Vec::new()
+    } else {
+        message.metadata.as_ref().map(|m| m.tool_calls.clone()).unwrap_or_default()
+    };
+    let reply_text = if tool_calls.is_empty() {
+        message.content.clone()
+    } else if is_legacy_status_line(&message.content) {
+        String::new()
+    } else {
+        strip_tool_calls(&message.content)
+    };
+    if !tool_calls.is_empty() && reply_text.is_empty() {
+        return rsx! {
+            div { class: "message-layout",
+                for (index, call) in tool_calls.into_iter().enumerate() {
+                    RecordedToolCard { key: "{index}", call }
                 }
-            };
-        }
+            }
+        };
     }
 
+    // A user message shows what was typed, its attached files as chips
+    let attachments = if is_user {
+        message.metadata.as_ref().map(|m| m.attachments.clone()).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let user_text = if attachments.is_empty() {
+        message.content.clone()
+    } else {
+        split_attachments(&message.content).0.to_string()
+    };
+
     let content_parts = if !is_user {
-        parse_thinking_blocks(&message.content)
+        parse_thinking_blocks(&reply_text)
     } else {
-        vec![ContentPart::Text(message.content.clone())]
+        vec![ContentPart::Text(user_text.clone())]
     };
 
+    // What the copy actions put on the clipboard: the text, and the same under its export heading
+    let copied_text = if is_user { user_text.trim().to_string() } else { copy_text(&content_parts) };
+    let copied_markdown = message_to_markdown(&crate::types::message::Message::new(
+        if is_user { Role::User } else { Role::Assistant },
+        copied_text.clone(),
+    ))
+    .unwrap_or_default();
+
+    // Reply text is parsed once here: the blocks render below, the headings feed the outline.
+    // Each text part keeps its settled blocks between renders, so a streaming
+    // reply only parses its tail on each update
+    let streaming_blocks = use_hook(|| Rc::new(RefCell::new(Vec::<StreamingBlocks>::new())));
+    let mut anchors = AnchorSet::new(anchor_prefix.as_deref().unwrap_or("msg"));
+    let docs: Vec<Option<MarkdownDoc>> = {
+        let mut parsers = streaming_blocks.borrow_mut();
+        parsers.resize_with(content_parts.len(), StreamingBlocks::default);
+        content_parts
+            .iter()
+            .zip(parsers.iter_mut())
+            .map(|(part, parser)| match part {
+                ContentPart::Text(text) if !is_user => Some(markdown_doc(parser.parse(text), &mut anchors)),
+                _ => None,
+            })
+            .collect()
+    };
+    let outline: Vec<OutlineEntry> = if anchor_prefix.is_some() && message.content.chars().count() >= OUTLINE_MIN_CHARS {
+        docs.iter().flatten().flat_map(|doc| doc.outline.iter().cloned()).collect()
+    } else {
+        Vec::new()
+    };
+
+    // Replies promoted from an experiment show the settings they were sampled with
+    let sampling_note = message.metadata.as_ref().and_then(|metadata| {
+        let sampling = metadata.sampling.as_ref()?;
+        let mut note = format!(
+            "{} · T {:.1} · top-p {:.2} · min-p {:.2}",
+            sampling.label, sampling.temperature, sampling.top_p, sampling.min_p
+        );
+        if !metadata.alternatives.is_empty() {
+            // Same word in both languages
+            note.push_str(&format!(" · {} alternative(s)", metadata.alternatives.len()));
+        }
+        Some(note)
+    });
+
+    // Speed and size of the generation, once it is finished
+    let stats_note = message.metadata.as_ref().and_then(|metadata| {
+        let stats = metadata.stats.as_ref()?;
+        let mut note = format!("{:.1} tok/s · {} tokens", stats.tokens_per_second(), stats.tokens_generated);
+        if let Some(model) = &metadata.model {
+            note.push_str(&format!(" · {}", model));
+        }
+        Some(note)
+    });
+
     if is_user {
         // User message — right-aligned, accent-tinted glass
         rsx! {
-            div { class: "message-layout animate-fade-in-up",
+            div { class: "message-layout group animate-fade-in-up",
+                id: dom_id,
+                role: "article",
+                "aria-label": t!(locale, "message.user"),
                 div { class: "flex justify-end mb-4",
-                    div {
-                        class: "message-user px-4 py-3 max-w-[85%]",
+                    if let (Some(text), Some(on_edit)) = (draft(), on_edit) {
                         div {
-                            class: "text-[15px] leading-relaxed text-[var(--text-primary)]",
-                            "{message.content}"
+                            class: "message-user px-4 py-3 w-[85%] flex flex-col gap-2",
+                            textarea {
+                                class: "w-full bg-transparent outline-none resize-none text-[15px] leading-relaxed text-[var(--text-primary)] custom-scrollbar",
+                                "aria-label": t!(locale, "message.edit_label"),
+                                rows: "{text.lines().count().clamp(2, 10)}",
+                                value: "{text}",
+                                autofocus: true,
+                                oninput: move |evt| draft.set(Some(evt.value())),
+                                onkeydown: move |evt: KeyboardEvent| {
+                                    if evt.key() == Key::Escape {
+                                        draft.set(None);
+                                    }
+                                },
+                            }
+                            div { class: "flex justify-end gap-2",
+                                button {
+                                    class: "btn-ghost text-xs",
+                                    onclick: move |_| draft.set(None),
+                                    {t!(locale, "message.cancel")}
+                                }
+                                button {
+                                    class: "btn-primary text-xs",
+                                    disabled: text.trim().is_empty(),
+                                    onclick: move |_| {
+                                        if let Some(text) = draft.take() {
+                                            on_edit.call(text);
+                                        }
+                                    },
+                                    {t!(locale, "message.save_resend")}
+                                }
+                            }
+                        }
+                    } else {
+                        div {
+                            class: "message-user px-4 py-3 max-w-[85%]",
+                            div {
+                                class: "text-[15px] leading-relaxed text-[var(--text-primary)]",
+                                "{user_text}"
+                            }
+                            if !attachments.is_empty() {
+                                div { class: "flex flex-wrap gap-1.5 mt-2",
+                                    for attachment in attachments {
+                                        span {
+                                            key: "{attachment.path.display()}",
+                                            class: "inline-flex items-center gap-1.5 max-w-[240px] px-2 py-0.5 rounded-full bg-white/[0.06] text-[11px] text-[var(--text-secondary)]",
+                                            title: "{attachment.path.display()} ({attachment.mime})",
+                                            span { class: "truncate", "{display_name(&attachment)}" }
+                                            span { class: "text-[var(--text-tertiary)]", "{format_size(attachment.size)}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if draft().is_none() {
+                    div { class: "flex justify-end gap-3 -mt-3 mb-3",
+                        CopyActions { text: copied_text, markdown: copied_markdown }
+                        if on_edit.is_some() {
+                            button {
+                                class: "text-[11px] text-[var(--text-tertiary)] hover:text-[var(--accent-primary)] transition-colors",
+                                title: t!(locale, "message.edit_hint"),
+                                onclick: {
+                                    let content = user_text.clone();
+                                    move |_| draft.set(Some(content.clone()))
+                                },
+                                {t!(locale, "message.edit")}
+                            }
+                        }
+                        if let Some(on_experiment) = on_experiment {
+                            button {
+                                class: "text-[11px] text-[var(--text-tertiary)] hover:text-[var(--accent-primary)] transition-colors",
+                                title: t!(locale, "message.experiment_hint"),
+                                onclick: move |_| on_experiment.call(()),
+                                {t!(locale, "message.experiment")}
+                            }
+                        }
+                        if let Some(on_fork) = on_fork {
+                            ForkButton { on_fork, locale }
                         }
                     }
                 }
@@ -1065,11 +1977,15 @@ pub fn MessageBubble(message: Message) -> Element {
     } else {
         // Assistant message — with small avatar, no bubble
         rsx! {
-            div { class: "message-layout animate-fade-in-up",
+            div { class: "message-layout group animate-fade-in-up",
+                id: dom_id,
+                role: "article",
+                "aria-label": t!(locale, "message.assistant"),
                 div { class: "flex items-start gap-3 mb-4",
                     // LocalClaw avatar — small circle with gradient
                     div {
                         class: "flex-shrink-0 w-6 h-6 rounded-full flex items-center justify-center mt-1",
+                        "aria-hidden": "true",
                         style: "background: var(--accent-primary); box-shadow: 0 4px 12px -4px var(--accent-glow);",
                         svg {
                             class: "w-3 h-3",
@@ -1087,22 +2003,208 @@ pub fn MessageBubble(message: Message) -> Element {
                     // Content
                     div {
                         class: "flex-1 min-w-0",
-                        for part in content_parts {
-                            match part {
-                                ContentPart::Thinking(text) => rsx! {
+                        if outline.len() >= 2 {
+                            MessageOutline { entries: outline, locale }
+                        }
+                        for (part, doc) in content_parts.into_iter().zip(docs) {
+                            match (part, doc) {
+                                (ContentPart::Thinking(text), _) => rsx! {
                                     ThinkingBlock { content: text }
                                 },
-                                ContentPart::ThinkingStreaming(text) => rsx! {
+                                (ContentPart::ThinkingStreaming(text), _) => rsx! {
                                     ThinkingBlockStreaming { content: text }
                                 },
-                                ContentPart::Text(text) => rsx! {
+                                (_, Some(doc)) => rsx! {
+                                    MarkdownDocument { doc }
+                                },
+                                (ContentPart::Text(text), None) => rsx! {
                                     MarkdownContent { content: text }
                                 },
                             }
                         }
+                        for (index, call) in tool_calls.into_iter().enumerate() {
+                            RecordedToolCard { key: "{index}", call }
+                        }
+                        if let Some(note) = sampling_note {
+                            div { class: "mt-1 text-[10px] font-mono text-[var(--text-tertiary)]", "{note}" }
+                        }
+                        if let Some(note) = stats_note {
+                            div {
+                                class: "mt-1 text-[10px] font-mono text-[var(--text-tertiary)]",
+                                title: t!(locale, "message.stats_hint"),
+                                "{note}"
+                            }
+                        }
+                        if on_regenerate.is_some() || on_fork.is_some() || !copied_text.is_empty() {
+                            div { class: "mt-1 flex items-center gap-2",
+                                if !copied_text.is_empty() {
+                                    CopyActions { text: copied_text, markdown: copied_markdown }
+                                }
+                                if let Some(on_regenerate) = on_regenerate {
+                                    button {
+                                        class: "text-[11px] text-[var(--text-tertiary)] hover:text-[var(--accent-primary)] transition-colors",
+                                        title: t!(locale, "message.regenerate_hint"),
+                                        onclick: move |_| on_regenerate.call(retry_temperature()),
+                                        {t!(locale, "message.regenerate")}
+                                    }
+                                    select {
+                                        class: "text-[11px] bg-transparent text-[var(--text-tertiary)] outline-none cursor-pointer",
+                                        "aria-label": t!(locale, "message.temperature"),
+                                        onchange: move |evt| retry_temperature.set(evt.value().parse::<f32>().ok()),
+                                        option { value: "", {t!(locale, "message.same_temperature")} }
+                                        for temperature in RETRY_TEMPERATURES {
+                                            option { value: "{temperature}", "T {temperature:.1}" }
+                                        }
+                                    }
+                                }
+                                if let Some(on_fork) = on_fork {
+                                    ForkButton { on_fork, locale }
+                                }
+                            }
+                        }
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_images_and_links() {
+        let segments = parse_inline_markdown("See ![screenshot](/data/assets/a.png) and [docs](https://x.y)!");
+        assert!(matches!(&segments[1], InlineSegment::Image(alt, path) if alt == "screenshot" && path == "/data/assets/a.png"));
+        assert!(matches!(&segments[3], InlineSegment::Link(text, url) if text == "docs" && url == "https://x.y"));
+        assert!(matches!(&segments[4], InlineSegment::Text(text) if text == "!"));
+        assert!(matches!(&parse_inline_markdown("![unclosed](a.png")[..], [InlineSegment::Text(_)]));
+
+        assert_eq!(image_mime("shot.PNG"), Some("image/png"));
+        assert_eq!(image_mime("notes.txt"), None);
+        assert_eq!(image_src("https://x.y/a.png").as_deref(), Some("https://x.y/a.png"));
+        assert_eq!(image_src("/no/such/file.png"), None);
+    }
+
+    #[test]
+    fn test_outline_comes_from_parsed_headings() {
+        let mut anchors = AnchorSet::new("msg-3");
+        let blocks = StreamingBlocks::default().parse("# Report\n\nIntro\n\n## Results\n\ntext\n\n## Results\n\n```\n# not a heading\n```");
+        let doc = markdown_doc(blocks, &mut anchors);
+        let outline: Vec<(u8, &str)> = doc.outline.iter().map(|e| (e.level, e.anchor.as_str())).collect();
+        assert_eq!(outline, vec![(1, "msg-3-report"), (2, "msg-3-results"), (2, "msg-3-results-2")]);
+    }
+
+    #[test]
+    fn test_math_blocks_and_spans() {
+        let blocks = parse_markdown_blocks("$$\\frac{a}{b}$$\n\n$$\nx^2\n$$\n\ntext");
+        assert_eq!(blocks[0], MarkdownBlock::MathBlock("\\frac{a}{b}".into(), true));
+        assert_eq!(blocks[1], MarkdownBlock::MathBlock("x^2".into(), true));
+        assert_eq!(blocks[2], MarkdownBlock::Paragraph("text".into()));
+        // Mid-stream, the open block is not rendered yet
+        let streaming = parse_markdown_blocks("Before\n\n$$\n\\sum_{i=1}");
+        assert_eq!(streaming[1], MarkdownBlock::MathBlock("\\sum_{i=1}".into(), false));
+
+        assert!(matches!(&parse_inline_markdown("Area $\\pi r^2$.")[1], InlineSegment::InlineMath(m) if m == "\\pi r^2"));
+        assert!(parse_inline_markdown("From $5 to $10, or $ 3 $")
+            .iter()
+            .all(|s| matches!(s, InlineSegment::Text(_))));
+    }
+
+    fn item(text: &str, children: Vec<MarkdownBlock>) -> ListItem {
+        ListItem { children, ..ListItem::new(text) }
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        let blocks = parse_markdown_blocks(
+            "- fruits\n  1. apple\n  2. pear\n     - ripe\n       wrapped\n- vegetables\n\t* leek\n\n1. first\n   - note\n2. second",
+        );
+        let ripe = MarkdownBlock::UnorderedList(vec![ListItem::new("ripe wrapped")]);
+        let fruits = MarkdownBlock::OrderedList(vec![item("apple", vec![]), item("pear", vec![ripe])]);
+        let vegetables = MarkdownBlock::UnorderedList(vec![ListItem::new("leek")]);
+        assert_eq!(
+            blocks[0],
+            MarkdownBlock::UnorderedList(vec![item("fruits", vec![fruits]), item("vegetables", vec![vegetables])])
+        );
+        assert_eq!(
+            blocks[1],
+            MarkdownBlock::OrderedList(vec![
+                item("first", vec![MarkdownBlock::UnorderedList(vec![ListItem::new("note")])]),
+                item("second", vec![]),
+            ])
+        );
+        assert_eq!(blocks.len(), 2);
+
+        // A number followed by text is not a list
+        assert_eq!(parse_markdown_blocks("3.5 is not an item. Right"), vec![MarkdownBlock::Paragraph("3.5 is not an item. Right".into())]);
+    }
+
+    #[test]
+    fn test_task_lists() {
+        let blocks = parse_markdown_blocks("- [x] read the code\n- [ ] write tests\n  - [X] parser\n- [x](y) stays plain");
+        let MarkdownBlock::UnorderedList(items) = &blocks[0] else { panic!("not a list") };
+        assert_eq!(items[0].kind, ListItemKind::Task { checked: true });
+        assert_eq!(items[0].text, "read the code");
+        assert_eq!(items[1].kind, ListItemKind::Task { checked: false });
+        let MarkdownBlock::UnorderedList(nested) = &items[1].children[0] else { panic!("not a list") };
+        assert_eq!(nested[0].kind, ListItemKind::Task { checked: true });
+        assert_eq!(items[2].kind, ListItemKind::Plain);
+        assert_eq!(items[2].text, "[x](y) stays plain");
+    }
+
+    #[test]
+    fn test_anchors_stable_while_streaming() {
+        let mut parser = StreamingBlocks::default();
+        let partial = markdown_doc(parser.parse("## Étape 1 : setup\n\nfoo"), &mut AnchorSet::new("m"));
+        let full = markdown_doc(parser.parse("## Étape 1 : setup\n\nfoo\n\n## Étape 2"), &mut AnchorSet::new("m"));
+        assert_eq!(partial.outline[0].anchor, "m-étape-1-setup");
+        assert_eq!(full.outline[0], partial.outline[0]);
+        assert_eq!(AnchorSet::new("m").next("!!!"), "m-section");
+    }
+
+    #[test]
+    fn test_tool_output_search() {
+        let (segments, count) = match_segments("Error: file.RS\nerror again", "error");
+        assert_eq!(count, 2);
+        assert_eq!(
+            segments,
+            vec![("Error".to_string(), true), (": file.RS\n".to_string(), false), ("error".to_string(), true), (" again".to_string(), false)]
+        );
+        assert_eq!(match_segments("Été été", "ÉTÉ").1, 2);
+        assert_eq!(match_segments("aaa", "aa").1, 1);
+        assert_eq!(match_segments("text", ""), (vec![("text".to_string(), false)], 0));
+    }
+
+    #[test]
+    fn test_copy_text_skips_thinking() {
+        let parts = parse_thinking_blocks("<think>plan it</think>\n\nFirst part\n<think>again</think>Second part");
+        assert_eq!(copy_text(&parts), "First part\n\nSecond part");
+        assert_eq!(copy_text(&parse_thinking_blocks("<think>still going")), "");
+    }
+
+    #[test]
+    fn test_streaming_parse_matches_full_parse() {
+        let reply = "# Plan\n\nIntro *text*\nwrapped\n\n```rust\nfn main() {\n\n}\n```\n\n$$\nx^2\n\ny\n$$\n\n\
+            - one\n  - [x] nested\n1. other\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n> quote\n> more\n---\n#######\nend";
+        let mut parser = StreamingBlocks::default();
+        for (end, _) in reply.char_indices().skip(1) {
+            let text = &reply[..end];
+            let streamed: Vec<MarkdownBlock> = parser.parse(text).into_iter().map(|(_, block)| block).collect();
+            assert_eq!(streamed, parse_markdown_blocks(text), "after {:?}", text);
+        }
+        assert_eq!(parser.parse(reply).len(), parse_markdown_blocks(reply).len());
+        assert!(parser.settled.len() > 5);
+
+        // A table row cut mid-line is dropped until its closing pipe arrives
+        let mut parser = StreamingBlocks::default();
+        parser.parse("text\n\n| a |\n| b");
+        let blocks = parser.parse("text\n\n| a |\n| b |");
+        assert_eq!(blocks[1], (6, MarkdownBlock::Table(vec![vec!["b".into()]], vec!["a".into()])));
+
+        // Other text starts over
+        let blocks = parser.parse("new\n\nreply");
+        assert_eq!(blocks, vec![(0, MarkdownBlock::Paragraph("new".into())), (5, MarkdownBlock::Paragraph("reply".into()))]);
+    }
+}
@@ -3,378 +3,211 @@
 //! Contains the main chat view, message display, and input components.
 //! Implements an advanced agentic loop inspired by Claude Code and OpenCode.
 
+pub mod attachments;
+pub mod experiment;
 pub mod input;
+pub mod math;
 pub mod message;
+pub mod queue;
+pub mod scroll;
+pub mod voice;
 
 use dioxus::prelude::*;
+use experiment::ExperimentPanel;
 use input::ChatInput;
-use message::{Message, MessageBubble, MessageRole};
+use message::{Message, MessageBubble, MessageRole, ToolActivity};
+use queue::{Draft, SendOutcome};
+use scroll::{ChatScroll, CHAT_SCROLL_ID};
 use std::sync::atomic::Ordering;
 
 use crate::agent::{
-    extract_tool_call,
-    format_tool_result_for_system,
+    extract_tool_calls,
+    format_tool_result,
+    call_preview,
+    get_call_permission,
     get_tool_permission,
+    needs_policy_approval,
+    is_tool_allowed_read_only,
     PermissionRequest,
     PermissionResult,
     PermissionDecision,
     AgentContext,
+    AgentEvent,
     AgentState,
+    AgentStrings,
+    EnvironmentContext,
+    Locale,
+    detect_language,
+    set_tool_language,
 };
-use crate::agent::loop_runner::ToolHistoryEntry;
-use crate::agent::tools::ToolResult;
-use crate::agent::prompts::build_agent_system_prompt;
-use crate::agent::prompts::build_reflection_prompt;
+use crate::agent::compression::{apply_hierarchical_compression_llm, get_compression_tier, prune_long_system_messages, CompressionTier};
+use crate::agent::loop_runner::{
+    ensure_pending_reply, has_stray_empty_replies, persisted_messages, prune_empty_replies, push_notice, retry_tool_call,
+    AnchorReason, LoopMessage, ToolHistoryEntry, PERMISSION_DENIED_ERROR, READ_ONLY_BLOCKED_ERROR,
+};
+use crate::agent::replay::{recordings_dir, save_recording, RunRecorder};
+use crate::agent::prompt_builder::{prompt_budget, PromptBuilder};
+use crate::agent::runner::{continuation_hint, find_fabricated_tool_output, is_garbage_text, looks_like_malformed_tool_call, parallel_batch_len, ToolCall, TOOL_CALL_STOP_SEQUENCES};
+use crate::agent::tools::{Tool, ToolError, ToolRegistry, ToolResult};
+use crate::agent::tools::filesystem::preview_change;
+use crate::agent::tools::schema::validate_tool_params;
+use crate::agent::prompts::{build_agent_system_prompt, build_memory_digest, build_tool_call_grammar, ThinkingGuidance};
+use crate::agent::prompts::build_error_reflection_prompt;
 use crate::agent::prompts::build_context_compression_prompt;
 use crate::agent::prompts::build_title_generation_prompt;
 use crate::app::{AppState, ModelState};
-use crate::inference::engine::GenerationParams;
-use crate::inference::streaming::StreamToken;
-use crate::storage::conversations::save_conversation;
-use crate::types::message::{Message as StorageMessage, Role as StorageRole};
+use crate::ui::components::command_palette::{use_command, Command};
+use crate::ui::i18n::t;
+use crate::inference::engine::{EngineError, GenerationParams};
+use crate::inference::metrics::EngineMetrics;
+use crate::inference::reasoning;
+use crate::inference::tokens::TokenCount;
+use crate::inference::streaming::{collect_stream_text, StreamToken};
+use crate::storage::conversations::{fork_conversation, list_conversations, save_conversation};
+use crate::storage::memory::update_memory;
+use crate::storage::settings::{default_system_prompt_for_lang, ToolCallFormat, ToolResultRole, ToolResultVerbosity};
+use crate::types::message::{Message as StorageMessage, MessageMetadata, NoticeKind, Role as StorageRole};
 use chrono::Utc;
+use futures::future::join_all;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Detect if generated text is garbage/corrupted (model hallucinating)
-fn is_garbage_text(content: &str) -> bool {
-    let lower = content.to_lowercase();
-    
-    // Patterns that indicate model is generating fake tool outputs
-    let garbage_patterns = [
-        "assistantcommentary",
-        "userresponse",
-        "toolresult:",
-        "✅ pdf_read:",
-        "✅ file_read:",
-        "contenu du pdf:",
-    ];
-    
-    for pattern in garbage_patterns {
-        if lower.matches(pattern).count() > 3 {
-            tracing::warn!("Garbage detected: pattern '{}' repeated", pattern);
-            return true;
-        }
-    }
-    
-    // Check for abnormal word/char ratio (text stuck together without spaces)
-    let words = content.split_whitespace().count();
-    if content.len() > 300 && words > 0 {
-        let avg_word_len = content.len() / words;
-        if avg_word_len > 25 {
-            tracing::warn!("Garbage detected: abnormal word length ratio {}", avg_word_len);
-            return true;
-        }
-    }
-    
-    // Check for excessive repetition of any 10+ char sequence
-    if content.len() > 200 {
-        let chunks: Vec<&str> = content.as_bytes()
-            .chunks(20)
-            .filter_map(|c| std::str::from_utf8(c).ok())
-            .collect();
-        if chunks.len() > 5 {
-            let first = chunks[0];
-            let repeat_count = chunks.iter().filter(|c| *c == &first).count();
-            if repeat_count > 3 {
-                tracing::warn!("Garbage detected: repeated chunk pattern");
-                return true;
-            }
-        }
+/// Context size compression thresholds are measured against.
+///
+/// In auto mode that is what the engine can actually grow to, once known.
+fn context_budget(params: &GenerationParams, metrics: &EngineMetrics) -> usize {
+    let limit = metrics.context_limit();
+    if params.auto_context && limit > 0 {
+        limit.min(params.max_context_size) as usize
+    } else {
+        params.max_context_size as usize
     }
-    
-    false
 }
 
-/// Estimate token count from message content (~4 chars per token)
-#[allow(dead_code)]
+/// What an agent run starts from
+enum RunStart {
+    /// A new user message
+    Send(Draft),
+    /// The last user message again, its previous reply dropped
+    Regenerate { temperature: Option<f32> },
+}
+
+/// Index where the last turn's reply starts, if the last user message has one
+fn last_reply_start(messages: &[Message]) -> Option<usize> {
+    let user = messages.iter().rposition(|m| m.role == MessageRole::User)?;
+    (user + 1 < messages.len()).then_some(user + 1)
+}
+
+/// Token count of the messages: cached tokenizer counts, else ~4 chars per token
 fn estimate_tokens(messages: &[Message]) -> usize {
-    messages.iter().map(|m| m.content.len() / 4).sum()
+    messages.iter().map(|m| m.tokens.get_or_estimate(&m.content)).sum()
 }
 
-// ============================================================================
-// 3-TIER HIERARCHICAL CONTEXT COMPRESSION (LoCoBench-Agent / Cursor pattern)
-// ============================================================================
-
-/// Context threshold for Working memory tier (40% of max context)
-/// At this tier, only selective pruning is applied (observation masking)
-pub const WORKING_THRESHOLD: f32 = 0.40;
-
-/// Context threshold for Compressed memory tier (60% of max context)
-/// At this tier, incremental summarization is applied
-pub const COMPRESSED_THRESHOLD: f32 = 0.60;
-
-/// Context threshold for Archived memory tier (80% of max context)
-/// At this tier, aggressive truncation keeping anchors + last 2 messages
-pub const ARCHIVED_THRESHOLD: f32 = 0.80;
-
-/// Compression tier based on context usage level
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CompressionTier {
-    /// Working memory: 0-40% context used - selective pruning only
-    Working,
-    /// Compressed: 40-60% context used - incremental summarization
-    Compressed,
-    /// Archived: 60-80% context used - aggressive truncation
-    Archived,
-    /// Critical: >80% context - fallback to existing compression
-    Critical,
+/// Ask the engine for the Compressed tier's summary of dropped messages
+async fn summarize_for_compression(app_state: &AppState, request: String, params: GenerationParams) -> Result<String, EngineError> {
+    // Release the engine lock before draining so Stop stays responsive
+    let (rx, stop) = {
+        let engine = app_state.engine.lock().await;
+        engine.generate_stream_messages(vec![StorageMessage::new(StorageRole::User, request)], params)?
+    };
+    Ok(collect_stream_text(rx, &stop, &app_state.stop_signal).await.0)
 }
 
-impl CompressionTier {
-    /// Get tier name for logging
-    pub fn name(&self) -> &'static str {
-        match self {
-            CompressionTier::Working => "Working",
-            CompressionTier::Compressed => "Compressed",
-            CompressionTier::Archived => "Archived",
-            CompressionTier::Critical => "Critical",
-        }
-    }
+/// Element id of the message at `index`, for scrolling to it
+fn message_dom_id(index: usize) -> String {
+    format!("message-{}", index)
 }
 
-/// Determine the current compression tier based on context usage
-/// 
-/// # Arguments
-/// * `current_tokens` - Estimated current token count
-/// * `max_tokens` - Maximum available context tokens
-/// 
-/// # Returns
-/// The appropriate CompressionTier based on usage percentage
-pub fn get_compression_tier(current_tokens: usize, max_tokens: usize) -> CompressionTier {
-    if max_tokens == 0 {
-        return CompressionTier::Critical;
-    }
-    
-    let usage_ratio = current_tokens as f32 / max_tokens as f32;
-    
-    if usage_ratio <= WORKING_THRESHOLD {
-        CompressionTier::Working
-    } else if usage_ratio <= COMPRESSED_THRESHOLD {
-        CompressionTier::Compressed
-    } else if usage_ratio <= ARCHIVED_THRESHOLD {
-        CompressionTier::Archived
+/// Validate and run one tool call under the per-tool timeout and the registry's limits, timing it
+///
+/// Transient failures are retried up to `max_retries` times with backoff,
+/// each one reported on `events`. Stop cancels `cancel`, which ends the call
+/// at once with `ToolError::Cancelled`.
+async fn run_tool_call(
+    registry: &ToolRegistry,
+    tool: Arc<dyn Tool>,
+    call: &ToolCall,
+    timeout_secs: u64,
+    max_retries: usize,
+    cancel: &CancellationToken,
+    events: &UnboundedSender<AgentEvent>,
+) -> (Result<ToolResult, ToolError>, u64) {
+    let start = Instant::now();
+    let result = if let Err(e) = validate_tool_params(tool.as_ref(), &call.params) {
+        // Bad params go straight to the error/reflection path without executing
+        Err(e)
     } else {
-        CompressionTier::Critical
-    }
+        let attempt = || {
+            let execution = tokio::time::timeout(
+                Duration::from_secs(timeout_secs),
+                registry.execute_tool(tool.as_ref(), call.params.clone(), cancel.clone()),
+            );
+            async move {
+                tokio::select! {
+                    result = execution => match result {
+                        Ok(result) => result,
+                        Err(_) => Err(ToolError::Timeout { after_secs: Some(timeout_secs) }),
+                    },
+                    _ = cancel.cancelled() => Err(ToolError::Cancelled),
+                }
+            }
+        };
+        retry_tool_call(max_retries, cancel, attempt, |e, retry_count| {
+            tracing::info!("Retrying {} ({}/{}) after: {}", call.tool, retry_count, max_retries, e);
+            let _ = events.send(AgentEvent::ToolCallFailed {
+                tool: call.tool.clone(),
+                error: e.to_string(),
+                retry_count,
+                will_retry: true,
+            });
+        })
+        .await
+    };
+    (result, start.elapsed().as_millis() as u64)
 }
 
-/// Apply observation masking: Replace old tool results with brief placeholders
-/// This is a zero-cost operation (no LLM needed) that reduces context while
-/// preserving the fact that tools were executed.
-/// 
-/// # Arguments
-/// * `messages` - Mutable reference to message Vec
-/// * `keep_count` - Number of recent tool results to preserve (default: 3)
-/// 
-/// # Returns
-/// Number of characters saved by masking
-pub fn apply_observation_masking(messages: &mut Vec<Message>, keep_count: usize) -> usize {
-    let mut chars_saved = 0;
-    let mut tool_result_indices: Vec<(usize, String)> = Vec::new();
-    
-    // Find all tool result messages (typically system messages with tool output)
-    for (idx, msg) in messages.iter_mut().enumerate() {
-        let role = &msg.role;
-        let content = &msg.content;
-        
-        // Identify tool result messages - look for common tool prefixes
-        let is_tool_result = *role == MessageRole::System && (
-            content.contains("file_read") ||
-            content.contains("tool_result") ||
-            content.contains("executed:") ||
-            content.contains("Output:")
-        );
-        
-        if is_tool_result && content.len() > 150 {
-            // Extract tool name for placeholder
-            let tool_name = content.lines()
-                .next()
-                .unwrap_or("tool")
-                .split(':')
-                .next()
-                .unwrap_or("tool")
-                .trim()
-                .to_string();
-            
-            tool_result_indices.push((idx, tool_name));
-        }
-    }
-    
-    // Mask all but the most recent tool results
-    let preserve_count = keep_count.min(tool_result_indices.len());
-    for (idx, (_, tool_name)) in tool_result_indices.iter()
-        .rev()
-        .skip(preserve_count)
-        .enumerate()
-    {
-        let msg_idx = tool_result_indices[idx].0;
-        if let Some(msg) = messages.get_mut(msg_idx) {
-            let original_len = msg.content.len();
-            let placeholder = format!(
-                "[Tool result for {} omitted for brevity - see earlier context]",
-                tool_name
-            );
-            chars_saved += original_len - placeholder.len();
-            msg.content = placeholder;
-        }
+/// Record a finished call on the reply that asked for it; the card is drawn from it
+fn record_on_reply(msgs: &mut [Message], reply_index: usize, entry: ToolHistoryEntry) {
+    if let Some(reply) = msgs.get_mut(reply_index) {
+        reply.record_tool_call(entry);
     }
-    
-    chars_saved
 }
 
-/// Apply hierarchical context compression based on the current tier
-/// 
-/// This implements the 3-tier approach from LoCoBench-Agent:
-/// - Working (0-40%): Selective pruning (observation masking)
-/// - Compressed (40-60%): Incremental summarization
-/// - Archived (60-80%): Aggressive truncation with anchors
-/// 
-/// # Arguments
-/// * `messages` - Mutable reference to message Vec
-/// * `current_tokens` - Estimated current token count
-/// * `max_tokens` - Maximum available context tokens
-/// * `anchor_messages` - Critical info to preserve from AgentContext
-/// 
-/// # Returns
-/// Tuple of (characters_saved, whether compression was applied)
-pub fn apply_hierarchical_compression(
-    messages: &mut Vec<Message>,
-    current_tokens: usize,
-    max_tokens: usize,
-    anchor_messages: &[(String, String)], // (content, reason)
-) -> (usize, bool) {
-    let tier = get_compression_tier(current_tokens, max_tokens);
-    
-    tracing::info!(
-        "Hierarchical compression: tier={} ({}% context, {}/{} tokens)",
-        tier.name(),
-        if max_tokens > 0 { (current_tokens as f32 / max_tokens as f32 * 100.0) as usize } else { 0 },
-        current_tokens,
-        max_tokens
-    );
-    
-    let mut total_saved = 0usize;
-    
-    match tier {
-        CompressionTier::Working => {
-            // Tier 1: Selective pruning only - zero-cost observation masking
-            let saved = apply_observation_masking(messages, 3);
-            total_saved += saved;
-            
-            if saved > 0 {
-                tracing::info!("Tier 1 (Working): Observation masking saved {} chars", saved);
-            }
-        }
-        
-        CompressionTier::Compressed => {
-            // Tier 2: Incremental summarization approach
-            // First apply observation masking, then truncate old messages
-            let saved_masking = apply_observation_masking(messages, 2);
-            total_saved += saved_masking;
-            
-            // Keep: last 3 messages + system prompt + anchor messages
-            let msg_count = messages.len();
-            let preserve_count = 4.min(msg_count); // Last 3 + potential system
-            
-            if msg_count > preserve_count + 2 {
-                // Create summary placeholder for middle messages
-                let middle_count = msg_count - preserve_count - 1;
-                
-                // Truncate old messages beyond anchors
-                // Keep system (if exists), recent messages
-                let system_msg = messages.first()
-                    .filter(|m| m.role == MessageRole::System)
-                    .cloned();
-                
-                let recent: Vec<_> = messages.iter()
-                    .rev()
-                    .take(preserve_count)
-                    .cloned()
-                    .collect();
-                
-                // Build new message list with summary
-                let summary_msg = Message {
-                    role: MessageRole::System,
-                    content: format!(
-                        "[{} messages compressed via incremental summarization]",
-                        middle_count
-                    ),
-                };
-                
-                messages.clear();
-                if let Some(sys) = system_msg {
-                    messages.push(sys);
-                }
-                messages.push(summary_msg);
-                messages.extend(recent.into_iter().rev());
-                
-                let saved_truncate = msg_count * 200; // Rough estimate
-                total_saved += saved_truncate;
-                
-                tracing::info!(
-                    "Tier 2 (Compressed): {} msgs summarized, {} total chars saved",
-                    middle_count,
-                    total_saved
-                );
-            }
-        }
-        
-        CompressionTier::Archived | CompressionTier::Critical => {
-            // Tier 3: Aggressive truncation - keep anchors + last 2 messages
-            let msg_count = messages.len();
-            
-            // Preserve: last 2 messages + anchor messages as system notes
-            let keep_recent = 2.min(msg_count);
-            
-            // Build anchor content
-            let anchor_content: String = if !anchor_messages.is_empty() {
-                format!(
-                    "\n\n[ANCHORED CONTEXT - PRESERVED]\n{}",
-                    anchor_messages
-                        .iter()
-                        .map(|(content, reason)| format!("- {}: {}", reason, content))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                )
-            } else {
-                String::new()
-            };
-            
-            // Get last messages
-            let recent: Vec<_> = messages.iter()
-                .rev()
-                .take(keep_recent)
-                .cloned()
-                .collect();
-            
-            // Clear and rebuild with anchors
-            messages.clear();
-            
-            if !anchor_content.is_empty() {
-                messages.push(Message {
-                    role: MessageRole::System,
-                    content: format!(
-                        "[{} previous messages archived - critical context preserved]{}",
-                        msg_count.saturating_sub(keep_recent),
-                        anchor_content
-                    ),
-                });
-            }
-            
-            messages.extend(recent.into_iter().rev());
-            
-            let saved_archived = msg_count * 300; // Rough estimate
-            total_saved += saved_archived;
-            
-            tracing::info!(
-                "Tier 3 (Archived): Aggressive truncation, {} total chars saved",
-                total_saved
-            );
+/// Record a successful call, then inject its result for the model (capped to prevent context overflow)
+fn push_tool_success(
+    msgs: &mut Vec<Message>,
+    reply_index: usize,
+    entry: ToolHistoryEntry,
+    result: &ToolResult,
+    verbosity: ToolResultVerbosity,
+    result_role: MessageRole,
+    strings: &AgentStrings,
+) {
+    let tool = entry.tool_name.clone();
+    record_on_reply(msgs, reply_index, entry);
+
+    let tool_result_text = format_tool_result(&tool, result, verbosity);
+    let tool_result_text = if tool_result_text.len() > 4000 {
+        let truncated: String = tool_result_text.chars().take(3500).collect();
+        let mut text = format!("{}...\n{}", truncated, strings.result_truncated(tool_result_text.len()));
+        // The paging hint sits at the end; keep it so the model can read on
+        if let Some(hint) = continuation_hint(&tool, result) {
+            text.push('\n');
+            text.push_str(&hint);
         }
-    }
-    
-    (total_saved, total_saved > 0)
+        text
+    } else {
+        tool_result_text
+    };
+    msgs.push(Message {
+        role: result_role,
+        content: tool_result_text,
+        metadata: None,
+        tokens: TokenCount::default(),
+    });
 }
 
 #[component]
@@ -421,92 +254,373 @@ pub fn ChatView() -> Element {
         });
     }
 
-    // Handler for sending a message
-    let handle_send = {
+    // Scroll anchoring: follow new content only from the bottom of the list,
+    // otherwise offer a jump down; the position outlives this view
+    let mut chat_scroll = app_state.chat_scroll;
+    let mut has_unseen = use_signal(|| false);
+    {
+        use_effect(move || {
+            spawn(async move {
+                let mut scrolled = document::eval(&scroll::scroll_listener_js());
+                while let Ok((top, height, visible)) = scrolled.recv::<(f64, f64, f64)>().await {
+                    chat_scroll.write().update(top, height, visible);
+                    if chat_scroll.peek().following && *has_unseen.peek() {
+                        has_unseen.set(false);
+                    }
+                }
+            });
+        });
+
+        let current_conversation = app_state.current_conversation;
+        let scroll_to_message = app_state.scroll_to_message;
+        let mut restored = use_signal(|| false);
+        use_effect(move || {
+            let _ = messages.read();
+            let conversation_id = current_conversation.read().as_ref().map(|c| c.id.clone());
+            // Where to move the list, if anywhere: the bottom (`None`) or an offset
+            let target = {
+                let mut scroll = chat_scroll.write();
+                if scroll.conversation_id != conversation_id {
+                    // Another conversation opens at its end, unless a search hit is waiting
+                    *scroll = ChatScroll { conversation_id, ..ChatScroll::default() };
+                    scroll.following = scroll_to_message.peek().is_none();
+                    has_unseen.set(false);
+                }
+                if scroll.following {
+                    Some(None)
+                } else if !*restored.peek() {
+                    // Back from another view: where the user left the list
+                    Some(Some(scroll.offset))
+                } else {
+                    None
+                }
+            };
+            restored.set(true);
+            match target {
+                Some(offset) => {
+                    spawn(async move {
+                        let _ = document::eval(&scroll::scroll_js(offset)).await;
+                    });
+                }
+                None if !*has_unseen.peek() => has_unseen.set(true),
+                None => {}
+            }
+        });
+    }
+
+    use_command(|| {
+        let label = t!(app_state.settings.peek().locale(), "chat.jump_to_latest");
+        Command::new("chat-jump-to-bottom", label, move || {
+            let (mut chat_scroll, mut has_unseen) = (chat_scroll, has_unseen);
+            chat_scroll.write().following = true;
+            has_unseen.set(false);
+            spawn(async move {
+                let _ = document::eval(&scroll::scroll_js(None)).await;
+            });
+        })
+    });
+
+    // Scroll to a message picked from the search results, once it is rendered
+    {
+        let mut scroll_to_message = app_state.scroll_to_message;
+        use_effect(move || {
+            let Some(index) = scroll_to_message() else {
+                return;
+            };
+            if messages.read().len() <= index {
+                return;
+            }
+            scroll_to_message.set(None);
+            let js = format!(
+                "const el = document.getElementById({:?}); if (el) {{ el.scrollIntoView({{ behavior: 'smooth', block: 'center' }}); }}",
+                message_dom_id(index)
+            );
+            spawn(async move {
+                let _ = document::eval(&js).await;
+            });
+        });
+    }
+
+    // Starts an agent run for a user message (the queue already counts it as running)
+    let start_run = {
         let mut messages = messages.clone();
         let _is_generating = is_generating.clone();
         let mut app_state = app_state.clone();
-        move |text: String| {
+        move |start: RunStart| {
             if !matches!(*app_state.model_state.read(), ModelState::Loaded(_)) {
                 messages.write().push(Message {
                     role: MessageRole::Assistant,
                     content: "Model not loaded. Please select and load a model first.".to_string(),
+                    metadata: None,
+                    tokens: TokenCount::default(),
                 });
+                app_state.send_queue.write().finish_run();
                 return;
             }
 
-            // Add user message immediately
-            messages.write().push(Message {
-                role: MessageRole::User,
-                content: text,
-            });
+            let temperature_override = match start {
+                RunStart::Send(draft) => {
+                    // Add user message immediately, with the files it carries
+                    let metadata = (!draft.attachments.is_empty()).then(|| MessageMetadata {
+                        attachments: draft.attachments,
+                        ..Default::default()
+                    });
+                    messages.write().push(Message {
+                        role: MessageRole::User,
+                        content: draft.content,
+                        metadata,
+                        tokens: TokenCount::default(),
+                    });
+                    None
+                }
+                RunStart::Regenerate { temperature } => {
+                    // The new reply replaces the whole previous turn: answer, tool results and notices
+                    let reply_start = last_reply_start(&messages.peek());
+                    match reply_start {
+                        Some(index) => messages.write().truncate(index),
+                        None => {
+                            app_state.send_queue.write().finish_run();
+                            return;
+                        }
+                    }
+                    temperature
+                }
+            };
 
             // Add empty assistant message to stream into
             messages.write().push(Message {
                 role: MessageRole::Assistant,
                 content: String::new(),
+                metadata: None,
+                tokens: TokenCount::default(),
             });
 
             app_state.stop_signal.store(false, Ordering::Relaxed);
+            let tool_cancel = CancellationToken::new();
+            app_state.tool_cancel.set(tool_cancel.clone());
             app_state.is_generating.set(true);
 
             let mut messages = messages.clone();
             let mut app_state = app_state.clone();
             let mut last_save_time = last_save_time.clone();
 
+            // The request this run answers, anchored so compression never drops it
+            let goal = messages.peek()
+                .iter()
+                .rev()
+                .find(|m| m.role == MessageRole::User)
+                .map(|m| m.content.clone());
+
             spawn(async move {
                 // Initialize agent context for this run
                 let mut agent_ctx = AgentContext::new();
                 agent_ctx.state = AgentState::Analyzing;
+                if let Some(goal) = goal.clone() {
+                    agent_ctx.add_anchor(goal, AnchorReason::Goal);
+                }
                 
-                let (params, base_system_prompt, tools_enabled, tool_timeout_secs, max_iterations) = {
+                // Agent language: fixed in the settings, or else the conversation language,
+                // stored once detected, otherwise detected from recent user messages,
+                // falling back to the settings language
+                let lang = {
+                    let fixed = app_state.settings.read().agent_locale();
+                    let stored = app_state.current_conversation.read()
+                        .as_ref()
+                        .and_then(|c| c.language.as_deref().and_then(Locale::from_code));
+                    match fixed.or(stored) {
+                        Some(lang) => lang,
+                        None => {
+                            let user_texts: Vec<String> = messages.read().iter()
+                                .filter(|m| m.role == MessageRole::User)
+                                .map(|m| m.content.clone())
+                                .collect();
+                            let refs: Vec<&str> = user_texts.iter().map(|s| s.as_str()).collect();
+                            match detect_language(&refs) {
+                                Some(lang) => {
+                                    if let Some(ref mut conv) = *app_state.current_conversation.write() {
+                                        conv.language = Some(lang.code().to_string());
+                                    }
+                                    lang
+                                }
+                                None => app_state.settings.read().locale(),
+                            }
+                        }
+                    }
+                };
+                let strings = AgentStrings::new(lang);
+                set_tool_language(lang);
+
+                // Fetched pages are only reused within the same conversation
+                if let Some(conv_id) = app_state.current_conversation.read().as_ref().map(|c| c.id.clone()) {
+                    app_state.agent.web_cache.bind(&conv_id);
+                }
+
+                // Reasoning models get native thinking guidance and room to finish their <think> block
+                let reasoning_model = app_state.is_reasoning_model();
+                let thinking = ThinkingGuidance::for_model(reasoning_model);
+                // Tool calls are asked for in the format the model was trained on
+                let call_format = app_state.tool_call_format();
+
+                let (mut params, base_system_prompt, tools_enabled, tool_timeout_secs, max_iterations, strict_tool_calls, garbage_detection) = {
                     let settings = app_state.settings.read();
+                    let overrides = app_state.current_conversation.read()
+                        .as_ref()
+                        .map(|c| c.overrides.clone())
+                        .unwrap_or_default();
+                    // Regenerate's temperature, then the conversation's, then the global one
                     let params = GenerationParams {
-                        max_tokens: settings.max_tokens,
-                        temperature: settings.temperature,
-                        top_k: settings.top_k,
-                        top_p: settings.top_p,
+                        max_tokens: reasoning::output_budget(overrides.max_tokens.unwrap_or(settings.max_tokens), reasoning_model),
+                        temperature: temperature_override.or(overrides.temperature).unwrap_or(settings.temperature),
+                        top_k: overrides.top_k.unwrap_or(settings.top_k),
+                        top_p: overrides.top_p.unwrap_or(settings.top_p),
+                        min_p: 0.0,
                         repeat_penalty: 1.1,
                         seed: 0,
-                        max_context_size: settings.context_size,
+                        max_context_size: app_state.context_cap(),
+                        auto_context: settings.auto_context,
+                        grammar: None,
+                        stop_sequences: settings.stop_sequences.clone(),
+                        frequency_penalty: settings.frequency_penalty,
+                        presence_penalty: settings.presence_penalty,
+                    };
+
+                    // Swap the stock prompt for the conversation language (custom prompts are kept)
+                    let base_prompt = if settings.system_prompt == default_system_prompt_for_lang(&settings.language)
+                        && settings.language != lang.code()
+                    {
+                        default_system_prompt_for_lang(lang.code())
+                    } else {
+                        settings.system_prompt.clone()
                     };
 
                     (
                         params,
-                        settings.system_prompt.clone(),
-                        app_state.agent.config.enable_tools,
+                        overrides.system_prompt.unwrap_or(base_prompt),
+                        overrides.enable_tools.unwrap_or(app_state.agent.config.enable_tools),
                         app_state.agent.config.tool_timeout_secs,
-                        app_state.agent.config.loop_config.max_iterations,
+                        overrides.max_iterations.unwrap_or(app_state.agent.config.loop_config.max_iterations),
+                        settings.strict_tool_calls,
+                        settings.garbage_detection,
                     )
                 };
+                let max_tool_retries = app_state.agent.config.loop_config.retry_limit();
 
-                // Build the enhanced system prompt with tools
-                let system_prompt = if tools_enabled {
-                    let tools = app_state.agent.tool_registry.list_tools();
-                    build_agent_system_prompt(&base_system_prompt, &tools, Some(&agent_ctx), None)
-                } else {
-                    base_system_prompt.clone()
+                // A model that starts writing the tool result itself is cut off there
+                if tools_enabled {
+                    for stop in TOOL_CALL_STOP_SEQUENCES {
+                        if !params.stop_sequences.iter().any(|s| s == stop) {
+                            params.stop_sequences.push(stop.to_string());
+                        }
+                    }
+                }
+
+                // Replies record the model they came from, to compare models across conversations
+                let model_name = match &*app_state.model_state.read() {
+                    ModelState::Loaded(source) => Some(source.display_name()),
+                    _ => None,
                 };
 
+                // Environment block is gathered once per run, not per iteration
+                let environment = {
+                    let env_settings = app_state.settings.read().environment_context.clone();
+                    EnvironmentContext::gather(&env_settings, model_name.as_deref())
+                };
+
+                // Memory notes relevant to the request, recalled once per run
+                let memory_digest = {
+                    let memory_settings = app_state.settings.read().memory.clone();
+                    match goal.as_deref() {
+                        Some(goal) if memory_settings.include_in_prompt && memory_settings.prompt_notes > 0 => {
+                            match update_memory(|store| store.search(goal, memory_settings.prompt_notes)) {
+                                Ok(hits) => {
+                                    let notes: Vec<_> = hits.into_iter().map(|(note, _)| note).collect();
+                                    Some(build_memory_digest(&notes)).filter(|digest| !digest.is_empty())
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Memory recall failed: {}", e);
+                                    None
+                                }
+                            }
+                        }
+                        _ => None,
+                    }
+                };
+
+                // Tool list is cached and only rebuilt when the registry generation moves
+                let mut tools_rx = app_state.agent.tool_registry.subscribe();
+                let mut tools = app_state.agent.tool_registry.list_tools();
+                let _ = tools_rx.borrow_and_update();
+
                 // Compression guard counter (allows proactive + post-truncation before stopping)
                 let mut compression_count: u32 = 0;
+                // A reasoning reply cut off inside <think> is regenerated once with a larger budget
+                let mut think_retry_used = false;
+
+                // Developer mode records every prompt as sent, with its response, for replay tests
+                let mut recorder = if app_state.settings.read().developer_mode {
+                    let history: Vec<StorageMessage> = messages.read().iter()
+                        .filter(|m| !(m.role == MessageRole::Assistant && m.content.is_empty()))
+                        .cloned()
+                        .map(|m| m.into())
+                        .collect();
+                    let name = app_state.current_conversation.read()
+                        .as_ref()
+                        .map(|c| c.title.clone())
+                        .unwrap_or_else(|| "run".to_string());
+                    Some(
+                        RunRecorder::new(name, &base_system_prompt, tools.clone(), history, context_budget(&params, &app_state.metrics))
+                            .with_thinking(thinking)
+                            .with_tool_call_format(call_format)
+                            .with_memory(memory_digest.clone()),
+                    )
+                } else {
+                    None
+                };
+                let mut sent_prompt: Option<Vec<StorageMessage>> = None;
+
+                // One KV cache session for the run: each iteration only evaluates
+                // what changed since the previous prompt
+                let session = app_state.engine.lock().await.begin_session().ok();
+
+                // Tool activity reaches the UI as events; replies keep the model's own text
+                let (events, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
+                let mut agent_events = app_state.agent_events;
+                agent_events.set(Vec::new());
+                spawn(async move {
+                    while let Some(event) = event_rx.recv().await {
+                        agent_events.write().push(event);
+                    }
+                });
 
                 // Advanced agent loop
                 while agent_ctx.iteration < max_iterations {
                     agent_ctx.iteration += 1;
 
+                    // Placeholders abandoned by the previous iteration's branches
+                    if has_stray_empty_replies(&messages.read()) {
+                        prune_empty_replies(&mut messages.write());
+                    }
+
                     // Check stop signal
                     if app_state.stop_signal.load(Ordering::Relaxed) {
                         tracing::info!("Agent stopped by user at iteration {}", agent_ctx.iteration);
                         break;
                     }
 
+                    // Read-only mode is re-read every iteration so a toggle applies to the next step
+                    let read_only = app_state.current_conversation.read()
+                        .as_ref()
+                        .map(|c| c.overrides.read_only)
+                        .unwrap_or(false);
+
                     // Check for stuck loop
                     if agent_ctx.is_stuck() {
                         let mut msgs = messages.write();
                         msgs.push(Message {
                             role: MessageRole::Assistant,
-                            content: "⚠️ J'ai détecté que je répète les mêmes actions. Laisse-moi reformuler ma réponse.".to_string(),
+                            content: strings.stuck(),
+                            metadata: None,
+                            tokens: TokenCount::default(),
                         });
                         break;
                     }
@@ -516,56 +630,60 @@ pub fn ChatView() -> Element {
                         let mut msgs = messages.write();
                         msgs.push(Message {
                             role: MessageRole::Assistant,
-                            content: "⏱️ Temps d'exécution maximal atteint. Voici ce que j'ai trouvé jusqu'à présent.".to_string(),
+                            content: strings.max_runtime(),
+                            metadata: None,
+                            tokens: TokenCount::default(),
                         });
                         break;
                     }
 
-                    // Build context-aware prompt with tool history
+                    // Build context-aware prompt with tool history, within the context window
                     let prompt_messages = {
-                        let mut history = messages.read().clone();
-                        if history
-                            .last()
-                            .map(|m| m.role == MessageRole::Assistant && m.content.is_empty())
-                            .unwrap_or(false)
-                        {
-                            history.pop();
-                        }
-
-                        // Keep more history for better context
-                        let max_history = 40usize;
-                        if history.len() > max_history {
-                            history = history[history.len() - max_history..].to_vec();
-                        }
+                        let history: Vec<StorageMessage> = messages.read().iter().cloned().map(|m| m.into()).collect();
 
-                        let mut prompt_messages: Vec<StorageMessage> = Vec::new();
-                        
                         // System prompt with dynamic context injection
-                        let dynamic_prompt = if agent_ctx.iteration > 1 && tools_enabled {
-                            let tools = app_state.agent.tool_registry.list_tools();
-                            build_agent_system_prompt(&base_system_prompt, &tools, Some(&agent_ctx), None)
+                        let dynamic_prompt = if tools_enabled {
+                            if tools_rx.has_changed().unwrap_or(false) {
+                                let _ = tools_rx.borrow_and_update();
+                                tools = app_state.agent.tool_registry.list_tools();
+                                tracing::info!("Tool set changed, rebuilding tool section ({} tools)", tools.len());
+                            }
+                            // The strict-format grammar (JSON calls only) follows the tools offered this iteration
+                            if read_only {
+                                let visible: Vec<_> = tools.iter()
+                                    .filter(|t| is_tool_allowed_read_only(&t.name))
+                                    .cloned()
+                                    .collect();
+                                params.grammar = if strict_tool_calls && call_format == ToolCallFormat::Json { build_tool_call_grammar(&visible) } else { None };
+                                build_agent_system_prompt(&base_system_prompt, &visible, Some(&agent_ctx), None, Some(&environment), memory_digest.as_deref(), Some(lang), thinking, call_format)
+                            } else {
+                                params.grammar = if strict_tool_calls && call_format == ToolCallFormat::Json { build_tool_call_grammar(&tools) } else { None };
+                                build_agent_system_prompt(&base_system_prompt, &tools, Some(&agent_ctx), None, Some(&environment), memory_digest.as_deref(), Some(lang), thinking, call_format)
+                            }
                         } else {
-                            system_prompt.clone()
+                            base_system_prompt.clone()
                         };
                         
-                        if !dynamic_prompt.trim().is_empty() {
-                            prompt_messages.push(StorageMessage::new(
-                                StorageRole::System,
-                                dynamic_prompt,
-                            ));
-                        }
-                        
-                        prompt_messages.extend(history.into_iter().map(|m| m.into()));
-                        prompt_messages
+                        PromptBuilder::new()
+                            .with_system(dynamic_prompt)
+                            .with_history(&history)
+                            .with_budget(prompt_budget(context_budget(&params, &app_state.metrics)))
+                            .build()
                     };
 
                     // === PROACTIVE COMPRESSION (3-Tier Hierarchical) ===
                     // Check if we're approaching context limit BEFORE generation
                     // Using tiered thresholds: 40% → Working, 60% → Compressed, 80% → Archived
-                    let estimated_tokens: usize = prompt_messages.iter()
-                        .map(|m| m.content.len() / 4)
-                        .sum();
-                    let max_context = params.max_context_size as usize;
+                    // Counted with the model tokenizer; history counts are cached per message
+                    let system_prompt = prompt_messages.first().filter(|m| m.is_pinned());
+                    let system_tokens = match system_prompt {
+                        Some(system) => app_state.count_tokens(&system.content).await,
+                        None => 0,
+                    };
+                    // The whole history, so compression runs before the prompt builder has to trim
+                    let estimated_tokens: usize = system_tokens
+                        + app_state.count_message_tokens().await.iter().sum::<usize>();
+                    let max_context = context_budget(&params, &app_state.metrics);
                     let tier = get_compression_tier(estimated_tokens, max_context);
                     
                     // Apply hierarchical compression based on tier (only if not already compressed this session)
@@ -587,27 +705,43 @@ pub fn ChatView() -> Element {
                         
                         // Apply hierarchical compression
                         let (saved, applied) = {
-                            let mut msgs = messages.write();
-                            apply_hierarchical_compression(
-                                &mut msgs,
+                            let mut history: Vec<StorageMessage> = messages.read().iter().cloned().map(|m| m.into()).collect();
+                            let masking = app_state.settings.read().tool_results.masking;
+                            let summary_params = GenerationParams {
+                                max_tokens: 600,
+                                temperature: 0.2,
+                                max_context_size: 4096,
+                                grammar: None,
+                                stop_sequences: Vec::new(),
+                                ..params.clone()
+                            };
+                            let outcome = apply_hierarchical_compression_llm(
+                                &mut history,
                                 estimated_tokens,
                                 max_context,
                                 &anchor_tuples,
+                                masking,
+                                |request| summarize_for_compression(&app_state, request, summary_params),
                             )
+                            .await;
+                            messages.set(history.into_iter().map(Message::from).collect());
+                            outcome
                         };
                         
                         if applied {
                             compression_count += 1;
+                            // Only proactive compression is replayed; post-truncation depends on the engine
+                            if let Some(recorder) = recorder.as_mut() {
+                                recorder.record_compression();
+                            }
                             
                             // Notify user
-                            messages.write().push(Message {
-                                role: MessageRole::System,
-                                content: format!(
-                                    "💾 Hierarchical compression applied (tier: {}, ~{} chars saved).",
-                                    tier.name(),
-                                    saved
-                                ),
-                            });
+                            push_notice(
+                                &mut messages.write(),
+                                NoticeKind::Compression,
+                                strings.compression_applied(tier, saved),
+                                &strings,
+                            );
                             
                             // Restart loop to rebuild prompt_messages from compressed messages
                             continue;
@@ -617,16 +751,26 @@ pub fn ChatView() -> Element {
                     // Generate response
                     agent_ctx.state = AgentState::Thinking;
                     
+                    // Pre-send capture
+                    if recorder.is_some() {
+                        sent_prompt = Some(prompt_messages.clone());
+                    }
+
                     let (rx, stop_signal) = {
                         let engine = app_state.engine.lock().await;
-                        match engine.generate_stream_messages(prompt_messages, params.clone()) {
+                        let started = match &session {
+                            Some(session) => engine.generate_stream_session(session, prompt_messages, params.clone()),
+                            None => engine.generate_stream_messages(prompt_messages, params.clone()),
+                        };
+                        match started {
                             Ok(result) => result,
                             Err(e) => {
                                 agent_ctx.consecutive_errors += 1;
-                                messages.write().push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: format!("❌ Erreur de génération: {e}"),
-                                });
+                                let mut msgs = messages.write();
+                                ensure_pending_reply(&mut msgs);
+                                if let Some(last) = msgs.last_mut() {
+                                    last.content = strings.generation_error(&e.to_string());
+                                }
                                 if agent_ctx.consecutive_errors >= 3 {
                                     break;
                                 }
@@ -634,10 +778,13 @@ pub fn ChatView() -> Element {
                             }
                         }
                     };
+                    // The reply to stream into; a failed start left its error in the previous one
+                    ensure_pending_reply(&mut messages.write());
 
                     // Stream tokens - drain all available tokens per tick for smooth display
                     let mut stream_done = false;
                     let mut was_truncated = false;
+                    let mut garbage = false;
                     while !stream_done {
                         if app_state.stop_signal.load(Ordering::Relaxed) {
                             stop_signal.store(true, Ordering::Relaxed);
@@ -653,6 +800,19 @@ pub fn ChatView() -> Element {
                                     batch_text.push_str(&text);
                                     got_any = true;
                                 }
+                                Ok(StreamToken::Stats(stats)) => {
+                                    tracing::debug!(
+                                        "Generation finished: {:?}, {:.1} t/s",
+                                        stats.stop_reason,
+                                        stats.tokens_per_second()
+                                    );
+                                    // Sent before Done and Truncated alike
+                                    if let Some(last) = messages.write().last_mut() {
+                                        let metadata = last.metadata.get_or_insert_with(Default::default);
+                                        metadata.stats = Some(stats);
+                                        metadata.model = model_name.clone();
+                                    }
+                                }
                                 Ok(StreamToken::Done) => {
                                     stream_done = true;
                                     break;
@@ -668,7 +828,7 @@ pub fn ChatView() -> Element {
                                 }
                                 Ok(StreamToken::Error(e)) => {
                                     agent_ctx.consecutive_errors += 1;
-                                    batch_text.push_str(&format!("\n\n❌ Erreur: {e}"));
+                                    batch_text.push_str(&format!("\n\n{} {e}", strings.stream_error_marker()));
                                     stream_done = true;
                                     break;
                                 }
@@ -686,12 +846,13 @@ pub fn ChatView() -> Element {
                             if let Some(last) = msgs.last_mut() {
                                 last.content.push_str(&batch_text);
                                 
-                                // Check for garbage text (model hallucinating)
-                                if last.content.len() > 200 && is_garbage_text(&last.content) {
+                                // Check for garbage text (model hallucinating); what came before stays
+                                if is_garbage_text(&last.content, garbage_detection) {
                                     tracing::error!("Garbage text detected, stopping generation");
-                                    last.content = "⚠️ Génération interrompue: texte corrompu détecté. Reformulons.\n\n".to_string();
+                                    stop_signal.store(true, Ordering::Relaxed);
+                                    last.content.push_str(&strings.corrupted_output());
+                                    garbage = true;
                                     stream_done = true;
-                                    // Break the outer loop after this
                                 }
                             }
                         }
@@ -702,9 +863,8 @@ pub fn ChatView() -> Element {
                             
                             // Periodic save during generation (every 3 seconds)
                             if last_save_time.read().elapsed().as_secs() >= 3 {
-                                let msgs = messages.read();
-                                let storage_messages: Vec<StorageMessage> = msgs.iter()
-                                    .cloned()
+                                let storage_messages: Vec<StorageMessage> = persisted_messages(&messages.read())
+                                    .into_iter()
                                     .map(|m| m.into())
                                     .collect();
                                 
@@ -719,6 +879,33 @@ pub fn ChatView() -> Element {
                         }
                     }
 
+                    if let (Some(recorder), Some(prompt)) = (recorder.as_mut(), sent_prompt.take()) {
+                        let response = messages.read().last().map(|m| m.content.clone()).unwrap_or_default();
+                        recorder.record_exchange(&prompt, &response);
+                    }
+
+                    // A corrupted reply ends the run; its tool calls are not trusted
+                    if garbage {
+                        break;
+                    }
+
+                    // Cut off while still thinking: the budget was too small, not the context
+                    if was_truncated
+                        && reasoning_model
+                        && !think_retry_used
+                        && !app_state.stop_signal.load(Ordering::Relaxed)
+                        && messages.read().last().is_some_and(|m| reasoning::ends_inside_think_block(&m.content))
+                    {
+                        think_retry_used = true;
+                        params.max_tokens = params.max_tokens.saturating_mul(2);
+                        tracing::warn!("Reply truncated inside <think>, retrying with {} max tokens", params.max_tokens);
+                        if let Some(last) = messages.write().last_mut() {
+                            last.content.clear();
+                        }
+                        agent_ctx.iteration -= 1;
+                        continue;
+                    }
+
                     // === POST-TRUNCATION HIERARCHICAL COMPRESSION ===
                     // If response was truncated due to context saturation, apply smart compression
                     if was_truncated && !app_state.stop_signal.load(Ordering::Relaxed) {
@@ -730,8 +917,8 @@ pub fn ChatView() -> Element {
                         
                         let msg_count = messages.read().len();
                         let total_chars: usize = messages.read().iter().map(|m| m.content.len()).sum();
-                        let estimated_tokens = total_chars / 4;
-                        let max_context = params.max_context_size as usize;
+                        let estimated_tokens: usize = app_state.count_message_tokens().await.iter().sum();
+                        let max_context = context_budget(&params, &app_state.metrics);
                         
                         tracing::info!(
                             "Post-truncation compression: {} msgs, {} chars, {}% capacity",
@@ -752,25 +939,41 @@ pub fn ChatView() -> Element {
                         
                         // Apply hierarchical compression based on tier
                         let (saved, applied) = {
-                            let mut msgs = messages.write();
-                            apply_hierarchical_compression(
-                                &mut msgs,
+                            let mut history: Vec<StorageMessage> = messages.read().iter().cloned().map(|m| m.into()).collect();
+                            let masking = app_state.settings.read().tool_results.masking;
+                            let summary_params = GenerationParams {
+                                max_tokens: 600,
+                                temperature: 0.2,
+                                max_context_size: 4096,
+                                grammar: None,
+                                stop_sequences: Vec::new(),
+                                ..params.clone()
+                            };
+                            let outcome = apply_hierarchical_compression_llm(
+                                &mut history,
                                 estimated_tokens,
                                 max_context,
                                 &anchor_tuples,
+                                masking,
+                                |request| summarize_for_compression(&app_state, request, summary_params),
                             )
+                            .await;
+                            messages.set(history.into_iter().map(Message::from).collect());
+                            outcome
                         };
                         
                         if applied {
                             // Notify user
-                            messages.write().push(Message {
-                                role: MessageRole::System,
-                                content: format!(
+                            push_notice(
+                                &mut messages.write(),
+                                NoticeKind::Compression,
+                                format!(
                                     "💾 Post-truncation compression applied (tier: {}, ~{} chars saved).",
                                     tier.name(),
                                     saved
                                 ),
-                            });
+                                &strings,
+                            );
                             
                             // Retry generation with compressed context
                             continue;
@@ -781,23 +984,14 @@ pub fn ChatView() -> Element {
                         
                         tracing::info!("Context saturated ({} msgs, {} chars), applying legacy compression", msg_count, total_chars);
                         
-                        // Phase 1: Zero-cost pruning
-                        let mut chars_saved = 0usize;
-                        {
+                        // Phase 1: Zero-cost pruning (the pinned system prompt is left alone)
+                        let chars_saved = {
                             let mut msgs = messages.write();
-                            for msg in msgs.iter_mut() {
-                                if msg.role == MessageRole::System && msg.content.len() > 2000 {
-                                    let original_len = msg.content.len();
-                                    let truncated = format!(
-                                        "{}...\n\n[Contenu tronqué - {} caractères]",
-                                        &msg.content[..500.min(msg.content.len())],
-                                        original_len
-                                    );
-                                    chars_saved += original_len - truncated.len();
-                                    msg.content = truncated;
-                                }
-                            }
-                        }
+                            let mut history: Vec<StorageMessage> = msgs.iter().cloned().map(|m| m.into()).collect();
+                            let saved = prune_long_system_messages(&mut history);
+                            *msgs = history.into_iter().map(Message::from).collect();
+                            saved
+                        };
                         
                         if chars_saved > 0 {
                             tracing::info!("Zero-cost pruning saved {} chars", chars_saved);
@@ -832,12 +1026,13 @@ pub fn ChatView() -> Element {
                                 let msgs = messages.read();
                                 msgs.iter()
                                     .take(msg_count.saturating_sub(2))
-                                    .filter(|m| m.role != MessageRole::System)
+                                    .filter(|m| !matches!(m.role, MessageRole::System | MessageRole::Tool))
                                     .map(|m| {
                                         let role = match m.role {
                                             MessageRole::User => "U",
                                             MessageRole::Assistant => "A",
                                             MessageRole::System => "S",
+                                            MessageRole::Tool => "T",
                                         };
                                         let content = if m.content.len() > 200 {
                                             format!("{}...", &m.content[..200])
@@ -860,6 +1055,8 @@ pub fn ChatView() -> Element {
                                 max_tokens: 600,
                                 temperature: 0.2,
                                 max_context_size: 4096,
+                                grammar: None,
+                                stop_sequences: Vec::new(),
                                 ..params.clone()
                             };
                             
@@ -867,34 +1064,31 @@ pub fn ChatView() -> Element {
                                 StorageMessage::new(StorageRole::User, compression_prompt),
                             ];
                             
-                            let summary = {
+                            // Release the engine lock before draining so Stop stays responsive
+                            let stream = {
                                 let engine = app_state.engine.lock().await;
-                                if let Ok((rx, _)) = engine.generate_stream_messages(summary_messages, summary_params) {
-                                    let mut text = String::new();
-                                    while let Ok(token) = rx.recv() {
-                                        match token {
-                                            StreamToken::Token(t) => text.push_str(&t),
-                                            StreamToken::Done | StreamToken::Truncated { .. } => break,
-                                            StreamToken::Error(_) => break,
-                                        }
-                                    }
-                                    text
-                                } else {
-                                    "Conversation précédente résumée.".to_string()
+                                engine.generate_stream_messages(summary_messages, summary_params)
+                            };
+                            let summary = match stream {
+                                Ok((rx, stop)) => {
+                                    collect_stream_text(rx, &stop, &app_state.stop_signal).await.0
                                 }
+                                Err(_) => strings.summary_fallback(),
                             };
                             
                             tracing::info!("LLM summary: {} chars", summary.len());
                             
-                            // Replace messages with summary + last message
+                            // Replace messages with summary + last message, keeping pinned ones
                             {
                                 let mut msgs = messages.write();
                                 let last_msg = msgs.last().cloned();
-                                msgs.clear();
+                                msgs.retain(|m| m.metadata.as_ref().is_some_and(|meta| meta.pinned));
                                 
                                 msgs.push(Message {
                                     role: MessageRole::System,
                                     content: format!("📋 {}", summary),
+                                    metadata: None,
+                                    tokens: TokenCount::default(),
                                 });
                                 
                                 if let Some(msg) = last_msg {
@@ -906,6 +1100,8 @@ pub fn ChatView() -> Element {
                                 msgs.push(Message {
                                     role: MessageRole::Assistant,
                                     content: String::new(),
+                                    metadata: None,
+                                    tokens: TokenCount::default(),
                                 });
                             }
                             
@@ -918,19 +1114,12 @@ pub fn ChatView() -> Element {
 
                     // Check if stream ended with errors
                     let last_content = messages.read().last().map(|m| m.content.clone()).unwrap_or_default();
-                    let had_stream_error = last_content.contains("❌ Erreur:");
+                    let had_stream_error = last_content.contains(strings.stream_error_marker());
                     
                     if had_stream_error {
                         // Stream error — give LLM a chance to recover
                         if agent_ctx.consecutive_errors < 3 {
-                            messages.write().push(Message {
-                                role: MessageRole::System,
-                                content: "Une erreur est survenue pendant la génération. Reformule ta réponse ou essaie une approche différente.".to_string(),
-                            });
-                            messages.write().push(Message {
-                                role: MessageRole::Assistant,
-                                content: String::new(),
-                            });
+                            push_notice(&mut messages.write(), NoticeKind::StreamRetry, strings.stream_error_retry(), &strings);
                             continue;
                         } else {
                             break;
@@ -956,31 +1145,240 @@ pub fn ChatView() -> Element {
                     // Store last response for context
                     agent_ctx.last_response = Some(last_text.clone());
 
-                    let tool_call = match extract_tool_call(&last_text) {
+                    let mut tool_calls = extract_tool_calls(&last_text);
+                    // Calls are recorded on this reply, even once results follow it
+                    let reply_index = messages.read().len().saturating_sub(1);
+                    if !tool_calls.is_empty() {
+                        let names: Vec<&str> = tool_calls.iter().map(|c| c.tool.as_str()).collect();
+                        let _ = events.send(AgentEvent::Progress {
+                            iteration: agent_ctx.iteration,
+                            max_iterations,
+                            message: names.join(", "),
+                        });
+                    }
+                    for call in &tool_calls {
+                        tracing::info!("Tool call extracted: {} with params keys: {:?}",
+                            call.tool,
+                            call.params.as_object().map(|o| o.keys().cloned().collect::<Vec<_>>()).unwrap_or_default()
+                        );
+                        if let Some(recorder) = recorder.as_mut() {
+                            recorder.record_tool_call(&call.tool);
+                        }
+                    }
+
+                    // Several independent reads in a row run together
+                    let batch_len = parallel_batch_len(&tool_calls);
+                    if batch_len > 1 {
+                        let batch: Vec<ToolCall> = tool_calls.drain(..batch_len).collect();
+
+                        // Read-only tools, so allowed in read-only mode; still asked about
+                        // one at a time if the user made them require approval
+                        let mut approved_calls = Vec::new();
+                        for call in batch {
+                            let auto_approved = {
+                                let settings = app_state.settings.read();
+                                settings.auto_approve_all_tools || settings.tool_allowlist.contains(&call.tool)
+                            };
+                            let request = PermissionRequest {
+                                id: Uuid::new_v4(),
+                                tool_name: call.tool.clone(),
+                                operation: "execute".to_string(),
+                                target: call.params.to_string(),
+                                level: get_tool_permission(&call.tool),
+                                params: call.params.clone(),
+                                timestamp: Utc::now(),
+                                preview: None,
+                            };
+                            let approved = auto_approved
+                                || match app_state.agent.permission_manager.request_permission(request.clone()).await {
+                                    PermissionResult::Approved => true,
+                                    PermissionResult::Denied => false,
+                                    PermissionResult::Pending => matches!(
+                                        app_state.agent.permission_manager
+                                            .wait_for_decision(request.id, Duration::from_secs(120))
+                                            .await,
+                                        Some(PermissionDecision::Approved)
+                                    ),
+                                };
+                            let tool = app_state.agent.tool_registry.get(&call.tool);
+                            match tool {
+                                Some(tool) if approved => approved_calls.push((call, tool)),
+                                _ => {
+                                    let error = if approved {
+                                        ToolError::UnknownTool(call.tool.clone()).to_string()
+                                    } else {
+                                        PERMISSION_DENIED_ERROR.to_string()
+                                    };
+                                    let entry = ToolHistoryEntry {
+                                        tool_name: call.tool.clone(),
+                                        params: call.params.clone(),
+                                        result: None,
+                                        error: Some(error.clone()),
+                                        timestamp: Utc::now().timestamp() as u64,
+                                        duration_ms: 0,
+                                    };
+                                    agent_ctx.tool_history.push(entry.clone());
+                                    let _ = events.send(AgentEvent::ToolCallFailed { tool: call.tool.clone(), error, retry_count: 0, will_retry: false });
+                                    let mut msgs = messages.write();
+                                    record_on_reply(&mut msgs, reply_index, entry);
+                                    if !approved {
+                                        msgs.push(Message {
+                                            role: MessageRole::System,
+                                            content: strings.tool_refused_hint(&call.tool),
+                                            metadata: None,
+                                            tokens: TokenCount::default(),
+                                        });
+                                    }
+                                }
+                            }
+                        }
+
+                        tracing::info!("Executing {} tool calls concurrently with timeout {}s", approved_calls.len(), tool_timeout_secs);
+                        for (call, _) in &approved_calls {
+                            let _ = events.send(AgentEvent::ToolCallStarted { tool: call.tool.clone(), params: call.params.clone() });
+                        }
+                        agent_ctx.state = AgentState::Observing;
+                        let outcomes = join_all(
+                            approved_calls
+                                .iter()
+                                .map(|(call, tool)| run_tool_call(&app_state.agent.tool_registry, tool.clone(), call, tool_timeout_secs, max_tool_retries, &tool_cancel, &events)),
+                        )
+                        .await;
+
+                        // Results go back in the order the model asked for them
+                        let mut failed = false;
+                        let mut cancelled = false;
+                        for ((call, _), (outcome, duration_ms)) in approved_calls.iter().zip(outcomes) {
+                            let (verbosity, result_role) = {
+                                let settings = app_state.settings.read();
+                                let role = match settings.tool_results.role {
+                                    ToolResultRole::System => MessageRole::System,
+                                    ToolResultRole::Tool => MessageRole::Tool,
+                                };
+                                (settings.tool_results.verbosity_for(&call.tool), role)
+                            };
+                            match outcome {
+                                Ok(result) => {
+                                    agent_ctx.anchor_tool_success(
+                                        &call.tool,
+                                        &result,
+                                        app_state.agent.config.loop_config.anchor_min_result_chars,
+                                    );
+                                    let entry = ToolHistoryEntry {
+                                        tool_name: call.tool.clone(),
+                                        params: call.params.clone(),
+                                        result: Some(result.clone()),
+                                        error: None,
+                                        timestamp: Utc::now().timestamp() as u64,
+                                        duration_ms,
+                                    };
+                                    agent_ctx.tool_history.push(entry.clone());
+                                    let _ = events.send(AgentEvent::ToolCallCompleted { tool: call.tool.clone(), result: result.clone() });
+                                    push_tool_success(&mut messages.write(), reply_index, entry, &result, verbosity, result_role, &strings);
+                                }
+                                Err(ToolError::Cancelled) => {
+                                    cancelled = true;
+                                    let entry = ToolHistoryEntry {
+                                        tool_name: call.tool.clone(),
+                                        params: call.params.clone(),
+                                        result: None,
+                                        error: Some(ToolError::Cancelled.to_string()),
+                                        timestamp: Utc::now().timestamp() as u64,
+                                        duration_ms,
+                                    };
+                                    agent_ctx.tool_history.push(entry.clone());
+                                    let _ = events.send(AgentEvent::ToolCallFailed {
+                                        tool: call.tool.clone(),
+                                        error: ToolError::Cancelled.to_string(),
+                                        retry_count: 0,
+                                        will_retry: false,
+                                    });
+                                    record_on_reply(&mut messages.write(), reply_index, entry);
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Tool {} failed after {}ms [{}]: {}", call.tool, duration_ms, e.category().as_str(), e);
+                                    app_state.metrics.record_tool_error(e.category().as_str());
+                                    failed = true;
+                                    let error_text = match e {
+                                        ToolError::Timeout { .. } => strings.tool_timeout(),
+                                        _ => e.to_string(),
+                                    };
+                                    let entry = ToolHistoryEntry {
+                                        tool_name: call.tool.clone(),
+                                        params: call.params.clone(),
+                                        result: None,
+                                        error: Some(error_text.clone()),
+                                        timestamp: Utc::now().timestamp() as u64,
+                                        duration_ms,
+                                    };
+                                    agent_ctx.tool_history.push(entry.clone());
+                                    let _ = events.send(AgentEvent::ToolCallFailed { tool: call.tool.clone(), error: error_text, retry_count: 0, will_retry: false });
+                                    let mut msgs = messages.write();
+                                    record_on_reply(&mut msgs, reply_index, entry);
+                                    msgs.push(Message {
+                                        role: result_role,
+                                        content: e.render_for_model(&call.tool),
+                                        metadata: None,
+                                        tokens: TokenCount::default(),
+                                    });
+                                }
+                            }
+                        }
+                        if cancelled {
+                            tracing::info!("Tool calls cancelled by the user, ending the run");
+                            break;
+                        }
+                        if failed {
+                            agent_ctx.consecutive_errors += 1;
+                        }
+
+                        agent_ctx.state = AgentState::Reflecting;
+                        ensure_pending_reply(&mut messages.write());
+                        if tool_calls.is_empty() {
+                            continue;
+                        }
+                    }
+
+                    let tool_call = match (!tool_calls.is_empty()).then(|| tool_calls.remove(0)) {
                         Some(call) => {
-                            tracing::info!("Tool call extracted: {} with params keys: {:?}",
-                                call.tool,
-                                call.params.as_object().map(|o| o.keys().cloned().collect::<Vec<_>>()).unwrap_or_default()
-                            );
+                            // Anything with side effects runs one call per iteration, in order
+                            if !tool_calls.is_empty() {
+                                let deferred: Vec<&str> = tool_calls.iter().map(|c| c.tool.as_str()).collect();
+                                push_notice(
+                                    &mut messages.write(),
+                                    NoticeKind::DeferredCalls,
+                                    strings.tool_calls_deferred(&call.tool, &deferred.join(", ")),
+                                    &strings,
+                                );
+                            }
                             call
                         }
                         None => {
+                            // No tool ran, yet the reply shows a tool result: the model made it up
+                            if let Some(start) = find_fabricated_tool_output(&last_text) {
+                                agent_ctx.hallucinated_tool_outputs += 1;
+                                tracing::warn!("Made-up tool result in reply ({} so far)", agent_ctx.hallucinated_tool_outputs);
+                                let mut msgs = messages.write();
+                                if let Some(last) = msgs.last_mut() {
+                                    last.content.truncate(start);
+                                    last.content.truncate(last.content.trim_end().len());
+                                }
+                                if agent_ctx.hallucinated_tool_outputs < 2 {
+                                    push_notice(&mut msgs, NoticeKind::FabricatedToolOutput, strings.fabricated_tool_output(), &strings);
+                                    continue;
+                                }
+                                if let Some(last) = msgs.last_mut() {
+                                    last.content.push_str(&strings.fabricated_tool_output_stopped());
+                                }
+                                agent_ctx.state = AgentState::Completed;
+                                break;
+                            }
+
                             // No tool call found — check if the LLM maybe tried but malformed the JSON
-                            // Be strict: must have both "tool" AND JSON object markers
-                            let looks_like_failed_json = (last_text.contains("{\"tool\"") || last_text.contains("{ \"tool\"")) 
-                                && last_text.contains("\"params\"");
-                            
-                            if looks_like_failed_json && agent_ctx.consecutive_errors < 2 {
+                            if looks_like_malformed_tool_call(&last_text) && agent_ctx.consecutive_errors < 2 {
                                 // LLM tried to call a tool but the JSON was malformed
                                 agent_ctx.consecutive_errors += 1;
-                                messages.write().push(Message {
-                                    role: MessageRole::System,
-                                    content: "Le format JSON de l'appel d'outil était invalide. Rappel: utilise exactement ce format sans texte avant ni après:\n```json\n{\"tool\": \"nom_outil\", \"params\": {...}}\n```\nRéessaie avec le bon format.".to_string(),
-                                });
-                                messages.write().push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: String::new(),
-                                });
+                                push_notice(&mut messages.write(), NoticeKind::InvalidToolCall, strings.invalid_tool_json(), &strings);
                                 continue;
                             }
                             
@@ -991,19 +1389,32 @@ pub fn ChatView() -> Element {
                         }
                     };
 
-                    // Show tool usage indicator
-                    {
+                    // Read-only mode overrides every approval rule, including auto-approve
+                    if read_only && !is_tool_allowed_read_only(&tool_call.tool) {
+                        tracing::info!("Tool {} blocked by read-only mode", tool_call.tool);
+                        let entry = ToolHistoryEntry {
+                            tool_name: tool_call.tool.clone(),
+                            params: tool_call.params.clone(),
+                            result: None,
+                            error: Some(READ_ONLY_BLOCKED_ERROR.to_string()),
+                            timestamp: Utc::now().timestamp() as u64,
+                            duration_ms: 0,
+                        };
+                        agent_ctx.tool_history.push(entry.clone());
+                        let _ = events.send(AgentEvent::ToolCallFailed {
+                            tool: tool_call.tool.clone(),
+                            error: READ_ONLY_BLOCKED_ERROR.to_string(),
+                            retry_count: 0,
+                            will_retry: false,
+                        });
                         let mut msgs = messages.write();
-                        if let Some(last) = msgs.last_mut() {
-                            last.content = format!(
-                                "🔧 Utilisation de l'outil `{}`... (itération {}/{})",
-                                tool_call.tool, agent_ctx.iteration, max_iterations
-                            );
-                        }
+                        record_on_reply(&mut msgs, reply_index, entry);
+                        push_notice(&mut msgs, NoticeKind::ReadOnly, strings.read_only_hint(&tool_call.tool), &strings);
+                        continue;
                     }
 
                     // Permission check
-                    let permission_level = get_tool_permission(&tool_call.tool);
+                    let permission_level = get_call_permission(&tool_call.tool, &tool_call.params);
                     let target = tool_call
                         .params
                         .get("path")
@@ -1015,6 +1426,13 @@ pub fn ChatView() -> Element {
                         .map(|s| s.to_string())
                         .unwrap_or_else(|| tool_call.params.to_string());
 
+                    // File writes show their diff, and are only applied to the file it was made from;
+                    // commits and pull requests show what they would record
+                    let preview = preview_change(&tool_call.tool, &tool_call.params).await;
+                    let preview_diff = match preview.as_ref() {
+                        Some(preview) => Some(preview.diff.clone()),
+                        None => call_preview(&tool_call.tool, &tool_call.params).await,
+                    };
                     let permission_request = PermissionRequest {
                         id: Uuid::new_v4(),
                         tool_name: tool_call.tool.clone(),
@@ -1023,6 +1441,7 @@ pub fn ChatView() -> Element {
                         level: permission_level,
                         params: tool_call.params.clone(),
                         timestamp: Utc::now(),
+                        preview: preview_diff,
                     };
 
                     // Check auto-approve settings before asking user
@@ -1030,10 +1449,13 @@ pub fn ChatView() -> Element {
                     let is_internal_safe_tool = matches!(tool_call.tool.as_str(),
                         "skill_create" | "skill_invoke" | "skill_list" | "think" | "todo_write"
                     );
+                    // A command the command policy leaves to the user is asked about
+                    // even when its tool (`command`, `job_start`) is allow-listed
+                    let policy_prompt = needs_policy_approval(&tool_call.tool, &tool_call.params);
                     let auto_approved = {
                         let settings = app_state.settings.read();
                         settings.auto_approve_all_tools
-                            || settings.tool_allowlist.contains(&tool_call.tool)
+                            || (settings.tool_allowlist.contains(&tool_call.tool) && !policy_prompt)
                             || is_internal_safe_tool
                     };
                     tracing::info!("Tool {} permission check: level={:?}, auto_approved={}", tool_call.tool, permission_level, auto_approved);
@@ -1053,17 +1475,11 @@ pub fn ChatView() -> Element {
                         PermissionResult::Pending => {
                             agent_ctx.state = AgentState::WaitingForUser;
                             tracing::info!("Waiting for user approval for tool: {}", tool_call.tool);
-                            {
-                                let mut msgs = messages.write();
-                                if let Some(last) = msgs.last_mut() {
-                                    last.content = format!(
-                                        "⏳ Autorisation requise pour `{}` ({}).\nCible: {}",
-                                        tool_call.tool,
-                                        permission_level.label(),
-                                        target
-                                    );
-                                }
-                            }
+                            let _ = events.send(AgentEvent::PermissionRequested {
+                                tool: tool_call.tool.clone(),
+                                level: permission_level.label().to_string(),
+                                target: target.clone(),
+                            });
 
                             match app_state
                                 .agent
@@ -1075,63 +1491,37 @@ pub fn ChatView() -> Element {
                                 .await
                             {
                                 Some(PermissionDecision::Approved) => true,
-                                Some(PermissionDecision::Denied) => {
-                                    let mut msgs = messages.write();
-                                    if let Some(last) = msgs.last_mut() {
-                                        last.content = format!(
-                                            "🚫 Permission refusée pour `{}`.",
-                                            tool_call.tool
-                                        );
-                                    }
-                                    false
-                                }
+                                Some(PermissionDecision::Denied) => false,
                                 None => {
-                                    let mut msgs = messages.write();
-                                    if let Some(last) = msgs.last_mut() {
-                                        last.content = format!(
-                                            "⏱️ Délai expiré pour `{}`.",
-                                            tool_call.tool
-                                        );
-                                    }
+                                    tracing::info!("Approval for {} timed out", tool_call.tool);
                                     false
                                 }
                             }
                         }
-                        PermissionResult::Denied => {
-                            let mut msgs = messages.write();
-                            if let Some(last) = msgs.last_mut() {
-                                last.content = format!(
-                                    "🚫 Permission refusée pour `{}`.",
-                                    tool_call.tool
-                                );
-                            }
-                            false
-                        }
+                        PermissionResult::Denied => false,
                     };
 
                     if !approved {
                         // Record denied permission in context and try alternative
-                        agent_ctx.tool_history.push(ToolHistoryEntry {
+                        let entry = ToolHistoryEntry {
                             tool_name: tool_call.tool.clone(),
                             params: tool_call.params.clone(),
                             result: None,
-                            error: Some("Permission denied".to_string()),
+                            error: Some(PERMISSION_DENIED_ERROR.to_string()),
                             timestamp: Utc::now().timestamp() as u64,
                             duration_ms: 0,
+                        };
+                        agent_ctx.tool_history.push(entry.clone());
+                        let _ = events.send(AgentEvent::ToolCallFailed {
+                            tool: tool_call.tool.clone(),
+                            error: PERMISSION_DENIED_ERROR.to_string(),
+                            retry_count: 0,
+                            will_retry: false,
                         });
+                        record_on_reply(&mut messages.write(), reply_index, entry);
                         
                         // Add message to help LLM find alternative
-                        messages.write().push(Message {
-                            role: MessageRole::System,
-                            content: format!(
-                                "L'outil {} a été refusé. Essaie une autre approche ou réponds avec les informations disponibles.",
-                                tool_call.tool
-                            ),
-                        });
-                        messages.write().push(Message {
-                            role: MessageRole::Assistant,
-                            content: String::new(),
-                        });
+                        push_notice(&mut messages.write(), NoticeKind::ToolRefused, strings.tool_refused_hint(&tool_call.tool), &strings);
                         continue;
                     }
 
@@ -1140,24 +1530,27 @@ pub fn ChatView() -> Element {
                         Some(tool) => tool,
                         None => {
                             agent_ctx.consecutive_errors += 1;
+                            let error = ToolError::UnknownTool(tool_call.tool.clone()).to_string();
+                            let entry = ToolHistoryEntry {
+                                tool_name: tool_call.tool.clone(),
+                                params: tool_call.params.clone(),
+                                result: None,
+                                error: Some(error.clone()),
+                                timestamp: Utc::now().timestamp() as u64,
+                                duration_ms: 0,
+                            };
+                            agent_ctx.tool_history.push(entry.clone());
+                            let _ = events.send(AgentEvent::ToolCallFailed { tool: tool_call.tool.clone(), error, retry_count: 0, will_retry: false });
                             let mut msgs = messages.write();
-                            if let Some(last) = msgs.last_mut() {
-                                last.content = format!("❌ Outil introuvable: `{}`.", tool_call.tool);
-                            }
+                            record_on_reply(&mut msgs, reply_index, entry);
                             // Let the LLM try a different tool
                             let available_tools: Vec<String> = app_state.agent.tool_registry.list_tools().iter().map(|t| t.name.clone()).collect();
-                            msgs.push(Message {
-                                role: MessageRole::System,
-                                content: format!(
-                                    "L'outil `{}` n'existe pas. Voici les outils disponibles: {}. Utilise un des outils existants ou réponds directement.",
-                                    tool_call.tool,
-                                    available_tools.join(", ")
-                                ),
-                            });
-                            msgs.push(Message {
-                                role: MessageRole::Assistant,
-                                content: String::new(),
-                            });
+                            push_notice(
+                                &mut msgs,
+                                NoticeKind::ToolNotFound,
+                                strings.tool_not_found_hint(&tool_call.tool, &available_tools.join(", ")),
+                                &strings,
+                            );
                             if agent_ctx.consecutive_errors >= 3 {
                                 break;
                             }
@@ -1166,18 +1559,15 @@ pub fn ChatView() -> Element {
                     };
 
                     tracing::info!("Executing tool: {} with timeout {}s", tool_call.tool, tool_timeout_secs);
-                    let start_time = Instant::now();
-                    let tool_result: Result<ToolResult, String> = match tokio::time::timeout(
-                        std::time::Duration::from_secs(tool_timeout_secs),
-                        tool.execute(tool_call.params.clone()),
-                    )
-                    .await
-                    {
-                        Ok(Ok(result)) => Ok(result),
-                        Ok(Err(e)) => Err(e.to_string()),
-                        Err(_) => Err("Timeout dépassé".to_string()),
+                    let _ = events.send(AgentEvent::ToolCallStarted { tool: tool_call.tool.clone(), params: tool_call.params.clone() });
+                    let conflict = match preview.as_ref() {
+                        Some(preview) => preview.check_unchanged().await.err(),
+                        None => None,
+                    };
+                    let (tool_result, duration_ms) = match conflict {
+                        Some(e) => (Err(e), 0),
+                        None => run_tool_call(&app_state.agent.tool_registry, tool, &tool_call, tool_timeout_secs, max_tool_retries, &tool_cancel, &events).await,
                     };
-                    let duration_ms = start_time.elapsed().as_millis() as u64;
 
                     // Process result and update context
                     agent_ctx.state = AgentState::Observing;
@@ -1188,118 +1578,127 @@ pub fn ChatView() -> Element {
                                 tool_call.tool, duration_ms, result.success, result.message.len()
                             );
                             // Record success in history
-                            agent_ctx.tool_history.push(ToolHistoryEntry {
+                            agent_ctx.anchor_tool_success(
+                                &tool_call.tool,
+                                &result,
+                                app_state.agent.config.loop_config.anchor_min_result_chars,
+                            );
+                            let entry = ToolHistoryEntry {
                                 tool_name: tool_call.tool.clone(),
                                 params: tool_call.params.clone(),
                                 result: Some(result.clone()),
                                 error: None,
                                 timestamp: Utc::now().timestamp() as u64,
                                 duration_ms,
-                            });
-
-                            // Show result summary (safe truncation)
-                            let result_preview = if result.message.len() > 200 {
-                                let safe = crate::truncate_str(&result.message, 200);
-                                format!("{}...", safe)
-                            } else {
-                                result.message.clone()
                             };
-                            
-                            messages.write().push(Message {
-                                role: MessageRole::Assistant,
-                                content: format!(
-                                    "✅ `{}` ({:.1}s): {}",
-                                    tool_call.tool,
-                                    duration_ms as f64 / 1000.0,
-                                    result_preview
-                                ),
-                            });
+                            agent_ctx.tool_history.push(entry.clone());
+                            let _ = events.send(AgentEvent::ToolCallCompleted { tool: tool_call.tool.clone(), result: result.clone() });
 
-                            // Inject tool result for LLM (capped to prevent context overflow)
-                            let tool_result_text = format_tool_result_for_system(&tool_call.tool, &result);
-                            let tool_result_text = if tool_result_text.len() > 4000 {
-                                let truncated: String = tool_result_text.chars().take(3500).collect();
-                                format!("{}...\n[Résultat tronqué: {} caractères au total]", truncated, tool_result_text.len())
-                            } else {
-                                tool_result_text
+                            // Record the call and inject its result for the LLM
+                            let (verbosity, result_role) = {
+                                let settings = app_state.settings.read();
+                                let role = match settings.tool_results.role {
+                                    ToolResultRole::System => MessageRole::System,
+                                    ToolResultRole::Tool => MessageRole::Tool,
+                                };
+                                (settings.tool_results.verbosity_for(&tool_call.tool), role)
                             };
-                            messages.write().push(Message {
-                                role: MessageRole::System,
-                                content: tool_result_text,
-                            });
+                            push_tool_success(&mut messages.write(), reply_index, entry, &result, verbosity, result_role, &strings);
 
                             // Prepare for reflection/next iteration
                             agent_ctx.state = AgentState::Reflecting;
-                            messages.write().push(Message {
-                                role: MessageRole::Assistant,
-                                content: String::new(),
+                            ensure_pending_reply(&mut messages.write());
+                        }
+                        Err(ToolError::Cancelled) => {
+                            // Stop pressed: record the call and end the run, no reflection
+                            tracing::info!("Tool {} cancelled by the user after {}ms", tool_call.tool, duration_ms);
+                            let entry = ToolHistoryEntry {
+                                tool_name: tool_call.tool.clone(),
+                                params: tool_call.params.clone(),
+                                result: None,
+                                error: Some(ToolError::Cancelled.to_string()),
+                                timestamp: Utc::now().timestamp() as u64,
+                                duration_ms,
+                            };
+                            agent_ctx.tool_history.push(entry.clone());
+                            let _ = events.send(AgentEvent::ToolCallFailed {
+                                tool: tool_call.tool.clone(),
+                                error: ToolError::Cancelled.to_string(),
+                                retry_count: 0,
+                                will_retry: false,
                             });
+                            record_on_reply(&mut messages.write(), reply_index, entry);
+                            break;
                         }
                         Err(e) => {
-                            tracing::warn!("Tool {} failed after {}ms: {}", tool_call.tool, duration_ms, e);
+                            tracing::warn!("Tool {} failed after {}ms [{}]: {}", tool_call.tool, duration_ms, e.category().as_str(), e);
+                            app_state.metrics.record_tool_error(e.category().as_str());
+                            let error_text = match e {
+                                ToolError::Timeout { .. } => strings.tool_timeout(),
+                                _ => e.to_string(),
+                            };
                             // Record error in history
-                            agent_ctx.tool_history.push(ToolHistoryEntry {
+                            let entry = ToolHistoryEntry {
                                 tool_name: tool_call.tool.clone(),
                                 params: tool_call.params.clone(),
                                 result: None,
-                                error: Some(e.clone()),
+                                error: Some(error_text.clone()),
                                 timestamp: Utc::now().timestamp() as u64,
                                 duration_ms,
-                            });
+                            };
+                            agent_ctx.tool_history.push(entry.clone());
+                            let _ = events.send(AgentEvent::ToolCallFailed { tool: tool_call.tool.clone(), error: error_text, retry_count: 0, will_retry: false });
                             
                             agent_ctx.consecutive_errors += 1;
                             
-                            // Show error and inject reflection prompt
-                            let error_msg = format!(
-                                "❌ Erreur `{}`: {}",
-                                tool_call.tool, e
-                            );
-                            
+                            // Record the error and inject reflection prompt
                             let mut msgs = messages.write();
-                            if let Some(last) = msgs.last_mut() {
-                                last.content = error_msg;
-                            }
+                            record_on_reply(&mut msgs, reply_index, entry);
                             
                             // Give LLM a chance to recover
                             if agent_ctx.consecutive_errors < 4 {
-                                msgs.push(Message {
-                                    role: MessageRole::System,
-                                    content: build_reflection_prompt(&tool_call.tool, &e, false),
-                                });
-                                msgs.push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: String::new(),
-                                });
+                                push_notice(&mut msgs, NoticeKind::Reflection, build_error_reflection_prompt(&tool_call.tool, &e), &strings);
                                 agent_ctx.state = AgentState::Reflecting;
                             } else {
                                 // Too many errors — add a final message explaining the situation
-                                msgs.push(Message {
-                                    role: MessageRole::System,
-                                    content: format!(
-                                        "Trop d'erreurs consécutives ({}). Arrête d'utiliser des outils et donne une réponse finale à l'utilisateur en expliquant ce que tu as essayé et ce qui n'a pas marché. Propose des solutions alternatives si possible.",
-                                        agent_ctx.consecutive_errors
-                                    ),
-                                });
-                                msgs.push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: String::new(),
-                                });
+                                push_notice(&mut msgs, NoticeKind::TooManyErrors, strings.too_many_errors(agent_ctx.consecutive_errors), &strings);
                                 // One last generation attempt for the final message
                             }
                         }
                     }
                 }
 
+                drop(session);
+                drop(events);
                 app_state.is_generating.set(false);
 
-                {
-                    let mut msgs = messages.write();
-                    if msgs
-                        .last()
-                        .map(|m| m.role == MessageRole::Assistant && m.content.is_empty())
-                        .unwrap_or(false)
-                    {
-                        msgs.pop();
+                let cache_stats = app_state.agent.web_cache.stats();
+                if cache_stats.hits + cache_stats.misses > 0 {
+                    tracing::info!(
+                        "web_fetch cache: {} hit(s), {} miss(es), hit rate {:.0}%, {} page(s) cached",
+                        cache_stats.hits,
+                        cache_stats.misses,
+                        cache_stats.hit_rate() * 100.0,
+                        cache_stats.entries
+                    );
+                }
+
+                // Placeholders never outlive the run
+                if messages.read().iter().any(|m| m.is_empty_reply()) {
+                    let kept = persisted_messages(&messages.read());
+                    messages.set(kept);
+                }
+
+                if let Some(recorder) = recorder.take() {
+                    let final_answer = messages.read().iter()
+                        .rev()
+                        .find(|m| m.role == MessageRole::Assistant)
+                        .map(|m| m.content.clone())
+                        .unwrap_or_default();
+                    let recording = recorder.finish(agent_ctx.iteration, &final_answer);
+                    match recordings_dir().and_then(|dir| save_recording(&recording, &dir)) {
+                        Ok(path) => tracing::info!("Run recorded to {}", path.display()),
+                        Err(e) => tracing::warn!("Failed to save run recording: {}", e),
                     }
                 }
                 
@@ -1339,27 +1738,29 @@ pub fn ChatView() -> Element {
                                 temperature: 0.3,
                                 top_k: 40,
                                 top_p: 0.9,
+                                min_p: 0.0,
                                 repeat_penalty: 1.1,
                                 seed: 0,
                                 max_context_size: 2048,
+                                auto_context: false,
+                                grammar: None,
+                                stop_sequences: Vec::new(),
+                                frequency_penalty: 0.0,
+                                presence_penalty: 0.0,
                             };
                             
                             let title_messages = vec![
                                 StorageMessage::new(StorageRole::User, title_prompt),
                             ];
                             
-                            // Generate title (non-blocking for the UI)
-                            let generated_title = {
+                            // Generate title (non-blocking for the UI, honors Stop)
+                            let stream = {
                                 let engine = app_state.engine.lock().await;
-                                if let Ok((rx, _)) = engine.generate_stream_messages(title_messages, title_params) {
-                                    let mut text = String::new();
-                                    while let Ok(token) = rx.recv() {
-                                        match token {
-                                            StreamToken::Token(t) => text.push_str(&t),
-                                            StreamToken::Done | StreamToken::Truncated { .. } => break,
-                                            StreamToken::Error(_) => break,
-                                        }
-                                    }
+                                engine.generate_stream_messages(title_messages, title_params)
+                            };
+                            let generated_title = {
+                                if let Ok((rx, stop)) = stream {
+                                    let (text, _) = collect_stream_text(rx, &stop, &app_state.stop_signal).await;
                                     // Clean up the title (remove thinking tags, quotes if present, trim)
                                     let cleaned = text
                                         .replace("<think>", "")
@@ -1410,17 +1811,169 @@ pub fn ChatView() -> Element {
                         }
                     }
                 }
+
+                app_state.send_queue.write().finish_run();
             });
         }
     };
 
+    // Handler for sending a message: starts a run, or queues it behind the active one
+    let handle_send = {
+        let mut send_queue = app_state.send_queue;
+        let mut start_run = start_run.clone();
+        move |draft: Draft| {
+            let outcome = send_queue.write().submit(draft, Instant::now());
+            if let SendOutcome::Start(draft) = outcome {
+                start_run(RunStart::Send(draft));
+            }
+        }
+    };
+
+    // Handler for editing a user message: drops it and everything after, then sends the new text
+    // with the same attachments
+    let handle_edit = {
+        let mut messages = messages;
+        let app_state = app_state.clone();
+        let mut handle_send = handle_send.clone();
+        move |(index, text): (usize, String)| {
+            if *app_state.is_generating.peek() || text.trim().is_empty() {
+                return;
+            }
+            let draft = match messages.peek().get(index) {
+                Some(edited) => Draft {
+                    content: attachments::replace_text(&edited.content, &text),
+                    attachments: edited.metadata.as_ref().map(|m| m.attachments.clone()).unwrap_or_default(),
+                },
+                None => Draft { content: text, attachments: Vec::new() },
+            };
+            messages.write().truncate(index);
+
+            let storage_messages: Vec<StorageMessage> = messages.peek()
+                .iter()
+                .cloned()
+                .map(|m| m.into())
+                .collect();
+            let mut current_conversation = app_state.current_conversation;
+            if let Some(ref mut conv) = *current_conversation.write() {
+                conv.messages = storage_messages;
+                // The title was made from the first exchange; let it be generated again
+                if index == 0 {
+                    conv.title = "New Conversation".to_string();
+                }
+                if let Err(e) = save_conversation(conv) {
+                    tracing::error!("Failed to save conversation: {}", e);
+                }
+            }
+
+            handle_send(draft);
+        }
+    };
+
+    // Handler for regenerating the last reply, optionally at another temperature
+    let handle_regenerate = {
+        let mut send_queue = app_state.send_queue;
+        let mut start_run = start_run.clone();
+        move |temperature: Option<f32>| {
+            if send_queue.write().start_regenerate() {
+                start_run(RunStart::Regenerate { temperature });
+            }
+        }
+    };
+
+    // Handler for forking: a new conversation with the messages up to the given one
+    let handle_fork = {
+        let app_state = app_state.clone();
+        move |index: usize| {
+            if *app_state.is_generating.peek() {
+                return;
+            }
+            let Some(mut snapshot) = app_state.current_conversation.peek().clone() else {
+                return;
+            };
+            snapshot.messages = messages.peek().iter().cloned().map(|m| m.into()).collect();
+            let fork = fork_conversation(&snapshot, index);
+            if let Err(e) = save_conversation(&fork) {
+                tracing::error!("Failed to save forked conversation: {}", e);
+                return;
+            }
+            tracing::info!("Forked conversation {} at message {} into {}", snapshot.id, index, fork.id);
+
+            let mut current_conversation = app_state.current_conversation;
+            let mut conversations = app_state.conversations;
+            current_conversation.set(Some(fork));
+            if let Ok(list) = list_conversations() {
+                conversations.set(list);
+            }
+        }
+    };
+
+    // Queued messages start once the active run is over, also after navigating back
+    {
+        let mut send_queue = app_state.send_queue;
+        let mut start_run = start_run.clone();
+        use_effect(move || {
+            if !send_queue.read().can_start_next() {
+                return;
+            }
+            let next = send_queue.write().start_next();
+            if let Some(draft) = next {
+                start_run(RunStart::Send(draft));
+            }
+        });
+    }
+
     // Handler for stopping generation
     let handle_stop = {
         let mut app_state = app_state.clone();
-        move |_| {
-            app_state.stop_signal.store(true, Ordering::Relaxed);
-            app_state.is_generating.set(false);
-        }
+        move |_| app_state.stop_generation()
+    };
+
+    let locale = app_state.locale();
+
+    // User message being re-run with other sampling settings
+    let mut experiment_target = use_signal(|| None::<usize>);
+
+    let mut send_queue = app_state.send_queue;
+    let queued: Vec<String> = send_queue
+        .read()
+        .pending()
+        .map(|draft| attachments::split_attachments(&draft.content).0.to_string())
+        .collect();
+    let queue_held = send_queue.read().is_held();
+
+    // Context meter: estimated conversation size against the allocated context
+    let used_tokens = estimate_tokens(&messages.read()) as u32;
+    let auto_context = app_state.settings.read().auto_context;
+    let context_limit = app_state.metrics.context_limit();
+    let context_total = match app_state.metrics.context_size() {
+        0 if auto_context => 0,
+        0 => app_state.context_cap(),
+        n => n,
+    };
+    let context_percent = if context_total > 0 {
+        (used_tokens as f32 / context_total as f32 * 100.0).min(100.0) as u32
+    } else {
+        0
+    };
+    let context_label = match (context_total, auto_context) {
+        (0, _) => format!("~{:.1}K / auto", used_tokens as f32 / 1024.0),
+        (total, true) if context_limit > total => format!(
+            "~{:.1}K / {}K (auto, max {}K)",
+            used_tokens as f32 / 1024.0, total / 1024, context_limit / 1024
+        ),
+        (total, true) => format!("~{:.1}K / {}K (auto)", used_tokens as f32 / 1024.0, total / 1024),
+        (total, false) => format!("~{:.1}K / {}K", used_tokens as f32 / 1024.0, total / 1024),
+    };
+    let show_context_meter = !messages.read().is_empty();
+
+    // The last reply of the conversation can be regenerated, once a user message precedes it
+    let last_reply = {
+        let msgs = messages.read();
+        last_reply_start(&msgs).and_then(|start| {
+            msgs.iter()
+                .rposition(|m| m.role == MessageRole::Assistant && !m.content.is_empty())
+                .filter(|&index| index >= start)
+        })
     };
 
     rsx! {
@@ -1428,17 +1981,84 @@ pub fn ChatView() -> Element {
             
             // Messages Area — narrower for readability
             div { class: "flex-1 min-h-0 overflow-y-auto px-4 py-4 custom-scrollbar scroll-smooth",
+                id: CHAT_SCROLL_ID,
+                // Announced as a log; busy while streaming so tokens are not read one by one
                 div { class: "max-w-3xl mx-auto w-full flex flex-col gap-1 pb-4",
+                    role: "log",
+                    "aria-live": "polite",
+                    "aria-busy": "{is_generating()}",
+                    "aria-label": "Conversation",
                     // Message List
                     for (idx, msg) in messages.read().iter().enumerate() {
-                        if msg.role != MessageRole::System {
-                            MessageBubble { key: "{idx}", message: msg.clone() }
+                        if msg.role == MessageRole::User && !is_generating() {
+                            MessageBubble {
+                                key: "{idx}",
+                                message: msg.clone(),
+                                dom_id: message_dom_id(idx),
+                                on_experiment: move |_| experiment_target.set(Some(idx)),
+                                on_edit: {
+                                    let mut handle_edit = handle_edit.clone();
+                                    move |text: String| handle_edit((idx, text))
+                                },
+                                on_fork: {
+                                    let mut handle_fork = handle_fork.clone();
+                                    move |_| handle_fork(idx)
+                                },
+                            }
+                        } else if !is_generating() && Some(idx) == last_reply {
+                            MessageBubble {
+                                key: "{idx}",
+                                message: msg.clone(),
+                                dom_id: message_dom_id(idx),
+                                anchor_prefix: format!("msg-{idx}"),
+                                on_regenerate: {
+                                    let mut handle_regenerate = handle_regenerate.clone();
+                                    move |temperature: Option<f32>| handle_regenerate(temperature)
+                                },
+                                on_fork: {
+                                    let mut handle_fork = handle_fork.clone();
+                                    move |_| handle_fork(idx)
+                                },
+                            }
+                        } else if !is_generating() && msg.role == MessageRole::Assistant {
+                            MessageBubble {
+                                key: "{idx}",
+                                message: msg.clone(),
+                                dom_id: message_dom_id(idx),
+                                anchor_prefix: format!("msg-{idx}"),
+                                on_fork: {
+                                    let mut handle_fork = handle_fork.clone();
+                                    move |_| handle_fork(idx)
+                                },
+                            }
+                        } else if msg.notice().is_some_and(|n| n.kind == NoticeKind::ModelSwitch) {
+                            div {
+                                key: "{idx}",
+                                id: message_dom_id(idx),
+                                class: "message-layout text-center text-xs text-[var(--text-tertiary)] py-1",
+                                role: "note",
+                                "{msg.content}"
+                            }
+                        } else if !matches!(msg.role, MessageRole::System | MessageRole::Tool) {
+                            MessageBubble {
+                                key: "{idx}",
+                                message: msg.clone(),
+                                dom_id: message_dom_id(idx),
+                                anchor_prefix: format!("msg-{idx}"),
+                            }
                         }
                     }
                     
+                    // Tool calls of the run still waiting or executing
+                    if is_generating() {
+                        ToolActivity {}
+                    }
+
                     // Typing / Generating Indicator — softer dots
                     if is_generating() {
                         div { class: "message-layout",
+                            role: "status",
+                            "aria-label": t!(locale, "chat.generating"),
                             div { class: "flex items-center gap-3 py-2 animate-fade-in",
                                 div {
                                     class: "w-6 h-6 rounded-full flex items-center justify-center",
@@ -1456,6 +2076,40 @@ pub fn ChatView() -> Element {
                     
                     div { class: "h-4" } // Spacer
                 }
+
+                // Content arrived below while the user reads further up
+                if has_unseen() {
+                    div { class: "sticky bottom-2 flex justify-center pointer-events-none",
+                        button {
+                            class: "pointer-events-auto glass-md px-3 py-1 rounded-full border border-[var(--border-subtle)] text-xs text-[var(--text-secondary)] hover:text-[var(--accent-primary)] shadow-lg transition-colors",
+                            onclick: move |_| {
+                                chat_scroll.write().following = true;
+                                has_unseen.set(false);
+                                spawn(async move {
+                                    let _ = document::eval(&scroll::scroll_js(None)).await;
+                                });
+                            },
+                            {t!(locale, "chat.new_messages")}
+                        }
+                    }
+                }
+            }
+
+            // Context meter
+            if show_context_meter {
+                div { class: "max-w-3xl mx-auto w-full px-4 flex items-center justify-end gap-2",
+                    div {
+                        class: "progress-track-mini",
+                        style: "width: 6rem;",
+                        role: "meter",
+                        "aria-label": t!(locale, "chat.context_used"),
+                        "aria-valuemin": "0",
+                        "aria-valuemax": "100",
+                        "aria-valuenow": "{context_percent}",
+                        div { class: "progress-fill", style: "width: {context_percent}%;" }
+                    }
+                    span { class: "text-[10px] font-mono text-[var(--text-tertiary)]", "{context_label}" }
+                }
             }
 
             // Input Area
@@ -1464,6 +2118,54 @@ pub fn ChatView() -> Element {
                 on_stop: handle_stop,
                 is_generating: is_generating(),
             }
+
+            // Messages waiting for the active run to end
+            if !queued.is_empty() {
+                div { class: "w-full px-4 -mt-3 pb-3",
+                    div { class: "max-w-3xl mx-auto flex flex-wrap items-center gap-2",
+                        role: "status",
+                        for (index, text) in queued.into_iter().enumerate() {
+                            span {
+                                key: "{index}",
+                                class: "inline-flex items-center gap-1.5 max-w-[260px] px-2.5 py-1 rounded-full glass-md text-[11px] text-[var(--text-secondary)]",
+                                title: "{text}",
+                                span { class: "font-semibold text-[var(--accent-primary)]",
+                                    {t!(locale, "chat.queued")}
+                                }
+                                span { class: "truncate", "{text}" }
+                                button {
+                                    class: "text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                    "aria-label": t!(locale, "chat.queue_remove"),
+                                    onclick: move |_| send_queue.write().remove(index),
+                                    "×"
+                                }
+                            }
+                        }
+                        if queue_held {
+                            span { class: "text-[11px] text-[var(--text-tertiary)]",
+                                {t!(locale, "chat.run_stopped")}
+                            }
+                            button {
+                                class: "text-[11px] font-medium text-[var(--accent-primary)] hover:underline",
+                                onclick: move |_| send_queue.write().keep(),
+                                {t!(locale, "chat.queue_keep")}
+                            }
+                            button {
+                                class: "text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                onclick: move |_| send_queue.write().discard(),
+                                {t!(locale, "chat.queue_discard")}
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(user_index) = experiment_target() {
+                ExperimentPanel {
+                    user_index,
+                    on_close: move |_| experiment_target.set(None),
+                }
+            }
         }
     }
 }
@@ -0,0 +1,226 @@
+//! Queue of user messages sent while a run is active
+//!
+//! Messages sent during a run wait here and start, in order, once it ends.
+//! Stopping a run holds the queue until the user keeps or discards it.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::types::message::Attachment;
+
+/// Identical sends closer than this are treated as one
+const DUPLICATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// A message ready to send: its full content and the files attached to it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Draft {
+    /// Typed text, followed by the attached content if any
+    pub content: String,
+    pub attachments: Vec<Attachment>,
+}
+
+impl From<&str> for Draft {
+    fn from(text: &str) -> Self {
+        Self { content: text.to_string(), attachments: Vec::new() }
+    }
+}
+
+/// What to do with a submitted message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendOutcome {
+    /// Nothing running: start a run with this message now
+    Start(Draft),
+    /// A run is active: the message waits its turn
+    Queued,
+    /// Same text as the previous send, just now: ignored
+    Duplicate,
+}
+
+#[derive(Debug, Default)]
+pub struct SendQueue {
+    pending: VecDeque<Draft>,
+    running: bool,
+    /// Set by Stop while messages are waiting, until kept or discarded
+    held: bool,
+    last_submit: Option<(Draft, Instant)>,
+}
+
+impl SendQueue {
+    pub fn submit(&mut self, draft: Draft, now: Instant) -> SendOutcome {
+        let duplicate = self.last_submit.as_ref().is_some_and(|(last, at)| {
+            last.content.trim() == draft.content.trim()
+                && last.attachments == draft.attachments
+                && now.saturating_duration_since(*at) < DUPLICATE_WINDOW
+        });
+        self.last_submit = Some((draft.clone(), now));
+        if duplicate {
+            return SendOutcome::Duplicate;
+        }
+
+        if self.running || self.held || !self.pending.is_empty() {
+            self.pending.push_back(draft);
+            SendOutcome::Queued
+        } else {
+            self.running = true;
+            SendOutcome::Start(draft)
+        }
+    }
+
+    /// Whether a queued message can start now
+    pub fn can_start_next(&self) -> bool {
+        !self.running && !self.held && !self.pending.is_empty()
+    }
+
+    /// Take the next message and mark its run as started
+    pub fn start_next(&mut self) -> Option<Draft> {
+        if !self.can_start_next() {
+            return None;
+        }
+        self.running = true;
+        self.pending.pop_front()
+    }
+
+    /// Start a run that regenerates the last reply, if nothing runs or waits
+    pub fn start_regenerate(&mut self) -> bool {
+        if self.running || self.held || !self.pending.is_empty() {
+            return false;
+        }
+        self.running = true;
+        true
+    }
+
+    /// The current run is over (finished, stopped or failed to start)
+    pub fn finish_run(&mut self) {
+        self.running = false;
+    }
+
+    /// Stop pressed: hold waiting messages instead of starting them
+    pub fn stop(&mut self) {
+        self.held = !self.pending.is_empty();
+    }
+
+    /// Start the held messages after all
+    pub fn keep(&mut self) {
+        self.held = false;
+    }
+
+    pub fn discard(&mut self) {
+        self.pending.clear();
+        self.held = false;
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.pending.remove(index);
+        if self.pending.is_empty() {
+            self.held = false;
+        }
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &Draft> {
+        self.pending.iter()
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.held
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_runs_in_order() {
+        let mut queue = SendQueue::default();
+        let t0 = Instant::now();
+
+        assert_eq!(queue.submit("first".into(), t0), SendOutcome::Start("first".into()));
+        assert!(queue.is_running());
+        assert_eq!(queue.submit("second".into(), t0 + Duration::from_millis(200)), SendOutcome::Queued);
+        assert_eq!(queue.submit("third".into(), t0 + Duration::from_millis(400)), SendOutcome::Queued);
+        assert_eq!(queue.start_next(), None);
+
+        queue.finish_run();
+        assert_eq!(queue.start_next().map(|d| d.content).as_deref(), Some("second"));
+        assert_eq!(queue.start_next(), None);
+        queue.finish_run();
+        assert_eq!(queue.start_next().map(|d| d.content).as_deref(), Some("third"));
+        queue.finish_run();
+        assert!(!queue.can_start_next());
+        assert_eq!(queue.submit("fourth".into(), t0 + Duration::from_secs(5)), SendOutcome::Start("fourth".into()));
+    }
+
+    #[test]
+    fn test_duplicate_sends_within_a_second() {
+        let mut queue = SendQueue::default();
+        let t0 = Instant::now();
+
+        assert_eq!(queue.submit("hello".into(), t0), SendOutcome::Start("hello".into()));
+        assert_eq!(queue.submit("hello ".into(), t0 + Duration::from_millis(300)), SendOutcome::Duplicate);
+        assert_eq!(queue.pending().count(), 0);
+        assert_eq!(queue.submit("hello".into(), t0 + Duration::from_millis(1500)), SendOutcome::Queued);
+
+        // Same text with a file attached is another message
+        let attached = Draft {
+            content: "hello".into(),
+            attachments: vec![Attachment { path: "notes.md".into(), mime: "text/markdown".into(), size: 8 }],
+        };
+        assert_eq!(queue.submit(attached, t0 + Duration::from_millis(1600)), SendOutcome::Queued);
+    }
+
+    #[test]
+    fn test_stop_holds_until_keep_or_discard() {
+        let mut queue = SendQueue::default();
+        let t0 = Instant::now();
+        queue.submit("a".into(), t0);
+        queue.submit("b".into(), t0 + Duration::from_secs(2));
+        queue.submit("c".into(), t0 + Duration::from_secs(3));
+
+        queue.stop();
+        queue.finish_run();
+        assert!(queue.is_held());
+        assert_eq!(queue.start_next(), None);
+        // New sends line up behind the held ones
+        assert_eq!(queue.submit("d".into(), t0 + Duration::from_secs(4)), SendOutcome::Queued);
+
+        queue.keep();
+        assert_eq!(queue.start_next().map(|d| d.content).as_deref(), Some("b"));
+
+        queue.stop();
+        queue.finish_run();
+        queue.discard();
+        assert!(!queue.is_held());
+        assert_eq!(queue.pending().count(), 0);
+        assert_eq!(queue.submit("e".into(), t0 + Duration::from_secs(6)), SendOutcome::Start("e".into()));
+    }
+
+    #[test]
+    fn test_stop_without_pending_does_not_hold() {
+        let mut queue = SendQueue::default();
+        queue.submit("a".into(), Instant::now());
+        queue.stop();
+        queue.finish_run();
+        assert!(!queue.is_held());
+    }
+
+    #[test]
+    fn test_regenerate_waits_for_an_idle_queue() {
+        let mut queue = SendQueue::default();
+        let t0 = Instant::now();
+
+        assert_eq!(queue.submit("first".into(), t0), SendOutcome::Start("first".into()));
+        assert!(!queue.start_regenerate());
+        queue.finish_run();
+
+        assert!(queue.start_regenerate());
+        assert!(queue.is_running());
+        assert_eq!(queue.submit("second".into(), t0 + Duration::from_secs(2)), SendOutcome::Queued);
+        queue.finish_run();
+        assert!(!queue.start_regenerate());
+        assert_eq!(queue.start_next().map(|d| d.content).as_deref(), Some("second"));
+    }
+}
@@ -0,0 +1,77 @@
+//! Scroll anchoring of the message list
+//!
+//! The chat follows new content only while the user is at the bottom;
+//! scrolled up, it stays put and offers a jump back down. The position is
+//! kept in `AppState` so it survives a trip to the settings.
+
+/// Distance from the bottom, in pixels, that still counts as "at the bottom"
+const FOLLOW_THRESHOLD_PX: f64 = 100.0;
+
+/// Element id of the scrolling message list
+pub const CHAT_SCROLL_ID: &str = "chat-scroll";
+
+/// Where the message list was last scrolled
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatScroll {
+    /// Conversation the position belongs to
+    pub conversation_id: Option<String>,
+    /// Pixels scrolled from the top
+    pub offset: f64,
+    /// At the bottom: new content scrolls into view
+    pub following: bool,
+}
+
+impl Default for ChatScroll {
+    fn default() -> Self {
+        Self { conversation_id: None, offset: 0.0, following: true }
+    }
+}
+
+impl ChatScroll {
+    /// Record a scroll event of the list (`scrollTop`, `scrollHeight`, `clientHeight`)
+    pub fn update(&mut self, top: f64, height: f64, visible: f64) {
+        self.offset = top;
+        self.following = height - top - visible <= FOLLOW_THRESHOLD_PX;
+    }
+}
+
+/// Script reporting every scroll of the list as `[scrollTop, scrollHeight, clientHeight]`
+pub fn scroll_listener_js() -> String {
+    format!(
+        "const el = document.getElementById({:?});
+        if (el) {{
+            el.addEventListener('scroll', () => dioxus.send([el.scrollTop, el.scrollHeight, el.clientHeight]), {{ passive: true }});
+        }}
+        await new Promise(() => {{}});",
+        CHAT_SCROLL_ID
+    )
+}
+
+/// Script scrolling the list to the bottom, or to `offset` pixels from the top
+pub fn scroll_js(offset: Option<f64>) -> String {
+    // Instant: a smooth scroll reports positions short of the bottom on the
+    // way down, which would stop the list from following
+    let top = offset.map_or("el.scrollHeight".to_string(), |offset| offset.to_string());
+    format!(
+        "const el = document.getElementById({:?}); if (el) {{ el.scrollTo({{ top: {}, behavior: 'instant' }}); }}",
+        CHAT_SCROLL_ID, top
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follows_only_near_the_bottom() {
+        let mut scroll = ChatScroll::default();
+        assert!(scroll.following);
+        scroll.update(1200.0, 2000.0, 720.0);
+        assert!(scroll.following);
+        scroll.update(300.0, 2000.0, 720.0);
+        assert!(!scroll.following);
+        assert_eq!(scroll.offset, 300.0);
+        assert!(scroll_js(Some(300.0)).contains("top: 300,"));
+        assert!(scroll_js(None).contains("top: el.scrollHeight,"));
+    }
+}
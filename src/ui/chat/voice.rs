@@ -0,0 +1,201 @@
+//! Push-to-talk button of the chat input
+//!
+//! Click to record, click again to stop (recording also stops at
+//! `MAX_RECORDING`). The transcript lands in the input box for review; it is
+//! never sent as is.
+
+use crate::agent::language::Locale;
+use crate::app::AppState;
+use crate::inference::voice::{transcribe, Recorder, VoiceError, MAX_RECORDING};
+use crate::storage::models::whisper_model_path;
+use crate::ui::i18n::t;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VoiceState {
+    Idle,
+    Starting,
+    Recording,
+    /// Percentage done
+    Transcribing(i32),
+}
+
+/// What to tell the user, with what to do about it
+pub fn voice_error_message(error: &VoiceError, locale: Locale) -> String {
+    match error {
+        VoiceError::NotBuilt => t!(locale, "voice.not_built").to_string(),
+        VoiceError::NoInputDevice => t!(locale, "voice.no_input_device").to_string(),
+        VoiceError::Microphone(e) => t!(locale, "voice.microphone", e),
+        VoiceError::NothingRecorded => t!(locale, "voice.nothing_recorded").to_string(),
+        VoiceError::Silent => t!(locale, "voice.silent").to_string(),
+        VoiceError::ModelMissing(_) => t!(locale, "voice.model_missing").to_string(),
+        VoiceError::Transcription(e) => t!(locale, "voice.transcription_failed", e),
+    }
+}
+
+/// "0:42"
+fn format_clock(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[component]
+pub fn VoiceButton(on_transcript: EventHandler<String>, on_error: EventHandler<String>, locale: Locale) -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings;
+    let mut state = use_signal(|| VoiceState::Idle);
+    let mut recorder = use_signal(|| None::<Recorder>);
+    let mut elapsed = use_signal(|| Duration::ZERO);
+
+    // Stop recording and transcribe off the UI thread
+    let mut finish = move || {
+        let Some(active) = recorder.write().take() else {
+            return;
+        };
+        let voice = settings.peek().voice.clone();
+        state.set(VoiceState::Transcribing(0));
+        spawn(async move {
+            let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+            let job = tokio::task::spawn_blocking(move || {
+                let audio = active.stop()?;
+                let model = whisper_model_path(&voice.model).map_err(|e| VoiceError::Transcription(e.to_string()))?;
+                transcribe(&model, &audio, &voice.language, move |percent| {
+                    let _ = progress_tx.send(percent);
+                })
+            });
+            // The sender goes away with the job, which ends this loop
+            while let Some(percent) = progress_rx.recv().await {
+                state.set(VoiceState::Transcribing(percent));
+            }
+            let outcome = job
+                .await
+                .unwrap_or_else(|e| Err(VoiceError::Transcription(e.to_string())));
+            state.set(VoiceState::Idle);
+            match outcome {
+                Ok(text) if !text.is_empty() => on_transcript.call(text),
+                Ok(_) => on_error.call(voice_error_message(&VoiceError::NothingRecorded, locale)),
+                Err(e) => {
+                    tracing::warn!("Voice input failed: {}", e);
+                    on_error.call(voice_error_message(&e, locale));
+                }
+            }
+        });
+    };
+
+    let mut start = move || {
+        let model = settings.peek().voice.model.clone();
+        match whisper_model_path(&model) {
+            Ok(path) if path.exists() => {}
+            Ok(path) => {
+                on_error.call(voice_error_message(&VoiceError::ModelMissing(path), locale));
+                return;
+            }
+            Err(e) => {
+                on_error.call(e.to_string());
+                return;
+            }
+        }
+        state.set(VoiceState::Starting);
+        spawn(async move {
+            let started = tokio::task::spawn_blocking(Recorder::start)
+                .await
+                .unwrap_or_else(|e| Err(VoiceError::Microphone(e.to_string())));
+            match started {
+                Ok(active) => {
+                    recorder.set(Some(active));
+                    elapsed.set(Duration::ZERO);
+                    state.set(VoiceState::Recording);
+                }
+                Err(e) => {
+                    state.set(VoiceState::Idle);
+                    on_error.call(voice_error_message(&e, locale));
+                    return;
+                }
+            }
+            // Clock of the indicator; also notices the time limit
+            loop {
+                tokio::time::sleep(Duration::from_millis(250)).await;
+                let Some((time, done)) = recorder.peek().as_ref().map(|r| (r.elapsed(), r.is_finished())) else {
+                    break;
+                };
+                elapsed.set(time);
+                if done {
+                    finish();
+                    break;
+                }
+            }
+        });
+    };
+
+    let current = state();
+    let (title, button_class) = match current {
+        VoiceState::Recording => (
+            t!(locale, "voice.stop"),
+            "text-[var(--error)] animate-pulse",
+        ),
+        VoiceState::Starting => (
+            t!(locale, "voice.opening"),
+            "text-[var(--text-tertiary)] opacity-60 cursor-wait",
+        ),
+        VoiceState::Transcribing(_) => (
+            t!(locale, "voice.transcribing"),
+            "text-[var(--text-tertiary)] opacity-60 cursor-wait",
+        ),
+        VoiceState::Idle => (
+            t!(locale, "voice.dictate"),
+            "text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+        ),
+    };
+    let clock = format!("{} / {}", format_clock(elapsed()), format_clock(MAX_RECORDING));
+
+    rsx! {
+        div { class: "flex-shrink-0 flex items-center",
+            button {
+                class: "w-9 h-9 rounded-full flex items-center justify-center transition-colors {button_class}",
+                title: "{title}",
+                "aria-label": "{title}",
+                "aria-pressed": "{current == VoiceState::Recording}",
+                disabled: matches!(current, VoiceState::Starting | VoiceState::Transcribing(_)),
+                onclick: move |_| match state() {
+                    VoiceState::Idle => start(),
+                    VoiceState::Recording => finish(),
+                    _ => {}
+                },
+                svg {
+                    width: "16",
+                    height: "16",
+                    view_box: "0 0 24 24",
+                    fill: "none",
+                    stroke: "currentColor",
+                    stroke_width: "2",
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                    path { d: "M12 1a3 3 0 0 0-3 3v8a3 3 0 0 0 6 0V4a3 3 0 0 0-3-3z" }
+                    path { d: "M19 10v2a7 7 0 0 1-14 0v-2" }
+                    line { x1: "12", y1: "19", x2: "12", y2: "23" }
+                }
+            }
+            match current {
+                VoiceState::Recording => rsx! {
+                    span { class: "text-[11px] tabular-nums text-[var(--error)] mr-1", role: "status", "{clock}" }
+                },
+                VoiceState::Transcribing(percent) => rsx! {
+                    span { class: "text-[11px] tabular-nums text-[var(--text-tertiary)] mr-1", role: "status", "{percent}%" }
+                },
+                _ => rsx! {},
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_clock() {
+        assert_eq!(format_clock(Duration::from_millis(42_900)), "0:42");
+        assert_eq!(format_clock(MAX_RECORDING), "2:00");
+    }
+}
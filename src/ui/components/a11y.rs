@@ -0,0 +1,91 @@
+//! Accessibility helpers
+//!
+//! Focus management for dialogs and keyboard-operable toggles.
+
+use dioxus::prelude::*;
+
+/// Index to focus next inside a focus trap
+///
+/// Tab moves forward and Shift+Tab backward, wrapping at both ends. When
+/// nothing in the trap has focus yet, Tab starts at the first element and
+/// Shift+Tab at the last.
+pub fn cycle_focus(current: Option<usize>, len: usize, backwards: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (current, backwards) {
+        (None, false) => 0,
+        (None, true) => len - 1,
+        (Some(i), false) => (i + 1) % len,
+        (Some(i), true) => (i + len - 1) % len,
+    })
+}
+
+/// Whether a key activates a focused toggle (Enter or Space)
+pub fn is_activation_key(key: &Key) -> bool {
+    match key {
+        Key::Enter => true,
+        Key::Character(c) => c == " ",
+        _ => false,
+    }
+}
+
+/// Move keyboard focus to the element with this id, once it is rendered
+pub fn focus_element(id: &str) {
+    let js = format!(
+        "setTimeout(() => {{ const el = document.getElementById({:?}); if (el) {{ el.focus(); }} }}, 0);",
+        id
+    );
+    spawn(async move {
+        let _ = document::eval(&js).await;
+    });
+}
+
+/// Value of the root `data-reduced-motion` attribute for a settings value
+///
+/// "auto" leaves the decision to the `prefers-reduced-motion` media query.
+pub fn reduced_motion_attr(setting: &str) -> &'static str {
+    match setting {
+        "on" => "on",
+        "off" => "off",
+        _ => "auto",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_focus_wraps_both_ways() {
+        // Parameters -> Deny -> Approve -> Parameters
+        assert_eq!(cycle_focus(Some(0), 3, false), Some(1));
+        assert_eq!(cycle_focus(Some(2), 3, false), Some(0));
+        assert_eq!(cycle_focus(Some(0), 3, true), Some(2));
+        assert_eq!(cycle_focus(Some(1), 3, true), Some(0));
+    }
+
+    #[test]
+    fn test_cycle_focus_entry_and_empty() {
+        assert_eq!(cycle_focus(None, 3, false), Some(0));
+        assert_eq!(cycle_focus(None, 3, true), Some(2));
+        assert_eq!(cycle_focus(None, 0, false), None);
+        assert_eq!(cycle_focus(Some(0), 1, false), Some(0));
+    }
+
+    #[test]
+    fn test_activation_keys() {
+        assert!(is_activation_key(&Key::Enter));
+        assert!(is_activation_key(&Key::Character(" ".to_string())));
+        assert!(!is_activation_key(&Key::Tab));
+        assert!(!is_activation_key(&Key::Character("a".to_string())));
+    }
+
+    #[test]
+    fn test_reduced_motion_attr() {
+        assert_eq!(reduced_motion_attr("on"), "on");
+        assert_eq!(reduced_motion_attr("off"), "off");
+        assert_eq!(reduced_motion_attr("auto"), "auto");
+        assert_eq!(reduced_motion_attr(""), "auto");
+    }
+}
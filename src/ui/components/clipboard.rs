@@ -0,0 +1,52 @@
+//! Clipboard access for the UI
+//!
+//! Everything copied from the interface goes through the webview's
+//! clipboard API here, so it behaves the same on every platform.
+
+use crate::app::AppState;
+use crate::ui::i18n::{lookup, t};
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// Put `text` on the clipboard
+pub fn copy_to_clipboard(text: &str) {
+    let js = format!(
+        "navigator.clipboard.writeText({})",
+        serde_json::to_string(text).unwrap_or_default()
+    );
+    spawn(async move {
+        let _ = document::eval(&js).await;
+    });
+}
+
+/// Button copying `text`, which reads "Copied" for a moment afterwards;
+/// `label` is the catalog key of its text
+#[component]
+pub fn CopyButton(text: String, label: &'static str, class: Option<String>) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let mut copied = use_signal(|| false);
+    let class = class.unwrap_or_else(|| {
+        "text-[11px] text-[var(--text-tertiary)] hover:text-[var(--accent-primary)] transition-colors".to_string()
+    });
+
+    rsx! {
+        button {
+            r#type: "button",
+            class: "{class}",
+            onclick: move |_| {
+                copy_to_clipboard(&text);
+                copied.set(true);
+                spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(1500)).await;
+                    copied.set(false);
+                });
+            },
+            if copied() {
+                {t!(locale, "copy.copied")}
+            } else {
+                {lookup(locale, label)}
+            }
+        }
+    }
+}
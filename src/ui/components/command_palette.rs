@@ -0,0 +1,239 @@
+//! Command palette (Ctrl+K)
+//!
+//! A modal listing actions, filtered by a fuzzy match on their labels.
+//! `Layout` lists the built-in ones; any component can add its own with
+//! `use_command` for as long as it is mounted.
+
+use crate::agent::language::Locale;
+use crate::ui::components::a11y::focus_element;
+use crate::ui::i18n::t;
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+const PALETTE_INPUT_ID: &str = "command-palette-input";
+
+/// An action of the palette
+#[derive(Clone)]
+pub struct Command {
+    /// Stable id; registering the same id again replaces the entry
+    pub id: String,
+    pub label: String,
+    pub action: Rc<dyn Fn()>,
+}
+
+impl Command {
+    pub fn new(id: impl Into<String>, label: impl Into<String>, action: impl Fn() + 'static) -> Self {
+        Self { id: id.into(), label: label.into(), action: Rc::new(action) }
+    }
+}
+
+impl PartialEq for Command {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.label == other.label && Rc::ptr_eq(&self.action, &other.action)
+    }
+}
+
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command").field("id", &self.id).field("label", &self.label).finish()
+    }
+}
+
+/// Commands contributed by mounted components, shared through the context
+#[derive(Clone, Copy)]
+pub struct CommandRegistry {
+    commands: Signal<Vec<Command>>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self { commands: Signal::new(Vec::new()) }
+    }
+}
+
+impl CommandRegistry {
+    pub fn register(&mut self, command: Command) {
+        let mut commands = self.commands.write();
+        match commands.iter_mut().find(|c| c.id == command.id) {
+            Some(existing) => *existing = command,
+            None => commands.push(command),
+        }
+    }
+
+    pub fn unregister(&mut self, id: &str) {
+        self.commands.write().retain(|c| c.id != id);
+    }
+
+    pub fn commands(&self) -> Vec<Command> {
+        self.commands.read().clone()
+    }
+}
+
+/// Add a command to the palette while the calling component is mounted
+pub fn use_command(make: impl FnOnce() -> Command) {
+    let mut registry = use_context::<CommandRegistry>();
+    let id = use_hook(move || {
+        let command = make();
+        let id = command.id.clone();
+        registry.register(command);
+        id
+    });
+    use_drop(move || registry.unregister(&id));
+}
+
+/// How well `query` matches `text`: its characters must appear in order,
+/// ignoring case and spaces; consecutive characters and word starts score
+/// higher. `None` when it does not match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+    for wanted in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if found > 0 && previous == Some(found - 1) {
+            score += 3;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Commands matching `query`, best first; ties keep their order
+fn filter_commands(commands: &[Command], query: &str) -> Vec<Command> {
+    let mut scored: Vec<(u32, &Command)> = commands
+        .iter()
+        .filter_map(|command| Some((fuzzy_score(query, &command.label)?, command)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, command)| command.clone()).collect()
+}
+
+#[component]
+pub fn CommandPalette(commands: Vec<Command>, on_close: EventHandler<()>, locale: Locale) -> Element {
+    let mut query = use_signal(String::new);
+    let mut selected = use_signal(|| 0usize);
+    use_hook(|| focus_element(PALETTE_INPUT_ID));
+
+    let matches = filter_commands(&commands, &query());
+    let count = matches.len();
+    let current = selected().min(count.saturating_sub(1));
+
+    let run = move |command: &Command| {
+        on_close.call(());
+        (command.action)();
+    };
+
+    let handle_keydown = {
+        let matches = matches.clone();
+        move |evt: KeyboardEvent| match evt.key() {
+            Key::ArrowDown if count > 0 => {
+                evt.prevent_default();
+                selected.set((current + 1) % count);
+            }
+            Key::ArrowUp if count > 0 => {
+                evt.prevent_default();
+                selected.set((current + count - 1) % count);
+            }
+            Key::Enter => {
+                evt.prevent_default();
+                if let Some(command) = matches.get(current) {
+                    run(command);
+                }
+            }
+            Key::Escape => on_close.call(()),
+            _ => {}
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 z-50 flex items-start justify-center pt-[15vh] bg-black/40 animate-fade-in",
+            onclick: move |_| on_close.call(()),
+            div {
+                class: "w-full max-w-lg rounded-xl overflow-hidden",
+                style: "background: var(--bg-elevated); border: 1px solid var(--border-medium); box-shadow: 0 12px 32px -4px rgba(30,25,20,0.35);",
+                role: "dialog",
+                "aria-modal": "true",
+                "aria-label": t!(locale, "palette.title"),
+                onclick: move |evt| evt.stop_propagation(),
+                input {
+                    id: PALETTE_INPUT_ID,
+                    r#type: "text",
+                    class: "w-full px-4 py-3 bg-transparent outline-none text-sm text-[var(--text-primary)] border-b border-[var(--border-subtle)] placeholder:text-[var(--text-tertiary)]",
+                    placeholder: t!(locale, "palette.placeholder"),
+                    role: "combobox",
+                    "aria-controls": "command-palette-list",
+                    "aria-activedescendant": "command-{current}",
+                    value: "{query}",
+                    oninput: move |evt| {
+                        query.set(evt.value());
+                        selected.set(0);
+                    },
+                    onkeydown: handle_keydown,
+                }
+                ul {
+                    id: "command-palette-list",
+                    class: "max-h-80 overflow-y-auto custom-scrollbar py-1",
+                    role: "listbox",
+                    if matches.is_empty() {
+                        li { class: "px-4 py-3 text-xs text-[var(--text-tertiary)]",
+                            {t!(locale, "palette.no_match")}
+                        }
+                    }
+                    for (index, command) in matches.into_iter().enumerate() {
+                        li {
+                            key: "{command.id}",
+                            id: "command-{index}",
+                            role: "option",
+                            "aria-selected": "{index == current}",
+                            class: if index == current {
+                                "px-4 py-2 text-sm cursor-pointer truncate bg-[var(--accent-soft)] text-[var(--accent-primary)]"
+                            } else {
+                                "px-4 py-2 text-sm cursor-pointer truncate text-[var(--text-primary)] hover:bg-white/[0.04]"
+                            },
+                            onmouseenter: move |_| selected.set(index),
+                            onclick: {
+                                let command = command.clone();
+                                move |_| run(&command)
+                            },
+                            "{command.label}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("nc", "New chat").is_some());
+        assert_eq!(fuzzy_score("xyz", "New chat"), None);
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        // Consecutive letters and word starts rank first
+        assert!(fuzzy_score("set", "Open settings") > fuzzy_score("set", "Select a theme"));
+        assert!(fuzzy_score("rust", "Rust lifetimes") > fuzzy_score("rust", "Review unsafe tests"));
+    }
+
+    #[test]
+    fn test_filter_commands_orders_by_score() {
+        let commands = vec![
+            Command::new("a", "Switch model: qwen", || {}),
+            Command::new("b", "Open settings", || {}),
+            Command::new("c", "Open conversation: Setup notes", || {}),
+        ];
+        let ids: Vec<String> = filter_commands(&commands, "set").into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+        assert_eq!(filter_commands(&commands, "").len(), 3);
+    }
+}
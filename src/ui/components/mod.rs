@@ -2,7 +2,11 @@
 //!
 //! Reusable components like buttons, inputs, cards, and other primitives.
 
+pub mod a11y;
+pub mod clipboard;
+pub mod command_palette;
 pub mod loading;
 pub mod monitoring;
 pub mod permission_dialog;
+pub mod shortcuts;
 pub mod tool_usage;
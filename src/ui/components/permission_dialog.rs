@@ -2,15 +2,38 @@
 //!
 //! Displays permission requests and allows user approval/denial
 
+use crate::agent::language::Locale;
 use crate::agent::permissions::PermissionLevel;
 use crate::app::AppState;
+use crate::ui::components::a11y::{cycle_focus, focus_element};
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
 
+/// Focusable elements of the dialog, in Tab order
+const FOCUS_ORDER: [&str; 3] = ["permission-params", "permission-deny", "permission-approve"];
+
 /// Permission dialog component
 #[component]
 pub fn PermissionDialog() -> Element {
     let app_state = use_context::<AppState>();
     let signals = app_state.agent.permission_manager.signals();
+    let mut focused = use_signal(|| None::<usize>);
+
+    // Focus "Deny" when a request appears, give focus back to the chat input once all are answered
+    {
+        let pending = signals.pending_requests;
+        let mut was_open = use_signal(|| false);
+        use_effect(move || {
+            let open = !pending.read().is_empty();
+            if open {
+                focus_element(FOCUS_ORDER[1]);
+            } else if *was_open.peek() {
+                focus_element("chat-input");
+            }
+            was_open.set(open);
+        });
+    }
+
     let requests = signals.pending_requests.read();
 
     if requests.is_empty() {
@@ -22,7 +45,29 @@ pub fn PermissionDialog() -> Element {
     let manager = app_state.agent.permission_manager.clone();
     let manager_deny = manager.clone();
     let manager_approve = manager.clone();
-    let is_en = app_state.settings.read().language == "en";
+    let manager_escape = manager.clone();
+    let locale = app_state.locale();
+
+    // Keep Tab inside the dialog; Escape denies
+    let handle_keydown = move |evt: KeyboardEvent| {
+        match evt.key() {
+            Key::Tab => {
+                evt.prevent_default();
+                let backwards = evt.modifiers().contains(Modifiers::SHIFT);
+                if let Some(next) = cycle_focus(focused(), FOCUS_ORDER.len(), backwards) {
+                    focus_element(FOCUS_ORDER[next]);
+                }
+            }
+            Key::Escape => {
+                evt.prevent_default();
+                let manager = manager_escape.clone();
+                spawn(async move {
+                    let _ = manager.deny(request_id).await;
+                });
+            }
+            _ => {}
+        }
+    };
 
     rsx! {
         // Backdrop — heavy blur
@@ -32,6 +77,11 @@ pub fn PermissionDialog() -> Element {
             // Dialog — glass-strong with spring animation
             div {
                 class: "w-full max-w-lg glass-strong rounded-2xl overflow-hidden animate-scale-in",
+                role: "alertdialog",
+                "aria-modal": "true",
+                "aria-labelledby": "permission-dialog-title",
+                "aria-describedby": "permission-dialog-description",
+                onkeydown: handle_keydown,
 
                 // Header — with warning icon
                 div {
@@ -59,14 +109,16 @@ pub fn PermissionDialog() -> Element {
                         }
 
                         h2 {
+                            id: "permission-dialog-title",
                             class: "text-lg font-semibold text-[var(--text-primary)]",
-                            if is_en { "Permission Required" } else { "Permission requise" }
+                            {t!(locale, "permission.title")}
                         }
                     }
 
                     p {
+                        id: "permission-dialog-description",
                         class: "text-sm text-[var(--text-secondary)]",
-                        if is_en { "The AI agent is requesting permission to perform an action." } else { "L'agent IA demande la permission d'effectuer une action." }
+                        {t!(locale, "permission.description")}
                     }
                 }
 
@@ -81,7 +133,7 @@ pub fn PermissionDialog() -> Element {
                         div {
                             class: "flex items-center justify-between mb-2",
                             span { class: "text-sm font-medium text-[var(--text-secondary)]",
-                                if is_en { "Tool" } else { "Outil" }
+                                {t!(locale, "permission.tool")}
                             }
                             span { class: "text-sm text-[var(--accent-primary)] font-medium", "{current_request.tool_name}" }
                         }
@@ -89,7 +141,7 @@ pub fn PermissionDialog() -> Element {
                         div {
                             class: "flex items-center justify-between mb-2",
                             span { class: "text-sm font-medium text-[var(--text-secondary)]",
-                                if is_en { "Operation" } else { "Operation" }
+                                {t!(locale, "permission.operation")}
                             }
                             span { class: "text-sm text-[var(--text-primary)]", "{current_request.operation}" }
                         }
@@ -97,7 +149,7 @@ pub fn PermissionDialog() -> Element {
                         div {
                             class: "flex items-center justify-between",
                             span { class: "text-sm font-medium text-[var(--text-secondary)]",
-                                if is_en { "Level" } else { "Niveau" }
+                                {t!(locale, "permission.level")}
                             }
                             PermissionLevelBadge { level: current_request.level }
                         }
@@ -107,16 +159,23 @@ pub fn PermissionDialog() -> Element {
                     div {
                         class: "p-4 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)]",
                         span { class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
-                            if is_en { "Target" } else { "Cible" }
+                            {t!(locale, "permission.target")}
                         }
                         p { class: "mt-1 text-sm font-mono text-[var(--text-secondary)] break-all", "{current_request.target}" }
                     }
 
+                    if let Some(diff) = current_request.preview.clone() {
+                        DiffPreview { diff, locale }
+                    }
+
                     // Parameters
                     details {
                         class: "p-4 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)]",
-                        summary { class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold cursor-pointer",
-                            if is_en { "Parameters" } else { "Parametres" }
+                        summary {
+                            id: "permission-params",
+                            class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold cursor-pointer",
+                            onfocus: move |_| focused.set(Some(0)),
+                            {t!(locale, "permission.parameters")}
                         }
                         pre { class: "mt-2 text-xs text-[var(--text-secondary)] overflow-x-auto font-mono", "{serde_json::to_string_pretty(&current_request.params).unwrap_or_default()}" }
                     }
@@ -127,25 +186,59 @@ pub fn PermissionDialog() -> Element {
                     class: "p-6 border-t border-[var(--border-subtle)] flex gap-3",
 
                     button {
+                        id: "permission-deny",
                         class: "btn-ghost flex-1",
+                        onfocus: move |_| focused.set(Some(1)),
                         onclick: move |_| {
                             let manager = manager_deny.clone();
                             spawn(async move {
                                 let _ = manager.deny(request_id).await;
                             });
                         },
-                        if is_en { "Deny" } else { "Refuser" }
+                        {t!(locale, "permission.deny")}
                     }
 
                     button {
+                        id: "permission-approve",
                         class: "btn-primary flex-1",
+                        onfocus: move |_| focused.set(Some(2)),
                         onclick: move |_| {
                             let manager = manager_approve.clone();
                             spawn(async move {
                                 let _ = manager.approve(request_id).await;
                             });
                         },
-                        if is_en { "Approve" } else { "Approuver" }
+                        {t!(locale, "permission.approve")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Proposed file change, collapsed behind a "show changes" toggle
+#[component]
+pub fn DiffPreview(diff: String, locale: Locale) -> Element {
+    rsx! {
+        details {
+            class: "p-4 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)]",
+            summary {
+                class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold cursor-pointer",
+                {t!(locale, "permission.show_changes")}
+            }
+            pre {
+                class: "mt-2 max-h-64 overflow-auto text-xs font-mono",
+                for (index, line) in diff.lines().enumerate() {
+                    {
+                        let color = match line.chars().next() {
+                            _ if line == "---" => "var(--text-tertiary)",
+                            Some('+') => "var(--success)",
+                            Some('-') => "var(--error)",
+                            _ => "var(--text-secondary)",
+                        };
+                        rsx! {
+                            div { key: "{index}", style: "color: {color};", "{line}" }
+                        }
                     }
                 }
             }
@@ -157,30 +250,30 @@ pub fn PermissionDialog() -> Element {
 #[component]
 fn PermissionLevelBadge(level: PermissionLevel) -> Element {
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
     let (label, bg_style) = match level {
         PermissionLevel::ReadOnly => (
-            if is_en { "Read only" } else { "Lecture seule" },
+            t!(locale, "permission.read_only"),
             "background: rgba(52,211,153,0.10); color: #34d399; border: 1px solid rgba(52,211,153,0.20);"
         ),
         PermissionLevel::WriteFile => (
-            if is_en { "File write" } else { "Ecriture fichier" },
+            t!(locale, "permission.write_file"),
             "background: rgba(251,191,36,0.10); color: #fbbf24; border: 1px solid rgba(251,191,36,0.20);"
         ),
         PermissionLevel::ReadWrite => (
-            if is_en { "Read/Write" } else { "Lecture/Ecriture" },
+            t!(locale, "permission.read_write"),
             "background: rgba(251,191,36,0.10); color: #fbbf24; border: 1px solid rgba(251,191,36,0.20);"
         ),
         PermissionLevel::ExecuteSafe => (
-            if is_en { "Safe commands" } else { "Commandes sures" },
+            t!(locale, "permission.execute_safe"),
             "background: rgba(251,146,60,0.10); color: #fb923c; border: 1px solid rgba(251,146,60,0.20);"
         ),
         PermissionLevel::ExecuteUnsafe => (
-            if is_en { "Unsafe commands" } else { "Commandes dangereuses" },
+            t!(locale, "permission.execute_unsafe"),
             "background: rgba(248,113,113,0.10); color: #f87171; border: 1px solid rgba(248,113,113,0.20);"
         ),
         PermissionLevel::Network => (
-            if is_en { "Network" } else { "Reseau" },
+            t!(locale, "permission.network"),
             "background: rgba(56,189,248,0.10); color: #38bdf8; border: 1px solid rgba(56,189,248,0.20);"
         ),
     };
@@ -0,0 +1,116 @@
+//! Application-wide keyboard shortcuts
+//!
+//! A document-level listener reports key presses to `Layout`, which runs the
+//! matching action. Ctrl stands for Cmd on macOS. Outside the input-safe
+//! ones, shortcuts stay quiet while a text field has focus, so typing is
+//! never hijacked.
+
+/// An action bound to a key combination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+    NewConversation,
+    CommandPalette,
+    /// Sends the chat input; the input handles it itself
+    Send,
+    StopGeneration,
+    SearchConversations,
+    OpenSettings,
+}
+
+impl Shortcut {
+    pub const ALL: [Shortcut; 6] = [
+        Shortcut::NewConversation,
+        Shortcut::CommandPalette,
+        Shortcut::Send,
+        Shortcut::StopGeneration,
+        Shortcut::SearchConversations,
+        Shortcut::OpenSettings,
+    ];
+
+    /// `KeyboardEvent.key` in lowercase, and whether Ctrl/Cmd is held
+    fn binding(self) -> (&'static str, bool) {
+        match self {
+            Shortcut::NewConversation => ("n", true),
+            Shortcut::CommandPalette => ("k", true),
+            Shortcut::Send => ("enter", true),
+            Shortcut::StopGeneration => ("escape", false),
+            Shortcut::SearchConversations => ("f", true),
+            Shortcut::OpenSettings => (",", true),
+        }
+    }
+
+    /// Fires even while a text field has focus
+    fn input_safe(self) -> bool {
+        matches!(self, Shortcut::Send | Shortcut::StopGeneration)
+    }
+
+    /// Key combination as shown to the user
+    pub fn keys(self) -> &'static str {
+        match self {
+            Shortcut::NewConversation => "Ctrl+N",
+            Shortcut::CommandPalette => "Ctrl+K",
+            Shortcut::Send => "Ctrl+Enter",
+            Shortcut::StopGeneration => "Esc",
+            Shortcut::SearchConversations => "Ctrl+F",
+            Shortcut::OpenSettings => "Ctrl+,",
+        }
+    }
+}
+
+/// The shortcut a key press triggers, if any
+pub fn match_shortcut(key: &str, primary: bool, in_text_field: bool) -> Option<Shortcut> {
+    let key = key.to_lowercase();
+    Shortcut::ALL.into_iter().find(|shortcut| {
+        let (bound_key, bound_primary) = shortcut.binding();
+        bound_key == key && bound_primary == primary && (shortcut.input_safe() || !in_text_field)
+    })
+}
+
+/// Script reporting key presses as `[key, ctrl or cmd held, focus in a text field]`
+///
+/// Bound combinations lose their browser default (Ctrl+F find, Ctrl+N new
+/// window). Presses in the chat input and inside dialogs are left to them:
+/// the input sends and stops on its own, a dialog closes on Escape.
+pub fn shortcut_listener_js() -> String {
+    let bindings: Vec<String> = Shortcut::ALL
+        .into_iter()
+        .map(|shortcut| {
+            let (key, primary) = shortcut.binding();
+            format!("[{:?}, {}, {}]", key, primary, shortcut.input_safe())
+        })
+        .collect();
+    format!(
+        "const bindings = [{}];
+        document.addEventListener('keydown', (e) => {{
+            const target = e.target;
+            if (target.id === 'chat-input' || (target.closest && target.closest('[role=\"dialog\"]'))) return;
+            const key = e.key.toLowerCase();
+            const primary = e.ctrlKey || e.metaKey;
+            const inField = target.isContentEditable || ['INPUT', 'TEXTAREA', 'SELECT'].includes(target.tagName);
+            if (!bindings.some(([k, p, safe]) => k === key && p === primary && (safe || !inField))) return;
+            e.preventDefault();
+            dioxus.send([key, primary, inField]);
+        }});
+        await new Promise(() => {{}});",
+        bindings.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcuts_respect_text_fields() {
+        assert_eq!(match_shortcut("k", true, false), Some(Shortcut::CommandPalette));
+        assert_eq!(match_shortcut("K", true, false), Some(Shortcut::CommandPalette));
+        assert_eq!(match_shortcut("k", false, false), None);
+        assert_eq!(match_shortcut(",", true, false), Some(Shortcut::OpenSettings));
+        // Typing in a field is never taken over, except by the input-safe keys
+        assert_eq!(match_shortcut("n", true, true), None);
+        assert_eq!(match_shortcut("f", true, true), None);
+        assert_eq!(match_shortcut("Escape", false, true), Some(Shortcut::StopGeneration));
+        assert_eq!(match_shortcut("Enter", true, true), Some(Shortcut::Send));
+        assert!(shortcut_listener_js().contains("[\"n\", true, false]"));
+    }
+}
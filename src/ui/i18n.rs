@@ -0,0 +1,1205 @@
+//! UI string catalog
+//!
+//! Strings are looked up by key in the interface locale with
+//! `t!(app_state.locale(), "key")`, arguments filling its `{}` in order.
+//! Every label of the interface goes through the catalog; tables of labels
+//! (tool groups, welcome suggestions) hold keys and use [`lookup`].
+
+use crate::agent::language::Locale;
+
+/// Look up a catalog string in a locale; an unknown key is shown as is
+macro_rules! t {
+    ($locale:expr, $key:literal) => {
+        $crate::ui::i18n::lookup($locale, $key)
+    };
+    ($locale:expr, $key:literal, $($arg:expr),+ $(,)?) => {
+        $crate::agent::language::fill(
+            $crate::ui::i18n::lookup($locale, $key),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+pub(crate) use t;
+
+/// `(key, English, French)`
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Chat view
+    ("chat.generating", "Generating a response", "Génération de la réponse"),
+    ("chat.new_messages", "↓ New messages", "↓ Nouveaux messages"),
+    ("chat.jump_to_latest", "Jump to the latest message", "Aller au dernier message"),
+    ("chat.context_used", "Context used", "Contexte utilisé"),
+    ("chat.queued", "Queued", "En attente"),
+    ("chat.queue_remove", "Remove from queue", "Retirer de la file"),
+    ("chat.run_stopped", "Run stopped.", "Exécution arrêtée."),
+    ("chat.queue_keep", "Keep queue", "Garder la file"),
+    ("chat.queue_discard", "Discard", "Abandonner"),
+    // Tool cards
+    ("tool.running", "running", "en cours"),
+    ("tool.awaiting_permission", "waiting for permission", "en attente de permission"),
+    ("tool.denied", "denied", "refusé"),
+    ("tool.done", "done", "terminé"),
+    ("tool.failed", "failed", "échec"),
+    ("tool.retrying", "retrying", "nouvel essai"),
+    ("tool.iteration", "Iteration", "Itération"),
+    // Permission prompts
+    ("permission.title", "Permission Required", "Permission requise"),
+    (
+        "permission.description",
+        "The AI agent is requesting permission to perform an action.",
+        "L'agent IA demande la permission d'effectuer une action.",
+    ),
+    ("permission.tool", "Tool", "Outil"),
+    ("permission.operation", "Operation", "Opération"),
+    ("permission.level", "Level", "Niveau"),
+    ("permission.target", "Target", "Cible"),
+    ("permission.parameters", "Parameters", "Paramètres"),
+    ("permission.deny", "Deny", "Refuser"),
+    ("permission.approve", "Approve", "Approuver"),
+    ("permission.read_only", "Read only", "Lecture seule"),
+    ("permission.write_file", "File write", "Écriture fichier"),
+    ("permission.read_write", "Read/Write", "Lecture/Écriture"),
+    ("permission.execute_safe", "Safe commands", "Commandes sûres"),
+    ("permission.execute_unsafe", "Unsafe commands", "Commandes dangereuses"),
+    ("permission.network", "Network", "Réseau"),
+    ("permission.show_changes", "Show changes", "Voir les modifications"),
+    // Settings tabs
+    ("settings.inference", "Inference", "Inférence"),
+    ("settings.hardware", "Hardware", "Matériel"),
+    ("settings.models", "Models", "Catalogue"),
+    ("settings.tools", "Tools", "Outils"),
+    ("settings.index", "Index", "Index"),
+    ("settings.memory", "Memory", "Mémoire"),
+    ("settings.voice", "Voice", "Voix"),
+    ("settings.skills", "Skills", "Skills"),
+    ("settings.mcp", "MCP", "MCP"),
+    ("settings.secrets", "Secrets", "Secrets"),
+    ("settings.templates", "Templates", "Modèles"),
+    ("settings.appearance", "Appearance", "Apparence"),
+    ("settings.diagnostics", "Diagnostics", "Diagnostic"),
+    ("settings.error", "Error: {}", "Erreur : {}"),
+    // Model details
+    ("model.reasoning", "Reasoning model", "Modèle de raisonnement"),
+    ("model.reasoning_hint", "Thinks in <think> blocks before answering", "Réfléchit dans des blocs <think> avant de répondre"),
+    ("model.template_hint", "Chat template prompts are written in", "Template de chat utilisé pour les prompts"),
+    ("model.trained_context", "Trained context", "Contexte d'entraînement"),
+    ("model.trained_context_rope", "Trained context, {} RoPE x{}", "Contexte d'entraînement, RoPE {} x{}"),
+    (
+        "model.trained_context_rope_from",
+        "Trained context, {} RoPE x{} from {}K",
+        "Contexte d'entraînement, RoPE {} x{} depuis {}K",
+    ),
+    ("model.memory_needed", "Needs ~{} at {}K ctx", "Nécessite ~{} à {}K de contexte"),
+    (
+        "model.memory_hint",
+        "Weights, KV cache and compute buffers with the current context and GPU layers",
+        "Poids, cache KV et tampons de calcul avec le contexte et les couches GPU actuels",
+    ),
+    (
+        "model.memory_refused",
+        "This model needs ~{} of {} at {}K ctx, only {} is free.",
+        "Ce modèle nécessite ~{} de {} à {}K de contexte, seulement {} de libre.",
+    ),
+    ("model.cancel", "Cancel", "Annuler"),
+    ("model.loading", "Loading into memory...", "Chargement en mémoire..."),
+    ("model.switching", "Switching model...", "Changement de modèle..."),
+    ("model.loading_percent", "Loading {}%", "Chargement {}%"),
+    ("model.switching_percent", "Switching {}%", "Changement {}%"),
+    ("model.reading", "Reading weights {}%", "Lecture des poids {}%"),
+    ("model.reading_eta", "Reading weights {}% - {} left", "Lecture des poids {}% - {} restantes"),
+    ("model.building", "llama.cpp is building the model...", "llama.cpp construit le modèle..."),
+    (
+        "model.building_no_cancel",
+        "llama.cpp cannot be interrupted while it builds the model",
+        "llama.cpp ne peut pas être interrompu pendant qu'il construit le modèle",
+    ),
+    ("model.cancelling", "Cancelling...", "Annulation..."),
+    ("model.load_anyway", "Load anyway", "Charger quand même"),
+    ("model.verifying", "Verifying checksum...", "Vérification de la somme de contrôle..."),
+    ("model.starting", "Starting...", "Démarrage..."),
+    // Copy buttons
+    ("copy.copied", "Copied", "Copié"),
+    ("copy.code", "Copy", "Copier"),
+    ("copy.text", "Copy text", "Copier le texte"),
+    ("copy.markdown", "Copy as Markdown", "Copier en Markdown"),
+    // Conversation templates
+    ("template.new_chat_from_template", "New chat from template", "Nouveau chat depuis un modèle"),
+    ("template.from_template", "From template...", "Depuis un modèle..."),
+    // Command palette
+    ("palette.title", "Command palette", "Palette de commandes"),
+    ("palette.placeholder", "Type a command or a conversation title", "Tapez une commande ou un titre de conversation"),
+    ("palette.no_match", "No matching command", "Aucune commande correspondante"),
+    // Sidebar
+    ("sidebar.new_chat", "New Chat", "Nouveau chat"),
+    ("sidebar.settings", "Settings", "Paramètres"),
+    ("sidebar.preferences", "Preferences", "Préférences"),
+    ("sidebar.help", "Help", "Aide"),
+    ("sidebar.tutorial", "Tutorial", "Tutoriel"),
+    // Conversation list
+    ("conversations.search", "Search conversations", "Rechercher dans les conversations"),
+    ("conversations.export", "Export conversation", "Exporter la conversation"),
+    ("conversations.delete", "Delete conversation", "Supprimer la conversation"),
+    ("conversations.searching", "Searching…", "Recherche…"),
+    ("conversations.no_results", "No results", "Aucun résultat"),
+    ("conversations.results", "{} results", "{} résultats"),
+    ("conversations.pin", "Pin conversation", "Épingler la conversation"),
+    ("conversations.unpin", "Unpin conversation", "Désépingler la conversation"),
+    ("conversations.empty", "No recent chats", "Aucune conversation récente"),
+    ("conversations.recent", "Recent", "Récentes"),
+    // Export dialog
+    ("export.title", "Export conversation", "Exporter la conversation"),
+    ("export.exported_to", "Exported to {}", "Exporté vers {}"),
+    ("export.redact", "Redact sensitive data", "Masquer les données sensibles"),
+    (
+        "export.redact_hint",
+        "API keys, emails, IP addresses and your own patterns (Settings > Tools)",
+        "Clés d'API, emails, adresses IP et vos propres motifs (Paramètres > Outils)",
+    ),
+    ("export.redacted_count", "{} value(s) redacted", "{} valeur(s) masquée(s)"),
+    ("export.preview", "Export preview", "Aperçu de l'export"),
+    ("export.path", "Export file path", "Chemin du fichier d'export"),
+    ("export.close", "Close", "Fermer"),
+    ("export.redact_and_export", "Redact and export", "Masquer et exporter"),
+    ("export.export", "Export", "Exporter"),
+    // Help
+    ("help.title", "Help & Tutorial", "Aide et tutoriel"),
+    ("help.getting_started", "Getting Started", "Comment commencer"),
+    (
+        "help.getting_started_body",
+        r#"<p class="mb-4">1. <strong>Load a model</strong>: Click the model selector in the header to choose a .gguf model. If you don't have any models, you can download them from HuggingFace using the sidebar.</p>
+<p class="mb-4">2. <strong>Start chatting</strong>: Once a model is loaded, type a message in the chat input and press Enter or click the send button.</p>
+<p>3. <strong>Ask for help</strong>: The AI can read files, run commands, search the web, and more. Just ask!</p>"#,
+        r#"<p class="mb-4">1. <strong>Charger un modèle</strong>: Cliquez sur le sélecteur de modèle dans l'en-tête pour choisir un fichier .gguf. Si vous n'avez pas de modèles, vous pouvez les télécharger depuis HuggingFace via la barre latérale.</p>
+<p class="mb-4">2. <strong>Commencer à discuter</strong>: Une fois un modèle chargé, tapez un message dans la zone de saisie et appuyez sur Entrée ou cliquez sur le bouton d'envoi.</p>
+<p>3. <strong>Demander de l'aide</strong>: L'IA peut lire des fichiers, exécuter des commandes, rechercher sur le web, et plus encore. Il suffit de demander!</p>"#,
+    ),
+    ("help.tools", "Agent Tools", "Outils de l'agent"),
+    (
+        "help.tools_body",
+        r#"<p class="mb-4">ClawRS includes <strong>30+ built-in tools</strong> organized by category:</p>
+<ul class="list-disc pl-6 mb-4 space-y-2">
+<li><strong>File Operations</strong>: Read, write, edit, search, and manage files and directories</li>
+<li><strong>Shell</strong>: Execute bash/PowerShell commands on your system</li>
+<li><strong>Git</strong>: Run git operations (status, diff, log, commit, branch, stash)</li>
+<li><strong>Web Search</strong>: Search the web and code repositories using Exa AI</li>
+<li><strong>Web Fetch</strong>: Download and extract content from URLs</li>
+<li><strong>Dev Tools</strong>: Diff, find-replace, patch, and code analysis</li>
+<li><strong>System</strong>: Process list, environment variables, system info</li>
+</ul>
+<p>The AI will automatically suggest which tools to use based on your request.</p>"#,
+        r#"<p class="mb-4">ClawRS inclut <strong>plus de 30 outils intégrés</strong> organisés par catégorie:</p>
+<ul class="list-disc pl-6 mb-4 space-y-2">
+<li><strong>Opérations fichiers</strong>: Lire, écrire, modifier, rechercher et gérer des fichiers et répertoires</li>
+<li><strong>Shell</strong>: Exécuter des commandes bash/PowerShell sur votre système</li>
+<li><strong>Git</strong>: Exécuter des opérations git (status, diff, log, commit, branch, stash)</li>
+<li><strong>Recherche web</strong>: Rechercher sur le web et les dépôts de code avec Exa AI</li>
+<li><strong>Extraction web</strong>: Télécharger et extraire le contenu des URLs</li>
+<li><strong>Outils dev</strong>: Diff, recherche-remplacement, patch et analyse de code</li>
+<li><strong>Système</strong>: Liste des processus, variables d'environnement, infos système</li>
+</ul>
+<p>L'IA suggère automatiquement quels outils utiliser en fonction de votre demande.</p>"#,
+    ),
+    ("help.permissions", "Permissions", "Permissions"),
+    (
+        "help.permissions_body",
+        r#"<p class="mb-4">ClawRS has a <strong>6-level permission system</strong> to control tool access:</p>
+<ul class="list-decimal pl-6 mb-4 space-y-2">
+<li><strong>ReadOnly</strong>: File read, grep, glob only</li>
+<li><strong>Filesystem</strong>: Read + write files</li>
+<li><strong>Execute</strong>: Filesystem + shell commands</li>
+<li><strong>Git</strong>: Full git access</li>
+<li><strong>Network</strong>: Web search and downloads</li>
+<li><strong>Admin</strong>: All capabilities</li>
+</ul>
+<p class="mb-4"><strong>Permission modes</strong>:</p>
+<ul class="list-disc pl-6 space-y-2">
+<li><strong>Manual approval</strong> (default): Each tool call shows a dialog for you to approve</li>
+<li><strong>Allowlist</strong>: Pre-approve specific tools in Settings > Tools</li>
+<li><strong>Auto-approve</strong>: Skip all dialogs (use with caution!)</li>
+</ul>"#,
+        r#"<p class="mb-4">ClawRS dispose d'un <strong>système de permissions à 6 niveaux</strong> pour contrôler l'accès aux outils:</p>
+<ul class="list-decimal pl-6 mb-4 space-y-2">
+<li><strong>ReadOnly</strong>: Lecture de fichiers, grep, glob uniquement</li>
+<li><strong>Filesystem</strong>: Lecture + écriture de fichiers</li>
+<li><strong>Execute</strong>: Fichiers + commandes shell</li>
+<li><strong>Git</strong>: Accès complet git</li>
+<li><strong>Network</strong>: Recherche web et téléchargements</li>
+<li><strong>Admin</strong>: Toutes les capacités</li>
+</ul>
+<p class="mb-4"><strong>Modes de permissions</strong>:</p>
+<ul class="list-disc pl-6 space-y-2">
+<li><strong>Approbation manuelle</strong> (défaut): Chaque appel d'outil affiche un dialogue pour validation</li>
+<li><strong>Liste blanche</strong>: Pré-approuver des outils spécifiques dans Paramètres > Outils</li>
+<li><strong>Auto-approuver</strong>: Sauter tous les dialogues (à utiliser avec précaution!)</li>
+</ul>"#,
+    ),
+    ("help.limitations", "Important Limitations", "Limitations importantes"),
+    (
+        "help.limitations_body",
+        r#"<p class="mb-4">ClawRS runs <strong>entirely offline</strong> using local models. Important constraints:</p>
+<ul class="list-disc pl-6 mb-4 space-y-2">
+<li><strong>VRAM/RAM</strong>: Models require 4-16GB. Use quantized models (Q4, Q5, Q8) for best results.</li>
+<li><strong>Context window</strong>: Limited to 4K-32K tokens. ClawRS automatically adjusts based on your VRAM.</li>
+<li><strong>Model quality</strong>: Local models have less knowledge than cloud models (GPT-5, Gemini 3, Claude Opus 4.6).</li>
+<li><strong>Reasoning</strong>: Complex multi-step reasoning may be less reliable.</li>
+<li><strong>Speed</strong>: Depends on your hardware. GPU acceleration recommended.</li>
+</ul>
+<p>Best for: coding help, file operations, local tasks. Not ideal for deep research.</p>"#,
+        r#"<p class="mb-4">ClawRS fonctionne <strong>entièrement hors ligne</strong> avec des modèles locaux. Contraintes importantes:</p>
+<ul class="list-disc pl-6 mb-4 space-y-2">
+<li><strong>VRAM/RAM</strong>: Les modèles nécessitent 4-16GB. Utilisez des modèles quantifiés (Q4, Q5, Q8) pour de meilleurs résultats.</li>
+<li><strong>Fenêtre de contexte</strong>: Limitée à 4K-32K tokens. ClawRS ajuste automatiquement selon votre VRAM.</li>
+<li><strong>Qualité du modèle</strong>: Les modèles locaux ont moins de connaissances que les modèles cloud (GPT-5, Gemini 3, Claude Opus 4.6).</li>
+<li><strong>Raisonnement</strong>: Le raisonnement complexe en plusieurs étapes peut être moins fiable.</li>
+<li><strong>Vitesse</strong>: Dépend de votre matériel. Accélération GPU recommandée.</li>
+</ul>
+<p>Idéal pour: aide au code, opérations sur fichiers, tâches locales. Pas idéal pour la recherche approfondie.</p>"#,
+    ),
+    ("help.tips", "Tips", "Conseils"),
+    (
+        "help.tips_body",
+        r#"<ul class="list-disc pl-6 space-y-3">
+<li>Use <strong>quantized models</strong> (Q4_K_M, Q5_K_S) for best speed/quality ratio</li>
+<li>Ensure <strong>sufficient VRAM</strong> before loading large models</li>
+<li>Keep conversations <strong>focused</strong> to avoid hitting context limits</li>
+<li>Use <strong>clear, specific requests</strong> for better results</li>
+<li>Check the <strong>Settings</strong> panel to customize inference parameters (temperature, top-p, etc.)</li>
+<li>Enable <strong>GPU acceleration</strong> in Hardware settings for faster inference</li>
+<li>Pre-approve frequent tools in the <strong>allowlist</strong> to speed up workflows</li>
+<li>For complex reasoning, consider using GPT-5, Gemini 3 or Claude Opus 4.6 and use ClawRS for execution</li>
+</ul>"#,
+        r#"<ul class="list-disc pl-6 space-y-3">
+<li>Utilisez des <strong>modèles quantifiés</strong> (Q4_K_M, Q5_K_S) pour le meilleur ratio vitesse/qualité</li>
+<li>Assurez-vous d'avoir <strong>suffisamment de VRAM</strong> avant de charger de grands modèles</li>
+<li>Gardez les conversations <strong>concentrées</strong> pour éviter d'atteindre les limites de contexte</li>
+<li>Faites des <strong>requêtes claires et spécifiques</strong> pour de meilleurs résultats</li>
+<li>Consultez le panneau <strong>Paramètres</strong> pour personnaliser les paramètres d'inférence (température, top-p, etc.)</li>
+<li>Activez <strong>l'accélération GPU</strong> dans les paramètres Matériel pour une inférence plus rapide</li>
+<li>Pré-approuvez les outils fréquents dans la <strong>liste blanche</strong> pour accélérer les flux de travail</li>
+<li>Pour un raisonnement complexe, utilisez GPT-5, Gemini 3 ou Claude Opus 4.6 et servez-vous de ClawRS pour l'exécution</li>
+</ul>"#,
+    ),
+    ("help.shortcuts", "Keyboard Shortcuts", "Raccourcis clavier"),
+    (
+        "help.shortcuts_body",
+        r#"<ul class="list-disc pl-6 space-y-2">
+<li><strong>Ctrl+N</strong> (Cmd+N on macOS): new conversation</li>
+<li><strong>Ctrl+K</strong>: command palette, to run an action, switch model or open a conversation by typing part of its title</li>
+<li><strong>Ctrl+Enter</strong> or <strong>Enter</strong>: send the message</li>
+<li><strong>Esc</strong>: stop the generation</li>
+<li><strong>Ctrl+F</strong>: search the conversations</li>
+<li><strong>Ctrl+,</strong>: open the settings</li>
+</ul>
+<p class="mt-3">While you type in a text field, only Ctrl+Enter and Esc are active.</p>"#,
+        r#"<ul class="list-disc pl-6 space-y-2">
+<li><strong>Ctrl+N</strong> (Cmd+N sur macOS) : nouvelle conversation</li>
+<li><strong>Ctrl+K</strong> : palette de commandes, pour lancer une action, changer de modèle ou ouvrir une conversation en tapant une partie de son titre</li>
+<li><strong>Ctrl+Entrée</strong> ou <strong>Entrée</strong> : envoyer le message</li>
+<li><strong>Échap</strong> : arrêter la génération</li>
+<li><strong>Ctrl+F</strong> : rechercher dans les conversations</li>
+<li><strong>Ctrl+,</strong> : ouvrir les paramètres</li>
+</ul>
+<p class="mt-3">Pendant la saisie dans un champ de texte, seuls Ctrl+Entrée et Échap sont actifs.</p>"#,
+    ),
+    // Voice input
+    (
+        "voice.not_built",
+        "Voice input is not in this build: rebuild with --features voice.",
+        "La saisie vocale n'est pas dans cette version : recompiler avec --features voice.",
+    ),
+    (
+        "voice.no_input_device",
+        "No microphone found. Plug one in or pick an input device in the system sound settings.",
+        "Aucun micro trouvé. Branchez-en un ou choisissez une entrée dans les réglages son du système.",
+    ),
+    (
+        "voice.microphone",
+        "Microphone unavailable ({}). Allow microphone access for this app in the system privacy settings.",
+        "Micro indisponible ({}). Autorisez l'accès au micro pour cette application dans les réglages de confidentialité du système.",
+    ),
+    ("voice.nothing_recorded", "Nothing was recorded.", "Rien n'a été enregistré."),
+    (
+        "voice.silent",
+        "Only silence was recorded. Check the microphone permission and input level.",
+        "Seul du silence a été enregistré. Vérifiez l'autorisation du micro et le niveau d'entrée.",
+    ),
+    (
+        "voice.model_missing",
+        "Whisper model missing: download it in Settings > Voice.",
+        "Modèle Whisper absent : téléchargez-le dans Paramètres > Voix.",
+    ),
+    ("voice.transcription_failed", "Transcription failed: {}", "Échec de la transcription : {}"),
+    ("voice.stop", "Stop and transcribe", "Arrêter et transcrire"),
+    ("voice.opening", "Opening the microphone...", "Ouverture du micro..."),
+    ("voice.transcribing", "Transcribing...", "Transcription..."),
+    ("voice.dictate", "Dictate (voice input)", "Dicter (saisie vocale)"),
+    // Sampling experiment
+    ("experiment.title", "Sampling experiment", "Expérience d'échantillonnage"),
+    ("experiment.presets", "Sampling presets", "Préréglages d'échantillonnage"),
+    (
+        "experiment.pick_hint",
+        "Pick 2 or 3. Variants run one after another and wait for the chat when it is busy.",
+        "Choisissez-en 2 ou 3. Les variantes passent l'une après l'autre et attendent le chat s'il est occupé.",
+    ),
+    ("experiment.use_reply", "Use this reply", "Garder cette réponse"),
+    ("experiment.generating", "Generating...", "Génération..."),
+    ("experiment.close", "Close", "Fermer"),
+    ("experiment.cancel", "Cancel", "Annuler"),
+    ("experiment.run", "Run variants", "Lancer les variantes"),
+    ("experiment.precise", "Precise", "Précis"),
+    ("experiment.balanced", "Balanced", "Équilibré"),
+    ("experiment.creative", "Creative", "Créatif"),
+    ("experiment.wide", "Wide", "Large"),
+    // Chat input
+    ("input.image_not_saved", "Pasted image not saved: {}", "Image collée non enregistrée : {}"),
+    ("input.stop", "Stop (Esc)", "Arrêter (Échap)"),
+    ("input.send", "Send (Enter)", "Envoyer (Entrée)"),
+    ("input.placeholder", "Send a message...", "Envoyer un message..."),
+    (
+        "input.queue_placeholder",
+        "Queue a message for after this run...",
+        "Mettre un message en file pour après cette exécution...",
+    ),
+    (
+        "input.hint",
+        "Enter to send, Shift+Enter for a new line, drop files to attach them",
+        "Entrée pour envoyer, Maj+Entrée pour un saut de ligne, déposez des fichiers pour les joindre",
+    ),
+    ("input.attach", "Attach files", "Joindre des fichiers"),
+    ("input.skills", "Available Skills", "Skills disponibles"),
+    ("input.remove_attachment", "Remove attachment", "Retirer la pièce jointe"),
+    // Secrets tab
+    (
+        "secrets.help",
+        "API keys and tokens stored in the system keyring (a file encrypted with a key derived from this machine and account where there is none). Refer to them as ${secret:NAME} in tool parameters (e.g. an http_request Authorization header) or MCP server environment variables; their values are filled in just before use and shown as *** in tool results.",
+        "Clés d'API et jetons stockés dans le trousseau du système (à défaut, un fichier chiffré avec une clé dérivée de cette machine et de ce compte). Utilisez ${secret:NOM} dans les paramètres d'outils (par ex. l'en-tête Authorization de http_request) ou les variables d'environnement des serveurs MCP ; les valeurs sont insérées juste avant l'utilisation et affichées *** dans les résultats.",
+    ),
+    ("secrets.empty", "No secrets yet.", "Aucun secret pour l'instant."),
+    ("secrets.remove", "Remove", "Supprimer"),
+    ("secrets.name", "NAME", "NOM"),
+    ("secrets.value", "Value", "Valeur"),
+    (
+        "secrets.invalid",
+        "Enter a name (letters, digits, _) and a value.",
+        "Saisissez un nom (lettres, chiffres, _) et une valeur.",
+    ),
+    ("secrets.add", "+ Add", "+ Ajouter"),
+    // MCP settings
+    ("mcp.title", "MCP Configuration", "Configuration MCP"),
+    ("mcp.edit_config", "Edit mcp.json", "Éditer mcp.json"),
+    ("mcp.servers", "MCP Servers", "Serveurs MCP"),
+    ("mcp.no_servers", "No MCP servers configured.", "Aucun serveur MCP configuré."),
+    ("mcp.skills", "Skills", "Compétences (Skills)"),
+    ("mcp.no_skills", "No skills loaded.", "Aucune compétence chargée."),
+    // Memory settings
+    (
+        "memory.usage",
+        "{} notes, {} / {} characters (the least recently used notes are forgotten past the limit)",
+        "{} notes, {} / {} caractères (les notes les moins utilisées sont oubliées au-delà)",
+    ),
+    ("memory.title", "Long-term memory", "Mémoire long terme"),
+    (
+        "memory.description",
+        "Short facts the agent saves with memory_write and finds again in later conversations.",
+        "Faits courts que l'agent enregistre avec memory_write et retrouve dans les conversations suivantes.",
+    ),
+    ("memory.recall", "Recall into the prompt", "Rappel dans le prompt"),
+    (
+        "memory.recall_hint",
+        "Notes relevant to each request are added to the system prompt.",
+        "Les notes utiles à chaque demande sont ajoutées au prompt système.",
+    ),
+    ("memory.recall_count", "Notes recalled per request", "Notes rappelées par demande"),
+    ("memory.saved_notes", "Saved notes", "Notes enregistrées"),
+    ("memory.empty", "No notes yet.", "Aucune note pour l'instant."),
+    ("memory.topics", "topics, comma separated", "sujets, séparés par des virgules"),
+    ("memory.cancel", "Cancel", "Annuler"),
+    ("memory.save", "Save", "Enregistrer"),
+    ("memory.edit", "Edit", "Modifier"),
+    ("memory.delete", "Delete", "Supprimer"),
+    // Diagnostics
+    ("diagnostics.warning", "Warning", "Attention"),
+    ("diagnostics.failed", "Failed", "Échec"),
+    ("diagnostics.data_dir", "App data folder", "Dossier de données"),
+    ("diagnostics.models", "Models", "Modèles"),
+    ("diagnostics.gpu", "GPU acceleration", "Accélération GPU"),
+    ("diagnostics.workspace", "Workspace", "Espace de travail"),
+    ("diagnostics.title", "Diagnostics", "Diagnostic"),
+    (
+        "diagnostics.description",
+        "Startup checks for the data folder, models, GPU backend and workspace. Copy the report when asking for help.",
+        "Vérifications du dossier de données, des modèles, du GPU et de l'espace de travail. Copiez le rapport pour demander de l'aide.",
+    ),
+    ("diagnostics.run_again", "Run again", "Relancer"),
+    ("diagnostics.copied", "Copied", "Copié"),
+    ("diagnostics.copy_report", "Copy report", "Copier le rapport"),
+    ("diagnostics.running", "Running checks...", "Vérifications en cours..."),
+    // Model search
+    ("models.downloaded", "Downloaded {}", "{} téléchargé"),
+    ("models.download_stopped", "Download stopped.", "Téléchargement arrêté."),
+    ("models.title", "Find models on Hugging Face", "Chercher des modèles sur Hugging Face"),
+    (
+        "models.description",
+        "GGUF repositories, most downloaded first. Results are kept for an hour.",
+        "Dépôts GGUF, les plus téléchargés d'abord. Les résultats sont gardés une heure.",
+    ),
+    ("models.searching", "Searching...", "Recherche..."),
+    ("models.search", "Search", "Chercher"),
+    ("models.stop", "Stop", "Arrêter"),
+    ("models.no_results", "No results yet.", "Aucun résultat pour l'instant."),
+    ("models.downloads", "Downloads", "Téléchargements"),
+    ("models.parts", "{} parts", "{} parties"),
+    ("models.download", "Download", "Télécharger"),
+    // Voice settings
+    ("voice_settings.downloaded", "Downloaded {}", "{} téléchargé"),
+    ("voice_settings.download_stopped", "Download stopped.", "Téléchargement arrêté."),
+    ("voice_settings.title", "Voice input", "Saisie vocale"),
+    (
+        "voice_settings.description",
+        "The microphone button of the chat box records up to two minutes and transcribes locally with whisper.cpp. The text is added to the input for review before sending.",
+        "Le bouton micro de la zone de saisie enregistre jusqu'à deux minutes et transcrit localement avec whisper.cpp. Le texte est ajouté à la saisie pour relecture avant envoi.",
+    ),
+    (
+        "voice_settings.not_built",
+        "This build has no voice input: rebuild with --features voice to enable it.",
+        "Cette version n'a pas la saisie vocale : recompilez avec --features voice pour l'activer.",
+    ),
+    ("voice_settings.language", "Spoken language", "Langue parlée"),
+    (
+        "voice_settings.language_hint",
+        "A code such as en or fr, or auto to detect it.",
+        "Un code comme fr ou en, ou auto pour la détecter.",
+    ),
+    ("voice_settings.model", "Whisper model", "Modèle Whisper"),
+    (
+        "voice_settings.model_hint",
+        "Larger models are more accurate but slower. Base suits most machines.",
+        "Les grands modèles sont plus précis mais plus lents. Base convient à la plupart des machines.",
+    ),
+    ("voice_settings.cancel", "Cancel", "Annuler"),
+    ("voice_settings.download", "Download", "Télécharger"),
+    ("voice_settings.in_use", "In use", "Utilisé"),
+    ("voice_settings.use", "Use", "Utiliser"),
+    // Document index settings
+    ("index.summary", "{} files, {} passages, updated {}", "{} fichiers, {} passages, mis à jour le {}"),
+    ("index.not_indexed", "Not indexed yet", "Pas encore indexé"),
+    (
+        "index.description",
+        "Folders of text, Markdown and PDF files the agent can search by meaning with index_search. index_build embeds them with the model below (a GGUF embedding model such as nomic-embed-text or bge-small, loaded next to the chat model); later builds only re-embed modified files.",
+        "Dossiers de fichiers texte, Markdown et PDF que l'agent peut chercher par le sens avec index_search. index_build les vectorise avec le modèle ci-dessous (un modèle GGUF d'embedding comme nomic-embed-text ou bge-small, chargé à côté du modèle de chat) ; les builds suivants ne revectorisent que les fichiers modifiés.",
+    ),
+    ("index.title", "Document index", "Index de documents"),
+    ("index.embedding_model", "Embedding model", "Modèle d'embedding"),
+    (
+        "index.embedding_model_hint",
+        "Changing it rebuilds the indexes in full on the next index_build.",
+        "En changer reconstruit entièrement les index au prochain index_build.",
+    ),
+    ("index.none", "None", "Aucun"),
+    ("index.folders", "Indexed folders", "Dossiers indexés"),
+    ("index.no_folders", "No folders yet.", "Aucun dossier pour l'instant."),
+    ("index.remove", "Remove", "Supprimer"),
+    ("index.folder_placeholder", "/path/to/docs", "/chemin/vers/docs"),
+    ("index.folder_missing", "This folder does not exist.", "Ce dossier n'existe pas."),
+    ("index.not_a_folder", "Not a folder.", "Ce n'est pas un dossier."),
+    ("index.add", "+ Add", "+ Ajouter"),
+    // Template settings
+    (
+        "templates.help",
+        "Saved starting points offered under \"New Chat\". Use {placeholders} in the first message; {date} and {time} are expanded in titles.",
+        "Points de départ proposés sous \"Nouveau Chat\". Utilisez des {placeholders} dans le premier message ; {date} et {time} sont remplacés dans les titres.",
+    ),
+    ("templates.title", "Conversation templates", "Modèles de conversation"),
+    ("templates.add", "+ Add", "+ Ajouter"),
+    ("templates.new_template", "New template", "Nouveau modèle"),
+    ("templates.empty", "No templates yet.", "Aucun modèle pour l'instant."),
+    ("templates.bundle", "Settings bundle", "Export des paramètres"),
+    (
+        "templates.bundle_hint",
+        "Exports settings and templates to a JSON file, or imports them back.",
+        "Exporte les paramètres et modèles dans un fichier JSON, ou les réimporte.",
+    ),
+    ("templates.exported", "Exported.", "Exporté."),
+    ("templates.export", "Export", "Exporter"),
+    ("templates.imported", "Imported.", "Importé."),
+    ("templates.import", "Import", "Importer"),
+    ("templates.title_placeholder", "Title pattern, e.g. PR review {date}", "Titre, ex. Revue PR {date}"),
+    (
+        "templates.prompt_placeholder",
+        "System prompt override (empty = global prompt)",
+        "System prompt spécifique (vide = prompt global)",
+    ),
+    (
+        "templates.message_placeholder",
+        "First message, e.g. Review the following PR: {url}",
+        "Premier message, ex. Revois cette PR : {url}",
+    ),
+    ("templates.iterations_placeholder", "Max iterations (default)", "Itérations max (défaut)"),
+    ("templates.name", "Name", "Nom"),
+    ("templates.delete", "Delete", "Supprimer"),
+    ("templates.tools_default", "Tools: default", "Outils : par défaut"),
+    ("templates.tools_on", "Tools: on", "Outils : activés"),
+    ("templates.tools_off", "Tools: off", "Outils : désactivés"),
+    // Tool settings
+    ("tools.skills_error", "Skills could not be loaded", "Impossible de charger les skills"),
+    ("tools.dismiss", "Dismiss", "Ignorer"),
+    (
+        "tools.consult_hint",
+        "Model to use when the AI consults a more powerful external model via the ai_consult tool.",
+        "Modèle à utiliser lorsque l'IA consulte un modèle externe plus puissant via l'outil ai_consult.",
+    ),
+    ("tools.model", "Model", "Modèle"),
+    (
+        "tools.openrouter_hint",
+        "Set OPENROUTER_API_KEY environment variable. Get a free key at openrouter.ai/keys",
+        "Définir la variable d'environnement OPENROUTER_API_KEY. Clé gratuite sur openrouter.ai/keys",
+    ),
+    ("tools.env_title", "Environment Context", "Contexte d'environnement"),
+    (
+        "tools.env_hint",
+        "Details injected into the agent system prompt. Disable any you prefer not to share with the model.",
+        "Informations injectées dans le prompt système de l'agent. Désactivez celles que vous ne voulez pas partager avec le modèle.",
+    ),
+    ("tools.env_os", "OS and path separator", "OS et séparateur de chemin"),
+    ("tools.env_shell", "Shell", "Shell"),
+    ("tools.env_datetime", "Date, time and timezone", "Date, heure et fuseau"),
+    ("tools.env_workspace", "Workspace path", "Chemin du workspace"),
+    ("tools.env_git", "Git repository and branch", "Dépôt git et branche"),
+    ("tools.env_model", "Model name", "Nom du modèle"),
+    ("tools.results_title", "Tool Results", "Résultats d'outils"),
+    (
+        "tools.results_hint",
+        "How tool results are shown to the model. Some models do better with compact text or raw JSON.",
+        "Forme des résultats d'outils transmis au modèle. Certains modèles préfèrent un texte compact ou du JSON brut.",
+    ),
+    ("tools.verbosity_full", "Full", "Complet"),
+    ("tools.verbosity_compact", "Compact (key fields, 1000 chars max)", "Compact (champs clés, 1000 caractères max)"),
+    ("tools.verbosity_json", "JSON (data only)", "JSON (données seules)"),
+    ("tools.role", "Role", "Rôle"),
+    ("tools.old_results", "Old results", "Anciens résultats"),
+    (
+        "tools.masking_digest",
+        "Digest (paths, counts, start of message)",
+        "Résumé (chemins, compteurs, début du message)",
+    ),
+    ("tools.masking_placeholder", "Placeholder only", "Simple mention"),
+    (
+        "tools.per_tool_format",
+        "Per-tool format (one \"tool = format\" per line)",
+        "Format par outil (un \"outil = format\" par ligne)",
+    ),
+    ("tools.rate_limits_title", "Rate Limits", "Limites d'appels"),
+    (
+        "tools.rate_limits_hint",
+        "Web, Exa and MCP tools are limited to 20 calls per minute, other tools are unlimited. Calls over the limit fail and the model is told when to retry.",
+        "Les outils web, Exa et MCP sont limités à 20 appels par minute, les autres sans limite. Les appels en trop échouent et le modèle sait quand réessayer.",
+    ),
+    (
+        "tools.rate_limits_field",
+        "Per-tool limits (one \"tool = calls per minute, max concurrent\" per line, - for unlimited)",
+        "Limites par outil (un \"outil = appels par minute, simultanés max\" par ligne, - pour illimité)",
+    ),
+    ("tools.redaction_title", "Redaction", "Masquage"),
+    (
+        "tools.redaction_hint",
+        "Values replaced by placeholders such as [EMAIL_1] when exporting with redaction, and before content enters conversation search or memory.",
+        "Valeurs remplacées par des marqueurs comme [EMAIL_1] lors d'un export masqué, et avant d'entrer dans la recherche de conversations ou la mémoire.",
+    ),
+    ("tools.redact_api_keys", "API keys and tokens", "Clés d'API et jetons"),
+    ("tools.redact_emails", "Email addresses", "Adresses email"),
+    ("tools.redact_ips", "IP addresses", "Adresses IP"),
+    (
+        "tools.redact_indexed",
+        "Always redact search index and memory",
+        "Toujours masquer l'index de recherche et la mémoire",
+    ),
+    ("tools.redaction_patterns", "Custom patterns (one regex per line)", "Motifs personnalisés (une regex par ligne)"),
+    ("tools.retention_title", "Conversation Retention", "Conservation des conversations"),
+    (
+        "tools.retention_hint",
+        "Oldest conversations beyond these limits are removed at startup and once a day. Pinned and open conversations are kept. Leave a field empty for no limit.",
+        "Les conversations les plus anciennes au-delà de ces limites sont supprimées au démarrage puis une fois par jour. Les conversations épinglées et ouvertes sont conservées. Laisser vide pour aucune limite.",
+    ),
+    ("tools.retention_age", "Maximum age (days)", "Âge maximum (jours)"),
+    ("tools.retention_count", "Maximum number of conversations", "Nombre maximum de conversations"),
+    ("tools.retention_size", "Maximum total size (MB)", "Taille totale maximum (Mo)"),
+    ("tools.retention_archive", "Archive to a zip instead of deleting", "Archiver dans un zip au lieu de supprimer"),
+    ("tools.auto_approve_title", "Auto-approve Mode", "Mode tout accepter"),
+    (
+        "tools.auto_approve_hint",
+        "When enabled, ALL tool calls are automatically approved without asking. Use with caution.",
+        "Quand activé, TOUS les appels d'outils sont approuvés automatiquement. À utiliser avec précaution.",
+    ),
+    ("tools.auto_approve", "Accept all tools", "Tout accepter"),
+    ("tools.dangerous", "DANGEROUS", "DANGEREUX"),
+    (
+        "tools.auto_approve_detail",
+        "Skip permission dialogs for all tools",
+        "Ignorer les dialogues de permission pour tous les outils",
+    ),
+    ("tools.sandbox_title", "Workspace Sandbox", "Bac à sable du workspace"),
+    (
+        "tools.sandbox_hint",
+        "File, dev and PDF tools refuse paths outside the workspace root, including through .. and symlinks.",
+        "Les outils fichiers, dev et PDF refusent les chemins hors de la racine du workspace, y compris via .. et les liens symboliques.",
+    ),
+    ("tools.sandbox", "Restrict tools to workspace", "Limiter les outils au workspace"),
+    ("tools.root", "Root", "Racine"),
+    ("tools.network_title", "Local Network Requests", "Requêtes vers le réseau local"),
+    (
+        "tools.network_hint",
+        "Lets the http_request tool call localhost and private addresses (10.x, 192.168.x, ...), e.g. a dev server. Off, such requests are refused so the model can't probe your network.",
+        "Autorise l'outil http_request à appeler localhost et les adresses privées (10.x, 192.168.x, ...), par exemple un serveur de dev. Désactivé, ces requêtes sont refusées pour que le modèle ne puisse pas sonder votre réseau.",
+    ),
+    ("tools.network", "Allow local network", "Autoriser le réseau local"),
+    ("tools.policy_title", "Command Policy", "Politique de commandes"),
+    (
+        "tools.policy_hint",
+        "Commands the command tool runs directly. Every command of a chain (&&, ;, |) must be allowed; denied patterns are refused in every mode.",
+        "Commandes que l'outil command exécute directement. Chaque commande d'une chaîne (&&, ;, |) doit être autorisée ; les motifs interdits sont refusés dans tous les modes.",
+    ),
+    ("tools.policy_other", "Other commands", "Autres commandes"),
+    ("tools.policy_strict", "Refused (strict allowlist)", "Refusées (liste stricte)"),
+    ("tools.policy_ask", "Ask for approval", "Demander l'approbation"),
+    ("tools.policy_allow", "Allowed", "Autorisées"),
+    (
+        "tools.policy_allowed",
+        "Allowed commands (separated by spaces)",
+        "Commandes autorisées (séparées par des espaces)",
+    ),
+    (
+        "tools.policy_arguments",
+        "Allowed arguments (one \"command = prefix, prefix, !denied\" per line, - for none)",
+        "Arguments autorisés (un \"commande = préfixe, préfixe, !interdit\" par ligne, - pour aucun)",
+    ),
+    ("tools.policy_denied", "Denied patterns (one per line)", "Motifs interdits (un par ligne)"),
+    ("tools.restore_defaults", "Restore defaults", "Rétablir les valeurs par défaut"),
+    ("tools.lsp_title", "Language Servers", "Serveurs de langage"),
+    (
+        "tools.lsp_hint",
+        "Servers the LSP tools start for each file type. They keep running per project and stop when the app exits.",
+        "Serveurs que les outils LSP démarrent pour chaque type de fichier. Ils restent actifs par projet et s'arrêtent à la fermeture de l'application.",
+    ),
+    (
+        "tools.lsp_field",
+        "One \"language .ext .ext = command args\" per line",
+        "Un \"langage .ext .ext = commande args\" par ligne",
+    ),
+    ("tools.strict_format_title", "Strict Tool-call Format", "Format strict des appels d'outils"),
+    (
+        "tools.strict_format_hint",
+        "Constrains the model's output so a reply is either plain text or a valid JSON tool call naming a registered tool. Stops small models from producing malformed calls; applies when tools are enabled.",
+        "Contraint la sortie du modèle : une réponse est soit du texte, soit un appel d'outil JSON valide nommant un outil enregistré. Évite les appels malformés des petits modèles ; s'applique quand les outils sont activés.",
+    ),
+    ("tools.strict_format", "Strict tool-call format", "Format strict"),
+    ("tools.developer_title", "Developer Mode", "Mode développeur"),
+    (
+        "tools.developer_hint",
+        "Records each agent run (prompts and responses) to the recordings folder. Replay them with `clawrs replay <file>` to check a prompt change did not alter the agent's behavior.",
+        "Enregistre chaque exécution de l'agent (prompts et réponses) dans le dossier recordings. Rejouez-les avec `clawrs replay <fichier>` pour vérifier qu'un changement de prompt n'a pas modifié le comportement de l'agent.",
+    ),
+    ("tools.record_runs", "Record runs", "Enregistrer les exécutions"),
+    ("tools.allowlist_title", "Tool Allowlist", "Liste d'outils autorisés"),
+    (
+        "tools.allowlist_hint",
+        "Tools in the allowlist are auto-approved. Others will require manual approval. {} tools currently registered.",
+        "Les outils dans la liste sont approuvés automatiquement. Les autres demanderont une approbation manuelle. {} outils actuellement enregistrés.",
+    ),
+    ("tools.group_file_read", "File Read", "Lecture fichiers"),
+    ("tools.group_file_write", "File Write", "Écriture fichiers"),
+    ("tools.group_shell", "Shell / Bash", "Shell / Bash"),
+    ("tools.group_git", "Git", "Git"),
+    ("tools.group_web", "Web / Network", "Web / Réseau"),
+    ("tools.group_dev", "Dev Tools", "Outils dev"),
+    ("tools.group_system", "System", "Système"),
+    ("tools.group_memory", "Memory", "Mémoire"),
+    ("tools.risk_high", "high risk", "risque élevé"),
+    ("tools.risk_moderate", "moderate", "modéré"),
+    ("tools.risk_safe", "safe", "sûr"),
+    // Appearance settings
+    ("appearance.language", "Language", "Langue"),
+    ("appearance.interface_language", "Interface language", "Langue de l'interface"),
+    (
+        "appearance.language_hint",
+        "Changes the UI language and AI responses",
+        "Change la langue de l'interface et des réponses de l'IA",
+    ),
+    ("appearance.agent_language", "Agent language", "Langue de l'agent"),
+    (
+        "appearance.agent_language_hint",
+        "Language of the system prompt, tool results and hints sent to the model. Auto follows the conversation; English often works better.",
+        "Langue du prompt système, des résultats d'outils et des consignes envoyés au modèle. Auto suit la langue de la conversation ; l'anglais donne souvent de meilleurs résultats.",
+    ),
+    ("appearance.theme", "Theme", "Thème"),
+    ("appearance.dark_mode", "Dark Mode", "Mode sombre"),
+    ("appearance.dark_mode_hint", "Switch between light and dark theme", "Basculer entre le thème clair et sombre"),
+    ("appearance.motion", "Motion", "Animations"),
+    ("appearance.reduced_motion", "Reduced motion", "Animations réduites"),
+    (
+        "appearance.reduced_motion_hint",
+        "Replaces loading animations with static indicators. Auto follows the system preference.",
+        "Remplace les animations de chargement par des indicateurs fixes. Auto suit la préférence du système.",
+    ),
+    ("appearance.motion_auto", "Auto", "Auto"),
+    ("appearance.motion_on", "On", "Activées"),
+    ("appearance.motion_off", "Off", "Désactivées"),
+    ("appearance.typography", "Typography", "Typographie"),
+    ("appearance.font_size", "Font Size", "Taille de police"),
+    ("appearance.font_size_hint", "Adjust text size in the chat interface", "Ajuster la taille du texte dans le chat"),
+    // Onboarding
+    ("onboarding.folder_missing", "This folder does not exist", "Ce dossier n'existe pas"),
+    ("onboarding.folder_unusable", "Cannot use this folder", "Impossible d'utiliser ce dossier"),
+    ("onboarding.model_downloaded", "Model downloaded", "Modèle téléchargé"),
+    ("onboarding.welcome", "Welcome to ClawRS", "Bienvenue dans ClawRS"),
+    (
+        "onboarding.intro",
+        "A quick check of what the app needs to run a model locally.",
+        "Une vérification rapide de ce dont l'application a besoin pour faire tourner un modèle en local.",
+    ),
+    ("onboarding.skip", "Skip", "Passer"),
+    ("onboarding.language", "Language", "Langue"),
+    ("onboarding.running", "Running checks...", "Vérifications en cours..."),
+    ("onboarding.models_folder", "Models folder", "Dossier des modèles"),
+    ("onboarding.use_this_folder", "Use this folder", "Utiliser ce dossier"),
+    ("onboarding.models_folder_path", "Models folder path", "Chemin du dossier des modèles"),
+    ("onboarding.use", "Use", "Utiliser"),
+    ("onboarding.downloading", "Downloading Qwen2.5 1.5B...", "Téléchargement de Qwen2.5 1.5B..."),
+    (
+        "onboarding.download_recommended",
+        "Download a small recommended model (Qwen2.5 1.5B, ~1 GB)",
+        "Télécharger un petit modèle recommandé (Qwen2.5 1.5B, ~1 Go)",
+    ),
+    ("onboarding.workspace", "Workspace", "Espace de travail"),
+    (
+        "onboarding.workspace_hint",
+        "Folder the agent works in by default.",
+        "Dossier dans lequel l'agent travaille par défaut.",
+    ),
+    ("onboarding.workspace_path", "Workspace path", "Chemin de l'espace de travail"),
+    ("onboarding.set", "Set", "Définir"),
+    ("onboarding.skip_for_now", "Skip for now", "Plus tard"),
+    ("onboarding.get_started", "Get started", "Commencer"),
+    // Model picker
+    ("model.active", "Active Model", "Modèle actif"),
+    ("model.rescan", "Rescan models", "Re-scanner les modèles"),
+    ("model.none_found", "No models found", "Aucun modèle trouvé"),
+    ("model.none_found_hint", "Place .gguf files in /models", "Placez des fichiers .gguf dans /models"),
+    ("model.select", "Select a model", "Choisir un modèle"),
+    ("model.reasoning_auto", "Reasoning: auto-detect", "Raisonnement : détection auto"),
+    ("model.reasoning_yes", "Reasoning: yes", "Raisonnement : oui"),
+    ("model.reasoning_no", "Reasoning: no", "Raisonnement : non"),
+    (
+        "model.tool_format_hint",
+        "How the model is asked to write tool calls",
+        "Format demandé au modèle pour appeler les outils",
+    ),
+    ("model.tool_format_auto", "Tool calls: from chat template", "Appels d'outils : selon le template"),
+    ("model.tool_format_json", "Tool calls: JSON block", "Appels d'outils : bloc JSON"),
+    ("model.tool_format_xml", "Tool calls: <tool_call> tags", "Appels d'outils : balises <tool_call>"),
+    ("model.tool_format_function", "Tool calls: name/arguments JSON", "Appels d'outils : JSON name/arguments"),
+    (
+        "model.template_format_hint",
+        "Prompt format, applied at the next load",
+        "Format des prompts, appliqué au prochain chargement",
+    ),
+    ("model.template_auto", "Chat template: from metadata", "Template de chat : selon les métadonnées"),
+    ("model.template_named", "Chat template: {}", "Template de chat : {}"),
+    ("model.load", "Load Model", "Charger le modèle"),
+    ("model.ready", "Ready", "Prêt"),
+    ("model.unload", "Unload Model", "Décharger le modèle"),
+    ("model.download_open", "Download from HuggingFace", "Télécharger depuis HuggingFace"),
+    ("model.download_title", "Download Model from HuggingFace", "Télécharger un modèle HuggingFace"),
+    (
+        "model.download_hint",
+        "Enter a HuggingFace repository URL or model ID. Example: TheBloke/Llama-2-7B-GGUF",
+        "Entrez une URL de dépôt HuggingFace ou un ID de modèle. Exemple : TheBloke/Llama-2-7B-GGUF",
+    ),
+    ("model.sha256", "SHA-256 (optional)", "SHA-256 (optionnel)"),
+    ("model.downloading", "Downloading...", "Téléchargement..."),
+    ("model.stop", "Stop", "Arrêter"),
+    (
+        "model.download_done",
+        "Download complete! Model is now available in the list.",
+        "Téléchargement terminé ! Le modèle est maintenant disponible.",
+    ),
+    ("model.download", "Download", "Télécharger"),
+    // Chat messages
+    ("message.thinking", "Thinking", "Réflexion"),
+    ("message.thinking_live", "Thinking...", "Réflexion en cours..."),
+    ("message.approve", "Approve", "Autoriser"),
+    (
+        "message.always_allow_hint",
+        "Add this tool to the allowlist: it will not ask again",
+        "Ajouter cet outil à la liste autorisée : il ne demandera plus",
+    ),
+    ("message.always_allow", "Always allow this tool", "Toujours autoriser cet outil"),
+    (
+        "message.fork_hint",
+        "Continue in a new conversation from this message",
+        "Continuer dans une nouvelle conversation à partir de ce message",
+    ),
+    ("message.fork", "Fork", "Bifurquer"),
+    ("message.outline", "On this message", "Dans ce message"),
+    ("message.outline_count", "On this message ({})", "Dans ce message ({})"),
+    (
+        "message.undo_hint",
+        "Restore the file as it was before the most recent change",
+        "Restaurer le fichier tel qu'il était avant la dernière modification",
+    ),
+    ("message.undo", "Undo", "Annuler"),
+    ("message.tool_output", "Tool output ({} lines)", "Sortie de l'outil ({} lignes)"),
+    ("message.output_search", "Search in the output", "Rechercher dans la sortie"),
+    ("message.output_search_label", "Search in the tool output", "Rechercher dans la sortie de l'outil"),
+    ("message.output_matches", "{} match(es)", "{} résultat(s)"),
+    ("message.user", "Your message", "Votre message"),
+    ("message.edit_label", "Edit message", "Modifier le message"),
+    ("message.cancel", "Cancel", "Annuler"),
+    ("message.save_resend", "Save & resend", "Enregistrer et renvoyer"),
+    ("message.edit_hint", "Edit this message and regenerate the reply", "Modifier ce message et régénérer la réponse"),
+    ("message.edit", "Edit", "Modifier"),
+    (
+        "message.experiment_hint",
+        "Retry this message with different sampling settings",
+        "Relancer ce message avec d'autres réglages d'échantillonnage",
+    ),
+    ("message.experiment", "Experiment", "Expérimenter"),
+    ("message.assistant", "Assistant message", "Message de l'assistant"),
+    (
+        "message.stats_hint",
+        "Generation speed, tokens generated and model",
+        "Vitesse de génération, tokens générés et modèle",
+    ),
+    ("message.regenerate_hint", "Replace this reply with a new one", "Remplacer cette réponse par une nouvelle"),
+    ("message.regenerate", "Regenerate", "Régénérer"),
+    ("message.temperature", "Temperature for the new reply", "Température de la nouvelle réponse"),
+    ("message.same_temperature", "Same temperature", "Même température"),
+    // Main window
+    ("header.no_model", "No model", "Aucun modèle"),
+    ("header.select_model", "Select Model", "Choisir un modèle"),
+    ("header.no_models", "No .gguf models found", "Aucun modèle .gguf trouvé"),
+    ("header.unload", "Unload model", "Décharger le modèle"),
+    ("header.read_only", "Read-only", "Lecture seule"),
+    (
+        "header.read_only_hint",
+        "Read-only: the agent cannot write files or run commands. Click to allow changes.",
+        "Lecture seule : l'agent ne peut ni écrire de fichiers ni exécuter de commandes. Cliquer pour autoriser les modifications.",
+    ),
+    ("header.make_read_only", "Make this conversation read-only", "Passer cette conversation en lecture seule"),
+    ("header.copy_conversation_hint", "Copy the conversation as Markdown", "Copier la conversation en Markdown"),
+    ("header.copy_conversation", "Copy conversation", "Copier la conversation"),
+    ("overrides.title", "Conversation settings", "Réglages de la conversation"),
+    ("overrides.heading", "This conversation", "Cette conversation"),
+    ("overrides.hint", "Empty fields use the global settings.", "Les champs vides utilisent les réglages globaux."),
+    ("overrides.system_prompt", "System prompt", "Prompt système"),
+    ("overrides.system_prompt_placeholder", "Global system prompt", "Prompt système global"),
+    ("overrides.max_tokens", "Max tokens", "Tokens max"),
+    ("overrides.reset", "Reset", "Réinitialiser"),
+    ("overrides.done", "Done", "Fermer"),
+    ("command.new_chat", "New chat", "Nouvelle conversation"),
+    ("command.search_conversations", "Search conversations", "Rechercher dans les conversations"),
+    ("command.open_settings", "Open settings", "Ouvrir les paramètres"),
+    (
+        "command.toggle_read_only",
+        "Toggle read-only tools for this conversation",
+        "Basculer les outils en lecture seule pour cette conversation",
+    ),
+    ("command.stop_generation", "Stop generation", "Arrêter la génération"),
+    ("command.switch_model", "Switch model: {}", "Changer de modèle : {}"),
+    ("command.open_conversation", "Open: {}", "Ouvrir : {}"),
+    ("header.new_chat", "New chat (Ctrl+N)", "Nouveau chat (Ctrl+N)"),
+    ("header.hide_sidebar", "Hide", "Masquer"),
+    ("header.show_sidebar", "Show", "Afficher"),
+    ("header.back_to_chat", "Back to Chat", "Retour au chat"),
+    ("welcome.tagline", "Your private AI, 100% local", "Votre IA privée, 100% locale"),
+    ("welcome.placeholder", "Send a message...", "Envoyer un message..."),
+    (
+        "welcome.privacy",
+        "100% private — no data leaves your device",
+        "100% privé — aucune donnée ne quitte votre appareil",
+    ),
+    ("welcome.plan", "Plan", "Planifier"),
+    ("welcome.plan_subtitle", "a trip, a project...", "un voyage, un projet..."),
+    (
+        "welcome.plan_prompt",
+        "Help me plan a trip to Paris. What are the must-sees and best times to visit?",
+        "Aide-moi à planifier un voyage à Paris. Quels sont les incontournables et les meilleures périodes ?",
+    ),
+    ("welcome.explain", "Explain", "Expliquer"),
+    ("welcome.explain_subtitle", "a complex concept", "un concept complexe"),
+    (
+        "welcome.explain_prompt",
+        "Explain quantum computing in simple terms that anyone can understand.",
+        "Explique-moi l'informatique quantique en termes simples que n'importe qui peut comprendre.",
+    ),
+    ("welcome.write", "Write", "Rédiger"),
+    ("welcome.write_subtitle", "an email, a document...", "un email, un texte..."),
+    (
+        "welcome.write_prompt",
+        "Help me write a professional email to my manager asking for time off.",
+        "Aide-moi à écrire un email professionnel à mon manager pour demander des congés.",
+    ),
+    ("welcome.code", "Code", "Coder"),
+    ("welcome.code_subtitle", "debug, explain...", "débugger, expliquer..."),
+    (
+        "welcome.code_prompt",
+        "I have a bug in my code. Can you help me debug it?",
+        "J'ai un bug dans mon code. Peux-tu m'aider à le débugger ?",
+    ),
+    // Hardware settings
+    ("hardware.no_gpu", "No GPU detected", "GPU non détecté"),
+    ("hardware.gpu_title", "GPU Information", "Carte graphique"),
+    ("hardware.vram_unavailable", "VRAM unavailable", "VRAM indisponible"),
+    ("hardware.vram_used", "VRAM used", "VRAM utilisée"),
+    ("hardware.vram_free", "VRAM free", "VRAM restante"),
+    ("hardware.vram_total", "VRAM total", "VRAM totale"),
+    ("hardware.vram_usage_unavailable", "VRAM usage unavailable", "Utilisation VRAM indisponible"),
+    ("hardware.memory_title", "System Memory", "Mémoire système"),
+    ("hardware.ram_unavailable", "RAM unavailable", "RAM indisponible"),
+    ("hardware.ram_used", "RAM used", "RAM utilisée"),
+    ("hardware.ram_free", "RAM free", "RAM restante"),
+    ("hardware.acceleration_title", "Hardware Acceleration", "Accélération matérielle"),
+    ("hardware.auto_load", "Auto-load on startup", "Charger auto. au démarrage"),
+    ("hardware.auto_load_last", "Last: {}", "Dernier : {}"),
+    ("hardware.auto_load_none", "No saved model", "Aucun modèle sauvegardé"),
+    ("hardware.probe_reasoning", "Probe for reasoning", "Tester le mode raisonnement"),
+    (
+        "hardware.probe_reasoning_hint",
+        "Short generation after loading when the metadata says nothing",
+        "Courte génération après chargement si les métadonnées ne disent rien",
+    ),
+    ("hardware.draft_model", "Draft model", "Modèle brouillon"),
+    (
+        "hardware.draft_model_hint",
+        "Small model of the same family that proposes tokens to the main model (speculative decoding). Applied at the next load; ignored if its vocabulary differs.",
+        "Petit modèle de la même famille qui propose des tokens au modèle principal (décodage spéculatif). Pris en compte au prochain chargement ; ignoré si son vocabulaire diffère.",
+    ),
+    ("hardware.draft_model_none", "None", "Aucun"),
+    ("hardware.gpu_layers", "GPU Layers", "Couches GPU"),
+    (
+        "hardware.gpu_layers_hint",
+        "Layers to offload to GPU. Higher values need more VRAM.",
+        "Couches déportées sur le GPU. Plus de couches demandent plus de VRAM.",
+    ),
+    ("hardware.models_dir", "Models Directory", "Dossier des modèles"),
+    ("hardware.open", "Open", "Ouvrir"),
+    (
+        "hardware.models_dir_hint",
+        "Location where model files (.gguf) are stored.",
+        "Emplacement des fichiers de modèles (.gguf).",
+    ),
+    // Inference settings
+    ("inference.generation", "Generation Parameters", "Paramètres de génération"),
+    ("inference.temperature", "Temperature", "Température"),
+    (
+        "inference.temperature_hint",
+        "Controls randomness. Higher values make output more random.",
+        "Contrôle l'aléatoire. Plus la valeur est haute, plus la sortie est variée.",
+    ),
+    ("inference.top_p_hint", "Nucleus sampling threshold.", "Seuil de l'échantillonnage nucleus."),
+    (
+        "inference.top_k_hint",
+        "Limits token selection to K most likely tokens.",
+        "Limite le choix aux K tokens les plus probables.",
+    ),
+    ("inference.frequency_penalty", "Frequency Penalty", "Pénalité de fréquence"),
+    (
+        "inference.frequency_penalty_hint",
+        "Penalizes tokens by how often they already appeared. 0 disables it.",
+        "Pénalise les tokens selon leur nombre d'apparitions. 0 la désactive.",
+    ),
+    ("inference.presence_penalty", "Presence Penalty", "Pénalité de présence"),
+    (
+        "inference.presence_penalty_hint",
+        "Penalizes tokens that already appeared, encouraging new topics. 0 disables it.",
+        "Pénalise les tokens déjà apparus, pour encourager de nouveaux sujets. 0 la désactive.",
+    ),
+    ("inference.stop_sequences", "Stop Sequences", "Séquences d'arrêt"),
+    (
+        "inference.stop_sequences_hint",
+        "One per line. The reply ends when the model writes one; it is not shown. Use \\n for a newline.",
+        "Une par ligne. La réponse s'arrête quand le modèle en écrit une ; elle n'est pas affichée. Utilisez \\n pour un retour à la ligne.",
+    ),
+    ("inference.model", "Model Configuration", "Configuration du modèle"),
+    ("inference.max_tokens", "Max Tokens (Output)", "Tokens max (sortie)"),
+    (
+        "inference.max_tokens_hint",
+        "Tokens to generate. Smaller = faster. (Default: 4096)",
+        "Tokens à générer. Plus petit = plus rapide. (Défaut : 4096)",
+    ),
+    ("inference.context", "Context Window", "Fenêtre de contexte"),
+    ("inference.context_auto", "Auto", "Auto"),
+    ("inference.context_fast", "Fast", "Rapide"),
+    ("inference.context_balanced", "Balanced", "Équilibré"),
+    ("inference.context_slow", "Slow", "Lent"),
+    ("inference.context_option_auto", "Auto - Adapts to the conversation", "Auto - S'adapte à la conversation"),
+    ("inference.context_option_2k", "2K - Very fast", "2K - Ultra rapide"),
+    ("inference.context_option_4k", "4K - Fast", "4K - Rapide"),
+    ("inference.context_option_8k", "8K - Recommended", "8K - Recommandé"),
+    ("inference.context_option_16k", "16K - Balanced", "16K - Équilibré"),
+    ("inference.context_option_32k", "32K - Long context", "32K - Long contexte"),
+    (
+        "inference.context_auto_hint",
+        "The context grows with the conversation, depending on the model and the available memory.",
+        "Le contexte grandit avec la conversation, selon le modèle et la mémoire disponible.",
+    ),
+    (
+        "inference.context_hint",
+        "Context size. Smaller = much faster.",
+        "Taille du contexte. Plus petit = beaucoup plus rapide.",
+    ),
+    (
+        "inference.context_over_trained",
+        "The loaded model was trained on {}K tokens: the context is capped at that size.",
+        "Le modèle chargé a été entraîné sur {}K tokens : le contexte est limité à cette taille.",
+    ),
+    ("inference.garbage", "Garbage Detection", "Détection de texte corrompu"),
+    ("inference.garbage_off", "Off", "Désactivée"),
+    (
+        "inference.garbage_low",
+        "Low - Only clear loops and fake tool output",
+        "Faible - Seulement les boucles évidentes et les faux résultats d'outils",
+    ),
+    (
+        "inference.garbage_strict",
+        "Strict - Also shorter loops and glued words",
+        "Stricte - Aussi les boucles courtes et les mots collés",
+    ),
+    (
+        "inference.garbage_hint",
+        "Stops a reply that turns into corrupted text. What was generated is kept, followed by a warning.",
+        "Arrête une réponse qui tourne au texte corrompu. Ce qui a été généré est gardé, suivi d'un avertissement.",
+    ),
+    ("inference.system_prompt", "System Prompt", "Prompt système"),
+    ("inference.system_prompt_placeholder", "Enter system prompt...", "Saisissez le prompt système..."),
+    (
+        "inference.system_prompt_hint",
+        "Initial instructions for the model's behavior.",
+        "Instructions initiales sur le comportement du modèle.",
+    ),
+    ("inference.web_search", "Web Search", "Recherche web"),
+    (
+        "inference.exa_hint",
+        "No key needed. You can add ?exaApiKey=... if you hit the rate limit.",
+        "Pas besoin de clé. Vous pouvez ajouter ?exaApiKey=... en cas de rate limit.",
+    ),
+    ("inference.remote", "Remote Server", "Serveur distant"),
+    ("inference.remote_api_key", "API Key", "Clé API"),
+    ("inference.remote_api_key_placeholder", "Optional", "Optionnelle"),
+    ("inference.remote_model", "Model", "Modèle"),
+    ("inference.remote_reconnect", "Reconnect", "Reconnecter"),
+    ("inference.remote_connect", "Use this server", "Utiliser ce serveur"),
+    (
+        "inference.remote_connected_hint",
+        "Generating with the server. Unload it from the sidebar or load a local model to switch back.",
+        "Génération avec le serveur. Déchargez-le depuis la barre latérale ou chargez un modèle local pour revenir en local.",
+    ),
+    (
+        "inference.remote_hint",
+        "Ollama, llama-server or any /v1/chat/completions endpoint, in place of a local model.",
+        "Ollama, llama-server ou tout endpoint /v1/chat/completions, à la place d'un modèle local.",
+    ),
+    ("inference.api_server", "Local API Server", "Serveur API local"),
+    ("inference.api_server_enable", "Serve the loaded model", "Servir le modèle chargé"),
+    (
+        "inference.api_server_hint",
+        "OpenAI-compatible /v1/chat/completions and /v1/models for editors and scripts. Chats in the app go first.",
+        "/v1/chat/completions et /v1/models compatibles OpenAI pour les éditeurs et les scripts. Les conversations de l'application passent en premier.",
+    ),
+    ("inference.api_server_bind", "Bind address", "Adresse d'écoute"),
+    ("inference.api_server_apply", "Apply", "Appliquer"),
+    ("inference.api_server_listening", "Listening on http://{}/v1", "En écoute sur http://{}/v1"),
+    (
+        "inference.api_server_down",
+        "Not running: check the address is free.",
+        "Arrêté : vérifiez que l'adresse est libre.",
+    ),
+    (
+        "inference.api_server_stopped",
+        "Stopped. Binding to 0.0.0.0 exposes the model to the network.",
+        "Arrêté. Écouter sur 0.0.0.0 expose le modèle au réseau.",
+    ),
+    // Skills settings
+    ("skills.title", "Skills Manager", "Gestion des skills"),
+    ("skills.new", "New Skill", "Nouveau skill"),
+    ("skills.empty", "No skills installed yet.", "Aucun skill installé pour l'instant."),
+    ("skills.delete", "Delete Skill", "Supprimer le skill"),
+];
+
+/// The string for `key` in `locale`, or `key` itself when the catalog lacks it
+pub fn lookup(locale: Locale, key: &'static str) -> &'static str {
+    match CATALOG.iter().find(|(k, _, _)| *k == key) {
+        Some((_, en, fr)) => match locale {
+            Locale::En => en,
+            Locale::Fr => fr,
+        },
+        None => {
+            tracing::warn!("Missing UI string: {}", key);
+            key
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_locale() {
+        assert_eq!(t!(Locale::En, "permission.deny"), "Deny");
+        assert_eq!(t!(Locale::Fr, "permission.deny"), "Refuser");
+        assert_eq!(lookup(Locale::Fr, "no.such.key"), "no.such.key");
+        assert_eq!(t!(Locale::En, "model.memory_needed", "4.2 GB", 8), "Needs ~4.2 GB at 8K ctx");
+
+        let mut keys: Vec<&str> = CATALOG.iter().map(|(key, _, _)| *key).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), CATALOG.len(), "duplicate keys");
+        assert!(CATALOG.iter().all(|(_, en, fr)| !en.is_empty() && !fr.is_empty()));
+    }
+
+    #[test]
+    fn test_every_used_key_is_in_the_catalog() {
+        let sources = [
+            include_str!("chat/experiment.rs"),
+            include_str!("chat/input.rs"),
+            include_str!("chat/message.rs"),
+            include_str!("chat/mod.rs"),
+            include_str!("chat/voice.rs"),
+            include_str!("components/clipboard.rs"),
+            include_str!("components/command_palette.rs"),
+            include_str!("components/permission_dialog.rs"),
+            include_str!("help/mod.rs"),
+            include_str!("mod.rs"),
+            include_str!("onboarding.rs"),
+            include_str!("settings/appearance.rs"),
+            include_str!("settings/diagnostics.rs"),
+            include_str!("settings/hardware.rs"),
+            include_str!("settings/index.rs"),
+            include_str!("settings/inference.rs"),
+            include_str!("settings/mcp.rs"),
+            include_str!("settings/memory.rs"),
+            include_str!("settings/mod.rs"),
+            include_str!("settings/models.rs"),
+            include_str!("settings/secrets.rs"),
+            include_str!("settings/skills.rs"),
+            include_str!("settings/templates.rs"),
+            include_str!("settings/tools.rs"),
+            include_str!("settings/voice.rs"),
+            include_str!("sidebar/conversation_list.rs"),
+            include_str!("sidebar/export_dialog.rs"),
+            include_str!("sidebar/mod.rs"),
+            include_str!("sidebar/model_picker.rs"),
+            include_str!("sidebar/template_picker.rs"),
+        ];
+        for source in sources {
+            for (at, _) in source.match_indices("t!(") {
+                // Skip `format!(`, `assert!(` and the like
+                let before = source[..at].chars().next_back();
+                if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+                let key = source[at..].split('"').nth(1).unwrap_or_default();
+                assert!(CATALOG.iter().any(|(k, _, _)| *k == key), "missing key {}", key);
+            }
+        }
+    }
+}
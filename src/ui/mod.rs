@@ -5,23 +5,29 @@
 pub mod chat;
 pub mod components;
 pub mod help;
+pub mod i18n;
+pub mod onboarding;
 pub mod settings;
 pub mod sidebar;
 
 use crate::ui::sidebar::Sidebar;
 use crate::ui::chat::ChatView;
 use crate::ui::help::HelpView;
+use crate::ui::onboarding::Onboarding;
 use crate::ui::settings::Settings as SettingsPanel;
 use crate::ui::components::permission_dialog::PermissionDialog;
+use crate::ui::components::a11y::{focus_element, reduced_motion_attr};
+use crate::ui::components::clipboard::copy_to_clipboard;
+use crate::ui::components::command_palette::{Command, CommandPalette, CommandRegistry};
+use crate::ui::components::shortcuts::{match_shortcut, shortcut_listener_js, Shortcut};
+use crate::ui::sidebar::conversation_list::CONVERSATION_SEARCH_ID;
 use crate::app::{AppState, ModelState};
+use crate::inference::LoadPhase;
+use crate::ui::i18n::{lookup, t};
+use crate::storage::conversations::{conversation_to_markdown, Conversation, ConversationOverrides};
 use crate::storage::models::scan_models_directory;
 use dioxus::prelude::*;
 
-/// Simple i18n helper — returns FR or EN string based on current language setting
-pub fn t<'a>(app_state: &AppState, fr: &'a str, en: &'a str) -> &'a str {
-    if app_state.settings.read().language == "en" { en } else { fr }
-}
-
 #[derive(Clone, Copy, PartialEq)]
 enum MainView {
     Chat,
@@ -33,7 +39,7 @@ enum MainView {
 #[component]
 fn HeaderModelPicker() -> Element {
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
     let mut dropdown_open = use_signal(|| false);
     let mut models = use_signal(Vec::new);
     let models_directory = app_state.settings.read().models_directory.clone();
@@ -47,55 +53,44 @@ fn HeaderModelPicker() -> Element {
 
     // Current state
     let model_state = app_state.model_state.read().clone();
-    let is_loading = matches!(model_state, ModelState::Loading);
+    let is_loading = model_state.is_loading();
     let is_loaded = matches!(model_state, ModelState::Loaded(_));
+    let load_percent = model_state.load_progress().map(|p| (p * 100.0).round() as u32).unwrap_or(0);
+    let building = app_state.model_load.read().as_ref().is_some_and(|c| c.phase() == LoadPhase::Building);
+    // Swapping mid-run would cut the reply short
+    let is_generating = *app_state.is_generating.read();
 
     let display_name = match &model_state {
-        ModelState::Loaded(path) => {
-            std::path::Path::new(path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .map(|s| if s.len() > 20 { format!("{}...", crate::truncate_str(s, 20)) } else { s.to_string() })
-                .unwrap_or_else(|| "Model".to_string())
+        ModelState::Loaded(source) => {
+            let name = source.display_name();
+            if name.len() > 20 { format!("{}...", crate::truncate_str(&name, 20)) } else { name }
         }
-        ModelState::Loading => if is_en { "Loading..." } else { "Chargement..." }.to_string(),
+        // llama.cpp reports no progress once it has the load
+        ModelState::Loading(_) if building => t!(locale, "model.loading").to_string(),
+        ModelState::Swapping { .. } if building => t!(locale, "model.switching").to_string(),
+        ModelState::Loading(_) => t!(locale, "model.loading_percent", load_percent),
+        ModelState::Swapping { .. } => t!(locale, "model.switching_percent", load_percent),
         ModelState::Error(msg) => {
             let short = if msg.len() > 20 { format!("{}...", crate::truncate_str(&msg, 20)) } else { msg.clone() };
             format!("{}", short)
         }
-        ModelState::NotLoaded => if is_en { "No model" } else { "Aucun modele" }.to_string(),
+        ModelState::NotLoaded => t!(locale, "header.no_model").to_string(),
     };
 
     // Dot color class
     let dot_class = match &model_state {
         ModelState::Loaded(_) => "status-dot status-dot-ready",
-        ModelState::Loading => "status-dot status-dot-loading",
+        ModelState::Loading(_) | ModelState::Swapping { .. } => "status-dot status-dot-loading",
         ModelState::Error(_) => "status-dot status-dot-error",
         ModelState::NotLoaded => "status-dot status-dot-idle",
     };
 
-    // Handle load
+    // Handle load: swaps in place when a model is already loaded
     let app_state_load = app_state.clone();
     let handle_load = move |path: String| {
-        let mut app_state = app_state_load.clone();
+        let app_state = app_state_load.clone();
         dropdown_open.set(false);
-        app_state.model_state.set(ModelState::Loading);
-        let gpu_layers = app_state.settings.read().gpu_layers;
-        spawn(async move {
-            let result = {
-                let mut engine = app_state.engine.lock().await;
-                if !engine.is_initialized() {
-                    if let Err(e) = engine.init() {
-                        return app_state.model_state.set(ModelState::Error(e.to_string()));
-                    }
-                }
-                engine.load_model_async(&path, gpu_layers).await
-            };
-            match result {
-                Ok(_) => app_state.model_state.set(ModelState::Loaded(path)),
-                Err(e) => app_state.model_state.set(ModelState::Error(e.to_string())),
-            }
-        });
+        app_state.start_model_swap(path);
     };
 
     // Handle unload
@@ -117,7 +112,8 @@ fn HeaderModelPicker() -> Element {
             // Trigger pill button
             button {
                 r#type: "button",
-                onclick: move |_| if !is_loading { dropdown_open.set(!dropdown_open()) },
+                onclick: move |_| if !is_loading && !is_generating { dropdown_open.set(!dropdown_open()) },
+                disabled: is_loading || is_generating,
                 class: "flex items-center gap-2 px-3 py-1.5 rounded-full hover:bg-white/[0.06] transition-all group",
 
                 div { class: "{dot_class}" }
@@ -131,8 +127,13 @@ fn HeaderModelPicker() -> Element {
                             "{display_name}"
                         }
                         div {
-                            class: "loading-bar-mini",
+                            class: "progress-track-mini",
                             style: "width: 80px;",
+                            role: "progressbar",
+                            "aria-valuemin": "0",
+                            "aria-valuemax": "100",
+                            "aria-valuenow": "{load_percent}",
+                            div { class: "progress-fill", style: "width: {load_percent}%;" }
                         }
                     }
                 } else {
@@ -164,7 +165,7 @@ fn HeaderModelPicker() -> Element {
                         class: "px-3 py-2 border-b border-[var(--border-subtle)]",
                         span {
                             class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
-                            if is_en { "Select Model" } else { "Choisir un modele" }
+                            {t!(locale, "header.select_model")}
                         }
                     }
 
@@ -176,7 +177,7 @@ fn HeaderModelPicker() -> Element {
                             div {
                                 class: "px-3 py-4 text-center",
                                 span { class: "text-xs text-[var(--text-tertiary)]",
-                                    if is_en { "No .gguf models found" } else { "Aucun modele .gguf trouve" }
+                                    {t!(locale, "header.no_models")}
                                 }
                             }
                         }
@@ -187,7 +188,7 @@ fn HeaderModelPicker() -> Element {
                                 let filename = model.filename.clone();
                                 let size = model.size_string();
                                 let is_current = match &model_state {
-                                    ModelState::Loaded(p) => *p == path_str,
+                                    ModelState::Loaded(source) => source.local_path() == Some(path_str.as_str()),
                                     _ => false,
                                 };
 
@@ -245,7 +246,7 @@ fn HeaderModelPicker() -> Element {
                                     path { d: "M18.36 6.64a9 9 0 1 1-12.73 0" }
                                     line { x1: "12", y1: "2", x2: "12", y2: "12" }
                                 }
-                                if is_en { "Unload model" } else { "Decharger le modele" }
+                                {t!(locale, "header.unload")}
                             }
                         }
                     }
@@ -255,57 +256,353 @@ fn HeaderModelPicker() -> Element {
     }
 }
 
-/// Prompt suggestion for welcome screen (bilingual)
+/// Prompt suggestion for welcome screen, as catalog keys
 struct PromptSuggestion {
     icon: &'static str,
-    title_fr: &'static str,
-    title_en: &'static str,
-    subtitle_fr: &'static str,
-    subtitle_en: &'static str,
-    prompt_fr: &'static str,
-    prompt_en: &'static str,
+    title: &'static str,
+    subtitle: &'static str,
+    prompt: &'static str,
 }
 
 const SUGGESTIONS: &[PromptSuggestion] = &[
     PromptSuggestion {
         icon: "M9 20l-5.447-2.724A1 1 0 0 1 3 16.382V5.618a1 1 0 0 1 1.447-.894L9 7m0 13l6-3m-6 3V7m6 10l4.553 2.276A1 1 0 0 0 21 18.382V7.618a1 1 0 0 0-.553-.894L15 4m0 13V4m0 0L9 7",
-        title_fr: "Planifier",
-        title_en: "Plan",
-        subtitle_fr: "un voyage, un projet...",
-        subtitle_en: "a trip, a project...",
-        prompt_fr: "Aide-moi a planifier un voyage a Paris. Quels sont les incontournables et les meilleures periodes ?",
-        prompt_en: "Help me plan a trip to Paris. What are the must-sees and best times to visit?",
+        title: "welcome.plan",
+        subtitle: "welcome.plan_subtitle",
+        prompt: "welcome.plan_prompt",
     },
     PromptSuggestion {
         icon: "M12 6.253v13m0-13C10.832 5.477 9.246 5 7.5 5S4.168 5.477 3 6.253v13C4.168 18.477 5.754 18 7.5 18s3.332.477 4.5 1.253m0-13C13.168 5.477 14.754 5 16.5 5c1.747 0 3.332.477 4.5 1.253v13C19.832 18.477 18.247 18 16.5 18c-1.746 0-3.332.477-4.5 1.253",
-        title_fr: "Expliquer",
-        title_en: "Explain",
-        subtitle_fr: "un concept complexe",
-        subtitle_en: "a complex concept",
-        prompt_fr: "Explique-moi l'informatique quantique en termes simples que n'importe qui peut comprendre.",
-        prompt_en: "Explain quantum computing in simple terms that anyone can understand.",
+        title: "welcome.explain",
+        subtitle: "welcome.explain_subtitle",
+        prompt: "welcome.explain_prompt",
     },
     PromptSuggestion {
         icon: "M11 5H6a2 2 0 0 0-2 2v11a2 2 0 0 0 2 2h11a2 2 0 0 0 2-2v-5m-1.414-9.414a2 2 0 1 1 2.828 2.828L11.828 15H9v-2.828l8.586-8.586z",
-        title_fr: "Rediger",
-        title_en: "Write",
-        subtitle_fr: "un email, un texte...",
-        subtitle_en: "an email, a document...",
-        prompt_fr: "Aide-moi a ecrire un email professionnel a mon manager pour demander des conges.",
-        prompt_en: "Help me write a professional email to my manager asking for time off.",
+        title: "welcome.write",
+        subtitle: "welcome.write_subtitle",
+        prompt: "welcome.write_prompt",
     },
     PromptSuggestion {
         icon: "M10 20l4-16m4 4l4 4-4 4M6 16l-4-4 4-4",
-        title_fr: "Coder",
-        title_en: "Code",
-        subtitle_fr: "debugger, expliquer...",
-        subtitle_en: "debug, explain...",
-        prompt_fr: "J'ai un bug dans mon code. Peux-tu m'aider a le debugger ?",
-        prompt_en: "I have a bug in my code. Can you help me debug it?",
+        title: "welcome.code",
+        subtitle: "welcome.code_subtitle",
+        prompt: "welcome.code_prompt",
     },
 ];
 
+/// Switch the open conversation in or out of read-only and save it
+fn toggle_read_only(mut current_conversation: Signal<Option<Conversation>>) {
+    let mut conv_write = current_conversation.write();
+    if let Some(ref mut conv) = *conv_write {
+        conv.overrides.read_only = !conv.overrides.read_only;
+        if let Err(e) = crate::storage::conversations::save_conversation(conv) {
+            tracing::error!("Failed to save conversation: {}", e);
+        }
+    }
+}
+
 /// Main Application Layout
+/// Read-only switch for the current conversation, with a lock indicator when on
+#[component]
+fn HeaderReadOnlyToggle() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let read_only = app_state.current_conversation.read()
+        .as_ref()
+        .map(|c| c.overrides.read_only);
+
+    // No conversation selected yet
+    let Some(read_only) = read_only else {
+        return rsx! {};
+    };
+
+    let current_conversation = app_state.current_conversation;
+    let toggle = move |_| toggle_read_only(current_conversation);
+
+    let title = if read_only { t!(locale, "header.read_only_hint") } else { t!(locale, "header.make_read_only") };
+
+    rsx! {
+        button {
+            onclick: toggle,
+            class: if read_only {
+                "h-8 px-2 rounded-lg flex items-center gap-1.5 text-[var(--accent-primary)] bg-white/[0.06] transition-all"
+            } else {
+                "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all"
+            },
+            title: "{title}",
+            svg {
+                width: "15",
+                height: "15",
+                view_box: "0 0 24 24",
+                fill: "none",
+                stroke: "currentColor",
+                stroke_width: "1.5",
+                stroke_linecap: "round",
+                stroke_linejoin: "round",
+                rect { x: "3", y: "11", width: "18", height: "11", rx: "2" }
+                if read_only {
+                    path { d: "M7 11V7a5 5 0 0 1 10 0v4" }
+                } else {
+                    path { d: "M7 11V7a5 5 0 0 1 9.9-1" }
+                }
+            }
+            if read_only {
+                span {
+                    class: "text-xs font-medium",
+                    {t!(locale, "header.read_only")}
+                }
+            }
+        }
+    }
+}
+
+/// Copies the open conversation as Markdown, the same text the export writes
+#[component]
+fn HeaderCopyConversation() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let mut copied = use_signal(|| false);
+    if app_state.current_conversation.read().is_none() {
+        return rsx! {};
+    }
+
+    let current_conversation = app_state.current_conversation;
+    let active_messages = app_state.active_messages;
+    // Built on click, from the messages on screen, so a reply still streaming is included
+    let copy = move |_| {
+        let Some(mut conversation) = current_conversation.peek().clone() else {
+            return;
+        };
+        conversation.messages = active_messages.peek().iter().cloned().map(Into::into).collect();
+        copy_to_clipboard(&conversation_to_markdown(&conversation));
+        copied.set(true);
+        spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+            copied.set(false);
+        });
+    };
+
+    rsx! {
+        button {
+            onclick: copy,
+            class: "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all",
+            title: t!(locale, "header.copy_conversation_hint"),
+            "aria-label": t!(locale, "header.copy_conversation"),
+            svg {
+                width: "15",
+                height: "15",
+                view_box: "0 0 24 24",
+                fill: "none",
+                stroke: "currentColor",
+                stroke_width: "1.5",
+                stroke_linecap: "round",
+                stroke_linejoin: "round",
+                if copied() {
+                    path { d: "M20 6 9 17l-5-5" }
+                } else {
+                    rect { x: "9", y: "9", width: "13", height: "13", rx: "2" }
+                    path { d: "M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1" }
+                }
+            }
+        }
+    }
+}
+
+/// Per-conversation system prompt and sampling overrides, edited in a popover
+#[component]
+fn HeaderConversationSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let mut is_open = use_signal(|| false);
+    let overrides = app_state.current_conversation.read()
+        .as_ref()
+        .map(|c| c.overrides.clone());
+
+    // No conversation selected yet
+    let Some(overrides) = overrides else {
+        return rsx! {};
+    };
+    let customized = overrides.system_prompt.is_some() || overrides.has_sampling();
+    let (global_temperature, global_top_p, global_top_k, global_max_tokens) = {
+        let settings = app_state.settings.read();
+        (settings.temperature, settings.top_p, settings.top_k, settings.max_tokens)
+    };
+
+    // Apply an edit to the open conversation and save it
+    let mut current_conversation = app_state.current_conversation.clone();
+    let mut update = move |edit: &dyn Fn(&mut ConversationOverrides)| {
+        let mut conv_write = current_conversation.write();
+        if let Some(ref mut conv) = *conv_write {
+            edit(&mut conv.overrides);
+            if let Err(e) = crate::storage::conversations::save_conversation(conv) {
+                tracing::error!("Failed to save conversation: {}", e);
+            }
+        }
+    };
+
+    let field_class = "w-full py-1.5 px-2.5 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs";
+    let label_class = "text-[11px] font-medium text-[var(--text-secondary)]";
+    let number = |value: Option<String>| value.unwrap_or_default();
+
+    rsx! {
+        div { class: "relative",
+            button {
+                onclick: move |_| is_open.set(!is_open()),
+                class: if customized {
+                    "w-8 h-8 rounded-lg flex items-center justify-center text-[var(--accent-primary)] bg-white/[0.06] transition-all"
+                } else {
+                    "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all"
+                },
+                title: t!(locale, "overrides.title"),
+                "aria-expanded": if is_open() { "true" } else { "false" },
+                svg {
+                    width: "15",
+                    height: "15",
+                    view_box: "0 0 24 24",
+                    fill: "none",
+                    stroke: "currentColor",
+                    stroke_width: "1.5",
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                    line { x1: "4", y1: "21", x2: "4", y2: "14" }
+                    line { x1: "4", y1: "10", x2: "4", y2: "3" }
+                    line { x1: "12", y1: "21", x2: "12", y2: "12" }
+                    line { x1: "12", y1: "8", x2: "12", y2: "3" }
+                    line { x1: "20", y1: "21", x2: "20", y2: "16" }
+                    line { x1: "20", y1: "12", x2: "20", y2: "3" }
+                    line { x1: "1", y1: "14", x2: "7", y2: "14" }
+                    line { x1: "9", y1: "8", x2: "15", y2: "8" }
+                    line { x1: "17", y1: "16", x2: "23", y2: "16" }
+                }
+            }
+
+            if is_open() {
+                div {
+                    class: "absolute right-0 mt-2 w-80 p-3 rounded-xl z-50 animate-fade-in space-y-3",
+                    style: "background: var(--bg-elevated); border: 1px solid var(--border-medium); box-shadow: 0 12px 32px -4px rgba(30,25,20,0.35);",
+                    role: "dialog",
+                    "aria-label": t!(locale, "overrides.title"),
+
+                    div {
+                        class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
+                        {t!(locale, "overrides.heading")}
+                    }
+                    p {
+                        class: "text-[11px] text-[var(--text-tertiary)]",
+                        {t!(locale, "overrides.hint")}
+                    }
+
+                    div { class: "space-y-1",
+                        label { class: label_class, r#for: "conv-system-prompt",
+                            {t!(locale, "overrides.system_prompt")}
+                        }
+                        textarea {
+                            id: "conv-system-prompt",
+                            class: "{field_class} h-24 resize-y custom-scrollbar",
+                            value: overrides.system_prompt.clone().unwrap_or_default(),
+                            placeholder: t!(locale, "overrides.system_prompt_placeholder"),
+                            oninput: move |e| {
+                                let value = e.value();
+                                update(&|o| o.system_prompt = if value.trim().is_empty() { None } else { Some(value.clone()) });
+                            },
+                        }
+                    }
+
+                    div { class: "grid grid-cols-2 gap-2",
+                        div { class: "space-y-1",
+                            label { class: label_class, r#for: "conv-temperature", "Temperature" }
+                            input {
+                                id: "conv-temperature",
+                                r#type: "number",
+                                min: "0",
+                                max: "2",
+                                step: "0.05",
+                                class: field_class,
+                                value: number(overrides.temperature.map(|v| v.to_string())),
+                                placeholder: "{global_temperature}",
+                                oninput: move |e| {
+                                    let value = e.value().trim().parse::<f32>().ok().map(|v| v.clamp(0.0, 2.0));
+                                    update(&|o| o.temperature = value);
+                                },
+                            }
+                        }
+                        div { class: "space-y-1",
+                            label { class: label_class, r#for: "conv-top-p", "Top-p" }
+                            input {
+                                id: "conv-top-p",
+                                r#type: "number",
+                                min: "0",
+                                max: "1",
+                                step: "0.05",
+                                class: field_class,
+                                value: number(overrides.top_p.map(|v| v.to_string())),
+                                placeholder: "{global_top_p}",
+                                oninput: move |e| {
+                                    let value = e.value().trim().parse::<f32>().ok().map(|v| v.clamp(0.0, 1.0));
+                                    update(&|o| o.top_p = value);
+                                },
+                            }
+                        }
+                        div { class: "space-y-1",
+                            label { class: label_class, r#for: "conv-top-k", "Top-k" }
+                            input {
+                                id: "conv-top-k",
+                                r#type: "number",
+                                min: "1",
+                                class: field_class,
+                                value: number(overrides.top_k.map(|v| v.to_string())),
+                                placeholder: "{global_top_k}",
+                                oninput: move |e| {
+                                    let value = e.value().trim().parse::<u32>().ok().filter(|v| *v > 0);
+                                    update(&|o| o.top_k = value);
+                                },
+                            }
+                        }
+                        div { class: "space-y-1",
+                            label { class: label_class, r#for: "conv-max-tokens",
+                                {t!(locale, "overrides.max_tokens")}
+                            }
+                            input {
+                                id: "conv-max-tokens",
+                                r#type: "number",
+                                min: "1",
+                                class: field_class,
+                                value: number(overrides.max_tokens.map(|v| v.to_string())),
+                                placeholder: "{global_max_tokens}",
+                                oninput: move |e| {
+                                    let value = e.value().trim().parse::<u32>().ok().filter(|v| *v > 0);
+                                    update(&|o| o.max_tokens = value);
+                                },
+                            }
+                        }
+                    }
+
+                    div { class: "flex justify-end gap-2 pt-1",
+                        button {
+                            class: "btn-ghost text-xs",
+                            disabled: !customized,
+                            onclick: move |_| update(&|o| {
+                                o.system_prompt = None;
+                                o.temperature = None;
+                                o.top_p = None;
+                                o.top_k = None;
+                                o.max_tokens = None;
+                            }),
+                            {t!(locale, "overrides.reset")}
+                        }
+                        button {
+                            class: "btn-primary text-xs",
+                            onclick: move |_| is_open.set(false),
+                            {t!(locale, "overrides.done")}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 pub fn Layout() -> Element {
     let mut current_view = use_signal(|| MainView::Chat);
@@ -314,12 +611,115 @@ pub fn Layout() -> Element {
     
     // Get theme from settings
     let theme_str = app_state.settings.read().theme.clone();
-    let is_en = app_state.settings.read().language == "en";
+    let motion = reduced_motion_attr(&app_state.settings.read().reduced_motion);
+    let locale = app_state.locale();
+    let show_onboarding = !app_state.settings.read().onboarding_completed;
+    let registry = use_context_provider(CommandRegistry::default);
+    let mut palette_open = use_signal(|| false);
+
+    // Actions shared by the header, the keyboard shortcuts and the command palette
+    let current_conversation = app_state.current_conversation;
+    let conversations = app_state.conversations;
+    let new_chat = move || {
+        use crate::storage::conversations::{save_conversation, list_conversations};
+        let (mut current_conversation, mut conversations, mut current_view) = (current_conversation, conversations, current_view);
+        let conversation = Conversation::new(None);
+        if let Err(e) = save_conversation(&conversation) {
+            tracing::error!("Failed to save conversation: {}", e);
+            return;
+        }
+        current_conversation.set(Some(conversation));
+        if let Ok(convs) = list_conversations() {
+            conversations.set(convs);
+        }
+        current_view.set(MainView::Chat);
+    };
+    let open_settings = move || {
+        let mut current_view = current_view;
+        current_view.set(MainView::Settings);
+    };
+    let search_conversations = move || {
+        let mut sidebar_visible = sidebar_visible;
+        sidebar_visible.set(true);
+        focus_element(CONVERSATION_SEARCH_ID);
+    };
+
+    // Keyboard shortcuts, listened to on the whole document
+    {
+        let app_state = app_state.clone();
+        use_effect(move || {
+            let mut app_state = app_state.clone();
+            spawn(async move {
+                let mut keys = document::eval(&shortcut_listener_js());
+                while let Ok((key, primary, in_text_field)) = keys.recv::<(String, bool, bool)>().await {
+                    match match_shortcut(&key, primary, in_text_field) {
+                        Some(Shortcut::NewConversation) => new_chat(),
+                        Some(Shortcut::CommandPalette) => palette_open.set(true),
+                        // The chat input sends on its own; from elsewhere, go to it
+                        Some(Shortcut::Send) => focus_element("chat-input"),
+                        Some(Shortcut::StopGeneration) => {
+                            if *app_state.is_generating.peek() {
+                                app_state.stop_generation();
+                            }
+                        }
+                        Some(Shortcut::SearchConversations) => search_conversations(),
+                        Some(Shortcut::OpenSettings) => open_settings(),
+                        None => {}
+                    }
+                }
+            });
+        });
+    }
+
+    // Palette entries, listed only while it is open
+    let palette_commands: Vec<Command> = if palette_open() {
+        let mut commands = vec![
+            Command::new("new-chat", t!(locale, "command.new_chat"), new_chat),
+            Command::new("search-conversations", t!(locale, "command.search_conversations"), search_conversations),
+            Command::new("open-settings", t!(locale, "command.open_settings"), open_settings),
+        ];
+        if current_conversation.read().is_some() {
+            commands.push(Command::new(
+                "toggle-read-only",
+                t!(locale, "command.toggle_read_only"),
+                move || toggle_read_only(current_conversation),
+            ));
+        }
+        if *app_state.is_generating.read() {
+            let app_state = app_state.clone();
+            commands.push(Command::new("stop-generation", t!(locale, "command.stop_generation"), move || {
+                app_state.clone().stop_generation();
+            }));
+        } else if !app_state.model_state.read().is_loading() {
+            // Swapping mid-run would cut the reply short
+            let models_directory = app_state.settings.read().models_directory.clone();
+            for model in scan_models_directory(&models_directory).unwrap_or_default() {
+                let path = model.path.to_string_lossy().to_string();
+                let label = t!(locale, "command.switch_model", model.filename);
+                let app_state = app_state.clone();
+                commands.push(Command::new(format!("model:{}", path), label, move || app_state.start_model_swap(path.clone())));
+            }
+        }
+        for conversation in conversations.read().iter() {
+            let label = t!(locale, "command.open_conversation", conversation.title);
+            let conversation = conversation.clone();
+            commands.push(Command::new(format!("conversation:{}", conversation.id), label, move || {
+                let (mut current_conversation, mut current_view) = (current_conversation, current_view);
+                current_conversation.set(Some(conversation.clone()));
+                current_view.set(MainView::Chat);
+            }));
+        }
+        commands.extend(registry.commands());
+        commands
+    } else {
+        Vec::new()
+    };
 
     rsx! {
         // Theme wrapper
         div {
             "data-theme": "{theme_str}",
+            "data-reduced-motion": "{motion}",
             class: "relative flex h-screen w-screen bg-[var(--bg-primary)] text-[var(--text-primary)] overflow-hidden",
 
             // Inline CSS
@@ -358,7 +758,7 @@ pub fn Layout() -> Element {
                         button {
                             onclick: move |_| sidebar_visible.set(!sidebar_visible()),
                             class: "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all",
-                            title: if sidebar_visible() { if is_en { "Hide" } else { "Masquer" } } else { if is_en { "Show" } else { "Afficher" } },
+                            title: if sidebar_visible() { t!(locale, "header.hide_sidebar") } else { t!(locale, "header.show_sidebar") },
                             svg {
                                 width: "16",
                                 height: "16",
@@ -374,25 +774,9 @@ pub fn Layout() -> Element {
                         }
 
                         button {
-                            onclick: {
-                                let mut current_conversation = app_state.current_conversation.clone();
-                                let mut conversations = app_state.conversations.clone();
-                                move |_| {
-                                    use crate::storage::conversations::{save_conversation, list_conversations, Conversation};
-                                    let conversation = Conversation::new(None);
-                                    if let Err(e) = save_conversation(&conversation) {
-                                        tracing::error!("Failed to save conversation: {}", e);
-                                        return;
-                                    }
-                                    current_conversation.set(Some(conversation));
-                                    if let Ok(convs) = list_conversations() {
-                                        conversations.set(convs);
-                                    }
-                                    current_view.set(MainView::Chat);
-                                }
-                            },
+                            onclick: move |_| new_chat(),
                             class: "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all",
-                            title: if is_en { "New chat" } else { "Nouveau chat" },
+                            title: t!(locale, "header.new_chat"),
                             svg {
                                 width: "16",
                                 height: "16",
@@ -411,22 +795,30 @@ pub fn Layout() -> Element {
                     // Center: Model picker dropdown
                     HeaderModelPicker {}
 
-                    // Right: Settings
-                    button {
-                        onclick: move |_| current_view.set(MainView::Settings),
-                        class: "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all",
-                        title: "Parametres",
-                        svg {
-                            width: "15",
-                            height: "15",
-                            view_box: "0 0 24 24",
-                            fill: "none",
-                            stroke: "currentColor",
-                            stroke_width: "1.5",
-                            stroke_linecap: "round",
-                            stroke_linejoin: "round",
-                            circle { cx: "12", cy: "12", r: "3" }
-                            path { d: "M19.4 15a1.65 1.65 0 0 0 .33 1.82l.06.06a2 2 0 0 1 0 2.83 2 2 0 0 1-2.83 0l-.06-.06a1.65 1.65 0 0 0-1.82-.33 1.65 1.65 0 0 0-1 1.51V21a2 2 0 0 1-2 2 2 2 0 0 1-2-2v-.09A1.65 1.65 0 0 0 9 19.4a1.65 1.65 0 0 0-1.82.33l-.06.06a2 2 0 0 1-2.83 0 2 2 0 0 1 0-2.83l.06-.06a1.65 1.65 0 0 0 .33-1.82 1.65 1.65 0 0 0-1.51-1H3a2 2 0 0 1-2-2 2 2 0 0 1 2-2h.09A1.65 1.65 0 0 0 4.6 9a1.65 1.65 0 0 0-.33-1.82l-.06-.06a2 2 0 0 1 0-2.83 2 2 0 0 1 2.83 0l.06.06a1.65 1.65 0 0 0 1.82.33H9a1.65 1.65 0 0 0 1-1.51V3a2 2 0 0 1 2-2 2 2 0 0 1 2 2v.09a1.65 1.65 0 0 0 1 1.51 1.65 1.65 0 0 0 1.82-.33l.06-.06a2 2 0 0 1 2.83 0 2 2 0 0 1 0 2.83l-.06.06a1.65 1.65 0 0 0-.33 1.82V9a1.65 1.65 0 0 0 1.51 1H21a2 2 0 0 1 2 2 2 2 0 0 1-2 2h-.09a1.65 1.65 0 0 0-1.51 1z" }
+                    // Right: Read-only toggle + Settings
+                    div {
+                        class: "flex items-center gap-1",
+
+                        HeaderConversationSettings {}
+                        HeaderCopyConversation {}
+                        HeaderReadOnlyToggle {}
+
+                        button {
+                            onclick: move |_| current_view.set(MainView::Settings),
+                            class: "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all",
+                            title: t!(locale, "sidebar.settings"),
+                            svg {
+                                width: "15",
+                                height: "15",
+                                view_box: "0 0 24 24",
+                                fill: "none",
+                                stroke: "currentColor",
+                                stroke_width: "1.5",
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                circle { cx: "12", cy: "12", r: "3" }
+                                path { d: "M19.4 15a1.65 1.65 0 0 0 .33 1.82l.06.06a2 2 0 0 1 0 2.83 2 2 0 0 1-2.83 0l-.06-.06a1.65 1.65 0 0 0-1.82-.33 1.65 1.65 0 0 0-1 1.51V21a2 2 0 0 1-2 2 2 2 0 0 1-2-2v-.09A1.65 1.65 0 0 0 9 19.4a1.65 1.65 0 0 0-1.82.33l-.06.06a2 2 0 0 1-2.83 0 2 2 0 0 1 0-2.83l.06-.06a1.65 1.65 0 0 0 .33-1.82 1.65 1.65 0 0 0-1.51-1H3a2 2 0 0 1-2-2 2 2 0 0 1 2-2h.09A1.65 1.65 0 0 0 4.6 9a1.65 1.65 0 0 0-.33-1.82l-.06-.06a2 2 0 0 1 0-2.83 2 2 0 0 1 2.83 0l.06.06a1.65 1.65 0 0 0 1.82.33H9a1.65 1.65 0 0 0 1-1.51V3a2 2 0 0 1 2-2 2 2 0 0 1 2 2v.09a1.65 1.65 0 0 0 1 1.51 1.65 1.65 0 0 0 1.82-.33l.06-.06a2 2 0 0 1 2.83 0 2 2 0 0 1 0 2.83l-.06.06a1.65 1.65 0 0 0-.33 1.82V9a1.65 1.65 0 0 0 1.51 1H21a2 2 0 0 1 2 2 2 2 0 0 1-2 2h-.09a1.65 1.65 0 0 0-1.51 1z" }
+                            }
                         }
                     }
                 }
@@ -451,7 +843,7 @@ pub fn Layout() -> Element {
                                     stroke_linejoin: "round",
                                     path { d: "M19 12H5M12 19l-7-7 7-7" }
                                 }
-                                "Back to Chat"
+                                {t!(locale, "header.back_to_chat")}
                             }
                         }
                         SettingsPanel {}
@@ -475,7 +867,7 @@ pub fn Layout() -> Element {
                                     stroke_linejoin: "round",
                                     path { d: "M19 12H5M12 19l-7-7 7-7" }
                                 }
-                                "Back to Chat"
+                                {t!(locale, "header.back_to_chat")}
                             }
                         }
                         HelpView {}
@@ -505,6 +897,18 @@ pub fn Layout() -> Element {
             }
 
             PermissionDialog {}
+
+            if palette_open() {
+                CommandPalette {
+                    commands: palette_commands,
+                    on_close: move |_| palette_open.set(false),
+                    locale,
+                }
+            }
+
+            if show_onboarding {
+                Onboarding {}
+            }
         }
     }
 }
@@ -513,7 +917,7 @@ pub fn Layout() -> Element {
 #[component]
 fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
     rsx! {
         div {
             class: "flex-1 flex flex-col relative overflow-hidden",
@@ -558,7 +962,7 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
                     p {
                         class: "text-[var(--text-tertiary)] text-center text-sm tracking-wide",
                         style: "letter-spacing: 0.12em; text-transform: uppercase; font-weight: 500;",
-                        if is_en { "Your private AI, 100% local" } else { "Votre IA privee, 100% locale" }
+                        {t!(locale, "welcome.tagline")}
                     }
                 }
 
@@ -568,9 +972,9 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
 
                     for (i, suggestion) in SUGGESTIONS.iter().enumerate() {
                         {
-                            let title = if is_en { suggestion.title_en } else { suggestion.title_fr };
-                            let subtitle = if is_en { suggestion.subtitle_en } else { suggestion.subtitle_fr };
-                            let prompt = if is_en { suggestion.prompt_en } else { suggestion.prompt_fr };
+                            let title = lookup(locale, suggestion.title);
+                            let subtitle = lookup(locale, suggestion.subtitle);
+                            let prompt = lookup(locale, suggestion.prompt);
                             rsx! {
                                 button {
                                     onclick: {
@@ -636,7 +1040,7 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
 
                         span {
                             class: "flex-1 text-left text-[var(--text-tertiary)] text-[15px]",
-                            if is_en { "Send a message..." } else { "Envoyer un message..." }
+                            {t!(locale, "welcome.placeholder")}
                         }
 
                         // Send arrow
@@ -661,7 +1065,7 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
                     // Privacy badge
                     p {
                         class: "text-center text-xs text-[var(--text-tertiary)] mt-3 opacity-40",
-                        if is_en { "100% private — no data leaves your device" } else { "100% prive — aucune donnee ne quitte votre appareil" }
+                        {t!(locale, "welcome.privacy")}
                     }
                 }
             }
@@ -0,0 +1,322 @@
+//! First-run onboarding
+//!
+//! Shown once on a fresh install: runs the startup self-check and offers the
+//! fixes it points at (models folder, a small starter model, workspace,
+//! language). Skipping counts as completing, so it never comes back.
+
+use crate::app::AppState;
+use crate::storage::huggingface::download_model;
+use crate::storage::settings::{default_system_prompt_for_lang, save_settings};
+use crate::system::diagnostics::{CheckKind, CheckStatus, SelfCheckReport};
+use crate::ui::components::loading::Spinner;
+use crate::ui::i18n::t;
+use crate::ui::settings::diagnostics::{spawn_self_check, CheckList};
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+/// Small instruct model that runs on most machines (about 1 GB)
+const RECOMMENDED_MODEL: &str = "Qwen/Qwen2.5-1.5B-Instruct-GGUF/qwen2.5-1.5b-instruct-q4_k_m.gguf";
+
+#[component]
+pub fn Onboarding() -> Element {
+    let app_state = use_context::<AppState>();
+    let mut settings = app_state.settings;
+    let locale = settings.read().locale();
+
+    let report = use_signal(|| None::<SelfCheckReport>);
+    let running = use_signal(|| false);
+    let mut models_dir_input = use_signal(|| settings.peek().models_directory.to_string_lossy().to_string());
+    let mut workspace_input = use_signal(|| {
+        settings
+            .peek()
+            .workspace_directory
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    let mut downloading = use_signal(|| false);
+    let mut notice = use_signal(|| None::<(bool, String)>);
+
+    use_hook(move || spawn_self_check(settings.peek().clone(), report, running));
+
+    let rerun = move || spawn_self_check(settings.peek().clone(), report, running);
+
+    let mut finish = move || {
+        let mut settings = settings.write();
+        settings.onboarding_completed = true;
+        if let Err(e) = save_settings(&settings) {
+            tracing::error!("Failed to save settings: {}", e);
+        }
+    };
+
+    let mut choose_models_dir = move |path: PathBuf| {
+        if !path.is_dir() {
+            let msg = t!(locale, "onboarding.folder_missing");
+            notice.set(Some((false, format!("{}: {}", msg, path.display()))));
+            return;
+        }
+        models_dir_input.set(path.to_string_lossy().to_string());
+        {
+            let mut settings = settings.write();
+            settings.models_directory = path;
+            if let Err(e) = save_settings(&settings) {
+                tracing::error!("Failed to save settings: {}", e);
+            }
+        }
+        notice.set(None);
+        rerun();
+    };
+
+    let mut set_workspace = move || {
+        let path = PathBuf::from(workspace_input().trim());
+        if let Err(e) = std::env::set_current_dir(&path) {
+            let msg = t!(locale, "onboarding.folder_unusable");
+            notice.set(Some((false, format!("{}: {}", msg, e))));
+            return;
+        }
+        {
+            let mut settings = settings.write();
+            settings.workspace_directory = Some(path);
+            if let Err(e) = save_settings(&settings) {
+                tracing::error!("Failed to save settings: {}", e);
+            }
+        }
+        notice.set(None);
+        rerun();
+    };
+
+    let download_recommended = move |_| {
+        downloading.set(true);
+        notice.set(None);
+        spawn(async move {
+            let result = download_model(RECOMMENDED_MODEL, |_downloaded, _total| {}).await;
+            downloading.set(false);
+            match result {
+                Ok(path) => {
+                    tracing::info!("Downloaded recommended model to: {:?}", path);
+                    let models_dir = path.parent().map(|p| p.to_path_buf());
+                    let has_models = report
+                        .peek()
+                        .as_ref()
+                        .and_then(|r| r.status(CheckKind::Models))
+                        == Some(CheckStatus::Pass);
+                    match models_dir {
+                        Some(dir) if !has_models => choose_models_dir(dir),
+                        _ => rerun(),
+                    }
+                    let msg = t!(locale, "onboarding.model_downloaded");
+                    notice.set(Some((true, msg.to_string())));
+                }
+                Err(e) => {
+                    tracing::error!("Download failed: {}", e);
+                    notice.set(Some((false, e)));
+                }
+            }
+        });
+    };
+
+    let current_lang = settings.read().language.clone();
+    let locations = report
+        .read()
+        .as_ref()
+        .map(|r| r.model_locations.clone())
+        .unwrap_or_default();
+    let configured_dir = settings.read().models_directory.clone();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/60 backdrop-blur-2xl z-40 flex items-center justify-center p-4",
+            onkeydown: move |evt: KeyboardEvent| {
+                if evt.key() == Key::Escape {
+                    finish();
+                }
+            },
+
+            div {
+                class: "w-full max-w-2xl max-h-[90vh] overflow-y-auto custom-scrollbar glass-strong rounded-2xl animate-scale-in",
+                role: "dialog",
+                "aria-modal": "true",
+                "aria-labelledby": "onboarding-title",
+
+                // Header
+                div {
+                    class: "p-6 border-b border-[var(--border-subtle)] flex items-start justify-between gap-4",
+                    div {
+                        h2 {
+                            id: "onboarding-title",
+                            class: "text-lg font-semibold text-[var(--text-primary)]",
+                            {t!(locale, "onboarding.welcome")}
+                        }
+                        p {
+                            class: "text-sm text-[var(--text-secondary)] mt-1",
+                            {t!(locale, "onboarding.intro")}
+                        }
+                    }
+                    button {
+                        class: "btn-ghost text-xs flex-shrink-0",
+                        onclick: move |_| finish(),
+                        {t!(locale, "onboarding.skip")}
+                    }
+                }
+
+                div {
+                    class: "p-6 space-y-6",
+
+                    // Language
+                    div {
+                        class: "grid grid-cols-2 gap-3",
+                        role: "radiogroup",
+                        "aria-label": t!(locale, "onboarding.language"),
+                        for (code, label) in [("fr", "Français"), ("en", "English")] {
+                            {
+                                let selected = current_lang == code;
+                                rsx! {
+                                    button {
+                                        role: "radio",
+                                        "aria-checked": "{selected}",
+                                        onclick: move |_| {
+                                            let mut settings = settings.write();
+                                            settings.language = code.to_string();
+                                            settings.system_prompt = default_system_prompt_for_lang(code);
+                                            if let Err(e) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", e);
+                                            }
+                                        },
+                                        class: if selected {
+                                            "py-2 px-4 rounded-xl border text-sm border-[var(--accent-primary)] bg-[var(--accent-primary-10)] text-[var(--accent-primary)]"
+                                        } else {
+                                            "py-2 px-4 rounded-xl border text-sm border-[var(--border-subtle)] bg-white/[0.02] text-[var(--text-secondary)] hover:border-[var(--border-medium)]"
+                                        },
+                                        "{label}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Self-check results
+                    match report.read().as_ref() {
+                        Some(report) => rsx! { CheckList { report: report.clone() } },
+                        None => rsx! {
+                            div {
+                                class: "flex items-center gap-2 text-xs text-[var(--text-tertiary)]",
+                                Spinner { size: 14 }
+                                {t!(locale, "onboarding.running")}
+                            }
+                        },
+                    }
+
+                    // Models folder
+                    div {
+                        class: "space-y-2",
+                        h3 {
+                            class: "text-sm font-semibold text-[var(--text-primary)]",
+                            {t!(locale, "onboarding.models_folder")}
+                        }
+                        for location in locations.iter().filter(|l| l.path != configured_dir) {
+                            {
+                                let path = location.path.clone();
+                                let display = path.display().to_string();
+                                let count = location.model_count;
+                                rsx! {
+                                    div {
+                                        class: "flex items-center justify-between gap-3 p-2 rounded-lg bg-white/[0.02] border border-[var(--border-subtle)]",
+                                        span { class: "text-xs font-mono text-[var(--text-secondary)] truncate", title: "{display}", "{display}" }
+                                        span { class: "text-[10px] text-[var(--text-tertiary)] flex-shrink-0", "{count} GGUF" }
+                                        button {
+                                            class: "btn-ghost text-xs flex-shrink-0",
+                                            onclick: move |_| choose_models_dir(path.clone()),
+                                            {t!(locale, "onboarding.use_this_folder")}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            class: "flex gap-2",
+                            input {
+                                r#type: "text",
+                                value: "{models_dir_input}",
+                                oninput: move |e| models_dir_input.set(e.value()),
+                                "aria-label": t!(locale, "onboarding.models_folder_path"),
+                                class: "flex-1 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-mono focus:border-[var(--accent-primary)] outline-none",
+                            }
+                            button {
+                                class: "btn-ghost text-xs",
+                                onclick: move |_| choose_models_dir(PathBuf::from(models_dir_input().trim())),
+                                {t!(locale, "onboarding.use")}
+                            }
+                        }
+                        button {
+                            class: "w-full flex items-center justify-center gap-2 py-2 rounded-xl text-xs font-medium bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:border-[var(--accent-primary)] hover:text-[var(--accent-primary)] transition-all",
+                            disabled: downloading(),
+                            onclick: download_recommended,
+                            if downloading() {
+                                Spinner { size: 12 }
+                                {t!(locale, "onboarding.downloading")}
+                            } else {
+                                {t!(locale, "onboarding.download_recommended")}
+                            }
+                        }
+                    }
+
+                    // Workspace
+                    div {
+                        class: "space-y-2",
+                        h3 {
+                            class: "text-sm font-semibold text-[var(--text-primary)]",
+                            {t!(locale, "onboarding.workspace")}
+                        }
+                        p {
+                            class: "text-xs text-[var(--text-tertiary)]",
+                            {t!(locale, "onboarding.workspace_hint")}
+                        }
+                        div {
+                            class: "flex gap-2",
+                            input {
+                                r#type: "text",
+                                value: "{workspace_input}",
+                                oninput: move |e| workspace_input.set(e.value()),
+                                "aria-label": t!(locale, "onboarding.workspace_path"),
+                                class: "flex-1 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-mono focus:border-[var(--accent-primary)] outline-none",
+                            }
+                            button {
+                                class: "btn-ghost text-xs",
+                                onclick: move |_| set_workspace(),
+                                {t!(locale, "onboarding.set")}
+                            }
+                        }
+                    }
+
+                    if let Some((ok, message)) = notice.read().as_ref() {
+                        div {
+                            class: if *ok {
+                                "p-3 rounded-xl text-xs bg-[var(--bg-success-subtle)] border border-[var(--border-success-subtle)] text-[var(--text-success)]"
+                            } else {
+                                "p-3 rounded-xl text-xs bg-[var(--bg-error-subtle)] border border-[var(--border-error-subtle)] text-[var(--text-error)]"
+                            },
+                            role: "status",
+                            "{message}"
+                        }
+                    }
+                }
+
+                // Footer
+                div {
+                    class: "p-6 border-t border-[var(--border-subtle)] flex gap-3",
+                    button {
+                        class: "btn-ghost flex-1",
+                        onclick: move |_| finish(),
+                        {t!(locale, "onboarding.skip_for_now")}
+                    }
+                    button {
+                        class: "btn-primary flex-1",
+                        onclick: move |_| finish(),
+                        {t!(locale, "onboarding.get_started")}
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::app::AppState;
 use crate::storage::settings::{default_system_prompt_for_lang, save_settings};
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
 
 pub fn AppearanceSettings() -> Element {
@@ -7,7 +8,7 @@ pub fn AppearanceSettings() -> Element {
     let settings = app_state.settings.read().clone();
     let dark_mode = settings.theme == "dark";
     let current_lang = settings.language.clone();
-    let is_fr = current_lang == "fr";
+    let locale = settings.locale();
     let font_size = settings.font_size.to_lowercase();
     let selected_font_size = match font_size.as_str() {
         "small" => "Small",
@@ -17,6 +18,10 @@ pub fn AppearanceSettings() -> Element {
     let mut app_state_theme = app_state.clone();
     let mut app_state_font_size = app_state.clone();
     let mut app_state_lang = app_state.clone();
+    let mut app_state_agent_lang = app_state.clone();
+    let agent_lang = settings.agent_language.clone();
+    let mut app_state_motion = app_state.clone();
+    let reduced_motion = settings.reduced_motion.clone();
 
     rsx! {
         div {
@@ -28,17 +33,17 @@ pub fn AppearanceSettings() -> Element {
 
                 h3 {
                     class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
-                    if is_fr { "Langue" } else { "Language" }
+                    {t!(locale, "appearance.language")}
                 }
 
                 div {
                     div {
                         class: "text-sm font-medium text-[var(--text-primary)] mb-1",
-                        if is_fr { "Langue de l'interface" } else { "Interface language" }
+                        {t!(locale, "appearance.interface_language")}
                     }
                     div {
                         class: "text-xs text-[var(--text-tertiary)] mb-4",
-                        if is_fr { "Change la langue de l'interface et des réponses de l'IA" } else { "Changes the UI language and AI responses" }
+                        {t!(locale, "appearance.language_hint")}
                     }
 
                     div { class: "grid grid-cols-2 gap-3",
@@ -71,6 +76,37 @@ pub fn AppearanceSettings() -> Element {
                             }
                         }
                     }
+
+                    div {
+                        class: "text-sm font-medium text-[var(--text-primary)] mt-5 mb-1",
+                        {t!(locale, "appearance.agent_language")}
+                    }
+                    div {
+                        class: "text-xs text-[var(--text-tertiary)] mb-4",
+                        {t!(locale, "appearance.agent_language_hint")}
+                    }
+                    div { class: "grid grid-cols-3 gap-3",
+                        for (code, label) in [("auto", "Auto"), ("fr", "Français"), ("en", "English")] {
+                            button {
+                                onclick: move |_| {
+                                    let mut settings = app_state_agent_lang.settings.write();
+                                    settings.agent_language = code.to_string();
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                                class: format!(
+                                    "py-2.5 px-4 rounded-xl border transition-all text-center text-sm font-medium {}",
+                                    if agent_lang == code {
+                                        "border-[var(--accent-primary)] bg-[var(--accent-primary-10)] text-[var(--accent-primary)]"
+                                    } else {
+                                        "border-[var(--border-subtle)] bg-white/[0.02] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04]"
+                                    }
+                                ),
+                                "{label}"
+                            }
+                        }
+                    }
                 }
             }
 
@@ -80,7 +116,7 @@ pub fn AppearanceSettings() -> Element {
 
                 h3 {
                     class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
-                    if is_fr { "Theme" } else { "Theme" }
+                    {t!(locale, "appearance.theme")}
                 }
 
                 div {
@@ -88,10 +124,10 @@ pub fn AppearanceSettings() -> Element {
 
                     div {
                         div { class: "text-sm font-medium text-[var(--text-primary)]",
-                            if is_fr { "Mode sombre" } else { "Dark Mode" }
+                            {t!(locale, "appearance.dark_mode")}
                         }
                         div { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
-                            if is_fr { "Basculer entre le theme clair et sombre" } else { "Switch between light and dark theme" }
+                            {t!(locale, "appearance.dark_mode_hint")}
                         }
                     }
                     button {
@@ -103,26 +139,78 @@ pub fn AppearanceSettings() -> Element {
                             }
                         },
                         class: if dark_mode { "toggle-switch active" } else { "toggle-switch" },
+                        role: "switch",
+                        "aria-checked": "{dark_mode}",
+                        "aria-label": t!(locale, "appearance.dark_mode"),
                         div { class: "toggle-switch-knob" }
                     }
                 }
             }
 
+            // Motion Card — reduced motion mode
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
+                    {t!(locale, "appearance.motion")}
+                }
+
+                div {
+                    div { class: "text-sm font-medium text-[var(--text-primary)] mb-1",
+                        {t!(locale, "appearance.reduced_motion")}
+                    }
+                    div { class: "text-xs text-[var(--text-tertiary)] mb-4",
+                        {t!(locale, "appearance.reduced_motion_hint")}
+                    }
+
+                    div { class: "grid grid-cols-3 gap-3",
+                        role: "radiogroup",
+                        for (value, label) in [
+                            ("auto", t!(locale, "appearance.motion_auto")),
+                            ("on", t!(locale, "appearance.motion_on")),
+                            ("off", t!(locale, "appearance.motion_off")),
+                        ] {
+                            button {
+                                role: "radio",
+                                "aria-checked": if reduced_motion == value { "true" } else { "false" },
+                                onclick: move |_| {
+                                    let mut settings = app_state_motion.settings.write();
+                                    settings.reduced_motion = value.to_string();
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                                class: format!(
+                                    "py-3 px-4 rounded-xl border transition-all text-center {}",
+                                    if reduced_motion == value {
+                                        "border-[var(--accent-primary)] bg-[var(--accent-primary-10)] text-[var(--accent-primary)]"
+                                    } else {
+                                        "border-[var(--border-subtle)] bg-white/[0.02] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04]"
+                                    }
+                                ),
+                                span { class: "text-sm font-medium", "{label}" }
+                            }
+                        }
+                    }
+                }
+            }
+
             // Font Size Card — glass with selection cards
             div {
                 class: "p-5 rounded-2xl glass-md",
 
                 h3 {
                     class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
-                    if is_fr { "Typographie" } else { "Typography" }
+                    {t!(locale, "appearance.typography")}
                 }
 
                 div {
                     div { class: "text-sm font-medium text-[var(--text-primary)] mb-1",
-                        if is_fr { "Taille de police" } else { "Font Size" }
+                        {t!(locale, "appearance.font_size")}
                     }
                     div { class: "text-xs text-[var(--text-tertiary)] mb-4",
-                        if is_fr { "Ajuster la taille du texte dans le chat" } else { "Adjust text size in the chat interface" }
+                        {t!(locale, "appearance.font_size_hint")}
                     }
 
                     div { class: "grid grid-cols-3 gap-3",
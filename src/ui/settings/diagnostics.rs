@@ -0,0 +1,165 @@
+use crate::agent::language::Locale;
+use crate::app::AppState;
+use crate::storage::settings::AppSettings;
+use crate::system::diagnostics::{run_self_check, CheckKind, CheckStatus, SelfCheckReport};
+use crate::ui::components::clipboard::copy_to_clipboard;
+use crate::ui::components::loading::Spinner;
+use crate::ui::i18n::t;
+use dioxus::prelude::*;
+
+/// Run the self-check on a blocking thread and publish the result
+pub fn spawn_self_check(
+    settings: AppSettings,
+    mut report: Signal<Option<SelfCheckReport>>,
+    mut running: Signal<bool>,
+) {
+    if *running.peek() {
+        return;
+    }
+    running.set(true);
+    spawn(async move {
+        match tokio::task::spawn_blocking(move || run_self_check(&settings)).await {
+            Ok(result) => report.set(Some(result)),
+            Err(e) => tracing::error!("Self-check failed: {}", e),
+        }
+        running.set(false);
+    });
+}
+
+fn check_title(kind: CheckKind, locale: Locale) -> &'static str {
+    match kind {
+        CheckKind::DataDir => t!(locale, "diagnostics.data_dir"),
+        CheckKind::Models => t!(locale, "diagnostics.models"),
+        CheckKind::GpuBackend => t!(locale, "diagnostics.gpu"),
+        CheckKind::Workspace => t!(locale, "diagnostics.workspace"),
+    }
+}
+
+/// One row per check with a pass/warn/fail badge
+#[component]
+pub fn CheckList(report: SelfCheckReport) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+
+    rsx! {
+        ul {
+            class: "space-y-2",
+            for check in report.checks.iter() {
+                {
+                    let (badge, style) = match check.status {
+                        CheckStatus::Pass => ("OK", "color: var(--text-success); background: var(--bg-success-subtle);"),
+                        CheckStatus::Warn => (
+                            t!(locale, "diagnostics.warning"),
+                            "color: #C4993B; background: rgba(196,153,59,0.12);",
+                        ),
+                        CheckStatus::Fail => (
+                            t!(locale, "diagnostics.failed"),
+                            "color: var(--text-error); background: var(--bg-error-subtle);",
+                        ),
+                    };
+                    let title = check_title(check.kind, locale);
+                    rsx! {
+                        li {
+                            class: "flex items-start gap-3 p-3 rounded-xl bg-white/[0.02] border border-[var(--border-subtle)]",
+                            span {
+                                class: "flex-shrink-0 px-2 py-0.5 rounded-md text-[10px] font-semibold uppercase tracking-wide",
+                                style: "{style}",
+                                "{badge}"
+                            }
+                            div {
+                                class: "min-w-0",
+                                div { class: "text-sm font-medium text-[var(--text-primary)]", "{title}" }
+                                div { class: "text-xs text-[var(--text-tertiary)] break-all", "{check.detail}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn DiagnosticsSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let report = use_signal(|| None::<SelfCheckReport>);
+    let running = use_signal(|| false);
+    let mut copied = use_signal(|| false);
+
+    let settings = app_state.settings;
+    use_hook(move || spawn_self_check(settings.peek().clone(), report, running));
+
+    let report_text = report.read().as_ref().map(|r| r.to_text()).unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                div {
+                    class: "flex items-start justify-between gap-4 mb-5",
+                    div {
+                        h3 {
+                            class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                            {t!(locale, "diagnostics.title")}
+                        }
+                        p {
+                            class: "text-xs text-[var(--text-tertiary)]",
+                            {t!(locale, "diagnostics.description")}
+                        }
+                    }
+                    div {
+                        class: "flex gap-2 flex-shrink-0",
+                        button {
+                            class: "btn-ghost text-xs",
+                            disabled: running(),
+                            onclick: move |_| {
+                                copied.set(false);
+                                spawn_self_check(settings.peek().clone(), report, running);
+                            },
+                            if running() {
+                                Spinner { size: 12 }
+                            }
+                            {t!(locale, "diagnostics.run_again")}
+                        }
+                        button {
+                            class: "btn-primary text-xs",
+                            disabled: report.read().is_none(),
+                            onclick: {
+                                let report_text = report_text.clone();
+                                move |_| {
+                                    copy_to_clipboard(&report_text);
+                                    copied.set(true);
+                                }
+                            },
+                            if copied() {
+                                {t!(locale, "diagnostics.copied")}
+                            } else {
+                                {t!(locale, "diagnostics.copy_report")}
+                            }
+                        }
+                    }
+                }
+
+                match report.read().as_ref() {
+                    Some(report) => rsx! {
+                        CheckList { report: report.clone() }
+                        pre {
+                            class: "mt-4 p-3 rounded-xl bg-white/[0.02] border border-[var(--border-subtle)] text-[11px] font-mono text-[var(--text-secondary)] whitespace-pre-wrap select-text",
+                            "{report_text}"
+                        }
+                    },
+                    None => rsx! {
+                        div {
+                            class: "flex items-center gap-2 text-xs text-[var(--text-tertiary)]",
+                            Spinner { size: 14 }
+                            {t!(locale, "diagnostics.running")}
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,16 @@
 use crate::app::AppState;
+use crate::storage::models::scan_models_directory;
 use crate::storage::settings::save_settings;
 use crate::system::gpu::{detect_gpu, GpuInfo};
 use crate::system::resources::{get_resource_usage, ResourceUsage};
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
 use std::process::Command;
 
 pub fn HardwareSettings() -> Element {
     let app_state = use_context::<AppState>();
     let settings = app_state.settings.read().clone();
+    let locale = settings.locale();
     let gpu_layers = settings.gpu_layers;
     let models_dir = settings.models_directory.to_string_lossy().to_string();
     let models_dir_path = settings.models_directory.clone();
@@ -15,6 +18,14 @@ pub fn HardwareSettings() -> Element {
     let last_model_path = settings.last_model_path.clone();
     let mut app_state_gpu_layers = app_state.clone();
     let mut app_state_auto_load = app_state.clone();
+    let probe_reasoning = settings.probe_reasoning;
+    let mut app_state_probe = app_state.clone();
+    let draft_model_path = settings.draft_model_path.clone().unwrap_or_default();
+    let mut app_state_draft = app_state.clone();
+    let draft_candidates = use_signal({
+        let models_dir_path = models_dir_path.clone();
+        move || scan_models_directory(&models_dir_path).unwrap_or_default()
+    });
 
     let gpu_info = use_signal(GpuInfo::default);
     let ram_usage = use_signal(ResourceUsage::default);
@@ -39,7 +50,7 @@ pub fn HardwareSettings() -> Element {
     let gpu_name = if gpu_snapshot.is_available && !gpu_snapshot.name.is_empty() {
         gpu_snapshot.name.clone()
     } else {
-        "GPU non detecte".to_string()
+        t!(locale, "hardware.no_gpu").to_string()
     };
 
     let vram_total_mb = gpu_snapshot.vram_total_mb;
@@ -76,7 +87,7 @@ pub fn HardwareSettings() -> Element {
 
                 h3 {
                     class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
-                    "GPU Information"
+                    {t!(locale, "hardware.gpu_title")}
                 }
 
                 div {
@@ -94,14 +105,14 @@ pub fn HardwareSettings() -> Element {
 
                         div { class: "mt-3 space-y-2",
                             if vram_total_mb == 0 {
-                                p { class: "text-xs text-[var(--text-tertiary)]", "VRAM indisponible" }
+                                p { class: "text-xs text-[var(--text-tertiary)]", {t!(locale, "hardware.vram_unavailable")} }
                             } else if vram_usage_available {
                                 div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                                    span { "VRAM utilisee" }
+                                    span { {t!(locale, "hardware.vram_used")} }
                                     span { class: "font-mono", "{vram_used_gb:.1} / {vram_total_gb:.1} GB" }
                                 }
                                 div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                                    span { "VRAM restante" }
+                                    span { {t!(locale, "hardware.vram_free")} }
                                     span { class: "font-mono", "{vram_free_gb:.1} GB" }
                                 }
                                 // Progress Bar — accent gradient
@@ -114,10 +125,10 @@ pub fn HardwareSettings() -> Element {
                                 }
                             } else {
                                 div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                                    span { "VRAM totale" }
+                                    span { {t!(locale, "hardware.vram_total")} }
                                     span { class: "font-mono", "{vram_total_gb:.1} GB" }
                                 }
-                                p { class: "text-xs text-[var(--text-tertiary)]", "Utilisation VRAM indisponible" }
+                                p { class: "text-xs text-[var(--text-tertiary)]", {t!(locale, "hardware.vram_usage_unavailable")} }
                             }
                         }
                     }
@@ -130,19 +141,19 @@ pub fn HardwareSettings() -> Element {
 
                 h3 {
                     class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
-                    "System Memory"
+                    {t!(locale, "hardware.memory_title")}
                 }
 
                 if ram_total_mb == 0 {
-                    p { class: "text-xs text-[var(--text-tertiary)]", "RAM indisponible" }
+                    p { class: "text-xs text-[var(--text-tertiary)]", {t!(locale, "hardware.ram_unavailable")} }
                 } else {
                     div { class: "space-y-2",
                         div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                            span { "RAM utilisee" }
+                            span { {t!(locale, "hardware.ram_used")} }
                             span { class: "font-mono", "{ram_used_gb:.1} / {ram_total_gb:.1} GB" }
                         }
                         div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                            span { "RAM restante" }
+                            span { {t!(locale, "hardware.ram_free")} }
                             span { class: "font-mono", "{ram_free_gb:.1} GB" }
                         }
                         div {
@@ -162,20 +173,20 @@ pub fn HardwareSettings() -> Element {
 
                 h3 {
                     class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
-                    "Hardware Acceleration"
+                    {t!(locale, "hardware.acceleration_title")}
                 }
 
                 // Auto-load Model Toggle
                 div { class: "mb-6",
                     div { class: "flex items-center justify-between",
                         div {
-                            label { class: "text-sm font-medium text-[var(--text-primary)]", "Charger auto. au demarrage" }
+                            label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "hardware.auto_load")} }
                             p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
                                 {
                                     if let Some(ref path) = last_model_path {
-                                        format!("Dernier: {}", std::path::Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default())
+                                        t!(locale, "hardware.auto_load_last", std::path::Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default())
                                     } else {
-                                        "Aucun modele sauvegarde".to_string()
+                                        t!(locale, "hardware.auto_load_none").to_string()
                                     }
                                 }
                             }
@@ -194,10 +205,63 @@ pub fn HardwareSettings() -> Element {
                     }
                 }
 
+                // Reasoning probe toggle
+                div { class: "mb-6",
+                    div { class: "flex items-center justify-between",
+                        div {
+                            label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "hardware.probe_reasoning")} }
+                            p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                                {t!(locale, "hardware.probe_reasoning_hint")}
+                            }
+                        }
+                        button {
+                            class: if probe_reasoning { "toggle-switch active" } else { "toggle-switch" },
+                            onclick: move |_| {
+                                let mut settings = app_state_probe.settings.write();
+                                settings.probe_reasoning = !settings.probe_reasoning;
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                            div { class: "toggle-switch-knob" }
+                        }
+                    }
+                }
+
+                // Draft model for speculative decoding
+                div { class: "mb-6",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", r#for: "draft-model", {t!(locale, "hardware.draft_model")} }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-0.5 mb-2",
+                        {t!(locale, "hardware.draft_model_hint")}
+                    }
+                    select {
+                        id: "draft-model",
+                        class: "w-full px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        value: "{draft_model_path}",
+                        onchange: move |e: Event<FormData>| {
+                            let value = e.value();
+                            let mut settings = app_state_draft.settings.write();
+                            settings.draft_model_path = (!value.is_empty()).then_some(value);
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        option { value: "", {t!(locale, "hardware.draft_model_none")} }
+                        for model in draft_candidates.read().iter() {
+                            option {
+                                key: "{model.path.display()}",
+                                value: "{model.path.display()}",
+                                selected: model.path.to_string_lossy() == draft_model_path.as_str(),
+                                "{model.filename}"
+                            }
+                        }
+                    }
+                }
+
                 // GPU Layers Control
                 div { class: "mb-6",
                     div { class: "flex justify-between items-center mb-2",
-                        label { class: "text-sm font-medium text-[var(--text-primary)]", "GPU Layers" }
+                        label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "hardware.gpu_layers")} }
                         span {
                             class: "text-xs font-mono px-2 py-1 rounded-lg bg-white/[0.04] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
                             "{gpu_layers}"
@@ -219,13 +283,13 @@ pub fn HardwareSettings() -> Element {
                         class: "w-full",
                     }
                     p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
-                        "Layers to offload to GPU. Higher values need more VRAM."
+                        {t!(locale, "hardware.gpu_layers_hint")}
                     }
                 }
 
                 // Models Directory Input
                 div {
-                    label { class: "text-sm font-medium text-[var(--text-primary)] mb-2 block", "Models Directory" }
+                    label { class: "text-sm font-medium text-[var(--text-primary)] mb-2 block", {t!(locale, "hardware.models_dir")} }
                     div { class: "flex gap-2",
                         input {
                             r#type: "text",
@@ -249,11 +313,11 @@ pub fn HardwareSettings() -> Element {
                                     tracing::error!("Failed to open models directory: {}", error);
                                 }
                             },
-                            "Open"
+                            {t!(locale, "hardware.open")}
                         }
                     }
                     p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
-                        "Location where model files (.gguf) are stored."
+                        {t!(locale, "hardware.models_dir_hint")}
                     }
                 }
             }
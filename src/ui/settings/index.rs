@@ -0,0 +1,174 @@
+use crate::agent::language::Locale;
+use crate::agent::tools::index::set_document_index_settings;
+use crate::app::AppState;
+use crate::storage::index::DocumentIndex;
+use crate::storage::models::scan_models_directory;
+use crate::storage::settings::{save_settings, DocumentIndexSettings};
+use crate::ui::i18n::t;
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+/// Change the document index settings, then put them in use
+fn update_index_settings(app_state: &mut AppState, change: impl FnOnce(&mut DocumentIndexSettings)) {
+    let mut settings = app_state.settings.write();
+    change(&mut settings.document_index);
+    set_document_index_settings(settings.document_index.clone());
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// One line about the index of `folder`: size and last build, or not built yet
+fn index_summary(folder: &std::path::Path, locale: Locale) -> String {
+    match DocumentIndex::load(folder) {
+        Ok(Some(index)) => {
+            let updated = index.updated_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+            t!(locale, "index.summary", index.files.len(), index.chunk_count(), updated)
+        }
+        Ok(None) => t!(locale, "index.not_indexed").to_string(),
+        Err(e) => t!(locale, "settings.error", e),
+    }
+}
+
+pub fn IndexSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let locale = settings.locale();
+    let model_path = settings.document_index.embedding_model_path.clone().unwrap_or_default();
+    let folders = settings.document_index.folders.clone();
+    let models = use_signal({
+        let models_dir = settings.models_directory.clone();
+        move || scan_models_directory(&models_dir).unwrap_or_default()
+    });
+    let mut new_folder = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+    let mut app_state_model = app_state.clone();
+    let mut app_state_add = app_state.clone();
+
+    let help = t!(locale, "index.description");
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-2 text-[var(--text-primary)]",
+                    {t!(locale, "index.title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    "{help}"
+                }
+
+                // Embedding model
+                div { class: "mb-6",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", r#for: "embedding-model",
+                        {t!(locale, "index.embedding_model")}
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-0.5 mb-2",
+                        {t!(locale, "index.embedding_model_hint")}
+                    }
+                    select {
+                        id: "embedding-model",
+                        class: "w-full px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        value: "{model_path}",
+                        onchange: move |e: Event<FormData>| {
+                            let value = e.value();
+                            update_index_settings(&mut app_state_model, |index| {
+                                index.embedding_model_path = (!value.is_empty()).then_some(value);
+                            });
+                        },
+                        option { value: "", {t!(locale, "index.none")} }
+                        for model in models.read().iter() {
+                            option {
+                                key: "{model.path.display()}",
+                                value: "{model.path.display()}",
+                                selected: model.path.to_string_lossy() == model_path.as_str(),
+                                "{model.filename}"
+                            }
+                        }
+                    }
+                }
+
+                // Indexed folders
+                label { class: "text-sm font-medium text-[var(--text-primary)] mb-2 block",
+                    {t!(locale, "index.folders")}
+                }
+                if folders.is_empty() {
+                    p {
+                        class: "text-sm text-[var(--text-secondary)] mb-4",
+                        {t!(locale, "index.no_folders")}
+                    }
+                }
+                div { class: "space-y-2 mb-5",
+                    for folder in folders {
+                        {
+                            let summary = index_summary(&folder, locale);
+                            let removed = folder.clone();
+                            let mut app_state_remove = app_state.clone();
+                            rsx! {
+                                div {
+                                    key: "{folder.display()}",
+                                    class: "flex items-center justify-between gap-3 px-3 py-2 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)]",
+                                    div { class: "min-w-0",
+                                        p { class: "text-sm font-mono text-[var(--text-primary)] truncate", "{folder.display()}" }
+                                        p { class: "text-xs text-[var(--text-tertiary)]", "{summary}" }
+                                    }
+                                    button {
+                                        onclick: move |_| {
+                                            let removed = removed.clone();
+                                            update_index_settings(&mut app_state_remove, |index| {
+                                                index.folders.retain(|f| *f != removed);
+                                            });
+                                        },
+                                        class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                                        {t!(locale, "index.remove")}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "flex gap-2",
+                    input {
+                        r#type: "text",
+                        value: "{new_folder}",
+                        placeholder: t!(locale, "index.folder_placeholder"),
+                        oninput: move |e| new_folder.set(e.value()),
+                        class: "flex-1 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm font-mono",
+                    }
+                    button {
+                        onclick: move |_| {
+                            let folder = PathBuf::from(new_folder().trim());
+                            let Ok(folder) = folder.canonicalize() else {
+                                status.set(Some(t!(locale, "index.folder_missing").to_string()));
+                                return;
+                            };
+                            if !folder.is_dir() {
+                                status.set(Some(t!(locale, "index.not_a_folder").to_string()));
+                                return;
+                            }
+                            update_index_settings(&mut app_state_add, |index| {
+                                if !index.folders.contains(&folder) {
+                                    index.folders.push(folder);
+                                }
+                            });
+                            new_folder.set(String::new());
+                            status.set(None);
+                        },
+                        class: "px-3 py-1.5 text-xs font-semibold rounded-lg transition-all hover:scale-[1.02]",
+                        style: "background: var(--accent-primary); color: #F2EDE7;",
+                        {t!(locale, "index.add")}
+                    }
+                }
+                if let Some(status) = status() {
+                    p { class: "text-xs text-[var(--text-secondary)] mt-3", "{status}" }
+                }
+            }
+        }
+    }
+}
@@ -1,40 +1,71 @@
 use crate::agent::{ExaSearchConfig, ExaSearchTool};
-use crate::app::AppState;
-use crate::storage::settings::save_settings;
+use crate::app::{AppState, ModelSource, ModelState};
+use crate::storage::settings::{save_settings, GarbageSensitivity};
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
 use std::sync::Arc;
 
 pub fn InferenceSettings() -> Element {
     let app_state = use_context::<AppState>();
     let settings = app_state.settings.read().clone();
+    let locale = settings.locale();
     let temperature = settings.temperature;
     let top_p = settings.top_p;
     let top_k = settings.top_k;
+    let frequency_penalty = settings.frequency_penalty;
+    let presence_penalty = settings.presence_penalty;
+    // Kept as typed, so blank lines survive until the next one is written
+    let mut stop_sequences = use_signal(|| {
+        settings.stop_sequences.iter().map(|stop| stop.replace('\n', "\\n")).collect::<Vec<_>>().join("\n")
+    });
     let max_tokens = settings.max_tokens;
     let context_size = settings.context_size;
+    let auto_context = settings.auto_context;
+    let context_value = if auto_context { "auto".to_string() } else { context_size.to_string() };
+    let garbage_detection = settings.garbage_detection;
     let system_prompt = settings.system_prompt.clone();
     let exa_mcp_url = settings.exa_mcp_url.clone();
     let mut app_state_temperature = app_state.clone();
     let mut app_state_top_p = app_state.clone();
     let mut app_state_top_k = app_state.clone();
+    let mut app_state_frequency = app_state.clone();
+    let mut app_state_presence = app_state.clone();
+    let mut app_state_stops = app_state.clone();
     let mut app_state_max_tokens = app_state.clone();
     let mut app_state_context_size = app_state.clone();
+    let mut app_state_garbage = app_state.clone();
     let mut app_state_system_prompt = app_state.clone();
     let mut app_state_exa_mcp_url = app_state.clone();
+    let remote = settings.remote_backend.clone();
+    let remote_connected = matches!(&*app_state.model_state.read(), ModelState::Loaded(ModelSource::Remote { .. }));
+    // Pinned size above what the loaded model was trained for
+    let context_over_trained = match &*app_state.model_state.read() {
+        ModelState::Loaded(source) => source.trained_context().filter(|&trained| !auto_context && context_size > trained),
+        _ => None,
+    };
+    let mut app_state_remote_url = app_state.clone();
+    let mut app_state_remote_key = app_state.clone();
+    let mut app_state_remote_model = app_state.clone();
+    let app_state_remote_connect = app_state.clone();
+    let api_server = settings.api_server.clone();
+    let api_server_addr = app_state.api_server.read().as_ref().map(|server| server.addr().to_string());
+    let mut app_state_api_toggle = app_state.clone();
+    let mut app_state_api_bind = app_state.clone();
+    let app_state_api_apply = app_state.clone();
 
     rsx! {
         div {
             class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
 
             // Section: Generation Parameters — glass
-            SettingsCard { title: "Generation Parameters",
+            SettingsCard { title: t!(locale, "inference.generation"),
                 SettingsSlider {
-                    label: "Temperature",
+                    label: t!(locale, "inference.temperature"),
                     value: temperature,
                     min: 0.0,
                     max: 2.0,
                     step: 0.1,
-                    description: "Controls randomness. Higher values make output more random.",
+                    description: t!(locale, "inference.temperature_hint"),
                     on_change: move |value| {
                         let mut settings = app_state_temperature.settings.write();
                         settings.temperature = value;
@@ -50,7 +81,7 @@ pub fn InferenceSettings() -> Element {
                     min: 0.0,
                     max: 1.0,
                     step: 0.05,
-                    description: "Nucleus sampling threshold.",
+                    description: t!(locale, "inference.top_p_hint"),
                     on_change: move |value| {
                         let mut settings = app_state_top_p.settings.write();
                         settings.top_p = value;
@@ -65,7 +96,7 @@ pub fn InferenceSettings() -> Element {
                     value: top_k as f64,
                     min: 0.0,
                     max: 100.0,
-                    description: "Limits token selection to K most likely tokens.",
+                    description: t!(locale, "inference.top_k_hint"),
                     on_change: move |value: f64| {
                         let mut settings = app_state_top_k.settings.write();
                         let clamped = value.clamp(0.0, 100.0).round() as u32;
@@ -75,16 +106,69 @@ pub fn InferenceSettings() -> Element {
                         }
                     }
                 }
+
+                SettingsSlider {
+                    label: t!(locale, "inference.frequency_penalty"),
+                    value: frequency_penalty,
+                    min: -2.0,
+                    max: 2.0,
+                    step: 0.1,
+                    description: t!(locale, "inference.frequency_penalty_hint"),
+                    on_change: move |value| {
+                        let mut settings = app_state_frequency.settings.write();
+                        settings.frequency_penalty = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsSlider {
+                    label: t!(locale, "inference.presence_penalty"),
+                    value: presence_penalty,
+                    min: -2.0,
+                    max: 2.0,
+                    step: 0.1,
+                    description: t!(locale, "inference.presence_penalty_hint"),
+                    on_change: move |value| {
+                        let mut settings = app_state_presence.settings.write();
+                        settings.presence_penalty = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                // Stop sequences, one per line
+                div { class: "space-y-2",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "inference.stop_sequences")} }
+                    textarea {
+                        value: "{stop_sequences}",
+                        oninput: move |e| {
+                            stop_sequences.set(e.value());
+                            let mut settings = app_state_stops.settings.write();
+                            settings.stop_sequences = parse_stop_sequences(&e.value());
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] font-mono focus:border-[var(--accent-primary)] transition-all outline-none text-sm h-20 resize-y",
+                        placeholder: "###\nUser:",
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)]",
+                        {t!(locale, "inference.stop_sequences_hint")}
+                    }
+                }
             }
 
             // Section: Model Configuration — glass
-            SettingsCard { title: "Model Configuration",
+            SettingsCard { title: t!(locale, "inference.model"),
                 SettingsNumber {
-                    label: "Max Tokens (Output)",
+                    label: t!(locale, "inference.max_tokens"),
                     value: max_tokens as f64,
                     min: 256.0,
                     max: 16384.0,
-                    description: "Tokens a generer. Plus petit = plus rapide. (Defaut: 4096)",
+                    description: t!(locale, "inference.max_tokens_hint"),
                     on_change: move |value: f64| {
                         let mut settings = app_state_max_tokens.settings.write();
                         settings.max_tokens = (value as u32).clamp(256, 16384);
@@ -97,35 +181,83 @@ pub fn InferenceSettings() -> Element {
                 // Context Size
                 div { class: "mb-6",
                     div { class: "flex justify-between items-center mb-2",
-                        label { class: "text-sm font-medium text-[var(--text-primary)]", "Context Window" }
+                        label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "inference.context")} }
                         span {
                             class: "text-xs px-2 py-0.5 rounded-md bg-[var(--bg-success-subtle)] text-[var(--text-success)] border border-[var(--border-success-subtle)]",
-                            if context_size <= 8192 { "Rapide" } else if context_size <= 16384 { "Equilibre" } else { "Lent" }
+                            if auto_context {
+                                {t!(locale, "inference.context_auto")}
+                            } else if context_size <= 8192 {
+                                {t!(locale, "inference.context_fast")}
+                            } else if context_size <= 16384 {
+                                {t!(locale, "inference.context_balanced")}
+                            } else {
+                                {t!(locale, "inference.context_slow")}
+                            }
                         }
                     }
                     select {
-                        value: "{context_size}",
+                        value: "{context_value}",
                         onchange: move |e| {
-                            let value = e.value().parse().unwrap_or(8192);
+                            let value = e.value();
                             let mut settings = app_state_context_size.settings.write();
-                            settings.context_size = value;
+                            settings.auto_context = value == "auto";
+                            if let Ok(size) = value.parse() {
+                                settings.context_size = size;
+                            }
                             if let Err(error) = save_settings(&settings) {
                                 tracing::error!("Failed to save settings: {}", error);
                             }
                         },
                         class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm appearance-none cursor-pointer",
-                        option { value: "2048", "2K - Ultra rapide" }
-                        option { value: "4096", "4K - Rapide" }
-                        option { value: "8192", "8K - Recommande" }
-                        option { value: "16384", "16K - Equilibre" }
-                        option { value: "32768", "32K - Long contexte" }
+                        option { value: "auto", {t!(locale, "inference.context_option_auto")} }
+                        option { value: "2048", {t!(locale, "inference.context_option_2k")} }
+                        option { value: "4096", {t!(locale, "inference.context_option_4k")} }
+                        option { value: "8192", {t!(locale, "inference.context_option_8k")} }
+                        option { value: "16384", {t!(locale, "inference.context_option_16k")} }
+                        option { value: "32768", {t!(locale, "inference.context_option_32k")} }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if auto_context {
+                            {t!(locale, "inference.context_auto_hint")}
+                        } else {
+                            {t!(locale, "inference.context_hint")}
+                        }
+                    }
+                    if let Some(trained) = context_over_trained {
+                        p { class: "text-xs text-[var(--warning)] mt-1.5",
+                            {t!(locale, "inference.context_over_trained", trained / 1024)}
+                        }
+                    }
+                }
+
+                // Garbage detection
+                div { class: "mb-6",
+                    label { class: "block text-sm font-medium text-[var(--text-primary)] mb-2", {t!(locale, "inference.garbage")} }
+                    select {
+                        value: "{garbage_detection.as_str()}",
+                        onchange: move |e| {
+                            let Some(sensitivity) = GarbageSensitivity::parse(&e.value()) else {
+                                return;
+                            };
+                            let mut settings = app_state_garbage.settings.write();
+                            settings.garbage_detection = sensitivity;
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm appearance-none cursor-pointer",
+                        option { value: "off", {t!(locale, "inference.garbage_off")} }
+                        option { value: "low", {t!(locale, "inference.garbage_low")} }
+                        option { value: "strict", {t!(locale, "inference.garbage_strict")} }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        {t!(locale, "inference.garbage_hint")}
                     }
-                    p { class: "text-xs text-[var(--text-tertiary)] mt-1.5", "Taille du contexte. Plus petit = beaucoup plus rapide." }
                 }
 
                 // System Prompt Textarea
                 div { class: "space-y-2",
-                    label { class: "text-sm font-medium text-[var(--text-primary)]", "System Prompt" }
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "inference.system_prompt")} }
                     textarea {
                         value: "{system_prompt}",
                         oninput: move |e| {
@@ -137,14 +269,14 @@ pub fn InferenceSettings() -> Element {
                             }
                         },
                         class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm h-28 resize-y",
-                        placeholder: "Enter system prompt..."
+                        placeholder: t!(locale, "inference.system_prompt_placeholder")
                     }
-                    p { class: "text-xs text-[var(--text-tertiary)]", "Initial instructions for the model's behavior." }
+                    p { class: "text-xs text-[var(--text-tertiary)]", {t!(locale, "inference.system_prompt_hint")} }
                 }
             }
 
             // Section: Web Search (Exa MCP) — glass
-            SettingsCard { title: "Web Search",
+            SettingsCard { title: t!(locale, "inference.web_search"),
                 div { class: "space-y-2",
                     label { class: "text-sm font-medium text-[var(--text-primary)]", "Exa MCP URL" }
                     input {
@@ -176,7 +308,138 @@ pub fn InferenceSettings() -> Element {
                         class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
                     }
                     p { class: "text-xs text-[var(--text-tertiary)]",
-                        "Pas besoin de cle. Tu peux ajouter ?exaApiKey=... en cas de rate limit."
+                        {t!(locale, "inference.exa_hint")}
+                    }
+                }
+            }
+
+            // Section: Remote server (OpenAI-compatible) — glass
+            SettingsCard { title: t!(locale, "inference.remote"),
+                div { class: "space-y-2",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", "Base URL" }
+                    input {
+                        r#type: "text",
+                        value: "{remote.base_url}",
+                        oninput: move |e| {
+                            let mut settings = app_state_remote_url.settings.write();
+                            settings.remote_backend.base_url = e.value();
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        placeholder: "http://localhost:11434/v1",
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                    }
+                }
+                div { class: "space-y-2 mt-4",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "inference.remote_api_key")} }
+                    input {
+                        r#type: "password",
+                        value: "{remote.api_key}",
+                        oninput: move |e| {
+                            let mut settings = app_state_remote_key.settings.write();
+                            settings.remote_backend.api_key = e.value();
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        placeholder: t!(locale, "inference.remote_api_key_placeholder"),
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                    }
+                }
+                div { class: "space-y-2 mt-4",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "inference.remote_model")} }
+                    input {
+                        r#type: "text",
+                        value: "{remote.model}",
+                        oninput: move |e| {
+                            let mut settings = app_state_remote_model.settings.write();
+                            settings.remote_backend.model = e.value();
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        placeholder: "qwen3:14b",
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] font-mono focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                    }
+                }
+                div { class: "flex items-center gap-3 mt-4",
+                    button {
+                        onclick: move |_| app_state_remote_connect.connect_remote_backend(),
+                        disabled: remote.base_url.trim().is_empty() || remote.model.trim().is_empty(),
+                        class: "px-3 py-1.5 text-xs font-semibold rounded-lg transition-all hover:scale-[1.02] disabled:opacity-50",
+                        style: "background: var(--accent-primary); color: #F2EDE7;",
+                        if remote_connected {
+                            {t!(locale, "inference.remote_reconnect")}
+                        } else {
+                            {t!(locale, "inference.remote_connect")}
+                        }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)]",
+                        if remote_connected {
+                            {t!(locale, "inference.remote_connected_hint")}
+                        } else {
+                            {t!(locale, "inference.remote_hint")}
+                        }
+                    }
+                }
+            }
+
+            // Section: Local API server — glass
+            SettingsCard { title: t!(locale, "inference.api_server"),
+                div { class: "flex items-center justify-between mb-4",
+                    div {
+                        label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "inference.api_server_enable")} }
+                        p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            {t!(locale, "inference.api_server_hint")}
+                        }
+                    }
+                    button {
+                        class: if api_server.enabled { "toggle-switch active" } else { "toggle-switch" },
+                        onclick: move |_| {
+                            {
+                                let mut settings = app_state_api_toggle.settings.write();
+                                settings.api_server.enabled = !settings.api_server.enabled;
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            }
+                            app_state_api_toggle.apply_api_server_settings();
+                        },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+                div { class: "space-y-2",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]", {t!(locale, "inference.api_server_bind")} }
+                    div { class: "flex gap-2",
+                        input {
+                            r#type: "text",
+                            value: "{api_server.bind_address}",
+                            oninput: move |e| {
+                                let mut settings = app_state_api_bind.settings.write();
+                                settings.api_server.bind_address = e.value();
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                            placeholder: "127.0.0.1:8765",
+                            class: "flex-1 py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] font-mono focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                        }
+                        button {
+                            onclick: move |_| app_state_api_apply.apply_api_server_settings(),
+                            disabled: !api_server.enabled,
+                            class: "px-3 py-1.5 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all disabled:opacity-50",
+                            {t!(locale, "inference.api_server_apply")}
+                        }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)]",
+                        if let Some(addr) = api_server_addr.as_ref() {
+                            {t!(locale, "inference.api_server_listening", addr)}
+                        } else if api_server.enabled {
+                            {t!(locale, "inference.api_server_down")}
+                        } else {
+                            {t!(locale, "inference.api_server_stopped")}
+                        }
                     }
                 }
             }
@@ -200,6 +463,15 @@ fn SettingsCard(title: &'static str, children: Element) -> Element {
     }
 }
 
+/// Stop sequences typed one per line, with `\n` standing for a newline
+fn parse_stop_sequences(input: &str) -> Vec<String> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.replace("\\n", "\n"))
+        .collect()
+}
+
 #[component]
 fn SettingsSlider(
     label: &'static str,
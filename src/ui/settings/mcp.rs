@@ -5,12 +5,13 @@ use crate::agent::skills::loader::SkillLoader;
 use crate::app::AppState;
 use crate::storage::settings::save_settings;
 use crate::storage::get_data_dir;
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
 
 pub fn McpSettings() -> Element {
     let app_state = use_context::<AppState>();
     let settings = app_state.settings.read().clone();
-    let is_en = settings.language == "en";
+    let locale = settings.locale();
     let disabled_servers = settings.disabled_mcp_servers.clone();
 
     // Load MCP servers
@@ -34,7 +35,7 @@ pub fn McpSettings() -> Element {
                 class: "flex items-center justify-between",
                 h2 {
                     class: "text-lg font-semibold text-[var(--text-primary)]",
-                    if is_en { "MCP Configuration" } else { "Configuration MCP" }
+                    {t!(locale, "mcp.title")}
                 }
                 button {
                     class: "px-3 py-1.5 rounded-lg bg-white/[0.05] hover:bg-white/[0.1] text-sm text-[var(--text-secondary)] transition-colors border border-[var(--border-subtle)]",
@@ -56,7 +57,7 @@ pub fn McpSettings() -> Element {
                             }
                         });
                     },
-                    if is_en { "Edit mcp.json" } else { "Editer mcp.json" }
+                    {t!(locale, "mcp.edit_config")}
                 }
             }
 
@@ -64,14 +65,14 @@ pub fn McpSettings() -> Element {
             div { class: "p-5 rounded-2xl glass-md",
                 h3 { 
                     class: "text-base font-semibold mb-4 text-[var(--text-primary)]",
-                    if is_en { "MCP Servers" } else { "Serveurs MCP" }
+                    {t!(locale, "mcp.servers")}
                 }
 
                 if let Some(servers) = mcp_servers.read().as_ref() {
                     if servers.is_empty() {
                         div { 
                             class: "text-sm text-[var(--text-tertiary)] italic",
-                            if is_en { "No MCP servers configured." } else { "Aucun serveur MCP configure." }
+                            {t!(locale, "mcp.no_servers")}
                         }
                     } else {
                         div { class: "space-y-3",
@@ -126,14 +127,14 @@ pub fn McpSettings() -> Element {
             div { class: "p-5 rounded-2xl glass-md",
                 h3 { 
                     class: "text-base font-semibold mb-4 text-[var(--text-primary)]",
-                    if is_en { "Skills" } else { "Comptences (Skills)" }
+                    {t!(locale, "mcp.skills")}
                 }
 
                 if let Some(loaded_skills) = skills.read().as_ref() {
                     if loaded_skills.is_empty() {
                         div { 
                             class: "text-sm text-[var(--text-tertiary)] italic",
-                            if is_en { "No skills loaded." } else { "Aucune competence chargee." }
+                            {t!(locale, "mcp.no_skills")}
                         }
                     } else {
                         div { class: "grid grid-cols-1 md:grid-cols-2 gap-3",
@@ -0,0 +1,224 @@
+use crate::app::AppState;
+use crate::storage::memory::{load_memory, update_memory, MemoryNote, MAX_TOTAL_CHARS};
+use crate::storage::settings::save_settings;
+use crate::ui::i18n::t;
+use dioxus::prelude::*;
+
+/// Change the memory settings and save them
+fn update_memory_settings(app_state: &mut AppState, change: impl FnOnce(&mut crate::storage::settings::MemorySettings)) {
+    let mut settings = app_state.settings.write();
+    change(&mut settings.memory);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Saved notes, most recently used first
+fn sorted_notes() -> Vec<MemoryNote> {
+    match load_memory() {
+        Ok(store) => {
+            let mut notes = store.notes;
+            notes.sort_by_key(|n| std::cmp::Reverse(n.last_used_at));
+            notes
+        }
+        Err(e) => {
+            tracing::error!("Failed to load memory: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn parse_topics(text: &str) -> Vec<String> {
+    text.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()
+}
+
+pub fn MemorySettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let locale = settings.locale();
+    let include_in_prompt = settings.memory.include_in_prompt;
+    let prompt_notes = settings.memory.prompt_notes;
+    let mut notes = use_signal(sorted_notes);
+    // Note being edited: id, content, comma-separated topics
+    let mut editing = use_signal(|| None::<(String, String, String)>);
+    let mut status = use_signal(|| None::<String>);
+    let mut app_state_toggle = app_state.clone();
+    let mut app_state_count = app_state.clone();
+
+    let (draft_content, draft_topics) = editing().map(|(_, content, topics)| (content, topics)).unwrap_or_default();
+
+    let total: usize = notes.read().iter().map(MemoryNote::size).sum();
+    let usage = t!(locale, "memory.usage", notes.read().len(), total, MAX_TOTAL_CHARS);
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-2 text-[var(--text-primary)]",
+                    {t!(locale, "memory.title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "memory.description")}
+                }
+
+                div {
+                    class: "flex items-center justify-between mb-4",
+                    div {
+                        span { class: "text-sm text-[var(--text-primary)]",
+                            {t!(locale, "memory.recall")}
+                        }
+                        p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            {t!(locale, "memory.recall_hint")}
+                        }
+                    }
+                    button {
+                        onclick: move |_| update_memory_settings(&mut app_state_toggle, |memory| {
+                            memory.include_in_prompt = !memory.include_in_prompt;
+                        }),
+                        class: if include_in_prompt { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+
+                div {
+                    class: "flex items-center justify-between",
+                    label { class: "text-sm text-[var(--text-primary)]", r#for: "memory-prompt-notes",
+                        {t!(locale, "memory.recall_count")}
+                    }
+                    input {
+                        id: "memory-prompt-notes",
+                        r#type: "number",
+                        min: "1",
+                        max: "20",
+                        value: "{prompt_notes}",
+                        disabled: !include_in_prompt,
+                        onchange: move |e: Event<FormData>| {
+                            if let Ok(count) = e.value().parse::<usize>() {
+                                update_memory_settings(&mut app_state_count, |memory| {
+                                    memory.prompt_notes = count.clamp(1, 20);
+                                });
+                            }
+                        },
+                        class: "w-20 py-1.5 px-2 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm outline-none focus:border-[var(--accent-primary)]",
+                    }
+                }
+            }
+
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "memory.saved_notes")}
+                }
+                p { class: "text-xs text-[var(--text-tertiary)] mb-4", "{usage}" }
+
+                if notes.read().is_empty() {
+                    p {
+                        class: "text-sm text-[var(--text-secondary)]",
+                        {t!(locale, "memory.empty")}
+                    }
+                }
+                div { class: "space-y-2",
+                    for note in notes.read().clone() {
+                        {
+                            let id = note.id.clone();
+                            let edit_id = note.id.clone();
+                            let edit_content = note.content.clone();
+                            let edit_topics = note.topics.join(", ");
+                            let updated = note.updated_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+                            let topics = note.topics.join(", ");
+                            let is_editing = editing.read().as_ref().is_some_and(|(editing_id, _, _)| *editing_id == note.id);
+                            rsx! {
+                                div {
+                                    key: "{note.id}",
+                                    class: "px-3 py-2 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)]",
+                                    if is_editing {
+                                        textarea {
+                                            rows: "3",
+                                            value: "{draft_content}",
+                                            oninput: move |e| {
+                                                if let Some(edit) = editing.write().as_mut() {
+                                                    edit.1 = e.value();
+                                                }
+                                            },
+                                            class: "w-full py-2 px-3 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm outline-none focus:border-[var(--accent-primary)] mb-2",
+                                        }
+                                        input {
+                                            r#type: "text",
+                                            value: "{draft_topics}",
+                                            placeholder: t!(locale, "memory.topics"),
+                                            oninput: move |e| {
+                                                if let Some(edit) = editing.write().as_mut() {
+                                                    edit.2 = e.value();
+                                                }
+                                            },
+                                            class: "w-full py-1.5 px-3 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-xs font-mono outline-none focus:border-[var(--accent-primary)] mb-2",
+                                        }
+                                        div { class: "flex gap-2 justify-end",
+                                            button {
+                                                onclick: move |_| editing.set(None),
+                                                class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                                                {t!(locale, "memory.cancel")}
+                                            }
+                                            button {
+                                                onclick: move |_| {
+                                                    let Some((id, content, topics)) = editing() else { return };
+                                                    match update_memory(|store| store.write(Some(&id), &content, &parse_topics(&topics))) {
+                                                        Ok(Ok(_)) => {
+                                                            editing.set(None);
+                                                            status.set(None);
+                                                        }
+                                                        Ok(Err(e)) | Err(e) => status.set(Some(e.to_string())),
+                                                    }
+                                                    notes.set(sorted_notes());
+                                                },
+                                                class: "px-3 py-1 text-xs font-semibold rounded-lg transition-all hover:scale-[1.02]",
+                                                style: "background: var(--accent-primary); color: #F2EDE7;",
+                                                {t!(locale, "memory.save")}
+                                            }
+                                        }
+                                    } else {
+                                        div { class: "flex items-start justify-between gap-3",
+                                            div { class: "min-w-0",
+                                                p { class: "text-sm text-[var(--text-primary)] whitespace-pre-wrap break-words", "{note.content}" }
+                                                p { class: "text-xs text-[var(--text-tertiary)] mt-1",
+                                                    if topics.is_empty() { "{updated}" } else { "{topics} · {updated}" }
+                                                }
+                                            }
+                                            div { class: "flex gap-2 flex-none",
+                                                button {
+                                                    onclick: move |_| editing.set(Some((edit_id.clone(), edit_content.clone(), edit_topics.clone()))),
+                                                    class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                                                    {t!(locale, "memory.edit")}
+                                                }
+                                                button {
+                                                    onclick: move |_| {
+                                                        if let Err(e) = update_memory(|store| store.delete(&id)) {
+                                                            status.set(Some(e.to_string()));
+                                                        }
+                                                        notes.set(sorted_notes());
+                                                    },
+                                                    class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                                                    {t!(locale, "memory.delete")}
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(status) = status() {
+                    p { class: "text-xs text-[var(--text-secondary)] mt-3", "{status}" }
+                }
+            }
+        }
+    }
+}
@@ -1,35 +1,57 @@
 #![allow(non_snake_case)]
 
 pub mod appearance;
+pub mod diagnostics;
 pub mod hardware;
+pub mod index;
 pub mod inference;
+pub mod memory;
 pub mod tools;
 pub mod skills;
 pub mod mcp;
+pub mod models;
+pub mod secrets;
+pub mod templates;
+pub mod voice;
 
 use crate::app::AppState;
+use crate::ui::i18n::t;
 use crate::ui::settings::appearance::AppearanceSettings;
+use crate::ui::settings::diagnostics::DiagnosticsSettings;
 use crate::ui::settings::hardware::HardwareSettings;
+use crate::ui::settings::index::IndexSettings;
 use crate::ui::settings::inference::InferenceSettings;
+use crate::ui::settings::memory::MemorySettings;
 use crate::ui::settings::tools::ToolsSettings;
 use crate::ui::settings::skills::SkillsSettings;
 use crate::ui::settings::mcp::McpSettings;
+use crate::ui::settings::models::ModelsSettings;
+use crate::ui::settings::secrets::SecretsSettings;
+use crate::ui::settings::templates::TemplatesSettings;
+use crate::ui::settings::voice::VoiceSettings;
 use dioxus::prelude::*;
 
 #[derive(PartialEq, Clone, Copy)]
 enum SettingsTab {
     Inference,
     Hardware,
+    Models,
     Tools,
+    Index,
+    Memory,
+    Voice,
     Skills,
     Mcp,
+    Secrets,
+    Templates,
     Appearance,
+    Diagnostics,
 }
 
 pub fn Settings() -> Element {
     let mut active_tab = use_signal(|| SettingsTab::Inference);
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
 
     rsx! {
         div {
@@ -50,32 +72,67 @@ pub fn Settings() -> Element {
                         TabButton {
                             active: active_tab() == SettingsTab::Inference,
                             onclick: move |_| active_tab.set(SettingsTab::Inference),
-                            label: if is_en { "Inference" } else { "Inference" },
+                            label: t!(locale, "settings.inference"),
                         }
                         TabButton {
                             active: active_tab() == SettingsTab::Hardware,
                             onclick: move |_| active_tab.set(SettingsTab::Hardware),
-                            label: if is_en { "Hardware" } else { "Materiel" },
+                            label: t!(locale, "settings.hardware"),
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Models,
+                            onclick: move |_| active_tab.set(SettingsTab::Models),
+                            label: t!(locale, "settings.models"),
                         }
                         TabButton {
                             active: active_tab() == SettingsTab::Tools,
                             onclick: move |_| active_tab.set(SettingsTab::Tools),
-                            label: if is_en { "Tools" } else { "Outils" },
+                            label: t!(locale, "settings.tools"),
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Index,
+                            onclick: move |_| active_tab.set(SettingsTab::Index),
+                            label: t!(locale, "settings.index"),
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Memory,
+                            onclick: move |_| active_tab.set(SettingsTab::Memory),
+                            label: t!(locale, "settings.memory"),
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Voice,
+                            onclick: move |_| active_tab.set(SettingsTab::Voice),
+                            label: t!(locale, "settings.voice"),
                         }
                         TabButton {
                             active: active_tab() == SettingsTab::Skills,
                             onclick: move |_| active_tab.set(SettingsTab::Skills),
-                            label: "Skills",
+                            label: t!(locale, "settings.skills"),
                         }
                         TabButton {
                             active: active_tab() == SettingsTab::Mcp,
                             onclick: move |_| active_tab.set(SettingsTab::Mcp),
-                            label: "MCP",
+                            label: t!(locale, "settings.mcp"),
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Secrets,
+                            onclick: move |_| active_tab.set(SettingsTab::Secrets),
+                            label: t!(locale, "settings.secrets"),
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Templates,
+                            onclick: move |_| active_tab.set(SettingsTab::Templates),
+                            label: t!(locale, "settings.templates"),
                         }
                         TabButton {
                             active: active_tab() == SettingsTab::Appearance,
                             onclick: move |_| active_tab.set(SettingsTab::Appearance),
-                            label: if is_en { "Appearance" } else { "Apparence" },
+                            label: t!(locale, "settings.appearance"),
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Diagnostics,
+                            onclick: move |_| active_tab.set(SettingsTab::Diagnostics),
+                            label: t!(locale, "settings.diagnostics"),
                         }
                     }
                 }
@@ -87,10 +144,17 @@ pub fn Settings() -> Element {
                 match active_tab() {
                     SettingsTab::Inference => rsx! { InferenceSettings {} },
                     SettingsTab::Hardware => rsx! { HardwareSettings {} },
+                    SettingsTab::Models => rsx! { ModelsSettings {} },
                     SettingsTab::Tools => rsx! { ToolsSettings {} },
+                    SettingsTab::Index => rsx! { IndexSettings {} },
+                    SettingsTab::Memory => rsx! { MemorySettings {} },
+                    SettingsTab::Voice => rsx! { VoiceSettings {} },
                     SettingsTab::Skills => rsx! { SkillsSettings {} },
                     SettingsTab::Mcp => rsx! { McpSettings {} },
+                    SettingsTab::Secrets => rsx! { SecretsSettings {} },
+                    SettingsTab::Templates => rsx! { TemplatesSettings {} },
                     SettingsTab::Appearance => rsx! { AppearanceSettings {} },
+                    SettingsTab::Diagnostics => rsx! { DiagnosticsSettings {} },
                 }
             }
         }
@@ -0,0 +1,254 @@
+use crate::app::AppState;
+use crate::storage::huggingface::format_size;
+use crate::storage::models::{search_hf_models, DownloadHandle, DownloadStatus, HfModelFile, HfModelInfo, ModelDownloader};
+use crate::ui::i18n::t;
+use crate::ui::sidebar::model_picker::download_status_label;
+use dioxus::prelude::*;
+use std::sync::Arc;
+
+/// Downloads as "1.2M" / "34k"
+fn format_count(count: u64) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{:.1}k", count as f64 / 1_000.0),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
+}
+
+pub fn ModelsSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let mut query = use_signal(String::new);
+    let mut quantization = use_signal(String::new);
+    let mut results = use_signal(Vec::<HfModelInfo>::new);
+    let mut is_searching = use_signal(|| false);
+    let mut search_error = use_signal(|| None::<String>);
+    let mut download_status = use_signal(|| None::<DownloadStatus>);
+    let mut download_handle = use_signal(|| None::<Arc<DownloadHandle>>);
+    let mut download_name = use_signal(String::new);
+    let mut download_message = use_signal(|| None::<String>);
+
+    let mut run_search = move || {
+        let search = query.read().trim().to_string();
+        if search.is_empty() || *is_searching.read() {
+            return;
+        }
+        let filter = quantization.read().trim().to_string();
+        is_searching.set(true);
+        search_error.set(None);
+        spawn(async move {
+            match search_hf_models(&search, (!filter.is_empty()).then_some(filter.as_str())).await {
+                Ok(found) => results.set(found),
+                Err(e) => {
+                    tracing::error!("Model search failed: {}", e);
+                    search_error.set(Some(e));
+                }
+            }
+            is_searching.set(false);
+        });
+    };
+
+    // Signals are Copy, so the handler can be shared by every download button
+    let settings = app_state.settings;
+    let mut start_download = move |repo_id: String, file: HfModelFile| {
+        if download_handle.read().is_some() {
+            return;
+        }
+        let directory = settings.read().models_directory.clone();
+        let parts = match file.download_parts(&repo_id, &directory) {
+            Ok(parts) => parts,
+            Err(e) => {
+                download_message.set(Some(e));
+                return;
+            }
+        };
+        download_name.set(file.name().to_string());
+        download_message.set(None);
+        let handle = ModelDownloader::new().start_download_parts(parts);
+        let mut status_rx = handle.subscribe();
+        download_handle.set(Some(Arc::new(handle)));
+        spawn(async move {
+            let outcome = loop {
+                let status = status_rx.borrow_and_update().clone();
+                download_status.set(Some(status.clone()));
+                if status.is_done() {
+                    break status;
+                }
+                if status_rx.changed().await.is_err() {
+                    break status_rx.borrow().clone();
+                }
+            };
+            download_handle.set(None);
+            download_status.set(None);
+            let message = match outcome {
+                DownloadStatus::Finished(path) => {
+                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    t!(locale, "models.downloaded", name)
+                }
+                DownloadStatus::Failed(e) => t!(locale, "settings.error", e),
+                _ => t!(locale, "models.download_stopped").to_string(),
+            };
+            download_message.set(Some(message));
+        });
+    };
+
+    let download_snapshot = download_status.read().clone();
+    let download_percent = download_snapshot
+        .as_ref()
+        .and_then(|status| status.fraction())
+        .map(|fraction| (fraction * 100.0).round() as u32);
+    let download_label = download_status_label(download_snapshot.as_ref(), locale);
+    let downloading = download_handle.read().is_some();
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            // Search
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-2 text-[var(--text-primary)]",
+                    {t!(locale, "models.title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-4",
+                    {t!(locale, "models.description")}
+                }
+                div { class: "flex gap-2",
+                    input {
+                        r#type: "text",
+                        value: "{query}",
+                        oninput: move |e| query.set(e.value()),
+                        onkeydown: move |e| {
+                            if e.key() == Key::Enter {
+                                run_search();
+                            }
+                        },
+                        placeholder: "qwen, llama, mistral...",
+                        class: "flex-1 py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                    }
+                    input {
+                        r#type: "text",
+                        value: "{quantization}",
+                        oninput: move |e| quantization.set(e.value()),
+                        placeholder: "Q4_K_M",
+                        class: "w-28 py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] font-mono focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                    }
+                    button {
+                        onclick: move |_| run_search(),
+                        disabled: *is_searching.read(),
+                        class: "px-4 py-2 text-sm font-semibold rounded-xl transition-all hover:scale-[1.02] disabled:opacity-50",
+                        style: "background: var(--accent-primary); color: #F2EDE7;",
+                        if *is_searching.read() {
+                            {t!(locale, "models.searching")}
+                        } else {
+                            {t!(locale, "models.search")}
+                        }
+                    }
+                }
+                if let Some(error) = search_error.read().as_ref() {
+                    p { class: "mt-3 text-xs text-[var(--text-error)]", "{error}" }
+                }
+            }
+
+            // Current download
+            if downloading || download_message.read().is_some() {
+                div {
+                    class: "p-4 rounded-2xl glass-md flex flex-col gap-2",
+                    if downloading {
+                        div {
+                            class: "flex items-center gap-2",
+                            span { class: "flex-1 text-sm font-mono text-[var(--text-secondary)] truncate", "{download_name}" }
+                            button {
+                                onclick: move |_| {
+                                    if let Some(handle) = download_handle.read().as_ref() {
+                                        handle.cancel();
+                                    }
+                                },
+                                class: "text-[10px] font-medium text-[var(--text-tertiary)] hover:text-[var(--text-error)] px-2 py-0.5 rounded-md hover:bg-[var(--bg-error-subtle)] transition-colors",
+                                {t!(locale, "models.stop")}
+                            }
+                        }
+                        if let Some(percent) = download_percent {
+                            div {
+                                class: "progress-track",
+                                role: "progressbar",
+                                "aria-valuemin": "0",
+                                "aria-valuemax": "100",
+                                "aria-valuenow": "{percent}",
+                                div { class: "progress-fill", style: "width: {percent}%;" }
+                            }
+                        }
+                        span { class: "text-[10px] font-mono text-[var(--text-tertiary)]", "{download_label}" }
+                    }
+                    if let Some(message) = download_message.read().as_ref() {
+                        p { class: "text-xs text-[var(--text-secondary)]", "{message}" }
+                    }
+                }
+            }
+
+            // Results
+            if results.read().is_empty() && !*is_searching.read() && search_error.read().is_none() {
+                p {
+                    class: "text-sm text-center text-[var(--text-tertiary)]",
+                    {t!(locale, "models.no_results")}
+                }
+            }
+            div { class: "space-y-4",
+                for model in results.read().iter().cloned() {
+                    div {
+                        key: "{model.repo_id}",
+                        class: "p-4 rounded-xl glass-md border border-[var(--border-subtle)]",
+
+                        div {
+                            class: "flex items-baseline justify-between gap-3 mb-3",
+                            h4 { class: "font-mono text-sm font-semibold text-[var(--text-primary)] truncate", "{model.repo_id}" }
+                            div {
+                                class: "flex-none flex items-center gap-3 text-xs text-[var(--text-tertiary)]",
+                                span { title: t!(locale, "models.downloads"), "↓ {format_count(model.downloads)}" }
+                                span { "♥ {format_count(model.likes)}" }
+                                if let Some(license) = model.license.as_ref() {
+                                    span { class: "px-1.5 py-0.5 rounded-md bg-white/[0.04]", "{license}" }
+                                }
+                            }
+                        }
+
+                        div { class: "space-y-1",
+                            for file in model.files.iter().cloned() {
+                                {
+                                    let repo_id = model.repo_id.clone();
+                                    let quant = file.quantization.clone().unwrap_or_else(|| "?".to_string());
+                                    let size = format_size(file.size_bytes());
+                                    let parts = file.parts.len();
+                                    let name = file.name().to_string();
+                                    rsx! {
+                                        div {
+                                            key: "{name}",
+                                            class: "flex items-center gap-3 py-1.5 px-2 rounded-lg hover:bg-white/[0.02]",
+                                            span { class: "w-20 font-mono text-xs text-[var(--text-primary)]", "{quant}" }
+                                            span { class: "flex-1 font-mono text-[11px] text-[var(--text-tertiary)] truncate", title: "{name}", "{name}" }
+                                            if parts > 1 {
+                                                span { class: "text-[10px] text-[var(--text-tertiary)]",
+                                                    {t!(locale, "models.parts", parts)}
+                                                }
+                                            }
+                                            span { class: "w-20 text-right text-xs text-[var(--text-secondary)]", "{size}" }
+                                            button {
+                                                onclick: move |_| start_download(repo_id.clone(), file.clone()),
+                                                disabled: downloading,
+                                                class: "px-2.5 py-1 text-[11px] font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all disabled:opacity-40",
+                                                {t!(locale, "models.download")}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::app::AppState;
+use crate::storage::secrets::{self, is_valid_name, SecretStore};
+use crate::ui::i18n::t;
+use dioxus::prelude::*;
+
+/// Apply a change to the vault, then put the result in use
+fn update_vault(
+    change: impl FnOnce(&SecretStore) -> Result<std::collections::BTreeMap<String, String>, secrets::SecretError>,
+) -> Result<(), String> {
+    let store = SecretStore::open_default().map_err(|e| e.to_string())?;
+    let updated = change(&store).map_err(|e| e.to_string())?;
+    secrets::install(updated);
+    Ok(())
+}
+
+pub fn SecretsSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let mut revision = use_signal(|| 0u32);
+    let mut new_name = use_signal(String::new);
+    let mut new_value = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+
+    // Reading the signal re-renders the list after each change
+    let _ = revision();
+    let names = secrets::names();
+
+    let help = t!(locale, "secrets.help");
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-2 text-[var(--text-primary)]",
+                    "Secrets"
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    "{help}"
+                }
+
+                if names.is_empty() {
+                    p {
+                        class: "text-sm text-[var(--text-secondary)] mb-4",
+                        {t!(locale, "secrets.empty")}
+                    }
+                }
+
+                div { class: "space-y-2 mb-5",
+                    for name in names {
+                        {
+                            let removed = name.clone();
+                            rsx! {
+                                div {
+                                    key: "{name}",
+                                    class: "flex items-center justify-between px-3 py-2 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)]",
+                                    span { class: "text-sm font-mono text-[var(--text-primary)]", "${{secret:{name}}}" }
+                                    button {
+                                        onclick: move |_| {
+                                            let result = update_vault(|store| store.remove(&removed));
+                                            status.set(result.err().map(|e| t!(locale, "settings.error", e)));
+                                            *revision.write() += 1;
+                                        },
+                                        class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                                        {t!(locale, "secrets.remove")}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "flex gap-2",
+                    input {
+                        r#type: "text",
+                        value: "{new_name}",
+                        placeholder: t!(locale, "secrets.name"),
+                        oninput: move |e| new_name.set(e.value()),
+                        class: "w-40 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm font-mono",
+                    }
+                    input {
+                        r#type: "password",
+                        value: "{new_value}",
+                        placeholder: t!(locale, "secrets.value"),
+                        oninput: move |e| new_value.set(e.value()),
+                        class: "flex-1 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                    }
+                    button {
+                        onclick: move |_| {
+                            let name = new_name().trim().to_string();
+                            let value = new_value();
+                            if !is_valid_name(&name) || value.is_empty() {
+                                status.set(Some(t!(locale, "secrets.invalid").to_string()));
+                                return;
+                            }
+                            match update_vault(|store| store.set(&name, &value)) {
+                                Ok(()) => {
+                                    new_name.set(String::new());
+                                    new_value.set(String::new());
+                                    status.set(None);
+                                }
+                                Err(e) => status.set(Some(t!(locale, "settings.error", e))),
+                            }
+                            *revision.write() += 1;
+                        },
+                        class: "px-3 py-1.5 text-xs font-semibold rounded-lg transition-all hover:scale-[1.02]",
+                        style: "background: var(--accent-primary); color: #F2EDE7;",
+                        {t!(locale, "secrets.add")}
+                    }
+                }
+                if let Some(status) = status() {
+                    p { class: "text-xs text-[var(--text-secondary)] mt-3", "{status}" }
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use crate::agent::skills::loader::SkillLoader;
 use crate::app::AppState;
+use crate::ui::i18n::t;
 use dioxus::prelude::*;
 
 pub fn SkillsSettings() -> Element {
     let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
     
     // Use resource to load skills async
     let mut skills_resource = use_resource(move || async move {
@@ -21,7 +23,7 @@ pub fn SkillsSettings() -> Element {
                 class: "flex items-center justify-between",
                 h2 {
                     class: "text-lg font-semibold text-[var(--text-primary)]",
-                    { "Skills Manager" }
+                    {t!(locale, "skills.title")}
                 }
 
                 button {
@@ -30,7 +32,7 @@ pub fn SkillsSettings() -> Element {
                          // Redirect logic (placeholder)
                     },
                     span { "+" }
-                    { "New Skill" }
+                    {t!(locale, "skills.new")}
                 }
             }
 
@@ -41,7 +43,7 @@ pub fn SkillsSettings() -> Element {
                     Some(skills) if skills.is_empty() => rsx! {
                         div {
                             class: "p-8 text-center text-[var(--text-tertiary)] border border-dashed border-[var(--border-medium)] rounded-xl",
-                            "No skills installed yet."
+                            {t!(locale, "skills.empty")}
                         }
                     },
                     Some(skills) => rsx! {
@@ -65,7 +67,7 @@ pub fn SkillsSettings() -> Element {
 
                                         button {
                                             class: "p-2 text-[var(--text-tertiary)] hover:text-[#C45B5B] hover:bg-[#C45B5B]/10 rounded-lg transition-colors",
-                                            title: "Delete Skill",
+                                            title: t!(locale, "skills.delete"),
                                             onclick: {
                                                 let skill_name = skill.name.clone();
                                                 let skill_path = skill.path.clone();
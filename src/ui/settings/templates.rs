@@ -0,0 +1,280 @@
+use crate::app::AppState;
+use crate::storage::settings::{export_settings_bundle, import_settings_bundle, save_settings};
+use crate::storage::templates::ConversationTemplate;
+use crate::ui::i18n::t;
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+/// Default location for the exported settings bundle
+fn default_bundle_path() -> String {
+    let home = std::env::var("USERPROFILE")
+        .or_else(|_| std::env::var("HOME"))
+        .unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home)
+        .join("Downloads")
+        .join("clawrs-settings.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+pub fn TemplatesSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let templates = app_state.settings.read().conversation_templates.clone();
+    let mut bundle_path = use_signal(default_bundle_path);
+    let mut bundle_status = use_signal(|| None::<String>);
+
+    let help = t!(locale, "templates.help");
+
+    let mut app_state_add = app_state.clone();
+    let app_state_export = app_state.clone();
+    let mut app_state_import = app_state.clone();
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            // Templates list
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                div {
+                    class: "flex items-center justify-between mb-2",
+                    h3 {
+                        class: "text-base font-semibold text-[var(--text-primary)]",
+                        {t!(locale, "templates.title")}
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_add.settings.write();
+                            let name = t!(settings.locale(), "templates.new_template");
+                            settings.conversation_templates.push(ConversationTemplate::new(name));
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: "px-3 py-1.5 text-xs font-semibold rounded-lg transition-all hover:scale-[1.02]",
+                        style: "background: var(--accent-primary); color: #F2EDE7;",
+                        {t!(locale, "templates.add")}
+                    }
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    "{help}"
+                }
+
+                if templates.is_empty() {
+                    p {
+                        class: "text-sm text-[var(--text-secondary)]",
+                        {t!(locale, "templates.empty")}
+                    }
+                }
+
+                div { class: "space-y-4",
+                    for (index, template) in templates.into_iter().enumerate() {
+                        {
+                            let id = template.id.clone();
+                            rsx! { TemplateEditor { key: "{id}", index: index, template: template } }
+                        }
+                    }
+                }
+            }
+
+            // Settings bundle export / import
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-2 text-[var(--text-primary)]",
+                    {t!(locale, "templates.bundle")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-4",
+                    {t!(locale, "templates.bundle_hint")}
+                }
+                input {
+                    r#type: "text",
+                    value: "{bundle_path}",
+                    oninput: move |e| bundle_path.set(e.value()),
+                    class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm mb-3",
+                }
+                div { class: "flex gap-2",
+                    button {
+                        onclick: move |_| {
+                            let path = PathBuf::from(bundle_path());
+                            let settings = app_state_export.settings.read().clone();
+                            let status = match export_settings_bundle(&settings, &path) {
+                                Ok(()) => t!(locale, "templates.exported").to_string(),
+                                Err(e) => t!(locale, "settings.error", e),
+                            };
+                            bundle_status.set(Some(status));
+                        },
+                        class: "px-3 py-1.5 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                        {t!(locale, "templates.export")}
+                    }
+                    button {
+                        onclick: move |_| {
+                            let path = PathBuf::from(bundle_path());
+                            let current = app_state_import.settings.read().clone();
+                            let status = match import_settings_bundle(&current, &path) {
+                                Ok(imported) => {
+                                    if let Err(error) = save_settings(&imported) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                    app_state_import.settings.set(imported);
+                                    t!(locale, "templates.imported").to_string()
+                                }
+                                Err(e) => t!(locale, "settings.error", e),
+                            };
+                            bundle_status.set(Some(status));
+                        },
+                        class: "px-3 py-1.5 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                        {t!(locale, "templates.import")}
+                    }
+                }
+                if let Some(status) = bundle_status() {
+                    p { class: "text-xs text-[var(--text-secondary)] mt-3", "{status}" }
+                }
+            }
+        }
+    }
+}
+
+/// Editable card for a single template
+#[component]
+fn TemplateEditor(index: usize, template: ConversationTemplate) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+
+    // Apply a change to this template and persist
+    let update = {
+        let mut settings_signal = app_state.settings.clone();
+        move |f: &dyn Fn(&mut ConversationTemplate)| {
+            let mut settings = settings_signal.write();
+            if let Some(t) = settings.conversation_templates.get_mut(index) {
+                f(t);
+            }
+            if let Err(error) = save_settings(&settings) {
+                tracing::error!("Failed to save settings: {}", error);
+            }
+        }
+    };
+
+    let mut update_name = update.clone();
+    let mut update_title = update.clone();
+    let mut update_prompt = update.clone();
+    let mut update_message = update.clone();
+    let mut update_tools = update.clone();
+    let mut update_iterations = update.clone();
+    let mut settings_remove = app_state.settings.clone();
+
+    let system_prompt = template.overrides.system_prompt.clone().unwrap_or_default();
+    let first_message = template.first_message.clone().unwrap_or_default();
+    let tools_value = match template.overrides.enable_tools {
+        Some(true) => "on",
+        Some(false) => "off",
+        None => "default",
+    };
+    let iterations = template.overrides.max_iterations.map(|n| n.to_string()).unwrap_or_default();
+    let title_placeholder = t!(locale, "templates.title_placeholder");
+    let prompt_placeholder = t!(locale, "templates.prompt_placeholder");
+    let message_placeholder = t!(locale, "templates.message_placeholder");
+    let iterations_placeholder = t!(locale, "templates.iterations_placeholder");
+    let field_class = "w-full py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm";
+
+    rsx! {
+        div {
+            class: "p-4 rounded-xl border border-[var(--border-subtle)] space-y-3",
+
+            div { class: "flex gap-2",
+                input {
+                    r#type: "text",
+                    value: "{template.name}",
+                    placeholder: t!(locale, "templates.name"),
+                    oninput: move |e| {
+                        let value = e.value();
+                        update_name(&|t| t.name = value.clone());
+                    },
+                    class: "{field_class}",
+                }
+                button {
+                    onclick: move |_| {
+                        let mut settings = settings_remove.write();
+                        if index < settings.conversation_templates.len() {
+                            settings.conversation_templates.remove(index);
+                        }
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    },
+                    class: "px-3 text-xs rounded-lg text-[var(--error)] hover:bg-white/[0.04] transition-all",
+                    {t!(locale, "templates.delete")}
+                }
+            }
+
+            input {
+                r#type: "text",
+                value: "{template.title_pattern}",
+                placeholder: "{title_placeholder}",
+                oninput: move |e| {
+                    let value = e.value();
+                    update_title(&|t| t.title_pattern = value.clone());
+                },
+                class: "{field_class}",
+            }
+
+            textarea {
+                value: "{system_prompt}",
+                placeholder: "{prompt_placeholder}",
+                oninput: move |e| {
+                    let value = e.value();
+                    update_prompt(&|t| {
+                        t.overrides.system_prompt = if value.trim().is_empty() { None } else { Some(value.clone()) };
+                    });
+                },
+                class: "{field_class} h-20 resize-y",
+            }
+
+            textarea {
+                value: "{first_message}",
+                placeholder: "{message_placeholder}",
+                oninput: move |e| {
+                    let value = e.value();
+                    update_message(&|t| {
+                        t.first_message = if value.trim().is_empty() { None } else { Some(value.clone()) };
+                    });
+                },
+                class: "{field_class} h-20 resize-y",
+            }
+
+            div { class: "grid grid-cols-2 gap-3",
+                select {
+                    value: "{tools_value}",
+                    onchange: move |e| {
+                        let value = match e.value().as_str() {
+                            "on" => Some(true),
+                            "off" => Some(false),
+                            _ => None,
+                        };
+                        update_tools(&|t| t.overrides.enable_tools = value);
+                    },
+                    class: "{field_class} appearance-none cursor-pointer",
+                    option { value: "default", {t!(locale, "templates.tools_default")} }
+                    option { value: "on", {t!(locale, "templates.tools_on")} }
+                    option { value: "off", {t!(locale, "templates.tools_off")} }
+                }
+                input {
+                    r#type: "number",
+                    min: "1",
+                    value: "{iterations}",
+                    placeholder: "{iterations_placeholder}",
+                    oninput: move |e| {
+                        let value = e.value().parse::<usize>().ok().filter(|n| *n > 0);
+                        update_iterations(&|t| t.overrides.max_iterations = value);
+                    },
+                    class: "{field_class}",
+                }
+            }
+        }
+    }
+}
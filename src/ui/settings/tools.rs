@@ -1,13 +1,25 @@
 use crate::agent::get_tool_permission;
+use crate::agent::tools::command_policy::set_command_policy;
+use crate::agent::tools::default_tool_limit;
+use crate::agent::tools::filesystem::set_workspace_sandbox;
+use crate::agent::tools::lsp::set_language_servers;
+use crate::agent::tools::web::set_allow_private_network;
+use crate::agent::skills::loader::{SkillLoader, SkillsStatus};
 use crate::app::AppState;
-use crate::storage::settings::save_settings;
+use crate::storage::redaction::compile_pattern;
+use crate::storage::settings::{
+    default_language_servers, save_settings, ArgumentRule, CommandPolicy, CommandPolicyMode, LanguageServerConfig,
+    MaskingStyle, ToolLimit, ToolResultRole, ToolResultVerbosity,
+};
+use crate::ui::i18n::{lookup, t};
 use dioxus::prelude::*;
+use std::collections::BTreeMap;
 
 /// Known tool groups for the allowlist UI
 const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
-    // (group_label_en, tool_names, icon, risk_level)
+    // (label key, tool_names, icon, risk_level)
     (
-        "File Read",
+        "tools.group_file_read",
         &[
             "file_read",
             "file_list",
@@ -15,37 +27,54 @@ const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
             "glob",
             "file_info",
             "file_search",
+            "code_search",
+            "csv_analyze",
+            "image_info",
+            "index_build",
+            "index_search",
+            "index_status",
         ],
         "📂",
         "safe",
     ),
     (
-        "File Write",
+        "tools.group_file_write",
         &[
             "file_write",
             "file_edit",
+            "file_multi_edit",
+            "file_patch_lines",
             "file_create",
             "file_delete",
             "file_move",
             "file_copy",
             "directory_create",
+            "file_convert",
+            "undo_file_change",
+            "list_file_backups",
         ],
         "📝",
         "moderate",
     ),
     (
-        "Shell / Bash",
-        &["bash", "bash_background", "command"],
+        "tools.group_shell",
+        &["bash", "bash_background", "job_start", "job_status", "job_logs", "job_kill", "command", "cargo_check", "cargo_test", "pytest_run", "npm_test"],
         "⚡",
         "dangerous",
     ),
     (
-        "Git",
+        "tools.group_git",
         &[
             "git_status",
             "git_diff",
+            "git_branch_diff",
             "git_log",
             "git_commit",
+            "git_commit_prepared",
+            "github_issue_view",
+            "github_issue_list",
+            "github_pr_view",
+            "github_pr_create",
             "git_branch",
             "git_stash",
         ],
@@ -53,13 +82,15 @@ const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
         "moderate",
     ),
     (
-        "Web / Network",
+        "tools.group_web",
         &[
             "web_search",
             "code_search",
             "company_research",
             "web_fetch",
+            "web_read",
             "web_download",
+            "http_request",
             "web_crawl",
             "ai_consult",
         ],
@@ -67,50 +98,288 @@ const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
         "moderate",
     ),
     (
-        "Dev Tools",
-        &["diff", "find_replace", "patch", "wc"],
+        "tools.group_dev",
+        &["diff", "find_replace", "patch", "wc", "lsp_diagnostics", "lsp_hover"],
         "🛠️",
         "safe",
     ),
     (
-        "System",
+        "tools.group_system",
         &[
             "process_list",
             "environment",
             "system_info",
             "which",
             "tree",
+            "project_overview",
         ],
         "💻",
         "safe",
     ),
+    (
+        "tools.group_memory",
+        &["memory_read", "memory_search", "memory_write"],
+        "🧠",
+        "moderate",
+    ),
 ];
 
-const TOOL_GROUPS_FR: &[&str] = &[
-    "Lecture fichiers",
-    "Ecriture fichiers",
-    "Shell / Bash",
-    "Git",
-    "Web / Reseau",
-    "Outils dev",
-    "Systeme",
-];
+/// `tool = calls per minute, max concurrent` lines, `-` for unlimited
+fn parse_tool_limits(text: &str) -> BTreeMap<String, ToolLimit> {
+    let parse_part = |part: Option<&str>| part.map(str::trim).and_then(|p| p.parse::<u32>().ok()).filter(|n| *n > 0);
+    text.lines()
+        .filter_map(|line| {
+            let (tool, limits) = line.split_once('=')?;
+            let tool = tool.trim();
+            let mut parts = limits.split(',');
+            let limit = ToolLimit {
+                max_calls_per_minute: parse_part(parts.next()),
+                max_concurrent: parse_part(parts.next()),
+            };
+            (!tool.is_empty()).then(|| (tool.to_string(), limit))
+        })
+        .collect()
+}
+
+fn format_tool_limits(limits: &BTreeMap<String, ToolLimit>) -> String {
+    let show = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+    limits
+        .iter()
+        .map(|(tool, limit)| format!("{} = {}, {}", tool, show(limit.max_calls_per_minute), show(limit.max_concurrent)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `binary = prefix, prefix, !denied` lines, `-` standing for no arguments
+fn parse_argument_rules(text: &str) -> BTreeMap<String, ArgumentRule> {
+    text.lines()
+        .filter_map(|line| {
+            let (binary, parts) = line.split_once('=')?;
+            let binary = binary.trim();
+            let mut rule = ArgumentRule::default();
+            for part in parts.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                match part.strip_prefix('!') {
+                    Some(denied) => rule.denied.push(denied.trim().to_string()),
+                    None if part == "-" => rule.allowed_prefixes.push(String::new()),
+                    None => rule.allowed_prefixes.push(part.to_string()),
+                }
+            }
+            (!binary.is_empty()).then(|| (binary.to_string(), rule))
+        })
+        .collect()
+}
+
+fn format_argument_rules(rules: &BTreeMap<String, ArgumentRule>) -> String {
+    rules
+        .iter()
+        .map(|(binary, rule)| {
+            let parts = rule
+                .allowed_prefixes
+                .iter()
+                .map(|p| if p.is_empty() { "-".to_string() } else { p.clone() })
+                .chain(rule.denied.iter().map(|d| format!("!{}", d)))
+                .collect::<Vec<_>>();
+            format!("{} = {}", binary, parts.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `language ext ext = command args` lines
+fn parse_language_servers(text: &str) -> Vec<LanguageServerConfig> {
+    text.lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once('=')?;
+            let mut names = left.split_whitespace();
+            let language = names.next()?.to_string();
+            let extensions = names
+                .map(|e| e.trim_start_matches('.').to_string())
+                .filter(|e| !e.is_empty())
+                .collect();
+            let mut words = right.split_whitespace().map(str::to_string);
+            let command = words.next()?;
+            Some(LanguageServerConfig {
+                language,
+                extensions,
+                command,
+                args: words.collect(),
+            })
+        })
+        .collect()
+}
+
+fn format_language_servers(servers: &[LanguageServerConfig]) -> String {
+    servers
+        .iter()
+        .map(|server| {
+            let extensions: Vec<String> = server.extensions.iter().map(|e| format!(".{}", e)).collect();
+            let command = std::iter::once(server.command.as_str())
+                .chain(server.args.iter().map(String::as_str))
+                .collect::<Vec<_>>();
+            format!("{} {} = {}", server.language, extensions.join(" "), command.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Change the language servers, then put them in use
+fn update_language_servers(app_state: &mut AppState, servers: Vec<LanguageServerConfig>) {
+    let mut settings = app_state.settings.write();
+    settings.language_servers = servers;
+    set_language_servers(settings.language_servers.clone());
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Change the command policy, then put it in use
+fn update_command_policy(app_state: &mut AppState, change: impl FnOnce(&mut CommandPolicy)) {
+    let mut settings = app_state.settings.write();
+    change(&mut settings.command_policy);
+    set_command_policy(settings.command_policy.clone());
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
 
 pub fn ToolsSettings() -> Element {
     let app_state = use_context::<AppState>();
     let settings = app_state.settings.read().clone();
-    let is_en = settings.language == "en";
+    let locale = settings.locale();
     let auto_approve = settings.auto_approve_all_tools;
     let allowlist = settings.tool_allowlist.clone();
 
     let mut app_state_toggle = app_state.clone();
     let mut app_state_group = app_state.clone();
     let mut app_state_tool = app_state.clone();
+    let mut app_state_dismiss = app_state.clone();
+    let mut app_state_dev = app_state.clone();
+    let mut app_state_redaction_patterns = app_state.clone();
+    let mut redaction_patterns = use_signal(|| settings.redaction.custom_patterns.join("\n"));
+    let redaction_errors: Vec<String> = settings
+        .redaction
+        .custom_patterns
+        .iter()
+        .filter_map(|p| compile_pattern(p).err().map(|e| e.to_string()))
+        .collect();
+    let developer_mode = settings.developer_mode;
+    let strict_tool_calls = settings.strict_tool_calls;
+    let restrict_to_workspace = settings.restrict_tools_to_workspace;
+    let allow_private_network = settings.allow_private_network_requests;
+    let workspace_root = settings.workspace_root.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+    let workspace_placeholder = settings
+        .workspace_directory
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    let mut app_state_sandbox = app_state.clone();
+    let mut app_state_sandbox_root = app_state.clone();
+    let mut app_state_strict = app_state.clone();
+    let mut app_state_private_network = app_state.clone();
+    let mut app_state_retention_archive = app_state.clone();
+    let mut app_state_result_verbosity = app_state.clone();
+    let mut app_state_result_role = app_state.clone();
+    let mut app_state_result_overrides = app_state.clone();
+    let mut app_state_result_masking = app_state.clone();
+    let tool_result_verbosity = settings.tool_results.verbosity.as_str();
+    let tool_result_role = settings.tool_results.role;
+    let tool_result_masking = settings.tool_results.masking.as_str();
+    let mut tool_result_overrides = use_signal(|| {
+        settings
+            .tool_results
+            .tool_overrides
+            .iter()
+            .map(|(tool, verbosity)| format!("{} = {}", tool, verbosity.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    let mut app_state_tool_limits = app_state.clone();
+    let mut app_state_command_mode = app_state.clone();
+    let mut app_state_command_binaries = app_state.clone();
+    let mut app_state_command_denied = app_state.clone();
+    let mut app_state_command_rules = app_state.clone();
+    let mut app_state_command_reset = app_state.clone();
+    let command_mode = settings.command_policy.mode.as_str();
+    let mut command_binaries = use_signal(|| settings.command_policy.allowed_binaries.join(" "));
+    let mut command_denied = use_signal(|| settings.command_policy.denied_patterns.join("\n"));
+    let mut command_rules = use_signal(|| format_argument_rules(&settings.command_policy.argument_rules));
+    let mut tool_limits = use_signal(|| format_tool_limits(&settings.tool_limits));
+    let mut app_state_language_servers = app_state.clone();
+    let mut app_state_language_servers_reset = app_state.clone();
+    let mut language_servers = use_signal(|| format_language_servers(&settings.language_servers));
+
+    // Re-render whenever tools are registered/unregistered (MCP refresh, skill reload...)
+    let mut registry_generation = use_signal(|| app_state.agent.tool_registry.generation());
+    {
+        let registry = app_state.agent.tool_registry.clone();
+        use_future(move || {
+            let registry = registry.clone();
+            async move {
+                let mut rx = registry.subscribe();
+                while rx.changed().await.is_ok() {
+                    let generation = *rx.borrow_and_update();
+                    registry_generation.set(generation);
+                }
+            }
+        });
+    }
+    // Reading the signal subscribes this component to registry changes
+    let _ = registry_generation();
+    let registered_tools = app_state.agent.tool_registry.count();
+
+    // Skills directory read failure, unless the user already dismissed this exact one
+    let skills_status = use_resource(move || async move {
+        SkillLoader::load_all_with_report().await.status()
+    });
+    let skills_warning = match &*skills_status.read_unchecked() {
+        Some(SkillsStatus::Unreadable { path, error }) => {
+            let signature = format!("{}: {}", path.display(), error);
+            if settings.dismissed_skills_warning.as_deref() == Some(signature.as_str()) {
+                None
+            } else {
+                Some(signature)
+            }
+        }
+        _ => None,
+    };
 
     rsx! {
         div {
             class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
 
+            // Skills directory warning (dismissible)
+            if let Some(signature) = skills_warning {
+                div {
+                    class: "p-4 rounded-2xl flex items-start gap-3",
+                    style: "background: rgba(196,69,69,0.08); border: 1px solid rgba(196,69,69,0.25);",
+
+                    span { "⚠️" }
+                    div {
+                        class: "flex-1 min-w-0",
+                        div {
+                            class: "text-sm font-medium text-[var(--text-primary)]",
+                            {t!(locale, "tools.skills_error")}
+                        }
+                        div {
+                            class: "text-xs text-[var(--text-tertiary)] mt-1 break-all",
+                            "{signature}"
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_dismiss.settings.write();
+                            settings.dismissed_skills_warning = Some(signature.clone());
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        class: "text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)] transition-colors",
+                        {t!(locale, "tools.dismiss")}
+                    }
+                }
+            }
+
             // OpenRouter model selector
             div {
                 class: "p-5 rounded-2xl glass-md",
@@ -121,11 +390,7 @@ pub fn ToolsSettings() -> Element {
                 }
                 p {
                     class: "text-xs text-[var(--text-tertiary)] mb-5",
-                    if is_en {
-                        "Model to use when the AI consults a more powerful external model via the ai_consult tool."
-                    } else {
-                        "Modèle à utiliser lorsque l'IA consulte un modèle externe plus puissant via l'outil ai_consult."
-                    }
+                    {t!(locale, "tools.consult_hint")}
                 }
 
                 div {
@@ -136,7 +401,7 @@ pub fn ToolsSettings() -> Element {
                         class: "flex items-center gap-4",
                         label {
                             class: "text-sm text-[var(--text-secondary)] w-32",
-                            if is_en { "Model" } else { "Modèle" }
+                            {t!(locale, "tools.model")}
                         }
                         {
                             let current_model = settings.openrouter_model.clone();
@@ -170,30 +435,421 @@ pub fn ToolsSettings() -> Element {
                     div {
                         class: "flex items-center gap-2 text-xs text-[var(--text-tertiary)]",
                         span { "💡" }
-                        if is_en {
-                            "Set OPENROUTER_API_KEY environment variable. Get a free key at openrouter.ai/keys"
-                        } else {
-                            "Définir la variable d'environnement OPENROUTER_API_KEY. Clé gratuite sur openrouter.ai/keys"
+                        {t!(locale, "tools.openrouter_hint")}
+                    }
+                }
+            }
+
+            // Environment context — privacy toggles
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.env_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.env_hint")}
+                }
+
+                div {
+                    class: "space-y-3",
+
+                    for (key, label, enabled) in [
+                        ("os", t!(locale, "tools.env_os"), settings.environment_context.include_os),
+                        ("shell", t!(locale, "tools.env_shell"), settings.environment_context.include_shell),
+                        ("datetime", t!(locale, "tools.env_datetime"), settings.environment_context.include_datetime),
+                        ("workspace", t!(locale, "tools.env_workspace"), settings.environment_context.include_workspace),
+                        ("git", t!(locale, "tools.env_git"), settings.environment_context.include_git),
+                        ("model", t!(locale, "tools.env_model"), settings.environment_context.include_model),
+                    ] {
+                        {
+                            let mut app_state_env = app_state.clone();
+                            rsx! {
+                                div {
+                                    class: "flex items-center justify-between",
+                                    span {
+                                        class: "text-sm text-[var(--text-primary)]",
+                                        "{label}"
+                                    }
+                                    button {
+                                        onclick: move |_| {
+                                            let mut settings = app_state_env.settings.write();
+                                            let env = &mut settings.environment_context;
+                                            let flag = match key {
+                                                "os" => &mut env.include_os,
+                                                "shell" => &mut env.include_shell,
+                                                "datetime" => &mut env.include_datetime,
+                                                "workspace" => &mut env.include_workspace,
+                                                "git" => &mut env.include_git,
+                                                _ => &mut env.include_model,
+                                            };
+                                            *flag = !*flag;
+                                            if let Err(e) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", e);
+                                            }
+                                        },
+                                        class: if enabled { "toggle-switch active" } else { "toggle-switch" },
+                                        div { class: "toggle-switch-knob" }
+                                    }
+                                }
+                            }
                         }
                     }
                 }
             }
 
-            // Auto-approve ALL toggle
+            // Tool results — how they are written back for the model
             div {
                 class: "p-5 rounded-2xl glass-md",
 
                 h3 {
                     class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
-                    if is_en { "Auto-approve Mode" } else { "Mode tout accepter" }
+                    {t!(locale, "tools.results_title")}
                 }
                 p {
                     class: "text-xs text-[var(--text-tertiary)] mb-5",
-                    if is_en {
-                        "When enabled, ALL tool calls are automatically approved without asking. Use with caution."
-                    } else {
-                        "Quand active, TOUS les appels d'outils sont approuves automatiquement. A utiliser avec precaution."
+                    {t!(locale, "tools.results_hint")}
+                }
+
+                div {
+                    class: "flex flex-col gap-3",
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-32",
+                            r#for: "tool-result-verbosity",
+                            "Format"
+                        }
+                        select {
+                            id: "tool-result-verbosity",
+                            class: "flex-1 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            value: "{tool_result_verbosity}",
+                            onchange: move |e: Event<FormData>| {
+                                let Some(verbosity) = ToolResultVerbosity::parse(&e.value()) else {
+                                    return;
+                                };
+                                let mut settings = app_state_result_verbosity.settings.write();
+                                settings.tool_results.verbosity = verbosity;
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            option { value: "full", {t!(locale, "tools.verbosity_full")} }
+                            option { value: "compact", {t!(locale, "tools.verbosity_compact")} }
+                            option { value: "json", {t!(locale, "tools.verbosity_json")} }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-32",
+                            r#for: "tool-result-role",
+                            {t!(locale, "tools.role")}
+                        }
+                        select {
+                            id: "tool-result-role",
+                            class: "flex-1 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            value: if tool_result_role == ToolResultRole::Tool { "tool" } else { "system" },
+                            onchange: move |e: Event<FormData>| {
+                                let mut settings = app_state_result_role.settings.write();
+                                settings.tool_results.role = if e.value() == "tool" { ToolResultRole::Tool } else { ToolResultRole::System };
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            option { value: "system", "System" }
+                            option { value: "tool", "Tool" }
+                        }
                     }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-32",
+                            r#for: "tool-result-masking",
+                            {t!(locale, "tools.old_results")}
+                        }
+                        select {
+                            id: "tool-result-masking",
+                            class: "flex-1 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            value: "{tool_result_masking}",
+                            onchange: move |e: Event<FormData>| {
+                                let Some(masking) = MaskingStyle::parse(&e.value()) else {
+                                    return;
+                                };
+                                let mut settings = app_state_result_masking.settings.write();
+                                settings.tool_results.masking = masking;
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            option { value: "digest", {t!(locale, "tools.masking_digest")} }
+                            option { value: "placeholder", {t!(locale, "tools.masking_placeholder")} }
+                        }
+                    }
+
+                    div {
+                        class: "space-y-2 pt-2",
+                        label {
+                            class: "text-sm text-[var(--text-primary)]",
+                            r#for: "tool-result-overrides",
+                            {t!(locale, "tools.per_tool_format")}
+                        }
+                        textarea {
+                            id: "tool-result-overrides",
+                            value: "{tool_result_overrides}",
+                            placeholder: "web_fetch = compact",
+                            oninput: move |e| {
+                                tool_result_overrides.set(e.value());
+                                let mut settings = app_state_result_overrides.settings.write();
+                                settings.tool_results.tool_overrides = e
+                                    .value()
+                                    .lines()
+                                    .filter_map(|l| {
+                                        let (tool, format) = l.split_once('=')?;
+                                        let tool = tool.trim();
+                                        let verbosity = ToolResultVerbosity::parse(format)?;
+                                        (!tool.is_empty()).then(|| (tool.to_string(), verbosity))
+                                    })
+                                    .collect();
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs font-mono h-20 resize-y",
+                        }
+                    }
+                }
+            }
+
+            // Rate limits — per-tool call caps enforced by the registry
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.rate_limits_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.rate_limits_hint")}
+                }
+
+                div {
+                    class: "space-y-2",
+                    label {
+                        class: "text-sm text-[var(--text-primary)]",
+                        r#for: "tool-limits",
+                        {t!(locale, "tools.rate_limits_field")}
+                    }
+                    textarea {
+                        id: "tool-limits",
+                        value: "{tool_limits}",
+                        placeholder: "web_fetch = 10, 2",
+                        oninput: move |e| {
+                            tool_limits.set(e.value());
+                            let limits = parse_tool_limits(&e.value());
+                            let registry = app_state_tool_limits.agent.tool_registry.clone();
+                            let mut settings = app_state_tool_limits.settings.write();
+                            // Tools dropped from the list go back to their default
+                            for tool in settings.tool_limits.keys().filter(|tool| !limits.contains_key(*tool)) {
+                                let default = default_tool_limit(tool);
+                                registry.set_limit(tool, default.max_calls_per_minute, default.max_concurrent);
+                            }
+                            for (tool, limit) in &limits {
+                                if settings.tool_limits.get(tool) != Some(limit) {
+                                    registry.set_limit(tool, limit.max_calls_per_minute, limit.max_concurrent);
+                                }
+                            }
+                            settings.tool_limits = limits;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs font-mono h-20 resize-y",
+                    }
+                }
+            }
+
+            // Redaction — export and cross-conversation stores
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.redaction_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.redaction_hint")}
+                }
+
+                div {
+                    class: "space-y-3",
+
+                    for (key, label, enabled) in [
+                        ("api_keys", t!(locale, "tools.redact_api_keys"), settings.redaction.redact_api_keys),
+                        ("emails", t!(locale, "tools.redact_emails"), settings.redaction.redact_emails),
+                        ("ips", t!(locale, "tools.redact_ips"), settings.redaction.redact_ip_addresses),
+                        ("indexed", t!(locale, "tools.redact_indexed"), settings.redaction.redact_indexed_content),
+                    ] {
+                        {
+                            let mut app_state_redaction = app_state.clone();
+                            rsx! {
+                                div {
+                                    class: "flex items-center justify-between",
+                                    span {
+                                        class: "text-sm text-[var(--text-primary)]",
+                                        "{label}"
+                                    }
+                                    button {
+                                        onclick: move |_| {
+                                            let mut settings = app_state_redaction.settings.write();
+                                            let redaction = &mut settings.redaction;
+                                            let flag = match key {
+                                                "api_keys" => &mut redaction.redact_api_keys,
+                                                "emails" => &mut redaction.redact_emails,
+                                                "ips" => &mut redaction.redact_ip_addresses,
+                                                _ => &mut redaction.redact_indexed_content,
+                                            };
+                                            *flag = !*flag;
+                                            if let Err(e) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", e);
+                                            }
+                                        },
+                                        class: if enabled { "toggle-switch active" } else { "toggle-switch" },
+                                        div { class: "toggle-switch-knob" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "space-y-2 pt-2",
+                        label {
+                            class: "text-sm text-[var(--text-primary)]",
+                            r#for: "redaction-patterns",
+                            {t!(locale, "tools.redaction_patterns")}
+                        }
+                        textarea {
+                            id: "redaction-patterns",
+                            value: "{redaction_patterns}",
+                            placeholder: "\\b[a-z0-9-]+\\.corp\\.lan\\b",
+                            oninput: move |e| {
+                                redaction_patterns.set(e.value());
+                                let mut settings = app_state_redaction_patterns.settings.write();
+                                settings.redaction.custom_patterns = e
+                                    .value()
+                                    .lines()
+                                    .map(|l| l.trim().to_string())
+                                    .filter(|l| !l.is_empty())
+                                    .collect();
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs font-mono h-20 resize-y",
+                        }
+                        for error in redaction_errors {
+                            p { class: "text-xs text-[var(--text-error)]", "{error}" }
+                        }
+                    }
+                }
+            }
+
+            // Conversation retention
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.retention_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.retention_hint")}
+                }
+
+                div {
+                    class: "space-y-3",
+
+                    for (key, label, value) in [
+                        ("age", t!(locale, "tools.retention_age"), settings.retention.max_age_days.map(|v| v.to_string())),
+                        ("count", t!(locale, "tools.retention_count"), settings.retention.max_count.map(|v| v.to_string())),
+                        ("size", t!(locale, "tools.retention_size"), settings.retention.max_total_mb.map(|v| v.to_string())),
+                    ] {
+                        {
+                            let mut app_state_retention = app_state.clone();
+                            rsx! {
+                                div {
+                                    class: "flex items-center justify-between gap-4",
+                                    label {
+                                        class: "text-sm text-[var(--text-primary)]",
+                                        r#for: "retention-{key}",
+                                        "{label}"
+                                    }
+                                    input {
+                                        id: "retention-{key}",
+                                        r#type: "number",
+                                        min: "1",
+                                        value: value.unwrap_or_default(),
+                                        oninput: move |e| {
+                                            let value = e.value().trim().parse::<u64>().ok().filter(|n| *n > 0);
+                                            let mut settings = app_state_retention.settings.write();
+                                            let retention = &mut settings.retention;
+                                            match key {
+                                                "age" => retention.max_age_days = value.map(|v| v.min(u32::MAX as u64) as u32),
+                                                "count" => retention.max_count = value.map(|v| v as usize),
+                                                _ => retention.max_total_mb = value,
+                                            }
+                                            if let Err(e) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", e);
+                                            }
+                                        },
+                                        class: "w-28 py-1.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center justify-between",
+                        span {
+                            class: "text-sm text-[var(--text-primary)]",
+                            {t!(locale, "tools.retention_archive")}
+                        }
+                        button {
+                            onclick: move |_| {
+                                let mut settings = app_state_retention_archive.settings.write();
+                                settings.retention.archive = !settings.retention.archive;
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            class: if settings.retention.archive { "toggle-switch active" } else { "toggle-switch" },
+                            div { class: "toggle-switch-knob" }
+                        }
+                    }
+                }
+            }
+
+            // Auto-approve ALL toggle
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.auto_approve_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.auto_approve_hint")}
                 }
 
                 div {
@@ -202,18 +858,18 @@ pub fn ToolsSettings() -> Element {
                     div {
                         div {
                             class: "text-sm font-medium text-[var(--text-primary)] flex items-center gap-2",
-                            if is_en { "Accept all tools" } else { "Tout accepter" }
+                            {t!(locale, "tools.auto_approve")}
                             if auto_approve {
                                 span {
                                     class: "px-1.5 py-0.5 rounded text-[10px] font-semibold uppercase",
                                     style: "background: rgba(196,69,69,0.12); color: #C45B5B;",
-                                    if is_en { "DANGEROUS" } else { "DANGEREUX" }
+                                    {t!(locale, "tools.dangerous")}
                                 }
                             }
                         }
                         div {
                             class: "text-xs text-[var(--text-tertiary)] mt-0.5",
-                            if is_en { "Skip permission dialogs for all tools" } else { "Ignorer les dialogues de permission pour tous les outils" }
+                            {t!(locale, "tools.auto_approve_detail")}
                         }
                     }
                     button {
@@ -230,6 +886,342 @@ pub fn ToolsSettings() -> Element {
                 }
             }
 
+            // Workspace sandbox — file tools confined to one directory
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.sandbox_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.sandbox_hint")}
+                }
+
+                div {
+                    class: "flex flex-col gap-3",
+
+                    div {
+                        class: "flex items-center justify-between",
+
+                        div {
+                            class: "text-sm font-medium text-[var(--text-primary)]",
+                            {t!(locale, "tools.sandbox")}
+                        }
+                        button {
+                            onclick: move |_| {
+                                let mut settings = app_state_sandbox.settings.write();
+                                settings.restrict_tools_to_workspace = !settings.restrict_tools_to_workspace;
+                                set_workspace_sandbox(settings.sandbox_root());
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            class: if restrict_to_workspace { "toggle-switch active" } else { "toggle-switch" },
+                            role: "switch",
+                            "aria-checked": "{restrict_to_workspace}",
+                            div { class: "toggle-switch-knob" }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-32",
+                            r#for: "workspace-root",
+                            {t!(locale, "tools.root")}
+                        }
+                        input {
+                            id: "workspace-root",
+                            r#type: "text",
+                            value: "{workspace_root}",
+                            placeholder: "{workspace_placeholder}",
+                            onchange: move |e: Event<FormData>| {
+                                let root = e.value().trim().to_string();
+                                let mut settings = app_state_sandbox_root.settings.write();
+                                settings.workspace_root = (!root.is_empty()).then(|| std::path::PathBuf::from(root));
+                                set_workspace_sandbox(settings.sandbox_root());
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            class: "flex-1 px-3 py-2 rounded-lg text-sm font-mono text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        }
+                    }
+                }
+            }
+
+            // Local network — http_request to localhost and private ranges
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.network_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.network_hint")}
+                }
+
+                div {
+                    class: "flex items-center justify-between",
+
+                    div {
+                        class: "text-sm font-medium text-[var(--text-primary)]",
+                        {t!(locale, "tools.network")}
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_private_network.settings.write();
+                            settings.allow_private_network_requests = !settings.allow_private_network_requests;
+                            set_allow_private_network(settings.allow_private_network_requests);
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        class: if allow_private_network { "toggle-switch active" } else { "toggle-switch" },
+                        role: "switch",
+                        "aria-checked": "{allow_private_network}",
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+            }
+
+            // Command policy — what the `command` tool runs without asking
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.policy_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.policy_hint")}
+                }
+
+                div {
+                    class: "flex flex-col gap-3",
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-32",
+                            r#for: "command-policy-mode",
+                            {t!(locale, "tools.policy_other")}
+                        }
+                        select {
+                            id: "command-policy-mode",
+                            class: "flex-1 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            value: "{command_mode}",
+                            onchange: move |e: Event<FormData>| {
+                                let Some(mode) = CommandPolicyMode::parse(&e.value()) else {
+                                    return;
+                                };
+                                update_command_policy(&mut app_state_command_mode, |policy| policy.mode = mode);
+                            },
+                            option { value: "strict", {t!(locale, "tools.policy_strict")} }
+                            option { value: "prompt", {t!(locale, "tools.policy_ask")} }
+                            option { value: "allow_all", {t!(locale, "tools.policy_allow")} }
+                        }
+                    }
+
+                    label {
+                        class: "text-sm text-[var(--text-primary)]",
+                        r#for: "command-policy-binaries",
+                        {t!(locale, "tools.policy_allowed")}
+                    }
+                    textarea {
+                        id: "command-policy-binaries",
+                        value: "{command_binaries}",
+                        oninput: move |e| {
+                            command_binaries.set(e.value());
+                            let binaries = e
+                                .value()
+                                .split(|c: char| c.is_whitespace() || c == ',')
+                                .filter(|b| !b.is_empty())
+                                .map(str::to_string)
+                                .collect();
+                            update_command_policy(&mut app_state_command_binaries, |policy| policy.allowed_binaries = binaries);
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs font-mono h-20 resize-y",
+                    }
+
+                    label {
+                        class: "text-sm text-[var(--text-primary)]",
+                        r#for: "command-policy-rules",
+                        {t!(locale, "tools.policy_arguments")}
+                    }
+                    textarea {
+                        id: "command-policy-rules",
+                        value: "{command_rules}",
+                        placeholder: "git = status, log, diff",
+                        oninput: move |e| {
+                            command_rules.set(e.value());
+                            let rules = parse_argument_rules(&e.value());
+                            update_command_policy(&mut app_state_command_rules, |policy| policy.argument_rules = rules);
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs font-mono h-24 resize-y",
+                    }
+
+                    label {
+                        class: "text-sm text-[var(--text-primary)]",
+                        r#for: "command-policy-denied",
+                        {t!(locale, "tools.policy_denied")}
+                    }
+                    textarea {
+                        id: "command-policy-denied",
+                        value: "{command_denied}",
+                        oninput: move |e| {
+                            command_denied.set(e.value());
+                            let patterns = e
+                                .value()
+                                .lines()
+                                .map(str::trim)
+                                .filter(|p| !p.is_empty())
+                                .map(str::to_string)
+                                .collect();
+                            update_command_policy(&mut app_state_command_denied, |policy| policy.denied_patterns = patterns);
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs font-mono h-20 resize-y",
+                    }
+
+                    div {
+                        button {
+                            onclick: move |_| {
+                                let defaults = CommandPolicy::default();
+                                command_binaries.set(defaults.allowed_binaries.join(" "));
+                                command_denied.set(defaults.denied_patterns.join("\n"));
+                                command_rules.set(format_argument_rules(&defaults.argument_rules));
+                                update_command_policy(&mut app_state_command_reset, |policy| *policy = defaults);
+                            },
+                            class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                            {t!(locale, "tools.restore_defaults")}
+                        }
+                    }
+                }
+            }
+
+            // Language servers — started by the lsp_diagnostics / lsp_hover tools
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.lsp_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.lsp_hint")}
+                }
+
+                div {
+                    class: "flex flex-col gap-3",
+
+                    label {
+                        class: "text-sm text-[var(--text-primary)]",
+                        r#for: "language-servers",
+                        {t!(locale, "tools.lsp_field")}
+                    }
+                    textarea {
+                        id: "language-servers",
+                        value: "{language_servers}",
+                        placeholder: "rust .rs = rust-analyzer",
+                        oninput: move |e| {
+                            language_servers.set(e.value());
+                            update_language_servers(&mut app_state_language_servers, parse_language_servers(&e.value()));
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-xs font-mono h-20 resize-y",
+                    }
+
+                    div {
+                        button {
+                            onclick: move |_| {
+                                let defaults = default_language_servers();
+                                language_servers.set(format_language_servers(&defaults));
+                                update_language_servers(&mut app_state_language_servers_reset, defaults);
+                            },
+                            class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                            {t!(locale, "tools.restore_defaults")}
+                        }
+                    }
+                }
+            }
+
+            // Strict tool-call format — grammar-constrained output
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.strict_format_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.strict_format_hint")}
+                }
+
+                div {
+                    class: "flex items-center justify-between",
+
+                    div {
+                        class: "text-sm font-medium text-[var(--text-primary)]",
+                        {t!(locale, "tools.strict_format")}
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_strict.settings.write();
+                            settings.strict_tool_calls = !settings.strict_tool_calls;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        class: if strict_tool_calls { "toggle-switch active" } else { "toggle-switch" },
+                        role: "switch",
+                        "aria-checked": "{strict_tool_calls}",
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+            }
+
+            // Developer mode — run recording for replay tests
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "tools.developer_title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "tools.developer_hint")}
+                }
+
+                div {
+                    class: "flex items-center justify-between",
+
+                    div {
+                        class: "text-sm font-medium text-[var(--text-primary)]",
+                        {t!(locale, "tools.record_runs")}
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_dev.settings.write();
+                            settings.developer_mode = !settings.developer_mode;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        class: if developer_mode { "toggle-switch active" } else { "toggle-switch" },
+                        role: "switch",
+                        "aria-checked": "{developer_mode}",
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+            }
+
             // Allowlist — per-group and per-tool toggles
             if !auto_approve {
                 div {
@@ -237,23 +1229,19 @@ pub fn ToolsSettings() -> Element {
 
                     h3 {
                         class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
-                        if is_en { "Tool Allowlist" } else { "Liste d'outils autorises" }
+                        {t!(locale, "tools.allowlist_title")}
                     }
                     p {
                         class: "text-xs text-[var(--text-tertiary)] mb-5",
-                        if is_en {
-                            "Tools in the allowlist are auto-approved. Others will require manual approval."
-                        } else {
-                            "Les outils dans la liste sont approuves automatiquement. Les autres demanderont une approbation manuelle."
-                        }
+                        {t!(locale, "tools.allowlist_hint", registered_tools)}
                     }
 
                     div {
                         class: "space-y-3",
 
-                        for (idx, (label_en, tools, icon, risk)) in TOOL_GROUPS.iter().enumerate() {
+                        for (label_key, tools, icon, risk) in TOOL_GROUPS.iter() {
                             {
-                                let label = if is_en { label_en.to_string() } else { TOOL_GROUPS_FR[idx].to_string() };
+                                let label = lookup(locale, label_key);
                                 let tools_vec: Vec<String> = tools.iter().map(|t| t.to_string()).collect();
                                 let all_in_allowlist = tools_vec.iter().all(|t| allowlist.contains(t));
                                 let some_in_allowlist = tools_vec.iter().any(|t| allowlist.contains(t));
@@ -263,13 +1251,10 @@ pub fn ToolsSettings() -> Element {
                                     "moderate" => "background: rgba(196,153,59,0.10); color: #C4993B; border: 1px solid rgba(196,153,59,0.15);",
                                     _ => "background: rgba(90,158,124,0.10); color: #5A9E7C; border: 1px solid rgba(90,158,124,0.15);",
                                 };
-                                let risk_label = match (*risk, is_en) {
-                                    ("dangerous", true) => "high risk",
-                                    ("dangerous", false) => "risque eleve",
-                                    ("moderate", true) => "moderate",
-                                    ("moderate", false) => "modere",
-                                    (_, true) => "safe",
-                                    (_, false) => "sur",
+                                let risk_label = match *risk {
+                                    "dangerous" => t!(locale, "tools.risk_high"),
+                                    "moderate" => t!(locale, "tools.risk_moderate"),
+                                    _ => t!(locale, "tools.risk_safe"),
                                 };
 
                                 {
@@ -356,6 +1341,7 @@ pub fn ToolsSettings() -> Element {
                                                         let tool = tool_name.to_string();
                                                         let is_allowed = allowlist.contains(&tool);
                                                         let perm = get_tool_permission(tool_name);
+                                                        let row_opacity = if app_state.agent.tool_registry.get(tool_name).is_some() { "" } else { "opacity: 0.45;" };
                                                         let tool_cb_style = if is_allowed {
                                                             "background: var(--accent-primary); border-color: var(--accent-primary);"
                                                         } else {
@@ -380,6 +1366,7 @@ pub fn ToolsSettings() -> Element {
                                                                     }
                                                                 },
                                                                 class: "w-full flex items-center justify-between px-4 py-2 text-left hover:bg-white/[0.03] transition-all",
+                                                                style: "{row_opacity}",
 
                                                                 div {
                                                                     class: "flex items-center gap-2",
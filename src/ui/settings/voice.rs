@@ -0,0 +1,210 @@
+use crate::app::AppState;
+use crate::inference::voice;
+use crate::storage::models::{whisper_model_path, DownloadHandle, DownloadStatus, ModelDownloader, WhisperModel, WHISPER_MODELS};
+use crate::storage::settings::save_settings;
+use crate::ui::i18n::t;
+use crate::ui::sidebar::model_picker::download_status_label;
+use dioxus::prelude::*;
+use std::sync::Arc;
+
+/// Change the voice settings and save them
+fn update_voice_settings(app_state: &mut AppState, change: impl FnOnce(&mut crate::storage::settings::VoiceSettings)) {
+    let mut settings = app_state.settings.write();
+    change(&mut settings.voice);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+fn is_downloaded(model: &WhisperModel) -> bool {
+    whisper_model_path(model.file).is_ok_and(|path| path.exists())
+}
+
+pub fn VoiceSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let locale = settings.locale();
+    let selected = settings.voice.model.clone();
+    let language = settings.voice.language.clone();
+    let mut download_status = use_signal(|| None::<DownloadStatus>);
+    let mut download_handle = use_signal(|| None::<Arc<DownloadHandle>>);
+    let mut download_file = use_signal(String::new);
+    let mut download_message = use_signal(|| None::<String>);
+    let mut app_state_language = app_state.clone();
+
+    let mut start_download = move |model: &'static WhisperModel| {
+        if download_handle.read().is_some() {
+            return;
+        }
+        let dest = match whisper_model_path(model.file) {
+            Ok(dest) => dest,
+            Err(e) => {
+                download_message.set(Some(e.to_string()));
+                return;
+            }
+        };
+        download_file.set(model.file.to_string());
+        download_message.set(None);
+        let handle = ModelDownloader::new().start_download(&model.url(), dest, None);
+        let mut status_rx = handle.subscribe();
+        download_handle.set(Some(Arc::new(handle)));
+        spawn(async move {
+            let outcome = loop {
+                let status = status_rx.borrow_and_update().clone();
+                download_status.set(Some(status.clone()));
+                if status.is_done() {
+                    break status;
+                }
+                if status_rx.changed().await.is_err() {
+                    break status_rx.borrow().clone();
+                }
+            };
+            download_handle.set(None);
+            download_status.set(None);
+            let message = match outcome {
+                DownloadStatus::Finished(_) => {
+                    t!(locale, "voice_settings.downloaded", model.label)
+                }
+                DownloadStatus::Failed(e) => t!(locale, "settings.error", e),
+                _ => t!(locale, "voice_settings.download_stopped").to_string(),
+            };
+            download_message.set(Some(message));
+        });
+    };
+
+    let download_snapshot = download_status.read().clone();
+    let download_percent = download_snapshot
+        .as_ref()
+        .and_then(|status| status.fraction())
+        .map(|fraction| (fraction * 100.0).round() as u32);
+    let download_label = download_status_label(download_snapshot.as_ref(), locale);
+    let downloading = download_handle.read().is_some();
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-2 text-[var(--text-primary)]",
+                    {t!(locale, "voice_settings.title")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    {t!(locale, "voice_settings.description")}
+                }
+                if !voice::AVAILABLE {
+                    p {
+                        class: "text-xs text-[var(--warning)] mb-4",
+                        {t!(locale, "voice_settings.not_built")}
+                    }
+                }
+
+                div {
+                    class: "flex items-center justify-between",
+                    div {
+                        label { class: "text-sm text-[var(--text-primary)]", r#for: "voice-language",
+                            {t!(locale, "voice_settings.language")}
+                        }
+                        p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            {t!(locale, "voice_settings.language_hint")}
+                        }
+                    }
+                    input {
+                        id: "voice-language",
+                        r#type: "text",
+                        value: "{language}",
+                        onchange: move |e: Event<FormData>| {
+                            let code = e.value().trim().to_lowercase();
+                            update_voice_settings(&mut app_state_language, |voice| {
+                                voice.language = if code.is_empty() { "auto".to_string() } else { code };
+                            });
+                        },
+                        class: "w-24 py-1.5 px-2 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-mono outline-none focus:border-[var(--accent-primary)]",
+                    }
+                }
+            }
+
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    {t!(locale, "voice_settings.model")}
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-4",
+                    {t!(locale, "voice_settings.model_hint")}
+                }
+
+                div { class: "space-y-2",
+                    for model in WHISPER_MODELS.iter() {
+                        {
+                            let is_selected = model.file == selected;
+                            let downloaded = is_downloaded(model);
+                            let is_downloading = downloading && download_file() == model.file;
+                            let mut app_state_select = app_state.clone();
+                            rsx! {
+                                div {
+                                    key: "{model.file}",
+                                    class: if is_selected {
+                                        "flex items-center justify-between gap-3 px-3 py-2 rounded-lg border border-[var(--accent-primary)] bg-white/[0.04]"
+                                    } else {
+                                        "flex items-center justify-between gap-3 px-3 py-2 rounded-lg border border-[var(--border-subtle)] bg-white/[0.03]"
+                                    },
+                                    div { class: "min-w-0",
+                                        p { class: "text-sm text-[var(--text-primary)]", "{model.label}" }
+                                        p { class: "text-xs text-[var(--text-tertiary)] font-mono", "{model.file} · {model.size_mb} MB" }
+                                        if is_downloading {
+                                            p { class: "text-xs text-[var(--text-secondary)] mt-1",
+                                                if let Some(percent) = download_percent { "{percent}% · {download_label}" } else { "{download_label}" }
+                                            }
+                                        }
+                                    }
+                                    div { class: "flex gap-2 flex-none",
+                                        if is_downloading {
+                                            button {
+                                                onclick: move |_| {
+                                                    if let Some(handle) = download_handle.read().as_ref() {
+                                                        handle.cancel();
+                                                    }
+                                                },
+                                                class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                                                {t!(locale, "voice_settings.cancel")}
+                                            }
+                                        } else if !downloaded {
+                                            button {
+                                                disabled: downloading,
+                                                onclick: move |_| start_download(model),
+                                                class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all disabled:opacity-40",
+                                                {t!(locale, "voice_settings.download")}
+                                            }
+                                        }
+                                        if is_selected {
+                                            span { class: "px-3 py-1 text-xs font-semibold text-[var(--accent-primary)]",
+                                                {t!(locale, "voice_settings.in_use")}
+                                            }
+                                        } else {
+                                            button {
+                                                onclick: move |_| update_voice_settings(&mut app_state_select, |voice| {
+                                                    voice.model = model.file.to_string();
+                                                }),
+                                                class: "px-3 py-1 text-xs font-medium rounded-lg border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.04] transition-all",
+                                                {t!(locale, "voice_settings.use")}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(message) = download_message() {
+                    p { class: "text-xs text-[var(--text-secondary)] mt-3", "{message}" }
+                }
+            }
+        }
+    }
+}
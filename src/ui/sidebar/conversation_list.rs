@@ -1,9 +1,16 @@
 use dioxus::prelude::*;
 
 use crate::app::AppState;
+use crate::ui::components::a11y::is_activation_key;
+use crate::ui::sidebar::export_dialog::ExportDialog;
 use crate::storage::conversations::{
-    delete_conversation, list_conversations, save_conversation, Conversation,
+    delete_conversation, list_conversations, load_conversation, save_conversation, scan_conversations,
+    Conversation, SearchHit,
 };
+use crate::ui::i18n::t;
+
+/// Element id of the search field, focused by Ctrl+F
+pub const CONVERSATION_SEARCH_ID: &str = "conversation-search";
 
 #[component]
 pub fn ConversationList() -> Element {
@@ -33,6 +40,62 @@ pub fn ConversationList() -> Element {
         }
     };
 
+    let mut export_target = use_signal(|| None::<Conversation>);
+
+    // Full-text search: the scan runs on a blocking thread and streams its hits in
+    let mut search_query = use_signal(String::new);
+    let mut search_hits = use_signal(Vec::<SearchHit>::new);
+    let mut searching = use_signal(|| false);
+    let mut search_task = use_signal(|| None::<Task>);
+
+    let handle_search = move |evt: FormEvent| {
+        let query = evt.value();
+        search_query.set(query.clone());
+        // A new query replaces the scan in progress; dropping its receiver stops it
+        if let Some(task) = search_task.take() {
+            task.cancel();
+        }
+        search_hits.set(Vec::new());
+        if query.trim().is_empty() {
+            searching.set(false);
+            return;
+        }
+        searching.set(true);
+        let task = spawn(async move {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = scan_conversations(&query, |hit| tx.send(hit).is_ok()) {
+                    tracing::error!("Conversation search failed: {}", e);
+                }
+            });
+            while let Some(hit) = rx.recv().await {
+                search_hits.write().push(hit);
+            }
+            searching.set(false);
+        });
+        search_task.set(Some(task));
+    };
+
+    let open_hit = {
+        let mut current_conversation_signal = app_state.current_conversation.clone();
+        let mut scroll_to_message = app_state.scroll_to_message;
+        move |hit: SearchHit| match load_conversation(&hit.conversation_id) {
+            Ok(conversation) => {
+                scroll_to_message.set(hit.message_index);
+                current_conversation_signal.set(Some(conversation));
+            }
+            Err(e) => tracing::error!("Failed to open search result: {}", e),
+        }
+    };
+
+    let locale = app_state.locale();
+    let is_searching = !search_query.read().trim().is_empty();
+    let hits = search_hits.read().clone();
+    let search_status = match (searching(), hits.len()) {
+        (true, _) => t!(locale, "conversations.searching").to_string(),
+        (false, 0) => t!(locale, "conversations.no_results").to_string(),
+        (false, n) => t!(locale, "conversations.results", n),
+    };
     let conversations = app_state.conversations.read().clone();
     let selected_id = app_state
         .current_conversation
@@ -44,16 +107,63 @@ pub fn ConversationList() -> Element {
         div {
             class: "flex-1 overflow-y-auto p-2 space-y-1 scrollbar-thin",
 
-            if conversations.is_empty() {
+            div { class: "px-1 pb-1",
+                input {
+                    id: CONVERSATION_SEARCH_ID,
+                    r#type: "search",
+                    value: "{search_query}",
+                    oninput: handle_search,
+                    placeholder: t!(locale, "conversations.search"),
+                    "aria-label": t!(locale, "conversations.search"),
+                    class: "w-full px-3 py-1.5 text-sm rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none",
+                }
+            }
+
+            if is_searching {
+                div {
+                    class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold px-3 py-2 select-none opacity-60",
+                    role: "status",
+                    "{search_status}"
+                }
+
+                {hits.into_iter().enumerate().map(|(i, hit)| {
+                    let mut open_hit = open_hit.clone();
+                    let hit_for_key = hit.clone();
+                    let mut open_hit_for_key = open_hit.clone();
+                    let hit_for_select = hit.clone();
+                    rsx! {
+                        div {
+                            key: "{i}",
+                            class: "px-1",
+                            div {
+                                class: "flex flex-col gap-0.5 px-3 py-2 rounded-lg hover:bg-white/[0.05] text-[var(--text-secondary)] hover:text-[var(--text-primary)] cursor-pointer transition-all",
+                                role: "button",
+                                tabindex: "0",
+                                onclick: move |_| open_hit(hit_for_select.clone()),
+                                onkeydown: move |evt: KeyboardEvent| {
+                                    if is_activation_key(&evt.key()) {
+                                        evt.prevent_default();
+                                        open_hit_for_key(hit_for_key.clone());
+                                    }
+                                },
+                                div { class: "truncate text-sm font-medium", "{hit.title}" }
+                                if hit.message_index.is_some() {
+                                    div { class: "text-[11px] text-[var(--text-tertiary)] line-clamp-2", "{hit.snippet}" }
+                                }
+                            }
+                        }
+                    }
+                })}
+            } else if conversations.is_empty() {
                 div {
                     class: "flex flex-col items-center justify-center py-10 text-[var(--text-tertiary)] gap-2 opacity-50",
                     svg { width: "24", height: "24", view_box: "0 0 24 24", fill: "none", stroke: "currentColor", stroke_width: "1.5", stroke_dasharray: "4 4", circle { cx: "12", cy: "12", r: "10" } }
-                    span { class: "text-xs font-medium", "No recent chats" }
+                    span { class: "text-xs font-medium", {t!(locale, "conversations.empty")} }
                 }
             } else {
                 div {
                     class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold px-3 py-2 select-none opacity-60",
-                    "Recent"
+                    {t!(locale, "conversations.recent")}
                 }
 
                 {conversations.into_iter().map(|conversation| {
@@ -69,6 +179,9 @@ pub fn ConversationList() -> Element {
                     };
 
                     let conversation_for_select = conversation.clone();
+                    let conversation_for_key = conversation.clone();
+                    let conversation_for_export = conversation.clone();
+                    let conversation_for_pin = conversation.clone();
                     let conversation_id = conversation.id.clone();
                     let mut current_conversation_signal = app_state.current_conversation.clone();
                     let mut conversations_signal = app_state.conversations.clone();
@@ -83,8 +196,18 @@ pub fn ConversationList() -> Element {
 
                             div {
                                 class: row_class,
+                                role: "button",
+                                tabindex: "0",
+                                "aria-current": if is_selected { "true" } else { "false" },
+                                onkeydown: move |evt: KeyboardEvent| {
+                                    if is_activation_key(&evt.key()) {
+                                        evt.prevent_default();
+                                        current_conversation_signal.set(Some(conversation_for_key.clone()));
+                                    }
+                                },
                                 // Icon
                                 div {
+                                    "aria-hidden": "true",
                                     class: "shrink-0 " .to_string() + if is_selected { "text-[var(--accent-primary)]" } else { "text-[var(--text-tertiary)] group-hover:text-[var(--text-secondary)]" },
                                     svg {
                                         width: "14",
@@ -105,9 +228,79 @@ pub fn ConversationList() -> Element {
                                     "{conversation.title}"
                                 }
 
+                                // Pinned conversations are kept by the retention cleanup
                                 button {
-                                    class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
-                                    title: if app_state.settings.read().language == "en" { "Delete conversation" } else { "Supprimer la conversation" },
+                                    class: if conversation.pinned {
+                                        "transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--accent-primary)]"
+                                    } else {
+                                        "opacity-0 group-hover:opacity-100 group-focus-within:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-primary)]"
+                                    },
+                                    title: if conversation.pinned {
+                                        t!(locale, "conversations.unpin")
+                                    } else {
+                                        t!(locale, "conversations.pin")
+                                    },
+                                    "aria-pressed": if conversation.pinned { "true" } else { "false" },
+                                    onclick: move |evt| {
+                                        evt.stop_propagation();
+                                        let mut updated = conversation_for_pin.clone();
+                                        updated.pinned = !updated.pinned;
+                                        // The open copy is saved again by the chat, keep it in step
+                                        let open = current_conversation_signal.peek().as_ref().is_some_and(|c| c.id == updated.id);
+                                        if open {
+                                            if let Some(current) = current_conversation_signal.write().as_mut() {
+                                                current.pinned = updated.pinned;
+                                                updated = current.clone();
+                                            }
+                                        }
+                                        if let Err(e) = save_conversation(&updated) {
+                                            tracing::error!("Failed to save conversation: {}", e);
+                                        }
+                                        if let Ok(conversations) = list_conversations() {
+                                            conversations_signal.set(conversations);
+                                        }
+                                    },
+                                    svg {
+                                        width: "12",
+                                        height: "12",
+                                        view_box: "0 0 24 24",
+                                        fill: if conversation.pinned { "currentColor" } else { "none" },
+                                        stroke: "currentColor",
+                                        stroke_width: "2",
+                                        stroke_linecap: "round",
+                                        stroke_linejoin: "round",
+                                        line { x1: "12", y1: "17", x2: "12", y2: "22" }
+                                        path { d: "M5 17h14v-1.76a2 2 0 0 0-1.11-1.79l-1.78-.9A2 2 0 0 1 15 10.76V6h1a2 2 0 0 0 0-4H8a2 2 0 0 0 0 4h1v4.76a2 2 0 0 1-1.11 1.79l-1.78.9A2 2 0 0 0 5 15.24Z" }
+                                    }
+                                }
+
+                                button {
+                                    class: "opacity-0 group-hover:opacity-100 group-focus-within:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                    title: t!(locale, "conversations.export"),
+                                    "aria-label": t!(locale, "conversations.export"),
+                                    onclick: move |evt| {
+                                        evt.stop_propagation();
+                                        export_target.set(Some(conversation_for_export.clone()));
+                                    },
+                                    svg {
+                                        width: "12",
+                                        height: "12",
+                                        view_box: "0 0 24 24",
+                                        fill: "none",
+                                        stroke: "currentColor",
+                                        stroke_width: "2",
+                                        stroke_linecap: "round",
+                                        stroke_linejoin: "round",
+                                        path { d: "M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4" }
+                                        polyline { points: "7 10 12 15 17 10" }
+                                        line { x1: "12", y1: "15", x2: "12", y2: "3" }
+                                    }
+                                }
+
+                                button {
+                                    class: "opacity-0 group-hover:opacity-100 group-focus-within:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
+                                    title: t!(locale, "conversations.delete"),
+                                    "aria-label": t!(locale, "conversations.delete"),
                                     onclick: move |evt| {
                                         evt.stop_propagation();
                                         if let Err(e) = delete_conversation(&conversation_id) {
@@ -143,6 +336,13 @@ pub fn ConversationList() -> Element {
                     }
                 })}
             }
+
+            if let Some(conversation) = export_target() {
+                ExportDialog {
+                    conversation,
+                    on_close: move |_| export_target.set(None),
+                }
+            }
         }
     }
 }
@@ -0,0 +1,190 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+use crate::app::AppState;
+use crate::storage::conversations::{conversation_to_markdown, export_conversation, Conversation};
+use crate::storage::redaction::Redactor;
+use crate::ui::i18n::t;
+
+/// Default export location: the Downloads folder, named after the conversation
+fn default_export_path(title: &str) -> PathBuf {
+    let name: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .take(60)
+        .collect();
+    let name = name.trim_matches('_');
+    let name = if name.is_empty() { "conversation" } else { name };
+    let dir = directories::UserDirs::new()
+        .and_then(|dirs| dirs.download_dir().map(|d| d.to_path_buf()))
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    dir.join(format!("{}.md", name))
+}
+
+/// Export a conversation as Markdown, optionally redacted, with a preview
+#[component]
+pub fn ExportDialog(conversation: Conversation, on_close: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let redaction = app_state.settings.read().redaction.clone();
+
+    let mut redact = use_signal(|| true);
+    let mut path_input = {
+        let title = conversation.title.clone();
+        use_signal(move || default_export_path(&title).to_string_lossy().to_string())
+    };
+    let mut status = use_signal(|| None::<(bool, String)>);
+
+    let markdown = conversation_to_markdown(&conversation);
+    let redactor = Redactor::new(&redaction);
+    let pattern_error = redactor.as_ref().err().map(|e| e.to_string());
+    let redacted = match (&redactor, redact()) {
+        (Ok(redactor), true) => Some(redactor.redact(&markdown)),
+        _ => None,
+    };
+    let redacted_count = redacted.as_ref().map(|r| r.spans.len()).unwrap_or(0);
+    let preview: Vec<(String, bool)> = match &redacted {
+        Some(redacted) => redacted
+            .segments()
+            .into_iter()
+            .map(|(text, marked)| (text.to_string(), marked))
+            .collect(),
+        None => vec![(markdown.clone(), false)],
+    };
+    let export_blocked = redact() && pattern_error.is_some();
+
+    let handle_export = move |_| {
+        let path = PathBuf::from(path_input().trim());
+        let result = if redact() {
+            Redactor::new(&redaction)
+                .map_err(|e| e.to_string())
+                .and_then(|r| export_conversation(&conversation, &path, Some(&r)).map_err(|e| e.to_string()))
+        } else {
+            export_conversation(&conversation, &path, None).map_err(|e| e.to_string())
+        };
+        match result {
+            Ok(()) => {
+                status.set(Some((true, t!(locale, "export.exported_to", path.display()))));
+            }
+            Err(e) => {
+                tracing::error!("Conversation export failed: {}", e);
+                status.set(Some((false, e)));
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/60 backdrop-blur-xl z-50 flex items-center justify-center p-4",
+            onclick: move |_| on_close.call(()),
+            onkeydown: move |evt: KeyboardEvent| {
+                if evt.key() == Key::Escape {
+                    on_close.call(());
+                }
+            },
+
+            div {
+                class: "w-full max-w-2xl glass-strong rounded-2xl p-6 animate-scale-in",
+                role: "dialog",
+                "aria-modal": "true",
+                "aria-labelledby": "export-dialog-title",
+                onclick: move |e| e.stop_propagation(),
+
+                h3 {
+                    id: "export-dialog-title",
+                    class: "text-lg font-semibold text-[var(--text-primary)] mb-4",
+                    {t!(locale, "export.title")}
+                }
+
+                div {
+                    class: "flex items-center justify-between mb-3",
+                    div {
+                        div { class: "text-sm font-medium text-[var(--text-primary)]",
+                            {t!(locale, "export.redact")}
+                        }
+                        div { class: "text-xs text-[var(--text-tertiary)]",
+                            {t!(locale, "export.redact_hint")}
+                        }
+                    }
+                    button {
+                        role: "switch",
+                        "aria-checked": "{redact()}",
+                        "aria-label": t!(locale, "export.redact"),
+                        onclick: move |_| redact.set(!redact()),
+                        class: if redact() { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+
+                if redact() {
+                    if let Some(error) = pattern_error.as_ref() {
+                        div {
+                            class: "p-3 mb-3 bg-[var(--bg-error-subtle)] border border-[var(--border-error-subtle)] rounded-xl text-xs text-[var(--text-error)]",
+                            "{error}"
+                        }
+                    } else {
+                        div { class: "text-xs text-[var(--text-secondary)] mb-2",
+                            {t!(locale, "export.redacted_count", redacted_count)}
+                        }
+                    }
+                }
+
+                // Preview with redactions highlighted
+                pre {
+                    class: "max-h-[45vh] overflow-y-auto custom-scrollbar p-3 mb-4 rounded-xl bg-white/[0.02] border border-[var(--border-subtle)] text-[11px] font-mono text-[var(--text-secondary)] whitespace-pre-wrap select-text",
+                    "aria-label": t!(locale, "export.preview"),
+                    for (text, marked) in preview {
+                        if marked {
+                            mark {
+                                class: "px-0.5 rounded bg-[var(--accent-primary-10)] text-[var(--accent-primary)]",
+                                "{text}"
+                            }
+                        } else {
+                            "{text}"
+                        }
+                    }
+                }
+
+                input {
+                    r#type: "text",
+                    value: "{path_input}",
+                    oninput: move |e| path_input.set(e.value()),
+                    "aria-label": t!(locale, "export.path"),
+                    class: "w-full py-2 px-3 mb-4 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-mono focus:border-[var(--accent-primary)] outline-none",
+                }
+
+                if let Some((ok, message)) = status.read().as_ref() {
+                    div {
+                        class: if *ok {
+                            "p-3 mb-4 rounded-xl text-xs bg-[var(--bg-success-subtle)] border border-[var(--border-success-subtle)] text-[var(--text-success)]"
+                        } else {
+                            "p-3 mb-4 rounded-xl text-xs bg-[var(--bg-error-subtle)] border border-[var(--border-error-subtle)] text-[var(--text-error)]"
+                        },
+                        role: "status",
+                        "{message}"
+                    }
+                }
+
+                div {
+                    class: "flex gap-3",
+                    button {
+                        onclick: move |_| on_close.call(()),
+                        class: "btn-ghost flex-1",
+                        {t!(locale, "export.close")}
+                    }
+                    button {
+                        onclick: handle_export,
+                        disabled: export_blocked,
+                        class: "btn-primary flex-1",
+                        if redact() {
+                            {t!(locale, "export.redact_and_export")}
+                        } else {
+                            {t!(locale, "export.export")}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
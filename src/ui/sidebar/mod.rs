@@ -1,16 +1,20 @@
 pub mod conversation_list;
+pub mod export_dialog;
 pub mod model_picker;
+pub mod template_picker;
 
 use crate::app::AppState;
 use crate::storage::conversations::{list_conversations, save_conversation, Conversation};
+use crate::ui::i18n::t;
 use crate::ui::sidebar::conversation_list::ConversationList;
 use crate::ui::sidebar::model_picker::ModelPicker;
+use crate::ui::sidebar::template_picker::TemplatePicker;
 use dioxus::prelude::*;
 
 #[component]
 pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHandler<()>, on_help_click: EventHandler<MouseEvent>) -> Element {
     let app_state = use_context::<AppState>();
-    let is_en = app_state.settings.read().language == "en";
+    let locale = app_state.locale();
     tracing::debug!("Sidebar rendered");
 
     let handle_new = {
@@ -60,8 +64,11 @@ pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHa
                         stroke_linejoin: "round",
                         path { d: "M12 5v14M5 12h14" }
                     }
-                    if is_en { "New Chat" } else { "Nouveau Chat" }
+                    {t!(locale, "sidebar.new_chat")}
                 }
+
+                // Start from a saved template
+                TemplatePicker { on_new_chat: on_new_chat }
             }
             
             // Conversation List
@@ -93,10 +100,10 @@ pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHa
                     div {
                         class: "flex flex-col items-start",
                         span { class: "font-medium text-[var(--text-primary)] text-sm",
-                            if is_en { "Settings" } else { "Parametres" }
+                            {t!(locale, "sidebar.settings")}
                         }
                         span { class: "text-[11px] text-[var(--text-tertiary)]",
-                            if is_en { "Preferences" } else { "Preferences" }
+                            {t!(locale, "sidebar.preferences")}
                         }
                     }
                 }
@@ -124,10 +131,10 @@ pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHa
                     div {
                         class: "flex flex-col items-start",
                         span { class: "font-medium text-[var(--text-primary)] text-sm",
-                            if is_en { "Help" } else { "Aide" }
+                            {t!(locale, "sidebar.help")}
                         }
                         span { class: "text-[11px] text-[var(--text-tertiary)]",
-                            if is_en { "Tutorial" } else { "Tutoriel" }
+                            {t!(locale, "sidebar.tutorial")}
                         }
                     }
                 }
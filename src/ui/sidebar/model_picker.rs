@@ -1,8 +1,14 @@
 use dioxus::prelude::*;
-use crate::app::{AppState, ModelState};
-use crate::storage::huggingface::download_model;
-use crate::storage::models::scan_models_directory;
+use crate::agent::language::Locale;
+use crate::app::{estimate_model_memory, AppState, ModelSource, ModelState};
+use crate::storage::huggingface::{format_size, resolve_model_file};
+use crate::inference::{ChatTemplate, LoadPhase, MemoryEstimate, MemoryKind};
+use crate::storage::settings::{save_settings, AppSettings, ToolCallFormat};
+use crate::storage::models::{scan_models_directory, DownloadHandle, DownloadStatus, ModelDownloader};
 use crate::ui::components::loading::Spinner;
+use crate::ui::i18n::t;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 
 #[component]
@@ -20,6 +26,9 @@ pub fn ModelPicker() -> Element {
     let mut is_downloading = use_signal(|| false);
     let mut download_error = use_signal(|| None::<String>);
     let mut download_success = use_signal(|| false);
+    let mut download_checksum = use_signal(String::new);
+    let mut download_status = use_signal(|| None::<DownloadStatus>);
+    let mut download_handle = use_signal(|| None::<Arc<DownloadHandle>>);
     
     let models_directory_clone = models_directory.clone();
     use_effect(move || {
@@ -34,32 +43,43 @@ pub fn ModelPicker() -> Element {
         models.set(found_models);
     });
 
+    // Memory each model needs with the current settings, from its GGUF header
+    let settings_for_estimates = app_state.settings;
+    let memory_estimates = use_resource(move || {
+        let paths: Vec<String> = models.read().iter().map(|m| m.path.to_string_lossy().to_string()).collect();
+        let settings = settings_for_estimates.read().clone();
+        async move {
+            tokio::task::spawn_blocking(move || {
+                paths
+                    .into_iter()
+                    .filter_map(|path| estimate_model_memory(&path, &settings).map(|estimate| (path, estimate)))
+                    .collect::<HashMap<_, _>>()
+            })
+            .await
+            .unwrap_or_default()
+        }
+    });
+    let estimate_for = move |path: &str| -> Option<(MemoryEstimate, u32)> {
+        match &*memory_estimates.read_unchecked() {
+            Some(estimates) => estimates.get(path).copied(),
+            None => None,
+        }
+    };
+
     // Handlers
     let app_state_for_load = app_state.clone();
     let selected_model_path_for_load = selected_model_path.clone();
     let handle_load = move |_| {
-        let mut app_state = app_state_for_load.clone();
-        app_state.model_state.set(ModelState::Loading);
         let path = selected_model_path_for_load
             .read()
             .clone()
             .unwrap_or_default();
-        let gpu_layers = app_state.settings.read().gpu_layers;
-        spawn(async move {
-            let result = {
-                let mut engine = app_state.engine.lock().await;
-                if !engine.is_initialized() {
-                    if let Err(e) = engine.init() {
-                        return app_state.model_state.set(ModelState::Error(e.to_string()));
-                    }
-                }
-                engine.load_model_async(&path, gpu_layers).await
-            };
-            match result {
-                Ok(_info) => app_state.model_state.set(ModelState::Loaded(path)),
-                Err(e) => app_state.model_state.set(ModelState::Error(e.to_string())),
-            }
-        });
+        app_state_for_load.start_model_load(path);
+    };
+
+    let app_state_for_cancel = app_state.clone();
+    let handle_cancel_load = move |_| {
+        app_state_for_cancel.cancel_model_load();
     };
 
     let app_state_for_unload = app_state.clone();
@@ -101,29 +121,68 @@ pub fn ModelPicker() -> Element {
         let mut models_inner = models.clone();
         let models_directory_inner = models_directory.clone();
         let mut download_url_inner = download_url.clone();
+        let checksum = download_checksum.read().trim().to_string();
         
         spawn(async move {
-            let result = download_model(&url, |_downloaded, _total| {
-            }).await;
+            // Runs in the background; the dialog only follows its status
+            let outcome = match resolve_model_file(&url).await {
+                Ok((file_url, filename)) => {
+                    let handle = ModelDownloader::new().start_download(
+                        &file_url,
+                        models_directory_inner.join(filename),
+                        (!checksum.is_empty()).then_some(checksum),
+                    );
+                    let mut status_rx = handle.subscribe();
+                    download_handle.set(Some(Arc::new(handle)));
+                    loop {
+                        let status = status_rx.borrow_and_update().clone();
+                        download_status.set(Some(status.clone()));
+                        if status.is_done() {
+                            break status;
+                        }
+                        if status_rx.changed().await.is_err() {
+                            break status_rx.borrow().clone();
+                        }
+                    }
+                }
+                Err(e) => DownloadStatus::Failed(e),
+            };
             
+            download_handle.set(None);
+            download_status.set(None);
             is_downloading_inner.set(false);
             
-            match result {
-                Ok(path) => {
+            match outcome {
+                DownloadStatus::Finished(path) => {
                     tracing::info!("Downloaded model to: {:?}", path);
                     download_success_inner.set(true);
                     let found_models = scan_models_directory(&models_directory_inner).unwrap_or_default();
                     models_inner.set(found_models);
                     download_url_inner.set(String::new());
                 }
-                Err(e) => {
+                DownloadStatus::Failed(e) => {
                     tracing::error!("Download failed: {}", e);
                     download_error_inner.set(Some(e));
                 }
+                _ => {}
             }
         });
     };
 
+    let handle_cancel_download = move |_| {
+        if let Some(handle) = download_handle.read().as_ref() {
+            handle.cancel();
+        }
+    };
+
+    let locale = app_state.locale();
+    let download_snapshot = download_status.read().clone();
+    let download_percent = download_snapshot
+        .as_ref()
+        .and_then(|status| status.fraction())
+        .map(|fraction| (fraction * 100.0).round() as u32);
+    let download_label = download_status_label(download_snapshot.as_ref(), locale);
+
     rsx! {
         div {
             class: "flex flex-col gap-3",
@@ -133,12 +192,12 @@ pub fn ModelPicker() -> Element {
                 class: "flex items-center justify-between",
                 span {
                     class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold select-none",
-                    if app_state.settings.read().language == "en" { "Active Model" } else { "Modele actif" }
+                    {t!(locale, "model.active")}
                 }
                 button {
                     onclick: handle_refresh,
                     class: "text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-colors p-1 rounded-md hover:bg-white/[0.06]",
-                    title: if app_state.settings.read().language == "en" { "Rescan models" } else { "Re-scanner les modeles" },
+                    title: t!(locale, "model.rescan"),
                     svg {
                         class: "w-3 h-3",
                         view_box: "0 0 24 24",
@@ -158,10 +217,10 @@ pub fn ModelPicker() -> Element {
                 div {
                     class: "flex flex-col items-center justify-center p-4 border border-dashed border-[var(--border-subtle)] rounded-xl gap-2",
                     span { class: "text-sm text-[var(--text-secondary)] font-medium",
-                        if app_state.settings.read().language == "en" { "No models found" } else { "Aucun modele trouve" }
+                        {t!(locale, "model.none_found")}
                     }
                     span { class: "text-[10px] text-[var(--text-tertiary)] text-center",
-                        if app_state.settings.read().language == "en" { "Place .gguf files in /models" } else { "Placez des fichiers .gguf dans /models" }
+                        {t!(locale, "model.none_found_hint")}
                     }
                 }
             } else {
@@ -170,11 +229,11 @@ pub fn ModelPicker() -> Element {
                     
                     // Model Selector — custom dropdown
                     {
-                        let is_disabled = matches!(*app_state.model_state.read(), ModelState::Loading | ModelState::Loaded(_));
+                        let is_disabled = matches!(*app_state.model_state.read(), ModelState::Loading(_) | ModelState::Swapping { .. } | ModelState::Loaded(_));
                         let selected_name = {
                             let sel = selected_model_path.read();
                             let mods = models.read();
-                            let fallback = if app_state.settings.read().language == "en" { "Select a model" } else { "Choisir un modele" };
+                            let fallback = t!(locale, "model.select");
                             sel.as_ref().and_then(|p| mods.iter().find(|m| m.path.to_string_lossy() == *p).map(|m| m.filename.clone())).unwrap_or_else(|| fallback.to_string())
                         };
 
@@ -222,6 +281,8 @@ pub fn ModelPicker() -> Element {
                                                     let is_selected = selected_model_path.read().as_ref().map_or(false, |p| *p == path_str);
                                                     let filename = model.filename.clone();
                                                     let size = model.size_string();
+                                                    let needs = estimate_for(&path_str)
+                                                        .map(|(estimate, context)| format!("~{} @ {}K", format_memory(estimate.total_bytes()), context / 1024));
 
                                                     rsx! {
                                                         button {
@@ -248,6 +309,9 @@ pub fn ModelPicker() -> Element {
                                                             span {
                                                                 class: "flex-shrink-0 text-[10px] font-mono text-[var(--text-tertiary)] ml-2",
                                                                 "{size}"
+                                                                if let Some(needs) = needs {
+                                                                    span { class: "ml-1.5 text-[var(--text-secondary)]", "{needs}" }
+                                                                }
                                                             }
                                                         }
                                                     }
@@ -260,14 +324,195 @@ pub fn ModelPicker() -> Element {
                         }
                     }
 
-                    // Size badge
+                    // Size and reasoning badges, reasoning override
                     if let Some(path) = selected_model_path.read().as_ref() {
                         if let Some(model) = models.read().iter().find(|m| m.path.to_string_lossy() == *path) {
-                            div {
-                                class: "flex justify-end",
-                                span {
-                                    class: "px-2 py-0.5 rounded-md text-[10px] font-mono bg-white/[0.03] text-[var(--text-tertiary)] border border-[var(--border-subtle)]",
-                                    "{model.size_string()}"
+                            {
+                                let is_loaded = matches!(&*app_state.model_state.read(), ModelState::Loaded(source) if source.local_path() == Some(path.as_str()));
+                                // Read the signal so the badge follows detection
+                                let _ = app_state.reasoning_detected.read();
+                                let show_reasoning = is_loaded && app_state.is_reasoning_model();
+                                let override_value = match app_state.settings.read().model_profile(path).and_then(|p| p.reasoning) {
+                                    Some(true) => "on",
+                                    Some(false) => "off",
+                                    None => "auto",
+                                };
+                                let format_value = app_state.settings.read()
+                                    .model_profile(path)
+                                    .and_then(|p| p.tool_call_format)
+                                    .map_or("auto", |format| format.as_str());
+                                let template_value = app_state.settings.read()
+                                    .model_profile(path)
+                                    .and_then(|p| p.chat_template)
+                                    .map_or("auto", |template| template.as_str());
+                                let template_in_use = if is_loaded { *app_state.chat_template_in_use.read() } else { None };
+                                let trained_context = match &*app_state.model_state.read() {
+                                    ModelState::Loaded(source) if is_loaded => source.trained_context(),
+                                    _ => None,
+                                };
+                                let context_title = match &*app_state.model_state.read() {
+                                    ModelState::Loaded(ModelSource::Local { rope_scaling: Some(scaling), .. }) => match scaling.original_context_length {
+                                        Some(base) => t!(locale, "model.trained_context_rope_from", scaling.kind, scaling.factor, base / 1024),
+                                        None => t!(locale, "model.trained_context_rope", scaling.kind, scaling.factor),
+                                    },
+                                    _ => t!(locale, "model.trained_context").to_string(),
+                                };
+                                let needs = estimate_for(path).map(|(estimate, context)| {
+                                    t!(locale, "model.memory_needed", format_memory(estimate.total_bytes()), context / 1024)
+                                });
+                                let profile_key = AppSettings::model_profile_key(path);
+                                let format_key = profile_key.clone();
+                                let template_key = profile_key.clone();
+                                let mut settings_signal = app_state.settings;
+                                rsx! {
+                                    div {
+                                        class: "flex items-center justify-end gap-1.5",
+                                        if show_reasoning {
+                                            span {
+                                                class: "px-2 py-0.5 rounded-md text-[10px] font-medium bg-[var(--accent-soft)] text-[var(--accent-primary)] border border-[var(--border-subtle)]",
+                                                title: t!(locale, "model.reasoning_hint"),
+                                                {t!(locale, "model.reasoning")}
+                                            }
+                                        }
+                                        if let Some(template) = template_in_use {
+                                            span {
+                                                class: "px-2 py-0.5 rounded-md text-[10px] font-medium bg-white/[0.03] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
+                                                title: t!(locale, "model.template_hint"),
+                                                "{template.label()}"
+                                            }
+                                        }
+                                        if let Some(context) = trained_context {
+                                            span {
+                                                class: "px-2 py-0.5 rounded-md text-[10px] font-mono bg-white/[0.03] text-[var(--text-tertiary)] border border-[var(--border-subtle)]",
+                                                title: "{context_title}",
+                                                "{context / 1024}K ctx"
+                                            }
+                                        }
+                                        span {
+                                            class: "px-2 py-0.5 rounded-md text-[10px] font-mono bg-white/[0.03] text-[var(--text-tertiary)] border border-[var(--border-subtle)]",
+                                            "{model.size_string()}"
+                                        }
+                                    }
+                                    if let Some(needs) = needs {
+                                        p {
+                                            class: "text-right text-[10px] text-[var(--text-tertiary)]",
+                                            title: t!(locale, "model.memory_hint"),
+                                            "{needs}"
+                                        }
+                                    }
+                                    select {
+                                        value: "{override_value}",
+                                        onchange: move |e| {
+                                            let reasoning = match e.value().as_str() {
+                                                "on" => Some(true),
+                                                "off" => Some(false),
+                                                _ => None,
+                                            };
+                                            let mut settings = settings_signal.write();
+                                            match reasoning {
+                                                Some(_) => settings.model_profiles.entry(profile_key.clone()).or_default().reasoning = reasoning,
+                                                None => {
+                                                    if let Some(profile) = settings.model_profiles.get_mut(&profile_key) {
+                                                        profile.reasoning = None;
+                                                    }
+                                                }
+                                            }
+                                            if let Err(error) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", error);
+                                            }
+                                        },
+                                        class: "w-full px-2 py-1 rounded-lg text-[11px] text-[var(--text-secondary)] bg-[var(--bg-tertiary)] border border-[var(--border-subtle)] appearance-none cursor-pointer",
+                                        option { value: "auto", {t!(locale, "model.reasoning_auto")} }
+                                        option { value: "on", {t!(locale, "model.reasoning_yes")} }
+                                        option { value: "off", {t!(locale, "model.reasoning_no")} }
+                                    }
+                                    select {
+                                        value: "{format_value}",
+                                        title: t!(locale, "model.tool_format_hint"),
+                                        onchange: move |e| {
+                                            let format = ToolCallFormat::parse(&e.value());
+                                            let mut settings = settings_signal.write();
+                                            match format {
+                                                Some(_) => settings.model_profiles.entry(format_key.clone()).or_default().tool_call_format = format,
+                                                None => {
+                                                    if let Some(profile) = settings.model_profiles.get_mut(&format_key) {
+                                                        profile.tool_call_format = None;
+                                                    }
+                                                }
+                                            }
+                                            if let Err(error) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", error);
+                                            }
+                                        },
+                                        class: "w-full px-2 py-1 rounded-lg text-[11px] text-[var(--text-secondary)] bg-[var(--bg-tertiary)] border border-[var(--border-subtle)] appearance-none cursor-pointer",
+                                        option { value: "auto", {t!(locale, "model.tool_format_auto")} }
+                                        option { value: "json", {t!(locale, "model.tool_format_json")} }
+                                        option { value: "xml_tag", {t!(locale, "model.tool_format_xml")} }
+                                        option { value: "function_call", {t!(locale, "model.tool_format_function")} }
+                                    }
+                                    select {
+                                        value: "{template_value}",
+                                        title: t!(locale, "model.template_format_hint"),
+                                        onchange: move |e| {
+                                            let template = ChatTemplate::parse(&e.value());
+                                            let mut settings = settings_signal.write();
+                                            match template {
+                                                Some(_) => settings.model_profiles.entry(template_key.clone()).or_default().chat_template = template,
+                                                None => {
+                                                    if let Some(profile) = settings.model_profiles.get_mut(&template_key) {
+                                                        profile.chat_template = None;
+                                                    }
+                                                }
+                                            }
+                                            if let Err(error) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", error);
+                                            }
+                                        },
+                                        class: "w-full px-2 py-1 rounded-lg text-[11px] text-[var(--text-secondary)] bg-[var(--bg-tertiary)] border border-[var(--border-subtle)] appearance-none cursor-pointer",
+                                        option { value: "auto", {t!(locale, "model.template_auto")} }
+                                        for template in ChatTemplate::ALL {
+                                            option { value: "{template.as_str()}", {t!(locale, "model.template_named", template.label())} }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Load refused for memory, with an override
+                    if let Some(refusal) = app_state.memory_refusal.read().clone() {
+                        {
+                            let memory = match refusal.shortfall.kind {
+                                MemoryKind::Ram => "RAM",
+                                MemoryKind::Vram => "VRAM",
+                            };
+                            let message = t!(
+                                locale,
+                                "model.memory_refused",
+                                format_memory(refusal.shortfall.needed_bytes),
+                                memory,
+                                refusal.context_size / 1024,
+                                format_memory(refusal.shortfall.available_bytes),
+                            );
+                            let app_state_for_override = app_state.clone();
+                            let mut memory_refusal = app_state.memory_refusal;
+                            rsx! {
+                                div {
+                                    class: "w-full p-2 bg-[var(--warning-bg)] border border-[var(--border-subtle)] rounded-xl text-xs text-[var(--text-secondary)] space-y-2",
+                                    p { "{message}" }
+                                    div {
+                                        class: "flex justify-end gap-2",
+                                        button {
+                                            onclick: move |_| memory_refusal.set(None),
+                                            class: "px-2 py-1 rounded-lg text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-colors",
+                                            {t!(locale, "model.cancel")}
+                                        }
+                                        button {
+                                            onclick: move |_| app_state_for_override.load_model_anyway(),
+                                            class: "px-2 py-1 rounded-lg border border-[var(--border-subtle)] text-[var(--warning)] hover:border-[var(--warning)] transition-colors",
+                                            {t!(locale, "model.load_anyway")}
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -290,20 +535,58 @@ pub fn ModelPicker() -> Element {
                                     path { d: "M5 12h14" }
                                     path { d: "M12 5l7 7-7 7" }
                                 }
-                                if app_state.settings.read().language == "en" { "Load Model" } else { "Charger le modele" }
+                                {t!(locale, "model.load")}
                             }
                         },
-                        ModelState::Loading => rsx! {
-                            div {
-                                class: "w-full flex flex-col gap-2 bg-white/[0.03] border border-[var(--border-subtle)] p-3 rounded-xl",
+                        ModelState::Loading(progress) | ModelState::Swapping { progress, .. } => {
+                            let swapping = matches!(*app_state.model_state.read(), ModelState::Swapping { .. });
+                            let loading_label = if swapping { t!(locale, "model.switching") } else { t!(locale, "model.loading") };
+                            let percent = (progress * 100.0).round() as u32;
+                            let (eta, phase) = match app_state.model_load.read().as_ref() {
+                                Some(control) => (control.eta(), control.phase()),
+                                None => (None, LoadPhase::Reading),
+                            };
+                            let building = phase == LoadPhase::Building;
+                            let status = match phase {
+                                LoadPhase::Cancelled => t!(locale, "model.cancelling").to_string(),
+                                LoadPhase::Building => t!(locale, "model.building").to_string(),
+                                LoadPhase::Reading => match eta {
+                                    Some(eta) => t!(locale, "model.reading_eta", percent, format_eta(eta)),
+                                    None => t!(locale, "model.reading", percent),
+                                },
+                            };
+                            rsx! {
                                 div {
-                                    class: "flex items-center gap-2",
-                                    Spinner { size: 14 }
-                                    span { class: "text-xs font-medium text-[var(--text-secondary)]",
-                                        if app_state.settings.read().language == "en" { "Loading into memory..." } else { "Chargement en memoire..." }
+                                    class: "w-full flex flex-col gap-2 bg-white/[0.03] border border-[var(--border-subtle)] p-3 rounded-xl",
+                                    div {
+                                        class: "flex items-center gap-2",
+                                        Spinner { size: 14 }
+                                        span { class: "flex-1 text-xs font-medium text-[var(--text-secondary)]",
+                                            "{loading_label}"
+                                        }
+                                        button {
+                                            onclick: handle_cancel_load,
+                                            disabled: phase != LoadPhase::Reading,
+                                            title: if building { t!(locale, "model.building_no_cancel") } else { "" },
+                                            class: "text-[10px] font-medium text-[var(--text-tertiary)] hover:text-[var(--text-error)] disabled:opacity-40 disabled:pointer-events-none px-2 py-0.5 rounded-md hover:bg-[var(--bg-error-subtle)] transition-colors",
+                                            {t!(locale, "model.cancel")}
+                                        }
+                                    }
+                                    // llama.cpp reports no progress, so its phase gets an indeterminate bar
+                                    if building {
+                                        div { class: "loading-bar" }
+                                    } else {
+                                        div {
+                                            class: "progress-track",
+                                            role: "progressbar",
+                                            "aria-valuemin": "0",
+                                            "aria-valuemax": "100",
+                                            "aria-valuenow": "{percent}",
+                                            div { class: "progress-fill", style: "width: {percent}%;" }
+                                        }
                                     }
+                                    span { class: "text-[10px] font-mono text-[var(--text-tertiary)]", "{status}" }
                                 }
-                                div { class: "loading-bar" }
                             }
                         },
                         ModelState::Loaded(_) => rsx! {
@@ -313,13 +596,13 @@ pub fn ModelPicker() -> Element {
                                     class: "flex-1 flex items-center gap-2 px-3 py-2 bg-[var(--bg-success-subtle)] border border-[var(--border-success-subtle)] rounded-xl",
                                     div { class: "status-dot status-dot-ready" }
                                     span { class: "text-xs font-medium text-[var(--text-success)]",
-                                        if app_state.settings.read().language == "en" { "Ready" } else { "Pret" }
+                                        {t!(locale, "model.ready")}
                                     }
                                 }
                                 button {
                                     onclick: handle_unload,
                                     class: "px-3 py-2 text-sm text-[var(--text-secondary)] border border-[var(--border-subtle)] rounded-xl hover:bg-[var(--bg-error-subtle)] hover:border-[var(--border-error-subtle)] hover:text-[var(--text-error)] transition-colors",
-                                    title: if app_state.settings.read().language == "en" { "Unload Model" } else { "Decharger le modele" },
+                                    title: t!(locale, "model.unload"),
                                     svg {
                                         class: "w-4 h-4",
                                         view_box: "0 0 24 24",
@@ -364,7 +647,7 @@ pub fn ModelPicker() -> Element {
                     polyline { points: "7 10 12 15 17 10" }
                     line { x1: "12", y1: "15", x2: "12", y2: "3" }
                 }
-                if app_state.settings.read().language == "en" { "Download from HuggingFace" } else { "Telecharger depuis HuggingFace" }
+                {t!(locale, "model.download_open")}
             }
 
             // Download Dialog
@@ -379,12 +662,12 @@ pub fn ModelPicker() -> Element {
                         
                         h3 {
                             class: "text-lg font-semibold text-[var(--text-primary)] mb-2",
-                            if app_state.settings.read().language == "en" { "Download Model from HuggingFace" } else { "Telecharger un modele HuggingFace" }
+                            {t!(locale, "model.download_title")}
                         }
                         
                         p {
                             class: "text-sm text-[var(--text-secondary)] mb-4",
-                            if app_state.settings.read().language == "en" { "Enter a HuggingFace repository URL or model ID. Example: TheBloke/Llama-2-7B-GGUF" } else { "Entrez une URL de depot HuggingFace ou un ID de modele. Exemple : TheBloke/Llama-2-7B-GGUF" }
+                            {t!(locale, "model.download_hint")}
                         }
                         
                         input {
@@ -393,16 +676,45 @@ pub fn ModelPicker() -> Element {
                             oninput: move |e| download_url.set(e.value()),
                             disabled: *is_downloading.read(),
                             placeholder: "username/repo or full URL",
-                            class: "w-full p-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none mb-4",
+                            class: "w-full p-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none mb-3",
+                        }
+
+                        input {
+                            r#type: "text",
+                            value: "{download_checksum.read()}",
+                            oninput: move |e| download_checksum.set(e.value()),
+                            disabled: *is_downloading.read(),
+                            placeholder: t!(locale, "model.sha256"),
+                            class: "w-full p-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] font-mono text-xs focus:border-[var(--accent-primary)] transition-all outline-none mb-4",
                         }
                         
                         if *is_downloading.read() {
                             div {
-                                class: "mb-4 flex items-center justify-center gap-3 p-3 bg-white/[0.02] rounded-xl border border-[var(--border-subtle)]",
-                                Spinner { size: 16 }
-                                span { class: "text-sm text-[var(--text-secondary)]",
-                                    if app_state.settings.read().language == "en" { "Downloading..." } else { "Telechargement..." }
+                                class: "mb-4 flex flex-col gap-2 p-3 bg-white/[0.02] rounded-xl border border-[var(--border-subtle)]",
+                                div {
+                                    class: "flex items-center gap-2",
+                                    Spinner { size: 14 }
+                                    span { class: "flex-1 text-sm text-[var(--text-secondary)]",
+                                        {t!(locale, "model.downloading")}
+                                    }
+                                    button {
+                                        onclick: handle_cancel_download,
+                                        disabled: download_handle.read().is_none(),
+                                        class: "text-[10px] font-medium text-[var(--text-tertiary)] hover:text-[var(--text-error)] px-2 py-0.5 rounded-md hover:bg-[var(--bg-error-subtle)] transition-colors",
+                                        {t!(locale, "model.stop")}
+                                    }
                                 }
+                                if let Some(percent) = download_percent {
+                                    div {
+                                        class: "progress-track",
+                                        role: "progressbar",
+                                        "aria-valuemin": "0",
+                                        "aria-valuemax": "100",
+                                        "aria-valuenow": "{percent}",
+                                        div { class: "progress-fill", style: "width: {percent}%;" }
+                                    }
+                                }
+                                span { class: "text-[10px] font-mono text-[var(--text-tertiary)]", "{download_label}" }
                             }
                         }
                         
@@ -416,7 +728,7 @@ pub fn ModelPicker() -> Element {
                         if *download_success.read() {
                             div {
                                 class: "p-3 mb-4 bg-[var(--bg-success-subtle)] border border-[var(--border-success-subtle)] rounded-xl text-xs text-[var(--text-success)]",
-                                if app_state.settings.read().language == "en" { "Download complete! Model is now available in the list." } else { "Telechargement termine ! Le modele est maintenant disponible." }
+                                {t!(locale, "model.download_done")}
                             }
                         }
                         
@@ -425,7 +737,7 @@ pub fn ModelPicker() -> Element {
                             button {
                                 onclick: move |_| show_download_dialog.set(false),
                                 class: "btn-ghost flex-1",
-                                if app_state.settings.read().language == "en" { "Cancel" } else { "Annuler" }
+                                {t!(locale, "model.cancel")}
                             }
                             button {
                                 onclick: handle_download,
@@ -433,9 +745,9 @@ pub fn ModelPicker() -> Element {
                                 class: "btn-primary flex-1 flex items-center justify-center gap-2",
                                 if *is_downloading.read() {
                                     Spinner { size: 14 }
-                                    if app_state.settings.read().language == "en" { "Downloading..." } else { "Telechargement..." }
+                                    {t!(locale, "model.downloading")}
                                 } else {
-                                    if app_state.settings.read().language == "en" { "Download" } else { "Telecharger" }
+                                    {t!(locale, "model.download")}
                                 }
                             }
                         }
@@ -445,3 +757,31 @@ pub fn ModelPicker() -> Element {
         }
     }
 }
+
+/// Progress line of a model download: size, speed and time left
+pub(crate) fn download_status_label(status: Option<&DownloadStatus>, locale: Locale) -> String {
+    match status {
+        Some(DownloadStatus::Downloading { downloaded, total, bytes_per_sec, eta }) => {
+            let total = total.map(format_size).unwrap_or_else(|| "?".to_string());
+            let eta = eta.map(|eta| format!(" - {}", format_eta(eta))).unwrap_or_default();
+            format!("{} / {} - {}/s{}", format_size(*downloaded), total, format_size(*bytes_per_sec as u64), eta)
+        }
+        Some(DownloadStatus::Verifying) => t!(locale, "model.verifying").to_string(),
+        _ => t!(locale, "model.starting").to_string(),
+    }
+}
+
+/// Memory size for estimates, one decimal in GB
+fn format_memory(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+/// Short remaining-time label for the load progress bar
+fn format_eta(eta: std::time::Duration) -> String {
+    let secs = eta.as_secs();
+    if secs < 60 {
+        format!("~{}s", secs.max(1))
+    } else {
+        format!("~{} min", secs.div_ceil(60))
+    }
+}
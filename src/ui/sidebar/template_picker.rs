@@ -0,0 +1,82 @@
+use dioxus::prelude::*;
+use crate::app::AppState;
+use crate::ui::i18n::t;
+use crate::storage::conversations::{list_conversations, save_conversation};
+
+/// Small dropdown next to "New Chat" to start a conversation from a template
+#[component]
+pub fn TemplatePicker(on_new_chat: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let locale = app_state.locale();
+    let templates = app_state.settings.read().conversation_templates.clone();
+    let mut dropdown_open = use_signal(|| false);
+
+    if templates.is_empty() {
+        return rsx! {};
+    }
+
+    let title = t!(locale, "template.new_chat_from_template");
+
+    rsx! {
+        div {
+            class: "relative",
+
+            button {
+                onclick: move |_| dropdown_open.set(!dropdown_open()),
+                class: "w-full flex items-center justify-between gap-2 px-3 py-2 text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)] rounded-xl hover:bg-white/[0.06] transition-all",
+                title: "{title}",
+                span { {t!(locale, "template.from_template")} }
+                svg {
+                    class: "w-3 h-3",
+                    view_box: "0 0 24 24",
+                    fill: "none",
+                    stroke: "currentColor",
+                    stroke_width: "2",
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                    polyline { points: "6 9 12 15 18 9" }
+                }
+            }
+
+            if dropdown_open() {
+                div {
+                    class: "absolute left-0 top-full mt-1 w-full rounded-xl overflow-hidden z-50 glass-md animate-fade-in-up",
+                    style: "border: 1px solid var(--border-medium); box-shadow: 0 12px 32px -4px rgba(30,25,20,0.35);",
+
+                    for template in templates {
+                        {
+                            let name = template.name.clone();
+                            let preview = template.first_message.clone().unwrap_or_default();
+                            let mut app_state = app_state.clone();
+                            rsx! {
+                                button {
+                                    key: "{template.id}",
+                                    onclick: move |_| {
+                                        let (conversation, scaffold) = template.instantiate();
+                                        if let Err(e) = save_conversation(&conversation) {
+                                            tracing::error!("Failed to save conversation: {}", e);
+                                            return;
+                                        }
+                                        tracing::info!("New conversation from template '{}'", template.name);
+                                        app_state.current_conversation.set(Some(conversation));
+                                        app_state.pending_input.set(scaffold);
+                                        if let Ok(conversations) = list_conversations() {
+                                            app_state.conversations.set(conversations);
+                                        }
+                                        dropdown_open.set(false);
+                                        on_new_chat.call(());
+                                    },
+                                    class: "w-full text-left px-3 py-2 flex flex-col gap-0.5 hover:bg-white/[0.06] transition-colors",
+                                    span { class: "text-sm font-medium text-[var(--text-primary)] truncate", "{name}" }
+                                    if !preview.is_empty() {
+                                        span { class: "text-[11px] text-[var(--text-tertiary)] truncate", "{preview}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}